@@ -0,0 +1,89 @@
+//! Workspace artifact cache: lowered topologies of NIR modules
+//!
+//! `snn nir run` stores the neurons and synapses a module lowers to under
+//! `<output.dir>/cache/<key>.ltop`, keyed by the module's canonical text, the
+//! compiler version and the files it reads (see
+//! [`cache_key`](shnn_compiler::cache::cache_key)). A later run of the same
+//! module loads the artifact instead of expanding its connectivity again.
+
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use shnn_compiler::cache::LoweredTopology;
+use shnn_storage::atomic::write_atomic;
+
+use crate::config::Config;
+use crate::error::CliResult;
+
+const ARTIFACT_EXTENSION: &str = "ltop";
+
+/// Contents of an artifact cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Stored artifacts
+    pub entries: usize,
+    /// Their total size (bytes)
+    pub bytes: u64,
+}
+
+/// Directory of lowered-topology artifacts
+#[derive(Debug, Clone)]
+pub struct ArtifactCache {
+    root: PathBuf,
+}
+
+impl ArtifactCache {
+    /// Cache rooted at `root`
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// The workspace cache, `<output.dir>/cache`
+    pub fn open(settings: &Config) -> Self {
+        Self::new(settings.output_dir().join("cache"))
+    }
+
+    /// Cache directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Artifact stored under `key` and its size; unreadable artifacts are
+    /// reported and treated as missing, so the next store replaces them
+    pub fn get(&self, key: &str) -> Option<(LoweredTopology, u64)> {
+        let path = self.path(key);
+        let bytes = std::fs::read(&path).ok()?;
+        match LoweredTopology::from_bytes(&bytes) {
+            Ok(topology) => Some((topology, bytes.len() as u64)),
+            Err(e) => {
+                warn!("ignoring cache artifact {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Store `topology` under `key`; returns the artifact size
+    pub fn put(&self, key: &str, topology: &LoweredTopology) -> CliResult<u64> {
+        std::fs::create_dir_all(&self.root)?;
+        let bytes = topology.to_bytes();
+        write_atomic(self.path(key), &bytes)?;
+        Ok(bytes.len() as u64)
+    }
+
+    /// Artifacts currently stored
+    pub fn stats(&self) -> CacheStats {
+        let Ok(entries) = std::fs::read_dir(&self.root) else { return CacheStats::default() };
+        entries
+            .flatten()
+            .filter(|entry| entry.path().extension().map_or(false, |e| e == ARTIFACT_EXTENSION))
+            .filter_map(|entry| entry.metadata().ok())
+            .fold(CacheStats::default(), |stats, meta| CacheStats {
+                entries: stats.entries + 1,
+                bytes: stats.bytes + meta.len(),
+            })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(format!("{}.{}", key, ARTIFACT_EXTENSION))
+    }
+}
@@ -0,0 +1,244 @@
+//! Standardized performance scenarios with a machine-readable report
+//!
+//! Each scenario builds the same feed-forward NIR module at a fixed scale and
+//! times the pipeline stages (parse, verify, lower, run) over several
+//! iterations, so reports from different releases can be compared directly.
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Instant;
+use tracing::info;
+
+use shnn_compiler::{compile_module, verify_module};
+use shnn_ir::{
+    layer_fully_connected_v1, lif_neuron_v1, parse_text, runtime_simulate_run_v1,
+    stimulus_poisson_v1, Module,
+};
+
+use crate::error::{CliError, CliResult};
+
+/// Report format identifier; bump the version when fields change meaning
+const REPORT_FORMAT: &str = "snn-bench-report";
+const REPORT_VERSION: u32 = 1;
+
+/// Simulation step used by every scenario (ms)
+const DT_MS: f32 = 0.1;
+
+/// Run standardized benchmark scenarios
+#[derive(Args, Debug)]
+pub struct BenchCommand {
+    /// Scenarios to run (repeatable; defaults to all)
+    #[arg(long = "scenario", value_enum)]
+    pub scenarios: Vec<BenchScenario>,
+
+    /// Timed iterations per scenario
+    #[arg(long, default_value_t = 3)]
+    pub iterations: usize,
+
+    /// Simulated time per run (ms)
+    #[arg(long, default_value_t = 100.0)]
+    pub duration_ms: f32,
+
+    /// Write the JSON report to this path
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Standardized scenario scales
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BenchScenario {
+    /// 64 neurons, 1k synapses
+    Small,
+    /// 256 neurons, 16k synapses
+    Medium,
+    /// 1024 neurons, 262k synapses
+    Large,
+}
+
+impl BenchScenario {
+    const ALL: [BenchScenario; 3] = [BenchScenario::Small, BenchScenario::Medium, BenchScenario::Large];
+
+    fn name(self) -> &'static str {
+        match self {
+            BenchScenario::Small => "small",
+            BenchScenario::Medium => "medium",
+            BenchScenario::Large => "large",
+        }
+    }
+
+    fn neurons(self) -> u32 {
+        match self {
+            BenchScenario::Small => 64,
+            BenchScenario::Medium => 256,
+            BenchScenario::Large => 1024,
+        }
+    }
+
+    /// Half the neurons are Poisson-driven inputs fully connected to the other half
+    fn module(self, duration_ms: f32) -> Module {
+        let neurons = self.neurons();
+        let half = neurons / 2;
+        let mut module = Module::new();
+        module.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        module.push(layer_fully_connected_v1(0, half - 1, half, neurons - 1, 0.5, 1.0));
+        for i in 0..half {
+            module.push(stimulus_poisson_v1(i, 100.0, 300.0, 0.0, duration_ms));
+        }
+        module.push(runtime_simulate_run_v1(DT_MS, duration_ms, false, Some(42)));
+        module
+    }
+}
+
+/// Wall-clock timings of one stage across iterations
+#[derive(Debug, Clone, Serialize)]
+struct StageTiming {
+    mean_ns: u64,
+    min_ns: u64,
+    max_ns: u64,
+}
+
+impl StageTiming {
+    fn from_samples(samples: &[u64]) -> Self {
+        Self {
+            mean_ns: samples.iter().sum::<u64>() / samples.len().max(1) as u64,
+            min_ns: samples.iter().copied().min().unwrap_or(0),
+            max_ns: samples.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StageTimings {
+    parse_text: StageTiming,
+    verify: StageTiming,
+    lower: StageTiming,
+    run: StageTiming,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScenarioReport {
+    name: &'static str,
+    neurons: u32,
+    synapses: usize,
+    ops: usize,
+    steps: u64,
+    /// Spikes in the last iteration (identical across iterations for a fixed seed)
+    spikes: usize,
+    stages: StageTimings,
+    /// Simulation steps per wall-clock second, from the mean run time
+    steps_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BenchReport {
+    format: &'static str,
+    version: u32,
+    snn_version: &'static str,
+    iterations: usize,
+    duration_ms: f32,
+    dt_ms: f32,
+    scenarios: Vec<ScenarioReport>,
+}
+
+impl BenchCommand {
+    pub async fn execute(self) -> CliResult<()> {
+        if self.iterations == 0 {
+            return Err(CliError::invalid_args("--iterations must be at least 1"));
+        }
+        if !(self.duration_ms.is_finite() && self.duration_ms >= DT_MS) {
+            return Err(CliError::invalid_args(format!("--duration-ms must be at least {}", DT_MS)));
+        }
+        let scenarios = if self.scenarios.is_empty() { BenchScenario::ALL.to_vec() } else { self.scenarios.clone() };
+
+        let mut reports = Vec::with_capacity(scenarios.len());
+        for scenario in scenarios {
+            info!("Benchmarking scenario '{}' ({} neurons)", scenario.name(), scenario.neurons());
+            reports.push(self.run_scenario(scenario)?);
+        }
+
+        println!(
+            "{:<8} {:>7} {:>9} {:>11} {:>11} {:>11} {:>11} {:>12}",
+            "SCENARIO", "NEURONS", "SYNAPSES", "PARSE ms", "VERIFY ms", "LOWER ms", "RUN ms", "STEPS/s"
+        );
+        let ms = |t: &StageTiming| t.mean_ns as f64 / 1_000_000.0;
+        for r in &reports {
+            println!(
+                "{:<8} {:>7} {:>9} {:>11.3} {:>11.3} {:>11.3} {:>11.3} {:>12.0}",
+                r.name,
+                r.neurons,
+                r.synapses,
+                ms(&r.stages.parse_text),
+                ms(&r.stages.verify),
+                ms(&r.stages.lower),
+                ms(&r.stages.run),
+                r.steps_per_sec
+            );
+        }
+
+        if let Some(path) = &self.output {
+            let report = BenchReport {
+                format: REPORT_FORMAT,
+                version: REPORT_VERSION,
+                snn_version: env!("CARGO_PKG_VERSION"),
+                iterations: self.iterations,
+                duration_ms: self.duration_ms,
+                dt_ms: DT_MS,
+                scenarios: reports,
+            };
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            std::fs::write(path, json)?;
+            info!("Wrote benchmark report to {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn run_scenario(&self, scenario: BenchScenario) -> CliResult<ScenarioReport> {
+        let module = scenario.module(self.duration_ms);
+        let text = module.to_text();
+        let generic = |e: shnn_compiler::CompilerError| CliError::Generic(anyhow::anyhow!(e));
+
+        let (mut parse, mut verify, mut lower, mut run) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let mut last = None;
+        for _ in 0..self.iterations {
+            let start = Instant::now();
+            let parsed = parse_text(&text).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            parse.push(start.elapsed().as_nanos() as u64);
+
+            let start = Instant::now();
+            verify_module(&parsed).map_err(generic)?;
+            verify.push(start.elapsed().as_nanos() as u64);
+
+            let start = Instant::now();
+            let mut program = compile_module(&parsed).map_err(generic)?;
+            lower.push(start.elapsed().as_nanos() as u64);
+
+            let start = Instant::now();
+            let result = program.engine.run()?;
+            run.push(start.elapsed().as_nanos() as u64);
+
+            last = Some((program.engine.network().synapse_count(), parsed.ops.len(), result.spikes.len()));
+        }
+        let (synapses, ops, spikes) = last.expect("at least one iteration");
+
+        let steps = ((self.duration_ms / DT_MS).round()) as u64;
+        let run = StageTiming::from_samples(&run);
+        let steps_per_sec = if run.mean_ns > 0 { steps as f64 * 1e9 / run.mean_ns as f64 } else { 0.0 };
+        Ok(ScenarioReport {
+            name: scenario.name(),
+            neurons: scenario.neurons(),
+            synapses,
+            ops,
+            steps,
+            spikes,
+            stages: StageTimings {
+                parse_text: StageTiming::from_samples(&parse),
+                verify: StageTiming::from_samples(&verify),
+                lower: StageTiming::from_samples(&lower),
+                run,
+            },
+            steps_per_sec,
+        })
+    }
+}
@@ -0,0 +1,161 @@
+//! Run a NIR network in closed loop with a reference environment
+//!
+//! Observations are rate-encoded onto input neuron pairs starting at
+//! `--input-start`; output spikes are counted per action in groups of
+//! `--output-group-size` neurons starting at `--output-start`.
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use shnn_env::{CartPole, CartPoleParams, ClosedLoop, CountDecoder, EpisodeStats, RateEncoder};
+
+use crate::commands::nir::load_program;
+use crate::error::{CliError, CliResult};
+
+/// Run a NIR network against an environment (observe → encode → step → decode → act)
+#[derive(Args, Debug)]
+pub struct RunClosedLoopCommand {
+    /// Textual NIR model (its run op sets dt and the per-episode duration)
+    pub model: PathBuf,
+
+    /// Environment to control
+    #[arg(long = "env", value_enum, default_value = "cartpole")]
+    pub env: EnvKind,
+
+    /// Episodes to run
+    #[arg(long, default_value_t = 1)]
+    pub episodes: usize,
+
+    /// Network steps per environment action
+    #[arg(long, default_value_t = 20)]
+    pub steps_per_action: usize,
+
+    /// Action limit per episode (also bounded by the model's duration)
+    #[arg(long, default_value_t = 500)]
+    pub max_actions: usize,
+
+    /// Seed for environment initial states
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// First input neuron; each observation value uses two (positive, negative)
+    #[arg(long, default_value_t = 0)]
+    pub input_start: u32,
+
+    /// Input rate for an observation at its full scale (Hz)
+    #[arg(long, default_value_t = 1000.0)]
+    pub max_rate_hz: f32,
+
+    /// First output neuron
+    #[arg(long, default_value_t = 8)]
+    pub output_start: u32,
+
+    /// Output neurons per action value
+    #[arg(long, default_value_t = 1)]
+    pub output_group_size: u32,
+
+    /// Write per-episode results as JSON to this path
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Available environments
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvKind {
+    /// Cart-pole balancing: 4 observations (8 input neurons), 2 actions (left, right)
+    Cartpole,
+}
+
+#[derive(Debug, Serialize)]
+struct EpisodeReport {
+    episode: usize,
+    actions: usize,
+    total_reward: f32,
+    done: bool,
+    network_steps: usize,
+    output_spikes: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ClosedLoopReport {
+    env: &'static str,
+    steps_per_action: usize,
+    mean_reward: f32,
+    episodes: Vec<EpisodeReport>,
+}
+
+impl RunClosedLoopCommand {
+    pub async fn execute(self) -> CliResult<()> {
+        if self.episodes == 0 {
+            return Err(CliError::invalid_args("--episodes must be at least 1"));
+        }
+        if !(self.max_rate_hz.is_finite() && self.max_rate_hz > 0.0) {
+            return Err(CliError::invalid_args("--max-rate-hz must be positive"));
+        }
+        let mut program = load_program(&self.model)?;
+        let neurons = program.engine.network().neuron_count() as u64;
+
+        let params = match self.env {
+            EnvKind::Cartpole => CartPoleParams { max_steps: self.max_actions, ..Default::default() },
+        };
+        let encoder = RateEncoder::new(self.input_start, params.observation_scales(), self.max_rate_hz);
+        let decoder = CountDecoder::new(self.output_start, 2, self.output_group_size);
+        let input_end = self.input_start as u64 + encoder.input_count() as u64;
+        let output_end = self.output_start as u64 + 2 * self.output_group_size.max(1) as u64;
+        if input_end > neurons || output_end > neurons {
+            return Err(CliError::invalid_args(format!(
+                "model has {} neurons but the loop uses inputs {}..{} and outputs {}..{}",
+                neurons, self.input_start, input_end, self.output_start, output_end
+            )));
+        }
+        let mut closed_loop = ClosedLoop::new(CartPole::new(params, self.seed), encoder, decoder, self.steps_per_action)
+            .map_err(|e| CliError::invalid_args(e.to_string()))?;
+
+        let mut episodes = Vec::with_capacity(self.episodes);
+        for episode in 1..=self.episodes {
+            let stats = closed_loop
+                .run_episode(&mut program.engine, self.max_actions)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            if !stats.done && stats.actions < self.max_actions {
+                warn!(
+                    "Episode {} stopped after {} actions: the model's duration is too short for --max-actions",
+                    episode, stats.actions
+                );
+            }
+            info!(
+                "Episode {}: {} actions, reward {:.1}{} ({} network steps, {} output spikes)",
+                episode,
+                stats.actions,
+                stats.total_reward,
+                if stats.done { ", done" } else { "" },
+                stats.network_steps,
+                stats.output_spikes
+            );
+            episodes.push(report(episode, stats));
+        }
+        let mean_reward = episodes.iter().map(|e| e.total_reward).sum::<f32>() / episodes.len() as f32;
+        info!("Mean reward over {} episode(s): {:.2}", episodes.len(), mean_reward);
+
+        if let Some(path) = &self.output {
+            let report = ClosedLoopReport { env: "cartpole", steps_per_action: self.steps_per_action, mean_reward, episodes };
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            std::fs::write(path, json)?;
+            info!("Wrote closed-loop results to {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+fn report(episode: usize, stats: EpisodeStats) -> EpisodeReport {
+    EpisodeReport {
+        episode,
+        actions: stats.actions,
+        total_reward: stats.total_reward,
+        done: stats.done,
+        network_steps: stats.network_steps,
+        output_spikes: stats.output_spikes,
+    }
+}
@@ -0,0 +1,90 @@
+//! `snn config get/set`: inspect and edit the layered configuration
+//!
+//! `get` without a key lists every known key with its resolved value and the
+//! layer it came from; `set` writes the workspace `hsnn.toml`, or the user file
+//! with `--user`.
+
+use clap::{Args, Subcommand};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::config::{self, Config, KEYS};
+use crate::error::{CliError, CliResult};
+
+/// Read and write configuration values
+#[derive(Args, Debug)]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    pub sub: ConfigSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigSubcommand {
+    /// Print a resolved value, or every key with its source
+    Get(ConfigGet),
+    /// Store a value in the workspace (default) or user configuration file
+    Set(ConfigSet),
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigGet {
+    /// Dotted key (e.g. viz.port); omit to list all keys
+    pub key: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigSet {
+    /// Dotted key (e.g. viz.port)
+    pub key: String,
+    /// New value
+    pub value: String,
+    /// Write the user configuration file instead of the workspace hsnn.toml
+    #[arg(long)]
+    pub user: bool,
+}
+
+impl ConfigCommand {
+    pub async fn execute(self, workspace: PathBuf, config: Option<PathBuf>) -> CliResult<()> {
+        match self.sub {
+            ConfigSubcommand::Get(cmd) => cmd.execute(&workspace, config.as_deref()),
+            ConfigSubcommand::Set(cmd) => cmd.execute(&workspace, config.as_deref()),
+        }
+    }
+}
+
+impl ConfigGet {
+    fn execute(self, workspace: &Path, user_file: Option<&Path>) -> CliResult<()> {
+        let settings = Config::load(workspace, user_file)?;
+        match &self.key {
+            Some(name) => {
+                let key = config::key(name)?;
+                match settings.get(key.name) {
+                    Some(value) => println!("{}", value),
+                    None => return Err(CliError::config(format!("{} is not set", key.name))),
+                }
+            }
+            None => {
+                println!("{:<12} {:<12} {:<40} {}", "KEY", "VALUE", "SOURCE", "DESCRIPTION");
+                for key in KEYS {
+                    let source = settings.source(key.name).map_or_else(|| "-".to_string(), |s| s.to_string());
+                    println!("{:<12} {:<12} {:<40} {}", key.name, settings.get(key.name).unwrap_or("-"), source, key.doc);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ConfigSet {
+    fn execute(self, workspace: &Path, user_file: Option<&Path>) -> CliResult<()> {
+        let path = match (self.user, user_file) {
+            (true, Some(path)) => path.to_path_buf(),
+            (true, None) => config::user_config_path()
+                .ok_or_else(|| CliError::config("Could not determine config directory"))?,
+            (false, _) => config::workspace_config_path(workspace),
+        };
+        config::set_in_file(&path, &self.key, &self.value)?;
+        info!("Set {} = {} in {}", self.key, self.value, path.display());
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+//! `snn dsl build`: generate textual NIR from a build script
+//!
+//! Build scripts are Rhai programs kept in the workspace next to the NIR they
+//! produce, for modules that are easier to write with loops and functions
+//! than as static text. Scripting is behind the `dsl` feature; see
+//! [`crate::dsl`] for the script API.
+
+use clap::{Args, Subcommand};
+use std::path::{Path, PathBuf};
+
+use crate::error::{CliError, CliResult};
+
+/// Generate NIR from scripts
+#[derive(Args, Debug)]
+pub struct DslCommand {
+    #[command(subcommand)]
+    pub sub: DslSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DslSubcommand {
+    /// Run a build script and write its module as textual NIR
+    Build(DslBuild),
+}
+
+#[derive(Args, Debug)]
+pub struct DslBuild {
+    /// Build script (.rhai), relative to the workspace
+    pub script: PathBuf,
+
+    /// Output NIR path (default: the script path with a .nir extension)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Script constant NAME=VALUE (integers, floats and booleans are typed, anything else is a string); repeatable
+    #[arg(short = 'D', long = "define", value_parser = parse_define)]
+    pub defines: Vec<(String, String)>,
+
+    /// Write the module without verifying it
+    #[arg(long)]
+    pub no_verify: bool,
+}
+
+impl DslCommand {
+    pub async fn execute(self, workspace: PathBuf) -> CliResult<()> {
+        match self.sub {
+            DslSubcommand::Build(cmd) => cmd.execute(&workspace),
+        }
+    }
+}
+
+impl DslBuild {
+    #[cfg(not(feature = "dsl"))]
+    fn execute(self, _workspace: &Path) -> CliResult<()> {
+        Err(CliError::invalid_args("snn was built without the scripting DSL; rebuild with `--features dsl`"))
+    }
+
+    #[cfg(feature = "dsl")]
+    fn execute(self, workspace: &Path) -> CliResult<()> {
+        let script = workspace.join(&self.script);
+        if !script.is_file() {
+            return Err(CliError::missing_resource(format!("build script {}", script.display())));
+        }
+        let defines: Vec<_> = self.defines.iter().map(|(name, raw)| (name.clone(), crate::dsl::define_value(raw))).collect();
+        let module = crate::dsl::build_module(&script, &defines)
+            .map_err(|e| CliError::Script(format!("{}: {}", script.display(), e)))?;
+        if !self.no_verify {
+            shnn_compiler::verify_module(&module).map_err(CliError::compile)?;
+        }
+
+        let output = self.output.map_or_else(|| script.with_extension("nir"), |path| workspace.join(path));
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&output, module.to_text())?;
+        println!("Built {} op(s) from {} -> {}", module.ops.len(), script.display(), output.display());
+        Ok(())
+    }
+}
+
+/// Parse NAME=VALUE with a non-empty identifier name
+fn parse_define(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s.split_once('=').ok_or_else(|| format!("invalid NAME=VALUE: no `=` found in `{s}`"))?;
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!("invalid constant name `{name}`"));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
@@ -1,52 +1,670 @@
 //! Hypergraph database operations
+//!
+//! The database is a FileStore rooted at `<workspace>/data/hypergraph` (override
+//! with `--store`); each generation is a VCSR file.
+//!
+//! Example:
+//!   snn hg create
+//!   snn hg import edges.csv
+//!   snn hg query 3 --direction in
+//!   snn hg info --gen 1
+//!   snn hg stats --json stats.json
+//!   snn hg extract --mask task.vmsk --out sub.vcsr
+//!   snn hg morph --script ops.toml
+//!
+//! Morphology scripts are TOML arrays of ops:
+//!   [[op]]
+//!   kind = "add_vertex"      # id, vertex_type?
+//!   [[op]]
+//!   kind = "add_edge"        # source, target, weight
+//!   # also: remove_vertex (id), remove_edge (source, target),
+//!   #       set_weight (source, target, weight), rewire (source, from, to),
+//!   #       add_hyperedge (vertices, weight)
 
-use clap::Args;
-use std::path::PathBuf;
+use clap::{Args, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use tracing::info;
 
-use crate::error::CliResult;
+use crate::commands::ttr::load_mask;
+use crate::error::{CliError, CliResult};
+use crate::workspace::Workspace;
+
+use shnn_storage::{
+    atomic::write_atomic, vcsr::VCSRSnapshot, FileStore, GenerationId, Histogram, HyperedgeId, HypergraphSnapshot,
+    HypergraphStore, MappedVCSR, Mask, MaskType, MorphologyOp, NeuronId, StructureStats, VertexProperties,
+};
 
 /// Hypergraph database operations
 #[derive(Args, Debug)]
 pub struct HgCommand {
-    /// Database operation
-    #[arg(long, default_value = "info")]
-    pub op: String,
-    
-    /// Generation ID to operate on
+    /// Store directory (defaults to <workspace>/data/hypergraph)
+    #[arg(long, global = true)]
+    pub store: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub sub: HgSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HgSubcommand {
+    /// Create an empty hypergraph store
+    Create(HgCreate),
+    /// Import a graph (VCSR, edge list or CSV) as a new generation
+    Import(HgImport),
+    /// Query incidence of a vertex (targets or sources)
+    Query(HgQuery),
+    /// Print graph statistics for a generation
+    Info(HgInfo),
+    /// Degree distributions, density and hyperedge cardinalities per generation
+    Stats(HgStats),
+    /// Extract the subgraph induced by a vertex mask into a VCSR file
+    Extract(HgExtract),
+    /// Apply a morphology script (growth/pruning/rewiring) as a new generation
+    Morph(HgMorph),
+}
+
+#[derive(Args, Debug)]
+pub struct HgCreate {
+    /// Fail if the store already contains generations
+    #[arg(long)]
+    pub fail_if_exists: bool,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum ImportFormat {
+    /// Detect from magic bytes / file extension
+    Auto,
+    Vcsr,
+    /// Whitespace separated `pre post [weight]` lines
+    Edgelist,
+    /// Comma separated `pre,post[,weight]` rows (optional header)
+    Csv,
+}
+
+#[derive(Args, Debug)]
+pub struct HgImport {
+    /// Input file
+    pub input: PathBuf,
+
+    /// Input format
+    #[arg(long, value_enum, default_value = "auto")]
+    pub format: ImportFormat,
+
+    /// Weight for edges without an explicit weight column
+    #[arg(long, default_value = "1.0")]
+    pub default_weight: f32,
+
+    /// Generation ID to assign (defaults to latest + 1)
     #[arg(long)]
     pub gen: Option<u64>,
-    
-    /// Export format
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum QueryDirection {
+    /// Targets of outgoing edges
+    Out,
+    /// Sources of incoming edges
+    In,
+}
+
+#[derive(Args, Debug)]
+pub struct HgQuery {
+    /// Vertex (neuron) ID
+    pub vertex: u32,
+
+    /// Edge direction
+    #[arg(long, value_enum, default_value = "out")]
+    pub direction: QueryDirection,
+
+    /// Generation ID (defaults to latest)
     #[arg(long)]
-    pub format: Option<String>,
-    
-    /// Output file
-    #[arg(short, long)]
-    pub output: Option<PathBuf>,
-    
-    /// Compact database
+    pub gen: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct HgInfo {
+    /// Generation ID (defaults to latest)
+    #[arg(long)]
+    pub gen: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct HgStats {
+    /// VCSR file to analyze instead of the store
+    pub input: Option<PathBuf>,
+
+    /// Generation ID (defaults to every generation)
+    #[arg(long, conflicts_with = "input")]
+    pub gen: Option<u64>,
+
+    /// Write the report as JSON
     #[arg(long)]
-    pub compact: bool,
+    pub json: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct HgExtract {
+    /// VCSR file to extract from instead of the store
+    pub input: Option<PathBuf>,
+
+    /// Vertex mask (`mask.vmsk` or `collection.vmsk#name`)
+    #[arg(long)]
+    pub mask: String,
+
+    /// Output VCSR file
+    #[arg(long)]
+    pub out: PathBuf,
+
+    /// Generation ID (defaults to latest)
+    #[arg(long, conflicts_with = "input")]
+    pub gen: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct HgMorph {
+    /// TOML script with [[op]] entries
+    #[arg(long)]
+    pub script: PathBuf,
+
+    /// Base generation (defaults to latest)
+    #[arg(long)]
+    pub gen: Option<u64>,
+}
+
+/// Morphology script file
+#[derive(Debug, Deserialize)]
+struct MorphScript {
+    #[serde(default)]
+    op: Vec<MorphStep>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum MorphStep {
+    AddVertex {
+        id: u32,
+        #[serde(default)]
+        vertex_type: u8,
+    },
+    RemoveVertex { id: u32 },
+    AddEdge { source: u32, target: u32, weight: f32 },
+    RemoveEdge { source: u32, target: u32 },
+    SetWeight { source: u32, target: u32, weight: f32 },
+    Rewire { source: u32, from: u32, to: u32 },
+    AddHyperedge { vertices: Vec<u32>, weight: f32 },
+}
+
+impl From<MorphStep> for MorphologyOp {
+    fn from(step: MorphStep) -> Self {
+        let n = NeuronId::new;
+        match step {
+            MorphStep::AddVertex { id, vertex_type } => MorphologyOp::AddVertex {
+                id: n(id),
+                properties: VertexProperties { vertex_type, flags: 0 },
+            },
+            MorphStep::RemoveVertex { id } => MorphologyOp::RemoveVertex { id: n(id) },
+            MorphStep::AddEdge { source, target, weight } => {
+                MorphologyOp::AddEdge { source: n(source), target: n(target), weight }
+            }
+            MorphStep::RemoveEdge { source, target } => {
+                MorphologyOp::RemoveEdge { source: n(source), target: n(target) }
+            }
+            MorphStep::SetWeight { source, target, weight } => {
+                MorphologyOp::SetWeight { source: n(source), target: n(target), weight }
+            }
+            MorphStep::Rewire { source, from, to } => {
+                MorphologyOp::Rewire { source: n(source), from: n(from), to: n(to) }
+            }
+            MorphStep::AddHyperedge { vertices, weight } => MorphologyOp::AddHyperedge {
+                // Ignored by the executor: VCSR edges are addressed by position/endpoints
+                id: HyperedgeId::new(u32::MAX),
+                vertices: vertices.into_iter().map(n).collect(),
+                weight,
+            },
+        }
+    }
 }
 
 impl HgCommand {
     pub async fn execute(
         self,
-        _workspace: PathBuf,
+        workspace: PathBuf,
         _config: Option<PathBuf>,
     ) -> CliResult<()> {
-        info!("Hypergraph database operations coming in Phase 4+");
-        info!("Operation: {}", self.op);
-        
-        if let Some(gen) = self.gen {
-            info!("Generation: {}", gen);
+        let store_dir = self
+            .store
+            .unwrap_or_else(|| Workspace::new(workspace).hypergraph_dir());
+        match self.sub {
+            HgSubcommand::Create(cmd) => cmd.execute(&store_dir),
+            HgSubcommand::Import(cmd) => cmd.execute(&store_dir),
+            HgSubcommand::Query(cmd) => cmd.execute(&store_dir),
+            HgSubcommand::Info(cmd) => cmd.execute(&store_dir),
+            HgSubcommand::Stats(cmd) => cmd.execute(&store_dir),
+            HgSubcommand::Extract(cmd) => cmd.execute(&store_dir),
+            HgSubcommand::Morph(cmd) => cmd.execute(&store_dir),
         }
-        
-        if self.compact {
-            info!("Would compact database");
+    }
+}
+
+impl HgCreate {
+    fn execute(self, store_dir: &Path) -> CliResult<()> {
+        let store = FileStore::new(store_dir)?;
+        let generations = store.list_generations(None, None)?;
+        if self.fail_if_exists && !generations.is_empty() {
+            return Err(CliError::workspace(format!(
+                "Hypergraph store at {} already has {} generation(s)",
+                store_dir.display(),
+                generations.len()
+            )));
+        }
+        println!(
+            "Hypergraph store ready at {} ({} generation(s))",
+            store_dir.display(),
+            generations.len()
+        );
+        Ok(())
+    }
+}
+
+impl HgImport {
+    fn execute(self, store_dir: &Path) -> CliResult<()> {
+        let mut store = open_store(store_dir)?;
+        let snapshot = load_graph(&self.input, &self.format, self.default_weight)?;
+        let generation = self
+            .gen
+            .map(GenerationId::new)
+            .unwrap_or_else(|| store.next_generation());
+        if store.get_snapshot(generation).is_ok() {
+            return Err(CliError::invalid_args(format!(
+                "Generation {} already exists",
+                generation.raw()
+            )));
         }
-        
+
+        let (vertices, edges) = (snapshot.header.num_vertices, snapshot.header.num_incidences);
+        store.add_snapshot(generation, snapshot)?;
+        info!("Imported {} into {}", self.input.display(), store_dir.display());
+        println!(
+            "Imported generation {} ({} vertices, {} edges)",
+            generation.raw(),
+            vertices,
+            edges
+        );
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+impl HgQuery {
+    fn execute(self, store_dir: &Path) -> CliResult<()> {
+        let store = open_store(store_dir)?;
+        let generation = resolve_generation(&store, self.gen)?;
+        let snapshot = store.get_snapshot(generation)?;
+        let vertex = NeuronId::new(self.vertex);
+
+        let results: Vec<(NeuronId, f32)> = match self.direction {
+            QueryDirection::Out => snapshot.neighbors(vertex)?.collect(),
+            QueryDirection::In => snapshot
+                .as_vcsr()
+                .edges()
+                .filter(|(_, target, _)| *target == vertex)
+                .map(|(source, _, weight)| (source, weight))
+                .collect(),
+        };
+
+        let label = match self.direction {
+            QueryDirection::Out => "targets",
+            QueryDirection::In => "sources",
+        };
+        println!(
+            "Vertex {} @ generation {}: {} {}",
+            self.vertex,
+            generation.raw(),
+            results.len(),
+            label
+        );
+        for (neuron, weight) in results {
+            println!("  {}\t{:.6}", neuron.raw(), weight);
+        }
+        Ok(())
+    }
+}
+
+impl HgInfo {
+    fn execute(self, store_dir: &Path) -> CliResult<()> {
+        let store = open_store(store_dir)?;
+        let generations = store.list_generations(None, None)?;
+        println!("Store: {}", store_dir.display());
+        println!(
+            "Generations: {}",
+            generations.iter().map(|g| g.raw().to_string()).collect::<Vec<_>>().join(", ")
+        );
+        if generations.is_empty() {
+            return Ok(());
+        }
+
+        let generation = resolve_generation(&store, self.gen)?;
+        let stats = store.get_snapshot(generation)?.stats();
+        println!("Generation {}:", generation.raw());
+        println!("  neurons:     {}", stats.num_neurons);
+        println!("  hyperedges:  {}", stats.num_hyperedges);
+        println!("  incidences:  {}", stats.num_incidences);
+        println!("  avg degree:  {:.3}", stats.avg_degree);
+        println!("  max degree:  {}", stats.max_degree);
+        println!("  density:     {:.6}", stats.density);
+        Ok(())
+    }
+}
+
+/// Result of `snn hg stats`
+#[derive(Debug, Serialize)]
+pub struct HgStatsReport {
+    /// Store directory or VCSR file
+    pub source: String,
+    pub generations: Vec<GenerationStatsReport>,
+}
+
+/// Per-generation row of [`HgStatsReport`]
+#[derive(Debug, Serialize)]
+pub struct GenerationStatsReport {
+    pub generation: u64,
+    pub vertices: u32,
+    pub removed_vertices: u32,
+    pub isolated_vertices: u32,
+    pub edges: u64,
+    pub self_loops: u64,
+    pub density: f32,
+    pub avg_degree: f32,
+    pub max_out_degree: u32,
+    pub max_in_degree: u32,
+    pub weight_min: Option<f32>,
+    pub weight_max: Option<f32>,
+    pub weight_mean: Option<f64>,
+    pub out_degree: Vec<HistogramBin>,
+    pub in_degree: Vec<HistogramBin>,
+    /// Hyperedges (a source and its targets) per cardinality
+    pub hyperedge_cardinality: Vec<HistogramBin>,
+}
+
+/// `count` items with value `value`
+#[derive(Debug, Serialize)]
+pub struct HistogramBin {
+    pub value: u32,
+    pub count: u64,
+}
+
+impl GenerationStatsReport {
+    fn new(stats: &StructureStats) -> Self {
+        let bins = |histogram: &Histogram| -> Vec<HistogramBin> {
+            histogram.iter().map(|&(value, count)| HistogramBin { value, count }).collect()
+        };
+        Self {
+            generation: stats.summary.generation.raw(),
+            vertices: stats.summary.num_neurons,
+            removed_vertices: stats.removed,
+            isolated_vertices: stats.isolated,
+            edges: stats.edges,
+            self_loops: stats.self_loops,
+            density: stats.summary.density,
+            avg_degree: stats.summary.avg_degree,
+            max_out_degree: stats.summary.max_degree,
+            max_in_degree: stats.max_in_degree,
+            weight_min: stats.weight_range.map(|r| r.0),
+            weight_max: stats.weight_range.map(|r| r.1),
+            weight_mean: stats.mean_weight,
+            out_degree: bins(&stats.out_degree),
+            in_degree: bins(&stats.in_degree),
+            hyperedge_cardinality: bins(&stats.cardinality),
+        }
+    }
+}
+
+impl HgStats {
+    fn execute(self, store_dir: &Path) -> CliResult<()> {
+        let (source, stats) = match &self.input {
+            Some(path) => {
+                let mapped = MappedVCSR::open(path)?;
+                let view = mapped.view()?;
+                view.verify_blocks()?;
+                (path.display().to_string(), vec![StructureStats::of_view(&view)])
+            }
+            None => {
+                let store = open_store(store_dir)?;
+                let generations = match self.gen {
+                    Some(g) => vec![GenerationId::new(g)],
+                    None => store.list_generations(None, None)?,
+                };
+                let mut stats = Vec::with_capacity(generations.len());
+                for generation in generations {
+                    stats.push(StructureStats::of_snapshot(store.get_snapshot(generation)?.as_vcsr()));
+                }
+                (store_dir.display().to_string(), stats)
+            }
+        };
+        let report = HgStatsReport {
+            source,
+            generations: stats.iter().map(GenerationStatsReport::new).collect(),
+        };
+
+        println!("Hypergraph stats: {}", report.source);
+        println!(
+            "  {:>5} {:>9} {:>10} {:>10} {:>8} {:>7} {:>7} {:>8}",
+            "gen", "vertices", "edges", "density", "avg deg", "max out", "max in", "isolated"
+        );
+        for g in &report.generations {
+            println!(
+                "  {:>5} {:>9} {:>10} {:>10.6} {:>8.3} {:>7} {:>7} {:>8}",
+                g.generation, g.vertices, g.edges, g.density, g.avg_degree, g.max_out_degree, g.max_in_degree,
+                g.isolated_vertices
+            );
+        }
+        for (g, stats) in report.generations.iter().zip(&stats) {
+            println!("Generation {}:", g.generation);
+            println!("  out-degree:  {}", log2_bins(&stats.out_degree));
+            println!("  in-degree:   {}", log2_bins(&stats.in_degree));
+            println!("  cardinality: {}", log2_bins(&stats.cardinality));
+            if let (Some((min, max)), Some(mean)) = (stats.weight_range, stats.mean_weight) {
+                println!("  weights:     min {:.4}  max {:.4}  mean {:.4}", min, max, mean);
+            }
+            if g.self_loops > 0 || g.removed_vertices > 0 {
+                println!("  self-loops:  {}  removed vertices: {}", g.self_loops, g.removed_vertices);
+            }
+        }
+
+        if let Some(path) = &self.json {
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            std::fs::write(path, text)?;
+            info!("Wrote hypergraph stats (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+/// Histogram merged into power-of-two bins, e.g. `0: 2  1: 3  2-3: 4  4-7: 1`
+fn log2_bins(histogram: &Histogram) -> String {
+    let mut bins: Vec<(u32, u32, u64)> = Vec::new();
+    for &(value, count) in histogram {
+        let (first, last) = match value {
+            0 => (0, 0),
+            v => {
+                let first = 1u32 << (31 - v.leading_zeros());
+                (first, first + (first - 1))
+            }
+        };
+        match bins.last_mut() {
+            Some(bin) if bin.0 == first => bin.2 += count,
+            _ => bins.push((first, last, count)),
+        }
+    }
+    if bins.is_empty() {
+        return "-".to_string();
+    }
+    bins.iter()
+        .map(|&(first, last, count)| match first == last {
+            true => format!("{}: {}", first, count),
+            false => format!("{}-{}: {}", first, last, count),
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+impl HgExtract {
+    fn execute(self, store_dir: &Path) -> CliResult<()> {
+        let (name, mask) = load_mask(&self.mask)?;
+        if !matches!(mask.mask_type(), MaskType::VertexMask | MaskType::ActivityMask) {
+            return Err(CliError::invalid_args(format!(
+                "mask '{}' is a {:?}; extraction needs a vertex or activity mask",
+                name,
+                mask.mask_type()
+            )));
+        }
+
+        // Mask bits address original vertex IDs
+        let keep = |id: NeuronId| mask.is_active(id.raw());
+        let (source, snapshot) = match &self.input {
+            Some(path) => {
+                let mapped = MappedVCSR::open(path)?;
+                let view = mapped.view()?;
+                view.verify_blocks()?;
+                (view.header, view.to_snapshot().extract(keep))
+            }
+            None => {
+                let store = open_store(store_dir)?;
+                let generation = resolve_generation(&store, self.gen)?;
+                let full = store.get_snapshot(generation)?;
+                (full.as_vcsr().header, full.as_vcsr().extract(keep))
+            }
+        };
+
+        write_atomic(&self.out, &snapshot.to_bytes())?;
+        info!("Wrote subview '{}' to {}", name, self.out.display());
+        println!(
+            "Extracted generation {} with mask '{}': {}/{} vertices, {}/{} edges -> {}",
+            source.generation,
+            name,
+            snapshot.header.num_vertices,
+            source.num_vertices,
+            snapshot.header.num_incidences,
+            source.num_incidences,
+            self.out.display()
+        );
+        Ok(())
+    }
+}
+
+impl HgMorph {
+    fn execute(self, store_dir: &Path) -> CliResult<()> {
+        let mut store = open_store(store_dir)?;
+        let base = resolve_generation(&store, self.gen)?;
+
+        let text = std::fs::read_to_string(&self.script)?;
+        let script: MorphScript = toml::from_str(&text)?;
+        let ops: Vec<MorphologyOp> = script.op.into_iter().map(MorphologyOp::from).collect();
+        if ops.is_empty() {
+            return Err(CliError::invalid_args(format!(
+                "{} contains no [[op]] entries",
+                self.script.display()
+            )));
+        }
+
+        let (generation, report) = store.create_generation_with_report(base, &ops)?;
+        println!(
+            "Applied {} op(s) to generation {} -> generation {}",
+            ops.len(),
+            base.raw(),
+            generation.raw()
+        );
+        println!(
+            "  vertices +{} -{}  edges +{} -{}  weights modified {}",
+            report.vertices_added,
+            report.vertices_removed,
+            report.edges_added,
+            report.edges_removed,
+            report.weights_modified
+        );
+        Ok(())
+    }
+}
+
+fn open_store(store_dir: &Path) -> CliResult<FileStore> {
+    if !store_dir.exists() {
+        return Err(CliError::missing_resource(format!(
+            "No hypergraph store at {} (run `snn hg create` first)",
+            store_dir.display()
+        )));
+    }
+    Ok(FileStore::new(store_dir)?)
+}
+
+fn resolve_generation(store: &FileStore, gen: Option<u64>) -> CliResult<GenerationId> {
+    match gen {
+        Some(g) => Ok(GenerationId::new(g)),
+        None => Ok(store.latest_generation()?),
+    }
+}
+
+// Load a graph file into a finalized VCSR snapshot.
+fn load_graph(path: &Path, format: &ImportFormat, default_weight: f32) -> CliResult<VCSRSnapshot> {
+    let bytes = std::fs::read(path)?;
+    let format = match format {
+        ImportFormat::Auto if bytes.starts_with(&shnn_storage::magic::VCSR) => ImportFormat::Vcsr,
+        ImportFormat::Auto => match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => ImportFormat::Csv,
+            _ => ImportFormat::Edgelist,
+        },
+        other => other.clone(),
+    };
+    if format == ImportFormat::Vcsr {
+        return Ok(VCSRSnapshot::from_bytes(&bytes)?);
+    }
+
+    let text = String::from_utf8(bytes)
+        .map_err(|_| CliError::invalid_args(format!("{} is not valid UTF-8 text", path.display())))?;
+    let mut edges = Vec::new();
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = if format == ImportFormat::Csv {
+            line.split(',').map(str::trim).collect()
+        } else {
+            line.split_whitespace().collect()
+        };
+        let parsed = parse_edge(&fields, default_weight);
+        match parsed {
+            Some(edge) => edges.push(edge),
+            // Tolerate a CSV header row
+            None if format == ImportFormat::Csv && lineno == 0 => continue,
+            None => {
+                return Err(CliError::invalid_args(format!(
+                    "{}:{}: expected `pre post [weight]`, got `{}`",
+                    path.display(),
+                    lineno + 1,
+                    line
+                )))
+            }
+        }
+    }
+
+    let num_vertices = edges
+        .iter()
+        .map(|(pre, post, _): &(NeuronId, NeuronId, f32)| pre.raw().max(post.raw()) + 1)
+        .max()
+        .unwrap_or(0);
+    Ok(VCSRSnapshot::from_edges(GenerationId::INITIAL, num_vertices, 0, &edges)?)
+}
+
+fn parse_edge(fields: &[&str], default_weight: f32) -> Option<(NeuronId, NeuronId, f32)> {
+    if fields.len() < 2 || fields.len() > 3 {
+        return None;
+    }
+    let pre = fields[0].parse::<u32>().ok()?;
+    let post = fields[1].parse::<u32>().ok()?;
+    let weight = match fields.get(2) {
+        Some(w) => w.parse::<f32>().ok()?,
+        None => default_weight,
+    };
+    Some((NeuronId::new(pre), NeuronId::new(post), weight))
+}
@@ -1,10 +1,26 @@
 //! Workspace and data inspection commands
 
 use clap::Args;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
 use tracing::info;
 
-use crate::error::CliResult;
+use shnn_compiler::symbols::resolve_symbols;
+use shnn_compiler::{canonicalize, compile_module, verify_module};
+use shnn_core::connectivity::Topology;
+use shnn_ir::{parse_text, AttributeValue, DialectKey, Module, Quantity};
+use shnn_runtime::{
+    firing, metrics, FiringPatternParams, FiringPatternReport, NeuronId, SNNNetwork, SimilarityParams, SimilarityReport,
+    SpectrumParams, SpikeTrains,
+};
+use shnn_runtime::simulation::StimulusPattern;
+use shnn_storage::inspect::{inspect, ArtifactInfo, ChecksumStatus};
+use shnn_storage::{RateMatrix, SpikeWindow, StorageError, WeightChange};
+
+use crate::commands::nir::load_program;
+use crate::commands::viz::{read_spike_recording, read_weight_changes};
+use crate::error::{CliError, CliResult};
 
 /// Inspect workspace and data
 #[derive(Args, Debug)]
@@ -12,6 +28,9 @@ pub struct InspectCommand {
     /// What to inspect
     #[arg(default_value = "workspace")]
     pub target: String,
+
+    /// Recordings to compare (.vevt or results .json), for `compare a.vevt b.vevt`; the recording for `firing-patterns run.vevt` and `oscillations run.vevt`; the event log for `weight-updates run.vlog`; the model for `nir model.nirt`, the file for `storage file.vcsr`
+    pub paths: Vec<PathBuf>,
     
     /// Show detailed information
     #[arg(short, long)]
@@ -24,6 +43,687 @@ pub struct InspectCommand {
     /// Verify data integrity
     #[arg(long)]
     pub verify: bool,
+
+    /// Model to analyze (textual NIR) for `topology`, recording (.vevt or results .json) for `spikes`
+    #[arg(long)]
+    pub input: Option<PathBuf>,
+
+    /// Write the `topology`, `nir`, `storage`, `spikes`, `compare`, `firing-patterns`, `oscillations` or `weight-updates` report as JSON
+    #[arg(long)]
+    pub json: Option<PathBuf>,
+
+    /// Time range for `spikes`, `compare`, `firing-patterns`, `oscillations` and `weight-updates`, e.g. `1s..2s`, `500ms..` or `..250ms` (default: whole recording)
+    #[arg(long)]
+    pub window: Option<String>,
+
+    /// Bin width for `spikes` and the `compare` rate correlation (e.g. `5ms`)
+    #[arg(long, default_value = "10ms")]
+    pub bin: String,
+
+    /// Inclusive neuron id range for `spikes` and `firing-patterns`, e.g. `0..99` (default: neurons that fired)
+    #[arg(long)]
+    pub neurons: Option<String>,
+
+    /// Merge adjacent bins until at most this many remain
+    #[arg(long)]
+    pub max_bins: Option<usize>,
+
+    /// Write the `spikes` rate matrix (Hz) as CSV
+    #[arg(long)]
+    pub csv: Option<PathBuf>,
+
+    /// Write the `spikes` rate matrix (Hz) as a (neurons, bins) float32 NPY
+    #[arg(long)]
+    pub npy: Option<PathBuf>,
+
+    /// van Rossum kernel time constant for `compare`
+    #[arg(long, default_value = "10ms")]
+    pub tau: String,
+
+    /// Victor–Purpura time scale for `compare` (shift costing as much as a deletion)
+    #[arg(long, default_value = "10ms")]
+    pub vp_timescale: String,
+
+    /// Longest intra-burst inter-spike interval for `firing-patterns`
+    #[arg(long, default_value = "10ms")]
+    pub burst_isi: String,
+
+    /// Population rate sampling interval for `oscillations` (the Nyquist frequency is half its inverse)
+    #[arg(long, default_value = "1ms")]
+    pub sample: String,
+
+    /// Welch segment length for `oscillations` (the frequency resolution is its inverse)
+    #[arg(long, default_value = "256ms")]
+    pub segment: String,
+
+    /// Two inclusive neuron ranges whose rate coherence `oscillations` reports, e.g. `0..79,80..99`
+    #[arg(long)]
+    pub groups: Option<String>,
+}
+
+/// Result of `snn inspect topology`
+#[derive(Debug, Serialize)]
+pub struct TopologyReport {
+    pub model: String,
+    pub nodes: usize,
+    pub edges: usize,
+    pub has_cycles: bool,
+    pub self_loops: usize,
+    /// Strongly connected components (recurrent groups count as one)
+    pub scc_count: usize,
+    pub largest_scc: usize,
+    /// Neurons without incoming synapses
+    pub sources: usize,
+    /// Neurons without outgoing synapses
+    pub sinks: usize,
+    /// Synapses on the longest feedforward path (absent if cyclic)
+    pub longest_path: Option<usize>,
+    /// `in_degree[d]` = neurons with `d` incoming synapses
+    pub in_degree: Vec<usize>,
+    /// `out_degree[d]` = neurons with `d` outgoing synapses
+    pub out_degree: Vec<usize>,
+    /// One example cycle (neuron ids), if any
+    pub cycle: Option<Vec<u32>>,
+    /// Components with more than one neuron
+    pub recurrent_components: Vec<Vec<u32>>,
+}
+
+impl TopologyReport {
+    fn new(model: &Path, topology: &Topology) -> Self {
+        let summary = topology.summary();
+        let raw = |ids: Vec<shnn_core::spike::NeuronId>| ids.into_iter().map(|id| id.raw()).collect::<Vec<u32>>();
+        Self {
+            model: model.display().to_string(),
+            nodes: summary.node_count,
+            edges: summary.edge_count,
+            has_cycles: summary.has_cycles,
+            self_loops: summary.self_loops,
+            scc_count: summary.scc_count,
+            largest_scc: summary.largest_scc,
+            sources: summary.sources,
+            sinks: summary.sinks,
+            longest_path: summary.longest_path,
+            in_degree: summary.degrees.in_degree,
+            out_degree: summary.degrees.out_degree,
+            cycle: topology.find_cycle().map(raw),
+            recurrent_components: topology.strongly_connected_components()
+                .into_iter()
+                .filter(|c| c.len() > 1)
+                .map(raw)
+                .collect(),
+        }
+    }
+}
+
+/// Result of `snn inspect nir`
+#[derive(Debug, Serialize)]
+pub struct NirReport {
+    pub model: String,
+    pub neurons: usize,
+    pub synapses: usize,
+    pub populations: Vec<PopulationReport>,
+    pub projections: Vec<ProjectionReport>,
+    /// `in_degree[d]` = neurons with `d` incoming synapses
+    pub in_degree: Vec<usize>,
+    /// `out_degree[d]` = neurons with `d` outgoing synapses
+    pub out_degree: Vec<usize>,
+    pub delays: Vec<DelayBin>,
+    pub stimuli: Vec<StimulusReport>,
+}
+
+/// Contiguous neuron ids between range boundaries declared in the module
+#[derive(Debug, Serialize)]
+pub struct PopulationReport {
+    pub start: u32,
+    pub end: u32,
+    pub size: usize,
+    /// `lif`, `adex` or `hh`
+    pub model: String,
+    /// Neurons driven by a stimulus
+    pub stimulated: usize,
+}
+
+/// Synapses from one population (index into `populations`) to another
+#[derive(Debug, Serialize)]
+pub struct ProjectionReport {
+    pub source: usize,
+    pub target: usize,
+    pub synapses: usize,
+    /// Synapses over source size × target size
+    pub density: f64,
+    pub weight_min: f32,
+    pub weight_mean: f32,
+    pub weight_max: f32,
+    pub delay_min_ms: f32,
+    pub delay_mean_ms: f32,
+    pub delay_max_ms: f32,
+}
+
+/// Synapses with a delay in `[start_ms, end_ms]` (`start_ms == end_ms` for exact values)
+#[derive(Debug, Serialize)]
+pub struct DelayBin {
+    pub start_ms: f32,
+    pub end_ms: f32,
+    pub count: usize,
+}
+
+/// One stimulus of the lowered program
+#[derive(Debug, Serialize)]
+pub struct StimulusReport {
+    /// `constant`, `poisson`, `spike_train` or `ou_noise`
+    pub kind: String,
+    /// Inclusive target range
+    pub neurons: (u32, u32),
+    pub start_ms: f64,
+    pub end_ms: f64,
+    pub rate_hz: Option<f32>,
+    /// Spikes of a `spike_train`
+    pub events: Option<usize>,
+}
+
+/// Distinct delays listed exactly up to this many; more are binned
+const DELAY_BINS: usize = 20;
+
+impl NirReport {
+    /// Populations are split at every range an op of the source `module` declares
+    /// (layer `in`/`out`, `neurons`, `sources`/`targets`), so they survive the
+    /// canonicalization that expands layers into single synapses
+    fn new(model: &Path, module: &Module, canonical: &Module, network: &SNNNetwork, stimuli: &[StimulusPattern]) -> Self {
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+        for op in &module.ops {
+            for value in op.attrs.values() {
+                if let AttributeValue::RangeU32 { start, end } = value {
+                    ranges.push((*start, *end));
+                }
+            }
+        }
+        let mut ids: Vec<u32> = network.neuron_ids().into_iter().map(|id| id.raw()).collect();
+        ids.sort_unstable();
+        let starts: BTreeSet<u32> = ranges.iter()
+            .flat_map(|&(start, end)| [start, end.saturating_add(1)])
+            .collect();
+        let mut bounds: Vec<(u32, u32)> = Vec::new();
+        for &id in &ids {
+            match bounds.last_mut() {
+                Some(last) if last.1 + 1 == id && !starts.contains(&id) => last.1 = id,
+                _ => bounds.push((id, id)),
+            }
+        }
+        let population_of = |id: u32| bounds.partition_point(|&(_, end)| end < id);
+
+        // OU noise is not a lowered stimulus pattern; it runs for the whole simulation
+        let duration_ms = canonical.ops.iter()
+            .find(|op| op.name == "simulate.run")
+            .and_then(|op| match op.attrs.get("duration") {
+                Some(AttributeValue::DurationNs(ns)) => Some(*ns as f64 / 1e6),
+                _ => None,
+            })
+            .unwrap_or(0.0);
+        let mut stimulus_reports: Vec<StimulusReport> = stimuli.iter().map(StimulusReport::new).collect();
+        for op in canonical.ops.iter().filter(|op| op.dialect == DialectKey::Stimulus && op.name == "ou_noise") {
+            if let Some(AttributeValue::RangeU32 { start, end }) = op.attrs.get("neurons") {
+                stimulus_reports.push(StimulusReport {
+                    kind: "ou_noise".to_string(),
+                    neurons: (*start, *end),
+                    start_ms: 0.0,
+                    end_ms: duration_ms,
+                    rate_hz: None,
+                    events: None,
+                });
+            }
+        }
+
+        let mut populations: Vec<PopulationReport> = bounds.iter()
+            .map(|&(start, end)| PopulationReport {
+                start,
+                end,
+                size: ids.iter().filter(|&&id| (start..=end).contains(&id)).count(),
+                model: model_of(canonical, start).to_string(),
+                stimulated: 0,
+            })
+            .collect();
+        let stimulated: BTreeSet<u32> = stimulus_reports.iter()
+            .flat_map(|s| s.neurons.0..=s.neurons.1)
+            .filter(|id| ids.binary_search(id).is_ok())
+            .collect();
+        for &id in &stimulated {
+            populations[population_of(id)].stimulated += 1;
+        }
+
+        let mut synapses: Vec<(u32, u32, f32, f32)> = network.synapse_connections().into_iter()
+            .map(|(pre, post, weight)| (pre.raw(), post.raw(), weight, network.get_delay(pre, post).unwrap_or(0.0)))
+            .collect();
+        synapses.sort_by_key(|&(pre, post, _, _)| (pre, post));
+        let mut grouped: BTreeMap<(usize, usize), Vec<(f32, f32)>> = BTreeMap::new();
+        for &(pre, post, weight, delay) in &synapses {
+            grouped.entry((population_of(pre), population_of(post))).or_default().push((weight, delay));
+        }
+        let projections = grouped.into_iter()
+            .map(|((source, target), edges)| {
+                let (weights, delays): (Vec<f32>, Vec<f32>) = edges.into_iter().unzip();
+                let pairs = populations[source].size as f64 * populations[target].size as f64;
+                let (weight_min, weight_mean, weight_max) = min_mean_max(&weights);
+                let (delay_min_ms, delay_mean_ms, delay_max_ms) = min_mean_max(&delays);
+                ProjectionReport {
+                    source,
+                    target,
+                    synapses: weights.len(),
+                    density: weights.len() as f64 / pairs.max(1.0),
+                    weight_min,
+                    weight_mean,
+                    weight_max,
+                    delay_min_ms,
+                    delay_mean_ms,
+                    delay_max_ms,
+                }
+            })
+            .collect();
+
+        let summary = Topology::from_edges(
+            network.neuron_ids(),
+            network.synapse_connections().into_iter().map(|(pre, post, _)| (pre, post)),
+        ).summary();
+        let delays: Vec<f32> = synapses.iter().map(|&(_, _, _, delay)| delay).collect();
+        Self {
+            model: model.display().to_string(),
+            neurons: ids.len(),
+            synapses: synapses.len(),
+            populations,
+            projections,
+            in_degree: summary.degrees.in_degree,
+            out_degree: summary.degrees.out_degree,
+            delays: delay_histogram(&delays),
+            stimuli: stimulus_reports,
+        }
+    }
+}
+
+impl StimulusReport {
+    fn new(stimulus: &StimulusPattern) -> Self {
+        let ms = |ns: u64| ns as f64 / 1e6;
+        match stimulus {
+            StimulusPattern::Constant { neuron, start_time, duration, .. } => Self {
+                kind: "constant".to_string(),
+                neurons: (neuron.raw(), neuron.raw()),
+                start_ms: ms(*start_time),
+                end_ms: ms(start_time.saturating_add(*duration)),
+                rate_hz: None,
+                events: None,
+            },
+            StimulusPattern::Poisson { neuron, rate, start_time, duration, .. } => Self {
+                kind: "poisson".to_string(),
+                neurons: (neuron.raw(), neuron.raw()),
+                start_ms: ms(*start_time),
+                end_ms: ms(start_time.saturating_add(*duration)),
+                rate_hz: Some(*rate),
+                events: None,
+            },
+            StimulusPattern::SpikeTrain { neuron, spike_times, .. } => Self {
+                kind: "spike_train".to_string(),
+                neurons: (neuron.raw(), neuron.raw()),
+                start_ms: ms(spike_times.iter().copied().min().unwrap_or(0)),
+                end_ms: ms(spike_times.iter().copied().max().unwrap_or(0)),
+                rate_hz: None,
+                events: Some(spike_times.len()),
+            },
+        }
+    }
+}
+
+/// Neuron model of `id`: the last `neuron.adex`/`neuron.alif`/`neuron.hh` op covering it, else LIF
+fn model_of(module: &Module, id: u32) -> &'static str {
+    module.ops.iter()
+        .filter(|op| op.dialect == DialectKey::Neuron)
+        .filter(|op| match op.attrs.get("neurons") {
+            Some(AttributeValue::RangeU32 { start, end }) => (*start..=*end).contains(&id),
+            _ => op.name == "adex" || op.name == "alif",
+        })
+        .filter_map(|op| match op.name.as_str() {
+            "adex" => Some("adex"),
+            "alif" => Some("alif"),
+            "hh" => Some("hh"),
+            _ => None,
+        })
+        .last()
+        .unwrap_or("lif")
+}
+
+fn min_mean_max(values: &[f32]) -> (f32, f32, f32) {
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    (min, values.iter().sum::<f32>() / values.len().max(1) as f32, max)
+}
+
+/// Exact counts for up to [`DELAY_BINS`] distinct delays (at 1 µs resolution), equal-width bins beyond
+fn delay_histogram(delays: &[f32]) -> Vec<DelayBin> {
+    let mut exact: BTreeMap<i64, usize> = BTreeMap::new();
+    for &delay in delays {
+        *exact.entry((delay as f64 * 1000.0).round() as i64).or_default() += 1;
+    }
+    if exact.len() <= DELAY_BINS {
+        return exact.into_iter()
+            .map(|(us, count)| DelayBin { start_ms: us as f32 / 1000.0, end_ms: us as f32 / 1000.0, count })
+            .collect();
+    }
+    let (min, _, max) = min_mean_max(delays);
+    let width = (max - min) / DELAY_BINS as f32;
+    let mut bins: Vec<DelayBin> = (0..DELAY_BINS)
+        .map(|i| DelayBin { start_ms: min + i as f32 * width, end_ms: min + (i + 1) as f32 * width, count: 0 })
+        .collect();
+    for &delay in delays {
+        let index = (((delay - min) / width) as usize).min(DELAY_BINS - 1);
+        bins[index].count += 1;
+    }
+    bins
+}
+
+/// Result of `snn inspect spikes`
+#[derive(Debug, Serialize)]
+pub struct SpikeWindowReport {
+    pub recording: String,
+    pub window_start_ns: u64,
+    pub window_end_ns: u64,
+    pub bin_ns: u64,
+    pub bins: usize,
+    /// Neuron id of each matrix row
+    pub neurons: Vec<u32>,
+    /// Spikes inside the window (and neuron range)
+    pub spikes: u64,
+    /// Mean rate per neuron over the window (Hz)
+    pub mean_rate_hz: f64,
+    /// Population rate per bin (Hz)
+    pub population_rate_hz: Vec<f32>,
+}
+
+impl SpikeWindowReport {
+    fn new(recording: &Path, matrix: &RateMatrix) -> Self {
+        let window = matrix.window();
+        let rows = matrix.neurons().len().max(1) as f64;
+        Self {
+            recording: recording.display().to_string(),
+            window_start_ns: window.start_ns,
+            window_end_ns: window.end_ns,
+            bin_ns: matrix.bin_ns(),
+            bins: matrix.bins(),
+            neurons: matrix.neurons().to_vec(),
+            spikes: matrix.total_spikes(),
+            mean_rate_hz: matrix.total_spikes() as f64 / rows / (window.duration_ns() as f64 / 1e9),
+            population_rate_hz: matrix.population_rate_hz(),
+        }
+    }
+}
+
+/// Result of `snn inspect compare`
+#[derive(Debug, Serialize)]
+pub struct CompareReport {
+    pub a: String,
+    pub b: String,
+    pub window_start_ns: u64,
+    pub window_end_ns: u64,
+    pub tau_ns: u64,
+    pub vp_timescale_ns: u64,
+    pub bin_ns: u64,
+    /// Means over the neurons that fired in either recording
+    pub van_rossum: f64,
+    pub victor_purpura: f64,
+    pub spike_distance: f64,
+    /// Pearson correlation of the binned population rates (absent if either is constant)
+    pub rate_correlation: Option<f64>,
+    pub neurons: Vec<NeuronCompareReport>,
+}
+
+/// Per-neuron row of [`CompareReport`]
+#[derive(Debug, Serialize)]
+pub struct NeuronCompareReport {
+    pub neuron: u32,
+    pub spikes_a: usize,
+    pub spikes_b: usize,
+    pub van_rossum: f64,
+    pub victor_purpura: f64,
+    pub spike_distance: f64,
+    pub rate_correlation: Option<f64>,
+}
+
+impl CompareReport {
+    fn new(a: &Path, b: &Path, params: &SimilarityParams, report: &SimilarityReport) -> Self {
+        Self {
+            a: a.display().to_string(),
+            b: b.display().to_string(),
+            window_start_ns: report.window.0,
+            window_end_ns: report.window.1,
+            tau_ns: params.tau_ns,
+            vp_timescale_ns: params.vp_timescale_ns,
+            bin_ns: params.bin_ns,
+            van_rossum: report.van_rossum,
+            victor_purpura: report.victor_purpura,
+            spike_distance: report.spike_distance,
+            rate_correlation: report.rate_correlation,
+            neurons: report.neurons.iter()
+                .map(|n| NeuronCompareReport {
+                    neuron: n.neuron.raw(),
+                    spikes_a: n.spikes.0,
+                    spikes_b: n.spikes.1,
+                    van_rossum: n.van_rossum,
+                    victor_purpura: n.victor_purpura,
+                    spike_distance: n.spike_distance,
+                    rate_correlation: n.rate_correlation,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Result of `snn inspect firing-patterns` (also served by `/api/firing-patterns`)
+#[derive(Debug, Serialize)]
+pub struct FiringPatternsReport {
+    pub recording: String,
+    pub window_start_ns: u64,
+    pub window_end_ns: u64,
+    pub burst_isi_ns: u64,
+    /// Neurons per pattern, in silent, tonic, bursting, irregular order
+    pub composition: Vec<PatternShare>,
+    pub neurons: Vec<NeuronFiringReport>,
+}
+
+/// One pattern's share of the population
+#[derive(Debug, Serialize)]
+pub struct PatternShare {
+    pub pattern: &'static str,
+    pub neurons: usize,
+    pub fraction: f64,
+}
+
+/// Per-neuron row of [`FiringPatternsReport`]
+#[derive(Debug, Serialize)]
+pub struct NeuronFiringReport {
+    pub neuron: u32,
+    pub spikes: usize,
+    pub rate_hz: f64,
+    pub cv: Option<f64>,
+    pub cv2: Option<f64>,
+    pub lv: Option<f64>,
+    pub burst_isi_fraction: f64,
+    pub pattern: &'static str,
+}
+
+impl FiringPatternsReport {
+    fn new(recording: &Path, params: &FiringPatternParams, report: &FiringPatternReport) -> Self {
+        Self {
+            recording: recording.display().to_string(),
+            window_start_ns: report.window.0,
+            window_end_ns: report.window.1,
+            burst_isi_ns: params.burst_isi_ns,
+            composition: report.composition().into_iter()
+                .map(|(pattern, neurons, fraction)| PatternShare { pattern: pattern.as_str(), neurons, fraction })
+                .collect(),
+            neurons: report.neurons.iter()
+                .map(|n| NeuronFiringReport {
+                    neuron: n.neuron.raw(),
+                    spikes: n.spikes,
+                    rate_hz: n.rate_hz,
+                    cv: n.cv,
+                    cv2: n.cv2,
+                    lv: n.lv,
+                    burst_isi_fraction: n.burst_isi_fraction,
+                    pattern: n.pattern.as_str(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Classify the neurons of a recording; `neurons` adds non-firing ids and drops the rest
+pub(crate) fn firing_patterns(
+    recording: &Path,
+    spikes: &[(u64, u32)],
+    params: &FiringPatternParams,
+    neurons: Option<(u32, u32)>,
+) -> CliResult<FiringPatternsReport> {
+    let mut trains = spike_trains(spikes);
+    if let Some((first, last)) = neurons {
+        trains.retain(|id, _| (first..=last).contains(&id.raw()));
+        for id in first..=last {
+            trains.entry(NeuronId::new(id)).or_default();
+        }
+    }
+    let report = firing::classify_firing(&trains, params).map_err(|e| CliError::invalid_args(e.to_string()))?;
+    Ok(FiringPatternsReport::new(recording, params, &report))
+}
+
+/// Result of `snn inspect oscillations` (also served by `/api/oscillations`)
+#[derive(Debug, Serialize)]
+pub struct OscillationReport {
+    pub recording: String,
+    pub window_start_ns: u64,
+    pub window_end_ns: u64,
+    pub sample_ns: u64,
+    pub segment_bins: usize,
+    pub segments: usize,
+    /// Synchrony index χ over all neurons (absent if no neuron's count varies)
+    pub synchrony: Option<f64>,
+    /// Strongest non-zero frequency of the population rate
+    pub peak_hz: Option<f64>,
+    pub peak_power: Option<f64>,
+    pub frequencies_hz: Vec<f64>,
+    /// Population rate power spectral density (Hz²/Hz)
+    pub power: Vec<f64>,
+    pub coherence: Option<CoherenceReport>,
+}
+
+/// Rate coherence of two subpopulations in [`OscillationReport`]
+#[derive(Debug, Serialize)]
+pub struct CoherenceReport {
+    pub groups: [(u32, u32); 2],
+    /// Coherence at the population peak frequency
+    pub at_peak: Option<f64>,
+    /// Per frequency, aligned with `frequencies_hz`
+    pub values: Vec<f64>,
+}
+
+/// Spectrum, synchrony and optional group coherence of a recording
+pub(crate) fn oscillations(
+    recording: &Path,
+    spikes: &[(u64, u32)],
+    params: &SpectrumParams,
+    groups: Option<[(u32, u32); 2]>,
+) -> CliResult<OscillationReport> {
+    let invalid = |e: shnn_runtime::RuntimeError| CliError::invalid_args(e.to_string());
+    let trains = spike_trains(spikes);
+    let spectrum = metrics::population_spectrum(&trains, params).map_err(invalid)?;
+    let (start, end) = spectrum.window;
+    let peak = spectrum.peak(f64::MIN_POSITIVE, f64::INFINITY);
+    let coherence = match groups {
+        Some(groups) => {
+            let group = |(first, last): (u32, u32)| -> SpikeTrains {
+                trains.iter().filter(|(id, _)| (first..=last).contains(&id.raw())).map(|(id, t)| (*id, t.clone())).collect()
+            };
+            let params = SpectrumParams { window: Some((start, end)), ..params.clone() };
+            let values = metrics::rate_coherence(&group(groups[0]), &group(groups[1]), &params).map_err(invalid)?;
+            Some(CoherenceReport { groups, at_peak: peak.and_then(|(hz, _)| values.at(hz)), values: values.values })
+        }
+        None => None,
+    };
+    Ok(OscillationReport {
+        recording: recording.display().to_string(),
+        window_start_ns: start,
+        window_end_ns: end,
+        sample_ns: params.bin_ns,
+        segment_bins: params.segment_bins.min((end - start).div_ceil(params.bin_ns) as usize),
+        segments: spectrum.segments,
+        synchrony: metrics::synchrony_index(&trains, start, end, params.bin_ns),
+        peak_hz: peak.map(|p| p.0),
+        peak_power: peak.map(|p| p.1),
+        frequencies_hz: spectrum.frequencies_hz,
+        power: spectrum.values,
+        coherence,
+    })
+}
+
+/// Result of `snn inspect weight-updates` (also served by `/api/weight-updates`)
+#[derive(Debug, Serialize)]
+pub struct WeightUpdateReport {
+    pub log: String,
+    pub window_start_ns: u64,
+    pub window_end_ns: u64,
+    pub updates: usize,
+    /// Updates that raised / lowered a weight
+    pub potentiations: usize,
+    pub depressions: usize,
+    pub net_delta: f64,
+    pub total_abs_delta: f64,
+    /// Per synapse, largest absolute net change first
+    pub synapses: Vec<SynapseUpdateReport>,
+}
+
+/// Per-synapse row of [`WeightUpdateReport`]
+#[derive(Debug, Serialize)]
+pub struct SynapseUpdateReport {
+    pub pre: u32,
+    pub post: u32,
+    pub updates: usize,
+    /// Weight before the first update in the window
+    pub initial_weight: f32,
+    pub final_weight: f32,
+    pub net_delta: f64,
+}
+
+/// Summarize the weight changes of an event log with `start <= time_ns < end`
+pub(crate) fn weight_updates(log: &Path, changes: &[WeightChange], (start, end): (u64, u64)) -> WeightUpdateReport {
+    let changes: Vec<_> = changes.iter().filter(|c| (start..end).contains(&c.time_ns)).collect();
+    let mut synapses: BTreeMap<(u32, u32), SynapseUpdateReport> = BTreeMap::new();
+    for change in &changes {
+        let row = synapses.entry((change.pre, change.post)).or_insert_with(|| SynapseUpdateReport {
+            pre: change.pre,
+            post: change.post,
+            updates: 0,
+            initial_weight: change.weight - change.delta,
+            final_weight: change.weight,
+            net_delta: 0.0,
+        });
+        row.updates += 1;
+        row.final_weight = change.weight;
+        row.net_delta += change.delta as f64;
+    }
+    let mut synapses: Vec<_> = synapses.into_values().collect();
+    synapses.sort_by(|a, b| b.net_delta.abs().total_cmp(&a.net_delta.abs()));
+    WeightUpdateReport {
+        log: log.display().to_string(),
+        window_start_ns: start,
+        window_end_ns: end,
+        updates: changes.len(),
+        potentiations: changes.iter().filter(|c| c.delta > 0.0).count(),
+        depressions: changes.iter().filter(|c| c.delta < 0.0).count(),
+        net_delta: changes.iter().map(|c| c.delta as f64).sum(),
+        total_abs_delta: changes.iter().map(|c| c.delta.abs() as f64).sum(),
+        synapses,
+    }
+}
+
+/// Parse `first..last,first..last` into two neuron ranges
+pub(crate) fn parse_groups(text: &str) -> CliResult<[(u32, u32); 2]> {
+    let bad = || CliError::invalid_args(format!("--groups '{}': expected <first>..<last>,<first>..<last>", text));
+    let (a, b) = text.split_once(',').ok_or_else(bad)?;
+    Ok([parse_neuron_range(a).map_err(|_| bad())?, parse_neuron_range(b).map_err(|_| bad())?])
 }
 
 impl InspectCommand {
@@ -44,6 +744,30 @@ impl InspectCommand {
             "network" => {
                 info!("Network inspection functionality coming soon");
             }
+            "topology" => {
+                self.inspect_topology()?;
+            }
+            "nir" => {
+                self.inspect_nir()?;
+            }
+            "storage" => {
+                self.inspect_storage()?;
+            }
+            "spikes" => {
+                self.inspect_spikes()?;
+            }
+            "compare" => {
+                self.inspect_compare()?;
+            }
+            "firing-patterns" => {
+                self.inspect_firing_patterns()?;
+            }
+            "oscillations" => {
+                self.inspect_oscillations()?;
+            }
+            "weight-updates" => {
+                self.inspect_weight_updates()?;
+            }
             _ => {
                 info!("Unknown inspection target: {}", self.target);
             }
@@ -52,6 +776,427 @@ impl InspectCommand {
         Ok(())
     }
     
+    fn inspect_topology(&self) -> CliResult<()> {
+        let input = self.input.as_ref()
+            .ok_or_else(|| CliError::invalid_args("inspect topology requires --input <model.nirt>"))?;
+        let program = load_program(input)?;
+        let network = program.engine.network();
+        let topology = Topology::from_edges(
+            network.neuron_ids(),
+            network.synapse_connections().into_iter().map(|(pre, post, _)| (pre, post)),
+        );
+        let report = TopologyReport::new(input, &topology);
+
+        println!("Topology: {}", report.model);
+        println!("  neurons: {}  synapses: {}", report.nodes, report.edges);
+        println!("  sources: {}  sinks: {}  self-loops: {}", report.sources, report.sinks, report.self_loops);
+        match report.longest_path {
+            Some(depth) => println!("  acyclic, longest path: {} synapses", depth),
+            None => println!(
+                "  cyclic: {} strongly connected components, largest {}",
+                report.scc_count, report.largest_scc
+            ),
+        }
+        if let Some(cycle) = &report.cycle {
+            println!("  example cycle: {:?}", cycle);
+        }
+        println!("  in-degree histogram:  {:?}", report.in_degree);
+        println!("  out-degree histogram: {:?}", report.out_degree);
+        if self.detailed {
+            for component in &report.recurrent_components {
+                println!("  recurrent component ({} neurons): {:?}", component.len(), component);
+            }
+        }
+
+        if let Some(path) = &self.json {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            std::fs::write(path, text)?;
+            info!("Wrote topology report (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+    
+    fn inspect_nir(&self) -> CliResult<()> {
+        let input = self.paths.first().or(self.input.as_ref())
+            .ok_or_else(|| CliError::invalid_args("inspect nir requires a model: nir <model.nirt>"))?;
+        let text = std::fs::read_to_string(input)?;
+        let module = parse_text(&text).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+        verify_module(&module).map_err(CliError::compile)?;
+        // Populations split the report at the ranges their symbols resolve to
+        let module = resolve_symbols(&module).map_err(CliError::compile)?;
+        let canonical = canonicalize(&module).map_err(CliError::compile)?;
+        let program = compile_module(&canonical).map_err(CliError::compile)?;
+        let report = NirReport::new(input, &module, &canonical, program.engine.network(), &program.stimuli);
+
+        println!("NIR: {}", report.model);
+        println!("  neurons: {}  synapses: {}", report.neurons, report.synapses);
+        println!("  populations:");
+        for (index, p) in report.populations.iter().enumerate() {
+            println!("    #{} {}..{}: {} {} neurons, {} stimulated", index, p.start, p.end, p.size, p.model, p.stimulated);
+        }
+        println!("  projections:");
+        for p in &report.projections {
+            println!(
+                "    #{} -> #{}: {} synapses ({:.1}% dense), weight {:.4} [{:.4}, {:.4}], delay {:.3} ms [{:.3}, {:.3}]",
+                p.source, p.target, p.synapses, p.density * 100.0,
+                p.weight_mean, p.weight_min, p.weight_max,
+                p.delay_mean_ms, p.delay_min_ms, p.delay_max_ms
+            );
+        }
+        println!("  in-degree histogram:  {:?}", report.in_degree);
+        println!("  out-degree histogram: {:?}", report.out_degree);
+        println!("  delay histogram (ms):");
+        for bin in &report.delays {
+            match bin.start_ms == bin.end_ms {
+                true => println!("    {:.3}: {}", bin.start_ms, bin.count),
+                false => println!("    {:.3}..{:.3}: {}", bin.start_ms, bin.end_ms, bin.count),
+            }
+        }
+        let mut kinds: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for s in &report.stimuli {
+            let entry = kinds.entry(s.kind.as_str()).or_default();
+            entry.0 += 1;
+            entry.1 += (s.neurons.1 - s.neurons.0) as usize + 1;
+        }
+        match kinds.is_empty() {
+            true => println!("  stimuli: none"),
+            false => {
+                let parts: Vec<String> = kinds.iter()
+                    .map(|(kind, (ops, neurons))| format!("{} {} ({} neurons)", ops, kind, neurons))
+                    .collect();
+                println!("  stimuli: {}", parts.join(", "));
+            }
+        }
+        if self.detailed {
+            for s in &report.stimuli {
+                let extra = match (s.rate_hz, s.events) {
+                    (Some(rate), _) => format!(" at {} Hz", rate),
+                    (_, Some(events)) => format!(", {} spikes", events),
+                    _ => String::new(),
+                };
+                println!("    {} {}..{}: {:.3}..{:.3} ms{}", s.kind, s.neurons.0, s.neurons.1, s.start_ms, s.end_ms, extra);
+            }
+        }
+
+        if let Some(path) = &self.json {
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            write_file(path, text.as_bytes())?;
+            info!("Wrote NIR report (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn inspect_storage(&self) -> CliResult<()> {
+        let input = self.paths.first().or(self.input.as_ref())
+            .ok_or_else(|| CliError::invalid_args("inspect storage requires a file: storage <file.vevt|.vcsr|.vmsk|.vdlt>"))?;
+        let artifact = inspect(&std::fs::read(input)?)?;
+
+        println!("Storage: {}", input.display());
+        println!("  format: {} v{}  size: {} bytes", artifact.kind, artifact.version, artifact.size_bytes);
+        for (name, value) in &artifact.fields {
+            println!("  {}: {}", name, value);
+        }
+        if let Some((start, end)) = artifact.time_range_ns {
+            println!("  time range: {:.3}..{:.3} ms", start as f64 / 1e6, end as f64 / 1e6);
+        }
+        for check in &artifact.checksums {
+            match check.status {
+                ChecksumStatus::Valid(crc) => println!("  {} checksum: ok ({:08x})", check.name, crc),
+                ChecksumStatus::Mismatch { expected, computed } => println!(
+                    "  {} checksum: MISMATCH (stored {:08x}, computed {:08x})",
+                    check.name, expected, computed
+                ),
+                ChecksumStatus::Absent => println!("  {} checksum: not stored", check.name),
+            }
+        }
+        if let Some(blocks) = artifact.blocks {
+            match artifact.corrupt_blocks.is_empty() {
+                true => println!("  block checksums: {} ok", blocks),
+                false => println!(
+                    "  block checksums: {} of {} CORRUPT at offsets {:?}",
+                    artifact.corrupt_blocks.len(), blocks, artifact.corrupt_blocks
+                ),
+            }
+        }
+        for problem in &artifact.problems {
+            println!("  problem: {}", problem);
+        }
+
+        if let Some(path) = &self.json {
+            let text = serde_json::to_string_pretty(&storage_json(input, &artifact))
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            write_file(path, text.as_bytes())?;
+            info!("Wrote storage report (JSON) to {}", path.display());
+        }
+        if !artifact.is_valid() {
+            let failed = artifact.problems.len()
+                + artifact.corrupt_blocks.len()
+                + artifact.checksums.iter().filter(|c| matches!(c.status, ChecksumStatus::Mismatch { .. })).count();
+            return Err(StorageError::invalid_format(format!(
+                "{}: {} integrity check(s) failed",
+                input.display(),
+                failed
+            )).into());
+        }
+        Ok(())
+    }
+
+    fn inspect_spikes(&self) -> CliResult<()> {
+        let input = self.input.as_ref()
+            .ok_or_else(|| CliError::invalid_args("inspect spikes requires --input <recording.vevt|results.json>"))?;
+        let spikes = read_spike_recording(input)?;
+
+        let last = spikes.iter().map(|&(t, _)| t.saturating_add(1)).max().unwrap_or(1);
+        let (start, end) = self.window_range(last)?;
+        let window = SpikeWindow::new(start, end)
+            .map_err(|e| CliError::invalid_args(format!("--window: {}", e)))?;
+        let bin_ns = required_time_ns("--bin", &self.bin)?;
+        let neurons = self.neurons.as_deref().map(parse_neuron_range).transpose()?;
+
+        let mut matrix = RateMatrix::bin(spikes, window, bin_ns, neurons)
+            .map_err(|e| CliError::invalid_args(e.to_string()))?;
+        if let Some(max_bins) = self.max_bins {
+            if max_bins == 0 {
+                return Err(CliError::invalid_args("--max-bins must be > 0"));
+            }
+            matrix = matrix.downsample(matrix.bins().div_ceil(max_bins))?;
+        }
+        let report = SpikeWindowReport::new(input, &matrix);
+
+        println!("Spikes: {}", report.recording);
+        println!(
+            "  window: {:.3}..{:.3} ms  bin: {:.3} ms ({} bins)",
+            report.window_start_ns as f64 / 1e6,
+            report.window_end_ns as f64 / 1e6,
+            report.bin_ns as f64 / 1e6,
+            report.bins
+        );
+        println!("  neurons: {}  spikes: {}  mean rate: {:.2} Hz", report.neurons.len(), report.spikes, report.mean_rate_hz);
+        let peak = report.population_rate_hz.iter().copied().enumerate().max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((bin, rate)) = peak {
+            println!("  peak population rate: {:.2} Hz at {:.3} ms", rate, matrix.bin_start_ns(bin) as f64 / 1e6);
+        }
+        if self.detailed {
+            println!("  population rate (Hz): {:?}", report.population_rate_hz);
+        }
+
+        if let Some(path) = &self.csv {
+            write_file(path, matrix.to_csv().as_bytes())?;
+            info!("Wrote rate matrix (CSV) to {}", path.display());
+        }
+        if let Some(path) = &self.npy {
+            write_file(path, &matrix.to_npy()?)?;
+            info!("Wrote rate matrix (NPY) to {}", path.display());
+        }
+        if let Some(path) = &self.json {
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            write_file(path, text.as_bytes())?;
+            info!("Wrote spike window report (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn inspect_compare(&self) -> CliResult<()> {
+        let [a, b] = self.paths.as_slice() else {
+            return Err(CliError::invalid_args("inspect compare requires two recordings: compare <a.vevt> <b.vevt>"));
+        };
+        let (spikes_a, spikes_b) = (read_spike_recording(a)?, read_spike_recording(b)?);
+        let last = spikes_a.iter().chain(&spikes_b).map(|&(t, _)| t.saturating_add(1)).max().unwrap_or(1);
+        let params = SimilarityParams {
+            tau_ns: required_time_ns("--tau", &self.tau)?,
+            vp_timescale_ns: required_time_ns("--vp-timescale", &self.vp_timescale)?,
+            bin_ns: required_time_ns("--bin", &self.bin)?,
+            window: Some(self.window_range(last)?),
+        };
+        let similarity = metrics::compare_trains(&spike_trains(&spikes_a), &spike_trains(&spikes_b), &params)
+            .map_err(|e| CliError::invalid_args(e.to_string()))?;
+        let report = CompareReport::new(a, b, &params, &similarity);
+
+        println!("Compare: {} vs {}", report.a, report.b);
+        println!(
+            "  window: {:.3}..{:.3} ms  neurons: {}",
+            report.window_start_ns as f64 / 1e6,
+            report.window_end_ns as f64 / 1e6,
+            report.neurons.len()
+        );
+        println!("  van Rossum (tau {:.3} ms): {:.4}", report.tau_ns as f64 / 1e6, report.van_rossum);
+        println!("  Victor-Purpura (timescale {:.3} ms): {:.4}", report.vp_timescale_ns as f64 / 1e6, report.victor_purpura);
+        println!("  SPIKE-distance: {:.4}", report.spike_distance);
+        match report.rate_correlation {
+            Some(r) => println!("  rate correlation (bin {:.3} ms): {:.4}", report.bin_ns as f64 / 1e6, r),
+            None => println!("  rate correlation: undefined (constant population rate)"),
+        }
+        if self.detailed {
+            for n in &report.neurons {
+                println!(
+                    "  neuron {}: spikes {}/{}  vR {:.4}  VP {:.4}  SPIKE {:.4}",
+                    n.neuron, n.spikes_a, n.spikes_b, n.van_rossum, n.victor_purpura, n.spike_distance
+                );
+            }
+        }
+
+        if let Some(path) = &self.json {
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            write_file(path, text.as_bytes())?;
+            info!("Wrote comparison report (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn inspect_firing_patterns(&self) -> CliResult<()> {
+        let input = self.paths.first().or(self.input.as_ref())
+            .ok_or_else(|| CliError::invalid_args("inspect firing-patterns requires a recording: firing-patterns <run.vevt>"))?;
+        let spikes = read_spike_recording(input)?;
+        let last = spikes.iter().map(|&(t, _)| t.saturating_add(1)).max().unwrap_or(1);
+        let params = FiringPatternParams {
+            burst_isi_ns: required_time_ns("--burst-isi", &self.burst_isi)?,
+            window: Some(self.window_range(last)?),
+            ..FiringPatternParams::default()
+        };
+        let neurons = self.neurons.as_deref().map(parse_neuron_range).transpose()?;
+        let report = firing_patterns(input, &spikes, &params, neurons)?;
+
+        println!("Firing patterns: {}", report.recording);
+        println!(
+            "  window: {:.3}..{:.3} ms  neurons: {}  burst ISI: {:.3} ms",
+            report.window_start_ns as f64 / 1e6,
+            report.window_end_ns as f64 / 1e6,
+            report.neurons.len(),
+            report.burst_isi_ns as f64 / 1e6
+        );
+        for share in &report.composition {
+            println!("  {:<9} {:>6} ({:.1}%)", share.pattern, share.neurons, share.fraction * 100.0);
+        }
+        if self.detailed {
+            let stat = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{:.3}", v));
+            for n in &report.neurons {
+                println!(
+                    "  neuron {}: {} spikes  {:.2} Hz  CV {}  CV2 {}  LV {}  {}",
+                    n.neuron, n.spikes, n.rate_hz, stat(n.cv), stat(n.cv2), stat(n.lv), n.pattern
+                );
+            }
+        }
+
+        if let Some(path) = &self.json {
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            write_file(path, text.as_bytes())?;
+            info!("Wrote firing pattern report (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn inspect_oscillations(&self) -> CliResult<()> {
+        let input = self.paths.first().or(self.input.as_ref())
+            .ok_or_else(|| CliError::invalid_args("inspect oscillations requires a recording: oscillations <run.vevt>"))?;
+        let spikes = read_spike_recording(input)?;
+        let last = spikes.iter().map(|&(t, _)| t.saturating_add(1)).max().unwrap_or(1);
+        let sample_ns = required_time_ns("--sample", &self.sample)?;
+        let params = SpectrumParams {
+            bin_ns: sample_ns,
+            segment_bins: (required_time_ns("--segment", &self.segment)? / sample_ns.max(1)) as usize,
+            window: Some(self.window_range(last)?),
+        };
+        let groups = self.groups.as_deref().map(parse_groups).transpose()?;
+        let report = oscillations(input, &spikes, &params, groups)?;
+
+        println!("Oscillations: {}", report.recording);
+        println!(
+            "  window: {:.3}..{:.3} ms  sample: {:.3} ms  segments: {} x {} bins",
+            report.window_start_ns as f64 / 1e6,
+            report.window_end_ns as f64 / 1e6,
+            report.sample_ns as f64 / 1e6,
+            report.segments,
+            report.segment_bins
+        );
+        match (report.peak_hz, report.peak_power) {
+            (Some(hz), Some(power)) => println!("  peak: {:.2} Hz (power {:.4} Hz²/Hz)", hz, power),
+            _ => println!("  peak: none"),
+        }
+        match report.synchrony {
+            Some(chi) => println!("  synchrony (chi): {:.4}", chi),
+            None => println!("  synchrony (chi): undefined (no activity)"),
+        }
+        if let Some(coherence) = &report.coherence {
+            let [(a0, a1), (b0, b1)] = coherence.groups;
+            match coherence.at_peak {
+                Some(c) => println!("  coherence {}..{} vs {}..{} at peak: {:.4}", a0, a1, b0, b1, c),
+                None => println!("  coherence {}..{} vs {}..{}: no peak", a0, a1, b0, b1),
+            }
+        }
+        if self.detailed {
+            for (hz, power) in report.frequencies_hz.iter().zip(&report.power) {
+                println!("  {:>8.2} Hz  {:.6}", hz, power);
+            }
+        }
+
+        if let Some(path) = &self.json {
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            write_file(path, text.as_bytes())?;
+            info!("Wrote oscillation report (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn inspect_weight_updates(&self) -> CliResult<()> {
+        let input = self.paths.first().or(self.input.as_ref())
+            .ok_or_else(|| CliError::invalid_args("inspect weight-updates requires an event log: weight-updates <run.vlog>"))?;
+        let changes = read_weight_changes(input)?;
+        let last = changes.iter().map(|c| c.time_ns.saturating_add(1)).max().unwrap_or(1);
+        let report = weight_updates(input, &changes, self.window_range(last)?);
+
+        println!("Weight updates: {}", report.log);
+        println!(
+            "  window: {:.3}..{:.3} ms  updates: {} on {} synapses",
+            report.window_start_ns as f64 / 1e6,
+            report.window_end_ns as f64 / 1e6,
+            report.updates,
+            report.synapses.len()
+        );
+        println!(
+            "  potentiation: {}  depression: {}  net dw: {:+.4}  total |dw|: {:.4}",
+            report.potentiations, report.depressions, report.net_delta, report.total_abs_delta
+        );
+        let shown = if self.detailed { report.synapses.len() } else { 10 };
+        for s in report.synapses.iter().take(shown) {
+            println!(
+                "  {} -> {}: {} updates  {:.4} -> {:.4} ({:+.4})",
+                s.pre, s.post, s.updates, s.initial_weight, s.final_weight, s.net_delta
+            );
+        }
+        if report.synapses.len() > shown {
+            println!("  ... {} more synapses (--detailed lists all)", report.synapses.len() - shown);
+        }
+
+        if let Some(path) = &self.json {
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            write_file(path, text.as_bytes())?;
+            info!("Wrote weight update report (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+
+    /// `--window` as `[start, end)` ns, with open ends at 0 and `last`
+    fn window_range(&self, last: u64) -> CliResult<(u64, u64)> {
+        match &self.window {
+            Some(text) => {
+                let (start, end) = text.split_once("..")
+                    .ok_or_else(|| CliError::invalid_args(format!("--window '{}': expected <start>..<end>", text)))?;
+                Ok((parse_time_ns("--window", start)?.unwrap_or(0), parse_time_ns("--window", end)?.unwrap_or(last)))
+            }
+            None => Ok((0, last)),
+        }
+    }
+
     async fn inspect_workspace(&self, workspace: PathBuf) -> CliResult<()> {
         info!("Workspace: {}", workspace.display());
         
@@ -91,4 +1236,76 @@ impl InspectCommand {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Parse a duration such as `5ms` into whole nanoseconds (`None` if blank)
+pub(crate) fn parse_time_ns(flag: &str, text: &str) -> CliResult<Option<u64>> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    text.parse::<Quantity>()
+        .and_then(Quantity::to_ns)
+        .map(Some)
+        .map_err(|e| CliError::invalid_args(format!("{} '{}': {}", flag, text.trim(), e)))
+}
+
+/// Parse a mandatory duration flag into nanoseconds
+pub(crate) fn required_time_ns(flag: &str, text: &str) -> CliResult<u64> {
+    parse_time_ns(flag, text)?
+        .ok_or_else(|| CliError::invalid_args(format!("{} needs a duration such as 5ms", flag)))
+}
+
+/// Group `(time_ns, neuron_id)` pairs into sorted per-neuron trains
+fn spike_trains(spikes: &[(u64, u32)]) -> SpikeTrains {
+    let mut trains = SpikeTrains::new();
+    for &(time_ns, id) in spikes {
+        trains.entry(NeuronId::new(id)).or_default().push(time_ns);
+    }
+    trains.values_mut().for_each(|train| train.sort_unstable());
+    trains
+}
+
+/// Parse an inclusive `first..last` neuron range
+fn parse_neuron_range(text: &str) -> CliResult<(u32, u32)> {
+    let bad = || CliError::invalid_args(format!("--neurons '{}': expected <first>..<last>", text));
+    let (first, last) = text.split_once("..").ok_or_else(bad)?;
+    Ok((first.trim().parse().map_err(|_| bad())?, last.trim().parse().map_err(|_| bad())?))
+}
+
+fn storage_json(path: &Path, artifact: &ArtifactInfo) -> serde_json::Value {
+    let fields: serde_json::Map<String, serde_json::Value> = artifact.fields.iter()
+        .map(|(name, value)| (name.to_string(), serde_json::Value::from(value.as_str())))
+        .collect();
+    let checksums: Vec<serde_json::Value> = artifact.checksums.iter()
+        .map(|check| match check.status {
+            ChecksumStatus::Valid(crc) => serde_json::json!({ "name": check.name, "status": "ok", "value": crc }),
+            ChecksumStatus::Mismatch { expected, computed } => serde_json::json!({
+                "name": check.name, "status": "mismatch", "expected": expected, "computed": computed,
+            }),
+            ChecksumStatus::Absent => serde_json::json!({ "name": check.name, "status": "absent" }),
+        })
+        .collect();
+    serde_json::json!({
+        "file": path.display().to_string(),
+        "format": artifact.kind.name(),
+        "version": artifact.version,
+        "size_bytes": artifact.size_bytes,
+        "fields": fields,
+        "time_range_ns": artifact.time_range_ns,
+        "generation": artifact.generation,
+        "records": artifact.records,
+        "checksums": checksums,
+        "blocks": artifact.blocks,
+        "corrupt_blocks": artifact.corrupt_blocks,
+        "problems": artifact.problems,
+        "valid": artifact.is_valid(),
+    })
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> CliResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
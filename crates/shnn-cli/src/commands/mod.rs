@@ -12,6 +12,12 @@ pub mod hg;
 pub mod inspect;
 pub mod nir;
 pub mod snapshot;
+pub mod bench;
+pub mod closed_loop;
+pub mod serve;
+pub mod validate;
+pub mod config;
+pub mod dsl;
 
 /// hSNN - CLI-first neuromorphic research substrate
 #[derive(Parser, Debug)]
@@ -32,7 +38,7 @@ pub struct HsnnCli {
     #[arg(short, long, global = true)]
     pub workspace: Option<std::path::PathBuf>,
     
-    /// Configuration file path
+    /// User configuration file (replaces <config dir>/hsnn/config.toml)
     #[arg(short, long, global = true)]
     pub config: Option<std::path::PathBuf>,
     
@@ -71,13 +77,36 @@ pub enum Commands {
 
     /// Snapshot export/import of weights
     Snapshot(snapshot::SnapshotCommand),
+
+    /// Run standardized benchmark scenarios and emit a performance report
+    Bench(bench::BenchCommand),
+
+    /// Run a NIR network in closed loop with a reference environment
+    RunClosedLoop(closed_loop::RunClosedLoopCommand),
+
+    /// Run as a daemon serving compile/verify/run RPCs
+    Serve(serve::ServeCommand),
+
+    /// Check the runtime numerics against analytic reference solutions
+    Validate(validate::ValidateCommand),
+
+    /// Read or write layered configuration (hsnn.toml, user config, HSNN_* env)
+    Config(config::ConfigCommand),
+
+    /// Generate NIR from Rhai build scripts (requires the `dsl` feature)
+    Dsl(dsl::DslCommand),
 }
 
 impl HsnnCli {
+    /// Workspace root: --workspace or the current directory
+    pub fn workspace_dir(&self) -> std::path::PathBuf {
+        self.workspace.clone().unwrap_or_else(|| std::env::current_dir().unwrap())
+    }
+
     /// Execute the CLI command
     pub async fn execute(self) -> CliResult<()> {
         // Set up workspace and config
-        let workspace = self.workspace.unwrap_or_else(|| std::env::current_dir().unwrap());
+        let workspace = self.workspace_dir();
         let config = self.config;
         
         // Execute the appropriate subcommand
@@ -89,8 +118,14 @@ impl HsnnCli {
             Commands::Ttr(cmd) => cmd.execute(workspace, config).await,
             Commands::Hg(cmd) => cmd.execute(workspace, config).await,
             Commands::Inspect(cmd) => cmd.execute(workspace, config).await,
-            Commands::Nir(cmd) => cmd.execute().await,
+            Commands::Nir(cmd) => cmd.execute(workspace, config).await,
             Commands::Snapshot(cmd) => cmd.execute(workspace, config).await,
+            Commands::Bench(cmd) => cmd.execute().await,
+            Commands::RunClosedLoop(cmd) => cmd.execute().await,
+            Commands::Serve(cmd) => cmd.execute().await,
+            Commands::Validate(cmd) => cmd.execute().await,
+            Commands::Config(cmd) => cmd.execute(workspace, config).await,
+            Commands::Dsl(cmd) => cmd.execute(workspace).await,
         }
     }
 }
\ No newline at end of file
@@ -2,20 +2,33 @@
 //! and op listing (dialects/ops/versions).
 
 use clap::{Args, Subcommand, ValueEnum};
-use std::path::PathBuf;
-use tracing::info;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::{info, warn};
 use std::fs;
-use shnn_storage::{vevt::{VEVTEvent, encode_vevt}, StreamId, Time as StorageTime};
+use shnn_storage::{atomic::write_atomic, GenerationId};
 
+use crate::cache::ArtifactCache;
+use crate::config::Config;
+use crate::runs::{RunSource, RunStore};
 use crate::error::{CliError, CliResult};
 
 use shnn_ir::{
-    Module, parse_text,
+    AttributeValue, Module, parse_text, substitute_vars,
     lif_neuron_v1, stdp_rule_v1, layer_fully_connected_v1,
-    stimulus_poisson_v1, runtime_simulate_run_v1,
+    stimulus_poisson_v1, runtime_simulate_run_v1, runtime_apply_mask_v1,
 };
 
-use shnn_compiler::{compile_with_passes, verify_module, list_ops};
+use shnn_compiler::{
+    check_module, compile_with_passes, compile_with_passes_profiled, estimate_memory, list_ops, program_shape, CpuTarget,
+    LoweredProgram, Severity,
+};
+use shnn_compiler::cache::{cache_key, compile_cached, compile_recording};
+use shnn_compiler::link::link_modules;
+use shnn_compiler::stability::apply_suggested_dt;
+use shnn_compiler::quantize::{quantization_report, quantize_module, FixedFormat};
+use shnn_runtime::{Profiler, SimulationResult, SpikeEndpoint};
 
 /// NIR-related commands
 #[derive(Args, Debug)]
@@ -34,15 +47,27 @@ pub enum NirSubcommand {
     OpList(NirOpList),
     /// Verify textual NIR file
     Verify(NirVerify),
+    /// Run every NIR file in a directory against checked-in golden outputs
+    Test(NirTest),
+    /// Generate static Rust source for an embedded target
+    Codegen(NirCodegen),
+    /// Quantize to an embedded fixed-point format and report float/fixed divergence
+    Quantize(NirQuantize),
+    /// Link several modules into one through structure.port interfaces
+    Link(NirLink),
 }
 
 /// Compile CLI params to textual NIR (.nirt), without executing
 #[derive(Args, Debug)]
 pub struct NirCompile {
-    /// Output NIR file path (.nirt)
+    /// Output file path (.nirt, or .vcsr with --emit vcsr)
     #[arg(short, long)]
     pub output: PathBuf,
 
+    /// Artifact to emit: textual NIR or the lowered connectivity as a VCSR snapshot
+    #[arg(long, value_enum, default_value = "nirt")]
+    pub emit: EmitFormat,
+
     /// Neuron model
     #[arg(long, default_value = "lif")]
     pub neurons: NeuronType,
@@ -82,6 +107,26 @@ pub struct NirCompile {
     /// Random seed
     #[arg(long)]
     pub seed: Option<u64>,
+
+    /// VMSK mask to attach at run time (runtime.apply_mask@v1)
+    #[arg(long)]
+    pub mask: Option<PathBuf>,
+
+    /// What the mask gates
+    #[arg(long, value_enum, default_value = "activity", requires = "mask")]
+    pub mask_mode: MaskModeArg,
+
+    /// Integrator for membrane dynamics
+    #[arg(long, value_enum, default_value = "euler")]
+    pub integrator: IntegratorArg,
+
+    /// Connectivity backend routing spikes (auto picks by density)
+    #[arg(long, value_enum, default_value = "auto")]
+    pub backend: BackendArg,
+
+    /// Pace the run against wall-clock time (runtime.simulate.run realtime = true)
+    #[arg(long)]
+    pub realtime: bool,
 }
 
 /// Run from textual NIR
@@ -90,13 +135,148 @@ pub struct NirRun {
     /// Input textual NIR file (.nirt)
     pub input: PathBuf,
 
-    /// Output file (JSON or VEVT depending on --spikes-format)
+    /// Output file (JSON, VEVT or VLOG event log depending on --spikes-format)
     #[arg(short, long)]
     pub output: Option<PathBuf>,
 
     /// Spikes export format
     #[arg(long, value_enum, default_value = "json")]
     pub spikes_format: SpikesFormat,
+
+    /// Write post-run synaptic weights as a VCSR snapshot
+    #[arg(long)]
+    pub snapshot_weights: Option<PathBuf>,
+
+    /// Write binned population rates (runtime.monitor_rate@v1) as CSV
+    #[arg(long)]
+    pub rates_csv: Option<PathBuf>,
+
+    /// Write sampled weight trajectories (runtime.monitor_weights@v1) as a binary trace
+    #[arg(long)]
+    pub weights_trace: Option<PathBuf>,
+
+    /// Run the program N times from scratch and require identical spike streams
+    #[arg(long, value_name = "N")]
+    pub audit_determinism: Option<usize>,
+
+    /// Worker threads for the neuron update (default: run.threads config, else one per core)
+    #[arg(long)]
+    pub threads: Option<usize>,
+
+    /// Worker thread counts cycled across audit runs (e.g. 1,2,8)
+    #[arg(long, value_delimiter = ',', requires = "audit_determinism")]
+    pub audit_threads: Vec<usize>,
+
+    /// Print a per-phase timing breakdown (parse, verify, passes, lowering, stepping, io)
+    #[arg(long)]
+    pub timings: bool,
+
+    /// Write phase spans as Chrome trace-event JSON (chrome://tracing, Perfetto)
+    #[arg(long)]
+    pub trace_events: Option<PathBuf>,
+    /// Refuse to start when the estimated peak memory exceeds this budget (MiB)
+    #[arg(long, env = "SNN_MEMORY_BUDGET_MB", default_value_t = 4096)]
+    pub memory_budget_mb: u64,
+
+    /// Run even if the memory estimate exceeds --memory-budget-mb
+    #[arg(long)]
+    pub force: bool,
+
+    /// Pace steps against wall-clock time, overriding the run op's realtime attribute
+    #[arg(long)]
+    pub realtime: bool,
+
+    /// Log every applied plasticity weight change as a weight-update stream of the
+    /// event log (requires --spikes-format vlog and -o)
+    #[arg(long)]
+    pub record_weight_updates: bool,
+
+    /// Lower dt to the suggestion of the dt stability analysis when it under-resolves
+    /// the fastest time constant or shortest delay (see warnings N105/N106)
+    #[arg(long)]
+    pub auto_dt: bool,
+
+    /// Record the run (manifest + results) in the workspace run store, <output.dir>/runs
+    #[arg(long)]
+    pub record: bool,
+
+    /// Lower without reading or writing the workspace artifact cache, <output.dir>/cache
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Inject spikes streamed from udp://bind-addr:port or tcp://producer:port during the run
+    #[arg(long, value_name = "ENDPOINT")]
+    pub spike_input: Option<String>,
+
+    /// Stream output spikes to udp://peer:port or to subscribers of tcp://bind-addr:port (repeatable)
+    #[arg(long, value_name = "ENDPOINT")]
+    pub spike_publish: Vec<String>,
+
+    #[command(flatten)]
+    pub template: TemplateArgs,
+
+    #[command(flatten)]
+    pub warnings: WarningArgs,
+}
+
+/// Bindings for `${name}` placeholders in the NIR text
+#[derive(Args, Debug, Clone, Default)]
+pub struct TemplateArgs {
+    /// Bind a `${KEY}` placeholder in the NIR text before parsing (repeatable; the last binding of a key wins)
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_binding)]
+    pub set: Vec<(String, String)>,
+}
+
+impl TemplateArgs {
+    /// Bound values by name
+    pub fn vars(&self) -> BTreeMap<String, String> {
+        self.set.iter().cloned().collect()
+    }
+}
+
+/// Parse KEY=VALUE
+fn parse_binding(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s.split_once('=').ok_or_else(|| format!("invalid KEY=VALUE: no `=` found in `{s}`"))?;
+    Ok((key.trim().to_string(), value.to_string()))
+}
+
+/// Handling of non-fatal compiler diagnostics
+#[derive(Args, Debug, Clone)]
+pub struct WarningArgs {
+    /// Compiler warnings: hide them, print them, or fail on them
+    #[arg(short = 'W', long = "warnings", value_enum, default_value = "warn")]
+    pub level: WarningLevel,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum WarningLevel {
+    /// Do not print warnings
+    Allow,
+    /// Print warnings and continue
+    Warn,
+    /// Treat warnings as errors
+    Error,
+}
+
+impl WarningArgs {
+    /// Verify and lint `module`, printing warnings per the level; fails on
+    /// verification errors and, with `-W error`, on any warning
+    pub fn check(&self, module: &Module) -> CliResult<()> {
+        let diagnostics = check_module(module);
+        if let Some(error) = diagnostics.with_severity(Severity::Error).next() {
+            return Err(CliError::Generic(anyhow::anyhow!(error.message.clone())));
+        }
+        if self.level != WarningLevel::Allow {
+            for warning in diagnostics.iter() {
+                warn!("{}", warning);
+            }
+        }
+        let count = diagnostics.warning_count();
+        if self.level == WarningLevel::Error && count > 0 {
+            return Err(CliError::Generic(anyhow::anyhow!("{} warning(s) treated as errors (-W error)", count)));
+        }
+        Ok(())
+    }
 }
 
 /// List available ops and versions
@@ -128,19 +308,62 @@ pub enum StimulusType {
     Custom,
 }
 
+#[derive(ValueEnum, Clone, Debug)]
+pub enum MaskModeArg {
+    /// Only masked neurons may spike
+    Activity,
+    /// Only synapses between masked neurons may update
+    Plasticity,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum IntegratorArg {
+    /// Forward Euler
+    Euler,
+    /// Exponential Euler
+    ExponentialEuler,
+    /// Fourth-order Runge–Kutta
+    Rk4,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum BackendArg {
+    /// Dense matrix for dense networks, CSR otherwise
+    Auto,
+    /// Adjacency lists
+    Graph,
+    /// Dense connection bitmap
+    Matrix,
+    /// Compressed sparse rows
+    Sparse,
+    /// Shared target sets
+    Hypergraph,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum EmitFormat {
+    Nirt,
+    Vcsr,
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 pub enum SpikesFormat {
     Json,
     Vevt,
+    Vlog,
 }
 
 impl NirCommand {
-    pub async fn execute(self) -> CliResult<()> {
+    pub async fn execute(self, workspace: PathBuf, config: Option<PathBuf>) -> CliResult<()> {
         match self.sub {
             NirSubcommand::Compile(cmd) => cmd.execute().await,
-            NirSubcommand::Run(cmd) => cmd.execute().await,
+            NirSubcommand::Run(cmd) => cmd.execute(&Config::load(&workspace, config.as_deref())?).await,
             NirSubcommand::OpList(cmd) => cmd.execute().await,
             NirSubcommand::Verify(cmd) => cmd.execute().await,
+            NirSubcommand::Test(cmd) => cmd.execute().await,
+            NirSubcommand::Codegen(cmd) => cmd.execute().await,
+            NirSubcommand::Quantize(cmd) => cmd.execute().await,
+            NirSubcommand::Link(cmd) => cmd.execute().await,
         }
     }
 }
@@ -236,41 +459,181 @@ impl NirCompile {
             _ => return Err(CliError::invalid_args("Only Poisson stimulus supported for NIR compile v0")),
         }
 
-        // runtime.simulate.run@v1
-        module.push(runtime_simulate_run_v1(
+        // runtime.apply_mask@v1
+        if let Some(mask) = &self.mask {
+            let mode = match self.mask_mode {
+                MaskModeArg::Activity => "activity",
+                MaskModeArg::Plasticity => "plasticity",
+            };
+            module.push(runtime_apply_mask_v1(mask.display().to_string(), mode));
+        }
+
+        // runtime.simulate.run@v1 (integrator/backend attrs only when not the default)
+        let mut run = runtime_simulate_run_v1(
             dt_ms,
             total_ms,
             self.record_potentials,
             self.seed,
-        ));
+        );
+        let integrator = match self.integrator {
+            IntegratorArg::Euler => None,
+            IntegratorArg::ExponentialEuler => Some("exponential_euler"),
+            IntegratorArg::Rk4 => Some("rk4"),
+        };
+        if let Some(name) = integrator {
+            run = run.with_attr("integrator", AttributeValue::String(name.into()));
+        }
+        if self.realtime {
+            run = run.with_attr("realtime", AttributeValue::Bool(true));
+        }
+        let backend = match self.backend {
+            BackendArg::Auto => None,
+            BackendArg::Graph => Some("graph"),
+            BackendArg::Matrix => Some("matrix"),
+            BackendArg::Sparse => Some("sparse"),
+            BackendArg::Hypergraph => Some("hypergraph"),
+        };
+        if let Some(name) = backend {
+            run = run.with_attr("backend", AttributeValue::String(name.into()));
+        }
+        module.push(run);
 
-        // Emit textual NIR
-        let text = module.to_text();
         if let Some(parent) = self.output.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        std::fs::write(&self.output, text)?;
-        info!("Emitted NIR to {}", self.output.display());
+        match self.emit {
+            EmitFormat::Nirt => {
+                // Emit textual NIR
+                let text = module.to_text();
+                std::fs::write(&self.output, text)?;
+                info!("Emitted NIR to {}", self.output.display());
+            }
+            EmitFormat::Vcsr => {
+                // Lower and emit the resulting connectivity (generation 0 = pre-run)
+                let program = compile_with_passes(&module).map_err(CliError::compile)?;
+                let snapshot = program.engine.network().to_vcsr_snapshot(GenerationId::new(0))?;
+                write_atomic(&self.output, &snapshot.to_bytes())?;
+                info!(
+                    "Emitted VCSR ({} vertices, {} edges) to {}",
+                    snapshot.header.num_vertices,
+                    snapshot.header.num_incidences,
+                    self.output.display()
+                );
+            }
+        }
         Ok(())
     }
 }
 
 impl NirRun {
-    pub async fn execute(self) -> CliResult<()> {
+    pub async fn execute(self, settings: &Config) -> CliResult<()> {
         // Read textual NIR, parse, verify, compile, and run
-        let text = fs::read_to_string(&self.input)?;
-        let module = parse_text(&text)
-            .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
-
-        verify_module(&module).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
-
         info!("Compiling NIR from {}", self.input.display());
-        let program = compile_with_passes(&module)
-            .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+        let profiling = self.timings || self.trace_events.is_some();
+        let mut profiler = Profiler::new();
+        let (module, bindings) = self.load(&mut profiler)?;
+        let estimated_bytes = self.preflight(&module)?;
+        let module_text = self.record.then(|| module.to_text());
+        let mut program = self.compile(&module, settings, &mut profiler)?;
+        if profiling {
+            program.engine.set_profiler(profiler);
+        }
+        if let Some(endpoint) = &self.spike_input {
+            program.engine.set_spike_source(endpoint.parse::<SpikeEndpoint>()?.open_source()?);
+            info!("Streaming input spikes from {}", endpoint);
+        }
+        for endpoint in &self.spike_publish {
+            program.engine.add_spike_sink(endpoint.parse::<SpikeEndpoint>()?.open_sink()?);
+            info!("Publishing output spikes to {}", endpoint);
+        }
 
         info!("Running simulation...");
-        let result = program.run()?;
+        let threads = self.audit_threads.first().copied()
+            .or(self.threads)
+            .or_else(|| settings.threads());
+        let result = match threads {
+            Some(threads) => program.engine.run_with_threads(threads)?,
+            None => program.engine.run()?,
+        };
         info!("Simulation completed: {} spikes", result.spikes.len());
+        if let Some(report) = &result.realtime {
+            println!(
+                "Realtime: {} steps, {} overruns ({:.1}%), max lag {:.3} ms, mean lag {:.3} ms, final drift {:+.3} ms",
+                report.steps,
+                report.overruns,
+                report.overrun_ratio() * 100.0,
+                report.max_lag_ns as f64 / 1_000_000.0,
+                report.mean_lag_ns as f64 / 1_000_000.0,
+                report.final_drift_ns as f64 / 1_000_000.0,
+            );
+        }
+        let mut profiler = program.engine.take_profiler().unwrap_or_default();
+
+        if let Some(runs) = self.audit_determinism {
+            self.audit(&result, runs)?;
+        }
+
+        // Evaluate test-dialect expectations; failures are reported after outputs are written
+        let outcomes = program.check_expectations(&result);
+        for outcome in &outcomes {
+            println!(
+                "{} {} (observed {})",
+                if outcome.passed { "PASS" } else { "FAIL" },
+                outcome.expectation,
+                outcome.observed
+            );
+        }
+        let failed = outcomes.iter().filter(|o| !o.passed).count();
+
+        let io_start = profiler.begin();
+        // Optionally write post-run weights (generation 1 = after one run)
+        if let Some(path) = &self.snapshot_weights {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let snapshot = program.engine.network().to_vcsr_snapshot(GenerationId::new(1))?;
+            write_atomic(path, &snapshot.to_bytes())?;
+            info!("Wrote weight snapshot (VCSR) to {}", path.display());
+        }
+
+        // Optionally write binned population rates as CSV
+        if let Some(path) = &self.rates_csv {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut csv = String::from("monitor,neuron_start,neuron_end,bin_start_ns,rate_hz\n");
+            for (index, trace) in result.rates.iter().enumerate() {
+                for (bin, rate) in trace.rates_hz.iter().enumerate() {
+                    csv.push_str(&format!(
+                        "{},{},{},{},{}\n",
+                        index,
+                        trace.start.raw(),
+                        trace.end.raw(),
+                        bin as u64 * trace.bin_ns,
+                        rate
+                    ));
+                }
+            }
+            std::fs::write(path, csv)?;
+            info!("Wrote population rates (CSV) to {}", path.display());
+        }
+
+        // Optionally write sampled weight trajectories
+        if let Some(path) = &self.weights_trace {
+            let trace = result.weight_trace.as_ref().ok_or_else(|| {
+                CliError::invalid_args("--weights-trace requires a runtime.monitor_weights@v1 op")
+            })?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            write_atomic(path, &trace.to_bytes())?;
+            info!(
+                "Wrote weight trace ({} synapses x {} samples) to {}",
+                trace.synapses.len(),
+                trace.times_ns.len(),
+                path.display()
+            );
+        }
 
         // Optionally write results in requested format (default JSON)
         if let Some(path) = &self.output {
@@ -279,61 +642,314 @@ impl NirRun {
             }
             match self.spikes_format {
                 SpikesFormat::Json => {
-                    let spike_data: Vec<_> = result.export_spikes().into_iter().map(|(time_ns, neuron_id)| {
-                        serde_json::json!({
-                            "neuron_id": neuron_id,
-                            "time_ns": time_ns,
-                            "time_ms": time_ns as f64 / 1_000_000.0,
-                        })
-                    }).collect();
-
-                    let json = serde_json::json!({
-                        "results": {
-                            "spike_count": result.spikes.len(),
-                            "spikes": spike_data
-                        }
-                    });
-                    std::fs::write(path, serde_json::to_string_pretty(&json)
-                        .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?)?;
+                    std::fs::write(path, result.to_json())?;
                     info!("Wrote results (JSON) to {}", path.display());
                 }
                 SpikesFormat::Vevt => {
-                    // Build VEVT events from exported spikes
-                    let spikes = result.export_spikes();
-                    let (start_ns, end_ns) = if spikes.is_empty() {
-                        (0u64, result.duration_ns)
-                    } else {
-                        let min_t = spikes.iter().map(|(t, _)| *t).min().unwrap();
-                        let max_t = spikes.iter().map(|(t, _)| *t).max().unwrap();
-                        (min_t, max_t)
-                    };
-
-                    let events: Vec<VEVTEvent> = spikes.into_iter().map(|(time_ns, neuron_id)| VEVTEvent {
-                        timestamp: time_ns,
-                        event_type: 0, // Spike
-                        source_id: neuron_id,
-                        target_id: u32::MAX,
-                        payload_size: 0,
-                        reserved: 0,
-                    }).collect();
-
-                    let bytes = encode_vevt(
-                        StreamId::new(1),
-                        StorageTime::from_nanos(start_ns),
-                        StorageTime::from_nanos(end_ns),
-                        &events
-                    ).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
-
-                    std::fs::write(path, &bytes)?;
+                    write_atomic(path, &result.to_vevt()?)?;
                     info!("Wrote results (VEVT) to {}", path.display());
                 }
+                SpikesFormat::Vlog => {
+                    let log = result.to_event_log()?;
+                    write_atomic(path, &log.to_bytes())?;
+                    info!("Wrote event log ({} streams, {} events) to {}", log.streams().count(), log.event_count(), path.display());
+                }
+            }
+        }
+        if let Some(text) = &module_text {
+            let store = RunStore::open(settings);
+            let params = program.engine.params();
+            let source = RunSource { path: &self.input, text, bindings: &bindings };
+            let manifest = store.record(source, params.random_seed, &program.seeds, params.dt_ns, &result)?;
+            info!("Recorded run {} in {}", manifest.id, store.root().display());
+            #[cfg(feature = "index")]
+            if let Err(e) = crate::index::ExperimentIndex::open_workspace(settings)
+                .and_then(|mut index| index.record_run(&manifest, &store.root().join(&manifest.id)))
+            {
+                warn!("Run {} not indexed: {}", manifest.id, e);
+            }
+        }
+        profiler.end("io", io_start);
+
+        match peak_rss_bytes() {
+            Some(rss) => info!(
+                "Memory: peak RSS {:.1} MiB (estimated {:.1} MiB)",
+                mib(rss),
+                mib(estimated_bytes)
+            ),
+            None => info!("Memory: peak RSS unavailable (estimated {:.1} MiB)", mib(estimated_bytes)),
+        }
+
+        if self.timings {
+            print_timings(&profiler);
+        }
+        if let Some(path) = &self.trace_events {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_string_pretty(&chrome_trace(&profiler))
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            std::fs::write(path, json)?;
+            info!("Wrote trace events to {}", path.display());
+        }
+
+        if failed > 0 {
+            return Err(CliError::TestFailed(failed));
+        }
+        Ok(())
+    }
+}
+
+impl NirRun {
+    /// Read the module with `--set` bindings and apply `--realtime`,
+    /// `--record-weight-updates` and `--auto-dt`
+    fn load(&self, profiler: &mut Profiler) -> CliResult<(Module, BTreeMap<String, String>)> {
+        let (mut module, bindings) = read_template(&self.input, &self.template, profiler)?;
+        if self.realtime {
+            force_run_flag(&mut module, "realtime");
+        }
+        if self.record_weight_updates {
+            if !matches!(self.spikes_format, SpikesFormat::Vlog) || self.output.is_none() {
+                return Err(CliError::invalid_args(
+                    "--record-weight-updates writes an event log: add --spikes-format vlog -o <run.vlog>",
+                ));
+            }
+            force_run_flag(&mut module, "record_weight_updates");
+        }
+        if self.auto_dt {
+            if let Some((old, new)) = apply_suggested_dt(&mut module).map_err(CliError::compile)? {
+                info!("--auto-dt: dt lowered from {} ms to {} ms", old as f64 / 1e6, new as f64 / 1e6);
+            }
+        }
+        Ok((module, bindings))
+    }
+
+    /// Lower the module through the workspace artifact cache, printing cache
+    /// stats; modules under `cache.min_synapses` synapses are lowered directly
+    fn compile(&self, module: &Module, settings: &Config, profiler: &mut Profiler) -> CliResult<LoweredProgram> {
+        let synapses = program_shape(module).map_err(CliError::compile)?.synapses;
+        if self.no_cache || synapses < settings.cache_min_synapses() {
+            return compile_with_passes_profiled(module, profiler).map_err(CliError::compile);
+        }
+        let Some(key) = cache_key(module).map_err(CliError::compile)? else {
+            info!("Cache: module reads a hypergraph store or an unreadable file, lowering without the cache");
+            return compile_with_passes_profiled(module, profiler).map_err(CliError::compile);
+        };
+
+        let cache = ArtifactCache::open(settings);
+        let start = Instant::now();
+        let program = match cache.get(&key) {
+            Some((topology, bytes)) => {
+                let program = compile_cached(module, CpuTarget, &topology, profiler).map_err(CliError::compile)?;
+                println!(
+                    "Cache: hit {} ({} neurons, {} synapses, {:.1} MiB), lowered in {:.1} ms",
+                    key,
+                    topology.neurons.len(),
+                    topology.synapses.len(),
+                    mib(bytes),
+                    start.elapsed().as_secs_f64() * 1e3,
+                );
+                program
+            }
+            None => {
+                let (program, topology) = compile_recording(module, CpuTarget, profiler).map_err(CliError::compile)?;
+                let bytes = cache.put(&key, &topology)?;
+                println!(
+                    "Cache: miss {}, lowered in {:.1} ms, stored {} neurons, {} synapses ({:.1} MiB)",
+                    key,
+                    start.elapsed().as_secs_f64() * 1e3,
+                    topology.neurons.len(),
+                    topology.synapses.len(),
+                    mib(bytes),
+                );
+                program
+            }
+        };
+        let stats = cache.stats();
+        println!("Cache: {} artifact(s), {:.1} MiB in {}", stats.entries, mib(stats.bytes), cache.root().display());
+        Ok(program)
+    }
+
+    /// Verify the module and check its memory estimate against the budget;
+    /// returns the estimate in bytes
+    fn preflight(&self, module: &Module) -> CliResult<u64> {
+        self.warnings.check(module)?;
+        let estimate = estimate_memory(module).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+        let total = estimate.total();
+        info!(
+            "Estimated peak memory: {:.1} MiB (neurons {:.1}, synapses {:.1}, spike buffers {:.1}, recordings {:.1}, lowering {:.1})",
+            mib(total),
+            mib(estimate.neurons),
+            mib(estimate.synapses),
+            mib(estimate.spike_buffers),
+            mib(estimate.recordings),
+            mib(estimate.lowering),
+        );
+        if total > self.memory_budget_mb.saturating_mul(1 << 20) {
+            let message = format!(
+                "estimated peak memory {:.1} MiB exceeds the {} MiB budget (--memory-budget-mb / SNN_MEMORY_BUDGET_MB)",
+                mib(total),
+                self.memory_budget_mb
+            );
+            if !self.force {
+                return Err(CliError::invalid_args(format!("{}; pass --force to run anyway", message)));
             }
+            warn!("{}; continuing because of --force", message);
         }
+        Ok(total)
+    }
+
+    /// Repeat the run `runs - 1` more times from a freshly lowered program and
+    /// compare each spike stream with the reference `result`
+    fn audit(&self, result: &SimulationResult, runs: usize) -> CliResult<()> {
+        if runs < 2 {
+            return Err(CliError::invalid_args("--audit-determinism needs at least 2 runs"));
+        }
+        let threads_for = |run: usize| match self.audit_threads.is_empty() {
+            true => None,
+            false => Some(self.audit_threads[run % self.audit_threads.len()]),
+        };
+        let label = |threads: Option<usize>| threads.map_or("default".to_string(), |t| t.to_string());
 
+        for run in 1..runs {
+            let threads = threads_for(run);
+            let (module, _) = self.load(&mut Profiler::new())?;
+            let mut program = compile_with_passes(&module).map_err(CliError::compile)?;
+            let rerun = match threads {
+                Some(t) => program.engine.run_with_threads(t)?,
+                None => program.engine.run()?,
+            };
+            if let Some(divergence) = result.first_divergence(&rerun) {
+                let event = |e: Option<(u64, u32)>| {
+                    e.map_or("<none>".to_string(), |(t, n)| format!("neuron {} at {} ns", n, t))
+                };
+                println!(
+                    "Determinism audit FAILED: run {} (threads {}) diverged from run 0 (threads {}) at spike #{}: expected {}, got {}",
+                    run,
+                    label(threads),
+                    label(threads_for(0)),
+                    divergence.index,
+                    event(divergence.expected),
+                    event(divergence.actual),
+                );
+                return Err(CliError::TestFailed(1));
+            }
+        }
+        println!(
+            "Determinism audit OK: {} runs produced identical spike streams ({} spikes, hash {:016x})",
+            runs,
+            result.spikes.len(),
+            result.spike_hash()
+        );
         Ok(())
     }
 }
 
+/// Parse, verify and lower a textual NIR file
+pub(crate) fn load_program(path: &Path) -> CliResult<LoweredProgram> {
+    let module = read_module(path, &mut Profiler::new())?;
+    compile_with_passes(&module).map_err(CliError::compile)
+}
+
+/// [`load_program`] with wall-clock pacing switched on, for interactive runs
+pub(crate) fn load_paced_program(path: &Path) -> CliResult<LoweredProgram> {
+    let mut module = read_module(path, &mut Profiler::new())?;
+    force_run_flag(&mut module, "realtime");
+    compile_with_passes(&module).map_err(CliError::compile)
+}
+
+/// Set the Bool attribute `key` on every `simulate.run` op
+fn force_run_flag(module: &mut Module, key: &str) {
+    for op in module.ops.iter_mut().filter(|op| op.name == "simulate.run") {
+        op.attrs.insert(key.into(), AttributeValue::Bool(true));
+    }
+}
+
+/// Read and parse a textual NIR file, recording `io` and `parse` spans
+fn read_module(path: &Path, profiler: &mut Profiler) -> CliResult<Module> {
+    read_template(path, &TemplateArgs::default(), profiler).map(|(module, _)| module)
+}
+
+/// [`read_module`] resolving `${name}` placeholders first; also returns the
+/// value each referenced name resolved to
+fn read_template(path: &Path, template: &TemplateArgs, profiler: &mut Profiler) -> CliResult<(Module, BTreeMap<String, String>)> {
+    let text = profiler.time("io", || fs::read_to_string(path))?;
+    let vars = template.vars();
+    let (text, bindings) = substitute_vars(&text, &vars)
+        .map_err(|e| CliError::invalid_args(format!("{}: {} (bind it with --set KEY=VALUE)", path.display(), e)))?;
+    for key in vars.keys().filter(|key| !bindings.contains_key(*key)) {
+        warn!("--set {} is not referenced by {}", key, path.display());
+    }
+    let module = profiler.time("parse", || parse_text(&text)).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+    Ok((module, bindings))
+}
+
+fn mib(bytes: u64) -> f64 {
+    bytes as f64 / (1u64 << 20) as f64
+}
+
+/// Peak resident set size of this process (Linux `VmHWM`), if available
+fn peak_rss_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Print phase totals; `parent.child` sub-phases are indented under their parent
+fn print_timings(profiler: &Profiler) {
+    let wall: u64 = profiler.totals().iter()
+        .filter(|t| !t.name.contains('.'))
+        .map(|t| t.total_ns)
+        .sum();
+    println!("{:<22} {:>12} {:>7} {:>8}", "PHASE", "TIME ms", "%", "COUNT");
+    let mut totals: Vec<_> = profiler.totals().iter().collect();
+    // Keep first-seen order of top-level phases, with sub-phases following their parent
+    let parent_rank = |name: &str| {
+        let parent = name.split('.').next().unwrap_or(name);
+        profiler.totals().iter().position(|t| t.name == parent).unwrap_or(usize::MAX)
+    };
+    totals.sort_by_key(|t| (parent_rank(t.name), t.name.contains('.')));
+    for total in totals {
+        let label = match total.name.split_once('.') {
+            Some((_, child)) => format!("  {}", child),
+            None => total.name.to_string(),
+        };
+        println!(
+            "{:<22} {:>12.3} {:>6.1}% {:>8}",
+            label,
+            total.total_ns as f64 / 1_000_000.0,
+            if wall > 0 { total.total_ns as f64 * 100.0 / wall as f64 } else { 0.0 },
+            total.count
+        );
+    }
+    println!("{:<22} {:>12.3}", "total", wall as f64 / 1_000_000.0);
+}
+
+/// Chrome trace-event JSON: one complete ("X") event per span; per-step
+/// sub-phase totals are attached as args of their parent span
+fn chrome_trace(profiler: &Profiler) -> serde_json::Value {
+    let events: Vec<_> = profiler.spans().iter().map(|span| {
+        let prefix = format!("{}.", span.name);
+        let args: serde_json::Map<String, serde_json::Value> = profiler.totals().iter()
+            .filter_map(|t| t.name.strip_prefix(&prefix).map(|child| {
+                (format!("{}_ms", child), serde_json::json!(t.total_ns as f64 / 1_000_000.0))
+            }))
+            .collect();
+        serde_json::json!({
+            "name": span.name,
+            "cat": "snn",
+            "ph": "X",
+            "ts": span.start_ns as f64 / 1_000.0,
+            "dur": span.duration_ns as f64 / 1_000.0,
+            "pid": 1,
+            "tid": 1,
+            "args": args,
+        })
+    }).collect();
+    serde_json::json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+}
+
 impl NirOpList {
     pub async fn execute(self) -> CliResult<()> {
         let ops = list_ops();
@@ -353,7 +969,10 @@ impl NirOpList {
                     println!("  - {}@v{} {{", op.name, op.version);
                     for attr in op.attrs {
                         let required = if attr.required { "" } else { "?" };
-                        println!("    {}{}: {} // {}", attr.name, required, attr.kind.name(), attr.doc);
+                        let default = attr.default
+                            .map(|d| format!(" = {}", d.to_value(attr.kind)))
+                            .unwrap_or_default();
+                        println!("    {}{}: {}{} // {}", attr.name, required, attr.kind.name(), default, attr.doc);
                     }
                     println!("  }}");
                 } else {
@@ -375,6 +994,8 @@ impl NirOpList {
             println!("- Weight: dimensionless synaptic weight");
             println!("- RangeU32: inclusive start..end range");
             println!("- NeuronRef: reference to neuron by ID (%nX format in textual NIR)");
+            println!("- %name, %name[i], %name[a..b]: neurons of a structure.population, for a NeuronRef or RangeU32");
+            println!("- List: per-element values [a, b, c], each in its attribute's units");
         }
 
         Ok(())
@@ -388,16 +1009,378 @@ impl NirOpList {
 pub struct NirVerify {
     /// Input textual NIR file (.nirt)
     pub input: std::path::PathBuf,
+
+    #[command(flatten)]
+    pub template: TemplateArgs,
+
+    #[command(flatten)]
+    pub warnings: WarningArgs,
 }
 
 impl NirVerify {
     pub async fn execute(self) -> crate::error::CliResult<()> {
-        let text = std::fs::read_to_string(&self.input)?;
-        let module = shnn_ir::parse_text(&text)
-            .map_err(|e| crate::error::CliError::Generic(anyhow::anyhow!(e)))?;
-        shnn_compiler::verify_module(&module)
-            .map_err(|e| crate::error::CliError::Generic(anyhow::anyhow!(e)))?;
+        let (module, _) = read_template(&self.input, &self.template, &mut Profiler::new())?;
+        self.warnings.check(&module)?;
         println!("Verification OK: {}", self.input.display());
         Ok(())
     }
 }
+
+// --- NirTest command (golden-output regression harness) ---
+
+/// Run every .nir/.nirt file in a directory and compare against `<stem>.golden.json`
+#[derive(clap::Args, Debug)]
+pub struct NirTest {
+    /// Directory containing NIR programs and their golden files
+    pub dir: PathBuf,
+
+    /// How results are compared with the golden files
+    #[arg(long, value_enum, default_value = "exact")]
+    pub tolerance: ToleranceMode,
+
+    /// Relative tolerance for --tolerance metrics
+    #[arg(long, default_value = "0.05")]
+    pub rel_tol: f64,
+
+    /// Write golden files from the current results instead of comparing
+    #[arg(long)]
+    pub update: bool,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum ToleranceMode {
+    /// Spike count and spike-stream hash must match exactly
+    Exact,
+    /// Spike count, mean rate and monitored rates within --rel-tol
+    Metrics,
+}
+
+/// Summary of one run, stored as `<stem>.golden.json`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct GoldenOutput {
+    spike_count: usize,
+    /// FNV-1a hash of the time-sorted spike stream (hex)
+    spike_hash: String,
+    mean_rate_hz: f64,
+    /// Mean rate of each runtime.monitor_rate@v1 trace (Hz)
+    #[serde(default)]
+    monitor_mean_hz: Vec<f64>,
+}
+
+impl GoldenOutput {
+    fn from_result(result: &SimulationResult) -> Self {
+        Self {
+            spike_count: result.spikes.len(),
+            spike_hash: format!("{:016x}", result.spike_hash()),
+            mean_rate_hz: result.average_firing_rate() as f64,
+            monitor_mean_hz: result.rates.iter().map(|t| t.stats.mean_hz as f64).collect(),
+        }
+    }
+
+    /// First mismatch against `golden`, if any
+    fn compare(&self, golden: &GoldenOutput, mode: &ToleranceMode, rel_tol: f64) -> Option<String> {
+        let close = |a: f64, b: f64| (a - b).abs() <= rel_tol * b.abs().max(f64::EPSILON);
+        match mode {
+            ToleranceMode::Exact => {
+                if self.spike_count != golden.spike_count {
+                    Some(format!("spike_count {} != {}", self.spike_count, golden.spike_count))
+                } else if self.spike_hash != golden.spike_hash {
+                    Some(format!("spike_hash {} != {}", self.spike_hash, golden.spike_hash))
+                } else {
+                    None
+                }
+            }
+            ToleranceMode::Metrics => {
+                if !close(self.spike_count as f64, golden.spike_count as f64) {
+                    return Some(format!("spike_count {} vs {}", self.spike_count, golden.spike_count));
+                }
+                if !close(self.mean_rate_hz, golden.mean_rate_hz) {
+                    return Some(format!("mean_rate_hz {:.3} vs {:.3}", self.mean_rate_hz, golden.mean_rate_hz));
+                }
+                if self.monitor_mean_hz.len() != golden.monitor_mean_hz.len() {
+                    return Some(format!(
+                        "{} rate monitors vs {}",
+                        self.monitor_mean_hz.len(),
+                        golden.monitor_mean_hz.len()
+                    ));
+                }
+                self.monitor_mean_hz
+                    .iter()
+                    .zip(&golden.monitor_mean_hz)
+                    .position(|(&a, &b)| !close(a, b))
+                    .map(|i| format!("monitor {} mean_hz {:.3} vs {:.3}", i, self.monitor_mean_hz[i], golden.monitor_mean_hz[i]))
+            }
+        }
+    }
+}
+
+impl NirTest {
+    pub async fn execute(self) -> CliResult<()> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("nir" | "nirt")))
+            .collect();
+        files.sort();
+        if files.is_empty() {
+            return Err(CliError::missing_resource(format!("no .nir/.nirt files in {}", self.dir.display())));
+        }
+
+        let mut rows = Vec::with_capacity(files.len());
+        for path in &files {
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let golden_path = path.with_extension("golden.json");
+            let (status, spikes, detail) = match self.check_file(path, &golden_path) {
+                Ok((spikes, None)) => (if self.update { "UPDATED" } else { "PASS" }, spikes.to_string(), String::new()),
+                Ok((spikes, Some(detail))) => ("FAIL", spikes.to_string(), detail),
+                Err(e) => ("ERROR", "-".to_string(), e.to_string()),
+            };
+            rows.push((name, status, spikes, detail));
+        }
+
+        let width = rows.iter().map(|r| r.0.len()).max().unwrap_or(4).max(4);
+        println!("{:<width$}  {:<7}  {:>8}  DETAIL", "FILE", "STATUS", "SPIKES", width = width);
+        for (name, status, spikes, detail) in &rows {
+            println!("{:<width$}  {:<7}  {:>8}  {}", name, status, spikes, detail, width = width);
+        }
+        let failed = rows.iter().filter(|r| r.1 == "FAIL" || r.1 == "ERROR").count();
+        println!("{} passed, {} failed", rows.len() - failed, failed);
+
+        if failed > 0 {
+            return Err(CliError::TestFailed(failed));
+        }
+        Ok(())
+    }
+
+    /// Run one program; returns its spike count and the first mismatch (or failed expectation)
+    fn check_file(&self, path: &Path, golden_path: &Path) -> CliResult<(usize, Option<String>)> {
+        let mut program = load_program(path)?;
+        let result = program.engine.run()?;
+        let actual = GoldenOutput::from_result(&result);
+
+        let failed_expectation = program
+            .check_expectations(&result)
+            .into_iter()
+            .find(|o| !o.passed)
+            .map(|o| format!("expectation {} (observed {})", o.expectation, o.observed));
+
+        if self.update {
+            let json = serde_json::to_string_pretty(&actual)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            fs::write(golden_path, json)?;
+            return Ok((actual.spike_count, failed_expectation));
+        }
+        if !golden_path.exists() {
+            return Ok((actual.spike_count, Some("missing golden file (run with --update)".into())));
+        }
+        let golden: GoldenOutput = serde_json::from_str(&fs::read_to_string(golden_path)?)
+            .map_err(|e| CliError::Generic(anyhow::anyhow!("{}: {}", golden_path.display(), e)))?;
+        let mismatch = actual.compare(&golden, &self.tolerance, self.rel_tol);
+        Ok((actual.spike_count, mismatch.or(failed_expectation)))
+    }
+}
+
+// --- NirCodegen command (static embedded code generation) ---
+
+/// Emit Rust source instantiating the compiled network on an embedded target
+#[derive(clap::Args, Debug)]
+pub struct NirCodegen {
+    /// Input textual NIR file (.nirt)
+    pub input: PathBuf,
+
+    /// Embedded target crate
+    #[arg(long, value_enum, default_value = "micro")]
+    pub target: CodegenTarget,
+
+    /// Output Rust file (stdout if omitted)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub template: TemplateArgs,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum CodegenTarget {
+    /// shnn-micro `MicroNetwork<N, C>` with Q15.16 tables
+    Micro,
+}
+
+impl NirCodegen {
+    pub async fn execute(self) -> CliResult<()> {
+        let (module, _) = read_template(&self.input, &self.template, &mut Profiler::new())?;
+        let source_name = self.input.file_name()
+            .map_or_else(|| self.input.display().to_string(), |name| name.to_string_lossy().into_owned());
+        let source = match self.target {
+            CodegenTarget::Micro => shnn_compiler::codegen::emit_micro(&module, &source_name),
+        }
+        .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+
+        match &self.output {
+            Some(path) => {
+                fs::write(path, &source)?;
+                println!("Wrote {}", path.display());
+            }
+            None => print!("{}", source),
+        }
+        Ok(())
+    }
+}
+
+// --- NirQuantize command (fixed-point accuracy report) ---
+
+/// Quantize weights and neuron parameters, then compare float and fixed-point runs
+#[derive(clap::Args, Debug)]
+pub struct NirQuantize {
+    /// Input textual NIR file (.nirt)
+    pub input: PathBuf,
+
+    /// Fixed-point format of the target
+    #[arg(long, value_enum, default_value = "q15-16")]
+    pub format: FixedFormatArg,
+
+    /// Write the quantized module (.nirt)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Write the divergence report as JSON
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub template: TemplateArgs,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum FixedFormatArg {
+    /// shnn-micro Q15.16 (potentials in V, refractory in whole ms)
+    #[value(name = "q15-16")]
+    Q15_16,
+    /// shnn-embedded Q16.16 (potentials in mV, times in s)
+    #[value(name = "q16-16")]
+    Q16_16,
+}
+
+impl From<FixedFormatArg> for FixedFormat {
+    fn from(arg: FixedFormatArg) -> Self {
+        match arg {
+            FixedFormatArg::Q15_16 => FixedFormat::Q15_16,
+            FixedFormatArg::Q16_16 => FixedFormat::Q16_16,
+        }
+    }
+}
+
+impl NirQuantize {
+    pub async fn execute(self) -> CliResult<()> {
+        let (module, _) = read_template(&self.input, &self.template, &mut Profiler::new())?;
+        let format = FixedFormat::from(self.format);
+        let compiler_err = |e: shnn_compiler::CompilerError| CliError::Generic(anyhow::anyhow!(e));
+
+        let report = quantization_report(&module, format).map_err(compiler_err)?;
+        println!("Quantized {} to {} ({})", self.input.display(), format, format.target());
+        println!("  spikes: float {}, fixed {} (rate error {:.2}%)", report.float_spikes, report.fixed_spikes, report.rate_error * 100.0);
+        println!(
+            "  spike-time jitter: mean {:.3} ms, max {:.3} ms over {} matched spikes; {} unmatched",
+            report.mean_jitter_ns / 1_000_000.0,
+            report.max_jitter_ns as f64 / 1_000_000.0,
+            report.matched_spikes,
+            report.unmatched_spikes
+        );
+        println!("  max weight error: {:e}", report.max_weight_error);
+
+        if let Some(path) = &self.output {
+            let quantized = quantize_module(&module, format).map_err(compiler_err)?;
+            fs::write(path, quantized.to_text())?;
+            println!("Wrote {}", path.display());
+        }
+        if let Some(path) = &self.report {
+            let json = serde_json::json!({
+                "format": format.name(),
+                "target": format.target(),
+                "float_spikes": report.float_spikes,
+                "fixed_spikes": report.fixed_spikes,
+                "rate_error": report.rate_error,
+                "matched_spikes": report.matched_spikes,
+                "unmatched_spikes": report.unmatched_spikes,
+                "mean_jitter_ns": report.mean_jitter_ns,
+                "max_jitter_ns": report.max_jitter_ns,
+                "max_weight_error": report.max_weight_error,
+                "exact": report.is_exact(),
+            });
+            let text = serde_json::to_string_pretty(&json).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            fs::write(path, text)?;
+            println!("Wrote {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+// --- NirLink command (multi-module composition) ---
+
+/// Link modules in order into one flattened module; same-named structure.port
+/// ops map a later module's neurons onto the first declaring module's
+#[derive(clap::Args, Debug)]
+pub struct NirLink {
+    /// Input textual NIR files, in link order
+    #[arg(required = true, num_args = 1..)]
+    pub inputs: Vec<PathBuf>,
+
+    /// Linked output file (.nirt)
+    #[arg(short, long)]
+    pub output: PathBuf,
+
+    #[command(flatten)]
+    pub template: TemplateArgs,
+
+    #[command(flatten)]
+    pub warnings: WarningArgs,
+}
+
+impl NirLink {
+    pub async fn execute(self) -> CliResult<()> {
+        let mut modules = Vec::with_capacity(self.inputs.len());
+        for path in &self.inputs {
+            let (module, _) = read_template(path, &self.template, &mut Profiler::new())?;
+            let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+            modules.push((name, module));
+        }
+        let units: Vec<(&str, &Module)> = modules.iter().map(|(name, module)| (name.as_str(), module)).collect();
+        let linked = link_modules(&units).map_err(CliError::compile)?;
+        self.warnings.check(&linked.module)?;
+
+        if let Some(parent) = self.output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.output, linked.module.to_text())?;
+        println!(
+            "Linked {} module(s) -> {} ({} neurons, {} ops)",
+            linked.units.len(),
+            self.output.display(),
+            linked.neurons(),
+            linked.module.ops.len()
+        );
+        for unit in &linked.units {
+            let owned = match unit.owned {
+                0 => "no own neurons".to_string(),
+                n => format!("neurons {}..{}", unit.first_id, unit.first_id + n - 1),
+            };
+            let ports: Vec<String> = unit.ports.iter()
+                .map(|p| match p.owner == unit.name {
+                    true => format!("{}={}..{}", p.name, p.range.0, p.range.1),
+                    false => format!("{}={}..{} (from {})", p.name, p.range.0, p.range.1, p.owner),
+                })
+                .collect();
+            let populations: Vec<String> = unit.populations.iter()
+                .map(|p| format!("{}={}..{}", p.name, p.neurons.0, p.neurons.1))
+                .collect();
+            let mut line = format!("  {:<20} {}", unit.name, owned);
+            if !ports.is_empty() {
+                line.push_str(&format!("; ports {}", ports.join(", ")));
+            }
+            if !populations.is_empty() {
+                line.push_str(&format!("; populations {}", populations.join(", ")));
+            }
+            println!("{}", line);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+//! Daemon mode: serve compile/verify/run over gRPC (shnn-server)
+
+use clap::Args;
+use std::net::SocketAddr;
+use tracing::info;
+
+use crate::error::{CliError, CliResult};
+
+/// Run hSNN as a long-lived service
+#[derive(Args, Debug)]
+pub struct ServeCommand {
+    /// Serve the shnn.v1.Hsnn gRPC API (VerifyModule, CompileModule, RunSimulation, ListOps)
+    #[arg(long)]
+    pub grpc: bool,
+
+    /// Address to listen on
+    #[arg(long, default_value = shnn_server::DEFAULT_ADDR)]
+    pub addr: SocketAddr,
+}
+
+impl ServeCommand {
+    pub async fn execute(self) -> CliResult<()> {
+        if !self.grpc {
+            return Err(CliError::invalid_args("select a protocol to serve: --grpc"));
+        }
+        info!("Serving gRPC on {} (Ctrl-C to stop)", self.addr);
+        shnn_server::serve(self.addr)
+            .await
+            .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))
+    }
+}
@@ -7,6 +7,8 @@
 //!   snn snapshot export --backend graph --inputs 10 --hidden 50 --outputs 5 --weight 1.0 --format json --out weights.json
 //!   snn snapshot import --backend graph --inputs 10 --hidden 50 --outputs 5 --format json --input weights.json
 //!
+//!   snn snapshot diff before.vcsr after.vcsr --top-k 10 --json diff.json
+//!
 //! Notes:
 //! - This constructs deterministic topologies locally (no persistence yet).
 //! - Graph: fully-connected Input->Hidden and Hidden->Output with uniform weight.
@@ -15,7 +17,9 @@
 
 use clap::{Args, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use tracing::info;
 
 use crate::error::{CliError, CliResult};
@@ -28,6 +32,8 @@ use shnn_core::connectivity::graph::{GraphEdge, GraphNetwork};
 use shnn_core::connectivity::matrix::MatrixNetwork;
 use shnn_core::connectivity::sparse::SparseMatrixNetwork;
 
+use shnn_storage::vcsr::MappedVCSR;
+
 #[derive(Args, Debug)]
 pub struct SnapshotCommand {
     #[command(subcommand)]
@@ -40,6 +46,8 @@ pub enum SnapshotSubcommand {
     Export(SnapshotExport),
     /// Import weight updates and apply to a deterministic locally constructed connectivity
     Import(SnapshotImport),
+    /// Compare two weight snapshots (VCSR or JSON) and report weight-change statistics
+    Diff(SnapshotDiff),
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -121,6 +129,27 @@ pub struct SnapshotImport {
     pub size: Option<u32>,
 }
 
+#[derive(Args, Debug)]
+pub struct SnapshotDiff {
+    /// Baseline snapshot (VCSR or JSON weight records)
+    pub before: PathBuf,
+
+    /// Snapshot to compare against the baseline
+    pub after: PathBuf,
+
+    /// Number of most-changed synapses to report
+    #[arg(long, default_value = "10")]
+    pub top_k: usize,
+
+    /// Number of histogram bins for Δw
+    #[arg(long, default_value = "10")]
+    pub bins: usize,
+
+    /// Write the full report as JSON (for the viz UI)
+    #[arg(long)]
+    pub json: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct WeightRecord {
     pre: u32,
@@ -133,6 +162,7 @@ impl SnapshotCommand {
         match self.sub {
             SnapshotSubcommand::Export(cmd) => cmd.execute().await,
             SnapshotSubcommand::Import(cmd) => cmd.execute().await,
+            SnapshotSubcommand::Diff(cmd) => cmd.execute().await,
         }
     }
 }
@@ -263,6 +293,190 @@ impl SnapshotImport {
     }
 }
 
+/// A synapse whose weight changed between snapshots
+#[derive(Debug, Clone, Serialize)]
+struct WeightChange {
+    pre: u32,
+    post: u32,
+    before: f32,
+    after: f32,
+    delta: f32,
+}
+
+/// Histogram bin over Δw, covering [lo, hi)
+#[derive(Debug, Clone, Serialize)]
+struct HistogramBin {
+    lo: f32,
+    hi: f32,
+    count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DiffReport {
+    before: String,
+    after: String,
+    common_edges: usize,
+    changed_edges: usize,
+    mean_abs_delta: f32,
+    max_abs_delta: f32,
+    mean_delta: f32,
+    histogram: Vec<HistogramBin>,
+    top_changes: Vec<WeightChange>,
+    added: Vec<WeightRecord>,
+    removed: Vec<WeightRecord>,
+}
+
+impl SnapshotDiff {
+    pub async fn execute(self) -> CliResult<()> {
+        let before = load_weight_map(&self.before)?;
+        let after = load_weight_map(&self.after)?;
+        let report = diff_weights(
+            &before,
+            &after,
+            self.top_k,
+            self.bins.max(1),
+            self.before.display().to_string(),
+            self.after.display().to_string(),
+        );
+
+        println!("Snapshot diff: {} -> {}", report.before, report.after);
+        println!(
+            "  common edges: {}  changed: {}  added: {}  removed: {}",
+            report.common_edges,
+            report.changed_edges,
+            report.added.len(),
+            report.removed.len()
+        );
+        println!(
+            "  mean |Δw|: {:.6}  max |Δw|: {:.6}  mean Δw: {:+.6}",
+            report.mean_abs_delta, report.max_abs_delta, report.mean_delta
+        );
+        if report.changed_edges > 0 {
+            println!("  Δw histogram:");
+            for bin in &report.histogram {
+                println!("    [{:+.4}, {:+.4}): {}", bin.lo, bin.hi, bin.count);
+            }
+        }
+        if !report.top_changes.is_empty() {
+            println!("  top {} changed synapses:", report.top_changes.len());
+            for c in &report.top_changes {
+                println!(
+                    "    {} -> {}: {:.6} -> {:.6} ({:+.6})",
+                    c.pre, c.post, c.before, c.after, c.delta
+                );
+            }
+        }
+
+        if let Some(path) = &self.json {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let text = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            std::fs::write(path, text)?;
+            info!("Wrote diff report (JSON) to {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+// Load a snapshot as a (pre, post) -> weight map. VCSR is detected by magic and read
+// in place from a memory map, otherwise JSON records.
+fn load_weight_map(path: &Path) -> CliResult<BTreeMap<(u32, u32), f32>> {
+    let mut magic = [0u8; 4];
+    let is_vcsr = std::fs::File::open(path)?.read_exact(&mut magic).is_ok() && magic == shnn_storage::magic::VCSR;
+    let mut map = BTreeMap::new();
+    if is_vcsr {
+        let mapped = MappedVCSR::open(path)?;
+        let view = mapped.view()?;
+        view.verify_blocks()?;
+        for (pre, post, weight) in view.edges() {
+            map.insert((pre.raw(), post.raw()), weight);
+        }
+    } else {
+        let bytes = std::fs::read(path)?;
+        let records: Vec<WeightRecord> = serde_json::from_slice(&bytes).map_err(|e| {
+            CliError::invalid_args(format!(
+                "{} is neither a VCSR file nor a JSON weight snapshot: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        for r in records {
+            map.insert((r.pre, r.post), r.weight);
+        }
+    }
+    Ok(map)
+}
+
+fn diff_weights(
+    before: &BTreeMap<(u32, u32), f32>,
+    after: &BTreeMap<(u32, u32), f32>,
+    top_k: usize,
+    bins: usize,
+    before_name: String,
+    after_name: String,
+) -> DiffReport {
+    let mut changes = Vec::new();
+    let mut removed = Vec::new();
+    for (&(pre, post), &w0) in before {
+        match after.get(&(pre, post)) {
+            Some(&w1) => changes.push(WeightChange { pre, post, before: w0, after: w1, delta: w1 - w0 }),
+            None => removed.push(WeightRecord { pre, post, weight: w0 }),
+        }
+    }
+    let added: Vec<WeightRecord> = after
+        .iter()
+        .filter(|(key, _)| !before.contains_key(key))
+        .map(|(&(pre, post), &weight)| WeightRecord { pre, post, weight })
+        .collect();
+
+    let common_edges = changes.len();
+    let changed_edges = changes.iter().filter(|c| c.delta != 0.0).count();
+    let (sum_abs, sum, max_abs) = changes.iter().fold((0.0f64, 0.0f64, 0.0f32), |(sa, s, m), c| {
+        (sa + c.delta.abs() as f64, s + c.delta as f64, m.max(c.delta.abs()))
+    });
+    let (mean_abs_delta, mean_delta) = if common_edges > 0 {
+        ((sum_abs / common_edges as f64) as f32, (sum / common_edges as f64) as f32)
+    } else {
+        (0.0, 0.0)
+    };
+
+    // Histogram over changed synapses only, symmetric around zero
+    let mut histogram = Vec::with_capacity(bins);
+    if changed_edges > 0 {
+        let lo = -max_abs;
+        let width = 2.0 * max_abs / bins as f32;
+        for i in 0..bins {
+            histogram.push(HistogramBin { lo: lo + width * i as f32, hi: lo + width * (i + 1) as f32, count: 0 });
+        }
+        for c in changes.iter().filter(|c| c.delta != 0.0) {
+            let idx = (((c.delta - lo) / width) as usize).min(bins - 1);
+            histogram[idx].count += 1;
+        }
+    }
+
+    changes.retain(|c| c.delta != 0.0);
+    changes.sort_by(|a, b| {
+        b.delta.abs().total_cmp(&a.delta.abs()).then((a.pre, a.post).cmp(&(b.pre, b.post)))
+    });
+    changes.truncate(top_k);
+
+    DiffReport {
+        before: before_name,
+        after: after_name,
+        common_edges,
+        changed_edges,
+        mean_abs_delta,
+        max_abs_delta: max_abs,
+        mean_delta,
+        histogram,
+        top_changes: changes,
+        added,
+        removed,
+    }
+}
+
 // Build a PlasticConn connectivity deterministically based on backend and params.
 fn build_connectivity(
     backend: &SnapshotBackend,
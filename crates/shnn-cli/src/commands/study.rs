@@ -1,24 +1,77 @@
 #![allow(clippy::needless_collect)]
 //! Parameter study and optimization command (minimal working runner)
+//!
+//! With workers (`[[workers]]` in the study config or `--worker`), the runner
+//! acts as a coordinator: every (run, repeat) trial is queued and handed to
+//! the next idle worker host, over SSH (`snn nir run` on the host, results
+//! JSON read back from stdout) or the gRPC service (`snn serve --grpc`).
+//! Artifacts are written to the local out dir and merged into one
+//! `summary.json` manifest recording which worker ran each trial.
+//!
+//! Trial seeds come from a [`SeedTree`]: a run's `seed` (else the study seed
+//! branched by run index) is the master, each repeat takes its own child seed
+//! and it replaces the module's `simulate.run` seed. Without either seed the
+//! module's own seed is used unchanged.
+//!
+//! Built with the `index` feature, every trial is also added to the workspace
+//! experiment index (see [`crate::index`]) with its metrics and `params`, and
+//! `snn study query "metric.rate > 5 AND params.weight < 0.2"` filters it.
 
-use clap::Args;
+use clap::{Args, Subcommand};
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncWriteExt;
 use tracing::{info, warn};
 
 use crate::error::{CliError, CliResult};
-use shnn_ir::parse_text;
+use shnn_ir::{parse_text, substitute_vars, AttributeValue, DialectKey, Module};
+use shnn_runtime::SeedTree;
 use shnn_compiler::{verify_module, compile_with_passes};
 
 /// Run parameter studies
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct StudyCommand {
+    #[command(subcommand)]
+    pub action: Option<StudyAction>,
+
     /// Study configuration file (.toml)
-    pub config: PathBuf,
+    #[arg(required = true)]
+    pub config: Option<PathBuf>,
 
     /// Number of parallel jobs (unused in v0; sequential)
     #[arg(short, long, default_value = "1")]
     pub jobs: u32,
+
+    /// Dispatch trials to a worker: ssh://[user@]host or grpc://host:port (repeatable, adds to [[workers]])
+    #[arg(long = "worker", value_name = "URL")]
+    pub workers: Vec<WorkerSpec>,
+}
+
+/// Study subcommands (running a config needs none)
+#[derive(Subcommand, Debug)]
+pub enum StudyAction {
+    /// Filter indexed runs and trials (requires the `index` feature)
+    Query(StudyQueryCommand),
+}
+
+/// Query the workspace experiment index
+#[derive(Args, Debug)]
+pub struct StudyQueryCommand {
+    /// Filter, e.g. "metric.rate > 5 AND params.weight < 0.2" (all entries if omitted)
+    pub filter: Option<String>,
+
+    /// Maximum number of entries
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Also write the matches as JSON to this file
+    #[arg(long, value_name = "FILE")]
+    pub json: Option<PathBuf>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,6 +80,8 @@ struct StudyConfig {
     study: StudySection,
     #[serde(default)]
     runs: Vec<StudyRun>,
+    #[serde(default)]
+    workers: Vec<WorkerSpec>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -48,21 +103,289 @@ struct StudyRun {
     seed: Option<i64>,
     #[serde(default)]
     record_potentials: Option<bool>,
+    /// Free-form labels recorded with each trial (and indexed as `params.<name>`);
+    /// they also bind `${name}` placeholders in the NIR text
+    #[serde(default)]
+    params: BTreeMap<String, toml::Value>,
+}
+
+/// A host that runs trials for a distributed study
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum WorkerSpec {
+    /// Run `snn nir run` on `host` through `ssh`
+    Ssh {
+        /// Destination passed to ssh (`host` or `user@host`)
+        host: String,
+        /// snn binary on the host
+        #[serde(default = "default_remote_snn")]
+        snn: String,
+        /// Scratch directory on the host for trial inputs and results
+        #[serde(default = "default_work_dir")]
+        work_dir: String,
+        /// Local ssh client
+        #[serde(default = "default_ssh")]
+        ssh: String,
+    },
+    /// Call RunSimulation on an `snn serve --grpc` endpoint
+    Grpc {
+        /// Endpoint (`http://host:port`)
+        addr: String,
+    },
+}
+
+fn default_remote_snn() -> String { "snn".into() }
+fn default_work_dir() -> String { "/tmp/hsnn-study".into() }
+fn default_ssh() -> String { "ssh".into() }
+
+impl WorkerSpec {
+    /// Name recorded in the manifest
+    fn label(&self) -> String {
+        match self {
+            WorkerSpec::Ssh { host, .. } => format!("ssh://{}", host),
+            WorkerSpec::Grpc { addr } => addr.replacen("http://", "grpc://", 1),
+        }
+    }
+}
+
+impl FromStr for WorkerSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(host) = s.strip_prefix("ssh://").filter(|h| !h.is_empty()) {
+            Ok(WorkerSpec::Ssh {
+                host: host.to_string(),
+                snn: default_remote_snn(),
+                work_dir: default_work_dir(),
+                ssh: default_ssh(),
+            })
+        } else if let Some(addr) = s.strip_prefix("grpc://").filter(|a| !a.is_empty()) {
+            Ok(WorkerSpec::Grpc { addr: format!("http://{}", addr) })
+        } else {
+            Err(format!("worker must be ssh://[user@]host or grpc://host:port, got '{}'", s))
+        }
+    }
+}
+
+/// One (run, repeat) unit of work
+#[derive(Debug, Clone)]
+struct Trial {
+    run_index: usize,
+    repeat_index: u32,
+    seed: Option<u64>,
+    module_text: Arc<String>,
+}
+
+impl Trial {
+    fn stem(&self) -> String {
+        format!("run{}_rep{}", self.run_index, self.repeat_index)
+    }
+}
+
+/// Seed of repeat `repeat` of run `run_index` (both 1-based), if the study or run is seeded
+fn trial_seed(study_seed: Option<i64>, run: &StudyRun, run_index: usize, repeat: u32) -> Option<u64> {
+    let master = match (run.seed, study_seed) {
+        (Some(seed), _) => SeedTree::new(seed as u64),
+        (None, Some(seed)) => SeedTree::new(seed as u64).child("run").index(run_index as u64),
+        (None, None) => return None,
+    };
+    Some(master.derive("repeat", repeat as u64))
+}
+
+/// `module` with its simulate.run seed replaced by `seed`
+fn with_seed(module: &Module, seed: Option<u64>) -> Module {
+    let mut module = module.clone();
+    if let Some(seed) = seed {
+        for op in &mut module.ops {
+            if op.dialect == DialectKey::Runtime && op.name == "simulate.run" {
+                op.attrs.insert("seed".to_string(), AttributeValue::I64(seed as i64));
+            }
+        }
+    }
+    module
+}
+
+/// `text` with `${name}` placeholders bound to the run's `params`
+fn bind_params(text: &str, params: &BTreeMap<String, toml::Value>) -> shnn_ir::Result<String> {
+    let vars = params
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (name.clone(), value)
+        })
+        .collect();
+    substitute_vars(text, &vars).map(|(text, _)| text)
+}
+
+/// What a worker reports back for a trial
+#[derive(Debug)]
+struct TrialOutcome {
+    steps_executed: u64,
+    spike_count: u64,
+    worker: String,
+    artifact: PathBuf,
+}
+
+/// Quote for a POSIX shell on the worker
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+async fn run_on_ssh(worker: &WorkerSpec, study: &str, trial: &Trial, base_out: &Path) -> CliResult<TrialOutcome> {
+    let WorkerSpec::Ssh { host, snn, work_dir, ssh } = worker else { unreachable!() };
+    let dir = shell_quote(&format!("{}/{}-{}", work_dir, study, trial.stem()));
+    // The module arrives on stdin; only the results JSON goes to stdout
+    let script = format!(
+        "mkdir -p {dir} && cat > {dir}/trial.nirt && {snn} nir run {dir}/trial.nirt -o {dir}/results.json >/dev/null && cat {dir}/results.json",
+    );
+    let mut child = tokio::process::Command::new(ssh)
+        .arg(host)
+        .arg(script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("piped stdin");
+    stdin.write_all(trial.module_text.as_bytes()).await?;
+    drop(stdin);
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(CliError::Generic(anyhow::anyhow!(
+            "{} failed ({}): {}",
+            worker.label(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| CliError::Generic(anyhow::anyhow!("{}: unreadable results: {}", worker.label(), e)))?;
+    let artifact = base_out.join(format!("{}.results.json", trial.stem()));
+    std::fs::write(&artifact, &output.stdout)?;
+    Ok(TrialOutcome {
+        steps_executed: results["results"]["steps_executed"].as_u64().unwrap_or(0),
+        spike_count: results["results"]["spike_count"].as_u64().unwrap_or(0),
+        worker: worker.label(),
+        artifact,
+    })
+}
+
+async fn run_on_grpc(worker: &WorkerSpec, trial: &Trial, base_out: &Path) -> CliResult<TrialOutcome> {
+    use shnn_server::proto::{hsnn_client::HsnnClient, run_event, RunRequest};
+
+    let WorkerSpec::Grpc { addr } = worker else { unreachable!() };
+    let rpc_error = |e: &dyn std::fmt::Display| CliError::Generic(anyhow::anyhow!("{}: {}", worker.label(), e));
+    let mut client = HsnnClient::connect(addr.clone()).await.map_err(|e| rpc_error(&e))?;
+    let request = RunRequest { module_text: trial.module_text.to_string(), batch_steps: u32::MAX };
+    let mut stream = client.run_simulation(request).await.map_err(|e| rpc_error(&e))?.into_inner();
+
+    let mut spikes = Vec::new();
+    let mut summary = None;
+    while let Some(event) = stream.message().await.map_err(|e| rpc_error(&e))? {
+        match event.event {
+            Some(run_event::Event::Spikes(batch)) => spikes.extend(batch.spikes.into_iter().map(|s| {
+                serde_json::json!({
+                    "neuron_id": s.neuron_id,
+                    "time_ns": s.time_ns,
+                    "time_ms": s.time_ns as f64 / 1_000_000.0,
+                })
+            })),
+            Some(run_event::Event::Summary(s)) => summary = Some(s),
+            None => {}
+        }
+    }
+    let summary = summary.ok_or_else(|| rpc_error(&"stream ended without a summary"))?;
+
+    // Same layout as `snn nir run -o` so artifacts from both worker kinds read alike
+    let json = serde_json::json!({
+        "results": {
+            "spike_count": summary.total_spikes,
+            "steps_executed": summary.steps_executed,
+            "spikes": spikes,
+        }
+    });
+    let artifact = base_out.join(format!("{}.results.json", trial.stem()));
+    std::fs::write(&artifact, serde_json::to_string_pretty(&json)
+        .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?)?;
+    Ok(TrialOutcome {
+        steps_executed: summary.steps_executed,
+        spike_count: summary.total_spikes,
+        worker: worker.label(),
+        artifact,
+    })
+}
+
+/// Hand queued trials to idle workers; a worker that fails is retired and its
+/// trial goes back on the queue for the others
+async fn dispatch(
+    study: &str,
+    trials: Vec<Trial>,
+    workers: &[WorkerSpec],
+    base_out: &Path,
+) -> CliResult<Vec<(Trial, TrialOutcome)>> {
+    let queue = Arc::new(Mutex::new(trials.into_iter().collect::<VecDeque<_>>()));
+    let done = Arc::new(Mutex::new(Vec::new()));
+    let mut tasks = Vec::new();
+    for worker in workers.iter().cloned() {
+        let (queue, done) = (queue.clone(), done.clone());
+        let (study, base_out) = (study.to_string(), base_out.to_path_buf());
+        tasks.push(tokio::spawn(async move {
+            loop {
+                let Some(trial) = queue.lock().unwrap().pop_front() else { break };
+                info!("Trial {} → {}", trial.stem(), worker.label());
+                let outcome = match &worker {
+                    WorkerSpec::Ssh { .. } => run_on_ssh(&worker, &study, &trial, &base_out).await,
+                    WorkerSpec::Grpc { .. } => run_on_grpc(&worker, &trial, &base_out).await,
+                };
+                match outcome {
+                    Ok(outcome) => done.lock().unwrap().push((trial, outcome)),
+                    Err(e) => {
+                        warn!("Retiring worker {}: {}", worker.label(), e);
+                        queue.lock().unwrap().push_back(trial);
+                        break;
+                    }
+                }
+            }
+        }));
+    }
+    for task in tasks {
+        task.await.map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+    }
+
+    let remaining = queue.lock().unwrap().len();
+    if remaining > 0 {
+        return Err(CliError::Generic(anyhow::anyhow!(
+            "{} trial(s) not completed: every worker failed", remaining
+        )));
+    }
+    let mut done = std::mem::take(&mut *done.lock().unwrap());
+    done.sort_by_key(|(trial, _)| (trial.run_index, trial.repeat_index));
+    Ok(done)
 }
 
 impl StudyCommand {
-    pub async fn execute(self, _workspace: PathBuf, _config: Option<PathBuf>) -> CliResult<()> {
-        let text = std::fs::read_to_string(&self.config)?;
-        let cfg: StudyConfig = toml::from_str(&text)
+    pub async fn execute(self, workspace: PathBuf, config: Option<PathBuf>) -> CliResult<()> {
+        if let Some(StudyAction::Query(cmd)) = self.action {
+            return cmd.execute(&workspace, config.as_deref());
+        }
+        let study_config = self.config.expect("clap requires a config without a subcommand");
+        let text = std::fs::read_to_string(&study_config)?;
+        let mut cfg: StudyConfig = toml::from_str(&text)
             .map_err(|e| CliError::config(format!("Invalid study config: {}", e)))?;
+        cfg.workers.extend(self.workers);
 
         let base_out = cfg.study.out_dir
+            .as_ref()
             .map(PathBuf::from)
             .unwrap_or_else(|| PathBuf::from("crates/shnn-cli/test_workspace/results/studies"));
         std::fs::create_dir_all(&base_out)?;
 
         info!("Study '{}' with {} runs", cfg.study.name, cfg.runs.len());
         let mut summary = Vec::new();
+        let mut trials = Vec::new();
 
         for (i, run) in cfg.runs.iter().enumerate() {
             let repeats = run.repeats.max(1);
@@ -70,13 +393,29 @@ impl StudyCommand {
 
             let nir_path = PathBuf::from(&run.nir);
             let nir_txt = std::fs::read_to_string(&nir_path)?;
+            let nir_txt = bind_params(&nir_txt, &run.params)
+                .map_err(|e| CliError::invalid_args(format!("{}: {}", nir_path.display(), e)))?;
             let module = parse_text(&nir_txt)
                 .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
             verify_module(&module).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
 
+            if !cfg.workers.is_empty() {
+                let shared = Arc::new(nir_txt);
+                trials.extend((1..=repeats).map(|r| {
+                    let seed = trial_seed(cfg.study.seed, run, i + 1, r);
+                    let module_text = match seed {
+                        Some(_) => Arc::new(with_seed(&module, seed).to_text()),
+                        None => shared.clone(),
+                    };
+                    Trial { run_index: i + 1, repeat_index: r, seed, module_text }
+                }));
+                continue;
+            }
+
             for r in 0..repeats {
-                let mut program = compile_with_passes(&module)
-                    .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+                let seed = trial_seed(cfg.study.seed, run, i + 1, r + 1);
+                let program = compile_with_passes(&with_seed(&module, seed)).map_err(CliError::compile)?;
+                let seeds = program.seeds.clone();
                 let result = program.run()?;
 
                 let out_file = base_out.join(format!("run{}_rep{}.json", i + 1, r + 1));
@@ -86,6 +425,31 @@ impl StudyCommand {
                     "repeat_index": r + 1,
                     "steps_executed": result.steps_executed,
                     "spike_count": result.spikes.len(),
+                    "mean_rate_hz": result.average_firing_rate() as f64 / result.neuron_count.max(1) as f64,
+                    "seed": seed,
+                    "seeds": seeds,
+                    "params": run.params,
+                });
+                std::fs::write(&out_file, serde_json::to_string_pretty(&json)
+                    .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?)?;
+                summary.push(json);
+            }
+        }
+
+        if !cfg.workers.is_empty() {
+            info!("Dispatching {} trials to {} workers", trials.len(), cfg.workers.len());
+            for (trial, outcome) in dispatch(&cfg.study.name, trials, &cfg.workers, &base_out).await? {
+                let out_file = base_out.join(format!("{}.json", trial.stem()));
+                let json = serde_json::json!({
+                    "study": cfg.study.name,
+                    "run_index": trial.run_index,
+                    "repeat_index": trial.repeat_index,
+                    "steps_executed": outcome.steps_executed,
+                    "spike_count": outcome.spike_count,
+                    "seed": trial.seed,
+                    "worker": outcome.worker,
+                    "artifact": outcome.artifact.file_name().map(|n| n.to_string_lossy().into_owned()),
+                    "params": cfg.runs[trial.run_index - 1].params,
                 });
                 std::fs::write(&out_file, serde_json::to_string_pretty(&json)
                     .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?)?;
@@ -93,10 +457,86 @@ impl StudyCommand {
             }
         }
 
+        let workers: Vec<String> = cfg.workers.iter().map(WorkerSpec::label).collect();
         let summary_file = base_out.join("summary.json");
-        std::fs::write(&summary_file, serde_json::to_string_pretty(&serde_json::json!({ "runs": summary }))
+        std::fs::write(&summary_file, serde_json::to_string_pretty(&serde_json::json!({ "workers": workers, "runs": summary }))
             .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?)?;
         warn!("Study summary: {}", summary_file.display());
+
+        #[cfg(feature = "index")]
+        index_trials(&crate::config::Config::load(&workspace, config.as_deref())?, &cfg, &base_out, &summary)?;
+        #[cfg(not(feature = "index"))]
+        let _ = (workspace, config);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Add every trial of a finished study to the workspace index
+#[cfg(feature = "index")]
+fn index_trials(settings: &crate::config::Config, cfg: &StudyConfig, base_out: &Path, summary: &[serde_json::Value]) -> CliResult<()> {
+    use crate::index::{EntryKind, ExperimentIndex, IndexEntry};
+
+    let created_unix_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    let mut index = ExperimentIndex::open_workspace(settings)?;
+    for trial in summary {
+        let (run_index, repeat_index) = (trial["run_index"].as_u64().unwrap_or(0), trial["repeat_index"].as_u64().unwrap_or(0));
+        let Some(run) = cfg.runs.get((run_index as usize).wrapping_sub(1)) else { continue };
+        let stem = format!("run{}_rep{}", run_index, repeat_index);
+        let mut entry = IndexEntry::new(format!("{}/{}", cfg.study.name, stem), EntryKind::Trial, created_unix_ms);
+        entry.study = Some(cfg.study.name.clone());
+        entry.nir_source = Some(run.nir.clone());
+        entry.seed = trial["seed"].as_u64();
+        for (metric, field) in [("steps_executed", "steps_executed"), ("spike_count", "spike_count"), ("rate", "mean_rate_hz")] {
+            if let Some(value) = trial[field].as_f64() {
+                entry.metrics.insert(metric.to_string(), value);
+            }
+        }
+        for (name, value) in &run.params {
+            let value: serde_json::Value = match value {
+                toml::Value::Integer(i) => (*i).into(),
+                toml::Value::Float(f) => (*f).into(),
+                toml::Value::Boolean(b) => (*b).into(),
+                toml::Value::String(s) => s.clone().into(),
+                other => other.to_string().into(),
+            };
+            entry.params.insert(name.clone(), value);
+        }
+        entry.artifacts.insert("trial".into(), base_out.join(format!("{}.json", stem)).display().to_string());
+        if let Some(artifact) = trial["artifact"].as_str() {
+            entry.artifacts.insert("results".into(), base_out.join(artifact).display().to_string());
+        }
+        index.upsert(&entry)?;
+    }
+    info!("Indexed {} trials of study '{}'", summary.len(), cfg.study.name);
+    Ok(())
+}
+
+impl StudyQueryCommand {
+    #[cfg(not(feature = "index"))]
+    fn execute(self, _workspace: &Path, _config: Option<&Path>) -> CliResult<()> {
+        Err(CliError::invalid_args("snn was built without the experiment index; rebuild with `--features index`"))
+    }
+
+    #[cfg(feature = "index")]
+    fn execute(self, workspace: &Path, config: Option<&Path>) -> CliResult<()> {
+        use crate::index::{ExperimentIndex, Query};
+
+        let query = self.filter.as_deref().map(str::parse::<Query>).transpose()?;
+        let settings = crate::config::Config::load(workspace, config)?;
+        let entries = ExperimentIndex::open_workspace(&settings)?.query(query.as_ref(), self.limit)?;
+        for entry in &entries {
+            let metrics: Vec<String> = entry.metrics.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let params: Vec<String> = entry.params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            println!("{}  [{}]  {}  {}", entry.key, entry.kind.as_str(), metrics.join(" "), params.join(" "));
+        }
+        println!("{} match(es)", entries.len());
+        if let Some(path) = &self.json {
+            let json = serde_json::to_string_pretty(&entries).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            std::fs::write(path, json)?;
+        }
+        Ok(())
+    }
+}
+
@@ -8,11 +8,14 @@ use crate::error::{CliError, CliResult};
 use shnn_runtime::{
     neuron::LIFParams,
     plasticity::STDPParams,
+    readout::stack_traces,
+    LinearReadout, ReadoutRole, ReadoutTrace, Reservoir, SimulationResult,
 };
 use shnn_ir::{
     Module,
     lif_neuron_v1, stdp_rule_v1, layer_fully_connected_v1,
     stimulus_poisson_v1, runtime_simulate_run_v1,
+    structure_reservoir_v1, runtime_readout_v1, AttributeValue,
 };
 use shnn_compiler::compile_module;
 
@@ -70,6 +73,14 @@ pub struct TrainCommand {
     /// STDP plasticity parameters (key=value pairs)
     #[arg(long = "stdp", value_parser = parse_key_val::<String, f32>)]
     pub stdp_params: Vec<(String, f32)>,
+
+    /// Reservoir parameters for --topology reservoir (p, radius, input_p, input_weight)
+    #[arg(long = "reservoir", value_parser = parse_key_val::<String, f32>)]
+    pub reservoir_params: Vec<(String, f32)>,
+
+    /// Readout parameters for --topology reservoir (tau_ms, interval_ms, ridge)
+    #[arg(long = "readout", value_parser = parse_key_val::<String, f32>)]
+    pub readout_params: Vec<(String, f32)>,
     
     /// Input stimulus pattern
     #[arg(long, default_value = "poisson")]
@@ -110,6 +121,8 @@ pub enum TopologyType {
     Random,
     /// Custom topology from file
     Custom,
+    /// Random recurrent reservoir with a trained linear readout (liquid state machine)
+    Reservoir,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -177,8 +190,11 @@ impl TrainCommand {
             lif_params.c_m,
         ));
 
-        // plasticity.stdp@v1 (if enabled)
+        // plasticity.stdp@v1 (if enabled; a reservoir keeps its random weights)
         match self.plasticity {
+            PlasticityType::Stdp if matches!(self.topology, TopologyType::Reservoir) => {
+                warn!("STDP is not applied to a reservoir; only the readout is trained");
+            }
             PlasticityType::Stdp => {
                 module.push(stdp_rule_v1(
                     stdp_params.a_plus,
@@ -192,6 +208,13 @@ impl TrainCommand {
             PlasticityType::None => { /* no-op */ }
         }
 
+        let dt_ms = (self.dt_us as f32) / 1000.0;
+        let total_ms = dt_ms * (self.steps as f32);
+        // Input current that takes a resting neuron to threshold within one step
+        let threshold_weight = (lif_params.v_thresh - lif_params.v_rest) * lif_params.tau_m / (lif_params.r_m * dt_ms);
+        let mut stimulus_amplitude = 10.0; // 10nA
+        let mut readout = ReadoutSettings::default();
+
         // connectivity.layer_fully_connected@v1
         match self.topology {
             TopologyType::FullyConnected => {
@@ -218,14 +241,64 @@ impl TrainCommand {
                     ));
                 }
             }
+            TopologyType::Reservoir => {
+                // Inputs (0..inputs-1) -> Reservoir (inputs..inputs+hidden-1), read at its first `outputs` neurons
+                if self.inputs == 0 || self.hidden == 0 || self.outputs == 0 || self.outputs > self.hidden {
+                    return Err(CliError::invalid_args(
+                        "Reservoir topology needs --inputs >= 1, --hidden >= 1 and 1 <= --outputs <= --hidden",
+                    ));
+                }
+                let mut rule = Reservoir {
+                    p: 0.1,
+                    radius: 0.9 * threshold_weight,
+                    input_p: 0.2,
+                    input_weight: 1.05 * threshold_weight,
+                };
+                for (key, value) in &self.reservoir_params {
+                    match key.as_str() {
+                        "p" => rule.p = *value,
+                        "radius" => rule.radius = *value,
+                        "input_p" => rule.input_p = *value,
+                        "input_weight" => rule.input_weight = *value,
+                        _ => {
+                            warn!("Unknown reservoir parameter: {}", key);
+                        }
+                    }
+                }
+                for (key, value) in &self.readout_params {
+                    match key.as_str() {
+                        "tau_ms" => readout.tau_ms = *value,
+                        "interval_ms" => readout.interval_ms = *value,
+                        "ridge" => readout.ridge = *value as f64,
+                        _ => {
+                            warn!("Unknown readout parameter: {}", key);
+                        }
+                    }
+                }
+                let first = self.inputs;
+                module.push(
+                    structure_reservoir_v1(
+                        (first, first + self.hidden - 1),
+                        (0, self.inputs - 1),
+                        (first, first + self.outputs - 1),
+                        rule.radius,
+                        rule.input_weight,
+                    )
+                    .with_attr("p", AttributeValue::F32(rule.p))
+                    .with_attr("input_p", AttributeValue::F32(rule.input_p)),
+                );
+                // The readout learns to reproduce the filtered input spike trains from the reservoir state
+                module.push(runtime_readout_v1(None, "state", readout.tau_ms, readout.interval_ms));
+                module.push(runtime_readout_v1(None, "target", readout.tau_ms, readout.interval_ms));
+                // Every input event fires its input neuron
+                stimulus_amplitude = 1.05 * threshold_weight;
+            }
             _ => {
-                return Err(CliError::invalid_args("Only fully-connected topology supported in v0"));
+                return Err(CliError::invalid_args("Only fully-connected and reservoir topologies supported in v0"));
             }
         }
 
         // Stimuli
-        let dt_ms = (self.dt_us as f32) / 1000.0;
-        let total_ms = dt_ms * (self.steps as f32);
 
         match self.stimulus {
             StimulusType::Poisson => {
@@ -233,7 +306,7 @@ impl TrainCommand {
                     module.push(stimulus_poisson_v1(
                         i,
                         self.stimulus_rate,
-                        10.0, // 10nA
+                        stimulus_amplitude,
                         0.0,  // start ms
                         total_ms,
                     ));
@@ -271,6 +344,18 @@ impl TrainCommand {
         if self.record_potentials {
             info!("Membrane potentials recorded: {}", result.potentials.len());
         }
+
+        let readout_fit = match self.topology {
+            TopologyType::Reservoir => {
+                let fit = fit_readout(&result, readout.ridge)?;
+                println!(
+                    "Readout: {} state features -> {} targets over {} samples, train NRMSE {:.3}, test NRMSE {:.3}",
+                    fit.features, fit.targets, fit.samples, fit.train_nrmse, fit.test_nrmse
+                );
+                Some(fit)
+            }
+            _ => None,
+        };
         
         // Save results if output specified
         if let Some(output_path) = self.output {
@@ -291,7 +376,7 @@ impl TrainCommand {
                 })
             }).collect();
             
-            let results_json = serde_json::json!({
+            let mut results_json = serde_json::json!({
                 "simulation": {
                     "steps": self.steps,
                     "dt_us": self.dt_us,
@@ -327,6 +412,20 @@ impl TrainCommand {
                     "spikes": spike_data,
                 }
             });
+            if let Some(fit) = &readout_fit {
+                results_json["readout"] = serde_json::json!({
+                    "tau_ms": readout.tau_ms,
+                    "interval_ms": readout.interval_ms,
+                    "ridge": readout.ridge,
+                    "samples": fit.samples,
+                    "train_samples": fit.train_samples,
+                    "features": fit.features,
+                    "targets": fit.targets,
+                    "train_nrmse": fit.train_nrmse,
+                    "test_nrmse": fit.test_nrmse,
+                    "weights": fit.readout.weights,
+                });
+            }
             
             let json_string = serde_json::to_string_pretty(&results_json)
                 .map_err(|e| CliError::Generic(anyhow::anyhow!("JSON serialization failed: {}", e)))?;
@@ -337,6 +436,65 @@ impl TrainCommand {
     }
 }
 
+/// Readout settings of the reservoir topology
+#[derive(Debug, Clone, Copy)]
+struct ReadoutSettings {
+    tau_ms: f32,
+    interval_ms: f32,
+    ridge: f64,
+}
+
+impl Default for ReadoutSettings {
+    fn default() -> Self {
+        Self { tau_ms: 20.0, interval_ms: 5.0, ridge: 1e-3 }
+    }
+}
+
+/// Trained readout and its errors
+#[derive(Debug)]
+struct ReadoutFit {
+    readout: LinearReadout,
+    samples: usize,
+    train_samples: usize,
+    features: usize,
+    targets: usize,
+    train_nrmse: f64,
+    test_nrmse: f64,
+}
+
+/// Fit state readouts to target readouts on the first 80% of samples and score the rest
+fn fit_readout(result: &SimulationResult, ridge: f64) -> CliResult<ReadoutFit> {
+    let by_role = |role: ReadoutRole| -> CliResult<Vec<Vec<f32>>> {
+        let traces: Vec<&ReadoutTrace> = result.readouts.iter().filter(|t| t.role == role).collect();
+        stack_traces(&traces).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))
+    };
+    let (states, targets) = (by_role(ReadoutRole::State)?, by_role(ReadoutRole::Target)?);
+    let samples = states.len().min(targets.len());
+    let train_samples = samples * 4 / 5;
+    if train_samples == 0 || train_samples == samples {
+        return Err(CliError::invalid_args(format!(
+            "Readout needs at least 2 samples to split into train and test, got {}; raise --steps or lower --readout interval_ms",
+            samples
+        )));
+    }
+    let readout = LinearReadout::fit(&states[..train_samples], &targets[..train_samples], ridge)
+        .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+    // Mean over the targets that vary
+    let mean_nrmse = |range: std::ops::Range<usize>| {
+        let errors: Vec<f64> = readout.nrmse(&states[range.clone()], &targets[range]).into_iter().flatten().collect();
+        if errors.is_empty() { f64::NAN } else { errors.iter().sum::<f64>() / errors.len() as f64 }
+    };
+    Ok(ReadoutFit {
+        train_nrmse: mean_nrmse(0..train_samples),
+        test_nrmse: mean_nrmse(train_samples..samples),
+        features: states[0].len(),
+        targets: targets[0].len(),
+        readout,
+        samples,
+        train_samples,
+    })
+}
+
 /// Parse a single key-value pair
 fn parse_key_val<T, U>(s: &str) -> Result<(T, U), Box<dyn std::error::Error + Send + Sync + 'static>>
 where
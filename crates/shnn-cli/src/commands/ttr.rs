@@ -1,31 +1,108 @@
-//! Task-Aware Topology Reshaping (TTR) - minimal working implementation (JSON mask)
+//! Task-Aware Topology Reshaping (TTR)
+//!
+//! `snn ttr generate` derives one activity mask (VMSK) per task from recorded spikes:
+//! a neuron is kept for a task when its firing rate inside the task window meets the
+//! program's criterion. Masks are written to `<workspace>/data/masks` unless the
+//! program or `--output-dir` says otherwise, together with a `manifest.json`.
+//!
+//! Example program:
+//!   [program]
+//!   name = "two-tasks"
+//!   [activity]
+//!   path = "spikes.vevt"     # nir run output (JSON or VEVT)
+//!   num_neurons = 100        # optional, defaults to highest seen ID + 1
+//!   [criterion]
+//!   min_rate_hz = 5.0        # task-conditional rate threshold
+//!   min_selectivity = 2.0    # optional: task rate / mean rate in other tasks
+//!   [inputs]
+//!   ranges = [{ start = 0, end = 9 }]   # always included in every mask
+//!   [[task]]
+//!   name = "a"
+//!   start_ms = 0.0
+//!   end_ms = 500.0
+//!
+//! `snn ttr mask-op` combines masks: union/intersect fold all inputs, difference
+//! subtracts inputs 2.. from the first, invert complements one input and bundle packs
+//! inputs into a named collection. Inputs are `file.vmsk` or `collection.vmsk#name`;
+//! with `--name` the result is stored in (or added to) the collection at `--output`.
+//!   snn ttr mask-op union data/masks/a.vmsk data/masks/b.vmsk -o ab.vmsk
+//!   snn ttr mask-op bundle data/masks/a.vmsk data/masks/b.vmsk -o tasks.vmsk
+//!   snn ttr mask-op difference tasks.vmsk#a tasks.vmsk#b -o tasks.vmsk --name a_only
 
-use clap::Args;
-use serde::Deserialize;
-use std::path::PathBuf;
+use clap::{Args, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use tracing::info;
 
 use crate::error::{CliError, CliResult};
+use crate::workspace::Workspace;
+
+use shnn_storage::{BitmapMask, GenerationId, Mask, MaskCollection, MaskId, MaskType};
 
 /// Task-Aware Topology Reshaping (TTR)
 #[derive(Args, Debug)]
 pub struct TtrCommand {
+    #[command(subcommand)]
+    pub sub: TtrSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TtrSubcommand {
+    /// Generate per-task activity masks (VMSK) from recorded spikes
+    Generate(TtrGenerate),
+    /// Combine masks (union/intersect/difference/invert) or bundle them
+    MaskOp(TtrMaskOp),
+}
+
+#[derive(Args, Debug)]
+pub struct TtrGenerate {
     /// Program file (TOML)
     #[arg(long)]
     pub program: PathBuf,
-    /// Output mask path (JSON, placeholder)
+    /// Output directory for masks (overrides [output] mask_dir)
     #[arg(long)]
+    pub output_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct TtrMaskOp {
+    /// Operation
+    #[arg(value_enum)]
+    pub op: MaskOpKind,
+    /// Input masks (`file.vmsk` or `collection.vmsk#name`)
+    #[arg(required = true)]
+    pub inputs: Vec<String>,
+    /// Output file
+    #[arg(short, long)]
     pub output: PathBuf,
+    /// Store the result under this name in the collection at --output
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum MaskOpKind {
+    Union,
+    Intersect,
+    /// First input minus all others
+    Difference,
+    /// Complement of a single input
+    Invert,
+    /// Pack inputs into a named collection
+    Bundle,
 }
 
 #[derive(Debug, Deserialize)]
 struct TtrProgram {
     #[serde(default)]
     program: ProgramSection,
+    activity: ActivitySection,
     #[serde(default)]
-    inputs: InputsSection,
+    criterion: CriterionSection,
     #[serde(default)]
-    ops: Vec<Op>,
+    inputs: InputsSection,
+    #[serde(default, rename = "task")]
+    tasks: Vec<TaskSpec>,
     #[serde(default)]
     output: OutputSection,
 }
@@ -38,6 +115,21 @@ struct ProgramSection {
     version: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ActivitySection {
+    path: PathBuf,
+    #[serde(default)]
+    num_neurons: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CriterionSection {
+    #[serde(default)]
+    min_rate_hz: f64,
+    #[serde(default)]
+    min_selectivity: Option<f64>,
+}
+
 #[derive(Debug, Default, Deserialize)]
 struct InputsSection {
     #[serde(default)]
@@ -50,58 +142,324 @@ struct RangeSpec {
     end: u32,
 }
 
+#[derive(Debug, Deserialize)]
+struct TaskSpec {
+    name: String,
+    start_ms: f64,
+    end_ms: f64,
+}
+
 #[derive(Debug, Default, Deserialize)]
 struct OutputSection {
     #[serde(default)]
-    mask_path: Option<String>,
+    mask_dir: Option<PathBuf>,
 }
 
-#[derive(Debug, Deserialize)]
-struct Op {
-    #[serde(default)]
-    op_type: String,
-    #[serde(default)]
-    params: toml::value::Table,
+/// Manifest entry describing one generated mask
+#[derive(Debug, Serialize)]
+struct MaskEntry {
+    task: String,
+    mask_id: u32,
+    path: String,
+    start_ms: f64,
+    end_ms: f64,
+    active: u64,
+    total: u64,
 }
 
 impl TtrCommand {
-    pub async fn execute(self, _workspace: PathBuf, _config: Option<PathBuf>) -> CliResult<()> {
-        // Parse program
+    pub async fn execute(self, workspace: PathBuf, _config: Option<PathBuf>) -> CliResult<()> {
+        match self.sub {
+            TtrSubcommand::Generate(cmd) => cmd.execute(&Workspace::new(workspace)),
+            TtrSubcommand::MaskOp(cmd) => cmd.execute(),
+        }
+    }
+}
+
+impl TtrGenerate {
+    fn execute(self, workspace: &Workspace) -> CliResult<()> {
         let text = std::fs::read_to_string(&self.program)?;
         let prog: TtrProgram = toml::from_str(&text)
             .map_err(|e| CliError::config(format!("bad TTR program: {}", e)))?;
-
-        // Placeholder engine: just echoes input ranges into a JSON mask
-        let mut neurons: Vec<u32> = Vec::new();
-        for r in prog.inputs.ranges {
-            for id in r.start..=r.end {
-                neurons.push(id);
+        if prog.tasks.is_empty() {
+            return Err(CliError::config("TTR program defines no [[task]] windows"));
+        }
+        for task in &prog.tasks {
+            if task.end_ms.is_nan() || task.start_ms.is_nan() || task.end_ms <= task.start_ms {
+                return Err(CliError::config(format!(
+                    "task '{}' has an empty window ({} .. {} ms)",
+                    task.name, task.start_ms, task.end_ms
+                )));
             }
         }
-        neurons.sort_unstable();
-        neurons.dedup();
 
-        // For now, ignore ops/params and just emit the mask
-        let json = serde_json::json!({
-            "mask": {
-                "neurons": neurons,
-            },
-            "meta": {
-                "program": {
-                    "name": prog.program.name,
-                    "version": prog.program.version,
-                },
+        let spikes = load_spikes(&prog.activity.path)?;
+        let always: Vec<u32> = prog
+            .inputs
+            .ranges
+            .iter()
+            .flat_map(|r| r.start..=r.end)
+            .collect();
+        let observed = spikes
+            .iter()
+            .map(|&(_, id)| id)
+            .chain(always.iter().copied())
+            .max()
+            .map(|id| id + 1)
+            .unwrap_or(0);
+        let num_neurons = prog.activity.num_neurons.unwrap_or(observed);
+        if num_neurons < observed {
+            return Err(CliError::config(format!(
+                "activity.num_neurons = {} but neuron {} is referenced",
+                num_neurons,
+                observed - 1
+            )));
+        }
+
+        let rates = task_rates(&spikes, &prog.tasks, num_neurons as usize);
+        let out_dir = self
+            .output_dir
+            .or(prog.output.mask_dir)
+            .unwrap_or_else(|| workspace.masks_dir());
+        std::fs::create_dir_all(&out_dir)?;
+
+        let mut entries = Vec::with_capacity(prog.tasks.len());
+        for (t, task) in prog.tasks.iter().enumerate() {
+            let selected = select_neurons(&rates, t, &prog.criterion);
+            let mask = BitmapMask::from_indices(
+                MaskId::new(t as u32 + 1),
+                MaskType::ActivityMask,
+                GenerationId::new(0),
+                num_neurons as u64,
+                selected.into_iter().chain(always.iter().copied()),
+            )?;
+            let path = out_dir.join(format!("{}.vmsk", task.name));
+            shnn_storage::atomic::write_atomic(&path, &mask.export_vmsk()?)?;
+            println!(
+                "task {}: {}/{} neurons -> {}",
+                task.name,
+                mask.active_count(),
+                num_neurons,
+                path.display()
+            );
+            entries.push(MaskEntry {
+                task: task.name.clone(),
+                mask_id: t as u32 + 1,
+                path: path.display().to_string(),
+                start_ms: task.start_ms,
+                end_ms: task.end_ms,
+                active: mask.active_count(),
+                total: num_neurons as u64,
+            });
+        }
+
+        let manifest = serde_json::json!({
+            "program": {
+                "name": prog.program.name,
+                "version": prog.program.version,
                 "source": self.program.display().to_string(),
-            }
+            },
+            "activity": prog.activity.path.display().to_string(),
+            "criterion": {
+                "min_rate_hz": prog.criterion.min_rate_hz,
+                "min_selectivity": prog.criterion.min_selectivity,
+            },
+            "masks": entries,
         });
+        std::fs::write(
+            out_dir.join("manifest.json"),
+            serde_json::to_string_pretty(&manifest).map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?,
+        )?;
+
+        info!("Wrote {} task masks to {}", entries.len(), out_dir.display());
+        Ok(())
+    }
+}
 
-        if let Some(parent) = self.output.parent() {
-            std::fs::create_dir_all(parent)?;
+impl TtrMaskOp {
+    fn execute(self) -> CliResult<()> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|spec| load_mask(spec))
+            .collect::<CliResult<Vec<_>>>()?;
+
+        if self.op == MaskOpKind::Bundle {
+            if self.name.is_some() {
+                return Err(CliError::invalid_args("--name is not used with bundle"));
+            }
+            let mut collection = MaskCollection::new();
+            for (name, mask) in inputs {
+                if collection.get(&name).is_some() {
+                    return Err(CliError::invalid_args(format!("duplicate mask name '{}'", name)));
+                }
+                collection.insert(name, mask);
+            }
+            write_file(&self.output, &collection.to_bytes()?)?;
+            println!(
+                "bundled {} masks ({}) -> {}",
+                collection.len(),
+                collection.names().collect::<Vec<_>>().join(", "),
+                self.output.display()
+            );
+            return Ok(());
         }
-        std::fs::write(&self.output, serde_json::to_string_pretty(&json)
-            .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?)?;
 
-        info!("Wrote JSON mask to {}", self.output.display());
+        let mut masks = inputs.into_iter().map(|(_, m)| m);
+        let first = masks.next().expect("clap requires at least one input");
+        let rest: Vec<_> = masks.collect();
+        let result = match self.op {
+            MaskOpKind::Invert => {
+                if !rest.is_empty() {
+                    return Err(CliError::invalid_args("invert takes exactly one input"));
+                }
+                first.inverted()
+            }
+            _ if rest.is_empty() => {
+                return Err(CliError::invalid_args(format!("{:?} needs at least two inputs", self.op)));
+            }
+            MaskOpKind::Union => rest.iter().try_fold(first, |acc, m| acc.union_with(m))?,
+            MaskOpKind::Intersect => rest.iter().try_fold(first, |acc, m| acc.intersect_with(m))?,
+            MaskOpKind::Difference => rest.iter().try_fold(first, |acc, m| acc.difference_with(m))?,
+            MaskOpKind::Bundle => unreachable!("handled above"),
+        };
+
+        match &self.name {
+            Some(name) => {
+                let mut collection = if self.output.exists() {
+                    MaskCollection::from_bytes(&std::fs::read(&self.output)?)?
+                } else {
+                    MaskCollection::new()
+                };
+                collection.insert(name.clone(), result.clone());
+                write_file(&self.output, &collection.to_bytes()?)?;
+            }
+            None => write_file(&self.output, &result.export_vmsk()?)?,
+        }
+        println!(
+            "{:?}: {}/{} active -> {}{}",
+            self.op,
+            result.active_count(),
+            result.total_count(),
+            self.output.display(),
+            self.name.as_deref().map(|n| format!("#{}", n)).unwrap_or_default()
+        );
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Load a mask from `file.vmsk` or `collection.vmsk#name`, returning its name
+pub(crate) fn load_mask(spec: &str) -> CliResult<(String, BitmapMask)> {
+    let (path, name) = match spec.rsplit_once('#') {
+        Some((path, name)) => (Path::new(path), Some(name)),
+        None => (Path::new(spec), None),
+    };
+    let bytes = std::fs::read(path)?;
+    if MaskCollection::is_collection(&bytes) {
+        let collection = MaskCollection::from_bytes(&bytes)?;
+        let available = || collection.names().collect::<Vec<_>>().join(", ");
+        let name = name.ok_or_else(|| {
+            CliError::invalid_args(format!(
+                "{} is a mask collection; select one with #name ({})",
+                path.display(),
+                available()
+            ))
+        })?;
+        let mask = collection.get(name).cloned().ok_or_else(|| {
+            CliError::invalid_args(format!("no mask '{}' in {} ({})", name, path.display(), available()))
+        })?;
+        return Ok((name.to_string(), mask));
+    }
+    if name.is_some() {
+        return Err(CliError::invalid_args(format!("{} is a single mask, not a collection", path.display())));
+    }
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("mask").to_string();
+    Ok((stem, BitmapMask::import_vmsk(&bytes)?))
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> CliResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Load `(time_ns, neuron_id)` spikes from nir run output (VEVT or JSON)
+fn load_spikes(path: &Path) -> CliResult<Vec<(u64, u32)>> {
+    let bytes = std::fs::read(path)?;
+    if bytes.starts_with(&shnn_storage::magic::VEVT) {
+        let (_, events) = shnn_storage::vevt::decode_vevt(&bytes)?;
+        return Ok(events
+            .into_iter()
+            .filter(|e| e.event_type == 0)
+            .map(|e| (e.timestamp, e.source_id))
+            .collect());
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&bytes).map_err(|e| {
+        CliError::invalid_args(format!("{} is neither VEVT nor JSON: {}", path.display(), e))
+    })?;
+    let list = json
+        .pointer("/results/spikes")
+        .or_else(|| json.get("spikes"))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| CliError::invalid_args(format!("{} has no spikes array", path.display())))?;
+    list.iter()
+        .map(|s| {
+            let id = s.get("neuron_id").and_then(|v| v.as_u64());
+            let t = s.get("time_ns").and_then(|v| v.as_u64());
+            match (t, id) {
+                (Some(t), Some(id)) => Ok((t, id as u32)),
+                _ => Err(CliError::invalid_args(format!("malformed spike record: {}", s))),
+            }
+        })
+        .collect()
+}
+
+/// Firing rate (Hz) of every neuron within every task window, indexed `[task][neuron]`
+fn task_rates(spikes: &[(u64, u32)], tasks: &[TaskSpec], num_neurons: usize) -> Vec<Vec<f64>> {
+    tasks
+        .iter()
+        .map(|task| {
+            let (start, end) = (task.start_ms * 1e6, task.end_ms * 1e6);
+            let mut counts = vec![0u64; num_neurons];
+            for &(t, id) in spikes {
+                let t = t as f64;
+                if t >= start && t < end {
+                    counts[id as usize] += 1;
+                }
+            }
+            let duration_s = (task.end_ms - task.start_ms) / 1e3;
+            counts.into_iter().map(|c| c as f64 / duration_s).collect()
+        })
+        .collect()
+}
+
+/// Neurons meeting the criterion for task `t`
+fn select_neurons(rates: &[Vec<f64>], t: usize, criterion: &CriterionSection) -> Vec<u32> {
+    let others = rates.len().saturating_sub(1);
+    (0..rates[t].len())
+        .filter(|&n| {
+            let rate = rates[t][n];
+            if rate <= 0.0 || rate < criterion.min_rate_hz {
+                return false;
+            }
+            match criterion.min_selectivity {
+                Some(min) if others > 0 => {
+                    let baseline = rates
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| i != t)
+                        .map(|(_, r)| r[n])
+                        .sum::<f64>()
+                        / others as f64;
+                    baseline == 0.0 || rate / baseline >= min
+                }
+                _ => true,
+            }
+        })
+        .map(|n| n as u32)
+        .collect()
+}
+
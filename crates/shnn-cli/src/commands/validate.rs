@@ -0,0 +1,177 @@
+//! Reference-model validation against analytic solutions
+//!
+//! Runs the canonical scenarios of `shnn_runtime::validation` (LIF f-I curve,
+//! Poisson-driven rate, STDP pair protocol), prints each check next to its
+//! analytic value and fails when any deviation exceeds its tolerance.
+
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+use tracing::info;
+
+use shnn_runtime::validation::run_scenario;
+use shnn_runtime::{IntegratorKind, ScenarioOutcome, ValidationConfig, ValidationScenario};
+
+use crate::commands::inspect::required_time_ns;
+use crate::error::{CliError, CliResult};
+
+/// Report format identifier; bump the version when fields change meaning
+const REPORT_FORMAT: &str = "snn-validate-report";
+const REPORT_VERSION: u32 = 1;
+
+/// Run reference scenarios with known analytic answers
+#[derive(Args, Debug)]
+pub struct ValidateCommand {
+    /// Scenarios to run (repeatable; defaults to all): lif-fi, poisson-rate, stdp-pair
+    #[arg(long = "scenario")]
+    pub scenarios: Vec<String>,
+
+    /// Simulation step
+    #[arg(long, default_value = "0.1ms")]
+    pub dt: String,
+
+    /// Membrane integrator: euler, exponential_euler or rk4
+    #[arg(long, default_value = "euler")]
+    pub integrator: String,
+
+    /// Simulated time of the Poisson scenario
+    #[arg(long, default_value = "20s")]
+    pub duration: String,
+
+    /// Stimulus seed
+    #[arg(long, default_value_t = 42)]
+    pub seed: u64,
+
+    /// Write the JSON report to this path
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckReport {
+    name: String,
+    unit: &'static str,
+    expected: f64,
+    measured: f64,
+    deviation: f64,
+    relative_deviation: Option<f64>,
+    tolerance: f64,
+    passed: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ScenarioReport {
+    name: &'static str,
+    description: &'static str,
+    passed: bool,
+    checks: Vec<CheckReport>,
+}
+
+impl ScenarioReport {
+    fn new(outcome: &ScenarioOutcome) -> Self {
+        Self {
+            name: outcome.scenario.as_str(),
+            description: outcome.scenario.description(),
+            passed: outcome.passed(),
+            checks: outcome.checks.iter()
+                .map(|check| CheckReport {
+                    name: check.name.clone(),
+                    unit: check.unit,
+                    expected: check.expected,
+                    measured: check.measured,
+                    deviation: check.deviation(),
+                    relative_deviation: check.relative_deviation(),
+                    tolerance: check.tolerance,
+                    passed: check.passed(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateReport {
+    format: &'static str,
+    version: u32,
+    snn_version: &'static str,
+    dt_ns: u64,
+    integrator: &'static str,
+    seed: u64,
+    passed: bool,
+    scenarios: Vec<ScenarioReport>,
+}
+
+impl ValidateCommand {
+    pub async fn execute(self) -> CliResult<()> {
+        let scenarios = if self.scenarios.is_empty() {
+            ValidationScenario::ALL.to_vec()
+        } else {
+            self.scenarios.iter()
+                .map(|name| name.parse::<ValidationScenario>())
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let config = ValidationConfig {
+            dt_ns: required_time_ns("--dt", &self.dt)?,
+            integrator: self.integrator.parse::<IntegratorKind>()?,
+            poisson_duration_ns: required_time_ns("--duration", &self.duration)?,
+            seed: self.seed,
+            ..ValidationConfig::default()
+        };
+
+        let mut reports = Vec::with_capacity(scenarios.len());
+        for scenario in scenarios {
+            info!("Validating '{}': {}", scenario.as_str(), scenario.description());
+            reports.push(ScenarioReport::new(&run_scenario(scenario, &config)?));
+        }
+
+        println!(
+            "{:<13} {:<16} {:>12} {:>12} {:>9} {:>11}  {}",
+            "SCENARIO", "CHECK", "EXPECTED", "MEASURED", "DEV %", "TOLERANCE", "STATUS"
+        );
+        for scenario in &reports {
+            for check in &scenario.checks {
+                let relative = check.relative_deviation
+                    .map_or_else(|| "-".to_string(), |r| format!("{:+.2}", r * 100.0));
+                println!(
+                    "{:<13} {:<16} {:>12.6} {:>12.6} {:>9} {:>11.3e}  {}",
+                    scenario.name,
+                    check.name,
+                    check.expected,
+                    check.measured,
+                    relative,
+                    check.tolerance,
+                    if check.passed { "ok" } else { "FAIL" }
+                );
+            }
+        }
+        let failed: usize = reports.iter()
+            .map(|scenario| scenario.checks.iter().filter(|check| !check.passed).count())
+            .sum();
+
+        if let Some(path) = &self.output {
+            let report = ValidateReport {
+                format: REPORT_FORMAT,
+                version: REPORT_VERSION,
+                snn_version: env!("CARGO_PKG_VERSION"),
+                dt_ns: config.dt_ns,
+                integrator: config.integrator.as_str(),
+                seed: config.seed,
+                passed: failed == 0,
+                scenarios: reports,
+            };
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+            std::fs::write(path, json)?;
+            info!("Wrote validation report to {}", path.display());
+        }
+
+        if failed > 0 {
+            return Err(CliError::ValidationFailed(failed));
+        }
+        println!("All validation checks passed");
+        Ok(())
+    }
+}
@@ -1,20 +1,34 @@
 #![allow(clippy::single_match)]
 //! Visualization and analysis commands (completed minimal layer)
-//! - Static server for SPA assets
-//! - JSON endpoints:
+//! - Static server for SPA assets, over HTTP or HTTPS (`--tls-cert`/`--tls-key`, PEM)
+//! - JSON endpoints (with `--token`, each needs `Authorization: Bearer <token>` or `?token=`):
 //!   - GET /api/health
-//!   - GET /api/list (list result files in results_dir)
-//!   - GET /api/spikes[?file=relative.json] (serve spikes JSON)
-//! - Frontend draws a basic spike raster on a Canvas using WebGL-like 2D primitives (Canvas 2D for portability)
+//!   - GET /api/list (result files in results_dir, and run manifests of the workspace run store)
+//!   - GET /api/spikes[?file=relative.json | ?run=<id> | ?live=1] (serve spikes JSON)
+//!   - GET /api/firing-patterns[?file=... | ?run=<id>][&burst_isi=10ms] (per-neuron firing pattern classification)
+//!   - GET /api/oscillations[?file=... | ?run=<id>][&groups=0..79,80..99] (rate spectrum, synchrony, group coherence)
+//!   - GET /api/weight-updates[?file=run.vlog] (plasticity weight changes of an event log, per synapse)
+//!   - GET /api/control, POST /api/control/pause|resume, POST /api/params (with `--live <model.nirt>`:
+//!     pause the paced run and change Poisson stimulus rates or the modulation level between steps)
+//! - Frontend draws a basic spike raster on a Canvas using WebGL-like 2D primitives (Canvas 2D for portability);
+//!   results carrying `positions` (structure.positions@v1) can also be drawn as a raster ordered by
+//!   position or as a spatial map of spike counts
 
 use clap::{Args, Subcommand, ValueEnum};
-use std::io::{Read, Write};
+use std::io::{BufReader, Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use tracing::{error, info, warn};
 
+use shnn_runtime::{NeuronId, ParamUpdate, SimulationControl, Spike, SpikeSink};
+use shnn_storage::{export, EventLog, RateMatrix, SpikeWindow, StreamKind, WeightChange};
+
+use crate::commands::inspect::{firing_patterns, oscillations, parse_groups, parse_time_ns, weight_updates};
+use crate::commands::nir::load_paced_program;
+use crate::config::Config;
+use crate::runs::RunStore;
 use crate::error::{CliError, CliResult};
 
 /// Visualization and analysis tools
@@ -28,7 +42,7 @@ pub struct VizCommand {
 pub enum VizSubcommand {
     /// Serve static viz and JSON endpoints
     Serve(VizServe),
-    /// Export network topology (stub)
+    /// Export spike recordings as NPY/Parquet (network formats are stubs)
     Export(VizExport),
     /// Generate static plots (stub)
     Plot(VizPlot),
@@ -37,18 +51,33 @@ pub enum VizSubcommand {
 /// Start visualization server
 #[derive(Args, Debug)]
 pub struct VizServe {
-    /// Host to bind to
-    #[arg(long, default_value = "127.0.0.1")]
-    pub host: String,
-    /// Port to listen on
-    #[arg(long, default_value = "7878")]
-    pub port: u16,
-    /// Directory containing simulation results (JSON)
+    /// Host to bind to (default: viz.host config, 127.0.0.1)
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Port to listen on (default: viz.port config, 7878)
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Directory containing simulation results (JSON; default: output.dir config if it exists)
     #[arg(long)]
     pub results_dir: Option<PathBuf>,
     /// Default results JSON to serve (if provided)
     #[arg(long)]
     pub results_file: Option<PathBuf>,
+    /// Require this bearer token on /api/* requests (header or ?token=)
+    #[arg(long, env = "HSNN_VIZ_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+    /// PEM certificate chain; serve HTTPS instead of HTTP
+    #[arg(long, requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+    /// PEM private key for --tls-cert
+    #[arg(long, requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+    /// Run this textual NIR model live (wall-clock paced) and expose pause/resume/parameter endpoints
+    #[arg(long)]
+    pub live: Option<PathBuf>,
+    /// Hold the live run before its first step until resumed
+    #[arg(long, requires = "live")]
+    pub paused: bool,
     /// Run in background (not implemented; logs note)
     #[arg(long)]
     pub background: bool,
@@ -56,7 +85,7 @@ pub struct VizServe {
 
 #[derive(Args, Debug)]
 pub struct VizExport {
-    /// Input network (NIR or runtime export)
+    /// Input network (NIR or runtime export), or a spike recording (.vevt or results .json) for npy/parquet
     pub input: PathBuf,
     /// Output file
     #[arg(short, long)]
@@ -64,6 +93,9 @@ pub struct VizExport {
     /// Format
     #[arg(short, long, default_value = "graphml")]
     pub format: ExportFormat,
+    /// Export a rate matrix binned at this width (e.g. `5ms`) instead of the spike table
+    #[arg(long)]
+    pub bin: Option<String>,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -72,6 +104,10 @@ pub enum ExportFormat {
     Dot,
     Json,
     Svg,
+    /// NumPy array (spike table: structured `time_ns`/`neuron_id`; rates: neurons x bins)
+    Npy,
+    /// Parquet table (spike table, or rates in long form)
+    Parquet,
 }
 
 #[derive(Args, Debug)]
@@ -98,12 +134,37 @@ struct ServerState {
     static_root: PathBuf,
     results_dir: Option<PathBuf>,
     default_results: Option<PathBuf>,
+    runs: RunStore,
+    token: Option<String>,
+    live: Option<LiveRun>,
+}
+
+/// A model running in the background under [`SimulationControl`]
+struct LiveRun {
+    source: PathBuf,
+    control: SimulationControl,
+    /// Current Poisson stimulus rates as `(neuron, rate_hz)`
+    stimuli: Mutex<Vec<(u32, f32)>>,
+    /// Spikes emitted so far as `(time_ns, neuron)`
+    spikes: Arc<Mutex<Vec<(u64, u32)>>>,
+}
+
+/// Collects a live run's spikes for `/api/spikes?live=1`
+#[derive(Debug)]
+struct LiveSink(Arc<Mutex<Vec<(u64, u32)>>>);
+
+impl SpikeSink for LiveSink {
+    fn publish(&mut self, spikes: &[Spike]) -> shnn_runtime::Result<()> {
+        let mut recorded = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        recorded.extend(spikes.iter().map(|s| (s.time.as_nanos(), s.neuron_id.raw())));
+        Ok(())
+    }
 }
 
 impl VizCommand {
-    pub async fn execute(self, _workspace: PathBuf, _config: Option<PathBuf>) -> CliResult<()> {
+    pub async fn execute(self, workspace: PathBuf, config: Option<PathBuf>) -> CliResult<()> {
         match self.sub {
-            VizSubcommand::Serve(cmd) => cmd.execute().await,
+            VizSubcommand::Serve(cmd) => cmd.execute(&Config::load(&workspace, config.as_deref())?).await,
             VizSubcommand::Export(cmd) => cmd.execute().await,
             VizSubcommand::Plot(cmd) => cmd.execute().await,
         }
@@ -111,20 +172,41 @@ impl VizCommand {
 }
 
 impl VizServe {
-    pub async fn execute(self) -> CliResult<()> {
+    pub async fn execute(self, settings: &Config) -> CliResult<()> {
         // Ensure static assets exist
         let static_root = ensure_static_assets()?;
 
-        let addr = format!("{}:{}", self.host, self.port);
-        info!("Starting viz server at http://{}", addr);
+        let host = self.host.clone().unwrap_or_else(|| settings.viz_host().to_string());
+        let addr = format!("{}:{}", host, self.port.unwrap_or_else(|| settings.viz_port()));
+        let results_dir = self.results_dir.clone()
+            .or_else(|| Some(settings.output_dir()).filter(|dir| dir.is_dir()));
+        let tls = match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => Some(tls_config(cert, key)?),
+            _ => None,
+        };
+        let token = self.token.clone().filter(|t| !t.is_empty());
+        info!("Starting viz server at {}://{}", if tls.is_some() { "https" } else { "http" }, addr);
+        match &token {
+            Some(_) => info!("API requests require the bearer token (open /?token=<token> in a browser)"),
+            None if !is_loopback(&host) => warn!("{} is reachable from the network and the API is unauthenticated; pass --token", host),
+            None => {}
+        }
         if self.background {
             warn!("--background not implemented; running in foreground");
         }
 
+        let live = match &self.live {
+            Some(model) => Some(start_live_run(model, self.paused)?),
+            None => None,
+        };
+
         let state = Arc::new(ServerState {
             static_root,
-            results_dir: self.results_dir.clone(),
+            results_dir,
             default_results: self.results_file.clone(),
+            runs: RunStore::open(settings),
+            token,
+            live,
         });
 
         let listener = TcpListener::bind(&addr)
@@ -134,8 +216,13 @@ impl VizServe {
             match stream {
                 Ok(stream) => {
                     let st = state.clone();
+                    let tls = tls.clone();
                     thread::spawn(move || {
-                        if let Err(e) = handle_client(stream, st) {
+                        let served = match tls {
+                            Some(config) => serve_tls(stream, config, st),
+                            None => handle_client(stream, st),
+                        };
+                        if let Err(e) = served {
                             error!("client error: {}", e);
                         }
                     });
@@ -147,8 +234,177 @@ impl VizServe {
     }
 }
 
+/// Compile `model` with realtime pacing and run it on a background thread
+///
+/// The program is built on that thread (the engine never crosses threads);
+/// compile errors are reported before the server starts listening.
+fn start_live_run(model: &Path, paused: bool) -> CliResult<LiveRun> {
+    let control = SimulationControl::new();
+    if paused {
+        control.pause();
+    }
+    let spikes = Arc::new(Mutex::new(Vec::new()));
+    let (ready, loaded) = mpsc::channel();
+    let path = model.to_path_buf();
+    let (engine_control, sink) = (control.clone(), LiveSink(spikes.clone()));
+    thread::spawn(move || {
+        let mut program = match load_paced_program(&path) {
+            Ok(program) => program,
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+        program.engine.set_control(engine_control);
+        program.engine.add_spike_sink(Box::new(sink));
+        let rates = program.engine.poisson_rates().into_iter().map(|(n, r)| (n.raw(), r)).collect();
+        let _ = ready.send(Ok(rates));
+        match program.engine.run() {
+            Ok(result) => info!("Live run finished: {} steps, {} spikes", result.steps_executed, result.spikes.len()),
+            Err(e) => error!("Live run failed: {}", e),
+        }
+    });
+    let stimuli = loaded.recv()
+        .map_err(|_| CliError::Generic(anyhow::anyhow!("live run thread exited before compiling")))??;
+    info!("Live run of {}{}", model.display(), if paused { " (paused; POST /api/control/resume)" } else { "" });
+    Ok(LiveRun { source: model.to_path_buf(), control, stimuli: Mutex::new(stimuli), spikes })
+}
+
+impl LiveRun {
+    fn status_json(&self) -> serde_json::Value {
+        let status = self.control.status();
+        let stimuli: Vec<_> = self.stimuli.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|&(neuron, rate_hz)| serde_json::json!({ "neuron": neuron, "rate_hz": rate_hz }))
+            .collect();
+        serde_json::json!({
+            "live": true,
+            "source": self.source.display().to_string(),
+            "paused": status.paused,
+            "finished": status.finished,
+            "step": status.step,
+            "time_ns": status.time_ns,
+            "applied": status.applied,
+            "stimuli": stimuli,
+        })
+    }
+
+    /// Spikes so far, shaped like a results file
+    fn results_json(&self) -> serde_json::Value {
+        let spikes: Vec<_> = self.spikes.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|&(time_ns, neuron_id)| serde_json::json!({
+                "neuron_id": neuron_id,
+                "time_ns": time_ns,
+                "time_ms": time_ns as f64 / 1_000_000.0,
+            }))
+            .collect();
+        serde_json::json!({ "results": {
+            "spike_count": spikes.len(),
+            "duration_ns": self.control.status().time_ns,
+            "spikes": spikes,
+        }})
+    }
+
+    /// Queue the changes in a `/api/params` body:
+    /// `{"stimulus": {"neuron": N, "rate_hz": R}}` and/or `{"modulator_level": L}`
+    fn apply_params(&self, body: &str) -> Result<(), String> {
+        let params: serde_json::Value = serde_json::from_str(body).map_err(|e| format!("invalid JSON: {}", e))?;
+        let mut updates = Vec::new();
+        if let Some(stimulus) = params.get("stimulus") {
+            let neuron = stimulus.get("neuron").and_then(|v| v.as_u64()).and_then(|v| u32::try_from(v).ok())
+                .ok_or("stimulus.neuron must be a neuron id")?;
+            let rate_hz = stimulus.get("rate_hz").and_then(|v| v.as_f64()).ok_or("stimulus.rate_hz must be a number")?;
+            if !self.stimuli.lock().unwrap_or_else(|e| e.into_inner()).iter().any(|&(n, _)| n == neuron) {
+                return Err(format!("no Poisson stimulus drives neuron {}", neuron));
+            }
+            updates.push(ParamUpdate::StimulusRate { neuron: NeuronId::new(neuron), rate_hz: rate_hz as f32 });
+        }
+        if let Some(level) = params.get("modulator_level") {
+            let level = level.as_f64().ok_or("modulator_level must be a number")?;
+            updates.push(ParamUpdate::ModulatorLevel(level as f32));
+        }
+        if updates.is_empty() {
+            return Err("expected \"stimulus\" or \"modulator_level\"".to_string());
+        }
+        for update in &updates {
+            update.validate().map_err(|e| e.to_string())?;
+        }
+        for update in updates {
+            if let ParamUpdate::StimulusRate { neuron, rate_hz } = update {
+                let mut stimuli = self.stimuli.lock().unwrap_or_else(|e| e.into_inner());
+                for entry in stimuli.iter_mut().filter(|(n, _)| *n == neuron.raw()) {
+                    entry.1 = rate_hz;
+                }
+            }
+            self.control.update(update).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// rustls server configuration from a PEM certificate chain and private key
+fn tls_config(cert: &Path, key: &Path) -> CliResult<Arc<rustls::ServerConfig>> {
+    let open = |path: &Path| {
+        std::fs::File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| CliError::invalid_args(format!("{}: {}", path.display(), e)))
+    };
+    let certs = rustls_pemfile::certs(&mut open(cert)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| CliError::invalid_args(format!("{}: {}", cert.display(), e)))?;
+    if certs.is_empty() {
+        return Err(CliError::invalid_args(format!("{}: no PEM certificates", cert.display())));
+    }
+    let private_key = rustls_pemfile::private_key(&mut open(key)?)
+        .map_err(|e| CliError::invalid_args(format!("{}: {}", key.display(), e)))?
+        .ok_or_else(|| CliError::invalid_args(format!("{}: no PEM private key", key.display())))?;
+    let config = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, private_key)
+        .map_err(|e| CliError::invalid_args(format!("TLS certificate/key rejected: {}", e)))?;
+    Ok(Arc::new(config))
+}
+
+fn serve_tls(stream: TcpStream, config: Arc<rustls::ServerConfig>, state: Arc<ServerState>) -> CliResult<()> {
+    let connection = rustls::ServerConnection::new(config)
+        .map_err(|e| CliError::Generic(anyhow::anyhow!("TLS session: {}", e)))?;
+    let mut tls = rustls::StreamOwned::new(connection, stream);
+    handle_client(&mut tls, state)?;
+    tls.conn.send_close_notify();
+    tls.flush()?;
+    Ok(())
+}
+
+fn is_loopback(host: &str) -> bool {
+    host == "localhost"
+        || host.trim_matches(|c| c == '[' || c == ']')
+            .parse::<std::net::IpAddr>()
+            .map_or(false, |ip| ip.is_loopback())
+}
+
+/// Bearer token from the Authorization header or the `token` query parameter
+fn request_token(request: &str, query: Option<&str>) -> Option<String> {
+    request.lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer ").map(|t| t.trim().to_string()))
+        .or_else(|| query.and_then(|q| query_param(q, "token")))
+}
+
+/// Length-revealing but otherwise constant-time comparison
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
 impl VizExport {
     pub async fn execute(self) -> CliResult<()> {
+        if matches!(self.format, ExportFormat::Npy | ExportFormat::Parquet) {
+            return self.export_recording();
+        }
         info!(
             "Export stub: {} -> {} ({:?})",
             self.input.display(),
@@ -160,6 +416,72 @@ impl VizExport {
     }
 }
 
+    fn export_recording(&self) -> CliResult<()> {
+        let spikes = read_spike_recording(&self.input)?;
+        let bytes = match &self.bin {
+            Some(bin) => {
+                let bin_ns = parse_time_ns("--bin", bin)?
+                    .ok_or_else(|| CliError::invalid_args("--bin needs a duration such as 5ms"))?;
+                let end = spikes.iter().map(|&(t, _)| t.saturating_add(1)).max().unwrap_or(1);
+                let matrix = RateMatrix::bin(spikes, SpikeWindow::new(0, end)?, bin_ns, None)
+                    .map_err(|e| CliError::invalid_args(e.to_string()))?;
+                match self.format {
+                    ExportFormat::Parquet => export::rates_to_parquet(&matrix)?,
+                    _ => matrix.to_npy()?,
+                }
+            }
+            None => match self.format {
+                ExportFormat::Parquet => export::spikes_to_parquet(&spikes)?,
+                _ => export::spikes_to_npy(&spikes)?,
+            },
+        };
+        if let Some(parent) = self.output.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.output, bytes)?;
+        info!(
+            "Exported {} ({}) from {} to {}",
+            if self.bin.is_some() { "rate matrix" } else { "spike table" },
+            if matches!(self.format, ExportFormat::Parquet) { "parquet" } else { "npy" },
+            self.input.display(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+/// `(time_ns, neuron_id)` spikes of a VEVT recording or a `{"results": {"spikes": [...]}}` JSON file
+pub(crate) fn read_spike_recording(path: &Path) -> CliResult<Vec<(u64, u32)>> {
+    if path.extension().and_then(|s| s.to_str()) == Some("vevt") {
+        let (_, events) = shnn_storage::vevt::decode_vevt(&std::fs::read(path)?)?;
+        return Ok(events.iter()
+            .filter(|e| e.event_type == 0 && e.source_id != u32::MAX)
+            .map(|e| (e.timestamp, e.source_id))
+            .collect());
+    }
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|e| CliError::invalid_args(format!("{}: not a VEVT or results JSON file: {}", path.display(), e)))?;
+    let spikes = value["results"]["spikes"].as_array()
+        .ok_or_else(|| CliError::invalid_args(format!("{}: missing results.spikes", path.display())))?;
+    spikes.iter()
+        .map(|spike| match (spike["time_ns"].as_u64(), spike["neuron_id"].as_u64()) {
+            (Some(time_ns), Some(id)) if id <= u32::MAX as u64 => Ok((time_ns, id as u32)),
+            _ => Err(CliError::invalid_args(format!("{}: malformed spike {}", path.display(), spike))),
+        })
+        .collect()
+}
+
+/// Weight changes of every weight-update stream of a VLOG event log, in time order
+pub(crate) fn read_weight_changes(path: &Path) -> CliResult<Vec<WeightChange>> {
+    let log = EventLog::from_bytes(&std::fs::read(path)?)?;
+    let mut changes: Vec<WeightChange> = log
+        .streams_of(StreamKind::WeightUpdates)
+        .flat_map(|(_, stream)| stream.weight_changes())
+        .collect();
+    changes.sort_by_key(|c| c.time_ns);
+    Ok(changes)
+}
+
 impl VizPlot {
     pub async fn execute(self) -> CliResult<()> {
         let out = self.output.unwrap_or_else(|| PathBuf::from("plots"));
@@ -169,18 +491,27 @@ impl VizPlot {
     }
 }
 
-fn handle_client(mut stream: TcpStream, state: Arc<ServerState>) -> CliResult<()> {
+fn handle_client<S: Read + Write>(mut stream: S, state: Arc<ServerState>) -> CliResult<()> {
     let mut buf = [0u8; 8192];
     let n = stream.read(&mut buf)?;
     if n == 0 {
         return Ok(());
     }
-    let req = String::from_utf8_lossy(&buf[..n]);
+    let data = read_body(&mut stream, buf[..n].to_vec())?;
+    let req = String::from_utf8_lossy(&data);
+    let request_body = req.split_once("\r\n\r\n").map_or("", |(_, body)| body);
     let mut lines = req.lines();
     let request_line = lines.next().unwrap_or("");
     let (method, full_path) = parse_request_line(request_line);
     let (path, query) = split_path_query(&full_path);
 
+    if let Some(expected) = &state.token {
+        let authorized = request_token(&req, query.as_deref()).map_or(false, |t| tokens_match(&t, expected));
+        if path.starts_with("/api/") && !authorized {
+            return respond_401(&mut stream);
+        }
+    }
+
     match (method, path.as_str()) {
         ("GET", "/api/health") => {
             let has_results = state.default_results.as_ref().map(|p| p.exists()).unwrap_or(false)
@@ -214,9 +545,34 @@ fn handle_client(mut stream: TcpStream, state: Arc<ServerState>) -> CliResult<()
                 }
             }
             entries.sort();
-            let json = serde_json::json!({ "files": entries });
+            let json = serde_json::json!({ "files": entries, "runs": state.runs.list() });
             respond_json(&mut stream, &serde_json::to_string(&json).unwrap())?;
         }
+        ("GET", "/api/control") => {
+            let json = state.live.as_ref().map_or_else(|| serde_json::json!({ "live": false }), LiveRun::status_json);
+            respond_json(&mut stream, &json.to_string())?;
+        }
+        ("POST", "/api/control/pause") | ("POST", "/api/control/resume") | ("POST", "/api/params") => {
+            let Some(live) = &state.live else {
+                return respond_error(&mut stream, "409 Conflict", "no live run (start the server with --live <model.nirt>)");
+            };
+            match path.as_str() {
+                "/api/control/pause" => live.control.pause(),
+                "/api/control/resume" => live.control.resume(),
+                _ => {
+                    if let Err(message) = live.apply_params(request_body) {
+                        return respond_error(&mut stream, "400 Bad Request", &message);
+                    }
+                }
+            }
+            respond_json(&mut stream, &live.status_json().to_string())?;
+        }
+        ("GET", "/api/spikes") if query.as_deref().and_then(|q| query_param(q, "live")).is_some() => {
+            match &state.live {
+                Some(live) => respond_json(&mut stream, &live.results_json().to_string())?,
+                None => respond_error(&mut stream, "409 Conflict", "no live run")?,
+            }
+        }
         ("GET", "/api/spikes") => {
             // optional query ?file=relative.(json|vevt)
             let mut body = serde_json::json!({ "results": { "spike_count": 0, "spikes": [] }});
@@ -272,6 +628,50 @@ fn handle_client(mut stream: TcpStream, state: Arc<ServerState>) -> CliResult<()
             }
             respond_json(&mut stream, &serde_json::to_string(&body).unwrap())?;
         }
+        ("GET", "/api/firing-patterns") => {
+            let Some(path) = resolve_results_path(&state, query.as_deref()) else {
+                return respond_error(&mut stream, "404 Not Found", "no recording (pass ?file= or ?run=)");
+            };
+            let burst_isi = query.as_deref().and_then(|q| query_param(q, "burst_isi"));
+            let report = read_spike_recording(&path).and_then(|spikes| {
+                let mut params = shnn_runtime::FiringPatternParams::default();
+                if let Some(ns) = parse_time_ns("burst_isi", burst_isi.as_deref().unwrap_or(""))? {
+                    params.burst_isi_ns = ns;
+                }
+                firing_patterns(&path, &spikes, &params, None)
+            });
+            match report {
+                Ok(report) => respond_json(&mut stream, &serde_json::to_string(&report).unwrap())?,
+                Err(e) => respond_error(&mut stream, "400 Bad Request", &e.to_string())?,
+            }
+        }
+        ("GET", "/api/oscillations") => {
+            let Some(path) = resolve_results_path(&state, query.as_deref()) else {
+                return respond_error(&mut stream, "404 Not Found", "no recording (pass ?file= or ?run=)");
+            };
+            let groups = query.as_deref().and_then(|q| query_param(q, "groups"));
+            let report = read_spike_recording(&path).and_then(|spikes| {
+                let groups = groups.as_deref().map(parse_groups).transpose()?;
+                oscillations(&path, &spikes, &shnn_runtime::SpectrumParams::default(), groups)
+            });
+            match report {
+                Ok(report) => respond_json(&mut stream, &serde_json::to_string(&report).unwrap())?,
+                Err(e) => respond_error(&mut stream, "400 Bad Request", &e.to_string())?,
+            }
+        }
+        ("GET", "/api/weight-updates") => {
+            let Some(path) = resolve_results_path(&state, query.as_deref()) else {
+                return respond_error(&mut stream, "404 Not Found", "no event log (pass ?file= or ?run=)");
+            };
+            let report = read_weight_changes(&path).map(|changes| {
+                let end = changes.iter().map(|c| c.time_ns.saturating_add(1)).max().unwrap_or(1);
+                weight_updates(&path, &changes, (0, end))
+            });
+            match report {
+                Ok(report) => respond_json(&mut stream, &serde_json::to_string(&report).unwrap())?,
+                Err(e) => respond_error(&mut stream, "400 Bad Request", &e.to_string())?,
+            }
+        }
         ("GET", "/") => {
             let index = state.static_root.join("index.html");
             serve_path(&mut stream, &index)?;
@@ -299,6 +699,29 @@ fn handle_client(mut stream: TcpStream, state: Arc<ServerState>) -> CliResult<()
     Ok(())
 }
 
+/// Complete a request whose `Content-Length` body did not arrive with the headers (bodies capped at 64 KiB)
+fn read_body(stream: &mut impl Read, mut data: Vec<u8>) -> CliResult<Vec<u8>> {
+    let Some(header_end) = data.windows(4).position(|w| w == b"\r\n\r\n") else { return Ok(data) };
+    let headers = String::from_utf8_lossy(&data[..header_end]).to_string();
+    let length = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(64 * 1024);
+    let wanted = header_end + 4 + length;
+    let mut chunk = [0u8; 8192];
+    while data.len() < wanted {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&chunk[..n]);
+    }
+    Ok(data)
+}
+
 fn parse_request_line(line: &str) -> (&str, String) {
     let mut parts = line.split_whitespace();
     let method = parts.next().unwrap_or("GET");
@@ -365,7 +788,7 @@ fn from_hex(b: u8) -> Option<u8> {
     }
 }
 
-fn serve_path(stream: &mut TcpStream, path: &Path) -> CliResult<()> {
+fn serve_path(stream: &mut impl Write, path: &Path) -> CliResult<()> {
     let content = std::fs::read(path)?;
     let mime = mime_for_path(path);
     write!(
@@ -378,7 +801,7 @@ fn serve_path(stream: &mut TcpStream, path: &Path) -> CliResult<()> {
     Ok(())
 }
 
-fn respond_json(stream: &mut TcpStream, body: &str) -> CliResult<()> {
+fn respond_json(stream: &mut impl Write, body: &str) -> CliResult<()> {
     write!(
         stream,
         "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n{}",
@@ -388,7 +811,30 @@ fn respond_json(stream: &mut TcpStream, body: &str) -> CliResult<()> {
     Ok(())
 }
 
-fn respond_404(stream: &mut TcpStream) -> CliResult<()> {
+fn respond_401(stream: &mut impl Write) -> CliResult<()> {
+    let body = b"Unauthorized";
+    write!(
+        stream,
+        "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    Ok(())
+}
+
+fn respond_error(stream: &mut impl Write, status: &str, message: &str) -> CliResult<()> {
+    let body = serde_json::json!({ "error": message }).to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+fn respond_404(stream: &mut impl Write) -> CliResult<()> {
     let body = b"Not Found";
     write!(
         stream,
@@ -406,11 +852,15 @@ fn mime_for_path(path: &Path) -> &'static str {
         Some("js") => "application/javascript; charset=utf-8",
         Some("json") => "application/json; charset=utf-8",
         Some("svg") => "image/svg+xml",
+        Some("wasm") => "application/wasm",
         _ => "application/octet-stream",
     }
 }
 
 fn resolve_results_path(state: &ServerState, query: Option<&str>) -> Option<PathBuf> {
+    if let Some(id) = query.and_then(|q| query_param(q, "run")) {
+        return state.runs.results_path(&id);
+    }
     if let Some(q) = query {
         if let Some(file_rel) = query_param(q, "file") {
             if let Some(dir) = &state.results_dir {
@@ -465,9 +915,41 @@ const DEFAULT_INDEX: &str = r#"<!doctype html>
         <label>File:
           <select id="fileSelect"></select>
         </label>
+        <label>Layout:
+          <select id="layoutSelect">
+            <option value="id">Raster by neuron id</option>
+            <option value="position">Raster by position</option>
+            <option value="map">Spatial map</option>
+          </select>
+        </label>
         <span class="status">Status: <span id="status">checking...</span></span>
       </section>
+      <section class="controls" id="wasmPanel" hidden>
+        <textarea id="nirText" rows="6" placeholder="Paste textual NIR to compile and run in the browser"></textarea>
+        <button id="runWasm">Run in browser</button>
+      </section>
+      <section class="controls" id="livePanel" hidden>
+        <button id="pauseLive">Pause</button>
+        <button id="resumeLive">Resume</button>
+        <label>Stimulus:
+          <select id="stimulusSelect"></select>
+        </label>
+        <input id="rateInput" type="number" min="0" step="1" placeholder="rate (Hz)">
+        <button id="applyRate">Set rate</button>
+        <input id="levelInput" type="number" step="0.1" placeholder="modulation level">
+        <button id="applyLevel">Set level</button>
+        <span id="liveStatus"></span>
+      </section>
       <canvas id="viz" width="1000" height="500"></canvas>
+      <section id="runsPanel" hidden>
+        <h2>Recorded runs <button id="compareRuns">Compare selected</button></h2>
+        <table id="runsTable">
+          <thead>
+            <tr><th></th><th>Run</th><th>NIR</th><th>NIR hash</th><th>Seed</th><th>Duration (ms)</th><th>Spikes</th><th>Mean rate (Hz)</th><th>Wall (ms)</th></tr>
+          </thead>
+          <tbody></tbody>
+        </table>
+      </section>
     </main>
     <script src="/app.js"></script>
   </body>
@@ -484,6 +966,12 @@ main { padding: 1rem; }
 canvas { border: 1px solid #ccc; background: #000; width: 100%; height: auto; max-height: 70vh; }
 label select { margin-left: 0.5rem; }
 button { padding: 0.25rem 0.75rem; }
+textarea { flex: 1; font-family: ui-monospace, monospace; }
+#livePanel input { width: 9rem; }
+#runsPanel h2 { font-size: 1rem; display: flex; gap: 1rem; align-items: center; }
+#runsTable { border-collapse: collapse; width: 100%; font-size: 0.875rem; }
+#runsTable th, #runsTable td { border-bottom: 1px solid #ccc; padding: 0.25rem 0.5rem; text-align: left; }
+#runsTable td.num { text-align: right; font-variant-numeric: tabular-nums; }
 "#;
 
 const DEFAULT_JS: &str = r#"
@@ -492,21 +980,39 @@ const fileSelect = document.getElementById('fileSelect');
 const canvas = document.getElementById('viz');
 const ctx = canvas.getContext('2d');
 const refreshBtn = document.getElementById('refresh');
+const layoutSelect = document.getElementById('layoutSelect');
+let lastResults = null;
 
 function setStatus(s) { statusEl.textContent = s; }
 
+// API token from /?token=..., kept for the tab and sent as a bearer header
+const urlToken = new URLSearchParams(location.search).get('token');
+if (urlToken) sessionStorage.setItem('hsnnToken', urlToken);
+const apiToken = sessionStorage.getItem('hsnnToken');
+
 async function getJSON(url) {
-  const res = await fetch(url, { cache: 'no-store' });
+  const headers = apiToken ? { Authorization: `Bearer ${apiToken}` } : {};
+  const res = await fetch(url, { cache: 'no-store', headers });
+  if (res.status === 401) throw new Error('Unauthorized (open /?token=<token>)');
   if (!res.ok) throw new Error(`HTTP ${res.status}`);
   return res.json();
 }
 
+async function postJSON(url, body=null) {
+  const headers = { 'Content-Type': 'application/json' };
+  if (apiToken) headers.Authorization = `Bearer ${apiToken}`;
+  const res = await fetch(url, { method: 'POST', headers, body: body ? JSON.stringify(body) : undefined });
+  const data = await res.json().catch(() => ({}));
+  if (!res.ok) throw new Error(data.error || `HTTP ${res.status}`);
+  return data;
+}
+
 async function refreshHealth() {
   try {
     const health = await getJSON('/api/health');
     setStatus(health.ok ? 'OK' : 'Error');
   } catch (e) {
-    setStatus('Unavailable');
+    setStatus(e.message.startsWith('Unauthorized') ? e.message : 'Unavailable');
   }
 }
 
@@ -520,28 +1026,104 @@ async function listFiles() {
       opt.textContent = f;
       fileSelect.appendChild(opt);
     }
+    listRuns(data.runs || []);
   } catch {
     // ignore
   }
 }
 
+// Manifests of the workspace run store (snn nir run --record)
+function listRuns(runs) {
+  const panel = document.getElementById('runsPanel');
+  const body = document.querySelector('#runsTable tbody');
+  body.innerHTML = '';
+  panel.hidden = runs.length === 0;
+  for (const run of runs) {
+    const tr = document.createElement('tr');
+    const cells = [
+      run.id,
+      run.nir_source,
+      run.nir_hash.slice(0, 12),
+      run.seed ?? '-',
+      (run.duration_ns / 1e6).toFixed(1),
+      run.metrics.spike_count,
+      run.metrics.mean_rate_hz.toFixed(2),
+      (run.wall_time_ns / 1e6).toFixed(1),
+    ];
+    const pick = document.createElement('td');
+    const box = document.createElement('input');
+    box.type = 'checkbox';
+    box.value = run.id;
+    pick.appendChild(box);
+    tr.appendChild(pick);
+    cells.forEach((value, i) => {
+      const td = document.createElement('td');
+      td.textContent = value;
+      if (i >= 3) td.className = 'num';
+      tr.appendChild(td);
+    });
+    body.appendChild(tr);
+  }
+}
+
+// One raster per selected run, stacked on a shared time axis
+async function compareRuns() {
+  const ids = [...document.querySelectorAll('#runsTable input:checked')].map(b => b.value).slice(0, 4);
+  if (ids.length === 0) {
+    setStatus('Select runs to compare');
+    return;
+  }
+  try {
+    const results = await Promise.all(ids.map(id => getJSON(`/api/spikes?run=${encodeURIComponent(id)}`)));
+    const tMax = Math.max(1e-6, ...results.map(r => (r.results.duration_ns ?? 0) / 1e6),
+      ...results.flatMap(r => (r.results.spikes || []).map(s => s.time_ms ?? 0)));
+    clearCanvas();
+    const h = canvas.height / ids.length;
+    results.forEach((r, i) => {
+      drawRaster(r.results.spikes, r.results.positions,
+        { x: 0, y: i * h, w: canvas.width, h, tRange: [0, tMax], title: `${ids[i]} (${r.results.spike_count ?? 0} spikes)` });
+    });
+    lastResults = null;
+    setStatus(`Comparing ${ids.length} runs`);
+  } catch (e) {
+    setStatus(`Compare failed: ${e.message ?? e}`);
+  }
+}
+
 function clearCanvas() {
   ctx.fillStyle = '#000';
   ctx.fillRect(0, 0, canvas.width, canvas.height);
 }
 
-function drawRaster(spikes) {
-  clearCanvas();
+// Row of each neuron: its id, or its rank when sorted by (z, y, x)
+function rowMapper(positions) {
+  if (layoutSelect.value !== 'position' || !positions || positions.length === 0) return n => n;
+  const order = [...positions].sort((a, b) => (a.z - b.z) || (a.y - b.y) || (a.x - b.x));
+  const rank = new Map(order.map((p, i) => [p.neuron_id, i]));
+  return n => rank.has(n) ? rank.get(n) : order.length + n;
+}
+
+// Raster in `area` (default: whole canvas); `area.tRange` shares the time axis between panels
+function drawRaster(spikes, positions=null, area=null) {
+  const row = rowMapper(positions);
+  if (!area) {
+    clearCanvas();
+    area = { x: 0, y: 0, w: canvas.width, h: canvas.height };
+  }
+  if (area.title) {
+    ctx.fillStyle = '#bbb';
+    ctx.fillText(area.title, area.x + 44, area.y + 12);
+  }
   if (!spikes || spikes.length === 0) {
     ctx.fillStyle = '#aaa';
-    ctx.fillText('No spikes', 20, 20);
+    ctx.fillText('No spikes', area.x + 20, area.y + 28);
     return;
   }
   // Determine bounds
   let tMin = Infinity, tMax = -Infinity, nMin = Infinity, nMax = -Infinity;
   for (const s of spikes) {
     const t = s.time_ms ?? (s.time_ns ? s.time_ns / 1_000_000.0 : 0.0);
-    const n = s.neuron_id ?? 0;
+    const n = row(s.neuron_id ?? 0);
     if (t < tMin) tMin = t;
     if (t > tMax) tMax = t;
     if (n < nMin) nMin = n;
@@ -549,9 +1131,10 @@ function drawRaster(spikes) {
   }
   if (!isFinite(tMin) || !isFinite(tMax)) { tMin = 0; tMax = 1; }
   if (!isFinite(nMin) || !isFinite(nMax)) { nMin = 0; nMax = 1; }
-  const w = canvas.width, h = canvas.height;
-  const padL = 40, padB = 20, padT = 10, padR = 10;
-  const innerW = w - padL - padR, innerH = h - padT - padB;
+  if (area.tRange) [tMin, tMax] = area.tRange;
+  const w = area.w, h = area.h;
+  const padL = area.x + 40, padB = 20, padT = area.y + (area.title ? 18 : 10), padR = 10;
+  const innerW = area.x + w - padL - padR, innerH = area.y + h - padT - padB;
 
   // axes
   ctx.strokeStyle = '#444';
@@ -570,7 +1153,7 @@ function drawRaster(spikes) {
   const r = 1.5;
   for (const s of spikes) {
     const t = s.time_ms ?? (s.time_ns ? s.time_ns / 1_000_000.0 : 0.0);
-    const n = s.neuron_id ?? 0;
+    const n = row(s.neuron_id ?? 0);
     const x = toX(t);
     const y = toY(n);
     ctx.beginPath();
@@ -584,10 +1167,49 @@ function drawRaster(spikes) {
   ctx.save();
   ctx.translate(padL - 28, padT + 20);
   ctx.rotate(-Math.PI / 2);
-  ctx.fillText(`Neuron IDs`, 0, 0);
+  ctx.fillText(layoutSelect.value === 'position' ? 'Neurons by position' : 'Neuron IDs', 0, 0);
   ctx.restore();
 }
 
+// Positions projected onto x/y, coloured by spike count
+function drawSpatialMap(spikes, positions) {
+  clearCanvas();
+  if (!positions || positions.length === 0) {
+    ctx.fillStyle = '#aaa';
+    ctx.fillText('No positions (add structure.positions@v1)', 20, 20);
+    return;
+  }
+  const counts = new Map();
+  for (const s of spikes || []) counts.set(s.neuron_id, (counts.get(s.neuron_id) || 0) + 1);
+  const maxCount = Math.max(1, ...counts.values());
+  const xs = positions.map(p => p.x), ys = positions.map(p => p.y);
+  const xMin = Math.min(...xs), xMax = Math.max(...xs), yMin = Math.min(...ys), yMax = Math.max(...ys);
+  const pad = 20;
+  const scale = Math.min(
+    (canvas.width - 2 * pad) / Math.max(1e-6, xMax - xMin),
+    (canvas.height - 2 * pad) / Math.max(1e-6, yMax - yMin));
+  const r = Math.max(2, Math.min(8, scale / 3));
+  for (const p of positions) {
+    const c = (counts.get(p.neuron_id) || 0) / maxCount;
+    ctx.fillStyle = c > 0 ? `rgb(${Math.round(40 + 215 * c)}, ${Math.round(200 * c)}, 60)` : '#333';
+    ctx.beginPath();
+    ctx.arc(pad + (p.x - xMin) * scale, canvas.height - pad - (p.y - yMin) * scale, r, 0, Math.PI * 2);
+    ctx.fill();
+  }
+  ctx.fillStyle = '#bbb';
+  ctx.fillText(`${positions.length} neurons, max ${maxCount} spikes`, pad, 12);
+}
+
+function drawResults(results) {
+  lastResults = results;
+  const spikes = results.spikes || [];
+  if (layoutSelect.value === 'map') {
+    drawSpatialMap(spikes, results.positions);
+  } else {
+    drawRaster(spikes, results.positions);
+  }
+}
+
 async function loadAndDraw(selectedFile=null) {
   let url = '/api/spikes';
   if (selectedFile) {
@@ -595,8 +1217,7 @@ async function loadAndDraw(selectedFile=null) {
   }
   try {
     const data = await getJSON(url);
-    const spikes = (data.results && data.results.spikes) ? data.results.spikes : [];
-    drawRaster(spikes);
+    drawResults(data.results || {});
   } catch {
     clearCanvas();
     ctx.fillStyle = '#f66';
@@ -614,9 +1235,99 @@ fileSelect.addEventListener('change', () => {
   loadAndDraw(f);
 });
 
+document.getElementById('compareRuns').addEventListener('click', compareRuns);
+
+layoutSelect.addEventListener('change', () => {
+  if (lastResults) drawResults(lastResults);
+});
+
+// In-browser runs, available when shnn-nir-wasm has been built into /pkg:
+// wasm-pack build crates/shnn-nir-wasm --target web --out-dir ../shnn-cli/static/viz/pkg
+let wasm = null;
+
+async function loadWasm() {
+  try {
+    const mod = await import('/pkg/shnn_nir_wasm.js');
+    await mod.default();
+    wasm = mod;
+    document.getElementById('wasmPanel').hidden = false;
+  } catch {
+    // not built; server-side results only
+  }
+}
+
+document.getElementById('runWasm').addEventListener('click', () => {
+  if (!wasm) return;
+  try {
+    const data = JSON.parse(wasm.run(document.getElementById('nirText').value));
+    drawResults(data.results);
+    setStatus(`Ran in browser: ${data.results.spike_count} spikes`);
+  } catch (e) {
+    setStatus(`NIR error: ${e.message ?? e}`);
+  }
+});
+
+// Live run (snn viz serve --live): pause, tweak stimulus rates or modulation, resume
+const liveStatus = document.getElementById('liveStatus');
+const stimulusSelect = document.getElementById('stimulusSelect');
+
+function showLiveStatus(status) {
+  const state = status.finished ? 'finished' : status.paused ? 'paused' : 'running';
+  liveStatus.textContent = `${state} at ${(status.time_ns / 1e6).toFixed(1)} ms (step ${status.step}, ${status.applied} changes applied)`;
+  const selected = stimulusSelect.value;
+  stimulusSelect.innerHTML = '';
+  for (const s of status.stimuli || []) {
+    const opt = document.createElement('option');
+    opt.value = s.neuron;
+    opt.textContent = `neuron ${s.neuron} (${s.rate_hz} Hz)`;
+    stimulusSelect.appendChild(opt);
+  }
+  if (selected) stimulusSelect.value = selected;
+}
+
+async function liveAction(url, body=null) {
+  try {
+    showLiveStatus(await postJSON(url, body));
+  } catch (e) {
+    setStatus(`Live control failed: ${e.message ?? e}`);
+  }
+}
+
+async function pollLive() {
+  try {
+    const status = await getJSON('/api/control');
+    showLiveStatus(status);
+    const data = await getJSON('/api/spikes?live=1');
+    drawResults(data.results || {});
+    if (!status.finished) setTimeout(pollLive, 500);
+  } catch {
+    setTimeout(pollLive, 2000);
+  }
+}
+
+async function startLive() {
+  try {
+    const status = await getJSON('/api/control');
+    if (!status.live) return false;
+  } catch {
+    return false;
+  }
+  document.getElementById('livePanel').hidden = false;
+  document.getElementById('pauseLive').addEventListener('click', () => liveAction('/api/control/pause'));
+  document.getElementById('resumeLive').addEventListener('click', () => liveAction('/api/control/resume'));
+  document.getElementById('applyRate').addEventListener('click', () => liveAction('/api/params',
+    { stimulus: { neuron: Number(stimulusSelect.value), rate_hz: Number(document.getElementById('rateInput').value) } }));
+  document.getElementById('applyLevel').addEventListener('click', () => liveAction('/api/params',
+    { modulator_level: Number(document.getElementById('levelInput').value) }));
+  pollLive();
+  return true;
+}
+
 async function boot() {
+  await loadWasm();
   await refreshHealth();
   await listFiles();
+  if (await startLive()) return;
   const f = fileSelect.value || null;
   await loadAndDraw(f);
 }
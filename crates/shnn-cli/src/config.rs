@@ -1,85 +1,294 @@
-//! Configuration management for hSNN CLI
+//! Layered configuration for the hSNN CLI
+//!
+//! Settings resolve from lowest to highest precedence:
+//! 1. built-in defaults ([`KEYS`])
+//! 2. the user file (`<config dir>/hsnn/config.toml`, or `--config <path>`)
+//! 3. the workspace file (`hsnn.toml`, or `hSNN.toml` as written by `snn init`)
+//! 4. `HSNN_*` environment variables (`viz.port` → `HSNN_VIZ_PORT`)
+//!
+//! Command-line flags override every layer. Files are TOML; a dotted key is a
+//! table path (`viz.port` is `port` under `[viz]`). Unknown keys are ignored so
+//! the workspace file can keep its other sections.
 
-use std::path::Path;
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 use crate::error::{CliError, CliResult};
 
-/// Global CLI configuration
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CliConfig {
-    /// Default workspace directory
-    pub default_workspace: Option<String>,
-    
-    /// Default logging level
-    pub log_level: Option<String>,
-    
-    /// Default number of parallel jobs
-    pub default_jobs: Option<u32>,
-    
-    /// User preferences
-    pub preferences: UserPreferences,
+/// Workspace-level configuration file
+pub const WORKSPACE_CONFIG_FILE: &str = "hsnn.toml";
+
+/// Workspace file name used by `snn init`
+const INIT_CONFIG_FILE: &str = "hSNN.toml";
+
+/// Value type of a configuration key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Free-form text
+    Text,
+    /// Filesystem path, relative paths resolve against the workspace root
+    Path,
+    /// Positive integer
+    Count,
+    /// TCP port (1-65535)
+    Port,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UserPreferences {
-    /// Show progress bars
-    pub show_progress: bool,
-    
-    /// Use colors in output
-    pub use_colors: bool,
-    
-    /// Default output format
-    pub output_format: String,
-    
-    /// Auto-save results
-    pub auto_save: bool,
+impl ValueKind {
+    fn check(self, value: &str) -> Result<(), String> {
+        let ok = match self {
+            ValueKind::Text | ValueKind::Path => !value.is_empty(),
+            ValueKind::Count => value.parse::<usize>().map_or(false, |n| n >= 1),
+            ValueKind::Port => value.parse::<u16>().map_or(false, |n| n >= 1),
+        };
+        match ok {
+            true => Ok(()),
+            false => Err(format!("expected {}", self)),
+        }
+    }
 }
 
-impl Default for CliConfig {
-    fn default() -> Self {
-        Self {
-            default_workspace: None,
-            log_level: Some("info".to_string()),
-            default_jobs: Some(1),
-            preferences: UserPreferences {
-                show_progress: true,
-                use_colors: true,
-                output_format: "json".to_string(),
-                auto_save: true,
-            },
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueKind::Text => write!(f, "a non-empty string"),
+            ValueKind::Path => write!(f, "a path"),
+            ValueKind::Count => write!(f, "an integer >= 1"),
+            ValueKind::Port => write!(f, "a port in 1-65535"),
         }
     }
 }
 
-impl CliConfig {
-    /// Load configuration from file
-    pub fn load_from_file(path: &Path) -> CliResult<Self> {
-        if path.exists() {
-            let content = std::fs::read_to_string(path)?;
-            toml::from_str(&content).map_err(|e| CliError::config(format!("Invalid config file: {}", e)))
-        } else {
-            Ok(Self::default())
+/// A known configuration key
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigKey {
+    /// Dotted name
+    pub name: &'static str,
+    /// Value type
+    pub kind: ValueKind,
+    /// Built-in default (None: unset unless a layer provides it)
+    pub default: Option<&'static str>,
+    /// One-line description
+    pub doc: &'static str,
+}
+
+impl ConfigKey {
+    /// Environment variable overriding this key
+    pub fn env_var(&self) -> String {
+        format!("HSNN_{}", self.name.replace('.', "_").to_uppercase())
+    }
+}
+
+/// Every key the CLI reads
+pub const KEYS: &[ConfigKey] = &[
+    ConfigKey { name: "output.dir", kind: ValueKind::Path, default: Some("results"), doc: "Results directory (default results dir of viz serve)" },
+    ConfigKey { name: "run.threads", kind: ValueKind::Count, default: None, doc: "Worker threads for nir run (default: one per core)" },
+    ConfigKey { name: "cache.min_synapses", kind: ValueKind::Count, default: Some("100000"), doc: "Smallest network (synapses) whose lowering nir run caches" },
+    ConfigKey { name: "viz.host", kind: ValueKind::Text, default: Some("127.0.0.1"), doc: "viz serve bind host" },
+    ConfigKey { name: "viz.port", kind: ValueKind::Port, default: Some("7878"), doc: "viz serve port" },
+    ConfigKey { name: "log.level", kind: ValueKind::Text, default: Some("info"), doc: "Log filter when RUST_LOG is unset" },
+];
+
+/// Look up a known key
+pub fn key(name: &str) -> CliResult<&'static ConfigKey> {
+    KEYS.iter().find(|k| k.name == name).ok_or_else(|| {
+        let known: Vec<_> = KEYS.iter().map(|k| k.name).collect();
+        CliError::config(format!("unknown key '{}' (known: {})", name, known.join(", ")))
+    })
+}
+
+/// Layer a value was taken from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Built-in default
+    Default,
+    /// User configuration file
+    User(PathBuf),
+    /// Workspace configuration file
+    Workspace(PathBuf),
+    /// Environment variable
+    Env(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::User(path) => write!(f, "user ({})", path.display()),
+            Source::Workspace(path) => write!(f, "workspace ({})", path.display()),
+            Source::Env(var) => write!(f, "env ({})", var),
         }
     }
-    
-    /// Save configuration to file
-    pub fn save_to_file(&self, path: &Path) -> CliResult<()> {
-        let content = toml::to_string_pretty(self)
-            .map_err(|e| CliError::config(format!("Failed to serialize config: {}", e)))?;
-        
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
+}
+
+/// Resolved CLI configuration
+#[derive(Debug, Clone)]
+pub struct Config {
+    workspace: PathBuf,
+    values: BTreeMap<&'static str, (String, Source)>,
+}
+
+impl Config {
+    /// Resolve every layer for `workspace`; `user_file` replaces the default user file
+    pub fn load(workspace: &Path, user_file: Option<&Path>) -> CliResult<Self> {
+        Self::resolve(workspace, user_file, |var| std::env::var(var).ok())
+    }
+
+    /// [`Config::load`] with an explicit environment lookup
+    pub fn resolve(
+        workspace: &Path,
+        user_file: Option<&Path>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> CliResult<Self> {
+        let mut values = BTreeMap::new();
+        for key in KEYS {
+            if let Some(default) = key.default {
+                values.insert(key.name, (default.to_string(), Source::Default));
+            }
+        }
+        let mut config = Self { workspace: workspace.to_path_buf(), values };
+
+        let user = match user_file {
+            Some(path) if !path.exists() => {
+                return Err(CliError::config(format!("config file {} does not exist", path.display())));
+            }
+            Some(path) => Some(path.to_path_buf()),
+            None => user_config_path(),
+        };
+        if let Some(path) = user.filter(|p| p.exists()) {
+            config.apply_file(&path, Source::User(path.clone()))?;
+        }
+        let local = workspace_config_path(workspace);
+        if local.exists() {
+            config.apply_file(&local, Source::Workspace(local.clone()))?;
+        }
+        for key in KEYS {
+            let var = key.env_var();
+            if let Some(value) = env(&var) {
+                key.kind.check(&value)
+                    .map_err(|e| CliError::config(format!("{}={}: {}", var, value, e)))?;
+                config.values.insert(key.name, (value, Source::Env(var)));
+            }
+        }
+        Ok(config)
+    }
+
+    fn apply_file(&mut self, path: &Path, source: Source) -> CliResult<()> {
+        let table = read_table(path)?;
+        for key in KEYS {
+            let Some(value) = lookup(&table, key.name) else { continue };
+            let text = match value {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => value.to_string(),
+                _ => return Err(CliError::config(format!("{}: {} must be a scalar", path.display(), key.name))),
+            };
+            key.kind.check(&text)
+                .map_err(|e| CliError::config(format!("{}: {} = {}: {}", path.display(), key.name, text, e)))?;
+            self.values.insert(key.name, (text, source.clone()));
         }
-        
-        std::fs::write(path, content)?;
         Ok(())
     }
-    
-    /// Get the default configuration file path
-    pub fn default_config_path() -> CliResult<std::path::PathBuf> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| CliError::config("Could not determine config directory"))?;
-        Ok(config_dir.join("hsnn").join("config.toml"))
+
+    /// Raw value of `name`, if set in any layer
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|(value, _)| value.as_str())
+    }
+
+    /// Layer that supplied `name`
+    pub fn source(&self, name: &str) -> Option<&Source> {
+        self.values.get(name).map(|(_, source)| source)
+    }
+
+    /// Results directory, resolved against the workspace root
+    pub fn output_dir(&self) -> PathBuf {
+        self.workspace.join(self.get("output.dir").unwrap_or("results"))
+    }
+
+    /// Worker thread count for runs (None: runtime default)
+    pub fn threads(&self) -> Option<usize> {
+        self.get("run.threads").and_then(|v| v.parse().ok())
+    }
+
+    /// Synapse count from which `snn nir run` caches lowered topologies
+    pub fn cache_min_synapses(&self) -> u64 {
+        self.get("cache.min_synapses").and_then(|v| v.parse().ok()).unwrap_or(100_000)
     }
-}
\ No newline at end of file
+
+    /// Bind host of `snn viz serve`
+    pub fn viz_host(&self) -> &str {
+        self.get("viz.host").unwrap_or("127.0.0.1")
+    }
+
+    /// Port of `snn viz serve`
+    pub fn viz_port(&self) -> u16 {
+        self.get("viz.port").and_then(|v| v.parse().ok()).unwrap_or(7878)
+    }
+
+    /// Log filter used when `RUST_LOG` is unset
+    pub fn log_level(&self) -> &str {
+        self.get("log.level").unwrap_or("info")
+    }
+}
+
+/// Default user configuration file
+pub fn user_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("hsnn").join("config.toml"))
+}
+
+/// Workspace configuration file: `hsnn.toml`, or an existing `hSNN.toml`
+pub fn workspace_config_path(workspace: &Path) -> PathBuf {
+    let init = workspace.join(INIT_CONFIG_FILE);
+    match workspace.join(WORKSPACE_CONFIG_FILE) {
+        path if !path.exists() && init.exists() => init,
+        path => path,
+    }
+}
+
+/// Validate `value` for `name` and store it in the TOML file at `path`, keeping its other entries
+pub fn set_in_file(path: &Path, name: &str, value: &str) -> CliResult<()> {
+    let key = key(name)?;
+    key.kind.check(value)
+        .map_err(|e| CliError::config(format!("{} = {}: {}", name, value, e)))?;
+    let mut table = match path.exists() {
+        true => read_table(path)?,
+        false => toml::Table::new(),
+    };
+    let typed = match key.kind {
+        ValueKind::Count | ValueKind::Port => toml::Value::Integer(value.parse()
+            .map_err(|_| CliError::config(format!("{} = {}: out of range", name, value)))?),
+        ValueKind::Text | ValueKind::Path => toml::Value::String(value.to_string()),
+    };
+    let mut parts: Vec<&str> = name.split('.').collect();
+    let leaf = parts.pop().expect("keys are non-empty");
+    let mut current = &mut table;
+    for part in parts {
+        let entry = current.entry(part).or_insert(toml::Value::Table(toml::Table::new()));
+        current = entry.as_table_mut()
+            .ok_or_else(|| CliError::config(format!("{}: '{}' is not a table", path.display(), part)))?;
+    }
+    current.insert(leaf.to_string(), typed);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let text = toml::to_string_pretty(&table)
+        .map_err(|e| CliError::config(format!("Failed to serialize config: {}", e)))?;
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+fn read_table(path: &Path) -> CliResult<toml::Table> {
+    let content = std::fs::read_to_string(path)?;
+    content.parse::<toml::Table>()
+        .map_err(|e| CliError::config(format!("Invalid config file {}: {}", path.display(), e)))
+}
+
+fn lookup<'a>(table: &'a toml::Table, name: &str) -> Option<&'a toml::Value> {
+    let mut parts = name.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    Some(value)
+}
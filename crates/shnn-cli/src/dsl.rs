@@ -0,0 +1,242 @@
+//! Scripted NIR construction (Rhai, `dsl` feature)
+//!
+//! A build script is a Rhai program that evaluates to a `Module` (or an
+//! array of ops) and can use loops, functions and `import`s of sibling
+//! scripts. Ops are built from their textual header and an attribute map:
+//!
+//! ```text
+//! let m = nir_module();
+//! m.push(op("neuron.lif@v1", #{ tau_m: ms(20), v_rest: -70.0, v_reset: -70.0,
+//!     v_thresh: -50.0, t_refrac: ms(2), r_m: 10.0, c_m: 1.0 }));
+//! for i in 0..n {
+//!     m.push(op("stimulus.poisson@v1", #{ neuron: i, rate: 20.0, amplitude: 10.0,
+//!         start: ms(0), duration: ms(100) }));
+//! }
+//! m
+//! ```
+//!
+//! Registered ops take their attribute kinds from the compiler registry:
+//! numbers become the kind's unit (mV, Hz, nA, ...), ranges (`0..10`,
+//! `0..=9` or `[0, 9]`) become inclusive neuron ranges, and times need an
+//! explicit unit (`ns`, `us`, `ms` or `s`). Ops outside the registry (e.g.
+//! research plugins) take untyped values.
+
+use rhai::module_resolvers::FileModuleResolver;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ExclusiveRange, InclusiveRange, Map, Scope};
+use std::path::Path;
+
+use shnn_compiler::{op_spec, AttrKind};
+use shnn_ir::{AttributeValue, Module, Operation, Quantity};
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Run the build script at `path` with `defines` in scope and return its module
+///
+/// `import "x"` resolves `x.rhai` next to the script.
+pub fn build_module(path: &Path, defines: &[(String, Dynamic)]) -> ScriptResult<Module> {
+    let mut engine = engine();
+    if let Some(dir) = path.parent() {
+        engine.set_module_resolver(FileModuleResolver::new_with_path(dir));
+    }
+    let mut scope = Scope::new();
+    for (name, value) in defines {
+        scope.push_constant_dynamic(name.as_str(), value.clone());
+    }
+    let value = engine.eval_file_with_scope::<Dynamic>(&mut scope, path.to_path_buf())?;
+    module_from(value)
+}
+
+/// Parse a `--define` value: integer, float, bool, else string
+pub fn define_value(raw: &str) -> Dynamic {
+    if let Ok(v) = raw.parse::<i64>() {
+        Dynamic::from(v)
+    } else if let Ok(v) = raw.parse::<f64>() {
+        Dynamic::from(v)
+    } else if let Ok(v) = raw.parse::<bool>() {
+        Dynamic::from(v)
+    } else {
+        Dynamic::from(raw.to_string())
+    }
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .register_type_with_name::<Module>("Module")
+        .register_fn("nir_module", Module::new)
+        .register_fn("push", |m: &mut Module, op: Operation| m.push(op))
+        .register_fn("len", |m: &mut Module| m.ops.len() as i64)
+        .register_fn("to_text", |m: &mut Module| m.to_text());
+    engine
+        .register_type_with_name::<Operation>("Op")
+        .register_fn("op", |header: &str| build_op(header, Map::new()))
+        .register_fn("op", build_op)
+        .register_fn("attr", |op: Operation, key: &str, value: Dynamic| with_attr(op, key, value));
+    engine.register_type_with_name::<Quantity>("Quantity");
+    for (unit, make) in [("ns", Quantity::ns as fn(f64) -> Quantity), ("us", Quantity::us), ("ms", Quantity::ms), ("s", Quantity::s)] {
+        engine.register_fn(unit, move |v: f64| make(v));
+        engine.register_fn(unit, move |v: i64| make(v as f64));
+    }
+    engine
+}
+
+/// Final script value as a module: a `Module` or an array of ops
+fn module_from(value: Dynamic) -> ScriptResult<Module> {
+    if value.is::<Module>() {
+        return Ok(value.cast::<Module>());
+    }
+    let type_name = value.type_name();
+    let ops = value
+        .try_cast::<Array>()
+        .ok_or_else(|| format!("script must evaluate to a Module or an array of ops, got {}", type_name))?;
+    let mut module = Module::new();
+    for item in ops {
+        let type_name = item.type_name();
+        module.push(item.try_cast::<Operation>().ok_or_else(|| format!("expected an op in the result array, got {}", type_name))?);
+    }
+    Ok(module)
+}
+
+fn build_op(header: &str, attrs: Map) -> ScriptResult<Operation> {
+    let mut op = shnn_ir::parse_text(header)
+        .ok()
+        .and_then(|mut m| (m.ops.len() == 1).then(|| m.ops.remove(0)))
+        .filter(|op| op.attrs.is_empty())
+        .ok_or_else(|| format!("bad op header '{}', expected dialect.name@vN", header))?;
+    for (key, value) in attrs {
+        op = with_attr(op, &key, value)?;
+    }
+    Ok(op)
+}
+
+fn with_attr(op: Operation, key: &str, value: Dynamic) -> ScriptResult<Operation> {
+    let header = format!("{}.{}@{}", op.dialect, op.name, op.version);
+    let value = match op_spec(&op) {
+        Some(spec) => {
+            let attr = spec.attrs.iter().find(|a| a.name == key).ok_or_else(|| {
+                let known: Vec<&str> = spec.attrs.iter().map(|a| a.name).collect();
+                format!("{} has no attribute '{}' (known: {})", header, key, known.join(", "))
+            })?;
+            typed_value(attr.kind, value)
+        }
+        None => untyped_value(value),
+    }
+    .map_err(|reason| format!("{} attribute '{}': {}", header, key, reason))?;
+    Ok(op.with_attr(key, value))
+}
+
+/// Attribute of a registered `kind`
+fn typed_value(kind: AttrKind, value: Dynamic) -> Result<AttributeValue, String> {
+    let found = value.type_name();
+    let mismatch = || format!("expected {}, got {}", kind.name(), found);
+    Ok(match kind {
+        AttrKind::Bool => AttributeValue::Bool(value.as_bool().map_err(|_| mismatch())?),
+        AttrKind::I64 => AttributeValue::I64(value.as_int().map_err(|_| mismatch())?),
+        AttrKind::F32 => AttributeValue::F32(number(&value).ok_or_else(mismatch)?),
+        AttrKind::VoltageMv => AttributeValue::VoltageMv(number(&value).ok_or_else(mismatch)?),
+        AttrKind::ResistanceMohm => AttributeValue::ResistanceMohm(number(&value).ok_or_else(mismatch)?),
+        AttrKind::CapacitanceNf => AttributeValue::CapacitanceNf(number(&value).ok_or_else(mismatch)?),
+        AttrKind::CurrentNa => AttributeValue::CurrentNa(number(&value).ok_or_else(mismatch)?),
+        AttrKind::RateHz => AttributeValue::RateHz(number(&value).ok_or_else(mismatch)?),
+        AttrKind::Weight => AttributeValue::Weight(number(&value).ok_or_else(mismatch)?),
+        AttrKind::DurationNs => AttributeValue::DurationNs(time_ns(value)?),
+        AttrKind::TimeNs => AttributeValue::TimeNs(time_ns(value)?),
+        AttrKind::RangeU32 => {
+            let (start, end) = range(&value).ok_or_else(mismatch)??;
+            AttributeValue::RangeU32 { start, end }
+        }
+        AttrKind::NeuronRef => AttributeValue::NeuronRef(neuron_id(&value).ok_or_else(mismatch)??),
+        AttrKind::NeuronSet => match range(&value) {
+            Some(bounds) => {
+                let (start, end) = bounds?;
+                AttributeValue::RangeU32 { start, end }
+            }
+            None => {
+                let ids = value.try_cast::<Array>().ok_or_else(mismatch)?;
+                let ids = ids
+                    .iter()
+                    .map(|id| match neuron_id(id) {
+                        Some(id) => id.map(|id| id.to_string()),
+                        None => Err(format!("expected neuron ids, got {}", id.type_name())),
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                AttributeValue::String(ids.join(";"))
+            }
+        },
+        AttrKind::String => AttributeValue::String(value.into_string().map_err(|_| mismatch())?),
+        AttrKind::List => {
+            let items = value.try_cast::<Array>().ok_or_else(mismatch)?;
+            AttributeValue::List(items.into_iter().map(untyped_value).collect::<Result<_, _>>()?)
+        }
+    })
+}
+
+/// Attribute of an op outside the registry, typed by the script value alone
+fn untyped_value(value: Dynamic) -> Result<AttributeValue, String> {
+    if let Ok(v) = value.as_bool() {
+        return Ok(AttributeValue::Bool(v));
+    }
+    if let Ok(v) = value.as_int() {
+        return Ok(AttributeValue::I64(v));
+    }
+    if let Some(v) = number(&value) {
+        return Ok(AttributeValue::F32(v));
+    }
+    // Only range literals: a two-element array stays a list
+    if value.is::<ExclusiveRange>() || value.is::<InclusiveRange>() {
+        if let Some(bounds) = range(&value) {
+            let (start, end) = bounds?;
+            return Ok(AttributeValue::RangeU32 { start, end });
+        }
+    }
+    if value.is::<Quantity>() {
+        return Ok(AttributeValue::DurationNs(time_ns(value)?));
+    }
+    if value.is_string() {
+        return Ok(AttributeValue::String(value.into_string()?));
+    }
+    let found = value.type_name();
+    match value.try_cast::<Array>() {
+        Some(items) => Ok(AttributeValue::List(items.into_iter().map(untyped_value).collect::<Result<_, _>>()?)),
+        None => Err(format!("unsupported value of type {}", found)),
+    }
+}
+
+fn number(value: &Dynamic) -> Option<f32> {
+    value.as_float().ok().map(|v| v as f32).or_else(|| value.as_int().ok().map(|v| v as f32))
+}
+
+fn time_ns(value: Dynamic) -> Result<u64, String> {
+    let found = value.type_name();
+    let quantity = value
+        .try_cast::<Quantity>()
+        .ok_or_else(|| format!("expected a time with a unit (ns(..), us(..), ms(..) or s(..)), got {}", found))?;
+    quantity.to_ns().map_err(|e| format!("{}: {}", quantity, e))
+}
+
+fn neuron_id(value: &Dynamic) -> Option<Result<u32, String>> {
+    let id = value.as_int().ok()?;
+    Some(u32::try_from(id).map_err(|_| format!("neuron id {} out of range", id)))
+}
+
+/// Inclusive bounds of `a..b`, `a..=b` or `[a, b]` (`None` if `value` is none of these)
+fn range(value: &Dynamic) -> Option<Result<(u32, u32), String>> {
+    let (start, end) = if let Some(r) = value.read_lock::<ExclusiveRange>() {
+        if r.start >= r.end {
+            return Some(Err(format!("empty range {}..{}", r.start, r.end)));
+        }
+        (r.start, r.end - 1)
+    } else if let Some(r) = value.read_lock::<InclusiveRange>() {
+        (*r.start(), *r.end())
+    } else {
+        let items = value.read_lock::<Array>()?;
+        match items.as_slice() {
+            [a, b] => (a.as_int().ok()?, b.as_int().ok()?),
+            _ => return None,
+        }
+    };
+    Some(match (u32::try_from(start), u32::try_from(end)) {
+        (Ok(s), Ok(e)) if s <= e => Ok((s, e)),
+        _ => Err(format!("bad neuron range {}..={}", start, end)),
+    })
+}
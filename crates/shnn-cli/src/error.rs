@@ -1,5 +1,7 @@
 //! Error handling for the hSNN CLI
 
+use shnn_compiler::CompilerError;
+use shnn_runtime::error::{BuildCode, RuntimeError};
 use thiserror::Error;
 
 /// Result type for CLI operations
@@ -32,6 +34,16 @@ pub enum CliError {
     #[error("Serialization error: {0}")]
     Serde(#[from] toml::de::Error),
     
+    /// Build script (`dsl` feature) failed to run
+    #[cfg(feature = "dsl")]
+    #[error("Script error: {0}")]
+    Script(String),
+
+    /// Experiment index (SQLite) error
+    #[cfg(feature = "index")]
+    #[error("Index error: {0}")]
+    Index(#[from] rusqlite::Error),
+
     /// Generic error
     #[error("Error: {0}")]
     Generic(#[from] anyhow::Error),
@@ -47,6 +59,25 @@ pub enum CliError {
     /// Missing required file or resource
     #[error("Missing resource: {0}")]
     MissingResource(String),
+
+    /// Network failed builder validation while lowering
+    #[error("{message} [{code}]\n  hint: {hint}")]
+    Build {
+        /// Stable validation code
+        code: BuildCode,
+        /// What was wrong
+        message: String,
+        /// How to fix it
+        hint: &'static str,
+    },
+
+    /// Test-dialect expectations failed after a successful run
+    #[error("{0} expectation(s) failed")]
+    TestFailed(usize),
+
+    /// Reference-model checks deviated beyond tolerance
+    #[error("{0} validation check(s) outside tolerance")]
+    ValidationFailed(usize),
 }
 
 impl CliError {
@@ -69,4 +100,37 @@ impl CliError {
     pub fn missing_resource(msg: impl Into<String>) -> Self {
         Self::MissingResource(msg.into())
     }
-}
\ No newline at end of file
+
+    /// Map a compiler error, turning builder validation failures into [`CliError::Build`]
+    pub fn compile(err: CompilerError) -> Self {
+        match err {
+            CompilerError::Runtime(RuntimeError::Build { code, message }) => Self::Build {
+                code,
+                message,
+                hint: build_hint(code),
+            },
+            other => Self::Generic(anyhow::anyhow!(other)),
+        }
+    }
+
+    /// Process exit code: 2 for failed expectations or validation checks, 1 for any other error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::TestFailed(_) | Self::ValidationFailed(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Suggested fix for a builder validation code
+pub fn build_hint(code: BuildCode) -> &'static str {
+    match code {
+        BuildCode::NoNeurons => "add a connectivity op (layer_fully_connected or synapse_connect) so the module creates neurons",
+        BuildCode::DuplicateNeuron => "declare each neuron id once",
+        BuildCode::UnknownNeuron => "connect only neurons covered by a connectivity range",
+        BuildCode::DuplicateSynapse => "remove the repeated connection or split overlapping connectivity ops",
+        BuildCode::NeuronIdGap => "renumber neurons so ids are contiguous",
+        BuildCode::InvalidDelay => "use finite, non-negative delays in milliseconds",
+        BuildCode::DelayBelowDt => "raise the delay to at least dt, lower dt, or set sub_dt_delay = \"interpolate\" on runtime.simulate.run",
+    }
+}
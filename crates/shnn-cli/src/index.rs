@@ -0,0 +1,458 @@
+//! Workspace experiment index (SQLite, `index` feature)
+//!
+//! Recorded runs (`snn nir run --record`) and study trials are mirrored into
+//! `<output.dir>/index.sqlite` with their metrics, parameters and artifact
+//! paths, so `snn study query` answers with one SQL query instead of reading
+//! every manifest. The JSON manifests stay authoritative; the index only
+//! accelerates lookups and can be deleted at any time.
+//!
+//! Filters are conjunctions/disjunctions of comparisons on `metric.<name>`
+//! and `params.<name>`, e.g. `metric.rate > 5 AND params.weight < 0.2`
+//! (`AND` binds tighter than `OR`). Strings compare with `=`/`!=` only.
+
+use rusqlite::{params, Connection, OptionalExtension, ToSql};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::config::Config;
+use crate::error::{CliError, CliResult};
+use crate::runs::RunManifest;
+
+/// Index database file under the workspace output dir
+pub const INDEX_FILE: &str = "index.sqlite";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    key TEXT NOT NULL UNIQUE,
+    kind TEXT NOT NULL,
+    study TEXT,
+    created_unix_ms INTEGER NOT NULL,
+    nir_source TEXT,
+    seed INTEGER
+);
+CREATE TABLE IF NOT EXISTS metrics (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    name TEXT NOT NULL,
+    value REAL NOT NULL,
+    PRIMARY KEY (run_id, name)
+);
+CREATE TABLE IF NOT EXISTS params (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    name TEXT NOT NULL,
+    value REAL,
+    text TEXT,
+    PRIMARY KEY (run_id, name)
+);
+CREATE TABLE IF NOT EXISTS artifacts (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    role TEXT NOT NULL,
+    path TEXT NOT NULL,
+    PRIMARY KEY (run_id, role)
+);
+CREATE INDEX IF NOT EXISTS metrics_by_name ON metrics (name, value);
+CREATE INDEX IF NOT EXISTS params_by_name ON params (name, value);
+";
+
+/// Kind of an indexed entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    /// A run recorded in the run store
+    Run,
+    /// One (run, repeat) trial of a study
+    Trial,
+}
+
+impl EntryKind {
+    /// Name stored in the index
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntryKind::Run => "run",
+            EntryKind::Trial => "trial",
+        }
+    }
+}
+
+/// One indexed run or trial
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexEntry {
+    /// Run-store id, or `<study>/<trial>` for study trials
+    pub key: String,
+    pub kind: EntryKind,
+    pub study: Option<String>,
+    pub created_unix_ms: u64,
+    pub nir_source: Option<String>,
+    pub seed: Option<u64>,
+    pub metrics: BTreeMap<String, f64>,
+    /// Numbers and strings (other TOML/JSON values are stored as their text)
+    pub params: BTreeMap<String, serde_json::Value>,
+    /// Artifact role → path
+    pub artifacts: BTreeMap<String, String>,
+}
+
+impl IndexEntry {
+    /// Empty entry of `kind` under `key`
+    pub fn new(key: impl Into<String>, kind: EntryKind, created_unix_ms: u64) -> Self {
+        Self {
+            key: key.into(),
+            kind,
+            study: None,
+            created_unix_ms,
+            nir_source: None,
+            seed: None,
+            metrics: BTreeMap::new(),
+            params: BTreeMap::new(),
+            artifacts: BTreeMap::new(),
+        }
+    }
+
+    /// Entry for a run-store manifest stored in `run_dir`
+    pub fn from_manifest(manifest: &RunManifest, run_dir: &Path) -> Self {
+        let mut entry = Self::new(manifest.id.clone(), EntryKind::Run, manifest.created_unix_ms);
+        entry.nir_source = Some(manifest.nir_source.clone());
+        entry.seed = manifest.seed;
+        let m = &manifest.metrics;
+        entry.metrics.insert("neurons".into(), m.neurons as f64);
+        entry.metrics.insert("steps_executed".into(), m.steps_executed as f64);
+        entry.metrics.insert("spike_count".into(), m.spike_count as f64);
+        entry.metrics.insert("rate".into(), m.mean_rate_hz);
+        entry.params.insert("dt_ns".into(), manifest.dt_ns.into());
+        entry.params.insert("nir_hash".into(), manifest.nir_hash.clone().into());
+        // Numeric bindings compare as numbers in queries
+        for (name, value) in &manifest.bindings {
+            let value = value.parse::<f64>().ok().and_then(serde_json::Number::from_f64)
+                .map_or_else(|| value.clone().into(), serde_json::Value::Number);
+            entry.params.insert(name.clone(), value);
+        }
+        entry.artifacts.insert("manifest".into(), run_dir.join("manifest.json").display().to_string());
+        entry.artifacts.insert("results".into(), run_dir.join(&manifest.results).display().to_string());
+        entry
+    }
+}
+
+/// Field a filter clause compares
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    Metric(String),
+    Param(String),
+}
+
+/// Comparison operator of a filter clause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    fn sql(self) -> &'static str {
+        match self {
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Eq => "=",
+            Op::Ne => "!=",
+        }
+    }
+}
+
+/// Right-hand side of a filter clause
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Number(f64),
+    Text(String),
+}
+
+/// `field op literal`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clause {
+    pub field: Field,
+    pub op: Op,
+    pub value: Literal,
+}
+
+/// Parsed filter: clauses joined by AND/OR, `or_before[i]` joining clause `i` to the previous one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub clauses: Vec<Clause>,
+    pub or_before: Vec<bool>,
+}
+
+impl FromStr for Query {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> CliResult<Self> {
+        let tokens = tokenize(s)?;
+        let mut tokens = tokens.into_iter();
+        let mut query = Query { clauses: Vec::new(), or_before: Vec::new() };
+        loop {
+            let field = match tokens.next() {
+                Some(Token::Word(word)) => parse_field(&word)?,
+                other => return Err(bad_query(format!("expected metric.<name> or params.<name>, got {}", describe(&other)))),
+            };
+            let op = match tokens.next() {
+                Some(Token::Op(op)) => op,
+                other => return Err(bad_query(format!("expected a comparison operator, got {}", describe(&other)))),
+            };
+            let value = match tokens.next() {
+                Some(Token::Text(text)) => Literal::Text(text),
+                Some(Token::Word(word)) => Literal::Number(word.parse().map_err(|_| {
+                    bad_query(format!("'{}' is not a number (quote strings)", word))
+                })?),
+                other => return Err(bad_query(format!("expected a value, got {}", describe(&other)))),
+            };
+            if matches!(value, Literal::Text(_)) && !matches!(op, Op::Eq | Op::Ne) {
+                return Err(bad_query("strings compare with = or != only"));
+            }
+            query.clauses.push(Clause { field, op, value });
+
+            match tokens.next() {
+                None => break,
+                Some(Token::Word(word)) if word.eq_ignore_ascii_case("and") => query.or_before.push(false),
+                Some(Token::Word(word)) if word.eq_ignore_ascii_case("or") => query.or_before.push(true),
+                other => return Err(bad_query(format!("expected AND or OR, got {}", describe(&other)))),
+            }
+        }
+        // or_before[0] belongs to the first clause, which has no predecessor
+        query.or_before.insert(0, false);
+        Ok(query)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Op(Op),
+}
+
+fn describe(token: &Option<Token>) -> String {
+    match token {
+        None => "end of query".into(),
+        Some(Token::Word(w)) => format!("'{}'", w),
+        Some(Token::Text(t)) => format!("\"{}\"", t),
+        Some(Token::Op(op)) => format!("'{}'", op.sql()),
+    }
+}
+
+fn bad_query(msg: impl std::fmt::Display) -> CliError {
+    CliError::invalid_args(format!("query: {}", msg))
+}
+
+fn tokenize(s: &str) -> CliResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '<' | '>' | '=' | '!' => {
+                chars.next();
+                let eq = chars.next_if_eq(&'=').is_some();
+                tokens.push(Token::Op(match (c, eq) {
+                    ('<', false) => Op::Lt,
+                    ('<', true) => Op::Le,
+                    ('>', false) => Op::Gt,
+                    ('>', true) => Op::Ge,
+                    ('=', _) => Op::Eq,
+                    ('!', true) => Op::Ne,
+                    _ => return Err(bad_query("'!' must be followed by '='")),
+                }));
+            }
+            '"' | '\'' => {
+                chars.next();
+                let text: String = chars.by_ref().take_while(|&ch| ch != c).collect();
+                tokens.push(Token::Text(text));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(ch) = chars.next_if(|ch| !ch.is_whitespace() && !"<>=!\"'".contains(*ch)) {
+                    word.push(ch);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_field(word: &str) -> CliResult<Field> {
+    let (scope, name) = word.split_once('.')
+        .filter(|(_, name)| !name.is_empty())
+        .ok_or_else(|| bad_query(format!("'{}' is not metric.<name> or params.<name>", word)))?;
+    match scope {
+        "metric" | "metrics" => Ok(Field::Metric(name.to_string())),
+        "param" | "params" => Ok(Field::Param(name.to_string())),
+        _ => Err(bad_query(format!("unknown field scope '{}' (use metric or params)", scope))),
+    }
+}
+
+/// The workspace experiment index
+pub struct ExperimentIndex {
+    conn: Connection,
+}
+
+impl ExperimentIndex {
+    /// Open (creating if needed) the index at `path`
+    pub fn open(path: &Path) -> CliResult<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// The workspace index, `<output.dir>/index.sqlite`
+    pub fn open_workspace(settings: &Config) -> CliResult<Self> {
+        Self::open(&settings.output_dir().join(INDEX_FILE))
+    }
+
+    /// Insert `entry`, replacing any entry with the same key
+    pub fn upsert(&mut self, entry: &IndexEntry) -> CliResult<()> {
+        let tx = self.conn.transaction()?;
+        let existing: Option<i64> = tx
+            .query_row("SELECT id FROM runs WHERE key = ?1", [&entry.key], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = existing {
+            for table in ["metrics", "params", "artifacts"] {
+                tx.execute(&format!("DELETE FROM {} WHERE run_id = ?1", table), [id])?;
+            }
+            tx.execute("DELETE FROM runs WHERE id = ?1", [id])?;
+        }
+        tx.execute(
+            "INSERT INTO runs (key, kind, study, created_unix_ms, nir_source, seed) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                entry.key,
+                entry.kind.as_str(),
+                entry.study,
+                entry.created_unix_ms as i64,
+                entry.nir_source,
+                entry.seed.map(|s| s as i64),
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        for (name, value) in &entry.metrics {
+            tx.execute("INSERT INTO metrics (run_id, name, value) VALUES (?1, ?2, ?3)", params![id, name, value])?;
+        }
+        for (name, value) in &entry.params {
+            let (number, text) = match value {
+                serde_json::Value::Number(n) => (n.as_f64(), None),
+                serde_json::Value::Bool(b) => (Some(*b as u8 as f64), None),
+                serde_json::Value::String(s) => (None, Some(s.clone())),
+                other => (None, Some(other.to_string())),
+            };
+            tx.execute(
+                "INSERT INTO params (run_id, name, value, text) VALUES (?1, ?2, ?3, ?4)",
+                params![id, name, number, text],
+            )?;
+        }
+        for (role, path) in &entry.artifacts {
+            tx.execute("INSERT INTO artifacts (run_id, role, path) VALUES (?1, ?2, ?3)", params![id, role, path])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Index a run recorded in the run store
+    pub fn record_run(&mut self, manifest: &RunManifest, run_dir: &Path) -> CliResult<()> {
+        self.upsert(&IndexEntry::from_manifest(manifest, run_dir))
+    }
+
+    /// Entries matching `query` (all entries if `None`), oldest first, at most `limit`
+    pub fn query(&self, query: Option<&Query>, limit: Option<usize>) -> CliResult<Vec<IndexEntry>> {
+        let mut sql = String::from("SELECT id, key, kind, study, created_unix_ms, nir_source, seed FROM runs r");
+        let mut args: Vec<Box<dyn ToSql>> = Vec::new();
+        if let Some(query) = query {
+            sql.push_str(" WHERE ");
+            for (i, clause) in query.clauses.iter().enumerate() {
+                if i > 0 {
+                    sql.push_str(if query.or_before[i] { " OR " } else { " AND " });
+                }
+                let (table, name) = match &clause.field {
+                    Field::Metric(name) => ("metrics", name),
+                    Field::Param(name) => ("params", name),
+                };
+                let column = match clause.value {
+                    Literal::Number(_) => "value",
+                    Literal::Text(_) => "text",
+                };
+                args.push(Box::new(name.clone()));
+                let name_arg = args.len();
+                let value: Box<dyn ToSql> = match &clause.value {
+                    Literal::Number(n) => Box::new(*n),
+                    Literal::Text(t) => Box::new(t.clone()),
+                };
+                args.push(value);
+                sql.push_str(&format!(
+                    "EXISTS (SELECT 1 FROM {table} t WHERE t.run_id = r.id AND t.name = ?{} AND t.{column} {} ?{})",
+                    name_arg,
+                    clause.op.sql(),
+                    name_arg + 1,
+                ));
+            }
+        }
+        sql.push_str(" ORDER BY created_unix_ms, key");
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(args.iter()), |row| {
+            let kind: String = row.get(2)?;
+            Ok((row.get::<_, i64>(0)?, IndexEntry {
+                key: row.get(1)?,
+                kind: if kind == "trial" { EntryKind::Trial } else { EntryKind::Run },
+                study: row.get(3)?,
+                created_unix_ms: row.get::<_, i64>(4)? as u64,
+                nir_source: row.get(5)?,
+                seed: row.get::<_, Option<i64>>(6)?.map(|s| s as u64),
+                metrics: BTreeMap::new(),
+                params: BTreeMap::new(),
+                artifacts: BTreeMap::new(),
+            }))
+        })?;
+        let mut entries = Vec::new();
+        for row in rows {
+            let (id, mut entry) = row?;
+            self.load_details(id, &mut entry)?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    fn load_details(&self, id: i64, entry: &mut IndexEntry) -> CliResult<()> {
+        let mut stmt = self.conn.prepare_cached("SELECT name, value FROM metrics WHERE run_id = ?1")?;
+        for row in stmt.query_map([id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?)))? {
+            let (name, value) = row?;
+            entry.metrics.insert(name, value);
+        }
+        let mut stmt = self.conn.prepare_cached("SELECT name, value, text FROM params WHERE run_id = ?1")?;
+        for row in stmt.query_map([id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<f64>>(1)?, row.get::<_, Option<String>>(2)?))
+        })? {
+            let (name, number, text) = row?;
+            let value = match (number, text) {
+                (Some(n), _) => serde_json::Number::from_f64(n).map_or(serde_json::Value::Null, serde_json::Value::Number),
+                (None, Some(t)) => serde_json::Value::String(t),
+                (None, None) => serde_json::Value::Null,
+            };
+            entry.params.insert(name, value);
+        }
+        let mut stmt = self.conn.prepare_cached("SELECT role, path FROM artifacts WHERE run_id = ?1")?;
+        for row in stmt.query_map([id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+            let (role, path) = row?;
+            entry.artifacts.insert(role, path);
+        }
+        Ok(())
+    }
+}
@@ -13,9 +13,11 @@
 //! Major commands (see [commands]):
 //! - nir: compile (TOML/CLI → textual NIR), verify (parse + verify), run (parse → verify → compile_with_passes → run),
 //!        op-list (dynamic registry introspection).
-//! - viz: serve a minimal SPA (static files) and JSON endpoints (/api/health, /api/list, /api/spikes)
+//! - viz: serve a minimal SPA (static files) and JSON endpoints (/api/health, /api/list, /api/spikes,
+//!        /api/firing-patterns, /api/oscillations)
 //!        to visualize spike rasters exported by nir run.
-//! - study (scaffolded runner) and ttr (JSON mask placeholder) are present but not the current focus.
+//! - ttr: generate per-task activity masks (VMSK) from recorded spikes.
+//! - study (scaffolded runner) is present but not the current focus.
 //!
 //! Integration points:
 //! - shnn_ir: parse_text/to_text for textual NIR serialization.
@@ -26,9 +28,15 @@
 //! - The library surface re-exports command modules to support integration testing without invoking
 //!   an external process.
 
+pub mod cache;
 pub mod commands;
 pub mod config;
+#[cfg(feature = "dsl")]
+pub mod dsl;
 pub mod error;
+#[cfg(feature = "index")]
+pub mod index;
+pub mod runs;
 pub mod workspace;
 
 pub use commands::HsnnCli;
\ No newline at end of file
@@ -8,32 +8,42 @@ use clap::Parser;
 use tracing::{error, info};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+mod cache;
 mod commands;
 mod config;
+#[cfg(feature = "dsl")]
+mod dsl;
 mod error;
+#[cfg(feature = "index")]
+mod index;
+mod runs;
 mod workspace;
 
 use commands::HsnnCli;
+use config::Config;
 use error::CliResult;
 
 #[tokio::main]
 async fn main() -> CliResult<()> {
-    // Initialize logging with environment variable support
+    // Parse CLI arguments
+    let cli = HsnnCli::parse();
+
+    // Initialize logging: RUST_LOG, else log.level from the layered config
+    // (an invalid config is reported by the command that loads it)
+    let level = Config::load(&cli.workspace_dir(), cli.config.as_deref())
+        .map_or_else(|_| "info".to_string(), |c| c.log_level().to_string());
     let filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info"));
+        .unwrap_or_else(|_| EnvFilter::new(level));
     
     tracing_subscriber::registry()
         .with(fmt::layer().with_target(false))
         .with(filter)
         .init();
-
-    // Parse CLI arguments
-    let cli = HsnnCli::parse();
     
     // Execute the command
     if let Err(err) = cli.execute().await {
         error!("Command failed: {}", err);
-        std::process::exit(1);
+        std::process::exit(err.exit_code());
     }
     
     Ok(())
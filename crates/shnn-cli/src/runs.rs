@@ -0,0 +1,201 @@
+//! Workspace run store: provenance manifests for recorded runs
+//!
+//! `snn nir run --record` stores each run under `<output.dir>/runs/<id>/` as a
+//! `manifest.json` (seed, NIR hash, timing, summary metrics) next to the run's
+//! `results.json`. `snn viz serve` lists the store so runs can be compared.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use shnn_runtime::SimulationResult;
+use shnn_storage::atomic::Commit;
+
+use crate::config::Config;
+use crate::error::{CliError, CliResult};
+
+/// Manifest format identifier; bump the version when fields change meaning
+pub const MANIFEST_FORMAT: &str = "snn-run-manifest";
+pub const MANIFEST_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const RESULTS_FILE: &str = "results.json";
+
+/// Summary metrics of a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetrics {
+    pub neurons: usize,
+    pub steps_executed: usize,
+    pub spike_count: usize,
+    /// Mean firing rate per neuron (Hz)
+    pub mean_rate_hz: f64,
+    /// FNV-1a hash of the time-sorted spike stream (hex)
+    pub spike_hash: String,
+}
+
+/// Provenance of one recorded run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub format: String,
+    pub version: u32,
+    /// Store key: `<created_unix_ms>-<nir hash prefix>`
+    pub id: String,
+    pub created_unix_ms: u64,
+    pub snn_version: String,
+    /// NIR file the run was started from
+    pub nir_source: String,
+    /// FNV-1a hash of the canonical module text (hex)
+    pub nir_hash: String,
+    pub seed: Option<u64>,
+    /// Seeds derived for each stochastic component (see `LoweredProgram::seeds`)
+    #[serde(default)]
+    pub seeds: BTreeMap<String, u64>,
+    /// Values bound to `${name}` placeholders in the NIR source
+    #[serde(default)]
+    pub bindings: BTreeMap<String, String>,
+    pub dt_ns: u64,
+    /// Simulated time (ns)
+    pub duration_ns: u64,
+    pub wall_time_ns: u64,
+    pub metrics: RunMetrics,
+    /// Results JSON, relative to the run directory
+    pub results: String,
+}
+
+/// What a recorded run was compiled from
+#[derive(Debug, Clone, Copy)]
+pub struct RunSource<'a> {
+    /// NIR file the run was started from
+    pub path: &'a Path,
+    /// Canonical module text after placeholder substitution
+    pub text: &'a str,
+    /// Placeholder values used by the text
+    pub bindings: &'a BTreeMap<String, String>,
+}
+
+/// Directory of recorded runs
+#[derive(Debug, Clone)]
+pub struct RunStore {
+    root: PathBuf,
+}
+
+impl RunStore {
+    /// Store rooted at `root`
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// The workspace store, `<output.dir>/runs`
+    pub fn open(settings: &Config) -> Self {
+        Self::new(settings.output_dir().join("runs"))
+    }
+
+    /// Store directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Record a finished run of `source`, writing its manifest and results
+    pub fn record(
+        &self,
+        source: RunSource<'_>,
+        seed: Option<u64>,
+        seeds: &BTreeMap<String, u64>,
+        dt_ns: u64,
+        result: &SimulationResult,
+    ) -> CliResult<RunManifest> {
+        let created_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        let nir_hash = format!("{:016x}", fnv1a(source.text.as_bytes()));
+        let base = format!("{}-{}", created_unix_ms, &nir_hash[..8]);
+        let id = (1..)
+            .map(|n| match n {
+                1 => base.clone(),
+                n => format!("{}-{}", base, n),
+            })
+            .find(|id| !self.root.join(id).exists())
+            .expect("unbounded candidates");
+
+        let manifest = RunManifest {
+            format: MANIFEST_FORMAT.to_string(),
+            version: MANIFEST_VERSION,
+            id: id.clone(),
+            created_unix_ms,
+            snn_version: env!("CARGO_PKG_VERSION").to_string(),
+            nir_source: source.path.display().to_string(),
+            nir_hash,
+            seed,
+            seeds: seeds.clone(),
+            bindings: source.bindings.clone(),
+            dt_ns,
+            duration_ns: result.duration_ns,
+            wall_time_ns: result.wall_time_ns,
+            metrics: RunMetrics {
+                neurons: result.neuron_count,
+                steps_executed: result.steps_executed,
+                spike_count: result.spikes.len(),
+                mean_rate_hz: result.average_firing_rate() as f64 / result.neuron_count.max(1) as f64,
+                spike_hash: format!("{:016x}", result.spike_hash()),
+            },
+            results: RESULTS_FILE.to_string(),
+        };
+
+        // Results and manifest appear together, so a listed run always has its results
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| CliError::Generic(anyhow::anyhow!(e)))?;
+        let mut commit = Commit::new(self.root.join(&id))?;
+        commit.stage(RESULTS_FILE, result.to_json().as_bytes())?;
+        commit.stage(MANIFEST_FILE, json.as_bytes())?;
+        commit.commit()?;
+        Ok(manifest)
+    }
+
+    /// Every readable manifest, oldest first; unreadable entries are skipped with a warning
+    pub fn list(&self) -> Vec<RunManifest> {
+        let Ok(entries) = std::fs::read_dir(&self.root) else { return Vec::new() };
+        let mut runs: Vec<RunManifest> = entries
+            .flatten()
+            .map(|entry| entry.path().join(MANIFEST_FILE))
+            .filter(|path| path.exists())
+            .filter_map(|path| {
+                let parsed = std::fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| serde_json::from_str::<RunManifest>(&text).map_err(|e| e.to_string()));
+                match parsed {
+                    Ok(manifest) if manifest.format == MANIFEST_FORMAT => Some(manifest),
+                    Ok(_) => None,
+                    Err(e) => {
+                        warn!("skipping run manifest {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        runs.sort_by(|a, b| (a.created_unix_ms, &a.id).cmp(&(b.created_unix_ms, &b.id)));
+        runs
+    }
+
+    /// Results file of run `id`, if recorded
+    pub fn results_path(&self, id: &str) -> Option<PathBuf> {
+        let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        let manifest = self.root.join(id).join(MANIFEST_FILE);
+        if !valid || !manifest.exists() {
+            return None;
+        }
+        let text = std::fs::read_to_string(manifest).ok()?;
+        let results = serde_json::from_str::<RunManifest>(&text).ok()?.results;
+        if Path::new(&results).components().any(|c| !matches!(c, std::path::Component::Normal(_))) {
+            return None;
+        }
+        Some(self.root.join(id).join(results)).filter(|path| path.exists())
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
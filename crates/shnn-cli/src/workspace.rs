@@ -85,6 +85,16 @@ impl Workspace {
         self.root.join("data")
     }
     
+    /// Get the hypergraph store directory
+    pub fn hypergraph_dir(&self) -> PathBuf {
+        self.data_dir().join("hypergraph")
+    }
+    
+    /// Get the TTR mask directory
+    pub fn masks_dir(&self) -> PathBuf {
+        self.data_dir().join("masks")
+    }
+    
     /// Get the results directory
     pub fn results_dir(&self) -> PathBuf {
         self.root.join("results")
@@ -0,0 +1,44 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn bench_writes_report() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let report = tmp.path().join("bench.json");
+
+    Command::cargo_bin("snn")?
+        .args(["bench", "--scenario", "small", "--iterations", "2", "--duration-ms", "5", "-o"])
+        .arg(&report)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("SCENARIO").and(predicate::str::contains("small")));
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    assert_eq!(json["format"], "snn-bench-report");
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["iterations"], 2);
+    let scenarios = json["scenarios"].as_array().unwrap();
+    assert_eq!(scenarios.len(), 1);
+    let small = &scenarios[0];
+    assert_eq!(small["name"], "small");
+    assert_eq!(small["neurons"], 64);
+    assert_eq!(small["synapses"], 32 * 32);
+    assert_eq!(small["steps"], 50);
+    for stage in ["parse_text", "verify", "lower", "run"] {
+        let timing = &small["stages"][stage];
+        assert!(timing["min_ns"].as_u64().unwrap() <= timing["mean_ns"].as_u64().unwrap());
+        assert!(timing["mean_ns"].as_u64().unwrap() <= timing["max_ns"].as_u64().unwrap());
+    }
+    Ok(())
+}
+
+#[test]
+fn bench_rejects_zero_iterations() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["bench", "--iterations", "0"])
+        .assert()
+        .failure();
+    Ok(())
+}
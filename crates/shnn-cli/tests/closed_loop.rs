@@ -0,0 +1,76 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1, stdp_rule_v1, synapse_connect_v1};
+
+/// Hand-wired cart-pole controller: pole angle and angular velocity inputs
+/// (neurons 4-7) drive the output pushing towards the side the pole leans to
+fn write_controller(path: &Path, duration_ms: f32) -> Result<(), Box<dyn Error>> {
+    let mut module = Module::new();
+    // Negligible learning rates keep the wired weights (near) fixed
+    module.push(stdp_rule_v1(1e-6, 1e-6, 20.0, 20.0, 0.0, 1000.0));
+    // Unused x / x_dot inputs still need neurons to receive injected spikes
+    module.push(layer_fully_connected_v1(0, 3, 8, 8, 0.0, 0.1));
+    for (input, output) in [(4, 9), (6, 9), (5, 8), (7, 8)] {
+        module.push(synapse_connect_v1(input, output, 1000.0, 0.1));
+    }
+    module.push(runtime_simulate_run_v1(0.1, duration_ms, false, Some(1)));
+    std::fs::write(path, module.to_text())?;
+    Ok(())
+}
+
+fn run_closed_loop(model: &Path, extra: &[&str]) -> Result<assert_cmd::assert::Assert, Box<dyn Error>> {
+    Ok(Command::cargo_bin("snn")?
+        .args(["run-closed-loop", model.to_str().unwrap(), "--env", "cartpole", "--steps-per-action", "100"])
+        .args(extra)
+        .assert())
+}
+
+#[test]
+fn run_closed_loop_balances_with_wired_controller() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("controller.nirt");
+    let report = tmp.path().join("episodes.json");
+    write_controller(&model, 1000.0)?;
+
+    run_closed_loop(&model, &["--episodes", "2", "--max-actions", "50", "-o", report.to_str().unwrap()])?
+        .success()
+        .stdout(predicate::str::contains("Episode 1: 50 actions, reward 50.0, done"))
+        .stdout(predicate::str::contains("Episode 2: 50 actions"));
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    assert_eq!(json["env"], "cartpole");
+    assert_eq!(json["mean_reward"], 50.0);
+    let episodes = json["episodes"].as_array().unwrap();
+    assert_eq!(episodes.len(), 2);
+    assert_eq!(episodes[0]["network_steps"], 5000);
+    assert!(episodes[0]["output_spikes"].as_u64().unwrap() > 0);
+    Ok(())
+}
+
+#[test]
+fn run_closed_loop_warns_when_model_duration_is_short() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("controller.nirt");
+    write_controller(&model, 25.0)?;
+
+    run_closed_loop(&model, &["--max-actions", "50"])?
+        .success()
+        .stdout(predicate::str::contains("Episode 1 stopped after 2 actions"));
+    Ok(())
+}
+
+#[test]
+fn run_closed_loop_rejects_missing_neurons() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("controller.nirt");
+    write_controller(&model, 10.0)?;
+
+    run_closed_loop(&model, &["--output-start", "9"])?
+        .failure()
+        .stdout(predicate::str::contains("model has 10 neurons"));
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// `snn` isolated from the caller's user config and HSNN_* variables
+fn snn(workspace: &Path) -> Result<Command, Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.env("XDG_CONFIG_HOME", workspace.join("xdg"))
+        .env_remove("HSNN_VIZ_PORT")
+        .env_remove("RUST_LOG")
+        .args(["-w", workspace.to_str().unwrap()]);
+    Ok(cmd)
+}
+
+#[test]
+fn config_layers_resolve_in_order() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    snn(tmp.path())?.args(["config", "get", "viz.port"]).assert().success().stdout("7878\n");
+
+    let user = tmp.path().join("user.toml");
+    std::fs::write(&user, "[viz]\nport = 9200\nhost = \"0.0.0.0\"\n")?;
+    let with_user = |args: &[&str]| -> Result<Command, Box<dyn Error>> {
+        let mut cmd = snn(tmp.path())?;
+        cmd.args(["--config", user.to_str().unwrap()]).args(args);
+        Ok(cmd)
+    };
+    with_user(&["config", "get", "viz.port"])?.assert().success().stdout("9200\n");
+
+    // Workspace hsnn.toml overrides the user file but keeps unrelated keys
+    std::fs::write(tmp.path().join("hsnn.toml"), "[workspace]\nname = \"demo\"\n")?;
+    snn(tmp.path())?.args(["config", "set", "viz.port", "9001"]).assert().success();
+    let text = std::fs::read_to_string(tmp.path().join("hsnn.toml"))?;
+    assert!(text.contains("name = \"demo\""));
+    assert!(text.contains("[viz]") && text.contains("port = 9001"));
+    with_user(&["config", "get", "viz.port"])?.assert().success().stdout("9001\n");
+    with_user(&["config", "get", "viz.host"])?.assert().success().stdout("0.0.0.0\n");
+
+    // HSNN_* variables override every file
+    with_user(&["config", "get", "viz.port"])?
+        .env("HSNN_VIZ_PORT", "9100")
+        .assert()
+        .success()
+        .stdout("9100\n");
+    with_user(&["config", "get"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("workspace (").and(predicate::str::contains("user (")));
+
+    // --user writes the user file
+    with_user(&["config", "set", "--user", "run.threads", "4"])?.assert().success();
+    assert!(std::fs::read_to_string(&user)?.contains("threads = 4"));
+    Ok(())
+}
+
+#[test]
+fn config_rejects_bad_keys_and_values() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    snn(tmp.path())?
+        .args(["config", "set", "viz.port", "0"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("expected a port in 1-65535"));
+    snn(tmp.path())?
+        .args(["config", "get", "viz.colour"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("unknown key 'viz.colour'"));
+    snn(tmp.path())?
+        .args(["config", "get", "viz.port"])
+        .env("HSNN_VIZ_PORT", "http")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("HSNN_VIZ_PORT=http"));
+    assert!(!tmp.path().join("hsnn.toml").exists());
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[cfg(feature = "dsl")]
+#[test]
+fn dsl_build_writes_verified_nir() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    std::fs::write(tmp.path().join("layers.rhai"), "fn layer(a, b, w) { op(\"connectivity.layer_fully_connected@v1\", #{ \"in\": a, out: b, weight: w, delay: ms(1) }) }")?;
+    std::fs::write(
+        tmp.path().join("net.rhai"),
+        r#"
+        import "layers" as l;
+        let m = nir_module();
+        m.push(op("neuron.lif@v1", #{ tau_m: ms(20), v_rest: -70.0, v_reset: -70.0, v_thresh: -50.0, t_refrac: ms(2), r_m: 10.0, c_m: 1.0 }));
+        m.push(l::layer(0..n, n..(2 * n), 1.5));
+        for i in 0..n {
+            m.push(op("stimulus.poisson@v1", #{ neuron: i, rate: 10.0 * (i + 1), amplitude: 10, start: ms(0), duration: ms(50) }));
+        }
+        m.push(op("runtime.simulate.run@v1").attr("dt", us(100)).attr("duration", ms(50)).attr("record_potentials", false));
+        m
+        "#,
+    )?;
+
+    Command::cargo_bin("snn")?
+        .args(["--workspace", tmp.path().to_str().unwrap(), "dsl", "build", "net.rhai", "-D", "n=3"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Built 6 op(s)"));
+
+    let text = std::fs::read_to_string(tmp.path().join("net.nir"))?;
+    assert!(text.contains("in = 0..2"));
+    assert!(text.contains("out = 3..5"));
+    assert!(text.contains("rate = 30 Hz"));
+    assert!(text.contains("dt = 100000 ns"));
+
+    // The generated NIR compiles like hand-written text
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", tmp.path().join("net.nir").to_str().unwrap()])
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[cfg(feature = "dsl")]
+#[test]
+fn dsl_build_reports_attribute_errors() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    std::fs::write(tmp.path().join("bad.rhai"), r#"[op("runtime.simulate.run@v1", #{ dt: 0.1 })]"#)?;
+    Command::cargo_bin("snn")?
+        .args(["--workspace", tmp.path().to_str().unwrap(), "dsl", "build", "bad.rhai"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("attribute 'dt': expected a time with a unit"));
+    Ok(())
+}
+
+#[cfg(not(feature = "dsl"))]
+#[test]
+fn dsl_build_requires_feature() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["dsl", "build", "net.rhai"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--features dsl"));
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::{BitmapMask, GenerationId, Mask, MaskId, MaskType, VCSRSnapshot};
+
+#[test]
+fn hg_create_import_query_info() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let ws = tmp.path().to_str().unwrap();
+    let csv = tmp.path().join("edges.csv");
+    std::fs::write(&csv, "pre,post,weight\n0,1,0.5\n0,2,0.25\n2,1,1.0\n")?;
+
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "create"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "import", csv.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported generation 1 (3 vertices, 3 edges)"));
+    assert!(tmp.path().join("data/hypergraph/gen_0000000000000001.vcsr").exists());
+
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "query", "1", "--direction", "in"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 sources"));
+
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "info"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("incidences:  3"))
+        .stdout(predicate::str::contains("max degree:  2"));
+    Ok(())
+}
+
+#[test]
+fn hg_query_without_store_fails() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    Command::cargo_bin("snn")?
+        .args(["-w", tmp.path().to_str().unwrap(), "hg", "query", "0"])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn hg_morph_script_creates_generation() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let ws = tmp.path().to_str().unwrap();
+    let edges = tmp.path().join("edges.txt");
+    std::fs::write(&edges, "0 1 0.5\n1 2 0.5\n")?;
+    let script = tmp.path().join("ops.toml");
+    std::fs::write(
+        &script,
+        r#"
+[[op]]
+kind = "add_vertex"
+id = 3
+
+[[op]]
+kind = "rewire"
+source = 0
+from = 1
+to = 3
+
+[[op]]
+kind = "remove_edge"
+source = 1
+target = 2
+"#,
+    )?;
+
+    Command::cargo_bin("snn")?.args(["-w", ws, "hg", "create"]).assert().success();
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "import", edges.to_str().unwrap()])
+        .assert()
+        .success();
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "morph", "--script", script.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("generation 1 -> generation 2"))
+        .stdout(predicate::str::contains("edges +1 -2"));
+
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "query", "0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  3\t0.500000"));
+    Ok(())
+}
+
+#[test]
+fn hg_stats_reports_distributions() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let ws = tmp.path().to_str().unwrap();
+    let csv = tmp.path().join("edges.csv");
+    std::fs::write(&csv, "pre,post,weight\n0,1,0.5\n0,2,0.25\n2,1,1.0\n")?;
+    Command::cargo_bin("snn")?.args(["-w", ws, "hg", "create"]).assert().success();
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "import", csv.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json = tmp.path().join("stats.json");
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "stats", "--json", json.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Generation 1:"))
+        .stdout(predicate::str::contains("out-degree:  0: 1  1: 1  2-3: 1"))
+        .stdout(predicate::str::contains("cardinality: 2-3: 2"));
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json)?)?;
+    let generation = &report["generations"][0];
+    assert_eq!(generation["edges"].as_u64(), Some(3));
+    assert_eq!(generation["density"].as_f64(), Some(0.5));
+    assert_eq!(generation["max_in_degree"].as_u64(), Some(2));
+    let cardinality: Vec<(u64, u64)> = generation["hyperedge_cardinality"].as_array().unwrap().iter()
+        .map(|bin| (bin["value"].as_u64().unwrap(), bin["count"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(cardinality, vec![(2, 1), (3, 1)]);
+
+    // A single VCSR file, checked against its block table
+    let file = tmp.path().join("data/hypergraph/gen_0000000000000001.vcsr");
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "stats", file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in-degree:   0: 1  1: 1  2-3: 1"));
+    Ok(())
+}
+
+#[test]
+fn hg_extract_mask_subview() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let ws = tmp.path().to_str().unwrap();
+    let csv = tmp.path().join("edges.csv");
+    std::fs::write(&csv, "pre,post,weight\n0,1,0.5\n1,2,0.25\n2,0,1.0\n3,1,0.75\n")?;
+    Command::cargo_bin("snn")?.args(["-w", ws, "hg", "create"]).assert().success();
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "import", csv.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let mask = tmp.path().join("task.vmsk");
+    let vertices = BitmapMask::from_indices(MaskId::new(1), MaskType::VertexMask, GenerationId::new(1), 4, [1, 2, 3])?;
+    std::fs::write(&mask, vertices.export_vmsk()?)?;
+    let out = tmp.path().join("sub.vcsr");
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "extract", "--mask", mask.to_str().unwrap(), "--out", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3/4 vertices, 2/4 edges"));
+
+    let sub = VCSRSnapshot::from_bytes(&std::fs::read(&out)?)?;
+    assert_eq!(sub.vertices.iter().map(|v| v.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    let edges: Vec<_> = sub.edges().map(|(s, t, w)| (s.raw(), t.raw(), w)).collect();
+    assert_eq!(edges, vec![(0, 1, 0.25), (2, 0, 0.75)]);
+
+    // Edge masks address edges, not vertices
+    let edge_mask = tmp.path().join("edges.vmsk");
+    let edges = BitmapMask::from_indices(MaskId::new(2), MaskType::EdgeMask, GenerationId::new(1), 4, [0])?;
+    std::fs::write(&edge_mask, edges.export_vmsk()?)?;
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "hg", "extract", "--mask", edge_mask.to_str().unwrap(), "--out", out.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("vertex or activity mask"));
+    Ok(())
+}
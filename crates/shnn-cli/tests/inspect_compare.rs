@@ -0,0 +1,81 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::vevt::{encode_vevt, VEVTEvent};
+use shnn_storage::{StreamId, Time};
+
+const MS: u64 = 1_000_000;
+
+fn write_recording(path: &Path, spikes: &[(u64, u32)]) -> Result<(), Box<dyn Error>> {
+    let events: Vec<VEVTEvent> = spikes
+        .iter()
+        .map(|&(time_ns, neuron)| VEVTEvent {
+            timestamp: time_ns,
+            event_type: 0,
+            source_id: neuron,
+            target_id: u32::MAX,
+            payload_size: 0,
+            reserved: 0,
+        })
+        .collect();
+    std::fs::write(path, encode_vevt(StreamId::new(1), Time::ZERO, Time::from_nanos(200 * MS), &events)?)?;
+    Ok(())
+}
+
+fn compare(a: &Path, b: &Path, json: &Path) -> Result<serde_json::Value, Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["inspect", "compare", a.to_str().unwrap(), b.to_str().unwrap()])
+        .args(["--window", "0ms..200ms", "--bin", "20ms", "--json", json.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("SPIKE-distance"));
+    Ok(serde_json::from_str(&std::fs::read_to_string(json)?)?)
+}
+
+#[test]
+fn inspect_compare_identical_and_shifted_recordings() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    // Neuron 0 fires every 10 ms, neuron 2 every 25 ms
+    let mut base: Vec<(u64, u32)> = (0..20).map(|i| (i * 10 * MS, 0)).collect();
+    base.extend((0..8).map(|i| (i * 25 * MS, 2)));
+    base.sort_unstable();
+    let shifted: Vec<(u64, u32)> = base.iter().map(|&(t, id)| (t + 2 * MS, id)).collect();
+
+    let (a, b, c) = (tmp.path().join("a.vevt"), tmp.path().join("b.vevt"), tmp.path().join("c.vevt"));
+    write_recording(&a, &base)?;
+    write_recording(&b, &base)?;
+    write_recording(&c, &shifted)?;
+
+    let same = compare(&a, &b, &tmp.path().join("same.json"))?;
+    assert_eq!(same["window_end_ns"], 200 * MS);
+    assert_eq!(same["van_rossum"], 0.0);
+    assert_eq!(same["victor_purpura"], 0.0);
+    assert_eq!(same["spike_distance"], 0.0);
+    assert!((same["rate_correlation"].as_f64().unwrap() - 1.0).abs() < 1e-12);
+    assert_eq!(same["neurons"].as_array().unwrap().len(), 2);
+    assert_eq!(same["neurons"][1]["neuron"], 2);
+
+    let moved = compare(&a, &c, &tmp.path().join("moved.json"))?;
+    assert!(moved["van_rossum"].as_f64().unwrap() > 0.0);
+    // Every spike moves by 2 ms at a 10 ms timescale (the last shifted spike stays in the window)
+    assert!((moved["neurons"][0]["victor_purpura"].as_f64().unwrap() - 20.0 * 0.2).abs() < 1e-9);
+    let spike_distance = moved["spike_distance"].as_f64().unwrap();
+    assert!(spike_distance > 0.0 && spike_distance < 1.0);
+    Ok(())
+}
+
+#[test]
+fn inspect_compare_requires_two_recordings() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let a = tmp.path().join("a.vevt");
+    write_recording(&a, &[(MS, 0)])?;
+    Command::cargo_bin("snn")?
+        .args(["inspect", "compare", a.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("requires two recordings"));
+    Ok(())
+}
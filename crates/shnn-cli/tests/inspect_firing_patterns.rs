@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::vevt::{encode_vevt, VEVTEvent};
+use shnn_storage::{StreamId, Time};
+
+const MS: u64 = 1_000_000;
+
+fn write_recording(path: &Path, spikes: &[(u64, u32)]) -> Result<(), Box<dyn Error>> {
+    let events: Vec<VEVTEvent> = spikes
+        .iter()
+        .map(|&(time_ns, neuron)| VEVTEvent {
+            timestamp: time_ns,
+            event_type: 0,
+            source_id: neuron,
+            target_id: u32::MAX,
+            payload_size: 0,
+            reserved: 0,
+        })
+        .collect();
+    std::fs::write(path, encode_vevt(StreamId::new(1), Time::ZERO, Time::from_nanos(300 * MS), &events)?)?;
+    Ok(())
+}
+
+#[test]
+fn inspect_firing_patterns_reports_composition() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    // Neuron 0 fires every 10 ms, neuron 1 in triplets 2 ms apart every 50 ms, neuron 2 once
+    let mut spikes: Vec<(u64, u32)> = (0..30).map(|i| (i * 10 * MS, 0)).collect();
+    spikes.extend((0..6).flat_map(|b| (0..3).map(move |s| (b * 50 * MS + s * 2 * MS, 1))));
+    spikes.push((100 * MS, 2));
+    spikes.sort_unstable();
+    let recording = tmp.path().join("run.vevt");
+    write_recording(&recording, &spikes)?;
+
+    let json = tmp.path().join("patterns.json");
+    Command::cargo_bin("snn")?
+        .args(["inspect", "firing-patterns", recording.to_str().unwrap()])
+        .args(["--neurons", "0..3", "--json", json.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bursting"));
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json)?)?;
+    let patterns: Vec<&str> = report["neurons"].as_array().unwrap().iter().map(|n| n["pattern"].as_str().unwrap()).collect();
+    assert_eq!(patterns, vec!["tonic", "bursting", "silent", "silent"]);
+    assert_eq!(report["neurons"][0]["lv"], 0.0);
+    assert_eq!(report["neurons"][3]["spikes"], 0);
+    assert_eq!(report["composition"][0]["pattern"], "silent");
+    assert_eq!(report["composition"][0]["neurons"], 2);
+    assert_eq!(report["composition"][0]["fraction"], 0.5);
+    Ok(())
+}
+
+#[test]
+fn inspect_firing_patterns_requires_recording() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["inspect", "firing-patterns"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("requires a recording"));
+    Ok(())
+}
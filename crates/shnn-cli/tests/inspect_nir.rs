@@ -0,0 +1,59 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1, stimulus_poisson_v1, synapse_connect_v1};
+
+#[test]
+fn inspect_nir_reports_populations_and_projections() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("net.nirt");
+    let report = tmp.path().join("nir.json");
+
+    // 0..1 -> 2..4 -> 5, with feedback 5 -> 2
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 4, 0.5, 1.0));
+    module.push(layer_fully_connected_v1(2, 4, 5, 5, 0.25, 2.0));
+    module.push(synapse_connect_v1(5, 2, 0.75, 3.0));
+    module.push(stimulus_poisson_v1(0, 40.0, 5.0, 0.0, 10.0));
+    module.push(stimulus_poisson_v1(1, 60.0, 5.0, 0.0, 10.0));
+    module.push(runtime_simulate_run_v1(0.1, 10.0, false, Some(1)));
+    std::fs::write(&model, module.to_text())?;
+
+    Command::cargo_bin("snn")?
+        .args(["inspect", "nir", model.to_str().unwrap(), "--json", report.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("neurons: 6  synapses: 10"))
+        .stdout(predicate::str::contains("#0 -> #1: 6 synapses (100.0% dense)"))
+        .stdout(predicate::str::contains("stimuli: 2 poisson (2 neurons)"));
+
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    let populations: Vec<_> = value["populations"].as_array().unwrap().iter()
+        .map(|p| (p["start"].as_u64().unwrap(), p["end"].as_u64().unwrap(), p["stimulated"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(populations, vec![(0, 1, 2), (2, 4, 0), (5, 5, 0)]);
+    let projections: Vec<_> = value["projections"].as_array().unwrap().iter()
+        .map(|p| (p["source"].as_u64().unwrap(), p["target"].as_u64().unwrap(), p["synapses"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(projections, vec![(0, 1, 6), (1, 2, 3), (2, 1, 1)]);
+    assert_eq!(value["projections"][2]["weight_mean"], 0.75);
+    let delays: Vec<_> = value["delays"].as_array().unwrap().iter()
+        .map(|d| (d["start_ms"].as_f64().unwrap(), d["count"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(delays, vec![(1.0, 6), (2.0, 3), (3.0, 1)]);
+    assert_eq!(value["in_degree"], serde_json::json!([2, 0, 2, 2]));
+    assert_eq!(value["stimuli"][1]["rate_hz"], 60.0);
+    Ok(())
+}
+
+#[test]
+fn inspect_nir_requires_model() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["inspect", "nir"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("nir <model.nirt>"));
+    Ok(())
+}
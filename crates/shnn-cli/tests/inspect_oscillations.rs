@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::vevt::{encode_vevt, VEVTEvent};
+use shnn_storage::{StreamId, Time};
+
+const MS: u64 = 1_000_000;
+
+fn write_recording(path: &Path, spikes: &[(u64, u32)]) -> Result<(), Box<dyn Error>> {
+    let events: Vec<VEVTEvent> = spikes
+        .iter()
+        .map(|&(time_ns, neuron)| VEVTEvent {
+            timestamp: time_ns,
+            event_type: 0,
+            source_id: neuron,
+            target_id: u32::MAX,
+            payload_size: 0,
+            reserved: 0,
+        })
+        .collect();
+    std::fs::write(path, encode_vevt(StreamId::new(1), Time::ZERO, Time::from_nanos(1000 * MS), &events)?)?;
+    Ok(())
+}
+
+#[test]
+fn inspect_oscillations_finds_gamma_rhythm() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    // 20 neurons firing once per 25 ms cycle (40 Hz), spread over 5 ms within the cycle
+    let mut spikes: Vec<(u64, u32)> = (0..20u32)
+        .flat_map(|i| (1..40u64).map(move |k| (k * 25 * MS + (i as u64 % 5) * MS, i)))
+        .collect();
+    spikes.sort_unstable();
+    let recording = tmp.path().join("run.vevt");
+    write_recording(&recording, &spikes)?;
+
+    let json = tmp.path().join("oscillations.json");
+    Command::cargo_bin("snn")?
+        .args(["inspect", "oscillations", recording.to_str().unwrap()])
+        .args(["--window", "0ms..1000ms", "--groups", "0..9,10..19", "--json", json.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("synchrony (chi)"));
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json)?)?;
+    assert_eq!(report["segment_bins"], 256);
+    assert_eq!(report["segments"], 6);
+    assert_eq!(report["power"].as_array().unwrap().len(), 129);
+    let peak_hz = report["peak_hz"].as_f64().unwrap();
+    assert!((35.0..45.0).contains(&peak_hz), "{}", peak_hz);
+    // Independent neurons would give about 1/sqrt(20) = 0.22
+    assert!(report["synchrony"].as_f64().unwrap() > 0.3);
+    assert!(report["coherence"]["at_peak"].as_f64().unwrap() > 0.9);
+    Ok(())
+}
+
+#[test]
+fn inspect_oscillations_rejects_bad_groups() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let recording = tmp.path().join("run.vevt");
+    write_recording(&recording, &[(MS, 0), (5 * MS, 1)])?;
+    Command::cargo_bin("snn")?
+        .args(["inspect", "oscillations", recording.to_str().unwrap(), "--groups", "0..9"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--groups"));
+    Ok(())
+}
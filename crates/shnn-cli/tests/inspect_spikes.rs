@@ -0,0 +1,93 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::vevt::{encode_vevt, VEVTEvent};
+use shnn_storage::{StreamId, Time};
+
+const MS: u64 = 1_000_000;
+
+fn spike(time_ns: u64, neuron: u32) -> VEVTEvent {
+    VEVTEvent { timestamp: time_ns, event_type: 0, source_id: neuron, target_id: u32::MAX, payload_size: 0, reserved: 0 }
+}
+
+#[test]
+fn inspect_spikes_windows_bins_and_exports() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let recording = tmp.path().join("run.vevt");
+    // Neuron 0 fires every ms for 3 s, neuron 4 only during the second second
+    let mut events: Vec<VEVTEvent> = (0..3000).map(|ms| spike(ms * MS, 0)).collect();
+    events.extend((1000..2000).step_by(10).map(|ms| spike(ms * MS + 1, 4)));
+    events.sort_by_key(|e| e.timestamp);
+    std::fs::write(&recording, encode_vevt(StreamId::new(1), Time::ZERO, Time::from_nanos(3000 * MS), &events)?)?;
+
+    let out = tmp.path().join("out");
+    Command::cargo_bin("snn")?
+        .args(["inspect", "spikes", "--input", recording.to_str().unwrap(), "--window", "1s..2s", "--bin", "5ms"])
+        .args(["--csv", out.join("rates.csv").to_str().unwrap()])
+        .args(["--npy", out.join("rates.npy").to_str().unwrap()])
+        .args(["--json", out.join("report.json").to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("200 bins"));
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(out.join("report.json"))?)?;
+    assert_eq!(report["window_start_ns"], 1000 * MS);
+    assert_eq!(report["window_end_ns"], 2000 * MS);
+    assert_eq!(report["bins"], 200);
+    assert_eq!(report["neurons"], serde_json::json!([0, 4]));
+    assert_eq!(report["spikes"], 1100);
+    assert_eq!(report["population_rate_hz"][0], 600.0);
+
+    let csv = std::fs::read_to_string(out.join("rates.csv"))?;
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows[0].starts_with("neuron_id,1000,1005,"));
+    assert!(rows[1].starts_with("0,1000,1000,"));
+    assert!(rows[2].starts_with("4,200,200,"));
+
+    let npy = std::fs::read(out.join("rates.npy"))?;
+    assert!(npy.starts_with(b"\x93NUMPY"));
+    assert!(npy.windows(8).any(|w| w == b"(2, 200)"));
+    Ok(())
+}
+
+#[test]
+fn inspect_spikes_downsamples_and_keeps_silent_neurons() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let recording = tmp.path().join("run.vevt");
+    let events: Vec<VEVTEvent> = (0..100).map(|ms| spike(ms * MS, 1)).collect();
+    std::fs::write(&recording, encode_vevt(StreamId::new(1), Time::ZERO, Time::from_nanos(100 * MS), &events)?)?;
+
+    let report = tmp.path().join("report.json");
+    Command::cargo_bin("snn")?
+        .args(["inspect", "spikes", "--input", recording.to_str().unwrap(), "--bin", "1ms"])
+        .args(["--neurons", "0..2", "--max-bins", "10", "--json", report.to_str().unwrap()])
+        .assert()
+        .success();
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(report)?)?;
+    assert_eq!(report["bins"], 10);
+    assert_eq!(report["bin_ns"], 10 * MS);
+    assert_eq!(report["neurons"], serde_json::json!([0, 1, 2]));
+    assert_eq!(report["spikes"], 100);
+    Ok(())
+}
+
+#[test]
+fn inspect_spikes_rejects_bad_window() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let recording = tmp.path().join("run.vevt");
+    std::fs::write(&recording, encode_vevt(StreamId::new(1), Time::ZERO, Time::ZERO, &[spike(0, 0)])?)?;
+    Command::cargo_bin("snn")?
+        .args(["inspect", "spikes", "--input", recording.to_str().unwrap(), "--window", "2s..1s"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--window"));
+    Command::cargo_bin("snn")?
+        .args(["inspect", "spikes", "--input", recording.to_str().unwrap(), "--bin", "5 parsecs"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--bin"));
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::vevt::{encode_vevt, VEVTEvent};
+use shnn_storage::{StreamId, Time};
+
+#[test]
+fn inspect_storage_reports_vcsr_and_corruption() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let snapshot = tmp.path().join("net.vcsr");
+    let report = tmp.path().join("storage.json");
+
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "compile", "-o", snapshot.to_str().unwrap(),
+            "--inputs", "4", "--hidden", "4", "--outputs", "2",
+            "--steps", "100", "--dt-us", "100", "--emit", "vcsr",
+        ])
+        .assert()
+        .success();
+
+    Command::cargo_bin("snn")?
+        .args(["inspect", "storage", snapshot.to_str().unwrap(), "--json", report.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("format: VCSR v2"))
+        .stdout(predicate::str::contains("num_incidences: 24"))
+        .stdout(predicate::str::contains("block checksums: 1 ok"))
+        .stdout(predicate::str::contains("data checksum: ok"));
+
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    assert_eq!(value["format"], "VCSR");
+    assert_eq!(value["fields"]["num_vertices"], "10");
+    assert_eq!(value["generation"], 0);
+    assert_eq!(value["valid"], true);
+
+    // Flip the last weight byte (the block table follows): the data checksum no longer matches
+    let mut bytes = std::fs::read(&snapshot)?;
+    let last_weight = bytes.len() - 5;
+    bytes[last_weight] ^= 0xff;
+    std::fs::write(&snapshot, bytes)?;
+    Command::cargo_bin("snn")?
+        .args(["inspect", "storage", snapshot.to_str().unwrap(), "--json", report.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("data checksum: MISMATCH"))
+        .stdout(predicate::str::contains("block checksums: 1 of 1 CORRUPT at offsets [128]"));
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    assert_eq!(value["valid"], false);
+    assert_eq!(value["checksums"][1]["status"], "mismatch");
+    Ok(())
+}
+
+#[test]
+fn inspect_storage_reports_vevt_time_range() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let stream = tmp.path().join("run.vevt");
+    let events: Vec<VEVTEvent> = (0..3)
+        .map(|i| VEVTEvent { timestamp: i * 1_000_000, event_type: 0, source_id: i as u32, target_id: u32::MAX, payload_size: 0, reserved: 0 })
+        .collect();
+    std::fs::write(&stream, encode_vevt(StreamId::new(9), Time::from_nanos(0), Time::from_nanos(2_000_000), &events)?)?;
+
+    Command::cargo_bin("snn")?
+        .args(["inspect", "storage", stream.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("format: VEVT v2"))
+        .stdout(predicate::str::contains("total_events: 3"))
+        .stdout(predicate::str::contains("time range: 0.000..2.000 ms"))
+        .stdout(predicate::str::contains("header checksum: ok"));
+
+    let junk = tmp.path().join("junk.bin");
+    std::fs::write(&junk, b"not a storage file")?;
+    Command::cargo_bin("snn")?
+        .args(["inspect", "storage", junk.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("unrecognized magic"));
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1, synapse_connect_v1};
+
+fn inspect(model: &Path, report: &Path) -> Result<serde_json::Value, Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["inspect", "topology", "--input", model.to_str().unwrap(), "--json", report.to_str().unwrap()])
+        .assert()
+        .success();
+    Ok(serde_json::from_str(&std::fs::read_to_string(report)?)?)
+}
+
+#[test]
+fn inspect_topology_feedforward_and_recurrent() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let report = tmp.path().join("topology.json");
+
+    // 0..1 -> 2..4 -> 5
+    let feedforward = tmp.path().join("ff.nirt");
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 4, 0.5, 1.0));
+    module.push(layer_fully_connected_v1(2, 4, 5, 5, 0.5, 1.0));
+    module.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(1)));
+    std::fs::write(&feedforward, module.to_text())?;
+
+    let value = inspect(&feedforward, &report)?;
+    assert_eq!(value["nodes"], 6);
+    assert_eq!(value["edges"], 9);
+    assert_eq!(value["has_cycles"], false);
+    assert_eq!(value["longest_path"], 2);
+    assert_eq!((value["sources"].clone(), value["sinks"].clone()), (2.into(), 1.into()));
+    assert_eq!(value["out_degree"], serde_json::json!([1, 3, 2]));
+
+    // Close a loop 5 -> 2
+    module.push(synapse_connect_v1(5, 2, 0.5, 1.0));
+    let recurrent = tmp.path().join("rec.nirt");
+    std::fs::write(&recurrent, module.to_text())?;
+
+    let value = inspect(&recurrent, &report)?;
+    assert_eq!(value["has_cycles"], true);
+    assert!(value["longest_path"].is_null());
+    assert_eq!(value["largest_scc"], 2);
+    assert_eq!(value["recurrent_components"], serde_json::json!([[2, 5]]));
+    assert_eq!(value["cycle"][0], 2);
+    Ok(())
+}
+
+#[test]
+fn inspect_topology_requires_input() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["inspect", "topology"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--input"));
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::{BitmapMask, GenerationId, Mask, MaskId, MaskType};
+
+fn spiking_neurons(path: &std::path::Path) -> Result<Vec<u64>, Box<dyn Error>> {
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let mut ids: Vec<u64> = json["results"]["spikes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["neuron_id"].as_u64().unwrap())
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(ids)
+}
+
+#[test]
+fn nir_run_with_activity_mask() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let mask_path = tmp.path().join("inputs.vmsk");
+    // Only the two input neurons may spike; hidden/output are silenced
+    let mask = BitmapMask::from_indices(
+        MaskId::new(1),
+        MaskType::ActivityMask,
+        GenerationId::new(0),
+        6,
+        [0, 1],
+    )?;
+    std::fs::write(&mask_path, mask.export_vmsk()?)?;
+
+    let model = tmp.path().join("m.nirt");
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "compile",
+            "-o", model.to_str().unwrap(),
+            "--inputs", "2",
+            "--hidden", "2",
+            "--outputs", "2",
+            "--steps", "2000",
+            "--dt-us", "100",
+            "--stimulus-rate", "2000",
+            "--seed", "7",
+            "--mask", mask_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    let text = std::fs::read_to_string(&model)?;
+    assert!(text.contains("runtime.apply_mask@v1"));
+
+    let spikes = tmp.path().join("spikes.json");
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "-o", spikes.to_str().unwrap()])
+        .assert()
+        .success();
+    let ids = spiking_neurons(&spikes)?;
+    assert!(!ids.is_empty());
+    assert!(ids.iter().all(|&id| id < 2), "masked neurons spiked: {:?}", ids);
+    Ok(())
+}
+
+#[test]
+fn nir_verify_rejects_bad_mask_mode() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "compile",
+            "-o", model.to_str().unwrap(),
+            "--inputs", "1", "--hidden", "1", "--outputs", "1",
+            "--steps", "10",
+            "--mask", "task.vmsk",
+        ])
+        .assert()
+        .success();
+    let text = std::fs::read_to_string(&model)?.replace("\"activity\"", "\"weights\"");
+    std::fs::write(&model, text)?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", model.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Bad attribute 'mode'"));
+    Ok(())
+}
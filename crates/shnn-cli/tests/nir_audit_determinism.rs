@@ -0,0 +1,58 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn compile_model(path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "compile",
+            "-o", path.to_str().unwrap(),
+            "--inputs", "4",
+            "--hidden", "8",
+            "--outputs", "2",
+            "--steps", "500",
+            "--dt-us", "100",
+            "--stimulus-rate", "500",
+            "--seed", "11",
+        ])
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn nir_run_audit_determinism_across_threads() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    compile_model(&model)?;
+
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "run", model.to_str().unwrap(),
+            "--audit-determinism", "3",
+            "--audit-threads", "1,4",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Determinism audit OK: 3 runs"));
+    Ok(())
+}
+
+#[test]
+fn nir_run_audit_requires_two_runs() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    compile_model(&model)?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--audit-determinism", "1"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("at least 2 runs"));
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--audit-threads", "2"])
+        .assert()
+        .failure();
+    Ok(())
+}
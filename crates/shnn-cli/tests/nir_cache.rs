@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, lif_neuron_v1, runtime_simulate_run_v1, stimulus_poisson_v1};
+
+fn snn(workspace: &Path) -> Result<Command, Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.env("XDG_CONFIG_HOME", workspace.join("xdg"))
+        .env("HSNN_CACHE_MIN_SYNAPSES", "100")
+        .args(["-w", workspace.to_str().unwrap()]);
+    Ok(cmd)
+}
+
+fn layered_module(weight: f32) -> Module {
+    let mut module = Module::new();
+    module.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+    module.push(layer_fully_connected_v1(0, 19, 20, 39, weight, 1.0));
+    for neuron in 0..20 {
+        module.push(stimulus_poisson_v1(neuron, 200.0, 300.0, 0.0, 50.0));
+    }
+    module.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(7)));
+    module
+}
+
+#[test]
+fn nir_run_reuses_cached_topology() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("layered.nir");
+    std::fs::write(&model, layered_module(2.0).to_text())?;
+    let run = |extra: &[&str]| -> Result<assert_cmd::assert::Assert, Box<dyn Error>> {
+        Ok(snn(tmp.path())?.args(["nir", "run", model.to_str().unwrap()]).args(extra).assert().success())
+    };
+
+    run(&[])?
+        .stdout(predicate::str::contains("Cache: miss"))
+        .stdout(predicate::str::contains("stored 40 neurons, 400 synapses"))
+        .stdout(predicate::str::contains("Cache: 1 artifact(s)"));
+    // The cached lowering runs the same spikes as a fresh one
+    run(&["--audit-determinism", "2"])?
+        .stdout(predicate::str::contains("Cache: hit"))
+        .stdout(predicate::str::contains("(40 neurons, 400 synapses"))
+        .stdout(predicate::str::contains("Determinism audit OK"));
+    run(&["--no-cache"])?.stdout(predicate::str::contains("Cache:").not());
+    assert_eq!(std::fs::read_dir(tmp.path().join("results").join("cache"))?.count(), 1);
+
+    // A changed module is a new artifact
+    std::fs::write(&model, layered_module(1.5).to_text())?;
+    run(&[])?
+        .stdout(predicate::str::contains("Cache: miss"))
+        .stdout(predicate::str::contains("Cache: 2 artifact(s)"));
+    Ok(())
+}
+
+#[test]
+fn nir_run_skips_cache_for_small_networks() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("layered.nir");
+    std::fs::write(&model, layered_module(2.0).to_text())?;
+    snn(tmp.path())?
+        .env("HSNN_CACHE_MIN_SYNAPSES", "401")
+        .args(["nir", "run", model.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cache:").not());
+    assert!(!tmp.path().join("results").join("cache").exists());
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, stimulus_poisson_v1, runtime_simulate_run_v1};
+
+fn write_model(path: &std::path::Path, dt_ms: f32) -> Result<(), Box<dyn Error>> {
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 2.0));
+    module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 50.0));
+    module.push(runtime_simulate_run_v1(dt_ms, 50.0, false, Some(1)));
+    std::fs::write(path, module.to_text())?;
+    Ok(())
+}
+
+#[test]
+fn nir_codegen_emits_micro_network() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let output = tmp.path().join("network.rs");
+    write_model(&model, 1.0)?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "codegen", model.to_str().unwrap(), "--target", "micro", "-o"])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let source = std::fs::read_to_string(&output)?;
+    assert!(source.contains("from model.nirt; do not edit"));
+    assert!(source.contains("pub const NEURONS: usize = 4;"));
+    assert!(source.contains("pub const CONNECTIONS: usize = 4;"));
+    assert!(source.contains("Connection::with_delay(NeuronId::new(0), NeuronId::new(2), Q15_16::from_raw(32768), 2)"));
+    assert!(source.contains("MicroNetwork::from_tables(&NEURON_TABLE, &CONNECTION_TABLE, TIME_STEP_MS)"));
+    Ok(())
+}
+
+#[test]
+fn nir_codegen_rejects_fractional_dt() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    write_model(&model, 0.1)?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "codegen", model.to_str().unwrap(), "--target", "micro"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("whole number of milliseconds"));
+    Ok(())
+}
@@ -97,4 +97,63 @@ fn nir_run_json_and_snapshot_smoke() -> Result<(), Box<dyn Error>> {
     import_cmd.assert().success();
 
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn nir_compile_with_integrator() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("rk4.nirt");
+
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.args([
+        "nir", "compile",
+        "-o", model.to_str().unwrap(),
+        "--steps", "100",
+        "--integrator", "rk4",
+    ]);
+    cmd.assert().success();
+    let text = fs::read_to_string(&model)?;
+    assert!(text.contains("integrator = \"rk4\""), "{}", text);
+
+    let spikes = tmp.path().join("spikes.json");
+    let mut run = Command::cargo_bin("snn")?;
+    run.args(["nir", "run", model.to_str().unwrap(), "-o", spikes.to_str().unwrap()]);
+    run.assert().success();
+
+    // Unknown integrators are rejected by verification
+    let bad = tmp.path().join("bad.nirt");
+    fs::write(&bad, text.replace("\"rk4\"", "\"midpoint\""))?;
+    let mut verify = Command::cargo_bin("snn")?;
+    verify.args(["nir", "verify", bad.to_str().unwrap()]);
+    verify.assert().failure().stdout(predicate::str::contains("Bad attribute 'integrator'"));
+
+    Ok(())
+}
+
+#[test]
+fn nir_compile_with_backend() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("csr.nirt");
+
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.args([
+        "nir", "compile",
+        "-o", model.to_str().unwrap(),
+        "--steps", "100",
+        "--backend", "sparse",
+    ]);
+    cmd.assert().success();
+    let text = fs::read_to_string(&model)?;
+    assert!(text.contains("backend = \"sparse\""), "{}", text);
+
+    let mut run = Command::cargo_bin("snn")?;
+    run.args(["nir", "run", model.to_str().unwrap()]);
+    run.assert().success();
+
+    let bad = tmp.path().join("bad.nirt");
+    fs::write(&bad, text.replace("\"sparse\"", "\"csr\""))?;
+    let mut verify = Command::cargo_bin("snn")?;
+    verify.args(["nir", "verify", bad.to_str().unwrap()]);
+    verify.assert().failure().stdout(predicate::str::contains("Bad attribute 'backend'"));
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::{EventLog, StreamId, StreamKind};
+
+#[test]
+fn nir_run_writes_event_log() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "compile",
+            "-o", model.to_str().unwrap(),
+            "--inputs", "4",
+            "--hidden", "4",
+            "--outputs", "2",
+            "--steps", "200",
+            "--dt-us", "100",
+            "--stimulus", "poisson",
+            "--stimulus-rate", "200.0",
+        ])
+        .assert()
+        .success();
+
+    let out = tmp.path().join("run.vlog");
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "-o", out.to_str().unwrap(), "--spikes-format", "vlog"])
+        .assert()
+        .success();
+
+    let log = EventLog::from_bytes(&std::fs::read(&out)?)?;
+    let spikes = log.stream(StreamId::new(1)).expect("spikes stream");
+    assert_eq!(spikes.kind, StreamKind::Spikes);
+    assert_eq!(log.event_count(), spikes.events().len() as u64);
+    assert!(spikes.events().windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+    Ok(())
+}
+
+#[test]
+fn nir_run_records_weight_updates() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "compile",
+            "-o", model.to_str().unwrap(),
+            "--inputs", "4",
+            "--hidden", "4",
+            "--outputs", "2",
+            "--steps", "1000",
+            "--dt-us", "100",
+            "--stimulus", "poisson",
+            "--stimulus-rate", "400.0",
+        ])
+        .assert()
+        .success();
+
+    // Weight updates only go to an event log
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--record-weight-updates"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--spikes-format vlog"));
+
+    let out = tmp.path().join("run.vlog");
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "run", model.to_str().unwrap(),
+            "-o", out.to_str().unwrap(),
+            "--spikes-format", "vlog",
+            "--record-weight-updates",
+        ])
+        .assert()
+        .success();
+
+    let log = EventLog::from_bytes(&std::fs::read(&out)?)?;
+    let changes: Vec<_> = log
+        .streams_of(StreamKind::WeightUpdates)
+        .flat_map(|(_, stream)| stream.weight_changes())
+        .collect();
+    assert!(changes.windows(2).all(|w| w[0].time_ns <= w[1].time_ns));
+
+    let report = tmp.path().join("updates.json");
+    Command::cargo_bin("snn")?
+        .args(["inspect", "weight-updates", out.to_str().unwrap(), "--json", report.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Weight updates:"));
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    assert_eq!(report["updates"].as_u64(), Some(changes.len() as u64));
+    let synapse_updates: u64 = report["synapses"].as_array().unwrap().iter().map(|s| s["updates"].as_u64().unwrap()).sum();
+    assert_eq!(synapse_updates, changes.len() as u64);
+    Ok(())
+}
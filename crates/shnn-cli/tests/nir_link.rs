@@ -0,0 +1,70 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{
+    Module, layer_fully_connected_v1, lif_neuron_v1, parse_text, runtime_simulate_run_v1, stimulus_poisson_v1,
+    structure_port_v1, AttributeValue,
+};
+
+#[test]
+fn nir_link_flattens_modules_through_ports() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let lif = lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0);
+
+    let mut sensory = Module::new();
+    sensory.push(lif.clone());
+    sensory.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 50.0));
+    sensory.push(layer_fully_connected_v1(0, 0, 1, 3, 2.0, 1.0));
+    sensory.push(structure_port_v1("features", 1, 3));
+    sensory.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(2)));
+    std::fs::write(tmp.path().join("sensory.nir"), sensory.to_text())?;
+
+    let mut cortex = Module::new();
+    cortex.push(lif);
+    cortex.push(structure_port_v1("features", 0, 2));
+    cortex.push(layer_fully_connected_v1(0, 2, 3, 5, 1.0, 1.0));
+    std::fs::write(tmp.path().join("cortex.nir"), cortex.to_text())?;
+
+    let linked = tmp.path().join("linked.nir");
+    Command::cargo_bin("snn")?
+        .current_dir(tmp.path())
+        .args(["nir", "link", "sensory.nir", "cortex.nir", "-o", "linked.nir"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Linked 2 module(s)"))
+        .stdout(predicate::str::contains("(7 neurons, 6 ops)"))
+        .stdout(predicate::str::contains("features=1..3 (from sensory.nir)"));
+
+    let module = parse_text(&std::fs::read_to_string(&linked)?)?;
+    let cortex_layer = &module.ops[5];
+    assert_eq!(cortex_layer.attrs.get("in"), Some(&AttributeValue::RangeU32 { start: 1, end: 3 }));
+    assert_eq!(cortex_layer.attrs.get("out"), Some(&AttributeValue::RangeU32 { start: 4, end: 6 }));
+
+    Command::cargo_bin("snn")?
+        .current_dir(tmp.path())
+        .args(["nir", "run", "linked.nir"])
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn nir_link_rejects_port_size_mismatch() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let mut a = Module::new();
+    a.push(structure_port_v1("bus", 0, 3));
+    std::fs::write(tmp.path().join("a.nir"), a.to_text())?;
+    let mut b = Module::new();
+    b.push(structure_port_v1("bus", 0, 1));
+    std::fs::write(tmp.path().join("b.nir"), b.to_text())?;
+
+    Command::cargo_bin("snn")?
+        .current_dir(tmp.path())
+        .args(["nir", "link", "a.nir", "b.nir", "-o", "out.nir"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("port 'bus' has 2 neurons here but 4 in a.nir"));
+    Ok(())
+}
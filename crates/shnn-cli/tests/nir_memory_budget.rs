@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1};
+
+/// 200 x 200 fully connected layer: well over 1 MiB once expanded and lowered
+fn write_model(path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 199, 200, 399, 0.1, 1.0));
+    module.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(1)));
+    std::fs::write(path, module.to_text())?;
+    Ok(())
+}
+
+fn nir_run(model: &Path, extra: &[&str]) -> Result<assert_cmd::assert::Assert, Box<dyn Error>> {
+    Ok(Command::cargo_bin("snn")?
+        .env_remove("SNN_MEMORY_BUDGET_MB")
+        .args(["nir", "run", model.to_str().unwrap()])
+        .args(extra)
+        .assert())
+}
+
+#[test]
+fn nir_run_reports_estimate_and_peak_rss() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    write_model(&model)?;
+
+    let assert = nir_run(&model, &[])?
+        .success()
+        .stdout(predicate::str::contains("Estimated peak memory"));
+    if cfg!(target_os = "linux") {
+        assert.stdout(predicate::str::contains("peak RSS"));
+    }
+    Ok(())
+}
+
+#[test]
+fn nir_run_refuses_over_budget_unless_forced() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    write_model(&model)?;
+
+    nir_run(&model, &["--memory-budget-mb", "1"])?
+        .failure()
+        .stdout(predicate::str::contains("exceeds the 1 MiB budget"))
+        .stdout(predicate::str::contains("Simulation completed").not());
+
+    Command::cargo_bin("snn")?
+        .env("SNN_MEMORY_BUDGET_MB", "1")
+        .args(["nir", "run", model.to_str().unwrap()])
+        .assert()
+        .failure();
+
+    nir_run(&model, &["--memory-budget-mb", "1", "--force"])?
+        .success()
+        .stdout(predicate::str::contains("continuing because of --force"));
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+
+use shnn_ir::{
+    Module, layer_fully_connected_v1, stimulus_ou_noise_v1,
+    runtime_monitor_rate_v1, runtime_simulate_run_v1,
+};
+
+#[test]
+fn nir_run_exports_population_rates() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 2, 0.0, 1.0));
+    module.push(stimulus_ou_noise_v1(0, 1, 5.0, 0.0, 5.0, None));
+    module.push(runtime_monitor_rate_v1(0, 1, 5.0));
+    module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(1)));
+    let model = tmp.path().join("m.nirt");
+    std::fs::write(&model, module.to_text())?;
+
+    let out = tmp.path().join("results.json");
+    let csv = tmp.path().join("rates.csv");
+    Command::cargo_bin("snn")?
+        .args([
+            "nir", "run", model.to_str().unwrap(),
+            "-o", out.to_str().unwrap(),
+            "--rates-csv", csv.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&out)?)?;
+    let rates = json["results"]["rates"].as_array().unwrap();
+    assert_eq!(rates.len(), 1);
+    assert_eq!(rates[0]["bin_ns"].as_u64(), Some(5_000_000));
+    assert_eq!(rates[0]["rates_hz"].as_array().unwrap().len(), 4);
+    assert!(rates[0]["mean_hz"].as_f64().unwrap() > 0.0);
+
+    let text = std::fs::read_to_string(&csv)?;
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("monitor,neuron_start,neuron_end,bin_start_ns,rate_hz"));
+    assert_eq!(lines.count(), 4);
+    assert!(text.contains("\n0,0,1,15000000,"));
+    Ok(())
+}
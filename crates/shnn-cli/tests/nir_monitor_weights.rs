@@ -0,0 +1,59 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{
+    Module, stdp_rule_v1, layer_fully_connected_v1, stimulus_ou_noise_v1,
+    runtime_monitor_weights_pairs_v1, runtime_simulate_run_v1,
+};
+use shnn_runtime::{NeuronId, WeightTrace};
+
+fn write_model(path: &std::path::Path, monitor: bool) -> Result<(), Box<dyn Error>> {
+    let mut module = Module::new();
+    module.push(stdp_rule_v1(0.05, 0.02, 20.0, 20.0, 0.0, 1.0));
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+    module.push(stimulus_ou_noise_v1(0, 3, 8.0, 2.0, 5.0, None));
+    if monitor {
+        module.push(runtime_monitor_weights_pairs_v1(&[(0, 2), (1, 3)], 5.0));
+    }
+    module.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(2)));
+    std::fs::write(path, module.to_text())?;
+    Ok(())
+}
+
+#[test]
+fn nir_run_writes_weight_trace() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    write_model(&model, true)?;
+
+    let trace_path = tmp.path().join("weights.wtrc");
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--weights-trace", trace_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let trace = WeightTrace::from_bytes(&std::fs::read(&trace_path)?)?;
+    assert_eq!(trace.synapses, vec![(NeuronId::new(0), NeuronId::new(2)), (NeuronId::new(1), NeuronId::new(3))]);
+    assert_eq!(trace.interval_ns, 5_000_000);
+    assert_eq!(trace.times_ns.len(), 11);
+    assert_eq!(trace.weight(0, 0), Some(0.5));
+    Ok(())
+}
+
+#[test]
+fn nir_run_weight_trace_requires_monitor() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    write_model(&model, false)?;
+
+    let trace_path = tmp.path().join("weights.wtrc");
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--weights-trace", trace_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("monitor_weights"));
+    assert!(!trace_path.exists());
+    Ok(())
+}
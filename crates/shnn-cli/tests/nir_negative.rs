@@ -77,4 +77,26 @@ fn nir_run_invalid_spikes_format_flag_fails_fast() -> Result<(), Box<dyn Error>>
         // clap typically prints possible values in the error message; ensure we see our valid ones
         .stderr(predicate::str::contains("json").and(predicate::str::contains("vevt")));
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn nir_run_duplicate_synapse_reports_build_code() -> Result<(), Box<dyn Error>> {
+    use shnn_ir::{runtime_simulate_run_v1, synapse_connect_v1, Module};
+
+    let tmp = tempdir()?;
+    let model = tmp.path().join("dup.nirt");
+    let mut module = Module::new();
+    module.push(synapse_connect_v1(0, 1, 0.5, 1.0));
+    module.push(synapse_connect_v1(0, 1, 0.5, 1.0));
+    module.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(1)));
+    std::fs::write(&model, module.to_text())?;
+
+    let assert = Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap()])
+        .assert()
+        .failure();
+    let output = assert.get_output();
+    let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    assert!(text.contains("[B004]"), "missing build code in:\n{}", text);
+    assert!(text.contains("hint: remove the repeated connection"), "missing hint in:\n{}", text);
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, parse_text, lif_neuron_v1, layer_fully_connected_v1, stimulus_poisson_v1, runtime_simulate_run_v1};
+
+#[test]
+fn nir_quantize_reports_divergence_and_writes_module() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let quantized = tmp.path().join("model.q.nirt");
+    let report = tmp.path().join("report.json");
+
+    let mut module = Module::new();
+    module.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.1, 1.0));
+    module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 50.0));
+    module.push(runtime_simulate_run_v1(1.0, 50.0, false, Some(1)));
+    std::fs::write(&model, module.to_text())?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "quantize", model.to_str().unwrap(), "--format", "q15-16", "-o"])
+        .arg(&quantized)
+        .arg("--report")
+        .arg(&report)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("spike-time jitter"));
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    assert_eq!(json["format"], "Q15.16");
+    assert!(json["float_spikes"].as_u64().unwrap() > 0);
+    let max_weight_error = json["max_weight_error"].as_f64().unwrap();
+    assert!(max_weight_error > 0.0 && max_weight_error <= 0.5 / 65536.0);
+
+    // The quantized module still parses and runs on the float engine
+    parse_text(&std::fs::read_to_string(&quantized)?)?;
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", quantized.to_str().unwrap()])
+        .assert()
+        .success();
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::error::Error;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, stimulus_poisson_v1, runtime_simulate_run_v1};
+
+#[test]
+fn nir_run_realtime_paces_and_reports_drift() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+    module.push(stimulus_poisson_v1(0, 100.0, 300.0, 0.0, 50.0));
+    // 50 steps of 1 ms
+    module.push(runtime_simulate_run_v1(1.0, 50.0, false, Some(1)));
+    std::fs::write(&model, module.to_text())?;
+
+    let begin = Instant::now();
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--realtime"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Realtime: 50 steps").and(predicate::str::contains("final drift")));
+    assert!(begin.elapsed() >= Duration::from_millis(50));
+
+    // Without the flag the run is not paced and no drift report is printed
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Realtime:").not());
+    Ok(())
+}
+
+#[test]
+fn nir_compile_realtime_sets_run_attribute() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let out = tmp.path().join("rt.nirt");
+    Command::cargo_bin("snn")?
+        .args(["nir", "compile", "--output", out.to_str().unwrap(), "--steps", "10", "--realtime"])
+        .assert()
+        .success();
+    assert!(std::fs::read_to_string(&out)?.contains("realtime = true"));
+    Ok(())
+}
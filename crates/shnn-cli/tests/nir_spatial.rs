@@ -0,0 +1,54 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{
+    AttributeValue, Module, distance_gaussian_v1, runtime_simulate_run_v1,
+    stimulus_ou_noise_v1, structure_positions_coords_v1, structure_positions_grid_v1,
+};
+
+#[test]
+fn nir_run_exports_positions() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let mut module = Module::new();
+    module.push(structure_positions_grid_v1(0, [4, 4, 1], 10.0));
+    module.push(
+        distance_gaussian_v1((0, 15), (0, 15), 10.0, 0.8, 1.0, 1.0, Some(5))
+            .with_attr("weight_profile", AttributeValue::String("gaussian".into())),
+    );
+    module.push(stimulus_ou_noise_v1(0, 3, 5.0, 0.0, 5.0, None));
+    module.push(runtime_simulate_run_v1(0.1, 10.0, false, Some(1)));
+    let model = tmp.path().join("m.nirt");
+    std::fs::write(&model, module.to_text())?;
+
+    let out = tmp.path().join("results.json");
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "-o", out.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&out)?)?;
+    let positions = json["results"]["positions"].as_array().unwrap();
+    assert_eq!(positions.len(), 16);
+    assert_eq!(positions[5]["neuron_id"].as_u64(), Some(5));
+    assert_eq!((positions[5]["x"].as_f64(), positions[5]["y"].as_f64()), (Some(10.0), Some(10.0)));
+    Ok(())
+}
+
+#[test]
+fn nir_verify_rejects_unplaced_neurons() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let mut module = Module::new();
+    module.push(structure_positions_coords_v1(0, &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]]));
+    module.push(distance_gaussian_v1((0, 2), (0, 1), 1.0, 1.0, 1.0, 1.0, None));
+    let model = tmp.path().join("m.nirt");
+    std::fs::write(&model, module.to_text())?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", model.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("neuron 2 has no structure.positions@v1 entry"));
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::error::Error;
+use std::net::UdpSocket;
+use std::time::Duration;
+use tempfile::tempdir;
+use assert_cmd::Command;
+
+use shnn_ir::{Module, layer_fully_connected_v1, stimulus_poisson_v1, runtime_simulate_run_v1};
+use shnn_runtime::stream::decode_frame;
+
+#[test]
+fn nir_run_publishes_spikes_over_udp() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let results = tmp.path().join("results.json");
+
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+    module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 50.0));
+    module.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(1)));
+    std::fs::write(&model, module.to_text())?;
+
+    let receiver = UdpSocket::bind("127.0.0.1:0")?;
+    receiver.set_read_timeout(Some(Duration::from_millis(200)))?;
+    let endpoint = format!("udp://{}", receiver.local_addr()?);
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--spike-publish", &endpoint, "-o"])
+        .arg(&results)
+        .assert()
+        .success();
+
+    let mut streamed = 0;
+    let mut buffer = vec![0u8; 65_536];
+    while let Ok(len) = receiver.recv(&mut buffer) {
+        streamed += decode_frame(&buffer[..len])?.len();
+    }
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&results)?)?;
+    let recorded = json["results"]["spike_count"].as_u64().unwrap() as usize;
+    assert!(recorded > 0);
+    assert_eq!(streamed, recorded);
+    Ok(())
+}
+
+#[test]
+fn nir_run_rejects_bad_endpoint() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 0, 1, 1, 0.5, 1.0));
+    module.push(runtime_simulate_run_v1(0.1, 1.0, false, None));
+    std::fs::write(&model, module.to_text())?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--spike-input", "http://127.0.0.1:1"])
+        .assert()
+        .failure();
+    Ok(())
+}
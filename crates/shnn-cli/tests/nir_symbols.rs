@@ -0,0 +1,94 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{
+    Module, layer_fully_connected_v1, lif_neuron_v1, runtime_simulate_run_v1, stimulus_poisson_v1,
+    structure_population_v1, structure_port_v1, AttributeValue, SymbolIndex,
+};
+
+/// Excitatory and inhibitory populations wired by name only
+fn named_module() -> Module {
+    let mut module = Module::new();
+    module.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+    module.push(structure_population_v1("exc", 4));
+    module.push(structure_population_v1("inh", 2));
+    module.push(layer_fully_connected_v1(0, 0, 0, 0, 2.0, 1.0)
+        .with_attr("in", AttributeValue::symbol("exc", SymbolIndex::All))
+        .with_attr("out", AttributeValue::symbol("inh", SymbolIndex::All)));
+    module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 50.0)
+        .with_attr("neuron", AttributeValue::symbol("exc", SymbolIndex::One(0))));
+    module.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(1)));
+    module
+}
+
+#[test]
+fn nir_run_resolves_population_symbols() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("named.nir");
+    let text = named_module().to_text();
+    assert!(text.contains("in = %exc, out = %inh"));
+    std::fs::write(&model, text)?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", model.to_str().unwrap()])
+        .assert()
+        .success();
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap()])
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn nir_verify_rejects_unknown_and_out_of_range_symbols() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("bad.nir");
+    let text = named_module().to_text();
+
+    std::fs::write(&model, text.replace("neuron = %exc[0]", "neuron = %exc[4]"))?;
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", model.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("index 4 is outside population 'exc' of 4 neurons"));
+
+    std::fs::write(&model, text.replace("out = %inh", "out = %inhib"))?;
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", model.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("unknown population 'inhib'"));
+    Ok(())
+}
+
+#[test]
+fn nir_link_namespaces_populations() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let mut sensory = named_module();
+    sensory.push(structure_port_v1("features", 0, 0)
+        .with_attr("neurons", AttributeValue::symbol("inh", SymbolIndex::All)));
+    std::fs::write(tmp.path().join("sensory.nir"), sensory.to_text())?;
+
+    let mut readout = Module::new();
+    readout.push(structure_population_v1("in", 2));
+    readout.push(structure_population_v1("exc", 3));
+    readout.push(structure_port_v1("features", 0, 0)
+        .with_attr("neurons", AttributeValue::symbol("in", SymbolIndex::All)));
+    readout.push(layer_fully_connected_v1(0, 0, 0, 0, 1.0, 1.0)
+        .with_attr("in", AttributeValue::symbol("in", SymbolIndex::All))
+        .with_attr("out", AttributeValue::symbol("exc", SymbolIndex::All)));
+    std::fs::write(tmp.path().join("readout.nir"), readout.to_text())?;
+
+    Command::cargo_bin("snn")?
+        .current_dir(tmp.path())
+        .args(["nir", "link", "sensory.nir", "readout.nir", "-o", "linked.nir"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(9 neurons,"))
+        .stdout(predicate::str::contains("populations sensory.exc=0..3, sensory.inh=4..5"))
+        .stdout(predicate::str::contains("populations readout.in=4..5, readout.exc=6..8"));
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1, stimulus_poisson_v1};
+
+/// Model whose stimulus rate and weight are `${rate}` and `${weight:-0.5}`
+fn write_template(path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+    module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 20.0));
+    module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(3)));
+    let text = module.to_text();
+    let template = text.replace("rate = 200 Hz", "rate = ${rate} Hz").replace("weight = 0.5", "weight = ${weight:-0.5}");
+    assert_ne!(template, text);
+    std::fs::write(path, template)?;
+    Ok(())
+}
+
+#[test]
+fn nir_run_records_template_bindings() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    write_template(&model)?;
+    Command::cargo_bin("snn")?
+        .env("XDG_CONFIG_HOME", tmp.path().join("xdg"))
+        .args(["-w", tmp.path().to_str().unwrap(), "nir", "run", model.to_str().unwrap(), "--record"])
+        .args(["--set", "rate=150", "--set", "rate=250"])
+        .assert()
+        .success();
+
+    let runs = tmp.path().join("results/runs");
+    let run_dir = std::fs::read_dir(&runs)?.next().expect("one recorded run")?.path();
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(run_dir.join("manifest.json"))?)?;
+    // The last --set wins and defaults are recorded as used
+    assert_eq!(manifest["bindings"]["rate"], "250");
+    assert_eq!(manifest["bindings"]["weight"], "0.5");
+    Ok(())
+}
+
+#[test]
+fn nir_verify_requires_bound_placeholders() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    write_template(&model)?;
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", model.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("unbound variable 'rate'"));
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", model.to_str().unwrap(), "--set", "rate=40"])
+        .assert()
+        .success();
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{
+    Module, layer_fully_connected_v1, stimulus_ou_noise_v1,
+    test_expect_rate_v1, test_expect_spike_count_v1, runtime_simulate_run_v1,
+};
+
+fn write_model(path: &std::path::Path, max_driven_spikes: i64) -> Result<(), Box<dyn Error>> {
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 2, 0.0, 1.0));
+    module.push(stimulus_ou_noise_v1(0, 1, 5.0, 0.0, 5.0, None));
+    module.push(test_expect_rate_v1(0, 1, Some(1.0), None));
+    module.push(test_expect_spike_count_v1(2, 2, None, Some(0)));
+    module.push(test_expect_spike_count_v1(0, 1, None, Some(max_driven_spikes)));
+    module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(1)));
+    std::fs::write(path, module.to_text())?;
+    Ok(())
+}
+
+#[test]
+fn nir_run_passing_expectations() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    write_model(&model, 1_000)?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "verify", model.to_str().unwrap()])
+        .assert()
+        .success();
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PASS rate[0..1] in [1, -] Hz"))
+        .stdout(predicate::str::contains("FAIL").not());
+    Ok(())
+}
+
+#[test]
+fn nir_run_failing_expectation_exits_nonzero() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    write_model(&model, 0)?;
+
+    let out = tmp.path().join("spikes.json");
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "-o", out.to_str().unwrap()])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("FAIL spike_count[0..1] in [-, 0]"))
+        .stdout(predicate::str::contains("1 expectation(s) failed"));
+    // Results are still written so the failure can be inspected
+    assert!(out.exists());
+    Ok(())
+}
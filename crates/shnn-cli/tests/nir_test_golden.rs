@@ -0,0 +1,75 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{
+    Module, layer_fully_connected_v1, stimulus_ou_noise_v1,
+    runtime_monitor_rate_v1, runtime_simulate_run_v1,
+};
+
+fn write_model(path: &Path, mu: f32) -> Result<(), Box<dyn Error>> {
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 2, 0.5, 1.0));
+    module.push(stimulus_ou_noise_v1(0, 1, mu, 1.0, 5.0, None));
+    module.push(runtime_monitor_rate_v1(0, 2, 5.0));
+    module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(3)));
+    std::fs::write(path, module.to_text())?;
+    Ok(())
+}
+
+fn nir_test(dir: &Path, extra: &[&str]) -> Result<assert_cmd::assert::Assert, Box<dyn Error>> {
+    let mut args = vec!["nir", "test", dir.to_str().unwrap()];
+    args.extend_from_slice(extra);
+    Ok(Command::cargo_bin("snn")?.args(args).assert())
+}
+
+#[test]
+fn nir_test_update_then_pass() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    write_model(&tmp.path().join("a.nirt"), 5.0)?;
+    write_model(&tmp.path().join("b.nir"), 8.0)?;
+
+    nir_test(tmp.path(), &[])?
+        .code(2)
+        .stdout(predicate::str::contains("missing golden file"));
+
+    nir_test(tmp.path(), &["--update"])?
+        .success()
+        .stdout(predicate::str::contains("UPDATED"));
+    assert!(tmp.path().join("a.golden.json").exists());
+    assert!(tmp.path().join("b.golden.json").exists());
+
+    nir_test(tmp.path(), &[])?
+        .success()
+        .stdout(predicate::str::contains("a.nirt"))
+        .stdout(predicate::str::contains("2 passed, 0 failed"));
+    Ok(())
+}
+
+#[test]
+fn nir_test_detects_changed_results() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    write_model(&model, 5.0)?;
+    nir_test(tmp.path(), &["--update"])?.success();
+
+    // A different spike stream with the same spike count fails exact mode only
+    let golden_path = tmp.path().join("m.golden.json");
+    let mut golden: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&golden_path)?)?;
+    golden["spike_hash"] = "0000000000000000".into();
+    std::fs::write(&golden_path, golden.to_string())?;
+    nir_test(tmp.path(), &[])?
+        .code(2)
+        .stdout(predicate::str::contains("FAIL"))
+        .stdout(predicate::str::contains("spike_hash"));
+    nir_test(tmp.path(), &["--tolerance", "metrics"])?.success();
+
+    // A changed engine input shifts the metrics beyond tolerance
+    write_model(&model, 9.0)?;
+    nir_test(tmp.path(), &["--tolerance", "metrics", "--rel-tol", "0.01"])?
+        .code(2)
+        .stdout(predicate::str::contains("1 failed"));
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, stimulus_poisson_v1, runtime_simulate_run_v1};
+
+#[test]
+fn nir_run_timings_and_trace_events() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let trace = tmp.path().join("trace/events.json");
+
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+    module.push(stimulus_poisson_v1(0, 100.0, 300.0, 0.0, 5.0));
+    module.push(runtime_simulate_run_v1(0.1, 5.0, false, Some(1)));
+    std::fs::write(&model, module.to_text())?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap(), "--timings", "--trace-events"])
+        .arg(&trace)
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("PHASE")
+                .and(predicate::str::contains("lowering"))
+                .and(predicate::str::contains("  network"))
+        );
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&trace)?)?;
+    let events = json["traceEvents"].as_array().unwrap();
+    let names: Vec<&str> = events.iter().map(|e| e["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["io", "parse", "verify", "passes", "lowering", "stepping", "io"]);
+    for event in events {
+        assert_eq!(event["ph"], "X");
+        assert!(event["dur"].as_f64().unwrap() >= 0.0);
+    }
+    let stepping = &events[5]["args"];
+    for phase in ["stimuli_ms", "network_ms", "monitors_ms", "recording_ms"] {
+        assert!(stepping[phase].is_number(), "missing {}", phase);
+    }
+    Ok(())
+}
+
+#[test]
+fn nir_run_without_timings_prints_no_breakdown() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 0, 1, 1, 0.5, 1.0));
+    module.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(1)));
+    std::fs::write(&model, module.to_text())?;
+
+    Command::cargo_bin("snn")?
+        .args(["nir", "run", model.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PHASE").not());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+
+use shnn_storage::vcsr::VCSRSnapshot;
+
+fn nir_compile_args(out: &str) -> Vec<String> {
+    [
+        "nir", "compile",
+        "-o", out,
+        "--inputs", "4",
+        "--hidden", "4",
+        "--outputs", "2",
+        "--steps", "100",
+        "--dt-us", "100",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+#[test]
+fn nir_compile_emit_vcsr() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let out = tmp.path().join("net.vcsr");
+
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.args(nir_compile_args(out.to_str().unwrap()));
+    cmd.args(["--emit", "vcsr"]);
+    cmd.assert().success();
+
+    let snapshot = VCSRSnapshot::from_bytes(&std::fs::read(&out)?)?;
+    assert_eq!(snapshot.header.num_vertices, 10);
+    // 4x4 input->hidden + 4x2 hidden->output
+    assert_eq!(snapshot.header.num_incidences, 24);
+    assert_eq!(snapshot.header.generation, 0);
+    Ok(())
+}
+
+#[test]
+fn nir_run_snapshot_weights() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    let weights = tmp.path().join("final.vcsr");
+
+    let mut compile = Command::cargo_bin("snn")?;
+    compile.args(nir_compile_args(model.to_str().unwrap()));
+    compile.assert().success();
+
+    let mut run = Command::cargo_bin("snn")?;
+    run.args([
+        "nir", "run",
+        model.to_str().unwrap(),
+        "--snapshot-weights", weights.to_str().unwrap(),
+    ]);
+    run.assert().success();
+
+    let snapshot = VCSRSnapshot::from_bytes(&std::fs::read(&weights)?)?;
+    assert_eq!(snapshot.header.num_incidences, 24);
+    assert_eq!(snapshot.header.generation, 1);
+    assert!(snapshot.weights.iter().all(|w| (0.0..=1.0).contains(w)));
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, lif_neuron_v1, runtime_simulate_run_v1, stimulus_poisson_v1, synapse_connect_v1};
+
+#[test]
+fn nir_verify_reports_warnings_and_promotes_with_w_error() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let mut module = Module::new();
+    // 0.05 ms delay under a 0.1 ms step, and a stimulus that never fires
+    module.push(synapse_connect_v1(0, 1, 0.5, 0.05));
+    module.push(stimulus_poisson_v1(0, 0.0, 1.0, 0.0, 10.0));
+    module.push(runtime_simulate_run_v1(0.1, 10.0, false, None));
+    std::fs::write(&model, module.to_text())?;
+    let model = model.to_str().unwrap();
+
+    Command::cargo_bin("snn")?
+        .env_remove("RUST_LOG")
+        .args(["nir", "verify", model])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("warning[N101]"))
+        .stdout(predicate::str::contains("warning[N102]"))
+        .stdout(predicate::str::contains("Verification OK"));
+
+    Command::cargo_bin("snn")?
+        .env_remove("RUST_LOG")
+        .args(["nir", "verify", model, "-W", "allow"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("warning[").not());
+
+    Command::cargo_bin("snn")?
+        .env_remove("RUST_LOG")
+        .args(["nir", "run", model, "-W", "error"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 warning(s) treated as errors (-W error)"));
+    Ok(())
+}
+
+#[test]
+fn nir_run_auto_dt_resolves_fast_time_constant() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let mut module = Module::new();
+    // tau_m = 2 ms under a 1 ms Euler step
+    module.push(lif_neuron_v1(2.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+    module.push(synapse_connect_v1(0, 1, 0.5, 1.0));
+    module.push(runtime_simulate_run_v1(1.0, 10.0, false, None));
+    std::fs::write(&model, module.to_text())?;
+    let model = model.to_str().unwrap();
+
+    Command::cargo_bin("snn")?
+        .env_remove("RUST_LOG")
+        .args(["nir", "verify", model])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("warning[N106]"))
+        .stdout(predicate::str::contains("suggested dt = 0.2 ms"));
+
+    Command::cargo_bin("snn")?
+        .env_remove("RUST_LOG")
+        .current_dir(tmp.path())
+        .args(["nir", "run", model, "-W", "error", "--auto-dt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dt lowered from 1 ms to 0.2 ms"));
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1, stimulus_poisson_v1};
+
+fn write_model(path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+    module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 20.0));
+    module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(3)));
+    std::fs::write(path, module.to_text())?;
+    Ok(())
+}
+
+fn trial(out: &Path, stem: &str) -> Result<serde_json::Value, Box<dyn Error>> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(out.join(format!("{}.json", stem)))?)?)
+}
+
+#[test]
+fn study_trials_get_distinct_reproducible_seeds() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    write_model(&model)?;
+    let config = tmp.path().join("study.toml");
+    std::fs::write(&config, format!(
+        "[study]\nname = \"seeded\"\nseed = 11\nout_dir = \"{out}\"\n\n[[runs]]\nnir = \"{model}\"\nrepeats = 2\n\n[[runs]]\nnir = \"{model}\"\nseed = 5\n",
+        out = tmp.path().join("out").display(),
+        model = model.display(),
+    ))?;
+
+    Command::cargo_bin("snn")?.args(["study", config.to_str().unwrap()]).assert().success();
+    let out = tmp.path().join("out");
+    let seeds: Vec<u64> = ["run1_rep1", "run1_rep2", "run2_rep1"]
+        .iter()
+        .map(|stem| Ok(trial(&out, stem)?["seed"].as_u64().expect("seeded trial")))
+        .collect::<Result<_, Box<dyn Error>>>()?;
+    assert_ne!(seeds[0], seeds[1]);
+    assert_ne!(seeds[0], seeds[2]);
+    let first = trial(&out, "run1_rep1")?;
+    assert!(first["seeds"]["stimulus#0"].is_u64());
+
+    // Same config, same seeds
+    Command::cargo_bin("snn")?.args(["study", config.to_str().unwrap()]).assert().success();
+    assert_eq!(trial(&out, "run1_rep1")?["seed"].as_u64(), Some(seeds[0]));
+    assert_eq!(trial(&out, "run1_rep1")?["spike_count"], first["spike_count"]);
+    Ok(())
+}
+
+#[test]
+fn recorded_run_manifest_lists_component_seeds() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    write_model(&model)?;
+    Command::cargo_bin("snn")?
+        .env("XDG_CONFIG_HOME", tmp.path().join("xdg"))
+        .args(["-w", tmp.path().to_str().unwrap(), "nir", "run", model.to_str().unwrap(), "--record"])
+        .assert()
+        .success();
+
+    let runs = tmp.path().join("results/runs");
+    let run_dir = std::fs::read_dir(&runs)?.next().expect("one recorded run")?.path();
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(run_dir.join("manifest.json"))?)?;
+    assert_eq!(manifest["seed"], 3);
+    assert!(manifest["seeds"]["stimulus#0"].is_u64());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+
+fn write_records(path: &std::path::Path, records: &[(u32, u32, f32)]) -> Result<(), Box<dyn Error>> {
+    let json: Vec<_> = records
+        .iter()
+        .map(|(pre, post, weight)| serde_json::json!({ "pre": pre, "post": post, "weight": weight }))
+        .collect();
+    std::fs::write(path, serde_json::to_string(&json)?)?;
+    Ok(())
+}
+
+#[test]
+fn snapshot_diff_json_report() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let before = tmp.path().join("before.json");
+    let after = tmp.path().join("after.json");
+    let report = tmp.path().join("diff.json");
+
+    write_records(&before, &[(0, 1, 0.5), (0, 2, 0.5), (1, 2, 0.5)])?;
+    write_records(&after, &[(0, 1, 0.75), (0, 2, 0.5), (2, 0, 0.1)])?;
+
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.args([
+        "snapshot", "diff",
+        before.to_str().unwrap(),
+        after.to_str().unwrap(),
+        "--top-k", "5",
+        "--json", report.to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    assert_eq!(value["common_edges"], 2);
+    assert_eq!(value["changed_edges"], 1);
+    assert_eq!(value["added"].as_array().unwrap().len(), 1);
+    assert_eq!(value["removed"].as_array().unwrap().len(), 1);
+    assert_eq!(value["top_changes"][0]["pre"], 0);
+    assert_eq!(value["top_changes"][0]["post"], 1);
+    assert!((value["mean_abs_delta"].as_f64().unwrap() - 0.125).abs() < 1e-6);
+    Ok(())
+}
+
+#[test]
+fn snapshot_diff_vcsr_against_itself() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let net = tmp.path().join("net.vcsr");
+
+    let mut compile = Command::cargo_bin("snn")?;
+    compile.args([
+        "nir", "compile",
+        "-o", net.to_str().unwrap(),
+        "--inputs", "2", "--hidden", "2", "--outputs", "1",
+        "--emit", "vcsr",
+    ]);
+    compile.assert().success();
+
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.args(["snapshot", "diff", net.to_str().unwrap(), net.to_str().unwrap()]);
+    cmd.assert().success();
+    Ok(())
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "index")]
+
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1, stimulus_poisson_v1};
+
+fn snn(workspace: &Path) -> Result<Command, Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.env("XDG_CONFIG_HOME", workspace.join("xdg"))
+        .env_remove("RUST_LOG")
+        .args(["-w", workspace.to_str().unwrap()]);
+    Ok(cmd)
+}
+
+fn query(workspace: &Path, filter: &str) -> Result<Vec<serde_json::Value>, Box<dyn Error>> {
+    let report = workspace.join("query.json");
+    snn(workspace)?
+        .args(["study", "query", filter, "--json", report.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("match(es)"));
+    Ok(serde_json::from_str(&std::fs::read_to_string(&report)?)?)
+}
+
+#[test]
+fn study_trials_and_recorded_runs_are_queryable() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("model.nirt");
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+    module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 20.0));
+    module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(3)));
+    std::fs::write(&model, module.to_text())?;
+
+    let config = tmp.path().join("study.toml");
+    std::fs::write(&config, format!(
+        "[study]\nname = \"sweep\"\nout_dir = \"{out}\"\n\n\
+         [[runs]]\nnir = \"{model}\"\nrepeats = 2\nparams = {{ weight = 0.1, label = \"low\" }}\n\n\
+         [[runs]]\nnir = \"{model}\"\nparams = {{ weight = 0.5, label = \"high\" }}\n",
+        out = tmp.path().join("out").display(),
+        model = model.display(),
+    ))?;
+    snn(tmp.path())?.args(["study", config.to_str().unwrap()]).assert().success();
+    snn(tmp.path())?.args(["nir", "run", model.to_str().unwrap(), "--record"]).assert().success();
+    assert!(tmp.path().join("results/index.sqlite").exists());
+
+    let low = query(tmp.path(), "params.weight < 0.2 AND metric.spike_count >= 0")?;
+    let keys: Vec<&str> = low.iter().map(|e| e["key"].as_str().unwrap()).collect();
+    assert_eq!(keys, ["sweep/run1_rep1", "sweep/run1_rep2"]);
+    assert_eq!(low[0]["params"]["label"], "low");
+    assert!(low[0]["artifacts"]["trial"].as_str().unwrap().ends_with("run1_rep1.json"));
+
+    let either = query(tmp.path(), "params.label = 'high' OR params.weight < 0.2")?;
+    assert_eq!(either.len(), 3);
+
+    let runs = query(tmp.path(), "metric.neurons > 0")?;
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0]["kind"], "run");
+
+    // Re-running a study replaces its trials instead of duplicating them
+    snn(tmp.path())?.args(["study", config.to_str().unwrap()]).assert().success();
+    assert_eq!(query(tmp.path(), "metric.steps_executed >= 0")?.len(), 4);
+
+    snn(tmp.path())?
+        .args(["study", "query", "metric.rate >"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("query: expected a value"));
+    Ok(())
+}
@@ -0,0 +1,114 @@
+#![cfg(unix)]
+
+use std::error::Error;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1, stimulus_poisson_v1};
+
+/// Stand-in for ssh: drops the host argument and runs the remote script locally
+fn write_fake_ssh(path: &Path, fail: bool) -> Result<(), Box<dyn Error>> {
+    let body = if fail { "#!/bin/sh\necho unreachable >&2\nexit 255\n" } else { "#!/bin/sh\nshift\nexec sh -c \"$1\"\n" };
+    std::fs::write(path, body)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+fn write_study(dir: &Path, workers: &str) -> Result<std::path::PathBuf, Box<dyn Error>> {
+    let model = dir.join("model.nirt");
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+    module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 20.0));
+    module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(3)));
+    std::fs::write(&model, module.to_text())?;
+
+    let config = dir.join("study.toml");
+    std::fs::write(&config, format!(
+        "[study]\nname = \"sweep\"\nout_dir = \"{out}\"\n\n[[runs]]\nnir = \"{model}\"\nrepeats = 3\n\n{workers}",
+        out = dir.join("out").display(),
+        model = model.display(),
+    ))?;
+    Ok(config)
+}
+
+fn ssh_worker(host: &str, ssh: &Path, work_dir: &Path) -> String {
+    format!(
+        "[[workers]]\nkind = \"ssh\"\nhost = \"{host}\"\nssh = \"{}\"\nsnn = \"{}\"\nwork_dir = \"{}\"\n\n",
+        ssh.display(),
+        env!("CARGO_BIN_EXE_snn"),
+        work_dir.display(),
+    )
+}
+
+#[test]
+fn study_dispatches_trials_to_workers_and_merges_manifest() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let ssh = tmp.path().join("fake-ssh");
+    write_fake_ssh(&ssh, false)?;
+    let workers = ssh_worker("node1", &ssh, &tmp.path().join("node1")) + &ssh_worker("node2", &ssh, &tmp.path().join("node2"));
+    let config = write_study(tmp.path(), &workers)?;
+
+    Command::cargo_bin("snn")?.args(["study", config.to_str().unwrap()]).assert().success();
+
+    let out = tmp.path().join("out");
+    let summary: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(out.join("summary.json"))?)?;
+    assert_eq!(summary["workers"].as_array().unwrap().len(), 2);
+    let runs = summary["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 3);
+    for (i, run) in runs.iter().enumerate() {
+        assert_eq!(run["repeat_index"], i as u64 + 1);
+        assert_eq!(run["steps_executed"], 200);
+        assert!(run["worker"].as_str().unwrap().starts_with("ssh://node"));
+        let artifact = out.join(run["artifact"].as_str().unwrap());
+        let results: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(artifact)?)?;
+        assert_eq!(results["results"]["spike_count"], run["spike_count"]);
+    }
+    Ok(())
+}
+
+#[test]
+fn study_requeues_trials_from_failed_worker() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let (good, bad) = (tmp.path().join("ssh-ok"), tmp.path().join("ssh-down"));
+    write_fake_ssh(&good, false)?;
+    write_fake_ssh(&bad, true)?;
+    let workers = ssh_worker("down", &bad, tmp.path()) + &ssh_worker("up", &good, tmp.path());
+    let config = write_study(tmp.path(), &workers)?;
+
+    Command::cargo_bin("snn")?.args(["study", config.to_str().unwrap()]).assert().success();
+
+    let summary: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(tmp.path().join("out/summary.json"))?)?;
+    let runs = summary["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 3);
+    assert!(runs.iter().all(|run| run["worker"] == "ssh://up"));
+    Ok(())
+}
+
+#[test]
+fn study_fails_when_every_worker_fails() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let bad = tmp.path().join("ssh-down");
+    write_fake_ssh(&bad, true)?;
+    let config = write_study(tmp.path(), &ssh_worker("down", &bad, tmp.path()))?;
+
+    Command::cargo_bin("snn")?
+        .args(["study", config.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("every worker failed"));
+    Ok(())
+}
+
+#[test]
+fn study_rejects_bad_worker_url() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let config = write_study(tmp.path(), "")?;
+    Command::cargo_bin("snn")?
+        .args(["study", config.to_str().unwrap(), "--worker", "node3"])
+        .assert()
+        .failure();
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn train_reservoir_fits_readout() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let nir = tmp.path().join("lsm.nir");
+    let output = tmp.path().join("lsm.json");
+    Command::cargo_bin("snn")?
+        .args(["train", "--topology", "reservoir", "--plasticity", "none"])
+        .args(["--inputs", "10", "--hidden", "100", "--outputs", "40", "--steps", "20000", "--seed", "1"])
+        .args(["--emit-nir", nir.to_str().unwrap(), "--output", output.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Readout: 40 state features -> 10 targets"));
+
+    let text = std::fs::read_to_string(&nir)?;
+    assert!(text.contains("structure.reservoir@v1"));
+    assert_eq!(text.matches("runtime.readout@v1").count(), 2);
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&output)?)?;
+    let readout = &report["readout"];
+    // 2 s sampled every 5 ms, split 80/20
+    assert_eq!(readout["samples"], 400);
+    assert_eq!(readout["train_samples"], 320);
+    assert_eq!(readout["weights"].as_array().unwrap().len(), 10);
+    assert_eq!(readout["weights"][0].as_array().unwrap().len(), 41);
+    let test_nrmse = readout["test_nrmse"].as_f64().unwrap();
+    assert!(test_nrmse < 1.0, "test NRMSE {}", test_nrmse);
+    Ok(())
+}
+
+#[test]
+fn train_reservoir_rejects_outputs_beyond_reservoir() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["train", "--topology", "reservoir", "--hidden", "5", "--outputs", "10", "--steps", "100"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("--outputs <= --hidden"));
+    Ok(())
+}
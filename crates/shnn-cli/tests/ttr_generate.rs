@@ -0,0 +1,80 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::{BitmapMask, Mask, MaskType};
+
+#[test]
+fn ttr_generate_writes_task_masks() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let ws = tmp.path().to_str().unwrap();
+
+    // Neuron 1 is active during task a, neuron 2 during task b, neuron 3 in both
+    let spikes = serde_json::json!({
+        "results": {
+            "spikes": [
+                {"neuron_id": 1, "time_ns": 1_000_000u64},
+                {"neuron_id": 1, "time_ns": 2_000_000u64},
+                {"neuron_id": 3, "time_ns": 3_000_000u64},
+                {"neuron_id": 2, "time_ns": 11_000_000u64},
+                {"neuron_id": 2, "time_ns": 12_000_000u64},
+                {"neuron_id": 3, "time_ns": 13_000_000u64}
+            ]
+        }
+    });
+    let spikes_path = tmp.path().join("spikes.json");
+    std::fs::write(&spikes_path, serde_json::to_string(&spikes)?)?;
+
+    let program = tmp.path().join("ttr.toml");
+    std::fs::write(
+        &program,
+        format!(
+            r#"
+[program]
+name = "two-tasks"
+
+[activity]
+path = "{}"
+num_neurons = 8
+
+[criterion]
+min_rate_hz = 150.0
+min_selectivity = 2.0
+
+[inputs]
+ranges = [{{ start = 0, end = 0 }}]
+
+[[task]]
+name = "a"
+start_ms = 0.0
+end_ms = 10.0
+
+[[task]]
+name = "b"
+start_ms = 10.0
+end_ms = 20.0
+"#,
+            spikes_path.display()
+        ),
+    )?;
+
+    Command::cargo_bin("snn")?
+        .args(["-w", ws, "ttr", "generate", "--program", program.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("task a: 2/8 neurons"));
+
+    let masks = tmp.path().join("data/masks");
+    let a = BitmapMask::import_vmsk(&std::fs::read(masks.join("a.vmsk"))?)?;
+    assert_eq!(a.mask_type(), MaskType::ActivityMask);
+    assert_eq!(a.total_count(), 8);
+    assert_eq!(a.active_indices(), vec![0, 1]);
+    let b = BitmapMask::import_vmsk(&std::fs::read(masks.join("b.vmsk"))?)?;
+    assert_eq!(b.active_indices(), vec![0, 2]);
+
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(masks.join("manifest.json"))?)?;
+    assert_eq!(manifest["masks"][1]["task"], "b");
+    assert_eq!(manifest["masks"][1]["active"], 2);
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::path::Path;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_storage::{BitmapMask, GenerationId, Mask, MaskCollection, MaskId, MaskType};
+
+fn write_mask(path: &Path, indices: &[u32]) -> Result<(), Box<dyn Error>> {
+    let mask = BitmapMask::from_indices(
+        MaskId::new(1),
+        MaskType::ActivityMask,
+        GenerationId::new(0),
+        8,
+        indices.iter().copied(),
+    )?;
+    std::fs::write(path, mask.export_vmsk()?)?;
+    Ok(())
+}
+
+fn read_mask(path: &Path) -> Result<Vec<u32>, Box<dyn Error>> {
+    Ok(BitmapMask::import_vmsk(&std::fs::read(path)?)?.active_indices())
+}
+
+#[test]
+fn ttr_mask_op_algebra() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let (a, b) = (tmp.path().join("a.vmsk"), tmp.path().join("b.vmsk"));
+    write_mask(&a, &[0, 1, 2])?;
+    write_mask(&b, &[2, 3])?;
+    let (a, b) = (a.to_str().unwrap(), b.to_str().unwrap());
+
+    let out = tmp.path().join("out.vmsk");
+    let out_s = out.to_str().unwrap();
+    for (op, expected) in [
+        ("union", vec![0, 1, 2, 3]),
+        ("intersect", vec![2]),
+        ("difference", vec![0, 1]),
+    ] {
+        Command::cargo_bin("snn")?
+            .args(["ttr", "mask-op", op, a, b, "-o", out_s])
+            .assert()
+            .success();
+        assert_eq!(read_mask(&out)?, expected, "{}", op);
+    }
+
+    Command::cargo_bin("snn")?
+        .args(["ttr", "mask-op", "invert", b, "-o", out_s])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("6/8 active"));
+    assert_eq!(read_mask(&out)?, vec![0, 1, 4, 5, 6, 7]);
+
+    Command::cargo_bin("snn")?
+        .args(["ttr", "mask-op", "invert", a, b, "-o", out_s])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn ttr_mask_op_collections() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let (a, b) = (tmp.path().join("a.vmsk"), tmp.path().join("b.vmsk"));
+    write_mask(&a, &[0, 1, 2])?;
+    write_mask(&b, &[2, 3])?;
+    let tasks = tmp.path().join("tasks.vmsk");
+    let tasks_s = tasks.to_str().unwrap();
+
+    Command::cargo_bin("snn")?
+        .args(["ttr", "mask-op", "bundle", a.to_str().unwrap(), b.to_str().unwrap(), "-o", tasks_s])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bundled 2 masks (a, b)"));
+
+    let a_ref = format!("{}#a", tasks_s);
+    let b_ref = format!("{}#b", tasks_s);
+    Command::cargo_bin("snn")?
+        .args(["ttr", "mask-op", "difference", &a_ref, &b_ref, "-o", tasks_s, "--name", "a_only"])
+        .assert()
+        .success();
+
+    let collection = MaskCollection::from_bytes(&std::fs::read(&tasks)?)?;
+    assert_eq!(collection.names().collect::<Vec<_>>(), vec!["a", "b", "a_only"]);
+    assert_eq!(collection.get("a_only").unwrap().active_indices(), vec![0, 1]);
+
+    // A collection must be indexed by name
+    Command::cargo_bin("snn")?
+        .args(["ttr", "mask-op", "invert", tasks_s, "-o", tmp.path().join("x.vmsk").to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("select one with #name"));
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn validate_runs_scenarios_and_writes_report() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let report = tmp.path().join("validate.json");
+
+    Command::cargo_bin("snn")?
+        .args(["validate", "--scenario", "lif-fi", "--scenario", "stdp-pair", "-o"])
+        .arg(&report)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("lif-fi").and(predicate::str::contains("All validation checks passed")));
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report)?)?;
+    assert_eq!(json["format"], "snn-validate-report");
+    assert_eq!(json["dt_ns"], 100_000);
+    assert_eq!(json["integrator"], "euler");
+    assert_eq!(json["passed"], true);
+    let scenarios = json["scenarios"].as_array().unwrap();
+    assert_eq!(scenarios.len(), 2);
+    assert_eq!(scenarios[0]["name"], "lif-fi");
+    let checks = scenarios[0]["checks"].as_array().unwrap();
+    assert_eq!(checks.len(), 6);
+    // Sub-rheobase current: silent neuron
+    assert_eq!(checks[0]["expected"], 0.0);
+    assert_eq!(checks[0]["measured"], 0.0);
+    for check in checks {
+        assert!(check["deviation"].as_f64().unwrap().abs() <= check["tolerance"].as_f64().unwrap());
+    }
+    assert_eq!(scenarios[1]["checks"].as_array().unwrap().len(), 8);
+    Ok(())
+}
+
+#[test]
+fn validate_rejects_unknown_scenario() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("snn")?
+        .args(["validate", "--scenario", "hh-gating"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("lif-fi | poisson-rate | stdp-pair"));
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::error::Error;
+use tempfile::tempdir;
+use assert_cmd::Command;
+
+use shnn_storage::vevt::{encode_vevt, VEVTEvent};
+use shnn_storage::{StreamId, Time};
+
+fn export(input: &std::path::Path, output: &std::path::Path, format: &str, bin: Option<&str>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut cmd = Command::cargo_bin("snn")?;
+    cmd.args(["viz", "export", input.to_str().unwrap(), "--output", output.to_str().unwrap(), "--format", format]);
+    if let Some(bin) = bin {
+        cmd.args(["--bin", bin]);
+    }
+    cmd.assert().success();
+    Ok(std::fs::read(output)?)
+}
+
+fn npy_header(bytes: &[u8]) -> String {
+    let len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    String::from_utf8_lossy(&bytes[10..10 + len]).into_owned()
+}
+
+#[test]
+fn viz_export_spike_table_and_rates() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let recording = tmp.path().join("run.vevt");
+    let events: Vec<VEVTEvent> = (0..40u64)
+        .map(|i| VEVTEvent { timestamp: i * 250_000, event_type: 0, source_id: (i % 4) as u32, target_id: u32::MAX, payload_size: 0, reserved: 0 })
+        .collect();
+    std::fs::write(&recording, encode_vevt(StreamId::new(1), Time::ZERO, Time::from_nanos(10_000_000), &events)?)?;
+
+    let table = export(&recording, &tmp.path().join("out/spikes.npy"), "npy", None)?;
+    assert!(npy_header(&table).contains("[('time_ns', '<u8'), ('neuron_id', '<u4')], 'fortran_order': False, 'shape': (40,)"));
+
+    let rates = export(&recording, &tmp.path().join("out/rates.npy"), "npy", Some("1ms"))?;
+    assert!(npy_header(&rates).contains("'descr': '<f4', 'fortran_order': False, 'shape': (4, 10)"));
+
+    for bin in [None, Some("2ms")] {
+        let parquet = export(&recording, &tmp.path().join("out/table.parquet"), "parquet", bin)?;
+        assert!(parquet.starts_with(b"PAR1") && parquet.ends_with(b"PAR1"));
+    }
+    Ok(())
+}
+
+#[test]
+fn viz_export_reads_results_json() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let results = tmp.path().join("results.json");
+    std::fs::write(&results, r#"{"results":{"spike_count":2,"spikes":[
+        {"neuron_id":3,"time_ns":1000,"time_ms":0.001},
+        {"neuron_id":5,"time_ns":2000,"time_ms":0.002}]}}"#)?;
+    let table = export(&results, &tmp.path().join("spikes.npy"), "npy", None)?;
+    assert!(npy_header(&table).contains("'shape': (2,)"));
+    let data = &table[table.len() - 24..];
+    assert_eq!(u64::from_le_bytes(data[12..20].try_into()?), 2000);
+    assert_eq!(u32::from_le_bytes(data[20..24].try_into()?), 5);
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Child, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+use shnn_ir::{Module, layer_fully_connected_v1, runtime_simulate_run_v1, stimulus_poisson_v1};
+
+/// Kills the server when the test ends, pass or fail
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn free_port() -> Result<u16, Box<dyn Error>> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+/// Status code and body of a raw HTTP/1.1 GET, retrying until the server accepts connections
+fn request(port: u16, path: &str, headers: &str) -> Result<(u16, String), Box<dyn Error>> {
+    send(port, "GET", path, headers, "")
+}
+
+fn post(port: u16, path: &str, body: &str) -> Result<(u16, String), Box<dyn Error>> {
+    send(port, "POST", path, &format!("Content-Length: {}\r\n", body.len()), body)
+}
+
+fn send(port: u16, method: &str, path: &str, headers: &str, body: &str) -> Result<(u16, String), Box<dyn Error>> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut stream = loop {
+        match TcpStream::connect(("127.0.0.1", port)) {
+            Ok(stream) => break stream,
+            Err(_) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(e.into()),
+        }
+    };
+    write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n{}", method, path, headers, body)?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let status = response.split_whitespace().nth(1).ok_or("empty response")?;
+    let body = response.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+    Ok((status.parse()?, body.to_string()))
+}
+
+fn get(port: u16, path: &str, headers: &str) -> Result<u16, Box<dyn Error>> {
+    Ok(request(port, path, headers)?.0)
+}
+
+fn spawn_server(workspace: &Path, args: &[&str]) -> Result<Server, Box<dyn Error>> {
+    Ok(Server(
+        std::process::Command::new(assert_cmd::cargo::cargo_bin("snn"))
+            .current_dir(workspace)
+            .env("XDG_CONFIG_HOME", workspace.join("xdg"))
+            .args(["-w", workspace.to_str().unwrap(), "viz", "serve"])
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?,
+    ))
+}
+
+#[test]
+fn viz_serve_guards_api_with_bearer_token() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let port = free_port()?;
+    let _server = spawn_server(tmp.path(), &["--port", &port.to_string(), "--token", "s3cret"])?;
+
+    assert_eq!(get(port, "/api/list", "")?, 401);
+    assert_eq!(get(port, "/api/list", "Authorization: Bearer wrong\r\n")?, 401);
+    assert_eq!(get(port, "/api/list", "Authorization: Bearer s3cret\r\n")?, 200);
+    assert_eq!(get(port, "/api/health?token=s3cret", "")?, 200);
+    // Static assets carry no simulation data
+    assert_eq!(get(port, "/", "")?, 200);
+    Ok(())
+}
+
+#[test]
+fn viz_serve_rejects_unreadable_tls_files() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    Command::cargo_bin("snn")?
+        .current_dir(tmp.path())
+        .env("XDG_CONFIG_HOME", tmp.path().join("xdg"))
+        .args(["viz", "serve", "--port", &free_port()?.to_string(), "--tls-cert", "cert.pem", "--tls-key", "key.pem"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("cert.pem"));
+    Ok(())
+}
+
+#[test]
+fn viz_serve_lists_recorded_runs() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("m.nirt");
+    for seed in [1, 2] {
+        let mut module = Module::new();
+        module.push(layer_fully_connected_v1(0, 1, 2, 3, 1.0, 1.0));
+        module.push(stimulus_poisson_v1(0, 50.0, 10.0, 0.0, 20.0));
+        module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(seed)));
+        std::fs::write(&model, module.to_text())?;
+        Command::cargo_bin("snn")?
+            .env("XDG_CONFIG_HOME", tmp.path().join("xdg"))
+            .args(["-w", tmp.path().to_str().unwrap(), "nir", "run", model.to_str().unwrap(), "--record"])
+            .assert()
+            .success();
+    }
+
+    let store = tmp.path().join("results").join("runs");
+    assert_eq!(std::fs::read_dir(&store)?.count(), 2);
+
+    let port = free_port()?;
+    let _server = spawn_server(tmp.path(), &["--port", &port.to_string()])?;
+    let (status, body) = request(port, "/api/list", "")?;
+    assert_eq!(status, 200);
+    let list: serde_json::Value = serde_json::from_str(&body)?;
+    let runs = list["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 2);
+    let seeds: Vec<_> = runs.iter().map(|r| r["seed"].as_u64()).collect();
+    assert!(seeds.contains(&Some(1)) && seeds.contains(&Some(2)));
+    // Same module text apart from the seed attribute, so hashes differ
+    assert_ne!(runs[0]["nir_hash"], runs[1]["nir_hash"]);
+    for run in runs {
+        assert_eq!(run["format"], "snn-run-manifest");
+        assert_eq!(run["duration_ns"].as_u64(), Some(20_000_000));
+        assert!(run["metrics"]["spike_count"].as_u64().is_some());
+
+        let id = run["id"].as_str().unwrap();
+        let (status, body) = request(port, &format!("/api/spikes?run={}", id), "")?;
+        assert_eq!(status, 200);
+        let results: serde_json::Value = serde_json::from_str(&body)?;
+        assert_eq!(results["results"]["spike_count"], run["metrics"]["spike_count"]);
+    }
+    // Unknown or traversing ids fall through to the empty default
+    let (_, body) = request(port, "/api/spikes?run=..%2F..%2Fetc", "")?;
+    assert!(body.contains("\"spike_count\":0"));
+    Ok(())
+}
+
+#[test]
+fn viz_serve_controls_live_run() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let model = tmp.path().join("live.nirt");
+    let mut module = Module::new();
+    module.push(layer_fully_connected_v1(0, 1, 2, 3, 1.0, 1.0));
+    module.push(stimulus_poisson_v1(0, 50.0, 10.0, 0.0, 50.0));
+    module.push(runtime_simulate_run_v1(1.0, 50.0, false, Some(7)));
+    std::fs::write(&model, module.to_text())?;
+
+    let port = free_port()?;
+    let _server = spawn_server(tmp.path(), &["--port", &port.to_string(), "--live", model.to_str().unwrap(), "--paused"])?;
+    let (status, body) = request(port, "/api/control", "")?;
+    assert_eq!(status, 200);
+    let control: serde_json::Value = serde_json::from_str(&body)?;
+    assert_eq!(control["live"], true);
+    assert_eq!(control["paused"], true);
+    assert_eq!(control["step"], 0);
+    assert_eq!(control["stimuli"][0]["neuron"], 0);
+
+    assert_eq!(post(port, "/api/params", r#"{"stimulus":{"neuron":0,"rate_hz":-5}}"#)?.0, 400);
+    assert_eq!(post(port, "/api/params", r#"{"stimulus":{"neuron":9,"rate_hz":5}}"#)?.0, 400);
+    assert_eq!(post(port, "/api/params", "{}")?.0, 400);
+    let (status, body) = post(port, "/api/params", r#"{"stimulus":{"neuron":0,"rate_hz":0},"modulator_level":0.5}"#)?;
+    assert_eq!(status, 200);
+    let control: serde_json::Value = serde_json::from_str(&body)?;
+    assert_eq!(control["stimuli"][0]["rate_hz"], 0.0);
+
+    post(port, "/api/control/resume", "")?;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let control = loop {
+        let control: serde_json::Value = serde_json::from_str(&request(port, "/api/control", "")?.1)?;
+        if control["finished"] == true || Instant::now() > deadline {
+            break control;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    assert_eq!(control["finished"], true);
+    assert_eq!(control["applied"], 2);
+    assert_eq!(control["step"], 50);
+    // The stimulus was silenced before the first step
+    let (_, body) = request(port, "/api/spikes?live=1", "")?;
+    let live: serde_json::Value = serde_json::from_str(&body)?;
+    assert_eq!(live["results"]["spike_count"], 0);
+    Ok(())
+}
+
+#[test]
+fn viz_serve_rejects_control_without_live_run() -> Result<(), Box<dyn Error>> {
+    let tmp = tempdir()?;
+    let port = free_port()?;
+    let _server = spawn_server(tmp.path(), &["--port", &port.to_string()])?;
+    assert_eq!(request(port, "/api/control", "")?.1, r#"{"live":false}"#);
+    assert_eq!(post(port, "/api/control/pause", "")?.0, 409);
+    assert_eq!(post(port, "/api/params", r#"{"modulator_level":1.0}"#)?.0, 409);
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use shnn_compiler::{compile_module, verify_module};
+use shnn_ir::{
+    layer_fully_connected_v1, lif_neuron_v1, parse_text, runtime_simulate_run_v1,
+    stimulus_poisson_v1, Module,
+};
+
+/// Total neuron counts; half inputs, half outputs, fully connected between them
+const SCALES: &[u32] = &[64, 256, 1024];
+
+/// Feed-forward LIF module with Poisson drive on every input neuron
+fn feedforward_module(neurons: u32, duration_ms: f32) -> Module {
+    let half = neurons / 2;
+    let mut module = Module::new();
+    module.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+    module.push(layer_fully_connected_v1(0, half - 1, half, neurons - 1, 0.5, 1.0));
+    for i in 0..half {
+        module.push(stimulus_poisson_v1(i, 100.0, 300.0, 0.0, duration_ms));
+    }
+    module.push(runtime_simulate_run_v1(0.1, duration_ms, false, Some(42)));
+    module
+}
+
+fn bench_frontend(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nir_frontend");
+    for &n in SCALES {
+        let module = feedforward_module(n, 10.0);
+        let text = module.to_text();
+        group.throughput(Throughput::Elements(module.ops.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("parse_text", n), &text, |b, text| {
+            b.iter(|| parse_text(text).unwrap());
+        });
+        group.bench_with_input(BenchmarkId::new("verify", n), &module, |b, module| {
+            b.iter(|| verify_module(module).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn bench_lowering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nir_lowering");
+    group.sample_size(10);
+    for &n in SCALES {
+        let module = feedforward_module(n, 10.0);
+        // Synapse count dominates lowering cost
+        group.throughput(Throughput::Elements(((n / 2) * (n / 2)) as u64));
+        group.bench_with_input(BenchmarkId::new("compile_module", n), &module, |b, module| {
+            b.iter(|| compile_module(module).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn bench_engine_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nir_engine_step");
+    group.sample_size(10);
+    // 10 ms at dt = 0.1 ms
+    let steps = 100;
+    for &n in SCALES {
+        let module = feedforward_module(n, 10.0);
+        group.throughput(Throughput::Elements(steps));
+        group.bench_with_input(BenchmarkId::new("run", n), &module, |b, module| {
+            b.iter_batched(
+                || compile_module(module).unwrap(),
+                |mut program| program.engine.run().unwrap(),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_frontend, bench_lowering, bench_engine_step);
+criterion_main!(benches);
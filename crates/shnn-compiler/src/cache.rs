@@ -0,0 +1,300 @@
+//! Lowered-topology cache artifacts
+//!
+//! Lowering a module expands its connectivity ops (layers, hyperedges, WTA
+//! circuits, reservoirs, snapshots) into one synapse each, which dominates
+//! compile time for million-edge layers. A [`LoweredTopology`] is what those
+//! ops produced: the network's neurons, synapses and the seeds they drew.
+//! [`cache_key`] names it by a hash of the module before expansion, the
+//! compiler version and the files the ops read, so a caller can store it and
+//! later lower the same module with [`compile_cached`], which skips expansion
+//! and every connectivity op. Where artifacts live is up to the caller.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use shnn_ir::{AttributeValue, DialectKey, Module};
+
+use crate::target::{CompileTarget, NetworkPlan, RunPlan, StimulusPlan};
+use crate::{lower_with, normalize, string_from_attr, verify_module, CompilerError, Connectivity, Profiler, Result};
+
+/// Compiler version folded into every key
+pub const COMPILER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Neurons, synapses and seeds produced by a module's connectivity ops
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoweredTopology {
+    /// Every neuron of the network, in creation order
+    pub neurons: Vec<u32>,
+    /// `(pre, post, weight, delay ms)`, in creation order
+    pub synapses: Vec<(u32, u32, f32, f32)>,
+    /// Seeds drawn by the connectivity ops (`<dialect>.<name>#<ordinal>`)
+    pub seeds: BTreeMap<String, u64>,
+}
+
+impl LoweredTopology {
+    /// Binary artifact magic
+    pub const MAGIC: [u8; 4] = *b"LTOP";
+    /// Binary artifact format version
+    pub const VERSION: u16 = 1;
+
+    /// Encode as a little-endian binary artifact
+    ///
+    /// Layout: magic, version (u16), reserved (u16), neuron, synapse and seed
+    /// counts (u32 each), neuron ids (u32), synapses as pre, post (u32) and
+    /// weight, delay (f32), then per seed its key length (u32), UTF-8 key and
+    /// value (u64).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(20 + self.neurons.len() * 4 + self.synapses.len() * 16);
+        out.extend_from_slice(&Self::MAGIC);
+        out.extend_from_slice(&Self::VERSION.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        for count in [self.neurons.len(), self.synapses.len(), self.seeds.len()] {
+            out.extend_from_slice(&(count as u32).to_le_bytes());
+        }
+        for id in &self.neurons {
+            out.extend_from_slice(&id.to_le_bytes());
+        }
+        for (pre, post, weight, delay_ms) in &self.synapses {
+            out.extend_from_slice(&pre.to_le_bytes());
+            out.extend_from_slice(&post.to_le_bytes());
+            out.extend_from_slice(&weight.to_le_bytes());
+            out.extend_from_slice(&delay_ms.to_le_bytes());
+        }
+        for (key, seed) in &self.seeds {
+            out.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            out.extend_from_slice(key.as_bytes());
+            out.extend_from_slice(&seed.to_le_bytes());
+        }
+        out
+    }
+
+    /// Decode an artifact written by [`LoweredTopology::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = ByteReader { bytes, pos: 0 };
+        if reader.take(4)? != Self::MAGIC {
+            return Err(CompilerError::Message("lowered topology: bad magic".into()));
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != Self::VERSION {
+            return Err(CompilerError::Message(format!("lowered topology: unsupported version {}", version)));
+        }
+        reader.take(2)?;
+        let neurons = reader.u32()? as usize;
+        let synapses = reader.u32()? as usize;
+        let seeds = reader.u32()? as usize;
+        // Counts come from the file, so capacity is bounded by what it can hold
+        let mut topology = LoweredTopology {
+            neurons: Vec::with_capacity(neurons.min(bytes.len() / 4)),
+            synapses: Vec::with_capacity(synapses.min(bytes.len() / 16)),
+            seeds: BTreeMap::new(),
+        };
+        for _ in 0..neurons {
+            topology.neurons.push(reader.u32()?);
+        }
+        for _ in 0..synapses {
+            let (pre, post) = (reader.u32()?, reader.u32()?);
+            let weight = f32::from_le_bytes(reader.array()?);
+            let delay_ms = f32::from_le_bytes(reader.array()?);
+            topology.synapses.push((pre, post, weight, delay_ms));
+        }
+        for _ in 0..seeds {
+            let len = reader.u32()? as usize;
+            let key = std::str::from_utf8(reader.take(len)?)
+                .map_err(|_| CompilerError::Message("lowered topology: seed key is not UTF-8".into()))?;
+            topology.seeds.insert(key.to_string(), u64::from_le_bytes(reader.array()?));
+        }
+        if reader.pos != bytes.len() {
+            return Err(CompilerError::Message("lowered topology: trailing bytes".into()));
+        }
+        Ok(topology)
+    }
+}
+
+/// Whether ops of `kind` (`<dialect>.<name>`) only wire the network, so a
+/// cached topology replaces them
+pub fn is_topology_kind(kind: &str) -> bool {
+    kind.starts_with("connectivity.") || matches!(kind, "structure.reservoir" | "structure.wta")
+}
+
+/// Cache key of what `module` lowers to (16 hex digits)
+///
+/// Hashes the compiler version, the module text after symbol resolution,
+/// defaulting and version upgrades (but not expansion), and the contents of
+/// every external array and snapshot file it names. `None` when the module
+/// can't be keyed: it reads a hypergraph store directory, whose latest
+/// generation changes under the same path, or a file that can't be read.
+pub fn cache_key(module: &Module) -> Result<Option<String>> {
+    let module = normalize(module)?;
+    let mut hash = Fnv1a::new();
+    hash.write(COMPILER_VERSION.as_bytes());
+    hash.write(&LoweredTopology::VERSION.to_le_bytes());
+    hash.write(module.to_text().as_bytes());
+    for op in &module.ops {
+        let snapshot = match (&op.dialect, op.name.as_str()) {
+            (DialectKey::Connectivity, "from_snapshot") => Some(string_from_attr(op, "path")?),
+            _ => None,
+        };
+        let externals = op.attrs.values().filter_map(|value| match value {
+            AttributeValue::ExternalData { path, .. } => Some(path.as_str()),
+            _ => None,
+        });
+        for path in snapshot.into_iter().chain(externals) {
+            let path = Path::new(path);
+            if path.is_dir() {
+                return Ok(None);
+            }
+            let Ok(bytes) = std::fs::read(path) else { return Ok(None) };
+            hash.write(&(bytes.len() as u64).to_le_bytes());
+            hash.write(&bytes);
+        }
+    }
+    Ok(Some(format!("{:016x}", hash.finish())))
+}
+
+/// [`compile_for_target_profiled`](crate::compile_for_target_profiled), also
+/// returning the topology to cache under [`cache_key`]
+pub fn compile_recording<T: CompileTarget>(
+    module: &Module,
+    target: T,
+    profiler: &mut Profiler,
+) -> Result<(T::Output, LoweredTopology)> {
+    crate::compile_for_target_profiled(module, Recording { inner: target, topology: LoweredTopology::default() }, profiler)
+}
+
+/// Verify `module` and lower it for `target` on top of `topology`, recorded
+/// by [`compile_recording`] for the same [`cache_key`]
+///
+/// Connectivity ops are neither expanded nor lowered; the network starts with
+/// the cached neurons and synapses. Records `verify`, `passes` and `lowering`
+/// spans.
+pub fn compile_cached<T: CompileTarget>(
+    module: &Module,
+    target: T,
+    topology: &LoweredTopology,
+    profiler: &mut Profiler,
+) -> Result<T::Output> {
+    profiler.time("verify", || verify_module(module))?;
+    let m = profiler.time("passes", || normalize(module))?;
+    profiler.time("lowering", || lower_with(&m, target, &Connectivity::Cached(topology)))
+}
+
+/// Target that copies the network's topology before handing it to `inner`
+struct Recording<T> {
+    inner: T,
+    topology: LoweredTopology,
+}
+
+impl<T: CompileTarget> CompileTarget for Recording<T> {
+    type Network = T::Network;
+    type Output = (T::Output, LoweredTopology);
+
+    fn build_network(&mut self, plan: NetworkPlan) -> Result<T::Network> {
+        self.topology.neurons = plan.builder.neuron_ids().iter().map(|id| id.raw()).collect();
+        self.topology.synapses = plan.builder.synapses().iter()
+            .map(|&(pre, post, weight, delay_ms)| (pre.raw(), post.raw(), weight, delay_ms))
+            .collect();
+        self.inner.build_network(plan)
+    }
+
+    fn build_stimuli(&mut self, network: &mut T::Network, plan: StimulusPlan<'_>) -> Result<()> {
+        self.inner.build_stimuli(network, plan)
+    }
+
+    fn finalize(mut self, network: T::Network, plan: RunPlan<'_>) -> Result<Self::Output> {
+        self.topology.seeds = plan.seeds.iter()
+            .filter(|(key, _)| key.split('#').next().map_or(false, is_topology_kind))
+            .map(|(key, seed)| (key.clone(), *seed))
+            .collect();
+        Ok((self.inner.finalize(network, plan)?, self.topology))
+    }
+}
+
+/// 64-bit FNV-1a
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let slice = self.pos.checked_add(len)
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or_else(|| CompilerError::Message("lowered topology: truncated".into()))?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compile_with_passes, CpuTarget};
+    use shnn_ir::{
+        layer_fully_connected_v1, layer_fully_connected_v2, lif_neuron_v1, runtime_simulate_run_v1, stimulus_poisson_v1,
+        structure_wta_v1, DelayDist,
+    };
+
+    fn module(weight: f32) -> Module {
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(layer_fully_connected_v1(0, 3, 4, 7, weight, 1.0));
+        m.push(layer_fully_connected_v2(4, 7, 8, 9, 1.0, DelayDist::Uniform { min_ms: 1.0, max_ms: 3.0 }, None));
+        m.push(structure_wta_v1(3, 2.0, 0.5));
+        m.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 50.0));
+        m.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(5)));
+        m
+    }
+
+    #[test]
+    fn cached_topology_lowers_like_expansion() {
+        let m = module(2.0);
+        let key = cache_key(&m).expect("key").expect("keyable");
+        assert_eq!(cache_key(&m).expect("key"), Some(key.clone()));
+        assert_ne!(cache_key(&module(1.5)).expect("key"), Some(key));
+
+        let (mut program, topology) = compile_recording(&m, CpuTarget, &mut Profiler::new()).expect("record");
+        assert_eq!(topology.neurons.len(), 10);
+        assert_eq!(topology.synapses.len(), 16 + 8 + 9);
+        assert_eq!(topology.seeds.keys().collect::<Vec<_>>(), ["connectivity.layer_fully_connected#0"]);
+        let decoded = LoweredTopology::from_bytes(&topology.to_bytes()).expect("decode");
+        assert_eq!(decoded, topology);
+
+        let mut cached = compile_cached(&m, CpuTarget, &decoded, &mut Profiler::new()).expect("cached");
+        assert_eq!(cached.seeds, program.seeds);
+        let expected = program.engine.run().expect("run");
+        assert_eq!(cached.engine.run().expect("run").spike_hash(), expected.spike_hash());
+        assert_eq!(compile_with_passes(&m).expect("compile").engine.network().synapse_count(), 33);
+
+        let mut bytes = topology.to_bytes();
+        bytes.pop();
+        assert!(LoweredTopology::from_bytes(&bytes).unwrap_err().to_string().contains("truncated"));
+    }
+}
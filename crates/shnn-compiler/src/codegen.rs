@@ -0,0 +1,332 @@
+//! Static code generation for embedded targets
+//!
+//! Lowers a NIR module through the normal pipeline for [`MicroTarget`], which
+//! reads the built network into fixed-size tables ([`MicroTables`]) that
+//! `shnn-micro` can instantiate without allocation. [`MicroTables::to_rust`] prints them
+//! as a Rust source file defining `const` tables and a `build()` function.
+
+use std::fmt::Write;
+
+use shnn_ir::{DialectKey, Module};
+use shnn_runtime::{delay::SubDtDelay, network::SNNNetwork, neuron::Refractoriness};
+
+use crate::quantize::FixedFormat;
+use crate::{compile_for_target, CompileTarget, CompilerError, NetworkPlan, Result, RunPlan, StimulusPlan};
+
+/// Largest neuron count addressable by shnn-micro's `u8` ids (255 is reserved as invalid)
+pub const MICRO_MAX_NEURONS: usize = 255;
+
+/// Largest connection count of shnn-micro's `u8` connection counter
+pub const MICRO_MAX_CONNECTIONS: usize = 255;
+
+/// Largest synaptic delay of a shnn-micro connection (steps, 4 bits)
+pub const MICRO_MAX_DELAY_STEPS: u8 = 15;
+
+/// LIF parameters in shnn-micro units (raw `Q15_16`, potentials in V)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MicroLif {
+    /// Resting potential
+    pub resting_potential: i32,
+    /// Spike threshold
+    pub threshold: i32,
+    /// Reset potential
+    pub reset_potential: i32,
+    /// Per-step membrane decay factor `exp(-dt / tau_m)`
+    pub decay_factor: i32,
+    /// Refractory period (ms)
+    pub refractory_period_ms: u8,
+}
+
+/// One shnn-micro connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MicroConnection {
+    /// Pre-synaptic neuron
+    pub source: u8,
+    /// Post-synaptic neuron
+    pub target: u8,
+    /// Weight (raw `Q15_16`)
+    pub weight: i32,
+    /// Delay (steps)
+    pub delay: u8,
+}
+
+/// Static tables for a `MicroNetwork<N, C>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MicroTables {
+    /// Neuron count `N`; runtime neuron ids are kept, so this is the largest id + 1
+    pub neurons: usize,
+    /// Step size (ms)
+    pub time_step_ms: u8,
+    /// Parameters shared by every neuron
+    pub lif: MicroLif,
+    /// Connections sorted by (source, target)
+    pub connections: Vec<MicroConnection>,
+}
+
+fn unsupported(reason: impl Into<String>) -> CompilerError {
+    CompilerError::Message(format!("micro codegen: {}", reason.into()))
+}
+
+fn q15_16(value: f32, what: &str) -> Result<i32> {
+    FixedFormat::Q15_16.to_raw(value).ok_or_else(|| unsupported(format!("{} {} is out of Q15.16 range", what, value)))
+}
+
+/// Compile `module` and read it back as shnn-micro tables
+///
+/// Fails if the program uses features shnn-micro cannot represent: non-LIF
+/// populations, a step that is not a whole number of milliseconds, more than
+/// 255 neurons or connections, delays above 15 steps, interpolated sub-dt
+/// delays, or a refractory policy other than hold. Other positive delays
+/// below dt take one step, as in the engine.
+pub fn micro_tables(module: &Module) -> Result<MicroTables> {
+    if let Some(op) = module.ops.iter().find(|op| op.dialect == DialectKey::Neuron && op.name != "lif") {
+        return Err(unsupported(format!("neuron.{} populations are not supported; shnn-micro only runs LIF", op.name)));
+    }
+
+    compile_for_target(module, MicroTarget)
+}
+
+/// Lowering to shnn-micro tables ([`micro_tables`])
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MicroTarget;
+
+impl CompileTarget for MicroTarget {
+    /// Built network and dt (ns)
+    type Network = (SNNNetwork, u64);
+    type Output = MicroTables;
+
+    fn build_network(&mut self, plan: NetworkPlan) -> Result<(SNNNetwork, u64)> {
+        let dt_ns = plan.params.dt_ns;
+        Ok((plan.build()?, dt_ns))
+    }
+
+    /// Tables carry no inputs; the firmware drives the network
+    fn build_stimuli(&mut self, _network: &mut (SNNNetwork, u64), _plan: StimulusPlan<'_>) -> Result<()> {
+        Ok(())
+    }
+
+    fn finalize(self, (network, dt_ns): (SNNNetwork, u64), _plan: RunPlan<'_>) -> Result<MicroTables> {
+        tables_from_network(&network, dt_ns)
+    }
+}
+
+fn tables_from_network(network: &SNNNetwork, dt_ns: u64) -> Result<MicroTables> {
+    if dt_ns % 1_000_000 != 0 || dt_ns == 0 || dt_ns / 1_000_000 > u8::MAX as u64 {
+        return Err(unsupported(format!(
+            "dt of {} ms is not a whole number of milliseconds in 1..=255",
+            dt_ns as f64 / 1_000_000.0
+        )));
+    }
+    let dt_ms = (dt_ns / 1_000_000) as f32;
+
+    let neurons = network.neuron_ids().iter().map(|id| id.raw() as usize + 1).max().unwrap_or(0);
+    if neurons == 0 {
+        return Err(unsupported("module has no neurons"));
+    }
+    if neurons > MICRO_MAX_NEURONS {
+        return Err(unsupported(format!("neuron ids must be below {}, found {}", MICRO_MAX_NEURONS, neurons - 1)));
+    }
+
+    let mut synapses = network.synapse_connections();
+    if synapses.len() > MICRO_MAX_CONNECTIONS {
+        return Err(unsupported(format!(
+            "{} connections exceed the limit of {}",
+            synapses.len(),
+            MICRO_MAX_CONNECTIONS
+        )));
+    }
+    synapses.sort_by_key(|(pre, post, _)| (pre.raw(), post.raw()));
+    let connections = synapses.into_iter().map(|(pre, post, weight)| {
+        let delay_ms = network.get_delay(pre, post)?;
+        if delay_ms < dt_ms && network.config.sub_dt_delay == SubDtDelay::Interpolate {
+            return Err(unsupported(format!(
+                "delay {} ms of {} -> {} is below dt and interpolated; shnn-micro delays are whole steps",
+                delay_ms,
+                pre.raw(),
+                post.raw()
+            )));
+        }
+        // Rounding must not turn a positive delay into a same-step delivery
+        let steps = match delay_ms > 0.0 {
+            true => (delay_ms / dt_ms).round().max(1.0),
+            false => 0.0,
+        };
+        if steps > MICRO_MAX_DELAY_STEPS as f32 {
+            return Err(unsupported(format!(
+                "delay {} ms of {} -> {} exceeds {} steps",
+                delay_ms,
+                pre.raw(),
+                post.raw(),
+                MICRO_MAX_DELAY_STEPS
+            )));
+        }
+        Ok(MicroConnection {
+            source: pre.raw() as u8,
+            target: post.raw() as u8,
+            weight: q15_16(weight, "weight")?,
+            delay: steps as u8,
+        })
+    }).collect::<Result<Vec<_>>>()?;
+
+    let params = &network.config.default_lif_params;
+    if params.refractory != Refractoriness::HoldAtReset {
+        return Err(unsupported(format!(
+            "refractory policy '{}' is not supported; shnn-micro holds at reset",
+            params.refractory.as_str()
+        )));
+    }
+    let lif = MicroLif {
+        resting_potential: q15_16(params.v_rest / 1000.0, "v_rest")?,
+        threshold: q15_16(params.v_thresh / 1000.0, "v_thresh")?,
+        reset_potential: q15_16(params.v_reset / 1000.0, "v_reset")?,
+        decay_factor: q15_16((-dt_ms / params.tau_m).exp(), "decay factor")?,
+        refractory_period_ms: params.t_refrac.round().clamp(0.0, u8::MAX as f32) as u8,
+    };
+
+    Ok(MicroTables { neurons, time_step_ms: dt_ms as u8, lif, connections })
+}
+
+fn raw_literal(raw: i32) -> String {
+    format!("Q15_16::from_raw({})", raw)
+}
+
+fn raw_value(raw: i32) -> f64 {
+    FixedFormat::Q15_16.from_raw(raw) as f64
+}
+
+impl MicroTables {
+    /// Print the tables as a Rust module for shnn-micro (`fixed-point` feature)
+    ///
+    /// `source` names the NIR input in the header comment.
+    pub fn to_rust(&self, source: &str) -> String {
+        let mut out = String::new();
+        // Writing into a String cannot fail
+        let _ = self.write_rust(&mut out, source);
+        out
+    }
+
+    fn write_rust(&self, out: &mut String, source: &str) -> std::fmt::Result {
+        writeln!(out, "//! Generated by `snn nir codegen --target micro` from {}; do not edit.", source)?;
+        writeln!(out, "//!")?;
+        writeln!(out, "//! Requires shnn-micro with the `fixed-point` feature (Q15.16 scalars).")?;
+        writeln!(out)?;
+        writeln!(out, "use shnn_micro::connectivity::Connection;")?;
+        writeln!(out, "use shnn_micro::neuron::{{LIFConfig, NeuronId}};")?;
+        writeln!(out, "use shnn_micro::{{MicroNetwork, Q15_16}};")?;
+        writeln!(out)?;
+        writeln!(out, "/// Neurons in the network")?;
+        writeln!(out, "pub const NEURONS: usize = {};", self.neurons)?;
+        writeln!(out, "/// Connections in the network")?;
+        writeln!(out, "pub const CONNECTIONS: usize = {};", self.connections.len())?;
+        writeln!(out, "/// Step size (ms)")?;
+        writeln!(out, "pub const TIME_STEP_MS: u8 = {};", self.time_step_ms)?;
+        writeln!(out)?;
+        writeln!(out, "/// LIF parameters shared by every neuron (potentials in V)")?;
+        writeln!(out, "pub const LIF: LIFConfig = LIFConfig {{")?;
+        for (name, raw) in [
+            ("resting_potential", self.lif.resting_potential),
+            ("threshold", self.lif.threshold),
+            ("reset_potential", self.lif.reset_potential),
+            ("decay_factor", self.lif.decay_factor),
+        ] {
+            writeln!(out, "    {}: {}, // {:.6}", name, raw_literal(raw), raw_value(raw))?;
+        }
+        writeln!(out, "    refractory_period_ms: {},", self.lif.refractory_period_ms)?;
+        writeln!(out, "}};")?;
+        writeln!(out)?;
+        writeln!(out, "/// Per-neuron parameters; neuron `i` gets `NeuronId(i)`")?;
+        writeln!(out, "pub const NEURON_TABLE: [LIFConfig; NEURONS] = [LIF; NEURONS];")?;
+        writeln!(out)?;
+        writeln!(out, "/// Connections as (source, target, weight, delay in steps)")?;
+        writeln!(out, "pub const CONNECTION_TABLE: [Connection; CONNECTIONS] = [")?;
+        for c in &self.connections {
+            writeln!(
+                out,
+                "    Connection::with_delay(NeuronId::new({}), NeuronId::new({}), {}, {}), // w={:.6}",
+                c.source,
+                c.target,
+                raw_literal(c.weight),
+                c.delay,
+                raw_value(c.weight)
+            )?;
+        }
+        writeln!(out, "];")?;
+        writeln!(out)?;
+        writeln!(out, "/// Instantiate the network")?;
+        writeln!(out, "pub fn build() -> shnn_micro::Result<MicroNetwork<NEURONS, CONNECTIONS>> {{")?;
+        writeln!(out, "    MicroNetwork::from_tables(&NEURON_TABLE, &CONNECTION_TABLE, TIME_STEP_MS)")?;
+        writeln!(out, "}}")
+    }
+}
+
+/// Compile `module` and print it as a shnn-micro Rust module
+pub fn emit_micro(module: &Module, source: &str) -> Result<String> {
+    Ok(micro_tables(module)?.to_rust(source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shnn_ir::{layer_fully_connected_v1, lif_neuron_v1, runtime_simulate_run_v1, synapse_connect_v1};
+
+    fn module(dt_ms: f32, delay_ms: f32) -> Module {
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, delay_ms));
+        m.push(synapse_connect_v1(3, 0, -0.25, 1.0));
+        m.push(runtime_simulate_run_v1(dt_ms, 10.0, false, Some(1)));
+        m
+    }
+
+    #[test]
+    fn test_micro_tables_quantize_network() {
+        let tables = micro_tables(&module(1.0, 2.0)).unwrap();
+        assert_eq!(tables.neurons, 4);
+        assert_eq!(tables.time_step_ms, 1);
+        assert_eq!(tables.connections.len(), 5);
+        assert_eq!(tables.connections[0], MicroConnection { source: 0, target: 2, weight: 32768, delay: 2 });
+        assert_eq!(tables.connections[4], MicroConnection { source: 3, target: 0, weight: -16384, delay: 1 });
+        assert_eq!(tables.lif.threshold, FixedFormat::Q15_16.to_raw(-0.05).unwrap());
+        // exp(-1 / 20)
+        assert_eq!(tables.lif.decay_factor, 62340);
+        assert_eq!(tables.lif.refractory_period_ms, 2);
+    }
+
+    #[test]
+    fn test_micro_tables_match_quantized_module() {
+        let m = module(1.0, 2.0);
+        let quantized = crate::quantize::quantize_module(&m, FixedFormat::Q15_16).unwrap();
+        assert_eq!(micro_tables(&quantized).unwrap(), micro_tables(&m).unwrap());
+    }
+
+    #[test]
+    fn test_emit_micro_source() {
+        let source = emit_micro(&module(1.0, 1.0), "net.nir").unwrap();
+        assert!(source.contains("from net.nir; do not edit"));
+        assert!(source.contains("pub const NEURONS: usize = 4;"));
+        assert!(source.contains("pub const CONNECTION_TABLE: [Connection; CONNECTIONS] = ["));
+        assert!(source.contains("Connection::with_delay(NeuronId::new(0), NeuronId::new(2), Q15_16::from_raw(32768), 1)"));
+        assert!(source.contains("MicroNetwork<NEURONS, CONNECTIONS>"));
+    }
+
+    #[test]
+    fn test_micro_codegen_rejects_unrepresentable_programs() {
+        let err = micro_tables(&module(0.1, 1.0)).unwrap_err().to_string();
+        assert!(err.contains("whole number of milliseconds"), "{}", err);
+        let err = micro_tables(&module(1.0, 20.0)).unwrap_err().to_string();
+        assert!(err.contains("exceeds 15 steps"), "{}", err);
+    }
+
+    #[test]
+    fn test_micro_sub_dt_delays() {
+        // 0.1 ms at dt = 1 ms is one step, not zero
+        let tables = micro_tables(&module(1.0, 0.1)).unwrap();
+        assert_eq!(tables.connections[0].delay, 1);
+
+        let mut m = module(1.0, 0.1);
+        let run = m.ops.last_mut().unwrap();
+        *run = run.clone().with_attr("sub_dt_delay", shnn_ir::AttributeValue::String("interpolate".into()));
+        let err = micro_tables(&m).unwrap_err().to_string();
+        assert!(err.contains("interpolated"), "{}", err);
+    }
+}
@@ -0,0 +1,300 @@
+//! Non-fatal compiler diagnostics
+//!
+//! [`check_module`] runs [`verify_module`] and, on success, lint rules that
+//! flag modules which compile but probably do not do what was meant. Errors
+//! and warnings are collected in [`Diagnostics`]; callers decide whether
+//! warnings are shown, ignored or promoted to errors.
+
+use core::fmt::{self, Display, Formatter};
+
+use shnn_ir::{DialectKey, Module, Operation};
+use shnn_runtime::delay::SubDtDelay;
+
+use crate::stability::analyze_dt;
+use crate::{duration_ns_from_attr, rate_hz_from_attr, string_from_attr, sub_dt_delay_from_attr, verify_module, Result};
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Compiles, but is likely a mistake
+    Warning,
+    /// Does not compile
+    Error,
+}
+
+/// Stable diagnostic code; `N0xx` are errors and `N1xx` warnings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    /// [`verify_module`] rejected the module
+    Verify,
+    /// A synaptic delay is shorter than dt (delivered on the next step)
+    DelayBelowDt,
+    /// A Poisson stimulus has a rate of 0 Hz and never fires
+    ZeroRateStimulus,
+    /// A plasticity rule is declared but the module has no synapses
+    UnusedPlasticity,
+    /// A synaptic delay is shorter than dt and interpolated across two steps
+    InterpolatedDelay,
+    /// dt is at or past the point where the fastest time constant diverges
+    UnstableDt,
+    /// dt under-resolves the fastest time constant (see [`analyze_dt`])
+    CoarseDt,
+}
+
+impl DiagnosticCode {
+    /// Code string, e.g. `N101`
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            DiagnosticCode::Verify => "N001",
+            DiagnosticCode::DelayBelowDt => "N101",
+            DiagnosticCode::ZeroRateStimulus => "N102",
+            DiagnosticCode::UnusedPlasticity => "N103",
+            DiagnosticCode::InterpolatedDelay => "N104",
+            DiagnosticCode::UnstableDt => "N105",
+            DiagnosticCode::CoarseDt => "N106",
+        }
+    }
+
+    /// Severity of diagnostics with this code
+    pub const fn severity(self) -> Severity {
+        match self {
+            DiagnosticCode::Verify => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One finding
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// Error or warning
+    pub severity: Severity,
+    /// Stable code
+    pub code: DiagnosticCode,
+    /// Op the finding is about, as `dialect.name@vN #index`
+    pub op: Option<String>,
+    /// What was found
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}[{}]", level, self.code)?;
+        if let Some(op) = &self.op {
+            write!(f, " {}", op)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Errors and warnings collected for a module
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Empty collection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a finding with `code`'s severity
+    pub fn push(&mut self, code: DiagnosticCode, op: Option<String>, message: impl Into<String>) {
+        self.items.push(Diagnostic { severity: code.severity(), code, op, message: message.into() });
+    }
+
+    /// Every finding, in the order found
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.items.iter()
+    }
+
+    /// Findings of `severity`
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Diagnostic> {
+        self.items.iter().filter(move |d| d.severity == severity)
+    }
+
+    /// Number of warnings
+    pub fn warning_count(&self) -> usize {
+        self.with_severity(Severity::Warning).count()
+    }
+
+    /// Whether any finding is an error
+    pub fn has_errors(&self) -> bool {
+        self.with_severity(Severity::Error).next().is_some()
+    }
+
+    /// Turn every warning into an error (`-W error`)
+    pub fn promote_warnings(&mut self) {
+        for item in &mut self.items {
+            item.severity = Severity::Error;
+        }
+    }
+
+    /// Whether nothing was found
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// Verify `module` and lint it for likely mistakes
+pub fn check_module(module: &Module) -> Diagnostics {
+    let mut diagnostics = Diagnostics::new();
+    if let Err(e) = verify_module(module) {
+        diagnostics.push(DiagnosticCode::Verify, None, e.to_string());
+        return diagnostics;
+    }
+    // Attributes were verified above, so lint helpers only fail on ops they do not understand
+    let linted = crate::symbols::resolved(module).and_then(|module| lint(&module, &mut diagnostics));
+    if let Err(e) = linted {
+        diagnostics.push(DiagnosticCode::Verify, None, e.to_string());
+    }
+    diagnostics
+}
+
+fn lint(module: &Module, diagnostics: &mut Diagnostics) -> Result<()> {
+    let run = module.ops.iter().find(|op| op.dialect == DialectKey::Runtime && op.name == "simulate.run");
+    let dt_ns = run.map(|op| duration_ns_from_attr(op, "dt")).transpose()?;
+    let sub_dt_delay = run.map(sub_dt_delay_from_attr).transpose()?.unwrap_or_default();
+    let has_synapses = module.ops.iter().any(|op| op.dialect == DialectKey::Connectivity);
+
+    for (index, op) in module.ops.iter().enumerate() {
+        let label = || Some(format!("{}.{}@{} #{}", op.dialect, op.name, op.version, index));
+        match (&op.dialect, op.name.as_str()) {
+            (DialectKey::Connectivity, _) => {
+                if let (Some(dt_ns), Some((_, delay_ns))) = (dt_ns, shortest_delay_ns(op)?) {
+                    if delay_ns < dt_ns {
+                        let (code, outcome) = match sub_dt_delay {
+                            SubDtDelay::Interpolate => (DiagnosticCode::InterpolatedDelay, "its input is split across two steps by arrival time"),
+                            _ => (DiagnosticCode::DelayBelowDt, "spikes are delivered on the next step"),
+                        };
+                        diagnostics.push(code, label(), format!(
+                            "delay {} ms is below dt = {} ms; {}",
+                            delay_ns as f64 / 1e6, dt_ns as f64 / 1e6, outcome,
+                        ));
+                    }
+                }
+            }
+            (DialectKey::Stimulus, "poisson") if rate_hz_from_attr(op, "rate")? == 0.0 => {
+                diagnostics.push(DiagnosticCode::ZeroRateStimulus, label(), "rate is 0 Hz; the stimulus never fires");
+            }
+            (DialectKey::Plasticity, _) if !has_synapses => {
+                diagnostics.push(DiagnosticCode::UnusedPlasticity, label(), "no connectivity op creates synapses for this rule to update");
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(analysis) = analyze_dt(module)? {
+        if let Some(bound) = analysis.fastest.as_ref().filter(|_| analysis.is_unstable() || analysis.is_coarse()) {
+            let integrator = analysis.integrator.as_str();
+            let (code, outcome) = match analysis.is_unstable() {
+                true => (DiagnosticCode::UnstableDt, format!("{} integration diverges", integrator)),
+                false => (DiagnosticCode::CoarseDt, format!(
+                    "{} integration is inaccurate above {} ms",
+                    integrator, bound.accurate_dt_ns(analysis.integrator) as f64 / 1e6,
+                )),
+            };
+            let suggestion = match analysis.needs_adjustment() {
+                true => format!("; suggested dt = {} ms", analysis.suggested_dt_ns as f64 / 1e6),
+                false => String::new(),
+            };
+            diagnostics.push(code, Some(bound.op.clone()), format!(
+                "dt = {} ms against {} = {} ms: {}{}",
+                analysis.dt_ns as f64 / 1e6, bound.attr, bound.ns as f64 / 1e6, outcome, suggestion,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Smallest delay a connectivity op can produce and the attr setting it, if it has one
+pub(crate) fn shortest_delay_ns(op: &Operation) -> Result<Option<(&'static str, u64)>> {
+    let key = match op.attrs.contains_key("delay_dist") {
+        true => match string_from_attr(op, "delay_dist")? {
+            "constant" => "delay",
+            "uniform" => "delay_min",
+            // Normal and gamma delays have no fixed lower bound
+            _ => return Ok(None),
+        },
+        false => "delay",
+    };
+    match op.attrs.contains_key(key) {
+        true => duration_ns_from_attr(op, key).map(|ns| Some((key, ns))),
+        false => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shnn_ir::*;
+
+    #[test]
+    fn test_check_module_warnings() {
+        let mut m = Module::new();
+        m.push(stdp_rule_v1(0.01, 0.012, 20.0, 20.0, 0.0, 1.0));
+        m.push(stimulus_poisson_v1(0, 0.0, 1.0, 0.0, 10.0));
+        m.push(runtime_simulate_run_v1(0.1, 10.0, false, None));
+        let diagnostics = check_module(&m);
+        let codes: Vec<_> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(codes, vec![DiagnosticCode::UnusedPlasticity, DiagnosticCode::ZeroRateStimulus]);
+        assert!(!diagnostics.has_errors());
+        assert_eq!(diagnostics.warning_count(), 2);
+        assert!(diagnostics.iter().next().unwrap().to_string().starts_with("warning[N103] plasticity.stdp@v1 #0:"));
+
+        let mut m = Module::new();
+        m.push(synapse_connect_v1(0, 1, 0.5, 0.05));
+        m.push(runtime_simulate_run_v1(0.1, 10.0, false, None));
+        let mut diagnostics = check_module(&m);
+        assert_eq!(diagnostics.iter().map(|d| d.code).collect::<Vec<_>>(), vec![DiagnosticCode::DelayBelowDt]);
+        diagnostics.promote_warnings();
+        assert!(diagnostics.has_errors());
+
+        // 0.1 ms at dt = 1 ms: flagged when interpolated, an error when rejected
+        let with_mode = |mode: &str| {
+            let mut m = Module::new();
+            m.push(synapse_connect_v1(0, 1, 0.5, 0.1));
+            m.push(runtime_simulate_run_v1(1.0, 10.0, false, None)
+                .with_attr("sub_dt_delay", AttributeValue::String(mode.into())));
+            check_module(&m)
+        };
+        let interpolated = with_mode("interpolate");
+        assert_eq!(interpolated.iter().map(|d| d.code).collect::<Vec<_>>(), vec![DiagnosticCode::InterpolatedDelay]);
+        assert!(interpolated.iter().next().unwrap().to_string().contains("split across two steps"));
+        let rejected = with_mode("reject");
+        assert!(rejected.has_errors());
+        assert!(rejected.iter().next().unwrap().message.contains("below dt = 1 ms"));
+
+        let mut bad = Module::new();
+        bad.push(runtime_simulate_run_v1(0.0, 10.0, false, None));
+        assert!(check_module(&bad).has_errors());
+    }
+
+    #[test]
+    fn test_check_module_dt_stability() {
+        let with_dt = |dt_ms: f32| {
+            let mut m = Module::new();
+            m.push(lif_neuron_v1(2.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+            m.push(runtime_simulate_run_v1(dt_ms, 10.0, false, None));
+            check_module(&m)
+        };
+        assert!(with_dt(0.1).is_empty());
+        let coarse = with_dt(1.0);
+        assert_eq!(coarse.iter().map(|d| d.code).collect::<Vec<_>>(), vec![DiagnosticCode::CoarseDt]);
+        assert!(coarse.iter().next().unwrap().to_string().ends_with("against tau_m = 2 ms: euler integration is inaccurate above 0.2 ms; suggested dt = 0.2 ms"));
+        let unstable = with_dt(4.0);
+        assert_eq!(unstable.iter().map(|d| d.code).collect::<Vec<_>>(), vec![DiagnosticCode::UnstableDt]);
+        assert!(unstable.iter().next().unwrap().to_string().starts_with("warning[N105] neuron.lif@v1 #0: dt = 4 ms"));
+    }
+}
@@ -0,0 +1,221 @@
+//! Streamed and compact expansion of connectivity
+//!
+//! [`CanonicalizePass`](crate::passes::CanonicalizePass) rewrites
+//! connectivity.layer_fully_connected@v1, connectivity.hyperedge@v1 and
+//! structure.wta@v1 into one connectivity.synapse_connect@v1 op per synapse,
+//! which for a million-synapse layer is a million ops with their attribute
+//! maps. [`edges`] generates the same synapses in the same order as they are
+//! consumed: the compile pipeline
+//! ([`compile_for_target`](crate::compile_for_target)) feeds them straight
+//! into the network builder, exactly as it would lower the ops, so a dense
+//! layer never exists in expanded form. [`expand`] collects them into an
+//! [`EdgeList`], from which the pass builds its ops.
+
+use shnn_ir::{DialectKey, Operation, OpVersion};
+
+use crate::{
+    duration_ns_from_attr, f32_from_attr, hyperedge_pairs, passes::synapse_connect_op, range_from_attr, wta_synapses,
+    Result,
+};
+
+/// Synapses as parallel arrays, in expansion order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EdgeList {
+    /// Presynaptic neuron ids
+    pub pre: Vec<u32>,
+    /// Postsynaptic neuron ids
+    pub post: Vec<u32>,
+    /// Weights
+    pub weight: Vec<f32>,
+    /// Delays (ns)
+    pub delay_ns: Vec<u64>,
+}
+
+impl EdgeList {
+    /// Bytes held per edge
+    pub const BYTES_PER_EDGE: u64 = 4 + 4 + 4 + 8;
+
+    /// Empty list with room for `capacity` edges
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            pre: Vec::with_capacity(capacity),
+            post: Vec::with_capacity(capacity),
+            weight: Vec::with_capacity(capacity),
+            delay_ns: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Append an edge
+    pub fn push(&mut self, pre: u32, post: u32, weight: f32, delay_ns: u64) {
+        self.pre.push(pre);
+        self.post.push(post);
+        self.weight.push(weight);
+        self.delay_ns.push(delay_ns);
+    }
+
+    /// Number of edges
+    pub fn len(&self) -> usize {
+        self.pre.len()
+    }
+
+    /// Whether the list has no edges
+    pub fn is_empty(&self) -> bool {
+        self.pre.is_empty()
+    }
+
+    /// Edges as `(pre, post, weight, delay_ns)`
+    pub fn iter(&self) -> impl Iterator<Item = (u32, u32, f32, u64)> + '_ {
+        (0..self.len()).map(move |i| (self.pre[i], self.post[i], self.weight[i], self.delay_ns[i]))
+    }
+
+    /// One connectivity.synapse_connect@v1 op per edge
+    pub fn to_ops(&self) -> Vec<Operation> {
+        self.iter().map(|(pre, post, weight, delay_ns)| synapse_connect_op(pre, post, weight, delay_ns)).collect()
+    }
+}
+
+/// Whether CanonicalizePass expands `op` into synapse_connect ops
+pub fn expands(op: &Operation) -> bool {
+    matches!(
+        (&op.dialect, op.name.as_str(), op.version),
+        (DialectKey::Connectivity, "layer_fully_connected", OpVersion(1))
+            | (DialectKey::Connectivity, "hyperedge", OpVersion(1))
+            | (DialectKey::Structure, "wta", OpVersion(1))
+    )
+}
+
+/// Synapses of an expanded op as `(pre, post, weight, delay_ns)`, generated on demand
+pub struct Edges {
+    remaining: usize,
+    inner: Box<dyn Iterator<Item = (u32, u32, f32, u64)>>,
+}
+
+impl Iterator for Edges {
+    type Item = (u32, u32, f32, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let edge = self.inner.next()?;
+        self.remaining -= 1;
+        Some(edge)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Edges {}
+
+/// Synapses `op` expands into, or `None` if [`expands`] is false
+///
+/// Layers are generated from their ranges; hyperedge pairs and WTA circuits
+/// are small and held as a list.
+pub fn edges(op: &Operation) -> Result<Option<Edges>> {
+    if !expands(op) {
+        return Ok(None);
+    }
+    let delay_ns = duration_ns_from_attr(op, "delay")?;
+    let edges = match op.name.as_str() {
+        "layer_fully_connected" => {
+            let (in_start, in_end) = range_from_attr(op, "in")?;
+            let (out_start, out_end) = range_from_attr(op, "out")?;
+            let weight = f32_from_attr(op, "weight")?;
+            Edges {
+                remaining: (in_end - in_start + 1) as usize * (out_end - out_start + 1) as usize,
+                inner: Box::new((in_start..=in_end).flat_map(move |pre| {
+                    (out_start..=out_end).map(move |post| (pre, post, weight, delay_ns))
+                })),
+            }
+        }
+        "hyperedge" => {
+            let weight = f32_from_attr(op, "weight")?;
+            let pairs = hyperedge_pairs(op)?;
+            Edges {
+                remaining: pairs.len(),
+                inner: Box::new(pairs.into_iter().map(move |(pre, post)| (pre, post, weight, delay_ns))),
+            }
+        }
+        _ => {
+            let (_, synapses) = wta_synapses(op)?;
+            Edges {
+                remaining: synapses.len(),
+                inner: Box::new(synapses.into_iter().map(move |(pre, post, weight)| (pre, post, weight, delay_ns))),
+            }
+        }
+    };
+    Ok(Some(edges))
+}
+
+/// [`edges`] collected into an [`EdgeList`]
+pub fn expand(op: &Operation) -> Result<Option<EdgeList>> {
+    Ok(edges(op)?.map(|edges| {
+        let mut list = EdgeList::with_capacity(edges.len());
+        for (pre, post, weight, delay_ns) in edges {
+            list.push(pre, post, weight, delay_ns);
+        }
+        list
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::passes::{CanonicalizePass, DefaultingPass, Pass};
+    use crate::{canonicalize, compile_module, compile_with_passes, LoweredProgram};
+    use shnn_ir::{
+        hyperedge_v1, layer_fully_connected_v1, layer_fully_connected_v2, lif_neuron_v1, runtime_simulate_run_v1,
+        stimulus_poisson_v1, structure_wta_v1, synapse_connect_v2, DelayDist, Module, NeuronSet,
+    };
+
+    #[test]
+    fn edge_lists_lower_like_expanded_ops() {
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(layer_fully_connected_v1(0, 3, 4, 6, 2.0, 1.0));
+        m.push(hyperedge_v1(NeuronSet::List(vec![4, 6]), NeuronSet::Range { start: 7, end: 8 }, 1.5, 2.0));
+        m.push(structure_wta_v1(3, 2.0, 0.5));
+        m.push(synapse_connect_v2(8, 0, 1.0, DelayDist::Uniform { min_ms: 1.0, max_ms: 4.0 }, None));
+        m.push(layer_fully_connected_v2(7, 8, 9, 9, 1.0, DelayDist::Uniform { min_ms: 1.0, max_ms: 4.0 }, None));
+        m.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 50.0));
+        m.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(3)));
+
+        let mut defaulted = m.clone();
+        DefaultingPass.run(&mut defaulted).expect("defaulting");
+        let edges = expand(&defaulted.ops[1]).expect("expand").expect("layer expands");
+        assert_eq!(edges.len(), 12);
+        assert_eq!(edges.iter().nth(3), Some((1, 4, 2.0, 1_000_000)));
+        let mut expanded = Module::new();
+        expanded.ops = defaulted.ops[1..4].to_vec();
+        CanonicalizePass.run(&mut expanded).expect("canonicalize");
+        let mut from_edges = Module::new();
+        from_edges.ops = defaulted.ops[1..4]
+            .iter()
+            .flat_map(|op| expand(op).expect("expand").expect("expands").to_ops())
+            .collect();
+        assert_eq!(expanded.ops.len(), 12 + 4 + 9);
+        assert_eq!(expanded.to_text(), from_edges.to_text());
+        assert!(expand(&defaulted.ops[4]).expect("expand").is_none());
+        let mut layer = edges(&defaulted.ops[1]).expect("edges").expect("layer expands");
+        assert_eq!(layer.len(), 12);
+        assert_eq!(layer.next(), Some((0, 4, 2.0, 1_000_000)));
+        assert_eq!(layer.len(), 11);
+
+        // Same network, seeds and spikes as lowering the canonical ops
+        let mut compact = compile_with_passes(&m).expect("compile");
+        let mut canonical = compile_module(&canonicalize(&m).expect("canonicalize")).expect("compile");
+        assert_eq!(compact.seeds, canonical.seeds);
+        let synapses = |program: &LoweredProgram| {
+            let network = program.engine.network();
+            let mut synapses: Vec<_> = network.synapse_connections().into_iter()
+                .map(|(pre, post, weight)| (pre.raw(), post.raw(), weight, network.get_delay(pre, post).expect("delay")))
+                .collect();
+            synapses.sort_by_key(|&(pre, post, _, _)| (pre, post));
+            synapses
+        };
+        assert_eq!(synapses(&compact), synapses(&canonical));
+        assert_eq!(
+            compact.engine.run().expect("run").spike_hash(),
+            canonical.engine.run().expect("run").spike_hash()
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Arrays referenced from NIR by `external("path", dtype, [dims])`
+//!
+//! Verification checks an external attribute's declared dtype and shape
+//! against the op without touching the file; lowering reads it on first use
+//! through an [`ExternalCache`], so one file referenced by several ops is
+//! loaded once. Supported formats are NumPy `.npy` (little-endian, C order)
+//! and `.csv` (numbers separated by commas or whitespace, one row per line).
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use shnn_ir::{AttributeValue, DataType, Operation};
+
+use crate::{bad_attr, CompilerError, Result};
+
+/// A loaded external array, row-major
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalArray {
+    /// Dimensions
+    pub shape: Vec<usize>,
+    /// Elements in row-major order
+    pub data: Vec<f64>,
+}
+
+impl ExternalArray {
+    /// Read `path` and check it holds `dtype` values of `shape`
+    pub fn load(path: &Path, dtype: DataType, shape: &[usize]) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| CompilerError::Message(format!("failed to read external data '{}': {}", path.display(), e)))?;
+        let fail = |reason: String| CompilerError::Message(format!("external data '{}': {}", path.display(), reason));
+        let array = match path.extension().and_then(|e| e.to_str()) {
+            Some("npy") => parse_npy(&bytes, dtype).map_err(fail)?,
+            Some("csv") => parse_csv(&bytes, dtype).map_err(fail)?,
+            _ => return Err(fail("expected a .npy or .csv file".into())),
+        };
+        array.check_shape(shape).map_err(fail)?;
+        Ok(array)
+    }
+
+    fn check_shape(&self, shape: &[usize]) -> std::result::Result<(), String> {
+        let count: usize = shape.iter().product();
+        let matches = match self.shape.len() {
+            // CSV files only know their row count, and 1-D CSV is a single row or column
+            0 => self.data.len() == count,
+            _ => self.shape == shape,
+        };
+        match matches {
+            true => Ok(()),
+            false => Err(format!("holds shape {:?} ({} values), declared {:?}", self.shape, self.data.len(), shape)),
+        }
+    }
+
+    /// Element at a 2-D index
+    pub fn at2(&self, row: usize, col: usize) -> f64 {
+        self.data[row * self.shape.get(1).copied().unwrap_or(1) + col]
+    }
+}
+
+/// External arrays loaded during one lowering, keyed by path
+#[derive(Debug, Default)]
+pub struct ExternalCache {
+    arrays: BTreeMap<String, Arc<ExternalArray>>,
+}
+
+impl ExternalCache {
+    /// Empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The array referenced by `op`'s `key` attr, read on first use
+    pub fn load(&mut self, op: &Operation, key: &str) -> Result<Arc<ExternalArray>> {
+        let (path, dtype, shape) = external_from_attr(op, key)?;
+        if let Some(array) = self.arrays.get(path) {
+            array.check_shape(shape).map_err(|reason| bad_attr(op, key, reason))?;
+            return Ok(array.clone());
+        }
+        let array = Arc::new(ExternalArray::load(Path::new(path), dtype, shape)?);
+        self.arrays.insert(path.to_string(), array.clone());
+        Ok(array)
+    }
+}
+
+/// Path, dtype and declared shape of an external attr
+pub(crate) fn external_from_attr<'a>(op: &'a Operation, key: &str) -> Result<(&'a str, DataType, &'a [usize])> {
+    match op.attrs.get(key) {
+        Some(AttributeValue::ExternalData { path, dtype, shape }) => Ok((path, *dtype, shape)),
+        other => Err(bad_attr(op, key, format!("expected external(\"path\", dtype, [dims]), got {:?}", other))),
+    }
+}
+
+/// Check an external attr declares `shape` (the file is not read)
+pub(crate) fn check_external_shape(op: &Operation, key: &str, shape: &[usize]) -> Result<()> {
+    let (path, _, declared) = external_from_attr(op, key)?;
+    if path.is_empty() {
+        return Err(bad_attr(op, key, "path must not be empty"));
+    }
+    match declared == shape {
+        true => Ok(()),
+        false => Err(bad_attr(op, key, format!("declares shape {:?}, expected {:?}", declared, shape))),
+    }
+}
+
+fn parse_npy(bytes: &[u8], dtype: DataType) -> std::result::Result<ExternalArray, String> {
+    let rest = bytes.strip_prefix(b"\x93NUMPY").ok_or("not a .npy file")?;
+    let (header_len, offset) = match rest.first() {
+        Some(1) if rest.len() >= 4 => (u16::from_le_bytes([rest[2], rest[3]]) as usize, 6 + 4),
+        Some(2 | 3) if rest.len() >= 6 => (u32::from_le_bytes([rest[2], rest[3], rest[4], rest[5]]) as usize, 6 + 6),
+        _ => return Err("unsupported .npy version".into()),
+    };
+    let header = bytes.get(offset..offset + header_len)
+        .and_then(|h| std::str::from_utf8(h).ok())
+        .ok_or("truncated .npy header")?;
+    let field = |name: &str| -> Option<&str> {
+        let start = header.find(&format!("'{}'", name))? + name.len() + 2;
+        let value = header[start..].trim_start().strip_prefix(':')?.trim_start();
+        let end = match value.starts_with('(') {
+            true => value.find(')')? + 1,
+            false => value.find(',').unwrap_or(value.len()),
+        };
+        Some(value[..end].trim())
+    };
+    let descr = field("descr").ok_or("header has no descr")?.trim_matches('\'');
+    let expected = match dtype {
+        DataType::F32 => ["<f4", "|f4"],
+        DataType::F64 => ["<f8", "|f8"],
+        DataType::I32 => ["<i4", "|i4"],
+        DataType::I64 => ["<i8", "|i8"],
+    };
+    if !expected.contains(&descr) {
+        return Err(format!("holds '{}', declared {} (little-endian)", descr, dtype.as_str()));
+    }
+    if field("fortran_order") != Some("False") {
+        return Err("Fortran-order arrays are not supported".into());
+    }
+    let shape = field("shape")
+        .and_then(|s| s.strip_prefix('(')?.strip_suffix(')'))
+        .ok_or("header has no shape")?
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| d.parse::<usize>().map_err(|_| format!("bad shape entry '{}'", d)))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let body = &bytes[offset + header_len..];
+    let count: usize = shape.iter().product();
+    if body.len() < count * dtype.size() {
+        return Err(format!("{} bytes of data, shape {:?} needs {}", body.len(), shape, count * dtype.size()));
+    }
+    let data = body.chunks_exact(dtype.size()).take(count).map(|c| match dtype {
+        DataType::F32 => f32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f64,
+        DataType::I32 => i32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f64,
+        DataType::F64 => f64::from_le_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]]),
+        DataType::I64 => i64::from_le_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]]) as f64,
+    }).collect();
+    // A 0-d array is a scalar; keep it distinguishable from the CSV "unknown shape"
+    let shape = match shape.is_empty() {
+        true => vec![1],
+        false => shape,
+    };
+    Ok(ExternalArray { shape, data })
+}
+
+fn parse_csv(bytes: &[u8], dtype: DataType) -> std::result::Result<ExternalArray, String> {
+    let text = std::str::from_utf8(bytes).map_err(|_| "not UTF-8 text")?;
+    let rows = text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|v| !v.is_empty())
+                .map(|v| parse_csv_value(v, dtype))
+                .collect::<std::result::Result<Vec<_>, _>>()
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let cols = rows.first().map_or(0, Vec::len);
+    if rows.iter().any(|r| r.len() != cols) {
+        return Err("rows have different lengths".into());
+    }
+    // A single row or column is 1-D; its declared shape decides the layout
+    let shape = match (rows.len(), cols) {
+        (1, _) | (_, 1) => Vec::new(),
+        (n, m) => vec![n, m],
+    };
+    Ok(ExternalArray { shape, data: rows.into_iter().flatten().collect() })
+}
+
+fn parse_csv_value(value: &str, dtype: DataType) -> std::result::Result<f64, String> {
+    let bad = || format!("'{}' is not a {} value", value, dtype.as_str());
+    match dtype {
+        DataType::F32 | DataType::F64 => value.parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(bad),
+        DataType::I32 => value.parse::<i32>().map(f64::from).map_err(|_| bad()),
+        DataType::I64 => value.parse::<i64>().map(|v| v as f64).map_err(|_| bad()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn npy(descr: &str, shape: &str, body: &[u8]) -> Vec<u8> {
+        let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(body);
+        bytes
+    }
+
+    #[test]
+    fn test_load_npy_and_csv() {
+        let dir = std::env::temp_dir().join(format!("shnn-external-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let body: Vec<u8> = [0.5f32, 1.0, 1.5, 2.0, 2.5, 3.0].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let path = dir.join("w.npy");
+        std::fs::write(&path, npy("<f4", "(2, 3)", &body)).unwrap();
+        let array = ExternalArray::load(&path, DataType::F32, &[2, 3]).expect("Should load npy");
+        assert_eq!(array.at2(1, 0), 2.0);
+        assert!(ExternalArray::load(&path, DataType::F32, &[3, 2]).is_err());
+        assert!(ExternalArray::load(&path, DataType::F64, &[2, 3]).is_err());
+
+        let path = dir.join("w.csv");
+        std::fs::write(&path, "# weights\n0.5, 1.0, 1.5\n2.0 2.5 3.0\n").unwrap();
+        let csv = ExternalArray::load(&path, DataType::F64, &[2, 3]).expect("Should load csv");
+        assert_eq!(csv.data, array.data);
+        std::fs::write(&path, "1\n2\n3\n").unwrap();
+        assert_eq!(ExternalArray::load(&path, DataType::I32, &[3]).expect("Should load column").data, vec![1.0, 2.0, 3.0]);
+        assert!(ExternalArray::load(&path, DataType::I32, &[4]).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,23 +1,77 @@
-#![doc = "Neuromorphic IR (NIR) compiler — verification, pass pipeline, and lowering to the runtime engine.\n\nPublic responsibilities:\n- Op Registry and schema introspection (list_ops) for dialects/ops/versions and attributes\n- Verification (verify_module): presence, type/unit validation, and semantic bounds\n- Pass pipeline (compile_with_passes): verify → canonicalize/upgrade → lower\n- Lowering (compile_module internal): build network + engine from NIR\n\nKey concepts:\n- Op Registry: Static OpSpec/AttributeSpec array with AttrKind describing attribute kinds/units\n- Verification: Ensures correctness (e.g., lif tau_m > 0; stdp w_min ≤ w_max; valid ranges; dt/duration > 0)\n- Passes: \n  * Canonicalize: expand composite connectivity (e.g., layer_fully_connected → synapse_connect)\n  * UpgradeVersions: scaffold to migrate older op versions to current ones with defaulted attrs\n- Lowering: Produces a runnable SimulationEngine by configuring NetworkBuilder, stimuli, and SimulationParams\n\nIntegration points:\n- shnn-ir: Provides Module/Operation and textual printer/parser\n- shnn-cli: Uses verify_module, list_ops, and compile_with_passes to power CLI commands\n\nSee also:\n- crates/shnn-compiler/src/passes.rs for Pass, PassManager, and built-in passes\n- docs/architecture/NIR_DIALECTS_AND_VERSIONING.md for dialects, ops, and versioning policy\n"]
+#![doc = "Neuromorphic IR (NIR) compiler — verification, pass pipeline, and lowering to the runtime engine.\n\nPublic responsibilities:\n- Op Registry and schema introspection (list_ops) for dialects/ops/versions and attributes\n- Verification (verify_module): presence, type/unit validation, and semantic bounds\n- Pass pipeline (compile_with_passes): verify → canonicalize/upgrade → lower\n- Lowering (compile_module internal): build network + engine from NIR\n\nKey concepts:\n- Op Registry: Static OpSpec/AttributeSpec array with AttrKind describing attribute kinds/units\n- Verification: Ensures correctness (e.g., lif tau_m > 0; stdp w_min ≤ w_max; valid ranges; dt/duration > 0)\n- Passes: \n  * Defaulting: fill omitted optional attributes from AttributeSpec defaults\n  * Canonicalize: expand composite connectivity (e.g., layer_fully_connected → synapse_connect)\n  * UpgradeVersions: scaffold to migrate older op versions to current ones with defaulted attrs\n- Lowering: Produces a runnable SimulationEngine by configuring NetworkBuilder, stimuli, and SimulationParams\n\nIntegration points:\n- shnn-ir: Provides Module/Operation and textual printer/parser\n- shnn-cli: Uses verify_module, list_ops, and compile_with_passes to power CLI commands\n\nSee also:\n- crates/shnn-compiler/src/passes.rs for Pass, PassManager, and built-in passes\n- docs/architecture/NIR_DIALECTS_AND_VERSIONING.md for dialects, ops, and versioning policy\n"]
 
 #![deny(missing_docs)]
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
 
 use shnn_ir::{
-    AttributeValue, DialectKey, Module, Operation, OpVersion,
+    units, AttributeValue, DialectKey, Module, Operation, OpVersion, Quantity,
 };
 use shnn_runtime::{
-    network::{NetworkBuilder, NetworkConfig},
+    brunel::BrunelNetwork,
+    delay::{DelayDistribution, DelaySampler, SubDtDelay},
+    expect::{Expectation, ExpectationOutcome},
+    integrator::{IntegratorKind, SpikeTiming},
+    mask::{MaskMode, NeuronMask},
+    memory::{MemoryEstimate, ProgramShape},
+    modulation::{Interpolation, Modulator},
+    monitor::{RateMonitor, WeightMonitor},
+    noise::OuNoise,
+    network::{NetworkBuilder, NetworkConfig, SNNNetwork},
     simulation::{SimulationEngine, SimulationParams, StimulusPattern, SimulationResult},
-    neuron::LIFParams,
+    neuron::{AdExParams, HHMethod, HHParams, LIFParams, NeuronModel, Refractoriness},
     plasticity::STDPParams,
-    NeuronId, Result as RuntimeResult,
+    profile::Profiler,
+    readout::{ReadoutMonitor, ReadoutRole},
+    reservoir::Reservoir,
+    rng::RngKind,
+    routing::ConnectivityBackend,
+    seed::SeedTree,
+    spatial::{DistanceRule, NeuronPositions, WeightProfile},
+    state::NetworkState,
+    NeuronId, Result as RuntimeResult, Spike,
 };
+use shnn_storage::{FileStore, GenerationId, HypergraphStore, VCSRSnapshot};
 
 /// Public pass framework (no-op scaffolding)
 pub mod passes;
 
+/// Static code generation for embedded targets (shnn-micro)
+pub mod codegen;
+
+/// Fixed-point quantization and float/fixed divergence reports
+pub mod quantize;
+
+/// Errors and non-fatal warnings collected over a module
+pub mod diagnostics;
+
+/// dt checks against time constants and delays, with a suggested dt
+pub mod stability;
+
+/// Linking several modules into one through structure.port@v1 interfaces
+pub mod link;
+
+/// Named populations and the `%name[i]` symbols resolved to their ids
+pub mod symbols;
+
+/// Backends lowering shares its op walk with (runtime engine, shnn-micro tables)
+pub mod target;
+
+/// Connectivity expansion as compact edge lists instead of per-synapse ops
+pub mod edges;
+
+/// Lowered-topology artifacts and their cache keys, for skipping expansion on repeated runs
+pub mod cache;
+
+/// Lazily loaded .npy/.csv arrays referenced by external(...) attributes
+pub mod external;
+
+/// Research-dialect neuron ops lowered to application-supplied models
+pub mod plugins;
+pub use diagnostics::{check_module, Diagnostic, DiagnosticCode, Diagnostics, Severity};
+pub use target::{CompileTarget, CpuTarget, NetworkPlan, RunPlan, StimulusPlan};
+
 /// Compiler error type
 #[derive(thiserror::Error, Debug)]
 pub enum CompilerError {
@@ -92,6 +146,12 @@ pub enum AttrKind {
     RangeU32,
     /// Reference to a neuron by id
     NeuronRef,
+    /// Set of neuron ids: a RangeU32 or a string list "id;id"
+    NeuronSet,
+    /// String attribute
+    String,
+    /// List of per-element values (`[a, b, c]`)
+    List,
 }
 
 impl AttrKind {
@@ -111,6 +171,44 @@ impl AttrKind {
             AttrKind::Weight => "Weight(f32)",
             AttrKind::RangeU32 => "RangeU32",
             AttrKind::NeuronRef => "NeuronRef(%n<u32>)",
+            AttrKind::NeuronSet => "NeuronSet(RangeU32 | \"id;id\")",
+            AttrKind::String => "string",
+            AttrKind::List => "List([..])",
+        }
+    }
+}
+
+/// Default of an optional attribute, in the units of its [`AttrKind`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttrDefault {
+    /// Bool attribute
+    Bool(bool),
+    /// I64 attribute
+    I64(i64),
+    /// Any f32-valued kind (F32, VoltageMv, RateHz, Weight, ...)
+    F32(f32),
+    /// DurationNs or TimeNs attribute (ns)
+    Ns(u64),
+    /// String attribute
+    Str(&'static str),
+}
+
+impl AttrDefault {
+    /// Attribute value for an attribute of `kind`
+    pub fn to_value(self, kind: AttrKind) -> AttributeValue {
+        match (self, kind) {
+            (AttrDefault::Bool(b), _) => AttributeValue::Bool(b),
+            (AttrDefault::I64(v), _) => AttributeValue::I64(v),
+            (AttrDefault::Ns(ns), AttrKind::TimeNs) => AttributeValue::TimeNs(ns),
+            (AttrDefault::Ns(ns), _) => AttributeValue::DurationNs(ns),
+            (AttrDefault::Str(s), _) => AttributeValue::String(s.to_string()),
+            (AttrDefault::F32(v), AttrKind::VoltageMv) => AttributeValue::VoltageMv(v),
+            (AttrDefault::F32(v), AttrKind::ResistanceMohm) => AttributeValue::ResistanceMohm(v),
+            (AttrDefault::F32(v), AttrKind::CapacitanceNf) => AttributeValue::CapacitanceNf(v),
+            (AttrDefault::F32(v), AttrKind::CurrentNa) => AttributeValue::CurrentNa(v),
+            (AttrDefault::F32(v), AttrKind::RateHz) => AttributeValue::RateHz(v),
+            (AttrDefault::F32(v), AttrKind::Weight) => AttributeValue::Weight(v),
+            (AttrDefault::F32(v), _) => AttributeValue::F32(v),
         }
     }
 }
@@ -124,6 +222,8 @@ pub struct AttributeSpec {
     pub kind: AttrKind,
     /// Required attribute (true) or optional (false)
     pub required: bool,
+    /// Value of an omitted optional attribute, filled in by [`passes::DefaultingPass`]
+    pub default: Option<AttrDefault>,
     /// Short doc string
     pub doc: &'static str,
 }
@@ -148,13 +248,71 @@ static OPS: &[OpSpec] = &[
         name: "lif",
         version: 1,
         attrs: &[
-            AttributeSpec { name: "tau_m", kind: AttrKind::DurationNs, required: true, doc: "Membrane time constant (ns)" },
-            AttributeSpec { name: "v_rest", kind: AttrKind::VoltageMv, required: true, doc: "Resting potential (mV)" },
-            AttributeSpec { name: "v_reset", kind: AttrKind::VoltageMv, required: true, doc: "Reset potential (mV)" },
-            AttributeSpec { name: "v_thresh", kind: AttrKind::VoltageMv, required: true, doc: "Threshold potential (mV)" },
-            AttributeSpec { name: "t_refrac", kind: AttrKind::DurationNs, required: true, doc: "Refractory period (ns)" },
-            AttributeSpec { name: "r_m", kind: AttrKind::ResistanceMohm, required: true, doc: "Membrane resistance (MΩ)" },
-            AttributeSpec { name: "c_m", kind: AttrKind::CapacitanceNf, required: true, doc: "Capacitance (nF)" },
+            AttributeSpec { name: "tau_m", kind: AttrKind::DurationNs, required: true, default: None, doc: "Membrane time constant (ns)" },
+            AttributeSpec { name: "v_rest", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Resting potential (mV)" },
+            AttributeSpec { name: "v_reset", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Reset potential (mV)" },
+            AttributeSpec { name: "v_thresh", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Threshold potential (mV)" },
+            AttributeSpec { name: "t_refrac", kind: AttrKind::DurationNs, required: true, default: None, doc: "Refractory period (ns)" },
+            AttributeSpec { name: "r_m", kind: AttrKind::ResistanceMohm, required: true, default: None, doc: "Membrane resistance (MΩ)" },
+            AttributeSpec { name: "c_m", kind: AttrKind::CapacitanceNf, required: true, default: None, doc: "Capacitance (nF)" },
+            AttributeSpec { name: "refractory", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("hold")), doc: "Refractory policy: hold (default) | ignore_input | relative" },
+            AttributeSpec { name: "refrac_jump", kind: AttrKind::VoltageMv, required: false, default: Some(AttrDefault::F32(0.0)), doc: "Threshold increase per spike under relative (mV, default 0)" },
+            AttributeSpec { name: "tau_refrac", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(10_000_000)), doc: "Decay time constant of the threshold increase (ns, default 10 ms)" },
+        ],
+    },
+    OpSpec {
+        dialect: "neuron",
+        name: "alif",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "tau_m", kind: AttrKind::DurationNs, required: true, default: None, doc: "Membrane time constant (ns)" },
+            AttributeSpec { name: "v_rest", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Resting potential (mV)" },
+            AttributeSpec { name: "v_reset", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Reset potential (mV)" },
+            AttributeSpec { name: "v_thresh", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Baseline threshold potential (mV)" },
+            AttributeSpec { name: "t_refrac", kind: AttrKind::DurationNs, required: true, default: None, doc: "Refractory period (ns)" },
+            AttributeSpec { name: "r_m", kind: AttrKind::ResistanceMohm, required: true, default: None, doc: "Membrane resistance (MΩ)" },
+            AttributeSpec { name: "c_m", kind: AttrKind::CapacitanceNf, required: true, default: None, doc: "Capacitance (nF)" },
+            AttributeSpec { name: "beta", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Threshold increase per spike (mV)" },
+            AttributeSpec { name: "tau_adapt", kind: AttrKind::DurationNs, required: true, default: None, doc: "Threshold adaptation decay time constant (ns)" },
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: false, default: None, doc: "Inclusive population using this model (default: all neurons)" },
+        ],
+    },
+    OpSpec {
+        dialect: "neuron",
+        name: "adex",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "c_m", kind: AttrKind::CapacitanceNf, required: true, default: None, doc: "Capacitance (nF)" },
+            AttributeSpec { name: "g_l", kind: AttrKind::F32, required: true, default: None, doc: "Leak conductance (nS)" },
+            AttributeSpec { name: "e_l", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Leak reversal potential (mV)" },
+            AttributeSpec { name: "v_t", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Threshold slope-factor potential (mV)" },
+            AttributeSpec { name: "delta_t", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Slope factor (mV)" },
+            AttributeSpec { name: "a", kind: AttrKind::F32, required: true, default: None, doc: "Subthreshold adaptation conductance (nS)" },
+            AttributeSpec { name: "tau_w", kind: AttrKind::DurationNs, required: true, default: None, doc: "Adaptation time constant (ns)" },
+            AttributeSpec { name: "b", kind: AttrKind::CurrentNa, required: true, default: None, doc: "Spike-triggered adaptation increment (nA)" },
+            AttributeSpec { name: "v_reset", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Reset potential (mV)" },
+            AttributeSpec { name: "v_peak", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Spike detection potential (mV)" },
+            AttributeSpec { name: "t_refrac", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(0)), doc: "Refractory period (ns, default 0)" },
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: false, default: None, doc: "Inclusive population using this model (default: all neurons)" },
+        ],
+    },
+    OpSpec {
+        dialect: "neuron",
+        name: "hh",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive population simulated with HH dynamics" },
+            AttributeSpec { name: "c_m", kind: AttrKind::CapacitanceNf, required: true, default: None, doc: "Capacitance (nF)" },
+            AttributeSpec { name: "g_na", kind: AttrKind::F32, required: true, default: None, doc: "Maximal sodium conductance (µS)" },
+            AttributeSpec { name: "g_k", kind: AttrKind::F32, required: true, default: None, doc: "Maximal potassium conductance (µS)" },
+            AttributeSpec { name: "g_l", kind: AttrKind::F32, required: true, default: None, doc: "Leak conductance (µS)" },
+            AttributeSpec { name: "e_na", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Sodium reversal potential (mV)" },
+            AttributeSpec { name: "e_k", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Potassium reversal potential (mV)" },
+            AttributeSpec { name: "e_l", kind: AttrKind::VoltageMv, required: true, default: None, doc: "Leak reversal potential (mV)" },
+            AttributeSpec { name: "v_init", kind: AttrKind::VoltageMv, required: false, default: Some(AttrDefault::F32(-65.0)), doc: "Initial potential (mV, default -65)" },
+            AttributeSpec { name: "v_spike", kind: AttrKind::VoltageMv, required: false, default: Some(AttrDefault::F32(0.0)), doc: "Spike detection potential (mV, default 0)" },
+            AttributeSpec { name: "substep", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(10_000)), doc: "Maximum sub-step within dt (ns, default 10000)" },
+            AttributeSpec { name: "method", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("rk4")), doc: "rk4 (default) | exponential" },
         ],
     },
     OpSpec {
@@ -162,34 +320,106 @@ static OPS: &[OpSpec] = &[
         name: "stdp",
         version: 1,
         attrs: &[
-            AttributeSpec { name: "a_plus", kind: AttrKind::F32, required: true, doc: "Potentiation amplitude" },
-            AttributeSpec { name: "a_minus", kind: AttrKind::F32, required: true, doc: "Depression amplitude" },
-            AttributeSpec { name: "tau_plus", kind: AttrKind::DurationNs, required: true, doc: "Potentiation time constant (ns)" },
-            AttributeSpec { name: "tau_minus", kind: AttrKind::DurationNs, required: true, doc: "Depression time constant (ns)" },
-            AttributeSpec { name: "w_min", kind: AttrKind::F32, required: true, doc: "Minimum weight" },
-            AttributeSpec { name: "w_max", kind: AttrKind::F32, required: true, doc: "Maximum weight" },
+            AttributeSpec { name: "a_plus", kind: AttrKind::F32, required: true, default: None, doc: "Potentiation amplitude" },
+            AttributeSpec { name: "a_minus", kind: AttrKind::F32, required: true, default: None, doc: "Depression amplitude" },
+            AttributeSpec { name: "tau_plus", kind: AttrKind::DurationNs, required: true, default: None, doc: "Potentiation time constant (ns)" },
+            AttributeSpec { name: "tau_minus", kind: AttrKind::DurationNs, required: true, default: None, doc: "Depression time constant (ns)" },
+            AttributeSpec { name: "w_min", kind: AttrKind::F32, required: true, default: None, doc: "Minimum weight" },
+            AttributeSpec { name: "w_max", kind: AttrKind::F32, required: true, default: None, doc: "Maximum weight" },
+        ],
+    },
+    OpSpec {
+        dialect: "connectivity",
+        name: "layer_fully_connected",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "in", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive input neuron range" },
+            AttributeSpec { name: "out", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive output neuron range" },
+            AttributeSpec { name: "weight", kind: AttrKind::Weight, required: true, default: None, doc: "Initial weight (unitless)" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: true, default: None, doc: "Synaptic delay (ns)" },
         ],
     },
     OpSpec {
         dialect: "connectivity",
         name: "layer_fully_connected",
+        version: 2,
+        attrs: &[
+            AttributeSpec { name: "in", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive input neuron range" },
+            AttributeSpec { name: "out", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive output neuron range" },
+            AttributeSpec { name: "weight", kind: AttrKind::Weight, required: true, default: None, doc: "Initial weight (unitless), or an [in, out] matrix external(\"w.npy\", f32, [n_in, n_out])" },
+            AttributeSpec { name: "delay_dist", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("constant")), doc: "constant (default) | uniform | normal | gamma" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: false, default: None, doc: "Delay for constant, mean for normal (ns)" },
+            AttributeSpec { name: "delay_min", kind: AttrKind::DurationNs, required: false, default: None, doc: "Lower bound for uniform, clamp for normal (ns)" },
+            AttributeSpec { name: "delay_max", kind: AttrKind::DurationNs, required: false, default: None, doc: "Upper bound for uniform (ns)" },
+            AttributeSpec { name: "delay_std", kind: AttrKind::DurationNs, required: false, default: None, doc: "Jitter standard deviation for normal (ns)" },
+            AttributeSpec { name: "delay_shape", kind: AttrKind::F32, required: false, default: None, doc: "Shape k for gamma" },
+            AttributeSpec { name: "delay_scale", kind: AttrKind::DurationNs, required: false, default: None, doc: "Scale θ for gamma (ns)" },
+            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, default: None, doc: "Delay sampling seed (default: derived from the simulate.run seed)" },
+        ],
+    },
+    OpSpec {
+        dialect: "connectivity",
+        name: "synapse_connect",
         version: 1,
         attrs: &[
-            AttributeSpec { name: "in", kind: AttrKind::RangeU32, required: true, doc: "Inclusive input neuron range" },
-            AttributeSpec { name: "out", kind: AttrKind::RangeU32, required: true, doc: "Inclusive output neuron range" },
-            AttributeSpec { name: "weight", kind: AttrKind::Weight, required: true, doc: "Initial weight (unitless)" },
-            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: true, doc: "Synaptic delay (ns)" },
+            AttributeSpec { name: "pre", kind: AttrKind::NeuronRef, required: true, default: None, doc: "Pre-synaptic neuron id" },
+            AttributeSpec { name: "post", kind: AttrKind::NeuronRef, required: true, default: None, doc: "Post-synaptic neuron id" },
+            AttributeSpec { name: "weight", kind: AttrKind::Weight, required: true, default: None, doc: "Synaptic weight (unitless)" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: true, default: None, doc: "Synaptic delay (ns)" },
         ],
     },
     OpSpec {
         dialect: "connectivity",
         name: "synapse_connect",
+        version: 2,
+        attrs: &[
+            AttributeSpec { name: "pre", kind: AttrKind::NeuronRef, required: true, default: None, doc: "Pre-synaptic neuron id" },
+            AttributeSpec { name: "post", kind: AttrKind::NeuronRef, required: true, default: None, doc: "Post-synaptic neuron id" },
+            AttributeSpec { name: "weight", kind: AttrKind::Weight, required: true, default: None, doc: "Synaptic weight (unitless)" },
+            AttributeSpec { name: "delay_dist", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("constant")), doc: "constant (default) | uniform | normal | gamma" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: false, default: None, doc: "Delay for constant, mean for normal (ns)" },
+            AttributeSpec { name: "delay_min", kind: AttrKind::DurationNs, required: false, default: None, doc: "Lower bound for uniform, clamp for normal (ns)" },
+            AttributeSpec { name: "delay_max", kind: AttrKind::DurationNs, required: false, default: None, doc: "Upper bound for uniform (ns)" },
+            AttributeSpec { name: "delay_std", kind: AttrKind::DurationNs, required: false, default: None, doc: "Jitter standard deviation for normal (ns)" },
+            AttributeSpec { name: "delay_shape", kind: AttrKind::F32, required: false, default: None, doc: "Shape k for gamma" },
+            AttributeSpec { name: "delay_scale", kind: AttrKind::DurationNs, required: false, default: None, doc: "Scale θ for gamma (ns)" },
+            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, default: None, doc: "Delay sampling seed (default: derived from the simulate.run seed)" },
+        ],
+    },
+    OpSpec {
+        dialect: "connectivity",
+        name: "hyperedge",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "sources", kind: AttrKind::NeuronSet, required: true, default: None, doc: "Source neurons" },
+            AttributeSpec { name: "targets", kind: AttrKind::NeuronSet, required: true, default: None, doc: "Target neurons (every source drives every target, self-pairs skipped)" },
+            AttributeSpec { name: "weight", kind: AttrKind::Weight, required: true, default: None, doc: "Weight of each expanded synapse (unitless)" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: true, default: None, doc: "Synaptic delay (ns)" },
+        ],
+    },
+    OpSpec {
+        dialect: "connectivity",
+        name: "distance_gaussian",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "sources", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive source range (needs structure.positions)" },
+            AttributeSpec { name: "targets", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive target range (needs structure.positions, self-pairs skipped)" },
+            AttributeSpec { name: "sigma", kind: AttrKind::F32, required: true, default: None, doc: "Gaussian length scale, in position units" },
+            AttributeSpec { name: "p_max", kind: AttrKind::F32, required: true, default: None, doc: "Connection probability at zero distance, in [0, 1]" },
+            AttributeSpec { name: "weight", kind: AttrKind::Weight, required: true, default: None, doc: "Peak synaptic weight (unitless)" },
+            AttributeSpec { name: "weight_profile", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("constant")), doc: "constant (default) | gaussian (weight scaled by exp(-d²/2σ²))" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: true, default: None, doc: "Synaptic delay (ns)" },
+            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, default: None, doc: "Connection sampling seed (default: derived from the simulate.run seed)" },
+        ],
+    },
+    OpSpec {
+        dialect: "connectivity",
+        name: "from_snapshot",
         version: 1,
         attrs: &[
-            AttributeSpec { name: "pre", kind: AttrKind::NeuronRef, required: true, doc: "Pre-synaptic neuron id" },
-            AttributeSpec { name: "post", kind: AttrKind::NeuronRef, required: true, doc: "Post-synaptic neuron id" },
-            AttributeSpec { name: "weight", kind: AttrKind::Weight, required: true, doc: "Synaptic weight (unitless)" },
-            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: true, doc: "Synaptic delay (ns)" },
+            AttributeSpec { name: "path", kind: AttrKind::String, required: true, default: None, doc: "VCSR file, or a hypergraph store directory of gen_*.vcsr files" },
+            AttributeSpec { name: "generation", kind: AttrKind::I64, required: false, default: None, doc: "Store generation (default: latest); checked against a file's header" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(1_000_000)), doc: "Delay of every synapse, which VCSR does not store (ns, default 1 ms)" },
         ],
     },
     OpSpec {
@@ -197,11 +427,23 @@ static OPS: &[OpSpec] = &[
         name: "poisson",
         version: 1,
         attrs: &[
-            AttributeSpec { name: "neuron", kind: AttrKind::NeuronRef, required: true, doc: "Target neuron id" },
-            AttributeSpec { name: "rate", kind: AttrKind::RateHz, required: true, doc: "Firing rate (Hz)" },
-            AttributeSpec { name: "amplitude", kind: AttrKind::CurrentNa, required: true, doc: "Current per spike (nA)" },
-            AttributeSpec { name: "start", kind: AttrKind::TimeNs, required: true, doc: "Start time (ns)" },
-            AttributeSpec { name: "duration", kind: AttrKind::DurationNs, required: true, doc: "Duration (ns)" },
+            AttributeSpec { name: "neuron", kind: AttrKind::NeuronRef, required: true, default: None, doc: "Target neuron id" },
+            AttributeSpec { name: "rate", kind: AttrKind::RateHz, required: true, default: None, doc: "Firing rate (Hz)" },
+            AttributeSpec { name: "amplitude", kind: AttrKind::CurrentNa, required: true, default: None, doc: "Current per spike (nA)" },
+            AttributeSpec { name: "start", kind: AttrKind::TimeNs, required: true, default: None, doc: "Start time (ns)" },
+            AttributeSpec { name: "duration", kind: AttrKind::DurationNs, required: true, default: None, doc: "Duration (ns)" },
+        ],
+    },
+    OpSpec {
+        dialect: "stimulus",
+        name: "ou_noise",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive target range (independent process per neuron)" },
+            AttributeSpec { name: "mu", kind: AttrKind::CurrentNa, required: true, default: None, doc: "Mean current (nA)" },
+            AttributeSpec { name: "sigma", kind: AttrKind::CurrentNa, required: true, default: None, doc: "Stationary standard deviation (nA)" },
+            AttributeSpec { name: "tau", kind: AttrKind::DurationNs, required: true, default: None, doc: "Correlation time (ns)" },
+            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, default: None, doc: "RNG seed (default: derived from the simulate.run seed)" },
         ],
     },
     OpSpec {
@@ -209,10 +451,191 @@ static OPS: &[OpSpec] = &[
         name: "simulate.run",
         version: 1,
         attrs: &[
-            AttributeSpec { name: "dt", kind: AttrKind::DurationNs, required: true, doc: "Timestep (ns)" },
-            AttributeSpec { name: "duration", kind: AttrKind::DurationNs, required: true, doc: "Total duration (ns)" },
-            AttributeSpec { name: "record_potentials", kind: AttrKind::Bool, required: true, doc: "Record membrane potentials" },
-            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, doc: "Optional RNG seed" },
+            AttributeSpec { name: "dt", kind: AttrKind::DurationNs, required: true, default: None, doc: "Timestep (ns)" },
+            AttributeSpec { name: "duration", kind: AttrKind::DurationNs, required: true, default: None, doc: "Total duration (ns)" },
+            AttributeSpec { name: "record_potentials", kind: AttrKind::Bool, required: true, default: None, doc: "Record membrane potentials" },
+            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, default: None, doc: "Optional RNG seed" },
+            AttributeSpec { name: "integrator", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("euler")), doc: "euler (default) | exponential_euler | rk4" },
+            AttributeSpec { name: "realtime", kind: AttrKind::Bool, required: false, default: Some(AttrDefault::Bool(false)), doc: "Pace steps against wall-clock time (default false)" },
+            AttributeSpec { name: "record_weight_updates", kind: AttrKind::Bool, required: false, default: Some(AttrDefault::Bool(false)), doc: "Record every applied plasticity weight change (default false)" },
+            AttributeSpec { name: "rng", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("sequential")), doc: "sequential (default) | philox (counter-based, thread-count independent)" },
+            AttributeSpec { name: "backend", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("auto")), doc: "Spike routing index: auto (default, by density) | graph | matrix | sparse | hypergraph" },
+            AttributeSpec { name: "sub_dt_delay", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("next_step")), doc: "Delays below dt: next_step (default) | interpolate (split input across two steps by arrival time) | reject (verification error)" },
+            AttributeSpec { name: "spike_timing", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("grid")), doc: "LIF spike times: grid (default, end of step) | linear | exponential (threshold crossing within the step)" },
+        ],
+    },
+    OpSpec {
+        dialect: "runtime",
+        name: "apply_mask",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "path", kind: AttrKind::String, required: true, default: None, doc: "VMSK mask file (bit i = neuron i)" },
+            AttributeSpec { name: "mode", kind: AttrKind::String, required: true, default: None, doc: "activity (gate spiking) | plasticity (gate weight updates)" },
+        ],
+    },
+    OpSpec {
+        dialect: "runtime",
+        name: "population_dt",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive population integrated with a finer step" },
+            AttributeSpec { name: "substeps", kind: AttrKind::I64, required: true, default: None, doc: "Population dt = global dt / substeps (spikes exchanged on the global grid)" },
+        ],
+    },
+    OpSpec {
+        dialect: "runtime",
+        name: "monitor_rate",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive population whose mean rate is binned" },
+            AttributeSpec { name: "bin", kind: AttrKind::DurationNs, required: true, default: None, doc: "Bin width (ns)" },
+        ],
+    },
+    OpSpec {
+        dialect: "runtime",
+        name: "monitor_weights",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "pairs", kind: AttrKind::String, required: false, default: None, doc: "Synapses \"pre:post;pre:post\" (exclusive with sample)" },
+            AttributeSpec { name: "sample", kind: AttrKind::I64, required: false, default: None, doc: "Number of synapses drawn at random (exclusive with pairs)" },
+            AttributeSpec { name: "interval", kind: AttrKind::DurationNs, required: true, default: None, doc: "Sampling interval (ns)" },
+            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, default: None, doc: "Selection seed for sample (default: derived from the simulate.run seed)" },
+        ],
+    },
+    OpSpec {
+        dialect: "runtime",
+        name: "readout",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: false, default: None, doc: "Inclusive population read (default: the first structure.reservoir's outputs, or its inputs for role = target)" },
+            AttributeSpec { name: "role", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("state")), doc: "state (default, readout features) | target (values the readout is trained to reproduce)" },
+            AttributeSpec { name: "tau", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(20_000_000)), doc: "Spike trace decay time constant (ns, default 20 ms)" },
+            AttributeSpec { name: "interval", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(5_000_000)), doc: "Sampling interval (ns, default 5 ms)" },
+        ],
+    },
+    OpSpec {
+        dialect: "runtime",
+        name: "load_state",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "path", kind: AttrKind::String, required: true, default: None, doc: "State checkpoint written by runtime.save_state@v1" },
+        ],
+    },
+    OpSpec {
+        dialect: "runtime",
+        name: "save_state",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "path", kind: AttrKind::String, required: true, default: None, doc: "Checkpoint file to write" },
+            AttributeSpec { name: "at", kind: AttrKind::TimeNs, required: true, default: None, doc: "Simulation time of the checkpoint (ns, <= duration)" },
+        ],
+    },
+    OpSpec {
+        dialect: "test",
+        name: "expect_rate",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive population" },
+            AttributeSpec { name: "min_hz", kind: AttrKind::RateHz, required: false, default: None, doc: "Lower bound on the mean per-neuron rate over the run (Hz)" },
+            AttributeSpec { name: "max_hz", kind: AttrKind::RateHz, required: false, default: None, doc: "Upper bound on the mean per-neuron rate over the run (Hz)" },
+        ],
+    },
+    OpSpec {
+        dialect: "test",
+        name: "expect_spike_count",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive population" },
+            AttributeSpec { name: "min", kind: AttrKind::I64, required: false, default: None, doc: "Lower bound on the total spike count" },
+            AttributeSpec { name: "max", kind: AttrKind::I64, required: false, default: None, doc: "Upper bound on the total spike count" },
+        ],
+    },
+    OpSpec {
+        dialect: "modulation",
+        name: "scalar",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "schedule", kind: AttrKind::String, required: false, default: None, doc: "Piecewise levels \"t_ns:level;t_ns:level\" (exclusive with source)" },
+            AttributeSpec { name: "interpolation", kind: AttrKind::String, required: false, default: Some(AttrDefault::Str("step")), doc: "step (default) | linear" },
+            AttributeSpec { name: "source", kind: AttrKind::RangeU32, required: false, default: None, doc: "Population whose rate drives the level (exclusive with schedule)" },
+            AttributeSpec { name: "window", kind: AttrKind::DurationNs, required: false, default: None, doc: "Rate estimation window (ns), required with source" },
+            AttributeSpec { name: "gain", kind: AttrKind::F32, required: false, default: Some(AttrDefault::F32(1.0)), doc: "Level per Hz of population rate (default 1)" },
+            AttributeSpec { name: "baseline", kind: AttrKind::F32, required: false, default: Some(AttrDefault::F32(0.0)), doc: "Level at zero rate (default 0)" },
+        ],
+    },
+    OpSpec {
+        dialect: "structure",
+        name: "positions",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive range being placed" },
+            AttributeSpec { name: "coords", kind: AttrKind::String, required: false, default: None, doc: "Explicit positions \"x:y[:z];x:y[:z]\", one per neuron (exclusive with nx)" },
+            AttributeSpec { name: "nx", kind: AttrKind::I64, required: false, default: None, doc: "Grid columns (x varies fastest; exclusive with coords)" },
+            AttributeSpec { name: "ny", kind: AttrKind::I64, required: false, default: Some(AttrDefault::I64(1)), doc: "Grid rows (default 1)" },
+            AttributeSpec { name: "nz", kind: AttrKind::I64, required: false, default: Some(AttrDefault::I64(1)), doc: "Grid layers (default 1)" },
+            AttributeSpec { name: "spacing", kind: AttrKind::F32, required: false, default: Some(AttrDefault::F32(1.0)), doc: "Grid pitch in position units (default 1)" },
+        ],
+    },
+    OpSpec {
+        dialect: "structure",
+        name: "ei_network",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "n_exc", kind: AttrKind::I64, required: true, default: None, doc: "Excitatory neurons (ids 0..n_exc)" },
+            AttributeSpec { name: "n_inh", kind: AttrKind::I64, required: true, default: None, doc: "Inhibitory neurons (ids after the excitatory ones)" },
+            AttributeSpec { name: "p", kind: AttrKind::F32, required: true, default: None, doc: "Connection probability in (0, 1]; every neuron gets p·n_exc excitatory and p·n_inh inhibitory inputs" },
+            AttributeSpec { name: "g", kind: AttrKind::F32, required: true, default: None, doc: "Inhibitory PSP relative to the excitatory one" },
+            AttributeSpec { name: "nu_ext", kind: AttrKind::RateHz, required: true, default: None, doc: "Rate of each of a neuron's p·n_exc external Poisson inputs (Hz)" },
+            AttributeSpec { name: "j", kind: AttrKind::VoltageMv, required: false, default: Some(AttrDefault::F32(0.1)), doc: "Excitatory PSP (mV, default 0.1)" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(1_500_000)), doc: "Synaptic delay (ns, default 1.5 ms)" },
+            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, default: None, doc: "Connection sampling seed (default: derived from the simulate.run seed)" },
+        ],
+    },
+    OpSpec {
+        dialect: "structure",
+        name: "reservoir",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive reservoir population (recurrently connected, self-pairs skipped)" },
+            AttributeSpec { name: "inputs", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive input population driving the reservoir (disjoint from it)" },
+            AttributeSpec { name: "outputs", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive readout population (within the reservoir)" },
+            AttributeSpec { name: "p", kind: AttrKind::F32, required: false, default: Some(AttrDefault::F32(0.1)), doc: "Recurrent connection probability in (0, 1] (default 0.1)" },
+            AttributeSpec { name: "radius", kind: AttrKind::F32, required: true, default: None, doc: "Spectral radius of the recurrent weight matrix; weights are drawn with std radius/sqrt(N·p)" },
+            AttributeSpec { name: "input_p", kind: AttrKind::F32, required: false, default: Some(AttrDefault::F32(0.2)), doc: "Input connection probability in [0, 1] (default 0.2)" },
+            AttributeSpec { name: "input_weight", kind: AttrKind::Weight, required: true, default: None, doc: "Weight of every input synapse (unitless)" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(1_000_000)), doc: "Synaptic delay (ns, default 1 ms)" },
+            AttributeSpec { name: "seed", kind: AttrKind::I64, required: false, default: None, doc: "Connection sampling seed (default: derived from the simulate.run seed)" },
+        ],
+    },
+    OpSpec {
+        dialect: "structure",
+        name: "wta",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "n", kind: AttrKind::I64, required: true, default: None, doc: "Competing neurons (>= 2)" },
+            AttributeSpec { name: "start", kind: AttrKind::I64, required: false, default: None, doc: "First neuron id (default 0); the circuit is start..start+n-1" },
+            AttributeSpec { name: "inhibition_weight", kind: AttrKind::Weight, required: true, default: None, doc: "Lateral inhibition magnitude (>= 0); every neuron drives every other with its negation" },
+            AttributeSpec { name: "self_excitation", kind: AttrKind::Weight, required: false, default: Some(AttrDefault::F32(0.0)), doc: "Weight of each neuron's synapse onto itself (>= 0, default 0 = none)" },
+            AttributeSpec { name: "delay", kind: AttrKind::DurationNs, required: false, default: Some(AttrDefault::Ns(1_000_000)), doc: "Synaptic delay (ns, default 1 ms)" },
+        ],
+    },
+    OpSpec {
+        dialect: "structure",
+        name: "port",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "name", kind: AttrKind::String, required: true, default: None, doc: "Interface name modules are linked by (see `snn nir link`)" },
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: true, default: None, doc: "Inclusive range exported, or aliased onto the owning module's range when linked" },
+        ],
+    },
+    OpSpec {
+        dialect: "structure",
+        name: "population",
+        version: 1,
+        attrs: &[
+            AttributeSpec { name: "name", kind: AttrKind::String, required: true, default: None, doc: "Name ops refer to as %name, %name[i] or %name[a..b] (letters, digits, '_', '.'-separated namespaces)" },
+            AttributeSpec { name: "size", kind: AttrKind::I64, required: true, default: None, doc: "Neurons in the population (>= 1)" },
+            AttributeSpec { name: "neurons", kind: AttrKind::RangeU32, required: false, default: None, doc: "Inclusive id range (default: laid out after earlier populations and raw ids)" },
         ],
     },
 ];
@@ -222,9 +645,28 @@ pub fn list_ops() -> &'static [OpSpec] {
     OPS
 }
 
+/// Registry entry for `op`'s dialect, name and version
+pub fn op_spec(op: &Operation) -> Option<&'static OpSpec> {
+    OPS.iter().find(|spec| {
+        spec.version == op.version.0 && spec.name == op.name && spec.dialect == op.dialect.to_string()
+    })
+}
+
 /// Verify that a NIR module is semantically valid (v0 minimal checks).
 /// Ensures required attributes exist and have acceptable types/units.
 pub fn verify_module(module: &Module) -> Result<()> {
+    if symbols::has_symbols(module) {
+        return verify_module(&symbols::resolve_symbols(module)?);
+    }
+    // Models whose forward-Euler stability depends on the simulation timestep
+    let mut adex_ops: Vec<(&Operation, AdExParams)> = Vec::new();
+    let mut substep_ops: Vec<(&Operation, u32)> = Vec::new();
+    let mut dt_ns: Option<u64> = None;
+    let mut sub_dt_delay = SubDtDelay::default();
+    // Distance-dependent ops are checked against the complete layout
+    let mut layout = NeuronPositions::new();
+    let mut distance_ops: Vec<&Operation> = Vec::new();
+
     for op in &module.ops {
         match (&op.dialect, op.name.as_str(), op.version) {
             (DialectKey::Neuron, "lif", OpVersion(1)) => {
@@ -265,6 +707,7 @@ pub fn verify_module(module: &Module) -> Result<()> {
                         reason: "must be > 0 nF".into(),
                     });
                 }
+                refractoriness_from_attrs(op)?;
             }
             (DialectKey::Plasticity, "stdp", OpVersion(1)) => {
                 let _ = f32_from_attr(op, "a_plus")?;
@@ -333,6 +776,84 @@ pub fn verify_module(module: &Module) -> Result<()> {
                 let _ = duration_ns_from_attr(op, "delay")?;
                 // Self-connections allowed; no further semantic checks here.
             }
+            (DialectKey::Connectivity, "layer_fully_connected", OpVersion(2)) => {
+                let (in_start, in_end) = range_from_attr(op, "in")?;
+                let (out_start, out_end) = range_from_attr(op, "out")?;
+                let _ = delay_sampler_from_attrs(op, 0)?;
+                if in_start > in_end {
+                    return Err(bad_attr(op, "in", "range must satisfy start <= end"));
+                }
+                if out_start > out_end {
+                    return Err(bad_attr(op, "out", "range must satisfy start <= end"));
+                }
+                match op.attrs.get("weight") {
+                    Some(AttributeValue::ExternalData { .. }) => {
+                        let shape = [(in_end - in_start) as usize + 1, (out_end - out_start) as usize + 1];
+                        external::check_external_shape(op, "weight", &shape)?;
+                    }
+                    _ => {
+                        let _ = f32_from_attr(op, "weight")?;
+                    }
+                }
+            }
+            (DialectKey::Connectivity, "synapse_connect", OpVersion(2)) => {
+                let _ = neuron_ref_from_attr(op, "pre")?;
+                let _ = neuron_ref_from_attr(op, "post")?;
+                let _ = f32_from_attr(op, "weight")?;
+                let _ = delay_sampler_from_attrs(op, 0)?;
+            }
+            (DialectKey::Connectivity, "hyperedge", OpVersion(1)) => {
+                let _ = hyperedge_pairs(op)?;
+                let _ = f32_from_attr(op, "weight")?;
+                let _ = duration_ns_from_attr(op, "delay")?;
+            }
+            (DialectKey::Connectivity, "from_snapshot", OpVersion(1)) => {
+                // The snapshot itself is read at lowering
+                if string_from_attr(op, "path")?.is_empty() {
+                    return Err(bad_attr(op, "path", "must not be empty"));
+                }
+                if i64_opt_from_attr(op, "generation")?.is_some_and(|g| g < 0) {
+                    return Err(bad_attr(op, "generation", "must be >= 0"));
+                }
+                let _ = duration_ns_from_attr(op, "delay")?;
+            }
+            (DialectKey::Connectivity, "distance_gaussian", OpVersion(1)) => {
+                let _ = distance_rule_from_attrs(op)?;
+                let _ = duration_ns_from_attr(op, "delay")?;
+                let _ = i64_opt_from_attr(op, "seed")?;
+                distance_ops.push(op);
+            }
+            (DialectKey::Structure, "positions", OpVersion(1)) => {
+                layout.extend(positions_from_attrs(op)?);
+            }
+            (DialectKey::Structure, "reservoir", OpVersion(1)) => {
+                let _ = reservoir_from_attrs(op)?;
+                let _ = duration_ns_from_attr(op, "delay")?;
+                let _ = i64_opt_from_attr(op, "seed")?;
+            }
+            (DialectKey::Runtime, "readout", OpVersion(1)) => {
+                let _ = readout_from_attrs(op, first_reservoir(module))?;
+            }
+            (DialectKey::Structure, "wta", OpVersion(1)) => {
+                let _ = wta_synapses(op)?;
+                let _ = duration_ns_from_attr(op, "delay")?;
+            }
+            (DialectKey::Structure, "port", OpVersion(1)) => {
+                let _ = port_from_attrs(op)?;
+            }
+            (DialectKey::Structure, "population", OpVersion(1)) => {
+                let _ = population_from_attrs(op)?;
+            }
+            (DialectKey::Structure, "ei_network", OpVersion(1)) => {
+                let _ = brunel_from_attrs(op)?;
+                let _ = duration_ns_from_attr(op, "delay")?;
+                let _ = i64_opt_from_attr(op, "seed")?;
+                if !module.ops.iter().any(|op| op.dialect == DialectKey::Runtime && op.name == "simulate.run") {
+                    return Err(CompilerError::Message(
+                        "structure.ei_network@v1 needs a runtime.simulate.run@v1 op for its dt and duration".into(),
+                    ));
+                }
+            }
             (DialectKey::Stimulus, "poisson", OpVersion(1)) => {
                 let _ = neuron_ref_from_attr(op, "neuron")?;
                 let rate = rate_hz_from_attr(op, "rate")?;
@@ -364,6 +885,14 @@ pub fn verify_module(module: &Module) -> Result<()> {
                 let dur = duration_ns_from_attr(op, "duration")?;
                 let _ = bool_from_attr(op, "record_potentials")?;
                 let _ = i64_opt_from_attr(op, "seed")?;
+                let _ = integrator_from_attr(op)?;
+                let _ = spike_timing_from_attr(op)?;
+                let _ = bool_from_attr(op, "realtime")?;
+                let _ = bool_from_attr(op, "record_weight_updates")?;
+                let _ = backend_from_attr(op)?;
+                let _ = rng_from_attr(op)?;
+                sub_dt_delay = sub_dt_delay_from_attr(op)?;
+                dt_ns = Some(dt);
 
                 if dt == 0 {
                     return Err(CompilerError::BadAttr {
@@ -385,6 +914,103 @@ pub fn verify_module(module: &Module) -> Result<()> {
                 }
                 // Non-divisible duration is allowed in v0; engine may truncate last partial step.
             }
+            (DialectKey::Runtime, "apply_mask", OpVersion(1)) => {
+                let path = string_from_attr(op, "path")?;
+                let mode = string_from_attr(op, "mode")?;
+                if path.is_empty() {
+                    return Err(CompilerError::BadAttr {
+                        key: "path".into(),
+                        dialect: op.dialect.to_string(),
+                        name: op.name.clone(),
+                        version: op.version.to_string(),
+                        reason: "must not be empty".into(),
+                    });
+                }
+                if mode.parse::<MaskMode>().is_err() {
+                    return Err(CompilerError::BadAttr {
+                        key: "mode".into(),
+                        dialect: op.dialect.to_string(),
+                        name: op.name.clone(),
+                        version: op.version.to_string(),
+                        reason: "must be \"activity\" or \"plasticity\"".into(),
+                    });
+                }
+            }
+            (DialectKey::Modulation, "scalar", OpVersion(1)) => {
+                let _ = modulator_from_attrs(op)?;
+            }
+            (DialectKey::Neuron, "adex", OpVersion(1)) => {
+                let params = adex_from_attrs(op)?;
+                if let Err(shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. }) = params.validate() {
+                    return Err(bad_attr(op, &parameter, format!("must be {}", constraint)));
+                }
+                if op.attrs.contains_key("neurons") {
+                    let (start, end) = range_from_attr(op, "neurons")?;
+                    if start > end {
+                        return Err(bad_attr(op, "neurons", "start must be <= end"));
+                    }
+                }
+                adex_ops.push((op, params));
+            }
+            (DialectKey::Neuron, "alif", OpVersion(1)) => {
+                let params = alif_from_attrs(op)?;
+                if let Err(shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. }) = params.validate() {
+                    return Err(bad_attr(op, &parameter, format!("must be {}", constraint)));
+                }
+                if op.attrs.contains_key("neurons") {
+                    let (start, end) = range_from_attr(op, "neurons")?;
+                    if start > end {
+                        return Err(bad_attr(op, "neurons", "start must be <= end"));
+                    }
+                }
+            }
+            (DialectKey::Runtime, "population_dt", OpVersion(1)) => {
+                let (start, end) = range_from_attr(op, "neurons")?;
+                if start > end {
+                    return Err(bad_attr(op, "neurons", "start must be <= end"));
+                }
+                substep_ops.push((op, substeps_from_attr(op)?));
+            }
+            (DialectKey::Stimulus, "ou_noise", OpVersion(1)) => {
+                let _ = ou_noise_from_attrs(op, 0)?;
+            }
+            (DialectKey::Runtime, "monitor_rate", OpVersion(1)) => {
+                let _ = rate_monitor_from_attrs(op)?;
+            }
+            (DialectKey::Test, "expect_rate", OpVersion(1))
+            | (DialectKey::Test, "expect_spike_count", OpVersion(1)) => {
+                let _ = expectation_from_attrs(op)?;
+            }
+            (DialectKey::Runtime, "load_state", OpVersion(1))
+            | (DialectKey::Runtime, "save_state", OpVersion(1)) => {
+                // The checkpoint is read at lowering, and `at` checked against the run duration
+                if string_from_attr(op, "path")?.is_empty() {
+                    return Err(bad_attr(op, "path", "must not be empty"));
+                }
+                if op.name == "save_state" {
+                    let _ = time_ns_from_attr(op, "at")?;
+                }
+            }
+            (DialectKey::Runtime, "monitor_weights", OpVersion(1)) => {
+                let _ = weight_selection_from_attrs(op)?;
+                if duration_ns_from_attr(op, "interval")? == 0 {
+                    return Err(bad_attr(op, "interval", "must be > 0"));
+                }
+            }
+            (DialectKey::Neuron, "hh", OpVersion(1)) => {
+                let (start, end) = range_from_attr(op, "neurons")?;
+                if start > end {
+                    return Err(bad_attr(op, "neurons", "start must be <= end"));
+                }
+                let params = hh_from_attrs(op)?;
+                if let Err(shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. }) = params.validate() {
+                    let key = if parameter == "max_substep" { "substep".to_string() } else { parameter };
+                    return Err(bad_attr(op, &key, format!("must be {}", constraint)));
+                }
+            }
+            (DialectKey::Research(_), _, _) if plugins::is_registered(op) => {
+                let _ = plugins::population_from_op(op)?;
+            }
             (d, n, v) => {
                 return Err(CompilerError::UnsupportedOp {
                     dialect: d.to_string(),
@@ -394,6 +1020,54 @@ pub fn verify_module(module: &Module) -> Result<()> {
             }
         }
     }
+
+    for op in distance_ops {
+        let (_, sources, targets) = distance_rule_from_attrs(op)?;
+        for (key, (start, end)) in [("sources", sources), ("targets", targets)] {
+            if let Some(id) = (start..=end).find(|&id| layout.get(NeuronId::new(id)).is_none()) {
+                return Err(bad_attr(op, key, format!("neuron {} has no structure.positions@v1 entry", id)));
+            }
+        }
+    }
+
+    // Strict delays: anything shorter than dt is an error instead of a next-step delivery
+    if let (Some(dt), SubDtDelay::Reject) = (dt_ns, sub_dt_delay) {
+        for op in module.ops.iter().filter(|op| op.dialect == DialectKey::Connectivity) {
+            if let Some((key, delay)) = diagnostics::shortest_delay_ns(op)? {
+                if delay < dt {
+                    return Err(bad_attr(op, key, format!(
+                        "{} ms is below dt = {} ms (runtime.simulate.run sub_dt_delay = \"reject\")",
+                        units::ns_to_ms(delay), units::ns_to_ms(dt),
+                    )));
+                }
+            }
+        }
+    }
+
+    // Sub-steps must tile the global step exactly in ns
+    if let Some(dt) = dt_ns {
+        for (op, substeps) in substep_ops {
+            if dt % u64::from(substeps) != 0 {
+                return Err(bad_attr(op, "substeps", format!("must divide dt ({} ns)", dt)));
+            }
+        }
+    }
+
+    // The exponential term makes AdEx stiff: dt must resolve min(C/g_L, tau_w)
+    if let Some(dt) = dt_ns {
+        let dt_ms = units::ns_to_ms(dt);
+        for (op, params) in adex_ops {
+            let limit_ms = params.max_stable_dt();
+            if dt_ms >= limit_ms {
+                let key = if params.tau_w < params.tau_m() { "tau_w" } else { "c_m" };
+                return Err(bad_attr(
+                    op,
+                    key,
+                    format!("dt {} ms is not below min(C/g_L, tau_w) = {} ms; integration is unstable", dt_ms, limit_ms),
+                ));
+            }
+        }
+    }
     Ok(())
 }
 
@@ -403,6 +1077,10 @@ pub struct LoweredProgram {
     pub engine: SimulationEngine,
     /// Stimuli collected during lowering (already added to engine)
     pub stimuli: Vec<StimulusPattern>,
+    /// Post-simulation assertions from the test dialect
+    pub expectations: Vec<Expectation>,
+    /// Seed of every stochastic component (`<dialect>.<name>#<ordinal>`, `stimulus#<index>`)
+    pub seeds: BTreeMap<String, u64>,
 }
 
 impl LoweredProgram {
@@ -410,19 +1088,131 @@ impl LoweredProgram {
     pub fn run(mut self) -> RuntimeResult<SimulationResult> {
         self.engine.run()
     }
-}
 
-/// Compile a NIR module into a runnable program (builds network + simulation engine)
-pub fn compile_module(module: &Module) -> Result<LoweredProgram> {
-    // Defaults that can be overridden by ops
+    /// Rewind the engine to time 0 before stepping
+    pub fn reset(&mut self) -> RuntimeResult<()> {
+        self.engine.reset()
+    }
+
+    /// Advance one time step (see [`SimulationEngine::step`])
+    pub fn step(&mut self) -> RuntimeResult<()> {
+        self.engine.step()
+    }
+
+    /// Deliver external spikes through their source neurons' synapses
+    pub fn inject_spikes(&mut self, spikes: &[Spike]) -> RuntimeResult<()> {
+        self.engine.inject_spikes(spikes)
+    }
+
+    /// Take the spikes emitted since the last drain
+    pub fn drain_output_spikes(&mut self) -> Vec<Spike> {
+        self.engine.drain_output_spikes()
+    }
+
+    /// Finalize the steps executed so far into a result
+    pub fn finish(&mut self) -> SimulationResult {
+        self.engine.finish()
+    }
+
+    /// Evaluate every test-dialect expectation against a finished run
+    pub fn check_expectations(&self, result: &SimulationResult) -> Vec<ExpectationOutcome> {
+        self.expectations.iter().map(|e| e.check(result)).collect()
+    }
+}
+
+/// Compile a NIR module into a runnable program (builds network + simulation engine)
+pub fn compile_module(module: &Module) -> Result<LoweredProgram> {
+    lower(module, CpuTarget)
+}
+
+/// Lower a NIR module for `target`
+///
+/// Walks and checks every op once, then hands the network, the run's inputs
+/// and its monitors to the target in turn (see [`CompileTarget`]). Like
+/// [`compile_module`], runs neither verification nor passes; see
+/// [`compile_for_target`].
+pub fn lower<T: CompileTarget>(module: &Module, target: T) -> Result<T::Output> {
+    lower_with(module, target, &Connectivity::AsWritten)
+}
+
+/// How [`lower_with`] treats connectivity ops
+pub(crate) enum Connectivity<'a> {
+    /// Lower each op as written
+    AsWritten,
+    /// Lower ops CanonicalizePass expands as their synapse_connect@v1 ops
+    /// would lower, streaming their [`edges::edges`] into the builder
+    Expanded,
+    /// Start from a cached topology and skip the ops it replaces (see
+    /// [`cache::is_topology_kind`])
+    Cached(&'a cache::LoweredTopology),
+}
+
+/// [`lower`] with a choice of how connectivity ops are lowered
+pub(crate) fn lower_with<T: CompileTarget>(module: &Module, mut target: T, connectivity: &Connectivity<'_>) -> Result<T::Output> {
+    // Templates depend on the run's dt, duration and seed, so they expand before anything is lowered
+    if module.ops.iter().any(passes::is_template) {
+        return lower_with(&passes::expand_templates(module)?, target, connectivity);
+    }
+    if symbols::has_symbols(module) {
+        return lower_with(&symbols::resolve_symbols(module)?, target, connectivity);
+    }
+
+    // Defaults that can be overridden by ops
     let mut net_cfg = NetworkConfig::default();
     let mut builder = NetworkBuilder::new();
     let mut added_neurons: BTreeSet<u32> = BTreeSet::new();
 
     let mut sim_params: Option<SimulationParams> = None;
     let mut stimuli: Vec<StimulusPattern> = Vec::new();
+    let mut masks: Vec<(MaskMode, NeuronMask)> = Vec::new();
+    let mut modulator: Option<Modulator> = None;
+    let mut noise: Vec<(&Operation, OuNoise)> = Vec::new();
+    let mut monitors: Vec<RateMonitor> = Vec::new();
+    let mut weight_monitor_op: Option<(&Operation, u64)> = None;
+    let mut expectations: Vec<Expectation> = Vec::new();
+    let mut layout = NeuronPositions::new();
+    let mut distance_ops: Vec<(&Operation, u64)> = Vec::new();
+    let mut external = external::ExternalCache::new();
+    let mut initial_state: Option<(&Operation, NetworkState)> = None;
+    let mut state_saves: Vec<&Operation> = Vec::new();
+    let mut readouts: Vec<ReadoutMonitor> = Vec::new();
+
+    // Stochastic ops without a seed attr derive one from the simulate.run seed, keyed by
+    // op kind and ordinal among ops of that kind, so adding an op never reseeds the others
+    let seed_tree = module_seed_tree(module)?;
+    let mut ordinals: BTreeMap<String, u64> = BTreeMap::new();
+    let mut seeds: BTreeMap<String, u64> = BTreeMap::new();
+
+    if let Connectivity::Cached(topology) = connectivity {
+        builder = add_ids_if_missing(builder, &mut added_neurons, topology.neurons.iter().copied());
+        builder = builder.add_synapses_from_iter(topology.synapses.iter().map(|&(pre, post, weight, delay_ms)| {
+            (NeuronId::new(pre), NeuronId::new(post), weight, delay_ms)
+        }));
+        seeds.extend(topology.seeds.clone());
+    }
 
     for op in &module.ops {
+        let kind = format!("{}.{}", op.dialect, op.name);
+        if let Connectivity::Expanded = connectivity {
+            if let Some(edges) = edges::edges(op)? {
+                // As the ops canonicalization would have put here, ordinals included
+                *ordinals.entry("connectivity.synapse_connect".to_string()).or_default() += edges.len() as u64;
+                let mut endpoints = BTreeSet::new();
+                builder = builder.add_synapses_from_iter(edges.map(|(pre, post, weight, delay_ns)| {
+                    endpoints.extend([pre, post]);
+                    (NeuronId::new(pre), NeuronId::new(post), weight, units::ns_to_ms(delay_ns))
+                }));
+                builder = add_ids_if_missing(builder, &mut added_neurons, endpoints);
+                continue;
+            }
+        }
+        let ordinal = ordinals.entry(kind.clone()).or_default();
+        let default_seed = seed_tree.child(&kind).index(*ordinal).seed();
+        let op_key = format!("{}#{}", kind, ordinal);
+        *ordinal += 1;
+        if matches!(connectivity, Connectivity::Cached(_)) && cache::is_topology_kind(&kind) {
+            continue;
+        }
         match (&op.dialect, op.name.as_str(), op.version) {
             (DialectKey::Neuron, "lif", OpVersion(1)) => {
                 let lif = lif_from_attrs(op)?;
@@ -444,11 +1234,10 @@ pub fn compile_module(module: &Module) -> Result<LoweredProgram> {
                 builder = add_range_if_missing(builder, &mut added_neurons, out_start, out_end);
 
                 // Add synapses (fully connected)
-                for pre in in_start..=in_end {
-                    for post in out_start..=out_end {
-                        builder = builder.add_synapse(NeuronId::new(pre), NeuronId::new(post), weight, delay_ms);
-                    }
-                }
+                let synapses = (in_start..=in_end).flat_map(|pre| {
+                    (out_start..=out_end).map(move |post| (NeuronId::new(pre), NeuronId::new(post), weight, delay_ms))
+                });
+                builder = builder.add_synapses_from_iter(synapses);
             }
             (DialectKey::Connectivity, "synapse_connect", OpVersion(1)) => {
                 let pre = neuron_ref_from_attr(op, "pre")?;
@@ -463,6 +1252,108 @@ pub fn compile_module(module: &Module) -> Result<LoweredProgram> {
                 // Add single synapse
                 builder = builder.add_synapse(NeuronId::new(pre), NeuronId::new(post), weight, delay_ms);
             }
+            (DialectKey::Connectivity, "layer_fully_connected", OpVersion(2)) => {
+                let (in_start, in_end) = range_from_attr(op, "in")?;
+                let (out_start, out_end) = range_from_attr(op, "out")?;
+                // Per-synapse weights come from an [in, out] matrix, read on first use
+                let matrix = match op.attrs.get("weight") {
+                    Some(AttributeValue::ExternalData { .. }) => Some(external.load(op, "weight")?),
+                    _ => None,
+                };
+                let uniform = match matrix {
+                    Some(_) => 0.0,
+                    None => f32_from_attr(op, "weight")?,
+                };
+                let mut delays = delay_sampler_from_attrs(op, default_seed)?;
+                seeds.insert(op_key, op_seed(op, default_seed)?);
+
+                builder = add_range_if_missing(builder, &mut added_neurons, in_start, in_end);
+                builder = add_range_if_missing(builder, &mut added_neurons, out_start, out_end);
+
+                // Delays are drawn pre-major, post-minor so a seed fixes every synapse
+                let synapses = (in_start..=in_end).flat_map(|pre| (out_start..=out_end).map(move |post| (pre, post)));
+                builder = builder.add_synapses_from_iter(synapses.map(|(pre, post)| {
+                    let delay_ms = delays.next_delay();
+                    let weight = matrix.as_ref().map_or(uniform, |w| {
+                        w.at2((pre - in_start) as usize, (post - out_start) as usize) as f32
+                    });
+                    (NeuronId::new(pre), NeuronId::new(post), weight, delay_ms)
+                }));
+            }
+            (DialectKey::Connectivity, "synapse_connect", OpVersion(2)) => {
+                let pre = neuron_ref_from_attr(op, "pre")?;
+                let post = neuron_ref_from_attr(op, "post")?;
+                let weight = f32_from_attr(op, "weight")?;
+                let delay_ms = delay_sampler_from_attrs(op, default_seed)?.next_delay();
+                seeds.insert(op_key, op_seed(op, default_seed)?);
+
+                builder = add_range_if_missing(builder, &mut added_neurons, pre, pre);
+                builder = add_range_if_missing(builder, &mut added_neurons, post, post);
+                builder = builder.add_synapse(NeuronId::new(pre), NeuronId::new(post), weight, delay_ms);
+            }
+            (DialectKey::Connectivity, "hyperedge", OpVersion(1)) => {
+                let weight = f32_from_attr(op, "weight")?;
+                let delay_ms = duration_ns_to_ms(op, "delay")?;
+                for key in ["sources", "targets"] {
+                    builder = add_ids_if_missing(builder, &mut added_neurons, neuron_set_from_attr(op, key)?);
+                }
+                builder = builder.add_synapses_from_iter(hyperedge_pairs(op)?.into_iter().map(|(pre, post)| {
+                    (NeuronId::new(pre), NeuronId::new(post), weight, delay_ms)
+                }));
+            }
+            (DialectKey::Connectivity, "from_snapshot", OpVersion(1)) => {
+                let snapshot = snapshot_from_attrs(op)?;
+                let delay_ms = duration_ns_to_ms(op, "delay")?;
+                if snapshot.header.num_vertices > 0 {
+                    builder = add_range_if_missing(builder, &mut added_neurons, 0, snapshot.header.num_vertices - 1);
+                }
+                builder = builder.add_synapses_from_iter(
+                    snapshot.edges().map(|(pre, post, weight)| (pre, post, weight, delay_ms)),
+                );
+            }
+            (DialectKey::Connectivity, "distance_gaussian", OpVersion(1)) => {
+                // Drawn once every structure.positions op has been seen
+                distance_ops.push((op, default_seed));
+                seeds.insert(op_key, op_seed(op, default_seed)?);
+            }
+            (DialectKey::Structure, "positions", OpVersion(1)) => {
+                let (start, end) = range_from_attr(op, "neurons")?;
+                builder = add_range_if_missing(builder, &mut added_neurons, start, end);
+                layout.extend(positions_from_attrs(op)?);
+            }
+            (DialectKey::Structure, "reservoir", OpVersion(1)) => {
+                let (rule, neurons, inputs, _) = reservoir_from_attrs(op)?;
+                let delay_ms = duration_ns_to_ms(op, "delay")?;
+                let seed = op_seed(op, default_seed)?;
+                seeds.insert(op_key, seed);
+                let synapses = rule.connect(inputs.0..=inputs.1, neurons.0..=neurons.1, seed)
+                    .map_err(CompilerError::Runtime)?;
+                builder = add_range_if_missing(builder, &mut added_neurons, inputs.0, inputs.1);
+                builder = add_range_if_missing(builder, &mut added_neurons, neurons.0, neurons.1);
+                builder = builder.add_synapses_from_iter(
+                    synapses.into_iter().map(|(pre, post, weight)| (pre, post, weight, delay_ms)),
+                );
+            }
+            (DialectKey::Runtime, "readout", OpVersion(1)) => {
+                readouts.push(readout_from_attrs(op, first_reservoir(module))?);
+            }
+            (DialectKey::Structure, "port", OpVersion(1)) => {
+                let (_, (start, end)) = port_from_attrs(op)?;
+                builder = add_range_if_missing(builder, &mut added_neurons, start, end);
+            }
+            (DialectKey::Structure, "population", OpVersion(1)) => {
+                if let (_, _, Some((start, end))) = population_from_attrs(op)? {
+                    builder = add_range_if_missing(builder, &mut added_neurons, start, end);
+                }
+            }
+            (DialectKey::Structure, "wta", OpVersion(1)) => {
+                let ((start, end), synapses) = wta_synapses(op)?;
+                let delay_ms = duration_ns_to_ms(op, "delay")?;
+                builder = add_range_if_missing(builder, &mut added_neurons, start, end);
+                builder = builder.add_synapses_from_iter(synapses.into_iter().map(|(pre, post, weight)| {
+                    (NeuronId::new(pre), NeuronId::new(post), weight, delay_ms)
+                }));
+            }
             (DialectKey::Stimulus, "poisson", OpVersion(1)) => {
                 let neuron = neuron_ref_from_attr(op, "neuron")?;
                 let rate = rate_hz_from_attr(op, "rate")?;
@@ -479,11 +1370,19 @@ pub fn compile_module(module: &Module) -> Result<LoweredProgram> {
                 };
                 stimuli.push(pattern);
             }
+            (DialectKey::Stimulus, "ou_noise", OpVersion(1)) => {
+                noise.push((op, ou_noise_from_attrs(op, default_seed)?));
+                seeds.insert(op_key, op_seed(op, default_seed)?);
+            }
             (DialectKey::Runtime, "simulate.run", OpVersion(1)) => {
                 let dt_ns = duration_ns_from_attr(op, "dt")?;
                 let duration_ns = duration_ns_from_attr(op, "duration")?;
                 let record_potentials = bool_from_attr(op, "record_potentials")?;
                 let seed = i64_opt_from_attr(op, "seed")?.map(|v| v as u64);
+                net_cfg.integrator = integrator_from_attr(op)?;
+                net_cfg.spike_timing = spike_timing_from_attr(op)?;
+                net_cfg.connectivity = backend_from_attr(op)?;
+                net_cfg.sub_dt_delay = sub_dt_delay_from_attr(op)?;
 
                 let mut params = SimulationParams::new(dt_ns, duration_ns)
                     .map_err(CompilerError::Runtime)?;
@@ -493,8 +1392,81 @@ pub fn compile_module(module: &Module) -> Result<LoweredProgram> {
                 if let Some(s) = seed {
                     params = params.with_seed(s);
                 }
+                params = params.with_realtime(bool_from_attr(op, "realtime")?);
+                params = params.with_weight_update_recording(bool_from_attr(op, "record_weight_updates")?);
+                params = params.with_rng(rng_from_attr(op)?);
                 sim_params = Some(params);
             }
+            (DialectKey::Runtime, "apply_mask", OpVersion(1)) => {
+                let path = string_from_attr(op, "path")?;
+                let mode = string_from_attr(op, "mode")?
+                    .parse::<MaskMode>()
+                    .map_err(CompilerError::Runtime)?;
+                let mask = NeuronMask::load(path).map_err(|e| {
+                    CompilerError::Message(format!("failed to load mask '{}': {}", path, e))
+                })?;
+                masks.push((mode, mask));
+            }
+            (DialectKey::Modulation, "scalar", OpVersion(1)) => {
+                modulator = Some(modulator_from_attrs(op)?);
+            }
+            (DialectKey::Neuron, "adex", OpVersion(1)) => {
+                let model = NeuronModel::AdEx(adex_from_attrs(op)?);
+                builder = match op.attrs.contains_key("neurons") {
+                    true => {
+                        let (start, end) = range_from_attr(op, "neurons")?;
+                        builder.with_population_model(start, end.saturating_add(1), model)
+                    }
+                    false => builder.with_population_model(0, u32::MAX, model),
+                };
+            }
+            (DialectKey::Neuron, "alif", OpVersion(1)) => {
+                let model = NeuronModel::Lif(alif_from_attrs(op)?);
+                builder = match op.attrs.contains_key("neurons") {
+                    true => {
+                        let (start, end) = range_from_attr(op, "neurons")?;
+                        builder.with_population_model(start, end.saturating_add(1), model)
+                    }
+                    false => builder.with_population_model(0, u32::MAX, model),
+                };
+            }
+            (DialectKey::Runtime, "population_dt", OpVersion(1)) => {
+                let (start, end) = range_from_attr(op, "neurons")?;
+                let substeps = substeps_from_attr(op)?;
+                builder = builder.with_population_substeps(start, end.saturating_add(1), substeps);
+            }
+            (DialectKey::Neuron, "hh", OpVersion(1)) => {
+                let (start, end) = range_from_attr(op, "neurons")?;
+                let model = NeuronModel::HH(hh_from_attrs(op)?);
+                builder = builder.with_population_model(start, end.saturating_add(1), model);
+            }
+            (DialectKey::Runtime, "monitor_rate", OpVersion(1)) => {
+                monitors.push(rate_monitor_from_attrs(op)?);
+            }
+            (DialectKey::Runtime, "load_state", OpVersion(1)) => {
+                // Applied to the built network; a later op replaces an earlier one
+                let path = string_from_attr(op, "path")?;
+                let state = NetworkState::load(path).map_err(|e| {
+                    CompilerError::Message(format!("failed to load state '{}': {}", path, e))
+                })?;
+                initial_state = Some((op, state));
+            }
+            (DialectKey::Runtime, "save_state", OpVersion(1)) => {
+                state_saves.push(op);
+            }
+            (DialectKey::Runtime, "monitor_weights", OpVersion(1)) => {
+                // Synapses are resolved against the built network; a later op replaces an earlier one
+                weight_monitor_op = Some((op, default_seed));
+                seeds.insert(op_key, op_seed(op, default_seed)?);
+            }
+            (DialectKey::Test, "expect_rate", OpVersion(1))
+            | (DialectKey::Test, "expect_spike_count", OpVersion(1)) => {
+                expectations.push(expectation_from_attrs(op)?);
+            }
+            (DialectKey::Research(_), _, _) if plugins::is_registered(op) => {
+                let (start, end, model) = plugins::population_from_op(op)?;
+                builder = builder.with_population_model(start, end.saturating_add(1), NeuronModel::Custom(model));
+            }
             (d, n, v) => {
                 return Err(CompilerError::UnsupportedOp {
                     dialect: d.to_string(),
@@ -505,39 +1477,286 @@ pub fn compile_module(module: &Module) -> Result<LoweredProgram> {
         }
     }
 
-    // Build network
-    let network = builder.with_config(net_cfg).build()
-        .map_err(CompilerError::Runtime)?;
+    for (op, default_seed) in distance_ops {
+        let (rule, (src_start, src_end), (tgt_start, tgt_end)) = distance_rule_from_attrs(op)?;
+        let delay_ms = duration_ns_to_ms(op, "delay")?;
+        let seed = op_seed(op, default_seed)?;
+        let connections = rule.connect(&layout, src_start..=src_end, tgt_start..=tgt_end, seed)
+            .map_err(|e| match e {
+                shnn_runtime::RuntimeError::InvalidParameter { value, .. } => {
+                    bad_attr(op, "sources", format!("neuron {} has no structure.positions@v1 entry", value))
+                }
+                other => CompilerError::Runtime(other),
+            })?;
+        builder = add_range_if_missing(builder, &mut added_neurons, src_start, src_end);
+        builder = add_range_if_missing(builder, &mut added_neurons, tgt_start, tgt_end);
+        builder = builder.add_synapses_from_iter(
+            connections.into_iter().map(|(pre, post, weight)| (pre, post, weight, delay_ms)),
+        );
+    }
 
     // Simulation params required
     let params = sim_params.ok_or_else(|| CompilerError::Message("Missing runtime.simulate.run@v1 op (no simulation params)".into()))?;
-
-    // Create engine and add stimuli
-    let mut engine = SimulationEngine::new(network, params)
-        .map_err(CompilerError::Runtime)?;
-    for s in &stimuli {
-        engine.add_stimulus(s.clone());
+    // Checks that need the complete neuron set or the run length, shared by every target
+    for (op, source) in &noise {
+        if let Some(id) = (source.start().raw()..=source.end().raw()).find(|id| !added_neurons.contains(id)) {
+            return Err(bad_attr(op, "neurons", format!("neuron {} does not exist", id)));
+        }
+    }
+    for op in &state_saves {
+        if time_ns_from_attr(op, "at")? > params.duration_ns {
+            return Err(bad_attr(op, "at", format!("is after the run ends ({} ns)", params.duration_ns)));
+        }
     }
 
-    Ok(LoweredProgram { engine, stimuli })
+    let builder = builder.with_config(net_cfg).with_dt(params.dt_ms());
+    let mut network = target.build_network(NetworkPlan { builder, masks, modulator, positions: layout, params })?;
+    target.build_stimuli(&mut network, StimulusPlan { stimuli, noise })?;
+    target.finalize(network, RunPlan {
+        monitors,
+        readouts,
+        weight_monitor: weight_monitor_op,
+        expectations,
+        initial_state,
+        state_saves,
+        seeds,
+    })
+}
+
+/// Root of the default seeds of a module's stochastic ops (the simulate.run seed, or 42)
+pub(crate) fn module_seed_tree(module: &Module) -> Result<SeedTree> {
+    let module_seed = module.ops.iter()
+        .find(|op| op.dialect == DialectKey::Runtime && op.name == "simulate.run")
+        .map(|op| i64_opt_from_attr(op, "seed"))
+        .transpose()?
+        .flatten()
+        .map_or(42, |s| s as u64);
+    Ok(SeedTree::new(module_seed))
 }
 
 /// Compile with a (currently no-op) pass pipeline, then lower to runtime.
 /// Runs verification before passes.
 pub fn compile_with_passes(module: &Module) -> Result<LoweredProgram> {
-    let mut m = module.clone();
+    compile_with_passes_profiled(module, &mut Profiler::new())
+}
+
+/// [`compile_with_passes`], recording `verify`, `passes` and `lowering` spans
+pub fn compile_with_passes_profiled(module: &Module, profiler: &mut Profiler) -> Result<LoweredProgram> {
+    compile_for_target_profiled(module, CpuTarget, profiler)
+}
+
+/// Verify, run the pass pipeline, then lower for `target`
+pub fn compile_for_target<T: CompileTarget>(module: &Module, target: T) -> Result<T::Output> {
+    compile_for_target_profiled(module, target, &mut Profiler::new())
+}
+
+/// [`compile_for_target`], recording `verify`, `passes` and `lowering` spans
+pub fn compile_for_target_profiled<T: CompileTarget>(module: &Module, target: T, profiler: &mut Profiler) -> Result<T::Output> {
     // Verify pre-pass
-    verify_module(&m)?;
-    // Run no-op passes (canonicalize, version upgrade)
+    profiler.time("verify", || verify_module(module))?;
+    let m = profiler.time("passes", || normalize(module))?;
+    // Lower, expanding connectivity into edge lists rather than ops
+    profiler.time("lowering", || lower_with(&m, target, &Connectivity::Expanded))
+}
+
+/// The pass pipeline without connectivity expansion (symbol resolution, defaulting, version upgrade)
+pub(crate) fn normalize(module: &Module) -> Result<Module> {
+    let mut m = module.clone();
     let mut pm = passes::PassManager::new();
+    pm.add(Box::new(passes::ResolveSymbolsPass));
+    pm.add(Box::new(passes::DefaultingPass));
+    pm.add(Box::new(passes::UpgradeVersionsPass));
+    pm.run(&mut m)?;
+    Ok(m)
+}
+
+/// Copy of `module` after the pass pipeline (symbol resolution, defaulting, canonicalize, version upgrade)
+///
+/// [`compile_with_passes`] lowers the same network without materializing the
+/// expanded synapse_connect ops (see [`edges`]).
+pub fn canonicalize(module: &Module) -> Result<Module> {
+    let mut m = module.clone();
+    let mut pm = passes::PassManager::new();
+    pm.add(Box::new(passes::ResolveSymbolsPass));
+    pm.add(Box::new(passes::DefaultingPass));
     pm.add(Box::new(passes::CanonicalizePass));
     pm.add(Box::new(passes::UpgradeVersionsPass));
     pm.run(&mut m)?;
-    // Lower
-    compile_module(&m)
+    Ok(m)
 }
+
+/// Sizes of a verified module that drive its memory use, read from its ops without lowering
+pub fn program_shape(module: &Module) -> Result<ProgramShape> {
+    if symbols::has_symbols(module) {
+        return program_shape(&symbols::resolve_symbols(module)?);
+    }
+    let mut shape = ProgramShape::default();
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    let mut run: Option<(SimulationParams, bool)> = None;
+    let mut t_refrac_ms = LIFParams::default().t_refrac;
+    let mut rate_bins_ns: Vec<u64> = Vec::new();
+    let mut weight_monitor: Option<(u64, u64)> = None;
+    // Ops CanonicalizePass would expand are streamed into the builder; only
+    // hyperedge pairs and WTA circuits are listed first, one op at a time
+    let mut expansion_bytes = 0u64;
+
+    for op in &module.ops {
+        match (&op.dialect, op.name.as_str(), op.version) {
+            (DialectKey::Connectivity, "layer_fully_connected", _) => {
+                let (in_start, in_end) = range_from_attr(op, "in")?;
+                let (out_start, out_end) = range_from_attr(op, "out")?;
+                ranges.extend([(in_start, in_end), (out_start, out_end)]);
+                let count = (in_end - in_start + 1) as u64 * (out_end - out_start + 1) as u64;
+                shape.synapses += count;
+            }
+            (DialectKey::Connectivity, "hyperedge", OpVersion(1)) => {
+                let pairs = hyperedge_pairs(op)?;
+                for key in ["sources", "targets"] {
+                    ranges.extend(neuron_set_from_attr(op, key)?.into_iter().map(|id| (id, id)));
+                }
+                shape.synapses += pairs.len() as u64;
+                expansion_bytes = expansion_bytes.max((pairs.len() * std::mem::size_of::<(u32, u32)>()) as u64);
+            }
+            (DialectKey::Connectivity, "from_snapshot", OpVersion(1)) => {
+                let header = snapshot_from_attrs(op)?.header;
+                if header.num_vertices > 0 {
+                    ranges.push((0, header.num_vertices - 1));
+                }
+                shape.synapses += header.num_incidences;
+            }
+            (DialectKey::Connectivity, "distance_gaussian", OpVersion(1)) => {
+                let (rule, sources, targets) = distance_rule_from_attrs(op)?;
+                ranges.extend([sources, targets]);
+                // Upper bound: every pair connected with probability p_max
+                let pairs = (sources.1 - sources.0 + 1) as u64 * (targets.1 - targets.0 + 1) as u64;
+                shape.synapses += (pairs as f64 * rule.p_max as f64).ceil() as u64;
+            }
+            (DialectKey::Structure, "positions", OpVersion(1)) => {
+                ranges.push(range_from_attr(op, "neurons")?);
+            }
+            (DialectKey::Structure, "reservoir", OpVersion(1)) => {
+                let (rule, neurons, inputs, _) = reservoir_from_attrs(op)?;
+                ranges.extend([neurons, inputs]);
+                // Expected counts of the input and recurrent draws
+                let size = (neurons.1 - neurons.0 + 1) as f64;
+                let fan_in = (inputs.1 - inputs.0 + 1) as f64;
+                shape.synapses += (fan_in * size * rule.input_p as f64 + size * (size - 1.0) * rule.p as f64).ceil() as u64;
+            }
+            (DialectKey::Structure, "port", OpVersion(1)) => {
+                ranges.push(port_from_attrs(op)?.1);
+            }
+            (DialectKey::Structure, "population", OpVersion(1)) => {
+                ranges.extend(population_from_attrs(op)?.2);
+            }
+            (DialectKey::Structure, "wta", OpVersion(1)) => {
+                let (range, synapses) = wta_synapses(op)?;
+                ranges.push(range);
+                shape.synapses += synapses.len() as u64;
+                expansion_bytes = expansion_bytes.max((synapses.len() * std::mem::size_of::<(u32, u32, f32)>()) as u64);
+            }
+            (DialectKey::Structure, "ei_network", OpVersion(1)) => {
+                let net = brunel_from_attrs(op)?;
+                let (c_exc, c_inh) = net.in_degrees();
+                ranges.push((0, net.neurons() - 1));
+                // Upper bound: full in-degrees, expanded into one synapse_connect op each
+                let count = net.neurons() as u64 * (c_exc + c_inh) as u64;
+                shape.synapses += count;
+                let single = passes::synapse_connect_op(0, 1, 0.0, duration_ns_from_attr(op, "delay")?);
+                shape.lowering_bytes += count * synapse_op_bytes(&single);
+            }
+            (DialectKey::Connectivity, "synapse_connect", _) => {
+                let pre = neuron_ref_from_attr(op, "pre")?;
+                let post = neuron_ref_from_attr(op, "post")?;
+                ranges.extend([(pre, pre), (post, post)]);
+                shape.synapses += 1;
+            }
+            (DialectKey::Neuron, "lif", OpVersion(1)) => {
+                t_refrac_ms = lif_from_attrs(op)?.t_refrac;
+            }
+            (DialectKey::Plasticity, "stdp", _) => shape.plasticity = true,
+            (DialectKey::Runtime, "simulate.run", OpVersion(1)) => {
+                let params = SimulationParams::new(duration_ns_from_attr(op, "dt")?, duration_ns_from_attr(op, "duration")?)
+                    .map_err(CompilerError::Runtime)?;
+                run = Some((params, bool_from_attr(op, "record_potentials")?));
+            }
+            (DialectKey::Runtime, "monitor_rate", OpVersion(1)) => {
+                rate_bins_ns.push(duration_ns_from_attr(op, "bin")?);
+            }
+            (DialectKey::Runtime, "monitor_weights", OpVersion(1)) => {
+                let count = match weight_selection_from_attrs(op)? {
+                    WeightSelection::Pairs(pairs) => pairs.len() as u64,
+                    WeightSelection::Sample(count) => count as u64,
+                };
+                weight_monitor = Some((count, duration_ns_from_attr(op, "interval")?));
+            }
+            (DialectKey::Research(_), _, _) if plugins::is_registered(op) => {
+                ranges.push(range_from_attr(op, "neurons")?);
+            }
+            _ => {}
+        }
+    }
+
+    // Neurons are created for every connectivity endpoint; count the union of ranges
+    ranges.sort_unstable();
+    let mut covered_end: Option<u32> = None;
+    for (start, end) in ranges {
+        let from = match covered_end {
+            Some(covered) if covered >= end => continue,
+            Some(covered) if covered >= start => covered + 1,
+            _ => start,
+        };
+        shape.neurons += (end - from) as u64 + 1;
+        covered_end = Some(end);
+    }
+
+    let (params, record_potentials) = run
+        .ok_or_else(|| CompilerError::Message("Missing runtime.simulate.run@v1 op (no simulation params)".into()))?;
+    let steps = params.num_steps() as u64;
+    // A neuron fires at most once per refractory period
+    let refrac_steps = (Quantity::ms(t_refrac_ms as f64).saturating_ns() / params.dt_ns).max(1);
+    let spikes = shape.neurons.saturating_mul(steps.div_ceil(refrac_steps));
+    shape.recorded_spikes = params.max_recorded_spikes.map_or(spikes, |max| spikes.min(max as u64));
+    if record_potentials {
+        shape.potential_samples = shape.neurons.saturating_mul(steps);
+    }
+    shape.rate_bins = rate_bins_ns.iter()
+        .map(|&bin| params.duration_ns.div_ceil(bin.max(1)))
+        .sum();
+    if let Some((count, interval_ns)) = weight_monitor {
+        shape.weight_samples = count.min(shape.synapses) * (params.duration_ns / interval_ns.max(1) + 1);
+    }
+    shape.lowering_bytes += expansion_bytes;
+    Ok(shape)
+}
+
+/// Estimate peak memory of lowering and running a verified module
+pub fn estimate_memory(module: &Module) -> Result<MemoryEstimate> {
+    Ok(MemoryEstimate::for_shape(&program_shape(module)?))
+}
+
+/// Bytes held per synapse_connect op of an expanded template
+fn synapse_op_bytes(synapse: &Operation) -> u64 {
+    // Attribute maps of this size fit one B-tree leaf
+    const BTREE_LEAF_CAPACITY: usize = 11;
+
+    let slots = synapse.attrs.len().div_ceil(BTREE_LEAF_CAPACITY) * BTREE_LEAF_CAPACITY;
+    let heap = synapse.name.capacity()
+        + synapse.attrs.keys().map(String::capacity).sum::<usize>()
+        + slots * (std::mem::size_of::<String>() + std::mem::size_of::<AttributeValue>());
+    // The op itself is moved between growing vectors during the pass
+    (heap + 3 * std::mem::size_of::<Operation>()) as u64
+}
+
 // ------------------------- Attribute helpers -------------------------
 
+/// Registry default for `key` when `op` omits it
+fn attr_default(op: &Operation, key: &str) -> Option<AttrDefault> {
+    match op.attrs.contains_key(key) {
+        true => None,
+        false => op_spec(op)?.attrs.iter().find(|a| a.name == key)?.default,
+    }
+}
+
 fn get_attr<'a>(op: &'a Operation, key: &str) -> Result<&'a AttributeValue> {
     op.attrs.get(key).ok_or_else(|| CompilerError::MissingAttr(
         key.to_string(),
@@ -548,6 +1767,9 @@ fn get_attr<'a>(op: &'a Operation, key: &str) -> Result<&'a AttributeValue> {
 }
 
 fn bool_from_attr(op: &Operation, key: &str) -> Result<bool> {
+    if let Some(AttrDefault::Bool(b)) = attr_default(op, key) {
+        return Ok(b);
+    }
     match get_attr(op, key)? {
         AttributeValue::Bool(b) => Ok(*b),
         other => Err(CompilerError::BadAttr {
@@ -560,6 +1782,22 @@ fn bool_from_attr(op: &Operation, key: &str) -> Result<bool> {
     }
 }
 
+fn string_from_attr<'a>(op: &'a Operation, key: &str) -> Result<&'a str> {
+    if let Some(AttrDefault::Str(s)) = attr_default(op, key) {
+        return Ok(s);
+    }
+    match get_attr(op, key)? {
+        AttributeValue::String(s) => Ok(s.as_str()),
+        other => Err(CompilerError::BadAttr {
+            key: key.to_string(),
+            dialect: op.dialect.to_string(),
+            name: op.name.clone(),
+            version: op.version.to_string(),
+            reason: format!("expected String, got {:?}", other),
+        }),
+    }
+}
+
 fn i64_opt_from_attr(op: &Operation, key: &str) -> Result<Option<i64>> {
     match op.attrs.get(key) {
         None => Ok(None),
@@ -575,6 +1813,9 @@ fn i64_opt_from_attr(op: &Operation, key: &str) -> Result<Option<i64>> {
 }
 
 fn f32_from_attr(op: &Operation, key: &str) -> Result<f32> {
+    if let Some(AttrDefault::F32(v)) = attr_default(op, key) {
+        return Ok(v);
+    }
     match get_attr(op, key)? {
         AttributeValue::F32(v) => Ok(*v),
         AttributeValue::Weight(w) => Ok(*w),
@@ -605,6 +1846,9 @@ fn time_ns_from_attr(op: &Operation, key: &str) -> Result<u64> {
 }
 
 fn duration_ns_from_attr(op: &Operation, key: &str) -> Result<u64> {
+    if let Some(AttrDefault::Ns(ns)) = attr_default(op, key) {
+        return Ok(ns);
+    }
     match get_attr(op, key)? {
         AttributeValue::DurationNs(ns) => Ok(*ns),
         other => Err(CompilerError::BadAttr {
@@ -618,8 +1862,7 @@ fn duration_ns_from_attr(op: &Operation, key: &str) -> Result<u64> {
 }
 
 fn duration_ns_to_ms(op: &Operation, key: &str) -> Result<f32> {
-    let ns = duration_ns_from_attr(op, key)?;
-    Ok(ns as f32 / 1_000_000.0)
+    Ok(units::ns_to_ms(duration_ns_from_attr(op, key)?))
 }
 
 fn rate_hz_from_attr(op: &Operation, key: &str) -> Result<f32> {
@@ -675,6 +1918,26 @@ fn range_from_attr(op: &Operation, key: &str) -> Result<(u32, u32)> {
 }
 
 fn lif_from_attrs(op: &Operation) -> Result<LIFParams> {
+    let mut params = lif_membrane_from_attrs(op)?;
+    params.refractory = refractoriness_from_attrs(op)?;
+    Ok(params)
+}
+
+/// neuron.alif@v1: LIF membrane with a per-spike threshold increment
+fn alif_from_attrs(op: &Operation) -> Result<LIFParams> {
+    let beta = f32_from_attr(op, "beta")?;
+    let tau_adapt_ns = duration_ns_from_attr(op, "tau_adapt")?;
+    if !(beta >= 0.0 && beta.is_finite()) {
+        return Err(bad_attr(op, "beta", "must be >= 0 mV"));
+    }
+    if tau_adapt_ns == 0 {
+        return Err(bad_attr(op, "tau_adapt", "must be > 0 ns"));
+    }
+    Ok(lif_membrane_from_attrs(op)?.with_threshold_adaptation(beta, units::ns_to_ms(tau_adapt_ns)))
+}
+
+/// Attributes shared by neuron.lif@v1 and neuron.alif@v1
+fn lif_membrane_from_attrs(op: &Operation) -> Result<LIFParams> {
     let tau_m_ns = duration_ns_from_attr(op, "tau_m")?;
     let t_refrac_ns = duration_ns_from_attr(op, "t_refrac")?;
     let v_rest = f32_from_attr(op, "v_rest")?;
@@ -684,8 +1947,8 @@ fn lif_from_attrs(op: &Operation) -> Result<LIFParams> {
     let c_m = f32_from_attr(op, "c_m")?;
 
     // Convert to existing runtime units (ms for times)
-    let tau_m_ms = tau_m_ns as f32 / 1_000_000.0;
-    let t_refrac_ms = t_refrac_ns as f32 / 1_000_000.0;
+    let tau_m_ms = units::ns_to_ms(tau_m_ns);
+    let t_refrac_ms = units::ns_to_ms(t_refrac_ns);
 
     let mut params = LIFParams::default();
     params.tau_m = tau_m_ms;
@@ -698,6 +1961,265 @@ fn lif_from_attrs(op: &Operation) -> Result<LIFParams> {
     Ok(params)
 }
 
+fn refractoriness_from_attrs(op: &Operation) -> Result<Refractoriness> {
+    let policy = match string_from_attr(op, "refractory")? {
+        "hold" => Refractoriness::HoldAtReset,
+        "ignore_input" => Refractoriness::IgnoreInput,
+        "relative" => Refractoriness::Relative {
+            jump: f32_from_attr(op, "refrac_jump")?,
+            tau: units::ns_to_ms(duration_ns_from_attr(op, "tau_refrac")?),
+        },
+        _ => return Err(bad_attr(op, "refractory", "must be \"hold\", \"ignore_input\" or \"relative\"")),
+    };
+    policy.validate().map_err(|_| match policy {
+        Refractoriness::Relative { jump, .. } if !(jump >= 0.0 && jump.is_finite()) => bad_attr(op, "refrac_jump", "must be >= 0 mV"),
+        _ => bad_attr(op, "tau_refrac", "must be > 0 ns"),
+    })?;
+    Ok(policy)
+}
+
+/// Delay sampler for v2 connectivity ops (delay_dist defaults to constant)
+/// The op's `seed` attr, else `default_seed`
+fn op_seed(op: &Operation, default_seed: u64) -> Result<u64> {
+    Ok(i64_opt_from_attr(op, "seed")?.map_or(default_seed, |v| v as u64))
+}
+
+fn delay_sampler_from_attrs(op: &Operation, default_seed: u64) -> Result<DelaySampler> {
+    let opt_ms = |key: &str| -> Result<Option<f32>> {
+        match op.attrs.contains_key(key) {
+            true => duration_ns_to_ms(op, key).map(Some),
+            false => Ok(None),
+        }
+    };
+    let req_ms = |key: &str| duration_ns_to_ms(op, key);
+    let dist = match string_from_attr(op, "delay_dist")? {
+        "constant" => DelayDistribution::Constant(req_ms("delay")?),
+        "uniform" => DelayDistribution::Uniform { min: req_ms("delay_min")?, max: req_ms("delay_max")? },
+        "normal" => DelayDistribution::Normal {
+            mean: req_ms("delay")?,
+            std: req_ms("delay_std")?,
+            min: opt_ms("delay_min")?.unwrap_or(0.0),
+        },
+        "gamma" => DelayDistribution::Gamma { shape: f32_from_attr(op, "delay_shape")?, scale: req_ms("delay_scale")? },
+        _ => return Err(bad_attr(op, "delay_dist", "must be constant, uniform, normal or gamma")),
+    };
+    let seed = op_seed(op, default_seed)?;
+    DelaySampler::new(dist, seed).map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })
+}
+
+fn ou_noise_from_attrs(op: &Operation, default_seed: u64) -> Result<OuNoise> {
+    let (start, end) = range_from_attr(op, "neurons")?;
+    let mu = current_na_from_attr(op, "mu")?;
+    let sigma = current_na_from_attr(op, "sigma")?;
+    let tau_ms = duration_ns_to_ms(op, "tau")?;
+    let seed = op_seed(op, default_seed)?;
+    OuNoise::new(NeuronId::new(start), NeuronId::new(end), mu, sigma, tau_ms, seed).map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })
+}
+
+fn rate_monitor_from_attrs(op: &Operation) -> Result<RateMonitor> {
+    let (start, end) = range_from_attr(op, "neurons")?;
+    let bin_ns = duration_ns_from_attr(op, "bin")?;
+    RateMonitor::new(NeuronId::new(start), NeuronId::new(end), bin_ns).map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })
+}
+
+fn expectation_from_attrs(op: &Operation) -> Result<Expectation> {
+    let (start, end) = range_from_attr(op, "neurons")?;
+    let (start, end) = (NeuronId::new(start), NeuronId::new(end));
+    let expectation = match op.name.as_str() {
+        "expect_rate" => {
+            let bound = |key| match op.attrs.contains_key(key) {
+                true => rate_hz_from_attr(op, key).map(Some),
+                false => Ok(None),
+            };
+            Expectation::Rate { start, end, min_hz: bound("min_hz")?, max_hz: bound("max_hz")? }
+        }
+        _ => {
+            let bound = |key| match i64_opt_from_attr(op, key)? {
+                Some(v) if v < 0 => Err(bad_attr(op, key, "must be >= 0")),
+                v => Ok(v.map(|v| v as u64)),
+            };
+            Expectation::SpikeCount { start, end, min: bound("min")?, max: bound("max")? }
+        }
+    };
+    expectation.validate().map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })?;
+    Ok(expectation)
+}
+
+/// Synapses selected by runtime.monitor_weights@v1
+enum WeightSelection {
+    Pairs(Vec<(u32, u32)>),
+    Sample(usize),
+}
+
+fn weight_selection_from_attrs(op: &Operation) -> Result<WeightSelection> {
+    match (op.attrs.contains_key("pairs"), op.attrs.contains_key("sample")) {
+        (true, false) => string_from_attr(op, "pairs")?
+            .split(';')
+            .map(|entry| {
+                let (pre, post) = entry.split_once(':')?;
+                Some((pre.trim().parse::<u32>().ok()?, post.trim().parse::<u32>().ok()?))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(WeightSelection::Pairs)
+            .ok_or_else(|| bad_attr(op, "pairs", "expected \"pre:post;pre:post\"")),
+        (false, true) => match i64_opt_from_attr(op, "sample")? {
+            Some(n) if n >= 1 => Ok(WeightSelection::Sample(n as usize)),
+            _ => Err(bad_attr(op, "sample", "must be >= 1")),
+        },
+        (true, true) => Err(bad_attr(op, "pairs", "pairs and sample are mutually exclusive")),
+        (false, false) => Err(bad_attr(op, "pairs", "one of pairs or sample is required")),
+    }
+}
+
+fn weight_monitor_from_attrs(op: &Operation, network: &SNNNetwork, default_seed: u64) -> Result<WeightMonitor> {
+    let interval_ns = duration_ns_from_attr(op, "interval")?;
+    let seed = op_seed(op, default_seed)?;
+    let monitor = match weight_selection_from_attrs(op)? {
+        WeightSelection::Pairs(pairs) => {
+            if let Some(&(pre, post)) = pairs.iter().find(|&&(pre, post)| {
+                network.get_weight(NeuronId::new(pre), NeuronId::new(post)).is_err()
+            }) {
+                return Err(bad_attr(op, "pairs", format!("no synapse {} -> {}", pre, post)));
+            }
+            let synapses = pairs.into_iter().map(|(pre, post)| (NeuronId::new(pre), NeuronId::new(post))).collect();
+            WeightMonitor::new(network, synapses, interval_ns)
+        }
+        WeightSelection::Sample(count) => WeightMonitor::sample(network, count, interval_ns, seed),
+    };
+    monitor.map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })
+}
+
+fn neuron_set_from_attr(op: &Operation, key: &str) -> Result<Vec<u32>> {
+    let ids: Vec<u32> = match get_attr(op, key)? {
+        AttributeValue::RangeU32 { start, end } if start <= end => (*start..=*end).collect(),
+        AttributeValue::RangeU32 { .. } => return Err(bad_attr(op, key, "range must satisfy start <= end")),
+        AttributeValue::String(list) => list
+            .split(';')
+            .map(|id| id.trim().parse::<u32>().ok())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| bad_attr(op, key, "expected a range or \"id;id\""))?,
+        _ => return Err(bad_attr(op, key, "expected a range or \"id;id\"")),
+    };
+    let mut sorted = ids.clone();
+    sorted.sort_unstable();
+    if let Some(pair) = sorted.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(bad_attr(op, key, format!("lists neuron {} more than once", pair[0])));
+    }
+    Ok(ids)
+}
+
+/// (pre, post) synapses of a hyperedge: every source to every target, in
+/// source-major order, skipping a neuron's connection to itself
+fn hyperedge_pairs(op: &Operation) -> Result<Vec<(u32, u32)>> {
+    let sources = neuron_set_from_attr(op, "sources")?;
+    let targets = neuron_set_from_attr(op, "targets")?;
+    let pairs: Vec<(u32, u32)> = sources.iter()
+        .flat_map(|&pre| targets.iter().filter(move |&&post| post != pre).map(move |&post| (pre, post)))
+        .collect();
+    if pairs.is_empty() {
+        return Err(bad_attr(op, "targets", "hyperedge has no synapses after skipping self-pairs"));
+    }
+    Ok(pairs)
+}
+
+fn substeps_from_attr(op: &Operation) -> Result<u32> {
+    get_attr(op, "substeps")?;
+    match i64_opt_from_attr(op, "substeps")? {
+        Some(n) if (1..=10_000).contains(&n) => Ok(n as u32),
+        _ => Err(bad_attr(op, "substeps", "must be in 1..=10000")),
+    }
+}
+
+fn integrator_from_attr(op: &Operation) -> Result<IntegratorKind> {
+    string_from_attr(op, "integrator")?
+        .parse::<IntegratorKind>()
+        .map_err(|_| bad_attr(op, "integrator", "must be \"euler\", \"exponential_euler\" or \"rk4\""))
+}
+
+fn rng_from_attr(op: &Operation) -> Result<RngKind> {
+    string_from_attr(op, "rng")?
+        .parse::<RngKind>()
+        .map_err(|_| bad_attr(op, "rng", "must be \"sequential\" or \"philox\""))
+}
+
+fn backend_from_attr(op: &Operation) -> Result<ConnectivityBackend> {
+    string_from_attr(op, "backend")?
+        .parse::<ConnectivityBackend>()
+        .map_err(|_| bad_attr(op, "backend", "must be \"auto\", \"graph\", \"matrix\", \"sparse\" or \"hypergraph\""))
+}
+
+fn spike_timing_from_attr(op: &Operation) -> Result<SpikeTiming> {
+    string_from_attr(op, "spike_timing")?
+        .parse::<SpikeTiming>()
+        .map_err(|_| bad_attr(op, "spike_timing", "must be \"grid\", \"linear\" or \"exponential\""))
+}
+
+fn sub_dt_delay_from_attr(op: &Operation) -> Result<SubDtDelay> {
+    string_from_attr(op, "sub_dt_delay")?
+        .parse::<SubDtDelay>()
+        .map_err(|_| bad_attr(op, "sub_dt_delay", "must be \"next_step\", \"interpolate\" or \"reject\""))
+}
+
+fn adex_from_attrs(op: &Operation) -> Result<AdExParams> {
+    Ok(AdExParams {
+        c_m: f32_from_attr(op, "c_m")?,
+        g_l: f32_from_attr(op, "g_l")?,
+        e_l: f32_from_attr(op, "e_l")?,
+        v_t: f32_from_attr(op, "v_t")?,
+        delta_t: f32_from_attr(op, "delta_t")?,
+        a: f32_from_attr(op, "a")?,
+        tau_w: duration_ns_to_ms(op, "tau_w")?,
+        b: current_na_from_attr(op, "b")?,
+        v_reset: f32_from_attr(op, "v_reset")?,
+        v_peak: f32_from_attr(op, "v_peak")?,
+        t_refrac: duration_ns_to_ms(op, "t_refrac")?,
+    })
+}
+
+fn hh_from_attrs(op: &Operation) -> Result<HHParams> {
+    let method = string_from_attr(op, "method")?
+        .parse::<HHMethod>()
+        .map_err(|_| bad_attr(op, "method", "must be \"rk4\" or \"exponential\""))?;
+    Ok(HHParams {
+        c_m: f32_from_attr(op, "c_m")?,
+        g_na: f32_from_attr(op, "g_na")?,
+        g_k: f32_from_attr(op, "g_k")?,
+        g_l: f32_from_attr(op, "g_l")?,
+        e_na: f32_from_attr(op, "e_na")?,
+        e_k: f32_from_attr(op, "e_k")?,
+        e_l: f32_from_attr(op, "e_l")?,
+        v_init: f32_from_attr(op, "v_init")?,
+        v_spike: f32_from_attr(op, "v_spike")?,
+        max_substep: duration_ns_to_ms(op, "substep")?,
+        method,
+    })
+}
+
 fn stdp_from_attrs(op: &Operation) -> Result<STDPParams> {
     let a_plus = f32_from_attr(op, "a_plus")?;
     let a_minus = f32_from_attr(op, "a_minus")?;
@@ -706,8 +2228,8 @@ fn stdp_from_attrs(op: &Operation) -> Result<STDPParams> {
     let w_min = f32_from_attr(op, "w_min")?;
     let w_max = f32_from_attr(op, "w_max")?;
 
-    let tau_plus_ms = tau_plus_ns as f32 / 1_000_000.0;
-    let tau_minus_ms = tau_minus_ns as f32 / 1_000_000.0;
+    let tau_plus_ms = units::ns_to_ms(tau_plus_ns);
+    let tau_minus_ms = units::ns_to_ms(tau_minus_ns);
 
     let mut params = STDPParams::default();
     params.a_plus = a_plus;
@@ -720,13 +2242,337 @@ fn stdp_from_attrs(op: &Operation) -> Result<STDPParams> {
     Ok(params)
 }
 
+fn bad_attr(op: &Operation, key: &str, reason: impl Into<String>) -> CompilerError {
+    CompilerError::BadAttr {
+        key: key.to_string(),
+        dialect: op.dialect.to_string(),
+        name: op.name.clone(),
+        version: op.version.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Build the modulator described by modulation.scalar@v1 (schedule xor source)
+fn modulator_from_attrs(op: &Operation) -> Result<Modulator> {
+    match (op.attrs.contains_key("schedule"), op.attrs.contains_key("source")) {
+        (true, false) => {
+            let interpolation = match string_from_attr(op, "interpolation")? {
+                "step" => Interpolation::Step,
+                "linear" => Interpolation::Linear,
+                _ => return Err(bad_attr(op, "interpolation", "must be \"step\" or \"linear\"")),
+            };
+            let points = string_from_attr(op, "schedule")?
+                .split(';')
+                .map(|entry| {
+                    let (t, level) = entry.split_once(':')?;
+                    Some((t.trim().parse::<u64>().ok()?, level.trim().parse::<f32>().ok()?))
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| bad_attr(op, "schedule", "expected \"t_ns:level;t_ns:level\""))?;
+            Modulator::schedule(points, interpolation).map_err(|e| bad_attr(op, "schedule", e.to_string()))
+        }
+        (false, true) => {
+            let (start, end) = range_from_attr(op, "source")?;
+            let window_ns = duration_ns_from_attr(op, "window")?;
+            let gain = f32_from_attr(op, "gain")?;
+            let baseline = f32_from_attr(op, "baseline")?;
+            Modulator::population_rate(NeuronId::new(start), NeuronId::new(end), window_ns, gain, baseline)
+                .map_err(|e| bad_attr(op, "source", e.to_string()))
+        }
+        (true, true) => Err(bad_attr(op, "schedule", "schedule and source are mutually exclusive")),
+        (false, false) => Err(bad_attr(op, "schedule", "one of schedule or source is required")),
+    }
+}
+
 // Ensure neurons in [start..=end] exist; add if missing
-fn add_range_if_missing(mut builder: NetworkBuilder, added: &mut BTreeSet<u32>, start: u32, end: u32) -> NetworkBuilder {
-    for id in start..=end {
-        if !added.contains(&id) {
-            builder = builder.add_neuron(NeuronId::new(id));
-            added.insert(id);
+/// Layout described by structure.positions@v1 (coords xor grid)
+fn positions_from_attrs(op: &Operation) -> Result<NeuronPositions> {
+    let (start, end) = range_from_attr(op, "neurons")?;
+    if start > end {
+        return Err(bad_attr(op, "neurons", "start must be <= end"));
+    }
+    let count = u64::from(end - start) + 1;
+    match (op.attrs.contains_key("coords"), op.attrs.contains_key("nx")) {
+        (true, false) => {
+            let coords = string_from_attr(op, "coords")?
+                .split(';')
+                .map(|entry| {
+                    let values = entry.split(':')
+                        .map(|v| v.trim().parse::<f32>().ok().filter(|v| v.is_finite()))
+                        .collect::<Option<Vec<_>>>()?;
+                    match values[..] {
+                        [x, y] => Some([x, y, 0.0]),
+                        [x, y, z] => Some([x, y, z]),
+                        _ => None,
+                    }
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| bad_attr(op, "coords", "expected \"x:y[:z];x:y[:z]\" with finite values"))?;
+            if coords.len() as u64 != count {
+                return Err(bad_attr(op, "coords", format!("lists {} positions for {} neurons", coords.len(), count)));
+            }
+            let mut positions = NeuronPositions::new();
+            for (id, position) in (start..=end).zip(coords) {
+                positions.insert(NeuronId::new(id), position);
+            }
+            Ok(positions)
+        }
+        (false, true) => {
+            let dim = |key: &str| -> Result<u32> {
+                match i64_opt_from_attr(op, key)? {
+                    None => Ok(1),
+                    Some(n) if (1..=i64::from(u32::MAX)).contains(&n) => Ok(n as u32),
+                    Some(_) => Err(bad_attr(op, key, "must be >= 1")),
+                }
+            };
+            let shape = [dim("nx")?, dim("ny")?, dim("nz")?];
+            let cells: u64 = shape.iter().map(|&n| u64::from(n)).product();
+            if cells != count {
+                return Err(bad_attr(op, "neurons", format!("covers {} neurons but nx * ny * nz = {}", count, cells)));
+            }
+            let spacing = f32_from_attr(op, "spacing")?;
+            NeuronPositions::grid(NeuronId::new(start), shape, spacing).map_err(|e| match e {
+                shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+                    bad_attr(op, &parameter, format!("must be {}", constraint))
+                }
+                other => CompilerError::Runtime(other),
+            })
         }
+        (true, true) => Err(bad_attr(op, "coords", "coords and nx/ny/nz are mutually exclusive")),
+        (false, false) => Err(bad_attr(op, "coords", "one of coords or nx is required")),
+    }
+}
+
+/// Snapshot named by connectivity.from_snapshot@v1: a VCSR file, or a generation
+/// (default latest) of a hypergraph store directory
+fn snapshot_from_attrs(op: &Operation) -> Result<VCSRSnapshot> {
+    let path = Path::new(string_from_attr(op, "path")?);
+    let generation = i64_opt_from_attr(op, "generation")?.map(|g| GenerationId::new(g as u64));
+    let fail = |e: shnn_storage::StorageError| {
+        CompilerError::Message(format!("failed to load snapshot '{}': {}", path.display(), e))
+    };
+    if path.is_dir() {
+        let store = FileStore::new(path).map_err(fail)?;
+        let generation = match generation {
+            Some(g) => g,
+            None => store.latest_generation().map_err(fail)?,
+        };
+        return Ok(store.get_snapshot(generation).map_err(fail)?.as_vcsr().clone());
+    }
+    let bytes = std::fs::read(path).map_err(|e| fail(e.into()))?;
+    let snapshot = VCSRSnapshot::from_bytes(&bytes).map_err(fail)?;
+    match generation {
+        Some(g) if g.raw() != snapshot.header.generation => Err(bad_attr(op, "generation", format!(
+            "{} holds generation {}", path.display(), snapshot.header.generation
+        ))),
+        _ => Ok(snapshot),
+    }
+}
+
+/// Rule and inclusive (sources, targets) ranges of connectivity.distance_gaussian@v1
+fn distance_rule_from_attrs(op: &Operation) -> Result<(DistanceRule, (u32, u32), (u32, u32))> {
+    let sources = range_from_attr(op, "sources")?;
+    let targets = range_from_attr(op, "targets")?;
+    for (key, (start, end)) in [("sources", sources), ("targets", targets)] {
+        if start > end {
+            return Err(bad_attr(op, key, "range must satisfy start <= end"));
+        }
+    }
+    let profile = string_from_attr(op, "weight_profile")?
+        .parse::<WeightProfile>()
+        .map_err(|_| bad_attr(op, "weight_profile", "must be \"constant\" or \"gaussian\""))?;
+    let rule = DistanceRule {
+        sigma: f32_from_attr(op, "sigma")?,
+        p_max: f32_from_attr(op, "p_max")?,
+        weight: f32_from_attr(op, "weight")?,
+        profile,
+    };
+    rule.validate().map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })?;
+    Ok((rule, sources, targets))
+}
+
+/// Recipe and inclusive (neurons, inputs, outputs) ranges of structure.reservoir@v1
+fn reservoir_from_attrs(op: &Operation) -> Result<(Reservoir, (u32, u32), (u32, u32), (u32, u32))> {
+    let neurons = range_from_attr(op, "neurons")?;
+    let inputs = range_from_attr(op, "inputs")?;
+    let outputs = range_from_attr(op, "outputs")?;
+    for (key, (start, end)) in [("neurons", neurons), ("inputs", inputs), ("outputs", outputs)] {
+        if start > end {
+            return Err(bad_attr(op, key, "range must satisfy start <= end"));
+        }
+    }
+    if inputs.0 <= neurons.1 && neurons.0 <= inputs.1 {
+        return Err(bad_attr(op, "inputs", "must not overlap the reservoir neurons"));
+    }
+    if outputs.0 < neurons.0 || outputs.1 > neurons.1 {
+        return Err(bad_attr(op, "outputs", "must lie within the reservoir neurons"));
+    }
+    let rule = Reservoir {
+        p: f32_from_attr(op, "p")?,
+        radius: f32_from_attr(op, "radius")?,
+        input_p: f32_from_attr(op, "input_p")?,
+        input_weight: f32_from_attr(op, "input_weight")?,
+    };
+    rule.validate().map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })?;
+    Ok((rule, neurons, inputs, outputs))
+}
+
+/// First structure.reservoir@v1 op, whose ranges runtime.readout@v1 reads by default
+fn first_reservoir(module: &Module) -> Option<&Operation> {
+    module.ops.iter().find(|op| op.dialect == DialectKey::Structure && op.name == "reservoir")
+}
+
+fn readout_from_attrs(op: &Operation, reservoir: Option<&Operation>) -> Result<ReadoutMonitor> {
+    let role = string_from_attr(op, "role")?
+        .parse::<ReadoutRole>()
+        .map_err(|_| bad_attr(op, "role", "must be \"state\" or \"target\""))?;
+    let (start, end) = match (op.attrs.contains_key("neurons"), reservoir) {
+        (true, _) => range_from_attr(op, "neurons")?,
+        (false, Some(reservoir)) => {
+            let (_, _, inputs, outputs) = reservoir_from_attrs(reservoir)?;
+            match role {
+                ReadoutRole::State => outputs,
+                ReadoutRole::Target => inputs,
+            }
+        }
+        (false, None) => return Err(bad_attr(op, "neurons", "is required without a structure.reservoir@v1 op")),
+    };
+    let tau_ns = duration_ns_from_attr(op, "tau")?;
+    let interval_ns = duration_ns_from_attr(op, "interval")?;
+    ReadoutMonitor::new(NeuronId::new(start), NeuronId::new(end), tau_ns, interval_ns, role).map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })
+}
+
+/// Inclusive neuron range and `(pre, post, weight)` synapses of a structure.wta@v1 op:
+/// all-to-all lateral inhibition plus optional self-excitation
+pub(crate) fn wta_synapses(op: &Operation) -> Result<((u32, u32), Vec<(u32, u32, f32)>)> {
+    get_attr(op, "n")?;
+    let n = match i64_opt_from_attr(op, "n")? {
+        Some(n) if (2..=u32::MAX as i64).contains(&n) => n as u32,
+        _ => return Err(bad_attr(op, "n", "must be in 2..=4294967295")),
+    };
+    let start = match i64_opt_from_attr(op, "start")? {
+        None => 0,
+        Some(s) if (0..=(u32::MAX - (n - 1)) as i64).contains(&s) => s as u32,
+        Some(_) => return Err(bad_attr(op, "start", "must be >= 0 with start + n - 1 within u32")),
+    };
+    let inhibition = f32_from_attr(op, "inhibition_weight")?;
+    if !(inhibition.is_finite() && inhibition >= 0.0) {
+        return Err(bad_attr(op, "inhibition_weight", "must be >= 0 and finite (the magnitude of the inhibition)"));
+    }
+    let excitation = f32_from_attr(op, "self_excitation")?;
+    if !(excitation.is_finite() && excitation >= 0.0) {
+        return Err(bad_attr(op, "self_excitation", "must be >= 0 and finite"));
+    }
+    let end = start + (n - 1);
+    let synapses = (start..=end)
+        .flat_map(|pre| {
+            (start..=end).filter_map(move |post| match pre == post {
+                false => Some((pre, post, -inhibition)),
+                true if excitation > 0.0 => Some((pre, post, excitation)),
+                true => None,
+            })
+        })
+        .collect();
+    Ok(((start, end), synapses))
+}
+
+/// Name and inclusive range of a structure.port@v1
+pub(crate) fn port_from_attrs(op: &Operation) -> Result<(&str, (u32, u32))> {
+    let name = string_from_attr(op, "name")?;
+    if name.is_empty() || name.chars().any(char::is_whitespace) {
+        return Err(bad_attr(op, "name", "must be non-empty without whitespace"));
+    }
+    let (start, end) = range_from_attr(op, "neurons")?;
+    if start > end {
+        return Err(bad_attr(op, "neurons", "start must be <= end"));
+    }
+    Ok((name, (start, end)))
+}
+
+/// Name, size and (once laid out) id range of a structure.population@v1 op
+pub(crate) fn population_from_attrs(op: &Operation) -> Result<(&str, u32, Option<(u32, u32)>)> {
+    let name = string_from_attr(op, "name")?;
+    if !shnn_ir::is_symbol_name(name) {
+        return Err(bad_attr(op, "name", "must start with a letter or '_' and hold only letters, digits, '_' and single '.'"));
+    }
+    get_attr(op, "size")?;
+    let size = match i64_opt_from_attr(op, "size")? {
+        Some(n) if (1..=u32::MAX as i64).contains(&n) => n as u32,
+        _ => return Err(bad_attr(op, "size", "must be in 1..=4294967295")),
+    };
+    let neurons = match op.attrs.contains_key("neurons") {
+        true => Some(range_from_attr(op, "neurons")?),
+        false => None,
+    };
+    if let Some((start, end)) = neurons {
+        if start > end || end - start != size - 1 {
+            return Err(bad_attr(op, "neurons", format!("{}..{} does not hold size = {} neurons", start, end, size)));
+        }
+    }
+    Ok((name, size, neurons))
+}
+
+/// Recipe of a structure.ei_network@v1 op
+pub(crate) fn brunel_from_attrs(op: &Operation) -> Result<BrunelNetwork> {
+    let count = |key: &str| -> Result<u32> {
+        get_attr(op, key)?;
+        match i64_opt_from_attr(op, key)? {
+            Some(n) if (1..=u32::MAX as i64).contains(&n) => Ok(n as u32),
+            _ => Err(bad_attr(op, key, "must be in 1..=4294967295")),
+        }
+    };
+    let net = BrunelNetwork {
+        n_exc: count("n_exc")?,
+        n_inh: count("n_inh")?,
+        p: f32_from_attr(op, "p")?,
+        g: f32_from_attr(op, "g")?,
+        j_mv: f32_from_attr(op, "j")?,
+        nu_ext_hz: rate_hz_from_attr(op, "nu_ext")?,
+    };
+    net.validate().map_err(|e| match e {
+        shnn_runtime::RuntimeError::InvalidParameter { parameter, constraint, .. } => {
+            bad_attr(op, &parameter, format!("must be {}", constraint))
+        }
+        other => CompilerError::Runtime(other),
+    })?;
+    Ok(net)
+}
+
+fn add_range_if_missing(builder: NetworkBuilder, added: &mut BTreeSet<u32>, start: u32, end: u32) -> NetworkBuilder {
+    add_ids_if_missing(builder, added, start..=end)
+}
+
+/// Add the `ids` not in `added` yet, as runs of contiguous ids
+fn add_ids_if_missing(
+    mut builder: NetworkBuilder,
+    added: &mut BTreeSet<u32>,
+    ids: impl IntoIterator<Item = u32>,
+) -> NetworkBuilder {
+    let mut missing: Vec<u32> = ids.into_iter().filter(|&id| added.insert(id)).collect();
+    missing.sort_unstable();
+    let mut i = 0;
+    while i < missing.len() {
+        let start = missing[i];
+        while i + 1 < missing.len() && missing[i + 1] == missing[i] + 1 {
+            i += 1;
+        }
+        builder = builder.add_neuron_range(start..=missing[i]);
+        i += 1;
     }
     builder
 }
@@ -748,4 +2594,895 @@ mod tests {
         let res = compile_module(&m).expect("compile").run().expect("run");
         assert!(res.steps_executed > 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn verify_modulation_scalar() {
+        let mut m = Module::new();
+        m.push(modulation_scalar_schedule_v1(&[(0.0, 1.0), (5.0, 0.0)], false));
+        m.push(modulation_scalar_rate_v1(0, 3, 10.0, 0.1, 0.0));
+        verify_module(&m).expect("valid modulation");
+
+        let bad = |op: Operation| {
+            let mut m = Module::new();
+            m.push(op);
+            verify_module(&m).is_err()
+        };
+        assert!(bad(Operation::new(DialectKey::Modulation, "scalar", OpVersion(1))));
+        assert!(bad(modulation_scalar_schedule_v1(&[(5.0, 1.0), (0.0, 0.0)], false)));
+        assert!(bad(modulation_scalar_rate_v1(3, 0, 10.0, 0.1, 0.0)));
+        assert!(bad(modulation_scalar_rate_v1(0, 3, 0.0, 0.1, 0.0)));
+        assert!(bad(modulation_scalar_schedule_v1(&[(0.0, 1.0)], false)
+            .with_attr("source", AttributeValue::RangeU32 { start: 0, end: 1 })));
+    }
+
+    #[test]
+    fn lower_zero_modulation_freezes_weights() {
+        let build = |modulated: bool| {
+            let mut m = Module::new();
+            m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+            m.push(stdp_rule_v1(0.01, 0.012, 20.0, 20.0, 0.0, 1.0));
+            m.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+            // Drive pre and post populations so STDP sees paired spikes
+            for n in 0..4 {
+                m.push(stimulus_poisson_v1(n, 500.0, 50.0, 0.0, 200.0));
+            }
+            if modulated {
+                m.push(modulation_scalar_schedule_v1(&[(0.0, 0.0)], false));
+            }
+            m.push(runtime_simulate_run_v1(0.1, 200.0, false, Some(3)));
+            let mut program = compile_module(&m).expect("compile");
+            program.engine.run().expect("run");
+            program.engine.network().synapse_connections()
+        };
+        assert!(build(false).iter().any(|&(_, _, w)| w != 0.5));
+        assert!(build(true).iter().all(|&(_, _, w)| w == 0.5));
+    }
+
+    #[test]
+    fn verify_apply_mask_mode() {
+        let mut m = Module::new();
+        m.push(runtime_apply_mask_v1("task.vmsk", "plasticity"));
+        verify_module(&m).expect("valid mode");
+
+        let mut bad = Module::new();
+        bad.push(runtime_apply_mask_v1("task.vmsk", "weights"));
+        assert!(matches!(verify_module(&bad), Err(CompilerError::BadAttr { ref key, .. }) if key == "mode"));
+    }
+
+    #[test]
+    fn verify_adex_bounds() {
+        let adex = |v_peak: f32, a: f32| adex_neuron_v1(0.281, 30.0, -70.6, -50.4, 2.0, a, 144.0, 0.0805, -70.6, v_peak, None);
+        let check = |op: Operation, dt_ms: f32| {
+            let mut m = Module::new();
+            m.push(op);
+            m.push(runtime_simulate_run_v1(dt_ms, 10.0, false, None));
+            verify_module(&m)
+        };
+        check(adex(20.0, 4.0), 0.1).expect("valid adex");
+
+        let key_of = |r: super::Result<()>| match r {
+            Err(CompilerError::BadAttr { key, .. }) => key,
+            other => panic!("expected BadAttr, got {:?}", other.map(|_| ())),
+        };
+        assert_eq!(key_of(check(adex(-60.0, 4.0), 0.1)), "v_peak");
+        assert_eq!(key_of(check(adex(20.0, -30.0), 0.1)), "a");
+        // C/g_L is ~9.4 ms; a 10 ms step cannot resolve the membrane dynamics
+        assert_eq!(key_of(check(adex(20.0, 4.0), 10.0)), "c_m");
+    }
+
+    #[test]
+    fn lower_adex_population() {
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(adex_neuron_v1(0.281, 30.0, -70.6, -50.4, 2.0, 4.0, 144.0, 0.0805, -70.6, 20.0, Some((2, 3))));
+        m.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+        m.push(runtime_simulate_run_v1(0.1, 10.0, false, None));
+        verify_module(&m).expect("verify");
+
+        let program = compile_module(&m).expect("compile");
+        let network = program.engine.network();
+        assert_eq!(network.get_membrane_potential(NeuronId::new(1)).unwrap(), -70.0);
+        assert_eq!(network.get_membrane_potential(NeuronId::new(2)).unwrap(), -70.6);
+        assert_eq!(network.get_membrane_potential(NeuronId::new(3)).unwrap(), -70.6);
+    }
+
+    #[test]
+    fn lower_alif_population() {
+        let alif = |beta: f32, tau_adapt_ms: f32| {
+            alif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0, beta, tau_adapt_ms, Some((2, 3)))
+        };
+        let build = |op: Operation| {
+            let mut m = Module::new();
+            m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+            m.push(op);
+            m.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+            m.push(runtime_simulate_run_v1(0.1, 10.0, false, None));
+            m
+        };
+        verify_module(&build(alif(2.0, 100.0))).expect("verify");
+
+        // ALIF neurons carry the threshold elevation as a model variable
+        let program = compile_module(&build(alif(2.0, 100.0))).expect("compile");
+        let state = program.engine.network().capture_state();
+        let vars: Vec<usize> = state.neurons.iter().map(|n| n.vars.len()).collect();
+        assert_eq!(vars, vec![0, 0, 1, 1]);
+
+        let key_of = |r: super::Result<()>| match r {
+            Err(CompilerError::BadAttr { key, .. }) => key,
+            other => panic!("expected BadAttr, got {:?}", other.map(|_| ())),
+        };
+        assert_eq!(key_of(verify_module(&build(alif(-1.0, 100.0)))), "beta");
+        assert_eq!(key_of(verify_module(&build(alif(2.0, 0.0)))), "tau_adapt");
+    }
+
+    #[test]
+    fn lower_ei_network_template() {
+        // ν_ext = 2 ν_thr: 8 external inputs of 0.1 mV each need 1250 Hz to reach threshold on average
+        let build = |n_exc: u32, p: f32| {
+            let mut m = Module::new();
+            m.push(structure_ei_network_v1(n_exc, 20, p, 5.0, 2500.0));
+            m.push(runtime_simulate_run_v1(0.1, 100.0, false, Some(3)));
+            m
+        };
+        verify_module(&build(80, 0.1)).expect("verify");
+
+        let raw = compile_module(&build(80, 0.1)).expect("compile");
+        assert_eq!(raw.engine.network().neuron_count(), 100);
+        // 20 kHz of drive per neuron, split so each stimulus fires with probability 0.1 per step
+        assert_eq!(raw.stimuli.len(), 100 * 20);
+        let raw = raw.run().expect("run");
+        assert!(raw.total_spikes > 0);
+        let piped = compile_with_passes(&build(80, 0.1)).expect("compile").run().expect("run");
+        assert_eq!(piped.total_spikes, raw.total_spikes);
+
+        assert!(matches!(verify_module(&build(0, 0.1)), Err(CompilerError::BadAttr { ref key, .. }) if key == "n_exc"));
+        assert!(matches!(verify_module(&build(80, 1.5)), Err(CompilerError::BadAttr { ref key, .. }) if key == "p"));
+        let mut no_run = Module::new();
+        no_run.push(structure_ei_network_v1(80, 20, 0.1, 5.0, 2500.0));
+        assert!(matches!(verify_module(&no_run), Err(CompilerError::Message(_))));
+    }
+
+    #[test]
+    fn lower_reservoir_with_readouts() {
+        let build = |outputs: (u32, u32)| {
+            let mut m = Module::new();
+            m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+            m.push(structure_reservoir_v1((2, 51), (0, 1), outputs, 300.0, 500.0));
+            m.push(runtime_readout_v1(None, "state", 20.0, 5.0));
+            m.push(runtime_readout_v1(None, "target", 20.0, 5.0));
+            m.push(stimulus_poisson_v1(0, 100.0, 500.0, 0.0, 100.0));
+            m.push(runtime_simulate_run_v1(0.1, 100.0, false, Some(3)));
+            m
+        };
+        verify_module(&build((2, 11))).expect("verify");
+
+        let mut program = compile_module(&build((2, 11))).expect("compile");
+        assert_eq!(program.engine.network().neuron_count(), 52);
+        let res = program.engine.run().expect("run");
+        assert_eq!(res.readouts.len(), 2);
+        let (state, target) = (&res.readouts[0], &res.readouts[1]);
+        assert_eq!((state.start.raw(), state.end.raw(), state.role), (2, 11, ReadoutRole::State));
+        assert_eq!((target.start.raw(), target.end.raw(), target.role), (0, 1, ReadoutRole::Target));
+        assert_eq!(state.times_ns.len(), 20);
+        assert!(state.states.iter().all(|row| row.len() == 10));
+        // The driven input fires, so its trace is non-zero
+        assert!(target.states.iter().any(|row| row[0] > 0.0));
+
+        assert!(matches!(verify_module(&build((40, 60))), Err(CompilerError::BadAttr { ref key, .. }) if key == "outputs"));
+        let mut orphan = Module::new();
+        orphan.push(runtime_readout_v1(None, "state", 20.0, 5.0));
+        assert!(matches!(verify_module(&orphan), Err(CompilerError::BadAttr { ref key, .. }) if key == "neurons"));
+        let mut bad_role = Module::new();
+        bad_role.push(runtime_readout_v1(Some((0, 1)), "output", 20.0, 5.0));
+        assert!(matches!(verify_module(&bad_role), Err(CompilerError::BadAttr { ref key, .. }) if key == "role"));
+    }
+
+    #[test]
+    fn lower_wta_circuit() {
+        let build = |n: u32, inhibition: f32| {
+            let mut m = Module::new();
+            m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+            m.push(structure_wta_v1(n, inhibition, 100.0));
+            m.push(stimulus_poisson_v1(0, 200.0, 500.0, 0.0, 100.0));
+            m.push(stimulus_poisson_v1(1, 200.0, 500.0, 0.0, 100.0));
+            m.push(runtime_simulate_run_v1(0.1, 100.0, false, Some(5)));
+            m
+        };
+        verify_module(&build(3, 400.0)).expect("verify");
+
+        let raw = compile_module(&build(3, 400.0)).expect("compile");
+        let network = raw.engine.network();
+        assert_eq!(network.neuron_count(), 3);
+        assert_eq!(network.synapse_count(), 9);
+        assert_eq!(network.get_weight(NeuronId::new(0), NeuronId::new(2)).unwrap(), -400.0);
+        assert_eq!(network.get_weight(NeuronId::new(1), NeuronId::new(1)).unwrap(), 100.0);
+        let raw = raw.run().expect("run");
+        let piped = compile_with_passes(&build(3, 400.0)).expect("compile").run().expect("run");
+        assert_eq!(piped.total_spikes, raw.total_spikes);
+
+        assert!(matches!(verify_module(&build(1, 400.0)), Err(CompilerError::BadAttr { ref key, .. }) if key == "n"));
+        assert!(matches!(verify_module(&build(3, -1.0)), Err(CompilerError::BadAttr { ref key, .. }) if key == "inhibition_weight"));
+    }
+
+    #[test]
+    fn lower_hh_population() {
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(hh_neuron_v1((1, 1), 1.0, 120.0, 36.0, 0.3, 50.0, -77.0, -54.387, 0.01, "exponential"));
+        m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 1.0));
+        m.push(stimulus_poisson_v1(0, 200.0, 100.0, 0.0, 50.0));
+        m.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(7)));
+        verify_module(&m).expect("verify");
+
+        let mut program = compile_module(&m).expect("compile");
+        assert_eq!(program.engine.network().get_membrane_potential(NeuronId::new(1)).unwrap(), -65.0);
+        let res = program.engine.run().expect("run");
+        assert!(res.steps_executed > 0);
+
+        let mut bad = Module::new();
+        bad.push(hh_neuron_v1((0, 0), 1.0, 120.0, 36.0, 0.3, 50.0, -77.0, -54.387, 0.01, "euler"));
+        assert!(matches!(verify_module(&bad), Err(CompilerError::BadAttr { ref key, .. }) if key == "method"));
+    }
+
+    #[test]
+    fn lower_simulate_run_integrator() {
+        let build = |integrator: &str| {
+            let mut m = Module::new();
+            m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+            m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 1.0));
+            m.push(runtime_simulate_run_v1(0.1, 10.0, false, None)
+                .with_attr("integrator", AttributeValue::String(integrator.into())));
+            m
+        };
+        verify_module(&build("exponential_euler")).expect("valid integrator");
+        let program = compile_module(&build("rk4")).expect("compile");
+        assert_eq!(program.engine.network().config().integrator, IntegratorKind::Rk4);
+
+        assert!(matches!(verify_module(&build("midpoint")), Err(CompilerError::BadAttr { ref key, .. }) if key == "integrator"));
+    }
+
+    #[test]
+    fn lower_simulate_run_spike_timing() {
+        let build = |timing: &str| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 1.0));
+            m.push(stimulus_poisson_v1(0, 400.0, 100.0, 0.0, 50.0));
+            m.push(runtime_simulate_run_v1(1.0, 50.0, false, Some(2))
+                .with_attr("spike_timing", AttributeValue::String(timing.into())));
+            m
+        };
+        let program = compile_module(&build("exponential")).expect("compile");
+        assert_eq!(program.engine.network().config().spike_timing, SpikeTiming::Exponential);
+        let grid = compile_module(&build("grid")).expect("compile").run().expect("run");
+        assert!(grid.spikes.iter().all(|s| s.time.nanos() % 1_000_000 == 0));
+        let linear = compile_module(&build("linear")).expect("compile").run().expect("run");
+        assert!(linear.spikes.iter().any(|s| s.time.nanos() % 1_000_000 != 0));
+        assert!(matches!(verify_module(&build("cubic")), Err(CompilerError::BadAttr { ref key, .. }) if key == "spike_timing"));
+    }
+
+    #[test]
+    fn lower_simulate_run_sub_dt_delay() {
+        // 0.1 ms delay at dt = 1 ms
+        let build = |mode: &str| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 0.1));
+            m.push(runtime_simulate_run_v1(1.0, 10.0, false, None)
+                .with_attr("sub_dt_delay", AttributeValue::String(mode.into())));
+            m
+        };
+        let program = compile_module(&build("interpolate")).expect("compile");
+        assert_eq!(program.engine.network().config().sub_dt_delay, SubDtDelay::Interpolate);
+        verify_module(&build("next_step")).expect("sub-dt delays are delivered on the next step by default");
+        assert!(matches!(verify_module(&build("reject")), Err(CompilerError::BadAttr { ref key, .. }) if key == "delay"));
+        assert!(matches!(verify_module(&build("truncate")), Err(CompilerError::BadAttr { ref key, .. }) if key == "sub_dt_delay"));
+    }
+
+    #[test]
+    fn lower_lif_refractory() {
+        let build = |lif: Operation| {
+            let mut m = Module::new();
+            m.push(lif);
+            m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 1.0));
+            m.push(runtime_simulate_run_v1(1.0, 10.0, false, None));
+            m
+        };
+        let lif = || lif_neuron_v1(20.0, -70.0, -80.0, -50.0, 2.0, 10.0, 1.0);
+        let program = compile_module(&build(lif())).expect("compile");
+        assert_eq!(program.engine.network().config().default_lif_params.refractory, Refractoriness::HoldAtReset);
+
+        let relative = lif()
+            .with_attr("refractory", AttributeValue::String("relative".into()))
+            .with_attr("refrac_jump", AttributeValue::VoltageMv(4.0))
+            .with_attr("tau_refrac", AttributeValue::DurationNs(5_000_000));
+        let program = compile_module(&build(relative.clone())).expect("compile");
+        assert_eq!(
+            program.engine.network().config().default_lif_params.refractory,
+            Refractoriness::Relative { jump: 4.0, tau: 5.0 }
+        );
+        let err = codegen::micro_tables(&build(relative.clone())).unwrap_err().to_string();
+        assert!(err.contains("refractory"), "{}", err);
+
+        let bad_tau = relative.with_attr("tau_refrac", AttributeValue::DurationNs(0));
+        assert!(matches!(verify_module(&build(bad_tau)), Err(CompilerError::BadAttr { ref key, .. }) if key == "tau_refrac"));
+        let bad_policy = lif().with_attr("refractory", AttributeValue::String("absolute".into()));
+        assert!(matches!(verify_module(&build(bad_policy)), Err(CompilerError::BadAttr { ref key, .. }) if key == "refractory"));
+    }
+
+    #[test]
+    fn lower_simulate_run_rng() {
+        let build = |rng: &str| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 1.0));
+            m.push(runtime_simulate_run_v1(0.1, 10.0, false, None)
+                .with_attr("rng", AttributeValue::String(rng.into())));
+            m
+        };
+        let program = compile_module(&build("philox")).expect("compile");
+        assert_eq!(program.engine.params().rng, RngKind::Philox);
+        assert!(matches!(verify_module(&build("mt19937")), Err(CompilerError::BadAttr { ref key, .. }) if key == "rng"));
+    }
+
+    #[test]
+    fn registry_defaults_match_runtime_defaults() {
+        let mut run = runtime_simulate_run_v1(0.1, 10.0, false, None);
+        assert_eq!(integrator_from_attr(&run).expect("integrator"), IntegratorKind::default());
+        assert_eq!(rng_from_attr(&run).expect("rng"), RngKind::default());
+        assert_eq!(backend_from_attr(&run).expect("backend"), ConnectivityBackend::default());
+        assert!(!bool_from_attr(&run, "realtime").expect("realtime"));
+        assert!(!bool_from_attr(&run, "record_weight_updates").expect("record_weight_updates"));
+        // Required attrs have no default
+        run.attrs.remove("dt");
+        assert!(matches!(duration_ns_from_attr(&run, "dt"), Err(CompilerError::MissingAttr(..))));
+
+        let mut hh = hh_neuron_v1((0, 0), 1.0, 120.0, 36.0, 0.3, 50.0, -77.0, -54.387, 0.01, "rk4");
+        hh.attrs.remove("substep");
+        hh.attrs.remove("method");
+        assert_eq!(hh_from_attrs(&hh).expect("hh"), HHParams::default());
+    }
+
+    #[test]
+    fn lower_simulate_run_backend() {
+        let build = |backend: Option<&str>| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v1(0, 9, 10, 19, 0.5, 1.0));
+            let mut run = runtime_simulate_run_v1(0.1, 1.0, false, None);
+            if let Some(name) = backend {
+                run = run.with_attr("backend", AttributeValue::String(name.into()));
+            }
+            m.push(run);
+            m
+        };
+        let backend = |m: &Module| compile_module(m).expect("compile").engine.network().connectivity_backend();
+        // 100 of 400 pairs connected sits at the dense threshold
+        assert_eq!(backend(&build(None)), ConnectivityBackend::Matrix);
+        assert_eq!(backend(&build(Some("hypergraph"))), ConnectivityBackend::Hypergraph);
+
+        let mut sparse = Module::new();
+        sparse.push(layer_fully_connected_v1(0, 0, 1, 99, 0.5, 1.0));
+        sparse.push(runtime_simulate_run_v1(0.1, 1.0, false, None));
+        assert_eq!(backend(&sparse), ConnectivityBackend::Sparse);
+
+        assert!(matches!(verify_module(&build(Some("csr"))), Err(CompilerError::BadAttr { ref key, .. }) if key == "backend"));
+    }
+
+    #[test]
+    fn lower_simulate_run_realtime() {
+        let build = |realtime: Option<AttributeValue>| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 1.0));
+            let mut run = runtime_simulate_run_v1(0.1, 10.0, false, None);
+            if let Some(value) = realtime {
+                run = run.with_attr("realtime", value);
+            }
+            m.push(run);
+            m
+        };
+        assert!(!compile_module(&build(None)).expect("compile").engine.params().realtime);
+        let program = compile_module(&build(Some(AttributeValue::Bool(true)))).expect("compile");
+        assert!(program.engine.params().realtime);
+        assert!(matches!(
+            verify_module(&build(Some(AttributeValue::I64(1)))),
+            Err(CompilerError::BadAttr { ref key, .. }) if key == "realtime"
+        ));
+    }
+
+    #[test]
+    fn lower_population_dt() {
+        let build = |substeps: i64| {
+            let mut m = Module::new();
+            m.push(hh_neuron_v1((1, 1), 1.0, 120.0, 36.0, 0.3, 50.0, -77.0, -54.387, 0.01, "rk4"));
+            m.push(runtime_population_dt_v1(1, 1, substeps));
+            m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 1.0));
+            m.push(runtime_simulate_run_v1(0.1, 5.0, false, None));
+            m
+        };
+        verify_module(&build(10)).expect("verify");
+        let program = compile_module(&build(10)).expect("compile");
+        assert_eq!(program.engine.network().substeps(NeuronId::new(0)), 1);
+        assert_eq!(program.engine.network().substeps(NeuronId::new(1)), 10);
+
+        for bad in [0, 3] {
+            assert!(matches!(verify_module(&build(bad)), Err(CompilerError::BadAttr { ref key, .. }) if key == "substeps"));
+        }
+    }
+
+    #[test]
+    fn lower_ou_noise() {
+        let build = |sigma: f32, end: u32| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v1(0, 1, 2, 2, 0.5, 1.0));
+            m.push(stimulus_ou_noise_v1(0, end, 0.5, sigma, 5.0, None));
+            m.push(runtime_simulate_run_v1(0.1, 20.0, true, Some(9)));
+            m
+        };
+        verify_module(&build(0.5, 1)).expect("verify");
+        let res = compile_module(&build(0.5, 1)).expect("compile").run().expect("run");
+        let v = |id: u32| res.potentials_for_neuron(NeuronId::new(id)).last().unwrap().potential;
+        assert!(v(0) > -70.0 && v(1) > -70.0);
+        assert_ne!(v(0), v(1));
+
+        assert!(matches!(verify_module(&build(-1.0, 1)), Err(CompilerError::BadAttr { ref key, .. }) if key == "sigma"));
+        assert!(matches!(compile_module(&build(0.5, 9)), Err(CompilerError::BadAttr { ref key, .. }) if key == "neurons"));
+    }
+
+    #[test]
+    fn lower_external_weight_matrix() {
+        let dir = std::env::temp_dir().join(format!("shnn-compiler-external-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("tmp dir");
+        let path = dir.join("w.csv");
+        std::fs::write(&path, "0.1, 0.2, 0.3\n0.4, 0.5, 0.6\n").expect("write csv");
+        let build = |shape: &[usize]| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v2(0, 1, 2, 4, 0.0, DelayDist::Constant { delay_ms: 1.0 }, None)
+                .with_attr("weight", AttributeValue::external(path.to_str().unwrap(), DataType::F32, shape)));
+            m.push(runtime_simulate_run_v1(0.1, 1.0, false, None));
+            m
+        };
+
+        let m = build(&[2, 3]);
+        verify_module(&m).expect("verify");
+        let program = compile_module(&m).expect("compile");
+        let mut weights = program.engine.network().synapse_connections();
+        weights.sort_by_key(|(pre, post, _)| (pre.raw(), post.raw()));
+        assert_eq!(weights[1], (NeuronId::new(0), NeuronId::new(3), 0.2));
+        assert_eq!(weights[5], (NeuronId::new(1), NeuronId::new(4), 0.6));
+
+        // The declared shape is checked against the ranges without reading the file
+        assert!(matches!(verify_module(&build(&[3, 2])), Err(CompilerError::BadAttr { ref key, .. }) if key == "weight"));
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(compile_module(&m).is_err());
+    }
+
+    #[test]
+    fn lower_from_snapshot() {
+        let mut source = Module::new();
+        source.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+        source.push(synapse_connect_v1(3, 0, 0.25, 1.0));
+        source.push(runtime_simulate_run_v1(0.1, 1.0, false, None));
+        let source = compile_module(&source).expect("compile source");
+        let snapshot = source.engine.network()
+            .to_vcsr_snapshot(GenerationId::new(3))
+            .expect("snapshot");
+        let dir = std::env::temp_dir().join(format!("shnn-compiler-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("tmp dir");
+        let path = dir.join("net.vcsr");
+        std::fs::write(&path, snapshot.to_bytes()).expect("write snapshot");
+
+        let build = |generation: Option<u64>| {
+            let mut m = Module::new();
+            m.push(connectivity_from_snapshot_v1(path.to_str().unwrap(), generation, 2.0));
+            m.push(runtime_simulate_run_v1(0.1, 1.0, false, None));
+            m
+        };
+        let m = build(Some(3));
+        verify_module(&m).expect("verify");
+        assert_eq!(program_shape(&m).expect("shape").synapses, 5);
+        let program = compile_module(&m).expect("compile");
+        let sorted = |mut synapses: Vec<(NeuronId, NeuronId, f32)>| {
+            synapses.sort_by_key(|(pre, post, _)| (pre.raw(), post.raw()));
+            synapses
+        };
+        let net = program.engine.network();
+        assert_eq!(sorted(net.synapse_connections()), sorted(source.engine.network().synapse_connections()));
+        assert_eq!(net.get_delay(NeuronId::new(3), NeuronId::new(0)).expect("delay"), 2.0);
+
+        assert!(matches!(compile_module(&build(Some(4))), Err(CompilerError::BadAttr { ref key, .. }) if key == "generation"));
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(compile_module(&build(None)).is_err());
+    }
+
+    #[test]
+    fn lower_save_and_load_state() {
+        let dir = std::env::temp_dir().join(format!("shnn-compiler-state-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("tmp dir");
+        let path = dir.join("mid.state");
+        let path = path.to_str().unwrap();
+        let build = |extra: Operation| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+            m.push(stimulus_poisson_v1(0, 400.0, 30.0, 0.0, 20.0));
+            m.push(extra);
+            m.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(3)));
+            m
+        };
+
+        let saving = build(runtime_save_state_v1(path, 10.0));
+        verify_module(&saving).expect("verify");
+        compile_module(&saving).expect("compile").run().expect("run");
+        let saved = NetworkState::load(path).expect("saved state");
+        assert_eq!(saved.time_ns, 10_000_000);
+
+        let loading = build(runtime_load_state_v1(path));
+        let mut program = compile_module(&loading).expect("compile");
+        program.reset().expect("reset");
+        assert_eq!(program.engine.network().capture_state().neurons, saved.neurons);
+
+        assert!(matches!(compile_module(&build(runtime_save_state_v1(path, 25.0))),
+            Err(CompilerError::BadAttr { ref key, .. }) if key == "at"));
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(compile_module(&loading).is_err());
+    }
+
+    /// Perfect integrator firing at `threshold` (plugin model for tests)
+    #[derive(Debug)]
+    struct Integrator {
+        threshold: f32,
+    }
+
+    impl shnn_runtime::neuron::NeuronDynamics for Integrator {
+        fn name(&self) -> &str {
+            "integrator"
+        }
+
+        fn v_rest(&self) -> f32 {
+            0.0
+        }
+
+        fn validate(&self) -> RuntimeResult<()> {
+            match self.threshold > 0.0 {
+                true => Ok(()),
+                false => Err(shnn_runtime::RuntimeError::invalid_parameter("threshold", self.threshold.to_string(), "> 0")),
+            }
+        }
+
+        fn step(&self, state: &mut shnn_runtime::neuron::NeuronState, _vars: &mut [f32], _dt_ms: f32, _current_time_ns: u64) -> RuntimeResult<bool> {
+            state.v_m += state.i_input;
+            let spiked = state.v_m >= self.threshold;
+            if spiked {
+                state.v_m = 0.0;
+            }
+            Ok(spiked)
+        }
+    }
+
+    #[test]
+    fn lower_plugin_neuron_model() {
+        let op = |threshold: f32| Operation::new(DialectKey::Research("test_plugin".into()), "integrator", OpVersion(1))
+            .with_attr("neurons", AttributeValue::RangeU32 { start: 1, end: 1 })
+            .with_attr("threshold", AttributeValue::F32(threshold));
+        let build = |threshold: f32| {
+            let mut m = Module::new();
+            m.push(op(threshold));
+            m.push(layer_fully_connected_v1(0, 0, 1, 1, 1.0, 1.0));
+            m.push(stimulus_poisson_v1(0, 400.0, 100.0, 0.0, 50.0));
+            m.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(5)));
+            m
+        };
+        assert!(matches!(verify_module(&build(1.0)), Err(CompilerError::UnsupportedOp { .. })));
+
+        plugins::register_neuron_model("test_plugin", "integrator", 1, |op| {
+            let threshold = plugins::f32_param(op, "threshold")?;
+            Ok(shnn_runtime::neuron::CustomModel::new(Integrator { threshold }))
+        });
+        assert!(plugins::registered_neuron_models().contains(&("test_plugin".into(), "integrator".into(), 1)));
+        verify_module(&build(1.0)).expect("verify");
+        assert!(verify_module(&build(0.0)).is_err());
+
+        let mut program = compile_module(&build(1.0)).expect("compile");
+        assert_eq!(program.engine.network().get_membrane_potential(NeuronId::new(1)).unwrap(), 0.0);
+        let res = program.engine.run().expect("run");
+        assert!(res.spikes.iter().any(|s| s.neuron_id == NeuronId::new(1)));
+        assert_eq!(program_shape(&build(1.0)).expect("shape").neurons, 2);
+
+        assert!(plugins::unregister_neuron_model("test_plugin", "integrator", 1));
+        assert!(compile_module(&build(1.0)).is_err());
+    }
+
+    #[test]
+    fn lower_sampled_delays() {
+        let build = |dist: DelayDist, seed: Option<u64>| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v2(0, 3, 4, 7, 0.5, dist, seed));
+            m.push(synapse_connect_v2(7, 0, 0.5, DelayDist::Constant { delay_ms: 1.5 }, None));
+            m.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(5)));
+            verify_module(&m).expect("verify");
+            let program = compile_module(&m).expect("compile");
+            let net = program.engine.network();
+            let mut delays = Vec::new();
+            for pre in 0..4 {
+                for post in 4..8 {
+                    delays.push(net.get_delay(NeuronId::new(pre), NeuronId::new(post)).unwrap());
+                }
+            }
+            assert_eq!(net.get_delay(NeuronId::new(7), NeuronId::new(0)).unwrap(), 1.5);
+            delays
+        };
+
+        let uniform = DelayDist::Uniform { min_ms: 1.0, max_ms: 4.0 };
+        let a = build(uniform, Some(3));
+        assert!(a.iter().all(|d| (1.0..=4.0).contains(d)));
+        assert!(a.iter().any(|&d| d != a[0]), "delays should be heterogeneous");
+        assert_eq!(a, build(uniform, Some(3)));
+        assert_ne!(a, build(uniform, Some(4)));
+        // Without an explicit seed the simulate.run seed keeps lowering reproducible
+        assert_eq!(build(uniform, None), build(uniform, None));
+
+        let gamma = build(DelayDist::Gamma { shape: 2.0, scale_ms: 0.5 }, Some(1));
+        assert!(gamma.iter().all(|&d| d > 0.0));
+
+        let mut bad = Module::new();
+        bad.push(layer_fully_connected_v2(0, 0, 1, 1, 0.5, DelayDist::Uniform { min_ms: 3.0, max_ms: 1.0 }, None));
+        assert!(matches!(verify_module(&bad), Err(CompilerError::BadAttr { ref key, .. }) if key == "delay_max"));
+    }
+
+    #[test]
+    fn lower_seeds_are_stable_when_ops_are_added() {
+        let uniform = DelayDist::Uniform { min_ms: 1.0, max_ms: 4.0 };
+        let build = |extra: bool| {
+            let mut m = Module::new();
+            m.push(layer_fully_connected_v2(0, 1, 2, 3, 0.5, uniform, None));
+            if extra {
+                m.push(stimulus_ou_noise_v1(0, 1, 0.5, 1.0, 5.0, None));
+                m.push(stimulus_poisson_v1(0, 100.0, 1.0, 0.0, 1.0));
+            }
+            m.push(layer_fully_connected_v2(4, 5, 6, 7, 0.5, uniform, Some(9)));
+            m.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(5)));
+            compile_module(&m).expect("compile").seeds
+        };
+
+        let base = build(false);
+        let extended = build(true);
+        assert_eq!(base["connectivity.layer_fully_connected#1"], 9);
+        for (key, seed) in &base {
+            assert_eq!(extended[key], *seed, "{} was reseeded", key);
+        }
+        assert!(extended.contains_key("stimulus.ou_noise#0"));
+        assert!(extended.contains_key("stimulus#0"));
+        assert_ne!(extended["stimulus.ou_noise#0"], base["connectivity.layer_fully_connected#0"]);
+    }
+
+    #[test]
+    fn lower_hyperedge_pairwise() {
+        let mut m = Module::new();
+        m.push(hyperedge_v1(NeuronSet::List(vec![0, 3]), NeuronSet::Range { start: 3, end: 4 }, 0.5, 1.0));
+        m.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(1)));
+        verify_module(&m).expect("verify");
+
+        let synapses = |program: LoweredProgram| {
+            let mut pairs: Vec<_> = program.engine.network().synapse_connections()
+                .into_iter()
+                .map(|(pre, post, w)| (pre.raw(), post.raw(), w))
+                .collect();
+            pairs.sort_by_key(|&(pre, post, _)| (pre, post));
+            pairs
+        };
+        let direct = synapses(compile_module(&m).expect("compile"));
+        // Self-pair 3 -> 3 is skipped
+        assert_eq!(direct, vec![(0, 3, 0.5), (0, 4, 0.5), (3, 4, 0.5)]);
+        assert_eq!(synapses(compile_with_passes(&m).expect("compile")), direct);
+
+        let shape = program_shape(&m).expect("shape");
+        assert_eq!((shape.neurons, shape.synapses), (3, 3));
+        assert!(shape.lowering_bytes > 0);
+
+        let bad = |sources: NeuronSet, targets: NeuronSet| {
+            let mut m = Module::new();
+            m.push(hyperedge_v1(sources, targets, 0.5, 1.0));
+            match verify_module(&m) {
+                Err(CompilerError::BadAttr { key, .. }) => key,
+                other => panic!("expected BadAttr, got {:?}", other),
+            }
+        };
+        assert_eq!(bad(NeuronSet::List(vec![1, 1]), NeuronSet::List(vec![2])), "sources");
+        assert_eq!(bad(NeuronSet::List(vec![]), NeuronSet::List(vec![2])), "sources");
+        assert_eq!(bad(NeuronSet::Range { start: 2, end: 1 }, NeuronSet::List(vec![2])), "sources");
+        assert_eq!(bad(NeuronSet::List(vec![2]), NeuronSet::List(vec![2])), "targets");
+    }
+
+    #[test]
+    fn lower_distance_gaussian() {
+        let mut m = Module::new();
+        m.push(structure_positions_grid_v1(0, [10, 10, 1], 1.0));
+        m.push(distance_gaussian_v1((0, 99), (0, 99), 1.0, 1.0, 0.5, 1.0, Some(3)));
+        m.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(1)));
+        verify_module(&m).expect("verify");
+
+        let program = compile_module(&m).expect("compile");
+        let synapses = program.engine.network().synapse_connections();
+        assert!(!synapses.is_empty());
+        // Grid neighbours are 1 apart; p = exp(-d²/2) is negligible beyond d = 4
+        let position = |id: NeuronId| (id.raw() % 10, id.raw() / 10);
+        assert!(synapses.iter().all(|&(pre, post, _)| {
+            let ((px, py), (qx, qy)) = (position(pre), position(post));
+            pre != post && px.abs_diff(qx) + py.abs_diff(qy) <= 8
+        }));
+        let shape = program_shape(&m).expect("shape");
+        assert_eq!(shape.neurons, 100);
+        assert!(shape.synapses >= synapses.len() as u64);
+
+        let res = program.run().expect("run");
+        assert_eq!(res.positions.len(), 100);
+        assert_eq!(res.positions.get(NeuronId::new(23)), Some([3.0, 2.0, 0.0]));
+
+        // Targets outside the layout are rejected at verify time
+        let mut missing = Module::new();
+        missing.push(structure_positions_coords_v1(0, &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]]));
+        missing.push(distance_gaussian_v1((0, 1), (0, 2), 1.0, 1.0, 0.5, 1.0, None));
+        assert!(matches!(verify_module(&missing), Err(CompilerError::BadAttr { ref key, .. }) if key == "targets"));
+        let mut bad_grid = Module::new();
+        bad_grid.push(structure_positions_grid_v1(0, [3, 3, 1], 1.0).with_attr("neurons", AttributeValue::RangeU32 { start: 0, end: 7 }));
+        assert!(matches!(verify_module(&bad_grid), Err(CompilerError::BadAttr { ref key, .. }) if key == "neurons"));
+    }
+
+    #[test]
+    fn lower_monitor_rate() {
+        let mut m = Module::new();
+        m.push(layer_fully_connected_v1(0, 1, 2, 2, 0.0, 1.0));
+        m.push(stimulus_ou_noise_v1(0, 1, 5.0, 0.0, 5.0, None));
+        m.push(runtime_monitor_rate_v1(0, 1, 5.0));
+        m.push(runtime_monitor_rate_v1(2, 2, 5.0));
+        m.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(1)));
+        verify_module(&m).expect("verify");
+        let res = compile_module(&m).expect("compile").run().expect("run");
+
+        assert_eq!(res.rates.len(), 2);
+        let driven = &res.rates[0];
+        assert_eq!((driven.start, driven.end, driven.rates_hz.len()), (NeuronId::new(0), NeuronId::new(1), 4));
+        // Binned counts account for every spike of the population
+        let binned: f32 = driven.rates_hz.iter().map(|r| r * 2.0 * 0.005).sum();
+        let spikes = res.spikes.iter().filter(|s| s.neuron_id.raw() <= 1).count();
+        assert_eq!(binned.round() as usize, spikes);
+        assert!(driven.stats.mean_hz > 0.0);
+        assert!(res.rates[1].rates_hz.iter().all(|&r| r == 0.0));
+
+        let mut bad = Module::new();
+        bad.push(runtime_monitor_rate_v1(0, 1, 0.0));
+        assert!(matches!(verify_module(&bad), Err(CompilerError::BadAttr { ref key, .. }) if key == "bin"));
+    }
+
+    #[test]
+    fn lower_monitor_weights() {
+        let build = |monitor: Operation| {
+            let mut m = Module::new();
+            m.push(stdp_rule_v1(0.05, 0.02, 20.0, 20.0, 0.0, 1.0));
+            m.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+            m.push(stimulus_ou_noise_v1(0, 3, 8.0, 2.0, 5.0, None));
+            m.push(monitor);
+            m.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(2)));
+            m
+        };
+
+        let m = build(runtime_monitor_weights_pairs_v1(&[(0, 2), (1, 3)], 5.0));
+        verify_module(&m).expect("verify");
+        let res = compile_module(&m).expect("compile").run().expect("run");
+        let trace = res.weight_trace.expect("weight trace");
+        assert_eq!(trace.synapses, vec![(NeuronId::new(0), NeuronId::new(2)), (NeuronId::new(1), NeuronId::new(3))]);
+        assert_eq!(trace.times_ns.len(), 11);
+        assert_eq!(trace.weight(0, 0), Some(0.5));
+        // The last sample matches the final weights
+        let last = trace.times_ns.len() - 1;
+        assert_eq!(trace.weight(last, 1), res.final_weights.get(&(NeuronId::new(1), NeuronId::new(3))).copied());
+        assert!(trace.trajectory(0).iter().any(|&w| w != 0.5), "STDP should move the weight");
+
+        let sampled = |seed| {
+            let m = build(runtime_monitor_weights_sample_v1(2, 1.0, Some(seed)));
+            compile_module(&m).expect("compile").run().expect("run").weight_trace.unwrap().synapses
+        };
+        assert_eq!(sampled(4).len(), 2);
+        assert_eq!(sampled(4), sampled(4));
+
+        let missing = build(runtime_monitor_weights_pairs_v1(&[(2, 0)], 1.0));
+        assert!(matches!(compile_module(&missing), Err(CompilerError::BadAttr { ref key, .. }) if key == "pairs"));
+        let mut bad = Module::new();
+        bad.push(runtime_monitor_weights_sample_v1(0, 1.0, None));
+        assert!(matches!(verify_module(&bad), Err(CompilerError::BadAttr { ref key, .. }) if key == "sample"));
+    }
+
+    #[test]
+    fn lower_expectations() {
+        let mut m = Module::new();
+        m.push(layer_fully_connected_v1(0, 1, 2, 2, 0.0, 1.0));
+        m.push(stimulus_ou_noise_v1(0, 1, 5.0, 0.0, 5.0, None));
+        m.push(test_expect_rate_v1(0, 1, Some(1.0), None));
+        m.push(test_expect_spike_count_v1(2, 2, None, Some(0)));
+        m.push(test_expect_spike_count_v1(0, 1, Some(1_000), None));
+        m.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(1)));
+        verify_module(&m).expect("verify");
+
+        let mut program = compile_module(&m).expect("compile");
+        let result = program.engine.run().expect("run");
+        let passed: Vec<bool> = program.check_expectations(&result).iter().map(|o| o.passed).collect();
+        assert_eq!(passed, vec![true, true, false]);
+
+        let mut bad = Module::new();
+        bad.push(test_expect_rate_v1(0, 1, None, None));
+        assert!(matches!(verify_module(&bad), Err(CompilerError::BadAttr { ref key, .. }) if key == "min_hz"));
+        let mut bad = Module::new();
+        bad.push(test_expect_spike_count_v1(0, 1, Some(-1), None));
+        assert!(matches!(verify_module(&bad), Err(CompilerError::BadAttr { ref key, .. }) if key == "min"));
+    }
+
+    #[test]
+    fn profiled_compile_records_phases() {
+        let mut m = Module::new();
+        m.push(layer_fully_connected_v1(0, 1, 2, 2, 0.5, 1.0));
+        m.push(runtime_simulate_run_v1(0.1, 1.0, false, Some(1)));
+
+        let mut profiler = Profiler::new();
+        compile_with_passes_profiled(&m, &mut profiler).expect("compile");
+        let names: Vec<_> = profiler.spans().iter().map(|s| s.name).collect();
+        assert_eq!(names, ["verify", "passes", "lowering"]);
+    }
+
+    #[test]
+    fn program_shape_counts_sizes() {
+        let mut m = Module::new();
+        m.push(stdp_rule_v1(0.01, 0.012, 20.0, 20.0, 0.0, 1.0));
+        m.push(layer_fully_connected_v1(0, 3, 4, 5, 0.5, 1.0));
+        m.push(synapse_connect_v1(5, 9, 0.5, 1.0));
+        m.push(runtime_monitor_rate_v1(0, 9, 5.0));
+        m.push(runtime_monitor_weights_sample_v1(4, 10.0, None));
+        m.push(runtime_simulate_run_v1(0.1, 20.0, true, Some(1)));
+
+        let shape = program_shape(&m).expect("shape");
+        assert_eq!((shape.neurons, shape.synapses), (7, 9));
+        assert!(shape.plasticity);
+        assert_eq!(shape.potential_samples, 7 * 200);
+        assert_eq!(shape.rate_bins, 4);
+        assert_eq!(shape.weight_samples, 4 * 3);
+        // LIF default refractory period (2 ms = 20 steps) caps spikes per neuron
+        assert_eq!(shape.recorded_spikes, 7 * 10);
+        // v1 layers stream into the builder without an expanded copy
+        assert_eq!(shape.lowering_bytes, 0);
+
+        let mut v2 = Module::new();
+        v2.push(layer_fully_connected_v2(0, 3, 4, 5, 0.5, DelayDist::Constant { delay_ms: 1.0 }, None));
+        v2.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(1)));
+        let shape = program_shape(&v2).expect("shape");
+        assert_eq!((shape.neurons, shape.synapses, shape.lowering_bytes), (6, 8, 0));
+
+        let mut big = Module::new();
+        big.push(layer_fully_connected_v1(0, 99, 100, 199, 0.5, 1.0));
+        big.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(1)));
+        assert!(estimate_memory(&big).unwrap().total() > estimate_memory(&m).unwrap().total());
+    }
+
+    #[test]
+    fn lowered_program_steps_externally() {
+        let mut m = Module::new();
+        m.push(synapse_connect_v1(0, 1, 1000.0, 0.5));
+        m.push(runtime_simulate_run_v1(0.1, 2.0, false, Some(1)));
+
+        let mut program = compile_with_passes(&m).expect("compile");
+        program.reset().expect("reset");
+        program.inject_spikes(&[Spike::new(NeuronId::new(0), shnn_runtime::Time::from_nanos(0))]).expect("inject");
+        let mut outputs = Vec::new();
+        for _ in 0..20 {
+            program.step().expect("step");
+            outputs.extend(program.drain_output_spikes());
+        }
+        assert!(program.step().is_err());
+        assert_eq!(outputs.iter().map(|s| s.neuron_id.raw()).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(program.finish().steps_executed, 20);
+    }
+
+    #[test]
+    fn missing_ids_are_added_in_runs() {
+        let mut added = BTreeSet::from([3, 4]);
+        let builder = add_range_if_missing(NetworkBuilder::new(), &mut added, 0, 6);
+        let builder = add_ids_if_missing(builder, &mut added, [9, 6, 8, 9]);
+        let ids: Vec<u32> = builder.neuron_ids().iter().map(|id| id.raw()).collect();
+        assert_eq!(ids, vec![0, 1, 2, 5, 6, 8, 9]);
+        assert_eq!(added.len(), 9);
+    }
+}
@@ -0,0 +1,405 @@
+//! Linking several NIR modules into one flattened module
+//!
+//! Every module numbers its neurons from 0. [`link_modules`] gives each
+//! module, in order, its own block of global ids, except for neurons exported
+//! through `structure.port@v1 { name, neurons }`: the first module declaring a
+//! port owns its neurons, and the same port in a later module maps that
+//! module's range onto the owner's. A `readout.nir` that declares
+//! `port { name = "cortex", neurons = 0..99 }` and connects from 0..99 thus
+//! reads the neurons `cortex.nir` exported under that name.
+//!
+//! Neuron ranges, references and sets are rewritten to global ids. Ops that
+//! configure the whole network (neuron.lif, plasticity.stdp,
+//! runtime.simulate.run, runtime.save_state) may appear in several modules
+//! only if they are identical. Ops whose ids live outside the text
+//! (connectivity.from_snapshot, runtime.apply_mask, runtime.load_state) and
+//! structure.ei_network, which numbers its neurons from 0, only link where
+//! their module keeps its local ids, i.e. first and without aliased ports.
+//!
+//! Symbols are resolved per module before linking, and each module's
+//! structure.population@v1 names are prefixed with its namespace (the unit
+//! name without extension), so `exc` in `sensory.nir` becomes `sensory.exc`.
+
+use std::collections::BTreeMap;
+
+use shnn_ir::{AttributeValue, DialectKey, Module, Operation};
+
+use crate::symbols::{self, Population};
+use crate::{brunel_from_attrs, i64_opt_from_attr, op_spec, port_from_attrs, string_from_attr, AttrKind, CompilerError, Result};
+
+/// Where one input module ended up in the linked module
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedUnit {
+    /// Name the module was linked under (e.g. its file name)
+    pub name: String,
+    /// Neurons the module numbers locally (highest referenced id + 1)
+    pub neurons: u32,
+    /// First global id of the neurons it owns
+    pub first_id: u32,
+    /// Neurons it owns, i.e. `neurons` minus those aliased onto other modules' ports
+    pub owned: u32,
+    /// Ports it declares, with their global ranges
+    pub ports: Vec<LinkedPort>,
+    /// Populations it declares, namespaced, with their global ranges
+    pub populations: Vec<Population>,
+}
+
+/// A port as seen by one module
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedPort {
+    /// Port name
+    pub name: String,
+    /// Inclusive global range
+    pub range: (u32, u32),
+    /// Module owning the neurons (the first one declaring the port)
+    pub owner: String,
+}
+
+/// Flattened module and the layout of its inputs
+#[derive(Debug, Clone)]
+pub struct Linked {
+    /// Linked module, in input order
+    pub module: Module,
+    /// One entry per input module
+    pub units: Vec<LinkedUnit>,
+}
+
+impl Linked {
+    /// Neurons in the linked module
+    pub fn neurons(&self) -> u32 {
+        self.units.iter().map(|unit| unit.owned).sum()
+    }
+}
+
+/// Link `(name, module)` pairs, in order, into one module
+pub fn link_modules(units: &[(&str, &Module)]) -> Result<Linked> {
+    let mut module = Module::new();
+    let mut ports: BTreeMap<String, ((u32, u32), String)> = BTreeMap::new();
+    let mut layout = Vec::with_capacity(units.len());
+    let mut next_id = 0u32;
+
+    for &(name, input) in units {
+        let fail = |reason: String| CompilerError::Message(format!("{}: {}", name, reason));
+        let input = &*symbols::resolved(input).map_err(|e| fail(e.to_string()))?;
+        let prefix = namespace(name);
+        let neurons = input.ops.iter().map(neuron_extent).try_fold(0, |acc, extent| extent.map(|e| acc.max(e)))?;
+
+        // Ports owned elsewhere become aliases; the rest are owned here
+        let mut aliases: Vec<((u32, u32), u32)> = Vec::new();
+        let mut declared: Vec<(String, (u32, u32))> = Vec::new();
+        let mut aliased_ports: Vec<String> = Vec::new();
+        for op in input.ops.iter().filter(|op| is_port(op)) {
+            let (port, local) = port_from_attrs(op)?;
+            if declared.iter().any(|(seen, _)| seen == port) {
+                return Err(fail(format!("port '{}' is declared twice", port)));
+            }
+            if let Some(((start, end), owner)) = ports.get(port) {
+                if end - start != local.1 - local.0 {
+                    return Err(fail(format!(
+                        "port '{}' has {} neurons here but {} in {}",
+                        port, local.1 - local.0 + 1, end - start + 1, owner,
+                    )));
+                }
+                if let Some(((s, e), _)) = aliases.iter().find(|((s, e), _)| local.0 <= *e && *s <= local.1) {
+                    return Err(fail(format!("port '{}' overlaps another linked port at {}..{}", port, s, e)));
+                }
+                aliases.push((local, *start));
+                aliased_ports.push(port.to_string());
+            }
+            declared.push((port.to_string(), local));
+        }
+        let aliased: u32 = aliases.iter().map(|((s, e), _)| e - s + 1).sum();
+        let relocation = Relocation { base: next_id, aliases };
+
+        let mut unit_ports = Vec::with_capacity(declared.len());
+        for (port, local) in declared {
+            let range = relocation.range(local).map_err(|e| fail(format!("port '{}': {}", port, e)))?;
+            let owner = ports.entry(port.clone()).or_insert_with(|| (range, name.to_string())).1.clone();
+            unit_ports.push(LinkedPort { name: port, range, owner });
+        }
+
+        let mut unit_populations = Vec::new();
+        for op in &input.ops {
+            // The owner's port op stands for aliased ones
+            if is_port(op) && port_from_attrs(op).is_ok_and(|(port, _)| aliased_ports.iter().any(|p| p == port)) {
+                continue;
+            }
+            if is_global(op) {
+                match module.ops.iter().find(|other| same_kind(other, op)) {
+                    Some(other) if other.attrs == op.attrs => continue,
+                    Some(_) => return Err(fail(format!(
+                        "{}.{}@{} differs from the one linked earlier; it applies to the whole network, so linked modules must agree",
+                        op.dialect, op.name, op.version,
+                    ))),
+                    None => {}
+                }
+            }
+            let mut op = relocation.relocate(op).map_err(|reason| fail(format!("{}.{}@{}: {}", op.dialect, op.name, op.version, reason)))?;
+            if is_population(&op) {
+                let local = string_from_attr(&op, "name")?;
+                let namespaced = format!("{}.{}", prefix, local);
+                op.attrs.insert("name".into(), AttributeValue::String(namespaced.clone()));
+                unit_populations.push(Population { name: namespaced, neurons: crate::range_from_attr(&op, "neurons")? });
+            }
+            module.push(op);
+        }
+
+        let owned = neurons.saturating_sub(aliased);
+        layout.push(LinkedUnit {
+            name: name.to_string(),
+            neurons,
+            first_id: next_id,
+            owned,
+            ports: unit_ports,
+            populations: unit_populations,
+        });
+        next_id = next_id
+            .checked_add(owned)
+            .ok_or_else(|| fail("linked module exceeds u32 neuron ids".to_string()))?;
+    }
+    Ok(Linked { module, units: layout })
+}
+
+/// Unit name without its extension, as a population namespace
+fn namespace(unit: &str) -> String {
+    let file = unit.rsplit(['/', '\\']).next().unwrap_or(unit);
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    let mut namespace: String = stem.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '_' }).collect();
+    if !shnn_ir::is_symbol_name(&namespace) {
+        namespace = format!("_{}", namespace.replace('.', "_"));
+    }
+    namespace
+}
+
+fn is_population(op: &Operation) -> bool {
+    op.dialect == DialectKey::Structure && op.name == "population"
+}
+
+fn is_port(op: &Operation) -> bool {
+    op.dialect == DialectKey::Structure && op.name == "port"
+}
+
+/// Ops configuring the whole network rather than some of its neurons
+fn is_global(op: &Operation) -> bool {
+    matches!(
+        (&op.dialect, op.name.as_str()),
+        (DialectKey::Neuron, "lif") | (DialectKey::Plasticity, "stdp") | (DialectKey::Runtime, "simulate.run" | "save_state")
+    )
+}
+
+fn same_kind(a: &Operation, b: &Operation) -> bool {
+    a.dialect == b.dialect && a.name == b.name && a.version == b.version
+}
+
+/// Ops that only link with their module's local ids unchanged
+fn is_fixed_layout(op: &Operation) -> bool {
+    matches!(
+        (&op.dialect, op.name.as_str()),
+        (DialectKey::Connectivity, "from_snapshot")
+            | (DialectKey::Runtime, "apply_mask" | "load_state")
+            | (DialectKey::Structure, "ei_network")
+    )
+}
+
+/// Whether `key` of `op` holds a neuron set ("id;id" strings included)
+fn is_neuron_set(op: &Operation, key: &str) -> bool {
+    op_spec(op).is_some_and(|spec| spec.attrs.iter().any(|a| a.name == key && a.kind == AttrKind::NeuronSet))
+}
+
+/// Highest neuron id `op` refers to, plus one
+pub(crate) fn neuron_extent(op: &Operation) -> Result<u32> {
+    let mut extent = 0u32;
+    for (key, value) in &op.attrs {
+        let top = match value {
+            AttributeValue::RangeU32 { start, end } => Some(*start.max(end)),
+            AttributeValue::NeuronRef(id) => Some(*id),
+            AttributeValue::String(list) if is_neuron_set(op, key) => list.split(';').filter_map(|id| id.trim().parse().ok()).max(),
+            _ => None,
+        };
+        extent = extent.max(top.map_or(0, |id: u32| id.saturating_add(1)));
+    }
+    match (&op.dialect, op.name.as_str()) {
+        (DialectKey::Structure, "wta") => {
+            let n = i64_opt_from_attr(op, "n")?.unwrap_or(0);
+            let start = i64_opt_from_attr(op, "start")?.unwrap_or(0);
+            extent = extent.max(u32::try_from(start.saturating_add(n)).unwrap_or(u32::MAX));
+        }
+        (DialectKey::Structure, "ei_network") => extent = extent.max(brunel_from_attrs(op)?.neurons()),
+        (DialectKey::Runtime, "monitor_weights") if op.attrs.contains_key("pairs") => {
+            let ids = string_from_attr(op, "pairs")?.split([';', ':']).filter_map(|id| id.trim().parse::<u32>().ok());
+            extent = extent.max(ids.max().map_or(0, |id| id.saturating_add(1)));
+        }
+        _ => {}
+    }
+    Ok(extent)
+}
+
+/// Local-to-global id map of one module
+struct Relocation {
+    /// Global id of the module's first owned neuron
+    base: u32,
+    /// Local ranges aliased onto other modules' ports, with their global start
+    aliases: Vec<((u32, u32), u32)>,
+}
+
+impl Relocation {
+    fn is_identity(&self) -> bool {
+        self.base == 0 && self.aliases.is_empty()
+    }
+
+    /// Owned neurons are numbered from `base` in local order, skipping aliased ones
+    fn id(&self, id: u32) -> u32 {
+        if let Some(((start, _), global)) = self.aliases.iter().find(|((s, e), _)| (*s..=*e).contains(&id)) {
+            return global + (id - start);
+        }
+        let skipped: u32 = self.aliases.iter().filter(|((_, e), _)| *e < id).map(|((s, e), _)| e - s + 1).sum();
+        self.base + (id - skipped)
+    }
+
+    /// Global range of a local one, unless it mixes aliased and owned neurons
+    fn range(&self, (start, end): (u32, u32)) -> std::result::Result<(u32, u32), String> {
+        let straddled = self.aliases.iter().find(|((s, e), _)| start <= *e && *s <= end && !(*s <= start && end <= *e));
+        match straddled {
+            Some(((s, e), _)) => Err(format!("range {}..{} straddles the linked port at {}..{}", start, end, s, e)),
+            None => Ok((self.id(start), self.id(end))),
+        }
+    }
+
+    fn relocate(&self, op: &Operation) -> std::result::Result<Operation, String> {
+        if self.is_identity() {
+            return Ok(op.clone());
+        }
+        if is_fixed_layout(op) {
+            return Err("its neuron ids cannot be renumbered; link this module first, without ports owned elsewhere".into());
+        }
+        let mut op = op.clone();
+        let keys: Vec<String> = op.attrs.keys().cloned().collect();
+        for key in keys {
+            let value = match &op.attrs[&key] {
+                AttributeValue::RangeU32 { start, end } => {
+                    let (start, end) = self.range((*start, *end)).map_err(|e| format!("attribute '{}': {}", key, e))?;
+                    AttributeValue::RangeU32 { start, end }
+                }
+                AttributeValue::NeuronRef(id) => AttributeValue::NeuronRef(self.id(*id)),
+                AttributeValue::String(list) if is_neuron_set(&op, &key) => AttributeValue::String(self.ids(list, ';')),
+                _ => continue,
+            };
+            op.attrs.insert(key, value);
+        }
+        match (&op.dialect, op.name.as_str()) {
+            (DialectKey::Structure, "wta") => {
+                let n = i64_opt_from_attr(&op, "n").map_err(|e| e.to_string())?.unwrap_or(0);
+                let start = i64_opt_from_attr(&op, "start").map_err(|e| e.to_string())?.unwrap_or(0);
+                let (Ok(first), Ok(last)) = (u32::try_from(start), u32::try_from(start + n - 1)) else {
+                    return Err(format!("circuit {}..{} is out of range", start, start + n - 1));
+                };
+                let (first, _) = self.range((first, last)).map_err(|e| format!("circuit: {}", e))?;
+                op.attrs.insert("start".into(), AttributeValue::I64(first.into()));
+            }
+            (DialectKey::Runtime, "monitor_weights") if op.attrs.contains_key("pairs") => {
+                let pairs = string_from_attr(&op, "pairs").map_err(|e| e.to_string())?;
+                let pairs = pairs.split(';').map(|pair| self.ids(pair, ':')).collect::<Vec<_>>().join(";");
+                op.attrs.insert("pairs".into(), AttributeValue::String(pairs));
+            }
+            _ => {}
+        }
+        Ok(op)
+    }
+
+    /// `sep`-separated ids relocated; entries that are not ids are kept for verification to reject
+    fn ids(&self, list: &str, sep: char) -> String {
+        list.split(sep)
+            .map(|id| id.trim().parse::<u32>().map_or_else(|_| id.to_string(), |id| self.id(id).to_string()))
+            .collect::<Vec<_>>()
+            .join(&sep.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shnn_ir::*;
+
+    #[test]
+    fn link_aliases_ports_and_renumbers_neurons() {
+        let lif = lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0);
+        let mut sensory = Module::new();
+        sensory.push(lif.clone());
+        sensory.push(stimulus_poisson_v1(0, 50.0, 10.0, 0.0, 100.0));
+        sensory.push(layer_fully_connected_v1(0, 1, 2, 4, 1.0, 1.0));
+        sensory.push(structure_port_v1("features", 2, 4));
+        sensory.push(runtime_simulate_run_v1(0.1, 100.0, false, Some(1)));
+
+        // Local 0..2 are the sensory features; 3..4 are this module's own
+        let mut cortex = Module::new();
+        cortex.push(lif);
+        cortex.push(structure_port_v1("features", 0, 2));
+        cortex.push(layer_fully_connected_v1(0, 2, 3, 4, 0.5, 1.0));
+        cortex.push(synapse_connect_v1(4, 3, -1.0, 1.0));
+
+        let linked = link_modules(&[("sensory.nir", &sensory), ("cortex.nir", &cortex)]).expect("link");
+        assert_eq!(linked.neurons(), 7);
+        assert_eq!(linked.units[1].first_id, 5);
+        assert_eq!(linked.units[1].owned, 2);
+        assert_eq!(linked.units[1].ports[0].range, (2, 4));
+        assert_eq!(linked.units[1].ports[0].owner, "sensory.nir");
+
+        // Duplicate neuron.lif and the aliased port are dropped
+        let ops = &linked.module.ops;
+        assert_eq!(ops.len(), 7);
+        assert_eq!(ops[5].attrs.get("in"), Some(&AttributeValue::RangeU32 { start: 2, end: 4 }));
+        assert_eq!(ops[5].attrs.get("out"), Some(&AttributeValue::RangeU32 { start: 5, end: 6 }));
+        assert_eq!(ops[6].attrs.get("pre"), Some(&AttributeValue::NeuronRef(6)));
+        crate::verify_module(&linked.module).expect("linked module verifies");
+    }
+
+    #[test]
+    fn link_namespaces_populations() {
+        let mut a = Module::new();
+        a.push(synapse_connect_v1(0, 1, 1.0, 1.0));
+        a.push(structure_population_v1("exc", 3));
+        a.push(structure_port_v1("bus", 0, 0).with_attr("neurons", AttributeValue::symbol("exc", SymbolIndex::Range(1, 2))));
+
+        let mut b = Module::new();
+        b.push(structure_population_v1("in", 2));
+        b.push(structure_population_v1("out", 2));
+        b.push(structure_port_v1("bus", 0, 0).with_attr("neurons", AttributeValue::symbol("in", SymbolIndex::All)));
+        b.push(layer_fully_connected_v1(0, 0, 0, 0, 1.0, 1.0)
+            .with_attr("in", AttributeValue::symbol("in", SymbolIndex::All))
+            .with_attr("out", AttributeValue::symbol("out", SymbolIndex::All)));
+
+        let linked = link_modules(&[("a.nir", &a), ("nets/b.nir", &b)]).expect("link");
+        // a: raw 0..1, exc 2..4 with bus = 3..4; b: in aliases the bus, out is its own
+        assert_eq!(linked.units[0].populations, vec![Population { name: "a.exc".into(), neurons: (2, 4) }]);
+        assert_eq!(linked.units[1].populations, vec![
+            Population { name: "b.in".into(), neurons: (3, 4) },
+            Population { name: "b.out".into(), neurons: (5, 6) },
+        ]);
+        assert_eq!(linked.neurons(), 7);
+        let layer = linked.module.ops.iter().find(|op| op.name == "layer_fully_connected").expect("layer");
+        assert_eq!(layer.attrs.get("out"), Some(&AttributeValue::RangeU32 { start: 5, end: 6 }));
+        let table = symbols::SymbolTable::build(&linked.module).expect("table");
+        assert_eq!(table.lookup(3).map(|(p, i)| (p.name.as_str(), i)), Some(("a.exc", 1)));
+        assert_eq!(namespace("1st-net.v2.nir"), "_1st_net_v2");
+    }
+
+    #[test]
+    fn link_rejects_mismatched_interfaces() {
+        let mut a = Module::new();
+        a.push(structure_port_v1("p", 0, 3));
+        a.push(runtime_simulate_run_v1(0.1, 10.0, false, None));
+        let mut smaller = Module::new();
+        smaller.push(structure_port_v1("p", 0, 1));
+        let err = link_modules(&[("a", &a), ("b", &smaller)]).unwrap_err();
+        assert!(err.to_string().contains("b: port 'p' has 2 neurons here but 4 in a"));
+
+        let mut straddling = Module::new();
+        straddling.push(structure_port_v1("p", 0, 3));
+        straddling.push(layer_fully_connected_v1(2, 5, 6, 7, 1.0, 1.0));
+        assert!(link_modules(&[("a", &a), ("c", &straddling)]).unwrap_err().to_string().contains("straddles"));
+
+        let mut other_run = Module::new();
+        other_run.push(runtime_simulate_run_v1(0.5, 10.0, false, None));
+        assert!(link_modules(&[("a", &a), ("d", &other_run)]).unwrap_err().to_string().contains("linked modules must agree"));
+    }
+}
@@ -38,80 +38,144 @@ impl PassManager {
     }
 }
 
+/// Symbol resolution pass
+/// - Lays out structure.population ops and replaces `%name[i]` symbols with ids
+///   (see [`resolve_symbols`](crate::symbols::resolve_symbols))
+/// - Leaves modules without symbols untouched
+pub struct ResolveSymbolsPass;
+
+impl Pass for ResolveSymbolsPass {
+    fn name(&self) -> &'static str { "resolve_symbols" }
+    fn run(&self, module: &mut Module) -> Result<()> {
+        if crate::symbols::has_symbols(module) {
+            *module = crate::symbols::resolve_symbols(module)?;
+        }
+        Ok(())
+    }
+}
+
+/// Defaulting pass
+/// - Inserts every omitted optional attribute that declares an [`AttributeSpec::default`](crate::AttributeSpec::default)
+/// - Leaves ops without a registry entry untouched
+pub struct DefaultingPass;
+
+impl Pass for DefaultingPass {
+    fn name(&self) -> &'static str { "defaulting" }
+    fn run(&self, module: &mut Module) -> Result<()> {
+        for op in &mut module.ops {
+            let Some(spec) = crate::op_spec(op) else { continue };
+            for attr in spec.attrs {
+                if let Some(default) = attr.default {
+                    op.attrs.entry(attr.name.to_string()).or_insert_with(|| default.to_value(attr.kind));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Canonicalization pass
 /// - Expands connectivity.layer_fully_connected into explicit connectivity.synapse_connect ops
+/// - Expands connectivity.hyperedge into one synapse_connect per (source, target)
+///   pair with the hyperedge's weight and delay, skipping self-pairs
+/// - Expands structure.wta into its inhibitory (and self-excitatory) synapse_connect ops
+///   (all three through [`edges::expand`](crate::edges::expand))
+/// - Expands structure.ei_network templates (see [`expand_templates`])
 /// - Normalizes attribute units to canonical forms
 pub struct CanonicalizePass;
 
 impl Pass for CanonicalizePass {
     fn name(&self) -> &'static str { "canonicalize" }
     fn run(&self, module: &mut Module) -> Result<()> {
-        let mut new_ops = Vec::new();
-        
+        if module.ops.iter().any(is_template) {
+            *module = expand_templates(module)?;
+        }
+        let mut new_ops = Vec::with_capacity(module.ops.len());
         for op in &module.ops {
-            match (&op.dialect, op.name.as_str(), op.version) {
-                (shnn_ir::DialectKey::Connectivity, "layer_fully_connected", shnn_ir::OpVersion(1)) => {
-                    // Expand into synapse_connect ops
-                    let expanded = expand_layer_fully_connected(op)?;
-                    new_ops.extend(expanded);
-                }
-                _ => {
-                    // Keep other ops as-is
-                    new_ops.push(op.clone());
-                }
+            match crate::edges::expand(op)? {
+                Some(edges) => new_ops.extend(edges.to_ops()),
+                // Keep other ops as-is
+                None => new_ops.push(op.clone()),
             }
         }
-        
         module.ops = new_ops;
         Ok(())
     }
 }
 
-fn expand_layer_fully_connected(op: &shnn_ir::Operation) -> Result<Vec<shnn_ir::Operation>> {
+/// connectivity.synapse_connect@v1 with a delay already in ns
+pub(crate) fn synapse_connect_op(pre: u32, post: u32, weight: f32, delay_ns: u64) -> shnn_ir::Operation {
     use shnn_ir::{AttributeValue, DialectKey, OpVersion};
-    
-    // Extract attributes
-    let in_range = match op.attrs.get("in") {
-        Some(AttributeValue::RangeU32 { start, end }) => (*start, *end),
-        _ => return Err(crate::CompilerError::Message("layer_fully_connected missing 'in' range".into())),
-    };
-    
-    let out_range = match op.attrs.get("out") {
-        Some(AttributeValue::RangeU32 { start, end }) => (*start, *end),
-        _ => return Err(crate::CompilerError::Message("layer_fully_connected missing 'out' range".into())),
-    };
-    
-    let weight = match op.attrs.get("weight") {
-        Some(AttributeValue::Weight(w)) => *w,
-        Some(AttributeValue::F32(w)) => *w,
-        _ => return Err(crate::CompilerError::Message("layer_fully_connected missing 'weight'".into())),
-    };
-    
-    let delay = match op.attrs.get("delay") {
-        Some(AttributeValue::DurationNs(ns)) => *ns,
-        _ => return Err(crate::CompilerError::Message("layer_fully_connected missing 'delay'".into())),
-    };
-    
-    // Generate synapse_connect ops
-    let mut synapse_ops = Vec::new();
-    for pre in in_range.0..=in_range.1 {
-        for post in out_range.0..=out_range.1 {
-            let mut synapse_op = shnn_ir::Operation::new(
-                DialectKey::Connectivity,
-                "synapse_connect",
-                OpVersion(1)
-            );
-            synapse_op = synapse_op
-                .with_attr("pre", AttributeValue::NeuronRef(pre))
-                .with_attr("post", AttributeValue::NeuronRef(post))
-                .with_attr("weight", AttributeValue::Weight(weight))
-                .with_attr("delay", AttributeValue::DurationNs(delay));
-            
-            synapse_ops.push(synapse_op);
+
+    shnn_ir::Operation::new(DialectKey::Connectivity, "synapse_connect", OpVersion(1))
+        .with_attr("pre", AttributeValue::NeuronRef(pre))
+        .with_attr("post", AttributeValue::NeuronRef(post))
+        .with_attr("weight", AttributeValue::Weight(weight))
+        .with_attr("delay", AttributeValue::DurationNs(delay_ns))
+}
+
+/// Whether `op` is a network template expanded by [`expand_templates`]
+pub(crate) fn is_template(op: &shnn_ir::Operation) -> bool {
+    op.dialect == shnn_ir::DialectKey::Structure && op.name == "ei_network" && op.version == shnn_ir::OpVersion(1)
+}
+
+/// Copy of `module` with each structure.ei_network@v1 op replaced, in place, by
+/// its neuron.lif@v1 model, connectivity.synapse_connect@v1 ops and
+/// stimulus.poisson@v1 external drive
+///
+/// Weights and stimulus rates depend on the simulate.run step and the drive
+/// lasts the whole run. Without a seed attr, connections are drawn from the
+/// seed lowering would derive for the op.
+pub(crate) fn expand_templates(module: &Module) -> Result<Module> {
+    use shnn_ir::{AttributeValue, DialectKey, OpVersion, Operation};
+
+    let run = module.ops.iter()
+        .find(|op| op.dialect == DialectKey::Runtime && op.name == "simulate.run")
+        .ok_or_else(|| crate::CompilerError::Message(
+            "structure.ei_network@v1 needs a runtime.simulate.run@v1 op for its dt and duration".into(),
+        ))?;
+    let dt_ms = crate::duration_ns_to_ms(run, "dt")?;
+    let duration_ns = crate::duration_ns_from_attr(run, "duration")?;
+    let seeds = crate::module_seed_tree(module)?.child("structure.ei_network");
+
+    let mut expanded = Module::new();
+    let mut ordinal = 0;
+    for op in &module.ops {
+        if !is_template(op) {
+            expanded.ops.push(op.clone());
+            continue;
+        }
+        let net = crate::brunel_from_attrs(op)?;
+        let delay_ns = crate::duration_ns_from_attr(op, "delay")?;
+        let seed = crate::i64_opt_from_attr(op, "seed")?.map_or(seeds.index(ordinal).seed(), |s| s as u64);
+        ordinal += 1;
+
+        let lif = shnn_runtime::BrunelNetwork::lif_params();
+        expanded.ops.push(shnn_ir::lif_neuron_v1(
+            lif.tau_m, lif.v_rest, lif.v_reset, lif.v_thresh, lif.t_refrac, lif.r_m, lif.c_m,
+        ));
+        let synapses = net.connect(dt_ms, seed).map_err(crate::CompilerError::Runtime)?;
+        expanded.ops.extend(synapses.into_iter().map(|(pre, post, weight)| {
+            synapse_connect_op(pre.raw(), post.raw(), weight, delay_ns)
+        }));
+        if net.nu_ext_hz > 0.0 {
+            let (count, rate_hz) = net.external_drive(dt_ms);
+            let amplitude = net.excitatory_weight(dt_ms);
+            for neuron in 0..net.neurons() {
+                for _ in 0..count {
+                    expanded.ops.push(
+                        Operation::new(DialectKey::Stimulus, "poisson", OpVersion(1))
+                            .with_attr("neuron", AttributeValue::NeuronRef(neuron))
+                            .with_attr("rate", AttributeValue::RateHz(rate_hz))
+                            .with_attr("amplitude", AttributeValue::CurrentNa(amplitude))
+                            .with_attr("start", AttributeValue::TimeNs(0))
+                            .with_attr("duration", AttributeValue::DurationNs(duration_ns)),
+                    );
+                }
+            }
         }
     }
-    
-    Ok(synapse_ops)
+    Ok(expanded)
 }
 
 /// Version upgrade pass
@@ -171,6 +235,100 @@ fn upgrade_stdp_v0_to_v1(op: &mut shnn_ir::Operation) -> Result<()> {
     Ok(())
 }
 
+/// Auto-dt pass
+/// - Lowers the `runtime.simulate.run` dt to the suggestion of
+///   [`analyze_dt`](crate::stability::analyze_dt) when dt under-resolves the
+///   fastest time constant or shortest delay
+/// - Never raises dt; leaves modules without a simulate.run op untouched
+pub struct AutoDtPass;
+
+impl Pass for AutoDtPass {
+    fn name(&self) -> &'static str { "auto_dt" }
+    fn run(&self, module: &mut Module) -> Result<()> {
+        crate::stability::apply_suggested_dt(module).map(|_| ())
+    }
+}
+
+/// Fixed-point quantization pass
+/// - Snaps every weight attribute, and the voltages and time constants of neuron ops,
+///   onto the grid of an embedded target's fixed-point format
+/// - Values stay in NIR units, so the quantized module still runs on the float engine
+pub struct QuantizePass {
+    /// Target format
+    pub format: crate::quantize::FixedFormat,
+}
+
+impl Pass for QuantizePass {
+    fn name(&self) -> &'static str { "quantize" }
+    fn run(&self, module: &mut Module) -> Result<()> {
+        use shnn_ir::{AttributeValue, DialectKey};
+
+        let dt_ns = module.ops.iter()
+            .find(|op| op.dialect == DialectKey::Runtime && op.name == "simulate.run")
+            .and_then(|op| match op.attrs.get("dt") {
+                Some(AttributeValue::DurationNs(ns)) => Some(*ns),
+                _ => None,
+            });
+
+        for op in &mut module.ops {
+            let is_neuron = op.dialect == DialectKey::Neuron;
+            let op_name = format!("{}.{}", op.dialect, op.name);
+            for (key, value) in op.attrs.iter_mut() {
+                match value {
+                    AttributeValue::Weight(w) => *w = self.snap(*w, 1.0, &op_name, key)?,
+                    AttributeValue::VoltageMv(v) if is_neuron => {
+                        *v = self.snap(*v, self.format.voltage_scale(), &op_name, key)?;
+                    }
+                    AttributeValue::DurationNs(ns) if is_neuron => {
+                        *ns = self.snap_duration(*ns, dt_ns, &op_name, key)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl QuantizePass {
+    /// Quantize `value` after converting it to target units with `scale`, then convert back
+    fn snap(&self, value: f32, scale: f32, op: &str, key: &str) -> Result<f32> {
+        self.format.quantize(value * scale)
+            .map(|q| q / scale)
+            .ok_or_else(|| crate::CompilerError::Message(format!(
+                "quantize: {}.{} = {} is out of {} range", op, key, value, self.format
+            )))
+    }
+
+    /// Quantize a neuron time constant the way the target represents it
+    fn snap_duration(&self, ns: u64, dt_ns: Option<u64>, op: &str, key: &str) -> Result<u64> {
+        use crate::quantize::FixedFormat;
+
+        let ms = ns as f64 / 1_000_000.0;
+        let snapped_ms = match (self.format, key, dt_ns) {
+            // shnn-micro keeps exp(-dt / tau_m) as a per-step decay factor
+            (FixedFormat::Q15_16, "tau_m", Some(dt_ns)) if ns > 0 => {
+                let dt_ms = dt_ns as f64 / 1_000_000.0;
+                let decay = self.snap((-dt_ms / ms).exp() as f32, 1.0, op, key)? as f64;
+                if decay <= 0.0 || decay >= 1.0 {
+                    return Err(crate::CompilerError::Message(format!(
+                        "quantize: {}.{} decay factor {} is not representable in {}", op, key, decay, self.format
+                    )));
+                }
+                -dt_ms / decay.ln()
+            }
+            // and the refractory period in whole milliseconds
+            (FixedFormat::Q15_16, "t_refrac", _) => ms.round().min(u8::MAX as f64),
+            (FixedFormat::Q15_16, _, _) => ms,
+            // shnn-embedded stores times in seconds
+            (FixedFormat::Q16_16, _, _) => self.snap((ms / 1000.0) as f32, 1.0, op, key)? as f64 * 1000.0,
+        };
+        shnn_ir::Quantity::ms(snapped_ms).round_ns().map_err(|e| crate::CompilerError::Message(format!(
+            "quantize: {}.{} = {} ms: {}", op, key, snapped_ms, e
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,6 +342,26 @@ mod tests {
         pm.add(Box::new(UpgradeVersionsPass));
         pm.run(&mut m).expect("passes run");
     }
+
+    #[test]
+    fn defaulting_fills_omitted_optional_attrs() {
+        use shnn_ir::{runtime_simulate_run_v1, AttributeValue};
+
+        let mut m = Module::new();
+        m.push(runtime_simulate_run_v1(0.1, 10.0, false, None).with_attr("rng", AttributeValue::String("philox".into())));
+        DefaultingPass.run(&mut m).expect("defaulting pass");
+
+        let attrs = &m.ops[0].attrs;
+        assert_eq!(attrs.get("integrator"), Some(&AttributeValue::String("euler".into())));
+        assert_eq!(attrs.get("realtime"), Some(&AttributeValue::Bool(false)));
+        // Explicit values win; attrs without a default stay omitted
+        assert_eq!(attrs.get("rng"), Some(&AttributeValue::String("philox".into())));
+        assert!(!attrs.contains_key("seed"));
+
+        let defaulted = m.ops[0].attrs.clone();
+        DefaultingPass.run(&mut m).expect("idempotent");
+        assert_eq!(m.ops[0].attrs, defaulted);
+    }
     
     #[test]
     fn canonicalize_expands_layer_fully_connected() {
@@ -212,6 +390,78 @@ mod tests {
         assert_eq!(layer_count, 0);
     }
     
+    #[test]
+    fn canonicalize_expands_hyperedge_pairwise() {
+        use shnn_ir::{hyperedge_v1, NeuronSet, AttributeValue::NeuronRef};
+
+        let mut m = Module::new();
+        m.push(hyperedge_v1(NeuronSet::List(vec![0, 2]), NeuronSet::Range { start: 2, end: 3 }, 0.5, 1.0));
+        CanonicalizePass.run(&mut m).expect("canonicalize pass");
+
+        let pairs: Vec<_> = m.ops.iter()
+            .map(|op| (op.attrs.get("pre").cloned(), op.attrs.get("post").cloned()))
+            .collect();
+        // 2 -> 2 is skipped
+        assert_eq!(pairs, vec![
+            (Some(NeuronRef(0)), Some(NeuronRef(2))),
+            (Some(NeuronRef(0)), Some(NeuronRef(3))),
+            (Some(NeuronRef(2)), Some(NeuronRef(3))),
+        ]);
+        assert!(m.ops.iter().all(|op| op.name == "synapse_connect"));
+    }
+
+    #[test]
+    fn canonicalize_expands_ei_network() {
+        use shnn_ir::{runtime_simulate_run_v1, structure_ei_network_v1, AttributeValue};
+
+        let mut m = Module::new();
+        m.push(structure_ei_network_v1(40, 10, 0.2, 4.0, 10.0));
+        m.push(runtime_simulate_run_v1(0.1, 50.0, false, Some(1)));
+        let mut again = m.clone();
+        CanonicalizePass.run(&mut m).expect("canonicalize pass");
+        CanonicalizePass.run(&mut again).expect("canonicalize pass");
+        assert_eq!(m.to_text(), again.to_text());
+
+        let count = |name: &str| m.ops.iter().filter(|op| op.name == name).count();
+        assert_eq!(count("ei_network"), 0);
+        assert_eq!(count("lif"), 1);
+        // In-degrees 8 + 2 per neuron; 80 Hz of external drive fits one stimulus per neuron
+        assert_eq!(count("synapse_connect"), 50 * 10);
+        assert_eq!(count("poisson"), 50);
+        let inhibitory = m.ops.iter()
+            .find(|op| op.attrs.get("pre") == Some(&AttributeValue::NeuronRef(45)))
+            .expect("inhibitory synapse");
+        // J = 0.1 mV at dt = 0.1 ms is weight 2; inhibition is -g times that
+        assert!(matches!(inhibitory.attrs.get("weight"), Some(AttributeValue::Weight(w)) if (w + 8.0).abs() < 1e-5));
+    }
+
+    #[test]
+    fn canonicalize_expands_wta() {
+        use shnn_ir::{structure_wta_v1, AttributeValue};
+
+        let mut m = Module::new();
+        m.push(structure_wta_v1(3, 2.0, 0.5).with_attr("start", AttributeValue::I64(4)));
+        CanonicalizePass.run(&mut m).expect("canonicalize pass");
+
+        assert_eq!(m.ops.len(), 9);
+        assert!(m.ops.iter().all(|op| op.name == "synapse_connect"));
+        for op in &m.ops {
+            let weight = match op.attrs.get("pre") == op.attrs.get("post") {
+                true => 0.5,
+                false => -2.0,
+            };
+            assert_eq!(op.attrs.get("weight"), Some(&AttributeValue::Weight(weight)));
+        }
+        assert_eq!(m.ops[0].attrs.get("pre"), Some(&AttributeValue::NeuronRef(4)));
+        assert_eq!(m.ops[8].attrs.get("post"), Some(&AttributeValue::NeuronRef(6)));
+
+        // Without self-excitation only the n(n-1) inhibitory synapses remain
+        let mut plain = Module::new();
+        plain.push(structure_wta_v1(3, 2.0, 0.0));
+        CanonicalizePass.run(&mut plain).expect("canonicalize pass");
+        assert_eq!(plain.ops.len(), 6);
+    }
+
     #[test]
     fn version_upgrade_handles_hypothetical_v0() {
         use shnn_ir::{Operation, DialectKey, OpVersion, AttributeValue};
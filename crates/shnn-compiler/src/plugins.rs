@@ -0,0 +1,84 @@
+//! Neuron models supplied by the embedding application
+//!
+//! [`register_neuron_model`] maps a research-dialect op, e.g.
+//! `research.izh.neuron@v1`, to a factory that builds a [`CustomModel`] from
+//! the op's attributes. Verification and lowering then accept the op like
+//! `neuron.hh@v1`: its required `neurons` range (inclusive) selects the
+//! population integrated by the model. The engine crate needs no change for
+//! a new model.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use shnn_ir::{DialectKey, Operation};
+use shnn_runtime::neuron::CustomModel;
+
+use crate::{bad_attr, f32_from_attr, range_from_attr, CompilerError, Result};
+
+/// Builds a model from a research op's attributes
+pub type NeuronModelFactory = dyn Fn(&Operation) -> Result<CustomModel> + Send + Sync;
+
+/// (research dialect key, op name, version)
+type PluginKey = (String, String, u16);
+
+fn registry() -> &'static RwLock<BTreeMap<PluginKey, Arc<NeuronModelFactory>>> {
+    static REGISTRY: OnceLock<RwLock<BTreeMap<PluginKey, Arc<NeuronModelFactory>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Lower `research.<dialect_key>.<name>@v<version>` ops with `factory`
+///
+/// Registration is process-wide; registering the same op again replaces the
+/// earlier factory.
+pub fn register_neuron_model<F>(dialect_key: &str, name: &str, version: u16, factory: F)
+where
+    F: Fn(&Operation) -> Result<CustomModel> + Send + Sync + 'static,
+{
+    let key = (dialect_key.to_string(), name.to_string(), version);
+    registry().write().unwrap_or_else(|e| e.into_inner()).insert(key, Arc::new(factory));
+}
+
+/// Remove a registration; returns whether one existed
+pub fn unregister_neuron_model(dialect_key: &str, name: &str, version: u16) -> bool {
+    let key = (dialect_key.to_string(), name.to_string(), version);
+    registry().write().unwrap_or_else(|e| e.into_inner()).remove(&key).is_some()
+}
+
+/// Registered research ops as (dialect key, name, version), sorted
+pub fn registered_neuron_models() -> Vec<(String, String, u16)> {
+    registry().read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect()
+}
+
+/// Numeric attribute of a research op (plain, weight, mV, MOhm or nF)
+pub fn f32_param(op: &Operation, key: &str) -> Result<f32> {
+    f32_from_attr(op, key)
+}
+
+fn factory_for(op: &Operation) -> Option<Arc<NeuronModelFactory>> {
+    let DialectKey::Research(dialect_key) = &op.dialect else {
+        return None;
+    };
+    let key = (dialect_key.clone(), op.name.clone(), op.version.0);
+    registry().read().unwrap_or_else(|e| e.into_inner()).get(&key).cloned()
+}
+
+/// Whether `op` is a registered plugin neuron op
+pub(crate) fn is_registered(op: &Operation) -> bool {
+    factory_for(op).is_some()
+}
+
+/// Inclusive `neurons` range and model of a plugin neuron op
+pub(crate) fn population_from_op(op: &Operation) -> Result<(u32, u32, CustomModel)> {
+    let factory = factory_for(op).ok_or_else(|| CompilerError::UnsupportedOp {
+        dialect: op.dialect.to_string(),
+        name: op.name.clone(),
+        version: op.version.to_string(),
+    })?;
+    let (start, end) = range_from_attr(op, "neurons")?;
+    if start > end {
+        return Err(bad_attr(op, "neurons", "start must be <= end"));
+    }
+    let model = factory(op)?;
+    model.dynamics().validate()?;
+    Ok((start, end, model))
+}
@@ -0,0 +1,229 @@
+//! Fixed-point quantization for embedded targets
+//!
+//! [`FixedFormat`] describes the 32-bit fixed-point scalars of the embedded
+//! crates (`Q15_16` in shnn-micro, `Q16_16` in shnn-embedded; both keep 16
+//! fractional bits but store voltages and times in different units).
+//! [`crate::passes::QuantizePass`] snaps a module's weights and neuron
+//! parameters onto that grid, and [`quantization_report`] runs the float and
+//! quantized programs side by side to measure how far their spikes diverge.
+
+use std::collections::BTreeMap;
+
+use shnn_ir::Module;
+
+use crate::passes::{Pass, QuantizePass};
+use crate::{compile_with_passes, Result};
+
+/// 32-bit fixed-point format of an embedded target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedFormat {
+    /// shnn-micro `Q15_16` (potentials in V, times in whole ms)
+    Q15_16,
+    /// shnn-embedded `Q16_16` (potentials in mV, times in s)
+    Q16_16,
+}
+
+impl FixedFormat {
+    /// Fractional bits of both formats
+    pub const FRAC_BITS: u32 = 16;
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            FixedFormat::Q15_16 => "Q15.16",
+            FixedFormat::Q16_16 => "Q16.16",
+        }
+    }
+
+    /// Crate whose tables use this format
+    pub fn target(self) -> &'static str {
+        match self {
+            FixedFormat::Q15_16 => "shnn-micro",
+            FixedFormat::Q16_16 => "shnn-embedded",
+        }
+    }
+
+    /// Factor from NIR millivolts to the target's voltage unit
+    pub fn voltage_scale(self) -> f32 {
+        match self {
+            FixedFormat::Q15_16 => 1e-3,
+            FixedFormat::Q16_16 => 1.0,
+        }
+    }
+
+    /// Raw fixed-point value nearest to `value`, or `None` if out of range
+    pub fn to_raw(self, value: f32) -> Option<i32> {
+        let raw = (value as f64 * (1u64 << Self::FRAC_BITS) as f64).round();
+        if raw.is_finite() && raw >= i32::MIN as f64 && raw <= i32::MAX as f64 {
+            Some(raw as i32)
+        } else {
+            None
+        }
+    }
+
+    /// Float value of a raw fixed-point value
+    pub fn from_raw(self, raw: i32) -> f32 {
+        (raw as f64 / (1u64 << Self::FRAC_BITS) as f64) as f32
+    }
+
+    /// `value` snapped to the fixed-point grid
+    pub fn quantize(self, value: f32) -> Option<f32> {
+        self.to_raw(value).map(|raw| self.from_raw(raw))
+    }
+}
+
+impl std::fmt::Display for FixedFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Copy of `module` with weights and neuron parameters quantized to `format`
+pub fn quantize_module(module: &Module, format: FixedFormat) -> Result<Module> {
+    let mut quantized = module.clone();
+    QuantizePass { format }.run(&mut quantized)?;
+    Ok(quantized)
+}
+
+/// Divergence between the float and quantized runs of a module
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizationReport {
+    /// Format the module was quantized to
+    pub format: FixedFormat,
+    /// Spikes of the float run
+    pub float_spikes: usize,
+    /// Spikes of the quantized run
+    pub fixed_spikes: usize,
+    /// |fixed - float| / float spike count (0 when both are silent)
+    pub rate_error: f64,
+    /// Spikes paired per neuron in firing order
+    pub matched_spikes: usize,
+    /// Spikes present in only one run
+    pub unmatched_spikes: usize,
+    /// Mean |Δt| over matched spikes (ns)
+    pub mean_jitter_ns: f64,
+    /// Largest |Δt| over matched spikes (ns)
+    pub max_jitter_ns: u64,
+    /// Largest absolute weight change made by quantization
+    pub max_weight_error: f32,
+}
+
+impl QuantizationReport {
+    /// True if both runs produced exactly the same spikes
+    pub fn is_exact(&self) -> bool {
+        self.unmatched_spikes == 0 && self.max_jitter_ns == 0
+    }
+}
+
+fn spike_times_by_neuron(spikes: Vec<(u64, u32)>) -> BTreeMap<u32, Vec<u64>> {
+    let mut by_neuron: BTreeMap<u32, Vec<u64>> = BTreeMap::new();
+    for (time_ns, neuron) in spikes {
+        by_neuron.entry(neuron).or_default().push(time_ns);
+    }
+    by_neuron
+}
+
+fn max_weight_error(module: &Module, quantized: &Module) -> f32 {
+    module.ops.iter().zip(&quantized.ops)
+        .flat_map(|(a, b)| a.attrs.iter().filter_map(move |(key, value)| match (value, b.attrs.get(key)) {
+            (shnn_ir::AttributeValue::Weight(x), Some(shnn_ir::AttributeValue::Weight(y))) => Some((x - y).abs()),
+            _ => None,
+        }))
+        .fold(0.0, f32::max)
+}
+
+/// Quantize `module` to `format`, run the float and quantized programs with
+/// the same seed, and compare their spikes
+///
+/// Spikes are paired per neuron in firing order; the k-th spike of a neuron
+/// in one run is matched with its k-th spike in the other.
+pub fn quantization_report(module: &Module, format: FixedFormat) -> Result<QuantizationReport> {
+    let quantized = quantize_module(module, format)?;
+    let float_result = compile_with_passes(module)?.run()?;
+    let fixed_result = compile_with_passes(&quantized)?.run()?;
+
+    let float_spikes = float_result.spikes.len();
+    let fixed_spikes = fixed_result.spikes.len();
+    let rate_error = if float_spikes == 0 {
+        if fixed_spikes == 0 { 0.0 } else { 1.0 }
+    } else {
+        (fixed_spikes as f64 - float_spikes as f64).abs() / float_spikes as f64
+    };
+
+    let float_trains = spike_times_by_neuron(float_result.canonical_spikes());
+    let mut fixed_trains = spike_times_by_neuron(fixed_result.canonical_spikes());
+    let (mut matched, mut unmatched, mut jitter_sum, mut max_jitter_ns) = (0usize, 0usize, 0u128, 0u64);
+    for (neuron, float_times) in &float_trains {
+        let fixed_times = fixed_trains.remove(neuron).unwrap_or_default();
+        for (a, b) in float_times.iter().zip(&fixed_times) {
+            let jitter = a.abs_diff(*b);
+            jitter_sum += jitter as u128;
+            max_jitter_ns = max_jitter_ns.max(jitter);
+        }
+        let pairs = float_times.len().min(fixed_times.len());
+        matched += pairs;
+        unmatched += float_times.len() + fixed_times.len() - 2 * pairs;
+    }
+    unmatched += fixed_trains.values().map(Vec::len).sum::<usize>();
+
+    Ok(QuantizationReport {
+        format,
+        float_spikes,
+        fixed_spikes,
+        rate_error,
+        matched_spikes: matched,
+        unmatched_spikes: unmatched,
+        mean_jitter_ns: if matched == 0 { 0.0 } else { jitter_sum as f64 / matched as f64 },
+        max_jitter_ns,
+        max_weight_error: max_weight_error(module, &quantized),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shnn_ir::{layer_fully_connected_v1, lif_neuron_v1, runtime_simulate_run_v1, stimulus_poisson_v1, AttributeValue};
+
+    fn module(weight: f32) -> Module {
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(layer_fully_connected_v1(0, 1, 2, 3, weight, 1.0));
+        m.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 50.0));
+        m.push(runtime_simulate_run_v1(1.0, 50.0, false, Some(3)));
+        m
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let q = FixedFormat::Q15_16;
+        assert_eq!(q.to_raw(0.5), Some(32768));
+        assert_eq!(q.from_raw(-16384), -0.25);
+        assert_eq!(q.quantize(0.1), Some(6554.0 / 65536.0));
+        assert_eq!(q.to_raw(1e6), None);
+    }
+
+    #[test]
+    fn test_quantize_module_snaps_weights_and_voltages() {
+        let quantized = quantize_module(&module(0.1), FixedFormat::Q15_16).unwrap();
+        let layer = &quantized.ops[1];
+        assert_eq!(layer.attrs["weight"], AttributeValue::Weight(6554.0 / 65536.0));
+        // Q15_16 stores volts, so -50 mV is snapped to the 1/65536 V grid
+        let expected = FixedFormat::Q15_16.quantize(-0.05).unwrap() / 1e-3;
+        assert_eq!(quantized.ops[0].attrs["v_thresh"], AttributeValue::VoltageMv(expected));
+        // Quantizing twice is a no-op
+        assert_eq!(quantize_module(&quantized, FixedFormat::Q15_16).unwrap().to_text(), quantized.to_text());
+    }
+
+    #[test]
+    fn test_report_exact_for_grid_weights() {
+        // 0.5 is on the grid, so only the neuron parameters move
+        let report = quantization_report(&module(0.5), FixedFormat::Q16_16).unwrap();
+        assert!(report.float_spikes > 0);
+        assert_eq!(report.max_weight_error, 0.0);
+        assert_eq!(2 * report.matched_spikes + report.unmatched_spikes, report.float_spikes + report.fixed_spikes);
+
+        let report = quantization_report(&module(0.1), FixedFormat::Q15_16).unwrap();
+        assert!(report.max_weight_error > 0.0 && report.max_weight_error <= 0.5 / 65536.0);
+        assert!(report.rate_error >= 0.0);
+    }
+}
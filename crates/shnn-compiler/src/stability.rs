@@ -0,0 +1,236 @@
+//! dt stability analysis
+//!
+//! Explicit integrators only follow dv/dt = (v_inf - v) / tau when dt is
+//! well below tau: forward Euler overshoots and oscillates once dt > tau,
+//! diverges at dt >= 2·tau, and is only accurate to a few percent up to
+//! dt = tau/10. [`analyze_dt`] finds the fastest time constant a module's
+//! integrator steps and its shortest synaptic delay, and suggests the largest
+//! round dt resolving both; [`AutoDtPass`](crate::passes::AutoDtPass) applies
+//! the suggestion.
+
+use shnn_ir::{AttributeValue, DialectKey, Module, Operation};
+use shnn_runtime::integrator::IntegratorKind;
+
+use crate::diagnostics::shortest_delay_ns;
+use crate::{adex_from_attrs, duration_ns_from_attr, i64_opt_from_attr, integrator_from_attr, range_from_attr, Result};
+
+/// A time constant or delay that dt should resolve
+#[derive(Debug, Clone, PartialEq)]
+pub struct DtBound {
+    /// Op it comes from, as `dialect.name@vN #index`
+    pub op: String,
+    /// Attribute, or the quantity derived from attributes (`c_m/g_l`)
+    pub attr: &'static str,
+    /// Value (ns)
+    pub ns: u64,
+    /// Steps of the global dt per step of this op's population (`runtime.population_dt`)
+    pub substeps: u64,
+    /// Stiff dynamics (AdEx): unstable at dt >= tau whatever the integrator
+    pub stiff: bool,
+}
+
+impl DtBound {
+    /// Largest global dt (ns) integrating this constant to the integrator's accuracy
+    pub fn accurate_dt_ns(&self, integrator: IntegratorKind) -> u64 {
+        let steps_per_tau = match integrator {
+            IntegratorKind::ForwardEuler => 10,
+            IntegratorKind::Rk4 => 2,
+            IntegratorKind::ExponentialEuler => 1,
+        };
+        self.ns * self.substeps / steps_per_tau
+    }
+
+    /// Global dt (ns) at which integrating this constant diverges, if any
+    pub fn unstable_dt_ns(&self, integrator: IntegratorKind) -> Option<u64> {
+        let factor = match (self.stiff, integrator) {
+            (true, _) => 1.0,
+            (false, IntegratorKind::ForwardEuler) => 2.0,
+            (false, IntegratorKind::Rk4) => 2.785,
+            // Exact for linear decay
+            (false, IntegratorKind::ExponentialEuler) => return None,
+        };
+        Some((self.ns as f64 * self.substeps as f64 * factor) as u64)
+    }
+}
+
+/// dt of a module against its fastest dynamics
+#[derive(Debug, Clone, PartialEq)]
+pub struct DtAnalysis {
+    /// Current `runtime.simulate.run` dt (ns)
+    pub dt_ns: u64,
+    /// Integrator the run uses
+    pub integrator: IntegratorKind,
+    /// Time constant with the smallest accurate dt, if any neuron model has one
+    pub fastest: Option<DtBound>,
+    /// Shortest fixed synaptic delay, if any
+    pub shortest_delay: Option<DtBound>,
+    /// Largest 1/2/5·10^k ns step resolving `fastest` and `shortest_delay`
+    /// (and tiling every `population_dt`); never above `dt_ns`
+    pub suggested_dt_ns: u64,
+}
+
+impl DtAnalysis {
+    /// Whether dt is at or past the point where `fastest` diverges
+    pub fn is_unstable(&self) -> bool {
+        self.fastest
+            .as_ref()
+            .and_then(|bound| bound.unstable_dt_ns(self.integrator))
+            .is_some_and(|limit| self.dt_ns >= limit)
+    }
+
+    /// Whether dt under-resolves `fastest` without diverging
+    pub fn is_coarse(&self) -> bool {
+        !self.is_unstable()
+            && self.fastest.as_ref().is_some_and(|bound| self.dt_ns > bound.accurate_dt_ns(self.integrator))
+    }
+
+    /// Whether the suggestion differs from the current dt
+    pub fn needs_adjustment(&self) -> bool {
+        self.suggested_dt_ns < self.dt_ns
+    }
+}
+
+/// Analyse dt against the module's time constants and delays
+///
+/// `None` without a `runtime.simulate.run` op. Hodgkin-Huxley populations
+/// pick their own sub-step and are not bounds.
+pub fn analyze_dt(module: &Module) -> Result<Option<DtAnalysis>> {
+    let module = &*crate::symbols::resolved(module)?;
+    let Some(run) = simulate_run(module) else { return Ok(None) };
+    let dt_ns = duration_ns_from_attr(run, "dt")?;
+    let integrator = integrator_from_attr(run)?;
+
+    let mut populations = Vec::new();
+    for op in module.ops.iter().filter(|op| op.dialect == DialectKey::Runtime && op.name == "population_dt") {
+        let substeps = i64_opt_from_attr(op, "substeps")?.unwrap_or(1).max(1) as u64;
+        populations.push((range_from_attr(op, "neurons")?, substeps));
+    }
+    // A model's population steps finer only if one population_dt covers all of it
+    let substeps_of = |op: &Operation| -> Result<u64> {
+        if !op.attrs.contains_key("neurons") {
+            return Ok(1);
+        }
+        let (start, end) = range_from_attr(op, "neurons")?;
+        Ok(populations
+            .iter()
+            .find(|((s, e), _)| *s <= start && end <= *e)
+            .map_or(1, |(_, substeps)| *substeps))
+    };
+
+    let mut taus = Vec::new();
+    let mut delays = Vec::new();
+    for (index, op) in module.ops.iter().enumerate() {
+        let label = format!("{}.{}@{} #{}", op.dialect, op.name, op.version, index);
+        let bound = |attr, ns, substeps, stiff| DtBound { op: label.clone(), attr, ns, substeps, stiff };
+        match (&op.dialect, op.name.as_str()) {
+            (DialectKey::Neuron, "lif" | "alif") => {
+                taus.push(bound("tau_m", duration_ns_from_attr(op, "tau_m")?, substeps_of(op)?, false));
+            }
+            (DialectKey::Neuron, "adex") => {
+                let params = adex_from_attrs(op)?;
+                let substeps = substeps_of(op)?;
+                taus.push(bound("c_m/g_l", (params.tau_m() as f64 * 1e6) as u64, substeps, true));
+                taus.push(bound("tau_w", duration_ns_from_attr(op, "tau_w")?, substeps, true));
+            }
+            (DialectKey::Connectivity | DialectKey::Structure, _) => {
+                if let Some((attr, ns)) = shortest_delay_ns(op)?.filter(|(_, ns)| *ns > 0) {
+                    delays.push(bound(attr, ns, 1, false));
+                }
+            }
+            _ => {}
+        }
+    }
+    let fastest = taus.into_iter().filter(|b| b.ns > 0).min_by_key(|b| b.accurate_dt_ns(integrator));
+    let shortest_delay = delays.into_iter().min_by_key(|b| b.ns);
+
+    let target = [fastest.as_ref().map(|b| b.accurate_dt_ns(integrator)), shortest_delay.as_ref().map(|b| b.ns)]
+        .into_iter()
+        .flatten()
+        .fold(dt_ns, u64::min);
+    let tiling = populations.iter().fold(1, |acc, (_, substeps)| lcm(acc, *substeps));
+    let suggested_dt_ns = match target < dt_ns {
+        true => round_step(target, tiling).unwrap_or(dt_ns),
+        false => dt_ns,
+    };
+    Ok(Some(DtAnalysis { dt_ns, integrator, fastest, shortest_delay, suggested_dt_ns }))
+}
+
+/// Lower the module's dt to the suggested one; returns `(old, new)` if it changed
+pub fn apply_suggested_dt(module: &mut Module) -> Result<Option<(u64, u64)>> {
+    let Some(analysis) = analyze_dt(module)? else { return Ok(None) };
+    if !analysis.needs_adjustment() {
+        return Ok(None);
+    }
+    for op in &mut module.ops {
+        if op.dialect == DialectKey::Runtime && op.name == "simulate.run" {
+            op.attrs.insert("dt".to_string(), AttributeValue::DurationNs(analysis.suggested_dt_ns));
+        }
+    }
+    Ok(Some((analysis.dt_ns, analysis.suggested_dt_ns)))
+}
+
+fn simulate_run(module: &Module) -> Option<&Operation> {
+    module.ops.iter().find(|op| op.dialect == DialectKey::Runtime && op.name == "simulate.run")
+}
+
+/// Largest 1/2/5·10^k ns at most `limit_ns` and divisible by `divisor`
+fn round_step(limit_ns: u64, divisor: u64) -> Option<u64> {
+    (0..=18u32)
+        .flat_map(|k| [1u64, 2, 5].map(|m| m.checked_mul(10u64.pow(k))))
+        .flatten()
+        .filter(|&step| step <= limit_ns && step % divisor == 0)
+        .max()
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    let gcd = |mut a: u64, mut b: u64| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    a / gcd(a, b) * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shnn_ir::*;
+
+    #[test]
+    fn analyze_dt_suggests_round_step() {
+        // tau_m = 2 ms at dt = 1 ms: Euler is coarse; tau_m/10 = 200 us
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(2.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(synapse_connect_v1(0, 1, 0.5, 1.0));
+        m.push(runtime_simulate_run_v1(1.0, 10.0, false, None));
+        let analysis = analyze_dt(&m).expect("analyze").expect("has simulate.run");
+        assert!(analysis.is_coarse());
+        assert!(!analysis.is_unstable());
+        assert_eq!(analysis.fastest.as_ref().map(|b| b.attr), Some("tau_m"));
+        assert_eq!(analysis.suggested_dt_ns, 200_000);
+
+        // 5 ms step diverges; a 150 us delay rounds down to 100 us
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(2.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(synapse_connect_v1(0, 1, 0.5, 0.15));
+        m.push(runtime_simulate_run_v1(5.0, 10.0, false, None));
+        let analysis = analyze_dt(&m).expect("analyze").expect("has simulate.run");
+        assert!(analysis.is_unstable());
+        assert_eq!(analysis.suggested_dt_ns, 100_000);
+        assert_eq!(apply_suggested_dt(&mut m).expect("apply"), Some((5_000_000, 100_000)));
+        assert_eq!(apply_suggested_dt(&mut m).expect("apply"), None);
+
+        // Exponential Euler integrates the decay exactly
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(2.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(runtime_simulate_run_v1(1.0, 10.0, false, None)
+            .with_attr("integrator", AttributeValue::String("exponential_euler".into())));
+        let analysis = analyze_dt(&m).expect("analyze").expect("has simulate.run");
+        assert!(!analysis.is_coarse() && !analysis.needs_adjustment());
+
+        assert_eq!(round_step(300_000, 1), Some(200_000));
+        assert_eq!(round_step(300_000, 3), None);
+        assert_eq!(round_step(0, 1), None);
+    }
+}
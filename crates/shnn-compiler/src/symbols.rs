@@ -0,0 +1,245 @@
+//! Symbolic neuron names
+//!
+//! `structure.population@v1 { name = "exc", size = 400 }` declares a named
+//! population that other ops address as `%exc`, `%exc[3]` or `%exc[0..99]`
+//! instead of raw ids. [`resolve_symbols`] lays populations out densely, in
+//! declaration order, after every raw id the module already uses, records each
+//! one's range in its `neurons` attr and rewrites symbols to the NeuronRef or
+//! RangeU32 their attribute expects. A population that already has `neurons`
+//! keeps it, so resolved modules print and resolve again unchanged; such
+//! pinned populations may share neurons, as linked ones do through ports.
+
+use std::borrow::Cow;
+
+use shnn_ir::{AttributeValue, DialectKey, Module, Operation, SymbolIndex};
+
+use crate::link::neuron_extent;
+use crate::{bad_attr, op_spec, population_from_attrs, AttrKind, CompilerError, Result};
+
+/// A declared population and its ids
+#[derive(Debug, Clone, PartialEq)]
+pub struct Population {
+    /// Name symbols refer to
+    pub name: String,
+    /// Inclusive id range
+    pub neurons: (u32, u32),
+}
+
+impl Population {
+    /// Neurons in the population
+    pub fn size(&self) -> u32 {
+        self.neurons.1 - self.neurons.0 + 1
+    }
+}
+
+/// Populations of a module, in declaration order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolTable {
+    populations: Vec<Population>,
+}
+
+impl SymbolTable {
+    /// Lay out the populations `module` declares
+    pub fn build(module: &Module) -> Result<Self> {
+        let mut declared: Vec<(&Operation, &str, u32, Option<(u32, u32)>)> = Vec::new();
+        let mut next_id = 0u32;
+        for op in &module.ops {
+            if is_population(op) {
+                let (name, size, neurons) = population_from_attrs(op)?;
+                if declared.iter().any(|(_, seen, _, _)| *seen == name) {
+                    return Err(bad_attr(op, "name", format!("population '{}' is declared twice", name)));
+                }
+                if let Some((_, end)) = neurons {
+                    next_id = next_id.max(end.saturating_add(1));
+                }
+                declared.push((op, name, size, neurons));
+            } else {
+                next_id = next_id.max(neuron_extent(op)?);
+            }
+        }
+
+        let mut populations: Vec<Population> = Vec::with_capacity(declared.len());
+        for (op, name, size, neurons) in declared {
+            let neurons = match neurons {
+                Some(range) => range,
+                None => {
+                    let start = next_id;
+                    next_id = start
+                        .checked_add(size)
+                        .ok_or_else(|| bad_attr(op, "size", "population exceeds u32 neuron ids"))?;
+                    (start, next_id - 1)
+                }
+            };
+            populations.push(Population { name: name.to_string(), neurons });
+        }
+        Ok(SymbolTable { populations })
+    }
+
+    /// Declared populations, in order
+    pub fn populations(&self) -> &[Population] {
+        &self.populations
+    }
+
+    /// Population called `name`
+    pub fn get(&self, name: &str) -> Option<&Population> {
+        self.populations.iter().find(|p| p.name == name)
+    }
+
+    /// First population containing `id` and the id's index in it
+    pub fn lookup(&self, id: u32) -> Option<(&Population, u32)> {
+        self.populations
+            .iter()
+            .find(|p| (p.neurons.0..=p.neurons.1).contains(&id))
+            .map(|p| (p, id - p.neurons.0))
+    }
+
+    /// Ids `index` names in population `name`, as an inclusive range
+    pub fn range(&self, name: &str, index: SymbolIndex) -> std::result::Result<(u32, u32), String> {
+        let population = self
+            .get(name)
+            .ok_or_else(|| format!("unknown population '{}' (declare it with structure.population@v1)", name))?;
+        let size = population.size();
+        let (first, last) = match index {
+            SymbolIndex::All => (0, size - 1),
+            SymbolIndex::One(i) => (i, i),
+            SymbolIndex::Range(first, last) if first > last => {
+                return Err(format!("%{}[{}..{}] must satisfy start <= end", name, first, last));
+            }
+            SymbolIndex::Range(first, last) => (first, last),
+        };
+        if last >= size {
+            return Err(format!("index {} is outside population '{}' of {} neurons", last, name, size));
+        }
+        Ok((population.neurons.0 + first, population.neurons.0 + last))
+    }
+
+    /// `value` with every symbol replaced by the ids it names, shaped for `kind`
+    fn resolve(&self, value: &AttributeValue, kind: Option<AttrKind>) -> std::result::Result<AttributeValue, String> {
+        match value {
+            AttributeValue::NeuronSymbol { name, index } => {
+                let (start, end) = self.range(name, *index)?;
+                let one = matches!(index, SymbolIndex::One(_)) || kind == Some(AttrKind::NeuronRef);
+                match (one, start == end) {
+                    (true, true) => Ok(AttributeValue::NeuronRef(start)),
+                    (true, false) => Err(format!("{} names {} neurons where one is expected, e.g. %{}[0]", value, end - start + 1, name)),
+                    (false, _) => Ok(AttributeValue::RangeU32 { start, end }),
+                }
+            }
+            AttributeValue::List(items) => items
+                .iter()
+                .map(|item| self.resolve(item, kind))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map(AttributeValue::List),
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+/// Whether `module` has symbols or populations left to resolve
+pub fn has_symbols(module: &Module) -> bool {
+    module.ops.iter().any(|op| {
+        (is_population(op) && !op.attrs.contains_key("neurons")) || op.attrs.values().any(is_symbol)
+    })
+}
+
+/// Copy of `module` with populations laid out and symbols replaced by ids
+pub fn resolve_symbols(module: &Module) -> Result<Module> {
+    let table = SymbolTable::build(module)?;
+    let mut resolved = Module::new();
+    for op in &module.ops {
+        let mut op = op.clone();
+        if is_population(&op) {
+            if let Some(population) = table.get(crate::string_from_attr(&op, "name")?) {
+                let (start, end) = population.neurons;
+                op.attrs.insert("neurons".to_string(), AttributeValue::RangeU32 { start, end });
+            }
+        }
+        let spec = op_spec(&op);
+        for (key, value) in op.attrs.iter_mut() {
+            if !is_symbol(value) {
+                continue;
+            }
+            let kind = spec.and_then(|spec| spec.attrs.iter().find(|a| a.name == key.as_str())).map(|a| a.kind);
+            *value = table.resolve(value, kind).map_err(|reason| CompilerError::Message(format!(
+                "{}.{}@{}: attribute '{}': {}", op.dialect, op.name, op.version, key, reason,
+            )))?;
+        }
+        resolved.push(op);
+    }
+    Ok(resolved)
+}
+
+/// `module` itself, or its resolved copy if it has symbols
+pub fn resolved(module: &Module) -> Result<Cow<'_, Module>> {
+    match has_symbols(module) {
+        true => resolve_symbols(module).map(Cow::Owned),
+        false => Ok(Cow::Borrowed(module)),
+    }
+}
+
+fn is_population(op: &Operation) -> bool {
+    op.dialect == DialectKey::Structure && op.name == "population"
+}
+
+fn is_symbol(value: &AttributeValue) -> bool {
+    match value {
+        AttributeValue::NeuronSymbol { .. } => true,
+        AttributeValue::List(items) => items.iter().any(is_symbol),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shnn_ir::*;
+
+    #[test]
+    fn resolve_symbols_lays_out_populations_after_raw_ids() {
+        let text = "nir.module {\n\
+            structure.population@v1 { name = \"exc\", size = 4 }\n\
+            structure.population@v1 { name = \"inh\", size = 2 }\n\
+            connectivity.layer_fully_connected@v1 { delay = 1000000 ns, in = %exc[0..1], out = %inh, weight = 0.5 }\n\
+            connectivity.synapse_connect@v1 { delay = 1000000 ns, post = %n2, pre = %inh[1], weight = 1 }\n\
+            stimulus.poisson@v1 { amplitude = 10 nA, duration = 1000000 ns, neuron = %exc[3], rate = 20 Hz, start = 0 ns }\n\
+            runtime.simulate.run@v1 { dt = 100000 ns, duration = 1000000 ns, record_potentials = false }\n\
+            }\n";
+        let module = parse_text(text).expect("parse");
+        assert!(has_symbols(&module));
+        let table = SymbolTable::build(&module).expect("table");
+        // %n2 is raw, so populations start at 3
+        assert_eq!(table.get("exc").map(|p| p.neurons), Some((3, 6)));
+        assert_eq!(table.get("inh").map(|p| p.neurons), Some((7, 8)));
+        assert_eq!(table.lookup(8).map(|(p, i)| (p.name.as_str(), i)), Some(("inh", 1)));
+
+        let resolved = resolve_symbols(&module).expect("resolve");
+        assert!(!has_symbols(&resolved));
+        assert_eq!(resolved.ops[0].attrs.get("neurons"), Some(&AttributeValue::RangeU32 { start: 3, end: 6 }));
+        assert_eq!(resolved.ops[2].attrs.get("in"), Some(&AttributeValue::RangeU32 { start: 3, end: 4 }));
+        assert_eq!(resolved.ops[2].attrs.get("out"), Some(&AttributeValue::RangeU32 { start: 7, end: 8 }));
+        assert_eq!(resolved.ops[3].attrs.get("pre"), Some(&AttributeValue::NeuronRef(8)));
+        assert_eq!(resolved.ops[4].attrs.get("neuron"), Some(&AttributeValue::NeuronRef(6)));
+        // Resolved text keeps its layout when parsed and resolved again
+        let reparsed = parse_text(&resolved.to_text()).expect("parse");
+        assert_eq!(SymbolTable::build(&reparsed).expect("table"), table);
+        crate::verify_module(&module).expect("verify");
+        assert_eq!(crate::program_shape(&module).expect("shape").neurons, 7);
+    }
+
+    #[test]
+    fn resolve_symbols_rejects_bad_references() {
+        let resolve = |population: Operation, value: AttributeValue| {
+            let mut m = Module::new();
+            m.push(population);
+            m.push(synapse_connect_v1(0, 0, 1.0, 1.0).with_attr("pre", value));
+            resolve_symbols(&m).map(|_| ()).unwrap_err().to_string()
+        };
+        let exc = || structure_population_v1("exc", 4);
+        assert!(resolve(exc(), AttributeValue::symbol("inh", SymbolIndex::One(0))).contains("unknown population 'inh'"));
+        assert!(resolve(exc(), AttributeValue::symbol("exc", SymbolIndex::One(4))).contains("outside population 'exc'"));
+        assert!(resolve(exc(), AttributeValue::symbol("exc", SymbolIndex::All)).contains("names 4 neurons where one is expected"));
+        assert!(resolve(exc().with_attr("neurons", AttributeValue::RangeU32 { start: 0, end: 2 }), AttributeValue::NeuronRef(0))
+            .contains("size"));
+        assert!(resolve(structure_population_v1("1x", 1), AttributeValue::NeuronRef(0)).contains("name"));
+    }
+}
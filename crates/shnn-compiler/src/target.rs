@@ -0,0 +1,219 @@
+//! Lowering targets
+//!
+//! [`lower`](crate::lower) walks a module once, checking every op and
+//! collecting what it describes, then hands the result to a [`CompileTarget`]
+//! in three steps: the network ([`NetworkPlan`]), the run's inputs
+//! ([`StimulusPlan`]) and what the run observes or checks ([`RunPlan`]).
+//! Backends implement only what they emit: [`CpuTarget`] builds the runtime's
+//! simulation engine, [`MicroTarget`](crate::codegen::MicroTarget) reads the
+//! network into shnn-micro tables.
+
+use std::collections::BTreeMap;
+
+use shnn_ir::Operation;
+use shnn_runtime::{
+    expect::Expectation,
+    mask::{MaskMode, NeuronMask},
+    modulation::Modulator,
+    monitor::RateMonitor,
+    network::{NetworkBuilder, SNNNetwork},
+    noise::OuNoise,
+    readout::ReadoutMonitor,
+    simulation::{SimulationEngine, SimulationParams, StimulusPattern},
+    spatial::NeuronPositions,
+    state::NetworkState,
+};
+
+use crate::{bad_attr, string_from_attr, time_ns_from_attr, weight_monitor_from_attrs, CompilerError, LoweredProgram, Result};
+
+/// Backend a module is lowered for
+///
+/// Called once per module, in order: [`build_network`](Self::build_network),
+/// [`build_stimuli`](Self::build_stimuli), [`finalize`](Self::finalize).
+/// Every op has been checked before the first call.
+pub trait CompileTarget {
+    /// Network as the backend represents it, carried between the steps
+    type Network;
+    /// What lowering produces
+    type Output;
+
+    /// Build the network
+    fn build_network(&mut self, plan: NetworkPlan) -> Result<Self::Network>;
+
+    /// Attach the run's stimuli and noise
+    fn build_stimuli(&mut self, network: &mut Self::Network, plan: StimulusPlan<'_>) -> Result<()>;
+
+    /// Attach monitors, state and expectations, and produce the output
+    fn finalize(self, network: Self::Network, plan: RunPlan<'_>) -> Result<Self::Output>;
+}
+
+/// Network of a lowered module
+#[derive(Debug)]
+pub struct NetworkPlan {
+    /// Neurons, synapses and population models, with the module's config and dt
+    pub builder: NetworkBuilder,
+    /// Masks in op order (a later mask replaces an earlier one in the same mode)
+    pub masks: Vec<(MaskMode, NeuronMask)>,
+    /// Neuromodulator, if any
+    pub modulator: Option<Modulator>,
+    /// Neuron positions
+    pub positions: NeuronPositions,
+    /// runtime.simulate.run parameters
+    pub params: SimulationParams,
+}
+
+impl NetworkPlan {
+    /// Build the runtime network with masks, modulator and positions attached
+    pub fn build(self) -> Result<SNNNetwork> {
+        let mut network = self.builder.build().map_err(CompilerError::Runtime)?;
+        for (mode, mask) in self.masks {
+            network.apply_mask(mode, mask);
+        }
+        if let Some(m) = self.modulator {
+            network.set_modulator(m);
+        }
+        network.set_positions(self.positions);
+        Ok(network)
+    }
+}
+
+/// Inputs of a lowered module's run
+#[derive(Debug)]
+pub struct StimulusPlan<'m> {
+    /// Stimuli in op order
+    pub stimuli: Vec<StimulusPattern>,
+    /// OU noise sources with their op, over neurons known to exist
+    pub noise: Vec<(&'m Operation, OuNoise)>,
+}
+
+/// What a lowered module's run observes, restores, saves and checks
+#[derive(Debug)]
+pub struct RunPlan<'m> {
+    /// Rate monitors
+    pub monitors: Vec<RateMonitor>,
+    /// Readout observers
+    pub readouts: Vec<ReadoutMonitor>,
+    /// runtime.monitor_weights op and its default seed, resolved against the network
+    pub weight_monitor: Option<(&'m Operation, u64)>,
+    /// Post-simulation assertions
+    pub expectations: Vec<Expectation>,
+    /// State to start from and the op that loaded it
+    pub initial_state: Option<(&'m Operation, NetworkState)>,
+    /// runtime.save_state ops, each within the run
+    pub state_saves: Vec<&'m Operation>,
+    /// Seed of every stochastic op (`<dialect>.<name>#<ordinal>`)
+    pub seeds: BTreeMap<String, u64>,
+}
+
+/// Lowering to the runtime's [`SimulationEngine`] ([`compile_module`](crate::compile_module))
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuTarget;
+
+impl CompileTarget for CpuTarget {
+    type Network = LoweredProgram;
+    type Output = LoweredProgram;
+
+    fn build_network(&mut self, plan: NetworkPlan) -> Result<LoweredProgram> {
+        let params = plan.params.clone();
+        let engine = SimulationEngine::new(plan.build()?, params).map_err(CompilerError::Runtime)?;
+        Ok(LoweredProgram { engine, stimuli: Vec::new(), expectations: Vec::new(), seeds: BTreeMap::new() })
+    }
+
+    fn build_stimuli(&mut self, program: &mut LoweredProgram, plan: StimulusPlan<'_>) -> Result<()> {
+        for (index, stimulus) in plan.stimuli.iter().enumerate() {
+            program.engine.add_stimulus(stimulus.clone());
+            if matches!(stimulus, StimulusPattern::Poisson { .. }) {
+                program.seeds.insert(format!("stimulus#{}", index), program.engine.stimulus_seed(index));
+            }
+        }
+        program.stimuli = plan.stimuli;
+        for (_, noise) in plan.noise {
+            program.engine.add_noise(noise);
+        }
+        Ok(())
+    }
+
+    fn finalize(self, mut program: LoweredProgram, plan: RunPlan<'_>) -> Result<LoweredProgram> {
+        let engine = &mut program.engine;
+        for monitor in plan.monitors {
+            engine.add_rate_monitor(monitor);
+        }
+        for readout in plan.readouts {
+            engine.add_observer(Box::new(readout));
+        }
+        if let Some((op, default_seed)) = plan.weight_monitor {
+            let monitor = weight_monitor_from_attrs(op, engine.network(), default_seed)?;
+            engine.set_weight_monitor(monitor);
+        }
+        if let Some((op, state)) = plan.initial_state {
+            engine.set_initial_state(state)
+                .map_err(|e| bad_attr(op, "path", format!("does not match the network: {}", e)))?;
+        }
+        for op in plan.state_saves {
+            engine.save_state_at(time_ns_from_attr(op, "at")?, string_from_attr(op, "path")?);
+        }
+        program.expectations = plan.expectations;
+        program.seeds.extend(plan.seeds);
+        Ok(program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compile_module, lower};
+    use shnn_ir::{
+        layer_fully_connected_v1, lif_neuron_v1, runtime_save_state_v1, runtime_simulate_run_v1, stimulus_poisson_v1,
+        test_expect_rate_v1, Module,
+    };
+
+    /// Counts what the walk hands a backend
+    #[derive(Default)]
+    struct Census {
+        neurons: usize,
+        inputs: usize,
+        expectations: usize,
+    }
+
+    impl CompileTarget for Census {
+        type Network = SNNNetwork;
+        type Output = Census;
+
+        fn build_network(&mut self, plan: NetworkPlan) -> Result<SNNNetwork> {
+            plan.build()
+        }
+
+        fn build_stimuli(&mut self, _network: &mut SNNNetwork, plan: StimulusPlan<'_>) -> Result<()> {
+            self.inputs = plan.stimuli.len() + plan.noise.len();
+            Ok(())
+        }
+
+        fn finalize(mut self, network: SNNNetwork, plan: RunPlan<'_>) -> Result<Census> {
+            self.neurons = network.neuron_ids().len();
+            self.expectations = plan.expectations.len();
+            Ok(self)
+        }
+    }
+
+    #[test]
+    fn targets_share_the_op_walk() {
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(20.0, -70.0, -70.0, -50.0, 2.0, 10.0, 1.0));
+        m.push(layer_fully_connected_v1(0, 1, 2, 4, 1.0, 1.0));
+        m.push(stimulus_poisson_v1(0, 50.0, 10.0, 0.0, 100.0));
+        m.push(test_expect_rate_v1(2, 4, Some(0.0), None));
+        m.push(runtime_simulate_run_v1(1.0, 100.0, false, Some(3)));
+
+        let census = lower(&m, Census::default()).expect("lower");
+        assert_eq!((census.neurons, census.inputs, census.expectations), (5, 1, 1));
+        let program = compile_module(&m).expect("compile");
+        assert_eq!(program.engine.network().neuron_ids().len(), 5);
+        assert!(program.seeds.contains_key("stimulus#0"));
+
+        // Checks run before any target is called
+        let mut late_save = m.clone();
+        late_save.push(runtime_save_state_v1("state.json", 500.0));
+        let err = lower(&late_save, Census::default()).err().expect("save after the run");
+        assert!(err.to_string().contains("is after the run ends"));
+    }
+}
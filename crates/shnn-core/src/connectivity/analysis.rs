@@ -0,0 +1,439 @@
+//! Topological analysis of pairwise connectivity
+//!
+//! [`Topology`] is a compact, index-based snapshot of a directed network
+//! (built from [`GraphNetwork`], [`SparseMatrixNetwork`] or a plain edge
+//! list) with cycle detection, strongly connected components, topological
+//! ordering, shortest paths and degree distributions. All traversals are
+//! iterative, so deep recurrent chains cannot overflow the stack, and results
+//! list neurons in ascending id order.
+
+use crate::{
+    connectivity::{graph::GraphNetwork, sparse::SparseMatrixNetwork},
+    spike::NeuronId,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Directed connectivity snapshot used for analysis
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Topology {
+    /// Neurons in ascending id order; positions are node indices
+    nodes: Vec<NeuronId>,
+    /// Sorted, de-duplicated successor indices per node
+    successors: Vec<Vec<usize>>,
+    /// Number of distinct edges
+    edge_count: usize,
+}
+
+/// Histograms of in- and out-degree (`histogram[d]` = neurons with degree `d`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DegreeDistribution {
+    /// Neurons per in-degree
+    pub in_degree: Vec<usize>,
+    /// Neurons per out-degree
+    pub out_degree: Vec<usize>,
+}
+
+/// One-call summary of a [`Topology`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TopologySummary {
+    /// Number of neurons
+    pub node_count: usize,
+    /// Number of distinct directed edges
+    pub edge_count: usize,
+    /// Whether any directed cycle (including a self-loop) exists
+    pub has_cycles: bool,
+    /// Number of neurons with a self-loop
+    pub self_loops: usize,
+    /// Number of strongly connected components
+    pub scc_count: usize,
+    /// Size of the largest strongly connected component
+    pub largest_scc: usize,
+    /// Neurons without incoming edges
+    pub sources: usize,
+    /// Neurons without outgoing edges
+    pub sinks: usize,
+    /// Edges on the longest path, if the network is acyclic
+    pub longest_path: Option<usize>,
+    /// In/out-degree histograms
+    pub degrees: DegreeDistribution,
+}
+
+impl Topology {
+    /// Build from neurons and directed `(source, target)` edges
+    ///
+    /// Neurons that only appear in `edges` are added; duplicate edges are
+    /// counted once.
+    pub fn from_edges<N, E>(nodes: N, edges: E) -> Self
+    where
+        N: IntoIterator<Item = NeuronId>,
+        E: IntoIterator<Item = (NeuronId, NeuronId)>,
+    {
+        let edges: Vec<(NeuronId, NeuronId)> = edges.into_iter().collect();
+        let mut ids: Vec<NeuronId> = nodes.into_iter()
+            .chain(edges.iter().flat_map(|&(s, t)| [s, t]))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut successors = vec![Vec::new(); ids.len()];
+        for (source, target) in edges {
+            // Both ends are in `ids` by construction
+            let s = ids.binary_search(&source).unwrap_or_default();
+            let t = ids.binary_search(&target).unwrap_or_default();
+            successors[s].push(t);
+        }
+        let mut edge_count = 0;
+        for targets in &mut successors {
+            targets.sort_unstable();
+            targets.dedup();
+            edge_count += targets.len();
+        }
+
+        Self { nodes: ids, successors, edge_count }
+    }
+
+    /// Snapshot of a [`GraphNetwork`]
+    pub fn from_graph(network: &GraphNetwork) -> Self {
+        Self::from_edges(network.neurons(), network.edge_pairs())
+    }
+
+    /// Snapshot of a [`SparseMatrixNetwork`]
+    pub fn from_sparse(network: &SparseMatrixNetwork) -> Self {
+        Self::from_edges(network.neurons(), network.edge_pairs())
+    }
+
+    /// Number of neurons
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Number of distinct directed edges
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Neurons in ascending id order
+    pub fn nodes(&self) -> &[NeuronId] {
+        &self.nodes
+    }
+
+    /// Successor node indices of the neuron at `nodes()[index]`
+    pub fn successors(&self, index: usize) -> &[usize] {
+        &self.successors[index]
+    }
+
+    /// Position of `id` in [`Topology::nodes`]
+    pub fn index_of(&self, id: NeuronId) -> Option<usize> {
+        self.nodes.binary_search(&id).ok()
+    }
+
+    /// Whether the network contains a directed cycle (self-loops count)
+    pub fn has_cycles(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// One directed cycle, listed from its first neuron back to the one
+    /// that closes it, or `None` if the network is acyclic
+    pub fn find_cycle(&self) -> Option<Vec<NeuronId>> {
+        const UNSEEN: u8 = 0;
+        const ON_STACK: u8 = 1;
+        const DONE: u8 = 2;
+
+        let mut state = vec![UNSEEN; self.nodes.len()];
+        // (node, next successor position)
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+        for root in 0..self.nodes.len() {
+            if state[root] != UNSEEN {
+                continue;
+            }
+            state[root] = ON_STACK;
+            stack.push((root, 0));
+            while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+                let Some(&succ) = self.successors[node].get(*next) else {
+                    state[node] = DONE;
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+                match state[succ] {
+                    UNSEEN => {
+                        state[succ] = ON_STACK;
+                        stack.push((succ, 0));
+                    }
+                    ON_STACK => {
+                        let start = stack.iter().position(|&(n, _)| n == succ).unwrap_or(0);
+                        return Some(stack[start..].iter().map(|&(n, _)| self.nodes[n]).collect());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Strongly connected components (Tarjan)
+    ///
+    /// Each component is sorted by neuron id and components are ordered by
+    /// their smallest neuron.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NeuronId>> {
+        const UNVISITED: usize = usize::MAX;
+        let n = self.nodes.len();
+        let mut index = vec![UNVISITED; n];
+        let mut lowlink = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut component_stack = Vec::new();
+        let mut call_stack: Vec<(usize, usize)> = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            call_stack.push((root, 0));
+            index[root] = next_index;
+            lowlink[root] = next_index;
+            next_index += 1;
+            component_stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&mut (node, ref mut next)) = call_stack.last_mut() {
+                if let Some(&succ) = self.successors[node].get(*next) {
+                    *next += 1;
+                    if index[succ] == UNVISITED {
+                        index[succ] = next_index;
+                        lowlink[succ] = next_index;
+                        next_index += 1;
+                        component_stack.push(succ);
+                        on_stack[succ] = true;
+                        call_stack.push((succ, 0));
+                    } else if on_stack[succ] {
+                        lowlink[node] = lowlink[node].min(index[succ]);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[node]);
+                }
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = component_stack.pop() {
+                        on_stack[member] = false;
+                        component.push(self.nodes[member]);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+
+        components.sort_unstable_by_key(|c| c[0]);
+        components
+    }
+
+    /// Topological order, or `None` if there is a cycle
+    ///
+    /// Neurons without inputs come first in id order, then the rest breadth-first.
+    pub fn topological_order(&self) -> Option<Vec<NeuronId>> {
+        self.kahn().map(|order| order.into_iter().map(|i| self.nodes[i]).collect())
+    }
+
+    /// Number of edges on the longest path, or `None` if there is a cycle
+    pub fn longest_path(&self) -> Option<usize> {
+        let order = self.kahn()?;
+        let mut depth = vec![0usize; self.nodes.len()];
+        for node in order {
+            for &succ in &self.successors[node] {
+                depth[succ] = depth[succ].max(depth[node] + 1);
+            }
+        }
+        Some(depth.into_iter().max().unwrap_or(0))
+    }
+
+    /// Fewest-edge path from `from` to `to` (both included), if one exists
+    pub fn shortest_path(&self, from: NeuronId, to: NeuronId) -> Option<Vec<NeuronId>> {
+        let start = self.index_of(from)?;
+        let goal = self.index_of(to)?;
+        let mut parent = vec![usize::MAX; self.nodes.len()];
+        parent[start] = start;
+        let mut frontier = vec![start];
+        let mut head = 0;
+        while head < frontier.len() && parent[goal] == usize::MAX {
+            let node = frontier[head];
+            head += 1;
+            for &succ in &self.successors[node] {
+                if parent[succ] == usize::MAX {
+                    parent[succ] = node;
+                    frontier.push(succ);
+                }
+            }
+        }
+        if parent[goal] == usize::MAX {
+            return None;
+        }
+        let mut path = vec![self.nodes[goal]];
+        let mut node = goal;
+        while node != start {
+            node = parent[node];
+            path.push(self.nodes[node]);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// In- and out-degree histograms
+    pub fn degree_distribution(&self) -> DegreeDistribution {
+        let in_degrees = self.in_degrees();
+        let mut distribution = DegreeDistribution::default();
+        for (node, targets) in self.successors.iter().enumerate() {
+            bump(&mut distribution.out_degree, targets.len());
+            bump(&mut distribution.in_degree, in_degrees[node]);
+        }
+        distribution
+    }
+
+    /// All analyses in one pass over the results
+    pub fn summary(&self) -> TopologySummary {
+        let in_degrees = self.in_degrees();
+        let components = self.strongly_connected_components();
+        let self_loops = self.successors.iter().enumerate()
+            .filter(|(node, targets)| targets.binary_search(node).is_ok())
+            .count();
+        let longest_path = self.longest_path();
+        TopologySummary {
+            node_count: self.nodes.len(),
+            edge_count: self.edge_count,
+            has_cycles: longest_path.is_none(),
+            self_loops,
+            scc_count: components.len(),
+            largest_scc: components.iter().map(Vec::len).max().unwrap_or(0),
+            sources: in_degrees.iter().filter(|&&d| d == 0).count(),
+            sinks: self.successors.iter().filter(|t| t.is_empty()).count(),
+            longest_path,
+            degrees: self.degree_distribution(),
+        }
+    }
+
+    fn in_degrees(&self) -> Vec<usize> {
+        let mut in_degrees = vec![0; self.nodes.len()];
+        for targets in &self.successors {
+            for &t in targets {
+                in_degrees[t] += 1;
+            }
+        }
+        in_degrees
+    }
+
+    /// Kahn's algorithm over node indices, visiting ready nodes first-in first-out
+    fn kahn(&self) -> Option<Vec<usize>> {
+        let mut in_degrees = self.in_degrees();
+        // `order` doubles as the queue: entries past `head` are ready
+        let mut order: Vec<usize> = (0..self.nodes.len()).filter(|&i| in_degrees[i] == 0).collect();
+        let mut head = 0;
+        while let Some(&node) = order.get(head) {
+            head += 1;
+            for &succ in &self.successors[node] {
+                in_degrees[succ] -= 1;
+                if in_degrees[succ] == 0 {
+                    order.push(succ);
+                }
+            }
+        }
+        (order.len() == self.nodes.len()).then_some(order)
+    }
+}
+
+fn bump(histogram: &mut Vec<usize>, degree: usize) {
+    if histogram.len() <= degree {
+        histogram.resize(degree + 1, 0);
+    }
+    histogram[degree] += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectivity::graph::GraphEdge;
+
+    fn ids(raw: &[u32]) -> Vec<NeuronId> {
+        raw.iter().copied().map(NeuronId::new).collect()
+    }
+
+    fn edges(raw: &[(u32, u32)]) -> Vec<(NeuronId, NeuronId)> {
+        raw.iter().map(|&(s, t)| (NeuronId::new(s), NeuronId::new(t))).collect()
+    }
+
+    #[test]
+    fn test_acyclic_feedforward() {
+        // 0 -> 1 -> 3, 0 -> 2 -> 3, isolated 4
+        let topology = Topology::from_edges(ids(&[4]), edges(&[(0, 1), (0, 2), (1, 3), (2, 3), (1, 3)]));
+        assert_eq!(topology.node_count(), 5);
+        assert_eq!(topology.edge_count(), 4);
+        assert!(!topology.has_cycles());
+        assert_eq!(topology.topological_order(), Some(ids(&[0, 4, 1, 2, 3])));
+        assert_eq!(topology.longest_path(), Some(2));
+        assert_eq!(topology.shortest_path(NeuronId::new(0), NeuronId::new(3)), Some(ids(&[0, 1, 3])));
+        assert_eq!(topology.shortest_path(NeuronId::new(3), NeuronId::new(0)), None);
+
+        let summary = topology.summary();
+        assert_eq!((summary.scc_count, summary.largest_scc), (5, 1));
+        assert_eq!((summary.sources, summary.sinks), (2, 2));
+        assert_eq!(summary.degrees.out_degree, vec![2, 2, 1]);
+        assert_eq!(summary.degrees.in_degree, vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_cycles_and_components() {
+        // Ring 0 -> 1 -> 2 -> 0 feeding 3, which has a self-loop
+        let topology = Topology::from_edges(Vec::new(), edges(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)]));
+        let cycle = topology.find_cycle().expect("Should find a cycle");
+        assert_eq!(cycle, ids(&[0, 1, 2]));
+        assert_eq!(topology.topological_order(), None);
+        assert_eq!(topology.longest_path(), None);
+        assert_eq!(topology.strongly_connected_components(), vec![ids(&[0, 1, 2]), ids(&[3])]);
+
+        let summary = topology.summary();
+        assert!(summary.has_cycles);
+        assert_eq!(summary.self_loops, 1);
+        assert_eq!(summary.largest_scc, 3);
+
+        let only_self_loop = Topology::from_edges(Vec::new(), edges(&[(5, 5)]));
+        assert_eq!(only_self_loop.find_cycle(), Some(ids(&[5])));
+    }
+
+    #[test]
+    fn test_deep_chain_does_not_recurse() {
+        let chain: Vec<(u32, u32)> = (0..100_000).map(|i| (i, i + 1)).collect();
+        let topology = Topology::from_edges(Vec::new(), edges(&chain));
+        assert!(!topology.has_cycles());
+        assert_eq!(topology.strongly_connected_components().len(), 100_001);
+        assert_eq!(topology.longest_path(), Some(100_000));
+    }
+
+    #[test]
+    fn test_backends_produce_same_topology() {
+        let mut graph = GraphNetwork::new();
+        let mut sparse = SparseMatrixNetwork::new(8);
+        for id in 0..3 {
+            sparse.add_neuron(NeuronId::new(id)).expect("Should add neuron");
+        }
+        for (s, t) in edges(&[(0, 1), (1, 2), (2, 1)]) {
+            graph.add_edge(GraphEdge::new(s, t, 0.5)).expect("Should add edge");
+            sparse.set_weight(s, t, 0.5).expect("Should set weight");
+        }
+        assert_eq!(Topology::from_graph(&graph), Topology::from_sparse(&sparse));
+        assert_eq!(
+            Topology::from_graph(&graph).strongly_connected_components(),
+            vec![ids(&[0]), ids(&[1, 2])]
+        );
+    }
+}
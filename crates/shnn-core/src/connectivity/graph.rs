@@ -7,6 +7,7 @@
 use crate::{
     connectivity::{
         NetworkConnectivity, BatchConnectivity, PlasticConnectivity,
+        analysis::Topology,
         types::{SpikeRoute, ConnectivityStats, ConnectivityError, ConnectionId},
     },
     spike::{NeuronId, Spike},
@@ -294,6 +295,16 @@ impl GraphNetwork {
         }
     }
     
+    /// All neurons with at least one edge
+    pub fn neurons(&self) -> impl Iterator<Item = NeuronId> + '_ {
+        self.neurons.iter().copied()
+    }
+
+    /// All `(source, target)` edges
+    pub fn edge_pairs(&self) -> impl Iterator<Item = (NeuronId, NeuronId)> + '_ {
+        self.edges.keys().map(|id| (id.source, id.target))
+    }
+    
     /// Remove neurons that have no connections
     fn cleanup_isolated_neurons(&mut self) {
         let connected_neurons: HashSet<NeuronId> = self.edges.keys()
@@ -517,7 +528,7 @@ impl NetworkConnectivity<NeuronId> for GraphNetwork {
             total_weight,
             average_weight,
             memory_usage,
-            has_cycles: None, // Would need cycle detection
+            has_cycles: Some(Topology::from_graph(self).has_cycles()),
             density,
             custom_stats: vec![
                 ("active_edges".to_string(), self.edges.values().filter(|e| e.active).count() as f32),
@@ -6,7 +6,7 @@
 use crate::{
     connectivity::{
         NetworkConnectivity, BatchConnectivity, PlasticConnectivity,
-        types::{SpikeRoute, ConnectivityStats, ConnectivityError, ConnectionId},
+        types::{SpikeRoute, ConnectivityStats, ConnectivityError, ConnectionId, RouteBuffer},
     },
     spike::{NeuronId, Spike},
     time::Time,
@@ -155,6 +155,82 @@ impl From<ConnectivityError> for MatrixConnectivityError {
     }
 }
 
+/// Connection statistics kept in sync with every adjacency write
+///
+/// Degrees count incoming plus outgoing connections, as in
+/// [`NetworkConnectivity::get_stats`]. `degree_histogram[d]` is the number of
+/// neurons with degree `d`; since a write moves a degree by one, the
+/// minimum and maximum can be updated in O(1) from the histogram.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct EdgeCounters {
+    connection_count: usize,
+    /// Sum of positive weights (f64 so repeated updates do not drift)
+    total_weight: f64,
+    degrees: Vec<u32>,
+    degree_histogram: Vec<usize>,
+    max_degree: usize,
+    min_degree: usize,
+}
+
+impl EdgeCounters {
+    fn add_node(&mut self) {
+        self.degrees.push(0);
+        if self.degree_histogram.is_empty() {
+            self.degree_histogram.push(0);
+        }
+        self.degree_histogram[0] += 1;
+        self.min_degree = 0;
+    }
+
+    /// Account for entry (row, col) changing from `old` to `new`
+    fn record(&mut self, row: usize, col: usize, old: f32, new: f32) {
+        let (was, is) = (old > 0.0, new > 0.0);
+        if was {
+            self.total_weight -= old as f64;
+        }
+        if is {
+            self.total_weight += new as f64;
+        }
+        if was != is {
+            if is {
+                self.connection_count += 1;
+            } else {
+                self.connection_count -= 1;
+            }
+            self.shift_degree(row, is);
+            self.shift_degree(col, is);
+        }
+        if self.connection_count == 0 {
+            self.total_weight = 0.0;
+        }
+    }
+
+    fn shift_degree(&mut self, index: usize, up: bool) {
+        let old = self.degrees[index] as usize;
+        let new = if up { old + 1 } else { old - 1 };
+        self.degrees[index] = new as u32;
+        if self.degree_histogram.len() <= new {
+            self.degree_histogram.resize(new + 1, 0);
+        }
+        self.degree_histogram[old] -= 1;
+        self.degree_histogram[new] += 1;
+
+        let emptied = self.degree_histogram[old] == 0;
+        if up {
+            self.max_degree = self.max_degree.max(new);
+            if emptied && self.min_degree == old {
+                self.min_degree = new;
+            }
+        } else {
+            self.min_degree = self.min_degree.min(new);
+            if emptied && self.max_degree == old {
+                self.max_degree = new;
+            }
+        }
+    }
+}
+
 /// Dense matrix-based neural network connectivity
 /// 
 /// This implementation uses a dense adjacency matrix to represent connections
@@ -175,6 +251,9 @@ pub struct MatrixNetwork {
     index_to_neuron_id: Vec<NeuronId>,
     /// Next available index for new neurons
     next_index: usize,
+    /// Incrementally maintained statistics
+    #[cfg_attr(feature = "serde", serde(default))]
+    counters: EdgeCounters,
 }
 
 impl MatrixNetwork {
@@ -187,6 +266,7 @@ impl MatrixNetwork {
             neuron_id_to_index: vec![None; max_neurons],
             index_to_neuron_id: Vec::with_capacity(max_neurons),
             next_index: 0,
+            counters: EdgeCounters::default(),
         }
     }
     
@@ -199,6 +279,7 @@ impl MatrixNetwork {
             neuron_id_to_index: vec![None; max_neurons],
             index_to_neuron_id: Vec::with_capacity(max_neurons),
             next_index: 0,
+            counters: EdgeCounters::default(),
         }
     }
     
@@ -280,6 +361,7 @@ impl MatrixNetwork {
         self.index_to_neuron_id.push(neuron_id);
         self.next_index += 1;
         self.neuron_count += 1;
+        self.counters.add_node();
         
         Ok(index)
     }
@@ -308,11 +390,25 @@ impl MatrixNetwork {
         let target_idx = self.get_neuron_index(target)
             .ok_or(MatrixConnectivityError::InvalidNeuronId(target))?;
         
-        self.adjacency_matrix.set(source_idx, target_idx, weight)
-            .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
+        self.write_weight(source_idx, target_idx, weight)?;
         
         Ok(())
     }
+
+    /// Write one adjacency entry and update the statistics, returning the old weight
+    fn write_weight(&mut self, row: usize, col: usize, weight: f32) -> Result<f32> {
+        let old = self.adjacency_matrix.get(row, col)
+            .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
+        self.adjacency_matrix.set(row, col, weight)
+            .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
+        self.counters.record(row, col, old, weight);
+        Ok(old)
+    }
+
+    /// Number of neurons with each degree (incoming plus outgoing), indexed by degree
+    pub fn degree_histogram(&self) -> &[usize] {
+        &self.counters.degree_histogram
+    }
     
     /// Get the weight between two neurons
     pub fn get_weight(&self, source: NeuronId, target: NeuronId) -> Result<f32> {
@@ -464,6 +560,41 @@ impl NetworkConnectivity<NeuronId> for MatrixNetwork {
         
         Ok(routes)
     }
+
+    fn route_spikes_batch(
+        &self,
+        spikes: &[Spike],
+        current_time: Time,
+        buffer: &mut RouteBuffer,
+    ) -> Result<()> {
+        buffer.clear();
+        for spike in spikes {
+            let source_idx = self.get_neuron_index(spike.source)
+                .ok_or(MatrixConnectivityError::InvalidNeuronId(spike.source))?;
+
+            // Same row scan as route_spike, written straight into the buffer
+            for target_idx in 0..self.neuron_count {
+                let weight = self.adjacency_matrix.get(source_idx, target_idx)
+                    .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
+                if weight <= 0.0 {
+                    continue;
+                }
+                let target_neuron_id = self.get_neuron_id(target_idx)
+                    .ok_or(MatrixConnectivityError::IndexOutOfBounds {
+                        index: target_idx,
+                        bound: self.neuron_count,
+                    })?;
+                let delay_ns = match self.delay_matrix {
+                    Some(ref delay_matrix) => delay_matrix.get(source_idx, target_idx)
+                        .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))? as u64,
+                    None => 0,
+                };
+                let delivery_time = current_time + crate::time::Duration::from_nanos(delay_ns);
+                buffer.push(spike.source, target_neuron_id, weight * spike.amplitude, delivery_time);
+            }
+        }
+        Ok(())
+    }
     
     fn get_targets(&self, source: NeuronId) -> Result<Vec<NeuronId>> {
         let source_idx = self.get_neuron_index(source)
@@ -522,8 +653,7 @@ impl NetworkConnectivity<NeuronId> for MatrixNetwork {
             }.into());
         }
         
-        self.adjacency_matrix.set(connection.row, connection.col, 1.0)
-            .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
+        self.write_weight(connection.row, connection.col, 1.0)?;
         
         Ok(())
     }
@@ -540,8 +670,7 @@ impl NetworkConnectivity<NeuronId> for MatrixNetwork {
             .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
         
         if weight > 0.0 {
-            self.adjacency_matrix.set(connection.row, connection.col, 0.0)
-                .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
+            self.write_weight(connection.row, connection.col, 0.0)?;
             
             let route_info = MatrixRouteInfo {
                 source_index: connection.row,
@@ -565,48 +694,28 @@ impl NetworkConnectivity<NeuronId> for MatrixNetwork {
             return Ok(None);
         }
         
-        let old_weight = self.adjacency_matrix.get(connection.row, connection.col)
-            .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
-        
-        self.adjacency_matrix.set(connection.row, connection.col, new_weight)
-            .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
+        let old_weight = self.write_weight(connection.row, connection.col, new_weight)?;
         
         Ok(Some(old_weight))
     }
     
     fn get_stats(&self) -> ConnectivityStats {
-        let mut connection_count = 0;
-        let mut total_weight = 0.0;
-        let mut degrees = vec![0u32; self.neuron_count];
-        
-        // Count connections and calculate statistics
-        for i in 0..self.neuron_count {
-            for j in 0..self.neuron_count {
-                if let Ok(weight) = self.adjacency_matrix.get(i, j) {
-                    if weight > 0.0 {
-                        connection_count += 1;
-                        total_weight += weight;
-                        degrees[i] += 1; // Out-degree
-                        degrees[j] += 1; // In-degree (counted separately)
-                    }
-                }
-            }
-        }
+        // O(1): counters are maintained on every weight write
+        let connection_count = self.counters.connection_count;
+        let total_weight = self.counters.total_weight as f32;
         
         let average_weight = if connection_count > 0 {
-            total_weight / connection_count as f32
+            (self.counters.total_weight / connection_count as f64) as f32
         } else {
             0.0
         };
         
-        let (max_degree, min_degree) = if !degrees.is_empty() {
-            (*degrees.iter().max().unwrap_or(&0), *degrees.iter().min().unwrap_or(&0))
-        } else {
-            (0, 0)
-        };
+        let max_degree = self.counters.max_degree as u32;
+        let min_degree = self.counters.min_degree as u32;
         
+        // Each connection adds one out-degree and one in-degree
         let average_degree = if self.neuron_count > 0 {
-            degrees.iter().sum::<u32>() as f32 / self.neuron_count as f32
+            (2 * connection_count) as f32 / self.neuron_count as f32
         } else {
             0.0
         };
@@ -682,20 +791,11 @@ impl NetworkConnectivity<NeuronId> for MatrixNetwork {
         self.neuron_id_to_index.iter_mut().for_each(|entry| *entry = None);
         self.index_to_neuron_id.clear();
         self.next_index = 0;
+        self.counters = EdgeCounters::default();
     }
     
     fn connection_count(&self) -> usize {
-        let mut count = 0;
-        for i in 0..self.neuron_count {
-            for j in 0..self.neuron_count {
-                if let Ok(weight) = self.adjacency_matrix.get(i, j) {
-                    if weight > 0.0 {
-                        count += 1;
-                    }
-                }
-            }
-        }
-        count
+        self.counters.connection_count
     }
     
     fn neuron_count(&self) -> usize {
@@ -759,8 +859,7 @@ impl PlasticConnectivity<NeuronId> for MatrixNetwork {
         
         let new_weight = (current_weight + weight_delta).max(0.0).min(10.0); // Clamp weights
         
-        self.adjacency_matrix.set(source_idx, target_idx, new_weight)
-            .map_err(|e| MatrixConnectivityError::MatrixError(format!("{:?}", e)))?;
+        self.write_weight(source_idx, target_idx, new_weight)?;
         
         Ok(Some(new_weight))
     }
@@ -823,6 +922,87 @@ mod tests {
         assert_eq!(routes[0].weights, vec![0.8]);
     }
     
+    #[test]
+    fn test_batch_routing_matches_route_spike() {
+        let mut network = MatrixNetwork::with_delays(10);
+        for id in 0..3 {
+            network.add_neuron(NeuronId::new(id)).expect("Should add neuron");
+        }
+        network.set_weight(NeuronId::new(0), NeuronId::new(1), 0.8).expect("Should set weight");
+        network.set_weight(NeuronId::new(0), NeuronId::new(2), 0.4).expect("Should set weight");
+        network.set_weight(NeuronId::new(1), NeuronId::new(2), 0.5).expect("Should set weight");
+        network.set_delay(NeuronId::new(0), NeuronId::new(2), Time::from_millis(2)).expect("Should set delay");
+
+        let spikes = [
+            Spike::new(NeuronId::new(0), Time::from_millis(10), 1.0).expect("Should create spike"),
+            Spike::new(NeuronId::new(1), Time::from_millis(10), 0.5).expect("Should create spike"),
+        ];
+        let now = Time::from_millis(10);
+
+        let mut buffer = RouteBuffer::new();
+        buffer.push(NeuronId::new(9), NeuronId::new(9), 1.0, now);
+        network.route_spikes_batch(&spikes, now, &mut buffer).expect("Should route batch");
+
+        let mut expected = RouteBuffer::new();
+        for spike in &spikes {
+            for route in network.route_spike(spike, now).expect("Should route spike") {
+                expected.push_route(spike.source, &route);
+            }
+        }
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), expected.iter().collect::<Vec<_>>());
+        assert_eq!(buffer.route_count(), 3);
+        assert_eq!(buffer.get(1).map(|r| r.delivery_time), Some(Time::from_millis(12)));
+
+        let bogus = Spike::new(NeuronId::new(7), now, 1.0).expect("Should create spike");
+        assert!(network.route_spikes_batch(&[bogus], now, &mut buffer).is_err());
+    }
+    
+    #[test]
+    fn test_incremental_stats_match_full_scan() {
+        let neurons: Vec<NeuronId> = (0..4).map(NeuronId::new).collect();
+        let mut network = MatrixNetwork::new(4);
+        for &id in &neurons {
+            network.add_neuron(id).expect("Should add neuron");
+        }
+        network.set_weight(neurons[0], neurons[1], 0.5).expect("Should set weight");
+        network.set_weight(neurons[0], neurons[2], 0.25).expect("Should set weight");
+        network.set_weight(neurons[2], neurons[2], 1.0).expect("Should set weight");
+        network.set_weight(neurons[0], neurons[1], 0.75).expect("Should set weight");
+        network.apply_plasticity(neurons[0], neurons[2], -1.0).expect("Should apply plasticity");
+        network.update_weight(MatrixConnectionId { row: 3, col: 1 }, 2.0).expect("Should update weight");
+        network.remove_connection(MatrixConnectionId { row: 2, col: 2 }).expect("Should remove connection");
+        network.add_connection(MatrixConnectionId { row: 1, col: 3 }).expect("Should add connection");
+
+        // Recompute what the old O(N^2) scan produced
+        let mut count = 0;
+        let mut total = 0.0;
+        let mut degrees = vec![0u32; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                let w = network.get_weight(neurons[i], neurons[j]).expect("Should get weight");
+                if w > 0.0 {
+                    count += 1;
+                    total += w;
+                    degrees[i] += 1;
+                    degrees[j] += 1;
+                }
+            }
+        }
+
+        let stats = network.get_stats();
+        assert_eq!(network.connection_count(), count);
+        assert_eq!(stats.connection_count, 3);
+        assert!((stats.total_weight - total).abs() < 1e-6);
+        assert_eq!(stats.max_degree, *degrees.iter().max().unwrap());
+        assert_eq!(stats.min_degree, *degrees.iter().min().unwrap());
+        assert_eq!(stats.average_degree, degrees.iter().sum::<u32>() as f32 / 4.0);
+        assert_eq!(network.degree_histogram(), &[1, 1, 1, 1]);
+
+        network.reset();
+        assert_eq!(network.get_stats().connection_count, 0);
+        assert!(network.degree_histogram().is_empty());
+    }
+    
     #[test]
     fn test_matrix_connection_id() {
         let connection_id = MatrixConnectionId { row: 42, col: 84 };
@@ -14,15 +14,19 @@ use core::fmt;
 use serde::{Deserialize, Serialize};
 
 // Re-export connectivity implementations
+pub mod analysis;
 pub mod hypergraph;
 pub mod graph;
 pub mod matrix;
+pub mod partition;
 pub mod sparse;
 pub mod types;
 #[cfg(feature = "plastic-sum")]
 pub mod plastic_enum;
 
-pub use types::{SpikeRoute, ConnectivityStats, ConnectionId};
+pub use types::{SpikeRoute, ConnectivityStats, ConnectionId, RouteBuffer, RoutedSpike};
+pub use analysis::{DegreeDistribution, Topology, TopologySummary};
+pub use partition::{partition, PartitionConfig, PartitionMap};
 #[cfg(feature = "plastic-sum")]
 pub use plastic_enum::PlasticConn;
 
@@ -57,6 +61,33 @@ pub trait NetworkConnectivity<NodeId> {
         spike: &Spike, 
         current_time: Time
     ) -> Result<Vec<SpikeRoute>>;
+
+    /// Route a batch of spikes into a reusable buffer
+    ///
+    /// Clears `buffer`, then appends the routes of each spike in order, as
+    /// [`NetworkConnectivity::route_spike`] would produce them. The default
+    /// implementation calls `route_spike` per spike; backends that can write
+    /// deliveries directly (matrix, sparse) override it so a buffer reused
+    /// across steps avoids per-spike allocation.
+    ///
+    /// # Arguments
+    /// * `spikes` - Spikes to route
+    /// * `current_time` - Current simulation time
+    /// * `buffer` - Output buffer, cleared first
+    fn route_spikes_batch(
+        &self,
+        spikes: &[Spike],
+        current_time: Time,
+        buffer: &mut RouteBuffer,
+    ) -> Result<()> {
+        buffer.clear();
+        for spike in spikes {
+            for route in self.route_spike(spike, current_time)? {
+                buffer.push_route(spike.source, &route);
+            }
+        }
+        Ok(())
+    }
     
     /// Get all target neurons for a given source neuron
     ///
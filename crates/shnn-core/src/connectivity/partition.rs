@@ -0,0 +1,423 @@
+//! Balanced K-way partitioning of connectivity
+//!
+//! Splits a [`Topology`] into `K` partitions of near-equal size while keeping
+//! as few synapses as possible between partitions, so each worker thread or
+//! device handles mostly local traffic. The heuristic follows METIS in
+//! spirit, without the multilevel coarsening:
+//!
+//! 1. Graph growing: each partition is grown breadth-first from the lowest
+//!    unassigned neuron until it reaches its target size.
+//! 2. Refinement: boundary neurons move to the neighbouring partition with the
+//!    largest cut reduction while sizes stay within the imbalance bound.
+//!    Moves the bound refuses are paired with opposite refused moves and
+//!    exchanged Kernighan–Lin style when the swap still reduces the cut.
+//!
+//! Edge direction is ignored for the cut (a synapse in either direction
+//! costs the same traffic) and the result is deterministic.
+
+use crate::{
+    connectivity::analysis::Topology,
+    spike::NeuronId,
+    error::{SHNNError, Result},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Settings for [`partition`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PartitionConfig {
+    /// Number of partitions (K)
+    pub parts: usize,
+    /// Allowed relative excess of a partition over `N / K` during refinement
+    pub imbalance: f32,
+    /// Maximum refinement sweeps over all neurons
+    pub refinement_passes: usize,
+}
+
+impl PartitionConfig {
+    /// `parts` partitions with 5% imbalance and up to 8 refinement sweeps
+    pub fn new(parts: usize) -> Self {
+        Self { parts, imbalance: 0.05, refinement_passes: 8 }
+    }
+
+    /// Set the allowed imbalance
+    pub fn with_imbalance(mut self, imbalance: f32) -> Self {
+        self.imbalance = imbalance;
+        self
+    }
+
+    /// Set the maximum number of refinement sweeps (0 keeps the grown partitions)
+    pub fn with_refinement_passes(mut self, passes: usize) -> Self {
+        self.refinement_passes = passes;
+        self
+    }
+}
+
+impl Default for PartitionConfig {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+/// Neuron to partition assignment produced by [`partition`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PartitionMap {
+    parts: usize,
+    /// Neurons in ascending id order
+    nodes: Vec<NeuronId>,
+    /// Partition of `nodes[i]`
+    assignment: Vec<usize>,
+}
+
+impl PartitionMap {
+    /// Number of partitions
+    pub fn parts(&self) -> usize {
+        self.parts
+    }
+
+    /// Number of assigned neurons
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// True if no neurons are assigned
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Partition of `neuron`, if it is part of the map
+    pub fn partition_of(&self, neuron: NeuronId) -> Option<usize> {
+        let index = self.nodes.binary_search(&neuron).ok()?;
+        Some(self.assignment[index])
+    }
+
+    /// `(neuron, partition)` pairs in ascending neuron order
+    pub fn iter(&self) -> impl Iterator<Item = (NeuronId, usize)> + '_ {
+        self.nodes.iter().copied().zip(self.assignment.iter().copied())
+    }
+
+    /// Neurons of partition `part` in ascending order
+    pub fn members(&self, part: usize) -> Vec<NeuronId> {
+        self.iter().filter(|&(_, p)| p == part).map(|(n, _)| n).collect()
+    }
+
+    /// Number of neurons in each partition
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.parts];
+        for &p in &self.assignment {
+            sizes[p] += 1;
+        }
+        sizes
+    }
+
+    /// Largest partition relative to the ideal `N / K`, minus one
+    pub fn imbalance(&self) -> f32 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        let ideal = self.nodes.len() as f32 / self.parts as f32;
+        let largest = self.sizes().into_iter().max().unwrap_or(0);
+        largest as f32 / ideal - 1.0
+    }
+
+    /// Directed edges of `topology` whose ends lie in different partitions
+    ///
+    /// Neurons missing from the map are ignored.
+    pub fn edge_cut(&self, topology: &Topology) -> usize {
+        let nodes = topology.nodes();
+        let part: Vec<Option<usize>> = nodes.iter().map(|&n| self.partition_of(n)).collect();
+        (0..nodes.len())
+            .flat_map(|u| topology.successors(u).iter().map(move |&v| (u, v)))
+            .filter(|&(u, v)| matches!((part[u], part[v]), (Some(a), Some(b)) if a != b))
+            .count()
+    }
+}
+
+/// Split `topology` into `config.parts` balanced partitions with few cross edges
+///
+/// Fails if `config.parts` is 0. With more partitions than neurons the extra
+/// partitions stay empty.
+pub fn partition(topology: &Topology, config: &PartitionConfig) -> Result<PartitionMap> {
+    if config.parts == 0 {
+        return Err(SHNNError::generic("partition count must be at least 1"));
+    }
+    if config.imbalance.is_nan() || config.imbalance < 0.0 {
+        return Err(SHNNError::generic("partition imbalance must be non-negative"));
+    }
+
+    let n = topology.node_count();
+    let k = config.parts;
+    let neighbours = undirected_neighbours(topology);
+    let mut assignment = grow(&neighbours, k);
+
+    if k > 1 && n > 0 {
+        let ideal = n as f32 / k as f32;
+        let bounds = SizeBounds {
+            // Never tighter than the sizes growing produced
+            max: ((ideal * (1.0 + config.imbalance)).floor() as usize).max(n.div_ceil(k)),
+            min: ((ideal * (1.0 - config.imbalance)).ceil() as usize).min(n / k),
+        };
+        refine(&neighbours, &mut assignment, k, bounds, config.refinement_passes);
+    }
+
+    Ok(PartitionMap {
+        parts: k,
+        nodes: topology.nodes().to_vec(),
+        assignment,
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SizeBounds {
+    max: usize,
+    min: usize,
+}
+
+/// Neighbours of each node ignoring direction, with the number of edges
+/// (0, 1 or 2) between the pair; self-loops are dropped
+fn undirected_neighbours(topology: &Topology) -> Vec<Vec<(usize, u32)>> {
+    let mut neighbours: Vec<Vec<(usize, u32)>> = vec![Vec::new(); topology.node_count()];
+    for u in 0..topology.node_count() {
+        for &v in topology.successors(u) {
+            if u != v {
+                neighbours[u].push((v, 1));
+                neighbours[v].push((u, 1));
+            }
+        }
+    }
+    for list in &mut neighbours {
+        list.sort_unstable_by_key(|&(v, _)| v);
+        list.dedup_by(|next, kept| {
+            let same = next.0 == kept.0;
+            if same {
+                kept.1 += next.1;
+            }
+            same
+        });
+    }
+    neighbours
+}
+
+/// Breadth-first graph growing into `k` partitions of `N / K` (+1 for the first `N % K`)
+fn grow(neighbours: &[Vec<(usize, u32)>], k: usize) -> Vec<usize> {
+    const UNASSIGNED: usize = usize::MAX;
+    let n = neighbours.len();
+    let mut assignment = vec![UNASSIGNED; n];
+    let mut queue: Vec<usize> = Vec::with_capacity(n);
+    let mut next_seed = 0;
+
+    for part in 0..k {
+        let target = n / k + usize::from(part < n % k);
+        let mut size = 0;
+        queue.clear();
+        let mut head = 0;
+        while size < target {
+            if head == queue.len() {
+                // Frontier exhausted (or first node): restart from the lowest unassigned neuron
+                while next_seed < n && assignment[next_seed] != UNASSIGNED {
+                    next_seed += 1;
+                }
+                if next_seed == n {
+                    break;
+                }
+                assignment[next_seed] = part;
+                size += 1;
+                queue.push(next_seed);
+                continue;
+            }
+            let node = queue[head];
+            head += 1;
+            for &(v, _) in &neighbours[node] {
+                if size == target {
+                    break;
+                }
+                if assignment[v] == UNASSIGNED {
+                    assignment[v] = part;
+                    size += 1;
+                    queue.push(v);
+                }
+            }
+        }
+    }
+    assignment
+}
+
+/// Greedy boundary refinement, then Kernighan–Lin swaps for moves the size bounds blocked
+fn refine(
+    neighbours: &[Vec<(usize, u32)>],
+    assignment: &mut [usize],
+    k: usize,
+    bounds: SizeBounds,
+    passes: usize,
+) {
+    let mut sizes = vec![0usize; k];
+    for &p in assignment.iter() {
+        sizes[p] += 1;
+    }
+    let mut links = vec![0i64; k];
+    // (gain, node, from, to) of improving moves refused by the size bounds
+    let mut blocked: Vec<(i64, usize, usize, usize)> = Vec::new();
+
+    for _ in 0..passes {
+        let mut improved = false;
+        blocked.clear();
+        for u in 0..neighbours.len() {
+            let own = assignment[u];
+            let Some((target, gain)) = best_move(&neighbours[u], assignment, own, &mut links) else { continue };
+            if sizes[target] < bounds.max && sizes[own] > bounds.min {
+                assignment[u] = target;
+                sizes[own] -= 1;
+                sizes[target] += 1;
+                improved = true;
+            } else {
+                blocked.push((gain, u, own, target));
+            }
+        }
+
+        // Pair opposite blocked moves; gains are recomputed since earlier moves changed them
+        blocked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for i in 0..blocked.len() {
+            let (_, u, from, to) = blocked[i];
+            if assignment[u] != from {
+                continue;
+            }
+            tally(&neighbours[u], assignment, &mut links);
+            let gain_u = links[to] - links[from];
+            let partner = blocked[i + 1..].iter()
+                .filter(|&&(_, v, vf, vt)| vf == to && vt == from && assignment[v] == to)
+                .map(|&(_, v, _, _)| {
+                    tally(&neighbours[v], assignment, &mut links);
+                    // Edges between u and v stay cut after the exchange
+                    let gain = gain_u + links[from] - links[to] - 2 * edge_weight(&neighbours[u], v);
+                    (gain, v)
+                })
+                .find(|&(gain, _)| gain > 0);
+            if let Some((_, v)) = partner {
+                assignment[u] = to;
+                assignment[v] = from;
+                improved = true;
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+}
+
+/// Neighbouring partition whose adoption of the node reduces the cut most
+/// (lowest partition on ties)
+fn best_move(neighbours: &[(usize, u32)], assignment: &[usize], own: usize, links: &mut [i64]) -> Option<(usize, i64)> {
+    tally(neighbours, assignment, links);
+    let internal = links[own];
+    let mut best: Option<(usize, i64)> = None;
+    for &(v, _) in neighbours {
+        let q = assignment[v];
+        let gain = links[q] - internal;
+        if q != own && gain > 0 && best.map_or(true, |(bq, bg)| gain > bg || (gain == bg && q < bq)) {
+            best = Some((q, gain));
+        }
+    }
+    best
+}
+
+/// Edges between a node (given its neighbour list) and `v`
+fn edge_weight(neighbours: &[(usize, u32)], v: usize) -> i64 {
+    neighbours.binary_search_by_key(&v, |&(w, _)| w)
+        .map_or(0, |i| neighbours[i].1 as i64)
+}
+
+/// Edges from a node to each partition
+fn tally(neighbours: &[(usize, u32)], assignment: &[usize], links: &mut [i64]) {
+    links.iter_mut().for_each(|l| *l = 0);
+    for &(v, weight) in neighbours {
+        links[assignment[v]] += weight as i64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topology(edges: &[(u32, u32)]) -> Topology {
+        Topology::from_edges(
+            Vec::new(),
+            edges.iter().map(|&(s, t)| (NeuronId::new(s), NeuronId::new(t))),
+        )
+    }
+
+    /// Two dense 4-cliques joined by a single bridge
+    fn two_clusters() -> Topology {
+        let mut edges = Vec::new();
+        for base in [0, 4] {
+            for i in base..base + 4 {
+                for j in base..base + 4 {
+                    if i != j {
+                        edges.push((i, j));
+                    }
+                }
+            }
+        }
+        edges.push((3, 4));
+        topology(&edges)
+    }
+
+    #[test]
+    fn test_clusters_are_separated() {
+        let topology = two_clusters();
+        let map = partition(&topology, &PartitionConfig::new(2)).expect("Should partition");
+        assert_eq!(map.sizes(), vec![4, 4]);
+        assert_eq!(map.edge_cut(&topology), 1);
+        assert_eq!(map.members(0), (0..4).map(NeuronId::new).collect::<Vec<_>>());
+        assert_eq!(map.imbalance(), 0.0);
+    }
+
+    #[test]
+    fn test_refinement_repairs_bad_growth() {
+        // Interleaved clusters {0,2,4,6} and {1,3,5,7}: growing from 0 first
+        // pulls in whole neighbourhoods, refinement must swap the rest back
+        let mut edges = Vec::new();
+        for parity in 0..2u32 {
+            let members: Vec<u32> = (0..8).filter(|i| i % 2 == parity).collect();
+            for &i in &members {
+                for &j in &members {
+                    if i != j {
+                        edges.push((i, j));
+                    }
+                }
+            }
+        }
+        edges.push((0, 1));
+        let topology = topology(&edges);
+
+        let unrefined = partition(&topology, &PartitionConfig::new(2).with_refinement_passes(0))
+            .expect("Should partition");
+        let refined = partition(&topology, &PartitionConfig::new(2)).expect("Should partition");
+        assert!(refined.edge_cut(&topology) <= unrefined.edge_cut(&topology));
+        assert_eq!(refined.edge_cut(&topology), 1);
+        assert_eq!(refined.sizes(), vec![4, 4]);
+    }
+
+    #[test]
+    fn test_balance_and_degenerate_inputs() {
+        // Ring of 10 into 3 parts: sizes 4/3/3, contiguous arcs cut 3 edges
+        let ring: Vec<(u32, u32)> = (0..10).map(|i| (i, (i + 1) % 10)).collect();
+        let topology = topology(&ring);
+        let map = partition(&topology, &PartitionConfig::new(3)).expect("Should partition");
+        assert_eq!(map.sizes().iter().sum::<usize>(), 10);
+        assert!(map.sizes().iter().all(|&s| (3..=4).contains(&s)));
+        assert_eq!(map.edge_cut(&topology), 3);
+        assert_eq!(map.len(), 10);
+        assert!(map.iter().all(|(n, p)| map.partition_of(n) == Some(p)));
+
+        // More parts than neurons leaves the extra parts empty
+        let map = partition(&topology, &PartitionConfig::new(12)).expect("Should partition");
+        assert_eq!(map.sizes().iter().filter(|&&s| s == 1).count(), 10);
+
+        assert!(partition(&topology, &PartitionConfig::new(0)).is_err());
+        assert!(partition(&Topology::default(), &PartitionConfig::new(2)).expect("Should partition").is_empty());
+    }
+}
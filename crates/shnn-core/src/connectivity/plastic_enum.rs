@@ -6,7 +6,7 @@
 
 use crate::{
     connectivity::{
-        types::{ConnectivityStats, SpikeRoute, ConnectionId},
+        types::{ConnectivityStats, SpikeRoute, ConnectionId, RouteBuffer},
         graph::GraphNetwork,
         matrix::MatrixNetwork,
         sparse::SparseMatrixNetwork,
@@ -109,6 +109,14 @@ impl NetworkConnectivity<NeuronId> for PlasticConn {
         }
     }
 
+    fn route_spikes_batch(&self, spikes: &[Spike], current_time: Time, buffer: &mut RouteBuffer) -> Result<()> {
+        match self {
+            Self::Graph(g) => g.route_spikes_batch(spikes, current_time, buffer),
+            Self::Matrix(m) => m.route_spikes_batch(spikes, current_time, buffer),
+            Self::Sparse(s) => s.route_spikes_batch(spikes, current_time, buffer),
+        }
+    }
+
     fn get_targets(&self, source: NeuronId) -> Result<Vec<NeuronId>> {
         match self {
             Self::Graph(g) => g.get_targets(source),
@@ -6,7 +6,8 @@
 use crate::{
     connectivity::{
         NetworkConnectivity, BatchConnectivity, PlasticConnectivity,
-        types::{SpikeRoute, ConnectivityStats, ConnectivityError, ConnectionId},
+        analysis::Topology,
+        types::{SpikeRoute, ConnectivityStats, ConnectivityError, ConnectionId, RouteBuffer},
     },
     spike::{NeuronId, Spike},
     time::Time,
@@ -399,6 +400,20 @@ impl SparseMatrixNetwork {
         
         Ok(connections)
     }
+
+    /// All neurons in index order
+    pub fn neurons(&self) -> impl Iterator<Item = NeuronId> + '_ {
+        self.index_to_neuron_id[..self.next_index].iter().flatten().copied()
+    }
+
+    /// All stored `(source, target)` connections
+    pub fn edge_pairs(&self) -> impl Iterator<Item = (NeuronId, NeuronId)> + '_ {
+        (0..self.next_index).flat_map(move |row| {
+            self.adjacency_matrix.row_iter(row).filter_map(move |(col, _)| {
+                Some((self.get_neuron_id(row)?, self.get_neuron_id(col)?))
+            })
+        })
+    }
 }
 
 impl Default for SparseMatrixNetwork {
@@ -485,6 +500,34 @@ impl NetworkConnectivity<NeuronId> for SparseMatrixNetwork {
         
         Ok(routes)
     }
+
+    fn route_spikes_batch(
+        &self,
+        spikes: &[Spike],
+        current_time: Time,
+        buffer: &mut RouteBuffer,
+    ) -> Result<()> {
+        buffer.clear();
+        for spike in spikes {
+            let source_idx = self.get_neuron_index(spike.source)
+                .ok_or(SparseConnectivityError::InvalidNeuronId(spike.source))?;
+
+            // Same row walk as route_spike, written straight into the buffer
+            for (target_idx, weight) in self.adjacency_matrix.row_iter(source_idx) {
+                let target_neuron_id = self.get_neuron_id(target_idx)
+                    .ok_or(SparseConnectivityError::IndexOutOfBounds {
+                        index: target_idx,
+                        bound: self.max_neurons,
+                    })?;
+                let delay = self.delays.get(&(source_idx, target_idx))
+                    .copied()
+                    .unwrap_or(Time::ZERO);
+                let delivery_time = current_time + crate::time::Duration::from_nanos(delay.as_nanos());
+                buffer.push(spike.source, target_neuron_id, (weight as f32) * spike.amplitude, delivery_time);
+            }
+        }
+        Ok(())
+    }
     
     fn get_targets(&self, source: NeuronId) -> Result<Vec<NeuronId>> {
         let connections = self.get_outgoing_connections(source)?;
@@ -621,7 +664,7 @@ impl NetworkConnectivity<NeuronId> for SparseMatrixNetwork {
             total_weight,
             average_weight,
             memory_usage,
-            has_cycles: None, // Would require cycle detection
+            has_cycles: Some(Topology::from_sparse(self).has_cycles()),
             density,
             custom_stats: vec![
                 ("sparsity".to_string(), self.sparsity()),
@@ -805,6 +848,41 @@ mod tests {
         assert_eq!(routes[0].weights, vec![0.8]);
     }
     
+    #[test]
+    fn test_sparse_batch_routing_matches_route_spike() {
+        let mut network = SparseMatrixNetwork::new(10);
+        for id in 0..3 {
+            network.add_neuron(NeuronId::new(id)).expect("Should add neuron");
+        }
+        network.set_weight(NeuronId::new(0), NeuronId::new(1), 0.8).expect("Should set weight");
+        network.set_weight(NeuronId::new(0), NeuronId::new(2), 0.4).expect("Should set weight");
+        network.set_weight(NeuronId::new(1), NeuronId::new(2), 0.5).expect("Should set weight");
+        network.set_delay(NeuronId::new(0), NeuronId::new(2), Time::from_millis(2)).expect("Should set delay");
+
+        let spikes = [
+            Spike::new(NeuronId::new(0), Time::from_millis(10), 1.0).expect("Should create spike"),
+            Spike::new(NeuronId::new(1), Time::from_millis(10), 0.5).expect("Should create spike"),
+        ];
+        let now = Time::from_millis(10);
+
+        let mut buffer = RouteBuffer::new();
+        buffer.push(NeuronId::new(9), NeuronId::new(9), 1.0, now);
+        network.route_spikes_batch(&spikes, now, &mut buffer).expect("Should route batch");
+
+        let mut expected = RouteBuffer::new();
+        for spike in &spikes {
+            for route in network.route_spike(spike, now).expect("Should route spike") {
+                expected.push_route(spike.source, &route);
+            }
+        }
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), expected.iter().collect::<Vec<_>>());
+        assert_eq!(buffer.route_count(), 3);
+        assert_eq!(buffer.get(1).map(|r| r.delivery_time), Some(Time::from_millis(12)));
+
+        let bogus = Spike::new(NeuronId::new(7), now, 1.0).expect("Should create spike");
+        assert!(network.route_spikes_batch(&[bogus], now, &mut buffer).is_err());
+    }
+    
     #[test]
     fn test_sparse_connection_id() {
         let connection_id = SparseConnectionId { row: 42, col: 84 };
@@ -133,6 +133,141 @@ impl SpikeRoute {
     }
 }
 
+/// One routed delivery in a [`RouteBuffer`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoutedSpike {
+    /// Neuron whose spike produced this delivery
+    pub source: NeuronId,
+    /// Receiving neuron
+    pub target: NeuronId,
+    /// Delivered weight (already scaled by spike amplitude)
+    pub weight: f32,
+    /// When the spike reaches `target`
+    pub delivery_time: Time,
+}
+
+/// Flat, reusable output of batch spike routing
+///
+/// Deliveries are stored as parallel vectors instead of one [`SpikeRoute`]
+/// (with its own target/weight vectors) per connection, so routing a batch
+/// into a buffer that is reused across steps allocates only while the
+/// buffer grows. Route boundaries are kept so multi-target routes (hyperedges)
+/// can still be processed as a group.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteBuffer {
+    sources: Vec<NeuronId>,
+    targets: Vec<NeuronId>,
+    weights: Vec<f32>,
+    delivery_times: Vec<Time>,
+    /// Exclusive end index of each route
+    route_ends: Vec<usize>,
+}
+
+impl RouteBuffer {
+    /// Empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty buffer with room for `deliveries` entries
+    pub fn with_capacity(deliveries: usize) -> Self {
+        Self {
+            sources: Vec::with_capacity(deliveries),
+            targets: Vec::with_capacity(deliveries),
+            weights: Vec::with_capacity(deliveries),
+            delivery_times: Vec::with_capacity(deliveries),
+            route_ends: Vec::with_capacity(deliveries),
+        }
+    }
+
+    /// Remove all deliveries, keeping the allocated capacity
+    pub fn clear(&mut self) {
+        self.sources.clear();
+        self.targets.clear();
+        self.weights.clear();
+        self.delivery_times.clear();
+        self.route_ends.clear();
+    }
+
+    /// Number of deliveries
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// True if no deliveries are buffered
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Number of routes
+    pub fn route_count(&self) -> usize {
+        self.route_ends.len()
+    }
+
+    /// Deliveries the buffer can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.targets.capacity()
+    }
+
+    /// Append a single-target route
+    pub fn push(&mut self, source: NeuronId, target: NeuronId, weight: f32, delivery_time: Time) {
+        self.push_delivery(source, target, weight, delivery_time);
+        self.route_ends.push(self.targets.len());
+    }
+
+    /// Append every target of `route` as one route
+    pub fn push_route(&mut self, source: NeuronId, route: &SpikeRoute) {
+        for (i, (&target, &weight)) in route.targets.iter().zip(&route.weights).enumerate() {
+            let delivery_time = route.target_delivery_time(i).unwrap_or(route.delivery_time);
+            self.push_delivery(source, target, weight, delivery_time);
+        }
+        self.route_ends.push(self.targets.len());
+    }
+
+    fn push_delivery(&mut self, source: NeuronId, target: NeuronId, weight: f32, delivery_time: Time) {
+        self.sources.push(source);
+        self.targets.push(target);
+        self.weights.push(weight);
+        self.delivery_times.push(delivery_time);
+    }
+
+    /// Delivery at `index`
+    pub fn get(&self, index: usize) -> Option<RoutedSpike> {
+        Some(RoutedSpike {
+            source: *self.sources.get(index)?,
+            target: self.targets[index],
+            weight: self.weights[index],
+            delivery_time: self.delivery_times[index],
+        })
+    }
+
+    /// All deliveries in routing order
+    pub fn iter(&self) -> impl Iterator<Item = RoutedSpike> + '_ {
+        (0..self.len()).filter_map(move |i| self.get(i))
+    }
+
+    /// Index range of each route in routing order
+    pub fn routes(&self) -> impl Iterator<Item = core::ops::Range<usize>> + '_ {
+        let starts = core::iter::once(0).chain(self.route_ends.iter().copied());
+        starts.zip(self.route_ends.iter().copied()).map(|(start, end)| start..end)
+    }
+
+    /// Receiving neurons, parallel to [`RouteBuffer::weights`]
+    pub fn targets(&self) -> &[NeuronId] {
+        &self.targets
+    }
+
+    /// Delivered weights
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Delivery times
+    pub fn delivery_times(&self) -> &[Time] {
+        &self.delivery_times
+    }
+}
+
 /// Metadata about a spike route
 #[derive(Debug, Clone, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -409,6 +544,37 @@ mod tests {
         );
     }
     
+    #[test]
+    fn test_route_buffer_keeps_routes_and_capacity() {
+        let source = NeuronId::new(0);
+        let hyperedge = SpikeRoute::with_delays(
+            vec![1],
+            vec![NeuronId::new(2), NeuronId::new(3)],
+            vec![0.5, 0.25],
+            Time::from_millis(10),
+            vec![Time::ZERO, Time::from_millis(5)],
+        ).unwrap();
+
+        let mut buffer = RouteBuffer::new();
+        buffer.push(source, NeuronId::new(1), 1.0, Time::from_millis(10));
+        buffer.push_route(source, &hyperedge);
+        assert_eq!((buffer.len(), buffer.route_count()), (3, 2));
+        assert_eq!(buffer.routes().collect::<Vec<_>>(), vec![0..1, 1..3]);
+        assert_eq!(buffer.targets(), &[NeuronId::new(1), NeuronId::new(2), NeuronId::new(3)]);
+        assert_eq!(buffer.get(2), Some(RoutedSpike {
+            source,
+            target: NeuronId::new(3),
+            weight: 0.25,
+            delivery_time: Time::from_millis(15),
+        }));
+        assert_eq!(buffer.get(3), None);
+
+        let capacity = buffer.capacity();
+        buffer.clear();
+        assert!(buffer.is_empty() && buffer.routes().next().is_none());
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
     #[test]
     fn test_connectivity_stats() {
         let mut stats = ConnectivityStats::new();
@@ -296,6 +296,12 @@ impl From<crate::connectivity::hypergraph::HypergraphConnectivityError> for SHNN
     }
 }
 
+impl From<shnn_types::TypesError> for SHNNError {
+    fn from(err: shnn_types::TypesError) -> Self {
+        SHNNError::time_error(err.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,447 @@
+//! Seeded random topology generators
+//!
+//! Erdős–Rényi, Watts–Strogatz small-world, Barabási–Albert scale-free and
+//! distance-dependent Gaussian wiring. Each generator returns a
+//! [`GeneratedTopology`]: a sorted directed edge list over neurons
+//! `first_id..first_id + neurons` that populates a [`GraphNetwork`],
+//! [`SparseMatrixNetwork`] or [`MatrixNetwork`], snapshots into a
+//! [`Topology`], or (with the `nir` feature) lowers to
+//! `connectivity.synapse_connect@v1` ops. The same seed always yields the
+//! same edges and weights; self-loops are never generated.
+
+use crate::{
+    connectivity::{
+        analysis::Topology,
+        graph::{GraphEdge, GraphNetwork},
+        matrix::MatrixNetwork,
+        sparse::SparseMatrixNetwork,
+    },
+    error::{Result, SHNNError},
+    spike::NeuronId,
+    time::Time,
+};
+
+/// Stream offset that decorrelates weight draws from edge draws
+const WEIGHT_STREAM: u64 = 0xA076_1D64_78BD_642F;
+
+/// SplitMix64 stream; small, fast and stable across platforms
+#[derive(Debug, Clone)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in `[0, bound)`
+    fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+/// Synaptic weight assignment for generated edges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightInit {
+    /// Every edge gets the same weight
+    Constant(f32),
+    /// Weights uniform in `[low, high)`
+    Uniform {
+        /// Lower bound (inclusive)
+        low: f32,
+        /// Upper bound (exclusive)
+        high: f32,
+    },
+}
+
+impl Default for WeightInit {
+    fn default() -> Self {
+        WeightInit::Constant(1.0)
+    }
+}
+
+/// Directed edges produced by a generator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedTopology {
+    first_id: u32,
+    neurons: u32,
+    /// Neuron-local `(source, target)` indices, sorted and distinct
+    edges: Vec<(u32, u32)>,
+    seed: u64,
+}
+
+impl GeneratedTopology {
+    fn new(neurons: u32, mut edges: Vec<(u32, u32)>, seed: u64) -> Self {
+        edges.sort_unstable();
+        edges.dedup();
+        Self { first_id: 0, neurons, edges, seed }
+    }
+
+    /// Shift neuron ids so they start at `first_id`
+    pub fn with_first_id(mut self, first_id: u32) -> Result<Self> {
+        if first_id.checked_add(self.neurons).is_none() {
+            return Err(SHNNError::generic("generated neuron ids overflow u32"));
+        }
+        self.first_id = first_id;
+        Ok(self)
+    }
+
+    /// Number of neurons
+    pub fn neuron_count(&self) -> usize {
+        self.neurons as usize
+    }
+
+    /// Number of directed edges
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Seed the edges were drawn with (also seeds weight draws)
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Neuron ids in ascending order
+    pub fn neurons(&self) -> impl Iterator<Item = NeuronId> + '_ {
+        (0..self.neurons).map(move |i| NeuronId::new(self.first_id + i))
+    }
+
+    /// Directed `(source, target)` edges, sorted by source then target
+    pub fn edges(&self) -> impl Iterator<Item = (NeuronId, NeuronId)> + '_ {
+        self.edges.iter().map(move |&(s, t)| {
+            (NeuronId::new(self.first_id + s), NeuronId::new(self.first_id + t))
+        })
+    }
+
+    /// One weight per edge, in [`GeneratedTopology::edges`] order
+    pub fn weights(&self, init: WeightInit) -> Vec<f32> {
+        match init {
+            WeightInit::Constant(weight) => vec![weight; self.edges.len()],
+            WeightInit::Uniform { low, high } => {
+                let mut rng = SplitMix64::new(self.seed ^ WEIGHT_STREAM);
+                (0..self.edges.len())
+                    .map(|_| low + (high - low) * rng.uniform() as f32)
+                    .collect()
+            }
+        }
+    }
+
+    /// Snapshot for [`analysis`](crate::connectivity::analysis)
+    pub fn to_topology(&self) -> Topology {
+        Topology::from_edges(self.neurons(), self.edges())
+    }
+
+    /// Populate a [`GraphNetwork`] with a uniform synaptic `delay`
+    pub fn to_graph(&self, init: WeightInit, delay: Time) -> Result<GraphNetwork> {
+        let mut network = GraphNetwork::with_capacity(self.edges.len(), self.neuron_count());
+        for ((source, target), weight) in self.edges().zip(self.weights(init)) {
+            network.add_edge(GraphEdge::with_delay(source, target, weight, delay))?;
+        }
+        Ok(network)
+    }
+
+    /// Populate a [`SparseMatrixNetwork`] (weights only)
+    pub fn to_sparse(&self, init: WeightInit) -> Result<SparseMatrixNetwork> {
+        let mut network = SparseMatrixNetwork::with_capacity(self.neuron_count(), self.edges.len());
+        for neuron in self.neurons() {
+            network.add_neuron(neuron)?;
+        }
+        for ((source, target), weight) in self.edges().zip(self.weights(init)) {
+            network.set_weight(source, target, weight)?;
+        }
+        Ok(network)
+    }
+
+    /// Populate a dense [`MatrixNetwork`] (weights only)
+    pub fn to_matrix(&self, init: WeightInit) -> Result<MatrixNetwork> {
+        let mut network = MatrixNetwork::new(self.neuron_count());
+        for neuron in self.neurons() {
+            network.add_neuron(neuron)?;
+        }
+        for ((source, target), weight) in self.edges().zip(self.weights(init)) {
+            network.set_weight(source, target, weight)?;
+        }
+        Ok(network)
+    }
+
+    /// One `connectivity.synapse_connect@v1` op per edge
+    #[cfg(feature = "nir")]
+    pub fn to_nir_ops(&self, init: WeightInit, delay_ms: f32) -> Vec<shnn_ir::Operation> {
+        self.edges()
+            .zip(self.weights(init))
+            .map(|((source, target), weight)| {
+                shnn_ir::synapse_connect_v1(source.raw(), target.raw(), weight, delay_ms)
+            })
+            .collect()
+    }
+}
+
+/// Erdős–Rényi G(n, p): each ordered pair `i != j` is connected with probability `p`
+///
+/// Samples the gaps between edges geometrically, so the cost is linear in
+/// the number of edges rather than `n²`.
+pub fn erdos_renyi(neurons: u32, p: f64, seed: u64) -> Result<GeneratedTopology> {
+    if !(0.0..=1.0).contains(&p) {
+        return Err(SHNNError::generic("connection probability must be in [0, 1]"));
+    }
+    let n = neurons as u64;
+    let mut edges = Vec::new();
+    if p >= 1.0 {
+        for s in 0..neurons {
+            edges.extend((0..neurons).filter(|&t| t != s).map(|t| (s, t)));
+        }
+    } else if p > 0.0 {
+        let mut rng = SplitMix64::new(seed);
+        let log_q = libm::log(1.0 - p);
+        let slots = n * n;
+        let mut slot = 0u64;
+        loop {
+            // Failures before the next success, with the uniform in (0, 1]
+            let skip = libm::floor(libm::log(1.0 - rng.uniform()) / log_q);
+            if skip >= (slots - slot) as f64 {
+                break;
+            }
+            slot += skip as u64;
+            let (s, t) = ((slot / n) as u32, (slot % n) as u32);
+            if s != t {
+                edges.push((s, t));
+            }
+            slot += 1;
+        }
+    }
+    Ok(GeneratedTopology::new(neurons, edges, seed))
+}
+
+/// Watts–Strogatz small world: a ring lattice rewired with probability `beta`
+///
+/// Every neuron projects to its `k` nearest ring neighbours (`k / 2` on each
+/// side); each projection is then redirected with probability `beta` to a
+/// uniformly chosen neuron it does not already target. Out-degree stays `k`.
+pub fn watts_strogatz(neurons: u32, k: u32, beta: f64, seed: u64) -> Result<GeneratedTopology> {
+    if k % 2 != 0 || k >= neurons.saturating_sub(1) {
+        return Err(SHNNError::generic("ring degree k must be even and below neurons - 1"));
+    }
+    if !(0.0..=1.0).contains(&beta) {
+        return Err(SHNNError::generic("rewiring probability must be in [0, 1]"));
+    }
+    let mut rng = SplitMix64::new(seed);
+    let mut edges = Vec::with_capacity(neurons as usize * k as usize);
+    let mut targets = Vec::with_capacity(k as usize);
+    for s in 0..neurons {
+        targets.clear();
+        for d in 1..=k / 2 {
+            targets.push((s + d) % neurons);
+            targets.push((s + neurons - d) % neurons);
+        }
+        for slot in 0..targets.len() {
+            if rng.uniform() >= beta {
+                continue;
+            }
+            // k < neurons - 1 leaves at least one free target
+            loop {
+                let candidate = rng.below(neurons as u64) as u32;
+                if candidate != s && !targets.contains(&candidate) {
+                    targets[slot] = candidate;
+                    break;
+                }
+            }
+        }
+        edges.extend(targets.iter().map(|&t| (s, t)));
+    }
+    Ok(GeneratedTopology::new(neurons, edges, seed))
+}
+
+/// Barabási–Albert scale free: preferential attachment with `m` edges per new neuron
+///
+/// Neurons `0..m` form the seed set; neuron `m` connects to all of them and
+/// every later neuron connects to `m` distinct earlier neurons chosen with
+/// probability proportional to their degree. Edges point from the newcomer to
+/// the existing neuron, so in-degree follows the power law.
+pub fn barabasi_albert(neurons: u32, m: u32, seed: u64) -> Result<GeneratedTopology> {
+    if m == 0 || m >= neurons {
+        return Err(SHNNError::generic("attachment count m must be in [1, neurons)"));
+    }
+    let mut rng = SplitMix64::new(seed);
+    let new_edges = (neurons - m) as usize * m as usize;
+    let mut edges = Vec::with_capacity(new_edges);
+    // Each neuron appears once per incident edge
+    let mut pool: Vec<u32> = Vec::with_capacity(2 * new_edges);
+    let mut chosen = Vec::with_capacity(m as usize);
+    for s in m..neurons {
+        chosen.clear();
+        if s == m {
+            chosen.extend(0..m);
+        } else {
+            while chosen.len() < m as usize {
+                let candidate = pool[rng.below(pool.len() as u64) as usize];
+                if !chosen.contains(&candidate) {
+                    chosen.push(candidate);
+                }
+            }
+        }
+        edges.extend(chosen.iter().map(|&t| (s, t)));
+        pool.extend_from_slice(&chosen);
+        pool.extend(core::iter::repeat(s).take(m as usize));
+    }
+    Ok(GeneratedTopology::new(neurons, edges, seed))
+}
+
+/// Distance-dependent wiring: `i -> j` with probability `p_max * exp(-d² / 2σ²)`
+///
+/// `positions[i]` is the location of neuron `i`, in the same unit as `sigma`.
+pub fn distance_gaussian(
+    positions: &[[f32; 3]],
+    sigma: f32,
+    p_max: f64,
+    seed: u64,
+) -> Result<GeneratedTopology> {
+    if !(sigma > 0.0 && sigma.is_finite()) {
+        return Err(SHNNError::generic("gaussian sigma must be positive and finite"));
+    }
+    if !(0.0..=1.0).contains(&p_max) {
+        return Err(SHNNError::generic("peak connection probability must be in [0, 1]"));
+    }
+    let neurons = u32::try_from(positions.len())
+        .map_err(|_| SHNNError::generic("too many positions for u32 neuron ids"))?;
+    let mut rng = SplitMix64::new(seed);
+    let scale = 2.0 * sigma as f64 * sigma as f64;
+    let mut edges = Vec::new();
+    for (s, a) in positions.iter().enumerate() {
+        for (t, b) in positions.iter().enumerate() {
+            if s == t {
+                continue;
+            }
+            let d2: f64 = a.iter().zip(b).map(|(x, y)| (*x as f64 - *y as f64).powi(2)).sum();
+            if rng.uniform() < p_max * libm::exp(-d2 / scale) {
+                edges.push((s as u32, t as u32));
+            }
+        }
+    }
+    Ok(GeneratedTopology::new(neurons, edges, seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn out_degrees(topology: &GeneratedTopology) -> Vec<usize> {
+        let mut degrees = vec![0; topology.neuron_count()];
+        for &(s, _) in &topology.edges {
+            degrees[s as usize] += 1;
+        }
+        degrees
+    }
+
+    #[test]
+    fn test_generators_are_deterministic() {
+        let a = erdos_renyi(100, 0.05, 7).expect("Should generate");
+        assert_eq!(a, erdos_renyi(100, 0.05, 7).expect("Should generate"));
+        assert_ne!(a.edges, erdos_renyi(100, 0.05, 8).expect("Should generate").edges);
+        assert_eq!(
+            watts_strogatz(50, 4, 0.3, 1).expect("Should generate"),
+            watts_strogatz(50, 4, 0.3, 1).expect("Should generate")
+        );
+        assert_eq!(
+            barabasi_albert(50, 2, 1).expect("Should generate"),
+            barabasi_albert(50, 2, 1).expect("Should generate")
+        );
+        let init = WeightInit::Uniform { low: 0.1, high: 0.5 };
+        let weights = a.weights(init);
+        assert_eq!(weights, a.weights(init));
+        assert!(weights.iter().all(|w| (0.1..0.5).contains(w)));
+    }
+
+    #[test]
+    fn test_erdos_renyi_density() {
+        let topology = erdos_renyi(200, 0.1, 42).expect("Should generate");
+        assert!(topology.edges.iter().all(|&(s, t)| s != t));
+        // Expected 3980 edges, standard deviation ~60
+        let expected = 0.1 * 200.0 * 199.0;
+        assert!((topology.edge_count() as f64 - expected).abs() < 300.0, "{}", topology.edge_count());
+
+        assert_eq!(erdos_renyi(10, 0.0, 1).expect("Should generate").edge_count(), 0);
+        assert_eq!(erdos_renyi(10, 1.0, 1).expect("Should generate").edge_count(), 90);
+        assert!(erdos_renyi(10, 1.5, 1).is_err());
+    }
+
+    #[test]
+    fn test_watts_strogatz_keeps_out_degree() {
+        let lattice = watts_strogatz(20, 4, 0.0, 3).expect("Should generate");
+        assert!(lattice.edges.contains(&(0, 19)) && lattice.edges.contains(&(0, 2)));
+        assert!(!lattice.edges.contains(&(0, 3)));
+
+        let rewired = watts_strogatz(100, 6, 0.5, 3).expect("Should generate");
+        assert!(out_degrees(&rewired).iter().all(|&d| d == 6));
+        assert!(rewired.edges.iter().all(|&(s, t)| s != t));
+        assert_ne!(rewired.edges, watts_strogatz(100, 6, 0.0, 3).expect("Should generate").edges);
+
+        assert!(watts_strogatz(10, 3, 0.1, 1).is_err());
+        assert!(watts_strogatz(5, 4, 0.1, 1).is_err());
+    }
+
+    #[test]
+    fn test_barabasi_albert_hubs() {
+        let topology = barabasi_albert(500, 3, 9).expect("Should generate");
+        assert_eq!(topology.edge_count(), (500 - 3) * 3);
+        assert!(out_degrees(&topology)[3..].iter().all(|&d| d == 3));
+        let mut in_degree = vec![0usize; 500];
+        for &(_, t) in &topology.edges {
+            in_degree[t as usize] += 1;
+        }
+        // Early neurons become hubs far above the mean in-degree of ~3
+        assert!(in_degree.iter().max().copied().unwrap_or(0) > 30);
+        assert!(barabasi_albert(3, 3, 1).is_err());
+    }
+
+    #[test]
+    fn test_distance_gaussian_prefers_neighbours() {
+        let positions: Vec<[f32; 3]> = (0..100).map(|i| [i as f32, 0.0, 0.0]).collect();
+        let topology = distance_gaussian(&positions, 2.0, 1.0, 5).expect("Should generate");
+        let near = topology.edges.iter().filter(|&&(s, t)| s.abs_diff(t) <= 2).count();
+        let far = topology.edges.iter().filter(|&&(s, t)| s.abs_diff(t) > 10).count();
+        assert!(near > 250, "{}", near);
+        assert_eq!(far, 0);
+        assert!(distance_gaussian(&positions, 0.0, 1.0, 5).is_err());
+    }
+
+    #[test]
+    fn test_populates_backends() {
+        let topology = erdos_renyi(30, 0.2, 11).expect("Should generate")
+            .with_first_id(100)
+            .expect("Should shift ids");
+        assert_eq!(topology.neurons().next(), Some(NeuronId::new(100)));
+
+        let graph = topology.to_graph(WeightInit::Constant(0.5), Time::from_millis(1)).expect("Should build graph");
+        let sparse = topology.to_sparse(WeightInit::Constant(0.5)).expect("Should build sparse");
+        assert_eq!(Topology::from_graph(&graph), topology.to_topology());
+        assert_eq!(Topology::from_sparse(&sparse), topology.to_topology());
+        let matrix = topology.to_matrix(WeightInit::Constant(0.5)).expect("Should build matrix");
+        let (s, t) = topology.edges().next().expect("Should have edges");
+        assert_eq!(matrix.get_weight(s, t).expect("Should read weight"), 0.5);
+
+        assert!(topology.with_first_id(u32::MAX).is_err());
+    }
+
+    #[cfg(feature = "nir")]
+    #[test]
+    fn test_nir_ops() {
+        let topology = watts_strogatz(10, 2, 0.0, 1).expect("Should generate");
+        let ops = topology.to_nir_ops(WeightInit::Constant(1.0), 1.0);
+        assert_eq!(ops.len(), 20);
+        assert_eq!(ops[0].attrs.get("pre"), Some(&shnn_ir::AttributeValue::NeuronRef(0)));
+    }
+}
@@ -20,48 +20,8 @@ use heapless::{FnvIndexMap as HashMap, FnvIndexSet as HashSet};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-/// Unique identifier for a hyperedge
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct HyperedgeId(pub u32);
-
-impl HyperedgeId {
-    /// Create a new hyperedge ID
-    pub const fn new(id: u32) -> Self {
-        Self(id)
-    }
-    
-    /// Get the raw ID value
-    pub const fn raw(&self) -> u32 {
-        self.0
-    }
-    
-    /// Invalid hyperedge ID constant
-    pub const INVALID: Self = Self(u32::MAX);
-    
-    /// Check if this is a valid hyperedge ID
-    pub const fn is_valid(&self) -> bool {
-        self.0 != u32::MAX
-    }
-}
-
-impl fmt::Display for HyperedgeId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "E{}", self.0)
-    }
-}
-
-impl From<u32> for HyperedgeId {
-    fn from(id: u32) -> Self {
-        Self(id)
-    }
-}
-
-impl From<HyperedgeId> for u32 {
-    fn from(id: HyperedgeId) -> Self {
-        id.0
-    }
-}
+/// Unique identifier for a hyperedge (shared with shnn-storage/shnn-runtime)
+pub use shnn_types::HyperedgeId;
 
 /// Weight function for hyperedge connections
 #[derive(Debug, Clone, PartialEq)]
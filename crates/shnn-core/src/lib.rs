@@ -42,6 +42,7 @@
 //! - `serialize`: Enable zero-copy serialization support
 //! - `simd`: Enable SIMD optimizations
 //! - `parallel`: Enable parallel processing
+//! - `nir`: Lower generated topologies to NIR connectivity ops
 //! - `hardware-accel`: Enable hardware acceleration support
 //! - `optimized`: Enable all performance optimizations
 
@@ -72,6 +73,7 @@ pub mod time;
 pub mod memory;
 pub mod error;
 pub mod connectivity;
+pub mod generators;
 pub mod network;
 
 // Re-export our custom zero-dependency crates
@@ -5,7 +5,7 @@
 //! flexible neural network construction.
 
 use crate::{
-    connectivity::{NetworkConnectivity, PlasticConnectivity, types::SpikeRoute},
+    connectivity::{NetworkConnectivity, PlasticConnectivity, RouteBuffer, types::SpikeRoute},
     neuron::{Neuron, NeuronPool, NeuronId},
     spike::{Spike, TimedSpike},
     plasticity::{PlasticityRule, STDPConfig},
@@ -94,6 +94,9 @@ where
 
     /// Determinism configuration
     determinism: DeterminismConfig,
+
+    /// Routing output reused across spikes
+    route_buffer: RouteBuffer,
 }
 
 impl<C, N> SpikeNetwork<C, N>
@@ -119,6 +122,7 @@ where
             time_step: crate::time::Duration::from_millis(1), // Default 1ms time step
             max_pending_spikes: 10000, // Default limit
             determinism: DeterminismConfig::default(),
+            route_buffer: RouteBuffer::new(),
         }
     }
     
@@ -183,6 +187,7 @@ where
     /// Process input spikes through the network
     pub fn process_spikes(&mut self, input_spikes: &[Spike]) -> Result<Vec<Spike>> {
         let mut output_spikes = Vec::new();
+        let mut route_pairs: Vec<(NeuronId, f32)> = Vec::new();
 
         // Add input spikes to processing queue with deterministic ordering if enabled
         #[cfg(feature = "std")]
@@ -211,41 +216,28 @@ where
                 break;
             }
             
-            // Route the spike through the connectivity structure
-            let routes = self.connectivity.route_spike(&timed_spike.spike, self.current_time)
+            // Route the spike into the reusable buffer (taken out so the
+            // neurons and plasticity can be borrowed mutably below)
+            let mut buffer = core::mem::take(&mut self.route_buffer);
+            self.connectivity
+                .route_spikes_batch(core::slice::from_ref(&timed_spike.spike), self.current_time, &mut buffer)
                 .map_err(|_e| SHNNError::generic("Connectivity routing failed"))?;
-            
-            // Process each route with stable target ordering if enabled
-            for route in routes {
-                #[cfg(feature = "std")]
-                let mut pairs: Vec<(NeuronId, f32)> = route
-                    .targets
-                    .iter()
-                    .zip(route.weights.iter())
-                    .map(|(t, w)| (*t, *w))
-                    .collect();
-
-                #[cfg(feature = "std")]
-                if self.determinism.enabled && self.determinism.stable_routing {
-                    pairs.sort_by_key(|(t, _)| t.raw());
-                }
 
-                #[cfg(not(feature = "std"))]
-                let pairs_iter = route.targets.iter().zip(route.weights.iter()).map(|(t, w)| (*t, *w));
+            // Process each route with stable target ordering if enabled
+            for range in buffer.routes() {
+                route_pairs.clear();
+                route_pairs.extend(
+                    buffer.targets()[range.clone()]
+                        .iter()
+                        .copied()
+                        .zip(buffer.weights()[range].iter().copied()),
+                );
 
-                #[cfg(feature = "std")]
-                for (target, weight) in pairs {
-                    if let Some(neuron) = self.neurons.get_neuron_mut(target.raw() as usize) {
-                        neuron.integrate(weight as f64, self.time_step.as_nanos() as u64);
-                        if let Some(output_spike) = neuron.update(self.time_step.as_nanos() as u64) {
-                            output_spikes.push(output_spike.clone());
-                            self.apply_plasticity(&timed_spike.spike, &output_spike, weight)?;
-                        }
-                    }
+                if self.determinism.enabled && self.determinism.stable_routing {
+                    route_pairs.sort_by_key(|(t, _)| t.raw());
                 }
 
-                #[cfg(not(feature = "std"))]
-                for (target, weight) in pairs_iter {
+                for &(target, weight) in &route_pairs {
                     if let Some(neuron) = self.neurons.get_neuron_mut(target.raw() as usize) {
                         neuron.integrate(weight as f64, self.time_step.as_nanos() as u64);
                         if let Some(output_spike) = neuron.update(self.time_step.as_nanos() as u64) {
@@ -255,6 +247,7 @@ where
                     }
                 }
             }
+            self.route_buffer = buffer;
             
             // Update statistics
             self.stats.total_spikes_processed += 1;
@@ -123,19 +123,6 @@ impl<T: Neuron> Default for NeuronPool<T> {
     }
 }
 
-// Conversion utilities for type safety
-impl From<usize> for NeuronId {
-    fn from(id: usize) -> Self {
-        Self::new(id as u32)
-    }
-}
-
-impl From<NeuronId> for usize {
-    fn from(id: NeuronId) -> Self {
-        id.raw() as usize
-    }
-}
-
 /// Current state of a neuron including membrane potential and internal variables
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
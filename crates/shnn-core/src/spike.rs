@@ -12,54 +12,8 @@ use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-/// Unique identifier for a neuron in the network
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct NeuronId(pub u32);
-
-impl NeuronId {
-    /// Create a new neuron ID
-    pub const fn new(id: u32) -> Self {
-        Self(id)
-    }
-    
-    /// Get the raw ID value
-    pub const fn raw(&self) -> u32 {
-        self.0
-    }
-    
-    /// Invalid neuron ID constant
-    pub const INVALID: Self = Self(u32::MAX);
-    
-    /// Check if this is a valid neuron ID
-    pub const fn is_valid(&self) -> bool {
-        self.0 != u32::MAX
-    }
-    
-    /// Convert to usize for array indexing (test helper)
-    #[cfg(test)]
-    pub fn as_usize(&self) -> usize {
-        self.0 as usize
-    }
-}
-
-impl fmt::Display for NeuronId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "N{}", self.0)
-    }
-}
-
-impl From<u32> for NeuronId {
-    fn from(id: u32) -> Self {
-        Self(id)
-    }
-}
-
-impl From<NeuronId> for u32 {
-    fn from(id: NeuronId) -> Self {
-        id.0
-    }
-}
+/// Unique identifier for a neuron (shared with shnn-storage/shnn-runtime)
+pub use shnn_types::NeuronId;
 
 /// A neural spike event
 ///
@@ -141,6 +95,21 @@ impl fmt::Display for Spike {
     }
 }
 
+// Transition conversions to the shared spike type used by shnn-storage/shnn-runtime
+impl From<Spike> for shnn_types::Spike {
+    fn from(spike: Spike) -> Self {
+        Self::with_amplitude(spike.source, spike.timestamp, spike.amplitude)
+    }
+}
+
+impl TryFrom<shnn_types::Spike> for Spike {
+    type Error = SHNNError;
+
+    fn try_from(spike: shnn_types::Spike) -> Result<Self> {
+        Self::new(spike.neuron_id, spike.time, spike.amplitude)
+    }
+}
+
 /// A spike with additional timing information for delayed processing
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -591,4 +560,16 @@ mod tests {
         // Perfectly regular -> CV should be 0
         assert_eq!(train.cv_isi(), 0.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_shared_spike_conversion() {
+        let spike = Spike::new(NeuronId::new(4), Time::from_micros(10), 0.5).unwrap();
+        let shared: shnn_types::Spike = spike.clone().into();
+        assert_eq!(shared.neuron_id, spike.source);
+        assert_eq!(shared.time, spike.timestamp);
+        assert_eq!(Spike::try_from(shared).unwrap(), spike);
+
+        let invalid = shnn_types::Spike::with_amplitude(NeuronId::new(1), Time::ZERO, -1.0);
+        assert!(Spike::try_from(invalid).is_err());
+    }
+}
@@ -75,6 +75,64 @@ pub struct NetworkStatistics<T: FixedPoint> {
     pub memory_usage: usize,
 }
 
+/// On-device STDP and pruning parameters
+///
+/// The learning rule is selected at compile time: the default simplified
+/// trace rule applies both LTP and LTD when a presynaptic spike is delivered;
+/// the `stdp-pair` feature switches to pair-based STDP (LTD on presynaptic
+/// spikes, LTP on postsynaptic spikes).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlasticityConfig<T: FixedPoint> {
+    /// Apply STDP during updates
+    pub enabled: bool,
+    /// Potentiation increment (A+)
+    pub a_plus: T,
+    /// Depression increment (A-)
+    pub a_minus: T,
+    /// Pre-synaptic trace decay factor per dt
+    pub trace_decay_pre: T,
+    /// Post-synaptic trace decay factor per dt
+    pub trace_decay_post: T,
+    /// Minimum weight clamp
+    pub w_min: T,
+    /// Maximum weight clamp
+    pub w_max: T,
+    /// Prune threshold (remove synapses with weight below; 0 disables)
+    pub prune_threshold: T,
+}
+
+impl<T: FixedPoint> PlasticityConfig<T> {
+    /// Check that bounds are ordered and trace decays lie in [0, 1]
+    pub fn validate(&self) -> EmbeddedResult<()> {
+        let unit = |x: T| x >= T::zero() && x <= T::one();
+        if self.w_min > self.w_max
+            || !unit(self.trace_decay_pre)
+            || !unit(self.trace_decay_post)
+            || self.a_plus < T::zero()
+            || self.a_minus < T::zero()
+            || self.prune_threshold < T::zero()
+        {
+            return Err(EmbeddedError::InvalidConfig);
+        }
+        Ok(())
+    }
+}
+
+impl<T: FixedPoint> Default for PlasticityConfig<T> {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            a_plus: T::from_float(0.01),
+            a_minus: T::from_float(0.012),
+            trace_decay_pre: T::from_float(0.96),
+            trace_decay_post: T::from_float(0.96),
+            w_min: T::from_float(0.0),
+            w_max: T::from_float(1.0),
+            prune_threshold: T::from_float(0.0),
+        }
+    }
+}
+
 /// Embedded spiking neural network implementation
 #[derive(Debug)]
 pub struct EmbeddedSNN<T: FixedPoint> {
@@ -97,24 +155,13 @@ pub struct EmbeddedSNN<T: FixedPoint> {
     #[cfg(feature = "partitioning")]
     /// Per-partition spike buckets for cross-partition deferral
     partition_spikes: crate::partitioning::PartitionedSpikeQueues<T>,
+    #[cfg(feature = "partitioning")]
+    /// Per-tick drain budgets and order of the partition queues
+    partition_scheduler: crate::partitioning::PartitionScheduler,
 
     // Plasticity parameters (quantized STDP with traces)
-    /// Enable plasticity
-    plastic_enable: bool,
-    /// Potentiation increment (A+)
-    plastic_a_plus: T,
-    /// Depression increment (A-)
-    plastic_a_minus: T,
-    /// Pre-synaptic trace decay rate
-    plastic_alpha_pre: T,
-    /// Post-synaptic trace decay rate
-    plastic_alpha_post: T,
-    /// Minimum weight clamp
-    plastic_w_min: T,
-    /// Maximum weight clamp
-    plastic_w_max: T,
-    /// Prune threshold (remove synapses with weight below)
-    prune_threshold: T,
+    /// STDP and pruning parameters
+    plasticity: PlasticityConfig<T>,
     
     // STDP trace state
     /// Pre-synaptic traces for each neuron
@@ -203,15 +250,9 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
             partition_map: crate::partitioning::PartitionMap::new(),
             #[cfg(feature = "partitioning")]
             partition_spikes: crate::partitioning::PartitionedSpikeQueues::new(),
-            // Plasticity defaults (STDP with exponential traces)
-            plastic_enable: true,
-            plastic_a_plus: T::from_float(0.01),
-            plastic_a_minus: T::from_float(0.012),
-            plastic_alpha_pre: T::from_float(0.96),   // decay factor per dt
-            plastic_alpha_post: T::from_float(0.96),  // decay factor per dt
-            plastic_w_min: T::from_float(0.0),
-            plastic_w_max: T::from_float(1.0),
-            prune_threshold: T::from_float(0.0),
+            #[cfg(feature = "partitioning")]
+            partition_scheduler: crate::partitioning::PartitionScheduler::new(MAX_SPIKES_PER_STEP),
+            plasticity: PlasticityConfig::default(),
             pre_trace: Vec::new(),
             post_trace: Vec::new(),
     
@@ -252,6 +293,23 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
             .map_err(|_| EmbeddedError::BufferFull)
     }
     
+    /// Turn on-device STDP on or off (on by default)
+    pub fn set_plasticity_enabled(&mut self, enabled: bool) {
+        self.plasticity.enabled = enabled;
+    }
+
+    /// STDP and pruning parameters
+    pub fn plasticity(&self) -> &PlasticityConfig<T> {
+        &self.plasticity
+    }
+
+    /// Replace the STDP and pruning parameters
+    pub fn set_plasticity(&mut self, config: PlasticityConfig<T>) -> EmbeddedResult<()> {
+        config.validate()?;
+        self.plasticity = config;
+        Ok(())
+    }
+
     /// Enable hypergraph connections
     pub fn enable_hypergraph(&mut self) -> EmbeddedResult<()> {
         self.hypergraph = Some(EmbeddedHypergraph::new());
@@ -268,6 +326,21 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
         Ok(())
     }
     
+    /// Number of input neurons (length of the `inputs` slice taken by `update`)
+    pub fn input_count(&self) -> usize {
+        self.input_indices.len()
+    }
+    
+    /// Number of neurons
+    pub fn neuron_count(&self) -> usize {
+        self.neurons.len()
+    }
+    
+    /// Synapses of the network
+    pub fn synapses(&self) -> &[EmbeddedSynapse<T>] {
+        &self.synapses
+    }
+    
     /// Set output neurons
     pub fn set_output_neurons(&mut self, indices: &[u16]) -> EmbeddedResult<()> {
         self.output_indices.clear();
@@ -283,6 +356,30 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
     pub fn set_neuron_partition(&mut self, neuron_id: u16, pid: crate::partitioning::PartitionId) {
         self.partition_map.assign(neuron_id, pid);
     }
+
+    #[cfg(feature = "partitioning")]
+    /// Replace all neuron partition assignments (e.g. from the core partitioner)
+    pub fn set_partition_map(&mut self, map: crate::partitioning::PartitionMap) {
+        self.partition_map = map;
+    }
+
+    #[cfg(feature = "partitioning")]
+    /// Scheduler controlling how deferred cross-partition spikes are drained
+    pub fn partition_scheduler(&self) -> &crate::partitioning::PartitionScheduler {
+        &self.partition_scheduler
+    }
+
+    #[cfg(feature = "partitioning")]
+    /// Mutable access to the partition scheduler (budgets, priorities, policy)
+    pub fn partition_scheduler_mut(&mut self) -> &mut crate::partitioning::PartitionScheduler {
+        &mut self.partition_scheduler
+    }
+
+    #[cfg(feature = "partitioning")]
+    /// Deferred spikes queued per partition
+    pub fn partition_backlog(&self) -> [usize; crate::partitioning::MAX_PARTITIONS] {
+        self.partition_spikes.backlog()
+    }
     
     /// Create a feedforward network topology
     pub fn create_feedforward(&mut self, layer_sizes: &[usize]) -> EmbeddedResult<()> {
@@ -378,9 +475,13 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
                     let input_current = synaptic_currents.get(i).copied().unwrap_or(T::zero());
                     if let Some(spike) = neuron.update(self.dt, input_current)? {
                         // Update post-synaptic trace on spike
-                        if self.plastic_enable && (spike.source as usize) < self.post_trace.len() {
+                        if self.plasticity.enabled && (spike.source as usize) < self.post_trace.len() {
                             self.post_trace[spike.source as usize] = self.post_trace[spike.source as usize] + T::one();
                         }
+                        #[cfg(feature = "stdp-pair")]
+                        if self.plasticity.enabled {
+                            self.potentiate_incoming(spike.source);
+                        }
                         
                         // Enforce spike budget across both pending and produced
                         if produced.len() + self.pending_spikes.len() < max_spikes {
@@ -398,7 +499,7 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
                                             // Same-partition: deliver immediately
                                             (Some(a), Some(b)) if a == b => {
                                                 synapse.receive_spike(&spike)?;
-                                                if self.plastic_enable {
+                                                if self.plasticity.enabled {
                                                     // Update pre-synaptic trace on spike
                                                     let pre_idx = spike.source as usize;
                                                     if pre_idx < self.pre_trace.len() {
@@ -421,7 +522,7 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
                                             // Unknown mapping: fallback to immediate delivery
                                             _ => {
                                                 synapse.receive_spike(&spike)?;
-                                                if self.plastic_enable {
+                                                if self.plasticity.enabled {
                                                     // Update pre-synaptic trace on spike
                                                     let pre_idx = spike.source as usize;
                                                     if pre_idx < self.pre_trace.len() {
@@ -442,7 +543,7 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
                                     #[cfg(not(feature = "partitioning"))]
                                     {
                                         synapse.receive_spike(&spike)?;
-                                        if self.plastic_enable {
+                                        if self.plasticity.enabled {
                                             // Update pre-synaptic trace on spike
                                             let pre_idx = spike.source as usize;
                                             if pre_idx < self.pre_trace.len() {
@@ -468,96 +569,46 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
 
         Ok(produced)
     }
+
+    /// Deliver deferred cross-partition spikes as planned by the partition scheduler
     #[cfg(feature = "partitioning")]
-    fn drain_partition_queues(&mut self, max_spikes: usize) {
-        use crate::partitioning::{MAX_PARTITIONS, PartitionId};
-        let mut processed = 0usize;
-        for pid in 0..MAX_PARTITIONS {
-            if processed >= max_spikes { break; }
-            let pid_u8: PartitionId = pid as PartitionId;
-
-            // Drain this partition's deferred spikes
-            let mut drained = self.partition_spikes.drain(pid_u8);
-            let mut idx = 0usize;
-
-            // Process up to the budget for this tick
-            while idx < drained.len() && processed < max_spikes {
-                if let Some(sp) = drained.get(idx).copied() {
-                    // Deliver only to synapses whose post is in this partition
-                    for syn in &mut self.synapses {
-                        if syn.pre_id == sp.source {
-                            if let Some(dst) = self.partition_map.get(syn.post_id) {
-                                if dst == pid_u8 {
-                                    let _ = syn.receive_spike(&sp);
-                                    if self.plastic_enable {
-                                        syn.potentiate(self.plastic_a_plus, self.plastic_w_min, self.plastic_w_max);
-                                    }
-                                    let _ = self.active_list.push_back(syn.post_id);
-                                    processed += 1;
-                                }
-                            }
+    fn drain_partition_queues(&mut self) {
+        let plan = self.partition_scheduler.plan(&self.partition_spikes.backlog());
+        for (pid, allowed) in plan {
+            let drained = self.partition_spikes.drain(pid);
+            for (i, sp) in drained.iter().copied().enumerate() {
+                if i >= allowed {
+                    // Spikes beyond this tick's allowance stay queued in order
+                    let _ = self.partition_spikes.push(pid, sp);
+                    continue;
+                }
+                // Deliver only to synapses whose post lies in this partition
+                for syn in &mut self.synapses {
+                    if syn.pre_id == sp.source && self.partition_map.get(syn.post_id) == Some(pid) {
+                        let _ = syn.receive_spike(&sp);
+                        if self.plasticity.enabled {
+                            syn.potentiate(self.plasticity.a_plus, self.plasticity.w_min, self.plasticity.w_max);
                         }
+                        let _ = self.active_list.push_back(syn.post_id);
                     }
                 }
-                idx += 1;
-            }
-
-            // Re-enqueue any unprocessed spikes for this partition
-            while idx < drained.len() {
-                if let Some(sp) = drained.get(idx).copied() {
-                    let _ = self.partition_spikes.push(pid_u8, sp);
-                }
-                idx += 1;
             }
         }
     }
     
-    #[cfg(feature = "partitioning")]
-    fn drain_partition_queues(&mut self, max_spikes: usize) {
-        use crate::partitioning::{MAX_PARTITIONS, PartitionId};
-        let mut processed = 0usize;
-        for pid in 0..MAX_PARTITIONS {
-            if processed >= max_spikes { break; }
-            let pid_u8: PartitionId = pid as PartitionId;
-            // Drain this partition's queue
-            let mut drained = self.partition_spikes.drain(pid_u8);
-            let mut idx = 0usize;
-            while idx < drained.len() && processed < max_spikes {
-                if let Some(sp) = drained.get(idx).copied() {
-                    // Deliver only to synapses whose post lies in this partition
-                    for syn in &mut self.synapses {
-                        if syn.pre_id == sp.source {
-                            if let Some(dst) = self.partition_map.get(syn.post_id) {
-                                if dst == pid_u8 {
-                                    let _ = syn.receive_spike(&sp);
-                                    if self.plastic_enable {
-                                        syn.potentiate(self.plastic_a_plus, self.plastic_w_min, self.plastic_w_max);
-                                    }
-                                    let _ = self.active_list.push_back(syn.post_id);
-                                    processed += 1;
-                                }
-                            }
-                        }
-                    }
-                }
-                idx += 1;
-            }
-            // Re-enqueue any unprocessed spikes
-            while idx < drained.len() {
-    
     /// Decay STDP traces for all neurons
     fn decay_traces(&mut self) {
         for trace in &mut self.pre_trace {
-            *trace = *trace * self.plastic_alpha_pre;
+            *trace = *trace * self.plasticity.trace_decay_pre;
         }
         for trace in &mut self.post_trace {
-            *trace = *trace * self.plastic_alpha_post;
+            *trace = *trace * self.plasticity.trace_decay_post;
         }
     }
     
     /// Apply STDP weight update based on pre/post traces
     fn apply_stdp(&mut self, pre_id: u16, post_id: u16, synapse_idx: usize) {
-        if !self.plastic_enable || synapse_idx >= self.synapses.len() {
+        if !self.plasticity.enabled || synapse_idx >= self.synapses.len() {
             return;
         }
         
@@ -569,29 +620,45 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
         }
         
         // LTD: w -= a_minus * post_trace[post]
-        let ltd_amount = self.plastic_a_minus * self.post_trace[post_idx];
+        let ltd_amount = self.plasticity.a_minus * self.post_trace[post_idx];
         
-        // LTP: w += a_plus * pre_trace[pre] 
-        let ltp_amount = self.plastic_a_plus * self.pre_trace[pre_idx];
+        // LTP: w += a_plus * pre_trace[pre] (pair-based STDP potentiates on post spikes instead)
+        #[cfg(not(feature = "stdp-pair"))]
+        let ltp_amount = self.plasticity.a_plus * self.pre_trace[pre_idx];
+        #[cfg(feature = "stdp-pair")]
+        let ltp_amount = T::zero();
         
         // Apply weight change
         let synapse = &mut self.synapses[synapse_idx];
         let mut new_weight = synapse.weight - ltd_amount + ltp_amount;
         
         // Clamp to bounds
-        if new_weight < self.plastic_w_min {
-            new_weight = self.plastic_w_min;
+        if new_weight < self.plasticity.w_min {
+            new_weight = self.plasticity.w_min;
         }
-        if new_weight > self.plastic_w_max {
-            new_weight = self.plastic_w_max;
+        if new_weight > self.plasticity.w_max {
+            new_weight = self.plasticity.w_max;
         }
         
         synapse.weight = new_weight;
     }
-                if let Some(sp) = drained.get(idx).copied() {
-                    let _ = self.partition_spikes.push(pid_u8, sp);
+
+    /// Pair-based LTP: w += a_plus * pre_trace[pre] on synapses onto a neuron that just spiked
+    #[cfg(feature = "stdp-pair")]
+    fn potentiate_incoming(&mut self, post_id: u16) {
+        let config = self.plasticity;
+        for synapse in &mut self.synapses {
+            if synapse.post_id == post_id {
+                if let Some(&trace) = self.pre_trace.get(synapse.pre_id as usize) {
+                    let mut new_weight = synapse.weight + config.a_plus * trace;
+                    if new_weight < config.w_min {
+                        new_weight = config.w_min;
+                    }
+                    if new_weight > config.w_max {
+                        new_weight = config.w_max;
+                    }
+                    synapse.weight = new_weight;
                 }
-                idx += 1;
             }
         }
     }
@@ -650,13 +717,13 @@ impl<T: FixedPoint> EmbeddedSNN<T> {
 impl<T: FixedPoint> EmbeddedNetwork<T> for EmbeddedSNN<T> {
     fn update(&mut self, inputs: &[T]) -> EmbeddedResult<Vec<FixedSpike<T>, MAX_SPIKES_PER_STEP>> {
         // Decay STDP traces each tick
-        if self.plastic_enable {
+        if self.plasticity.enabled {
             self.decay_traces();
         }
 
         // Drain any deferred cross-partition spikes from previous ticks
         #[cfg(feature = "partitioning")]
-        self.drain_partition_queues(MAX_SPIKES_PER_STEP);
+        self.drain_partition_queues();
 
         // Apply external inputs (schedules input neurons as active)
         self.apply_inputs(inputs)?;
@@ -678,8 +745,8 @@ impl<T: FixedPoint> EmbeddedNetwork<T> for EmbeddedSNN<T> {
         }
 
         // Prune weak synapses if pruning is configured
-        if self.prune_threshold > T::zero() {
-            self.synapses.retain(|s| s.weight >= self.prune_threshold);
+        if self.plasticity.prune_threshold > T::zero() {
+            self.synapses.retain(|s| s.weight >= self.plasticity.prune_threshold);
         }
         
         // Update time and statistics
@@ -700,6 +767,8 @@ impl<T: FixedPoint> EmbeddedNetwork<T> for EmbeddedSNN<T> {
         self.pending_spikes.clear();
         #[cfg(feature = "partitioning")]
         self.partition_spikes.clear_all();
+        #[cfg(feature = "partitioning")]
+        self.partition_scheduler.reset();
         self.current_time = T::zero();
         self.simulation_steps = 0;
 
@@ -748,6 +817,7 @@ pub struct EmbeddedNetworkBuilder<T: FixedPoint> {
     topology: EmbeddedTopology,
     layer_sizes: Vec<usize, 8>,
     use_hypergraph: bool,
+    plasticity: PlasticityConfig<T>,
     _phantom: PhantomData<T>,
 }
 
@@ -759,6 +829,7 @@ impl<T: FixedPoint> EmbeddedNetworkBuilder<T> {
             topology: EmbeddedTopology::Custom,
             layer_sizes: Vec::new(),
             use_hypergraph: false,
+            plasticity: PlasticityConfig::default(),
             _phantom: PhantomData,
         }
     }
@@ -782,9 +853,54 @@ impl<T: FixedPoint> EmbeddedNetworkBuilder<T> {
         self
     }
     
+    /// Enable or disable on-device STDP
+    pub fn plasticity(mut self, enabled: bool) -> Self {
+        self.plasticity.enabled = enabled;
+        self
+    }
+    
+    /// Set all STDP and pruning parameters at once
+    pub fn plasticity_config(mut self, config: PlasticityConfig<T>) -> Self {
+        self.plasticity = config;
+        self
+    }
+    
+    /// Set the potentiation increment (A+)
+    pub fn a_plus(mut self, a_plus: T) -> Self {
+        self.plasticity.a_plus = a_plus;
+        self
+    }
+    
+    /// Set the depression increment (A-)
+    pub fn a_minus(mut self, a_minus: T) -> Self {
+        self.plasticity.a_minus = a_minus;
+        self
+    }
+    
+    /// Set the per-dt decay factors of the pre- and post-synaptic traces
+    pub fn trace_decay(mut self, pre: T, post: T) -> Self {
+        self.plasticity.trace_decay_pre = pre;
+        self.plasticity.trace_decay_post = post;
+        self
+    }
+    
+    /// Set the weight clamp range
+    pub fn weight_bounds(mut self, w_min: T, w_max: T) -> Self {
+        self.plasticity.w_min = w_min;
+        self.plasticity.w_max = w_max;
+        self
+    }
+    
+    /// Remove synapses whose weight falls below `threshold` (0 disables pruning)
+    pub fn prune_threshold(mut self, threshold: T) -> Self {
+        self.plasticity.prune_threshold = threshold;
+        self
+    }
+    
     /// Build the network
     pub fn build(self) -> EmbeddedResult<EmbeddedSNN<T>> {
         let mut network = EmbeddedSNN::new(self.dt, self.topology);
+        network.set_plasticity(self.plasticity)?;
         
         if self.use_hypergraph {
             network.enable_hypergraph()?;
@@ -867,7 +983,7 @@ mod tests {
             net.add_synapse(EmbeddedSynapse::new(0, 1, Q16_16::from_float(0.05), 1)).unwrap();
             net.add_synapse(EmbeddedSynapse::new(0, 1, Q16_16::from_float(0.0001), 1)).unwrap();
             // Configure pruning threshold
-            net.prune_threshold = Q16_16::from_float(0.001);
+            net.plasticity.prune_threshold = Q16_16::from_float(0.001);
     
             // Drive input neuron strongly to cause pre spikes and potentiation
             net.input_indices = {
@@ -886,6 +1002,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_builder_plasticity_config() {
+        let network = EmbeddedNetworkBuilder::<Q16_16>::new(Q16_16::from_float(0.001))
+            .topology(EmbeddedTopology::Feedforward)
+            .add_layer(2).unwrap()
+            .add_layer(1).unwrap()
+            .a_plus(Q16_16::from_float(0.02))
+            .a_minus(Q16_16::from_float(0.03))
+            .trace_decay(Q16_16::from_float(0.9), Q16_16::from_float(0.8))
+            .weight_bounds(Q16_16::from_float(-0.5), Q16_16::from_float(2.0))
+            .prune_threshold(Q16_16::from_float(0.001))
+            .plasticity(false)
+            .build().unwrap();
+        let config = network.plasticity();
+        assert!(!config.enabled);
+        assert_eq!(config.a_plus, Q16_16::from_float(0.02));
+        assert_eq!(config.trace_decay_post, Q16_16::from_float(0.8));
+        assert_eq!(config.w_min, Q16_16::from_float(-0.5));
+
+        // Inverted bounds and decays above 1 are rejected
+        let inverted = EmbeddedNetworkBuilder::<Q16_16>::new(Q16_16::from_float(0.001))
+            .weight_bounds(Q16_16::one(), Q16_16::zero())
+            .build();
+        assert_eq!(inverted.err(), Some(EmbeddedError::InvalidConfig));
+        let mut net = EmbeddedSNN::<Q16_16>::new(Q16_16::from_float(0.001), EmbeddedTopology::Custom);
+        let bad = PlasticityConfig { trace_decay_pre: Q16_16::from_float(1.5), ..PlasticityConfig::default() };
+        assert_eq!(net.set_plasticity(bad), Err(EmbeddedError::InvalidConfig));
+    }
+
+    #[test]
+    fn test_disabled_plasticity_keeps_weights() {
+        let mut net = EmbeddedSNN::<Q16_16>::new(Q16_16::from_float(0.001), EmbeddedTopology::Custom);
+        let _ = net.add_neuron(EmbeddedNeuronWrapper::LIF(EmbeddedLIFNeuron::new(0))).unwrap();
+        let _ = net.add_neuron(EmbeddedNeuronWrapper::LIF(EmbeddedLIFNeuron::new(1))).unwrap();
+        net.add_synapse(EmbeddedSynapse::new(0, 1, Q16_16::from_float(0.05), 1)).unwrap();
+        net.set_input_neurons(&[0]).unwrap();
+        net.set_plasticity_enabled(false);
+        for _ in 0..10 {
+            let _ = net.update(&[Q16_16::from_float(20.0)]).unwrap();
+        }
+        assert_eq!(net.synapses()[0].weight, Q16_16::from_float(0.05));
+    }
+
     #[cfg(feature = "partitioning")]
     #[test]
     fn test_partition_cross_deferral_queue_drains_next_tick() {
@@ -916,6 +1075,28 @@ mod tests {
         let _ = net.update(&[Q16_16::zero()]).unwrap();
         assert_eq!(net.partition_spikes.len(1), 0, "destination partition queue should have been drained");
     }
+
+    #[cfg(feature = "partitioning")]
+    #[test]
+    fn test_partition_scheduler_budget_holds_back_spikes() {
+        let mut net = EmbeddedSNN::<Q16_16>::new(Q16_16::from_float(0.001), EmbeddedTopology::Custom);
+        let _ = net.add_neuron(EmbeddedNeuronWrapper::LIF(EmbeddedLIFNeuron::new(0))).unwrap();
+        let _ = net.add_neuron(EmbeddedNeuronWrapper::LIF(EmbeddedLIFNeuron::new(1))).unwrap();
+        net.set_neuron_partition(0, 0);
+        net.set_neuron_partition(1, 1);
+        net.add_synapse(EmbeddedSynapse::new(0, 1, Q16_16::from_float(0.5), 0)).unwrap();
+        net.set_input_neurons(&[0]).unwrap();
+
+        // Pause partition 1: its deferred spikes wait in the queue
+        net.partition_scheduler_mut().set_budget(1, 0);
+        let _ = net.update(&[Q16_16::from_float(20.0)]).unwrap();
+        let _ = net.update(&[Q16_16::zero()]).unwrap();
+        assert!(net.partition_backlog()[1] > 0);
+
+        net.partition_scheduler_mut().set_budget(1, crate::partitioning::PER_PARTITION_SPIKES);
+        let _ = net.update(&[Q16_16::zero()]).unwrap();
+        assert_eq!(net.partition_backlog()[1], 0);
+    }
     
     #[test]
     fn test_neuron_wrapper() {
@@ -0,0 +1,368 @@
+//! Host-side emulation with a cycle budget model
+//!
+//! Runs [`EmbeddedSNN`] and shnn-micro `MicroNetwork` configurations on the
+//! host, counts the work each tick performs (neuron updates, synapse scans,
+//! synaptic events, spikes, inputs) and prices it with a per-MCU
+//! [`CycleCosts`] table. The resulting [`EmulationReport`] tells whether a
+//! network fits the target's per-tick cycle budget and RAM before flashing.
+//!
+//! The cost tables are abstract estimates for fixed-point code, not cycle
+//! accurate; calibrate them against a DWT/mcycle measurement on the real part
+//! when margins are tight.
+
+use core::fmt;
+use std::vec::Vec;
+
+use crate::{
+    error::{EmbeddedError, EmbeddedResult},
+    fixed_point::FixedPoint,
+    embedded_network::{EmbeddedNetwork, EmbeddedSNN, MAX_ACTIVE_UPDATES_PER_STEP},
+};
+
+/// Cycles charged per operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleCosts {
+    /// Fixed cost of one tick (bookkeeping, time advance, statistics)
+    pub tick_overhead: u32,
+    /// One neuron state update
+    pub neuron_update: u32,
+    /// Visiting one synapse/connection entry during a scan
+    pub synapse_scan: u32,
+    /// Delivering a spike to one post-synaptic target
+    pub synaptic_event: u32,
+    /// Emitting and recording one spike
+    pub spike: u32,
+    /// Applying one external input
+    pub input: u32,
+}
+
+impl CycleCosts {
+    /// Cortex-M0/M0+ (no long multiply; fixed-point products in software)
+    pub const CORTEX_M0: Self = Self {
+        tick_overhead: 400,
+        neuron_update: 90,
+        synapse_scan: 6,
+        synaptic_event: 40,
+        spike: 60,
+        input: 25,
+    };
+
+    /// Cortex-M4/M4F (single-cycle SMULL)
+    pub const CORTEX_M4: Self = Self {
+        tick_overhead: 250,
+        neuron_update: 35,
+        synapse_scan: 4,
+        synaptic_event: 18,
+        spike: 40,
+        input: 12,
+    };
+
+    /// RV32IMC
+    pub const RV32IMC: Self = Self {
+        tick_overhead: 300,
+        neuron_update: 45,
+        synapse_scan: 5,
+        synaptic_event: 22,
+        spike: 45,
+        input: 15,
+    };
+}
+
+/// Target microcontroller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct McuProfile {
+    /// Part name
+    pub name: &'static str,
+    /// Core clock (Hz)
+    pub clock_hz: u32,
+    /// RAM available to the network (bytes)
+    pub ram_bytes: usize,
+    /// Per-operation cycle costs
+    pub costs: CycleCosts,
+}
+
+impl McuProfile {
+    /// Built-in profiles
+    pub const PRESETS: [McuProfile; 4] = [
+        McuProfile { name: "rp2040", clock_hz: 133_000_000, ram_bytes: 264 * 1024, costs: CycleCosts::CORTEX_M0 },
+        McuProfile { name: "stm32f411", clock_hz: 100_000_000, ram_bytes: 128 * 1024, costs: CycleCosts::CORTEX_M4 },
+        McuProfile { name: "nrf52840", clock_hz: 64_000_000, ram_bytes: 256 * 1024, costs: CycleCosts::CORTEX_M4 },
+        McuProfile { name: "esp32c3", clock_hz: 160_000_000, ram_bytes: 400 * 1024, costs: CycleCosts::RV32IMC },
+    ];
+
+    /// Built-in profile by name
+    pub fn by_name(name: &str) -> Option<Self> {
+        Self::PRESETS.iter().copied().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Cycles available in a tick of `tick_us` microseconds
+    pub fn cycles_per_tick(&self, tick_us: u32) -> u64 {
+        self.clock_hz as u64 * tick_us as u64 / 1_000_000
+    }
+}
+
+/// Operations performed during one tick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TickWork {
+    /// Neuron state updates
+    pub neuron_updates: u32,
+    /// Synapse/connection entries visited
+    pub synapse_scans: u32,
+    /// Spikes delivered to post-synaptic targets
+    pub synaptic_events: u32,
+    /// Spikes emitted
+    pub spikes: u32,
+    /// External inputs applied
+    pub inputs: u32,
+}
+
+impl TickWork {
+    /// Estimated cycles of this tick
+    pub fn cycles(&self, costs: &CycleCosts) -> u64 {
+        costs.tick_overhead as u64
+            + self.neuron_updates as u64 * costs.neuron_update as u64
+            + self.synapse_scans as u64 * costs.synapse_scan as u64
+            + self.synaptic_events as u64 * costs.synaptic_event as u64
+            + self.spikes as u64 * costs.spike as u64
+            + self.inputs as u64 * costs.input as u64
+    }
+}
+
+/// Outcome of an emulated run
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmulationReport {
+    /// Target part
+    pub profile: &'static str,
+    /// Ticks emulated
+    pub ticks: u32,
+    /// Cycles available per tick
+    pub budget_cycles: u64,
+    /// Most expensive tick (cycles)
+    pub max_cycles: u64,
+    /// Mean cycles per tick
+    pub mean_cycles: f64,
+    /// Index of the most expensive tick
+    pub worst_tick: u32,
+    /// Ticks exceeding the budget
+    pub overruns: u32,
+    /// Spikes emitted over the run
+    pub total_spikes: u64,
+    /// Static size of the network state (bytes)
+    pub memory_bytes: usize,
+    /// RAM of the target (bytes)
+    pub ram_bytes: usize,
+}
+
+impl EmulationReport {
+    /// True if every tick met the budget and the network fits in RAM
+    pub fn fits(&self) -> bool {
+        self.overruns == 0 && self.memory_bytes <= self.ram_bytes
+    }
+
+    /// Worst-case fraction of the tick budget used
+    pub fn utilization(&self) -> f64 {
+        if self.budget_cycles == 0 {
+            f64::INFINITY
+        } else {
+            self.max_cycles as f64 / self.budget_cycles as f64
+        }
+    }
+}
+
+impl fmt::Display for EmulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} ticks, worst {} / {} cycles ({:.1}%) at tick {}, mean {:.0}, {} overruns, {} spikes, {} / {} bytes RAM -> {}",
+            self.profile,
+            self.ticks,
+            self.max_cycles,
+            self.budget_cycles,
+            self.utilization() * 100.0,
+            self.worst_tick,
+            self.mean_cycles,
+            self.overruns,
+            self.total_spikes,
+            self.memory_bytes,
+            self.ram_bytes,
+            if self.fits() { "fits" } else { "does not fit" },
+        )
+    }
+}
+
+/// Host-side emulator for a target MCU and tick period
+#[derive(Debug, Clone)]
+pub struct Emulator {
+    profile: McuProfile,
+    tick_us: u32,
+}
+
+impl Emulator {
+    /// Emulate `profile` with one network tick every `tick_us` microseconds
+    pub fn new(profile: McuProfile, tick_us: u32) -> Self {
+        Self { profile, tick_us }
+    }
+
+    /// Target profile
+    pub fn profile(&self) -> &McuProfile {
+        &self.profile
+    }
+
+    /// Run `network` for `ticks` ticks, taking each tick's inputs from `inputs`
+    pub fn run_embedded<T: FixedPoint>(
+        &self,
+        network: &mut EmbeddedSNN<T>,
+        ticks: u32,
+        mut inputs: impl FnMut(u32) -> Vec<T>,
+    ) -> EmbeddedResult<EmulationReport> {
+        let mut work = Vec::with_capacity(ticks as usize);
+        // Deliveries schedule their targets for the next tick's update pass
+        let mut scheduled = 0u32;
+        for tick in 0..ticks {
+            let values = inputs(tick);
+            let applied = values.len().min(network.input_count()) as u32;
+            let spikes = network.update(&values)?;
+            let synapses = network.synapses();
+            let events = spikes.iter()
+                .map(|s| synapses.iter().filter(|syn| syn.pre_id == s.source).count() as u32)
+                .sum::<u32>();
+            work.push(TickWork {
+                neuron_updates: (scheduled + applied).min(MAX_ACTIVE_UPDATES_PER_STEP as u32),
+                // Current accumulation and buffer cleanup, plus one routing scan per spike
+                synapse_scans: synapses.len() as u32 * (2 + spikes.len() as u32),
+                synaptic_events: events,
+                spikes: spikes.len() as u32,
+                inputs: applied,
+            });
+            scheduled = events;
+        }
+        Ok(self.report(&work, core::mem::size_of::<EmbeddedSNN<T>>()))
+    }
+
+    /// Run a shnn-micro network for `ticks` ticks, taking each tick's inputs from `inputs`
+    ///
+    /// The micro network reports spike counts only, so synaptic events are
+    /// estimated from the mean fan-out.
+    pub fn run_micro<const N: usize, const C: usize>(
+        &self,
+        network: &mut shnn_micro::MicroNetwork<N, C>,
+        ticks: u32,
+        mut inputs: impl FnMut(u32) -> Vec<shnn_micro::Scalar>,
+    ) -> EmbeddedResult<EmulationReport> {
+        let mut work = Vec::with_capacity(ticks as usize);
+        let neurons = network.neuron_count() as u32;
+        let connections = network.connection_count() as u32;
+        for tick in 0..ticks {
+            let values = inputs(tick);
+            network.set_inputs(&values).map_err(micro_error)?;
+            let spikes = network.step().map_err(micro_error)?.spike_count as u32;
+            work.push(TickWork {
+                neuron_updates: neurons,
+                synapse_scans: spikes * connections,
+                synaptic_events: spikes * connections / neurons.max(1),
+                spikes,
+                inputs: values.len() as u32,
+            });
+        }
+        Ok(self.report(&work, shnn_micro::MicroNetwork::<N, C>::memory_usage()))
+    }
+
+    /// Price per-tick work against the budget
+    pub fn report(&self, work: &[TickWork], memory_bytes: usize) -> EmulationReport {
+        let budget_cycles = self.profile.cycles_per_tick(self.tick_us);
+        let (mut max_cycles, mut worst_tick, mut overruns, mut total_cycles, mut total_spikes) = (0u64, 0u32, 0u32, 0u64, 0u64);
+        for (tick, w) in work.iter().enumerate() {
+            let cycles = w.cycles(&self.profile.costs);
+            if cycles > max_cycles {
+                max_cycles = cycles;
+                worst_tick = tick as u32;
+            }
+            if cycles > budget_cycles {
+                overruns += 1;
+            }
+            total_cycles += cycles;
+            total_spikes += w.spikes as u64;
+        }
+        EmulationReport {
+            profile: self.profile.name,
+            ticks: work.len() as u32,
+            budget_cycles,
+            max_cycles,
+            mean_cycles: if work.is_empty() { 0.0 } else { total_cycles as f64 / work.len() as f64 },
+            worst_tick,
+            overruns,
+            total_spikes,
+            memory_bytes,
+            ram_bytes: self.profile.ram_bytes,
+        }
+    }
+}
+
+fn micro_error(err: shnn_micro::MicroError) -> EmbeddedError {
+    match err {
+        shnn_micro::MicroError::InvalidNeuronId => EmbeddedError::InvalidNeuronId,
+        shnn_micro::MicroError::BufferOverflow => EmbeddedError::BufferOverflow,
+        shnn_micro::MicroError::Timeout => EmbeddedError::Timeout,
+        shnn_micro::MicroError::HardwareFault => EmbeddedError::HardwareError,
+        _ => EmbeddedError::InvalidConfig,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedded_network::{EmbeddedNetworkBuilder, EmbeddedTopology};
+    use crate::fixed_point::Q16_16;
+    use std::vec;
+
+    fn network() -> EmbeddedSNN<Q16_16> {
+        EmbeddedNetworkBuilder::<Q16_16>::new(Q16_16::from_float(0.001))
+            .topology(EmbeddedTopology::Feedforward)
+            .add_layer(4).unwrap()
+            .add_layer(4).unwrap()
+            .add_layer(2).unwrap()
+            .build().unwrap()
+    }
+
+    #[test]
+    fn test_tick_work_cycles() {
+        let work = TickWork { neuron_updates: 2, synapse_scans: 10, synaptic_events: 1, spikes: 1, inputs: 1 };
+        let c = CycleCosts::CORTEX_M4;
+        assert_eq!(work.cycles(&c), 250 + 2 * 35 + 10 * 4 + 18 + 40 + 12);
+        assert_eq!(McuProfile::by_name("STM32F411").unwrap().cycles_per_tick(1000), 100_000);
+    }
+
+    #[test]
+    fn test_embedded_network_fits_fast_part() {
+        let emulator = Emulator::new(McuProfile::by_name("stm32f411").unwrap(), 1000);
+        let mut net = network();
+        let report = emulator.run_embedded(&mut net, 50, |_| vec![Q16_16::from_float(20.0); 4]).unwrap();
+        assert_eq!(report.ticks, 50);
+        assert!(report.max_cycles > 0 && report.mean_cycles <= report.max_cycles as f64);
+        assert!(report.fits(), "{}", report);
+    }
+
+    #[test]
+    fn test_overrun_on_slow_clock() {
+        let slow = McuProfile { name: "slow", clock_hz: 1_000_000, ram_bytes: 1 << 20, costs: CycleCosts::CORTEX_M0 };
+        let mut net = network();
+        let report = Emulator::new(slow, 100).run_embedded(&mut net, 10, |_| vec![Q16_16::one(); 4]).unwrap();
+        assert_eq!(report.budget_cycles, 100);
+        assert_eq!(report.overruns, 10);
+        assert!(!report.fits());
+    }
+
+    #[test]
+    fn test_micro_network_runs() {
+        let mut net: shnn_micro::MicroNetwork<4, 8> = shnn_micro::MicroNetwork::new();
+        let a = net.add_neuron(shnn_micro::LIFNeuron::new_default()).unwrap();
+        let b = net.add_neuron(shnn_micro::LIFNeuron::new_default()).unwrap();
+        net.add_connection(a, b, shnn_micro::Scalar::from_float(0.5)).unwrap();
+
+        let emulator = Emulator::new(McuProfile::by_name("rp2040").unwrap(), 1000);
+        let report = emulator.run_micro(&mut net, 20, |_| vec![shnn_micro::Scalar::from_float(1.0)]).unwrap();
+        assert_eq!(report.ticks, 20);
+        assert_eq!(report.memory_bytes, shnn_micro::MicroNetwork::<4, 8>::memory_usage());
+        assert!(report.fits());
+    }
+}
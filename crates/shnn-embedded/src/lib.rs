@@ -44,8 +44,8 @@
 #[cfg(any(test, feature = "std"))]
 extern crate std;
 
-// Optional allocator support
-#[cfg(feature = "alloc")]
+// Optional allocator support (NIR loading parses into heap collections)
+#[cfg(any(feature = "alloc", feature = "nir"))]
 extern crate alloc;
 
 #[cfg(feature = "alloc")]
@@ -67,6 +67,7 @@ pub mod embedded_neuron;
 pub mod embedded_network;
 pub mod embedded_memory;
 pub mod hal;
+pub mod spike_queue;
 
  // Optional modules based on features
 #[cfg(feature = "rtic")]
@@ -75,6 +76,12 @@ pub mod rtic_support;
 #[cfg(feature = "partitioning")]
 pub mod partitioning;
 
+#[cfg(feature = "nir")]
+pub mod nir;
+
+#[cfg(feature = "emulator")]
+pub mod emulator;
+
 // Hardware-specific optimizations (future extensions)
 #[cfg(feature = "arm-math")]
 pub mod arm_optimizations;
@@ -92,7 +99,7 @@ pub use crate::{
     },
     embedded_network::{
         EmbeddedNetwork, EmbeddedSNN, EmbeddedTopology, EmbeddedNetworkBuilder,
-        NetworkStatistics, EmbeddedNeuronWrapper
+        NetworkStatistics, EmbeddedNeuronWrapper, PlasticityConfig
     },
     embedded_memory::{
         EmbeddedHypergraph, EmbeddedSpikeBuffer, EmbeddedWeightMatrix,
@@ -102,13 +109,21 @@ pub use crate::{
         EmbeddedHAL, HardwareTimer, GpioPin, AnalogToDigital, PulseWidthModulation,
         HALFactory, PlatformInfo
     },
+    spike_queue::{InputEvent, SpikeInputQueue, SpikeProducer, SpikeConsumer, DrainStats},
 };
 
 #[cfg(feature = "partitioning")]
 pub use crate::partitioning::{
-    PartitionId, PartitionMap, PartitionedSpikeQueues, MAX_PARTITIONS, PER_PARTITION_SPIKES
+    PartitionId, PartitionMap, PartitionedSpikeQueues, PartitionScheduler, PartitionPlan,
+    SchedulePolicy, MAX_PARTITIONS, PER_PARTITION_SPIKES
 };
 
+#[cfg(feature = "nir")]
+pub use crate::nir::{lower_module, load_text, verify_embedded, EmbeddedProgram, NirError};
+
+#[cfg(feature = "emulator")]
+pub use crate::emulator::{CycleCosts, EmulationReport, Emulator, McuProfile, TickWork};
+
 #[cfg(feature = "rtic")]
 pub use crate::rtic_support::{
     RTICScheduler, RTICTaskConfig, RTICTaskType, RTICStatistics,
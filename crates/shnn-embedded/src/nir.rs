@@ -0,0 +1,316 @@
+//! NIR → EmbeddedSNN lowering (reduced compiler subset)
+//!
+//! Instantiates a textual NIR program directly on the device instead of a
+//! parallel hand-coded network description. Only the ops that map onto
+//! [`EmbeddedSNN`] are accepted; anything else fails [`verify_embedded`]:
+//!
+//! - `neuron.lif@v1`: parameters for every neuron (refractory and membrane
+//!   time constants in seconds, potentials in mV)
+//! - `connectivity.layer_fully_connected@v1`, `connectivity.synapse_connect@v1`:
+//!   weights copied as-is, delays rounded to whole steps
+//! - `plasticity.stdp@v1`: enables on-device STDP (embedded trace constants)
+//! - `runtime.simulate.run@v1`: dt and the number of steps
+//!
+//! Neuron ids must be below [`MAX_NEURONS`]; every id up to the largest one
+//! referenced is instantiated so ids index the network directly. Needs `alloc`
+//! for the parsed module; the lowered network itself is heap-free.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use shnn_ir::{AttributeValue, DialectKey, Module, Operation, OpVersion};
+
+use crate::{
+    embedded_network::{EmbeddedNeuronWrapper, EmbeddedSNN, EmbeddedTopology, MAX_NEURONS},
+    embedded_neuron::{EmbeddedLIFNeuron, EmbeddedSynapse},
+    error::EmbeddedError,
+    fixed_point::FixedPoint,
+};
+
+/// (dialect, op, version) accepted by the embedded lowering
+pub const SUPPORTED_OPS: &[(&str, &str, u16)] = &[
+    ("neuron", "lif", 1),
+    ("connectivity", "layer_fully_connected", 1),
+    ("connectivity", "synapse_connect", 1),
+    ("plasticity", "stdp", 1),
+    ("runtime", "simulate.run", 1),
+];
+
+/// NIR verification/lowering errors
+#[derive(Debug, Clone, PartialEq)]
+pub enum NirError {
+    /// Textual NIR could not be parsed
+    Parse(String),
+    /// Op outside [`SUPPORTED_OPS`]
+    UnsupportedOp(String),
+    /// Required attribute missing or of the wrong kind
+    BadAttr {
+        /// Op header (`dialect.name@vN`)
+        op: String,
+        /// Attribute key
+        attr: &'static str,
+    },
+    /// Neuron id does not fit the network capacity
+    NeuronOutOfRange(u32),
+    /// Module has no `runtime.simulate.run@v1`
+    MissingRun,
+    /// Network capacity exceeded while instantiating
+    Network(EmbeddedError),
+}
+
+impl fmt::Display for NirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(msg) => write!(f, "NIR parse error: {}", msg),
+            Self::UnsupportedOp(op) => write!(f, "{} is not supported on embedded targets", op),
+            Self::BadAttr { op, attr } => write!(f, "missing or invalid attribute '{}' in {}", attr, op),
+            Self::NeuronOutOfRange(id) => write!(f, "neuron {} exceeds embedded capacity ({})", id, MAX_NEURONS),
+            Self::MissingRun => write!(f, "missing runtime.simulate.run@v1 op"),
+            Self::Network(e) => write!(f, "network construction failed: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NirError {}
+
+impl From<EmbeddedError> for NirError {
+    fn from(e: EmbeddedError) -> Self {
+        NirError::Network(e)
+    }
+}
+
+/// Embedded network lowered from NIR
+#[derive(Debug)]
+pub struct EmbeddedProgram<T: FixedPoint> {
+    /// Instantiated network (time in seconds)
+    pub network: EmbeddedSNN<T>,
+    /// Steps covering the run's duration
+    pub steps: u32,
+}
+
+fn header(op: &Operation) -> String {
+    alloc::format!("{}.{}@{}", op.dialect, op.name, op.version)
+}
+
+fn bad_attr(op: &Operation, attr: &'static str) -> NirError {
+    NirError::BadAttr { op: header(op), attr }
+}
+
+fn duration_s(op: &Operation, attr: &'static str) -> Result<f32, NirError> {
+    match op.attrs.get(attr) {
+        Some(AttributeValue::DurationNs(ns)) => Ok(*ns as f32 / 1e9),
+        _ => Err(bad_attr(op, attr)),
+    }
+}
+
+fn duration_ns(op: &Operation, attr: &'static str) -> Result<u64, NirError> {
+    match op.attrs.get(attr) {
+        Some(AttributeValue::DurationNs(ns)) => Ok(*ns),
+        _ => Err(bad_attr(op, attr)),
+    }
+}
+
+fn voltage(op: &Operation, attr: &'static str) -> Result<f32, NirError> {
+    match op.attrs.get(attr) {
+        Some(AttributeValue::VoltageMv(mv)) => Ok(*mv),
+        _ => Err(bad_attr(op, attr)),
+    }
+}
+
+fn weight(op: &Operation) -> Result<f32, NirError> {
+    match op.attrs.get("weight") {
+        Some(AttributeValue::Weight(w)) | Some(AttributeValue::F32(w)) => Ok(*w),
+        _ => Err(bad_attr(op, "weight")),
+    }
+}
+
+fn range(op: &Operation, attr: &'static str) -> Result<(u32, u32), NirError> {
+    match op.attrs.get(attr) {
+        Some(AttributeValue::RangeU32 { start, end }) if start <= end => Ok((*start, *end)),
+        _ => Err(bad_attr(op, attr)),
+    }
+}
+
+fn neuron_ref(op: &Operation, attr: &'static str) -> Result<u32, NirError> {
+    match op.attrs.get(attr) {
+        Some(AttributeValue::NeuronRef(id)) => Ok(*id),
+        _ => Err(bad_attr(op, attr)),
+    }
+}
+
+fn check_neuron(id: u32) -> Result<u16, NirError> {
+    if (id as usize) < MAX_NEURONS {
+        Ok(id as u16)
+    } else {
+        Err(NirError::NeuronOutOfRange(id))
+    }
+}
+
+fn is_supported(op: &Operation) -> bool {
+    let dialect = op.dialect.to_string();
+    SUPPORTED_OPS.iter().any(|&(d, n, v)| d == dialect && n == op.name && OpVersion(v) == op.version)
+}
+
+/// Check that every op is in the embedded subset with well-formed attributes,
+/// neuron ids fit [`MAX_NEURONS`], and a run op sets dt
+pub fn verify_embedded(module: &Module) -> Result<(), NirError> {
+    let mut has_run = false;
+    for op in &module.ops {
+        if !is_supported(op) {
+            return Err(NirError::UnsupportedOp(header(op)));
+        }
+        match (&op.dialect, op.name.as_str()) {
+            (DialectKey::Neuron, "lif") => {
+                for attr in ["tau_m", "t_refrac"] {
+                    duration_ns(op, attr)?;
+                }
+                for attr in ["v_rest", "v_reset", "v_thresh"] {
+                    voltage(op, attr)?;
+                }
+            }
+            (DialectKey::Connectivity, "layer_fully_connected") => {
+                for attr in ["in", "out"] {
+                    let (_, end) = range(op, attr)?;
+                    check_neuron(end)?;
+                }
+                weight(op)?;
+                duration_ns(op, "delay")?;
+            }
+            (DialectKey::Connectivity, "synapse_connect") => {
+                check_neuron(neuron_ref(op, "pre")?)?;
+                check_neuron(neuron_ref(op, "post")?)?;
+                weight(op)?;
+                duration_ns(op, "delay")?;
+            }
+            (DialectKey::Runtime, "simulate.run") => {
+                if duration_ns(op, "dt")? == 0 {
+                    return Err(bad_attr(op, "dt"));
+                }
+                duration_ns(op, "duration")?;
+                has_run = true;
+            }
+            _ => {}
+        }
+    }
+    if has_run { Ok(()) } else { Err(NirError::MissingRun) }
+}
+
+/// Verify and instantiate a module as an [`EmbeddedSNN`]
+pub fn lower_module<T: FixedPoint>(module: &Module) -> Result<EmbeddedProgram<T>, NirError> {
+    verify_embedded(module)?;
+
+    let run = module.ops.iter()
+        .find(|op| op.dialect == DialectKey::Runtime && op.name == "simulate.run")
+        .ok_or(NirError::MissingRun)?;
+    let dt_ns = duration_ns(run, "dt")?;
+    let steps = (duration_ns(run, "duration")? / dt_ns) as u32;
+    let delay_steps = |op: &Operation| -> Result<u16, NirError> {
+        let steps = (duration_ns(op, "delay")? + dt_ns / 2) / dt_ns;
+        u16::try_from(steps).map_err(|_| bad_attr(op, "delay"))
+    };
+
+    // Synapses first: they determine the neuron count
+    let mut synapses: heapless::Vec<EmbeddedSynapse<T>, { crate::embedded_network::MAX_SYNAPSES }> = heapless::Vec::new();
+    let mut max_id: Option<u16> = None;
+    let mut lif: Option<&Operation> = None;
+    let mut plasticity = false;
+    for op in &module.ops {
+        match (&op.dialect, op.name.as_str()) {
+            (DialectKey::Neuron, "lif") => lif = Some(op),
+            (DialectKey::Plasticity, "stdp") => plasticity = true,
+            (DialectKey::Connectivity, "layer_fully_connected") => {
+                let (in_start, in_end) = range(op, "in")?;
+                let (out_start, out_end) = range(op, "out")?;
+                let (w, delay) = (T::from_float(weight(op)?), delay_steps(op)?);
+                for pre in in_start..=in_end {
+                    for post in out_start..=out_end {
+                        synapses.push(EmbeddedSynapse::new(pre as u16, post as u16, w, delay))
+                            .map_err(|_| EmbeddedError::BufferFull)?;
+                    }
+                }
+                max_id = max_id.max(Some(in_end.max(out_end) as u16));
+            }
+            (DialectKey::Connectivity, "synapse_connect") => {
+                let (pre, post) = (check_neuron(neuron_ref(op, "pre")?)?, check_neuron(neuron_ref(op, "post")?)?);
+                synapses.push(EmbeddedSynapse::new(pre, post, T::from_float(weight(op)?), delay_steps(op)?))
+                    .map_err(|_| EmbeddedError::BufferFull)?;
+                max_id = max_id.max(Some(pre.max(post)));
+            }
+            _ => {}
+        }
+    }
+
+    let mut network = EmbeddedSNN::new(T::from_float(dt_ns as f32 / 1e9), EmbeddedTopology::Custom);
+    network.set_plasticity_enabled(plasticity);
+    for id in max_id.map(|max| 0..=max).into_iter().flatten() {
+        let neuron = match lif {
+            Some(op) => EmbeddedLIFNeuron::with_parameters(
+                id,
+                T::from_float(voltage(op, "v_rest")?),
+                T::from_float(voltage(op, "v_thresh")?),
+                T::from_float(voltage(op, "v_reset")?),
+                T::from_float(duration_s(op, "tau_m")?),
+                T::from_float(duration_s(op, "t_refrac")?),
+            ),
+            None => EmbeddedLIFNeuron::new(id),
+        };
+        network.add_neuron(EmbeddedNeuronWrapper::LIF(neuron))?;
+    }
+    for synapse in synapses {
+        network.add_synapse(synapse)?;
+    }
+    Ok(EmbeddedProgram { network, steps })
+}
+
+/// Parse textual NIR and lower it with [`lower_module`]
+pub fn load_text<T: FixedPoint>(text: &str) -> Result<EmbeddedProgram<T>, NirError> {
+    let module = shnn_ir::parse_text(text).map_err(|e| NirError::Parse(e.to_string()))?;
+    lower_module(&module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedded_network::EmbeddedNetwork;
+    use crate::fixed_point::Q16_16;
+    use shnn_ir::{
+        layer_fully_connected_v1, lif_neuron_v1, runtime_simulate_run_v1, stimulus_poisson_v1, synapse_connect_v1,
+    };
+
+    fn module() -> Module {
+        let mut m = Module::new();
+        m.push(lif_neuron_v1(20.0, -70.0, -75.0, -55.0, 2.0, 10.0, 1.0));
+        m.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+        m.push(synapse_connect_v1(3, 4, 0.25, 0.0));
+        m.push(runtime_simulate_run_v1(1.0, 50.0, false, None));
+        m
+    }
+
+    #[test]
+    fn test_lower_module_instantiates_network() {
+        let mut program = load_text::<Q16_16>(&module().to_text()).unwrap();
+        assert_eq!(program.steps, 50);
+        // Neurons 0..=4 and 4 + 1 synapses
+        assert_eq!(program.network.get_statistics().total_spikes, 0);
+        for _ in 0..program.steps {
+            program.network.update(&[]).unwrap();
+        }
+        assert!(program.network.current_time().to_float() > 0.049);
+    }
+
+    #[test]
+    fn test_verify_rejects_ops_outside_subset() {
+        let mut m = module();
+        m.push(stimulus_poisson_v1(0, 10.0, 0.0, 0.0, 10.0));
+        assert!(matches!(verify_embedded(&m), Err(NirError::UnsupportedOp(op)) if op == "stimulus.poisson@v1"));
+
+        let mut m = module();
+        m.push(synapse_connect_v1(0, MAX_NEURONS as u32, 1.0, 0.0));
+        assert_eq!(verify_embedded(&m), Err(NirError::NeuronOutOfRange(MAX_NEURONS as u32)));
+
+        let mut m = module();
+        m.ops.retain(|op| op.name != "simulate.run");
+        assert_eq!(verify_embedded(&m), Err(NirError::MissingRun));
+    }
+}
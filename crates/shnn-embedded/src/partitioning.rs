@@ -2,11 +2,14 @@
 //!
 //! Provides:
 //! - PartitionId: small integer partition identifiers
-//! - PartitionMap: neuron_id -> partition mapping
+//! - PartitionMap: neuron_id -> partition mapping (convertible from
+//!   `shnn_core::connectivity::PartitionMap`, the balanced partitioner's output)
 //! - PartitionedSpikeQueues: per-partition fixed-capacity spike buckets
+//! - PartitionScheduler: per-tick drain budgets with round-robin or priority policies
 //!
 //! This module is gated by the `partitioning` feature.
 
+use crate::error::EmbeddedError;
 use crate::fixed_point::{FixedPoint, FixedSpike};
 use heapless::{Deque, FnvIndexMap, Vec};
 
@@ -67,6 +70,29 @@ impl PartitionMap {
     }
 }
 
+/// Adopt a host-side partitioning (e.g. from `shnn_core::connectivity::partition`).
+///
+/// Fails with `InvalidConfig` if it uses more than `MAX_PARTITIONS` partitions,
+/// `InvalidNeuronId` if a neuron ID does not fit in `u16`, and
+/// `BufferOverflow` if it assigns more neurons than the map holds.
+impl TryFrom<&shnn_core::connectivity::PartitionMap> for PartitionMap {
+    type Error = EmbeddedError;
+
+    fn try_from(source: &shnn_core::connectivity::PartitionMap) -> Result<Self, Self::Error> {
+        if source.parts() > MAX_PARTITIONS {
+            return Err(EmbeddedError::InvalidConfig);
+        }
+        let mut map = Self::new();
+        for (neuron, pid) in source.iter() {
+            let neuron_id = u16::try_from(neuron.raw()).map_err(|_| EmbeddedError::InvalidNeuronId)?;
+            if !map.assign(neuron_id, pid as PartitionId) {
+                return Err(EmbeddedError::BufferOverflow);
+            }
+        }
+        Ok(map)
+    }
+}
+
 /// Fixed-capacity per-partition spike queues.
 /// Implements drop-oldest on overflow to preserve most recent activity.
 #[derive(Debug)]
@@ -132,6 +158,155 @@ impl<T: FixedPoint> PartitionedSpikeQueues<T> {
             while b.pop_front().is_some() {}
         }
     }
+
+    /// Queued spikes of every partition.
+    pub fn backlog(&self) -> [usize; MAX_PARTITIONS] {
+        core::array::from_fn(|idx| self.buckets[idx].len())
+    }
+}
+
+/// Order in which partitions are drained each tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulePolicy {
+    /// Rotate the first partition every tick
+    RoundRobin,
+    /// Highest priority first; partitions left waiting gain one priority
+    /// level per tick until served (aging), so none starves
+    Priority,
+}
+
+/// Per-tick drain plan: partitions in service order with their spike allowance
+pub type PartitionPlan = Vec<(PartitionId, usize), MAX_PARTITIONS>;
+
+/// Scheduler deciding how many deferred spikes each partition delivers per tick.
+///
+/// Each tick the scheduler serves partitions in policy order, granting each
+/// at most its own budget until the tick budget is spent. A partition with a
+/// budget of 0 is paused.
+#[derive(Debug, Clone)]
+pub struct PartitionScheduler {
+    policy: SchedulePolicy,
+    tick_budget: usize,
+    budgets: [usize; MAX_PARTITIONS],
+    priorities: [u8; MAX_PARTITIONS],
+    /// Ticks each partition has waited with a backlog but no allowance
+    waiting: [u32; MAX_PARTITIONS],
+    /// First partition of the next round-robin tick
+    cursor: usize,
+}
+
+impl PartitionScheduler {
+    /// Round-robin scheduler delivering at most `tick_budget` spikes per tick
+    pub fn new(tick_budget: usize) -> Self {
+        Self {
+            policy: SchedulePolicy::RoundRobin,
+            tick_budget,
+            budgets: [PER_PARTITION_SPIKES; MAX_PARTITIONS],
+            priorities: [0; MAX_PARTITIONS],
+            waiting: [0; MAX_PARTITIONS],
+            cursor: 0,
+        }
+    }
+
+    /// Set the scheduling policy
+    pub fn with_policy(mut self, policy: SchedulePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Current policy
+    pub fn policy(&self) -> SchedulePolicy {
+        self.policy
+    }
+
+    /// Change the scheduling policy
+    pub fn set_policy(&mut self, policy: SchedulePolicy) {
+        self.policy = policy;
+    }
+
+    /// Total spikes delivered per tick across all partitions
+    pub fn tick_budget(&self) -> usize {
+        self.tick_budget
+    }
+
+    /// Set the total per-tick budget
+    pub fn set_tick_budget(&mut self, budget: usize) {
+        self.tick_budget = budget;
+    }
+
+    /// Per-tick budget of a partition (0 if out of range)
+    pub fn budget(&self, pid: PartitionId) -> usize {
+        self.budgets.get(pid as usize).copied().unwrap_or(0)
+    }
+
+    /// Set a partition's per-tick budget. Returns false if pid is out of range.
+    pub fn set_budget(&mut self, pid: PartitionId, budget: usize) -> bool {
+        match self.budgets.get_mut(pid as usize) {
+            Some(slot) => {
+                *slot = budget;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Priority of a partition (0 if out of range)
+    pub fn priority(&self, pid: PartitionId) -> u8 {
+        self.priorities.get(pid as usize).copied().unwrap_or(0)
+    }
+
+    /// Set a partition's priority (higher is served first under
+    /// [`SchedulePolicy::Priority`]). Returns false if pid is out of range.
+    pub fn set_priority(&mut self, pid: PartitionId, priority: u8) -> bool {
+        match self.priorities.get_mut(pid as usize) {
+            Some(slot) => {
+                *slot = priority;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget round-robin position and aging state
+    pub fn reset(&mut self) {
+        self.waiting = [0; MAX_PARTITIONS];
+        self.cursor = 0;
+    }
+
+    /// Plan one tick for the given per-partition backlog.
+    ///
+    /// Returns the partitions with a non-zero allowance in service order and
+    /// advances the round-robin cursor and aging counters.
+    pub fn plan(&mut self, backlog: &[usize; MAX_PARTITIONS]) -> PartitionPlan {
+        let mut order: Vec<usize, MAX_PARTITIONS> = Vec::new();
+        for offset in 0..MAX_PARTITIONS {
+            let _ = order.push((self.cursor + offset) % MAX_PARTITIONS);
+        }
+        if self.policy == SchedulePolicy::Priority {
+            // Ties keep the rotating order
+            let cursor = self.cursor;
+            let (priorities, waiting) = (&self.priorities, &self.waiting);
+            order.sort_unstable_by_key(|&idx| {
+                let effective = priorities[idx] as u32 + waiting[idx];
+                (core::cmp::Reverse(effective), (idx + MAX_PARTITIONS - cursor) % MAX_PARTITIONS)
+            });
+        }
+
+        let mut plan = PartitionPlan::new();
+        let mut remaining = self.tick_budget;
+        for idx in order {
+            let allowed = backlog[idx].min(self.budgets[idx]).min(remaining);
+            if allowed > 0 {
+                remaining -= allowed;
+                let _ = plan.push((idx as PartitionId, allowed));
+                self.waiting[idx] = 0;
+            } else if backlog[idx] > 0 && self.budgets[idx] > 0 {
+                self.waiting[idx] = self.waiting[idx].saturating_add(1);
+            }
+        }
+        self.cursor = (self.cursor + 1) % MAX_PARTITIONS;
+        plan
+    }
 }
 
 #[cfg(test)]
@@ -149,6 +324,25 @@ mod tests {
         assert_eq!(pm.len(), 1);
     }
 
+    #[test]
+    fn test_partition_map_from_core_partitioner() {
+        use shnn_core::connectivity::{partition, PartitionConfig, Topology};
+        use shnn_core::spike::NeuronId;
+
+        // Two 3-neuron chains joined by one synapse
+        let edges = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]
+            .map(|(s, t)| (NeuronId::new(s), NeuronId::new(t)));
+        let topology = Topology::from_edges([], edges);
+        let core_map = partition(&topology, &PartitionConfig::new(2)).unwrap();
+
+        let pm = PartitionMap::try_from(&core_map).unwrap();
+        assert_eq!(pm.len(), 6);
+        assert_eq!((pm.get(0), pm.get(2), pm.get(3), pm.get(5)), (Some(0), Some(0), Some(1), Some(1)));
+
+        let too_many = partition(&topology, &PartitionConfig::new(MAX_PARTITIONS + 1)).unwrap();
+        assert_eq!(PartitionMap::try_from(&too_many).unwrap_err(), EmbeddedError::InvalidConfig);
+    }
+
     #[test]
     fn test_partitioned_spike_queues_push_and_drain() {
         let mut qs = PartitionedSpikeQueues::<Q16_16>::new();
@@ -164,4 +358,56 @@ mod tests {
         assert!(drained.len() <= PER_PARTITION_SPIKES);
         assert!(qs.is_empty(pid));
     }
+
+    /// Ticks until `pid` is first granted an allowance while every partition stays backlogged
+    fn ticks_until_served(scheduler: &mut PartitionScheduler, pid: PartitionId) -> Option<usize> {
+        let backlog = [PER_PARTITION_SPIKES; MAX_PARTITIONS];
+        (1..=1000).find(|_| scheduler.plan(&backlog).iter().any(|&(p, _)| p == pid))
+    }
+
+    #[test]
+    fn test_scheduler_respects_budgets() {
+        let mut scheduler = PartitionScheduler::new(10);
+        scheduler.set_budget(0, 3);
+        assert!(!scheduler.set_budget(MAX_PARTITIONS as PartitionId, 1));
+        let mut backlog = [0; MAX_PARTITIONS];
+        backlog[0] = 5;
+        backlog[1] = 20;
+        backlog[2] = 4;
+
+        let plan = scheduler.plan(&backlog);
+        assert_eq!(plan.as_slice(), &[(0, 3), (1, 7)]);
+        // Next tick starts from partition 1
+        let plan = scheduler.plan(&backlog);
+        assert_eq!(plan.as_slice(), &[(1, 10)]);
+    }
+
+    #[test]
+    fn test_round_robin_is_starvation_free() {
+        // A tick budget of one spike serves a single partition per tick
+        let mut scheduler = PartitionScheduler::new(1);
+        for pid in 0..MAX_PARTITIONS as PartitionId {
+            let ticks = ticks_until_served(&mut scheduler, pid).unwrap();
+            assert!(ticks <= MAX_PARTITIONS);
+        }
+    }
+
+    #[test]
+    fn test_priority_orders_and_ages() {
+        let mut scheduler = PartitionScheduler::new(1).with_policy(SchedulePolicy::Priority);
+        scheduler.set_priority(3, 200);
+        scheduler.set_priority(5, 255);
+        let mut backlog = [0; MAX_PARTITIONS];
+        backlog[3] = 1;
+        backlog[5] = 1;
+        assert_eq!(scheduler.plan(&backlog).as_slice(), &[(5, 1)]);
+
+        // Partition 0 has the lowest priority but is still served eventually
+        let ticks = ticks_until_served(&mut scheduler, 0).unwrap();
+        assert!(ticks <= 256 + MAX_PARTITIONS);
+
+        // Paused partitions are never served
+        scheduler.set_budget(1, 0);
+        assert_eq!(ticks_until_served(&mut scheduler, 1), None);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,261 @@
+//! Interrupt-driven spike input queue
+//!
+//! [`SpikeInputQueue`] is a lock-free single-producer/single-consumer ring
+//! (`heapless::spsc`) for feeding external events into an [`EmbeddedSNN`].
+//! Interrupt handlers (DMA completion, event cameras, encoders) push
+//! [`InputEvent`]s through a [`SpikeProducer`]; the main loop drains them with
+//! a [`SpikeConsumer`] before each network update. Pushing never blocks:
+//! events that arrive while the queue is full are dropped and counted.
+//!
+//! ```ignore
+//! static mut QUEUE: SpikeInputQueue<Q16_16, 64> = SpikeInputQueue::new();
+//! let (producer, mut consumer) = unsafe { QUEUE.split() };
+//! // ISR: producer.push(InputEvent::binary(pixel));
+//! // main loop: consumer.update(&mut network)?;
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use heapless::spsc::{Consumer, Producer, Queue};
+use heapless::Vec;
+
+use crate::{
+    error::EmbeddedResult,
+    fixed_point::{FixedPoint, FixedSpike},
+    embedded_network::{EmbeddedNetwork, EmbeddedSNN, MAX_NEURONS, MAX_SPIKES_PER_STEP},
+};
+
+/// External input event for one input channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputEvent<T: FixedPoint> {
+    /// Input channel (index into the network's input neurons)
+    pub channel: u16,
+    /// Input current added to the channel on the next update
+    pub value: T,
+}
+
+impl<T: FixedPoint> InputEvent<T> {
+    /// Event with an explicit input current
+    pub fn new(channel: u16, value: T) -> Self {
+        Self { channel, value }
+    }
+
+    /// Unit event (e.g. a single event-camera pixel)
+    pub fn binary(channel: u16) -> Self {
+        Self::new(channel, T::one())
+    }
+}
+
+/// Result of draining the queue into an input vector
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrainStats {
+    /// Events accumulated into the inputs
+    pub events: usize,
+    /// Events discarded because their channel has no input neuron
+    pub out_of_range: usize,
+}
+
+/// Lock-free SPSC spike input queue holding up to `N - 1` events
+pub struct SpikeInputQueue<T: FixedPoint, const N: usize> {
+    queue: Queue<InputEvent<T>, N>,
+    overflows: AtomicU32,
+}
+
+impl<T: FixedPoint, const N: usize> SpikeInputQueue<T, N> {
+    /// Create an empty queue (usable in a `static`)
+    pub const fn new() -> Self {
+        Self {
+            queue: Queue::new(),
+            overflows: AtomicU32::new(0),
+        }
+    }
+
+    /// Maximum number of queued events
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+
+    /// Split into the interrupt-side producer and main-loop consumer
+    pub fn split(&mut self) -> (SpikeProducer<'_, T, N>, SpikeConsumer<'_, T, N>) {
+        let Self { queue, overflows } = self;
+        let overflows: &AtomicU32 = overflows;
+        let (producer, consumer) = queue.split();
+        (
+            SpikeProducer { inner: producer, overflows },
+            SpikeConsumer { inner: consumer, overflows, reported: 0 },
+        )
+    }
+}
+
+impl<T: FixedPoint, const N: usize> Default for SpikeInputQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Interrupt-side handle of a [`SpikeInputQueue`]
+pub struct SpikeProducer<'a, T: FixedPoint, const N: usize> {
+    inner: Producer<'a, InputEvent<T>, N>,
+    overflows: &'a AtomicU32,
+}
+
+impl<'a, T: FixedPoint, const N: usize> SpikeProducer<'a, T, N> {
+    /// Queue an event without blocking; returns false (and counts an
+    /// overflow) if the queue is full
+    pub fn push(&mut self, event: InputEvent<T>) -> bool {
+        match self.inner.enqueue(event) {
+            Ok(()) => true,
+            Err(_) => {
+                // Only the producer writes the counter, so a plain load/store
+                // is enough and works on cores without atomic RMW (Cortex-M0)
+                let count = self.overflows.load(Ordering::Relaxed);
+                self.overflows.store(count.wrapping_add(1), Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    /// True if the next push would overflow
+    pub fn is_full(&self) -> bool {
+        !self.inner.ready()
+    }
+
+    /// Total events dropped because the queue was full
+    pub fn overflows(&self) -> u32 {
+        self.overflows.load(Ordering::Relaxed)
+    }
+}
+
+/// Main-loop handle of a [`SpikeInputQueue`]
+pub struct SpikeConsumer<'a, T: FixedPoint, const N: usize> {
+    inner: Consumer<'a, InputEvent<T>, N>,
+    overflows: &'a AtomicU32,
+    reported: u32,
+}
+
+impl<'a, T: FixedPoint, const N: usize> SpikeConsumer<'a, T, N> {
+    /// Take the oldest queued event
+    pub fn pop(&mut self) -> Option<InputEvent<T>> {
+        self.inner.dequeue()
+    }
+
+    /// Number of queued events
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// True if no events are queued
+    pub fn is_empty(&self) -> bool {
+        !self.inner.ready()
+    }
+
+    /// Total events dropped because the queue was full
+    pub fn overflows(&self) -> u32 {
+        self.overflows.load(Ordering::Relaxed)
+    }
+
+    /// Events dropped since the previous call
+    pub fn take_overflows(&mut self) -> u32 {
+        let total = self.overflows();
+        let new = total.wrapping_sub(self.reported);
+        self.reported = total;
+        new
+    }
+
+    /// Accumulate queued events into `inputs` (one slot per input channel)
+    ///
+    /// At most `N - 1` events are taken, so an interrupt storm cannot keep
+    /// the main loop draining forever.
+    pub fn drain_into(&mut self, inputs: &mut [T]) -> DrainStats {
+        let mut stats = DrainStats::default();
+        for _ in 0..N.saturating_sub(1) {
+            let Some(event) = self.inner.dequeue() else { break };
+            match inputs.get_mut(event.channel as usize) {
+                Some(slot) => {
+                    *slot = slot.saturating_add(event.value);
+                    stats.events += 1;
+                }
+                None => stats.out_of_range += 1,
+            }
+        }
+        stats
+    }
+
+    /// Drain queued events into the network's inputs and advance it one step
+    pub fn update(&mut self, network: &mut EmbeddedSNN<T>) -> EmbeddedResult<Vec<FixedSpike<T>, MAX_SPIKES_PER_STEP>> {
+        let mut inputs: Vec<T, MAX_NEURONS> = Vec::new();
+        // input_count() never exceeds MAX_NEURONS
+        let _ = inputs.resize(network.input_count(), T::zero());
+        self.drain_into(&mut inputs);
+        network.update(&inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedded_network::{EmbeddedNetworkBuilder, EmbeddedTopology};
+    use crate::fixed_point::Q16_16;
+
+    fn network() -> EmbeddedSNN<Q16_16> {
+        EmbeddedNetworkBuilder::<Q16_16>::new(Q16_16::from_float(0.001))
+            .topology(EmbeddedTopology::Feedforward)
+            .add_layer(2).unwrap()
+            .add_layer(1).unwrap()
+            .build().unwrap()
+    }
+
+    #[test]
+    fn test_full_queue_counts_overflows() {
+        let mut queue: SpikeInputQueue<Q16_16, 4> = SpikeInputQueue::new();
+        assert_eq!(queue.capacity(), 3);
+        let (mut producer, mut consumer) = queue.split();
+
+        for _ in 0..3 {
+            assert!(producer.push(InputEvent::binary(0)));
+        }
+        assert!(producer.is_full());
+        assert!(!producer.push(InputEvent::binary(1)));
+        assert!(!producer.push(InputEvent::binary(1)));
+        assert_eq!(producer.overflows(), 2);
+
+        assert_eq!(consumer.len(), 3);
+        assert_eq!(consumer.take_overflows(), 2);
+        assert_eq!(consumer.take_overflows(), 0);
+        assert_eq!(consumer.pop(), Some(InputEvent::binary(0)));
+        assert!(producer.push(InputEvent::binary(1)));
+        assert_eq!(consumer.overflows(), 2);
+    }
+
+    #[test]
+    fn test_drain_accumulates_per_channel() {
+        let mut queue: SpikeInputQueue<Q16_16, 8> = SpikeInputQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+        producer.push(InputEvent::new(0, Q16_16::from_float(0.5)));
+        producer.push(InputEvent::new(0, Q16_16::from_float(0.25)));
+        producer.push(InputEvent::binary(1));
+        producer.push(InputEvent::binary(5));
+
+        let mut inputs = [Q16_16::zero(); 2];
+        let stats = consumer.drain_into(&mut inputs);
+        assert_eq!(stats, DrainStats { events: 3, out_of_range: 1 });
+        assert_eq!(inputs, [Q16_16::from_float(0.75), Q16_16::one()]);
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn test_update_matches_direct_inputs() {
+        let mut direct = network();
+        direct.update(&[Q16_16::from_float(1.0), Q16_16::from_float(0.5)]).unwrap();
+
+        let mut queued = network();
+        let mut queue: SpikeInputQueue<Q16_16, 8> = SpikeInputQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+        producer.push(InputEvent::new(0, Q16_16::from_float(0.5)));
+        producer.push(InputEvent::new(1, Q16_16::from_float(0.5)));
+        producer.push(InputEvent::new(0, Q16_16::from_float(0.5)));
+        consumer.update(&mut queued).unwrap();
+
+        assert_eq!(queued.get_outputs(), direct.get_outputs());
+        assert!(consumer.is_empty());
+    }
+}
@@ -0,0 +1,183 @@
+//! Cart-pole balancing (classic Barto, Sutton & Anderson dynamics)
+//!
+//! Observation: `[x, x_dot, theta, theta_dot]`. Action: two scores, pushing
+//! left when `action[0] >= action[1]` and right otherwise. Every step the pole
+//! stays up earns a reward of 1.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{EnvError, Environment, Result, StepOutcome};
+
+/// Physical constants and episode limits
+#[derive(Debug, Clone, PartialEq)]
+pub struct CartPoleParams {
+    /// Gravity (m/s²)
+    pub gravity: f32,
+    /// Cart mass (kg)
+    pub cart_mass: f32,
+    /// Pole mass (kg)
+    pub pole_mass: f32,
+    /// Half the pole length (m)
+    pub half_length: f32,
+    /// Push force magnitude (N)
+    pub force: f32,
+    /// Control interval (s)
+    pub tau: f32,
+    /// Episode fails when |x| exceeds this (m)
+    pub x_limit: f32,
+    /// Episode fails when |theta| exceeds this (rad)
+    pub theta_limit: f32,
+    /// Episode ends after this many steps
+    pub max_steps: usize,
+}
+
+impl Default for CartPoleParams {
+    fn default() -> Self {
+        Self {
+            gravity: 9.8,
+            cart_mass: 1.0,
+            pole_mass: 0.1,
+            half_length: 0.5,
+            force: 10.0,
+            tau: 0.02,
+            x_limit: 2.4,
+            theta_limit: 12.0_f32.to_radians(),
+            max_steps: 500,
+        }
+    }
+}
+
+impl CartPoleParams {
+    /// Typical magnitude of each observation element, for scaling encoders:
+    /// the failure limits for positions and 2.0 for velocities
+    pub fn observation_scales(&self) -> Vec<f32> {
+        vec![self.x_limit, 2.0, self.theta_limit, 2.0]
+    }
+}
+
+/// Cart-pole reference environment
+#[derive(Debug, Clone)]
+pub struct CartPole {
+    params: CartPoleParams,
+    /// `[x, x_dot, theta, theta_dot]`
+    state: [f32; 4],
+    steps: usize,
+    rng: StdRng,
+}
+
+impl CartPole {
+    /// Cart-pole whose initial states are drawn from `seed`
+    pub fn new(params: CartPoleParams, seed: u64) -> Self {
+        Self { params, state: [0.0; 4], steps: 0, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    /// Current `[x, x_dot, theta, theta_dot]`
+    pub fn state(&self) -> [f32; 4] {
+        self.state
+    }
+
+    /// Steps taken in the current episode
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn failed(&self) -> bool {
+        self.state[0].abs() > self.params.x_limit || self.state[2].abs() > self.params.theta_limit
+    }
+}
+
+impl Environment for CartPole {
+    fn observation_size(&self) -> usize {
+        4
+    }
+
+    fn action_size(&self) -> usize {
+        2
+    }
+
+    fn reset(&mut self) -> Vec<f32> {
+        for value in &mut self.state {
+            *value = self.rng.gen_range(-0.05..0.05);
+        }
+        self.steps = 0;
+        self.state.to_vec()
+    }
+
+    fn step(&mut self, action: &[f32]) -> Result<StepOutcome> {
+        if action.len() != 2 {
+            return Err(EnvError::InvalidAction { expected: 2, got: action.len() });
+        }
+        let p = &self.params;
+        let force = if action[0] >= action[1] { -p.force } else { p.force };
+        let [x, x_dot, theta, theta_dot] = self.state;
+
+        let total_mass = p.cart_mass + p.pole_mass;
+        let pole_moment = p.pole_mass * p.half_length;
+        let (sin, cos) = theta.sin_cos();
+        let temp = (force + pole_moment * theta_dot * theta_dot * sin) / total_mass;
+        let theta_acc = (p.gravity * sin - cos * temp)
+            / (p.half_length * (4.0 / 3.0 - p.pole_mass * cos * cos / total_mass));
+        let x_acc = temp - pole_moment * theta_acc * cos / total_mass;
+
+        // Explicit Euler, as in the original formulation
+        self.state = [
+            x + p.tau * x_dot,
+            x_dot + p.tau * x_acc,
+            theta + p.tau * theta_dot,
+            theta_dot + p.tau * theta_acc,
+        ];
+        self.steps += 1;
+
+        let done = self.failed() || self.steps >= self.params.max_steps;
+        Ok(StepOutcome { observation: self.state.to_vec(), reward: 1.0, done })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_is_seeded_and_small() {
+        let mut a = CartPole::new(CartPoleParams::default(), 3);
+        let mut b = CartPole::new(CartPoleParams::default(), 3);
+        let obs = a.reset();
+        assert_eq!(obs, b.reset());
+        assert!(obs.iter().all(|v| v.abs() < 0.05));
+    }
+
+    #[test]
+    fn test_constant_push_fails_before_limit() {
+        let mut env = CartPole::new(CartPoleParams::default(), 1);
+        env.reset();
+        let mut total = 0.0;
+        let mut outcome = env.step(&[1.0, 0.0]).unwrap();
+        total += outcome.reward;
+        while !outcome.done {
+            outcome = env.step(&[1.0, 0.0]).unwrap();
+            total += outcome.reward;
+        }
+        // Always pushing left tips the pole within a couple of seconds
+        assert!(env.steps() < 100);
+        assert_eq!(total, env.steps() as f32);
+        assert!(env.state()[2].abs() > 0.2 || env.state()[0].abs() > 2.4);
+        assert!(matches!(env.step(&[1.0]), Err(EnvError::InvalidAction { expected: 2, got: 1 })));
+    }
+
+    #[test]
+    fn test_balancing_controller_reaches_max_steps() {
+        let params = CartPoleParams { max_steps: 200, ..Default::default() };
+        let mut env = CartPole::new(params, 2);
+        let mut obs = env.reset();
+        loop {
+            // Push towards the side the pole is falling to
+            let lean = obs[2] + 0.5 * obs[3];
+            let outcome = env.step(if lean > 0.0 { &[0.0, 1.0] } else { &[1.0, 0.0] }).unwrap();
+            obs = outcome.observation;
+            if outcome.done {
+                break;
+            }
+        }
+        assert_eq!(env.steps(), 200);
+    }
+}
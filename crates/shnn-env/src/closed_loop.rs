@@ -0,0 +1,161 @@
+//! Observe → encode → step network → decode → act driver
+
+use shnn_runtime::SimulationEngine;
+
+use crate::{Decoder, Encoder, EnvError, Environment, Result};
+
+/// Summary of one episode
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpisodeStats {
+    /// Actions applied to the environment
+    pub actions: usize,
+    /// Sum of rewards
+    pub total_reward: f32,
+    /// Whether the environment ended the episode (false if cut short by
+    /// `max_actions` or the engine's duration)
+    pub done: bool,
+    /// Network steps executed
+    pub network_steps: usize,
+    /// Output spikes emitted by the network
+    pub output_spikes: usize,
+}
+
+/// Couples an environment to a network through an encoder and a decoder
+#[derive(Debug, Clone)]
+pub struct ClosedLoop<E, Enc, Dec> {
+    env: E,
+    encoder: Enc,
+    decoder: Dec,
+    steps_per_action: usize,
+}
+
+impl<E: Environment, Enc: Encoder, Dec: Decoder> ClosedLoop<E, Enc, Dec> {
+    /// Loop running `steps_per_action` network steps per environment action
+    pub fn new(env: E, encoder: Enc, decoder: Dec, steps_per_action: usize) -> Result<Self> {
+        if encoder.observation_size() != env.observation_size() {
+            return Err(EnvError::Config(format!(
+                "encoder expects {} observation values, environment produces {}",
+                encoder.observation_size(),
+                env.observation_size()
+            )));
+        }
+        if decoder.action_size() != env.action_size() {
+            return Err(EnvError::Config(format!(
+                "decoder produces {} action values, environment expects {}",
+                decoder.action_size(),
+                env.action_size()
+            )));
+        }
+        if steps_per_action == 0 {
+            return Err(EnvError::Config("steps_per_action must be >= 1".into()));
+        }
+        Ok(Self { env, encoder, decoder, steps_per_action })
+    }
+
+    /// The driven environment
+    pub fn env(&self) -> &E {
+        &self.env
+    }
+
+    /// Run one episode from a reset engine and environment
+    ///
+    /// Stops when the environment is done, after `max_actions`, or when the
+    /// engine has fewer than `steps_per_action` steps of its duration left.
+    pub fn run_episode(&mut self, engine: &mut SimulationEngine, max_actions: usize) -> Result<EpisodeStats> {
+        engine.reset()?;
+        self.encoder.reset();
+        let mut observation = self.env.reset();
+        let dt_ns = engine.params().dt_ns;
+        let total_steps = engine.params().num_steps();
+
+        let mut stats = EpisodeStats { actions: 0, total_reward: 0.0, done: false, network_steps: 0, output_spikes: 0 };
+        let mut outputs = Vec::new();
+        while stats.actions < max_actions && total_steps - engine.current_step() >= self.steps_per_action {
+            outputs.clear();
+            for _ in 0..self.steps_per_action {
+                let time_ns = engine.current_step() as u64 * dt_ns;
+                let inputs = self.encoder.encode(&observation, time_ns, dt_ns);
+                engine.inject_spikes(&inputs)?;
+                engine.step()?;
+                outputs.extend(engine.drain_output_spikes());
+            }
+            stats.network_steps += self.steps_per_action;
+            stats.output_spikes += outputs.len();
+
+            let action = self.decoder.decode(&outputs);
+            let outcome = self.env.step(&action)?;
+            stats.actions += 1;
+            stats.total_reward += outcome.reward;
+            observation = outcome.observation;
+            if outcome.done {
+                stats.done = true;
+                break;
+            }
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CartPole, CartPoleParams, CountDecoder, RateEncoder};
+    use shnn_runtime::{NetworkBuilder, NetworkConfig, NeuronId, SimulationParams};
+
+    /// 8 input neurons wired one-to-one onto two output neurons by sign of theta
+    fn engine(duration_ns: u64) -> SimulationEngine {
+        // Fixed weights: STDP would depress the hand-wired controller
+        let config = NetworkConfig { plasticity_enabled: false, ..Default::default() };
+        let mut builder = NetworkBuilder::new().with_config(config).add_neurons(0, 10);
+        // theta > 0 (neuron 4) and theta_dot > 0 (neuron 6) drive "push right" (neuron 9)
+        for (input, output) in [(4, 9), (6, 9), (5, 8), (7, 8)] {
+            builder = builder.add_synapse(NeuronId::new(input), NeuronId::new(output), 1000.0, 0.1);
+        }
+        let network = builder.build().unwrap();
+        SimulationEngine::new(network, SimulationParams::new(100_000, duration_ns).unwrap()).unwrap()
+    }
+
+    fn closed_loop() -> ClosedLoop<CartPole, RateEncoder, CountDecoder> {
+        let params = CartPoleParams { max_steps: 100, ..Default::default() };
+        let encoder = RateEncoder::new(0, params.observation_scales(), 1000.0);
+        let env = CartPole::new(params, 4);
+        ClosedLoop::new(env, encoder, CountDecoder::new(8, 2, 1), 100).unwrap()
+    }
+
+    #[test]
+    fn test_wired_controller_balances_pole() {
+        let mut engine = engine(1_000_000_000);
+        let mut cl = closed_loop();
+        let stats = cl.run_episode(&mut engine, 1_000).unwrap();
+        assert!(stats.done);
+        assert_eq!(stats.actions, 100);
+        assert_eq!(stats.total_reward, 100.0);
+        assert_eq!(stats.network_steps, 10_000);
+        assert!(stats.output_spikes > 0);
+
+        // Episodes restart from a reset engine
+        let again = cl.run_episode(&mut engine, 10).unwrap();
+        assert_eq!((again.actions, again.done), (10, false));
+    }
+
+    #[test]
+    fn test_engine_duration_truncates_episode() {
+        // 25 ms = 250 steps: two full actions of 100 steps
+        let mut engine = engine(25_000_000);
+        let stats = closed_loop().run_episode(&mut engine, 1_000).unwrap();
+        assert_eq!((stats.actions, stats.done, stats.network_steps), (2, false, 200));
+    }
+
+    #[test]
+    fn test_mismatched_sizes_are_rejected() {
+        let env = CartPole::new(CartPoleParams::default(), 1);
+        let encoder = RateEncoder::new(0, vec![1.0; 3], 100.0);
+        assert!(matches!(
+            ClosedLoop::new(env.clone(), encoder, CountDecoder::new(8, 2, 1), 10),
+            Err(EnvError::Config(_))
+        ));
+        let encoder = RateEncoder::new(0, vec![1.0; 4], 100.0);
+        assert!(ClosedLoop::new(env.clone(), encoder.clone(), CountDecoder::new(8, 3, 1), 10).is_err());
+        assert!(ClosedLoop::new(env, encoder, CountDecoder::new(8, 2, 1), 0).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Reference spike encoder and decoder
+//!
+//! [`RateEncoder`] gives each observation value a pair of input neurons (positive
+//! and negative part) firing at a rate proportional to the value. Spikes are
+//! emitted by a deterministic phase accumulator, so closed loops need no seed.
+//! [`CountDecoder`] counts spikes in consecutive groups of output neurons.
+
+use shnn_runtime::{NeuronId, Spike, Time};
+
+use crate::{Decoder, Encoder};
+
+/// Observation-to-rate encoder over neurons `input_start..input_start + 2 * scales.len()`
+#[derive(Debug, Clone)]
+pub struct RateEncoder {
+    input_start: u32,
+    /// Value mapped to `max_rate_hz`, per observation element
+    scales: Vec<f32>,
+    max_rate_hz: f32,
+    /// Accumulated spike phase per input neuron
+    phase: Vec<f64>,
+}
+
+impl RateEncoder {
+    /// Encoder for observations whose element `i` saturates at `±scales[i]`
+    pub fn new(input_start: u32, scales: Vec<f32>, max_rate_hz: f32) -> Self {
+        let phase = vec![0.0; scales.len() * 2];
+        Self { input_start, scales, max_rate_hz, phase }
+    }
+
+    /// Input neuron for the positive (`negative = false`) or negative part of element `index`
+    pub fn neuron(&self, index: usize, negative: bool) -> NeuronId {
+        NeuronId::new(self.input_start + 2 * index as u32 + negative as u32)
+    }
+
+    /// Number of input neurons used
+    pub fn input_count(&self) -> usize {
+        self.phase.len()
+    }
+}
+
+impl Encoder for RateEncoder {
+    fn observation_size(&self) -> usize {
+        self.scales.len()
+    }
+
+    fn reset(&mut self) {
+        self.phase.iter_mut().for_each(|p| *p = 0.0);
+    }
+
+    fn encode(&mut self, observation: &[f32], time_ns: u64, dt_ns: u64) -> Vec<Spike> {
+        let dt_s = dt_ns as f64 / 1_000_000_000.0;
+        let mut spikes = Vec::new();
+        for (index, (&value, &scale)) in observation.iter().zip(&self.scales).enumerate() {
+            let level = (value / scale).clamp(-1.0, 1.0);
+            for negative in [false, true] {
+                let drive = if negative { -level } else { level };
+                let slot = 2 * index + negative as usize;
+                self.phase[slot] += (drive.max(0.0) * self.max_rate_hz) as f64 * dt_s;
+                // Tolerance keeps exact rates (e.g. 100 Hz at 1 ms) from losing a spike to rounding
+                if self.phase[slot] >= 1.0 - 1e-9 {
+                    self.phase[slot] -= 1.0;
+                    spikes.push(Spike::new(self.neuron(index, negative), Time::from_nanos(time_ns)));
+                }
+            }
+        }
+        spikes
+    }
+}
+
+/// Spike counts per group of `group_size` neurons starting at `output_start`
+#[derive(Debug, Clone)]
+pub struct CountDecoder {
+    output_start: u32,
+    groups: usize,
+    group_size: u32,
+}
+
+impl CountDecoder {
+    /// Decoder producing `groups` counts
+    pub fn new(output_start: u32, groups: usize, group_size: u32) -> Self {
+        Self { output_start, groups, group_size: group_size.max(1) }
+    }
+}
+
+impl Decoder for CountDecoder {
+    fn action_size(&self) -> usize {
+        self.groups
+    }
+
+    fn decode(&mut self, spikes: &[Spike]) -> Vec<f32> {
+        let mut counts = vec![0.0; self.groups];
+        for spike in spikes {
+            let Some(offset) = spike.neuron_id.raw().checked_sub(self.output_start) else { continue };
+            if let Some(count) = counts.get_mut((offset / self.group_size) as usize) {
+                *count += 1.0;
+            }
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_encoder_rates() {
+        let mut encoder = RateEncoder::new(10, vec![1.0, 2.0], 100.0);
+        assert_eq!(encoder.input_count(), 4);
+        // 1 s of 1 ms steps: element 0 at +0.5 (50 Hz), element 1 at -2.0 (saturated, 100 Hz)
+        let mut counts = [0usize; 4];
+        for step in 0..1000u64 {
+            for spike in encoder.encode(&[0.5, -4.0], step * 1_000_000, 1_000_000) {
+                counts[(spike.neuron_id.raw() - 10) as usize] += 1;
+            }
+        }
+        assert_eq!(counts, [50, 0, 0, 100]);
+
+        encoder.reset();
+        assert!(encoder.encode(&[1.0, 1.0], 0, 1_000_000).is_empty());
+    }
+
+    #[test]
+    fn test_count_decoder_groups() {
+        let mut decoder = CountDecoder::new(4, 2, 2);
+        let spike = |id: u32| Spike::new(NeuronId::new(id), Time::from_nanos(0));
+        let spikes = [spike(3), spike(4), spike(5), spike(7), spike(7), spike(8)];
+        assert_eq!(decoder.decode(&spikes), vec![2.0, 2.0]);
+    }
+}
@@ -0,0 +1,93 @@
+//! Closed-loop environments for spiking networks
+//!
+//! A closed loop alternates between an [`Environment`] and a running
+//! [`SimulationEngine`](shnn_runtime::SimulationEngine):
+//!
+//! 1. observe: the environment reports its state as a vector of floats
+//! 2. encode: an [`Encoder`] turns the observation into input spikes
+//! 3. step network: the spikes are injected while the engine advances
+//! 4. decode: a [`Decoder`] reduces the output spikes to an action vector
+//! 5. act: the environment applies the action and returns a reward
+//!
+//! [`ClosedLoop`] drives this cycle; [`CartPole`] is the reference environment.
+
+#![deny(missing_docs)]
+
+use shnn_runtime::{RuntimeError, Spike};
+
+pub mod cartpole;
+pub mod closed_loop;
+pub mod codec;
+
+pub use cartpole::{CartPole, CartPoleParams};
+pub use closed_loop::{ClosedLoop, EpisodeStats};
+pub use codec::{CountDecoder, RateEncoder};
+
+/// Environment error type
+#[derive(thiserror::Error, Debug)]
+pub enum EnvError {
+    /// Runtime error while stepping the network
+    #[error("Runtime error: {0}")]
+    Runtime(#[from] RuntimeError),
+    /// Action vector of the wrong length
+    #[error("Invalid action: expected {expected} values, got {got}")]
+    InvalidAction {
+        /// Expected length
+        expected: usize,
+        /// Actual length
+        got: usize,
+    },
+    /// Encoder, decoder and environment disagree on sizes
+    #[error("Invalid closed-loop configuration: {0}")]
+    Config(String),
+}
+
+/// Result alias for environment operations
+pub type Result<T> = std::result::Result<T, EnvError>;
+
+/// Result of applying one action
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepOutcome {
+    /// Observation after the action
+    pub observation: Vec<f32>,
+    /// Reward earned by the action
+    pub reward: f32,
+    /// Whether the episode has ended
+    pub done: bool,
+}
+
+/// Environment driven by a network's decoded actions
+pub trait Environment {
+    /// Length of every observation vector
+    fn observation_size(&self) -> usize;
+
+    /// Length of the action vector expected by [`Environment::step`]
+    fn action_size(&self) -> usize;
+
+    /// Start a new episode and return the initial observation
+    fn reset(&mut self) -> Vec<f32>;
+
+    /// Apply `action` and advance the environment by one control step
+    fn step(&mut self, action: &[f32]) -> Result<StepOutcome>;
+}
+
+/// Turns observations into input spikes, one network step at a time
+pub trait Encoder {
+    /// Observation length this encoder accepts
+    fn observation_size(&self) -> usize;
+
+    /// Forget state carried between steps (called at episode start)
+    fn reset(&mut self);
+
+    /// Spikes to inject for a network step of `dt_ns` starting at `time_ns`
+    fn encode(&mut self, observation: &[f32], time_ns: u64, dt_ns: u64) -> Vec<Spike>;
+}
+
+/// Reduces the output spikes of one control step to an action vector
+pub trait Decoder {
+    /// Length of the produced action vector
+    fn action_size(&self) -> usize;
+
+    /// Action for the spikes emitted during one control step
+    fn decode(&mut self, spikes: &[Spike]) -> Vec<f32>;
+}
@@ -1,20 +1,40 @@
 #![doc = "Neuromorphic IR (NIR) - minimal core with MLIR-like textual printing (text-only v0)\n\nGoals (initial milestone):\n- Versioned, strongly-typed, unit-aware ops\n- MLIR-like textual format: dialect.op@vN { attrs }\n- Minimal types/attributes, verifier stubs, and printer\n\nFollow-ups (next milestones):\n- Parser, pass manager, type inference, proper verification\n- Registry/Lowering located in shnn-compiler (next crate)\n"]
 #![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::collections::BTreeMap;
-use std::fmt::{Display, Formatter};
+// no_std + alloc: the IR, printer and parser only need heap collections
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+pub use shnn_types::units::{self, Quantity, TimeUnit};
+pub use shnn_types::TypesError;
 
 /// IR-wide result type
-pub type Result<T> = std::result::Result<T, IrError>;
+pub type Result<T> = core::result::Result<T, IrError>;
 
 /// IR errors
-#[derive(thiserror::Error, Debug)]
+#[derive(Debug)]
 pub enum IrError {
     /// Generic IR error
-    #[error("IR error: {0}")]
     Message(String),
 }
 
+impl Display for IrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            IrError::Message(msg) => write!(f, "IR error: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IrError {}
+
 /// Dialect key (static for now; aligns to MLIR-like dialect grouping)
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DialectKey {
@@ -28,18 +48,27 @@ pub enum DialectKey {
     Stimulus,
     /// Runtime dialect (e.g., simulate.run)
     Runtime,
+    /// Neuromodulation dialect (e.g., scalar)
+    Modulation,
+    /// Post-simulation assertions (e.g., expect_rate)
+    Test,
+    /// Spatial structure (e.g., positions)
+    Structure,
     /// Research/experimental dialects can use a string key
     Research(String),
 }
 
 impl Display for DialectKey {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             DialectKey::Neuron => write!(f, "neuron"),
             DialectKey::Plasticity => write!(f, "plasticity"),
             DialectKey::Connectivity => write!(f, "connectivity"),
             DialectKey::Stimulus => write!(f, "stimulus"),
             DialectKey::Runtime => write!(f, "runtime"),
+            DialectKey::Modulation => write!(f, "modulation"),
+            DialectKey::Test => write!(f, "test"),
+            DialectKey::Structure => write!(f, "structure"),
             DialectKey::Research(s) => write!(f, "research.{}", s),
         }
     }
@@ -50,7 +79,7 @@ impl Display for DialectKey {
 pub struct OpVersion(pub u16);
 
 impl Display for OpVersion {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "v{}", self.0)
     }
 }
@@ -80,10 +109,75 @@ pub enum AttributeValue {
     // Ranges and references
     RangeU32 { start: u32, end: u32 }, // inclusive start..end
     NeuronRef(u32),
+    // Neurons of a declared population, printed as `%exc`, `%exc[3]` or `%exc[0..99]`;
+    // the compiler resolves it to a NeuronRef or RangeU32
+    NeuronSymbol { name: String, index: SymbolIndex },
+
+    // Per-element values, printed as `[a, b, c]`
+    List(Vec<AttributeValue>),
+
+    // Array stored outside the program, printed as `external("w.npy", f32, [4, 3])`;
+    // the compiler reads it only when lowering needs it
+    ExternalData { path: String, dtype: DataType, shape: Vec<usize> },
+}
+
+/// Part of a population named by [`AttributeValue::NeuronSymbol`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolIndex {
+    /// Every neuron: `%exc`
+    All,
+    /// One neuron by its index in the population: `%exc[3]`
+    One(u32),
+    /// Inclusive index range: `%exc[0..99]`
+    Range(u32, u32),
+}
+
+/// Element type of [`AttributeValue::ExternalData`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    /// 32-bit float
+    F32,
+    /// 64-bit float
+    F64,
+    /// 32-bit signed integer
+    I32,
+    /// 64-bit signed integer
+    I64,
+}
+
+impl DataType {
+    /// Name used in textual NIR
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DataType::F32 => "f32",
+            DataType::F64 => "f64",
+            DataType::I32 => "i32",
+            DataType::I64 => "i64",
+        }
+    }
+
+    /// Parse a textual NIR name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "f32" => Some(DataType::F32),
+            "f64" => Some(DataType::F64),
+            "i32" => Some(DataType::I32),
+            "i64" => Some(DataType::I64),
+            _ => None,
+        }
+    }
+
+    /// Size of one element in bytes
+    pub fn size(self) -> usize {
+        match self {
+            DataType::F32 | DataType::I32 => 4,
+            DataType::F64 | DataType::I64 => 8,
+        }
+    }
 }
 
 impl Display for AttributeValue {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             AttributeValue::Bool(v) => write!(f, "{}", v),
             AttributeValue::I64(v) => write!(f, "{}", v),
@@ -105,10 +199,63 @@ impl Display for AttributeValue {
             AttributeValue::Weight(w) => write!(f, "{}", w),
             AttributeValue::RangeU32 { start, end } => write!(f, "{}..{}", start, end),
             AttributeValue::NeuronRef(id) => write!(f, "%n{}", id),
+            AttributeValue::NeuronSymbol { name, index } => match index {
+                SymbolIndex::All => write!(f, "%{}", name),
+                SymbolIndex::One(i) => write!(f, "%{}[{}]", name, i),
+                SymbolIndex::Range(start, end) => write!(f, "%{}[{}..{}]", name, start, end),
+            },
+            AttributeValue::List(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            AttributeValue::ExternalData { path, dtype, shape } => {
+                let dims = shape.iter().map(usize::to_string).collect::<Vec<_>>().join(", ");
+                write!(f, "external(\"{}\", {}, [{}])", path.escape_debug(), dtype.as_str(), dims)
+            }
         }
     }
 }
 
+impl AttributeValue {
+    /// Checked duration attribute (whole nanoseconds)
+    pub fn duration(q: Quantity) -> Result<Self> {
+        q.to_ns()
+            .map(AttributeValue::DurationNs)
+            .map_err(|e| IrError::Message(format!("duration {}: {}", q, e)))
+    }
+
+    /// Reference to an array in a .npy or .csv file
+    pub fn external(path: impl Into<String>, dtype: DataType, shape: &[usize]) -> Self {
+        AttributeValue::ExternalData { path: path.into(), dtype, shape: shape.to_vec() }
+    }
+
+    /// Neurons `index` of population `name`
+    pub fn symbol(name: impl Into<String>, index: SymbolIndex) -> Self {
+        AttributeValue::NeuronSymbol { name: name.into(), index }
+    }
+
+    /// Checked time attribute (whole nanoseconds)
+    pub fn time(q: Quantity) -> Result<Self> {
+        q.to_ns()
+            .map(AttributeValue::TimeNs)
+            .map_err(|e| IrError::Message(format!("time {}: {}", q, e)))
+    }
+}
+
+/// Millisecond literal of a sugar builder to nanoseconds
+///
+/// Rounds to the nearest ns; negative/NaN saturate to 0 and are caught by the
+/// compiler's verifier. Use [`AttributeValue::duration`] for a checked value.
+fn ms_to_ns(ms: f32) -> u64 {
+    Quantity::ms(ms as f64).saturating_ns()
+}
+
 /// A minimal type system placeholder (for future type inference)
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Type {
@@ -268,15 +415,105 @@ pub fn lif_neuron_v1(
     c_m_nf: f32,
 ) -> Operation {
     Operation::new(DialectKey::Neuron, "lif", OpVersion(1))
-        .with_attr("tau_m", AttributeValue::DurationNs((tau_m_ms * 1_000_000.0) as u64))
+        .with_attr("tau_m", AttributeValue::DurationNs(ms_to_ns(tau_m_ms)))
         .with_attr("v_rest", AttributeValue::VoltageMv(v_rest_mv))
         .with_attr("v_reset", AttributeValue::VoltageMv(v_reset_mv))
         .with_attr("v_thresh", AttributeValue::VoltageMv(v_thresh_mv))
-        .with_attr("t_refrac", AttributeValue::DurationNs((t_refrac_ms * 1_000_000.0) as u64))
+        .with_attr("t_refrac", AttributeValue::DurationNs(ms_to_ns(t_refrac_ms)))
         .with_attr("r_m", AttributeValue::ResistanceMohm(r_m_mohm))
         .with_attr("c_m", AttributeValue::CapacitanceNf(c_m_nf))
 }
 
+/// neuron.alif@v1: LIF with the threshold raised by `beta_mv` per spike, decaying over
+/// `tau_adapt_ms`; `neurons` restricts it to an inclusive population
+#[allow(clippy::too_many_arguments)]
+pub fn alif_neuron_v1(
+    tau_m_ms: f32,
+    v_rest_mv: f32,
+    v_reset_mv: f32,
+    v_thresh_mv: f32,
+    t_refrac_ms: f32,
+    r_m_mohm: f32,
+    c_m_nf: f32,
+    beta_mv: f32,
+    tau_adapt_ms: f32,
+    neurons: Option<(u32, u32)>,
+) -> Operation {
+    let mut op = Operation::new(DialectKey::Neuron, "alif", OpVersion(1))
+        .with_attr("tau_m", AttributeValue::DurationNs(ms_to_ns(tau_m_ms)))
+        .with_attr("v_rest", AttributeValue::VoltageMv(v_rest_mv))
+        .with_attr("v_reset", AttributeValue::VoltageMv(v_reset_mv))
+        .with_attr("v_thresh", AttributeValue::VoltageMv(v_thresh_mv))
+        .with_attr("t_refrac", AttributeValue::DurationNs(ms_to_ns(t_refrac_ms)))
+        .with_attr("r_m", AttributeValue::ResistanceMohm(r_m_mohm))
+        .with_attr("c_m", AttributeValue::CapacitanceNf(c_m_nf))
+        .with_attr("beta", AttributeValue::VoltageMv(beta_mv))
+        .with_attr("tau_adapt", AttributeValue::DurationNs(ms_to_ns(tau_adapt_ms)));
+    if let Some((start, end)) = neurons {
+        op = op.with_attr("neurons", AttributeValue::RangeU32 { start, end });
+    }
+    op
+}
+
+/// neuron.adex@v1 (conductances in nS); `neurons` restricts it to an inclusive population
+#[allow(clippy::too_many_arguments)]
+pub fn adex_neuron_v1(
+    c_m_nf: f32,
+    g_l_ns: f32,
+    e_l_mv: f32,
+    v_t_mv: f32,
+    delta_t_mv: f32,
+    a_ns: f32,
+    tau_w_ms: f32,
+    b_na: f32,
+    v_reset_mv: f32,
+    v_peak_mv: f32,
+    neurons: Option<(u32, u32)>,
+) -> Operation {
+    let mut op = Operation::new(DialectKey::Neuron, "adex", OpVersion(1))
+        .with_attr("c_m", AttributeValue::CapacitanceNf(c_m_nf))
+        .with_attr("g_l", AttributeValue::F32(g_l_ns))
+        .with_attr("e_l", AttributeValue::VoltageMv(e_l_mv))
+        .with_attr("v_t", AttributeValue::VoltageMv(v_t_mv))
+        .with_attr("delta_t", AttributeValue::VoltageMv(delta_t_mv))
+        .with_attr("a", AttributeValue::F32(a_ns))
+        .with_attr("tau_w", AttributeValue::DurationNs(ms_to_ns(tau_w_ms)))
+        .with_attr("b", AttributeValue::CurrentNa(b_na))
+        .with_attr("v_reset", AttributeValue::VoltageMv(v_reset_mv))
+        .with_attr("v_peak", AttributeValue::VoltageMv(v_peak_mv));
+    if let Some((start, end)) = neurons {
+        op = op.with_attr("neurons", AttributeValue::RangeU32 { start, end });
+    }
+    op
+}
+
+/// neuron.hh@v1 for the inclusive population `neurons` (conductances in µS)
+#[allow(clippy::too_many_arguments)]
+pub fn hh_neuron_v1(
+    neurons: (u32, u32),
+    c_m_nf: f32,
+    g_na_us: f32,
+    g_k_us: f32,
+    g_l_us: f32,
+    e_na_mv: f32,
+    e_k_mv: f32,
+    e_l_mv: f32,
+    substep_ms: f32,
+    method: &str,
+) -> Operation {
+    Operation::new(DialectKey::Neuron, "hh", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start: neurons.0, end: neurons.1 })
+        .with_attr("c_m", AttributeValue::CapacitanceNf(c_m_nf))
+        .with_attr("g_na", AttributeValue::F32(g_na_us))
+        .with_attr("g_k", AttributeValue::F32(g_k_us))
+        .with_attr("g_l", AttributeValue::F32(g_l_us))
+        .with_attr("e_na", AttributeValue::VoltageMv(e_na_mv))
+        .with_attr("e_k", AttributeValue::VoltageMv(e_k_mv))
+        .with_attr("e_l", AttributeValue::VoltageMv(e_l_mv))
+        .with_attr("substep", AttributeValue::DurationNs(ms_to_ns(substep_ms)))
+        .with_attr("method", AttributeValue::String(method.into()))
+}
+
 /// plasticity.stdp@v1
 pub fn stdp_rule_v1(
     a_plus: f32,
@@ -289,8 +526,8 @@ pub fn stdp_rule_v1(
     Operation::new(DialectKey::Plasticity, "stdp", OpVersion(1))
         .with_attr("a_plus", AttributeValue::F32(a_plus))
         .with_attr("a_minus", AttributeValue::F32(a_minus))
-        .with_attr("tau_plus", AttributeValue::DurationNs((tau_plus_ms * 1_000_000.0) as u64))
-        .with_attr("tau_minus", AttributeValue::DurationNs((tau_minus_ms * 1_000_000.0) as u64))
+        .with_attr("tau_plus", AttributeValue::DurationNs(ms_to_ns(tau_plus_ms)))
+        .with_attr("tau_minus", AttributeValue::DurationNs(ms_to_ns(tau_minus_ms)))
         .with_attr("w_min", AttributeValue::Weight(w_min))
         .with_attr("w_max", AttributeValue::Weight(w_max))
 }
@@ -308,7 +545,7 @@ pub fn layer_fully_connected_v1(
         .with_attr("in", AttributeValue::RangeU32 { start: in_start, end: in_end })
         .with_attr("out", AttributeValue::RangeU32 { start: out_start, end: out_end })
         .with_attr("weight", AttributeValue::Weight(weight))
-        .with_attr("delay", AttributeValue::DurationNs((delay_ms * 1_000_000.0) as u64))
+        .with_attr("delay", AttributeValue::DurationNs(ms_to_ns(delay_ms)))
 }
 
 /// stimulus.poisson@v1
@@ -323,8 +560,186 @@ pub fn stimulus_poisson_v1(
         .with_attr("neuron", AttributeValue::NeuronRef(neuron_id))
         .with_attr("rate", AttributeValue::RateHz(rate_hz))
         .with_attr("amplitude", AttributeValue::CurrentNa(amplitude_na))
-        .with_attr("start", AttributeValue::TimeNs((start_ms * 1_000_000.0) as u64))
-        .with_attr("duration", AttributeValue::DurationNs((duration_ms * 1_000_000.0) as u64))
+        .with_attr("start", AttributeValue::TimeNs(ms_to_ns(start_ms)))
+        .with_attr("duration", AttributeValue::DurationNs(ms_to_ns(duration_ms)))
+}
+
+/// Delay distribution attributes for v2 connectivity ops (times in ms)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DelayDist {
+    /// Same delay for every synapse
+    Constant {
+        /// Delay (ms)
+        delay_ms: f32,
+    },
+    /// Uniform on [min_ms, max_ms]
+    Uniform {
+        /// Lower bound (ms)
+        min_ms: f32,
+        /// Upper bound (ms)
+        max_ms: f32,
+    },
+    /// Gaussian jitter around mean_ms, clamped below at min_ms
+    Normal {
+        /// Mean (ms)
+        mean_ms: f32,
+        /// Standard deviation (ms)
+        std_ms: f32,
+        /// Lower clamp (ms)
+        min_ms: f32,
+    },
+    /// Gamma with mean shape * scale_ms
+    Gamma {
+        /// Shape k
+        shape: f32,
+        /// Scale θ (ms)
+        scale_ms: f32,
+    },
+}
+
+impl DelayDist {
+    fn apply(self, op: Operation) -> Operation {
+        let ns = |ms: f32| AttributeValue::DurationNs(ms_to_ns(ms));
+        let dist = |name: &str| AttributeValue::String(name.into());
+        match self {
+            DelayDist::Constant { delay_ms } => op
+                .with_attr("delay_dist", dist("constant"))
+                .with_attr("delay", ns(delay_ms)),
+            DelayDist::Uniform { min_ms, max_ms } => op
+                .with_attr("delay_dist", dist("uniform"))
+                .with_attr("delay_min", ns(min_ms))
+                .with_attr("delay_max", ns(max_ms)),
+            DelayDist::Normal { mean_ms, std_ms, min_ms } => op
+                .with_attr("delay_dist", dist("normal"))
+                .with_attr("delay", ns(mean_ms))
+                .with_attr("delay_std", ns(std_ms))
+                .with_attr("delay_min", ns(min_ms)),
+            DelayDist::Gamma { shape, scale_ms } => op
+                .with_attr("delay_dist", dist("gamma"))
+                .with_attr("delay_shape", AttributeValue::F32(shape))
+                .with_attr("delay_scale", ns(scale_ms)),
+        }
+    }
+}
+
+/// connectivity.layer_fully_connected@v2 with per-synapse delays sampled from `delay`
+pub fn layer_fully_connected_v2(
+    in_start: u32,
+    in_end: u32,
+    out_start: u32,
+    out_end: u32,
+    weight: f32,
+    delay: DelayDist,
+    seed: Option<u64>,
+) -> Operation {
+    let mut op = Operation::new(DialectKey::Connectivity, "layer_fully_connected", OpVersion(2))
+        .with_attr("in", AttributeValue::RangeU32 { start: in_start, end: in_end })
+        .with_attr("out", AttributeValue::RangeU32 { start: out_start, end: out_end })
+        .with_attr("weight", AttributeValue::Weight(weight));
+    op = delay.apply(op);
+    if let Some(s) = seed {
+        op = op.with_attr("seed", AttributeValue::I64(s as i64));
+    }
+    op
+}
+
+/// connectivity.synapse_connect@v2 with its delay sampled from `delay`
+pub fn synapse_connect_v2(
+    pre_neuron: u32,
+    post_neuron: u32,
+    weight: f32,
+    delay: DelayDist,
+    seed: Option<u64>,
+) -> Operation {
+    let mut op = Operation::new(DialectKey::Connectivity, "synapse_connect", OpVersion(2))
+        .with_attr("pre", AttributeValue::NeuronRef(pre_neuron))
+        .with_attr("post", AttributeValue::NeuronRef(post_neuron))
+        .with_attr("weight", AttributeValue::Weight(weight));
+    op = delay.apply(op);
+    if let Some(s) = seed {
+        op = op.with_attr("seed", AttributeValue::I64(s as i64));
+    }
+    op
+}
+
+/// Neuron selection for set-valued connectivity attributes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NeuronSet {
+    /// Inclusive id range
+    Range {
+        /// First id
+        start: u32,
+        /// Last id (inclusive)
+        end: u32,
+    },
+    /// Explicit ids
+    List(Vec<u32>),
+}
+
+impl NeuronSet {
+    fn into_attr(self) -> AttributeValue {
+        match self {
+            NeuronSet::Range { start, end } => AttributeValue::RangeU32 { start, end },
+            // Encoded as "id;id;id" (commas are attribute separators)
+            NeuronSet::List(ids) => AttributeValue::String(
+                ids.iter().map(u32::to_string).collect::<Vec<_>>().join(";"),
+            ),
+        }
+    }
+}
+
+/// connectivity.hyperedge@v1 joining every source to every target
+pub fn hyperedge_v1(
+    sources: NeuronSet,
+    targets: NeuronSet,
+    weight: f32,
+    delay_ms: f32,
+) -> Operation {
+    Operation::new(DialectKey::Connectivity, "hyperedge", OpVersion(1))
+        .with_attr("sources", sources.into_attr())
+        .with_attr("targets", targets.into_attr())
+        .with_attr("weight", AttributeValue::Weight(weight))
+        .with_attr("delay", AttributeValue::DurationNs(ms_to_ns(delay_ms)))
+}
+
+/// connectivity.distance_gaussian@v1: sources -> targets with probability p_max * exp(-d² / 2σ²)
+///
+/// Ranges are inclusive `(start, end)`; weights are constant unless a
+/// `weight_profile = "gaussian"` attribute is added.
+pub fn distance_gaussian_v1(
+    sources: (u32, u32),
+    targets: (u32, u32),
+    sigma: f32,
+    p_max: f32,
+    weight: f32,
+    delay_ms: f32,
+    seed: Option<u64>,
+) -> Operation {
+    let mut op = Operation::new(DialectKey::Connectivity, "distance_gaussian", OpVersion(1))
+        .with_attr("sources", AttributeValue::RangeU32 { start: sources.0, end: sources.1 })
+        .with_attr("targets", AttributeValue::RangeU32 { start: targets.0, end: targets.1 })
+        .with_attr("sigma", AttributeValue::F32(sigma))
+        .with_attr("p_max", AttributeValue::F32(p_max))
+        .with_attr("weight", AttributeValue::Weight(weight))
+        .with_attr("delay", AttributeValue::DurationNs(ms_to_ns(delay_ms)));
+    if let Some(s) = seed {
+        op = op.with_attr("seed", AttributeValue::I64(s as i64));
+    }
+    op
+}
+
+/// connectivity.from_snapshot@v1: the synapses of a VCSR file or store directory
+///
+/// `generation` picks a store generation (default latest); every synapse
+/// gets `delay_ms`, since snapshots do not record delays.
+pub fn connectivity_from_snapshot_v1(path: &str, generation: Option<u64>, delay_ms: f32) -> Operation {
+    let mut op = Operation::new(DialectKey::Connectivity, "from_snapshot", OpVersion(1))
+        .with_attr("path", AttributeValue::String(path.to_string()))
+        .with_attr("delay", AttributeValue::DurationNs(ms_to_ns(delay_ms)));
+    if let Some(g) = generation {
+        op = op.with_attr("generation", AttributeValue::I64(g as i64));
+    }
+    op
 }
 
 /// connectivity.synapse_connect@v1
@@ -338,7 +753,82 @@ pub fn synapse_connect_v1(
         .with_attr("pre", AttributeValue::NeuronRef(pre_neuron))
         .with_attr("post", AttributeValue::NeuronRef(post_neuron))
         .with_attr("weight", AttributeValue::Weight(weight))
-        .with_attr("delay", AttributeValue::DurationNs((delay_ms * 1_000_000.0) as u64))
+        .with_attr("delay", AttributeValue::DurationNs(ms_to_ns(delay_ms)))
+}
+
+/// structure.positions@v1 placing `nx * ny * nz` neurons from `start` on a grid (x fastest)
+pub fn structure_positions_grid_v1(start: u32, shape: [u32; 3], spacing: f32) -> Operation {
+    let count = shape.iter().fold(1u32, |acc, &n| acc.saturating_mul(n));
+    Operation::new(DialectKey::Structure, "positions", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start, end: start.saturating_add(count.saturating_sub(1)) })
+        .with_attr("nx", AttributeValue::I64(shape[0] as i64))
+        .with_attr("ny", AttributeValue::I64(shape[1] as i64))
+        .with_attr("nz", AttributeValue::I64(shape[2] as i64))
+        .with_attr("spacing", AttributeValue::F32(spacing))
+}
+
+/// structure.positions@v1 with one `[x, y, z]` per neuron from `start`
+pub fn structure_positions_coords_v1(start: u32, coords: &[[f32; 3]]) -> Operation {
+    // Encoded as "x:y:z;x:y:z" (commas are attribute separators)
+    let encoded = coords
+        .iter()
+        .map(|[x, y, z]| format!("{}:{}:{}", x, y, z))
+        .collect::<Vec<_>>()
+        .join(";");
+    let end = start.saturating_add((coords.len() as u32).saturating_sub(1));
+    Operation::new(DialectKey::Structure, "positions", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start, end })
+        .with_attr("coords", AttributeValue::String(encoded))
+}
+
+/// structure.ei_network@v1: Brunel network of `n_exc` excitatory then `n_inh` inhibitory neurons
+/// (PSP, delay and seed left at their defaults)
+pub fn structure_ei_network_v1(n_exc: u32, n_inh: u32, p: f32, g: f32, nu_ext_hz: f32) -> Operation {
+    Operation::new(DialectKey::Structure, "ei_network", OpVersion(1))
+        .with_attr("n_exc", AttributeValue::I64(n_exc as i64))
+        .with_attr("n_inh", AttributeValue::I64(n_inh as i64))
+        .with_attr("p", AttributeValue::F32(p))
+        .with_attr("g", AttributeValue::F32(g))
+        .with_attr("nu_ext", AttributeValue::RateHz(nu_ext_hz))
+}
+
+/// structure.reservoir@v1: `inputs` drive the recurrent `neurons`, read out at `outputs`
+/// (p, input_p, delay and seed left at their defaults)
+pub fn structure_reservoir_v1(
+    neurons: (u32, u32),
+    inputs: (u32, u32),
+    outputs: (u32, u32),
+    radius: f32,
+    input_weight: f32,
+) -> Operation {
+    Operation::new(DialectKey::Structure, "reservoir", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start: neurons.0, end: neurons.1 })
+        .with_attr("inputs", AttributeValue::RangeU32 { start: inputs.0, end: inputs.1 })
+        .with_attr("outputs", AttributeValue::RangeU32 { start: outputs.0, end: outputs.1 })
+        .with_attr("radius", AttributeValue::F32(radius))
+        .with_attr("input_weight", AttributeValue::Weight(input_weight))
+}
+
+/// structure.wta@v1 over neurons 0..n-1 (start and delay left at their defaults)
+pub fn structure_wta_v1(n: u32, inhibition_weight: f32, self_excitation: f32) -> Operation {
+    Operation::new(DialectKey::Structure, "wta", OpVersion(1))
+        .with_attr("n", AttributeValue::I64(n as i64))
+        .with_attr("inhibition_weight", AttributeValue::Weight(inhibition_weight))
+        .with_attr("self_excitation", AttributeValue::Weight(self_excitation))
+}
+
+/// structure.port@v1 exporting neurons start..=end under `name`
+pub fn structure_port_v1(name: &str, start: u32, end: u32) -> Operation {
+    Operation::new(DialectKey::Structure, "port", OpVersion(1))
+        .with_attr("name", AttributeValue::String(name.to_string()))
+        .with_attr("neurons", AttributeValue::RangeU32 { start, end })
+}
+
+/// structure.population@v1 declaring `size` neurons named `%name`
+pub fn structure_population_v1(name: &str, size: u32) -> Operation {
+    Operation::new(DialectKey::Structure, "population", OpVersion(1))
+        .with_attr("name", AttributeValue::String(name.to_string()))
+        .with_attr("size", AttributeValue::I64(size as i64))
 }
 
 /// runtime.simulate.run@v1
@@ -349,8 +839,8 @@ pub fn runtime_simulate_run_v1(
     seed: Option<u64>,
 ) -> Operation {
     let mut op = Operation::new(DialectKey::Runtime, "simulate.run", OpVersion(1))
-        .with_attr("dt", AttributeValue::DurationNs((dt_ms * 1_000_000.0) as u64))
-        .with_attr("duration", AttributeValue::DurationNs((duration_ms * 1_000_000.0) as u64))
+        .with_attr("dt", AttributeValue::DurationNs(ms_to_ns(dt_ms)))
+        .with_attr("duration", AttributeValue::DurationNs(ms_to_ns(duration_ms)))
         .with_attr("record_potentials", AttributeValue::Bool(record_potentials));
 
     if let Some(s) = seed {
@@ -359,6 +849,196 @@ pub fn runtime_simulate_run_v1(
     op
 }
 
+/// runtime.apply_mask@v1 (mode: "activity" | "plasticity")
+pub fn runtime_apply_mask_v1(path: impl Into<String>, mode: impl Into<String>) -> Operation {
+    Operation::new(DialectKey::Runtime, "apply_mask", OpVersion(1))
+        .with_attr("path", AttributeValue::String(path.into()))
+        .with_attr("mode", AttributeValue::String(mode.into()))
+}
+
+/// stimulus.ou_noise@v1 on neurons start..=end (seed defaults to the simulation seed)
+pub fn stimulus_ou_noise_v1(
+    start: u32,
+    end: u32,
+    mu_na: f32,
+    sigma_na: f32,
+    tau_ms: f32,
+    seed: Option<u64>,
+) -> Operation {
+    let mut op = Operation::new(DialectKey::Stimulus, "ou_noise", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start, end })
+        .with_attr("mu", AttributeValue::CurrentNa(mu_na))
+        .with_attr("sigma", AttributeValue::CurrentNa(sigma_na))
+        .with_attr("tau", AttributeValue::DurationNs(ms_to_ns(tau_ms)));
+    if let Some(s) = seed {
+        op = op.with_attr("seed", AttributeValue::I64(s as i64));
+    }
+    op
+}
+
+/// runtime.population_dt@v1: integrate neurons start..=end with dt / substeps
+pub fn runtime_population_dt_v1(start: u32, end: u32, substeps: i64) -> Operation {
+    Operation::new(DialectKey::Runtime, "population_dt", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start, end })
+        .with_attr("substeps", AttributeValue::I64(substeps))
+}
+
+/// runtime.monitor_rate@v1: bin the mean rate of neurons start..=end
+pub fn runtime_monitor_rate_v1(start: u32, end: u32, bin_ms: f32) -> Operation {
+    Operation::new(DialectKey::Runtime, "monitor_rate", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start, end })
+        .with_attr("bin", AttributeValue::DurationNs(ms_to_ns(bin_ms)))
+}
+
+/// runtime.readout@v1 with role "state" or "target"; without `neurons` it reads the
+/// structure.reservoir outputs (state) or inputs (target)
+pub fn runtime_readout_v1(neurons: Option<(u32, u32)>, role: &str, tau_ms: f32, interval_ms: f32) -> Operation {
+    let mut op = Operation::new(DialectKey::Runtime, "readout", OpVersion(1))
+        .with_attr("role", AttributeValue::String(role.to_string()))
+        .with_attr("tau", AttributeValue::DurationNs(ms_to_ns(tau_ms)))
+        .with_attr("interval", AttributeValue::DurationNs(ms_to_ns(interval_ms)));
+    if let Some((start, end)) = neurons {
+        op = op.with_attr("neurons", AttributeValue::RangeU32 { start, end });
+    }
+    op
+}
+
+/// runtime.monitor_weights@v1 recording the given (pre, post) synapses every interval_ms
+pub fn runtime_monitor_weights_pairs_v1(pairs: &[(u32, u32)], interval_ms: f32) -> Operation {
+    // Encoded as "pre:post;pre:post" (commas are attribute separators)
+    let pairs = pairs
+        .iter()
+        .map(|(pre, post)| format!("{}:{}", pre, post))
+        .collect::<Vec<_>>()
+        .join(";");
+    Operation::new(DialectKey::Runtime, "monitor_weights", OpVersion(1))
+        .with_attr("pairs", AttributeValue::String(pairs))
+        .with_attr("interval", AttributeValue::DurationNs(ms_to_ns(interval_ms)))
+}
+
+/// runtime.monitor_weights@v1 recording `count` randomly chosen synapses every interval_ms
+pub fn runtime_monitor_weights_sample_v1(count: i64, interval_ms: f32, seed: Option<u64>) -> Operation {
+    let mut op = Operation::new(DialectKey::Runtime, "monitor_weights", OpVersion(1))
+        .with_attr("sample", AttributeValue::I64(count))
+        .with_attr("interval", AttributeValue::DurationNs(ms_to_ns(interval_ms)));
+    if let Some(s) = seed {
+        op = op.with_attr("seed", AttributeValue::I64(s as i64));
+    }
+    op
+}
+
+/// runtime.load_state@v1: start from the network state saved in `path`
+pub fn runtime_load_state_v1(path: &str) -> Operation {
+    Operation::new(DialectKey::Runtime, "load_state", OpVersion(1))
+        .with_attr("path", AttributeValue::String(path.to_string()))
+}
+
+/// runtime.save_state@v1: write the network state at `at_ms` to `path`
+pub fn runtime_save_state_v1(path: &str, at_ms: f32) -> Operation {
+    Operation::new(DialectKey::Runtime, "save_state", OpVersion(1))
+        .with_attr("path", AttributeValue::String(path.to_string()))
+        .with_attr("at", AttributeValue::TimeNs(ms_to_ns(at_ms)))
+}
+
+/// modulation.scalar@v1 driven by a schedule of (time_ms, level) breakpoints
+pub fn modulation_scalar_schedule_v1(points: &[(f32, f32)], linear: bool) -> Operation {
+    // Encoded as "t_ns:level;t_ns:level" (commas are attribute separators)
+    let schedule = points
+        .iter()
+        .map(|(t_ms, level)| format!("{}:{}", ms_to_ns(*t_ms), level))
+        .collect::<Vec<_>>()
+        .join(";");
+    Operation::new(DialectKey::Modulation, "scalar", OpVersion(1))
+        .with_attr("schedule", AttributeValue::String(schedule))
+        .with_attr("interpolation", AttributeValue::String(if linear { "linear" } else { "step" }.into()))
+}
+
+/// modulation.scalar@v1 derived from the firing rate of neurons start..=end
+pub fn modulation_scalar_rate_v1(
+    start: u32,
+    end: u32,
+    window_ms: f32,
+    gain: f32,
+    baseline: f32,
+) -> Operation {
+    Operation::new(DialectKey::Modulation, "scalar", OpVersion(1))
+        .with_attr("source", AttributeValue::RangeU32 { start, end })
+        .with_attr("window", AttributeValue::DurationNs(ms_to_ns(window_ms)))
+        .with_attr("gain", AttributeValue::F32(gain))
+        .with_attr("baseline", AttributeValue::F32(baseline))
+}
+
+/// test.expect_rate@v1: mean rate of neurons start..=end within [min_hz, max_hz]
+pub fn test_expect_rate_v1(start: u32, end: u32, min_hz: Option<f32>, max_hz: Option<f32>) -> Operation {
+    let mut op = Operation::new(DialectKey::Test, "expect_rate", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start, end });
+    if let Some(v) = min_hz {
+        op = op.with_attr("min_hz", AttributeValue::RateHz(v));
+    }
+    if let Some(v) = max_hz {
+        op = op.with_attr("max_hz", AttributeValue::RateHz(v));
+    }
+    op
+}
+
+/// test.expect_spike_count@v1: spike count of neurons start..=end within [min, max]
+pub fn test_expect_spike_count_v1(start: u32, end: u32, min: Option<i64>, max: Option<i64>) -> Operation {
+    let mut op = Operation::new(DialectKey::Test, "expect_spike_count", OpVersion(1))
+        .with_attr("neurons", AttributeValue::RangeU32 { start, end });
+    if let Some(v) = min {
+        op = op.with_attr("min", AttributeValue::I64(v));
+    }
+    if let Some(v) = max {
+        op = op.with_attr("max", AttributeValue::I64(v));
+    }
+    op
+}
+
+/// Resolve `${name}` placeholders in textual NIR before parsing
+///
+/// `${name:-default}` falls back to `default` when `name` is not in `vars`,
+/// and `$${` writes a literal `${`. Returns the text and the value each
+/// referenced name resolved to; an unbound name without a default is an error.
+pub fn substitute_vars(text: &str, vars: &BTreeMap<String, String>) -> Result<(String, BTreeMap<String, String>)> {
+    let mut out = String::with_capacity(text.len());
+    let mut resolved = BTreeMap::new();
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let mut rest = line;
+        while let Some(pos) = rest.find("${") {
+            if rest[..pos].ends_with('$') {
+                out.push_str(&rest[..pos - 1]);
+                out.push_str("${");
+                rest = &rest[pos + 2..];
+                continue;
+            }
+            out.push_str(&rest[..pos]);
+            let close = rest[pos..]
+                .find('}')
+                .ok_or_else(|| IrError::Message(format!("line {}: unterminated '${{'", index + 1)))?;
+            let body = &rest[pos + 2..pos + close];
+            let (name, default) = match body.split_once(":-") {
+                Some((name, default)) => (name.trim(), Some(default)),
+                None => (body.trim(), None),
+            };
+            let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+            if !valid {
+                return Err(IrError::Message(format!("line {}: bad variable name '{}'", index + 1, name)));
+            }
+            let value = vars
+                .get(name)
+                .map(String::as_str)
+                .or(default)
+                .ok_or_else(|| IrError::Message(format!("line {}: unbound variable '{}'", index + 1, name)))?;
+            out.push_str(value);
+            resolved.insert(name.to_string(), value.to_string());
+            rest = &rest[pos + close + 1..];
+        }
+        out.push_str(rest);
+    }
+    Ok((out, resolved))
+}
+
 /// Parse textual NIR (MLIR-like) into a Module (minimal subset).
 /// Supports single-line ops with attribute list printed by to_text().
 pub fn parse_text(input: &str) -> Result<Module> {
@@ -402,13 +1082,13 @@ fn parse_op_line(header: &str, attrs: &str) -> Result<Operation> {
     let dialect_str = &left[..dot_pos];
     let name = left[dot_pos + 1..].to_string();
 
-    let dialect = match dialect_str {
-        "neuron" => DialectKey::Neuron,
-        "plasticity" => DialectKey::Plasticity,
-        "connectivity" => DialectKey::Connectivity,
-        "stimulus" => DialectKey::Stimulus,
-        "runtime" => DialectKey::Runtime,
-        other => DialectKey::Research(other.to_string()),
+    let (dialect, name) = match dialect_str {
+        // Printed as research.<key>.<name>
+        "research" => match name.split_once('.') {
+            Some((key, rest)) => (DialectKey::Research(key.to_string()), rest.to_string()),
+            None => (DialectKey::Research(dialect_str.to_string()), name),
+        },
+        _ => (parse_dialect(dialect_str), name),
     };
 
     let mut op = Operation::new(dialect, name, OpVersion(version_num));
@@ -431,10 +1111,89 @@ fn parse_op_line(header: &str, attrs: &str) -> Result<Operation> {
     Ok(op)
 }
 
+fn parse_dialect(dialect_str: &str) -> DialectKey {
+    match dialect_str {
+        "neuron" => DialectKey::Neuron,
+        "plasticity" => DialectKey::Plasticity,
+        "connectivity" => DialectKey::Connectivity,
+        "stimulus" => DialectKey::Stimulus,
+        "runtime" => DialectKey::Runtime,
+        "modulation" => DialectKey::Modulation,
+        "test" => DialectKey::Test,
+        "structure" => DialectKey::Structure,
+        other => DialectKey::Research(other.to_string()),
+    }
+}
+
 fn split_top_level(s: &str, delim: char) -> Vec<String> {
-    // Because our values never contain commas except as separators,
-    // a simple split is sufficient.
-    s.split(delim).map(|t| t.to_string()).collect()
+    // Delimiters inside strings, lists and external(...) belong to the value
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let (mut depth, mut quoted) = (0usize, false);
+    for c in s.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' | '(' if !quoted => depth += 1,
+            ']' | ')' if !quoted => depth = depth.saturating_sub(1),
+            c if c == delim && !quoted && depth == 0 => {
+                parts.push(core::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current);
+    parts
+}
+
+fn parse_external(inner: &str) -> Option<AttributeValue> {
+    let parts = split_top_level(inner, ',');
+    let [path, dtype, shape] = &parts[..] else { return None };
+    let path = path.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let dtype = DataType::from_name(dtype.trim())?;
+    let dims = shape.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let shape = match dims.trim().is_empty() {
+        true => Vec::new(),
+        false => dims.split(',').map(|d| d.trim().parse().ok()).collect::<Option<Vec<usize>>>()?,
+    };
+    Some(AttributeValue::external(path, dtype, &shape))
+}
+
+fn parse_symbol(s: &str) -> Option<AttributeValue> {
+    let (name, index) = match s.split_once('[') {
+        None => (s, SymbolIndex::All),
+        Some((name, rest)) => {
+            let inner = rest.strip_suffix(']')?;
+            let index = match inner.split_once("..") {
+                Some((a, b)) => SymbolIndex::Range(a.trim().parse().ok()?, b.trim().parse().ok()?),
+                None => SymbolIndex::One(inner.trim().parse().ok()?),
+            };
+            (name, index)
+        }
+    };
+    is_symbol_name(name).then(|| AttributeValue::symbol(name, index))
+}
+
+/// Population names: `[A-Za-z_][A-Za-z0-9_.]*`, dots separating namespaces
+pub fn is_symbol_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        && !name.ends_with('.')
+        && !name.contains("..")
+}
+
+/// Unitless attributes that are always printed and parsed as I64
+const INTEGER_KEYS: &[&str] = &["seed", "substeps", "sample", "min", "max", "nx", "ny", "nz", "generation", "size"];
+
+/// Heuristic: "start" and "at" are TimeNs, others are DurationNs in v0 printer
+fn time_attr(key: &str, ns: u64) -> AttributeValue {
+    if key == "start" || key == "at" {
+        AttributeValue::TimeNs(ns)
+    } else {
+        AttributeValue::DurationNs(ns)
+    }
 }
 
 fn parse_attr_value(key: &str, s: &str) -> Result<AttributeValue> {
@@ -444,10 +1203,33 @@ fn parse_attr_value(key: &str, s: &str) -> Result<AttributeValue> {
         return Ok(AttributeValue::String(inner.to_string()));
     }
 
-    // NeuronRef: %n123
-    if let Some(nstr) = s.strip_prefix("%n") {
-        let id: u32 = nstr.trim().parse().map_err(|_| IrError::Message(format!("bad NeuronRef '{}'", s)))?;
-        return Ok(AttributeValue::NeuronRef(id));
+    // External array: external("path", dtype, [d0, d1])
+    if let Some(inner) = s.strip_prefix("external(").and_then(|rest| rest.strip_suffix(')')) {
+        return parse_external(inner).ok_or_else(|| IrError::Message(format!(
+            "{}: expected external(\"path\", f32|f64|i32|i64, [dims]), got '{}'", key, s
+        )));
+    }
+
+    // List: [a, b, c], elements parsed as values of `key`
+    if let Some(inner) = s.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+        if inner.trim().is_empty() {
+            return Ok(AttributeValue::List(Vec::new()));
+        }
+        return split_top_level(inner, ',')
+            .iter()
+            .map(|item| parse_attr_value(key, item.trim()))
+            .collect::<Result<Vec<_>>>()
+            .map(AttributeValue::List);
+    }
+
+    // NeuronRef: %n123; any other %name is a population symbol
+    if let Some(rest) = s.strip_prefix('%') {
+        if let Some(id) = rest.strip_prefix('n').and_then(|n| n.trim().parse::<u32>().ok()) {
+            return Ok(AttributeValue::NeuronRef(id));
+        }
+        return parse_symbol(rest).ok_or_else(|| IrError::Message(format!(
+            "bad neuron reference '{}' (expected %n<id>, %name, %name[i] or %name[a..b])", s
+        )));
     }
 
     // Range: a..b
@@ -462,11 +1244,16 @@ fn parse_attr_value(key: &str, s: &str) -> Result<AttributeValue> {
     // Units: order matters (match longer suffixes first)
     if let Some(val) = s.strip_suffix(" ns") {
         let ns: u64 = val.trim().parse().map_err(|_| IrError::Message(format!("bad ns value '{}'", s)))?;
-        // Heuristic: "start" is TimeNs, others are DurationNs in v0 printer
-        if key == "start" {
-            return Ok(AttributeValue::TimeNs(ns));
-        } else {
-            return Ok(AttributeValue::DurationNs(ns));
+        return Ok(time_attr(key, ns));
+    }
+    // Hand-written times in coarser units must name a whole number of ns
+    for unit in [TimeUnit::Us, TimeUnit::Ms, TimeUnit::S] {
+        if let Some(val) = s.strip_suffix(unit.symbol()).and_then(|v| v.strip_suffix(' ')) {
+            let value: f64 = val.trim().parse()
+                .map_err(|_| IrError::Message(format!("bad {} value '{}'", unit.symbol(), s)))?;
+            let ns = Quantity::new(value, unit).to_ns()
+                .map_err(|e| IrError::Message(format!("{} = '{}': {}", key, s, e)))?;
+            return Ok(time_attr(key, ns));
         }
     }
     if let Some(val) = s.strip_suffix(" mV") {
@@ -499,12 +1286,12 @@ fn parse_attr_value(key: &str, s: &str) -> Result<AttributeValue> {
     }
 
     // Numeric values: context-sensitive parsing
-    // Special-case: seeds should be integers
-    if key == "seed" {
+    // Special-case: seeds and counts should be integers
+    if INTEGER_KEYS.contains(&key) {
         if let Ok(i) = s.parse::<i64>() {
             return Ok(AttributeValue::I64(i));
         } else {
-            return Err(IrError::Message(format!("seed must be integer i64, got '{}'", s)));
+            return Err(IrError::Message(format!("{} must be integer i64, got '{}'", key, s)));
         }
     }
 
@@ -551,6 +1338,64 @@ mod tests {
 mod parser_roundtrip_tests {
     use super::*;
 
+    #[test]
+    fn hyperedge_sets_round_trip() {
+        let mut m = Module::new();
+        m.push(hyperedge_v1(NeuronSet::List(vec![0, 3, 7]), NeuronSet::Range { start: 8, end: 9 }, 0.5, 1.0));
+        let text = m.to_text();
+        assert!(text.contains("connectivity.hyperedge@v1"));
+        let parsed = parse_text(&text).expect("parse");
+        assert_eq!(parsed.ops[0].attrs.get("sources"), Some(&AttributeValue::String("0;3;7".into())));
+        assert_eq!(parsed.ops[0].attrs.get("targets"), Some(&AttributeValue::RangeU32 { start: 8, end: 9 }));
+    }
+
+    #[test]
+    fn substitute_placeholders_before_parsing() {
+        let template = "nir.module {\n  stimulus.poisson@v1 { amplitude = ${amp:-10} nA, duration = 500000000 ns, neuron = %n${neuron}, rate = ${rate} Hz, start = 0 ns}\n}\n";
+        let vars: BTreeMap<String, String> = [("rate", "35.5"), ("neuron", "4")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let (text, resolved) = substitute_vars(template, &vars).expect("substitute");
+        let module = parse_text(&text).expect("parse");
+        assert_eq!(module.ops[0].attrs.get("rate"), Some(&AttributeValue::RateHz(35.5)));
+        assert_eq!(module.ops[0].attrs.get("neuron"), Some(&AttributeValue::NeuronRef(4)));
+        assert_eq!(module.ops[0].attrs.get("amplitude"), Some(&AttributeValue::CurrentNa(10.0)));
+        assert_eq!(resolved.get("amp").map(String::as_str), Some("10"));
+        assert_eq!(resolved.len(), 3);
+
+        let (escaped, _) = substitute_vars("$${rate}", &BTreeMap::new()).expect("substitute");
+        assert_eq!(escaped, "${rate}");
+        let err = substitute_vars("a\nb = ${missing}", &vars).unwrap_err();
+        assert!(format!("{}", err).contains("line 2: unbound variable 'missing'"));
+        assert!(substitute_vars("${rate", &vars).is_err());
+        assert!(substitute_vars("${1x}", &vars).is_err());
+    }
+
+    #[test]
+    fn roundtrip_neuron_symbols() {
+        let mut m = Module::new();
+        m.push(structure_population_v1("cortex.exc", 400));
+        m.push(layer_fully_connected_v1(0, 0, 0, 0, 1.0, 1.0)
+            .with_attr("in", AttributeValue::symbol("cortex.exc", SymbolIndex::Range(0, 99)))
+            .with_attr("out", AttributeValue::symbol("inh", SymbolIndex::All)));
+        m.push(stimulus_poisson_v1(0, 20.0, 10.0, 0.0, 100.0)
+            .with_attr("neuron", AttributeValue::symbol("inh", SymbolIndex::One(3))));
+        let text = m.to_text();
+        assert!(text.contains("in = %cortex.exc[0..99]"));
+        assert!(text.contains("neuron = %inh[3]"));
+        let parsed = parse_text(&text).expect("parse");
+        assert_eq!(parsed.to_text(), text);
+        assert_eq!(parsed.ops[0].attrs.get("size"), Some(&AttributeValue::I64(400)));
+        assert_eq!(parsed.ops[1].attrs.get("out"), Some(&AttributeValue::symbol("inh", SymbolIndex::All)));
+        // %n<id> stays a raw id; a bare `n` is a name
+        assert_eq!(parse_attr_value("pre", "%n7").ok(), Some(AttributeValue::NeuronRef(7)));
+        assert_eq!(parse_attr_value("pre", "%n").ok(), Some(AttributeValue::symbol("n", SymbolIndex::All)));
+        for bad in ["%exc[", "%exc[1..]", "%1exc", "%exc.", "%a..b"] {
+            assert!(parse_attr_value("pre", bad).is_err(), "{}", bad);
+        }
+    }
+
     #[test]
     fn parse_single_op_line() {
         let text = "nir.module {\n  neuron.lif@v1 { c_m = 1 nF, r_m = 10 MΩ, t_refrac = 2000000 ns, tau_m = 20000000 ns, v_reset = -70 mV, v_rest = -70 mV, v_thresh = -50 mV}\n}\n";
@@ -577,4 +1422,149 @@ mod parser_roundtrip_tests {
         // The textual printer is canonical; round-trip must be identical.
         assert_eq!(text1, text2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_coarse_time_units() {
+        let text = "nir.module {\n  neuron.lif@v1 { t_refrac = 2.5 ms, tau_m = 0.02 s, delay = 1.5 us }\n}\n";
+        let module = parse_text(text).expect("parse");
+        let attrs = &module.ops[0].attrs;
+        assert_eq!(attrs["t_refrac"], AttributeValue::DurationNs(2_500_000));
+        assert_eq!(attrs["tau_m"], AttributeValue::DurationNs(20_000_000));
+        assert_eq!(attrs["delay"], AttributeValue::DurationNs(1_500));
+
+        let lossy = "nir.module {\n  neuron.lif@v1 { t_refrac = 0.0000005 ms }\n}\n";
+        assert!(parse_text(lossy).is_err());
+        let negative = "nir.module {\n  neuron.lif@v1 { t_refrac = -1 ms }\n}\n";
+        assert!(parse_text(negative).is_err());
+    }
+
+    #[test]
+    fn checked_duration_attributes() {
+        assert_eq!(AttributeValue::duration(Quantity::ms(0.1)).unwrap(), AttributeValue::DurationNs(100_000));
+        assert!(AttributeValue::duration(Quantity::ns(0.5)).is_err());
+        assert!(AttributeValue::time(Quantity::s(f64::NAN)).is_err());
+        // Sugar builders round instead of truncating
+        let op = runtime_simulate_run_v1(0.3, 123.456_79, false, None);
+        assert_eq!(op.attrs["dt"], AttributeValue::DurationNs(300_000));
+        assert_eq!(op.attrs["duration"], AttributeValue::DurationNs(123_456_787));
+    }
+
+    #[test]
+    fn parse_modulation_ops() {
+        let mut m = Module::new();
+        m.push(modulation_scalar_schedule_v1(&[(0.0, 0.0), (50.0, 1.5)], true));
+        m.push(modulation_scalar_rate_v1(10, 19, 20.0, 0.05, 0.0));
+
+        let text = m.to_text();
+        assert!(text.contains("modulation.scalar@v1 { interpolation = \"linear\", schedule = \"0:0;50000000:1.5\"}"));
+        let parsed = parse_text(&text).expect("parse");
+        assert_eq!(parsed.ops[0].dialect, DialectKey::Modulation);
+        assert_eq!(parsed.to_text(), text);
+    }
+
+    #[test]
+    fn parse_structure_ops() {
+        let mut m = Module::new();
+        m.push(structure_positions_grid_v1(0, [4, 3, 1], 25.0));
+        m.push(structure_positions_coords_v1(12, &[[0.0, 0.0, 0.0], [1.5, -2.0, 0.0]]));
+        m.push(distance_gaussian_v1((0, 11), (0, 13), 50.0, 0.8, 0.5, 1.0, Some(7)));
+
+        let text = m.to_text();
+        assert!(text.contains("structure.positions@v1 { neurons = 0..11, nx = 4, ny = 3, nz = 1, spacing = 25}"));
+        assert!(text.contains("coords = \"0:0:0;1.5:-2:0\", neurons = 12..13"));
+        let parsed = parse_text(&text).expect("parse");
+        assert_eq!(parsed.ops[0].dialect, DialectKey::Structure);
+        for (parsed, op) in parsed.ops.iter().zip(&m.ops) {
+            assert_eq!(parsed.attrs, op.attrs);
+        }
+    }
+
+    #[test]
+    fn parse_state_ops() {
+        let mut m = Module::new();
+        m.push(runtime_load_state_v1("warm.state"));
+        m.push(runtime_save_state_v1("out.state", 250.0));
+        let parsed = parse_text(&m.to_text()).expect("parse");
+        assert_eq!(parsed.ops[1].attrs["at"], AttributeValue::TimeNs(250_000_000));
+        for (parsed, op) in parsed.ops.iter().zip(&m.ops) {
+            assert_eq!(parsed.attrs, op.attrs);
+        }
+    }
+
+    #[test]
+    fn parse_research_ops() {
+        let op = Operation::new(DialectKey::Research("izh".into()), "neuron", OpVersion(1))
+            .with_attr("neurons", AttributeValue::RangeU32 { start: 0, end: 3 });
+        let mut m = Module::new();
+        m.push(op.clone());
+        let text = m.to_text();
+        assert!(text.contains("research.izh.neuron@v1"));
+        let parsed = parse_text(&text).expect("parse");
+        assert_eq!(parsed.ops[0].dialect, op.dialect);
+        assert_eq!(parsed.ops[0].name, "neuron");
+        assert_eq!(parsed.ops[0].attrs, op.attrs);
+    }
+
+    #[test]
+    fn parse_list_attrs() {
+        let op = Operation::new(DialectKey::Neuron, "lif", OpVersion(1))
+            .with_attr("v_thresh", AttributeValue::List(vec![AttributeValue::VoltageMv(-50.0), AttributeValue::VoltageMv(-52.5)]))
+            .with_attr("seed", AttributeValue::List(vec![AttributeValue::I64(1), AttributeValue::I64(2)]))
+            .with_attr("labels", AttributeValue::List(vec![AttributeValue::String("a,b".into()), AttributeValue::String("[c]".into())]))
+            .with_attr("nested", AttributeValue::List(vec![
+                AttributeValue::List(vec![AttributeValue::F32(1.0), AttributeValue::F32(2.5)]),
+                AttributeValue::List(Vec::new()),
+            ]))
+            .with_attr("weight", AttributeValue::F32(0.5));
+        let mut m = Module::new();
+        m.push(op.clone());
+        let text = m.to_text();
+        assert!(text.contains("v_thresh = [-50 mV, -52.5 mV]"));
+        assert!(text.contains("nested = [[1, 2.5], []]"));
+        let parsed = parse_text(&text).expect("parse");
+        assert_eq!(parsed.ops[0].attrs.get("v_thresh"), op.attrs.get("v_thresh"));
+        assert_eq!(parsed.ops[0].attrs.get("seed"), op.attrs.get("seed"));
+        assert_eq!(parsed.ops[0].attrs.get("labels"), op.attrs.get("labels"));
+        assert_eq!(parsed.ops[0].attrs.get("nested"), op.attrs.get("nested"));
+
+        let hand = parse_text("neuron.lif@v1 { v_thresh = [ -50 mV ,-49 mV ], t_refrac = [1 ms, 2 ms] }").expect("parse");
+        assert_eq!(hand.ops[0].attrs.get("t_refrac"), Some(&AttributeValue::List(vec![
+            AttributeValue::DurationNs(1_000_000),
+            AttributeValue::DurationNs(2_000_000),
+        ])));
+        assert!(parse_text("neuron.lif@v1 { seed = [1, 2.5] }").is_err());
+    }
+
+    #[test]
+    fn parse_external_data_attrs() {
+        let op = Operation::new(DialectKey::Connectivity, "layer_fully_connected", OpVersion(2))
+            .with_attr("weight", AttributeValue::external("data/w, v2.npy", DataType::F32, &[4, 3]));
+        let mut m = Module::new();
+        m.push(op.clone());
+        let text = m.to_text();
+        assert!(text.contains("weight = external(\"data/w, v2.npy\", f32, [4, 3])"));
+        let parsed = parse_text(&text).expect("parse");
+        assert_eq!(parsed.ops[0].attrs.get("weight"), op.attrs.get("weight"));
+
+        let hand = parse_text("stimulus.x@v1 { wave = external( \"a.csv\" , f64, [] ) }").expect("parse");
+        assert_eq!(hand.ops[0].attrs.get("wave"), Some(&AttributeValue::external("a.csv", DataType::F64, &[])));
+        assert!(parse_text("stimulus.x@v1 { wave = external(\"a.csv\", u8, [2]) }").is_err());
+        assert!(parse_text("stimulus.x@v1 { wave = external(\"a.csv\", f32) }").is_err());
+    }
+
+    #[test]
+    fn parse_integer_count_attrs() {
+        let mut m = Module::new();
+        m.push(runtime_population_dt_v1(0, 9, 4));
+        m.push(runtime_monitor_weights_sample_v1(8, 1.0, Some(3)));
+        m.push(test_expect_spike_count_v1(0, 9, Some(1), Some(20)));
+        m.push(test_expect_rate_v1(0, 9, None, Some(50.0)));
+
+        let parsed = parse_text(&m.to_text()).expect("parse");
+        for (parsed, op) in parsed.ops.iter().zip(&m.ops) {
+            assert_eq!(parsed.dialect, op.dialect);
+            assert_eq!(parsed.attrs, op.attrs);
+        }
+        assert_eq!(parsed.ops[2].dialect, DialectKey::Test);
+    }
+}
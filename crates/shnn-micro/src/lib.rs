@@ -40,6 +40,7 @@ pub mod connectivity;
 pub mod fixed_point;
 pub mod network;
 pub mod time;
+pub mod persist;
 
 // Platform-specific optimizations
 #[cfg(any(feature = "simd-neon", feature = "simd-sse2", feature = "simd-avx2"))]
@@ -51,7 +52,7 @@ pub mod rtic;
 
 // Re-export core types
 pub use crate::{
-    neuron::{LIFNeuron, NeuronState, NeuronConfig},
+    neuron::{LIFNeuron, MicroNeuron, NeuronModel, NeuronState, NeuronConfig},
     connectivity::{BasicConnectivity, SparseConnectivity},
     fixed_point::{FixedPoint, Q15_16},
     network::{MicroNetwork, NetworkConfig, ProcessingResult},
@@ -129,6 +130,8 @@ pub enum MicroError {
     Timeout,
     /// Hardware fault
     HardwareFault,
+    /// Stored data failed magic/CRC validation
+    CorruptData,
 }
 
 /// Result type for micro operations
@@ -5,8 +5,9 @@
 
 use crate::{
     Scalar, MicroError, Result, MicroConfig,
-    neuron::{NeuronId, NeuronState, NeuronPool, LIFNeuron},
+    neuron::{NeuronId, NeuronState, NeuronPool, LIFNeuron, MicroNeuron, NeuronModel},
     connectivity::{Connectivity, Connection},
+    persist::{self, WeightStore},
     time::{MicroTime, Duration},
 };
 
@@ -16,8 +17,8 @@ use crate::{
 /// All memory is statically allocated at compile time.
 #[derive(Debug)]
 pub struct MicroNetwork<const N: usize, const C: usize> {
-    /// Neuron pool (stack allocated)
-    neurons: NeuronPool<LIFNeuron, N>,
+    /// Neuron pool (stack allocated, model tagged per index)
+    neurons: NeuronPool<MicroNeuron, N>,
     /// Connection matrix (sparse representation)
     connectivity: Connectivity<C>,
     /// Input buffer for external signals
@@ -63,7 +64,7 @@ impl<const N: usize, const C: usize> MicroNetwork<N, C> {
         // Add neurons according to configuration
         for i in 0..config.num_neurons.min(N as u8) {
             let neuron = LIFNeuron::new(config.neuron_config);
-            network.neurons.add_neuron(neuron)?;
+            network.neurons.add_neuron(neuron.into())?;
         }
         
         // Add connections according to configuration
@@ -75,10 +76,41 @@ impl<const N: usize, const C: usize> MicroNetwork<N, C> {
         
         Ok(network)
     }
-    
-    /// Add neuron to network
-    pub fn add_neuron(&mut self, neuron: LIFNeuron) -> Result<NeuronId> {
-        self.neurons.add_neuron(neuron)
+
+    /// Build a network from static neuron and connection tables
+    ///
+    /// Neuron `i` of the table gets `NeuronId(i)`. Entries may be `LIFConfig`s
+    /// or any other type convertible to [`MicroNeuron`] (e.g. `NeuronConfig`
+    /// for mixed models). Used by code generated with
+    /// `snn nir codegen --target micro`, which emits the tables as constants.
+    pub fn from_tables<T: Copy + Into<MicroNeuron>>(neurons: &[T], connections: &[Connection], time_step_ms: u8) -> Result<Self> {
+        if neurons.len() > N || connections.len() > C {
+            return Err(MicroError::NetworkFull);
+        }
+
+        let mut network = Self::new();
+        for config in neurons {
+            network.neurons.add_neuron((*config).into())?;
+        }
+        for conn in connections {
+            if conn.source.0 as usize >= neurons.len() || conn.target.0 as usize >= neurons.len() {
+                return Err(MicroError::InvalidNeuronId);
+            }
+            network.connectivity.add_connection(*conn)?;
+        }
+        network.time_step_ms = time_step_ms;
+
+        Ok(network)
+    }
+
+    /// Add neuron of any enabled model to network
+    pub fn add_neuron(&mut self, neuron: impl Into<MicroNeuron>) -> Result<NeuronId> {
+        self.neurons.add_neuron(neuron.into())
+    }
+
+    /// Model of a neuron
+    pub fn neuron_model(&self, id: NeuronId) -> Option<NeuronModel> {
+        self.neurons.get(id).map(MicroNeuron::model)
     }
     
     /// Add connection between neurons
@@ -87,6 +119,27 @@ impl<const N: usize, const C: usize> MicroNetwork<N, C> {
         self.connectivity.add_connection(connection)
     }
     
+    /// Save connection weights to non-volatile storage, returning the image size
+    ///
+    /// `scratch` must hold `persist::image_size(C)` bytes.
+    pub fn save_weights(&self, store: &mut impl WeightStore, scratch: &mut [u8]) -> Result<usize> {
+        let len = persist::serialize_weights(&self.connectivity, scratch)?;
+        if len > store.capacity() {
+            return Err(MicroError::BufferOverflow);
+        }
+        store.write(&scratch[..len])?;
+        Ok(len)
+    }
+
+    /// Restore connection weights saved by [`Self::save_weights`], returning the count restored
+    ///
+    /// Fails with [`MicroError::CorruptData`] if the image is damaged (e.g.
+    /// power lost mid-write) and leaves the current weights untouched.
+    pub fn restore_weights(&mut self, store: &mut impl WeightStore, scratch: &mut [u8]) -> Result<u8> {
+        let len = store.read(scratch)?;
+        persist::deserialize_weights(&mut self.connectivity, &scratch[..len])
+    }
+
     /// Set input values (from sensors)
     pub fn set_inputs(&mut self, inputs: &[Scalar]) -> Result<()> {
         if inputs.len() > self.input_buffer.len() {
@@ -473,6 +526,66 @@ mod tests {
         assert_eq!(network.connection_count(), 1);
     }
     
+    #[test]
+    fn test_from_tables() {
+        use crate::neuron::LIFConfig;
+
+        let neurons = [LIFConfig::default(); 3];
+        let connections = [
+            Connection::with_delay(NeuronId::new(0), NeuronId::new(1), Scalar::from_float(0.5), 2),
+            Connection::new(NeuronId::new(1), NeuronId::new(2), Scalar::from_float(0.25)),
+        ];
+        let network: MicroNetwork<3, 2> = MicroNetwork::from_tables(&neurons, &connections, 1).unwrap();
+        assert_eq!(network.neuron_count(), 3);
+        assert_eq!(network.connection_count(), 2);
+
+        let dangling = [Connection::new(NeuronId::new(0), NeuronId::new(3), Scalar::from_float(0.5))];
+        assert!(MicroNetwork::<3, 2>::from_tables(&neurons, &dangling, 1).is_err());
+        assert!(MicroNetwork::<2, 2>::from_tables(&neurons, &connections, 1).is_err());
+    }
+    
+    #[cfg(feature = "izhikevich-neuron")]
+    #[test]
+    fn test_mixed_model_network() {
+        use crate::neuron::{IzhikevichConfig, LIFConfig, NeuronConfig};
+
+        let neurons = [
+            NeuronConfig::Izhikevich(IzhikevichConfig::chattering()),
+            NeuronConfig::LIF(LIFConfig::default()),
+        ];
+        let connections = [Connection::new(NeuronId::new(0), NeuronId::new(1), Scalar::from_float(0.01))];
+        let mut network: MicroNetwork<2, 1> = MicroNetwork::from_tables(&neurons, &connections, 1).unwrap();
+        assert_eq!(network.neuron_model(NeuronId::new(0)), Some(NeuronModel::Izhikevich));
+        assert_eq!(network.neuron_model(NeuronId::new(1)), Some(NeuronModel::Lif));
+        assert_eq!(network.neuron_model(NeuronId::new(2)), None);
+
+        // Drive the pattern generator through input 0
+        network.set_inputs(&[Scalar::from_int(10)]).unwrap();
+        for _ in 0..100 {
+            network.step().unwrap();
+        }
+        assert!(network.stats().total_spikes > 0);
+    }
+
+    #[test]
+    fn test_weights_survive_restart() {
+        use crate::persist::{image_size, SliceStore};
+
+        let mut network: MicroNetwork<4, 4> = MicroNetwork::feedforward(1, 2, 1).unwrap();
+        network.connectivity.update_weight(NeuronId::new(0), NeuronId::new(1), Scalar::from_float(0.75)).unwrap();
+
+        let mut flash = [0u8; image_size(4)];
+        let mut scratch = [0u8; image_size(4)];
+        let mut store = SliceStore::new(&mut flash);
+        network.save_weights(&mut store, &mut scratch).unwrap();
+
+        // Power cycle: same topology, initial weights
+        let mut rebooted: MicroNetwork<4, 4> = MicroNetwork::feedforward(1, 2, 1).unwrap();
+        assert_eq!(rebooted.restore_weights(&mut store, &mut scratch).unwrap(), 4);
+        let weight = rebooted.connectivity.find_connection(NeuronId::new(0), NeuronId::new(1)).unwrap().weight;
+        assert_eq!(weight, Scalar::from_float(0.75));
+    }
+
     #[test]
     fn test_step_processing() {
         let mut network: MicroNetwork<4, 8> = MicroNetwork::new();
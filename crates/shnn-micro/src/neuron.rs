@@ -211,10 +211,14 @@ impl Default for LIFConfig {
     }
 }
 
-/// Simplified Izhikevich neuron for more realistic dynamics
+/// Izhikevich neuron for bursting and fast-spiking dynamics
 ///
-/// Memory usage: 16 bytes per neuron
-/// Processing time: ~30 instructions per update
+/// Unlike [`LIFNeuron`] (volts), state is kept in the model's native units:
+/// `v` in mV and the input as a current term. `v` is integrated in 0.5 ms
+/// half-steps, as in Izhikevich's reference implementation.
+///
+/// Memory usage: 24 bytes per neuron
+/// Processing time: ~15 multiplications per ms of simulated time
 #[cfg(feature = "izhikevich-neuron")]
 #[derive(Debug, Clone, Copy)]
 #[repr(C, align(4))]
@@ -227,79 +231,137 @@ pub struct IzhikevichNeuron {
     pub a: Scalar,
     /// Parameter b (sensitivity of u to v)
     pub b: Scalar,
-    /// Parameter c (reset value of v)
+    /// Parameter c (reset value of v, mV)
     pub c: Scalar,
-    /// Parameter d (reset value of u)
+    /// Parameter d (reset increment of u)
     pub d: Scalar,
-    /// Refractory counter
-    pub refractory_counter: u8,
-    /// Padding for alignment
-    _padding: [u8; 3],
 }
 
+/// Izhikevich model constants (Q15.16)
 #[cfg(feature = "izhikevich-neuron")]
-impl IzhikevichNeuron {
-    /// Create regular spiking neuron
+mod izhikevich {
+    use crate::fixed_point::Q15_16;
+
+    /// Spike cut-off potential (30 mV)
+    pub const PEAK: Q15_16 = Q15_16::from_int(30);
+    /// Quadratic coefficient 0.04
+    pub const K2: Q15_16 = Q15_16::from_raw(2621);
+    /// Linear coefficient 5
+    pub const K1: Q15_16 = Q15_16::from_int(5);
+    /// Constant term 140
+    pub const K0: Q15_16 = Q15_16::from_int(140);
+    /// Half-step of 0.5 ms
+    pub const HALF: Q15_16 = Q15_16::from_raw(Q15_16::SCALE / 2);
+}
+
+/// Parameters (a, b, c, d) of an Izhikevich neuron
+#[cfg(feature = "izhikevich-neuron")]
+#[derive(Debug, Clone, Copy)]
+pub struct IzhikevichConfig {
+    /// Recovery time scale
+    pub a: Scalar,
+    /// Sensitivity of u to v
+    pub b: Scalar,
+    /// Reset potential (mV)
+    pub c: Scalar,
+    /// Reset increment of u
+    pub d: Scalar,
+}
+
+#[cfg(feature = "izhikevich-neuron")]
+impl IzhikevichConfig {
+    /// Regular spiking (excitatory cortical)
     pub fn regular_spiking() -> Self {
+        Self::from_floats(0.02, 0.2, -65.0, 8.0)
+    }
+
+    /// Fast spiking (inhibitory interneuron)
+    pub fn fast_spiking() -> Self {
+        Self::from_floats(0.1, 0.2, -65.0, 2.0)
+    }
+
+    /// Chattering (fast rhythmic bursts)
+    pub fn chattering() -> Self {
+        Self::from_floats(0.02, 0.2, -50.0, 2.0)
+    }
+
+    /// Intrinsically bursting (initial burst, then tonic spikes)
+    pub fn intrinsically_bursting() -> Self {
+        Self::from_floats(0.02, 0.2, -55.0, 4.0)
+    }
+
+    /// Parameters from floats
+    pub fn from_floats(a: f32, b: f32, c: f32, d: f32) -> Self {
         Self {
-            v: constants::RESTING,
-            u: Scalar::ZERO,
-            a: Q15_16::from_float(0.02),
-            b: Q15_16::from_float(0.2),
-            c: constants::RESET,
-            d: Q15_16::from_float(8.0),
-            refractory_counter: 0,
-            _padding: [0; 3],
+            a: Q15_16::from_float(a),
+            b: Q15_16::from_float(b),
+            c: Q15_16::from_float(c),
+            d: Q15_16::from_float(d),
+        }
+    }
+}
+
+#[cfg(feature = "izhikevich-neuron")]
+impl IzhikevichNeuron {
+    /// Create neuron at rest (v = -65 mV, u = b * v)
+    pub fn new(config: IzhikevichConfig) -> Self {
+        let v = Q15_16::from_int(-65);
+        Self {
+            v,
+            u: config.b * v,
+            a: config.a,
+            b: config.b,
+            c: config.c,
+            d: config.d,
         }
     }
+
+    /// Create regular spiking neuron
+    pub fn regular_spiking() -> Self {
+        Self::new(IzhikevichConfig::regular_spiking())
+    }
     
     /// Create fast spiking neuron
     pub fn fast_spiking() -> Self {
-        Self {
-            v: constants::RESTING,
-            u: Scalar::ZERO,
-            a: Q15_16::from_float(0.1),
-            b: Q15_16::from_float(0.2),
-            c: constants::RESET,
-            d: Q15_16::from_float(2.0),
-            refractory_counter: 0,
-            _padding: [0; 3],
-        }
+        Self::new(IzhikevichConfig::fast_spiking())
+    }
+
+    /// Create chattering (bursting) neuron
+    pub fn chattering() -> Self {
+        Self::new(IzhikevichConfig::chattering())
+    }
+}
+
+#[cfg(feature = "izhikevich-neuron")]
+impl Default for IzhikevichNeuron {
+    fn default() -> Self {
+        Self::regular_spiking()
     }
 }
 
 #[cfg(feature = "izhikevich-neuron")]
 impl NeuronState for IzhikevichNeuron {
     #[inline]
-    fn update(&mut self, input_current: Scalar, _time_step_ms: u8) -> bool {
-        if self.refractory_counter > 0 {
-            self.refractory_counter -= 1;
-            return false;
+    fn update(&mut self, input_current: Scalar, time_step_ms: u8) -> bool {
+        use izhikevich::{HALF, K0, K1, K2, PEAK};
+
+        // v' = 0.04*v^2 + 5*v + 140 - u + I, integrated in 0.5 ms half-steps
+        let half_steps = 2 * time_step_ms.max(1) as u16;
+        for _ in 0..half_steps {
+            let dv = K2 * self.v * self.v + K1 * self.v + K0 - self.u + input_current;
+            self.v += dv * HALF;
+            if self.v >= PEAK {
+                break;
+            }
         }
-        
-        // Izhikevich model equations (simplified for fixed-point)
-        // v' = 0.04*v^2 + 5*v + 140 - u + I
+
         // u' = a*(b*v - u)
+        let dt = Q15_16::from_int(time_step_ms.max(1) as i32);
+        self.u += self.a * (self.b * self.v - self.u) * dt;
         
-        let v_squared = self.v.saturating_mul(self.v);
-        let five_v = self.v.shl(2) + self.v; // 5*v (approximately)
-        
-        #[cfg(feature = "fixed-point")]
-        let v_update = v_squared.shr(5) + five_v + Q15_16::from_int(140) - self.u + input_current;
-        
-        #[cfg(not(feature = "fixed-point"))]
-        let v_update = 0.04 * v_squared + 5.0 * self.v + 140.0 - self.u + input_current;
-        
-        self.v += v_update.shr(4); // Scale down for stability
-        
-        let u_update = self.a.saturating_mul(self.b.saturating_mul(self.v) - self.u);
-        self.u += u_update;
-        
-        // Check for spike
-        if self.v >= constants::THRESHOLD {
+        if self.v >= PEAK {
             self.v = self.c;
             self.u += self.d;
-            self.refractory_counter = 1;
             return true;
         }
         
@@ -308,9 +370,8 @@ impl NeuronState for IzhikevichNeuron {
     
     #[inline]
     fn reset(&mut self) {
-        self.v = self.c;
-        self.u = Scalar::ZERO;
-        self.refractory_counter = 0;
+        self.v = Q15_16::from_int(-65);
+        self.u = self.b * self.v;
     }
     
     #[inline]
@@ -320,7 +381,130 @@ impl NeuronState for IzhikevichNeuron {
     
     #[inline]
     fn is_refractory(&self) -> bool {
-        self.refractory_counter > 0
+        false
+    }
+}
+
+/// Neuron model tag of one entry of a network's neuron array
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum NeuronModel {
+    /// Leaky integrate-and-fire
+    Lif = 0,
+    /// Izhikevich
+    #[cfg(feature = "izhikevich-neuron")]
+    Izhikevich = 1,
+}
+
+/// A neuron of any enabled model
+///
+/// The enum discriminant tags each slot of the static neuron array, so one
+/// network can mix models (e.g. bursting pattern generators driving LIF
+/// motor neurons) without heap allocation. With only `lif-neuron` enabled
+/// the enum has a single variant.
+#[derive(Debug, Clone, Copy)]
+pub enum MicroNeuron {
+    /// LIF neuron
+    Lif(LIFNeuron),
+    /// Izhikevich neuron
+    #[cfg(feature = "izhikevich-neuron")]
+    Izhikevich(IzhikevichNeuron),
+}
+
+impl MicroNeuron {
+    /// Model tag
+    #[inline(always)]
+    pub fn model(&self) -> NeuronModel {
+        match self {
+            MicroNeuron::Lif(_) => NeuronModel::Lif,
+            #[cfg(feature = "izhikevich-neuron")]
+            MicroNeuron::Izhikevich(_) => NeuronModel::Izhikevich,
+        }
+    }
+}
+
+impl Default for MicroNeuron {
+    fn default() -> Self {
+        MicroNeuron::Lif(LIFNeuron::new_default())
+    }
+}
+
+impl From<LIFNeuron> for MicroNeuron {
+    fn from(neuron: LIFNeuron) -> Self {
+        MicroNeuron::Lif(neuron)
+    }
+}
+
+impl From<LIFConfig> for MicroNeuron {
+    fn from(config: LIFConfig) -> Self {
+        MicroNeuron::Lif(LIFNeuron::new(config))
+    }
+}
+
+#[cfg(feature = "izhikevich-neuron")]
+impl From<IzhikevichNeuron> for MicroNeuron {
+    fn from(neuron: IzhikevichNeuron) -> Self {
+        MicroNeuron::Izhikevich(neuron)
+    }
+}
+
+#[cfg(feature = "izhikevich-neuron")]
+impl From<IzhikevichConfig> for MicroNeuron {
+    fn from(config: IzhikevichConfig) -> Self {
+        MicroNeuron::Izhikevich(IzhikevichNeuron::new(config))
+    }
+}
+
+impl From<NeuronConfig> for MicroNeuron {
+    fn from(config: NeuronConfig) -> Self {
+        match config {
+            #[cfg(feature = "lif-neuron")]
+            NeuronConfig::LIF(config) => config.into(),
+            #[cfg(feature = "izhikevich-neuron")]
+            NeuronConfig::IzhikevichRS => IzhikevichNeuron::regular_spiking().into(),
+            #[cfg(feature = "izhikevich-neuron")]
+            NeuronConfig::IzhikevichFS => IzhikevichNeuron::fast_spiking().into(),
+            #[cfg(feature = "izhikevich-neuron")]
+            NeuronConfig::Izhikevich(config) => config.into(),
+        }
+    }
+}
+
+impl NeuronState for MicroNeuron {
+    #[inline(always)]
+    fn update(&mut self, input_current: Scalar, time_step_ms: u8) -> bool {
+        match self {
+            MicroNeuron::Lif(n) => n.update(input_current, time_step_ms),
+            #[cfg(feature = "izhikevich-neuron")]
+            MicroNeuron::Izhikevich(n) => n.update(input_current, time_step_ms),
+        }
+    }
+
+    #[inline(always)]
+    fn reset(&mut self) {
+        match self {
+            MicroNeuron::Lif(n) => n.reset(),
+            #[cfg(feature = "izhikevich-neuron")]
+            MicroNeuron::Izhikevich(n) => n.reset(),
+        }
+    }
+
+    #[inline(always)]
+    fn membrane_potential(&self) -> Scalar {
+        match self {
+            MicroNeuron::Lif(n) => n.membrane_potential(),
+            #[cfg(feature = "izhikevich-neuron")]
+            MicroNeuron::Izhikevich(n) => n.membrane_potential(),
+        }
+    }
+
+    #[inline(always)]
+    fn is_refractory(&self) -> bool {
+        match self {
+            MicroNeuron::Lif(n) => n.is_refractory(),
+            #[cfg(feature = "izhikevich-neuron")]
+            MicroNeuron::Izhikevich(n) => n.is_refractory(),
+        }
     }
 }
 
@@ -336,6 +520,9 @@ pub enum NeuronConfig {
     /// Izhikevich neuron (fast spiking)
     #[cfg(feature = "izhikevich-neuron")]
     IzhikevichFS,
+    /// Izhikevich neuron with custom parameters
+    #[cfg(feature = "izhikevich-neuron")]
+    Izhikevich(IzhikevichConfig),
 }
 
 impl Default for NeuronConfig {
@@ -572,6 +759,36 @@ mod tests {
         assert!(pool.get(id).is_some());
     }
     
+    #[test]
+    fn test_micro_neuron_tags_model() {
+        let lif = MicroNeuron::from(LIFConfig::default());
+        assert_eq!(lif.model(), NeuronModel::Lif);
+        assert_eq!(MicroNeuron::default().model(), NeuronModel::Lif);
+        assert_eq!(MicroNeuron::from(NeuronConfig::default()).model(), NeuronModel::Lif);
+    }
+
+    #[cfg(feature = "izhikevich-neuron")]
+    #[test]
+    fn test_izhikevich_chattering_bursts() {
+        let mut neuron = MicroNeuron::from(IzhikevichConfig::chattering());
+        assert_eq!(neuron.model(), NeuronModel::Izhikevich);
+
+        // Constant drive: record spike times over 200 ms
+        let mut spike_times = [0u16; 64];
+        let mut spikes = 0;
+        for t in 0..200u16 {
+            if neuron.update(Q15_16::from_int(10), 1) && spikes < spike_times.len() {
+                spike_times[spikes] = t;
+                spikes += 1;
+            }
+        }
+        assert!(spikes >= 4);
+        // Bursting: some inter-spike intervals are much shorter than others
+        let isis = spike_times[..spikes].windows(2).map(|w| w[1] - w[0]);
+        let (min, max) = isis.fold((u16::MAX, 0), |(lo, hi), isi| (lo.min(isi), hi.max(isi)));
+        assert!(max >= 3 * min, "min ISI {} max ISI {}", min, max);
+    }
+
     #[test]
     fn test_memory_layout() {
         // Verify compact memory layout
@@ -0,0 +1,256 @@
+//! Weight persistence for flash/EEPROM
+//!
+//! Learned weights are serialized into a compact, CRC-protected image that a
+//! [`WeightStore`] writes to non-volatile memory, so on-device STDP learning
+//! survives power cycles. [`SliceStore`] keeps the image in a byte slice (a
+//! RAM buffer or a memory-mapped flash page); board support code implements
+//! [`WeightStore`] for its own flash/EEPROM driver.
+//!
+//! Image layout (little-endian):
+//!
+//! ```text
+//! magic "SNNW" | version u8 | count u8 | reserved u16 | count x (source u8, target u8, weight i32) | crc32
+//! ```
+
+use crate::{
+    Scalar, MicroError, Result,
+    connectivity::Connectivity,
+};
+
+/// Image magic
+pub const MAGIC: [u8; 4] = *b"SNNW";
+
+/// Image format version
+pub const VERSION: u8 = 1;
+
+/// Header size (bytes)
+pub const HEADER_SIZE: usize = 8;
+
+/// Bytes per stored connection
+pub const ENTRY_SIZE: usize = 6;
+
+/// CRC trailer size (bytes)
+pub const CRC_SIZE: usize = 4;
+
+/// Image size for `connections` connections
+pub const fn image_size(connections: usize) -> usize {
+    HEADER_SIZE + connections * ENTRY_SIZE + CRC_SIZE
+}
+
+/// Non-volatile storage for a weight image
+pub trait WeightStore {
+    /// Replace the stored image with `data`
+    fn write(&mut self, data: &[u8]) -> Result<()>;
+
+    /// Read the stored image into `buf`, returning its length
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Largest image the store can hold (bytes)
+    fn capacity(&self) -> usize;
+}
+
+/// [`WeightStore`] backed by a byte slice
+#[derive(Debug)]
+pub struct SliceStore<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceStore<'a> {
+    /// Empty store over `buf`
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Store over `buf` that already holds an image (e.g. a flash page read at boot)
+    pub fn with_contents(buf: &'a mut [u8]) -> Self {
+        let len = buf.len();
+        Self { buf, len }
+    }
+
+    /// Stored bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl WeightStore for SliceStore<'_> {
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        if data.len() > self.buf.len() {
+            return Err(MicroError::BufferOverflow);
+        }
+        self.buf[..data.len()].copy_from_slice(data);
+        self.len = data.len();
+        Ok(())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.len() < self.len {
+            return Err(MicroError::BufferOverflow);
+        }
+        buf[..self.len].copy_from_slice(&self.buf[..self.len]);
+        Ok(self.len)
+    }
+
+    fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+}
+
+/// CRC-32 (IEEE 802.3), bitwise to avoid a 1 KB table in flash
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(feature = "fixed-point")]
+fn weight_bits(weight: Scalar) -> i32 {
+    weight.to_raw()
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn weight_bits(weight: Scalar) -> i32 {
+    weight.to_bits() as i32
+}
+
+#[cfg(feature = "fixed-point")]
+fn weight_from_bits(bits: i32) -> Scalar {
+    Scalar::from_raw(bits)
+}
+
+#[cfg(not(feature = "fixed-point"))]
+fn weight_from_bits(bits: i32) -> Scalar {
+    f32::from_bits(bits as u32)
+}
+
+/// Serialize the weights of `connectivity` into `out`, returning the image length
+pub fn serialize_weights<const C: usize>(connectivity: &Connectivity<C>, out: &mut [u8]) -> Result<usize> {
+    let count = connectivity.len() as usize;
+    let size = image_size(count);
+    if out.len() < size {
+        return Err(MicroError::BufferOverflow);
+    }
+
+    out[..4].copy_from_slice(&MAGIC);
+    out[4] = VERSION;
+    out[5] = count as u8;
+    out[6..HEADER_SIZE].copy_from_slice(&[0, 0]);
+    for i in 0..count {
+        // Indices below len() are always present
+        if let Some(conn) = connectivity.get(i as u8) {
+            let entry = &mut out[HEADER_SIZE + i * ENTRY_SIZE..HEADER_SIZE + (i + 1) * ENTRY_SIZE];
+            entry[0] = conn.source.raw();
+            entry[1] = conn.target.raw();
+            entry[2..].copy_from_slice(&weight_bits(conn.weight).to_le_bytes());
+        }
+    }
+    let crc = crc32(&out[..size - CRC_SIZE]);
+    out[size - CRC_SIZE..size].copy_from_slice(&crc.to_le_bytes());
+    Ok(size)
+}
+
+/// Check an image's header, length and CRC, returning its connection count
+pub fn validate_image(data: &[u8]) -> Result<u8> {
+    if data.len() < image_size(0) || data[..4] != MAGIC || data[4] != VERSION {
+        return Err(MicroError::CorruptData);
+    }
+    let count = data[5];
+    let size = image_size(count as usize);
+    if data.len() < size {
+        return Err(MicroError::CorruptData);
+    }
+    let mut stored = [0u8; CRC_SIZE];
+    stored.copy_from_slice(&data[size - CRC_SIZE..size]);
+    if crc32(&data[..size - CRC_SIZE]) != u32::from_le_bytes(stored) {
+        return Err(MicroError::CorruptData);
+    }
+    Ok(count)
+}
+
+/// Restore weights from an image into `connectivity`, returning the number restored
+///
+/// The image must describe the same connections in the same order; a
+/// different topology is rejected with [`MicroError::InvalidConnectionId`]
+/// before any weight is changed.
+pub fn deserialize_weights<const C: usize>(connectivity: &mut Connectivity<C>, data: &[u8]) -> Result<u8> {
+    let count = validate_image(data)?;
+    if count != connectivity.len() {
+        return Err(MicroError::InvalidConnectionId);
+    }
+    let entries = &data[HEADER_SIZE..HEADER_SIZE + count as usize * ENTRY_SIZE];
+    for (i, entry) in entries.chunks_exact(ENTRY_SIZE).enumerate() {
+        match connectivity.get(i as u8) {
+            Some(conn) if conn.source.raw() == entry[0] && conn.target.raw() == entry[1] => {}
+            _ => return Err(MicroError::InvalidConnectionId),
+        }
+    }
+    for (i, entry) in entries.chunks_exact(ENTRY_SIZE).enumerate() {
+        if let Some(conn) = connectivity.get_mut(i as u8) {
+            conn.weight = weight_from_bits(i32::from_le_bytes([entry[2], entry[3], entry[4], entry[5]]));
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectivity::Connection;
+    use crate::neuron::NeuronId;
+
+    fn connectivity() -> Connectivity<4> {
+        let mut c = Connectivity::new();
+        c.add_connection(Connection::new(NeuronId::new(0), NeuronId::new(1), Scalar::from_float(0.5))).unwrap();
+        c.add_connection(Connection::new(NeuronId::new(1), NeuronId::new(2), Scalar::from_float(-0.25))).unwrap();
+        c
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_weights_round_trip_through_store() {
+        let source = connectivity();
+        let mut image = [0u8; image_size(4)];
+        let len = serialize_weights(&source, &mut image).unwrap();
+        assert_eq!(len, image_size(2));
+
+        let mut flash = [0xFFu8; 64];
+        let mut store = SliceStore::new(&mut flash);
+        store.write(&image[..len]).unwrap();
+
+        let mut restored = connectivity();
+        restored.update_weight(NeuronId::new(0), NeuronId::new(1), Scalar::from_float(0.9)).unwrap();
+        let mut buf = [0u8; 64];
+        let read = store.read(&mut buf).unwrap();
+        assert_eq!(deserialize_weights(&mut restored, &buf[..read]).unwrap(), 2);
+        assert_eq!(restored.get(0).unwrap().weight, Scalar::from_float(0.5));
+    }
+
+    #[test]
+    fn test_corrupt_or_mismatched_images_are_rejected() {
+        let mut image = [0u8; image_size(2)];
+        serialize_weights(&connectivity(), &mut image).unwrap();
+
+        let mut flipped = image;
+        flipped[HEADER_SIZE + 3] ^= 0x01;
+        assert_eq!(validate_image(&flipped), Err(MicroError::CorruptData));
+        assert_eq!(validate_image(&image[..image.len() - 1]), Err(MicroError::CorruptData));
+
+        let mut other: Connectivity<4> = Connectivity::new();
+        other.add_connection(Connection::new(NeuronId::new(0), NeuronId::new(2), Scalar::from_float(0.5))).unwrap();
+        other.add_connection(Connection::new(NeuronId::new(1), NeuronId::new(2), Scalar::from_float(0.5))).unwrap();
+        assert_eq!(deserialize_weights(&mut other, &image), Err(MicroError::InvalidConnectionId));
+        // Nothing was changed on mismatch
+        assert_eq!(other.get(1).unwrap().weight, Scalar::from_float(0.5));
+    }
+}
@@ -0,0 +1,111 @@
+//! Browser bindings for the NIR pipeline
+//!
+//! Compiles to `wasm32-unknown-unknown` so the viz SPA can verify, compile and
+//! run small textual NIR programs without a server round-trip:
+//!
+//! ```text
+//! wasm-pack build crates/shnn-nir-wasm --target web --out-dir ../shnn-cli/static/viz/pkg
+//! ```
+//!
+//! ```javascript
+//! import init, { verify, run } from '/pkg/shnn_nir_wasm.js';
+//! await init();
+//! const result = JSON.parse(run(nirText));
+//! drawRaster(result.results.spikes);
+//! ```
+//!
+//! Results use the same JSON layout as `snn nir run -o`. The pure-Rust
+//! entry points ([`verify_text`], [`run_text`]) are usable natively as well.
+
+#![deny(missing_docs)]
+
+use wasm_bindgen::prelude::*;
+
+use shnn_compiler::{compile_with_passes, verify_module};
+use shnn_ir::{parse_text, Module};
+
+/// Largest run accepted in the browser, where a long run freezes the page
+pub const MAX_STEPS: usize = 2_000_000;
+
+fn parse(text: &str) -> Result<Module, String> {
+    parse_text(text).map_err(|e| format!("parse error: {}", e))
+}
+
+/// Parse and verify textual NIR, returning the op count
+pub fn verify_text(text: &str) -> Result<usize, String> {
+    let module = parse(text)?;
+    verify_module(&module).map_err(|e| e.to_string())?;
+    Ok(module.ops.len())
+}
+
+/// Compile and run textual NIR, returning results JSON (`{"results": {...}}`)
+pub fn run_text(text: &str) -> Result<String, String> {
+    let module = parse(text)?;
+    let program = compile_with_passes(&module).map_err(|e| e.to_string())?;
+    let steps = program.engine.params().num_steps();
+    if steps > MAX_STEPS {
+        return Err(format!(
+            "run of {} steps exceeds the in-browser limit of {}; shorten duration or raise dt",
+            steps, MAX_STEPS
+        ));
+    }
+    let result = program.run().map_err(|e| e.to_string())?;
+
+    let spikes: Vec<_> = result.export_spikes().into_iter().map(|(time_ns, neuron_id)| {
+        serde_json::json!({
+            "neuron_id": neuron_id,
+            "time_ns": time_ns,
+            "time_ms": time_ns as f64 / 1_000_000.0,
+        })
+    }).collect();
+    let json = serde_json::json!({
+        "results": {
+            "spike_count": result.spikes.len(),
+            "steps_executed": result.steps_executed,
+            "spikes": spikes,
+        }
+    });
+    Ok(json.to_string())
+}
+
+/// Verify textual NIR; throws with the parse/verification error
+#[wasm_bindgen]
+pub fn verify(text: &str) -> Result<usize, JsError> {
+    verify_text(text).map_err(|e| JsError::new(&e))
+}
+
+/// Compile and run textual NIR; returns results JSON as a string
+#[wasm_bindgen]
+pub fn run(text: &str) -> Result<String, JsError> {
+    run_text(text).map_err(|e| JsError::new(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shnn_ir::{layer_fully_connected_v1, runtime_simulate_run_v1, stimulus_poisson_v1};
+
+    fn program(duration_ms: f32) -> String {
+        let mut module = Module::new();
+        module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+        module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, duration_ms));
+        module.push(runtime_simulate_run_v1(0.1, duration_ms, false, Some(5)));
+        module.to_text()
+    }
+
+    #[test]
+    fn test_run_text_matches_cli_layout() {
+        let json: serde_json::Value = serde_json::from_str(&run_text(&program(20.0)).unwrap()).unwrap();
+        let results = &json["results"];
+        assert_eq!(results["steps_executed"], 200);
+        assert_eq!(results["spikes"].as_array().unwrap().len() as u64, results["spike_count"].as_u64().unwrap());
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        assert_eq!(verify_text(&program(20.0)).unwrap(), 3);
+        assert!(verify_text("not nir").unwrap_err().starts_with("parse error"));
+        // 300 s at 0.1 ms
+        assert!(run_text(&program(300_000.0)).unwrap_err().contains("in-browser limit"));
+    }
+}
@@ -0,0 +1,230 @@
+//! Sensor → spikes → network → spikes → motor loop
+
+use std::time::{Duration, Instant};
+
+use shnn_env::{CountDecoder, Decoder, Encoder, RateEncoder};
+use shnn_runtime::SimulationEngine;
+
+use crate::{BridgeConfig, Result, RosError, Transport};
+
+/// Counters for one [`Bridge::run`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BridgeStats {
+    /// Control periods completed (one motor command per motor topic each)
+    pub periods: usize,
+    /// Network steps executed
+    pub network_steps: usize,
+    /// Sensor messages consumed
+    pub sensor_messages: usize,
+    /// Output spikes emitted by the network
+    pub output_spikes: usize,
+    /// Periods whose computation ran past their wall-clock deadline (realtime only)
+    pub overruns: usize,
+    /// Largest lateness past a deadline (ns, realtime only)
+    pub max_lag_ns: u64,
+}
+
+struct SensorChannel {
+    topic: String,
+    encoder: RateEncoder,
+    /// Last received values, held until the next message (zeros before the first)
+    observation: Vec<f32>,
+}
+
+struct MotorChannel {
+    topic: String,
+    decoder: CountDecoder,
+    gain: f32,
+}
+
+/// Drives an engine from sensor topics and publishes motor commands
+pub struct Bridge {
+    sensors: Vec<SensorChannel>,
+    motors: Vec<MotorChannel>,
+    period: Duration,
+    period_ns: u64,
+    realtime: bool,
+}
+
+impl Bridge {
+    /// Bridge for a validated mapping
+    pub fn new(config: &BridgeConfig) -> Result<Self> {
+        config.validate()?;
+        let sensors = config.sensors.iter().map(|s| SensorChannel {
+            topic: s.topic.clone(),
+            encoder: RateEncoder::new(s.input_start, s.scales.clone(), s.max_rate_hz),
+            observation: vec![0.0; s.scales.len()],
+        }).collect();
+        let motors = config.motors.iter().map(|m| MotorChannel {
+            topic: m.topic.clone(),
+            decoder: CountDecoder::new(m.output_start, m.groups, m.group_size),
+            gain: m.gain,
+        }).collect();
+        Ok(Self {
+            sensors,
+            motors,
+            period: Duration::from_nanos(config.control_period_ns()),
+            period_ns: config.control_period_ns(),
+            realtime: config.realtime,
+        })
+    }
+
+    /// Network steps per control period for an engine timestep
+    pub fn steps_per_period(&self, dt_ns: u64) -> Result<usize> {
+        if self.period_ns < dt_ns {
+            return Err(RosError::Config(format!(
+                "control period ({} ns) is shorter than the network timestep ({} ns)",
+                self.period_ns, dt_ns
+            )));
+        }
+        Ok((self.period_ns / dt_ns) as usize)
+    }
+
+    /// Reset the engine and exchange messages until its duration is simulated
+    /// or `max_periods` control periods have run
+    pub fn run<T: Transport>(
+        &mut self,
+        engine: &mut SimulationEngine,
+        transport: &mut T,
+        max_periods: Option<usize>,
+    ) -> Result<BridgeStats> {
+        let dt_ns = engine.params().dt_ns;
+        let steps = self.steps_per_period(dt_ns)?;
+        let total_steps = engine.params().num_steps();
+        engine.reset()?;
+        for sensor in &mut self.sensors {
+            sensor.encoder.reset();
+            sensor.observation.iter_mut().for_each(|v| *v = 0.0);
+        }
+
+        let mut stats = BridgeStats::default();
+        let start = Instant::now();
+        let mut outputs = Vec::new();
+        while max_periods.map_or(true, |max| stats.periods < max)
+            && total_steps - engine.current_step() >= steps
+        {
+            for sensor in &mut self.sensors {
+                if let Some(values) = transport.latest(&sensor.topic)? {
+                    if values.len() != sensor.observation.len() {
+                        return Err(RosError::MessageSize {
+                            topic: sensor.topic.clone(),
+                            expected: sensor.observation.len(),
+                            got: values.len(),
+                        });
+                    }
+                    sensor.observation = values;
+                    stats.sensor_messages += 1;
+                }
+            }
+
+            outputs.clear();
+            for _ in 0..steps {
+                let time_ns = engine.current_step() as u64 * dt_ns;
+                for sensor in &mut self.sensors {
+                    let inputs = sensor.encoder.encode(&sensor.observation, time_ns, dt_ns);
+                    engine.inject_spikes(&inputs)?;
+                }
+                engine.step()?;
+                outputs.extend(engine.drain_output_spikes());
+            }
+            stats.network_steps += steps;
+            stats.output_spikes += outputs.len();
+
+            for motor in &mut self.motors {
+                let command: Vec<f32> = motor.decoder.decode(&outputs)
+                    .into_iter()
+                    .map(|count| count * motor.gain)
+                    .collect();
+                transport.publish(&motor.topic, &command)?;
+            }
+            stats.periods += 1;
+
+            if self.realtime {
+                let deadline = start + self.period * stats.periods as u32;
+                let now = Instant::now();
+                match deadline.checked_duration_since(now) {
+                    Some(remaining) => std::thread::sleep(remaining),
+                    None => {
+                        stats.overruns += 1;
+                        stats.max_lag_ns = stats.max_lag_ns.max((now - deadline).as_nanos() as u64);
+                    }
+                }
+            }
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryTransport;
+    use shnn_runtime::{NetworkBuilder, NetworkConfig, NeuronId, SimulationParams};
+
+    const MAPPING: &str = r#"
+        control_period_ms = 10.0
+        realtime = false
+
+        [[sensors]]
+        topic = "/tilt"
+        input_start = 0
+        scales = [1.0]
+
+        [[motors]]
+        topic = "/cmd"
+        output_start = 2
+        groups = 2
+        gain = 0.5
+    "#;
+
+    /// Positive tilt (neuron 0) drives output 2, negative tilt (neuron 1) drives output 3
+    fn engine(duration_ns: u64) -> SimulationEngine {
+        let config = NetworkConfig { plasticity_enabled: false, ..Default::default() };
+        let network = NetworkBuilder::new()
+            .with_config(config)
+            .add_neurons(0, 4)
+            .add_synapse(NeuronId::new(0), NeuronId::new(2), 1000.0, 0.1)
+            .add_synapse(NeuronId::new(1), NeuronId::new(3), 1000.0, 0.1)
+            .build()
+            .unwrap();
+        SimulationEngine::new(network, SimulationParams::new(100_000, duration_ns).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_sensor_values_steer_motor_commands() {
+        let mut bridge = Bridge::new(&BridgeConfig::from_toml(MAPPING).unwrap()).unwrap();
+        let mut engine = engine(100_000_000);
+        let mut transport = MemoryTransport::new();
+        transport.send("/tilt", vec![-0.5]);
+        transport.send("/tilt", vec![0.8]);
+
+        let stats = bridge.run(&mut engine, &mut transport, Some(3)).unwrap();
+        assert_eq!((stats.periods, stats.network_steps, stats.sensor_messages), (3, 300, 1));
+        assert_eq!(stats.overruns, 0);
+
+        // Only the newest message counts, and it is held for later periods
+        let commands: Vec<_> = transport.published_on("/cmd").collect();
+        assert_eq!(commands.len(), 3);
+        for command in commands {
+            assert!(command[0] > 0.0);
+            assert_eq!(command[1], 0.0);
+        }
+    }
+
+    #[test]
+    fn test_engine_duration_bounds_periods() {
+        let mut bridge = Bridge::new(&BridgeConfig::from_toml(MAPPING).unwrap()).unwrap();
+        // 25 ms = two full 10 ms periods
+        let stats = bridge.run(&mut engine(25_000_000), &mut MemoryTransport::new(), None).unwrap();
+        assert_eq!(stats.periods, 2);
+    }
+
+    #[test]
+    fn test_wrong_message_size_is_reported() {
+        let mut bridge = Bridge::new(&BridgeConfig::from_toml(MAPPING).unwrap()).unwrap();
+        let mut transport = MemoryTransport::new();
+        transport.send("/tilt", vec![0.1, 0.2]);
+        let err = bridge.run(&mut engine(25_000_000), &mut transport, None).unwrap_err();
+        assert!(matches!(err, RosError::MessageSize { expected: 1, got: 2, .. }));
+    }
+}
@@ -0,0 +1,172 @@
+//! TOML mapping between topics and neurons
+//!
+//! ```toml
+//! control_period_ms = 20.0
+//!
+//! [[sensors]]
+//! topic = "/imu/tilt"
+//! input_start = 0
+//! scales = [0.5, 2.0]
+//! max_rate_hz = 500.0
+//!
+//! [[motors]]
+//! topic = "/wheels/cmd"
+//! output_start = 16
+//! groups = 2
+//! group_size = 4
+//! gain = 0.05
+//! ```
+
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::{Result, RosError};
+
+/// Sensor topic rate-encoded onto `2 * scales.len()` input neurons from `input_start`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SensorMapping {
+    /// Topic carrying `Float32MultiArray` messages
+    pub topic: String,
+    /// First input neuron; element `i` drives neurons `input_start + 2i` (positive) and `+ 2i + 1` (negative)
+    pub input_start: u32,
+    /// Value mapped to `max_rate_hz`, per message element
+    pub scales: Vec<f32>,
+    /// Input rate at full scale (Hz)
+    #[serde(default = "default_max_rate_hz")]
+    pub max_rate_hz: f32,
+}
+
+/// Motor topic publishing `gain * spike count` per group of output neurons
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MotorMapping {
+    /// Topic receiving `Float32MultiArray` commands
+    pub topic: String,
+    /// First output neuron
+    pub output_start: u32,
+    /// Values per command
+    pub groups: usize,
+    /// Output neurons per value
+    #[serde(default = "default_group_size")]
+    pub group_size: u32,
+    /// Command units per output spike in one control period
+    #[serde(default = "default_gain")]
+    pub gain: f32,
+}
+
+/// Bridge mapping file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BridgeConfig {
+    /// Wall-clock interval between sensor reads and motor commands (ms)
+    pub control_period_ms: f32,
+    /// Sleep out the rest of each period (false runs as fast as possible)
+    #[serde(default = "default_realtime")]
+    pub realtime: bool,
+    /// Sensor topics
+    #[serde(default)]
+    pub sensors: Vec<SensorMapping>,
+    /// Motor topics
+    #[serde(default)]
+    pub motors: Vec<MotorMapping>,
+}
+
+fn default_max_rate_hz() -> f32 {
+    1000.0
+}
+
+fn default_group_size() -> u32 {
+    1
+}
+
+fn default_gain() -> f32 {
+    1.0
+}
+
+fn default_realtime() -> bool {
+    true
+}
+
+impl BridgeConfig {
+    /// Parse and validate a mapping from TOML text
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text).map_err(|e| RosError::Config(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Read a mapping file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| RosError::Config(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml(&text)
+    }
+
+    /// Control period in nanoseconds
+    pub fn control_period_ns(&self) -> u64 {
+        (self.control_period_ms as f64 * 1_000_000.0) as u64
+    }
+
+    /// Check periods, scales and group sizes
+    pub fn validate(&self) -> Result<()> {
+        if !(self.control_period_ms > 0.0) {
+            return Err(RosError::Config("control_period_ms must be > 0".into()));
+        }
+        if self.sensors.is_empty() && self.motors.is_empty() {
+            return Err(RosError::Config("no sensors or motors mapped".into()));
+        }
+        for sensor in &self.sensors {
+            if sensor.scales.is_empty() || sensor.scales.iter().any(|&s| !(s > 0.0)) {
+                return Err(RosError::Config(format!("sensor '{}': scales must be non-empty and > 0", sensor.topic)));
+            }
+            if sensor.max_rate_hz < 0.0 {
+                return Err(RosError::Config(format!("sensor '{}': max_rate_hz must be >= 0", sensor.topic)));
+            }
+        }
+        for motor in &self.motors {
+            if motor.groups == 0 || motor.group_size == 0 {
+                return Err(RosError::Config(format!("motor '{}': groups and group_size must be >= 1", motor.topic)));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPING: &str = r#"
+        control_period_ms = 20.0
+
+        [[sensors]]
+        topic = "/imu/tilt"
+        input_start = 0
+        scales = [0.5, 2.0]
+
+        [[motors]]
+        topic = "/wheels/cmd"
+        output_start = 16
+        groups = 2
+        group_size = 4
+        gain = 0.05
+    "#;
+
+    #[test]
+    fn test_parse_mapping_with_defaults() {
+        let config = BridgeConfig::from_toml(MAPPING).unwrap();
+        assert!(config.realtime);
+        assert_eq!(config.control_period_ns(), 20_000_000);
+        assert_eq!(config.sensors[0].max_rate_hz, 1000.0);
+        assert_eq!(config.motors[0].group_size, 4);
+        assert_eq!(config.motors[0].gain, 0.05);
+    }
+
+    #[test]
+    fn test_invalid_mappings_are_rejected() {
+        assert!(BridgeConfig::from_toml("control_period_ms = 20.0").is_err());
+        assert!(BridgeConfig::from_toml(&MAPPING.replace("20.0", "0.0")).is_err());
+        assert!(BridgeConfig::from_toml(&MAPPING.replace("[0.5, 2.0]", "[0.5, 0.0]")).is_err());
+        assert!(BridgeConfig::from_toml(&MAPPING.replace("groups = 2", "groups = 0")).is_err());
+        assert!(matches!(BridgeConfig::from_toml("control_period_ms = ["), Err(RosError::Config(_))));
+    }
+}
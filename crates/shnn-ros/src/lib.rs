@@ -0,0 +1,56 @@
+//! ROS 2 bridge for spike I/O
+//!
+//! A [`Bridge`] connects a running [`SimulationEngine`](shnn_runtime::SimulationEngine)
+//! to robot topics, once per control period:
+//!
+//! 1. take the latest message of every sensor topic (`Float32MultiArray`)
+//! 2. rate-encode each sensor onto its input neurons while stepping the network
+//! 3. count the output spikes of every motor mapping and publish the scaled counts
+//!
+//! Topics, neuron ranges and scales come from a TOML [`BridgeConfig`]. The
+//! transport is abstracted by [`Transport`]: [`MemoryTransport`] is always
+//! available for tests and offline replay, and the `ros2` feature adds
+//! `Ros2Transport` backed by `r2r` (which needs a sourced ROS 2 installation).
+
+#![deny(missing_docs)]
+
+use shnn_runtime::RuntimeError;
+
+pub mod bridge;
+pub mod config;
+#[cfg(feature = "ros2")]
+pub mod ros2;
+pub mod transport;
+
+pub use bridge::{Bridge, BridgeStats};
+pub use config::{BridgeConfig, MotorMapping, SensorMapping};
+#[cfg(feature = "ros2")]
+pub use ros2::Ros2Transport;
+pub use transport::{MemoryTransport, Transport};
+
+/// Bridge error type
+#[derive(thiserror::Error, Debug)]
+pub enum RosError {
+    /// Runtime error while stepping the network
+    #[error("Runtime error: {0}")]
+    Runtime(#[from] RuntimeError),
+    /// Invalid or unreadable mapping file
+    #[error("Invalid bridge configuration: {0}")]
+    Config(String),
+    /// Sensor message of the wrong length
+    #[error("Topic '{topic}': expected {expected} values, got {got}")]
+    MessageSize {
+        /// Sensor topic
+        topic: String,
+        /// Values in the mapping's scales
+        expected: usize,
+        /// Values in the received message
+        got: usize,
+    },
+    /// Error reported by the ROS 2 client or another transport
+    #[error("Transport error: {0}")]
+    Transport(String),
+}
+
+/// Result alias for bridge operations
+pub type Result<T> = std::result::Result<T, RosError>;
@@ -0,0 +1,68 @@
+//! `r2r`-backed transport using `std_msgs/msg/Float32MultiArray` on every topic
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::{FutureExt, Stream, StreamExt};
+use r2r::std_msgs::msg::Float32MultiArray;
+use r2r::QosProfile;
+
+use crate::{BridgeConfig, Result, RosError, Transport};
+
+type Subscription = std::pin::Pin<Box<dyn Stream<Item = Float32MultiArray> + Send>>;
+
+/// ROS 2 node subscribed to the mapping's sensor topics and publishing its motor topics
+pub struct Ros2Transport {
+    node: r2r::Node,
+    subscriptions: HashMap<String, Subscription>,
+    publishers: HashMap<String, r2r::Publisher<Float32MultiArray>>,
+}
+
+impl Ros2Transport {
+    /// Create node `name` in `namespace` with one subscription/publisher per mapped topic
+    pub fn new(name: &str, namespace: &str, config: &BridgeConfig) -> Result<Self> {
+        let context = r2r::Context::create().map_err(transport_error)?;
+        let mut node = r2r::Node::create(context, name, namespace).map_err(transport_error)?;
+        let mut subscriptions = HashMap::new();
+        for sensor in &config.sensors {
+            let stream = node
+                .subscribe::<Float32MultiArray>(&sensor.topic, QosProfile::default())
+                .map_err(transport_error)?;
+            subscriptions.insert(sensor.topic.clone(), Box::pin(stream) as Subscription);
+        }
+        let mut publishers = HashMap::new();
+        for motor in &config.motors {
+            let publisher = node
+                .create_publisher::<Float32MultiArray>(&motor.topic, QosProfile::default())
+                .map_err(transport_error)?;
+            publishers.insert(motor.topic.clone(), publisher);
+        }
+        Ok(Self { node, subscriptions, publishers })
+    }
+}
+
+impl Transport for Ros2Transport {
+    fn latest(&mut self, topic: &str) -> Result<Option<Vec<f32>>> {
+        // Deliver whatever arrived since the last period without blocking
+        self.node.spin_once(Duration::ZERO);
+        let Some(stream) = self.subscriptions.get_mut(topic) else {
+            return Err(RosError::Transport(format!("not subscribed to '{}'", topic)));
+        };
+        let mut newest = None;
+        while let Some(Some(message)) = stream.next().now_or_never() {
+            newest = Some(message.data);
+        }
+        Ok(newest)
+    }
+
+    fn publish(&mut self, topic: &str, values: &[f32]) -> Result<()> {
+        let publisher = self.publishers.get(topic)
+            .ok_or_else(|| RosError::Transport(format!("no publisher for '{}'", topic)))?;
+        let message = Float32MultiArray { data: values.to_vec(), ..Default::default() };
+        publisher.publish(&message).map_err(transport_error)
+    }
+}
+
+fn transport_error(e: r2r::Error) -> RosError {
+    RosError::Transport(e.to_string())
+}
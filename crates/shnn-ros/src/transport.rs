@@ -0,0 +1,58 @@
+//! Message transport between the bridge and the robot
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::Result;
+
+/// Source of sensor messages and sink for motor commands
+pub trait Transport {
+    /// Most recent message received on `topic` since the last call, if any
+    fn latest(&mut self, topic: &str) -> Result<Option<Vec<f32>>>;
+
+    /// Publish a command on `topic`
+    fn publish(&mut self, topic: &str, values: &[f32]) -> Result<()>;
+}
+
+/// In-process transport: queued sensor messages in, recorded commands out
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+    inbox: HashMap<String, VecDeque<Vec<f32>>>,
+    published: Vec<(String, Vec<f32>)>,
+}
+
+impl MemoryTransport {
+    /// Empty transport
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a sensor message; [`Transport::latest`] returns the newest queued one
+    pub fn send(&mut self, topic: impl Into<String>, values: Vec<f32>) {
+        self.inbox.entry(topic.into()).or_default().push_back(values);
+    }
+
+    /// Every command published so far, in order
+    pub fn published(&self) -> &[(String, Vec<f32>)] {
+        &self.published
+    }
+
+    /// Commands published on `topic`
+    pub fn published_on<'a>(&'a self, topic: &'a str) -> impl Iterator<Item = &'a [f32]> + 'a {
+        self.published.iter().filter(move |(t, _)| t == topic).map(|(_, v)| v.as_slice())
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn latest(&mut self, topic: &str) -> Result<Option<Vec<f32>>> {
+        Ok(self.inbox.get_mut(topic).and_then(|queue| {
+            let newest = queue.pop_back();
+            queue.clear();
+            newest
+        }))
+    }
+
+    fn publish(&mut self, topic: &str, values: &[f32]) -> Result<()> {
+        self.published.push((topic.to_string(), values.to_vec()));
+        Ok(())
+    }
+}
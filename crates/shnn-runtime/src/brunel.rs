@@ -0,0 +1,178 @@
+//! Balanced excitatory/inhibitory network (Brunel 2000)
+//!
+//! [`BrunelNetwork`] lays out `n_exc` excitatory neurons (ids `0..n_exc`)
+//! followed by `n_inh` inhibitory ones. All are LIF neurons with τ = 20 ms,
+//! threshold 20 mV and reset 10 mV above rest and a 2 ms refractory period.
+//! Every neuron receives exactly `C_E = p·n_exc` excitatory and
+//! `C_I = p·n_inh` inhibitory inputs, without self-connections. An
+//! excitatory synapse depolarizes by the PSP `J` and an inhibitory one
+//! hyperpolarizes by `g·J`. External drive stands for `C_E` excitatory
+//! inputs from outside the network, each a Poisson process at `ν_ext`.
+//!
+//! The engine applies a synapse weight as input current for one step, so a
+//! PSP of `J` mV needs the weight `J·τ_m / (R_m·dt)`.
+
+use crate::{
+    error::{Result, RuntimeError},
+    neuron::LIFParams,
+    NeuronId,
+};
+use rand::{rngs::StdRng, seq::index, SeedableRng};
+
+/// Largest firing probability per step of one external Poisson stimulus
+pub const MAX_STIMULUS_PROBABILITY: f64 = 0.1;
+
+/// Brunel network recipe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BrunelNetwork {
+    /// Excitatory neurons
+    pub n_exc: u32,
+    /// Inhibitory neurons
+    pub n_inh: u32,
+    /// Connection probability, which fixes the in-degrees
+    pub p: f32,
+    /// Relative strength of inhibition
+    pub g: f32,
+    /// Excitatory PSP (mV)
+    pub j_mv: f32,
+    /// Rate of each external input (Hz)
+    pub nu_ext_hz: f32,
+}
+
+impl BrunelNetwork {
+    /// Check population sizes, p within (0, 1], g >= 0, J > 0 and ν_ext >= 0
+    pub fn validate(&self) -> Result<()> {
+        if self.n_exc == 0 {
+            return Err(invalid("n_exc", "0", ">= 1"));
+        }
+        if self.n_inh == 0 {
+            return Err(invalid("n_inh", "0", ">= 1"));
+        }
+        if self.n_exc.checked_add(self.n_inh).is_none() {
+            return Err(invalid("n_inh", &self.n_inh.to_string(), "n_exc + n_inh within u32"));
+        }
+        if !(self.p > 0.0 && self.p <= 1.0) {
+            return Err(invalid("p", &self.p.to_string(), "within (0, 1]"));
+        }
+        if !(self.g.is_finite() && self.g >= 0.0) {
+            return Err(invalid("g", &self.g.to_string(), ">= 0 and finite"));
+        }
+        if !(self.j_mv.is_finite() && self.j_mv > 0.0) {
+            return Err(invalid("j", &self.j_mv.to_string(), "> 0 and finite"));
+        }
+        if !(self.nu_ext_hz.is_finite() && self.nu_ext_hz >= 0.0) {
+            return Err(invalid("nu_ext", &self.nu_ext_hz.to_string(), ">= 0 and finite"));
+        }
+        Ok(())
+    }
+
+    /// Total neuron count
+    pub fn neurons(&self) -> u32 {
+        self.n_exc + self.n_inh
+    }
+
+    /// Membrane parameters shared by both populations
+    pub fn lif_params() -> LIFParams {
+        LIFParams { v_reset: -60.0, ..LIFParams::default() }
+    }
+
+    /// Excitatory and inhibitory in-degrees (C_E, C_I)
+    pub fn in_degrees(&self) -> (u32, u32) {
+        let degree = |n: u32| ((self.p as f64 * n as f64).round() as u32).max(1);
+        (degree(self.n_exc), degree(self.n_inh))
+    }
+
+    /// Weight of an excitatory synapse at step `dt_ms`; inhibitory synapses carry `-g` times it
+    pub fn excitatory_weight(&self, dt_ms: f32) -> f32 {
+        let lif = Self::lif_params();
+        self.j_mv * lif.tau_m / (lif.r_m * dt_ms)
+    }
+
+    /// Poisson stimuli per neuron and the rate of each (Hz) for the `C_E·ν_ext` external drive
+    ///
+    /// The engine draws at most one event per stimulus per step, so the drive
+    /// is split until each stimulus fires with probability at most
+    /// [`MAX_STIMULUS_PROBABILITY`] per step.
+    pub fn external_drive(&self, dt_ms: f32) -> (u32, f32) {
+        let total_hz = self.in_degrees().0 as f64 * self.nu_ext_hz as f64;
+        let per_step = total_hz * dt_ms as f64 / 1000.0;
+        let count = ((per_step / MAX_STIMULUS_PROBABILITY).ceil() as u32).max(1);
+        (count, (total_hz / count as f64) as f32)
+    }
+
+    /// Draw `(pre, post, weight)` synapses at step `dt_ms`
+    ///
+    /// Inputs are drawn post by post, excitatory then inhibitory, from one
+    /// seeded stream, so a seed fixes the whole connection pattern. A
+    /// population smaller than its in-degree plus the target itself gives
+    /// every other member.
+    pub fn connect(&self, dt_ms: f32, seed: u64) -> Result<Vec<(NeuronId, NeuronId, f32)>> {
+        self.validate()?;
+        if !(dt_ms.is_finite() && dt_ms > 0.0) {
+            return Err(invalid("dt", &dt_ms.to_string(), "> 0 and finite"));
+        }
+        let (c_exc, c_inh) = self.in_degrees();
+        let w_exc = self.excitatory_weight(dt_ms);
+        let populations = [(0, self.n_exc, c_exc, w_exc), (self.n_exc, self.n_inh, c_inh, -self.g * w_exc)];
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut synapses = Vec::with_capacity(self.neurons() as usize * (c_exc + c_inh) as usize);
+        for post in 0..self.neurons() {
+            for &(first, size, degree, weight) in &populations {
+                // Candidates skip the target itself when it belongs to this population
+                let own = post.checked_sub(first).filter(|&i| i < size);
+                let candidates = size - own.is_some() as u32;
+                for i in index::sample(&mut rng, candidates as usize, degree.min(candidates) as usize).into_iter() {
+                    let offset = match own {
+                        Some(skip) if i as u32 >= skip => i as u32 + 1,
+                        _ => i as u32,
+                    };
+                    synapses.push((NeuronId::new(first + offset), NeuronId::new(post), weight));
+                }
+            }
+        }
+        Ok(synapses)
+    }
+}
+
+fn invalid(parameter: &str, value: &str, constraint: &str) -> RuntimeError {
+    RuntimeError::invalid_parameter(parameter, value, constraint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_brunel_connectivity() {
+        let net = BrunelNetwork { n_exc: 40, n_inh: 10, p: 0.2, g: 5.0, j_mv: 0.1, nu_ext_hz: 20.0 };
+        assert_eq!(net.in_degrees(), (8, 2));
+        // 0.1 mV · 20 ms / (10 MΩ · 0.1 ms)
+        assert!((net.excitatory_weight(0.1) - 2.0).abs() < 1e-6);
+
+        let synapses = net.connect(0.1, 7).expect("Should connect");
+        assert_eq!(synapses, net.connect(0.1, 7).expect("Should connect"));
+        assert_ne!(synapses, net.connect(0.1, 8).expect("Should connect"));
+        assert_eq!(synapses.len(), 50 * 10);
+        for post in 0..50 {
+            let inputs: Vec<_> = synapses.iter().filter(|(_, to, _)| to.raw() == post).collect();
+            let pres: BTreeSet<u32> = inputs.iter().map(|(from, _, _)| from.raw()).collect();
+            assert_eq!(pres.len(), 10);
+            assert!(!pres.contains(&post));
+            assert_eq!(pres.iter().filter(|&&pre| pre < 40).count(), 8);
+        }
+        assert!(synapses.iter().all(|(pre, _, w)| if pre.raw() < 40 { *w > 0.0 } else { (*w + 10.0).abs() < 1e-5 }));
+
+        // 8 inputs at 20 Hz = 160 Hz: 0.016 per 0.1 ms step fits one stimulus, 0.16 per 1 ms step needs two
+        assert_eq!(net.external_drive(0.1), (1, 160.0));
+        assert_eq!(net.external_drive(1.0), (2, 80.0));
+
+        // A population no larger than the in-degree connects to every other member
+        let dense = BrunelNetwork { p: 1.0, ..net };
+        assert_eq!(dense.connect(0.1, 1).expect("Should connect").len(), 50 * 49);
+        assert!(BrunelNetwork { p: 0.0, ..net }.validate().is_err());
+        assert!(BrunelNetwork { n_inh: 0, ..net }.validate().is_err());
+        assert!(BrunelNetwork { g: -1.0, ..net }.validate().is_err());
+    }
+}
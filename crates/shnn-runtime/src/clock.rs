@@ -0,0 +1,12 @@
+//! Monotonic clock that also works in browsers
+//!
+//! `std::time::Instant` panics on `wasm32-unknown-unknown`; there the
+//! `performance.now()`-backed `Instant` from `web-time` is used instead.
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub use web_time::Instant;
+
+/// Whether the thread may block in `std::thread::sleep` (browsers cannot)
+pub const CAN_SLEEP: bool = !cfg!(all(target_arch = "wasm32", target_os = "unknown"));
@@ -0,0 +1,162 @@
+//! Pause/resume and live parameter changes for a running simulation
+//!
+//! A [`SimulationControl`] is a cloneable handle shared between the engine and
+//! another thread (e.g. an HTTP server). The engine checks it before every
+//! step: while paused it blocks, and queued [`ParamUpdate`]s are applied at
+//! that step boundary, so a change never lands in the middle of a step.
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use crate::{error::{Result, RuntimeError}, NeuronId};
+
+/// A parameter change applied between steps
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamUpdate {
+    /// Set the rate of every Poisson stimulus driving `neuron`
+    StimulusRate {
+        /// Stimulated neuron
+        neuron: NeuronId,
+        /// New rate (Hz)
+        rate_hz: f32,
+    },
+    /// Hold the STDP modulation level constant from now on (replaces any modulator)
+    ModulatorLevel(f32),
+}
+
+impl ParamUpdate {
+    /// Check the new value is usable
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            ParamUpdate::StimulusRate { rate_hz, .. } if !(rate_hz.is_finite() && rate_hz >= 0.0) => {
+                Err(RuntimeError::invalid_parameter("rate_hz", &rate_hz.to_string(), ">= 0 and finite"))
+            }
+            ParamUpdate::ModulatorLevel(level) if !level.is_finite() => {
+                Err(RuntimeError::invalid_parameter("level", &level.to_string(), "finite"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Snapshot of a controlled run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ControlStatus {
+    /// Whether the engine is (or will stop at the next step) paused
+    pub paused: bool,
+    /// Whether the run has finished or been stopped
+    pub finished: bool,
+    /// Steps executed so far
+    pub step: usize,
+    /// Simulated time reached (ns)
+    pub time_ns: u64,
+    /// Parameter updates applied so far
+    pub applied: usize,
+}
+
+#[derive(Debug, Default)]
+struct ControlState {
+    status: ControlStatus,
+    stopped: bool,
+    pending: Vec<ParamUpdate>,
+}
+
+/// Shared pause/resume/parameter handle for a [`crate::SimulationEngine`]
+#[derive(Debug, Clone, Default)]
+pub struct SimulationControl {
+    shared: Arc<(Mutex<ControlState>, Condvar)>,
+}
+
+impl SimulationControl {
+    /// Handle for a run that starts unpaused
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold the run before its next step
+    pub fn pause(&self) {
+        self.state().status.paused = true;
+    }
+
+    /// Continue a paused run
+    pub fn resume(&self) {
+        self.state().status.paused = false;
+        self.shared.1.notify_all();
+    }
+
+    /// End the run before its next step, even while paused
+    pub fn stop(&self) {
+        self.state().stopped = true;
+        self.shared.1.notify_all();
+    }
+
+    /// Queue `update` for the next step boundary
+    pub fn update(&self, update: ParamUpdate) -> Result<()> {
+        update.validate()?;
+        self.state().pending.push(update);
+        Ok(())
+    }
+
+    /// Current progress and pause state
+    pub fn status(&self) -> ControlStatus {
+        self.state().status
+    }
+
+    /// Record progress, block while paused, then hand over queued updates;
+    /// returns `None` once the run should stop
+    pub(crate) fn checkpoint(&self, step: usize, time_ns: u64) -> Option<Vec<ParamUpdate>> {
+        let (lock, resumed) = &*self.shared;
+        let mut state = lock.lock().unwrap_or_else(|e| e.into_inner());
+        state.status.step = step;
+        state.status.time_ns = time_ns;
+        while state.status.paused && !state.stopped {
+            state = resumed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        if state.stopped {
+            return None;
+        }
+        let updates = std::mem::take(&mut state.pending);
+        state.status.applied += updates.len();
+        Some(updates)
+    }
+
+    /// Mark the run finished at `step`
+    pub(crate) fn finish(&self, step: usize, time_ns: u64) {
+        let mut state = self.state();
+        state.status.step = step;
+        state.status.time_ns = time_ns;
+        state.status.finished = true;
+    }
+
+    fn state(&self) -> MutexGuard<'_, ControlState> {
+        self.shared.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pause_blocks_until_resume() {
+        let control = SimulationControl::new();
+        control.update(ParamUpdate::ModulatorLevel(0.5)).expect("Should queue update");
+        assert!(control.update(ParamUpdate::StimulusRate { neuron: NeuronId::new(0), rate_hz: -1.0 }).is_err());
+        control.pause();
+
+        let engine_side = control.clone();
+        let worker = std::thread::spawn(move || engine_side.checkpoint(3, 300));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!worker.is_finished());
+        assert_eq!(control.status().step, 3);
+
+        control.resume();
+        let updates = worker.join().expect("Should join").expect("Should not stop");
+        assert_eq!(updates, vec![ParamUpdate::ModulatorLevel(0.5)]);
+        assert_eq!(control.status().applied, 1);
+
+        control.pause();
+        control.stop();
+        assert!(control.checkpoint(4, 400).is_none());
+    }
+}
@@ -0,0 +1,350 @@
+//! Axonal delays: per-synapse delay distributions and the delivery queue
+//!
+//! [`DelayDistribution`] samples heterogeneous synaptic delays (uniform, normal
+//! jitter, gamma). [`DelayQueue`] holds in-flight events in a bucketed ring
+//! buffer keyed by delivery time, so pushing and collecting due events costs
+//! O(1) per event regardless of how many distinct delays are in flight.
+//! [`SubDtDelay`] decides what happens to delays shorter than the time step.
+
+use core::str::FromStr;
+
+use crate::error::{Result, RuntimeError};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Distribution of synaptic delays (ms); samples are never negative
+#[derive(Debug, Clone, PartialEq)]
+pub enum DelayDistribution {
+    /// Every synapse gets the same delay
+    Constant(f32),
+    /// Uniform on `[min, max]`
+    Uniform {
+        /// Lower bound (ms)
+        min: f32,
+        /// Upper bound (ms)
+        max: f32,
+    },
+    /// Gaussian jitter around `mean`, truncated below at `min`
+    Normal {
+        /// Mean delay (ms)
+        mean: f32,
+        /// Standard deviation (ms)
+        std: f32,
+        /// Lower clamp (ms)
+        min: f32,
+    },
+    /// Gamma with mean `shape * scale`
+    Gamma {
+        /// Shape k
+        shape: f32,
+        /// Scale θ (ms)
+        scale: f32,
+    },
+}
+
+impl DelayDistribution {
+    /// Validate parameters
+    pub fn validate(&self) -> Result<()> {
+        let check = |name: &str, value: f32, ok: bool, constraint: &str| {
+            if ok && !value.is_nan() {
+                Ok(())
+            } else {
+                Err(RuntimeError::invalid_parameter(name, value.to_string(), constraint))
+            }
+        };
+        match *self {
+            DelayDistribution::Constant(d) => check("delay", d, d >= 0.0, ">= 0.0"),
+            DelayDistribution::Uniform { min, max } => {
+                check("delay_min", min, min >= 0.0, ">= 0.0")?;
+                check("delay_max", max, max >= min, ">= delay_min")
+            }
+            DelayDistribution::Normal { mean, std, min } => {
+                check("delay", mean, mean >= 0.0, ">= 0.0")?;
+                check("delay_std", std, std >= 0.0, ">= 0.0")?;
+                check("delay_min", min, min >= 0.0, ">= 0.0")
+            }
+            DelayDistribution::Gamma { shape, scale } => {
+                check("delay_shape", shape, shape > 0.0, "> 0.0")?;
+                check("delay_scale", scale, scale > 0.0, "> 0.0")
+            }
+        }
+    }
+
+    /// Draw one delay (ms)
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        match *self {
+            DelayDistribution::Constant(d) => d,
+            DelayDistribution::Uniform { min, max } => min + (max - min) * rng.gen::<f32>(),
+            DelayDistribution::Normal { mean, std, min } => (mean + std * standard_normal(rng)).max(min),
+            DelayDistribution::Gamma { shape, scale } => scale * standard_gamma(rng, shape),
+        }
+    }
+}
+
+/// Seeded stream of delays drawn from a [`DelayDistribution`]
+#[derive(Debug, Clone)]
+pub struct DelaySampler {
+    dist: DelayDistribution,
+    rng: StdRng,
+}
+
+impl DelaySampler {
+    /// Validate `dist` and seed the stream
+    pub fn new(dist: DelayDistribution, seed: u64) -> Result<Self> {
+        dist.validate()?;
+        Ok(Self { dist, rng: StdRng::seed_from_u64(seed) })
+    }
+
+    /// Next delay (ms)
+    pub fn next_delay(&mut self) -> f32 {
+        self.dist.sample(&mut self.rng)
+    }
+}
+
+/// Box–Muller standard normal
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f32 {
+    let u1 = 1.0 - rng.gen::<f64>(); // (0, 1]
+    let u2 = rng.gen::<f64>();
+    ((-2.0 * u1.ln()).sqrt() * (2.0 * core::f64::consts::PI * u2).cos()) as f32
+}
+
+/// Marsaglia–Tsang gamma(shape, 1)
+fn standard_gamma<R: Rng + ?Sized>(rng: &mut R, shape: f32) -> f32 {
+    if shape < 1.0 {
+        // Boost: G(k) = G(k + 1) * U^(1/k)
+        let u = 1.0 - rng.gen::<f32>();
+        return standard_gamma(rng, shape + 1.0) * u.powf(1.0 / shape);
+    }
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = standard_normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u = 1.0 - rng.gen::<f32>();
+        if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+            return d * v;
+        }
+    }
+}
+
+/// Handling of synaptic delays shorter than dt, e.g. from the
+/// `sub_dt_delay` attribute of `runtime.simulate.run`
+///
+/// Spikes are delivered at step boundaries, so a 0.1 ms delay at dt = 1 ms
+/// cannot arrive 0.1 ms after its spike.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubDtDelay {
+    /// Deliver the whole input in the first step ending at or after arrival
+    #[default]
+    NextStep,
+    /// Spread input arriving at `a` over [a, a + dt], split between the
+    /// arrival step and the next by overlap
+    Interpolate,
+    /// Refuse to build networks with delays below dt
+    Reject,
+}
+
+impl SubDtDelay {
+    /// Canonical name used in NIR attributes
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SubDtDelay::NextStep => "next_step",
+            SubDtDelay::Interpolate => "interpolate",
+            SubDtDelay::Reject => "reject",
+        }
+    }
+
+    /// Share of an input arriving at `arrival_ns` that falls in the step ending at `step_end_ns`
+    pub fn arrival_fraction(self, arrival_ns: u64, step_end_ns: u64, dt_ns: u64) -> f32 {
+        match self {
+            SubDtDelay::Interpolate if dt_ns > 0 => {
+                (step_end_ns.saturating_sub(arrival_ns).min(dt_ns) as f64 / dt_ns as f64) as f32
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+impl FromStr for SubDtDelay {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "next_step" => Ok(SubDtDelay::NextStep),
+            "interpolate" => Ok(SubDtDelay::Interpolate),
+            "reject" => Ok(SubDtDelay::Reject),
+            other => Err(RuntimeError::invalid_parameter("sub_dt_delay", other, "next_step | interpolate | reject")),
+        }
+    }
+}
+
+/// Ring buffer of time buckets holding events until their delivery time
+#[derive(Debug, Clone)]
+pub struct DelayQueue<T> {
+    bucket_ns: u64,
+    buckets: Vec<Vec<(u64, T)>>,
+    /// Ring slot of the earliest bucket
+    head: usize,
+    /// Absolute bucket index (time / bucket_ns) stored at `head`
+    base: u64,
+    len: usize,
+}
+
+impl<T> DelayQueue<T> {
+    /// Empty queue grouping events into buckets `bucket_ns` wide
+    pub fn new(bucket_ns: u64) -> Self {
+        Self {
+            bucket_ns: bucket_ns.max(1),
+            buckets: (0..16).map(|_| Vec::new()).collect(),
+            head: 0,
+            base: 0,
+            len: 0,
+        }
+    }
+
+    /// Schedule `item` for delivery at `time_ns` (past times are delivered next)
+    pub fn push(&mut self, time_ns: u64, item: T) {
+        let offset = (time_ns / self.bucket_ns).saturating_sub(self.base) as usize;
+        if offset >= self.buckets.len() {
+            self.grow(offset + 1);
+        }
+        let slot = (self.head + offset) % self.buckets.len();
+        self.buckets[slot].push((time_ns, item));
+        self.len += 1;
+    }
+
+    /// Move every event due at or before `now_ns` into `out`
+    pub fn pop_due(&mut self, now_ns: u64, out: &mut Vec<T>) {
+        let target = now_ns / self.bucket_ns;
+        while self.base < target && self.len > 0 {
+            let drained = core::mem::take(&mut self.buckets[self.head]);
+            self.len -= drained.len();
+            out.extend(drained.into_iter().map(|(_, item)| item));
+            self.head = (self.head + 1) % self.buckets.len();
+            self.base += 1;
+        }
+        if self.len == 0 {
+            self.base = self.base.max(target);
+            return;
+        }
+        if self.base == target {
+            let bucket = &mut self.buckets[self.head];
+            let mut i = 0;
+            while i < bucket.len() {
+                if bucket[i].0 <= now_ns {
+                    out.push(bucket.swap_remove(i).1);
+                    self.len -= 1;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    /// Keep only events for which `keep` returns true
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        for bucket in &mut self.buckets {
+            bucket.retain(|(_, item)| keep(item));
+        }
+        self.len = self.buckets.iter().map(Vec::len).sum();
+    }
+
+    /// Drop all events and rewind to time zero
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.head = 0;
+        self.base = 0;
+        self.len = 0;
+    }
+
+    /// Number of events in flight
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no events are in flight
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn grow(&mut self, min_len: usize) {
+        let new_len = min_len.next_power_of_two().max(self.buckets.len() * 2);
+        let mut buckets: Vec<Vec<(u64, T)>> = (0..new_len).map(|_| Vec::new()).collect();
+        let old_len = self.buckets.len();
+        for (i, slot) in buckets.iter_mut().take(old_len).enumerate() {
+            *slot = core::mem::take(&mut self.buckets[(self.head + i) % old_len]);
+        }
+        self.buckets = buckets;
+        self.head = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_delay_queue_delivery_order() {
+        let mut queue = DelayQueue::new(100);
+        // Spread far beyond the initial ring to force growth
+        for (t, id) in [(50, 0), (5_000, 1), (150, 2), (120, 3), (99_999, 4)] {
+            queue.push(t, id);
+        }
+        assert_eq!(queue.len(), 5);
+
+        let mut out = Vec::new();
+        queue.pop_due(130, &mut out);
+        out.sort_unstable();
+        assert_eq!(out, vec![0, 3]);
+
+        out.clear();
+        queue.pop_due(10_000, &mut out);
+        out.sort_unstable();
+        assert_eq!(out, vec![1, 2]);
+
+        // Late pushes are delivered at the next pop
+        queue.push(0, 5);
+        out.clear();
+        queue.pop_due(10_001, &mut out);
+        assert_eq!(out, vec![5]);
+
+        queue.retain(|&id| id != 4);
+        assert!(queue.is_empty());
+        queue.push(20_000, 6);
+        queue.clear();
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_delay_distribution_samples() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let n = 20_000;
+        let mean_of = |dist: &DelayDistribution, rng: &mut StdRng| {
+            (0..n).map(|_| dist.sample(rng)).sum::<f32>() / n as f32
+        };
+
+        let uniform = DelayDistribution::Uniform { min: 1.0, max: 3.0 };
+        assert!((0..1000).map(|_| uniform.sample(&mut rng)).all(|d| (1.0..=3.0).contains(&d)));
+        assert!((mean_of(&uniform, &mut rng) - 2.0).abs() < 0.05);
+
+        let normal = DelayDistribution::Normal { mean: 2.0, std: 0.5, min: 0.0 };
+        assert!((mean_of(&normal, &mut rng) - 2.0).abs() < 0.05);
+        let clamped = DelayDistribution::Normal { mean: 0.0, std: 1.0, min: 0.5 };
+        assert!((0..1000).map(|_| clamped.sample(&mut rng)).all(|d| d >= 0.5));
+
+        for shape in [0.5, 3.0] {
+            let gamma = DelayDistribution::Gamma { shape, scale: 2.0 };
+            let mean = mean_of(&gamma, &mut rng);
+            assert!((mean - shape * 2.0).abs() < 0.1 * shape * 2.0, "shape {} mean {}", shape, mean);
+        }
+
+        assert!(DelayDistribution::Uniform { min: 3.0, max: 1.0 }.validate().is_err());
+        assert!(DelayDistribution::Gamma { shape: 0.0, scale: 1.0 }.validate().is_err());
+        assert!(DelayDistribution::Normal { mean: 1.0, std: -0.1, min: 0.0 }.validate().is_err());
+        assert!(DelayDistribution::Constant(1.0).validate().is_ok());
+    }
+}
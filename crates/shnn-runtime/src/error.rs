@@ -71,6 +71,15 @@ pub enum RuntimeError {
         reason: String 
     },
 
+    /// Network builder validation failure
+    #[error("[{code}] {message}")]
+    Build {
+        /// Stable error code
+        code: BuildCode,
+        /// What was wrong
+        message: String,
+    },
+
     /// Resource exhaustion
     #[error("Resource exhausted: {resource} (limit: {limit})")]
     ResourceExhausted { 
@@ -81,6 +90,50 @@ pub enum RuntimeError {
     },
 }
 
+/// Stable codes for [`NetworkBuilder`](crate::network::NetworkBuilder) validation
+///
+/// `B0xx` codes are errors returned by `build`; `B1xx` codes are warnings
+/// reported by `validate`/`build_with_warnings` unless the configuration
+/// makes them errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildCode {
+    /// The network has no neurons
+    NoNeurons,
+    /// A neuron id was added twice
+    DuplicateNeuron,
+    /// A synapse endpoint is not a neuron of the network
+    UnknownNeuron,
+    /// Two synapses connect the same pair under [`DuplicateSynapses::Reject`](crate::network::DuplicateSynapses::Reject)
+    DuplicateSynapse,
+    /// Neuron ids skip values (error when gaps are denied, warning otherwise)
+    NeuronIdGap,
+    /// Negative, non-finite or overflowing synaptic delay
+    InvalidDelay,
+    /// Synaptic delay shorter than the time step (error under [`SubDtDelay::Reject`](crate::delay::SubDtDelay::Reject))
+    DelayBelowDt,
+}
+
+impl BuildCode {
+    /// Code string, e.g. `B004`
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            BuildCode::NoNeurons => "B001",
+            BuildCode::DuplicateNeuron => "B002",
+            BuildCode::UnknownNeuron => "B003",
+            BuildCode::DuplicateSynapse => "B004",
+            BuildCode::NeuronIdGap => "B005",
+            BuildCode::InvalidDelay => "B006",
+            BuildCode::DelayBelowDt => "B101",
+        }
+    }
+}
+
+impl std::fmt::Display for BuildCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl RuntimeError {
     /// Create an invalid configuration error
     pub fn invalid_config(reason: impl Into<String>) -> Self {
@@ -102,6 +155,31 @@ impl RuntimeError {
         }
     }
 
+    /// Create an invalid parameter error for a time that failed unit conversion
+    pub fn invalid_time(
+        parameter: impl Into<String>,
+        value: impl ToString,
+        error: shnn_types::TypesError,
+    ) -> Self {
+        Self::invalid_parameter(parameter, value.to_string(), format!("a representable time ({})", error))
+    }
+
+    /// Create a builder validation error
+    pub fn build_error(code: BuildCode, message: impl Into<String>) -> Self {
+        Self::Build {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Builder validation code, if this is a build error
+    pub fn build_code(&self) -> Option<BuildCode> {
+        match self {
+            Self::Build { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
     /// Create a simulation step error
     pub fn simulation_step(time_ns: u64, reason: impl Into<String>) -> Self {
         Self::SimulationStep {
@@ -0,0 +1,176 @@
+//! Post-simulation expectations
+//!
+//! Expectations are checked against a finished [`SimulationResult`], letting a
+//! NIR program double as a regression test for the simulator (`test` dialect).
+
+use crate::{
+    error::{Result, RuntimeError},
+    simulation::SimulationResult,
+    NeuronId,
+};
+
+use core::fmt;
+
+/// Condition on a finished simulation over neurons `start..=end`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expectation {
+    /// Mean per-neuron firing rate over the whole run lies in `[min_hz, max_hz]`
+    Rate {
+        /// First neuron
+        start: NeuronId,
+        /// Last neuron (inclusive)
+        end: NeuronId,
+        /// Lower bound (Hz)
+        min_hz: Option<f32>,
+        /// Upper bound (Hz)
+        max_hz: Option<f32>,
+    },
+    /// Total number of spikes lies in `[min, max]`
+    SpikeCount {
+        /// First neuron
+        start: NeuronId,
+        /// Last neuron (inclusive)
+        end: NeuronId,
+        /// Lower bound
+        min: Option<u64>,
+        /// Upper bound
+        max: Option<u64>,
+    },
+}
+
+/// Outcome of checking one [`Expectation`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectationOutcome {
+    /// The checked expectation
+    pub expectation: Expectation,
+    /// Observed rate (Hz) or spike count
+    pub observed: f64,
+    /// Whether the observed value is within bounds
+    pub passed: bool,
+}
+
+impl Expectation {
+    /// Validate ranges and bounds
+    pub fn validate(&self) -> Result<()> {
+        let (start, end) = self.range();
+        if start.raw() > end.raw() {
+            return Err(RuntimeError::invalid_parameter(
+                "neurons",
+                format!("{}..{}", start.raw(), end.raw()),
+                "start <= end",
+            ));
+        }
+        match *self {
+            Expectation::Rate { min_hz, max_hz, .. } => {
+                if min_hz.is_none() && max_hz.is_none() {
+                    return Err(RuntimeError::invalid_parameter("min_hz", "none", "at least one of min_hz, max_hz"));
+                }
+                if let (Some(min), Some(max)) = (min_hz, max_hz) {
+                    if min.is_nan() || max.is_nan() || min > max {
+                        return Err(RuntimeError::invalid_parameter("max_hz", max.to_string(), ">= min_hz"));
+                    }
+                }
+            }
+            Expectation::SpikeCount { min, max, .. } => {
+                if min.is_none() && max.is_none() {
+                    return Err(RuntimeError::invalid_parameter("min", "none", "at least one of min, max"));
+                }
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(RuntimeError::invalid_parameter("max", max.to_string(), ">= min"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Inclusive neuron range the expectation covers
+    pub fn range(&self) -> (NeuronId, NeuronId) {
+        match *self {
+            Expectation::Rate { start, end, .. } | Expectation::SpikeCount { start, end, .. } => (start, end),
+        }
+    }
+
+    /// Check against a finished run
+    pub fn check(&self, result: &SimulationResult) -> ExpectationOutcome {
+        let (start, end) = self.range();
+        let count = result.spikes.iter()
+            .filter(|spike| (start.raw()..=end.raw()).contains(&spike.neuron_id.raw()))
+            .count() as u64;
+        let (observed, passed) = match *self {
+            Expectation::Rate { min_hz, max_hz, .. } => {
+                let population = (end.raw() - start.raw()) as f64 + 1.0;
+                let duration_s = result.duration_ns as f64 / 1_000_000_000.0;
+                let rate = if duration_s > 0.0 { count as f64 / population / duration_s } else { 0.0 };
+                let passed = min_hz.map_or(true, |min| rate >= min as f64)
+                    && max_hz.map_or(true, |max| rate <= max as f64);
+                (rate, passed)
+            }
+            Expectation::SpikeCount { min, max, .. } => {
+                let passed = min.map_or(true, |min| count >= min) && max.map_or(true, |max| count <= max);
+                (count as f64, passed)
+            }
+        };
+        ExpectationOutcome { expectation: self.clone(), observed, passed }
+    }
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bound = |v: Option<String>| v.unwrap_or_else(|| "-".into());
+        match self {
+            Expectation::Rate { start, end, min_hz, max_hz } => write!(
+                f,
+                "rate[{}..{}] in [{}, {}] Hz",
+                start.raw(),
+                end.raw(),
+                bound(min_hz.map(|v| v.to_string())),
+                bound(max_hz.map(|v| v.to_string())),
+            ),
+            Expectation::SpikeCount { start, end, min, max } => write!(
+                f,
+                "spike_count[{}..{}] in [{}, {}]",
+                start.raw(),
+                end.raw(),
+                bound(min.map(|v| v.to_string())),
+                bound(max.map(|v| v.to_string())),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Spike, Time};
+
+    #[test]
+    fn test_expectations_check() {
+        let mut result = SimulationResult::new(500_000_000);
+        for (id, ms) in [(0, 10), (0, 20), (1, 30), (5, 40)] {
+            result.spikes.push(Spike::new(NeuronId::new(id), Time::from_millis(ms)));
+        }
+        let (a, b) = (NeuronId::new(0), NeuronId::new(1));
+
+        // 3 spikes / 2 neurons / 0.5 s = 3 Hz
+        let rate = Expectation::Rate { start: a, end: b, min_hz: Some(2.0), max_hz: Some(4.0) };
+        let outcome = rate.check(&result);
+        assert!(outcome.passed);
+        assert!((outcome.observed - 3.0).abs() < 1e-9);
+        let too_slow = Expectation::Rate { start: a, end: b, min_hz: Some(5.0), max_hz: None };
+        assert!(!too_slow.check(&result).passed);
+
+        let count = Expectation::SpikeCount { start: a, end: b, min: Some(3), max: Some(3) };
+        assert!(count.check(&result).passed);
+        let none = Expectation::SpikeCount { start: a, end: b, min: None, max: Some(2) };
+        assert_eq!(none.check(&result).observed, 3.0);
+        assert!(!none.check(&result).passed);
+        assert_eq!(none.to_string(), "spike_count[0..1] in [-, 2]");
+
+        assert!(Expectation::SpikeCount { start: a, end: b, min: None, max: None }.validate().is_err());
+        assert!(Expectation::Rate { start: b, end: a, min_hz: Some(1.0), max_hz: None }.validate().is_err());
+        assert!(Expectation::Rate { start: a, end: b, min_hz: Some(2.0), max_hz: Some(1.0) }.validate().is_err());
+        assert!(rate.validate().is_ok());
+    }
+}
@@ -0,0 +1,249 @@
+//! Firing pattern classification
+//!
+//! Each neuron's spike train is summarized by statistics of its inter-spike
+//! intervals (ISIs) and assigned a [`FiringPattern`]:
+//!
+//! - CV: standard deviation of the ISIs over their mean.
+//! - CV2 (Holt et al. 1996): mean of `2 |I[i+1] - I[i]| / (I[i+1] + I[i])`,
+//!   insensitive to slow rate changes.
+//! - LV (Shinomoto et al. 2003): `3 / (n - 1) Σ ((I[i] - I[i+1]) / (I[i] + I[i+1]))²`,
+//!   0 for a perfectly regular train, 1 for Poisson, above 1 for bursts.
+//!
+//! A train with too few spikes is silent, a low LV is tonic, a high LV with
+//! many short ISIs is bursting and anything else is irregular.
+
+use crate::{
+    error::{Result, RuntimeError},
+    metrics::SpikeTrains,
+    NeuronId,
+};
+
+/// Firing pattern of one neuron
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FiringPattern {
+    /// Fewer spikes than [`FiringPatternParams::min_spikes`]
+    Silent,
+    /// Regular firing (LV below [`FiringPatternParams::tonic_lv`])
+    Tonic,
+    /// Groups of closely spaced spikes separated by pauses
+    Bursting,
+    /// Neither regular nor bursting
+    Irregular,
+}
+
+impl FiringPattern {
+    /// Every pattern, in report order
+    pub const ALL: [FiringPattern; 4] = [
+        FiringPattern::Silent,
+        FiringPattern::Tonic,
+        FiringPattern::Bursting,
+        FiringPattern::Irregular,
+    ];
+
+    /// Lowercase name used in reports
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FiringPattern::Silent => "silent",
+            FiringPattern::Tonic => "tonic",
+            FiringPattern::Bursting => "bursting",
+            FiringPattern::Irregular => "irregular",
+        }
+    }
+}
+
+/// Thresholds and window of a classification
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiringPatternParams {
+    /// Neurons with fewer spikes are silent (>= 3, so LV has two intervals)
+    pub min_spikes: usize,
+    /// LV below this is tonic
+    pub tonic_lv: f64,
+    /// LV at or above this may be bursting
+    pub burst_lv: f64,
+    /// ISIs up to this length are intra-burst (ns)
+    pub burst_isi_ns: u64,
+    /// Minimum fraction of intra-burst ISIs for bursting
+    pub burst_fraction: f64,
+    /// Analyzed range `[start, end)` (ns); `None` = 0 to just past the last spike
+    pub window: Option<(u64, u64)>,
+}
+
+impl Default for FiringPatternParams {
+    fn default() -> Self {
+        Self {
+            min_spikes: 3,
+            tonic_lv: 0.5,
+            burst_lv: 1.0,
+            burst_isi_ns: 10_000_000,
+            burst_fraction: 0.3,
+            window: None,
+        }
+    }
+}
+
+impl FiringPatternParams {
+    /// Reject thresholds that cannot classify and empty windows
+    pub fn validate(&self) -> Result<()> {
+        if self.min_spikes < 3 {
+            return Err(RuntimeError::invalid_parameter("min_spikes", self.min_spikes.to_string(), ">= 3"));
+        }
+        if !(self.tonic_lv >= 0.0 && self.tonic_lv <= self.burst_lv) {
+            return Err(RuntimeError::invalid_parameter(
+                "tonic_lv",
+                format!("{} (with burst_lv={})", self.tonic_lv, self.burst_lv),
+                "0 <= tonic_lv <= burst_lv",
+            ));
+        }
+        if self.burst_isi_ns == 0 {
+            return Err(RuntimeError::invalid_parameter("burst_isi", "0", "> 0"));
+        }
+        if !(0.0..=1.0).contains(&self.burst_fraction) {
+            return Err(RuntimeError::invalid_parameter("burst_fraction", self.burst_fraction.to_string(), "in [0, 1]"));
+        }
+        if let Some((start, end)) = self.window {
+            if end <= start {
+                return Err(RuntimeError::invalid_parameter("window", format!("{}..{}", start, end), "start < end"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// ISI statistics and pattern of one neuron
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeuronFiring {
+    /// Neuron id
+    pub neuron: NeuronId,
+    /// Spikes inside the window
+    pub spikes: usize,
+    /// Mean rate over the window (Hz)
+    pub rate_hz: f64,
+    /// Coefficient of variation of the ISIs (`None` below two ISIs)
+    pub cv: Option<f64>,
+    /// Mean CV2 (`None` below two ISIs)
+    pub cv2: Option<f64>,
+    /// Local variation (`None` below two ISIs)
+    pub lv: Option<f64>,
+    /// Fraction of ISIs up to [`FiringPatternParams::burst_isi_ns`]
+    pub burst_isi_fraction: f64,
+    /// Assigned pattern
+    pub pattern: FiringPattern,
+}
+
+/// Classification of a recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiringPatternReport {
+    /// Analyzed range `[start, end)` (ns)
+    pub window: (u64, u64),
+    /// Per-neuron results, by neuron id
+    pub neurons: Vec<NeuronFiring>,
+}
+
+impl FiringPatternReport {
+    /// Neurons with `pattern`
+    pub fn count(&self, pattern: FiringPattern) -> usize {
+        self.neurons.iter().filter(|n| n.pattern == pattern).count()
+    }
+
+    /// Population composition as (pattern, neurons, fraction), in [`FiringPattern::ALL`] order
+    pub fn composition(&self) -> Vec<(FiringPattern, usize, f64)> {
+        let total = self.neurons.len().max(1) as f64;
+        FiringPattern::ALL.iter().map(|&p| (p, self.count(p), self.count(p) as f64 / total)).collect()
+    }
+}
+
+/// CV, mean CV2 and LV of a train's ISIs (`None` below two ISIs)
+fn isi_statistics(isis: &[f64]) -> Option<(f64, f64, f64)> {
+    if isis.len() < 2 {
+        return None;
+    }
+    let n = isis.len() as f64;
+    let mean = isis.iter().sum::<f64>() / n;
+    let variance = isis.iter().map(|isi| (isi - mean) * (isi - mean)).sum::<f64>() / n;
+    let cv = if mean > 0.0 { variance.sqrt() / mean } else { 0.0 };
+    // Coincident spikes give a 0/0 pair; it counts as perfectly regular
+    let ratio = |a: f64, b: f64| if a + b > 0.0 { (a - b) / (a + b) } else { 0.0 };
+    let pairs = isis.windows(2).map(|w| ratio(w[1], w[0]));
+    let cv2 = 2.0 * pairs.clone().map(f64::abs).sum::<f64>() / (n - 1.0);
+    let lv = 3.0 * pairs.map(|r| r * r).sum::<f64>() / (n - 1.0);
+    Some((cv, cv2, lv))
+}
+
+/// Classify every neuron in `trains`; add empty trains to report neurons that never fired as silent
+pub fn classify_firing(trains: &SpikeTrains, params: &FiringPatternParams) -> Result<FiringPatternReport> {
+    params.validate()?;
+    let last = trains.values().filter_map(|train| train.last()).max();
+    let (start, end) = params.window.unwrap_or((0, last.map_or(1, |&t| t.saturating_add(1))));
+    let seconds = (end - start) as f64 / 1e9;
+
+    let neurons = trains
+        .iter()
+        .map(|(&neuron, train)| {
+            let clipped: Vec<u64> = train.iter().copied().filter(|&t| t >= start && t < end).collect();
+            let isis: Vec<f64> = clipped.windows(2).map(|w| (w[1] - w[0]) as f64).collect();
+            let short = isis.iter().filter(|&&isi| isi <= params.burst_isi_ns as f64).count();
+            let burst_isi_fraction = short as f64 / isis.len().max(1) as f64;
+            let stats = isi_statistics(&isis);
+            let pattern = match stats {
+                _ if clipped.len() < params.min_spikes => FiringPattern::Silent,
+                Some((_, _, lv)) if lv < params.tonic_lv => FiringPattern::Tonic,
+                Some((_, _, lv)) if lv >= params.burst_lv && burst_isi_fraction >= params.burst_fraction => {
+                    FiringPattern::Bursting
+                }
+                _ => FiringPattern::Irregular,
+            };
+            NeuronFiring {
+                neuron,
+                spikes: clipped.len(),
+                rate_hz: clipped.len() as f64 / seconds,
+                cv: stats.map(|s| s.0),
+                cv2: stats.map(|s| s.1),
+                lv: stats.map(|s| s.2),
+                burst_isi_fraction,
+                pattern,
+            }
+        })
+        .collect();
+    Ok(FiringPatternReport { window: (start, end), neurons })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn test_classify_firing_patterns() {
+        let regular: Vec<u64> = (0..20).map(|i| i * 10 * MS).collect();
+        // Triplets 2 ms apart every 50 ms: 10 of the 16 ISI pairs straddle a pause, LV ≈ 1.58
+        let bursts: Vec<u64> = (0..6).flat_map(|b| (0..3).map(move |s| b * 50 * MS + s * 2 * MS)).collect();
+        // Alternating 10 and 30 ms: LV = 3 * (20/40)^2 = 0.75
+        let alternating: Vec<u64> = (0..12).scan(0, |t, i| {
+            *t += if i % 2 == 0 { 10 * MS } else { 30 * MS };
+            Some(*t)
+        }).collect();
+        let trains: SpikeTrains = [
+            (NeuronId::new(0), regular),
+            (NeuronId::new(1), bursts),
+            (NeuronId::new(2), alternating),
+            (NeuronId::new(3), vec![5 * MS]),
+            (NeuronId::new(4), Vec::new()),
+        ].into();
+
+        let report = classify_firing(&trains, &FiringPatternParams::default()).expect("Should classify");
+        let patterns: Vec<FiringPattern> = report.neurons.iter().map(|n| n.pattern).collect();
+        use FiringPattern::*;
+        assert_eq!(patterns, vec![Tonic, Bursting, Irregular, Silent, Silent]);
+        assert_eq!((report.neurons[0].cv, report.neurons[0].lv), (Some(0.0), Some(0.0)));
+        assert!((report.neurons[1].lv.unwrap() - 3.0 * 10.0 * (44.0f64 / 48.0).powi(2) / 16.0).abs() < 1e-9);
+        assert!((report.neurons[2].lv.unwrap() - 0.75).abs() < 1e-9);
+        assert!((report.neurons[2].cv2.unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(report.neurons[4].rate_hz, 0.0);
+        assert_eq!(report.composition()[0], (Silent, 2, 0.4));
+
+        let windowed = FiringPatternParams { window: Some((0, 15 * MS)), ..Default::default() };
+        assert_eq!(classify_firing(&trains, &windowed).expect("Should classify").count(Silent), 4);
+        assert!(classify_firing(&trains, &FiringPatternParams { min_spikes: 2, ..Default::default() }).is_err());
+    }
+}
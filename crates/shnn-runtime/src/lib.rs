@@ -8,26 +8,83 @@
 #![deny(missing_docs)]
 #![warn(clippy::all)]
 
+// Shared identifier/time/spike types (the same types shnn-core and shnn-storage use)
+pub use shnn_types::{NeuronId, HyperedgeId, Time, Spike};
+
 // Re-export essential types from storage
 pub use shnn_storage::{
-    NeuronId, HyperedgeId, Time, Spike,
     GenerationId, HypergraphStore, EventStore,
     Result as StorageResult, StorageError,
 };
 
 // Core modules
+pub mod brunel;
+pub mod clock;
+pub mod control;
+pub mod delay;
 pub mod error;
+pub mod expect;
+pub mod firing;
+pub mod integrator;
 pub mod neuron;
 pub mod plasticity;
+pub mod mask;
+pub mod memory;
+pub mod metrics;
+pub mod modulation;
+pub mod monitor;
 pub mod network;
+pub mod noise;
+pub mod observer;
+pub mod profile;
+pub mod readout;
+pub mod realtime;
+pub mod rng;
+pub mod reservoir;
+pub mod routing;
+pub mod seed;
 pub mod simulation;
+pub mod spatial;
+pub mod state;
+pub mod stream;
+pub mod validation;
 
 // Re-export essential types
-pub use error::{RuntimeError, Result};
-pub use neuron::{LIFNeuron, LIFParams, NeuronState};
+pub use brunel::BrunelNetwork;
+pub use control::{ControlStatus, ParamUpdate, SimulationControl};
+pub use delay::{DelayDistribution, DelayQueue, DelaySampler, SubDtDelay};
+pub use error::{BuildCode, RuntimeError, Result};
+pub use expect::{Expectation, ExpectationOutcome};
+pub use firing::{FiringPattern, FiringPatternParams, FiringPatternReport, NeuronFiring};
+pub use integrator::{ExponentialEuler, ForwardEuler, Integrator, IntegratorKind, RungeKutta4, SpikeTiming};
+pub use neuron::{
+    AdExNeuron, AdExParams, CustomModel, CustomNeuron, HHMethod, HHNeuron, HHParams, LIFNeuron, LIFParams, Neuron,
+    NeuronDynamics, NeuronModel, NeuronState, Refractoriness,
+};
 pub use plasticity::{STDPRule, STDPParams, PlasticityRule};
-pub use network::{SNNNetwork, NetworkBuilder, NetworkConfig};
-pub use simulation::{SimulationEngine, SimulationParams, SimulationResult};
+pub use mask::{MaskMode, NeuronMask};
+pub use memory::{MemoryEstimate, ProgramShape};
+pub use metrics::{NeuronSimilarity, SimilarityParams, SimilarityReport, Spectrum, SpectrumParams, SpikeTrains};
+pub use modulation::{Interpolation, Modulator};
+pub use monitor::{RateMonitor, RateStats, RateTrace, WeightMonitor, WeightTrace};
+pub use network::{BuildWarning, DuplicateSynapses, SNNNetwork, NetworkBuilder, NetworkConfig};
+pub use noise::OuNoise;
+pub use observer::{SimulationObserver, WeightUpdate};
+pub use profile::{PhaseTotal, Profiler, Span};
+pub use readout::{LinearReadout, ReadoutMonitor, ReadoutRole, ReadoutTrace};
+pub use realtime::{Pacer, RealtimeReport};
+pub use rng::{CounterRng, RngKind};
+pub use reservoir::Reservoir;
+pub use routing::{ConnectivityBackend, SpikeRouter};
+pub use seed::SeedTree;
+pub use simulation::{
+    PopulationSummary, PotentialTrace, PotentialTraces, SimulationEngine, SimulationParams, SimulationResult,
+    SpikeDivergence,
+};
+pub use spatial::{DistanceRule, NeuronPositions, Position, WeightProfile};
+pub use state::{NetworkState, NeuronSnapshot};
+pub use stream::{SpikeEndpoint, SpikeSink, SpikeSource};
+pub use validation::{ScenarioOutcome, ValidationCheck, ValidationConfig, ValidationScenario};
 
 /// Runtime crate version for compatibility checking
 pub const RUNTIME_VERSION: u32 = 1;
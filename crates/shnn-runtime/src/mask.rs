@@ -0,0 +1,135 @@
+//! Neuron masks for gating activity and plasticity
+//!
+//! A mask is loaded from a VMSK file (bit `i` = neuron `i`) and attached to a
+//! network in one of two modes:
+//! - `Activity`: only neurons in the mask may emit spikes
+//! - `Plasticity`: only synapses whose pre- and post-synaptic neurons are both in
+//!   the mask may change weight
+//!
+//! Neurons beyond the mask's bit range are treated as inactive.
+
+use crate::{error::{Result, RuntimeError}, NeuronId};
+use shnn_storage::{BitmapMask, Mask};
+
+use core::str::FromStr;
+
+/// What a mask gates when attached to a network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskMode {
+    /// Restrict which neurons can spike
+    Activity,
+    /// Restrict which synapses can update
+    Plasticity,
+}
+
+impl MaskMode {
+    /// Canonical name used in NIR attributes
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MaskMode::Activity => "activity",
+            MaskMode::Plasticity => "plasticity",
+        }
+    }
+}
+
+impl FromStr for MaskMode {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "activity" => Ok(MaskMode::Activity),
+            "plasticity" => Ok(MaskMode::Plasticity),
+            other => Err(RuntimeError::InvalidParameter {
+                parameter: "mode".into(),
+                value: other.into(),
+                constraint: "activity | plasticity".into(),
+            }),
+        }
+    }
+}
+
+/// Dense set of neuron IDs indexed by raw ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NeuronMask {
+    active: Vec<bool>,
+}
+
+impl NeuronMask {
+    /// Build a mask covering `0..len` with the given neurons active
+    pub fn from_ids(len: u32, ids: impl IntoIterator<Item = NeuronId>) -> Result<Self> {
+        let mut active = vec![false; len as usize];
+        for id in ids {
+            let slot = active.get_mut(id.raw() as usize).ok_or_else(|| RuntimeError::InvalidParameter {
+                parameter: "mask".into(),
+                value: id.raw().to_string(),
+                constraint: format!("neuron id < {}", len),
+            })?;
+            *slot = true;
+        }
+        Ok(Self { active })
+    }
+
+    /// Decode a VMSK container
+    pub fn from_vmsk(bytes: &[u8]) -> Result<Self> {
+        let mask = BitmapMask::import_vmsk(bytes)?;
+        let len = mask.total_count() as u32;
+        Self::from_ids(len, mask.active_indices().into_iter().map(NeuronId::new))
+    }
+
+    /// Load a VMSK file from disk
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let bytes = std::fs::read(path.as_ref()).map_err(shnn_storage::StorageError::from)?;
+        Self::from_vmsk(&bytes)
+    }
+
+    /// Whether a neuron is in the mask
+    pub fn contains(&self, id: NeuronId) -> bool {
+        self.active.get(id.raw() as usize).copied().unwrap_or(false)
+    }
+
+    /// Number of active neurons
+    pub fn active_count(&self) -> usize {
+        self.active.iter().filter(|&&a| a).count()
+    }
+
+    /// Number of neurons covered by the mask
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Whether the mask covers no neurons
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shnn_storage::{GenerationId, MaskId, MaskType};
+
+    #[test]
+    fn test_mask_from_vmsk() {
+        let bitmap = BitmapMask::from_indices(
+            MaskId::new(1),
+            MaskType::ActivityMask,
+            GenerationId::new(0),
+            4,
+            [1, 3],
+        ).unwrap();
+        let mask = NeuronMask::from_vmsk(&bitmap.export_vmsk().unwrap()).unwrap();
+        assert_eq!(mask.len(), 4);
+        assert_eq!(mask.active_count(), 2);
+        assert!(mask.contains(NeuronId::new(3)));
+        assert!(!mask.contains(NeuronId::new(0)));
+        assert!(!mask.contains(NeuronId::new(10)));
+    }
+
+    #[test]
+    fn test_mask_mode_parse() {
+        assert_eq!("activity".parse::<MaskMode>().unwrap(), MaskMode::Activity);
+        assert_eq!("plasticity".parse::<MaskMode>().unwrap(), MaskMode::Plasticity);
+        assert!("weights".parse::<MaskMode>().is_err());
+    }
+}
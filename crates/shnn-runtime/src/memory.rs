@@ -0,0 +1,118 @@
+//! Preflight memory estimation
+//!
+//! [`MemoryEstimate::for_shape`] prices the sizes of a program ([`ProgramShape`])
+//! with the runtime's own container layouts. Figures lean high (hash tables at
+//! worst-case load, vectors at worst-case growth) since they gate whether a run
+//! is allowed to start.
+
+use crate::{
+    network::{Synapse, DELAYED_SPIKE_BYTES},
+    neuron::Neuron,
+    plasticity::SynapseId,
+    simulation::PotentialSample,
+    NeuronId, Spike,
+};
+
+use core::mem::size_of;
+
+/// Sizes of a program that drive its memory use
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgramShape {
+    /// Neurons in the network
+    pub neurons: u64,
+    /// Synapses in the network
+    pub synapses: u64,
+    /// STDP enabled (adds a per-step weight table)
+    pub plasticity: bool,
+    /// Upper bound on recorded spikes
+    pub recorded_spikes: u64,
+    /// Recorded membrane potential samples
+    pub potential_samples: u64,
+    /// Rate monitor bins across all monitors
+    pub rate_bins: u64,
+    /// Weight monitor samples (samples x synapses)
+    pub weight_samples: u64,
+    /// Extra bytes held during lowering (e.g. expanded IR)
+    pub lowering_bytes: u64,
+}
+
+/// Estimated peak memory by component (bytes)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// Neuron state
+    pub neurons: u64,
+    /// Synapse tables, builder staging, plasticity scratch and final weights
+    pub synapses: u64,
+    /// Recorded spikes and in-flight delayed spikes
+    pub spike_buffers: u64,
+    /// Potential traces and monitor outputs
+    pub recordings: u64,
+    /// Transient lowering state
+    pub lowering: u64,
+}
+
+/// Bytes per hash map entry at worst-case load (power-of-two growth, 7/8 max load)
+fn hash_entry<K, V>() -> u64 {
+    2 * (size_of::<K>() + size_of::<V>() + 1) as u64
+}
+
+/// Results are built in a growing vector and cloned once when returned
+const RESULT_COPIES: u64 = 3;
+
+impl MemoryEstimate {
+    /// Estimate peak memory for a program of the given shape
+    pub fn for_shape(shape: &ProgramShape) -> Self {
+        let neurons = shape.neurons * hash_entry::<NeuronId, Neuron>();
+
+        let mut synapses = shape.synapses * (
+            hash_entry::<SynapseId, Synapse>()
+                // Builder staging (pre, post, weight, delay)
+                + 2 * size_of::<(NeuronId, NeuronId, f32, f32)>() as u64
+                // Final weights, in the engine and in the returned result
+                + 2 * hash_entry::<(NeuronId, NeuronId), f32>()
+        );
+        if shape.plasticity {
+            synapses += shape.synapses * hash_entry::<SynapseId, f32>();
+        }
+
+        let spike_buffers = shape.recorded_spikes * size_of::<Spike>() as u64 * RESULT_COPIES
+            // At most one spike in flight per synapse
+            + shape.synapses * DELAYED_SPIKE_BYTES as u64;
+
+        let recordings = shape.potential_samples * size_of::<PotentialSample>() as u64 * RESULT_COPIES
+            + (shape.rate_bins + shape.weight_samples) * size_of::<f32>() as u64 * 2;
+
+        Self { neurons, synapses, spike_buffers, recordings, lowering: shape.lowering_bytes }
+    }
+
+    /// Sum of all components
+    pub fn total(&self) -> u64 {
+        self.neurons + self.synapses + self.spike_buffers + self.recordings + self.lowering
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_scales_with_shape() {
+        let empty = MemoryEstimate::for_shape(&ProgramShape::default());
+        assert_eq!(empty.total(), 0);
+
+        let shape = ProgramShape { neurons: 100, synapses: 1_000, recorded_spikes: 10, ..Default::default() };
+        let base = MemoryEstimate::for_shape(&shape);
+        assert!(base.neurons > 0 && base.synapses > 0 && base.spike_buffers > 0);
+        assert_eq!(base.recordings, 0);
+
+        let doubled = MemoryEstimate::for_shape(&ProgramShape { synapses: 2_000, ..shape.clone() });
+        assert_eq!(doubled.synapses, 2 * base.synapses);
+
+        let plastic = MemoryEstimate::for_shape(&ProgramShape { plasticity: true, ..shape.clone() });
+        assert!(plastic.synapses > base.synapses);
+
+        let traced = MemoryEstimate::for_shape(&ProgramShape { potential_samples: 50, lowering_bytes: 7, ..shape });
+        assert!(traced.recordings > 0);
+        assert_eq!(traced.total(), base.total() + traced.recordings + 7);
+    }
+}
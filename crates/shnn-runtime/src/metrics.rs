@@ -0,0 +1,599 @@
+//! Spike train similarity and population oscillation metrics
+//!
+//! Distances between two spike trains (sorted spike times in ns) and a
+//! recording-level comparison that applies them neuron by neuron:
+//!
+//! - van Rossum distance: L2 distance of the trains filtered with a causal
+//!   exponential kernel of time constant `tau`, normalized so a single
+//!   unmatched spike contributes `sqrt(1/2)`.
+//! - Victor–Purpura distance: cheapest edit turning one train into the other,
+//!   where inserting or deleting a spike costs 1 and moving it by `dt` costs
+//!   `dt / timescale`.
+//! - SPIKE-distance (Kreuz et al. 2013): time-averaged, parameter-free
+//!   dissimilarity in `[0, 1]`; both trains get auxiliary spikes at the window
+//!   edges.
+//! - Pearson correlation of binned spike counts.
+//!
+//! Population-level oscillation measures work on binned spike counts:
+//!
+//! - Power spectrum of the pooled population rate (Welch: Hann-windowed
+//!   segments overlapping by half, mean removed per segment).
+//! - Magnitude-squared coherence between the pooled rates of two
+//!   subpopulations, in `[0, 1]` per frequency.
+//! - Synchrony index χ (Golomb 2007): square root of the variance of the
+//!   population-mean count over the mean single-neuron variance; 1 when all
+//!   neurons fire together, near `1/sqrt(N)` for independent neurons.
+
+use crate::{
+    error::{Result, RuntimeError},
+    NeuronId,
+};
+
+use core::f64::consts::PI;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Sorted spike times (ns) per neuron, as returned by `SimulationResult::spike_trains`
+pub type SpikeTrains = BTreeMap<NeuronId, Vec<u64>>;
+
+/// van Rossum distance between two sorted trains (`tau_ns` > 0)
+pub fn van_rossum_distance(a: &[u64], b: &[u64], tau_ns: u64) -> f64 {
+    let tau = tau_ns as f64;
+    let squared = (kernel_sum(a, a, tau) + kernel_sum(b, b, tau) - 2.0 * kernel_sum(a, b, tau)) / 2.0;
+    squared.max(0.0).sqrt()
+}
+
+/// Σ_ij exp(-|x_i - y_j| / tau), in O(|x| + |y|)
+fn kernel_sum(x: &[u64], y: &[u64], tau: f64) -> f64 {
+    // Every ordered pair once: y_j <= x_i from the first pass, x_i < y_j from the second
+    causal_sum(x, y, tau, true) + causal_sum(y, x, tau, false)
+}
+
+/// Σ_i Σ_{j: y_j <= x_i} exp(-(x_i - y_j) / tau) (`y_j < x_i` unless `inclusive`)
+fn causal_sum(x: &[u64], y: &[u64], tau: f64, inclusive: bool) -> f64 {
+    let decay = |dt: u64| (-(dt as f64) / tau).exp();
+    let (mut trace, mut last, mut j, mut total) = (0.0, 0u64, 0, 0.0);
+    for &t in x {
+        while j < y.len() && (y[j] < t || (inclusive && y[j] == t)) {
+            trace = trace * decay(y[j] - last) + 1.0;
+            last = y[j];
+            j += 1;
+        }
+        total += trace * decay(t - last);
+    }
+    total
+}
+
+/// Victor–Purpura distance between two sorted trains (`timescale_ns` > 0)
+pub fn victor_purpura_distance(a: &[u64], b: &[u64], timescale_ns: u64) -> f64 {
+    let timescale = timescale_ns as f64;
+    let mut previous: Vec<f64> = (0..=b.len()).map(|j| j as f64).collect();
+    for (i, &ta) in a.iter().enumerate() {
+        let mut row = Vec::with_capacity(b.len() + 1);
+        row.push((i + 1) as f64);
+        for (j, &tb) in b.iter().enumerate() {
+            let shift = previous[j] + ta.abs_diff(tb) as f64 / timescale;
+            row.push(shift.min(previous[j + 1] + 1.0).min(row[j] + 1.0));
+        }
+        previous = row;
+    }
+    previous[b.len()]
+}
+
+/// SPIKE-distance of two sorted trains over `[start_ns, end_ns]` (0 for an empty window)
+pub fn spike_distance(a: &[u64], b: &[u64], start_ns: u64, end_ns: u64) -> f64 {
+    if end_ns <= start_ns {
+        return 0.0;
+    }
+    let a = with_edges(a, start_ns, end_ns);
+    let b = with_edges(b, start_ns, end_ns);
+    let mut points: Vec<u64> = a.iter().chain(&b).copied().collect();
+    points.sort_unstable();
+    points.dedup();
+
+    // S(t) is linear between consecutive spikes of either train, so the trapezoid rule is exact
+    let mut integral = 0.0;
+    for segment in points.windows(2) {
+        let (u, v) = (segment[0], segment[1]);
+        let (side_a, side_b) = (Side::new(&a, &b, u), Side::new(&b, &a, u));
+        let dissimilarity = |t: f64| {
+            let ((s_a, isi_a), (s_b, isi_b)) = (side_a.at(t), side_b.at(t));
+            let mean_isi = (isi_a + isi_b) / 2.0;
+            (s_a * isi_b + s_b * isi_a) / (2.0 * mean_isi * mean_isi)
+        };
+        integral += (dissimilarity(u as f64) + dissimilarity(v as f64)) / 2.0 * (v - u) as f64;
+    }
+    integral / (end_ns - start_ns) as f64
+}
+
+/// Spikes inside `[start, end]` plus auxiliary spikes at both edges
+fn with_edges(train: &[u64], start: u64, end: u64) -> Vec<u64> {
+    let mut out = Vec::with_capacity(train.len() + 2);
+    out.push(start);
+    out.extend(train.iter().copied().filter(|&t| t > start && t < end));
+    out.push(end);
+    out.dedup();
+    out
+}
+
+/// One train's surrounding spikes and their distances to the other train on a segment
+struct Side {
+    previous: f64,
+    following: f64,
+    previous_gap: f64,
+    following_gap: f64,
+}
+
+impl Side {
+    /// Segment starting at `u` (no spike of either train strictly inside it)
+    fn new(own: &[u64], other: &[u64], u: u64) -> Self {
+        let index = own.partition_point(|&t| t <= u);
+        let (previous, following) = (own[index - 1], own[index]);
+        Self {
+            previous: previous as f64,
+            following: following as f64,
+            previous_gap: nearest_gap(other, previous),
+            following_gap: nearest_gap(other, following),
+        }
+    }
+
+    /// Local dissimilarity and inter-spike interval at time `t`
+    fn at(&self, t: f64) -> (f64, f64) {
+        let isi = self.following - self.previous;
+        let s = (self.previous_gap * (self.following - t) + self.following_gap * (t - self.previous)) / isi;
+        (s, isi)
+    }
+}
+
+/// Distance from `t` to the closest spike of a non-empty sorted train
+fn nearest_gap(train: &[u64], t: u64) -> f64 {
+    let index = train.partition_point(|&s| s < t);
+    let after = train.get(index).map(|&s| s - t);
+    let before = index.checked_sub(1).map(|i| t - train[i]);
+    after.into_iter().chain(before).min().unwrap_or(0) as f64
+}
+
+/// Pearson correlation of spike counts in `bin_ns` (> 0) bins over `[start_ns, end_ns)`
+///
+/// `None` if either count series is constant (including empty windows).
+pub fn rate_correlation(a: &[u64], b: &[u64], start_ns: u64, end_ns: u64, bin_ns: u64) -> Option<f64> {
+    pearson(&binned_counts([a], start_ns, end_ns, bin_ns), &binned_counts([b], start_ns, end_ns, bin_ns))
+}
+
+/// Spike counts of the pooled `trains` in `bin_ns` bins over `[start_ns, end_ns)`
+fn binned_counts<'a>(trains: impl IntoIterator<Item = &'a [u64]>, start_ns: u64, end_ns: u64, bin_ns: u64) -> Vec<f64> {
+    let mut counts = vec![0.0; end_ns.saturating_sub(start_ns).div_ceil(bin_ns) as usize];
+    for train in trains {
+        for &t in train.iter().filter(|&&t| t >= start_ns && t < end_ns) {
+            counts[((t - start_ns) / bin_ns) as usize] += 1.0;
+        }
+    }
+    counts
+}
+
+fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    let n = x.len() as f64;
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let (mut covariance, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (&xi, &yi) in x.iter().zip(y) {
+        covariance += (xi - mean_x) * (yi - mean_y);
+        var_x += (xi - mean_x) * (xi - mean_x);
+        var_y += (yi - mean_y) * (yi - mean_y);
+    }
+    (var_x > 0.0 && var_y > 0.0).then(|| covariance / (var_x * var_y).sqrt())
+}
+
+/// Sampling and segmentation of a spectral analysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectrumParams {
+    /// Population rate sampling interval (ns); the Nyquist frequency is `1 / (2 bin)`
+    pub bin_ns: u64,
+    /// Welch segment length in bins; the frequency resolution is `1 / (segment_bins bin)`
+    pub segment_bins: usize,
+    /// Analyzed range `[start, end)` (ns); `None` = 0 to just past the last spike
+    pub window: Option<(u64, u64)>,
+}
+
+impl Default for SpectrumParams {
+    fn default() -> Self {
+        Self { bin_ns: 1_000_000, segment_bins: 256, window: None }
+    }
+}
+
+impl SpectrumParams {
+    /// Reject a zero bin, segments too short to resolve a frequency and empty windows
+    pub fn validate(&self) -> Result<()> {
+        if self.bin_ns == 0 {
+            return Err(RuntimeError::invalid_parameter("bin", "0", "> 0"));
+        }
+        if self.segment_bins < 4 {
+            return Err(RuntimeError::invalid_parameter("segment_bins", self.segment_bins.to_string(), ">= 4"));
+        }
+        if let Some((start, end)) = self.window {
+            if end <= start {
+                return Err(RuntimeError::invalid_parameter("window", format!("{}..{}", start, end), "start < end"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Analyzed range: the window, else 0 to just past the last spike of `trains`
+    fn range<'a>(&self, trains: impl IntoIterator<Item = &'a SpikeTrains>) -> (u64, u64) {
+        let last = trains.into_iter().flat_map(|t| t.values()).filter_map(|train| train.last()).max();
+        self.window.unwrap_or((0, last.map_or(1, |&t| t.saturating_add(1))))
+    }
+}
+
+/// A one-sided spectrum over `0..=Nyquist`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    /// Analyzed range `[start, end)` (ns)
+    pub window: (u64, u64),
+    /// Frequency of each value (Hz), ascending from 0
+    pub frequencies_hz: Vec<f64>,
+    /// Power spectral density (Hz²/Hz) or coherence, per frequency
+    pub values: Vec<f64>,
+    /// Averaged Welch segments
+    pub segments: usize,
+}
+
+impl Spectrum {
+    /// Largest value with frequency in `[min_hz, max_hz]` as (frequency, value)
+    pub fn peak(&self, min_hz: f64, max_hz: f64) -> Option<(f64, f64)> {
+        self.frequencies_hz.iter().copied().zip(self.values.iter().copied())
+            .filter(|&(f, _)| f >= min_hz && f <= max_hz)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Value at the frequency closest to `hz`
+    pub fn at(&self, hz: f64) -> Option<f64> {
+        let index = self.frequencies_hz.iter()
+            .enumerate()
+            .min_by(|a, b| (a.1 - hz).abs().total_cmp(&(b.1 - hz).abs()))?
+            .0;
+        self.values.get(index).copied()
+    }
+}
+
+/// Welch auto- and cross-spectra of two equally long series sampled every `dt_s`
+///
+/// Returns (Sxx, Syy, Sxy as (re, im), segments), one-sided and averaged over segments.
+fn welch(x: &[f64], y: &[f64], segment_bins: usize, dt_s: f64) -> (Vec<f64>, Vec<f64>, Vec<(f64, f64)>, usize) {
+    let len = segment_bins.min(x.len());
+    let bins = len / 2 + 1;
+    let hann: Vec<f64> = (0..len).map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / len as f64).cos()).collect();
+    let norm = dt_s / hann.iter().map(|w| w * w).sum::<f64>();
+    let twiddle: Vec<(f64, f64)> = (0..len).map(|m| {
+        let phase = -2.0 * PI * m as f64 / len as f64;
+        (phase.cos(), phase.sin())
+    }).collect();
+    // Naive DFT of the non-negative frequencies; segments are a few hundred bins
+    let dft = |segment: &[f64]| -> Vec<(f64, f64)> {
+        let mean = segment.iter().sum::<f64>() / len as f64;
+        let tapered: Vec<f64> = segment.iter().zip(&hann).map(|(v, w)| (v - mean) * w).collect();
+        (0..bins).map(|k| {
+            tapered.iter().enumerate().fold((0.0, 0.0), |(re, im), (n, v)| {
+                let (c, s) = twiddle[(k * n) % len];
+                (re + v * c, im + v * s)
+            })
+        }).collect()
+    };
+
+    let (mut sxx, mut syy, mut sxy) = (vec![0.0; bins], vec![0.0; bins], vec![(0.0, 0.0); bins]);
+    let step = (len / 2).max(1);
+    let mut segments = 0;
+    let mut start = 0;
+    while start + len <= x.len() {
+        let (fx, fy) = (dft(&x[start..start + len]), dft(&y[start..start + len]));
+        for k in 0..bins {
+            let ((xr, xi), (yr, yi)) = (fx[k], fy[k]);
+            sxx[k] += xr * xr + xi * xi;
+            syy[k] += yr * yr + yi * yi;
+            sxy[k].0 += xr * yr + xi * yi;
+            sxy[k].1 += xi * yr - xr * yi;
+        }
+        segments += 1;
+        start += step;
+    }
+    for k in 0..bins {
+        // One-sided: every bin but DC and Nyquist also holds its negative frequency
+        let scale = norm / segments as f64 * if k == 0 || 2 * k == len { 1.0 } else { 2.0 };
+        sxx[k] *= scale;
+        syy[k] *= scale;
+        sxy[k] = (sxy[k].0 * scale, sxy[k].1 * scale);
+    }
+    (sxx, syy, sxy, segments)
+}
+
+/// Frequencies (Hz) of the one-sided bins of a `len`-bin segment
+fn frequencies(len: usize, dt_s: f64) -> Vec<f64> {
+    (0..len / 2 + 1).map(|k| k as f64 / (len as f64 * dt_s)).collect()
+}
+
+/// Binned pooled counts of two groups over the analyzed range, with the bin in seconds
+fn pooled_series(a: &SpikeTrains, b: &SpikeTrains, params: &SpectrumParams) -> Result<((u64, u64), Vec<f64>, Vec<f64>, f64)> {
+    params.validate()?;
+    let (start, end) = params.range([a, b]);
+    let x = binned_counts(a.values().map(Vec::as_slice), start, end, params.bin_ns);
+    let y = binned_counts(b.values().map(Vec::as_slice), start, end, params.bin_ns);
+    if x.len() < 4 {
+        return Err(RuntimeError::invalid_parameter(
+            "window",
+            format!("{} bins", x.len()),
+            ">= 4 bins of the sampling interval",
+        ));
+    }
+    Ok(((start, end), x, y, params.bin_ns as f64 / 1e9))
+}
+
+/// Power spectral density of the pooled population rate (Hz²/Hz)
+pub fn population_spectrum(trains: &SpikeTrains, params: &SpectrumParams) -> Result<Spectrum> {
+    let (window, counts, _, dt_s) = pooled_series(trains, &SpikeTrains::new(), params)?;
+    let rate: Vec<f64> = counts.iter().map(|c| c / dt_s).collect();
+    let (power, _, _, segments) = welch(&rate, &rate, params.segment_bins, dt_s);
+    Ok(Spectrum { window, frequencies_hz: frequencies(params.segment_bins.min(rate.len()), dt_s), values: power, segments })
+}
+
+/// Magnitude-squared coherence of the pooled rates of two subpopulations
+///
+/// Frequencies where either group has no power get 0. A single segment
+/// (window shorter than two segments) gives 1 everywhere else.
+pub fn rate_coherence(a: &SpikeTrains, b: &SpikeTrains, params: &SpectrumParams) -> Result<Spectrum> {
+    let (window, x, y, dt_s) = pooled_series(a, b, params)?;
+    let (sxx, syy, sxy, segments) = welch(&x, &y, params.segment_bins, dt_s);
+    let values = sxx.iter().zip(&syy).zip(&sxy)
+        .map(|((&pxx, &pyy), &(re, im))| match pxx * pyy > 0.0 {
+            true => ((re * re + im * im) / (pxx * pyy)).min(1.0),
+            false => 0.0,
+        })
+        .collect();
+    Ok(Spectrum { window, frequencies_hz: frequencies(params.segment_bins.min(x.len()), dt_s), values, segments })
+}
+
+/// Synchrony index χ of `trains` from spike counts in `bin_ns` (> 0) bins over `[start_ns, end_ns)`
+///
+/// `None` if no neuron's count varies.
+pub fn synchrony_index(trains: &SpikeTrains, start_ns: u64, end_ns: u64, bin_ns: u64) -> Option<f64> {
+    let variance = |series: &[f64]| {
+        let mean = series.iter().sum::<f64>() / series.len().max(1) as f64;
+        series.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / series.len().max(1) as f64
+    };
+    let counts: Vec<Vec<f64>> = trains.values().map(|train| binned_counts([train.as_slice()], start_ns, end_ns, bin_ns)).collect();
+    let single = counts.iter().map(|c| variance(c)).sum::<f64>() / counts.len().max(1) as f64;
+    if single <= 0.0 {
+        return None;
+    }
+    let bins = counts.first().map_or(0, Vec::len);
+    let population: Vec<f64> = (0..bins).map(|i| counts.iter().map(|c| c[i]).sum::<f64>() / counts.len() as f64).collect();
+    Some((variance(&population) / single).sqrt())
+}
+
+/// Time scales and window of a recording comparison
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarityParams {
+    /// van Rossum kernel time constant (ns)
+    pub tau_ns: u64,
+    /// Shift that costs as much as deleting a spike in Victor–Purpura (ns)
+    pub vp_timescale_ns: u64,
+    /// Bin width for the rate correlation (ns)
+    pub bin_ns: u64,
+    /// Compared range `[start, end)` (ns); `None` = 0 to just past the last spike of either recording
+    pub window: Option<(u64, u64)>,
+}
+
+impl Default for SimilarityParams {
+    fn default() -> Self {
+        Self {
+            tau_ns: 10_000_000,
+            vp_timescale_ns: 10_000_000,
+            bin_ns: 10_000_000,
+            window: None,
+        }
+    }
+}
+
+impl SimilarityParams {
+    /// Reject zero time scales and empty windows
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [("tau", self.tau_ns), ("vp_timescale", self.vp_timescale_ns), ("bin", self.bin_ns)] {
+            if value == 0 {
+                return Err(RuntimeError::invalid_parameter(name, "0", "> 0"));
+            }
+        }
+        if let Some((start, end)) = self.window {
+            if end <= start {
+                return Err(RuntimeError::invalid_parameter("window", format!("{}..{}", start, end), "start < end"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Metrics of one neuron present in either recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeuronSimilarity {
+    /// Neuron id
+    pub neuron: NeuronId,
+    /// Spikes inside the window in each recording
+    pub spikes: (usize, usize),
+    /// van Rossum distance
+    pub van_rossum: f64,
+    /// Victor–Purpura distance
+    pub victor_purpura: f64,
+    /// SPIKE-distance
+    pub spike_distance: f64,
+    /// Pearson correlation of binned counts (`None` if either is constant)
+    pub rate_correlation: Option<f64>,
+}
+
+/// Comparison of two recordings
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarityReport {
+    /// Compared range `[start, end)` (ns)
+    pub window: (u64, u64),
+    /// Per-neuron metrics, by neuron id
+    pub neurons: Vec<NeuronSimilarity>,
+    /// Mean van Rossum distance over neurons
+    pub van_rossum: f64,
+    /// Mean Victor–Purpura distance over neurons
+    pub victor_purpura: f64,
+    /// Mean SPIKE-distance over neurons
+    pub spike_distance: f64,
+    /// Pearson correlation of the binned population rates
+    pub rate_correlation: Option<f64>,
+}
+
+/// Compare two recordings neuron by neuron (union of the neurons that fired in either)
+pub fn compare_trains(a: &SpikeTrains, b: &SpikeTrains, params: &SimilarityParams) -> Result<SimilarityReport> {
+    params.validate()?;
+    let last = a.values().chain(b.values()).filter_map(|train| train.last()).max();
+    let (start, end) = params.window.unwrap_or((0, last.map_or(1, |&t| t.saturating_add(1))));
+    let clip = |train: Option<&Vec<u64>>| -> Vec<u64> {
+        train.map_or_else(Vec::new, |train| train.iter().copied().filter(|&t| t >= start && t < end).collect())
+    };
+
+    let ids: BTreeSet<NeuronId> = a.keys().chain(b.keys()).copied().collect();
+    let (mut population_a, mut population_b) = (Vec::new(), Vec::new());
+    let neurons: Vec<NeuronSimilarity> = ids
+        .into_iter()
+        .map(|neuron| {
+            let (x, y) = (clip(a.get(&neuron)), clip(b.get(&neuron)));
+            population_a.extend_from_slice(&x);
+            population_b.extend_from_slice(&y);
+            NeuronSimilarity {
+                neuron,
+                spikes: (x.len(), y.len()),
+                van_rossum: van_rossum_distance(&x, &y, params.tau_ns),
+                victor_purpura: victor_purpura_distance(&x, &y, params.vp_timescale_ns),
+                spike_distance: spike_distance(&x, &y, start, end),
+                rate_correlation: rate_correlation(&x, &y, start, end, params.bin_ns),
+            }
+        })
+        .collect();
+
+    let mean = |metric: fn(&NeuronSimilarity) -> f64| {
+        neurons.iter().map(metric).sum::<f64>() / neurons.len().max(1) as f64
+    };
+    Ok(SimilarityReport {
+        window: (start, end),
+        van_rossum: mean(|n| n.van_rossum),
+        victor_purpura: mean(|n| n.victor_purpura),
+        spike_distance: mean(|n| n.spike_distance),
+        rate_correlation: rate_correlation(&population_a, &population_b, start, end, params.bin_ns),
+        neurons,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    fn brute_kernel_sum(x: &[u64], y: &[u64], tau: f64) -> f64 {
+        x.iter().flat_map(|&a| y.iter().map(move |&b| (-(a.abs_diff(b) as f64) / tau).exp())).sum()
+    }
+
+    #[test]
+    fn test_van_rossum_matches_closed_form() {
+        let (x, y) = ([0, 3 * MS, 3 * MS, 9 * MS, 20 * MS], [MS, 3 * MS, 15 * MS]);
+        for (p, q) in [(&x[..], &y[..]), (&y[..], &x[..]), (&x[..], &x[..])] {
+            let (fast, brute) = (kernel_sum(p, q, 4e6), brute_kernel_sum(p, q, 4e6));
+            assert!((fast - brute).abs() < 1e-9, "{} vs {}", fast, brute);
+        }
+        assert_eq!(van_rossum_distance(&x, &x, 4 * MS), 0.0);
+        assert!((van_rossum_distance(&[5 * MS], &[], 4 * MS) - 0.5f64.sqrt()).abs() < 1e-12);
+        // One spike shifted by tau: D^2 = 1 - e^-1
+        let shifted = van_rossum_distance(&[0], &[4 * MS], 4 * MS);
+        assert!((shifted * shifted - (1.0 - (-1.0f64).exp())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_victor_purpura_edit_costs() {
+        assert_eq!(victor_purpura_distance(&[0], &[5 * MS], 10 * MS), 0.5);
+        // Shifting by more than two timescales is dearer than delete + insert
+        assert_eq!(victor_purpura_distance(&[0], &[30 * MS], 10 * MS), 2.0);
+        assert_eq!(victor_purpura_distance(&[0, 10 * MS], &[], 10 * MS), 2.0);
+        assert_eq!(victor_purpura_distance(&[0, 10 * MS, 20 * MS], &[MS, 20 * MS], 10 * MS), 1.1);
+    }
+
+    #[test]
+    fn test_spike_distance_bounds_and_ordering() {
+        let train = [10 * MS, 30 * MS, 50 * MS, 70 * MS];
+        assert_eq!(spike_distance(&train, &train, 0, 100 * MS), 0.0);
+        let near: Vec<u64> = train.iter().map(|t| t + MS).collect();
+        let far: Vec<u64> = train.iter().map(|t| t + 8 * MS).collect();
+        let (d_near, d_far) = (spike_distance(&train, &near, 0, 100 * MS), spike_distance(&train, &far, 0, 100 * MS));
+        assert!(0.0 < d_near && d_near < d_far && d_far <= 1.0, "{} {}", d_near, d_far);
+        let silent = spike_distance(&train, &[], 0, 100 * MS);
+        assert!(silent > d_far && silent <= 1.0, "{}", silent);
+        assert_eq!(spike_distance(&train, &near, 5, 5), 0.0);
+    }
+
+    #[test]
+    fn test_rate_correlation() {
+        let even: Vec<u64> = (0..10).map(|i| 2 * i * MS).collect();
+        let odd: Vec<u64> = (0..10).map(|i| (2 * i + 1) * MS).collect();
+        assert!((rate_correlation(&even, &even, 0, 20 * MS, MS).unwrap() - 1.0).abs() < 1e-12);
+        assert!((rate_correlation(&even, &odd, 0, 20 * MS, MS).unwrap() + 1.0).abs() < 1e-12);
+        // 2 ms bins hold one spike of each train: constant counts
+        assert_eq!(rate_correlation(&even, &odd, 0, 20 * MS, 2 * MS), None);
+    }
+
+    /// Ten neurons firing at 40 Hz, neuron `i` offset by `i - 4.5` ms within each cycle
+    fn gamma_trains(ids: std::ops::Range<u32>) -> SpikeTrains {
+        ids.map(|i| {
+            let offset = (i as u64 % 10) * MS + 500_000;
+            (NeuronId::new(i), (1..40).map(|k| k * 25 * MS + offset - 5 * MS).collect())
+        }).collect()
+    }
+
+    #[test]
+    fn test_population_spectrum_and_coherence() {
+        let params = SpectrumParams { window: Some((0, 1000 * MS)), ..Default::default() };
+        let trains = gamma_trains(0..10);
+        let spectrum = population_spectrum(&trains, &params).expect("Should compute spectrum");
+        assert_eq!(spectrum.segments, 6);
+        assert_eq!(spectrum.frequencies_hz.len(), 129);
+        assert!((spectrum.frequencies_hz[1] - 1000.0 / 256.0).abs() < 1e-9);
+        let (peak_hz, _) = spectrum.peak(1.0, 500.0).unwrap();
+        assert!((35.0..45.0).contains(&peak_hz), "{}", peak_hz);
+
+        let (a, b) = (gamma_trains(0..5), gamma_trains(5..10));
+        let coherence = rate_coherence(&a, &b, &params).expect("Should compute coherence");
+        assert!(coherence.at(40.0).unwrap() > 0.9, "{:?}", coherence.at(40.0));
+        assert!(coherence.values.iter().all(|c| (0.0..=1.0).contains(c)));
+        assert!(population_spectrum(&trains, &SpectrumParams { segment_bins: 2, ..params.clone() }).is_err());
+        assert!(population_spectrum(&trains, &SpectrumParams { window: Some((0, 2 * MS)), ..params }).is_err());
+    }
+
+    #[test]
+    fn test_synchrony_index() {
+        let together: SpikeTrains = (0..10).map(|i| (NeuronId::new(i), (0..50).map(|k| k * 10 * MS).collect())).collect();
+        assert!((synchrony_index(&together, 0, 500 * MS, MS).unwrap() - 1.0).abs() < 1e-12);
+        // Staggered by 1 ms: one spike in every bin, a flat population rate
+        let staggered: SpikeTrains = (0..10).map(|i| (NeuronId::new(i), (0..50).map(|k| (k * 10 + i as u64) * MS).collect())).collect();
+        assert!(synchrony_index(&staggered, 0, 500 * MS, MS).unwrap() < 1e-9);
+        assert_eq!(synchrony_index(&SpikeTrains::new(), 0, 500 * MS, MS), None);
+    }
+
+    #[test]
+    fn test_compare_trains_covers_union_of_neurons() {
+        let a: SpikeTrains = [(NeuronId::new(0), vec![MS, 5 * MS]), (NeuronId::new(2), vec![3 * MS])].into();
+        let b: SpikeTrains = [(NeuronId::new(0), vec![MS, 5 * MS]), (NeuronId::new(1), vec![7 * MS])].into();
+        let report = compare_trains(&a, &b, &SimilarityParams::default()).expect("Should compare");
+        assert_eq!(report.window, (0, 7 * MS + 1));
+        let ids: Vec<u32> = report.neurons.iter().map(|n| n.neuron.raw()).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+        assert_eq!(report.neurons[0].van_rossum, 0.0);
+        assert_eq!(report.neurons[0].victor_purpura, 0.0);
+        assert_eq!(report.neurons[1].spikes, (0, 1));
+        assert_eq!(report.neurons[2].victor_purpura, 1.0);
+        assert!((report.victor_purpura - 2.0 / 3.0).abs() < 1e-12);
+
+        let same = compare_trains(&a, &a, &SimilarityParams { window: Some((0, 10 * MS)), bin_ns: MS, ..Default::default() })
+            .expect("Should compare");
+        assert_eq!((same.van_rossum, same.victor_purpura, same.spike_distance), (0.0, 0.0, 0.0));
+        assert!((same.rate_correlation.unwrap() - 1.0).abs() < 1e-12);
+        assert!(compare_trains(&a, &b, &SimilarityParams { tau_ns: 0, ..Default::default() }).is_err());
+    }
+}
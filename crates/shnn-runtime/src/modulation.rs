@@ -0,0 +1,169 @@
+//! Global neuromodulatory signals (three-factor learning)
+//!
+//! A [`Modulator`] produces a scalar level m(t) that multiplies every STDP weight
+//! change, so plasticity is gated/scaled by a dopamine-like third factor. The level
+//! either follows a fixed schedule or is derived from a population's firing rate:
+//! m(t) = baseline + gain * rate_hz(population, window).
+
+use crate::{error::{Result, RuntimeError}, NeuronId};
+
+use std::collections::VecDeque;
+
+/// How a schedule is evaluated between breakpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Hold the value of the latest breakpoint
+    Step,
+    /// Linear interpolation between neighbouring breakpoints
+    Linear,
+}
+
+/// Source of a global modulatory signal
+#[derive(Debug, Clone)]
+pub enum Modulator {
+    /// Piecewise schedule of `(time_ns, level)` breakpoints (sorted by time)
+    Schedule {
+        /// Breakpoints
+        points: Vec<(u64, f32)>,
+        /// Evaluation between breakpoints
+        interpolation: Interpolation,
+    },
+    /// Level derived from the recent firing rate of a neuron population
+    PopulationRate {
+        /// First neuron of the population (inclusive)
+        start: NeuronId,
+        /// Last neuron of the population (inclusive)
+        end: NeuronId,
+        /// Rate estimation window (ns)
+        window_ns: u64,
+        /// Level per Hz of population rate
+        gain: f32,
+        /// Level at zero rate
+        baseline: f32,
+        /// Spike times of the population inside the window
+        recent: VecDeque<u64>,
+    },
+}
+
+impl Modulator {
+    /// Schedule-driven modulator; breakpoints must be non-empty with non-decreasing times
+    pub fn schedule(points: Vec<(u64, f32)>, interpolation: Interpolation) -> Result<Self> {
+        if points.is_empty() {
+            return Err(invalid("schedule", "<empty>", "at least one breakpoint"));
+        }
+        if points.windows(2).any(|w| w[1].0 < w[0].0) {
+            return Err(invalid("schedule", "<unsorted>", "non-decreasing breakpoint times"));
+        }
+        Ok(Modulator::Schedule { points, interpolation })
+    }
+
+    /// Population-rate modulator over neurons `start..=end`
+    pub fn population_rate(start: NeuronId, end: NeuronId, window_ns: u64, gain: f32, baseline: f32) -> Result<Self> {
+        if start.raw() > end.raw() {
+            return Err(invalid("source", &format!("{}..{}", start.raw(), end.raw()), "start <= end"));
+        }
+        if window_ns == 0 {
+            return Err(invalid("window", "0", "> 0 ns"));
+        }
+        Ok(Modulator::PopulationRate {
+            start,
+            end,
+            window_ns,
+            gain,
+            baseline,
+            recent: VecDeque::new(),
+        })
+    }
+
+    /// Feed a spike (only relevant for population-rate modulators)
+    pub fn observe_spike(&mut self, neuron: NeuronId, time_ns: u64) {
+        if let Modulator::PopulationRate { start, end, recent, .. } = self {
+            if (start.raw()..=end.raw()).contains(&neuron.raw()) {
+                recent.push_back(time_ns);
+            }
+        }
+    }
+
+    /// Modulation level at `time_ns` (drops spikes that left the rate window)
+    pub fn level(&mut self, time_ns: u64) -> f32 {
+        match self {
+            Modulator::Schedule { points, interpolation } => schedule_level(points, *interpolation, time_ns),
+            Modulator::PopulationRate { start, end, window_ns, gain, baseline, recent } => {
+                let cutoff = time_ns.saturating_sub(*window_ns);
+                while recent.front().map_or(false, |&t| t < cutoff) {
+                    recent.pop_front();
+                }
+                let size = (end.raw() - start.raw() + 1) as f32;
+                let window_s = *window_ns as f32 / 1e9;
+                *baseline + *gain * recent.len() as f32 / (size * window_s)
+            }
+        }
+    }
+
+    /// Forget observed activity
+    pub fn reset(&mut self) {
+        if let Modulator::PopulationRate { recent, .. } = self {
+            recent.clear();
+        }
+    }
+}
+
+fn schedule_level(points: &[(u64, f32)], interpolation: Interpolation, t: u64) -> f32 {
+    // Index of the first breakpoint strictly after t
+    let next = points.partition_point(|&(pt, _)| pt <= t);
+    if next == 0 {
+        return points[0].1;
+    }
+    let (t0, v0) = points[next - 1];
+    match (interpolation, points.get(next)) {
+        (Interpolation::Linear, Some(&(t1, v1))) if t1 > t0 => {
+            let frac = (t - t0) as f32 / (t1 - t0) as f32;
+            v0 + (v1 - v0) * frac
+        }
+        _ => v0,
+    }
+}
+
+fn invalid(parameter: &str, value: &str, constraint: &str) -> RuntimeError {
+    RuntimeError::InvalidParameter {
+        parameter: parameter.into(),
+        value: value.into(),
+        constraint: constraint.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_levels() {
+        let points = vec![(0, 0.0), (10, 1.0), (20, -1.0)];
+        let mut step = Modulator::schedule(points.clone(), Interpolation::Step).unwrap();
+        assert_eq!(step.level(5), 0.0);
+        assert_eq!(step.level(10), 1.0);
+        assert_eq!(step.level(100), -1.0);
+
+        let mut linear = Modulator::schedule(points, Interpolation::Linear).unwrap();
+        assert_eq!(linear.level(5), 0.5);
+        assert_eq!(linear.level(15), 0.0);
+        assert_eq!(linear.level(100), -1.0);
+
+        assert!(Modulator::schedule(vec![], Interpolation::Step).is_err());
+        assert!(Modulator::schedule(vec![(10, 1.0), (5, 0.0)], Interpolation::Step).is_err());
+    }
+
+    #[test]
+    fn test_population_rate_level() {
+        // Two-neuron population, 10 ms window
+        let mut m = Modulator::population_rate(NeuronId::new(2), NeuronId::new(3), 10_000_000, 0.1, 0.5).unwrap();
+        assert_eq!(m.level(0), 0.5);
+        m.observe_spike(NeuronId::new(2), 1_000_000);
+        m.observe_spike(NeuronId::new(3), 2_000_000);
+        m.observe_spike(NeuronId::new(7), 2_000_000); // outside population
+        // 2 spikes / (2 neurons * 0.01 s) = 100 Hz
+        assert!((m.level(5_000_000) - 10.5).abs() < 1e-4);
+        // Spikes age out of the window
+        assert_eq!(m.level(20_000_000), 0.5);
+    }
+}
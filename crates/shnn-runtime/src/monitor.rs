@@ -0,0 +1,387 @@
+//! Online population monitors
+//!
+//! A [`RateMonitor`] counts spikes of a neuron range into fixed time bins while
+//! the simulation runs, so per-trial population rates are available without
+//! keeping (or re-scanning) the full spike raster. A [`WeightMonitor`] samples
+//! the weights of a few synapses at a fixed interval, giving learning curves
+//! without snapshotting the whole weight matrix.
+
+use crate::{error::{Result, RuntimeError}, network::SNNNetwork, NeuronId, Spike};
+
+use rand::{rngs::StdRng, seq::index, SeedableRng};
+
+/// Bins the population rate of neurons `start..=end`
+#[derive(Debug, Clone)]
+pub struct RateMonitor {
+    start: NeuronId,
+    end: NeuronId,
+    bin_ns: u64,
+    counts: Vec<u32>,
+}
+
+impl RateMonitor {
+    /// Monitor neurons `start..=end` with bins `bin_ns` wide
+    pub fn new(start: NeuronId, end: NeuronId, bin_ns: u64) -> Result<Self> {
+        if start.raw() > end.raw() {
+            return Err(RuntimeError::invalid_parameter(
+                "neurons",
+                format!("{}..{}", start.raw(), end.raw()),
+                "start <= end",
+            ));
+        }
+        if bin_ns == 0 {
+            return Err(RuntimeError::invalid_parameter("bin", "0", "> 0"));
+        }
+        Ok(Self { start, end, bin_ns, counts: Vec::new() })
+    }
+
+    /// Forget all counts
+    pub fn reset(&mut self) {
+        self.counts.clear();
+    }
+
+    /// Count the in-range spikes of one step
+    pub fn record(&mut self, spikes: &[Spike]) {
+        for spike in spikes {
+            let id = spike.neuron_id.raw();
+            if id < self.start.raw() || id > self.end.raw() {
+                continue;
+            }
+            // Spikes are stamped at the end of their step; t - 1 lands in that step's bin
+            let bin = (spike.time.nanos().saturating_sub(1) / self.bin_ns) as usize;
+            if bin >= self.counts.len() {
+                self.counts.resize(bin + 1, 0);
+            }
+            self.counts[bin] += 1;
+        }
+    }
+
+    /// Rates for a run of `duration_ns` (a trailing partial bin uses its own width)
+    pub fn finish(&self, duration_ns: u64) -> RateTrace {
+        let population = (self.end.raw() - self.start.raw()) as f32 + 1.0;
+        let num_bins = duration_ns.div_ceil(self.bin_ns).max(self.counts.len() as u64) as usize;
+        let mut stats = RateStats::default();
+        let rates_hz = (0..num_bins)
+            .map(|bin| {
+                let bin_start = bin as u64 * self.bin_ns;
+                let width_ns = duration_ns.saturating_sub(bin_start).clamp(1, self.bin_ns);
+                let count = self.counts.get(bin).copied().unwrap_or(0);
+                let rate = count as f32 / population / (width_ns as f32 / 1_000_000_000.0);
+                stats.push(rate);
+                rate
+            })
+            .collect();
+        RateTrace { start: self.start, end: self.end, bin_ns: self.bin_ns, rates_hz, stats }
+    }
+}
+
+/// Binned population rate recorded by a [`RateMonitor`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateTrace {
+    /// First monitored neuron
+    pub start: NeuronId,
+    /// Last monitored neuron (inclusive)
+    pub end: NeuronId,
+    /// Bin width (ns)
+    pub bin_ns: u64,
+    /// Mean per-neuron rate in each bin (Hz)
+    pub rates_hz: Vec<f32>,
+    /// Summary over bins
+    pub stats: RateStats,
+}
+
+/// Running mean/variance/extrema over binned rates (Welford)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateStats {
+    /// Number of bins
+    pub count: usize,
+    /// Mean rate (Hz)
+    pub mean_hz: f32,
+    /// Smallest bin rate (Hz)
+    pub min_hz: f32,
+    /// Largest bin rate (Hz)
+    pub max_hz: f32,
+    m2: f64,
+}
+
+impl RateStats {
+    /// Add one bin rate
+    pub fn push(&mut self, rate_hz: f32) {
+        if self.count == 0 {
+            self.min_hz = rate_hz;
+            self.max_hz = rate_hz;
+        } else {
+            self.min_hz = self.min_hz.min(rate_hz);
+            self.max_hz = self.max_hz.max(rate_hz);
+        }
+        self.count += 1;
+        let delta = rate_hz as f64 - self.mean_hz as f64;
+        let mean = self.mean_hz as f64 + delta / self.count as f64;
+        self.m2 += delta * (rate_hz as f64 - mean);
+        self.mean_hz = mean as f32;
+    }
+
+    /// Population standard deviation across bins (Hz)
+    pub fn std_hz(&self) -> f32 {
+        if self.count == 0 {
+            0.0
+        } else {
+            (self.m2 / self.count as f64).sqrt() as f32
+        }
+    }
+}
+
+/// Samples the weights of selected synapses every `interval_ns`
+#[derive(Debug, Clone)]
+pub struct WeightMonitor {
+    synapses: Vec<(NeuronId, NeuronId)>,
+    interval_ns: u64,
+    next_sample_ns: u64,
+    times_ns: Vec<u64>,
+    weights: Vec<f32>,
+}
+
+impl WeightMonitor {
+    /// Monitor the given `(pre, post)` synapses, which must exist in `network`
+    pub fn new(network: &SNNNetwork, synapses: Vec<(NeuronId, NeuronId)>, interval_ns: u64) -> Result<Self> {
+        if interval_ns == 0 {
+            return Err(RuntimeError::invalid_parameter("interval", "0", "> 0"));
+        }
+        if synapses.is_empty() {
+            return Err(RuntimeError::invalid_parameter("pairs", "[]", "at least one synapse"));
+        }
+        for &(pre, post) in &synapses {
+            network.get_weight(pre, post)?;
+        }
+        Ok(Self { synapses, interval_ns, next_sample_ns: 0, times_ns: Vec::new(), weights: Vec::new() })
+    }
+
+    /// Monitor `count` synapses of `network` chosen uniformly without replacement
+    /// (all of them if it has fewer), reproducibly for a given `seed`
+    pub fn sample(network: &SNNNetwork, count: usize, interval_ns: u64, seed: u64) -> Result<Self> {
+        let mut all: Vec<(NeuronId, NeuronId)> = network.synapse_connections()
+            .into_iter()
+            .map(|(pre, post, _)| (pre, post))
+            .collect();
+        // Synapses are stored unordered; sort so the seed alone fixes the choice
+        all.sort_unstable_by_key(|(pre, post)| (pre.raw(), post.raw()));
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut chosen: Vec<usize> = index::sample(&mut rng, all.len(), count.min(all.len())).into_vec();
+        chosen.sort_unstable();
+        let synapses = chosen.into_iter().map(|i| all[i]).collect();
+        Self::new(network, synapses, interval_ns)
+    }
+
+    /// Monitored `(pre, post)` synapses, in trace column order
+    pub fn synapses(&self) -> &[(NeuronId, NeuronId)] {
+        &self.synapses
+    }
+
+    /// Drop recorded samples; the next observation is sampled immediately
+    pub fn reset(&mut self) {
+        self.next_sample_ns = 0;
+        self.times_ns.clear();
+        self.weights.clear();
+    }
+
+    /// Record the current weights if a sample is due at `time_ns`
+    pub fn observe(&mut self, network: &SNNNetwork, time_ns: u64) -> Result<()> {
+        if time_ns < self.next_sample_ns {
+            return Ok(());
+        }
+        for &(pre, post) in &self.synapses {
+            self.weights.push(network.get_weight(pre, post)?);
+        }
+        self.times_ns.push(time_ns);
+        self.next_sample_ns = (time_ns / self.interval_ns + 1) * self.interval_ns;
+        Ok(())
+    }
+
+    /// Recorded trajectory
+    pub fn finish(&self) -> WeightTrace {
+        WeightTrace {
+            synapses: self.synapses.clone(),
+            interval_ns: self.interval_ns,
+            times_ns: self.times_ns.clone(),
+            weights: self.weights.clone(),
+        }
+    }
+}
+
+/// Weight trajectories recorded by a [`WeightMonitor`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightTrace {
+    /// Monitored `(pre, post)` synapses (columns)
+    pub synapses: Vec<(NeuronId, NeuronId)>,
+    /// Sampling interval (ns)
+    pub interval_ns: u64,
+    /// Sample times (ns, rows)
+    pub times_ns: Vec<u64>,
+    /// Row-major weights: `weights[row * synapses.len() + column]`
+    pub weights: Vec<f32>,
+}
+
+impl WeightTrace {
+    /// Binary trace magic
+    pub const MAGIC: [u8; 4] = *b"WTRC";
+    /// Binary trace format version
+    pub const VERSION: u16 = 1;
+
+    /// Weight of synapse `column` at sample `row`
+    pub fn weight(&self, row: usize, column: usize) -> Option<f32> {
+        if column >= self.synapses.len() {
+            return None;
+        }
+        self.weights.get(row * self.synapses.len() + column).copied()
+    }
+
+    /// Trajectory of one synapse across all samples
+    pub fn trajectory(&self, column: usize) -> Vec<f32> {
+        (0..self.times_ns.len()).filter_map(|row| self.weight(row, column)).collect()
+    }
+
+    /// Encode as a little-endian binary trace
+    ///
+    /// Layout: magic, version (u16), reserved (u16), synapse count (u32),
+    /// sample count (u32), interval (u64), `(pre, post)` pairs as u32, then
+    /// per sample its time (u64) followed by one f32 weight per synapse.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let columns = self.synapses.len();
+        let mut out = Vec::with_capacity(24 + columns * 8 + self.times_ns.len() * (8 + columns * 4));
+        out.extend_from_slice(&Self::MAGIC);
+        out.extend_from_slice(&Self::VERSION.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&(columns as u32).to_le_bytes());
+        out.extend_from_slice(&(self.times_ns.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.interval_ns.to_le_bytes());
+        for (pre, post) in &self.synapses {
+            out.extend_from_slice(&pre.raw().to_le_bytes());
+            out.extend_from_slice(&post.raw().to_le_bytes());
+        }
+        for (row, time_ns) in self.times_ns.iter().enumerate() {
+            out.extend_from_slice(&time_ns.to_le_bytes());
+            for weight in &self.weights[row * columns..(row + 1) * columns] {
+                out.extend_from_slice(&weight.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// Decode a trace written by [`WeightTrace::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = ByteReader { bytes, pos: 0 };
+        if reader.take(4)? != Self::MAGIC {
+            return Err(RuntimeError::invalid_config("weight trace: bad magic"));
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != Self::VERSION {
+            return Err(RuntimeError::invalid_config(format!("weight trace: unsupported version {}", version)));
+        }
+        reader.take(2)?;
+        let columns = u32::from_le_bytes(reader.array()?) as usize;
+        let rows = u32::from_le_bytes(reader.array()?) as usize;
+        let interval_ns = u64::from_le_bytes(reader.array()?);
+        let synapses = (0..columns)
+            .map(|_| {
+                let pre = u32::from_le_bytes(reader.array()?);
+                let post = u32::from_le_bytes(reader.array()?);
+                Ok((NeuronId::new(pre), NeuronId::new(post)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut times_ns = Vec::with_capacity(rows);
+        let mut weights = Vec::with_capacity(rows * columns);
+        for _ in 0..rows {
+            times_ns.push(u64::from_le_bytes(reader.array()?));
+            for _ in 0..columns {
+                weights.push(f32::from_le_bytes(reader.array()?));
+            }
+        }
+        if reader.pos != bytes.len() {
+            return Err(RuntimeError::invalid_config("weight trace: trailing bytes"));
+        }
+        Ok(Self { synapses, interval_ns, times_ns, weights })
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos + len;
+        let slice = self.bytes.get(self.pos..end)
+            .ok_or_else(|| RuntimeError::invalid_config("weight trace: truncated"))?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Time;
+
+    fn spike(id: u32, t_ns: u64) -> Spike {
+        Spike::new(NeuronId::new(id), Time::from_nanos(t_ns))
+    }
+
+    #[test]
+    fn test_rate_monitor_bins() {
+        // Two neurons, 10 ms bins, 25 ms run (last bin is 5 ms wide)
+        let mut monitor = RateMonitor::new(NeuronId::new(1), NeuronId::new(2), 10_000_000).unwrap();
+        monitor.record(&[spike(1, 1_000_000), spike(2, 10_000_000), spike(3, 5_000_000)]);
+        monitor.record(&[spike(2, 10_000_001), spike(1, 24_000_000)]);
+
+        let trace = monitor.finish(25_000_000);
+        // 2 spikes / 2 neurons / 10 ms, 1 / 2 / 10 ms, 1 / 2 / 5 ms
+        assert_eq!(trace.rates_hz, vec![100.0, 50.0, 100.0]);
+        assert_eq!(trace.stats.count, 3);
+        assert!((trace.stats.mean_hz - 250.0 / 3.0).abs() < 1e-3);
+        assert_eq!((trace.stats.min_hz, trace.stats.max_hz), (50.0, 100.0));
+        assert!((trace.stats.std_hz() - 23.570_227).abs() < 1e-3);
+
+        monitor.reset();
+        assert!(monitor.finish(25_000_000).rates_hz.iter().all(|&r| r == 0.0));
+        assert!(RateMonitor::new(NeuronId::new(0), NeuronId::new(0), 0).is_err());
+    }
+
+    #[test]
+    fn test_weight_monitor_sampling_and_roundtrip() {
+        use crate::network::NetworkBuilder;
+
+        let network = NetworkBuilder::new()
+            .add_neurons(0, 4)
+            .fully_connected(0.5)
+            .build()
+            .unwrap();
+        let pick = |seed| WeightMonitor::sample(&network, 3, 1_000_000, seed).unwrap().synapses().to_vec();
+        assert_eq!(pick(1).len(), 3);
+        assert_eq!(pick(1), pick(1));
+        assert_eq!(WeightMonitor::sample(&network, 100, 1, 0).unwrap().synapses().len(), network.synapse_count());
+
+        let pairs = vec![(NeuronId::new(0), NeuronId::new(1)), (NeuronId::new(2), NeuronId::new(3))];
+        let mut monitor = WeightMonitor::new(&network, pairs.clone(), 1_000_000).unwrap();
+        // Sampled at 0, then on the first observation at or after each 1 ms boundary
+        for t in [0, 400_000, 1_000_000, 1_500_000, 2_300_000] {
+            monitor.observe(&network, t).unwrap();
+        }
+        let trace = monitor.finish();
+        assert_eq!(trace.times_ns, vec![0, 1_000_000, 2_300_000]);
+        assert_eq!(trace.trajectory(1), vec![0.5; 3]);
+        assert_eq!(trace.weight(0, 2), None);
+        assert_eq!(WeightTrace::from_bytes(&trace.to_bytes()).unwrap(), trace);
+        assert!(WeightTrace::from_bytes(&trace.to_bytes()[..30]).is_err());
+
+        let missing = vec![(NeuronId::new(0), NeuronId::new(9))];
+        assert!(WeightMonitor::new(&network, missing, 1).is_err());
+        assert!(WeightMonitor::new(&network, pairs, 0).is_err());
+    }
+}
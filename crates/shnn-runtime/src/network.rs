@@ -1,12 +1,23 @@
 //! SNN network implementation
 
 use crate::{
+    delay::{DelayQueue, SubDtDelay},
     error::*,
-    neuron::{LIFNeuron, LIFParams},
+    integrator::{IntegratorKind, SpikeTiming},
+    mask::{MaskMode, NeuronMask},
+    modulation::Modulator,
+    observer::WeightUpdate,
+    neuron::{LIFParams, Neuron, NeuronModel},
     plasticity::{STDPRule, STDPParams, SynapseId, PlasticityRule},
-    NeuronId, Time, Spike,
+    routing::{ConnectivityBackend, SpikeRouter},
+    spatial::NeuronPositions,
+    state::{NetworkState, NeuronSnapshot},
+    NeuronId, Time, Spike, GenerationId,
 };
-use std::collections::HashMap;
+use shnn_storage::vcsr::VCSRSnapshot;
+use shnn_types::units::{self, Quantity};
+use std::collections::{BTreeSet, HashMap};
+use std::ops::RangeInclusive;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -24,6 +35,14 @@ pub struct NetworkConfig {
     pub input_scale: f32,
     /// Enable plasticity updates
     pub plasticity_enabled: bool,
+    /// Integrator used for LIF and AdEx membrane dynamics
+    pub integrator: IntegratorKind,
+    /// Placement of LIF spikes within a step
+    pub spike_timing: SpikeTiming,
+    /// Index used to find outgoing synapses of a spiking neuron
+    pub connectivity: ConnectivityBackend,
+    /// Handling of synaptic delays shorter than dt
+    pub sub_dt_delay: SubDtDelay,
 }
 
 impl Default for NetworkConfig {
@@ -34,6 +53,10 @@ impl Default for NetworkConfig {
             default_weight: 0.1,
             input_scale: 1.0,
             plasticity_enabled: true,
+            integrator: IntegratorKind::default(),
+            spike_timing: SpikeTiming::default(),
+            connectivity: ConnectivityBackend::default(),
+            sub_dt_delay: SubDtDelay::default(),
         }
     }
 }
@@ -77,25 +100,49 @@ struct DelayedSpike {
     target: NeuronId,
     /// Synaptic weight
     weight: f32,
-    /// Delivery time (ns)
-    delivery_time: u64,
+    /// Arrival time at the target (ns)
+    arrival_ns: u64,
 }
 
+/// Size of one queued delayed spike, for memory estimation
+pub(crate) const DELAYED_SPIKE_BYTES: usize = core::mem::size_of::<DelayedSpike>();
+
+/// Width of the delay queue's time buckets (ns)
+const DELAY_BUCKET_NS: u64 = 100_000;
+
 /// Spiking Neural Network implementation
 #[derive(Debug)]
 pub struct SNNNetwork {
     /// Network configuration
     pub config: NetworkConfig,
     /// All neurons in the network
-    neurons: HashMap<NeuronId, LIFNeuron>,
+    neurons: HashMap<NeuronId, Neuron>,
     /// All synaptic connections
     synapses: HashMap<SynapseId, Synapse>,
     /// Plasticity rule
     plasticity: Option<STDPRule>,
-    /// Delayed spike queue
-    spike_queue: Vec<DelayedSpike>,
+    /// Delayed spike queue (bucketed by delivery time)
+    spike_queue: DelayQueue<DelayedSpike>,
     /// Current simulation time (ns)
     current_time: u64,
+    /// Neurons allowed to spike (all if None)
+    activity_mask: Option<NeuronMask>,
+    /// Neurons whose synapses may update (all if None)
+    plasticity_mask: Option<NeuronMask>,
+    /// Global modulator scaling STDP (plain STDP if None)
+    modulator: Option<Modulator>,
+    /// Sub-steps per global step for neurons with a finer dt (1 if absent)
+    substeps: HashMap<NeuronId, u32>,
+    /// Spatial layout (empty unless positions were assigned)
+    positions: NeuronPositions,
+    /// Outgoing-synapse index (rebuilt lazily after topology changes)
+    router: SpikeRouter,
+    /// Reused target buffer for spike propagation
+    route_buf: Vec<NeuronId>,
+    /// Plasticity weight changes not yet taken (None when not tracking)
+    weight_updates: Option<Vec<WeightUpdate>>,
+    /// Interpolated input spilling into the next step
+    carried_input: Vec<(NeuronId, f32)>,
 }
 
 impl SNNNetwork {
@@ -110,13 +157,23 @@ impl SNNNetwork {
             None
         };
 
+        let router = SpikeRouter::new(config.connectivity);
         Ok(Self {
             config,
             neurons: HashMap::new(),
             synapses: HashMap::new(),
             plasticity,
-            spike_queue: Vec::new(),
+            spike_queue: DelayQueue::new(DELAY_BUCKET_NS),
             current_time: 0,
+            activity_mask: None,
+            plasticity_mask: None,
+            modulator: None,
+            substeps: HashMap::new(),
+            positions: NeuronPositions::new(),
+            router,
+            route_buf: Vec::new(),
+            weight_updates: None,
+            carried_input: Vec::new(),
         })
     }
 
@@ -127,17 +184,43 @@ impl SNNNetwork {
 
     /// Add a neuron with specific parameters
     pub fn add_neuron_with_params(&mut self, id: NeuronId, params: LIFParams) -> Result<()> {
+        self.add_neuron_with_model(id, NeuronModel::Lif(params))
+    }
+
+    /// Add a neuron of any supported model
+    pub fn add_neuron_with_model(&mut self, id: NeuronId, model: NeuronModel) -> Result<()> {
         if self.neurons.contains_key(&id) {
             return Err(RuntimeError::invalid_config(
                 format!("Neuron {} already exists", id.raw())
             ));
         }
 
-        let neuron = LIFNeuron::new(id, params)?;
+        let neuron = Neuron::new(id, model)?;
         self.neurons.insert(id, neuron);
         Ok(())
     }
 
+    /// Integrate a neuron with dt / `substeps` inside each global step
+    pub fn set_substeps(&mut self, id: NeuronId, substeps: u32) -> Result<()> {
+        if !self.neurons.contains_key(&id) {
+            return Err(RuntimeError::NeuronNotFound { neuron_id: id.raw() });
+        }
+        if substeps == 0 {
+            return Err(RuntimeError::invalid_parameter("substeps", "0", ">= 1"));
+        }
+        if substeps == 1 {
+            self.substeps.remove(&id);
+        } else {
+            self.substeps.insert(id, substeps);
+        }
+        Ok(())
+    }
+
+    /// Sub-steps per global step for a neuron
+    pub fn substeps(&self, id: NeuronId) -> u32 {
+        self.substeps.get(&id).copied().unwrap_or(1)
+    }
+
     /// Add a synaptic connection
     pub fn add_synapse(&mut self, pre: NeuronId, post: NeuronId, weight: f32, delay: f32) -> Result<()> {
         // Validate neurons exist
@@ -156,6 +239,9 @@ impl SNNNetwork {
                 ">= 0.0",
             ));
         }
+        if let Err(e) = Quantity::ms(delay as f64).round_ns() {
+            return Err(RuntimeError::invalid_time("delay", delay, e));
+        }
 
         let synapse = Synapse::new(pre, post, weight, delay);
         let synapse_id = synapse.id();
@@ -167,6 +253,7 @@ impl SNNNetwork {
         }
 
         self.synapses.insert(synapse_id, synapse);
+        self.router.invalidate();
         Ok(())
     }
 
@@ -183,11 +270,13 @@ impl SNNNetwork {
         self.synapses.retain(|synapse_id, _| {
             synapse_id.pre != id && synapse_id.post != id
         });
+        self.router.invalidate();
 
         // Remove any delayed spikes for this neuron
         self.spike_queue.retain(|delayed_spike| {
             delayed_spike.spike.neuron_id != id && delayed_spike.target != id
         });
+        self.carried_input.retain(|(target, _)| *target != id);
 
         Ok(())
     }
@@ -202,6 +291,11 @@ impl SNNNetwork {
         self.synapses.len()
     }
 
+    /// Network configuration
+    pub fn config(&self) -> &NetworkConfig {
+        &self.config
+    }
+
     /// Get current simulation time
     pub fn current_time(&self) -> Time {
         Time::from_nanos(self.current_time)
@@ -216,33 +310,60 @@ impl SNNNetwork {
         Ok(())
     }
 
+    /// Treat `spike` as fired by its neuron: STDP and modulation observe it and
+    /// it is queued on every outgoing synapse (spikes in the past arrive on the next step)
+    pub fn inject_spike(&mut self, spike: &Spike) -> Result<()> {
+        if !self.neurons.contains_key(&spike.neuron_id) {
+            return Err(RuntimeError::NeuronNotFound { neuron_id: spike.neuron_id.raw() });
+        }
+        if let Some(ref mut plasticity) = self.plasticity {
+            plasticity.record_spike(spike.neuron_id, spike.time);
+        }
+        if let Some(ref mut modulator) = self.modulator {
+            modulator.observe_spike(spike.neuron_id, spike.time.nanos());
+        }
+        self.propagate_spike(spike)
+    }
+
     /// Step the network forward by one time step
     pub fn step(&mut self, dt_ms: f32) -> Result<Vec<Spike>> {
         let mut output_spikes = Vec::new();
 
         // Update simulation time
-        let dt_ns = (dt_ms * 1_000_000.0) as u64;
+        let dt_ns = units::ms_to_ns(dt_ms).map_err(|e| RuntimeError::invalid_time("dt_ms", dt_ms, e))?;
         self.current_time += dt_ns;
 
         // Process delayed spikes
-        self.process_delayed_spikes(&mut output_spikes)?;
+        self.process_delayed_spikes(dt_ns, &mut output_spikes)?;
 
         // Update all neurons
         let neuron_spikes = self.update_neurons(dt_ms)?;
 
-        // Process new spikes
+        // Process new spikes (neurons outside the activity mask are silenced)
         for spike in neuron_spikes {
+            if let Some(ref mask) = self.activity_mask {
+                if !mask.contains(spike.neuron_id) {
+                    continue;
+                }
+            }
+
             // Record for plasticity
             if let Some(ref mut plasticity) = self.plasticity {
                 plasticity.record_spike(spike.neuron_id, spike.time);
             }
+            if let Some(ref mut modulator) = self.modulator {
+                modulator.observe_spike(spike.neuron_id, spike.time.nanos());
+            }
 
             // Propagate through synapses
             self.propagate_spike(&spike)?;
             output_spikes.push(spike);
         }
 
-        // Apply plasticity updates
+        // Apply plasticity updates, scaled by the current modulation level
+        if let (Some(modulator), Some(plasticity)) = (self.modulator.as_mut(), self.plasticity.as_mut()) {
+            plasticity.set_modulation(modulator.level(self.current_time));
+        }
         if self.config.plasticity_enabled {
             self.apply_plasticity_updates()?;
         }
@@ -251,23 +372,25 @@ impl SNNNetwork {
     }
 
     /// Process delayed spikes that should be delivered now
-    fn process_delayed_spikes(&mut self, output_spikes: &mut Vec<Spike>) -> Result<()> {
-        let current_time = self.current_time;
-        let mut delivered_indices = Vec::new();
-
-        for (i, delayed_spike) in self.spike_queue.iter().enumerate() {
-            if delayed_spike.delivery_time <= current_time {
-                // Deliver spike to target neuron
-                if let Some(neuron) = self.neurons.get_mut(&delayed_spike.target) {
-                    neuron.receive_input(delayed_spike.weight);
-                }
-                delivered_indices.push(i);
+    fn process_delayed_spikes(&mut self, dt_ns: u64, output_spikes: &mut Vec<Spike>) -> Result<()> {
+        for (target, weight) in core::mem::take(&mut self.carried_input) {
+            if let Some(neuron) = self.neurons.get_mut(&target) {
+                neuron.receive_input(weight);
             }
         }
 
-        // Remove delivered spikes (in reverse order to maintain indices)
-        for &i in delivered_indices.iter().rev() {
-            self.spike_queue.swap_remove(i);
+        let mut due = Vec::new();
+        self.spike_queue.pop_due(self.current_time, &mut due);
+
+        for delayed_spike in due {
+            // Deliver spike to target neuron; interpolated input may spill into the next step
+            let fraction = self.config.sub_dt_delay.arrival_fraction(delayed_spike.arrival_ns, self.current_time, dt_ns);
+            if let Some(neuron) = self.neurons.get_mut(&delayed_spike.target) {
+                neuron.receive_input(delayed_spike.weight * fraction);
+                if fraction < 1.0 {
+                    self.carried_input.push((delayed_spike.target, delayed_spike.weight * (1.0 - fraction)));
+                }
+            }
         }
 
         Ok(())
@@ -276,16 +399,25 @@ impl SNNNetwork {
     /// Update all neurons for one time step
     fn update_neurons(&mut self, dt_ms: f32) -> Result<Vec<Spike>> {
         let current_time = self.current_time;
+        let integrator = self.config.integrator;
+        let timing = self.config.spike_timing;
+        let substeps = &self.substeps;
         let mut spikes = Vec::new();
 
         #[cfg(feature = "parallel")]
         let neuron_updates: Result<Vec<_>> = self.neurons.par_iter_mut()
-            .map(|(_, neuron)| neuron.update(dt_ms, current_time))
+            .map(|(id, neuron)| {
+                let n = substeps.get(id).copied().unwrap_or(1);
+                neuron.update_substepped(integrator, timing, dt_ms, current_time, n)
+            })
             .collect();
 
         #[cfg(not(feature = "parallel"))]
         let neuron_updates: Result<Vec<_>> = self.neurons.iter_mut()
-            .map(|(_, neuron)| neuron.update(dt_ms, current_time))
+            .map(|(id, neuron)| {
+                let n = substeps.get(id).copied().unwrap_or(1);
+                neuron.update_substepped(integrator, timing, dt_ms, current_time, n)
+            })
             .collect();
 
         for spike_opt in neuron_updates? {
@@ -299,26 +431,44 @@ impl SNNNetwork {
 
     /// Propagate a spike through the network
     fn propagate_spike(&mut self, spike: &Spike) -> Result<()> {
+        if self.router.is_stale() {
+            self.router.rebuild(self.neurons.len(), self.synapses.keys());
+        }
+
         // Find all outgoing synapses from the spiking neuron
-        for (synapse_id, synapse) in &self.synapses {
-            if synapse_id.pre == spike.neuron_id {
-                let delay_ns = (synapse.delay * 1_000_000.0) as u64;
-                let delivery_time = spike.time.nanos() + delay_ns;
-
-                let delayed_spike = DelayedSpike {
-                    spike: spike.clone(),
-                    target: synapse.post,
-                    weight: synapse.weight,
-                    delivery_time,
-                };
-
-                self.spike_queue.push(delayed_spike);
-            }
+        let mut targets = core::mem::take(&mut self.route_buf);
+        targets.clear();
+        self.router.targets(spike.neuron_id, &mut targets);
+        for &post in &targets {
+            let Some(synapse) = self.synapses.get(&SynapseId::new(spike.neuron_id, post)) else { continue };
+            // Sampled delays are arbitrary floats; rounding to whole ns is intended
+            let delay_ns = Quantity::ms(synapse.delay as f64)
+                .round_ns()
+                .map_err(|e| RuntimeError::invalid_time("delay", synapse.delay, e))?;
+            let delivery_time = spike.time.nanos() + delay_ns;
+
+            let delayed_spike = DelayedSpike {
+                spike: spike.clone(),
+                target: synapse.post,
+                weight: synapse.weight,
+                arrival_ns: delivery_time,
+            };
+
+            self.spike_queue.push(delivery_time, delayed_spike);
         }
+        self.route_buf = targets;
 
         Ok(())
     }
 
+    /// Connectivity backend routing spikes (resolves [`ConnectivityBackend::Auto`] by density)
+    pub fn connectivity_backend(&self) -> ConnectivityBackend {
+        match (self.router.requested(), self.router.is_stale()) {
+            (ConnectivityBackend::Auto, true) => ConnectivityBackend::for_density(self.neurons.len(), self.synapses.len()),
+            _ => self.router.backend(),
+        }
+    }
+
     /// Apply plasticity updates to synaptic weights
     fn apply_plasticity_updates(&mut self) -> Result<()> {
         if let Some(ref mut plasticity) = self.plasticity {
@@ -329,9 +479,23 @@ impl SNNNetwork {
             let updates = plasticity.apply_updates(&mut weights, Time::from_nanos(self.current_time))?;
 
             // Apply weight updates
-            for (synapse_id, _old_weight, new_weight) in updates {
+            for (synapse_id, old_weight, new_weight) in updates {
+                if let Some(ref mask) = self.plasticity_mask {
+                    if !mask.contains(synapse_id.pre) || !mask.contains(synapse_id.post) {
+                        continue;
+                    }
+                }
                 if let Some(synapse) = self.synapses.get_mut(&synapse_id) {
                     synapse.weight = new_weight;
+                    if let Some(tracked) = &mut self.weight_updates {
+                        tracked.push(WeightUpdate {
+                            pre: synapse_id.pre,
+                            post: synapse_id.post,
+                            old_weight,
+                            new_weight,
+                            time_ns: self.current_time,
+                        });
+                    }
                 }
             }
         }
@@ -339,6 +503,16 @@ impl SNNNetwork {
         Ok(())
     }
 
+    /// Record plasticity weight changes for [`SNNNetwork::take_weight_updates`]
+    pub fn track_weight_updates(&mut self, enabled: bool) {
+        self.weight_updates = enabled.then(Vec::new);
+    }
+
+    /// Weight changes since the last call (always empty unless tracking)
+    pub fn take_weight_updates(&mut self) -> Vec<WeightUpdate> {
+        self.weight_updates.as_mut().map(core::mem::take).unwrap_or_default()
+    }
+
     /// Get neuron membrane potential
     pub fn get_membrane_potential(&self, neuron_id: NeuronId) -> Result<f32> {
         let neuron = self.neurons.get(&neuron_id)
@@ -346,6 +520,16 @@ impl SNNNetwork {
         Ok(neuron.membrane_potential())
     }
 
+    /// Get synaptic delay (ms)
+    pub fn get_delay(&self, pre: NeuronId, post: NeuronId) -> Result<f32> {
+        let synapse_id = SynapseId::new(pre, post);
+        let synapse = self.synapses.get(&synapse_id)
+            .ok_or(RuntimeError::network_topology(
+                format!("No synapse from {} to {}", pre.raw(), post.raw())
+            ))?;
+        Ok(synapse.delay)
+    }
+
     /// Get synaptic weight
     pub fn get_weight(&self, pre: NeuronId, post: NeuronId) -> Result<f32> {
         let synapse_id = SynapseId::new(pre, post);
@@ -379,6 +563,96 @@ impl SNNNetwork {
             .collect()
     }
 
+    /// Export the current connectivity and weights as a VCSR snapshot.
+    ///
+    /// Vertices are indexed by raw neuron ID, so the snapshot spans
+    /// `0..=max_neuron_id` even if some IDs are unused.
+    pub fn to_vcsr_snapshot(&self, generation: GenerationId) -> Result<VCSRSnapshot> {
+        let num_vertices = self.neurons.keys()
+            .map(|id| id.raw() + 1)
+            .max()
+            .unwrap_or(0);
+        let edges = self.synapse_connections();
+        Ok(VCSRSnapshot::from_edges(generation, num_vertices, 0, &edges)?)
+    }
+
+    /// Neuron potentials, model variables and synapse weights at the current time
+    pub fn capture_state(&self) -> NetworkState {
+        let mut neurons: Vec<NeuronSnapshot> = self.neurons.values()
+            .map(|neuron| NeuronSnapshot { id: neuron.id(), v_m: neuron.membrane_potential(), vars: neuron.model_vars() })
+            .collect();
+        neurons.sort_by_key(|n| n.id.raw());
+        let mut synapses = self.synapse_connections();
+        synapses.sort_by_key(|(pre, post, _)| (pre.raw(), post.raw()));
+        NetworkState { time_ns: self.current_time, neurons, synapses }
+    }
+
+    /// Load neuron and synapse state saved by [`SNNNetwork::capture_state`]
+    ///
+    /// Every neuron and synapse in `state` must exist; ones it omits keep their
+    /// current state. Spikes in flight and plasticity traces are cleared, and the
+    /// network clock is left unchanged.
+    pub fn restore_state(&mut self, state: &NetworkState) -> Result<()> {
+        for saved in &state.neurons {
+            let neuron = self.neurons.get_mut(&saved.id)
+                .ok_or(RuntimeError::NeuronNotFound { neuron_id: saved.id.raw() })?;
+            neuron.restore(saved.v_m, &saved.vars)?;
+        }
+        for &(pre, post, weight) in &state.synapses {
+            self.set_weight(pre, post, weight)?;
+        }
+        self.spike_queue.clear();
+        self.carried_input.clear();
+        if let Some(ref mut plasticity) = self.plasticity {
+            plasticity.clear_history();
+        }
+        Ok(())
+    }
+
+    /// Attach a mask gating activity or plasticity (replaces any mask in the same mode)
+    pub fn apply_mask(&mut self, mode: MaskMode, mask: NeuronMask) {
+        match mode {
+            MaskMode::Activity => self.activity_mask = Some(mask),
+            MaskMode::Plasticity => self.plasticity_mask = Some(mask),
+        }
+    }
+
+    /// Detach the mask for a mode
+    pub fn clear_mask(&mut self, mode: MaskMode) {
+        match mode {
+            MaskMode::Activity => self.activity_mask = None,
+            MaskMode::Plasticity => self.plasticity_mask = None,
+        }
+    }
+
+    /// Mask currently attached for a mode
+    pub fn mask(&self, mode: MaskMode) -> Option<&NeuronMask> {
+        match mode {
+            MaskMode::Activity => self.activity_mask.as_ref(),
+            MaskMode::Plasticity => self.plasticity_mask.as_ref(),
+        }
+    }
+
+    /// Install a global modulator that scales STDP weight changes
+    pub fn set_modulator(&mut self, modulator: Modulator) {
+        self.modulator = Some(modulator);
+    }
+
+    /// Attach a spatial layout (carried into results for visualization)
+    pub fn set_positions(&mut self, positions: NeuronPositions) {
+        self.positions = positions;
+    }
+
+    /// Spatial layout of the neurons (empty if none was assigned)
+    pub fn positions(&self) -> &NeuronPositions {
+        &self.positions
+    }
+
+    /// Current modulation level applied to STDP (1.0 without a modulator)
+    pub fn modulation_level(&self) -> f32 {
+        self.plasticity.as_ref().map_or(1.0, |p| p.modulation())
+    }
+
     /// Count outgoing synapses for a given pre-synaptic neuron
     pub fn outgoing_count(&self, pre: NeuronId) -> usize {
         self.synapses.keys().filter(|id| id.pre == pre).count()
@@ -388,27 +662,63 @@ impl SNNNetwork {
     pub fn reset(&mut self) -> Result<()> {
         self.current_time = 0;
         self.spike_queue.clear();
+        self.carried_input.clear();
 
         // Reset all neurons
         for (id, neuron) in &mut self.neurons {
-            *neuron = LIFNeuron::new(*id, neuron.params.clone())?;
+            *neuron = Neuron::new(*id, neuron.model())?;
         }
 
         // Reset plasticity
         if let Some(ref mut plasticity) = self.plasticity {
             plasticity.clear_history();
+            plasticity.set_modulation(1.0);
+        }
+        if let Some(ref mut modulator) = self.modulator {
+            modulator.reset();
+        }
+        if let Some(tracked) = &mut self.weight_updates {
+            tracked.clear();
         }
 
         Ok(())
     }
 }
 
+/// How [`NetworkBuilder::build`] treats repeated (pre, post) synapses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateSynapses {
+    /// Fail with [`BuildCode::DuplicateSynapse`]
+    #[default]
+    Reject,
+    /// Keep the first synapse added
+    KeepFirst,
+    /// Keep the last synapse added
+    KeepLast,
+    /// Merge into one synapse with the summed weight (first delay)
+    SumWeights,
+}
+
+/// Non-fatal finding of [`NetworkBuilder::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildWarning {
+    /// Stable warning code
+    pub code: BuildCode,
+    /// What was found
+    pub message: String,
+}
+
 /// Builder for constructing SNN networks
 #[derive(Debug)]
 pub struct NetworkBuilder {
     config: NetworkConfig,
-    neurons: Vec<(NeuronId, Option<LIFParams>)>,
+    neurons: Vec<(NeuronId, Option<NeuronModel>)>,
+    populations: Vec<(u32, u32, NeuronModel)>, // (start, end exclusive, model)
+    population_substeps: Vec<(u32, u32, u32)>,  // (start, end exclusive, substeps)
     synapses: Vec<(NeuronId, NeuronId, f32, f32)>, // (pre, post, weight, delay)
+    duplicate_synapses: DuplicateSynapses,
+    allow_id_gaps: bool,
+    dt_ms: Option<f32>,
 }
 
 impl NetworkBuilder {
@@ -417,10 +727,33 @@ impl NetworkBuilder {
         Self {
             config: NetworkConfig::default(),
             neurons: Vec::new(),
+            populations: Vec::new(),
+            population_substeps: Vec::new(),
             synapses: Vec::new(),
+            duplicate_synapses: DuplicateSynapses::default(),
+            allow_id_gaps: true,
+            dt_ms: None,
         }
     }
 
+    /// Policy for repeated (pre, post) synapses (default: reject)
+    pub fn duplicate_synapses(mut self, policy: DuplicateSynapses) -> Self {
+        self.duplicate_synapses = policy;
+        self
+    }
+
+    /// Whether non-contiguous neuron ids are allowed (default: yes, with a warning)
+    pub fn allow_id_gaps(mut self, allow: bool) -> Self {
+        self.allow_id_gaps = allow;
+        self
+    }
+
+    /// Time step the network will run at, to flag delays shorter than it
+    pub fn with_dt(mut self, dt_ms: f32) -> Self {
+        self.dt_ms = Some(dt_ms);
+        self
+    }
+
     /// Set network configuration
     pub fn with_config(mut self, config: NetworkConfig) -> Self {
         self.config = config;
@@ -435,7 +768,27 @@ impl NetworkBuilder {
 
     /// Add a neuron with specific parameters
     pub fn add_neuron_with_params(mut self, id: NeuronId, params: LIFParams) -> Self {
-        self.neurons.push((id, Some(params)));
+        self.neurons.push((id, Some(NeuronModel::Lif(params))));
+        self
+    }
+
+    /// Add a neuron of any supported model
+    pub fn add_neuron_with_model(mut self, id: NeuronId, model: NeuronModel) -> Self {
+        self.neurons.push((id, Some(model)));
+        self
+    }
+
+    /// Use `model` for neurons in `start..end` that have no explicit parameters
+    ///
+    /// Later populations override earlier ones where they overlap.
+    pub fn with_population_model(mut self, start: u32, end: u32, model: NeuronModel) -> Self {
+        self.populations.push((start, end, model));
+        self
+    }
+
+    /// Integrate neurons in `start..end` with dt / `substeps` (later entries win)
+    pub fn with_population_substeps(mut self, start: u32, end: u32, substeps: u32) -> Self {
+        self.population_substeps.push((start, end, substeps));
         self
     }
 
@@ -447,18 +800,47 @@ impl NetworkBuilder {
         self
     }
 
+    /// Add neurons `ids` with default parameters, reserving room for all of them
+    pub fn add_neuron_range(mut self, ids: RangeInclusive<u32>) -> Self {
+        self.neurons.reserve(ids.size_hint().0);
+        self.neurons.extend(ids.map(|id| (NeuronId::new(id), None)));
+        self
+    }
+
     /// Add a synapse
     pub fn add_synapse(mut self, pre: NeuronId, post: NeuronId, weight: f32, delay: f32) -> Self {
         self.synapses.push((pre, post, weight, delay));
         self
     }
 
+    /// Add (pre, post, weight, delay) synapses in order, reserving room for
+    /// as many as `synapses` reports
+    pub fn add_synapses_from_iter<I>(mut self, synapses: I) -> Self
+    where
+        I: IntoIterator<Item = (NeuronId, NeuronId, f32, f32)>,
+    {
+        let synapses = synapses.into_iter();
+        self.synapses.reserve(synapses.size_hint().0);
+        self.synapses.extend(synapses);
+        self
+    }
+
     /// Add synapses with default delay
     pub fn add_synapse_simple(mut self, pre: NeuronId, post: NeuronId, weight: f32) -> Self {
         self.synapses.push((pre, post, weight, 1.0)); // 1ms default delay
         self
     }
 
+    /// Neuron ids in the order they were added
+    pub fn neuron_ids(&self) -> Vec<NeuronId> {
+        self.neurons.iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Synapses as (pre, post, weight, delay), in the order they were added
+    pub fn synapses(&self) -> &[(NeuronId, NeuronId, f32, f32)] {
+        &self.synapses
+    }
+
     /// Connect all neurons in a fully connected pattern
     pub fn fully_connected(mut self, weight: f32) -> Self {
         let neurons: Vec<_> = self.neurons.iter().map(|(id, _)| *id).collect();
@@ -473,28 +855,156 @@ impl NetworkBuilder {
         self
     }
 
-    /// Build the network
+    /// Check the builder's contents without building
+    ///
+    /// Returns the warnings, or the first problem as a [`RuntimeError::Build`].
+    pub fn validate(&self) -> Result<Vec<BuildWarning>> {
+        let mut warnings = Vec::new();
+        if self.neurons.is_empty() {
+            return Err(RuntimeError::build_error(BuildCode::NoNeurons, "network has no neurons"));
+        }
+
+        let mut ids = BTreeSet::new();
+        for (id, _) in &self.neurons {
+            if !ids.insert(id.raw()) {
+                return Err(RuntimeError::build_error(
+                    BuildCode::DuplicateNeuron,
+                    format!("neuron {} is added more than once", id.raw()),
+                ));
+            }
+        }
+
+        let gaps: Vec<(u32, u32)> = ids.iter().zip(ids.iter().skip(1))
+            .filter(|(a, b)| **b > **a + 1)
+            .map(|(a, b)| (a + 1, b - 1))
+            .collect();
+        if let Some(&(first, last)) = gaps.first() {
+            let message = format!(
+                "neuron ids are not contiguous: {} gap(s), first {}..={}",
+                gaps.len(), first, last
+            );
+            if !self.allow_id_gaps {
+                return Err(RuntimeError::build_error(BuildCode::NeuronIdGap, message));
+            }
+            warnings.push(BuildWarning { code: BuildCode::NeuronIdGap, message });
+        }
+
+        let mut pairs = BTreeSet::new();
+        let mut below_dt = (0usize, f32::INFINITY);
+        for &(pre, post, _, delay) in &self.synapses {
+            for id in [pre, post] {
+                if !ids.contains(&id.raw()) {
+                    return Err(RuntimeError::build_error(
+                        BuildCode::UnknownNeuron,
+                        format!("synapse {} -> {} references missing neuron {}", pre.raw(), post.raw(), id.raw()),
+                    ));
+                }
+            }
+            if let Err(e) = Quantity::ms(delay as f64).round_ns() {
+                return Err(RuntimeError::build_error(
+                    BuildCode::InvalidDelay,
+                    format!("synapse {} -> {} has delay {} ms: {}", pre.raw(), post.raw(), delay, e),
+                ));
+            }
+            if !pairs.insert((pre, post)) && self.duplicate_synapses == DuplicateSynapses::Reject {
+                return Err(RuntimeError::build_error(
+                    BuildCode::DuplicateSynapse,
+                    format!("synapse {} -> {} is added more than once", pre.raw(), post.raw()),
+                ));
+            }
+            if self.dt_ms.is_some_and(|dt| delay < dt) {
+                below_dt = (below_dt.0 + 1, below_dt.1.min(delay));
+            }
+        }
+        if let (count @ 1.., shortest) = below_dt {
+            let outcome = match self.config.sub_dt_delay {
+                SubDtDelay::NextStep => "they are delivered on the next step",
+                SubDtDelay::Interpolate => "their input is split across two steps by arrival time",
+                SubDtDelay::Reject => "sub-dt delays are rejected",
+            };
+            let message = format!(
+                "{} synapse(s) have delays below dt = {} ms (shortest {} ms); {}",
+                count, self.dt_ms.unwrap_or_default(), shortest, outcome
+            );
+            if self.config.sub_dt_delay == SubDtDelay::Reject {
+                return Err(RuntimeError::build_error(BuildCode::DelayBelowDt, message));
+            }
+            warnings.push(BuildWarning { code: BuildCode::DelayBelowDt, message });
+        }
+        Ok(warnings)
+    }
+
+    /// Build the network, logging validation warnings
     pub fn build(self) -> Result<SNNNetwork> {
+        let (network, warnings) = self.build_with_warnings()?;
+        for warning in warnings {
+            log::warn!("[{}] {}", warning.code, warning.message);
+        }
+        Ok(network)
+    }
+
+    /// Build the network and return the validation warnings
+    pub fn build_with_warnings(self) -> Result<(SNNNetwork, Vec<BuildWarning>)> {
+        let warnings = self.validate()?;
+        let synapses = merge_duplicate_synapses(self.synapses, self.duplicate_synapses);
         let mut network = SNNNetwork::new(self.config)?;
 
         // Add neurons
-        for (id, params_opt) in self.neurons {
-            if let Some(params) = params_opt {
-                network.add_neuron_with_params(id, params)?;
-            } else {
-                network.add_neuron(id)?;
+        for (id, model_opt) in self.neurons {
+            let population = self.populations.iter().rev()
+                .find(|(start, end, _)| (*start..*end).contains(&id.raw()))
+                .map(|(_, _, model)| model.clone());
+            match model_opt.or(population) {
+                Some(model) => network.add_neuron_with_model(id, model)?,
+                None => network.add_neuron(id)?,
+            }
+        }
+
+        // Per-population sub-stepping
+        for (start, end, substeps) in self.population_substeps {
+            let ids: Vec<_> = network.neurons.keys()
+                .filter(|id| (start..end).contains(&id.raw()))
+                .copied()
+                .collect();
+            for id in ids {
+                network.set_substeps(id, substeps)?;
             }
         }
 
         // Add synapses
-        for (pre, post, weight, delay) in self.synapses {
+        for (pre, post, weight, delay) in synapses {
             network.add_synapse(pre, post, weight, delay)?;
         }
 
-        Ok(network)
+        Ok((network, warnings))
     }
 }
 
+/// Apply a duplicate policy, keeping the order of first occurrence
+fn merge_duplicate_synapses(
+    synapses: Vec<(NeuronId, NeuronId, f32, f32)>,
+    policy: DuplicateSynapses,
+) -> Vec<(NeuronId, NeuronId, f32, f32)> {
+    let mut merged: Vec<(NeuronId, NeuronId, f32, f32)> = Vec::with_capacity(synapses.len());
+    let mut index: HashMap<(NeuronId, NeuronId), usize> = HashMap::new();
+    for synapse in synapses {
+        let (pre, post, weight, _) = synapse;
+        match index.get(&(pre, post)) {
+            None => {
+                index.insert((pre, post), merged.len());
+                merged.push(synapse);
+            }
+            Some(&i) => match policy {
+                // Rejected by validate before merging
+                DuplicateSynapses::Reject | DuplicateSynapses::KeepFirst => {}
+                DuplicateSynapses::KeepLast => merged[i] = synapse,
+                DuplicateSynapses::SumWeights => merged[i].2 += weight,
+            },
+        }
+    }
+    merged
+}
+
 impl Default for NetworkBuilder {
     fn default() -> Self {
         Self::new()
@@ -504,6 +1014,7 @@ impl Default for NetworkBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modulation::Interpolation;
 
     #[test]
     fn test_network_creation() {
@@ -545,6 +1056,38 @@ mod tests {
         assert_eq!(network.get_weight(id1, id2).unwrap(), 0.5);
     }
 
+    #[test]
+    fn test_unrepresentable_times_are_rejected() {
+        let mut network = SNNNetwork::new(NetworkConfig::default()).unwrap();
+        let (id1, id2) = (NeuronId::new(0), NeuronId::new(1));
+        network.add_neuron(id1).unwrap();
+        network.add_neuron(id2).unwrap();
+
+        assert!(network.add_synapse(id1, id2, 0.5, f32::NAN).is_err());
+        assert!(network.add_synapse(id1, id2, 0.5, 1e30).is_err());
+        assert!(network.step(f32::NAN).is_err());
+        assert!(network.step(-0.1).is_err());
+        // Sub-nanosecond steps would silently stall the clock
+        assert!(network.step(1e-7).is_err());
+        assert!(network.step(0.1).is_ok());
+    }
+
+    #[test]
+    fn test_to_vcsr_snapshot() {
+        let network = NetworkBuilder::new()
+            .add_neurons(0, 3)
+            .add_synapse_simple(NeuronId::new(1), NeuronId::new(2), 0.3)
+            .add_synapse_simple(NeuronId::new(0), NeuronId::new(1), 0.5)
+            .build()
+            .unwrap();
+
+        let snapshot = network.to_vcsr_snapshot(GenerationId::new(1)).unwrap();
+        assert_eq!(snapshot.header.num_vertices, 3);
+        assert_eq!(snapshot.header.num_incidences, 2);
+        let neighbors: Vec<_> = snapshot.neighbors(NeuronId::new(0)).collect();
+        assert_eq!(neighbors, vec![(NeuronId::new(1), 0.5)]);
+    }
+
     #[test]
     fn test_network_builder() {
         let network = NetworkBuilder::new()
@@ -558,6 +1101,67 @@ mod tests {
         assert_eq!(network.synapse_count(), 2);
     }
 
+    #[test]
+    fn test_builder_validation_codes() {
+        let code = |builder: NetworkBuilder| builder.build().unwrap_err().build_code();
+        let (n0, n1) = (NeuronId::new(0), NeuronId::new(1));
+
+        assert_eq!(code(NetworkBuilder::new()), Some(BuildCode::NoNeurons));
+        assert_eq!(code(NetworkBuilder::new().add_neurons(0, 2).add_neuron(n1)), Some(BuildCode::DuplicateNeuron));
+        assert_eq!(
+            code(NetworkBuilder::new().add_neurons(0, 2).add_synapse_simple(n0, NeuronId::new(5), 1.0)),
+            Some(BuildCode::UnknownNeuron)
+        );
+        assert_eq!(
+            code(NetworkBuilder::new().add_neurons(0, 2).add_synapse(n0, n1, 1.0, f32::NAN)),
+            Some(BuildCode::InvalidDelay)
+        );
+        let duplicated = || NetworkBuilder::new()
+            .add_neurons(0, 2)
+            .add_synapse(n0, n1, 0.5, 1.0)
+            .add_synapse(n0, n1, 0.25, 2.0);
+        assert_eq!(code(duplicated()), Some(BuildCode::DuplicateSynapse));
+        let gapped = || NetworkBuilder::new().add_neurons(0, 2).add_neurons(10, 2);
+        assert_eq!(code(gapped().allow_id_gaps(false)), Some(BuildCode::NeuronIdGap));
+
+        let err = duplicated().build().unwrap_err();
+        assert!(err.to_string().starts_with("[B004] synapse 0 -> 1"));
+
+        // Policies resolve duplicates instead of failing
+        let weight = |policy| duplicated().duplicate_synapses(policy).build().unwrap().get_weight(n0, n1).unwrap();
+        assert_eq!(weight(DuplicateSynapses::KeepFirst), 0.5);
+        assert_eq!(weight(DuplicateSynapses::KeepLast), 0.25);
+        assert_eq!(weight(DuplicateSynapses::SumWeights), 0.75);
+
+        // Gaps and sub-dt delays are warnings by default
+        let (network, warnings) = gapped()
+            .add_synapse(n0, NeuronId::new(10), 1.0, 0.05)
+            .with_dt(0.1)
+            .build_with_warnings()
+            .unwrap();
+        assert_eq!(network.neuron_count(), 4);
+        let codes: Vec<_> = warnings.iter().map(|w| w.code).collect();
+        assert_eq!(codes, vec![BuildCode::NeuronIdGap, BuildCode::DelayBelowDt]);
+        assert!(warnings[0].message.contains("first 2..=9"));
+    }
+
+    #[test]
+    fn test_bulk_builder() {
+        let edges = (0..4u32).flat_map(|pre| (4..8u32).map(move |post| (pre, post)));
+        let builder = NetworkBuilder::new()
+            .add_neuron_range(0..=7)
+            .add_neuron_range(9..=8)
+            .add_synapses_from_iter(edges.map(|(pre, post)| {
+                (NeuronId::new(pre), NeuronId::new(post), 0.5, 1.0)
+            }));
+        assert_eq!(builder.neuron_ids().len(), 8);
+        assert_eq!(builder.synapses()[1], (NeuronId::new(0), NeuronId::new(5), 0.5, 1.0));
+
+        let network = builder.build().unwrap();
+        assert_eq!(network.neuron_count(), 8);
+        assert_eq!(network.synapse_count(), 16);
+    }
+
     #[test]
     fn test_fully_connected_builder() {
         let network = NetworkBuilder::new()
@@ -619,4 +1223,251 @@ mod tests {
         assert_eq!(network.current_time().nanos(), 0);
         assert_eq!(network.get_membrane_potential(id).unwrap(), -70.0); // Should be reset potential
     }
-}
\ No newline at end of file
+
+    fn drive(network: &mut SNNNetwork, ids: &[NeuronId], steps: usize) -> Vec<Spike> {
+        let mut spikes = Vec::new();
+        for _ in 0..steps {
+            for &id in ids {
+                network.apply_input(id, 100.0).unwrap();
+            }
+            spikes.extend(network.step(1.0).unwrap());
+        }
+        spikes
+    }
+
+    #[test]
+    fn test_activity_mask() {
+        let (a, b) = (NeuronId::new(0), NeuronId::new(1));
+        let mut network = NetworkBuilder::new().add_neurons(0, 2).build().unwrap();
+        network.apply_mask(MaskMode::Activity, NeuronMask::from_ids(2, [a]).unwrap());
+
+        let spikes = drive(&mut network, &[a, b], 50);
+        assert!(spikes.iter().any(|s| s.neuron_id == a));
+        assert!(spikes.iter().all(|s| s.neuron_id != b));
+
+        network.clear_mask(MaskMode::Activity);
+        let spikes = drive(&mut network, &[b], 50);
+        assert!(spikes.iter().any(|s| s.neuron_id == b));
+    }
+
+    #[test]
+    fn test_plasticity_mask() {
+        let (a, b) = (NeuronId::new(0), NeuronId::new(1));
+        let build = || {
+            NetworkBuilder::new()
+                .add_neurons(0, 2)
+                .add_synapse(a, b, 0.5, 1.0)
+                .build()
+                .unwrap()
+        };
+
+        let mut free = build();
+        drive(&mut free, &[a, b], 50);
+        assert_ne!(free.get_weight(a, b).unwrap(), 0.5);
+
+        let mut gated = build();
+        gated.apply_mask(MaskMode::Plasticity, NeuronMask::from_ids(2, [a]).unwrap());
+        drive(&mut gated, &[a, b], 50);
+        assert_eq!(gated.get_weight(a, b).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_modulated_plasticity() {
+        let (a, b) = (NeuronId::new(0), NeuronId::new(1));
+        let run = |modulator: Option<Modulator>| {
+            let mut network = NetworkBuilder::new()
+                .add_neurons(0, 2)
+                .add_synapse(a, b, 0.5, 1.0)
+                .build()
+                .unwrap();
+            if let Some(m) = modulator {
+                network.set_modulator(m);
+            }
+            drive(&mut network, &[a, b], 50);
+            network.get_weight(a, b).unwrap() - 0.5
+        };
+
+        let plain = run(None);
+        let silenced = run(Some(Modulator::schedule(vec![(0, 0.0)], Interpolation::Step).unwrap()));
+        let reversed = run(Some(Modulator::schedule(vec![(0, -1.0)], Interpolation::Step).unwrap()));
+        assert_ne!(plain, 0.0);
+        assert_eq!(silenced, 0.0);
+        assert!(plain * reversed < 0.0, "plain {} reversed {}", plain, reversed);
+    }
+
+    #[test]
+    fn test_population_models() {
+        let adex = NeuronModel::AdEx(crate::neuron::AdExParams::default());
+        let network = NetworkBuilder::new()
+            .add_neurons(0, 4)
+            .add_neuron_with_params(NeuronId::new(4), LIFParams::default())
+            .with_population_model(2, 5, adex.clone())
+            .build()
+            .unwrap();
+
+        assert!(matches!(network.neurons[&NeuronId::new(1)], Neuron::Lif(_)));
+        assert!(matches!(network.neurons[&NeuronId::new(2)], Neuron::AdEx(_)));
+        assert!(matches!(network.neurons[&NeuronId::new(3)], Neuron::AdEx(_)));
+        // Explicit parameters win over the population model
+        assert!(matches!(network.neurons[&NeuronId::new(4)], Neuron::Lif(_)));
+        assert_eq!(network.get_membrane_potential(NeuronId::new(3)).unwrap(), -70.6);
+
+        let mut network = network;
+        network.reset().unwrap();
+        assert_eq!(network.neurons[&NeuronId::new(2)].model(), adex);
+    }
+
+    #[test]
+    fn test_population_substeps() {
+        // tau_m = 2 ms against a 1 ms global step: plain Euler overshoots
+        let params = LIFParams { tau_m: 2.0, v_thresh: 0.0, t_refrac: 0.0, ..LIFParams::default() };
+        let mut network = NetworkBuilder::new()
+            .add_neuron_with_params(NeuronId::new(0), params.clone())
+            .add_neuron_with_params(NeuronId::new(1), params.clone())
+            .with_population_substeps(1, 2, 10)
+            .build()
+            .unwrap();
+        assert_eq!(network.substeps(NeuronId::new(0)), 1);
+        assert_eq!(network.substeps(NeuronId::new(1)), 10);
+
+        for _ in 0..2 {
+            network.apply_input(NeuronId::new(0), 1.0).unwrap();
+            network.apply_input(NeuronId::new(1), 1.0).unwrap();
+            network.step(1.0).unwrap();
+        }
+        // V(t) = v_rest + R*I (1 - e^(-t/tau)) at t = 2 ms
+        let exact = params.v_rest + params.r_m * (1.0 - (-1.0f32).exp());
+        let coarse = network.get_membrane_potential(NeuronId::new(0)).unwrap();
+        let fine = network.get_membrane_potential(NeuronId::new(1)).unwrap();
+        assert!((fine - exact).abs() < 0.2, "fine {} exact {}", fine, exact);
+        assert!((coarse - exact).abs() > 1.0, "coarse {} exact {}", coarse, exact);
+
+        assert!(network.set_substeps(NeuronId::new(0), 0).is_err());
+        assert!(network.set_substeps(NeuronId::new(9), 2).is_err());
+    }
+
+    #[test]
+    fn test_substep_spikes_on_global_grid() {
+        let mut network = NetworkBuilder::new()
+            .add_neurons(0, 1)
+            .with_population_substeps(0, 1, 7)
+            .build()
+            .unwrap();
+        let mut spikes = Vec::new();
+        for _ in 0..50 {
+            network.apply_input(NeuronId::new(0), 5.0).unwrap();
+            spikes.extend(network.step(0.5).unwrap());
+        }
+        assert!(!spikes.is_empty());
+        assert!(spikes.iter().all(|s| s.time.nanos() % 500_000 == 0));
+    }
+
+    #[test]
+    fn test_connectivity_backends_agree() {
+        let run = |backend: ConnectivityBackend| {
+            let config = NetworkConfig { connectivity: backend, plasticity_enabled: false, ..Default::default() };
+            let mut network = NetworkBuilder::new()
+                .with_config(config)
+                .add_neurons(0, 6)
+                .fully_connected(500.0)
+                .build()
+                .unwrap();
+            let mut spikes = Vec::new();
+            for step in 0..60 {
+                if step == 30 {
+                    // Topology changes after routing started are picked up
+                    network.add_neuron(NeuronId::new(9)).unwrap();
+                    network.add_synapse(NeuronId::new(0), NeuronId::new(9), 500.0, 0.5).unwrap();
+                }
+                network.apply_input(NeuronId::new(0), 1000.0).unwrap();
+                spikes.extend(network.step(0.1).unwrap().into_iter().map(|s| (s.neuron_id.raw(), s.time.nanos())));
+            }
+            (network.connectivity_backend(), spikes)
+        };
+
+        let (resolved, reference) = run(ConnectivityBackend::Graph);
+        assert_eq!(resolved, ConnectivityBackend::Graph);
+        assert!(reference.iter().any(|&(id, _)| id == 9), "added synapse must route");
+        assert!(reference.iter().any(|&(id, _)| id != 0 && id != 9));
+        for backend in [ConnectivityBackend::Matrix, ConnectivityBackend::Sparse, ConnectivityBackend::Hypergraph] {
+            assert_eq!(run(backend), (backend, reference.clone()));
+        }
+        // 30 of 36 pairs connected (31 of 49 after the addition) is dense
+        assert_eq!(run(ConnectivityBackend::Auto), (ConnectivityBackend::Matrix, reference));
+    }
+
+    #[test]
+    fn test_heterogeneous_delays() {
+        // One source fanning out with delays 0.3 / 1.0 / 2.5 ms
+        let mut network = NetworkBuilder::new()
+            .add_neurons(0, 4)
+            .add_synapse(NeuronId::new(0), NeuronId::new(1), 30.0, 0.3)
+            .add_synapse(NeuronId::new(0), NeuronId::new(2), 30.0, 1.0)
+            .add_synapse(NeuronId::new(0), NeuronId::new(3), 30.0, 2.5)
+            .build()
+            .unwrap();
+        assert_eq!(network.get_delay(NeuronId::new(0), NeuronId::new(3)).unwrap(), 2.5);
+
+        network.apply_input(NeuronId::new(0), 1000.0).unwrap();
+        let source = network.step(0.1).unwrap();
+        assert_eq!(source.len(), 1);
+        let sent = source[0].time.nanos();
+
+        // Record the step at which each target first depolarises
+        let mut arrival = [None; 4];
+        for step in 1..=40u64 {
+            network.step(0.1).unwrap();
+            for (id, slot) in arrival.iter_mut().enumerate().skip(1) {
+                if slot.is_none() && network.get_membrane_potential(NeuronId::new(id as u32)).unwrap() > -70.0 {
+                    *slot = Some(sent + step * 100_000);
+                }
+            }
+        }
+        let delay_ns = |id: usize| arrival[id].unwrap() - sent;
+        assert!(delay_ns(1) >= 300_000 && delay_ns(1) < 400_000);
+        assert!(delay_ns(2) >= 1_000_000 && delay_ns(2) < 1_100_000);
+        assert!(delay_ns(3) >= 2_500_000 && delay_ns(3) < 2_600_000);
+    }
+
+    #[test]
+    fn test_sub_dt_delay_modes() {
+        // 0.1 ms and 0.9 ms delays at dt = 1 ms
+        let build = |mode: SubDtDelay| {
+            let config = NetworkConfig { sub_dt_delay: mode, plasticity_enabled: false, ..NetworkConfig::default() };
+            NetworkBuilder::new()
+                .with_config(config)
+                .with_dt(1.0)
+                .add_neurons(0, 3)
+                .add_synapse(NeuronId::new(0), NeuronId::new(1), 5.0, 0.1)
+                .add_synapse(NeuronId::new(0), NeuronId::new(2), 5.0, 0.9)
+                .build_with_warnings()
+        };
+        let potentials = |mode: SubDtDelay| {
+            let (mut network, warnings) = build(mode).unwrap();
+            assert_eq!(warnings[0].code, BuildCode::DelayBelowDt);
+            network.inject_spike(&Spike::new(NeuronId::new(0), Time::from_nanos(0))).unwrap();
+            let v = |network: &SNNNetwork, id| network.get_membrane_potential(NeuronId::new(id)).unwrap();
+            network.step(1.0).unwrap();
+            let first = (v(&network, 1), v(&network, 2));
+            network.step(1.0).unwrap();
+            (first, (v(&network, 1), v(&network, 2)))
+        };
+
+        // Whole input on the first step regardless of the delay
+        let (first, _) = potentials(SubDtDelay::NextStep);
+        assert_eq!(first.0, first.1);
+        assert!(first.0 > -70.0);
+
+        // 90% vs 10% on the first step, the rest on the second
+        let (first, second) = potentials(SubDtDelay::Interpolate);
+        assert!(first.0 > first.1 && first.1 > -70.0, "{:?}", first);
+        assert!(second.1 > first.1);
+        assert!((SubDtDelay::Interpolate.arrival_fraction(100_000, 1_000_000, 1_000_000) - 0.9).abs() < 1e-6);
+        assert_eq!(SubDtDelay::NextStep.arrival_fraction(100_000, 1_000_000, 1_000_000), 1.0);
+
+        let err = build(SubDtDelay::Reject).unwrap_err();
+        assert!(err.to_string().contains("[B101]"), "{}", err);
+        assert_eq!("interpolate".parse::<SubDtDelay>().unwrap(), SubDtDelay::Interpolate);
+        assert!("truncate".parse::<SubDtDelay>().is_err());
+    }
+}
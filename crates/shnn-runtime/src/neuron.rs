@@ -1,6 +1,8 @@
 //! Neuron models for SNN simulation
 
-use crate::{error::*, NeuronId, Time, Spike};
+use crate::{error::*, integrator::{ForwardEuler, Integrator, IntegratorKind, SpikeTiming}, NeuronId, Time, Spike};
+use shnn_types::units::{self, Quantity};
+use std::sync::Arc;
 
 /// Parameters for Leaky Integrate-and-Fire neurons
 #[derive(Debug, Clone, PartialEq)]
@@ -19,6 +21,51 @@ pub struct LIFParams {
     pub r_m: f32,
     /// Capacitance (nF)
     pub c_m: f32,
+    /// Behaviour after a spike
+    pub refractory: Refractoriness,
+}
+
+/// What a LIF neuron does after a spike
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Refractoriness {
+    /// Hold V at `v_reset` and discard input for `t_refrac`
+    #[default]
+    HoldAtReset,
+    /// Discard input for `t_refrac` while V relaxes toward `v_rest`
+    IgnoreInput,
+    /// Hold at reset for `t_refrac`, then face a threshold raised by `jump`
+    /// mV per spike that decays with time constant `tau` (ms); increments
+    /// from successive spikes add up
+    Relative {
+        /// Threshold increase per spike (mV)
+        jump: f32,
+        /// Decay time constant of the increase (ms)
+        tau: f32,
+    },
+}
+
+impl Refractoriness {
+    /// Canonical name used in NIR attributes
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Refractoriness::HoldAtReset => "hold",
+            Refractoriness::IgnoreInput => "ignore_input",
+            Refractoriness::Relative { .. } => "relative",
+        }
+    }
+
+    /// Check the relative-refractoriness constants
+    pub fn validate(&self) -> Result<()> {
+        if let Refractoriness::Relative { jump, tau } = *self {
+            if !(jump >= 0.0 && jump.is_finite()) {
+                return Err(RuntimeError::invalid_parameter("refrac_jump", jump.to_string(), ">= 0.0"));
+            }
+            if !(tau > 0.0 && tau.is_finite()) {
+                return Err(RuntimeError::invalid_parameter("tau_refrac", tau.to_string(), "> 0.0"));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for LIFParams {
@@ -31,6 +78,7 @@ impl Default for LIFParams {
             t_refrac: 2.0,    // 2ms refractory period
             r_m: 10.0,        // 10MΩ resistance
             c_m: 1.0,         // 1nF capacitance
+            refractory: Refractoriness::default(),
         }
     }
 }
@@ -90,6 +138,7 @@ impl LIFParams {
             t_refrac,
             r_m,
             c_m,
+            refractory: Refractoriness::default(),
         })
     }
 
@@ -104,7 +153,14 @@ impl LIFParams {
             self.r_m,
             self.c_m,
         )?;
-        Ok(())
+        self.refractory.validate()
+    }
+
+    /// Adaptive-threshold LIF (ALIF): the threshold rises by `beta` mV per
+    /// spike and decays back with time constant `tau_adapt` (ms)
+    pub fn with_threshold_adaptation(mut self, beta: f32, tau_adapt: f32) -> Self {
+        self.refractory = Refractoriness::Relative { jump: beta, tau: tau_adapt };
+        self
     }
 }
 
@@ -136,7 +192,7 @@ impl NeuronState {
     pub fn is_refractory(&self, current_time_ns: u64, t_refrac_ms: f32) -> bool {
         if let Some(last_spike) = self.last_spike_time {
             let dt_ns = current_time_ns.saturating_sub(last_spike);
-            let dt_ms = dt_ns as f32 / 1_000_000.0;
+            let dt_ms = units::ns_to_ms(dt_ns);
             dt_ms < t_refrac_ms
         } else {
             false
@@ -163,6 +219,8 @@ pub struct LIFNeuron {
     pub params: LIFParams,
     /// Current state
     pub state: NeuronState,
+    /// Threshold elevation from [`Refractoriness::Relative`] (mV)
+    pub theta: f32,
 }
 
 impl LIFNeuron {
@@ -170,27 +228,66 @@ impl LIFNeuron {
     pub fn new(id: NeuronId, params: LIFParams) -> Result<Self> {
         params.validate()?;
         let state = NeuronState::new(id, params.v_rest);
-        Ok(Self { params, state })
+        Ok(Self { params, state, theta: 0.0 })
     }
 
-    /// Update neuron for one time step
+    /// Effective threshold, including any relative-refractory elevation (mV)
+    pub fn threshold(&self) -> f32 {
+        self.params.v_thresh + self.theta
+    }
+
+    /// Update neuron for one time step (forward Euler)
     pub fn update(&mut self, dt_ms: f32, current_time_ns: u64) -> Result<Option<Spike>> {
-        // Check refractory period
+        self.update_with(&ForwardEuler, dt_ms, current_time_ns)
+    }
+
+    /// Update neuron for one time step with the given integrator
+    pub fn update_with<I: Integrator>(&mut self, integrator: &I, dt_ms: f32, current_time_ns: u64) -> Result<Option<Spike>> {
+        self.update_timed(integrator, SpikeTiming::Grid, dt_ms, current_time_ns)
+    }
+
+    /// Update for one time step, placing a spike inside the step by `timing`
+    ///
+    /// The spike and the refractory period start at the interpolated time.
+    pub fn update_timed<I: Integrator>(
+        &mut self,
+        integrator: &I,
+        timing: SpikeTiming,
+        dt_ms: f32,
+        current_time_ns: u64,
+    ) -> Result<Option<Spike>> {
+        if let Refractoriness::Relative { tau, .. } = self.params.refractory {
+            self.theta *= (-dt_ms / tau).exp();
+        }
+
+        // Input arriving during the refractory period is discarded, as in AdEx
         if self.state.is_refractory(current_time_ns, self.params.t_refrac) {
+            self.state.i_input = 0.0;
+            if self.params.refractory == Refractoriness::IgnoreInput {
+                let p = &self.params;
+                let [v_m] = integrator.step([self.state.v_m], dt_ms, |&[v]| [(p.v_rest - v) / p.tau_m]);
+                self.state.v_m = v_m;
+            }
             return Ok(None);
         }
 
-        // Update membrane potential using Euler integration
         // dV/dt = (v_rest - v_m + R*I) / tau_m
-        let dv_dt = (self.params.v_rest - self.state.v_m + self.params.r_m * self.state.i_input) 
-                    / self.params.tau_m;
-        
-        self.state.v_m += dv_dt * dt_ms;
+        let p = &self.params;
+        let v_0 = self.state.v_m;
+        let drive = p.v_rest + p.r_m * self.state.i_input;
+        let [v_m] = integrator.step([v_0], dt_ms, |&[v]| [(drive - v) / p.tau_m]);
+        self.state.v_m = v_m;
 
         // Check for spike
-        if self.state.v_m >= self.params.v_thresh {
-            let spike = Spike::new(self.state.id, Time::from_nanos(current_time_ns));
-            self.state.reset(self.params.v_reset, current_time_ns);
+        if self.state.v_m >= self.threshold() {
+            let fraction = self.crossing_fraction(timing, v_0, drive, dt_ms);
+            if let Refractoriness::Relative { jump, .. } = self.params.refractory {
+                self.theta += jump;
+            }
+            let early_ns = ((1.0 - fraction as f64) * dt_ms as f64 * 1e6).round() as u64;
+            let spike_time_ns = current_time_ns.saturating_sub(early_ns);
+            let spike = Spike::new(self.state.id, Time::from_nanos(spike_time_ns));
+            self.state.reset(self.params.v_reset, spike_time_ns);
             Ok(Some(spike))
         } else {
             // Decay input current
@@ -199,6 +296,28 @@ impl LIFNeuron {
         }
     }
 
+    /// Share of the step elapsed at the threshold crossing, in [0, 1]
+    ///
+    /// `v_0` is the potential at the step's start, `self.state.v_m` at its end
+    /// (before reset) and `drive` the steady state under the step's input.
+    fn crossing_fraction(&self, timing: SpikeTiming, v_0: f32, drive: f32, dt_ms: f32) -> f32 {
+        let (v_thresh, v_1) = (self.threshold(), self.state.v_m);
+        let linear = || match v_1 > v_0 {
+            true => (v_thresh - v_0) / (v_1 - v_0),
+            false => 1.0,
+        };
+        let fraction = match timing {
+            SpikeTiming::Grid => 1.0,
+            SpikeTiming::Linear => linear(),
+            // V(t) = drive + (v_0 - drive) e^(-t / tau_m) reaches threshold at tau_m ln((drive - v_0) / (drive - v_thresh))
+            SpikeTiming::Exponential if drive > v_thresh && v_0 < v_thresh => {
+                self.params.tau_m * ((drive - v_0) / (drive - v_thresh)).ln() / dt_ms
+            }
+            SpikeTiming::Exponential => linear(),
+        };
+        fraction.clamp(0.0, 1.0)
+    }
+
     /// Add synaptic input current
     pub fn receive_input(&mut self, current: f32) {
         self.state.add_current(current);
@@ -220,6 +339,736 @@ impl LIFNeuron {
     }
 }
 
+/// Parameters for adaptive exponential integrate-and-fire (AdEx) neurons
+///
+/// C dV/dt = -g_L (V - E_L) + g_L Δ_T exp((V - V_T) / Δ_T) - w + I
+/// τ_w dw/dt = a (V - E_L) - w, and on V >= V_peak: V <- V_reset, w <- w + b
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdExParams {
+    /// Capacitance (nF)
+    pub c_m: f32,
+    /// Leak conductance (nS)
+    pub g_l: f32,
+    /// Leak reversal potential (mV)
+    pub e_l: f32,
+    /// Threshold slope-factor potential (mV)
+    pub v_t: f32,
+    /// Slope factor (mV)
+    pub delta_t: f32,
+    /// Subthreshold adaptation conductance (nS)
+    pub a: f32,
+    /// Adaptation time constant (ms)
+    pub tau_w: f32,
+    /// Spike-triggered adaptation increment (nA)
+    pub b: f32,
+    /// Reset potential (mV)
+    pub v_reset: f32,
+    /// Spike detection potential (mV)
+    pub v_peak: f32,
+    /// Refractory period (ms)
+    pub t_refrac: f32,
+}
+
+impl Default for AdExParams {
+    fn default() -> Self {
+        // Brette & Gerstner (2005) regular-spiking parameters
+        Self {
+            c_m: 0.281,
+            g_l: 30.0,
+            e_l: -70.6,
+            v_t: -50.4,
+            delta_t: 2.0,
+            a: 4.0,
+            tau_w: 144.0,
+            b: 0.0805,
+            v_reset: -70.6,
+            v_peak: 20.0,
+            t_refrac: 0.0,
+        }
+    }
+}
+
+impl AdExParams {
+    /// Membrane time constant C / g_L (ms)
+    pub fn tau_m(&self) -> f32 {
+        // nF / nS = s; scale to ms
+        self.c_m / self.g_l * 1_000.0
+    }
+
+    /// Validate parameters (positivity and the bounds keeping the model well-posed)
+    pub fn validate(&self) -> Result<()> {
+        let positive = [
+            ("c_m", self.c_m),
+            ("g_l", self.g_l),
+            ("delta_t", self.delta_t),
+            ("tau_w", self.tau_w),
+        ];
+        for (name, value) in positive {
+            if value.is_nan() || value <= 0.0 {
+                return Err(RuntimeError::invalid_parameter(name, value.to_string(), "> 0.0"));
+            }
+        }
+        if self.t_refrac < 0.0 {
+            return Err(RuntimeError::invalid_parameter("t_refrac", self.t_refrac.to_string(), ">= 0.0"));
+        }
+        if self.v_peak <= self.v_t {
+            return Err(RuntimeError::invalid_parameter(
+                "v_peak",
+                format!("{} (with v_t={})", self.v_peak, self.v_t),
+                "> v_t",
+            ));
+        }
+        if self.v_reset >= self.v_peak {
+            return Err(RuntimeError::invalid_parameter(
+                "v_reset",
+                format!("{} (with v_peak={})", self.v_reset, self.v_peak),
+                "< v_peak",
+            ));
+        }
+        // a <= -g_L makes the subthreshold fixed point unstable
+        if self.a <= -self.g_l {
+            return Err(RuntimeError::invalid_parameter(
+                "a",
+                format!("{} (with g_l={})", self.a, self.g_l),
+                "> -g_l",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Largest forward-Euler step (ms) considered stable for these parameters
+    pub fn max_stable_dt(&self) -> f32 {
+        self.tau_m().min(self.tau_w)
+    }
+}
+
+/// Adaptive exponential integrate-and-fire neuron
+#[derive(Debug, Clone)]
+pub struct AdExNeuron {
+    /// Neuron parameters
+    pub params: AdExParams,
+    /// Current state
+    pub state: NeuronState,
+    /// Adaptation current (nA)
+    pub w: f32,
+}
+
+impl AdExNeuron {
+    /// Create a new AdEx neuron at rest
+    pub fn new(id: NeuronId, params: AdExParams) -> Result<Self> {
+        params.validate()?;
+        let state = NeuronState::new(id, params.e_l);
+        Ok(Self { params, state, w: 0.0 })
+    }
+
+    /// Update neuron for one time step (forward Euler)
+    pub fn update(&mut self, dt_ms: f32, current_time_ns: u64) -> Result<Option<Spike>> {
+        self.update_with(&ForwardEuler, dt_ms, current_time_ns)
+    }
+
+    /// Update neuron for one time step with the given integrator
+    pub fn update_with<I: Integrator>(&mut self, integrator: &I, dt_ms: f32, current_time_ns: u64) -> Result<Option<Spike>> {
+        let p = &self.params;
+        let i_input = self.state.i_input;
+        self.state.i_input = 0.0;
+        // Conductances in nS times mV give pA; scale to nA
+        let dw_dt = |v: f32, w: f32| (p.a * 1e-3 * (v - p.e_l) - w) / p.tau_w;
+
+        if self.state.is_refractory(current_time_ns, p.t_refrac) {
+            let v = p.v_reset;
+            let [w] = integrator.step([self.w], dt_ms, |&[w]| [dw_dt(v, w)]);
+            self.state.v_m = v;
+            self.w = w;
+            return Ok(None);
+        }
+
+        let [v, w] = integrator.step([self.state.v_m, self.w], dt_ms, |&[v, w]| {
+            // Cap the exponent so a single step past threshold cannot overflow
+            let exp_term = ((v - p.v_t) / p.delta_t).min(30.0).exp();
+            let i_ion = -p.g_l * 1e-3 * (v - p.e_l) + p.g_l * 1e-3 * p.delta_t * exp_term;
+            // nA / nF = mV/ms
+            [(i_ion - w + i_input) / p.c_m, dw_dt(v, w)]
+        });
+        self.state.v_m = v;
+        self.w = w;
+
+        if !self.state.v_m.is_finite() {
+            return Err(RuntimeError::NumericalError {
+                reason: format!("AdEx neuron {} diverged (dt too large?)", self.state.id.raw()),
+            });
+        }
+
+        if self.state.v_m >= p.v_peak {
+            let spike = Spike::new(self.state.id, Time::from_nanos(current_time_ns));
+            self.state.reset(p.v_reset, current_time_ns);
+            self.w += p.b;
+            Ok(Some(spike))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Add synaptic input current
+    pub fn receive_input(&mut self, current: f32) {
+        self.state.add_current(current);
+    }
+
+    /// Get current membrane potential
+    pub fn membrane_potential(&self) -> f32 {
+        self.state.v_m
+    }
+
+    /// Get neuron ID
+    pub fn id(&self) -> NeuronId {
+        self.state.id
+    }
+}
+
+/// Integration scheme used inside each Hodgkin–Huxley sub-step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HHMethod {
+    /// Classic fourth-order Runge–Kutta
+    Rk4,
+    /// Exponential (Rush–Larsen) update of gates and membrane potential
+    Exponential,
+}
+
+impl HHMethod {
+    /// Canonical name used in NIR attributes
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HHMethod::Rk4 => "rk4",
+            HHMethod::Exponential => "exponential",
+        }
+    }
+}
+
+impl core::str::FromStr for HHMethod {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "rk4" => Ok(HHMethod::Rk4),
+            "exponential" => Ok(HHMethod::Exponential),
+            other => Err(RuntimeError::invalid_parameter("method", other, "rk4 | exponential")),
+        }
+    }
+}
+
+/// Parameters for Hodgkin–Huxley neurons
+///
+/// Absolute units for a 10⁻³ cm² patch, so the textbook per-area values carry
+/// over unchanged (1 µF/cm² -> 1 nF, 120 mS/cm² -> 120 µS) and currents are nA.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HHParams {
+    /// Capacitance (nF)
+    pub c_m: f32,
+    /// Maximal sodium conductance (µS)
+    pub g_na: f32,
+    /// Maximal potassium conductance (µS)
+    pub g_k: f32,
+    /// Leak conductance (µS)
+    pub g_l: f32,
+    /// Sodium reversal potential (mV)
+    pub e_na: f32,
+    /// Potassium reversal potential (mV)
+    pub e_k: f32,
+    /// Leak reversal potential (mV)
+    pub e_l: f32,
+    /// Initial membrane potential (mV); gates start at their steady state
+    pub v_init: f32,
+    /// Upward crossing of this potential is reported as a spike (mV)
+    pub v_spike: f32,
+    /// Largest sub-step used within one simulation step (ms)
+    pub max_substep: f32,
+    /// Sub-step integration scheme
+    pub method: HHMethod,
+}
+
+impl Default for HHParams {
+    fn default() -> Self {
+        // Hodgkin & Huxley (1952), shifted so rest is near -65 mV
+        Self {
+            c_m: 1.0,
+            g_na: 120.0,
+            g_k: 36.0,
+            g_l: 0.3,
+            e_na: 50.0,
+            e_k: -77.0,
+            e_l: -54.387,
+            v_init: -65.0,
+            v_spike: 0.0,
+            max_substep: 0.01,
+            method: HHMethod::Rk4,
+        }
+    }
+}
+
+impl HHParams {
+    /// Validate parameters
+    pub fn validate(&self) -> Result<()> {
+        if self.c_m.is_nan() || self.c_m <= 0.0 {
+            return Err(RuntimeError::invalid_parameter("c_m", self.c_m.to_string(), "> 0.0"));
+        }
+        for (name, value) in [("g_na", self.g_na), ("g_k", self.g_k), ("g_l", self.g_l)] {
+            if value.is_nan() || value < 0.0 {
+                return Err(RuntimeError::invalid_parameter(name, value.to_string(), ">= 0.0"));
+            }
+        }
+        if self.max_substep.is_nan() || self.max_substep <= 0.0 {
+            return Err(RuntimeError::invalid_parameter("max_substep", self.max_substep.to_string(), "> 0.0"));
+        }
+        if self.e_na <= self.e_k {
+            return Err(RuntimeError::invalid_parameter(
+                "e_na",
+                format!("{} (with e_k={})", self.e_na, self.e_k),
+                "> e_k",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Opening/closing rates (1/ms) of the m, h and n gates at potential `v` (mV)
+fn hh_rates(v: f32) -> [(f32, f32); 3] {
+    // x / (exp(x / y) - 1) with its limit y at x = 0
+    fn vtrap(x: f32, y: f32) -> f32 {
+        if (x / y).abs() < 1e-6 {
+            y * (1.0 - x / y / 2.0)
+        } else {
+            x / ((x / y).exp() - 1.0)
+        }
+    }
+    [
+        (0.1 * vtrap(-(v + 40.0), 10.0), 4.0 * (-(v + 65.0) / 18.0).exp()),
+        (0.07 * (-(v + 65.0) / 20.0).exp(), 1.0 / (1.0 + (-(v + 35.0) / 10.0).exp())),
+        (0.01 * vtrap(-(v + 55.0), 10.0), 0.125 * (-(v + 65.0) / 80.0).exp()),
+    ]
+}
+
+/// Hodgkin–Huxley neuron integrated with adaptive sub-steps inside each `dt`
+#[derive(Debug, Clone)]
+pub struct HHNeuron {
+    /// Neuron parameters
+    pub params: HHParams,
+    /// Current state
+    pub state: NeuronState,
+    /// Sodium activation gate
+    pub m: f32,
+    /// Sodium inactivation gate
+    pub h: f32,
+    /// Potassium activation gate
+    pub n: f32,
+    above_spike: bool,
+}
+
+impl HHNeuron {
+    /// Largest membrane potential change allowed in one sub-step (mV)
+    const MAX_DV: f32 = 1.0;
+    /// Smallest sub-step as a fraction of `max_substep`
+    const MIN_SUBSTEP_FRACTION: f32 = 1.0 / 64.0;
+
+    /// Create a new HH neuron with gates at steady state for `v_init`
+    pub fn new(id: NeuronId, params: HHParams) -> Result<Self> {
+        params.validate()?;
+        let [m, h, n] = hh_rates(params.v_init).map(|(a, b)| a / (a + b));
+        let state = NeuronState::new(id, params.v_init);
+        let above_spike = params.v_init >= params.v_spike;
+        Ok(Self { params, state, m, h, n, above_spike })
+    }
+
+    /// Time derivatives of (V, m, h, n) for input current `i` (nA)
+    fn derivatives(&self, [v, m, h, n]: [f32; 4], i: f32) -> [f32; 4] {
+        let p = &self.params;
+        let i_ion = p.g_na * m * m * m * h * (v - p.e_na)
+            + p.g_k * n * n * n * n * (v - p.e_k)
+            + p.g_l * (v - p.e_l);
+        let [(am, bm), (ah, bh), (an, bn)] = hh_rates(v);
+        [
+            (i - i_ion) / p.c_m,
+            am * (1.0 - m) - bm * m,
+            ah * (1.0 - h) - bh * h,
+            an * (1.0 - n) - bn * n,
+        ]
+    }
+
+    fn rk4_step(&self, y: [f32; 4], k1: [f32; 4], i: f32, h: f32) -> [f32; 4] {
+        let add = |a: [f32; 4], k: [f32; 4], s: f32| [a[0] + k[0] * s, a[1] + k[1] * s, a[2] + k[2] * s, a[3] + k[3] * s];
+        let k2 = self.derivatives(add(y, k1, h / 2.0), i);
+        let k3 = self.derivatives(add(y, k2, h / 2.0), i);
+        let k4 = self.derivatives(add(y, k3, h), i);
+        core::array::from_fn(|j| y[j] + h / 6.0 * (k1[j] + 2.0 * k2[j] + 2.0 * k3[j] + k4[j]))
+    }
+
+    fn exponential_step(&self, [v, m, h, n]: [f32; 4], i: f32, dt: f32) -> [f32; 4] {
+        let p = &self.params;
+        let gate = |x: f32, (a, b): (f32, f32)| {
+            let x_inf = a / (a + b);
+            x_inf + (x - x_inf) * (-dt * (a + b)).exp()
+        };
+        let [rm, rh, rn] = hh_rates(v);
+        let g_na = p.g_na * m * m * m * h;
+        let g_k = p.g_k * n * n * n * n;
+        let g_tot = g_na + g_k + p.g_l;
+        // µS and nF: C / g is in ms
+        let v_inf = (g_na * p.e_na + g_k * p.e_k + p.g_l * p.e_l + i) / g_tot;
+        let v_new = v_inf + (v - v_inf) * (-dt * g_tot / p.c_m).exp();
+        [v_new, gate(m, rm), gate(h, rh), gate(n, rn)]
+    }
+
+    /// Advance the neuron by `dt_ms`, sub-stepping so no sub-step exceeds
+    /// `max_substep` or moves V by more than ~1 mV
+    pub fn update(&mut self, dt_ms: f32, current_time_ns: u64) -> Result<Option<Spike>> {
+        let i = self.state.i_input;
+        let max_h = self.params.max_substep;
+        let min_h = max_h * Self::MIN_SUBSTEP_FRACTION;
+        let mut y = [self.state.v_m, self.m, self.h, self.n];
+        let mut crossed = false;
+        let mut remaining = dt_ms;
+
+        while remaining > 0.0 {
+            let k1 = self.derivatives(y, i);
+            let mut h = max_h.min(remaining);
+            if k1[0].abs() * h > Self::MAX_DV {
+                h = (Self::MAX_DV / k1[0].abs()).max(min_h).min(h);
+            }
+            y = match self.params.method {
+                HHMethod::Rk4 => self.rk4_step(y, k1, i, h),
+                HHMethod::Exponential => self.exponential_step(y, i, h),
+            };
+            remaining -= h;
+
+            if y[0] >= self.params.v_spike {
+                crossed |= !self.above_spike;
+                self.above_spike = true;
+            } else {
+                self.above_spike = false;
+            }
+        }
+
+        if !y.iter().all(|x| x.is_finite()) {
+            return Err(RuntimeError::numerical_error(format!(
+                "HH neuron {} diverged (max_substep too large?)",
+                self.state.id.raw()
+            )));
+        }
+        [self.state.v_m, self.m, self.h, self.n] = y;
+        self.state.i_input = 0.0;
+
+        if crossed {
+            self.state.last_spike_time = Some(current_time_ns);
+            Ok(Some(Spike::new(self.state.id, Time::from_nanos(current_time_ns))))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Add synaptic input current
+    pub fn receive_input(&mut self, current: f32) {
+        self.state.add_current(current);
+    }
+
+    /// Get current membrane potential
+    pub fn membrane_potential(&self) -> f32 {
+        self.state.v_m
+    }
+}
+
+/// Neuron model selection with its parameters
+#[derive(Debug, Clone, PartialEq)]
+pub enum NeuronModel {
+    /// Leaky integrate-and-fire
+    Lif(LIFParams),
+    /// Adaptive exponential integrate-and-fire
+    AdEx(AdExParams),
+    /// Hodgkin–Huxley
+    HH(HHParams),
+    /// User-defined dynamics
+    Custom(CustomModel),
+}
+
+impl NeuronModel {
+    /// Validate the model parameters
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            NeuronModel::Lif(p) => p.validate(),
+            NeuronModel::AdEx(p) => p.validate(),
+            NeuronModel::HH(p) => p.validate(),
+            NeuronModel::Custom(m) => m.dynamics().validate(),
+        }
+    }
+}
+
+/// Dynamics of a user-defined neuron model, shared by every neuron using it
+///
+/// An implementation holds the model parameters; each neuron's state is a
+/// [`NeuronState`] plus the model variables started from [`NeuronDynamics::initial_vars`].
+pub trait NeuronDynamics: core::fmt::Debug + Send + Sync {
+    /// Model name used in messages
+    fn name(&self) -> &str;
+
+    /// Membrane potential of a neuron at rest (mV)
+    fn v_rest(&self) -> f32;
+
+    /// Model variables of a neuron at rest
+    fn initial_vars(&self) -> Vec<f32> {
+        Vec::new()
+    }
+
+    /// Check the parameters
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Advance one step of `dt_ms` driven by `state.i_input` (nA) and return
+    /// whether the neuron spiked; the engine then clears the input and records
+    /// the spike time, so refractoriness can be read from `state.last_spike_time`
+    fn step(&self, state: &mut NeuronState, vars: &mut [f32], dt_ms: f32, current_time_ns: u64) -> Result<bool>;
+}
+
+/// A [`NeuronDynamics`] implementation usable as a [`NeuronModel`]
+///
+/// Models compare equal when they share one implementation instance.
+#[derive(Debug, Clone)]
+pub struct CustomModel(Arc<dyn NeuronDynamics>);
+
+impl CustomModel {
+    /// Wrap `dynamics`
+    pub fn new(dynamics: impl NeuronDynamics + 'static) -> Self {
+        Self(Arc::new(dynamics))
+    }
+
+    /// The wrapped implementation
+    pub fn dynamics(&self) -> &dyn NeuronDynamics {
+        self.0.as_ref()
+    }
+}
+
+impl PartialEq for CustomModel {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Neuron driven by a [`CustomModel`]
+#[derive(Debug, Clone)]
+pub struct CustomNeuron {
+    /// Shared model
+    pub model: CustomModel,
+    /// Current state
+    pub state: NeuronState,
+    /// Model variables
+    pub vars: Vec<f32>,
+}
+
+impl CustomNeuron {
+    /// Create a neuron of `model` at rest
+    pub fn new(id: NeuronId, model: CustomModel) -> Result<Self> {
+        let dynamics = model.dynamics();
+        dynamics.validate()?;
+        let state = NeuronState::new(id, dynamics.v_rest());
+        let vars = dynamics.initial_vars();
+        Ok(Self { model, state, vars })
+    }
+
+    /// Update neuron for one time step
+    pub fn update(&mut self, dt_ms: f32, current_time_ns: u64) -> Result<Option<Spike>> {
+        let dynamics = self.model.dynamics();
+        let spiked = dynamics.step(&mut self.state, &mut self.vars, dt_ms, current_time_ns)?;
+        self.state.i_input = 0.0;
+        if !self.state.v_m.is_finite() {
+            return Err(RuntimeError::NumericalError {
+                reason: format!("{} neuron {} diverged", dynamics.name(), self.state.id.raw()),
+            });
+        }
+        match spiked {
+            true => {
+                self.state.last_spike_time = Some(current_time_ns);
+                Ok(Some(Spike::new(self.state.id, Time::from_nanos(current_time_ns))))
+            }
+            false => Ok(None),
+        }
+    }
+}
+
+/// A neuron instance of any supported model
+#[derive(Debug, Clone)]
+pub enum Neuron {
+    /// Leaky integrate-and-fire
+    Lif(LIFNeuron),
+    /// Adaptive exponential integrate-and-fire
+    AdEx(AdExNeuron),
+    /// Hodgkin–Huxley
+    HH(HHNeuron),
+    /// User-defined dynamics
+    Custom(CustomNeuron),
+}
+
+impl Neuron {
+    /// Create a neuron of the given model at rest
+    pub fn new(id: NeuronId, model: NeuronModel) -> Result<Self> {
+        Ok(match model {
+            NeuronModel::Lif(p) => Neuron::Lif(LIFNeuron::new(id, p)?),
+            NeuronModel::AdEx(p) => Neuron::AdEx(AdExNeuron::new(id, p)?),
+            NeuronModel::HH(p) => Neuron::HH(HHNeuron::new(id, p)?),
+            NeuronModel::Custom(m) => Neuron::Custom(CustomNeuron::new(id, m)?),
+        })
+    }
+
+    /// Model and parameters of this neuron
+    pub fn model(&self) -> NeuronModel {
+        match self {
+            Neuron::Lif(n) => NeuronModel::Lif(n.params.clone()),
+            Neuron::AdEx(n) => NeuronModel::AdEx(n.params.clone()),
+            Neuron::HH(n) => NeuronModel::HH(n.params.clone()),
+            Neuron::Custom(n) => NeuronModel::Custom(n.model.clone()),
+        }
+    }
+
+    /// Update neuron for one time step (forward Euler)
+    pub fn update(&mut self, dt_ms: f32, current_time_ns: u64) -> Result<Option<Spike>> {
+        self.update_with(IntegratorKind::ForwardEuler, dt_ms, current_time_ns)
+    }
+
+    /// Update neuron for one time step with the given integrator
+    ///
+    /// HH neurons always use their own sub-stepping scheme ([`HHParams::method`]),
+    /// and custom models integrate themselves.
+    pub fn update_with(&mut self, integrator: IntegratorKind, dt_ms: f32, current_time_ns: u64) -> Result<Option<Spike>> {
+        self.update_timed(integrator, SpikeTiming::Grid, dt_ms, current_time_ns)
+    }
+
+    /// [`Neuron::update_with`], placing LIF spikes inside the step by `timing`
+    /// (other models report spikes at the end of the step)
+    pub fn update_timed(
+        &mut self,
+        integrator: IntegratorKind,
+        timing: SpikeTiming,
+        dt_ms: f32,
+        current_time_ns: u64,
+    ) -> Result<Option<Spike>> {
+        match self {
+            Neuron::Lif(n) => n.update_timed(&integrator, timing, dt_ms, current_time_ns),
+            Neuron::AdEx(n) => n.update_with(&integrator, dt_ms, current_time_ns),
+            Neuron::HH(n) => n.update(dt_ms, current_time_ns),
+            Neuron::Custom(n) => n.update(dt_ms, current_time_ns),
+        }
+    }
+
+    /// Update neuron for one global step split into `substeps` equal sub-steps
+    ///
+    /// The input accumulated for the step is held across sub-steps. A spike in
+    /// any sub-step is reported at `current_time_ns` so spike exchange stays on
+    /// the global time grid, unless `timing` interpolates it (then the first
+    /// sub-step spike's time is kept); at most one spike is reported per global step.
+    pub fn update_substepped(
+        &mut self,
+        integrator: IntegratorKind,
+        timing: SpikeTiming,
+        dt_ms: f32,
+        current_time_ns: u64,
+        substeps: u32,
+    ) -> Result<Option<Spike>> {
+        if substeps <= 1 {
+            return self.update_timed(integrator, timing, dt_ms, current_time_ns);
+        }
+        let input = self.state().i_input;
+        let h_ms = dt_ms / substeps as f32;
+        let dt_ns = Quantity::ms(dt_ms as f64)
+            .round_ns()
+            .map_err(|e| RuntimeError::invalid_time("dt_ms", dt_ms, e))?;
+        let h_ns = dt_ns / u64::from(substeps);
+        let mut first_spike: Option<Time> = None;
+        for k in 0..substeps {
+            self.state_mut().i_input = input;
+            let t_k = current_time_ns.saturating_sub(u64::from(substeps - 1 - k) * h_ns);
+            if let Some(spike) = self.update_timed(integrator, timing, h_ms, t_k)? {
+                first_spike.get_or_insert(spike.time);
+            }
+        }
+        self.state_mut().i_input = 0.0;
+        let report = |time: Time| match timing {
+            SpikeTiming::Grid => Time::from_nanos(current_time_ns),
+            _ => time,
+        };
+        Ok(first_spike.map(|time| Spike::new(self.id(), report(time))))
+    }
+
+    /// Add synaptic input current
+    pub fn receive_input(&mut self, current: f32) {
+        self.state_mut().add_current(current);
+    }
+
+    /// Get current membrane potential
+    pub fn membrane_potential(&self) -> f32 {
+        self.state().v_m
+    }
+
+    /// Get neuron ID
+    pub fn id(&self) -> NeuronId {
+        self.state().id
+    }
+
+    /// Shared state (potential, input, last spike)
+    pub fn state(&self) -> &NeuronState {
+        match self {
+            Neuron::Lif(n) => &n.state,
+            Neuron::AdEx(n) => &n.state,
+            Neuron::HH(n) => &n.state,
+            Neuron::Custom(n) => &n.state,
+        }
+    }
+
+    /// Model variables besides the membrane potential: AdEx `[w]`, HH `[m, h, n]`,
+    /// LIF `[theta]` under [`Refractoriness::Relative`]
+    pub fn model_vars(&self) -> Vec<f32> {
+        match self {
+            Neuron::Lif(n) => match n.params.refractory {
+                Refractoriness::Relative { .. } => vec![n.theta],
+                _ => Vec::new(),
+            },
+            Neuron::AdEx(n) => vec![n.w],
+            Neuron::HH(n) => vec![n.m, n.h, n.n],
+            Neuron::Custom(n) => n.vars.clone(),
+        }
+    }
+
+    /// Set the membrane potential and [`Neuron::model_vars`], clearing input and spike history
+    pub fn restore(&mut self, v_m: f32, vars: &[f32]) -> Result<()> {
+        let expected = self.model_vars().len();
+        if vars.len() != expected {
+            return Err(RuntimeError::invalid_parameter(
+                "state",
+                format!("{} model variables for neuron {}", vars.len(), self.id().raw()),
+                format!("{} for this model", expected),
+            ));
+        }
+        match self {
+            Neuron::Lif(n) => n.theta = vars.first().copied().unwrap_or(0.0),
+            Neuron::AdEx(n) => n.w = vars[0],
+            Neuron::HH(n) => {
+                [n.m, n.h, n.n] = [vars[0], vars[1], vars[2]];
+                n.above_spike = v_m >= n.params.v_spike;
+            }
+            Neuron::Custom(n) => n.vars.copy_from_slice(vars),
+        }
+        let state = self.state_mut();
+        state.v_m = v_m;
+        state.i_input = 0.0;
+        state.last_spike_time = None;
+        Ok(())
+    }
+
+    fn state_mut(&mut self) -> &mut NeuronState {
+        match self {
+            Neuron::Lif(n) => &mut n.state,
+            Neuron::AdEx(n) => &mut n.state,
+            Neuron::HH(n) => &mut n.state,
+            Neuron::Custom(n) => &mut n.state,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -326,4 +1175,224 @@ mod tests {
         let spike3 = neuron.update(1.0, 1000000 + 3000000).unwrap(); // +3ms
         assert!(spike3.is_some());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_refractoriness_policies() {
+        let params = |refractory| LIFParams { t_refrac: 5.0, v_reset: -80.0, refractory, ..LIFParams::default() };
+        // Spike at 1 ms, then 2 ms of refractory steps with strong input
+        let run = |refractory| {
+            let mut neuron = LIFNeuron::new(NeuronId::new(0), params(refractory)).unwrap();
+            neuron.receive_input(100.0);
+            assert!(neuron.update(1.0, 1_000_000).unwrap().is_some());
+            for step in 2..=3u64 {
+                neuron.receive_input(100.0);
+                assert!(neuron.update(1.0, step * 1_000_000).unwrap().is_none());
+            }
+            neuron
+        };
+        // Clamped at reset vs relaxing toward rest; input is ignored either way
+        assert_eq!(run(Refractoriness::HoldAtReset).membrane_potential(), -80.0);
+        let relaxing = run(Refractoriness::IgnoreInput).membrane_potential();
+        assert!(relaxing > -80.0 && relaxing < -79.0, "{}", relaxing);
+
+        // Relative: the threshold is raised after each spike and decays back
+        let relative = Refractoriness::Relative { jump: 10.0, tau: 10.0 };
+        let mut neuron = run(relative);
+        assert!((neuron.threshold() - (-50.0 + 10.0 * (-0.2f32).exp())).abs() < 1e-4);
+        let mut model = Neuron::Lif(neuron.clone());
+        assert_eq!(model.model_vars(), vec![neuron.theta]);
+        model.restore(-70.0, &[2.5]).unwrap();
+        assert_eq!(model.model_vars(), vec![2.5]);
+
+        // An input that fires a fresh neuron fails against the raised threshold
+        for step in 4..=6u64 {
+            neuron.update(1.0, step * 1_000_000).unwrap();
+        }
+        neuron.state.v_m = -46.0;
+        assert!(neuron.update(1.0, 7_000_000).unwrap().is_none());
+        let mut fresh = LIFNeuron::new(NeuronId::new(1), params(relative)).unwrap();
+        fresh.state.v_m = -46.0;
+        assert!(fresh.update(1.0, 7_000_000).unwrap().is_some());
+
+        assert!(params(Refractoriness::Relative { jump: 1.0, tau: 0.0 }).validate().is_err());
+    }
+
+    #[test]
+    fn test_lif_spike_time_interpolation() {
+        // Constant 3 nA: V_inf = -40 mV crosses -50 mV at 20 ln 3 ≈ 21.97 ms
+        let spike_time = |timing: SpikeTiming| {
+            let mut neuron = LIFNeuron::new(NeuronId::new(0), LIFParams::default()).unwrap();
+            for step in 1..=30u64 {
+                neuron.receive_input(3.0);
+                if let Some(spike) = neuron.update_timed(&IntegratorKind::ExponentialEuler, timing, 1.0, step * 1_000_000).unwrap() {
+                    return (spike.time.nanos(), neuron.state.last_spike_time);
+                }
+            }
+            panic!("no spike");
+        };
+        let exact = 20.0 * 3f64.ln() * 1e6;
+        assert_eq!(spike_time(SpikeTiming::Grid).0, 22_000_000);
+        let (exponential, last_spike) = spike_time(SpikeTiming::Exponential);
+        assert!((exponential as f64 - exact).abs() < 1_000.0, "{}", exponential);
+        assert_eq!(last_spike, Some(exponential));
+        let (linear, _) = spike_time(SpikeTiming::Linear);
+        assert!((21_000_000..22_000_000).contains(&linear));
+        assert!((linear as f64 - exact).abs() < 50_000.0, "{}", linear);
+
+        // Sub-stepped neurons keep the sub-step crossing when interpolating
+        let mut neuron = Neuron::new(NeuronId::new(1), NeuronModel::Lif(LIFParams::default())).unwrap();
+        neuron.receive_input(100.0);
+        let spike = neuron.update_substepped(IntegratorKind::ForwardEuler, SpikeTiming::Linear, 1.0, 1_000_000, 4).unwrap().unwrap();
+        assert!((250_000..500_000).contains(&spike.time.nanos()), "{:?}", spike.time);
+        assert!("linear".parse::<SpikeTiming>().is_ok() && "cubic".parse::<SpikeTiming>().is_err());
+    }
+
+    #[test]
+    fn test_adex_params_validation() {
+        let params = AdExParams::default();
+        assert!(params.validate().is_ok());
+        assert!((params.tau_m() - 9.3667).abs() < 1e-3);
+
+        let bad = [
+            AdExParams { delta_t: 0.0, ..AdExParams::default() },
+            AdExParams { v_peak: -60.0, ..AdExParams::default() },
+            AdExParams { v_reset: 30.0, ..AdExParams::default() },
+            AdExParams { a: -40.0, ..AdExParams::default() },
+            AdExParams { tau_w: -1.0, ..AdExParams::default() },
+        ];
+        for p in bad {
+            assert!(p.validate().is_err(), "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_adex_rest_and_adaptation() {
+        let mut neuron = AdExNeuron::new(NeuronId::new(0), AdExParams::default()).unwrap();
+
+        // Without input the neuron stays at E_L
+        for step in 1..=100 {
+            assert!(neuron.update(0.1, step * 100_000).unwrap().is_none());
+        }
+        assert!((neuron.state.v_m - -70.6).abs() < 1e-3);
+
+        // Constant suprathreshold drive: inter-spike intervals lengthen (adaptation)
+        let mut spikes = Vec::new();
+        for step in 0..10_000u64 {
+            neuron.receive_input(1.0);
+            if let Some(s) = neuron.update(0.1, step * 100_000).unwrap() {
+                spikes.push(s.time.nanos());
+            }
+        }
+        assert!(spikes.len() >= 3);
+        let first_isi = spikes[1] - spikes[0];
+        let last_isi = spikes[spikes.len() - 1] - spikes[spikes.len() - 2];
+        assert!(last_isi > first_isi, "first {} last {}", first_isi, last_isi);
+        assert!(neuron.w > 0.0);
+    }
+
+    #[test]
+    fn test_neuron_model_dispatch() {
+        let mut lif = Neuron::new(NeuronId::new(1), NeuronModel::Lif(LIFParams::default())).unwrap();
+        let adex = Neuron::new(NeuronId::new(2), NeuronModel::AdEx(AdExParams::default())).unwrap();
+        assert_eq!(lif.membrane_potential(), -70.0);
+        assert_eq!(adex.membrane_potential(), -70.6);
+        assert!(matches!(adex.model(), NeuronModel::AdEx(_)));
+
+        lif.receive_input(100.0);
+        assert!(lif.update(1.0, 1_000_000).unwrap().is_some());
+    }
+
+    fn hh_spike_times(method: HHMethod, max_substep: f32, current: f32) -> Vec<u64> {
+        let params = HHParams { method, max_substep, ..HHParams::default() };
+        let mut neuron = HHNeuron::new(NeuronId::new(0), params).unwrap();
+        let mut spikes = Vec::new();
+        for step in 0..1_000u64 {
+            neuron.receive_input(current);
+            if let Some(s) = neuron.update(0.1, step * 100_000).unwrap() {
+                spikes.push(s.time.nanos());
+            }
+        }
+        spikes
+    }
+
+    #[test]
+    fn test_hh_rest_and_tonic_firing() {
+        // Without input the gates start at steady state and V stays near rest
+        let mut neuron = HHNeuron::new(NeuronId::new(0), HHParams::default()).unwrap();
+        for step in 0..500u64 {
+            assert!(neuron.update(0.1, step * 100_000).unwrap().is_none());
+        }
+        assert!((neuron.membrane_potential() - -65.0).abs() < 1.0);
+
+        // 10 nA on the 1e-3 cm² patch (10 µA/cm²) gives tonic firing around 70 Hz
+        let rk4 = hh_spike_times(HHMethod::Rk4, 0.01, 10.0);
+        assert!((5..=9).contains(&rk4.len()), "{} spikes", rk4.len());
+
+        // The exponential scheme tolerates coarser sub-steps and agrees on the rate
+        let exp = hh_spike_times(HHMethod::Exponential, 0.05, 10.0);
+        assert!((rk4.len() as i64 - exp.len() as i64).abs() <= 1);
+        let drift = (rk4[0] as i64 - exp[0] as i64).abs();
+        assert!(drift <= 300_000, "first spike differs by {} ns", drift);
+    }
+
+    #[test]
+    fn test_hh_params_validation() {
+        assert!(HHParams::default().validate().is_ok());
+        assert!(HHParams { c_m: 0.0, ..HHParams::default() }.validate().is_err());
+        assert!(HHParams { g_k: -1.0, ..HHParams::default() }.validate().is_err());
+        assert!(HHParams { max_substep: 0.0, ..HHParams::default() }.validate().is_err());
+        assert!(HHParams { e_na: -90.0, ..HHParams::default() }.validate().is_err());
+        assert_eq!("exponential".parse::<HHMethod>().unwrap(), HHMethod::Exponential);
+        assert!("euler".parse::<HHMethod>().is_err());
+    }
+
+    /// Perfect integrator with a fixed threshold and reset to 0
+    #[derive(Debug)]
+    struct Accumulator {
+        threshold: f32,
+    }
+
+    impl NeuronDynamics for Accumulator {
+        fn name(&self) -> &str {
+            "accumulator"
+        }
+
+        fn v_rest(&self) -> f32 {
+            0.0
+        }
+
+        fn initial_vars(&self) -> Vec<f32> {
+            vec![0.0]
+        }
+
+        fn step(&self, state: &mut NeuronState, vars: &mut [f32], _dt_ms: f32, _current_time_ns: u64) -> Result<bool> {
+            state.v_m += state.i_input;
+            let spiked = state.v_m >= self.threshold;
+            if spiked {
+                state.v_m = 0.0;
+                vars[0] += 1.0;
+            }
+            Ok(spiked)
+        }
+    }
+
+    #[test]
+    fn test_custom_model_neuron() {
+        let model = CustomModel::new(Accumulator { threshold: 1.0 });
+        let mut neuron = Neuron::new(NeuronId::new(4), NeuronModel::Custom(model.clone())).unwrap();
+        assert_eq!(neuron.model(), NeuronModel::Custom(model.clone()));
+        assert_ne!(model, CustomModel::new(Accumulator { threshold: 1.0 }));
+
+        let mut spikes = Vec::new();
+        for step in 1..=5u64 {
+            neuron.receive_input(0.4);
+            spikes.extend(neuron.update_with(IntegratorKind::Rk4, 0.1, step * 100_000).unwrap());
+        }
+        // 0.4, 0.8, 1.2 -> spike, 0.4, 0.8
+        assert_eq!(spikes.len(), 1);
+        assert_eq!(spikes[0].time.nanos(), 300_000);
+        assert_eq!(neuron.model_vars(), vec![1.0]);
+        assert_eq!(neuron.state().last_spike_time, Some(300_000));
+        assert!((neuron.membrane_potential() - 0.8).abs() < 1e-6);
+    }
+}
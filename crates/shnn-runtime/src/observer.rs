@@ -0,0 +1,181 @@
+//! Hooks into the stepping loop
+//!
+//! A [`SimulationObserver`] added with [`SimulationEngine::add_observer`] is
+//! reset with the engine, told about every step, spike and plasticity weight
+//! change, and may add to the result when the run finishes. The engine's own
+//! [`RateMonitor`]s and [`WeightMonitor`] are observers driven the same way.
+//!
+//! [`SimulationEngine::add_observer`]: crate::simulation::SimulationEngine::add_observer
+
+use crate::{
+    error::Result,
+    monitor::{RateMonitor, WeightMonitor},
+    network::SNNNetwork,
+    simulation::SimulationResult,
+    NeuronId, Spike,
+};
+
+/// A synapse weight changed by plasticity during a step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightUpdate {
+    /// Presynaptic neuron
+    pub pre: NeuronId,
+    /// Postsynaptic neuron
+    pub post: NeuronId,
+    /// Weight before the update
+    pub old_weight: f32,
+    /// Weight after the update
+    pub new_weight: f32,
+    /// Network time of the update (ns)
+    pub time_ns: u64,
+}
+
+/// Receiver of engine events; every method defaults to doing nothing
+///
+/// Per step, the engine calls `on_spike` for each emitted spike, then
+/// `on_weight_update` for each plasticity change, then `on_step`.
+pub trait SimulationObserver: std::fmt::Debug + Send {
+    /// The engine was rewound to time 0 (called before the first step of every run)
+    fn on_reset(&mut self, _network: &SNNNetwork) -> Result<()> {
+        Ok(())
+    }
+
+    /// A neuron fired
+    fn on_spike(&mut self, _spike: &Spike) -> Result<()> {
+        Ok(())
+    }
+
+    /// Plasticity changed a weight
+    fn on_weight_update(&mut self, _update: &WeightUpdate) -> Result<()> {
+        Ok(())
+    }
+
+    /// A step completed; `time_ns` is the network time at its end
+    fn on_step(&mut self, _network: &SNNNetwork, _time_ns: u64, _spikes: &[Spike]) -> Result<()> {
+        Ok(())
+    }
+
+    /// The run's result was assembled; observers may add to it
+    fn on_finish(&mut self, _result: &mut SimulationResult) {}
+
+    /// Whether the engine must collect [`WeightUpdate`]s for this observer
+    fn wants_weight_updates(&self) -> bool {
+        false
+    }
+}
+
+impl SimulationObserver for RateMonitor {
+    fn on_reset(&mut self, _network: &SNNNetwork) -> Result<()> {
+        self.reset();
+        Ok(())
+    }
+
+    fn on_step(&mut self, _network: &SNNNetwork, _time_ns: u64, spikes: &[Spike]) -> Result<()> {
+        self.record(spikes);
+        Ok(())
+    }
+
+    fn on_finish(&mut self, result: &mut SimulationResult) {
+        result.rates.push(self.finish(result.duration_ns));
+    }
+}
+
+impl SimulationObserver for WeightMonitor {
+    fn on_reset(&mut self, network: &SNNNetwork) -> Result<()> {
+        self.reset();
+        self.observe(network, 0)
+    }
+
+    fn on_step(&mut self, network: &SNNNetwork, time_ns: u64, _spikes: &[Spike]) -> Result<()> {
+        self.observe(network, time_ns)
+    }
+
+    fn on_finish(&mut self, result: &mut SimulationResult) {
+        result.weight_trace = Some(self.finish());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        network::{NetworkBuilder, NetworkConfig},
+        simulation::{SimulationEngine, SimulationParams, StimulusPattern},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default)]
+    struct Counts {
+        resets: usize,
+        spikes: usize,
+        updates: usize,
+        steps: usize,
+        finished_spikes: Option<usize>,
+    }
+
+    #[derive(Debug, Default)]
+    struct Counter(Arc<Mutex<Counts>>);
+
+    impl SimulationObserver for Counter {
+        fn on_reset(&mut self, _network: &SNNNetwork) -> Result<()> {
+            self.0.lock().unwrap().resets += 1;
+            Ok(())
+        }
+
+        fn on_spike(&mut self, _spike: &Spike) -> Result<()> {
+            self.0.lock().unwrap().spikes += 1;
+            Ok(())
+        }
+
+        fn on_weight_update(&mut self, _update: &WeightUpdate) -> Result<()> {
+            self.0.lock().unwrap().updates += 1;
+            Ok(())
+        }
+
+        fn on_step(&mut self, _network: &SNNNetwork, _time_ns: u64, _spikes: &[Spike]) -> Result<()> {
+            self.0.lock().unwrap().steps += 1;
+            Ok(())
+        }
+
+        fn on_finish(&mut self, result: &mut SimulationResult) {
+            self.0.lock().unwrap().finished_spikes = Some(result.total_spikes);
+        }
+
+        fn wants_weight_updates(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_observer_sees_every_event() {
+        let config = NetworkConfig { plasticity_enabled: true, ..NetworkConfig::default() };
+        let network = NetworkBuilder::new()
+            .with_config(config)
+            .add_neurons(0, 2)
+            .add_synapse(NeuronId::new(0), NeuronId::new(1), 0.5, 1.0)
+            .build()
+            .unwrap();
+        let params = SimulationParams::new(100_000, 50_000_000).unwrap();
+        let mut engine = SimulationEngine::new(network, params).unwrap();
+        for neuron in [0, 1] {
+            engine.add_stimulus(StimulusPattern::Constant {
+                neuron: NeuronId::new(neuron),
+                amplitude: 5.0,
+                start_time: 0,
+                duration: 50_000_000,
+            });
+        }
+        engine.add_rate_monitor(RateMonitor::new(NeuronId::new(0), NeuronId::new(1), 10_000_000).unwrap());
+        let counts = Arc::new(Mutex::new(Counts::default()));
+        engine.add_observer(Box::new(Counter(counts.clone())));
+        let result = engine.run().unwrap();
+
+        let counts = counts.lock().unwrap();
+        assert!(result.total_spikes > 0);
+        assert_eq!(result.rates.len(), 1);
+        assert_eq!((counts.resets, counts.steps), (1, 500));
+        assert_eq!(counts.spikes, result.total_spikes);
+        assert_eq!(counts.finished_spikes, Some(result.total_spikes));
+        assert!(counts.updates > 0);
+    }
+}
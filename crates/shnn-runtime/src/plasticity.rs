@@ -1,6 +1,7 @@
 //! Plasticity rules for synaptic learning
 
 use crate::{error::*, NeuronId, Time};
+use shnn_types::units::{self, Quantity};
 use std::collections::HashMap;
 
 /// Parameters for STDP (Spike-Timing Dependent Plasticity) rule
@@ -89,6 +90,9 @@ impl STDPParams {
                 "> 0.0",
             ));
         }
+        if let Err(e) = Quantity::ms(max_window as f64).round_ns() {
+            return Err(RuntimeError::invalid_time("max_window", max_window, e));
+        }
 
         Ok(Self {
             a_plus,
@@ -168,6 +172,8 @@ pub struct STDPRule {
     pub params: STDPParams,
     /// Recent spike history for neurons
     spike_history: HashMap<NeuronId, Vec<SpikeRecord>>,
+    /// Neuromodulatory factor scaling every weight change (1.0 = plain STDP)
+    modulation: f32,
 }
 
 impl STDPRule {
@@ -177,9 +183,20 @@ impl STDPRule {
         Ok(Self {
             params,
             spike_history: HashMap::new(),
+            modulation: 1.0,
         })
     }
 
+    /// Set the third-factor level that scales subsequent weight changes
+    pub fn set_modulation(&mut self, level: f32) {
+        self.modulation = level;
+    }
+
+    /// Current third-factor level
+    pub fn modulation(&self) -> f32 {
+        self.modulation
+    }
+
     /// Record a spike for STDP calculation
     pub fn record_spike(&mut self, neuron_id: NeuronId, spike_time: Time) {
         let spike_record = SpikeRecord {
@@ -199,7 +216,7 @@ impl STDPRule {
 
     /// Clean up spike history outside the time window
     fn cleanup_old_spikes(&mut self, current_time_ns: u64) {
-        let max_window_ns = (self.params.max_window * 1_000_000.0) as u64;
+        let max_window_ns = Quantity::ms(self.params.max_window as f64).saturating_ns();
         let cutoff_time = current_time_ns.saturating_sub(max_window_ns);
 
         for spikes in self.spike_history.values_mut() {
@@ -249,19 +266,16 @@ impl STDPRule {
         current_weight: f32,
         pre_spikes: &[SpikeRecord],
         post_spikes: &[SpikeRecord],
-        current_time: Time,
+        _current_time: Time,
     ) -> Result<Option<f32>> {
         let mut total_delta = 0.0;
-        let current_time_ms = current_time.nanos() as f32 / 1_000_000.0;
         let max_window_ms = self.params.max_window;
 
         // Calculate potentiation (pre before post)
         for pre_spike in pre_spikes {
-            let pre_time_ms = pre_spike.time_ns as f32 / 1_000_000.0;
-            
             for post_spike in post_spikes {
-                let post_time_ms = post_spike.time_ns as f32 / 1_000_000.0;
-                let dt = post_time_ms - pre_time_ms;
+                // Subtract in ns: absolute times lose precision as f32 ms
+                let dt = interval_ms(pre_spike.time_ns, post_spike.time_ns);
 
                 // Skip if outside time window
                 if dt.abs() > max_window_ms {
@@ -280,6 +294,7 @@ impl STDPRule {
             }
         }
 
+        total_delta *= self.modulation;
         if total_delta.abs() < f32::EPSILON {
             return Ok(None);
         }
@@ -317,8 +332,7 @@ impl PlasticityRule for STDPRule {
         pre_spike_time: Time,
         post_spike_time: Time,
     ) -> Result<f32> {
-        let dt_ns = post_spike_time.nanos() as i64 - pre_spike_time.nanos() as i64;
-        let dt_ms = dt_ns as f32 / 1_000_000.0;
+        let dt_ms = interval_ms(pre_spike_time.nanos(), post_spike_time.nanos());
 
         // Check if within time window
         if dt_ms.abs() > self.params.max_window {
@@ -353,6 +367,15 @@ impl PlasticityRule for STDPRule {
     }
 }
 
+/// Signed interval `to - from` in ms
+fn interval_ms(from_ns: u64, to_ns: u64) -> f32 {
+    if to_ns >= from_ns {
+        units::ns_to_ms(to_ns - from_ns)
+    } else {
+        -units::ns_to_ms(from_ns - to_ns)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,11 +399,23 @@ mod tests {
         let result = STDPParams::new(0.01, 0.012, 20.0, 20.0, 0.0, 1.0, 100.0);
         assert!(result.is_err());
 
+        // Window not representable in ns
+        let result = STDPParams::new(0.01, 0.012, 20.0, 20.0, 1.0, 0.0, f32::INFINITY);
+        assert!(result.is_err());
+
         // Valid parameters
         let result = STDPParams::new(0.01, 0.012, 20.0, 20.0, 1.0, 0.0, 100.0);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_interval_keeps_precision_late_in_a_run() {
+        // 1000 s into a run an f32 ms timestamp only resolves ~60 us
+        let t = 1_000_000_000_000;
+        assert_eq!(interval_ms(t, t + 1_500), 0.0015);
+        assert_eq!(interval_ms(t + 1_500, t), -0.0015);
+    }
+
     #[test]
     fn test_synapse_id() {
         let pre = NeuronId::new(0);
@@ -0,0 +1,132 @@
+//! Lightweight phase profiling
+//!
+//! A [`Profiler`] collects wall-clock spans for coarse pipeline phases (parse,
+//! verify, passes, lowering, stepping, I/O) on a single timeline. Hot per-step
+//! phases are only accumulated into [`PhaseTotal`]s, since one span per step
+//! would dwarf the phases being measured.
+
+use std::time::Duration;
+
+use crate::clock::Instant;
+
+/// Completed span, relative to the profiler origin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// Phase name (sub-phases use a `parent.child` name)
+    pub name: &'static str,
+    /// Start offset from the profiler origin (ns)
+    pub start_ns: u64,
+    /// Wall-clock duration (ns)
+    pub duration_ns: u64,
+}
+
+/// Accumulated wall-clock time of one phase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseTotal {
+    /// Phase name
+    pub name: &'static str,
+    /// Total time across all occurrences (ns)
+    pub total_ns: u64,
+    /// Number of occurrences
+    pub count: u64,
+}
+
+/// Span and phase-total collector shared by the compiler and the engine
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    origin: Instant,
+    spans: Vec<Span>,
+    /// Totals in first-seen order
+    totals: Vec<PhaseTotal>,
+}
+
+impl Profiler {
+    /// Start a profiler whose timeline begins now
+    pub fn new() -> Self {
+        Self { origin: Instant::now(), spans: Vec::new(), totals: Vec::new() }
+    }
+
+    /// Start timestamp for a span closed with [`Profiler::end`]
+    pub fn begin(&self) -> Instant {
+        Instant::now()
+    }
+
+    /// Close a span opened at `start`
+    pub fn end(&mut self, name: &'static str, start: Instant) {
+        let elapsed = start.elapsed();
+        self.spans.push(Span {
+            name,
+            start_ns: start.saturating_duration_since(self.origin).as_nanos() as u64,
+            duration_ns: elapsed.as_nanos() as u64,
+        });
+        self.accumulate(name, elapsed);
+    }
+
+    /// Time `f` as a span
+    pub fn time<T>(&mut self, name: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = self.begin();
+        let value = f();
+        self.end(name, start);
+        value
+    }
+
+    /// Add to a phase total without recording a span
+    pub fn accumulate(&mut self, name: &'static str, elapsed: Duration) {
+        let ns = elapsed.as_nanos() as u64;
+        match self.totals.iter_mut().find(|t| t.name == name) {
+            Some(total) => {
+                total.total_ns += ns;
+                total.count += 1;
+            }
+            None => self.totals.push(PhaseTotal { name, total_ns: ns, count: 1 }),
+        }
+    }
+
+    /// Recorded spans in completion order
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Phase totals in first-seen order
+    pub fn totals(&self) -> &[PhaseTotal] {
+        &self.totals
+    }
+
+    /// Total time of `name` (0 if never recorded)
+    pub fn total_ns(&self, name: &str) -> u64 {
+        self.totals.iter().find(|t| t.name == name).map_or(0, |t| t.total_ns)
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_and_totals() {
+        let mut profiler = Profiler::new();
+        let value = profiler.time("parse", || 7);
+        assert_eq!(value, 7);
+        let start = profiler.begin();
+        profiler.accumulate("stepping.network", Duration::from_nanos(300));
+        profiler.accumulate("stepping.network", Duration::from_nanos(200));
+        profiler.end("stepping", start);
+        profiler.time("parse", || ());
+
+        let names: Vec<_> = profiler.spans().iter().map(|s| s.name).collect();
+        assert_eq!(names, ["parse", "stepping", "parse"]);
+        let totals: Vec<_> = profiler.totals().iter().map(|t| (t.name, t.count)).collect();
+        assert_eq!(totals, [("parse", 2), ("stepping.network", 2), ("stepping", 1)]);
+        assert_eq!(profiler.total_ns("stepping.network"), 500);
+        assert_eq!(profiler.total_ns("io"), 0);
+
+        let spans = profiler.spans();
+        assert!(spans[1].start_ns >= spans[0].start_ns);
+    }
+}
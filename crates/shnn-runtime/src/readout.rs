@@ -0,0 +1,334 @@
+//! Trained linear readouts of population activity
+//!
+//! A [`ReadoutMonitor`] low-pass filters the spike trains of a neuron range
+//! while the simulation runs (each spike adds 1 to its neuron's trace, which
+//! decays with time constant `tau`) and samples the traces every `interval`.
+//! Traces with [`ReadoutRole::State`] are the state of a liquid state
+//! machine; [`LinearReadout::fit`] trains a ridge regression from them to the
+//! [`ReadoutRole::Target`] traces (or any other target rows).
+
+use crate::{
+    error::{Result, RuntimeError},
+    network::SNNNetwork,
+    observer::SimulationObserver,
+    simulation::SimulationResult,
+    NeuronId, Spike,
+};
+use std::str::FromStr;
+
+/// What a readout's traces are used for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ReadoutRole {
+    /// Features of the trained readout
+    #[default]
+    State,
+    /// Values the readout is trained to reproduce
+    Target,
+}
+
+impl ReadoutRole {
+    /// Lowercase name used in NIR and reports
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReadoutRole::State => "state",
+            ReadoutRole::Target => "target",
+        }
+    }
+}
+
+impl FromStr for ReadoutRole {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "state" => Ok(ReadoutRole::State),
+            "target" => Ok(ReadoutRole::Target),
+            other => Err(RuntimeError::invalid_parameter("role", other, "state | target")),
+        }
+    }
+}
+
+/// Samples exponentially filtered spike trains of neurons `start..=end`
+#[derive(Debug, Clone)]
+pub struct ReadoutMonitor {
+    start: NeuronId,
+    end: NeuronId,
+    tau_ns: u64,
+    interval_ns: u64,
+    role: ReadoutRole,
+    traces: Vec<f64>,
+    last_ns: u64,
+    times_ns: Vec<u64>,
+    states: Vec<Vec<f32>>,
+}
+
+impl ReadoutMonitor {
+    /// Filter with time constant `tau_ns` and sample every `interval_ns`
+    pub fn new(start: NeuronId, end: NeuronId, tau_ns: u64, interval_ns: u64, role: ReadoutRole) -> Result<Self> {
+        if start.raw() > end.raw() {
+            return Err(RuntimeError::invalid_parameter(
+                "neurons",
+                format!("{}..{}", start.raw(), end.raw()),
+                "start <= end",
+            ));
+        }
+        if tau_ns == 0 {
+            return Err(RuntimeError::invalid_parameter("tau", "0", "> 0"));
+        }
+        if interval_ns == 0 {
+            return Err(RuntimeError::invalid_parameter("interval", "0", "> 0"));
+        }
+        let width = (end.raw() - start.raw()) as usize + 1;
+        Ok(Self {
+            start,
+            end,
+            tau_ns,
+            interval_ns,
+            role,
+            traces: vec![0.0; width],
+            last_ns: 0,
+            times_ns: Vec::new(),
+            states: Vec::new(),
+        })
+    }
+
+    /// Zero the traces and forget all samples
+    pub fn reset(&mut self) {
+        self.traces.iter_mut().for_each(|t| *t = 0.0);
+        self.last_ns = 0;
+        self.times_ns.clear();
+        self.states.clear();
+    }
+
+    /// Advance the traces to `time_ns`, the end of a step, adding its in-range spikes
+    ///
+    /// A sample due within the step takes the traces at the step's end.
+    pub fn record(&mut self, time_ns: u64, spikes: &[Spike]) {
+        let decay = (-(time_ns.saturating_sub(self.last_ns) as f64) / self.tau_ns as f64).exp();
+        self.traces.iter_mut().for_each(|t| *t *= decay);
+        for spike in spikes {
+            let id = spike.neuron_id.raw();
+            if id < self.start.raw() || id > self.end.raw() {
+                continue;
+            }
+            let age = time_ns.saturating_sub(spike.time.nanos()) as f64;
+            self.traces[(id - self.start.raw()) as usize] += (-age / self.tau_ns as f64).exp();
+        }
+        self.last_ns = time_ns;
+
+        let mut next = (self.times_ns.len() as u64 + 1) * self.interval_ns;
+        while next <= time_ns {
+            self.times_ns.push(next);
+            self.states.push(self.traces.iter().map(|&t| t as f32).collect());
+            next += self.interval_ns;
+        }
+    }
+
+    /// Samples taken so far
+    pub fn finish(&self) -> ReadoutTrace {
+        ReadoutTrace {
+            start: self.start,
+            end: self.end,
+            role: self.role,
+            tau_ns: self.tau_ns,
+            interval_ns: self.interval_ns,
+            times_ns: self.times_ns.clone(),
+            states: self.states.clone(),
+        }
+    }
+}
+
+impl SimulationObserver for ReadoutMonitor {
+    fn on_reset(&mut self, _network: &SNNNetwork) -> Result<()> {
+        self.reset();
+        Ok(())
+    }
+
+    fn on_step(&mut self, _network: &SNNNetwork, time_ns: u64, spikes: &[Spike]) -> Result<()> {
+        self.record(time_ns, spikes);
+        Ok(())
+    }
+
+    fn on_finish(&mut self, result: &mut SimulationResult) {
+        result.readouts.push(self.finish());
+    }
+}
+
+/// Filtered spike trains sampled by a [`ReadoutMonitor`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadoutTrace {
+    /// First read neuron
+    pub start: NeuronId,
+    /// Last read neuron (inclusive)
+    pub end: NeuronId,
+    /// Use of the traces
+    pub role: ReadoutRole,
+    /// Filter time constant (ns)
+    pub tau_ns: u64,
+    /// Sampling interval (ns)
+    pub interval_ns: u64,
+    /// Sample times (ns)
+    pub times_ns: Vec<u64>,
+    /// One row per sample, one column per neuron
+    pub states: Vec<Vec<f32>>,
+}
+
+/// Rows of `traces` side by side (columns in trace order); every trace needs the same sample times
+pub fn stack_traces(traces: &[&ReadoutTrace]) -> Result<Vec<Vec<f32>>> {
+    let Some(first) = traces.first() else {
+        return Ok(Vec::new());
+    };
+    if let Some(other) = traces.iter().find(|t| t.times_ns != first.times_ns) {
+        return Err(RuntimeError::invalid_parameter(
+            "interval",
+            other.interval_ns.to_string(),
+            format!("the same sample times as the first readout ({} ns)", first.interval_ns),
+        ));
+    }
+    Ok((0..first.times_ns.len())
+        .map(|row| traces.iter().flat_map(|t| t.states[row].iter().copied()).collect())
+        .collect())
+}
+
+/// Affine map from state rows to target rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearReadout {
+    /// One row per target: a weight per state column, then the bias
+    pub weights: Vec<Vec<f64>>,
+}
+
+impl LinearReadout {
+    /// Ridge regression minimizing `Σ |y - W x - b|² + ridge |W|²` (the bias is not penalized)
+    pub fn fit(states: &[Vec<f32>], targets: &[Vec<f32>], ridge: f64) -> Result<Self> {
+        if states.is_empty() || states.len() != targets.len() {
+            return Err(RuntimeError::invalid_parameter(
+                "targets",
+                format!("{} rows for {} state rows", targets.len(), states.len()),
+                "one row per state row, at least one",
+            ));
+        }
+        if !(ridge.is_finite() && ridge >= 0.0) {
+            return Err(RuntimeError::invalid_parameter("ridge", ridge.to_string(), ">= 0 and finite"));
+        }
+        let features = states[0].len();
+        let outputs = targets[0].len();
+        if states.iter().any(|s| s.len() != features) || targets.iter().any(|t| t.len() != outputs) {
+            return Err(RuntimeError::invalid_parameter("states", "ragged rows", "rows of equal length"));
+        }
+
+        // Normal equations over [x, 1]: (XᵀX + ridge·I) W = XᵀY
+        let n = features + 1;
+        let mut a = vec![vec![0.0f64; n + outputs]; n];
+        for (state, target) in states.iter().zip(targets) {
+            let x: Vec<f64> = state.iter().map(|&v| v as f64).chain([1.0]).collect();
+            for (row, &xi) in a.iter_mut().zip(&x) {
+                for (cell, &xj) in row.iter_mut().zip(&x) {
+                    *cell += xi * xj;
+                }
+                for (cell, &y) in row[n..].iter_mut().zip(target) {
+                    *cell += xi * y as f64;
+                }
+            }
+        }
+        for (i, row) in a.iter_mut().enumerate().take(features) {
+            row[i] += ridge;
+        }
+        let solution = solve(a, n).ok_or_else(|| {
+            RuntimeError::invalid_parameter("ridge", ridge.to_string(), "> 0 for linearly dependent states")
+        })?;
+        let weights = (0..outputs).map(|k| solution.iter().map(|row| row[k]).collect()).collect();
+        Ok(Self { weights })
+    }
+
+    /// Predicted target row for `state`
+    pub fn predict(&self, state: &[f32]) -> Vec<f64> {
+        self.weights
+            .iter()
+            .map(|w| {
+                let (bias, coeffs) = w.split_last().expect("weights end with a bias");
+                coeffs.iter().zip(state).map(|(c, &x)| c * x as f64).sum::<f64>() + bias
+            })
+            .collect()
+    }
+
+    /// Per-target RMS error over the targets' standard deviation (`None` for a constant target)
+    pub fn nrmse(&self, states: &[Vec<f32>], targets: &[Vec<f32>]) -> Vec<Option<f64>> {
+        let n = targets.len() as f64;
+        (0..self.weights.len())
+            .map(|k| {
+                let mean = targets.iter().map(|t| t[k] as f64).sum::<f64>() / n;
+                let variance = targets.iter().map(|t| (t[k] as f64 - mean).powi(2)).sum::<f64>() / n;
+                let mse = states.iter().zip(targets)
+                    .map(|(s, t)| (self.predict(s)[k] - t[k] as f64).powi(2))
+                    .sum::<f64>() / n;
+                (variance > 0.0).then(|| (mse / variance).sqrt())
+            })
+            .collect()
+    }
+}
+
+/// Gauss–Jordan elimination with partial pivoting on `[A | B]` (`A` is `n × n`); `None` if singular
+fn solve(mut a: Vec<Vec<f64>>, n: usize) -> Option<Vec<Vec<f64>>> {
+    let scale = a.iter().flat_map(|row| row[..n].iter()).fold(0.0f64, |m, v| m.max(v.abs()));
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= scale * 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        let inv = 1.0 / a[col][col];
+        a[col].iter_mut().for_each(|v| *v *= inv);
+        let pivot_row = a[col].clone();
+        for (i, row) in a.iter_mut().enumerate() {
+            if i != col && row[col] != 0.0 {
+                let factor = row[col];
+                row.iter_mut().zip(&pivot_row).for_each(|(v, p)| *v -= factor * p);
+            }
+        }
+    }
+    Some(a.into_iter().map(|row| row[n..].to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Time;
+
+    #[test]
+    fn test_readout_monitor_filters_and_samples() {
+        let mut monitor = ReadoutMonitor::new(NeuronId::new(2), NeuronId::new(3), 10, 20, ReadoutRole::State)
+            .expect("Should create monitor");
+        monitor.record(10, &[Spike::new(NeuronId::new(2), Time::from_nanos(10)), Spike::new(NeuronId::new(5), Time::from_nanos(10))]);
+        monitor.record(20, &[Spike::new(NeuronId::new(3), Time::from_nanos(20))]);
+        monitor.record(30, &[]);
+        monitor.record(40, &[]);
+        let trace = monitor.finish();
+        assert_eq!(trace.times_ns, vec![20, 40]);
+        let e = (-1.0f64).exp();
+        assert!((trace.states[0][0] as f64 - e).abs() < 1e-6);
+        assert_eq!(trace.states[0][1], 1.0);
+        assert!((trace.states[1][0] as f64 - e.powi(3)).abs() < 1e-6);
+        assert!(ReadoutMonitor::new(NeuronId::new(0), NeuronId::new(1), 0, 20, ReadoutRole::State).is_err());
+        assert_eq!("target".parse::<ReadoutRole>().expect("Should parse"), ReadoutRole::Target);
+    }
+
+    #[test]
+    fn test_linear_readout_fit() {
+        // y = 2 x0 - x1 + 0.5, exactly representable
+        let states: Vec<Vec<f32>> = (0..20).map(|i| vec![i as f32 * 0.1, ((i * 7) % 5) as f32]).collect();
+        let targets: Vec<Vec<f32>> = states.iter().map(|s| vec![2.0 * s[0] - s[1] + 0.5]).collect();
+        let readout = LinearReadout::fit(&states, &targets, 0.0).expect("Should fit");
+        for (w, expected) in readout.weights[0].iter().zip([2.0, -1.0, 0.5]) {
+            assert!((w - expected).abs() < 1e-6, "{:?}", readout.weights);
+        }
+        assert!(readout.nrmse(&states, &targets)[0].expect("Should vary") < 1e-6);
+
+        // A duplicated column is singular without a penalty and fine with one
+        let doubled: Vec<Vec<f32>> = states.iter().map(|s| vec![s[0], s[0]]).collect();
+        assert!(LinearReadout::fit(&doubled, &targets, 0.0).is_err());
+        assert!(LinearReadout::fit(&doubled, &targets, 1e-3).is_ok());
+        assert!(LinearReadout::fit(&states, &targets[1..], 0.0).is_err());
+        let constant = vec![vec![1.0f32]; 20];
+        assert_eq!(readout.nrmse(&states, &constant), vec![None]);
+    }
+}
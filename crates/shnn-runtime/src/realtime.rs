@@ -0,0 +1,161 @@
+//! Wall-clock pacing for real-time runs
+//!
+//! A [`Pacer`] holds step `k` back until `k * dt` of wall-clock time has
+//! passed since the run started. Steps that finish after their deadline are
+//! counted as overruns; the schedule is never shifted, so a slow stretch is
+//! followed by catch-up steps without sleeping. Only a pause moves it
+//! ([`Pacer::shift`]), so paused time is not counted as lag.
+
+use std::time::Duration;
+
+use crate::clock::Instant;
+
+/// Lateness of a step past its deadline that triggers an overload warning (ns)
+pub const OVERLOAD_WARN_NS: u64 = 10_000_000;
+
+/// Drift statistics of a paced run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RealtimeReport {
+    /// Steps paced
+    pub steps: usize,
+    /// Steps that completed after their deadline
+    pub overruns: usize,
+    /// Largest lateness past a deadline (ns)
+    pub max_lag_ns: u64,
+    /// Mean lateness over the overrun steps (ns)
+    pub mean_lag_ns: u64,
+    /// Wall-clock time minus simulated time at the end of the run (ns, negative if ahead)
+    pub final_drift_ns: i64,
+    /// Total time spent sleeping (ns)
+    pub slept_ns: u64,
+}
+
+impl RealtimeReport {
+    /// Fraction of steps that missed their deadline
+    pub fn overrun_ratio(&self) -> f64 {
+        if self.steps == 0 { 0.0 } else { self.overruns as f64 / self.steps as f64 }
+    }
+}
+
+/// Paces simulation steps against wall-clock time
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    dt_ns: u64,
+    start: Option<Instant>,
+    report: RealtimeReport,
+    lag_sum_ns: u128,
+    /// Set while the run is behind schedule, so each overload is warned about once
+    overloaded: bool,
+}
+
+impl Pacer {
+    /// Pacer for steps of `dt_ns`
+    pub fn new(dt_ns: u64) -> Self {
+        Self {
+            dt_ns,
+            start: None,
+            report: RealtimeReport::default(),
+            lag_sum_ns: 0,
+            overloaded: false,
+        }
+    }
+
+    /// Start the wall-clock schedule now and clear statistics
+    pub fn start(&mut self) {
+        *self = Self::new(self.dt_ns);
+        self.start = Some(Instant::now());
+    }
+
+    /// Called after `steps_done` steps: sleep until their simulated time has
+    /// elapsed, or record the overrun
+    pub fn wait(&mut self, steps_done: usize) {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let deadline = start + self.simulated(steps_done);
+        let now = Instant::now();
+        self.report.steps += 1;
+        match deadline.checked_duration_since(now) {
+            Some(remaining) => {
+                if self.overloaded {
+                    log::info!("Realtime: caught up with wall-clock at step {}", steps_done);
+                    self.overloaded = false;
+                }
+                std::thread::sleep(remaining);
+                self.report.slept_ns += remaining.as_nanos() as u64;
+            }
+            None => {
+                let lag_ns = (now - deadline).as_nanos() as u64;
+                self.report.overruns += 1;
+                self.report.max_lag_ns = self.report.max_lag_ns.max(lag_ns);
+                self.lag_sum_ns += lag_ns as u128;
+                if lag_ns >= OVERLOAD_WARN_NS && !self.overloaded {
+                    log::warn!(
+                        "Realtime overload: step {} is {:.1} ms behind wall-clock; the network cannot keep up with dt",
+                        steps_done,
+                        lag_ns as f64 / 1_000_000.0
+                    );
+                    self.overloaded = true;
+                }
+            }
+        }
+    }
+
+    /// Delay the schedule by `held` (time the run spent paused)
+    pub fn shift(&mut self, held: Duration) {
+        if let Some(start) = &mut self.start {
+            *start += held;
+        }
+    }
+
+    /// Statistics so far, with drift measured at `steps_done`
+    pub fn report(&self, steps_done: usize) -> RealtimeReport {
+        let mut report = self.report.clone();
+        if report.overruns > 0 {
+            report.mean_lag_ns = (self.lag_sum_ns / report.overruns as u128) as u64;
+        }
+        if let Some(start) = self.start {
+            let wall_ns = start.elapsed().as_nanos() as i64;
+            let sim_ns = self.simulated(steps_done).as_nanos() as i64;
+            report.final_drift_ns = wall_ns - sim_ns;
+        }
+        report
+    }
+
+    fn simulated(&self, steps: usize) -> Duration {
+        Duration::from_nanos(self.dt_ns.saturating_mul(steps as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pacer_holds_steps_to_wall_clock() {
+        let mut pacer = Pacer::new(2_000_000);
+        let begin = Instant::now();
+        pacer.start();
+        for step in 1..=5 {
+            pacer.wait(step);
+        }
+        // 5 steps of 2 ms take at least 10 ms
+        assert!(begin.elapsed() >= Duration::from_millis(10));
+        let report = pacer.report(5);
+        assert_eq!(report.steps, 5);
+        assert!(report.slept_ns > 0);
+    }
+
+    #[test]
+    fn test_pacer_counts_overruns() {
+        let mut pacer = Pacer::new(1_000);
+        pacer.start();
+        std::thread::sleep(Duration::from_millis(2));
+        pacer.wait(1);
+        pacer.wait(2);
+        let report = pacer.report(2);
+        assert_eq!(report.overruns, 2);
+        assert!(report.max_lag_ns >= 1_000_000);
+        assert!(report.mean_lag_ns > 0 && report.mean_lag_ns <= report.max_lag_ns);
+        assert!(report.final_drift_ns > 0);
+        assert_eq!(report.overrun_ratio(), 1.0);
+    }
+}
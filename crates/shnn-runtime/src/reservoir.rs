@@ -0,0 +1,131 @@
+//! Random recurrent reservoirs (liquid state machines)
+//!
+//! A [`Reservoir`] wires an input population into a reservoir population with
+//! probability `input_p` and a fixed weight, and the reservoir to itself with
+//! probability `p` (no self-connections) and Gaussian weights. By the
+//! circular law, an `N × N` matrix with density `p` and i.i.d. entries of
+//! standard deviation `σ` has its eigenvalues in a disc of radius about
+//! `σ·sqrt(N·p)`, so the recurrent weights use `σ = radius / sqrt(N·p)` to
+//! place the spectral radius near `radius`. Pairs are drawn input-major, then
+//! pre-major, from one seeded stream, so a seed fixes the whole reservoir.
+
+use crate::{error::{Result, RuntimeError}, NeuronId};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::ops::RangeInclusive;
+
+/// Reservoir connection recipe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reservoir {
+    /// Recurrent connection probability
+    pub p: f32,
+    /// Target spectral radius of the recurrent weight matrix (weight units)
+    pub radius: f32,
+    /// Input-to-reservoir connection probability
+    pub input_p: f32,
+    /// Weight of every input synapse
+    pub input_weight: f32,
+}
+
+impl Reservoir {
+    /// Check p within (0, 1], input_p within [0, 1], radius >= 0 and a finite input weight
+    pub fn validate(&self) -> Result<()> {
+        if !(self.p > 0.0 && self.p <= 1.0) {
+            return Err(invalid("p", &self.p.to_string(), "within (0, 1]"));
+        }
+        if !(self.radius.is_finite() && self.radius >= 0.0) {
+            return Err(invalid("radius", &self.radius.to_string(), ">= 0 and finite"));
+        }
+        if !(0.0..=1.0).contains(&self.input_p) {
+            return Err(invalid("input_p", &self.input_p.to_string(), "within [0, 1]"));
+        }
+        if !self.input_weight.is_finite() {
+            return Err(invalid("input_weight", &self.input_weight.to_string(), "finite"));
+        }
+        Ok(())
+    }
+
+    /// Standard deviation of the recurrent weights of a reservoir of `size` neurons
+    pub fn weight_std(&self, size: u32) -> f32 {
+        self.radius / (size as f32 * self.p).sqrt()
+    }
+
+    /// Draw `(pre, post, weight)` input and recurrent synapses
+    ///
+    /// `inputs` and `neurons` must not overlap.
+    pub fn connect(
+        &self,
+        inputs: RangeInclusive<u32>,
+        neurons: RangeInclusive<u32>,
+        seed: u64,
+    ) -> Result<Vec<(NeuronId, NeuronId, f32)>> {
+        self.validate()?;
+        if inputs.start() <= neurons.end() && neurons.start() <= inputs.end() {
+            return Err(invalid(
+                "inputs",
+                &format!("{}..={}", inputs.start(), inputs.end()),
+                "disjoint from the reservoir neurons",
+            ));
+        }
+        let size = neurons.end().saturating_sub(*neurons.start()) + 1;
+        let std = self.weight_std(size);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut synapses = Vec::new();
+        for pre in inputs {
+            for post in neurons.clone() {
+                if rng.gen::<f32>() < self.input_p {
+                    synapses.push((NeuronId::new(pre), NeuronId::new(post), self.input_weight));
+                }
+            }
+        }
+        for pre in neurons.clone() {
+            for post in neurons.clone() {
+                if pre == post {
+                    continue;
+                }
+                if rng.gen::<f32>() < self.p {
+                    synapses.push((NeuronId::new(pre), NeuronId::new(post), std * standard_normal(&mut rng)));
+                }
+            }
+        }
+        Ok(synapses)
+    }
+}
+
+/// Box–Muller standard normal
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f32 {
+    let u1 = 1.0 - rng.gen::<f64>(); // (0, 1]
+    let u2 = rng.gen::<f64>();
+    ((-2.0 * u1.ln()).sqrt() * (2.0 * core::f64::consts::PI * u2).cos()) as f32
+}
+
+fn invalid(parameter: &str, value: &str, constraint: &str) -> RuntimeError {
+    RuntimeError::invalid_parameter(parameter, value, constraint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservoir_connectivity() {
+        let reservoir = Reservoir { p: 0.1, radius: 2.0, input_p: 0.5, input_weight: 3.0 };
+        let synapses = reservoir.connect(0..=9, 10..=209, 5).expect("Should connect");
+        assert_eq!(synapses, reservoir.connect(0..=9, 10..=209, 5).expect("Should connect"));
+
+        let (input, recurrent): (Vec<_>, Vec<_>) = synapses.iter().partition(|(pre, _, _)| pre.raw() < 10);
+        assert!(input.iter().all(|(_, post, w)| post.raw() >= 10 && *w == 3.0));
+        assert!((800..1200).contains(&input.len()));
+        assert!(recurrent.iter().all(|(pre, post, _)| pre != post && post.raw() >= 10));
+        assert!((3600..4400).contains(&recurrent.len()));
+
+        // Empirical weight spread matches radius / sqrt(N p) = 2 / sqrt(20)
+        let n = recurrent.len() as f32;
+        let mean = recurrent.iter().map(|(_, _, w)| w).sum::<f32>() / n;
+        let std = (recurrent.iter().map(|(_, _, w)| (w - mean) * (w - mean)).sum::<f32>() / n).sqrt();
+        assert!((std - reservoir.weight_std(200)).abs() < 0.03, "std {}", std);
+
+        assert!(reservoir.connect(0..=10, 10..=20, 5).is_err());
+        assert!(Reservoir { p: 0.0, ..reservoir }.validate().is_err());
+        assert!(Reservoir { radius: -1.0, ..reservoir }.validate().is_err());
+    }
+}
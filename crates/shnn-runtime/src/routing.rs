@@ -0,0 +1,339 @@
+//! Connectivity backends for spike routing
+//!
+//! The network keeps every synapse (weight, delay) in one map; a
+//! [`SpikeRouter`] indexes that map so a spiking neuron's targets are found
+//! without scanning all synapses. The index layout follows the connectivity
+//! structures of shnn-core:
+//!
+//! - [`ConnectivityBackend::Graph`]: adjacency list per source neuron
+//! - [`ConnectivityBackend::Matrix`]: dense N×N connection bitmap, one row scan per spike
+//! - [`ConnectivityBackend::Sparse`]: compressed sparse rows (CSR)
+//! - [`ConnectivityBackend::Hypergraph`]: one hyperedge per distinct target set,
+//!   shared by every source with that fan-out (a fully connected layer is a single hyperedge)
+//!
+//! Every backend yields targets in ascending id order, so the choice changes
+//! speed and memory but not simulation results.
+
+use crate::{
+    error::{Result, RuntimeError},
+    plasticity::SynapseId,
+    NeuronId,
+};
+use core::str::FromStr;
+use std::collections::HashMap;
+
+/// Connectivity backend selection, e.g. from the `backend` attribute of `runtime.simulate.run`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ConnectivityBackend {
+    /// Choose by connection density when the index is built ([`ConnectivityBackend::for_density`])
+    #[default]
+    Auto,
+    /// Adjacency lists
+    Graph,
+    /// Dense connection bitmap
+    Matrix,
+    /// Compressed sparse rows
+    Sparse,
+    /// Shared target sets
+    Hypergraph,
+}
+
+impl ConnectivityBackend {
+    /// Density at or above which [`ConnectivityBackend::Auto`] picks the dense matrix
+    pub const DENSE_THRESHOLD: f64 = 0.25;
+
+    /// Largest network [`ConnectivityBackend::Auto`] stores as a dense matrix (8 MiB bitmap)
+    pub const MAX_AUTO_MATRIX_NEURONS: usize = 8192;
+
+    /// Canonical name used in NIR attributes
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConnectivityBackend::Auto => "auto",
+            ConnectivityBackend::Graph => "graph",
+            ConnectivityBackend::Matrix => "matrix",
+            ConnectivityBackend::Sparse => "sparse",
+            ConnectivityBackend::Hypergraph => "hypergraph",
+        }
+    }
+
+    /// Default backend for `synapses` connections among `neurons` neurons
+    ///
+    /// Dense networks (at least [`Self::DENSE_THRESHOLD`] of all pairs
+    /// connected, up to [`Self::MAX_AUTO_MATRIX_NEURONS`] neurons) use the
+    /// matrix, whose row scan beats pointer chasing; everything else uses
+    /// CSR. Graph and hypergraph are only used when requested.
+    pub fn for_density(neurons: usize, synapses: usize) -> Self {
+        if neurons == 0 || synapses == 0 {
+            return ConnectivityBackend::Sparse;
+        }
+        let density = synapses as f64 / (neurons as f64 * neurons as f64);
+        if density >= Self::DENSE_THRESHOLD && neurons <= Self::MAX_AUTO_MATRIX_NEURONS {
+            ConnectivityBackend::Matrix
+        } else {
+            ConnectivityBackend::Sparse
+        }
+    }
+}
+
+impl core::fmt::Display for ConnectivityBackend {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ConnectivityBackend {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(ConnectivityBackend::Auto),
+            "graph" => Ok(ConnectivityBackend::Graph),
+            "matrix" => Ok(ConnectivityBackend::Matrix),
+            "sparse" => Ok(ConnectivityBackend::Sparse),
+            "hypergraph" => Ok(ConnectivityBackend::Hypergraph),
+            other => Err(RuntimeError::invalid_parameter(
+                "backend",
+                other,
+                "auto | graph | matrix | sparse | hypergraph",
+            )),
+        }
+    }
+}
+
+/// Index layout of a built router
+#[derive(Debug, Clone)]
+enum RouteIndex {
+    Graph(HashMap<NeuronId, Vec<NeuronId>>),
+    Matrix {
+        /// Dense index of each neuron (ids in ascending order)
+        index: HashMap<NeuronId, usize>,
+        ids: Vec<NeuronId>,
+        /// Row-major bitmap, `words` u64 per row
+        bits: Vec<u64>,
+        words: usize,
+    },
+    Sparse {
+        index: HashMap<NeuronId, usize>,
+        row_ptr: Vec<usize>,
+        cols: Vec<NeuronId>,
+    },
+    Hypergraph {
+        /// Hyperedge of each source neuron
+        source_edge: HashMap<NeuronId, usize>,
+        /// Distinct target sets
+        edges: Vec<Vec<NeuronId>>,
+    },
+}
+
+/// Outgoing-synapse index over a network's synapse map
+///
+/// Topology changes only mark the index stale; it is rebuilt on the next
+/// spike, so building a network stays linear in its synapse count.
+#[derive(Debug, Clone)]
+pub struct SpikeRouter {
+    requested: ConnectivityBackend,
+    resolved: ConnectivityBackend,
+    index: Option<RouteIndex>,
+}
+
+impl SpikeRouter {
+    /// Unbuilt router for `backend`
+    pub fn new(backend: ConnectivityBackend) -> Self {
+        Self { requested: backend, resolved: backend, index: None }
+    }
+
+    /// Requested backend (may be [`ConnectivityBackend::Auto`])
+    pub fn requested(&self) -> ConnectivityBackend {
+        self.requested
+    }
+
+    /// Backend of the current index; [`ConnectivityBackend::Auto`] until first built
+    pub fn backend(&self) -> ConnectivityBackend {
+        self.resolved
+    }
+
+    /// Whether the index must be rebuilt before routing
+    pub fn is_stale(&self) -> bool {
+        self.index.is_none()
+    }
+
+    /// Drop the index after a topology change
+    pub fn invalidate(&mut self) {
+        self.index = None;
+    }
+
+    /// Rebuild the index from the network's synapses
+    pub fn rebuild<'a>(&mut self, neurons: usize, synapses: impl IntoIterator<Item = &'a SynapseId>) {
+        let mut pairs: Vec<(NeuronId, NeuronId)> = synapses.into_iter().map(|id| (id.pre, id.post)).collect();
+        pairs.sort_unstable();
+        self.resolved = match self.requested {
+            ConnectivityBackend::Auto => ConnectivityBackend::for_density(neurons, pairs.len()),
+            backend => backend,
+        };
+        self.index = Some(match self.resolved {
+            ConnectivityBackend::Graph => {
+                let mut adjacency: HashMap<NeuronId, Vec<NeuronId>> = HashMap::new();
+                for (pre, post) in pairs {
+                    adjacency.entry(pre).or_default().push(post);
+                }
+                RouteIndex::Graph(adjacency)
+            }
+            ConnectivityBackend::Matrix => {
+                let (index, ids) = dense_index(&pairs);
+                let words = ids.len().div_ceil(64);
+                let mut bits = vec![0u64; ids.len() * words];
+                for (pre, post) in pairs {
+                    let col = index[&post];
+                    bits[index[&pre] * words + col / 64] |= 1 << (col % 64);
+                }
+                RouteIndex::Matrix { index, ids, bits, words }
+            }
+            ConnectivityBackend::Sparse | ConnectivityBackend::Auto => {
+                let (index, ids) = dense_index(&pairs);
+                let mut row_ptr = vec![0usize; ids.len() + 1];
+                for (pre, _) in &pairs {
+                    row_ptr[index[pre] + 1] += 1;
+                }
+                for row in 0..ids.len() {
+                    row_ptr[row + 1] += row_ptr[row];
+                }
+                // Pairs are sorted by source, so columns land row by row
+                let cols = pairs.into_iter().map(|(_, post)| post).collect();
+                RouteIndex::Sparse { index, row_ptr, cols }
+            }
+            ConnectivityBackend::Hypergraph => {
+                let mut interned: HashMap<Vec<NeuronId>, usize> = HashMap::new();
+                let mut edges: Vec<Vec<NeuronId>> = Vec::new();
+                let mut source_edge = HashMap::new();
+                for row in pairs.chunk_by(|a, b| a.0 == b.0) {
+                    let targets: Vec<NeuronId> = row.iter().map(|&(_, post)| post).collect();
+                    let edge = *interned.entry(targets).or_insert_with_key(|targets| {
+                        edges.push(targets.clone());
+                        edges.len() - 1
+                    });
+                    source_edge.insert(row[0].0, edge);
+                }
+                RouteIndex::Hypergraph { source_edge, edges }
+            }
+        });
+    }
+
+    /// Append the targets of `pre` to `out` in ascending order (nothing if stale)
+    pub fn targets(&self, pre: NeuronId, out: &mut Vec<NeuronId>) {
+        match &self.index {
+            None => {}
+            Some(RouteIndex::Graph(adjacency)) => {
+                out.extend(adjacency.get(&pre).into_iter().flatten());
+            }
+            Some(RouteIndex::Matrix { index, ids, bits, words }) => {
+                let Some(&row) = index.get(&pre) else { return };
+                for (w, &word) in bits[row * words..(row + 1) * words].iter().enumerate() {
+                    let mut word = word;
+                    while word != 0 {
+                        out.push(ids[w * 64 + word.trailing_zeros() as usize]);
+                        word &= word - 1;
+                    }
+                }
+            }
+            Some(RouteIndex::Sparse { index, row_ptr, cols }) => {
+                if let Some(&row) = index.get(&pre) {
+                    out.extend_from_slice(&cols[row_ptr[row]..row_ptr[row + 1]]);
+                }
+            }
+            Some(RouteIndex::Hypergraph { source_edge, edges }) => {
+                if let Some(&edge) = source_edge.get(&pre) {
+                    out.extend_from_slice(&edges[edge]);
+                }
+            }
+        }
+    }
+
+    /// Number of hyperedges (distinct target sets) in a hypergraph index
+    pub fn hyperedge_count(&self) -> Option<usize> {
+        match &self.index {
+            Some(RouteIndex::Hypergraph { edges, .. }) => Some(edges.len()),
+            _ => None,
+        }
+    }
+}
+
+/// Dense indices for every neuron appearing in `pairs`, in ascending id order
+fn dense_index(pairs: &[(NeuronId, NeuronId)]) -> (HashMap<NeuronId, usize>, Vec<NeuronId>) {
+    let mut ids: Vec<NeuronId> = pairs.iter().flat_map(|&(pre, post)| [pre, post]).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let index = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    (index, ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router(backend: ConnectivityBackend, synapses: &[(u32, u32)]) -> SpikeRouter {
+        let ids: Vec<SynapseId> = synapses.iter()
+            .map(|&(pre, post)| SynapseId::new(NeuronId::new(pre), NeuronId::new(post)))
+            .collect();
+        let mut router = SpikeRouter::new(backend);
+        router.rebuild(8, &ids);
+        router
+    }
+
+    fn targets(router: &SpikeRouter, pre: u32) -> Vec<u32> {
+        let mut out = Vec::new();
+        router.targets(NeuronId::new(pre), &mut out);
+        out.into_iter().map(|id| id.raw()).collect()
+    }
+
+    #[test]
+    fn test_backends_route_identically() {
+        // Two sources share a fan-out; ids are sparse and unsorted on input
+        let synapses = [(5, 70), (0, 3), (5, 3), (0, 70), (3, 0), (70, 70)];
+        for backend in [
+            ConnectivityBackend::Graph,
+            ConnectivityBackend::Matrix,
+            ConnectivityBackend::Sparse,
+            ConnectivityBackend::Hypergraph,
+        ] {
+            let r = router(backend, &synapses);
+            assert_eq!(r.backend(), backend);
+            assert_eq!(targets(&r, 0), [3, 70], "{}", backend);
+            assert_eq!(targets(&r, 5), [3, 70], "{}", backend);
+            assert_eq!(targets(&r, 3), [0], "{}", backend);
+            assert_eq!(targets(&r, 70), [70], "{}", backend);
+            assert!(targets(&r, 4).is_empty(), "{}", backend);
+        }
+        assert_eq!(router(ConnectivityBackend::Hypergraph, &synapses).hyperedge_count(), Some(3));
+    }
+
+    #[test]
+    fn test_auto_selection_by_density() {
+        assert_eq!(ConnectivityBackend::for_density(10, 50), ConnectivityBackend::Matrix);
+        assert_eq!(ConnectivityBackend::for_density(10, 20), ConnectivityBackend::Sparse);
+        assert_eq!(ConnectivityBackend::for_density(10_000, 50_000_000), ConnectivityBackend::Sparse);
+        assert_eq!(ConnectivityBackend::for_density(0, 0), ConnectivityBackend::Sparse);
+        // One synapse among 8 neurons
+        assert_eq!(router(ConnectivityBackend::Auto, &[(0, 1)]).backend(), ConnectivityBackend::Sparse);
+
+        let mut r = SpikeRouter::new(ConnectivityBackend::Auto);
+        assert!(r.is_stale());
+        r.rebuild(2, &[SynapseId::new(NeuronId::new(0), NeuronId::new(1))]);
+        assert_eq!(r.backend(), ConnectivityBackend::Matrix);
+        r.invalidate();
+        assert!(r.is_stale() && targets(&r, 0).is_empty());
+    }
+
+    #[test]
+    fn test_backend_parse() {
+        for backend in [
+            ConnectivityBackend::Auto,
+            ConnectivityBackend::Graph,
+            ConnectivityBackend::Matrix,
+            ConnectivityBackend::Sparse,
+            ConnectivityBackend::Hypergraph,
+        ] {
+            assert_eq!(backend.as_str().parse::<ConnectivityBackend>().unwrap(), backend);
+        }
+        assert!("csr".parse::<ConnectivityBackend>().is_err());
+    }
+}
@@ -0,0 +1,66 @@
+//! Hierarchical deterministic seeds
+//!
+//! A [`SeedTree`] derives child seeds from a master seed by label and index
+//! (`master → "op/connectivity.layer_fully_connected" → 2`), mixing with
+//! splitmix64. A component's seed depends only on its own path, so adding a
+//! stochastic component never shifts the streams of the others.
+
+/// One splitmix64 step: a well-mixed 64-bit output for `state`
+pub fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A node of a seed hierarchy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SeedTree {
+    seed: u64,
+}
+
+impl SeedTree {
+    /// Root of a hierarchy
+    pub const fn new(master: u64) -> Self {
+        Self { seed: master }
+    }
+
+    /// Seed of this node
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Child named `label`
+    pub fn child(&self, label: &str) -> Self {
+        // FNV-1a keeps the label hash stable across platforms and releases
+        let hash = label.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3));
+        Self { seed: splitmix64(self.seed ^ splitmix64(hash)) }
+    }
+
+    /// `index`-th child (repeats, trials, stimuli of one kind)
+    pub fn index(&self, index: u64) -> Self {
+        Self { seed: splitmix64(splitmix64(self.seed) ^ index) }
+    }
+
+    /// Seed of the `index`-th child of `label`
+    pub fn derive(&self, label: &str, index: u64) -> u64 {
+        self.child(label).index(index).seed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_tree_paths_are_independent() {
+        let root = SeedTree::new(42);
+        assert_eq!(root.derive("op", 3), SeedTree::new(42).derive("op", 3));
+        assert_ne!(root.derive("op", 3), root.derive("op", 4));
+        assert_ne!(root.derive("op", 0), root.derive("stimulus", 0));
+        assert_ne!(root.derive("op", 0), SeedTree::new(43).derive("op", 0));
+        assert_ne!(root.child("a").seed(), root.seed());
+        // Nested paths differ from flat ones with the same parts
+        assert_ne!(root.child("trial").index(1).index(2), root.child("trial").index(2).index(1));
+    }
+}
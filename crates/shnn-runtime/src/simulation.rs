@@ -1,12 +1,30 @@
 //! Simulation engine for SNN networks
 
 use crate::{
+    clock::{self, Instant},
+    control::{ParamUpdate, SimulationControl},
     error::*,
+    metrics::{self, SimilarityParams, SimilarityReport},
+    modulation::{Interpolation, Modulator},
     network::{SNNNetwork, NetworkConfig},
+    monitor::{RateMonitor, RateTrace, WeightMonitor, WeightTrace},
+    readout::ReadoutTrace,
+    noise::OuNoise,
+    observer::{SimulationObserver, WeightUpdate},
+    profile::Profiler,
+    realtime::{Pacer, RealtimeReport},
+    rng::{CounterRng, RngKind},
+    seed::SeedTree,
+    spatial::NeuronPositions,
+    state::NetworkState,
+    stream::{SpikeSink, SpikeSource},
     NeuronId, Time, Spike,
 };
-use std::collections::HashMap;
-use std::time::Instant;
+use shnn_storage::vevt::{encode_vevt_spikes, VEVTEvent, WeightChange};
+use shnn_storage::{CompressedSpikes, EventLog, StreamId, StreamKind};
+use shnn_types::units;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -22,12 +40,20 @@ pub struct SimulationParams {
     pub record_neurons: Option<Vec<NeuronId>>,
     /// Record membrane potentials (expensive)
     pub record_potentials: bool,
+    /// Record every applied plasticity weight change
+    pub record_weight_updates: bool,
     /// Apply random seed for reproducibility
     pub random_seed: Option<u64>,
+    /// Generator for stochastic draws (Poisson stimuli, OU noise)
+    pub rng: RngKind,
     /// Maximum spikes to record (prevents memory issues)
     pub max_recorded_spikes: Option<usize>,
+    /// Keep spikes in compressed blocks while running (decoded once by `finish`)
+    pub compress_spikes: bool,
     /// Enable performance sampling
     pub perf_enabled: bool,
+    /// Pace `run` against wall-clock time (one dt of real time per step)
+    pub realtime: bool,
 }
 
 impl Default for SimulationParams {
@@ -37,9 +63,13 @@ impl Default for SimulationParams {
             duration_ns: 1_000_000_000,  // 1 second
             record_neurons: None,        // Record all neurons
             record_potentials: false,    // Don't record potentials by default
+            record_weight_updates: false,
             random_seed: None,           // No deterministic seed
+            rng: RngKind::Sequential,
             max_recorded_spikes: Some(1_000_000), // 1M spike limit
+            compress_spikes: false,
             perf_enabled: false,
+            realtime: false,
         }
     }
 }
@@ -88,32 +118,56 @@ impl SimulationParams {
         self
     }
 
+    /// Enable recording of plasticity weight changes
+    pub fn with_weight_update_recording(mut self, enabled: bool) -> Self {
+        self.record_weight_updates = enabled;
+        self
+    }
+
     /// Set random seed for reproducibility
     pub fn with_seed(mut self, seed: u64) -> Self {
         self.random_seed = Some(seed);
         self
     }
 
+    /// Choose the generator for stochastic draws
+    pub fn with_rng(mut self, rng: RngKind) -> Self {
+        self.rng = rng;
+        self
+    }
+
     /// Set maximum spike recording limit
     pub fn with_spike_limit(mut self, limit: usize) -> Self {
         self.max_recorded_spikes = Some(limit);
         self
     }
 
+    /// Record spikes into compressed blocks during the run
+    pub fn with_spike_compression(mut self, enabled: bool) -> Self {
+        self.compress_spikes = enabled;
+        self
+    }
+
     /// Enable or disable performance sampling
     pub fn with_perf(mut self, enabled: bool) -> Self {
         self.perf_enabled = enabled;
         self
     }
 
+    /// Pace runs against wall-clock time
+    pub fn with_realtime(mut self, enabled: bool) -> Self {
+        self.realtime = enabled;
+        self
+    }
+
     /// Get timestep in milliseconds
     pub fn dt_ms(&self) -> f32 {
-        self.dt_ns as f32 / 1_000_000.0
+        units::ns_to_ms(self.dt_ns)
     }
 
     /// Get duration in milliseconds
     pub fn duration_ms(&self) -> f32 {
-        self.duration_ns as f32 / 1_000_000.0
+        units::ns_to_ms(self.duration_ns)
     }
 
     /// Get number of simulation steps
@@ -194,6 +248,22 @@ pub struct SimulationResult {
     pub total_spikes: usize,
     /// Optional performance report
     pub perf: Option<PerfReport>,
+    /// Binned population rates, one per rate monitor
+    pub rates: Vec<RateTrace>,
+    /// Sampled weight trajectories (if a weight monitor is set)
+    pub weight_trace: Option<WeightTrace>,
+    /// Every applied plasticity weight change, in time order (if recorded)
+    pub weight_updates: Vec<WeightUpdate>,
+    /// Wall-clock drift statistics (realtime runs only)
+    pub realtime: Option<RealtimeReport>,
+    /// Neurons in the simulated network
+    pub neuron_count: usize,
+    /// Wall-clock time from reset to finish (ns)
+    pub wall_time_ns: u64,
+    /// Spatial layout of the network (empty if none was assigned)
+    pub positions: NeuronPositions,
+    /// Sampled filtered spike trains, one per readout monitor
+    pub readouts: Vec<ReadoutTrace>,
 }
 
 impl SimulationResult {
@@ -207,6 +277,14 @@ impl SimulationResult {
             steps_executed: 0,
             total_spikes: 0,
             perf: None,
+            rates: Vec::new(),
+            weight_trace: None,
+            weight_updates: Vec::new(),
+            realtime: None,
+            neuron_count: 0,
+            wall_time_ns: 0,
+            positions: NeuronPositions::new(),
+            readouts: Vec::new(),
         }
     }
 
@@ -243,6 +321,326 @@ impl SimulationResult {
             .map(|spike| (spike.time.nanos(), spike.neuron_id.raw()))
             .collect()
     }
+
+    /// Spikes as (time_ns, neuron_id) sorted by time then neuron, independent of
+    /// the order neurons were updated within a step
+    pub fn canonical_spikes(&self) -> Vec<(u64, u32)> {
+        let mut spikes = self.export_spikes();
+        spikes.sort_unstable();
+        spikes
+    }
+
+    /// FNV-1a hash of [`SimulationResult::canonical_spikes`], for golden-output comparisons
+    pub fn spike_hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for (time_ns, neuron_id) in self.canonical_spikes() {
+            for byte in time_ns.to_le_bytes().into_iter().chain(neuron_id.to_le_bytes()) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+        hash
+    }
+
+    /// First point where this run's canonical spike stream differs from `other`'s
+    pub fn first_divergence(&self, other: &SimulationResult) -> Option<SpikeDivergence> {
+        let (a, b) = (self.canonical_spikes(), other.canonical_spikes());
+        (0..a.len().max(b.len()))
+            .find(|&i| a.get(i) != b.get(i))
+            .map(|index| SpikeDivergence {
+                index,
+                expected: a.get(index).copied(),
+                actual: b.get(index).copied(),
+            })
+    }
+
+    /// Sorted spike times (ns) of one neuron
+    pub fn spike_times(&self, neuron_id: NeuronId) -> Vec<u64> {
+        let mut times: Vec<u64> = self.spikes.iter()
+            .filter(|spike| spike.neuron_id == neuron_id)
+            .map(|spike| spike.time.nanos())
+            .collect();
+        times.sort_unstable();
+        times
+    }
+
+    /// Sorted spike times (ns) of every neuron that fired
+    pub fn spike_trains(&self) -> BTreeMap<NeuronId, Vec<u64>> {
+        let mut trains: BTreeMap<NeuronId, Vec<u64>> = BTreeMap::new();
+        for spike in &self.spikes {
+            trains.entry(spike.neuron_id).or_default().push(spike.time.nanos());
+        }
+        for times in trains.values_mut() {
+            times.sort_unstable();
+        }
+        trains
+    }
+
+    /// Spike train similarity to `other`, neuron by neuron (see [`crate::metrics`])
+    pub fn similarity(&self, other: &SimulationResult, params: &SimilarityParams) -> Result<SimilarityReport> {
+        metrics::compare_trains(&self.spike_trains(), &other.spike_trains(), params)
+    }
+
+    /// Spike statistics of neurons `start..=end` (silent neurons included)
+    pub fn population_summary(&self, start: NeuronId, end: NeuronId) -> PopulationSummary {
+        let neurons = end.raw().saturating_sub(start.raw()) as usize + 1;
+        PopulationSummary::from_trains(
+            self.spike_trains().range(start..=end).map(|(_, times)| times.len()),
+            neurons,
+            self.duration_ns,
+        )
+    }
+
+    /// Spike statistics of the whole network
+    pub fn summary(&self) -> PopulationSummary {
+        let trains = self.spike_trains();
+        let neurons = self.neuron_count.max(trains.len());
+        PopulationSummary::from_trains(trains.values().map(Vec::len), neurons, self.duration_ns)
+    }
+
+    /// Recorded membrane potentials, if the run recorded any
+    pub fn recorded_potentials(&self) -> Option<PotentialTraces<'_>> {
+        (!self.potentials.is_empty()).then_some(PotentialTraces { samples: &self.potentials })
+    }
+
+    /// Wall-clock time of the run
+    pub fn wall_time(&self) -> std::time::Duration {
+        std::time::Duration::from_nanos(self.wall_time_ns)
+    }
+
+    /// Spikes as a delta-compressed VEVT stream (decodes to event_type 0, source = neuron, no target)
+    ///
+    /// The stream spans the first to last spike, or the whole run if none fired.
+    pub fn to_vevt(&self) -> Result<Vec<u8>> {
+        let start = self.spikes.iter().map(|s| s.time).min().unwrap_or(Time::ZERO);
+        let end = self.spikes.iter().map(|s| s.time).max().unwrap_or(Time::from_nanos(self.duration_ns));
+        let spikes: CompressedSpikes = self.spikes.iter().collect();
+        Ok(encode_vevt_spikes(StreamId::new(1), start, end, &spikes)?)
+    }
+
+    /// Spikes as stream 1 ([`StreamKind::Spikes`]) of a multiplexed event log, and
+    /// recorded weight updates as stream 2 ([`StreamKind::WeightUpdates`])
+    pub fn to_event_log(&self) -> Result<EventLog> {
+        let mut events: Vec<VEVTEvent> = self
+            .spikes
+            .iter()
+            .map(|s| VEVTEvent {
+                timestamp: s.time.as_nanos(),
+                event_type: 0,
+                source_id: s.neuron_id.raw(),
+                target_id: u32::MAX,
+                payload_size: 0,
+                reserved: 0,
+            })
+            .collect();
+        events.sort_by_key(|e| e.timestamp);
+        let mut log = EventLog::new();
+        log.add_stream(StreamId::new(1), StreamKind::Spikes, "spikes")?;
+        log.append(StreamId::new(1), &events)?;
+        if !self.weight_updates.is_empty() {
+            let mut changes: Vec<WeightChange> = self
+                .weight_updates
+                .iter()
+                .map(|u| WeightChange {
+                    time_ns: u.time_ns,
+                    pre: u.pre.raw(),
+                    post: u.post.raw(),
+                    delta: u.new_weight - u.old_weight,
+                    weight: u.new_weight,
+                })
+                .collect();
+            changes.sort_by_key(|c| c.time_ns);
+            log.add_stream(StreamId::new(2), StreamKind::WeightUpdates, "weight-updates")?;
+            log.append_weight_changes(StreamId::new(2), &changes)?;
+        }
+        Ok(log)
+    }
+
+    /// Spikes as CSV with a `time_ns,neuron_id` header, in recording order
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time_ns,neuron_id\n");
+        for (time_ns, neuron_id) in self.export_spikes() {
+            csv.push_str(&format!("{},{}\n", time_ns, neuron_id));
+        }
+        csv
+    }
+
+    /// Spikes, run statistics and rate traces as JSON
+    ///
+    /// Same `{"results": {...}}` layout `snn nir run --output` writes, so
+    /// results from library runs load in `snn viz` and `snn ttr`.
+    pub fn to_json(&self) -> String {
+        let spikes: Vec<String> = self.export_spikes().into_iter()
+            .map(|(time_ns, neuron_id)| format!(
+                "{{\"neuron_id\":{},\"time_ns\":{},\"time_ms\":{}}}",
+                neuron_id,
+                time_ns,
+                json_number(time_ns as f64 / 1_000_000.0)
+            ))
+            .collect();
+        let rates: Vec<String> = self.rates.iter()
+            .map(|trace| format!(
+                "{{\"neurons\":[{},{}],\"bin_ns\":{},\"rates_hz\":[{}],\"mean_hz\":{},\"std_hz\":{},\"min_hz\":{},\"max_hz\":{}}}",
+                trace.start.raw(),
+                trace.end.raw(),
+                trace.bin_ns,
+                trace.rates_hz.iter().map(|r| json_number(*r as f64)).collect::<Vec<_>>().join(","),
+                json_number(trace.stats.mean_hz as f64),
+                json_number(trace.stats.std_hz() as f64),
+                json_number(trace.stats.min_hz as f64),
+                json_number(trace.stats.max_hz as f64),
+            ))
+            .collect();
+        // Layouts are only written when present, so unplaced networks keep their output
+        let positions = match self.positions.is_empty() {
+            true => String::new(),
+            false => format!(
+                ",\"positions\":[{}]",
+                self.positions.iter()
+                    .map(|(id, [x, y, z])| format!(
+                        "{{\"neuron_id\":{},\"x\":{},\"y\":{},\"z\":{}}}",
+                        id.raw(),
+                        json_number(x as f64),
+                        json_number(y as f64),
+                        json_number(z as f64)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        };
+        let readouts = match self.readouts.is_empty() {
+            true => String::new(),
+            false => format!(
+                ",\"readouts\":[{}]",
+                self.readouts.iter()
+                    .map(|trace| format!(
+                        "{{\"neurons\":[{},{}],\"role\":\"{}\",\"tau_ns\":{},\"interval_ns\":{},\"times_ns\":[{}],\"states\":[{}]}}",
+                        trace.start.raw(),
+                        trace.end.raw(),
+                        trace.role.as_str(),
+                        trace.tau_ns,
+                        trace.interval_ns,
+                        trace.times_ns.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(","),
+                        trace.states.iter()
+                            .map(|row| format!("[{}]", row.iter().map(|v| json_number(*v as f64)).collect::<Vec<_>>().join(",")))
+                            .collect::<Vec<_>>()
+                            .join(","),
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        };
+        format!(
+            "{{\"results\":{{\"spike_count\":{},\"steps_executed\":{},\"duration_ns\":{},\"wall_time_ns\":{},\"spikes\":[{}],\"rates\":[{}]{}{}}}}}",
+            self.spikes.len(),
+            self.steps_executed,
+            self.duration_ns,
+            self.wall_time_ns,
+            spikes.join(","),
+            rates.join(","),
+            positions,
+            readouts,
+        )
+    }
+}
+
+/// JSON has no NaN/infinity; write them as null
+fn json_number(value: f64) -> String {
+    if value.is_finite() { value.to_string() } else { "null".to_string() }
+}
+
+/// Spike statistics over a group of neurons
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopulationSummary {
+    /// Neurons in the group
+    pub neurons: usize,
+    /// Neurons that fired at least once
+    pub active_neurons: usize,
+    /// Spikes emitted by the group
+    pub spike_count: usize,
+    /// Mean per-neuron firing rate (Hz)
+    pub mean_rate_hz: f32,
+    /// Firing rate of the most active neuron (Hz)
+    pub max_rate_hz: f32,
+}
+
+impl PopulationSummary {
+    fn from_trains(counts: impl Iterator<Item = usize>, neurons: usize, duration_ns: u64) -> Self {
+        let (mut active_neurons, mut spike_count, mut max_count) = (0, 0, 0);
+        for count in counts.filter(|&count| count > 0) {
+            active_neurons += 1;
+            spike_count += count;
+            max_count = max_count.max(count);
+        }
+        let duration_s = duration_ns as f64 / 1_000_000_000.0;
+        let rate = |count: f64| if duration_s > 0.0 { (count / duration_s) as f32 } else { 0.0 };
+        Self {
+            neurons,
+            active_neurons,
+            spike_count,
+            mean_rate_hz: if neurons > 0 { rate(spike_count as f64 / neurons as f64) } else { 0.0 },
+            max_rate_hz: rate(max_count as f64),
+        }
+    }
+}
+
+/// Borrowed view of recorded membrane potentials
+#[derive(Debug, Clone, Copy)]
+pub struct PotentialTraces<'a> {
+    samples: &'a [PotentialSample],
+}
+
+/// Membrane potential trace of one neuron
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PotentialTrace {
+    /// Sample times (ns)
+    pub times_ns: Vec<u64>,
+    /// Membrane potentials (mV)
+    pub potentials: Vec<f32>,
+}
+
+impl<'a> PotentialTraces<'a> {
+    /// All samples in recording order
+    pub fn samples(&self) -> &'a [PotentialSample] {
+        self.samples
+    }
+
+    /// Number of samples
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// True if nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Recorded neurons, sorted
+    pub fn neurons(&self) -> Vec<NeuronId> {
+        let ids: BTreeSet<NeuronId> = self.samples.iter().map(|sample| sample.neuron_id).collect();
+        ids.into_iter().collect()
+    }
+
+    /// Trace of one neuron (empty if it was not recorded)
+    pub fn trace(&self, neuron_id: NeuronId) -> PotentialTrace {
+        let mut trace = PotentialTrace::default();
+        for sample in self.samples.iter().filter(|sample| sample.neuron_id == neuron_id) {
+            trace.times_ns.push(sample.time_ns);
+            trace.potentials.push(sample.potential);
+        }
+        trace
+    }
+}
+
+/// First differing event between two canonical spike streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpikeDivergence {
+    /// Position in the time-sorted stream
+    pub index: usize,
+    /// (time_ns, neuron_id) in the reference run, if it has that many spikes
+    pub expected: Option<(u64, u32)>,
+    /// (time_ns, neuron_id) in the compared run, if it has that many spikes
+    pub actual: Option<(u64, u32)>,
 }
 
 /// Performance metrics collected during simulation steps.
@@ -268,33 +666,152 @@ pub struct SimulationEngine {
     stimuli: Vec<StimulusPattern>,
     /// Current simulation results
     results: SimulationResult,
-    /// Random number generator state
-    rng_state: u64,
+    /// Random state of each stimulus, seeded from the run seed by stimulus index
+    stimulus_rng: Vec<u64>,
+    /// Counter-based generator of each stimulus (used with [`RngKind::Philox`])
+    stimulus_keys: Vec<CounterRng>,
+    /// Ornstein–Uhlenbeck background currents
+    noise: Vec<OuNoise>,
+    /// Population rate monitors
+    monitors: Vec<RateMonitor>,
+    /// Weight trajectory monitor
+    weight_monitor: Option<WeightMonitor>,
+    /// Observers added with [`SimulationEngine::add_observer`]
+    observers: Vec<Box<dyn SimulationObserver>>,
     /// Per-step timing samples (ns), captured when perf_enabled
     perf_samples: Vec<u64>,
+    /// Phase profiler, attached with [`SimulationEngine::set_profiler`]
+    profiler: Option<Profiler>,
+    /// Steps executed since the last reset
+    step_index: usize,
+    /// Spikes emitted by `step` and not yet drained
+    output_spikes: Vec<Spike>,
+    /// External input polled before every step
+    spike_source: Option<Box<dyn SpikeSource>>,
+    /// Receivers of every step's spikes
+    spike_sinks: Vec<Box<dyn SpikeSink>>,
+    /// Wall-clock start of the current run (set by reset)
+    started: Instant,
+    /// Spikes recorded so far when `compress_spikes` is set
+    compressed_spikes: CompressedSpikes,
+    /// Pause/resume and live parameter handle, checked before every step
+    control: Option<SimulationControl>,
+    /// State restored on every reset instead of the rest state
+    initial_state: Option<NetworkState>,
+    /// Checkpoints to write as (time_ns, path), by ascending time
+    state_saves: Vec<(u64, PathBuf)>,
+    /// Entries of `state_saves` written since the last reset
+    saved_states: usize,
 }
 
 impl SimulationEngine {
     /// Create a new simulation engine
-    pub fn new(network: SNNNetwork, params: SimulationParams) -> Result<Self> {
+    pub fn new(mut network: SNNNetwork, params: SimulationParams) -> Result<Self> {
         params.validate()?;
         
         let results = SimulationResult::new(params.duration_ns);
-        let rng_state = params.random_seed.unwrap_or(42);
+        if params.record_weight_updates {
+            network.track_weight_updates(true);
+        }
 
         Ok(Self {
             network,
             params,
             stimuli: Vec::new(),
             results,
-            rng_state,
+            stimulus_rng: Vec::new(),
+            stimulus_keys: Vec::new(),
+            noise: Vec::new(),
+            monitors: Vec::new(),
+            weight_monitor: None,
+            observers: Vec::new(),
             perf_samples: Vec::new(),
+            profiler: None,
+            step_index: 0,
+            output_spikes: Vec::new(),
+            spike_source: None,
+            spike_sinks: Vec::new(),
+            started: Instant::now(),
+            compressed_spikes: CompressedSpikes::new(),
+            control: None,
+            initial_state: None,
+            state_saves: Vec::new(),
+            saved_states: 0,
         })
     }
 
     /// Add an input stimulus
     pub fn add_stimulus(&mut self, stimulus: StimulusPattern) {
         self.stimuli.push(stimulus);
+        let seed = self.stimulus_seed(self.stimuli.len() - 1);
+        self.stimulus_rng.push(seed);
+        self.stimulus_keys.push(CounterRng::new(seed));
+    }
+
+    /// Seed of stimulus `index`'s random stream (later stimuli never shift earlier streams)
+    pub fn stimulus_seed(&self, index: usize) -> u64 {
+        SeedTree::new(self.params.random_seed.unwrap_or(42)).derive("stimulus", index as u64)
+    }
+
+    /// Add an Ornstein–Uhlenbeck background current (restarted from its seed on each run)
+    pub fn add_noise(&mut self, noise: OuNoise) {
+        self.noise.push(noise.with_rng(self.params.rng));
+    }
+
+    /// Add a population rate monitor (its trace is returned in [`SimulationResult::rates`])
+    pub fn add_rate_monitor(&mut self, monitor: RateMonitor) {
+        self.monitors.push(monitor);
+    }
+
+    /// Record weight trajectories (returned in [`SimulationResult::weight_trace`])
+    pub fn set_weight_monitor(&mut self, monitor: WeightMonitor) {
+        self.weight_monitor = Some(monitor);
+    }
+
+    /// Notify `observer` of every reset, step, spike and weight update, after the built-in monitors
+    pub fn add_observer(&mut self, observer: Box<dyn SimulationObserver>) {
+        if observer.wants_weight_updates() {
+            self.network.track_weight_updates(true);
+        }
+        self.observers.push(observer);
+    }
+
+    /// Record a `stepping` span plus per-step `stepping.*` phase totals on each run
+    pub fn set_profiler(&mut self, profiler: Profiler) {
+        self.profiler = Some(profiler);
+    }
+
+    /// Poll `source` before every step and inject its spikes at the current time
+    pub fn set_spike_source(&mut self, source: Box<dyn SpikeSource>) {
+        self.spike_source = Some(source);
+    }
+
+    /// Send the spikes of every step that emitted any to `sink`
+    pub fn add_spike_sink(&mut self, sink: Box<dyn SpikeSink>) {
+        self.spike_sinks.push(sink);
+    }
+
+    /// Let `control` pause the run and change parameters between steps
+    pub fn set_control(&mut self, control: SimulationControl) {
+        self.control = Some(control);
+    }
+
+    /// Start every run from `state` (see [`SNNNetwork::restore_state`]) instead of rest
+    pub fn set_initial_state(&mut self, state: NetworkState) -> Result<()> {
+        self.network.restore_state(&state)?;
+        self.initial_state = Some(state);
+        Ok(())
+    }
+
+    /// Write the network state to `path` once the clock reaches `time_ns`
+    pub fn save_state_at(&mut self, time_ns: u64, path: impl Into<PathBuf>) {
+        let index = self.state_saves.partition_point(|(t, _)| *t <= time_ns);
+        self.state_saves.insert(index, (time_ns, path.into()));
+    }
+
+    /// Detach the profiler, with everything recorded so far
+    pub fn take_profiler(&mut self) -> Option<Profiler> {
+        self.profiler.take()
     }
 
 
@@ -302,57 +819,50 @@ impl SimulationEngine {
     pub fn run(&mut self) -> Result<SimulationResult> {
         log::info!("Starting simulation: {}ms with {}ms timestep", 
                    self.params.duration_ms(), self.params.dt_ms());
+        let run_start = self.profiler.as_ref().map(Profiler::begin);
 
-        // Reset network
-        self.network.reset()?;
-        
-        // Reset results
-        self.results = SimulationResult::new(self.params.duration_ns);
-
+        if self.params.realtime && !clock::CAN_SLEEP {
+            return Err(RuntimeError::invalid_config(
+                "realtime pacing needs a blocking sleep, which is unavailable in the browser",
+            ));
+        }
+        self.reset()?;
         let num_steps = self.params.num_steps();
-        let dt_ms = self.params.dt_ms();
+        let mut pacer = self.params.realtime.then(|| Pacer::new(self.params.dt_ns));
+        if let Some(pacer) = &mut pacer {
+            pacer.start();
+        }
 
         // Main simulation loop
-        for step in 0..num_steps {
-            let current_time_ns = step as u64 * self.params.dt_ns;
-
-            // Step timing start
-            let step_start = Instant::now();
-            
-            // Apply stimuli
-            self.apply_stimuli(current_time_ns)?;
-
-            // Step the network
-            let step_spikes = self.network.step(dt_ms)
-                .map_err(|e| RuntimeError::simulation_step(current_time_ns, e.to_string()))?;
-
-            // Record spikes
-            self.record_spikes(step_spikes)?;
-
-            // Record membrane potentials
-            if self.params.record_potentials {
-                self.record_potentials(current_time_ns)?;
+        while !self.is_finished() {
+            let step = self.step_index;
+            if let Some(control) = self.control.clone() {
+                let held = Instant::now();
+                let Some(updates) = control.checkpoint(step, step as u64 * self.params.dt_ns) else {
+                    log::info!("Simulation stopped by control at step {}", step);
+                    break;
+                };
+                // Time spent paused is not a realtime overrun
+                if let Some(pacer) = &mut pacer {
+                    pacer.shift(held.elapsed());
+                }
+                for update in updates {
+                    self.apply_update(update)?;
+                }
+            }
+            self.advance()?;
+            if let Some(pacer) = &mut pacer {
+                pacer.wait(self.step_index);
             }
 
             // Check spike limit
             if let Some(max_spikes) = self.params.max_recorded_spikes {
-                if self.results.spikes.len() >= max_spikes {
+                if self.recorded_spike_count() >= max_spikes {
                     log::warn!("Spike recording limit reached: {}", max_spikes);
-                    // Capture timing before early break if perf enabled
-                    if self.params.perf_enabled {
-                        let elapsed_ns = step_start.elapsed().as_nanos() as u64;
-                        self.perf_samples.push(elapsed_ns);
-                    }
                     break;
                 }
             }
 
-            // Capture step timing
-            if self.params.perf_enabled {
-                let elapsed_ns = step_start.elapsed().as_nanos() as u64;
-                self.perf_samples.push(elapsed_ns);
-            }
-
             // Progress logging
             if step % (num_steps / 10).max(1) == 0 {
                 let progress = (step as f32 / num_steps as f32) * 100.0;
@@ -360,15 +870,126 @@ impl SimulationEngine {
             }
         }
 
+        if let Some(control) = &self.control {
+            control.finish(self.step_index, self.step_index as u64 * self.params.dt_ns);
+        }
+        if let Some(pacer) = &pacer {
+            let report = pacer.report(self.step_index);
+            if report.overruns > 0 {
+                log::warn!(
+                    "Realtime: {} of {} steps missed their deadline (max lag {:.1} ms)",
+                    report.overruns,
+                    report.steps,
+                    report.max_lag_ns as f64 / 1_000_000.0
+                );
+            }
+            self.results.realtime = Some(report);
+        }
+        let result = self.finish();
+        if let (Some(profiler), Some(start)) = (&mut self.profiler, run_start) {
+            profiler.end("stepping", start);
+        }
+
+        log::info!("Simulation completed: {} spikes in {} steps",
+                   result.total_spikes, result.steps_executed);
+        Ok(result)
+    }
+
+    /// Rewind to time 0: network state, stimulus RNG, noise, monitors and results
+    pub fn reset(&mut self) -> Result<()> {
+        self.network.reset()?;
+        if let Some(state) = &self.initial_state {
+            self.network.restore_state(state)?;
+        }
+        self.stimulus_rng = (0..self.stimuli.len()).map(|i| self.stimulus_seed(i)).collect();
+        for noise in &mut self.noise {
+            noise.reset();
+        }
+        let network = &self.network;
+        for observer in observers(&mut self.monitors, &mut self.weight_monitor, &mut self.observers) {
+            observer.on_reset(network)?;
+        }
+        self.results = SimulationResult::new(self.params.duration_ns);
+        self.perf_samples.clear();
+        self.output_spikes.clear();
+        self.compressed_spikes.clear();
+        self.step_index = 0;
+        self.started = Instant::now();
+        self.saved_states = 0;
+        self.save_due_states()
+    }
+
+    /// Advance one time step under external control
+    ///
+    /// Spikes emitted by the step are buffered for [`SimulationEngine::drain_output_spikes`].
+    /// Call [`SimulationEngine::reset`] before the first step and
+    /// [`SimulationEngine::finish`] to collect results; unlike `run`, stepping does
+    /// not stop at `max_recorded_spikes`.
+    pub fn step(&mut self) -> Result<()> {
+        if self.is_finished() {
+            return Err(RuntimeError::invalid_config(format!(
+                "simulation already reached its duration ({} steps); call reset() to start over",
+                self.step_index
+            )));
+        }
+        let spikes = self.advance()?;
+        self.output_spikes.extend(spikes);
+        Ok(())
+    }
+
+    /// Deliver external spikes through each source neuron's outgoing synapses,
+    /// as if it had fired at the spike's time (injected spikes are not recorded)
+    pub fn inject_spikes(&mut self, spikes: &[Spike]) -> Result<()> {
+        for spike in spikes {
+            self.network.inject_spike(spike)?;
+        }
+        Ok(())
+    }
+
+    /// Take the spikes emitted by [`SimulationEngine::step`] since the last drain
+    pub fn drain_output_spikes(&mut self) -> Vec<Spike> {
+        core::mem::take(&mut self.output_spikes)
+    }
+
+    /// Spikes recorded since the last reset
+    pub fn recorded_spike_count(&self) -> usize {
+        if self.params.compress_spikes {
+            self.compressed_spikes.len()
+        } else {
+            self.results.spikes.len()
+        }
+    }
+
+    /// Compressed spike recording (empty unless `compress_spikes` is set)
+    pub fn compressed_spikes(&self) -> &CompressedSpikes {
+        &self.compressed_spikes
+    }
+
+    /// Steps executed since the last reset
+    pub fn current_step(&self) -> usize {
+        self.step_index
+    }
+
+    /// Whether the configured duration has been simulated
+    pub fn is_finished(&self) -> bool {
+        self.step_index >= self.params.num_steps()
+    }
+
+    /// Finalize the steps executed so far into a result (weights, monitors, perf)
+    pub fn finish(&mut self) -> SimulationResult {
         // Record final weights
         self.record_final_weights();
 
+        if self.params.compress_spikes {
+            self.results.spikes = self.compressed_spikes.to_vec();
+        }
+
         // Update final statistics
-        self.results.steps_executed = num_steps;
+        self.results.steps_executed = self.step_index;
         self.results.total_spikes = self.results.spikes.len();
-
-        log::info!("Simulation completed: {} spikes in {} steps",
-                   self.results.total_spikes, self.results.steps_executed);
+        self.results.neuron_count = self.network.neuron_count();
+        self.results.positions = self.network.positions().clone();
+        self.results.wall_time_ns = self.started.elapsed().as_nanos() as u64;
 
         // Build performance report if enabled
         if self.params.perf_enabled && !self.perf_samples.is_empty() {
@@ -383,15 +1004,164 @@ impl SimulationEngine {
             });
         }
 
-        Ok(self.results.clone())
+        // Rate and weight traces come from the monitors, like any observer's additions
+        self.results.rates.clear();
+        self.results.weight_trace = None;
+        self.results.readouts.clear();
+        for observer in observers(&mut self.monitors, &mut self.weight_monitor, &mut self.observers) {
+            observer.on_finish(&mut self.results);
+        }
+
+        self.results.clone()
+    }
+
+    /// Apply a live parameter change from the control handle
+    fn apply_update(&mut self, update: ParamUpdate) -> Result<()> {
+        match update {
+            ParamUpdate::StimulusRate { neuron, rate_hz } => {
+                let mut matched = 0;
+                for stimulus in &mut self.stimuli {
+                    if let StimulusPattern::Poisson { neuron: target, rate, .. } = stimulus {
+                        if *target == neuron {
+                            *rate = rate_hz;
+                            matched += 1;
+                        }
+                    }
+                }
+                match matched {
+                    0 => log::warn!("No Poisson stimulus drives neuron {}; rate change ignored", neuron.raw()),
+                    n => log::info!("Poisson rate of neuron {} set to {} Hz ({} stimuli)", neuron.raw(), rate_hz, n),
+                }
+            }
+            ParamUpdate::ModulatorLevel(level) => {
+                self.network.set_modulator(Modulator::schedule(vec![(0, level)], Interpolation::Step)?);
+                log::info!("Modulation level held at {}", level);
+            }
+        }
+        Ok(())
+    }
+
+    /// Poisson stimuli as `(neuron, rate_hz)`, in the order they were added
+    pub fn poisson_rates(&self) -> Vec<(NeuronId, f32)> {
+        self.stimuli.iter()
+            .filter_map(|stimulus| match stimulus {
+                StimulusPattern::Poisson { neuron, rate, .. } => Some((*neuron, *rate)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Execute one step and return its spikes (after recording them)
+    fn advance(&mut self) -> Result<Vec<Spike>> {
+        let current_time_ns = self.step_index as u64 * self.params.dt_ns;
+        let dt_ms = self.params.dt_ms();
+
+        // Step timing start
+        let step_start = Instant::now();
+        let mut lap = self.profiler.as_ref().map(|_| step_start);
+        
+        // External input arrives on the simulation clock, whatever its producer's timestamps
+        if let Some(source) = &mut self.spike_source {
+            let time = Time::from_nanos(current_time_ns);
+            for mut spike in source.poll()? {
+                spike.time = time;
+                self.network.inject_spike(&spike)?;
+            }
+        }
+
+        // Apply stimuli
+        self.apply_stimuli(current_time_ns)?;
+        self.lap("stepping.stimuli", &mut lap);
+
+        // Step the network
+        let step_spikes = self.network.step(dt_ms)
+            .map_err(|e| RuntimeError::simulation_step(current_time_ns, e.to_string()))?;
+        self.lap("stepping.network", &mut lap);
+
+        if !step_spikes.is_empty() {
+            for sink in &mut self.spike_sinks {
+                sink.publish(&step_spikes)?;
+            }
+        }
+
+        // Monitors and observers see every spike, independent of which neurons are recorded
+        let weight_updates = self.network.take_weight_updates();
+        let (network, time_ns) = (&self.network, self.network.current_time().nanos());
+        for observer in observers(&mut self.monitors, &mut self.weight_monitor, &mut self.observers) {
+            for spike in &step_spikes {
+                observer.on_spike(spike)?;
+            }
+            for update in &weight_updates {
+                observer.on_weight_update(update)?;
+            }
+            observer.on_step(network, time_ns, &step_spikes)?;
+        }
+        if self.params.record_weight_updates {
+            self.results.weight_updates.extend(weight_updates);
+        }
+        self.lap("stepping.monitors", &mut lap);
+
+        // Record spikes
+        self.record_spikes(&step_spikes)?;
+
+        // Record membrane potentials
+        if self.params.record_potentials {
+            self.record_potentials(current_time_ns)?;
+        }
+        self.lap("stepping.recording", &mut lap);
+
+        // Capture step timing
+        if self.params.perf_enabled {
+            let elapsed_ns = step_start.elapsed().as_nanos() as u64;
+            self.perf_samples.push(elapsed_ns);
+        }
+
+        self.step_index += 1;
+        self.save_due_states()?;
+        Ok(step_spikes)
+    }
+
+    /// Write the checkpoints due at the current network time
+    fn save_due_states(&mut self) -> Result<()> {
+        let now_ns = self.network.current_time().nanos();
+        while let Some((time_ns, path)) = self.state_saves.get(self.saved_states) {
+            if *time_ns > now_ns {
+                break;
+            }
+            self.network.capture_state().save(path)?;
+            log::info!("Saved network state at {} ns to {}", now_ns, path.display());
+            self.saved_states += 1;
+        }
+        Ok(())
+    }
+
+    /// Run on a dedicated pool of `threads` workers for the parallel neuron update
+    #[cfg(feature = "parallel")]
+    pub fn run_with_threads(&mut self, threads: usize) -> Result<SimulationResult> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(|e| RuntimeError::invalid_config(format!("failed to build thread pool: {}", e)))?;
+        pool.install(|| self.run())
+    }
+
+    /// Charge the time since `mark` to phase `name` and restart it (profiling only)
+    fn lap(&mut self, name: &'static str, mark: &mut Option<Instant>) {
+        if let (Some(profiler), Some(start)) = (&mut self.profiler, mark.as_mut()) {
+            let now = Instant::now();
+            profiler.accumulate(name, now - *start);
+            *start = now;
+        }
     }
 
     /// Apply input stimuli at current time
     fn apply_stimuli(&mut self, current_time_ns: u64) -> Result<()> {
         // Clone stimuli to avoid borrowing issues
         let stimuli = self.stimuli.clone();
-        
-        for stimulus in &stimuli {
+        let dt_s = self.params.dt_ns as f32 / 1_000_000_000.0;
+        let philox_fires = (self.params.rng == RngKind::Philox).then(|| self.philox_poisson_fires(dt_s));
+
+        for (index, stimulus) in stimuli.iter().enumerate() {
             match stimulus {
                 StimulusPattern::Constant { neuron, amplitude, start_time, duration } => {
                     if current_time_ns >= *start_time &&
@@ -402,10 +1172,11 @@ impl SimulationEngine {
                 StimulusPattern::Poisson { neuron, rate, amplitude, start_time, duration } => {
                     if current_time_ns >= *start_time &&
                        current_time_ns < start_time + duration {
-                        let dt_s = self.params.dt_ns as f32 / 1_000_000_000.0;
-                        let spike_prob = rate * dt_s;
-                        
-                        if self.random_uniform() < spike_prob {
+                        let fired = match &philox_fires {
+                            Some(fires) => fires[index],
+                            None => self.random_uniform(index) < rate * dt_s,
+                        };
+                        if fired {
                             self.network.apply_input(*neuron, *amplitude)?;
                         }
                     }
@@ -419,11 +1190,20 @@ impl SimulationEngine {
                 }
             }
         }
+
+        // Background noise: inject the current value, then advance to the next step
+        let dt_ms = self.params.dt_ms();
+        for noise in &mut self.noise {
+            for (neuron, current) in noise.currents() {
+                self.network.apply_input(neuron, current)?;
+            }
+            noise.advance(dt_ms);
+        }
         Ok(())
     }
 
     /// Record spikes from network step
-    fn record_spikes(&mut self, spikes: Vec<Spike>) -> Result<()> {
+    fn record_spikes(&mut self, spikes: &[Spike]) -> Result<()> {
         for spike in spikes {
             // Check if we should record this neuron
             let should_record = match &self.params.record_neurons {
@@ -431,8 +1211,13 @@ impl SimulationEngine {
                 None => true,
             };
 
-            if should_record {
-                self.results.spikes.push(spike);
+            if !should_record {
+                continue;
+            }
+            if self.params.compress_spikes {
+                self.compressed_spikes.push(spike);
+            } else {
+                self.results.spikes.push(spike.clone());
             }
         }
         Ok(())
@@ -465,11 +1250,28 @@ impl SimulationEngine {
         }
     }
 
-    /// Generate random uniform value [0, 1)
-    fn random_uniform(&mut self) -> f32 {
+    /// Whether each Poisson stimulus draws a spike at the current step; every
+    /// draw is addressed by (stimulus seed, neuron, step), so the order and
+    /// thread they are evaluated on cannot change the result
+    fn philox_poisson_fires(&self, dt_s: f32) -> Vec<bool> {
+        let step = self.step_index as u64;
+        let fires = |(stimulus, key): (&StimulusPattern, &CounterRng)| match stimulus {
+            StimulusPattern::Poisson { neuron, rate, .. } => (key.uniform(neuron.raw(), step, 0) as f32) < rate * dt_s,
+            _ => false,
+        };
+        #[cfg(feature = "parallel")]
+        let fired = self.stimuli.par_iter().zip(self.stimulus_keys.par_iter()).map(fires).collect();
+        #[cfg(not(feature = "parallel"))]
+        let fired = self.stimuli.iter().zip(&self.stimulus_keys).map(fires).collect();
+        fired
+    }
+
+    /// Generate random uniform value [0, 1) from stimulus `index`'s stream
+    fn random_uniform(&mut self, index: usize) -> f32 {
         // Simple LCG for reproducibility
-        self.rng_state = self.rng_state.wrapping_mul(1664525).wrapping_add(1013904223);
-        (self.rng_state as f32) / (u64::MAX as f32)
+        let state = &mut self.stimulus_rng[index];
+        *state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+        (*state as f32) / (u64::MAX as f32)
     }
 
     /// Get reference to network
@@ -506,6 +1308,17 @@ pub fn run_fixed_step(
     engine.run()
 }
 
+/// Built-in monitors followed by user observers, in notification order
+fn observers<'a>(
+    monitors: &'a mut [RateMonitor],
+    weight_monitor: &'a mut Option<WeightMonitor>,
+    added: &'a mut [Box<dyn SimulationObserver>],
+) -> impl Iterator<Item = &'a mut dyn SimulationObserver> {
+    monitors.iter_mut().map(|m| m as &mut dyn SimulationObserver)
+        .chain(weight_monitor.iter_mut().map(|m| m as &mut dyn SimulationObserver))
+        .chain(added.iter_mut().map(|o| o.as_mut() as &mut dyn SimulationObserver))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -561,6 +1374,102 @@ mod tests {
         assert_eq!(result.firing_rate(neuron_id), 2.0); // 2 spikes in 1 second
     }
 
+    #[test]
+    fn test_result_accessors_and_exports() {
+        let mut result = SimulationResult::new(1_000_000_000);
+        result.neuron_count = 4;
+        for (id, ms) in [(2, 30), (0, 10), (2, 20)] {
+            result.spikes.push(Spike::new(NeuronId::new(id), Time::from_millis(ms)));
+        }
+
+        assert_eq!(result.spike_times(NeuronId::new(2)), vec![20_000_000, 30_000_000]);
+        let trains = result.spike_trains();
+        assert_eq!(trains.keys().copied().collect::<Vec<_>>(), vec![NeuronId::new(0), NeuronId::new(2)]);
+
+        let all = result.summary();
+        assert_eq!((all.neurons, all.active_neurons, all.spike_count), (4, 2, 3));
+        assert_eq!(all.mean_rate_hz, 0.75);
+        assert_eq!(all.max_rate_hz, 2.0);
+        let upper = result.population_summary(NeuronId::new(1), NeuronId::new(3));
+        assert_eq!((upper.neurons, upper.active_neurons, upper.spike_count), (3, 1, 2));
+        assert!(result.recorded_potentials().is_none());
+
+        assert_eq!(result.to_csv(), "time_ns,neuron_id\n30000000,2\n10000000,0\n20000000,2\n");
+        let json = result.to_json();
+        assert!(json.starts_with("{\"results\":{\"spike_count\":3,"));
+        assert!(json.contains("{\"neuron_id\":0,\"time_ns\":10000000,\"time_ms\":10}"));
+
+        let (header, events) = shnn_storage::vevt::decode_vevt(&result.to_vevt().unwrap()).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(header.time_start, 10_000_000);
+        assert_eq!(header.time_end, 30_000_000);
+
+        let log = result.to_event_log().unwrap();
+        let spikes = log.stream(StreamId::new(1)).unwrap();
+        assert_eq!(spikes.kind, StreamKind::Spikes);
+        let times: Vec<_> = spikes.events().iter().map(|e| e.timestamp).collect();
+        assert_eq!(times, vec![10_000_000, 20_000_000, 30_000_000]);
+        assert!(log.stream(StreamId::new(2)).is_none());
+    }
+
+    #[test]
+    fn test_weight_update_recording() {
+        let network = NetworkBuilder::new()
+            .add_neurons(0, 2)
+            .add_synapse(NeuronId::new(0), NeuronId::new(1), 0.5, 1.0)
+            .build()
+            .unwrap();
+        let params = SimulationParams::new(100_000, 50_000_000).unwrap().with_weight_update_recording(true);
+        let mut engine = SimulationEngine::new(network, params).unwrap();
+        for neuron in [0, 1] {
+            engine.add_stimulus(StimulusPattern::Constant {
+                neuron: NeuronId::new(neuron),
+                amplitude: 5.0,
+                start_time: 0,
+                duration: 50_000_000,
+            });
+        }
+        let result = engine.run().unwrap();
+        assert!(!result.weight_updates.is_empty());
+        assert!(result.weight_updates.windows(2).all(|w| w[0].time_ns <= w[1].time_ns));
+
+        let log = result.to_event_log().unwrap();
+        let stream = log.stream(StreamId::new(2)).unwrap();
+        assert_eq!(stream.kind, StreamKind::WeightUpdates);
+        let changes: Vec<_> = stream.weight_changes().collect();
+        assert_eq!(changes.len(), result.weight_updates.len());
+        let last = result.weight_updates.last().unwrap();
+        let change = changes.last().unwrap();
+        assert_eq!((change.pre, change.post, change.weight), (0, 1, last.new_weight));
+        assert!((change.delta - (last.new_weight - last.old_weight)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_spike_hash_is_order_independent() {
+        let spikes = [(0, 100), (3, 100), (1, 200)];
+        let result_from = |order: &[usize]| {
+            let mut result = SimulationResult::new(1_000_000_000);
+            for &i in order {
+                let (id, ms) = spikes[i];
+                result.spikes.push(Spike::new(NeuronId::new(id), Time::from_millis(ms)));
+            }
+            result
+        };
+        let a = result_from(&[0, 1, 2]);
+        let b = result_from(&[1, 0, 2]);
+        assert_eq!(a.canonical_spikes(), b.canonical_spikes());
+        assert_eq!(a.spike_hash(), b.spike_hash());
+        assert_ne!(a.spike_hash(), result_from(&[0, 1]).spike_hash());
+        assert_ne!(a.spike_hash(), SimulationResult::new(0).spike_hash());
+
+        assert_eq!(a.first_divergence(&b), None);
+        let shorter = result_from(&[0, 1]);
+        assert_eq!(
+            a.first_divergence(&shorter),
+            Some(SpikeDivergence { index: 2, expected: Some((200_000_000, 1)), actual: None })
+        );
+    }
+
     #[test]
     fn test_stimulus_patterns() {
         let constant = StimulusPattern::Constant {
@@ -591,6 +1500,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_stimulus_streams_are_independent() {
+        let poisson = |neuron| StimulusPattern::Poisson {
+            neuron: NeuronId::new(neuron),
+            rate: 2000.0,
+            amplitude: 100.0,
+            start_time: 0,
+            duration: 10_000_000,
+        };
+        let spikes_of_neuron_0 = |extra: bool| {
+            let network = NetworkBuilder::new().add_neurons(0, 2).build().unwrap();
+            let params = SimulationParams::new(100_000, 10_000_000).unwrap().with_seed(7);
+            let mut engine = SimulationEngine::new(network, params).unwrap();
+            engine.add_stimulus(poisson(0));
+            if extra {
+                engine.add_stimulus(poisson(1));
+            }
+            let result = engine.run().expect("Should run");
+            result.spikes.iter().filter(|s| s.neuron_id == NeuronId::new(0)).map(|s| s.time).collect::<Vec<_>>()
+        };
+
+        let alone = spikes_of_neuron_0(false);
+        assert!(!alone.is_empty());
+        // A second stimulus draws from its own stream instead of interleaving with the first
+        assert_eq!(alone, spikes_of_neuron_0(true));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_philox_runs_match_for_any_thread_count() {
+        let run = |threads: usize| {
+            let network = NetworkBuilder::new().add_neurons(0, 7).build().unwrap();
+            let params = SimulationParams::new(100_000, 10_000_000).unwrap().with_seed(9).with_rng(RngKind::Philox);
+            let mut engine = SimulationEngine::new(network, params).unwrap();
+            for neuron in 0..8 {
+                engine.add_stimulus(StimulusPattern::Poisson {
+                    neuron: NeuronId::new(neuron),
+                    rate: 1500.0,
+                    amplitude: 100.0,
+                    start_time: 0,
+                    duration: 10_000_000,
+                });
+            }
+            engine.add_noise(OuNoise::new(NeuronId::new(0), NeuronId::new(7), 0.5, 1.0, 5.0, 4).unwrap());
+            engine.run_with_threads(threads).expect("Should run").spikes
+        };
+
+        let serial = run(1);
+        assert!(!serial.is_empty());
+        assert_eq!(serial, run(4));
+        assert_eq!(serial, run(3));
+    }
+
     #[test]
     fn test_simulation_engine_creation() {
         let network = NetworkBuilder::new()
@@ -679,5 +1641,272 @@ mod tests {
         
         // Should have recorded potentials
         assert!(!result.potentials.is_empty());
+        let traces = result.recorded_potentials().unwrap();
+        assert_eq!(traces.neurons(), vec![NeuronId::new(0)]);
+        assert_eq!(traces.trace(NeuronId::new(0)).times_ns, vec![0, 100_000, 200_000, 300_000, 400_000]);
+        assert!(traces.trace(NeuronId::new(7)).potentials.is_empty());
+        assert_eq!(result.neuron_count, 1);
+    }
+
+    #[test]
+    fn test_ou_noise_fluctuations() {
+        let network = NetworkBuilder::new().add_neurons(0, 2).build().unwrap();
+        let params = SimulationParams::new(100_000, 20_000_000)
+            .unwrap()
+            .with_potential_recording(true);
+        let mut engine = SimulationEngine::new(network, params).unwrap();
+        engine.add_noise(OuNoise::new(NeuronId::new(1), NeuronId::new(1), 0.5, 0.5, 5.0, 3).unwrap());
+
+        let first = engine.run().unwrap();
+        let trace = |result: &SimulationResult, id: u32| -> Vec<f32> {
+            result.potentials_for_neuron(NeuronId::new(id)).iter().map(|s| s.potential).collect()
+        };
+        // Un-driven neuron stays at rest; the driven one is depolarised and fluctuates
+        assert!(trace(&first, 0).iter().all(|&v| v == -70.0));
+        let noisy = trace(&first, 1);
+        let max = noisy.iter().cloned().fold(f32::MIN, f32::max);
+        let min = noisy.iter().cloned().fold(f32::MAX, f32::min);
+        assert!(max > -70.0 && max - min > 1.0, "min {} max {}", min, max);
+
+        // Noise restarts from its seed on every run
+        let second = engine.run().unwrap();
+        assert_eq!(noisy, trace(&second, 1));
+    }
+
+    #[test]
+    fn test_compressed_recording_matches_plain() {
+        let run = |compress: bool| {
+            let network = NetworkBuilder::new().add_neurons(0, 16).fully_connected(0.3).build().unwrap();
+            let params = SimulationParams::new(100_000, 50_000_000).unwrap().with_spike_compression(compress);
+            let mut engine = SimulationEngine::new(network, params).unwrap();
+            engine.add_noise(OuNoise::new(NeuronId::new(0), NeuronId::new(15), 3.0, 1.0, 5.0, 7).unwrap());
+            let result = engine.run().unwrap();
+            (result, engine.compressed_spikes().len())
+        };
+        let (plain, plain_blocks) = run(false);
+        let (compressed, recorded) = run(true);
+        assert!(!plain.spikes.is_empty());
+        assert_eq!(plain_blocks, 0);
+        assert_eq!(recorded, plain.spikes.len());
+        assert_eq!(compressed.spikes, plain.spikes);
+        assert_eq!(compressed.total_spikes, plain.total_spikes);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_thread_count_does_not_change_spikes() {
+        let run = |threads: usize| {
+            let network = NetworkBuilder::new()
+                .add_neurons(0, 16)
+                .fully_connected(0.3)
+                .build()
+                .unwrap();
+            let params = SimulationParams::new(100_000, 20_000_000).unwrap();
+            let mut engine = SimulationEngine::new(network, params).unwrap();
+            engine.add_noise(OuNoise::new(NeuronId::new(0), NeuronId::new(15), 3.0, 1.0, 5.0, 7).unwrap());
+            engine.run_with_threads(threads).unwrap()
+        };
+        let single = run(1);
+        assert!(!single.spikes.is_empty());
+        assert_eq!(single.first_divergence(&run(4)), None);
+    }
+
+    #[test]
+    fn test_profiler_records_stepping_phases() {
+        let network = NetworkBuilder::new().add_neurons(0, 4).fully_connected(0.3).build().unwrap();
+        let params = SimulationParams::new(100_000, 1_000_000).unwrap();
+        let mut engine = SimulationEngine::new(network, params).unwrap();
+        engine.set_profiler(Profiler::new());
+        engine.run().unwrap();
+
+        let profiler = engine.take_profiler().unwrap();
+        assert_eq!(profiler.spans().len(), 1);
+        assert_eq!(profiler.spans()[0].name, "stepping");
+        for phase in ["stepping.stimuli", "stepping.network", "stepping.monitors", "stepping.recording"] {
+            let total = profiler.totals().iter().find(|t| t.name == phase).unwrap();
+            assert_eq!(total.count, 10);
+        }
+        let phases: u64 = profiler.totals().iter()
+            .filter(|t| t.name.starts_with("stepping."))
+            .map(|t| t.total_ns)
+            .sum();
+        assert!(phases <= profiler.total_ns("stepping"));
+        assert!(engine.take_profiler().is_none());
+    }
+
+    #[test]
+    fn test_stepping_matches_run() {
+        let engine = || {
+            let network = NetworkBuilder::new().add_neurons(0, 8).fully_connected(0.3).build().unwrap();
+            let params = SimulationParams::new(100_000, 20_000_000).unwrap().with_seed(5);
+            let mut engine = SimulationEngine::new(network, params).unwrap();
+            engine.add_noise(OuNoise::new(NeuronId::new(0), NeuronId::new(7), 3.0, 1.0, 5.0, 3).unwrap());
+            engine
+        };
+        let expected = engine().run().unwrap();
+        assert!(!expected.spikes.is_empty());
+
+        let mut stepped = engine();
+        stepped.reset().unwrap();
+        let mut drained = Vec::new();
+        while !stepped.is_finished() {
+            stepped.step().unwrap();
+            drained.extend(stepped.drain_output_spikes());
+        }
+        assert_eq!(stepped.current_step(), 200);
+        assert!(stepped.step().is_err());
+        let result = stepped.finish();
+        assert_eq!(result.first_divergence(&expected), None);
+        assert_eq!(result.steps_executed, expected.steps_executed);
+        assert_eq!(drained.len(), expected.spikes.len());
+        assert!(stepped.drain_output_spikes().is_empty());
+    }
+
+    #[test]
+    fn test_saved_state_resumes_run() {
+        let engine = || {
+            let network = NetworkBuilder::new().add_neurons(0, 4).fully_connected(0.3).build().unwrap();
+            let params = SimulationParams::new(100_000, 20_000_000).unwrap().with_seed(5);
+            let mut engine = SimulationEngine::new(network, params).unwrap();
+            engine.add_noise(OuNoise::new(NeuronId::new(0), NeuronId::new(3), 3.0, 1.0, 5.0, 3).unwrap());
+            engine
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let (mid, end) = (dir.path().join("mid.state"), dir.path().join("end.state"));
+        let mut first = engine();
+        first.save_state_at(20_000_000, &end);
+        first.save_state_at(10_000_000, &mid);
+        first.run().unwrap();
+        let saved = NetworkState::load(&mid).unwrap();
+        assert_eq!(saved.time_ns, 10_000_000);
+        assert_eq!(NetworkState::load(&end).unwrap().neurons, first.network().capture_state().neurons);
+
+        let mut resumed = engine();
+        resumed.set_initial_state(saved.clone()).unwrap();
+        resumed.reset().unwrap();
+        let restored = resumed.network().capture_state();
+        assert_eq!((restored.time_ns, &restored.neurons), (0, &saved.neurons));
+        assert_ne!(restored.neurons, engine().network().capture_state().neurons);
+    }
+
+    #[test]
+    fn test_realtime_run_paces_and_reports() {
+        let network = NetworkBuilder::new().add_neurons(0, 4).build().unwrap();
+        // 20 steps of 1 ms
+        let params = SimulationParams::new(1_000_000, 20_000_000).unwrap().with_realtime(true);
+        let mut engine = SimulationEngine::new(network, params).unwrap();
+        let begin = Instant::now();
+        let result = engine.run().unwrap();
+        assert!(begin.elapsed() >= std::time::Duration::from_millis(20));
+        let report = result.realtime.unwrap();
+        assert_eq!(report.steps, 20);
+
+        let params = SimulationParams::new(1_000_000, 20_000_000).unwrap();
+        let network = NetworkBuilder::new().add_neurons(0, 4).build().unwrap();
+        assert!(SimulationEngine::new(network, params).unwrap().run().unwrap().realtime.is_none());
+    }
+
+    #[test]
+    fn test_control_updates_and_stop() {
+        let poisson = StimulusPattern::Poisson {
+            neuron: NeuronId::new(0),
+            rate: 5000.0,
+            amplitude: 100.0,
+            start_time: 0,
+            duration: 10_000_000,
+        };
+        let run = |control: &SimulationControl| {
+            let network = NetworkBuilder::new().add_neurons(0, 1).build().unwrap();
+            let params = SimulationParams::new(100_000, 10_000_000).unwrap().with_seed(3);
+            let mut engine = SimulationEngine::new(network, params).unwrap();
+            engine.add_stimulus(poisson.clone());
+            engine.set_control(control.clone());
+            let result = engine.run().expect("Should run");
+            (result, engine.poisson_rates())
+        };
+
+        let (baseline, _) = run(&SimulationControl::new());
+        assert!(baseline.total_spikes > 0);
+
+        let silenced = SimulationControl::new();
+        silenced.update(ParamUpdate::StimulusRate { neuron: NeuronId::new(0), rate_hz: 0.0 }).expect("Should queue");
+        let (result, rates) = run(&silenced);
+        assert_eq!(result.total_spikes, 0);
+        assert_eq!(rates, vec![(NeuronId::new(0), 0.0)]);
+        let status = silenced.status();
+        assert!(status.finished);
+        assert_eq!((status.step, status.applied), (100, 1));
+
+        let stopped = SimulationControl::new();
+        stopped.stop();
+        assert_eq!(run(&stopped).0.steps_executed, 0);
+    }
+
+    #[derive(Debug, Default)]
+    struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<Spike>>>);
+
+    impl SpikeSink for Recorder {
+        fn publish(&mut self, spikes: &[Spike]) -> Result<()> {
+            self.0.lock().unwrap().extend_from_slice(spikes);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct OneShot(Option<Spike>);
+
+    impl SpikeSource for OneShot {
+        fn poll(&mut self) -> Result<Vec<Spike>> {
+            Ok(self.0.take().into_iter().collect())
+        }
+    }
+
+    #[test]
+    fn test_streamed_input_and_output() {
+        let network = NetworkBuilder::new()
+            .add_neurons(0, 2)
+            .add_synapse(NeuronId::new(0), NeuronId::new(1), 1000.0, 0.5)
+            .build()
+            .unwrap();
+        let params = SimulationParams::new(100_000, 5_000_000).unwrap();
+        let mut engine = SimulationEngine::new(network, params).unwrap();
+        // Producer timestamps are ignored: the spike arrives at the first step (t = 0)
+        engine.set_spike_source(Box::new(OneShot(Some(Spike::new(NeuronId::new(0), Time::from_millis(900))))));
+        let recorder = Recorder::default();
+        let published = recorder.0.clone();
+        engine.add_spike_sink(Box::new(recorder));
+
+        let result = engine.run().unwrap();
+        let published = published.lock().unwrap();
+        assert!(!published.is_empty());
+        assert_eq!(*published, result.spikes);
+        assert_eq!(published[0].neuron_id, NeuronId::new(1));
+        assert!(published[0].time.nanos() >= 500_000);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_injected_spikes_drive_targets() {
+        let network = NetworkBuilder::new()
+            .add_neurons(0, 2)
+            .add_synapse(NeuronId::new(0), NeuronId::new(1), 1000.0, 0.5)
+            .build()
+            .unwrap();
+        let params = SimulationParams::new(100_000, 5_000_000).unwrap();
+        let mut engine = SimulationEngine::new(network, params).unwrap();
+        engine.reset().unwrap();
+
+        engine.inject_spikes(&[Spike::new(NeuronId::new(0), Time::from_nanos(0))]).unwrap();
+        let mut fired = Vec::new();
+        while !engine.is_finished() && fired.is_empty() {
+            engine.step().unwrap();
+            fired = engine.drain_output_spikes();
+        }
+        // Only the target fires, once the 0.5 ms delay has elapsed
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].neuron_id, NeuronId::new(1));
+        assert!(fired[0].time.nanos() >= 500_000);
+
+        let missing = Spike::new(NeuronId::new(9), Time::from_nanos(0));
+        assert!(engine.inject_spikes(&[missing]).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+//! Spatial neuron positions and distance-dependent connectivity
+//!
+//! [`NeuronPositions`] places neurons in 3-D space (2-D layouts keep z = 0) in
+//! an arbitrary length unit, µm by convention. [`DistanceRule`] wires a source
+//! population to a target population with probability
+//! `p_max * exp(-d² / 2σ²)`; the weight is either constant or scaled by the
+//! same Gaussian. Pairs are drawn source-major, target-minor from one seeded
+//! stream, so a seed fixes the whole connection pattern.
+
+use crate::{error::{Result, RuntimeError}, NeuronId};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// `[x, y, z]` coordinates
+pub type Position = [f32; 3];
+
+/// Squared Euclidean distance between two positions
+pub fn distance_squared(a: Position, b: Position) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Neuron coordinates, ordered by neuron id
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NeuronPositions {
+    positions: BTreeMap<NeuronId, Position>,
+}
+
+impl NeuronPositions {
+    /// Empty layout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `nx * ny * nz` neurons from `start` on a regular grid, x varying fastest
+    pub fn grid(start: NeuronId, shape: [u32; 3], spacing: f32) -> Result<Self> {
+        if shape.contains(&0) {
+            return Err(invalid("shape", &format!("{:?}", shape), "every dimension >= 1"));
+        }
+        if !(spacing.is_finite() && spacing > 0.0) {
+            return Err(invalid("spacing", &spacing.to_string(), "> 0 and finite"));
+        }
+        let count = shape.iter().try_fold(1u32, |acc, &n| acc.checked_mul(n))
+            .filter(|count| start.raw().checked_add(count - 1).is_some())
+            .ok_or_else(|| invalid("shape", &format!("{:?}", shape), "neuron ids within u32"))?;
+        let [nx, ny, _] = shape;
+        let positions = (0..count)
+            .map(|i| {
+                let (x, y, z) = (i % nx, (i / nx) % ny, i / (nx * ny));
+                let position = [x as f32 * spacing, y as f32 * spacing, z as f32 * spacing];
+                (NeuronId::new(start.raw() + i), position)
+            })
+            .collect();
+        Ok(Self { positions })
+    }
+
+    /// Place `id`, returning its previous position
+    pub fn insert(&mut self, id: NeuronId, position: Position) -> Option<Position> {
+        self.positions.insert(id, position)
+    }
+
+    /// Merge `other` into this layout (its positions win)
+    pub fn extend(&mut self, other: NeuronPositions) {
+        self.positions.extend(other.positions);
+    }
+
+    /// Position of `id`
+    pub fn get(&self, id: NeuronId) -> Option<Position> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Number of placed neurons
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether no neuron is placed
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Placed neurons in ascending id order
+    pub fn iter(&self) -> impl Iterator<Item = (NeuronId, Position)> + '_ {
+        self.positions.iter().map(|(&id, &position)| (id, position))
+    }
+
+    fn require(&self, id: u32) -> Result<Position> {
+        self.get(NeuronId::new(id))
+            .ok_or_else(|| invalid("positions", &id.to_string(), "a position for every connected neuron"))
+    }
+}
+
+/// How a distance-dependent connection's weight varies with distance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightProfile {
+    /// The rule's weight for every connection
+    #[default]
+    Constant,
+    /// The rule's weight scaled by `exp(-d² / 2σ²)`
+    Gaussian,
+}
+
+impl WeightProfile {
+    /// Attribute spelling
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WeightProfile::Constant => "constant",
+            WeightProfile::Gaussian => "gaussian",
+        }
+    }
+}
+
+impl FromStr for WeightProfile {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "constant" => Ok(WeightProfile::Constant),
+            "gaussian" => Ok(WeightProfile::Gaussian),
+            other => Err(invalid("weight_profile", other, "constant | gaussian")),
+        }
+    }
+}
+
+/// Gaussian distance-dependent connection rule
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistanceRule {
+    /// Length scale σ, in the positions' unit
+    pub sigma: f32,
+    /// Connection probability at zero distance
+    pub p_max: f32,
+    /// Peak synaptic weight
+    pub weight: f32,
+    /// Weight dependence on distance
+    pub profile: WeightProfile,
+}
+
+impl DistanceRule {
+    /// Check σ > 0 and p_max within [0, 1]
+    pub fn validate(&self) -> Result<()> {
+        if !(self.sigma.is_finite() && self.sigma > 0.0) {
+            return Err(invalid("sigma", &self.sigma.to_string(), "> 0 and finite"));
+        }
+        if !(0.0..=1.0).contains(&self.p_max) {
+            return Err(invalid("p_max", &self.p_max.to_string(), "within [0, 1]"));
+        }
+        Ok(())
+    }
+
+    /// `exp(-d² / 2σ²)` for squared distance `d2`
+    pub fn falloff(&self, d2: f32) -> f32 {
+        (-d2 / (2.0 * self.sigma * self.sigma)).exp()
+    }
+
+    /// Connection probability at squared distance `d2`
+    pub fn probability(&self, d2: f32) -> f32 {
+        self.p_max * self.falloff(d2)
+    }
+
+    /// Synaptic weight at squared distance `d2`
+    pub fn weight_at(&self, d2: f32) -> f32 {
+        match self.profile {
+            WeightProfile::Constant => self.weight,
+            WeightProfile::Gaussian => self.weight * self.falloff(d2),
+        }
+    }
+
+    /// Draw `(pre, post, weight)` connections from `sources` to `targets`
+    ///
+    /// Self-pairs are skipped; every other neuron in both ranges needs a position.
+    pub fn connect(
+        &self,
+        positions: &NeuronPositions,
+        sources: RangeInclusive<u32>,
+        targets: RangeInclusive<u32>,
+        seed: u64,
+    ) -> Result<Vec<(NeuronId, NeuronId, f32)>> {
+        self.validate()?;
+        let targets: Vec<(u32, Position)> = targets
+            .map(|id| Ok((id, positions.require(id)?)))
+            .collect::<Result<_>>()?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut connections = Vec::new();
+        for pre in sources {
+            let from = positions.require(pre)?;
+            for &(post, to) in &targets {
+                if pre == post {
+                    continue;
+                }
+                let d2 = distance_squared(from, to);
+                if rng.gen::<f32>() < self.probability(d2) {
+                    connections.push((NeuronId::new(pre), NeuronId::new(post), self.weight_at(d2)));
+                }
+            }
+        }
+        Ok(connections)
+    }
+}
+
+fn invalid(parameter: &str, value: &str, constraint: &str) -> RuntimeError {
+    RuntimeError::invalid_parameter(parameter, value, constraint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_layout() {
+        let grid = NeuronPositions::grid(NeuronId::new(10), [3, 2, 2], 5.0).expect("Should build grid");
+        assert_eq!(grid.len(), 12);
+        assert_eq!(grid.get(NeuronId::new(10)), Some([0.0, 0.0, 0.0]));
+        assert_eq!(grid.get(NeuronId::new(12)), Some([10.0, 0.0, 0.0]));
+        assert_eq!(grid.get(NeuronId::new(13)), Some([0.0, 5.0, 0.0]));
+        assert_eq!(grid.get(NeuronId::new(21)), Some([10.0, 5.0, 5.0]));
+        assert!(NeuronPositions::grid(NeuronId::new(0), [3, 0, 1], 1.0).is_err());
+        assert!(NeuronPositions::grid(NeuronId::new(u32::MAX), [2, 1, 1], 1.0).is_err());
+    }
+
+    #[test]
+    fn test_distance_rule_prefers_neighbours() {
+        let positions = NeuronPositions::grid(NeuronId::new(0), [100, 1, 1], 1.0).expect("Should build grid");
+        let rule = DistanceRule { sigma: 2.0, p_max: 1.0, weight: 0.5, profile: WeightProfile::Gaussian };
+        let connections = rule.connect(&positions, 0..=99, 0..=99, 7).expect("Should connect");
+        assert_eq!(connections, rule.connect(&positions, 0..=99, 0..=99, 7).expect("Should connect"));
+        assert!(connections.iter().all(|(pre, post, _)| pre != post && pre.raw().abs_diff(post.raw()) <= 10));
+        assert!(connections.iter().filter(|(pre, post, _)| pre.raw().abs_diff(post.raw()) == 1).count() > 150);
+        let (_, _, weight) = connections.iter()
+            .find(|(pre, post, _)| post.raw() == pre.raw() + 1)
+            .expect("Should connect a neighbour");
+        assert!((weight - 0.5 * (-1.0f32 / 8.0).exp()).abs() < 1e-6);
+
+        let never = DistanceRule { p_max: 0.0, ..rule };
+        assert!(never.connect(&positions, 0..=99, 0..=99, 7).expect("Should connect").is_empty());
+        assert!(rule.connect(&positions, 0..=100, 0..=99, 7).is_err());
+        assert!(DistanceRule { sigma: 0.0, ..rule }.validate().is_err());
+        assert_eq!("gaussian".parse::<WeightProfile>().expect("Should parse"), WeightProfile::Gaussian);
+    }
+}
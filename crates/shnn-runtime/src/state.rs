@@ -0,0 +1,140 @@
+//! Network state checkpoints
+//!
+//! A [`NetworkState`] is the dynamic state of a network at one instant: each
+//! neuron's membrane potential and model variables (AdEx `w`, HH gates) and
+//! every synapse weight. It is stored as text, one record per line:
+//!
+//! ```text
+//! shnn-state v1
+//! time_ns 250000000
+//! neuron 0 -64.2
+//! neuron 1 -58.9 0.031
+//! synapse 0 1 0.52
+//! ```
+//!
+//! Spikes in flight, refractory timers and plasticity traces are not saved;
+//! a restored network starts with none.
+
+use crate::{error::{Result, RuntimeError}, NeuronId};
+
+use core::fmt::Write as _;
+
+const HEADER: &str = "shnn-state v1";
+
+/// Saved state of one neuron
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeuronSnapshot {
+    /// Neuron ID
+    pub id: NeuronId,
+    /// Membrane potential (mV)
+    pub v_m: f32,
+    /// Model variables ([`crate::Neuron::model_vars`])
+    pub vars: Vec<f32>,
+}
+
+/// Neuron potentials, model variables and synapse weights at one time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkState {
+    /// Network time when the state was captured (ns)
+    pub time_ns: u64,
+    /// Neurons, by ascending ID
+    pub neurons: Vec<NeuronSnapshot>,
+    /// Synapse weights as (pre, post, weight), by ascending (pre, post)
+    pub synapses: Vec<(NeuronId, NeuronId, f32)>,
+}
+
+impl NetworkState {
+    /// Encode in the text format
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{}\ntime_ns {}\n", HEADER, self.time_ns);
+        for neuron in &self.neurons {
+            let _ = write!(out, "neuron {} {}", neuron.id.raw(), neuron.v_m);
+            for var in &neuron.vars {
+                let _ = write!(out, " {}", var);
+            }
+            out.push('\n');
+        }
+        for (pre, post, weight) in &self.synapses {
+            let _ = writeln!(out, "synapse {} {} {}", pre.raw(), post.raw(), weight);
+        }
+        out
+    }
+
+    /// Decode the text format
+    pub fn from_text(text: &str) -> Result<Self> {
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        if lines.next().map(|(_, l)| l.trim()) != Some(HEADER) {
+            return Err(RuntimeError::invalid_config(format!("not a state checkpoint (expected '{}' header)", HEADER)));
+        }
+        let mut state = NetworkState::default();
+        for (index, line) in lines {
+            let bad = |what: String| RuntimeError::invalid_config(format!("state line {}: {}", index + 1, what));
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let id = |f: &str| f.parse::<u32>().map(NeuronId::new).map_err(|_| bad(format!("'{}' is not a neuron id", f)));
+            let value = |f: &str| f.parse::<f32>().map_err(|_| bad(format!("'{}' is not a number", f)));
+            match fields.as_slice() {
+                ["time_ns", t] => state.time_ns = t.parse().map_err(|_| bad(format!("'{}' is not a time", t)))?,
+                ["neuron", n, v_m, vars @ ..] => state.neurons.push(NeuronSnapshot {
+                    id: id(n)?,
+                    v_m: value(v_m)?,
+                    vars: vars.iter().map(|v| value(v)).collect::<Result<_>>()?,
+                }),
+                ["synapse", pre, post, weight] => state.synapses.push((id(pre)?, id(post)?, value(weight)?)),
+                _ => return Err(bad(format!("unrecognized record '{}'", line.trim()))),
+            }
+        }
+        Ok(state)
+    }
+
+    /// Read a checkpoint file
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let text = std::fs::read_to_string(path.as_ref()).map_err(shnn_storage::StorageError::from)?;
+        Self::from_text(&text)
+    }
+
+    /// Write a checkpoint file
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path.as_ref(), self.to_text()).map_err(shnn_storage::StorageError::from)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::NetworkBuilder;
+    use crate::neuron::{AdExParams, NeuronModel};
+
+    #[test]
+    fn test_state_round_trip_and_restore() {
+        let mut network = NetworkBuilder::new()
+            .add_neurons(0, 2)
+            .add_neuron_with_model(NeuronId::new(2), NeuronModel::AdEx(AdExParams::default()))
+            .add_synapse(NeuronId::new(0), NeuronId::new(2), 0.5, 1.0)
+            .build()
+            .unwrap();
+        for _ in 0..20 {
+            network.apply_input(NeuronId::new(2), 0.8).unwrap();
+            network.step(0.1).unwrap();
+        }
+        network.set_weight(NeuronId::new(0), NeuronId::new(2), 0.75).unwrap();
+        let state = network.capture_state();
+        assert_eq!(state.neurons.len(), 3);
+        assert_eq!(state.neurons[2].vars.len(), 1);
+        assert_eq!(NetworkState::from_text(&state.to_text()).unwrap(), state);
+
+        network.reset().unwrap();
+        network.set_weight(NeuronId::new(0), NeuronId::new(2), 0.5).unwrap();
+        network.restore_state(&state).unwrap();
+        let restored = network.capture_state();
+        assert_eq!((restored.neurons, restored.synapses), (state.neurons.clone(), state.synapses.clone()));
+
+        let mut missing = state.clone();
+        missing.synapses.push((NeuronId::new(2), NeuronId::new(0), 1.0));
+        assert!(network.restore_state(&missing).is_err());
+        assert!(NetworkState::from_text("shnn-state v1\nneuron x 1\n").is_err());
+        assert!(NetworkState::from_text("time_ns 0\n").is_err());
+    }
+}
@@ -0,0 +1,360 @@
+//! Spike streaming over UDP/TCP
+//!
+//! Wire protocol: every message is one frame, a little-endian `u32` byte
+//! length followed by a VEVT blob (header + spike events, `event_type` 0,
+//! `source_id` = neuron). Over UDP each datagram carries exactly one frame;
+//! over TCP frames are sent back to back on the connection.
+//!
+//! A [`SpikeSource`] attached to an engine is polled (without blocking) before
+//! every step and its spikes are injected at the current simulation time,
+//! whatever timestamps the producer used. Every [`SpikeSink`] receives the
+//! spikes of each step that emitted any.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+
+use shnn_storage::vevt::{decode_vevt, encode_vevt, VEVTEvent};
+use shnn_storage::{StorageError, StreamId};
+
+use crate::{error::*, NeuronId, Spike, Time};
+
+/// Largest accepted frame body (bytes)
+pub const MAX_FRAME_BYTES: usize = 16 << 20;
+
+/// Encode spikes as one length-prefixed VEVT frame
+pub fn encode_frame(spikes: &[Spike]) -> Result<Vec<u8>> {
+    let events: Vec<VEVTEvent> = spikes.iter().map(|spike| VEVTEvent {
+        timestamp: spike.time.nanos(),
+        event_type: 0,
+        source_id: spike.neuron_id.raw(),
+        target_id: u32::MAX,
+        payload_size: 0,
+        reserved: 0,
+    }).collect();
+    let start = spikes.iter().map(|s| s.time).min().unwrap_or(Time::ZERO);
+    let end = spikes.iter().map(|s| s.time).max().unwrap_or(Time::ZERO);
+    let body = encode_vevt(StreamId::new(1), start, end, &events)?;
+    let mut frame = Vec::with_capacity(4 + body.len());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+/// Decode one complete frame (e.g. a UDP datagram); non-spike events are skipped
+pub fn decode_frame(frame: &[u8]) -> Result<Vec<Spike>> {
+    let Some(length) = frame.get(..4) else {
+        return Err(invalid_frame("frame shorter than its length prefix"));
+    };
+    let length = u32::from_le_bytes([length[0], length[1], length[2], length[3]]) as usize;
+    if frame.len() - 4 != length {
+        return Err(invalid_frame(format!("length prefix {} but {} body bytes", length, frame.len() - 4)));
+    }
+    decode_body(&frame[4..])
+}
+
+fn decode_body(body: &[u8]) -> Result<Vec<Spike>> {
+    let (_, events) = decode_vevt(body)?;
+    Ok(events
+        .into_iter()
+        .filter(|e| e.event_type == 0 && e.source_id != u32::MAX)
+        .map(|e| Spike::new(NeuronId::new(e.source_id), Time::from_nanos(e.timestamp)))
+        .collect())
+}
+
+fn invalid_frame(reason: impl Into<String>) -> RuntimeError {
+    StorageError::InvalidFormat { reason: reason.into() }.into()
+}
+
+fn io_error(e: std::io::Error) -> RuntimeError {
+    StorageError::from(e).into()
+}
+
+/// Reassembles frames from a byte stream (TCP)
+#[derive(Debug, Default, Clone)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+}
+
+impl FrameDecoder {
+    /// Empty decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append received bytes and return the spikes of every frame completed by them
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Vec<Spike>> {
+        self.buffer.extend_from_slice(bytes);
+        let mut spikes = Vec::new();
+        let mut consumed = 0;
+        while let Some(prefix) = self.buffer.get(consumed..consumed + 4) {
+            let length = u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+            if length > MAX_FRAME_BYTES {
+                return Err(invalid_frame(format!("frame of {} bytes exceeds {}", length, MAX_FRAME_BYTES)));
+            }
+            let Some(body) = self.buffer.get(consumed + 4..consumed + 4 + length) else { break };
+            spikes.extend(decode_body(body)?);
+            consumed += 4 + length;
+        }
+        self.buffer.drain(..consumed);
+        Ok(spikes)
+    }
+}
+
+/// Producer of external input spikes
+pub trait SpikeSource: std::fmt::Debug + Send {
+    /// Spikes received since the last poll; must not block
+    fn poll(&mut self) -> Result<Vec<Spike>>;
+}
+
+/// Consumer of the spikes emitted during a run
+pub trait SpikeSink: std::fmt::Debug + Send {
+    /// Deliver the spikes of one step
+    fn publish(&mut self, spikes: &[Spike]) -> Result<()>;
+}
+
+/// Receives one frame per datagram on a bound UDP socket
+#[derive(Debug)]
+pub struct UdpSpikeSource {
+    socket: UdpSocket,
+    buffer: Vec<u8>,
+}
+
+impl UdpSpikeSource {
+    /// Listen on `addr`
+    pub fn bind(addr: impl std::net::ToSocketAddrs) -> Result<Self> {
+        let socket = UdpSocket::bind(addr).map_err(io_error)?;
+        socket.set_nonblocking(true).map_err(io_error)?;
+        Ok(Self { socket, buffer: vec![0; 65_536] })
+    }
+
+    /// Bound address (useful after binding port 0)
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.socket.local_addr().map_err(io_error)
+    }
+}
+
+impl SpikeSource for UdpSpikeSource {
+    fn poll(&mut self) -> Result<Vec<Spike>> {
+        let mut spikes = Vec::new();
+        loop {
+            match self.socket.recv(&mut self.buffer) {
+                Ok(len) => match decode_frame(&self.buffer[..len]) {
+                    Ok(frame) => spikes.extend(frame),
+                    // A corrupt datagram is dropped; the stream goes on
+                    Err(e) => log::warn!("Dropping malformed spike datagram: {}", e),
+                },
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(spikes),
+                Err(e) => return Err(io_error(e)),
+            }
+        }
+    }
+}
+
+/// Reads frames from a TCP connection to a producer
+#[derive(Debug)]
+pub struct TcpSpikeSource {
+    stream: TcpStream,
+    decoder: FrameDecoder,
+}
+
+impl TcpSpikeSource {
+    /// Connect to a producer at `addr`
+    pub fn connect(addr: impl std::net::ToSocketAddrs) -> Result<Self> {
+        let stream = TcpStream::connect(addr).map_err(io_error)?;
+        stream.set_nonblocking(true).map_err(io_error)?;
+        Ok(Self { stream, decoder: FrameDecoder::new() })
+    }
+}
+
+impl SpikeSource for TcpSpikeSource {
+    fn poll(&mut self) -> Result<Vec<Spike>> {
+        let mut spikes = Vec::new();
+        let mut chunk = [0u8; 8192];
+        loop {
+            match self.stream.read(&mut chunk) {
+                // Producer closed the connection: no more input
+                Ok(0) => return Ok(spikes),
+                Ok(len) => spikes.extend(self.decoder.push(&chunk[..len])?),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(spikes),
+                Err(e) => return Err(io_error(e)),
+            }
+        }
+    }
+}
+
+/// Sends one frame per step to a UDP peer
+#[derive(Debug)]
+pub struct UdpSpikePublisher {
+    socket: UdpSocket,
+    target: SocketAddr,
+}
+
+impl UdpSpikePublisher {
+    /// Publish to `target` from an ephemeral local port
+    pub fn new(target: SocketAddr) -> Result<Self> {
+        let local: SocketAddr = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }.parse().unwrap();
+        let socket = UdpSocket::bind(local).map_err(io_error)?;
+        Ok(Self { socket, target })
+    }
+}
+
+impl SpikeSink for UdpSpikePublisher {
+    fn publish(&mut self, spikes: &[Spike]) -> Result<()> {
+        self.socket.send_to(&encode_frame(spikes)?, self.target).map_err(io_error)?;
+        Ok(())
+    }
+}
+
+/// Accepts TCP subscribers and sends every frame to all of them
+#[derive(Debug)]
+pub struct TcpSpikePublisher {
+    listener: TcpListener,
+    subscribers: Vec<TcpStream>,
+}
+
+impl TcpSpikePublisher {
+    /// Listen for subscribers on `addr`
+    pub fn bind(addr: impl std::net::ToSocketAddrs) -> Result<Self> {
+        let listener = TcpListener::bind(addr).map_err(io_error)?;
+        listener.set_nonblocking(true).map_err(io_error)?;
+        Ok(Self { listener, subscribers: Vec::new() })
+    }
+
+    /// Bound address (useful after binding port 0)
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(io_error)
+    }
+
+    /// Connected subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Accept every pending connection
+    pub fn accept_pending(&mut self) -> Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, peer)) => {
+                    stream.set_nonblocking(false).map_err(io_error)?;
+                    stream.set_nodelay(true).map_err(io_error)?;
+                    log::info!("Spike subscriber connected: {}", peer);
+                    self.subscribers.push(stream);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(io_error(e)),
+            }
+        }
+    }
+}
+
+impl SpikeSink for TcpSpikePublisher {
+    fn publish(&mut self, spikes: &[Spike]) -> Result<()> {
+        self.accept_pending()?;
+        let frame = encode_frame(spikes)?;
+        // Subscribers that hung up are dropped without failing the run
+        self.subscribers.retain_mut(|stream| stream.write_all(&frame).is_ok());
+        Ok(())
+    }
+}
+
+/// `udp://host:port` or `tcp://host:port`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpikeEndpoint {
+    /// UDP datagrams
+    Udp(SocketAddr),
+    /// TCP stream
+    Tcp(SocketAddr),
+}
+
+impl std::str::FromStr for SpikeEndpoint {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (scheme, addr) = s.split_once("://").ok_or_else(|| {
+            RuntimeError::invalid_parameter("endpoint", s, "udp://host:port or tcp://host:port")
+        })?;
+        let addr: SocketAddr = addr
+            .parse()
+            .map_err(|_| RuntimeError::invalid_parameter("endpoint", s, "a socket address such as 127.0.0.1:9000"))?;
+        match scheme {
+            "udp" => Ok(Self::Udp(addr)),
+            "tcp" => Ok(Self::Tcp(addr)),
+            _ => Err(RuntimeError::invalid_parameter("endpoint", s, "scheme udp or tcp")),
+        }
+    }
+}
+
+impl SpikeEndpoint {
+    /// Input from this endpoint: bind for UDP, connect to a producer for TCP
+    pub fn open_source(self) -> Result<Box<dyn SpikeSource>> {
+        Ok(match self {
+            Self::Udp(addr) => Box::new(UdpSpikeSource::bind(addr)?),
+            Self::Tcp(addr) => Box::new(TcpSpikeSource::connect(addr)?),
+        })
+    }
+
+    /// Output to this endpoint: send to a UDP peer, accept subscribers for TCP
+    pub fn open_sink(self) -> Result<Box<dyn SpikeSink>> {
+        Ok(match self {
+            Self::Udp(addr) => Box::new(UdpSpikePublisher::new(addr)?),
+            Self::Tcp(addr) => Box::new(TcpSpikePublisher::bind(addr)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spikes() -> Vec<Spike> {
+        vec![
+            Spike::new(NeuronId::new(3), Time::from_nanos(100)),
+            Spike::new(NeuronId::new(7), Time::from_nanos(250)),
+        ]
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let frame = encode_frame(&spikes()).unwrap();
+        assert_eq!(decode_frame(&frame).unwrap(), spikes());
+        assert!(decode_frame(&frame[..frame.len() - 1]).is_err());
+        assert!(decode_frame(&[1, 0]).is_err());
+    }
+
+    #[test]
+    fn test_frame_decoder_reassembles_split_stream() {
+        let mut bytes = encode_frame(&spikes()).unwrap();
+        bytes.extend(encode_frame(&spikes()[..1]).unwrap());
+        let mut decoder = FrameDecoder::new();
+        let (head, tail) = bytes.split_at(7);
+        assert!(decoder.push(head).unwrap().is_empty());
+        assert_eq!(decoder.push(tail).unwrap().len(), 3);
+        assert!(decoder.push(&(u32::MAX).to_le_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_udp_source_receives_datagrams() {
+        let mut source = UdpSpikeSource::bind("127.0.0.1:0").unwrap();
+        let mut publisher = UdpSpikePublisher::new(source.local_addr().unwrap()).unwrap();
+        publisher.publish(&spikes()).unwrap();
+        let mut received = Vec::new();
+        for _ in 0..100 {
+            received.extend(source.poll().unwrap());
+            if !received.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(received, spikes());
+    }
+
+    #[test]
+    fn test_endpoint_parsing() {
+        assert_eq!(
+            "tcp://127.0.0.1:9000".parse::<SpikeEndpoint>().unwrap(),
+            SpikeEndpoint::Tcp("127.0.0.1:9000".parse().unwrap())
+        );
+        assert!("udp://localhost".parse::<SpikeEndpoint>().is_err());
+        assert!("http://127.0.0.1:9000".parse::<SpikeEndpoint>().is_err());
+    }
+}
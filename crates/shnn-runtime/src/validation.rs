@@ -0,0 +1,451 @@
+//! Reference-model validation against analytic solutions
+//!
+//! Each [`ValidationScenario`] runs a canonical setup through the runtime and
+//! compares what it measures with a closed-form answer:
+//!
+//! - `lif-fi`: single LIF neurons under DC current. Above rheobase the steady
+//!   rate is 1 / (t_ref + tau_m ln((V∞ - V_reset) / (V∞ - V_th))) with
+//!   V∞ = V_rest + R I; below it the neuron stays silent.
+//! - `poisson-rate`: LIF neurons driven by suprathreshold Poisson pulses fire
+//!   once per pulse outside refractoriness, at λ / (1 + λ t_ref).
+//! - `stdp-pair`: a single pre/post pair changes the weight by A+ e^(-Δt/τ+)
+//!   when pre leads and by -A- e^(Δt/τ-) when post leads.
+//!
+//! Tolerances cover spike times snapping to the step grid and, for Poisson
+//! input, sampling error (four standard errors), so a failing check points at
+//! the numerics rather than at chance.
+
+use crate::{
+    error::{Result, RuntimeError},
+    integrator::IntegratorKind,
+    network::{NetworkConfig, SNNNetwork},
+    neuron::LIFParams,
+    plasticity::{STDPParams, STDPRule, SynapseId},
+    simulation::{SimulationEngine, SimulationParams, SimulationResult, StimulusPattern},
+    NeuronId, Time,
+};
+
+use core::str::FromStr;
+use std::collections::HashMap;
+
+/// DC currents of the f-I curve, as multiples of the rheobase current
+const FI_RHEOBASE_MULTIPLES: [f64; 6] = [0.75, 1.25, 1.5, 2.0, 3.0, 5.0];
+/// Simulated time per f-I point (ms)
+const FI_DURATION_MS: f64 = 1_000.0;
+/// Allowed relative f-I error on top of step-grid quantization
+const FI_REL_TOLERANCE: f64 = 0.02;
+/// Input rate of the Poisson scenario (Hz)
+const POISSON_RATE_HZ: f64 = 100.0;
+/// Standard errors a Poisson rate may stray before the check fails
+const POISSON_STANDARD_ERRORS: f64 = 4.0;
+/// Pair intervals of the STDP protocol, as multiples of the time constants
+const STDP_TAU_MULTIPLES: [f64; 4] = [0.25, 0.5, 1.0, 2.0];
+/// Pre spike time of every STDP pair (ms), far from 0 so intervals come from absolute times
+const STDP_PAIR_START_MS: f64 = 1_000.0;
+
+/// Canonical scenario with a known analytic answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationScenario {
+    /// LIF firing rate under DC current (f-I curve)
+    LifFiCurve,
+    /// Stationary rate of Poisson-driven LIF neurons
+    PoissonRate,
+    /// STDP weight change of a single spike pair
+    StdpPair,
+}
+
+impl ValidationScenario {
+    /// Every scenario, in report order
+    pub const ALL: [ValidationScenario; 3] = [
+        ValidationScenario::LifFiCurve,
+        ValidationScenario::PoissonRate,
+        ValidationScenario::StdpPair,
+    ];
+
+    /// Canonical name used on the command line and in reports
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ValidationScenario::LifFiCurve => "lif-fi",
+            ValidationScenario::PoissonRate => "poisson-rate",
+            ValidationScenario::StdpPair => "stdp-pair",
+        }
+    }
+
+    /// One-line description of what is compared
+    pub fn description(self) -> &'static str {
+        match self {
+            ValidationScenario::LifFiCurve => "LIF steady firing rate under DC current vs. closed-form f-I curve",
+            ValidationScenario::PoissonRate => "Poisson-driven LIF rate vs. dead-time Poisson process",
+            ValidationScenario::StdpPair => "single-pair STDP weight change vs. exponential window",
+        }
+    }
+}
+
+impl FromStr for ValidationScenario {
+    type Err = RuntimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|scenario| scenario.as_str() == s)
+            .ok_or_else(|| RuntimeError::invalid_parameter("scenario", s, "lif-fi | poisson-rate | stdp-pair"))
+    }
+}
+
+/// Step, integrator and model parameters the scenarios run with
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationConfig {
+    /// Simulation step (ns)
+    pub dt_ns: u64,
+    /// Membrane integrator
+    pub integrator: IntegratorKind,
+    /// Simulated time of the Poisson scenario (ns)
+    pub poisson_duration_ns: u64,
+    /// Stimulus seed of the Poisson scenario
+    pub seed: u64,
+    /// Neuron parameters (the Poisson scenario also runs a copy without refractoriness)
+    pub lif: LIFParams,
+    /// STDP parameters
+    pub stdp: STDPParams,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            dt_ns: 100_000,
+            integrator: IntegratorKind::default(),
+            poisson_duration_ns: 20_000_000_000,
+            seed: 42,
+            lif: LIFParams::default(),
+            stdp: STDPParams::default(),
+        }
+    }
+}
+
+impl ValidationConfig {
+    /// Reject a zero step or duration and invalid model parameters
+    pub fn validate(&self) -> Result<()> {
+        if self.dt_ns == 0 {
+            return Err(RuntimeError::invalid_parameter("dt_ns", "0", "> 0"));
+        }
+        if self.poisson_duration_ns < self.dt_ns {
+            return Err(RuntimeError::invalid_parameter(
+                "poisson_duration_ns",
+                self.poisson_duration_ns.to_string(),
+                ">= dt_ns",
+            ));
+        }
+        self.lif.validate()?;
+        self.stdp.validate()
+    }
+
+    fn dt_ms(&self) -> f64 {
+        self.dt_ns as f64 / 1e6
+    }
+}
+
+/// One measured quantity and its analytic reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationCheck {
+    /// What was measured, e.g. `I=4nA`
+    pub name: String,
+    /// Unit of `expected`, `measured` and `tolerance`
+    pub unit: &'static str,
+    /// Analytic value
+    pub expected: f64,
+    /// Simulated value
+    pub measured: f64,
+    /// Largest accepted |measured - expected|
+    pub tolerance: f64,
+}
+
+impl ValidationCheck {
+    /// measured - expected
+    pub fn deviation(&self) -> f64 {
+        self.measured - self.expected
+    }
+
+    /// Deviation relative to the expected value (`None` if that is 0)
+    pub fn relative_deviation(&self) -> Option<f64> {
+        (self.expected != 0.0).then(|| self.deviation() / self.expected.abs())
+    }
+
+    /// Whether the deviation is within tolerance
+    pub fn passed(&self) -> bool {
+        self.deviation().abs() <= self.tolerance
+    }
+}
+
+/// Checks of one scenario run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioOutcome {
+    /// Scenario that ran
+    pub scenario: ValidationScenario,
+    /// One check per measured point
+    pub checks: Vec<ValidationCheck>,
+}
+
+impl ScenarioOutcome {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(ValidationCheck::passed)
+    }
+
+    /// Checks outside tolerance
+    pub fn failures(&self) -> impl Iterator<Item = &ValidationCheck> {
+        self.checks.iter().filter(|check| !check.passed())
+    }
+}
+
+/// Run one scenario
+pub fn run_scenario(scenario: ValidationScenario, config: &ValidationConfig) -> Result<ScenarioOutcome> {
+    config.validate()?;
+    let checks = match scenario {
+        ValidationScenario::LifFiCurve => lif_fi_curve(config)?,
+        ValidationScenario::PoissonRate => poisson_rate(config)?,
+        ValidationScenario::StdpPair => stdp_pair(config)?,
+    };
+    Ok(ScenarioOutcome { scenario, checks })
+}
+
+/// Steady LIF firing rate (Hz) under a constant current (nA); 0 at or below rheobase
+pub fn lif_rate_hz(params: &LIFParams, current_na: f64) -> f64 {
+    let v_inf = params.v_rest as f64 + params.r_m as f64 * current_na;
+    let v_thresh = params.v_thresh as f64;
+    if v_inf <= v_thresh {
+        return 0.0;
+    }
+    let charge_ms = params.tau_m as f64 * ((v_inf - params.v_reset as f64) / (v_inf - v_thresh)).ln();
+    1_000.0 / (params.t_refrac as f64 + charge_ms)
+}
+
+/// Rate (Hz) of a Poisson process of `input_hz` with a dead time of `t_refrac_ms` after each event
+pub fn dead_time_rate_hz(input_hz: f64, t_refrac_ms: f64) -> f64 {
+    input_hz / (1.0 + input_hz * t_refrac_ms / 1_000.0)
+}
+
+/// STDP weight change for one pair, `dt_ms` = post time - pre time (before clamping)
+pub fn stdp_pair_dw(params: &STDPParams, dt_ms: f64) -> f64 {
+    if dt_ms.abs() > params.max_window as f64 {
+        0.0
+    } else if dt_ms > 0.0 {
+        params.a_plus as f64 * (-dt_ms / params.tau_plus as f64).exp()
+    } else if dt_ms < 0.0 {
+        -(params.a_minus as f64) * (dt_ms / params.tau_minus as f64).exp()
+    } else {
+        0.0
+    }
+}
+
+/// One neuron per DC level, all in a single run
+fn lif_fi_curve(config: &ValidationConfig) -> Result<Vec<ValidationCheck>> {
+    let lif = &config.lif;
+    let rheobase_na = (lif.v_thresh - lif.v_rest) as f64 / lif.r_m as f64;
+    let currents: Vec<f64> = FI_RHEOBASE_MULTIPLES.iter().map(|m| m * rheobase_na).collect();
+    let duration_ns = (FI_DURATION_MS * 1e6) as u64;
+
+    let mut network = network(config)?;
+    for i in 0..currents.len() {
+        network.add_neuron_with_params(NeuronId::new(i as u32), lif.clone())?;
+    }
+    let mut engine = engine(network, config, duration_ns)?;
+    for (i, &current) in currents.iter().enumerate() {
+        engine.add_stimulus(StimulusPattern::Constant {
+            neuron: NeuronId::new(i as u32),
+            amplitude: current as f32,
+            start_time: 0,
+            duration: duration_ns,
+        });
+    }
+    let result = engine.run()?;
+
+    Ok(currents
+        .iter()
+        .enumerate()
+        .map(|(i, &current)| {
+            let expected = lif_rate_hz(lif, current);
+            ValidationCheck {
+                name: format!("I={:.3}nA", current),
+                unit: "Hz",
+                expected,
+                measured: steady_rate_hz(&result, NeuronId::new(i as u32), duration_ns),
+                // Each interval can be off by up to one step
+                tolerance: expected * (FI_REL_TOLERANCE + config.dt_ms() * expected / 1_000.0),
+            }
+        })
+        .collect())
+}
+
+/// Rate from the mean inter-spike interval, so the first (from-rest) interval does not bias it
+fn steady_rate_hz(result: &SimulationResult, neuron: NeuronId, duration_ns: u64) -> f64 {
+    let times = result.spike_times(neuron);
+    match (times.first(), times.last()) {
+        (Some(&first), Some(&last)) if times.len() >= 2 => {
+            (times.len() - 1) as f64 * 1e9 / (last - first) as f64
+        }
+        _ => times.len() as f64 * 1e9 / duration_ns as f64,
+    }
+}
+
+/// Neuron 0 without and neuron 1 with refractoriness, each under its own Poisson pulses
+fn poisson_rate(config: &ValidationConfig) -> Result<Vec<ValidationCheck>> {
+    let lif = &config.lif;
+    let duration_ns = config.poisson_duration_ns;
+    // Twice the current that lifts V from its lowest value to threshold within one step
+    let swing = (lif.v_thresh - lif.v_rest.min(lif.v_reset)) as f64;
+    let step_gain = lif.r_m as f64 * -(-config.dt_ms() / lif.tau_m as f64).exp_m1();
+    let amplitude = (2.0 * swing / step_gain) as f32;
+
+    let cases = [LIFParams { t_refrac: 0.0, ..lif.clone() }, lif.clone()];
+    let mut network = network(config)?;
+    for (i, params) in cases.iter().enumerate() {
+        network.add_neuron_with_params(NeuronId::new(i as u32), params.clone())?;
+    }
+    let mut engine = engine(network, config, duration_ns)?;
+    for i in 0..cases.len() {
+        engine.add_stimulus(StimulusPattern::Poisson {
+            neuron: NeuronId::new(i as u32),
+            rate: POISSON_RATE_HZ as f32,
+            amplitude,
+            start_time: 0,
+            duration: duration_ns,
+        });
+    }
+    let result = engine.run()?;
+
+    let seconds = duration_ns as f64 / 1e9;
+    Ok(cases
+        .iter()
+        .enumerate()
+        .map(|(i, params)| {
+            let t_refrac_ms = params.t_refrac as f64;
+            let expected = dead_time_rate_hz(POISSON_RATE_HZ, t_refrac_ms);
+            // Intervals are renewal times with CV 1 / (1 + λ t_ref)
+            let cv = 1.0 / (1.0 + POISSON_RATE_HZ * t_refrac_ms / 1_000.0);
+            let standard_error = expected * cv / (expected * seconds).max(1.0).sqrt();
+            // The first free step after a dead time can already fire, shortening intervals by up to dt
+            let grid = if t_refrac_ms > 0.0 { expected * config.dt_ms() * expected / 1_000.0 } else { 0.0 };
+            ValidationCheck {
+                name: format!("t_ref={}ms", t_refrac_ms),
+                unit: "Hz",
+                expected,
+                measured: result.spike_times(NeuronId::new(i as u32)).len() as f64 / seconds,
+                tolerance: POISSON_STANDARD_ERRORS * standard_error + grid,
+            }
+        })
+        .collect())
+}
+
+/// A fresh rule per interval: pre then post (potentiation) and post then pre (depression)
+fn stdp_pair(config: &ValidationConfig) -> Result<Vec<ValidationCheck>> {
+    let params = &config.stdp;
+    let (pre, post) = (NeuronId::new(0), NeuronId::new(1));
+    let synapse = SynapseId::new(pre, post);
+    let w0 = (params.w_min + params.w_max) / 2.0;
+    let start_ns = (STDP_PAIR_START_MS * 1e6) as u64;
+
+    let intervals = STDP_TAU_MULTIPLES
+        .iter()
+        .map(|m| m * params.tau_plus as f64)
+        .chain(STDP_TAU_MULTIPLES.iter().map(|m| -m * params.tau_minus as f64));
+    let mut checks = Vec::new();
+    for dt_ms in intervals {
+        let offset_ns = (dt_ms.abs() * 1e6).round() as u64;
+        let (first, second) = if dt_ms > 0.0 { (pre, post) } else { (post, pre) };
+        let mut rule = STDPRule::new(params.clone())?;
+        rule.record_spike(first, Time::from_nanos(start_ns));
+        rule.record_spike(second, Time::from_nanos(start_ns + offset_ns));
+
+        let mut weights = HashMap::from([(synapse, w0)]);
+        rule.apply_updates(&mut weights, Time::from_nanos(start_ns + offset_ns))?;
+        let measured = (weights[&synapse] - w0) as f64;
+
+        let w0 = w0 as f64;
+        let expected = (w0 + stdp_pair_dw(params, dt_ms)).clamp(params.w_min as f64, params.w_max as f64) - w0;
+        checks.push(ValidationCheck {
+            name: format!("dt={:+}ms", dt_ms),
+            unit: "dw",
+            expected,
+            measured,
+            // f32 weights and kernel
+            tolerance: 1e-4 * expected.abs() + 1e-6 * (params.w_max - params.w_min) as f64,
+        });
+    }
+    Ok(checks)
+}
+
+/// Empty network with the configured integrator and no plasticity
+fn network(config: &ValidationConfig) -> Result<SNNNetwork> {
+    SNNNetwork::new(NetworkConfig {
+        default_lif_params: config.lif.clone(),
+        default_stdp_params: config.stdp.clone(),
+        plasticity_enabled: false,
+        integrator: config.integrator,
+        ..NetworkConfig::default()
+    })
+}
+
+fn engine(network: SNNNetwork, config: &ValidationConfig, duration_ns: u64) -> Result<SimulationEngine> {
+    let params = SimulationParams::new(config.dt_ns, duration_ns)?.with_seed(config.seed);
+    SimulationEngine::new(network, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analytic_references() {
+        let lif = LIFParams::default();
+        // Rheobase is (v_thresh - v_rest) / r_m = 2 nA
+        assert_eq!(lif_rate_hz(&lif, 2.0), 0.0);
+        let expected = 1_000.0 / (2.0 + 20.0 * (40.0f64 / 20.0).ln());
+        assert!((lif_rate_hz(&lif, 4.0) - expected).abs() < 1e-9);
+        assert!((dead_time_rate_hz(100.0, 2.0) - 100.0 / 1.2).abs() < 1e-9);
+
+        let stdp = STDPParams::default();
+        assert!((stdp_pair_dw(&stdp, 20.0) - 0.01 * (-1.0f64).exp()).abs() < 1e-9);
+        assert!((stdp_pair_dw(&stdp, -20.0) + 0.012 * (-1.0f64).exp()).abs() < 1e-9);
+        assert_eq!(stdp_pair_dw(&stdp, 150.0), 0.0);
+        assert_eq!("stdp-pair".parse::<ValidationScenario>().expect("Should parse"), ValidationScenario::StdpPair);
+        assert!("nope".parse::<ValidationScenario>().is_err());
+    }
+
+    #[test]
+    fn test_lif_fi_curve_matches_analytic() {
+        let outcome = run_scenario(ValidationScenario::LifFiCurve, &ValidationConfig::default())
+            .expect("Should run f-I scenario");
+        assert_eq!(outcome.checks.len(), FI_RHEOBASE_MULTIPLES.len());
+        // Below rheobase the neuron stays silent
+        assert_eq!(outcome.checks[0].measured, 0.0);
+        assert!(outcome.passed(), "{:?}", outcome.failures().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_poisson_rate_matches_dead_time_process() {
+        let config = ValidationConfig { poisson_duration_ns: 5_000_000_000, ..ValidationConfig::default() };
+        let outcome = run_scenario(ValidationScenario::PoissonRate, &config).expect("Should run Poisson scenario");
+        assert_eq!(outcome.checks.len(), 2);
+        assert!(outcome.checks[1].measured < outcome.checks[0].measured);
+        assert!(outcome.passed(), "{:?}", outcome.failures().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_stdp_pair_matches_window() {
+        let outcome = run_scenario(ValidationScenario::StdpPair, &ValidationConfig::default())
+            .expect("Should run STDP scenario");
+        assert_eq!(outcome.checks.len(), 2 * STDP_TAU_MULTIPLES.len());
+        assert!(outcome.checks.iter().take(4).all(|c| c.measured > 0.0));
+        assert!(outcome.checks.iter().skip(4).all(|c| c.measured < 0.0));
+        assert!(outcome.passed(), "{:?}", outcome.failures().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_check_tolerance() {
+        let check = ValidationCheck { name: "x".into(), unit: "Hz", expected: 10.0, measured: 10.4, tolerance: 0.5 };
+        assert!(check.passed());
+        assert!((check.relative_deviation().unwrap() - 0.04).abs() < 1e-12);
+        assert!(!ValidationCheck { measured: 11.0, ..check }.passed());
+        assert!(ValidationConfig { dt_ns: 0, ..ValidationConfig::default() }.validate().is_err());
+    }
+}
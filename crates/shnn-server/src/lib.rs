@@ -0,0 +1,269 @@
+//! gRPC service for the CLI-first neuromorphic research substrate
+//!
+//! Exposes the `shnn.v1.Hsnn` service (see `proto/shnn.proto`) so non-Rust
+//! clients and cluster schedulers can verify, compile and run textual NIR
+//! modules without shelling out to `snn`. `RunSimulation` streams output
+//! spikes in batches while the engine steps and ends with a summary event.
+
+#![deny(missing_docs)]
+
+use std::net::SocketAddr;
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use shnn_compiler::{compile_with_passes, estimate_memory, list_ops, program_shape, verify_module, LoweredProgram};
+use shnn_ir::{parse_text, Module};
+
+/// Generated protobuf messages, client and server
+#[allow(missing_docs)]
+pub mod proto {
+    tonic::include_proto!("shnn.v1");
+}
+
+use proto::hsnn_server::{Hsnn, HsnnServer};
+use proto::{
+    run_event, AttributeInfo, CompileResponse, ListOpsRequest, ListOpsResponse, ModuleRequest, OpInfo,
+    RunEvent, RunRequest, RunSummary, SpikeBatch, VerifyResponse,
+};
+
+/// Default listen address of `snn serve --grpc`
+pub const DEFAULT_ADDR: &str = "127.0.0.1:50051";
+
+/// Spike batches buffered ahead of a slow client before the engine waits
+const STREAM_BUFFER: usize = 64;
+
+/// Implementation of the `Hsnn` service
+#[derive(Debug, Clone, Default)]
+pub struct HsnnService;
+
+impl HsnnService {
+    /// New service
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Wrap in the generated tonic server
+    pub fn into_server(self) -> HsnnServer<Self> {
+        HsnnServer::new(self)
+    }
+}
+
+/// Serve the service on `addr` until the process exits
+pub async fn serve(addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+    log::info!("hSNN gRPC service listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(HsnnService::new().into_server())
+        .serve(addr)
+        .await
+}
+
+fn parse_module(text: &str) -> Result<Module, Status> {
+    parse_text(text).map_err(|e| Status::invalid_argument(format!("parse error: {}", e)))
+}
+
+fn compile(module: &Module) -> Result<LoweredProgram, Status> {
+    compile_with_passes(module).map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+fn spike_message(spike: &shnn_runtime::Spike) -> proto::Spike {
+    proto::Spike {
+        neuron_id: spike.neuron_id.raw(),
+        time_ns: spike.time.as_nanos(),
+        amplitude: spike.amplitude,
+    }
+}
+
+/// Step `program` to completion, sending a batch every `batch_steps` steps and a final summary
+fn run_program(mut program: LoweredProgram, batch_steps: usize, tx: mpsc::Sender<Result<RunEvent, Status>>) {
+    let send = |event: run_event::Event| tx.blocking_send(Ok(RunEvent { event: Some(event) })).is_ok();
+
+    if let Err(e) = program.reset() {
+        let _ = tx.blocking_send(Err(Status::internal(e.to_string())));
+        return;
+    }
+    let mut batch = Vec::new();
+    while !program.engine.is_finished() {
+        if let Err(e) = program.step() {
+            let _ = tx.blocking_send(Err(Status::internal(e.to_string())));
+            return;
+        }
+        batch.extend(program.drain_output_spikes().iter().map(spike_message));
+        let step = program.engine.current_step();
+        if !batch.is_empty() && (step % batch_steps == 0 || program.engine.is_finished()) {
+            let spikes = std::mem::take(&mut batch);
+            // A closed channel means the client went away; stop simulating for it
+            if !send(run_event::Event::Spikes(SpikeBatch { step: step as u64, spikes })) {
+                log::info!("RunSimulation client disconnected at step {}", step);
+                return;
+            }
+        }
+    }
+
+    let result = program.finish();
+    let outcomes = program.check_expectations(&result);
+    let failures = outcomes.iter()
+        .filter(|o| !o.passed)
+        .map(|o| format!("{} (observed {})", o.expectation, o.observed))
+        .collect::<Vec<_>>();
+    send(run_event::Event::Summary(RunSummary {
+        steps_executed: result.steps_executed as u64,
+        total_spikes: result.total_spikes as u64,
+        duration_ns: result.duration_ns,
+        expectations_passed: (outcomes.len() - failures.len()) as u32,
+        failures,
+    }));
+}
+
+#[tonic::async_trait]
+impl Hsnn for HsnnService {
+    async fn verify_module(&self, request: Request<ModuleRequest>) -> Result<Response<VerifyResponse>, Status> {
+        let text = request.into_inner().module_text;
+        let response = match parse_text(&text) {
+            Err(e) => VerifyResponse { ok: false, error: format!("parse error: {}", e), op_count: 0 },
+            Ok(module) => {
+                let op_count = module.ops.len() as u32;
+                match verify_module(&module) {
+                    Ok(()) => VerifyResponse { ok: true, error: String::new(), op_count },
+                    Err(e) => VerifyResponse { ok: false, error: e.to_string(), op_count },
+                }
+            }
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn compile_module(&self, request: Request<ModuleRequest>) -> Result<Response<CompileResponse>, Status> {
+        let module = parse_module(&request.into_inner().module_text)?;
+        let program = tokio::task::spawn_blocking(move || {
+            let program = compile(&module)?;
+            let shape = program_shape(&module).map_err(|e| Status::invalid_argument(e.to_string()))?;
+            let estimate = estimate_memory(&module).map_err(|e| Status::invalid_argument(e.to_string()))?;
+            let params = program.engine.params();
+            Ok::<_, Status>(CompileResponse {
+                neurons: shape.neurons,
+                synapses: shape.synapses,
+                dt_ns: params.dt_ns,
+                duration_ns: params.duration_ns,
+                steps: params.num_steps() as u64,
+                estimated_bytes: estimate.total(),
+                expectations: program.expectations.len() as u32,
+            })
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))??;
+        Ok(Response::new(program))
+    }
+
+    type RunSimulationStream = ReceiverStream<Result<RunEvent, Status>>;
+
+    async fn run_simulation(&self, request: Request<RunRequest>) -> Result<Response<Self::RunSimulationStream>, Status> {
+        let request = request.into_inner();
+        let module = parse_module(&request.module_text)?;
+        let batch_steps = request.batch_steps.max(1) as usize;
+        // Compile before answering so invalid modules fail the call instead of the stream
+        let program = tokio::task::spawn_blocking(move || compile(&module))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))??;
+
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || run_program(program, batch_steps, tx));
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn list_ops(&self, _request: Request<ListOpsRequest>) -> Result<Response<ListOpsResponse>, Status> {
+        let ops = list_ops().iter().map(|op| OpInfo {
+            dialect: op.dialect.to_string(),
+            name: op.name.to_string(),
+            version: op.version as u32,
+            attrs: op.attrs.iter().map(|attr| AttributeInfo {
+                name: attr.name.to_string(),
+                kind: attr.kind.name().to_string(),
+                required: attr.required,
+                doc: attr.doc.to_string(),
+            }).collect(),
+        }).collect();
+        Ok(Response::new(ListOpsResponse { ops }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto::hsnn_client::HsnnClient;
+    use shnn_ir::{layer_fully_connected_v1, runtime_simulate_run_v1, stimulus_poisson_v1};
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    fn module_text() -> String {
+        let mut module = Module::new();
+        module.push(layer_fully_connected_v1(0, 1, 2, 3, 0.5, 1.0));
+        module.push(stimulus_poisson_v1(0, 200.0, 300.0, 0.0, 20.0));
+        module.push(runtime_simulate_run_v1(0.1, 20.0, false, Some(7)));
+        module.to_text()
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_errors_in_response() {
+        let service = HsnnService::new();
+        let ok = service.verify_module(Request::new(ModuleRequest { module_text: module_text() }))
+            .await.unwrap().into_inner();
+        assert!(ok.ok);
+        assert_eq!(ok.op_count, 3);
+
+        let bad = service.verify_module(Request::new(ModuleRequest { module_text: "not nir".into() }))
+            .await.unwrap().into_inner();
+        assert!(!bad.ok);
+        assert!(!bad.error.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_compile_reports_program_size() {
+        let response = HsnnService::new()
+            .compile_module(Request::new(ModuleRequest { module_text: module_text() }))
+            .await.unwrap().into_inner();
+        assert_eq!((response.neurons, response.synapses), (4, 4));
+        assert_eq!(response.steps, 200);
+        assert!(response.estimated_bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_ops_matches_registry() {
+        let response = HsnnService::new().list_ops(Request::new(ListOpsRequest {})).await.unwrap().into_inner();
+        assert_eq!(response.ops.len(), list_ops().len());
+        assert!(response.ops.iter().any(|op| op.dialect == "runtime" && op.name == "simulate.run"));
+    }
+
+    #[tokio::test]
+    async fn test_run_streams_spikes_over_grpc() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(HsnnService::new().into_server())
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let mut client = HsnnClient::connect(format!("http://{}", addr)).await.unwrap();
+        let request = RunRequest { module_text: module_text(), batch_steps: 10 };
+        let mut stream = client.run_simulation(request).await.unwrap().into_inner();
+
+        let mut streamed = 0;
+        let mut summary = None;
+        while let Some(event) = stream.message().await.unwrap() {
+            match event.event.unwrap() {
+                run_event::Event::Spikes(batch) => {
+                    assert!(batch.step % 10 == 0 || batch.step == 200);
+                    streamed += batch.spikes.len() as u64;
+                }
+                run_event::Event::Summary(s) => summary = Some(s),
+            }
+        }
+        let summary = summary.expect("summary event");
+        assert_eq!(summary.steps_executed, 200);
+        assert!(streamed > 0);
+        assert_eq!(streamed, summary.total_spikes);
+
+        let invalid = client.run_simulation(RunRequest { module_text: "not nir".into(), batch_steps: 0 }).await;
+        assert_eq!(invalid.unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,295 @@
+//! Crash-safe artifact writes
+//!
+//! [`write_atomic`] writes to a temporary sibling, fsyncs it, renames it over
+//! the target and fsyncs the directory, so readers see either the old file or
+//! the new one, never a prefix. [`Commit`] extends this to several files in
+//! one directory with a write-ahead journal: files are staged as temporaries,
+//! the journal naming them is made durable, then each is renamed into place.
+//! [`recover`] rolls a journaled commit forward if it was interrupted after
+//! the journal was written, and discards one interrupted before.
+//!
+//! Directories are assumed to have a single writer; recovery removes any
+//! temporaries left in them.
+
+use crate::{
+    error::{Result, StorageError},
+    schemas::calculate_checksum,
+};
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Journal file of an in-progress [`Commit`]
+pub const JOURNAL_FILE: &str = "journal.wal";
+
+const JOURNAL_HEADER: &str = "snn-journal v1";
+const TEMP_MARKER: &str = ".snn-tmp-";
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Replace `path` with `bytes` atomically and durably
+pub fn write_atomic<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Result<()> {
+    let path = path.as_ref();
+    let (dir, name) = split_path(path)?;
+    let temp = write_temp(&dir, &name, bytes)?;
+    if let Err(e) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    sync_dir(&dir)
+}
+
+/// Flush a directory's entries (renames, creations) to disk
+pub fn sync_dir<P: AsRef<Path>>(dir: P) -> Result<()> {
+    #[cfg(unix)]
+    File::open(dir.as_ref())?.sync_all()?;
+    // Directory handles cannot be synced elsewhere; renames are durable once the files are
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Several files replaced together in one directory
+///
+/// Staged files are written to temporaries immediately; nothing is visible
+/// under the final names until [`Commit::commit`]. Dropping an uncommitted
+/// commit removes its temporaries.
+#[derive(Debug)]
+pub struct Commit {
+    dir: PathBuf,
+    staged: Vec<(String, PathBuf)>,
+}
+
+impl Commit {
+    /// Start a commit into `dir` (created if missing)
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, staged: Vec::new() })
+    }
+
+    /// Stage `bytes` as file `name` (a plain file name within the directory)
+    pub fn stage(&mut self, name: &str, bytes: &[u8]) -> Result<()> {
+        let valid = !name.is_empty()
+            && name != JOURNAL_FILE
+            && !name.contains(TEMP_MARKER)
+            && !name.contains(['/', '\\', '\t', '\n'])
+            && name != "."
+            && name != "..";
+        if !valid {
+            return Err(StorageError::invalid_format(format!("Invalid commit file name: {:?}", name)));
+        }
+        let temp = write_temp(&self.dir, name, bytes)?;
+        match self.staged.iter_mut().find(|(staged, _)| staged == name) {
+            Some(entry) => {
+                let _ = fs::remove_file(std::mem::replace(&mut entry.1, temp));
+            }
+            None => self.staged.push((name.to_string(), temp)),
+        }
+        Ok(())
+    }
+
+    /// Make every staged file visible under its final name
+    pub fn commit(mut self) -> Result<()> {
+        let staged = std::mem::take(&mut self.staged);
+        if staged.is_empty() {
+            return Ok(());
+        }
+        let mut journal = format!("{}\n", JOURNAL_HEADER);
+        for (name, temp) in &staged {
+            let temp_name = temp.file_name().and_then(|n| n.to_str()).expect("temporaries have UTF-8 names");
+            journal.push_str(&format!("{}\t{}\n", name, temp_name));
+        }
+        journal.push_str(&format!("commit {:08x}\n", calculate_checksum(journal.as_bytes())));
+
+        // The journal is the commit point: once it is durable the commit rolls forward
+        if let Err(e) = write_atomic(self.dir.join(JOURNAL_FILE), journal.as_bytes()) {
+            for (_, temp) in &staged {
+                let _ = fs::remove_file(temp);
+            }
+            return Err(e);
+        }
+        for (name, temp) in &staged {
+            fs::rename(temp, self.dir.join(name))?;
+        }
+        sync_dir(&self.dir)?;
+        fs::remove_file(self.dir.join(JOURNAL_FILE))?;
+        sync_dir(&self.dir)
+    }
+}
+
+impl Drop for Commit {
+    fn drop(&mut self) {
+        for (_, temp) in &self.staged {
+            let _ = fs::remove_file(temp);
+        }
+    }
+}
+
+/// What [`recover`] did to a directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recovery {
+    /// Files moved into place from an interrupted commit
+    pub rolled_forward: Vec<String>,
+    /// Temporaries deleted (staged files of an unfinished commit, partial writes)
+    pub discarded: usize,
+}
+
+/// Finish or discard an interrupted commit in `dir` and remove stray temporaries
+pub fn recover<P: AsRef<Path>>(dir: P) -> Result<Recovery> {
+    let dir = dir.as_ref();
+    let mut recovery = Recovery::default();
+    let journal_path = dir.join(JOURNAL_FILE);
+    if journal_path.exists() {
+        if let Some(entries) = parse_journal(&fs::read_to_string(&journal_path).unwrap_or_default()) {
+            for (name, temp_name) in entries {
+                let temp = dir.join(&temp_name);
+                // Entries already renamed before the interruption have no temporary left
+                if temp.exists() {
+                    fs::rename(&temp, dir.join(&name))?;
+                    recovery.rolled_forward.push(name);
+                }
+            }
+            sync_dir(dir)?;
+        }
+        fs::remove_file(&journal_path)?;
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_temp = path.file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.starts_with('.') && n.contains(TEMP_MARKER))
+            .unwrap_or(false);
+        if is_temp {
+            fs::remove_file(&path)?;
+            recovery.discarded += 1;
+        }
+    }
+    if recovery.discarded > 0 || !recovery.rolled_forward.is_empty() {
+        sync_dir(dir)?;
+    }
+    Ok(recovery)
+}
+
+/// Entries of a complete journal, or `None` if it is torn or corrupt
+fn parse_journal(text: &str) -> Option<Vec<(String, String)>> {
+    let body_end = text.trim_end_matches('\n').rfind('\n')? + 1;
+    let (body, trailer) = text.split_at(body_end);
+    let checksum = u32::from_str_radix(trailer.trim_end().strip_prefix("commit ")?, 16).ok()?;
+    if checksum != calculate_checksum(body.as_bytes()) {
+        return None;
+    }
+    let mut lines = body.lines();
+    if lines.next()? != JOURNAL_HEADER {
+        return None;
+    }
+    lines
+        .map(|line| {
+            let (name, temp) = line.split_once('\t')?;
+            Some((name.to_string(), temp.to_string()))
+        })
+        .collect()
+}
+
+fn split_path(path: &Path) -> Result<(PathBuf, String)> {
+    let name = path.file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| StorageError::invalid_format(format!("Not a file path: {}", path.display())))?;
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    Ok((dir, name.to_string()))
+}
+
+/// Write and fsync a hidden temporary next to `name` in `dir`
+fn write_temp(dir: &Path, name: &str, bytes: &[u8]) -> Result<PathBuf> {
+    let temp = dir.join(format!(
+        ".{}{}{}-{}",
+        name,
+        TEMP_MARKER,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let written = File::create(&temp).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    Ok(temp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .expect("Should list directory")
+            .map(|e| e.expect("Should read entry").file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = tempfile::tempdir().expect("Should create temp dir");
+        let path = dir.path().join("a.vcsr");
+        write_atomic(&path, b"first").expect("Should write");
+        write_atomic(&path, b"second").expect("Should overwrite");
+        assert_eq!(fs::read(&path).expect("Should read"), b"second");
+        assert_eq!(names(dir.path()), vec!["a.vcsr"]);
+    }
+
+    #[test]
+    fn test_commit_and_drop() {
+        let dir = tempfile::tempdir().expect("Should create temp dir");
+        let mut commit = Commit::new(dir.path()).expect("Should start commit");
+        commit.stage("gen.vcsr", b"snapshot").expect("Should stage");
+        commit.stage("manifest.json", b"{}").expect("Should stage");
+        assert!(commit.stage("../escape", b"").is_err());
+        assert!(!dir.path().join("gen.vcsr").exists());
+        commit.commit().expect("Should commit");
+        assert_eq!(names(dir.path()), vec!["gen.vcsr", "manifest.json"]);
+
+        let mut abandoned = Commit::new(dir.path()).expect("Should start commit");
+        abandoned.stage("gen.vcsr", b"partial").expect("Should stage");
+        drop(abandoned);
+        assert_eq!(fs::read(dir.path().join("gen.vcsr")).expect("Should read"), b"snapshot");
+        assert_eq!(names(dir.path()), vec!["gen.vcsr", "manifest.json"]);
+    }
+
+    #[test]
+    fn test_recover_rolls_forward_or_discards() {
+        let dir = tempfile::tempdir().expect("Should create temp dir");
+        // Interrupted after the journal was written: one file renamed, one still staged
+        let staged = write_temp(dir.path(), "manifest.json", b"{\"v\":2}").expect("Should write temp");
+        fs::write(dir.path().join("gen.vcsr"), b"new").expect("Should write");
+        let mut journal = format!("{}\ngen.vcsr\t.gen.vcsr{}0-0\n", JOURNAL_HEADER, TEMP_MARKER);
+        journal.push_str(&format!("manifest.json\t{}\n", staged.file_name().unwrap().to_str().unwrap()));
+        journal.push_str(&format!("commit {:08x}\n", calculate_checksum(journal.as_bytes())));
+        fs::write(dir.path().join(JOURNAL_FILE), &journal).expect("Should write journal");
+
+        let recovery = recover(dir.path()).expect("Should recover");
+        assert_eq!(recovery.rolled_forward, vec!["manifest.json".to_string()]);
+        assert_eq!(fs::read(dir.path().join("manifest.json")).expect("Should read"), b"{\"v\":2}");
+        assert_eq!(names(dir.path()), vec!["gen.vcsr", "manifest.json"]);
+
+        // Torn journal: the commit never happened, its temporaries go away
+        let staged = write_temp(dir.path(), "manifest.json", b"{\"v\":3}").expect("Should write temp");
+        let torn = format!("{}\nmanifest.json\t{}\n", JOURNAL_HEADER, staged.file_name().unwrap().to_str().unwrap());
+        fs::write(dir.path().join(JOURNAL_FILE), torn).expect("Should write journal");
+        let recovery = recover(dir.path()).expect("Should recover");
+        assert!(recovery.rolled_forward.is_empty());
+        assert_eq!(recovery.discarded, 1);
+        assert_eq!(fs::read(dir.path().join("manifest.json")).expect("Should read"), b"{\"v\":2}");
+        assert_eq!(names(dir.path()), vec!["gen.vcsr", "manifest.json"]);
+    }
+}
@@ -0,0 +1,484 @@
+//! Generation-chained VCSR snapshots with delta encoding
+//!
+//! A [`GenerationChain`] stores the first generation (and every `keyframe_interval`-th
+//! generation after it) as a full [`VCSRSnapshot`]; every other generation is stored as a
+//! [`VCSRDelta`] against its parent. Any generation can be materialized by walking back to
+//! the nearest keyframe and replaying deltas forward.
+//!
+//! Binary delta layout (little-endian), magic "VDLT":
+//! ```text
+//! magic[4] version:u32 generation:u64 parent:u64 num_vertices:u32
+//! num_changes:u64 data_checksum:u32
+//! changes: num_changes * (source:u32 target:u32 kind:u8 weight:f32)
+//! ```
+
+use crate::{
+    atomic::{self, Commit},
+    error::{Result, StorageError},
+    ids::GenerationId,
+    magic,
+    schemas::{calculate_checksum, validate_checksum, validate_magic},
+    vcsr::VCSRSnapshot,
+    NeuronId,
+};
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Current delta schema version
+pub const VDLT_VERSION: u32 = 1;
+
+const HEADER_SIZE: usize = 4 + 4 + 8 + 8 + 4 + 8 + 4;
+const CHANGE_SIZE: usize = 4 + 4 + 1 + 4;
+
+/// Single edge change recorded in a delta
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeDelta {
+    /// Edge added or weight changed
+    Set {
+        /// Source neuron
+        source: NeuronId,
+        /// Target neuron
+        target: NeuronId,
+        /// New weight
+        weight: f32,
+    },
+    /// Edge removed
+    Remove {
+        /// Source neuron
+        source: NeuronId,
+        /// Target neuron
+        target: NeuronId,
+    },
+}
+
+impl EdgeDelta {
+    fn key(&self) -> (u32, u32) {
+        match *self {
+            EdgeDelta::Set { source, target, .. } | EdgeDelta::Remove { source, target } => {
+                (source.raw(), target.raw())
+            }
+        }
+    }
+}
+
+/// Difference between a generation and its parent
+#[derive(Debug, Clone, PartialEq)]
+pub struct VCSRDelta {
+    /// Generation this delta produces
+    pub generation: GenerationId,
+    /// Parent generation the delta applies to
+    pub parent: GenerationId,
+    /// Vertex count of the resulting snapshot
+    pub num_vertices: u32,
+    /// Edge changes sorted by (source, target)
+    pub changes: Vec<EdgeDelta>,
+}
+
+impl VCSRDelta {
+    /// Compute the delta that turns `parent` into `child`
+    pub fn between(parent: &VCSRSnapshot, child: &VCSRSnapshot) -> Self {
+        let before = edge_map(parent);
+        let after = edge_map(child);
+
+        let mut changes = Vec::new();
+        for (&(source, target), &weight) in &after {
+            if before.get(&(source, target)).map(|w| w.to_bits()) != Some(weight.to_bits()) {
+                changes.push(EdgeDelta::Set {
+                    source: NeuronId::new(source),
+                    target: NeuronId::new(target),
+                    weight,
+                });
+            }
+        }
+        for &(source, target) in before.keys() {
+            if !after.contains_key(&(source, target)) {
+                changes.push(EdgeDelta::Remove {
+                    source: NeuronId::new(source),
+                    target: NeuronId::new(target),
+                });
+            }
+        }
+        changes.sort_by_key(EdgeDelta::key);
+
+        Self {
+            generation: GenerationId::new(child.header.generation),
+            parent: GenerationId::new(parent.header.generation),
+            num_vertices: child.header.num_vertices,
+            changes,
+        }
+    }
+
+    /// Apply this delta to its parent snapshot
+    pub fn apply(&self, base: &VCSRSnapshot) -> Result<VCSRSnapshot> {
+        if base.header.generation != self.parent.raw() {
+            return Err(StorageError::invalid_format(format!(
+                "Delta for {} expects parent {}, got G{}",
+                self.generation, self.parent, base.header.generation
+            )));
+        }
+
+        let mut edges = edge_map(base);
+        for change in &self.changes {
+            match *change {
+                EdgeDelta::Set { source, target, weight } => {
+                    edges.insert((source.raw(), target.raw()), weight);
+                }
+                EdgeDelta::Remove { source, target } => {
+                    edges.remove(&(source.raw(), target.raw()));
+                }
+            }
+        }
+
+        let vertex_type = base.vertices.first().map(|v| v.vertex_type).unwrap_or(0);
+        let triples: Vec<_> = edges
+            .into_iter()
+            .map(|((s, t), w)| (NeuronId::new(s), NeuronId::new(t), w))
+            .collect();
+        VCSRSnapshot::from_edges(self.generation, self.num_vertices, vertex_type, &triples)
+    }
+
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut body = Vec::with_capacity(self.changes.len() * CHANGE_SIZE);
+        for change in &self.changes {
+            let (source, target) = change.key();
+            body.extend_from_slice(&source.to_le_bytes());
+            body.extend_from_slice(&target.to_le_bytes());
+            match *change {
+                EdgeDelta::Set { weight, .. } => {
+                    body.push(0);
+                    body.extend_from_slice(&weight.to_le_bytes());
+                }
+                EdgeDelta::Remove { .. } => {
+                    body.push(1);
+                    body.extend_from_slice(&0f32.to_le_bytes());
+                }
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + body.len());
+        bytes.extend_from_slice(&magic::VDLT);
+        bytes.extend_from_slice(&VDLT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.generation.raw().to_le_bytes());
+        bytes.extend_from_slice(&self.parent.raw().to_le_bytes());
+        bytes.extend_from_slice(&self.num_vertices.to_le_bytes());
+        bytes.extend_from_slice(&(self.changes.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&calculate_checksum(&body).to_le_bytes());
+        bytes.extend_from_slice(&body);
+        bytes
+    }
+
+    /// Load from bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.len() < HEADER_SIZE {
+            return Err(StorageError::invalid_format("Data too short for VDLT header"));
+        }
+        validate_magic(data, magic::VDLT)?;
+
+        let version = read_u32(data, 4);
+        if version != VDLT_VERSION {
+            return Err(StorageError::BadVersion {
+                version,
+                supported: VDLT_VERSION,
+            });
+        }
+        let generation = GenerationId::new(read_u64(data, 8));
+        let parent = GenerationId::new(read_u64(data, 16));
+        let num_vertices = read_u32(data, 24);
+        let num_changes = read_u64(data, 28) as usize;
+        let checksum = read_u32(data, 36);
+
+        let body = &data[HEADER_SIZE..];
+        if body.len() != num_changes.saturating_mul(CHANGE_SIZE) {
+            return Err(StorageError::invalid_format("VDLT body length mismatch"));
+        }
+        validate_checksum(body, checksum)?;
+
+        let mut changes = Vec::with_capacity(num_changes);
+        for chunk in body.chunks_exact(CHANGE_SIZE) {
+            let source = NeuronId::new(read_u32(chunk, 0));
+            let target = NeuronId::new(read_u32(chunk, 4));
+            let change = match chunk[8] {
+                0 => EdgeDelta::Set {
+                    source,
+                    target,
+                    weight: f32::from_le_bytes([chunk[9], chunk[10], chunk[11], chunk[12]]),
+                },
+                1 => EdgeDelta::Remove { source, target },
+                kind => {
+                    return Err(StorageError::invalid_format(format!(
+                        "Unknown VDLT change kind {}",
+                        kind
+                    )))
+                }
+            };
+            changes.push(change);
+        }
+
+        Ok(Self {
+            generation,
+            parent,
+            num_vertices,
+            changes,
+        })
+    }
+}
+
+/// Stored form of a generation
+#[derive(Debug, Clone)]
+pub enum GenerationEntry {
+    /// Full snapshot (keyframe)
+    Full(VCSRSnapshot),
+    /// Delta against a parent generation
+    Delta(VCSRDelta),
+}
+
+impl GenerationEntry {
+    /// Parent generation, if this entry is a delta
+    pub fn parent(&self) -> Option<GenerationId> {
+        match self {
+            GenerationEntry::Full(_) => None,
+            GenerationEntry::Delta(delta) => Some(delta.parent),
+        }
+    }
+}
+
+/// Chain of VCSR generations stored as keyframes plus deltas
+#[derive(Debug, Clone)]
+pub struct GenerationChain {
+    entries: BTreeMap<GenerationId, GenerationEntry>,
+    keyframe_interval: u32,
+}
+
+impl GenerationChain {
+    /// Create an empty chain that stores a full snapshot every `keyframe_interval`
+    /// generations (0 = only the root generation is stored in full)
+    pub fn new(keyframe_interval: u32) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            keyframe_interval,
+        }
+    }
+
+    /// Insert a full snapshot as a root/keyframe generation
+    pub fn commit_full(&mut self, snapshot: VCSRSnapshot) -> GenerationId {
+        let generation = GenerationId::new(snapshot.header.generation);
+        self.entries.insert(generation, GenerationEntry::Full(snapshot));
+        generation
+    }
+
+    /// Commit `snapshot` as a child of `parent`, storing only the delta unless
+    /// the keyframe interval requires a full snapshot
+    pub fn commit(&mut self, parent: GenerationId, snapshot: VCSRSnapshot) -> Result<GenerationId> {
+        let generation = GenerationId::new(snapshot.header.generation);
+        if generation <= parent {
+            return Err(StorageError::invalid_format(format!(
+                "Generation {} must be newer than parent {}",
+                generation, parent
+            )));
+        }
+        if self.entries.contains_key(&generation) {
+            return Err(StorageError::invalid_format(format!(
+                "Generation {} already exists",
+                generation
+            )));
+        }
+
+        if self.keyframe_interval > 0 && self.depth(parent)? + 1 >= self.keyframe_interval {
+            return Ok(self.commit_full(snapshot));
+        }
+
+        let base = self.materialize(parent)?;
+        let delta = VCSRDelta::between(&base, &snapshot);
+        self.entries.insert(generation, GenerationEntry::Delta(delta));
+        Ok(generation)
+    }
+
+    /// Reconstruct the full snapshot for `generation`
+    pub fn materialize(&self, generation: GenerationId) -> Result<VCSRSnapshot> {
+        let mut path = Vec::new();
+        let mut current = generation;
+        let base = loop {
+            match self.entry(current)? {
+                GenerationEntry::Full(snapshot) => break snapshot,
+                GenerationEntry::Delta(delta) => {
+                    path.push(delta);
+                    current = delta.parent;
+                }
+            }
+        };
+
+        let mut snapshot = base.clone();
+        for delta in path.into_iter().rev() {
+            snapshot = delta.apply(&snapshot)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Number of deltas between `generation` and its nearest keyframe
+    pub fn depth(&self, generation: GenerationId) -> Result<u32> {
+        let mut depth = 0;
+        let mut current = generation;
+        while let Some(parent) = self.entry(current)?.parent() {
+            depth += 1;
+            current = parent;
+        }
+        Ok(depth)
+    }
+
+    /// Get the stored entry for a generation
+    pub fn entry(&self, generation: GenerationId) -> Result<&GenerationEntry> {
+        self.entries.get(&generation).ok_or(StorageError::GenerationNotFound {
+            generation: generation.raw(),
+        })
+    }
+
+    /// List stored generations in ascending order
+    pub fn generations(&self) -> Vec<GenerationId> {
+        self.entries.keys().copied().collect()
+    }
+
+    /// Latest stored generation
+    pub fn latest(&self) -> Option<GenerationId> {
+        self.entries.keys().next_back().copied()
+    }
+
+    /// Write every generation into `dir` as `gen_<hex>.vcsr` or `gen_<hex>.vdlt`
+    ///
+    /// All files are committed together (see [`crate::atomic::Commit`]).
+    pub fn save_dir<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
+        let mut commit = Commit::new(dir)?;
+        for (generation, entry) in &self.entries {
+            match entry {
+                GenerationEntry::Full(snapshot) => {
+                    commit.stage(&generation_file(*generation, "vcsr"), &snapshot.to_bytes())?;
+                }
+                GenerationEntry::Delta(delta) => {
+                    commit.stage(&generation_file(*generation, "vdlt"), &delta.to_bytes())?;
+                }
+            }
+        }
+        commit.commit()
+    }
+
+    /// Load all `gen_*.vcsr` / `gen_*.vdlt` files from `dir`, first finishing an interrupted save
+    pub fn load_dir<P: AsRef<Path>>(dir: P, keyframe_interval: u32) -> Result<Self> {
+        let dir = dir.as_ref();
+        atomic::recover(dir)?;
+        let mut chain = Self::new(keyframe_interval);
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+            if !name.starts_with("gen_") {
+                continue;
+            }
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("vcsr") => {
+                    chain.commit_full(VCSRSnapshot::from_bytes(&std::fs::read(&path)?)?);
+                }
+                Some("vdlt") => {
+                    let delta = VCSRDelta::from_bytes(&std::fs::read(&path)?)?;
+                    chain.entries.insert(delta.generation, GenerationEntry::Delta(delta));
+                }
+                _ => {}
+            }
+        }
+        Ok(chain)
+    }
+}
+
+impl Default for GenerationChain {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// File name used for a stored generation
+pub fn generation_file(generation: GenerationId, extension: &str) -> String {
+    format!("gen_{:016x}.{}", generation.raw(), extension)
+}
+
+fn edge_map(snapshot: &VCSRSnapshot) -> BTreeMap<(u32, u32), f32> {
+    snapshot
+        .edges()
+        .map(|(s, t, w)| ((s.raw(), t.raw()), w))
+        .collect()
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(generation: u64, edges: &[(u32, u32, f32)]) -> VCSRSnapshot {
+        let triples: Vec<_> = edges
+            .iter()
+            .map(|&(s, t, w)| (NeuronId::new(s), NeuronId::new(t), w))
+            .collect();
+        VCSRSnapshot::from_edges(GenerationId::new(generation), 4, 0, &triples).unwrap()
+    }
+
+    #[test]
+    fn test_delta_roundtrip() {
+        let parent = snapshot(1, &[(0, 1, 0.5), (1, 2, 0.5), (2, 3, 0.5)]);
+        let child = snapshot(2, &[(0, 1, 0.7), (1, 2, 0.5), (3, 0, 0.1)]);
+
+        let delta = VCSRDelta::between(&parent, &child);
+        assert_eq!(delta.changes.len(), 3);
+
+        let decoded = VCSRDelta::from_bytes(&delta.to_bytes()).unwrap();
+        assert_eq!(decoded, delta);
+
+        let rebuilt = decoded.apply(&parent).unwrap();
+        assert_eq!(rebuilt.row_ptr, child.row_ptr);
+        assert_eq!(rebuilt.col_indices, child.col_indices);
+        assert_eq!(rebuilt.weights, child.weights);
+        assert_eq!(rebuilt.header.generation, 2);
+
+        // Wrong parent is rejected
+        assert!(delta.apply(&child).is_err());
+    }
+
+    #[test]
+    fn test_chain_materialize_and_keyframes() {
+        let mut chain = GenerationChain::new(3);
+        chain.commit_full(snapshot(0, &[(0, 1, 0.1)]));
+        for g in 1..=4u64 {
+            let weight = 0.1 * (g + 1) as f32;
+            chain.commit(GenerationId::new(g - 1), snapshot(g, &[(0, 1, weight)])).unwrap();
+        }
+
+        assert!(matches!(chain.entry(GenerationId::new(1)).unwrap(), GenerationEntry::Delta(_)));
+        assert!(matches!(chain.entry(GenerationId::new(3)).unwrap(), GenerationEntry::Full(_)));
+        assert_eq!(chain.depth(GenerationId::new(4)).unwrap(), 1);
+
+        let g2 = chain.materialize(GenerationId::new(2)).unwrap();
+        assert_eq!(g2.weights, vec![0.3]);
+        assert!(chain.materialize(GenerationId::new(9)).is_err());
+    }
+
+    #[test]
+    fn test_chain_save_load_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut chain = GenerationChain::default();
+        chain.commit_full(snapshot(0, &[(0, 1, 0.1), (1, 2, 0.2)]));
+        chain.commit(GenerationId::new(0), snapshot(1, &[(0, 1, 0.4)])).unwrap();
+        chain.save_dir(temp_dir.path()).unwrap();
+
+        let loaded = GenerationChain::load_dir(temp_dir.path(), 0).unwrap();
+        assert_eq!(loaded.generations(), vec![GenerationId::new(0), GenerationId::new(1)]);
+        let g1 = loaded.materialize(GenerationId::new(1)).unwrap();
+        assert_eq!(g1.col_indices, vec![1]);
+        assert_eq!(g1.weights, vec![0.4]);
+    }
+}
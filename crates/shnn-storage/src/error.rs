@@ -10,19 +10,19 @@ pub type Result<T> = std::result::Result<T, StorageError>;
 pub enum StorageError {
     /// Invalid magic number in binary format
     #[error("Invalid magic number: expected {expected:?}, found {found:?}")]
-    InvalidMagic {
+    BadMagic {
         /// Expected magic number
         expected: [u8; 4],
         /// Found magic number
         found: [u8; 4]
     },
 
-    /// Unsupported version
-    #[error("Unsupported version: {version}, supported: {supported}")]
-    UnsupportedVersion {
+    /// Format version newer than (or older than any) version this build reads
+    #[error("Unsupported format version {version} (supported: up to {supported})")]
+    BadVersion {
         /// Version found
         version: u32,
-        /// Supported version
+        /// Newest supported version
         supported: u32
     },
 
@@ -35,6 +35,13 @@ pub enum StorageError {
         computed: u32
     },
 
+    /// A block failed its CRC32C check; `offset` is the block's byte offset in the artifact
+    #[error("Corrupt block at byte offset {offset}")]
+    CorruptBlock {
+        /// Offset of the first byte of the corrupt block (0 for the header)
+        offset: u64,
+    },
+
     /// Invalid file format or corrupted data
     #[error("Invalid format: {reason}")]
     InvalidFormat {
@@ -147,7 +154,7 @@ mod tests {
 
     #[test]
     fn test_error_display() {
-        let err = StorageError::InvalidMagic {
+        let err = StorageError::BadMagic {
             expected: [0x56, 0x43, 0x53, 0x52],
             found: [0x00, 0x00, 0x00, 0x00],
         };
@@ -0,0 +1,707 @@
+//! Multiplexed append-only event log (VLOG)
+//!
+//! An [`EventLog`] holds several event streams of one run — spikes, weight
+//! updates, structural changes, stimuli — each under its own [`StreamId`] and
+//! kept in time order, so every stream can be iterated or windowed by time on
+//! its own (binary search) or merged with the others. Events may carry a
+//! payload of `payload_size` bytes, e.g. the [`WeightChange`] of a weight
+//! update. [`FileEventLog`] grows a
+//! VLOG file by appending records and never rewrites earlier bytes; a record
+//! torn by a crash is dropped when the file is reopened.
+//!
+//! Binary layout (little-endian), magic "VLOG": `magic[4] version:u32`, then
+//! records in write order:
+//! ```text
+//! stream   tag=0 stream_id:u64 kind:u8 name_len:u16 name[name_len]
+//! segment  tag=1 stream_id:u64 count:u32 body_len:u64 first_ns:u64 last_ns:u64 data_checksum:u32
+//!          body[body_len] = events: count * (timestamp:u64 event_type:u8 source_id:u32 target_id:u32
+//!                           payload_size:u16 reserved:u8 payload[payload_size])
+//! ```
+//! A stream record precedes the stream's segments; segments of a stream hold
+//! consecutive, time-ordered runs of its events.
+
+use crate::{
+    error::{Result, StorageError},
+    ids::StreamId,
+    magic,
+    schemas::{calculate_checksum, validate_magic, validate_version},
+    vevt::{encode_vevt, VEVTEvent, WeightChange},
+    Time,
+};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Current event log version
+pub const VLOG_VERSION: u32 = 1;
+
+const FILE_HEADER_SIZE: usize = 4 + 4;
+const EVENT_SIZE: usize = 8 + 1 + 4 + 4 + 2 + 1;
+const STREAM_RECORD: u8 = 0;
+const SEGMENT_RECORD: u8 = 1;
+const SEGMENT_HEADER_SIZE: usize = 1 + 8 + 4 + 8 + 8 + 8 + 4;
+/// Events per segment record; larger appends are split
+const SEGMENT_EVENTS: usize = 1 << 16;
+
+/// What a stream of an event log carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamKind {
+    /// Neuron spikes
+    Spikes,
+    /// Plasticity weight changes
+    WeightUpdates,
+    /// Structural (morphology) changes
+    Structural,
+    /// Stimulus input
+    Stimuli,
+    /// Control and marker events
+    Control,
+}
+
+impl StreamKind {
+    /// Lower-case kind name
+    pub fn name(self) -> &'static str {
+        match self {
+            StreamKind::Spikes => "spikes",
+            StreamKind::WeightUpdates => "weight-updates",
+            StreamKind::Structural => "structural",
+            StreamKind::Stimuli => "stimuli",
+            StreamKind::Control => "control",
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            StreamKind::Spikes => 0,
+            StreamKind::WeightUpdates => 1,
+            StreamKind::Structural => 2,
+            StreamKind::Stimuli => 3,
+            StreamKind::Control => 4,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(StreamKind::Spikes),
+            1 => Some(StreamKind::WeightUpdates),
+            2 => Some(StreamKind::Structural),
+            3 => Some(StreamKind::Stimuli),
+            4 => Some(StreamKind::Control),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for StreamKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One stream of an event log
+#[derive(Debug, Clone)]
+pub struct LogStream {
+    /// What the stream carries
+    pub kind: StreamKind,
+    /// Free-form label
+    pub name: String,
+    events: Vec<VEVTEvent>,
+    /// Payload bytes of all events, in event order
+    payloads: Vec<u8>,
+    /// Start of each event's payload (empty while no event has one)
+    payload_offsets: Vec<usize>,
+}
+
+impl LogStream {
+    fn new(kind: StreamKind, name: String) -> Self {
+        Self { kind, name, events: Vec::new(), payloads: Vec::new(), payload_offsets: Vec::new() }
+    }
+
+    /// Events in time order
+    pub fn events(&self) -> &[VEVTEvent] {
+        &self.events
+    }
+
+    /// Payload of event `index` (empty if it has none or is out of range)
+    pub fn payload(&self, index: usize) -> &[u8] {
+        match (self.payload_offsets.get(index), self.events.get(index)) {
+            (Some(&start), Some(event)) => &self.payloads[start..start + event.payload_size as usize],
+            _ => &[],
+        }
+    }
+
+    /// Weight changes carried by the stream's weight-update events, in time order
+    pub fn weight_changes(&self) -> impl Iterator<Item = WeightChange> + '_ {
+        self.events
+            .iter()
+            .enumerate()
+            .filter_map(|(index, event)| WeightChange::from_event(event, self.payload(index)))
+    }
+
+    /// Events with `start <= timestamp <= end`
+    pub fn window(&self, start: Time, end: Time) -> &[VEVTEvent] {
+        let (start, end) = (start.as_nanos(), end.as_nanos());
+        let from = self.events.partition_point(|e| e.timestamp < start);
+        let to = self.events.partition_point(|e| e.timestamp <= end).max(from);
+        &self.events[from..to]
+    }
+
+    /// First and last event times
+    pub fn time_range(&self) -> Option<(Time, Time)> {
+        let first = self.events.first()?;
+        let last = self.events.last()?;
+        Some((Time::from_nanos(first.timestamp), Time::from_nanos(last.timestamp)))
+    }
+}
+
+/// Event streams of a run, multiplexed by [`StreamId`]
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    streams: BTreeMap<StreamId, LogStream>,
+}
+
+impl EventLog {
+    /// Empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare stream `id`; fails if it already exists
+    pub fn add_stream(&mut self, id: StreamId, kind: StreamKind, name: impl Into<String>) -> Result<()> {
+        let name = name.into();
+        self.check_stream(id, &name)?;
+        self.streams.insert(id, LogStream::new(kind, name));
+        Ok(())
+    }
+
+    /// Append `events` to stream `id`
+    ///
+    /// Events must be in time order and not earlier than the stream's last event.
+    pub fn append(&mut self, id: StreamId, events: &[VEVTEvent]) -> Result<()> {
+        self.append_with_payloads(id, events, &[])
+    }
+
+    /// Append `events` and their concatenated `payloads` (`payload_size` bytes each) to stream `id`
+    pub fn append_with_payloads(&mut self, id: StreamId, events: &[VEVTEvent], payloads: &[u8]) -> Result<()> {
+        self.check_append(id, events, payloads)?;
+        self.push(id, events, payloads);
+        Ok(())
+    }
+
+    /// Append weight changes (in time order) to stream `id`
+    pub fn append_weight_changes(&mut self, id: StreamId, changes: &[WeightChange]) -> Result<()> {
+        let (events, payloads) = weight_change_events(changes);
+        self.append_with_payloads(id, &events, &payloads)
+    }
+
+    /// Stream `id`, if declared
+    pub fn stream(&self, id: StreamId) -> Option<&LogStream> {
+        self.streams.get(&id)
+    }
+
+    /// All streams in id order
+    pub fn streams(&self) -> impl Iterator<Item = (StreamId, &LogStream)> {
+        self.streams.iter().map(|(&id, stream)| (id, stream))
+    }
+
+    /// Streams carrying `kind`, in id order
+    pub fn streams_of(&self, kind: StreamKind) -> impl Iterator<Item = (StreamId, &LogStream)> {
+        self.streams().filter(move |(_, stream)| stream.kind == kind)
+    }
+
+    /// Events across all streams
+    pub fn event_count(&self) -> u64 {
+        self.streams.values().map(|s| s.events.len() as u64).sum()
+    }
+
+    /// Earliest and latest event time across all streams
+    pub fn time_range(&self) -> Option<(Time, Time)> {
+        self.streams.values().filter_map(LogStream::time_range).reduce(|(a0, a1), (b0, b1)| {
+            (a0.min(b0), a1.max(b1))
+        })
+    }
+
+    /// Events of every stream with `start <= timestamp <= end`, in time order
+    /// (ties in stream id order)
+    pub fn merged(&self, start: Time, end: Time) -> Vec<(StreamId, &VEVTEvent)> {
+        let mut events: Vec<_> = self
+            .streams()
+            .flat_map(|(id, stream)| stream.window(start, end).iter().map(move |e| (id, e)))
+            .collect();
+        events.sort_by_key(|(id, e)| (e.timestamp, *id));
+        events
+    }
+
+    /// Stream `id` between `start` and `end` as a standalone VEVT stream
+    ///
+    /// VEVT events are fixed-size, so event payloads are not exported.
+    pub fn export_vevt(&self, id: StreamId, start: Time, end: Time) -> Result<Vec<u8>> {
+        let stream = self.streams.get(&id).ok_or_else(|| unknown_stream(id))?;
+        encode_vevt(id, start, end, stream.window(start, end))
+    }
+
+    /// Serialize every stream (one stream record and its segments each)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = file_header();
+        for (id, stream) in self.streams() {
+            bytes.extend_from_slice(&stream_record(id, stream.kind, &stream.name));
+            bytes.extend_from_slice(&segment_records(id, &stream.events, &stream.payloads));
+        }
+        bytes
+    }
+
+    /// Load from bytes; a truncated final record is an error
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let (log, complete) = read_records(data)?;
+        if complete != data.len() {
+            return Err(StorageError::invalid_format(format!("VLOG record truncated at offset {}", complete)));
+        }
+        Ok(log)
+    }
+
+    fn check_stream(&self, id: StreamId, name: &str) -> Result<()> {
+        if self.streams.contains_key(&id) {
+            return Err(StorageError::invalid_format(format!("VLOG stream {} declared twice", id)));
+        }
+        if name.len() > u16::MAX as usize {
+            return Err(StorageError::invalid_format(format!("VLOG stream {} name too long", id)));
+        }
+        Ok(())
+    }
+
+    fn check_append(&self, id: StreamId, events: &[VEVTEvent], payloads: &[u8]) -> Result<()> {
+        let stream = self.streams.get(&id).ok_or_else(|| unknown_stream(id))?;
+        let declared: usize = events.iter().map(|e| e.payload_size as usize).sum();
+        if declared != payloads.len() {
+            return Err(StorageError::invalid_format(format!(
+                "VLOG stream {}: events declare {} payload bytes, {} given",
+                id,
+                declared,
+                payloads.len()
+            )));
+        }
+        let mut previous = stream.events.last().map_or(0, |e| e.timestamp);
+        for event in events {
+            if event.timestamp < previous {
+                return Err(StorageError::invalid_format(format!(
+                    "VLOG stream {}: event at {} ns appended after {} ns",
+                    id, event.timestamp, previous
+                )));
+            }
+            previous = event.timestamp;
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, id: StreamId, events: &[VEVTEvent], payloads: &[u8]) {
+        let Some(stream) = self.streams.get_mut(&id) else { return };
+        if !payloads.is_empty() || !stream.payload_offsets.is_empty() {
+            // Earlier events without offsets carried no payload bytes
+            stream.payload_offsets.resize(stream.events.len(), 0);
+            let mut offset = stream.payloads.len();
+            for event in events {
+                stream.payload_offsets.push(offset);
+                offset += event.payload_size as usize;
+            }
+            stream.payloads.extend_from_slice(payloads);
+        }
+        stream.events.extend_from_slice(events);
+    }
+}
+
+/// Event log file that grows by appending records
+#[derive(Debug)]
+pub struct FileEventLog {
+    file: File,
+    path: PathBuf,
+    log: EventLog,
+}
+
+impl FileEventLog {
+    /// Create (or truncate) an empty log file at `path`
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new().create(true).write(true).truncate(true).open(&path)?;
+        file.write_all(&file_header())?;
+        Ok(Self { file, path, log: EventLog::new() })
+    }
+
+    /// Open an existing log file for appending, dropping a record torn by a crash
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let bytes = std::fs::read(&path)?;
+        let (log, complete) = read_records(&bytes)?;
+        let file = OpenOptions::new().append(true).open(&path)?;
+        if complete < bytes.len() {
+            log::warn!(
+                "dropping {} byte(s) of a torn record at the end of {}",
+                bytes.len() - complete,
+                path.display()
+            );
+            file.set_len(complete as u64)?;
+        }
+        Ok(Self { file, path, log })
+    }
+
+    /// Declare stream `id` and write its record
+    pub fn add_stream(&mut self, id: StreamId, kind: StreamKind, name: impl Into<String>) -> Result<()> {
+        let name = name.into();
+        self.log.check_stream(id, &name)?;
+        self.file.write_all(&stream_record(id, kind, &name))?;
+        self.log.streams.insert(id, LogStream::new(kind, name));
+        Ok(())
+    }
+
+    /// Append `events` to stream `id` (see [`EventLog::append`]) and write them
+    pub fn append(&mut self, id: StreamId, events: &[VEVTEvent]) -> Result<()> {
+        self.append_with_payloads(id, events, &[])
+    }
+
+    /// Append `events` with their payloads (see [`EventLog::append_with_payloads`]) and write them
+    pub fn append_with_payloads(&mut self, id: StreamId, events: &[VEVTEvent], payloads: &[u8]) -> Result<()> {
+        self.log.check_append(id, events, payloads)?;
+        self.file.write_all(&segment_records(id, events, payloads))?;
+        self.log.push(id, events, payloads);
+        Ok(())
+    }
+
+    /// Append weight changes (see [`EventLog::append_weight_changes`]) and write them
+    pub fn append_weight_changes(&mut self, id: StreamId, changes: &[WeightChange]) -> Result<()> {
+        let (events, payloads) = weight_change_events(changes);
+        self.append_with_payloads(id, &events, &payloads)
+    }
+
+    /// Flush written records to disk
+    pub fn sync(&self) -> Result<()> {
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// Streams written so far
+    pub fn log(&self) -> &EventLog {
+        &self.log
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn unknown_stream(id: StreamId) -> StorageError {
+    StorageError::invalid_format(format!("VLOG stream {} is not declared", id))
+}
+
+fn weight_change_events(changes: &[WeightChange]) -> (Vec<VEVTEvent>, Vec<u8>) {
+    let events = changes.iter().map(WeightChange::event).collect();
+    let payloads = changes.iter().flat_map(|change| change.payload()).collect();
+    (events, payloads)
+}
+
+fn file_header() -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(FILE_HEADER_SIZE);
+    bytes.extend_from_slice(&magic::VLOG);
+    bytes.extend_from_slice(&VLOG_VERSION.to_le_bytes());
+    bytes
+}
+
+fn stream_record(id: StreamId, kind: StreamKind, name: &str) -> Vec<u8> {
+    let mut record = Vec::with_capacity(1 + 8 + 1 + 2 + name.len());
+    record.push(STREAM_RECORD);
+    record.extend_from_slice(&id.raw().to_le_bytes());
+    record.push(kind.code());
+    record.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    record.extend_from_slice(name.as_bytes());
+    record
+}
+
+/// Segment records for `events` and their concatenated `payloads`, at most
+/// [`SEGMENT_EVENTS`] events each
+fn segment_records(id: StreamId, events: &[VEVTEvent], payloads: &[u8]) -> Vec<u8> {
+    let segments = events.len().div_ceil(SEGMENT_EVENTS);
+    let mut records =
+        Vec::with_capacity(events.len() * EVENT_SIZE + payloads.len() + segments * SEGMENT_HEADER_SIZE);
+    let mut payload_start = 0;
+    for chunk in events.chunks(SEGMENT_EVENTS) {
+        let mut body = Vec::with_capacity(chunk.len() * EVENT_SIZE);
+        for event in chunk {
+            body.extend_from_slice(&event.timestamp.to_le_bytes());
+            body.push(event.event_type);
+            body.extend_from_slice(&event.source_id.to_le_bytes());
+            body.extend_from_slice(&event.target_id.to_le_bytes());
+            body.extend_from_slice(&event.payload_size.to_le_bytes());
+            body.push(event.reserved);
+            let payload_end = payload_start + event.payload_size as usize;
+            body.extend_from_slice(&payloads[payload_start..payload_end]);
+            payload_start = payload_end;
+        }
+        records.push(SEGMENT_RECORD);
+        records.extend_from_slice(&id.raw().to_le_bytes());
+        records.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+        records.extend_from_slice(&(body.len() as u64).to_le_bytes());
+        records.extend_from_slice(&chunk[0].timestamp.to_le_bytes());
+        records.extend_from_slice(&chunk[chunk.len() - 1].timestamp.to_le_bytes());
+        records.extend_from_slice(&calculate_checksum(&body).to_le_bytes());
+        records.extend_from_slice(&body);
+    }
+    records
+}
+
+/// Record as read, before it is checked against the log
+enum Record<'a> {
+    Stream { id: StreamId, kind: u8, name: &'a [u8] },
+    Segment { id: StreamId, count: usize, first_ns: u64, last_ns: u64, checksum: u32, body: &'a [u8] },
+    Unknown(u8),
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len())?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Some(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    /// Next record, or `None` if the bytes end inside it
+    fn record(&mut self) -> Option<Record<'a>> {
+        match self.u8()? {
+            STREAM_RECORD => {
+                let id = StreamId::new(self.u64()?);
+                let kind = self.u8()?;
+                let len = self.u16()? as usize;
+                Some(Record::Stream { id, kind, name: self.take(len)? })
+            }
+            SEGMENT_RECORD => {
+                let id = StreamId::new(self.u64()?);
+                let count = self.u32()? as usize;
+                let body_len = usize::try_from(self.u64()?).ok()?;
+                let first_ns = self.u64()?;
+                let last_ns = self.u64()?;
+                let checksum = self.u32()?;
+                let body = self.take(body_len)?;
+                Some(Record::Segment { id, count, first_ns, last_ns, checksum, body })
+            }
+            tag => Some(Record::Unknown(tag)),
+        }
+    }
+}
+
+/// Events and concatenated payloads of a segment body, or `None` unless
+/// exactly `count` events fill it
+fn decode_segment(body: &[u8], count: usize) -> Option<(Vec<VEVTEvent>, Vec<u8>)> {
+    let mut cursor = Cursor { bytes: body, pos: 0 };
+    let mut events = Vec::with_capacity(count.min(body.len() / EVENT_SIZE));
+    let mut payloads = Vec::new();
+    for _ in 0..count {
+        let event = VEVTEvent {
+            timestamp: cursor.u64()?,
+            event_type: cursor.u8()?,
+            source_id: cursor.u32()?,
+            target_id: cursor.u32()?,
+            payload_size: cursor.u16()?,
+            reserved: cursor.u8()?,
+        };
+        payloads.extend_from_slice(cursor.take(event.payload_size as usize)?);
+        events.push(event);
+    }
+    (cursor.pos == body.len()).then_some((events, payloads))
+}
+
+/// Read the file header and every complete record; returns the log and the
+/// length of the bytes it was read from
+fn read_records(data: &[u8]) -> Result<(EventLog, usize)> {
+    if data.len() < FILE_HEADER_SIZE {
+        return Err(StorageError::invalid_format("Data too short for VLOG header"));
+    }
+    validate_magic(data, magic::VLOG)?;
+    validate_version(u32::from_le_bytes([data[4], data[5], data[6], data[7]]), VLOG_VERSION)?;
+
+    let mut log = EventLog::new();
+    let mut cursor = Cursor { bytes: data, pos: FILE_HEADER_SIZE };
+    let mut complete = cursor.pos;
+    while let Some(record) = cursor.record() {
+        let offset = complete as u64;
+        match record {
+            Record::Stream { id, kind, name } => {
+                let kind = StreamKind::from_code(kind).ok_or_else(|| {
+                    StorageError::invalid_format(format!("VLOG stream {} has unknown kind {}", id, kind))
+                })?;
+                let name = String::from_utf8(name.to_vec())
+                    .map_err(|_| StorageError::invalid_format(format!("VLOG stream {} name is not UTF-8", id)))?;
+                log.add_stream(id, kind, name)?;
+            }
+            Record::Segment { id, count, first_ns, last_ns, checksum, body } => {
+                if calculate_checksum(body) != checksum {
+                    return Err(StorageError::CorruptBlock { offset });
+                }
+                let (events, payloads) =
+                    decode_segment(body, count).ok_or(StorageError::CorruptBlock { offset })?;
+                let (first, last) = (events.first().map(|e| e.timestamp), events.last().map(|e| e.timestamp));
+                if (first, last) != (Some(first_ns), Some(last_ns)) {
+                    return Err(StorageError::CorruptBlock { offset });
+                }
+                log.append_with_payloads(id, &events, &payloads)?;
+            }
+            Record::Unknown(tag) => {
+                return Err(StorageError::invalid_format(format!(
+                    "VLOG record at offset {} has unknown tag {}",
+                    offset, tag
+                )));
+            }
+        }
+        complete = cursor.pos;
+    }
+    Ok((log, complete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(timestamp: u64, event_type: u8, source_id: u32) -> VEVTEvent {
+        VEVTEvent { timestamp, event_type, source_id, target_id: u32::MAX, payload_size: 0, reserved: 0 }
+    }
+
+    fn spikes_and_stimuli() -> EventLog {
+        let mut log = EventLog::new();
+        log.add_stream(StreamId::new(1), StreamKind::Spikes, "spikes").expect("Should add stream");
+        log.add_stream(StreamId::new(4), StreamKind::Stimuli, "poisson").expect("Should add stream");
+        log.append(StreamId::new(1), &[event(100, 0, 3), event(300, 0, 1)]).expect("Should append");
+        log.append(StreamId::new(4), &[event(100, 5, 0), event(200, 5, 2)]).expect("Should append");
+        log.append(StreamId::new(1), &[event(300, 0, 2)]).expect("Should append");
+        log
+    }
+
+    #[test]
+    fn streams_are_windowed_and_merged() {
+        let log = spikes_and_stimuli();
+        assert_eq!(log.event_count(), 5);
+        assert_eq!(log.time_range(), Some((Time::from_nanos(100), Time::from_nanos(300))));
+        let spikes = log.stream(StreamId::new(1)).expect("Should have spikes");
+        assert_eq!(spikes.window(Time::from_nanos(150), Time::from_nanos(300)).len(), 2);
+        assert!(spikes.window(Time::from_nanos(301), Time::from_nanos(200)).is_empty());
+        assert_eq!(log.streams_of(StreamKind::Stimuli).count(), 1);
+
+        let merged: Vec<_> = log
+            .merged(Time::ZERO, Time::from_nanos(250))
+            .into_iter()
+            .map(|(id, e)| (id.raw(), e.timestamp))
+            .collect();
+        assert_eq!(merged, vec![(1, 100), (4, 100), (4, 200)]);
+
+        let mut log = log;
+        assert!(log.append(StreamId::new(1), &[event(200, 0, 0)]).is_err());
+        assert!(log.append(StreamId::new(2), &[event(400, 0, 0)]).is_err());
+        assert!(log.add_stream(StreamId::new(4), StreamKind::Control, "again").is_err());
+
+        let (_, events) = crate::vevt::decode_vevt(
+            &log.export_vevt(StreamId::new(4), Time::ZERO, Time::from_nanos(1000)).expect("Should export"),
+        )
+        .expect("Should decode");
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test]
+    fn log_bytes_round_trip() {
+        let log = spikes_and_stimuli();
+        let bytes = log.to_bytes();
+        let loaded = EventLog::from_bytes(&bytes).expect("Should load");
+        let stimuli = loaded.stream(StreamId::new(4)).expect("Should have stimuli");
+        assert_eq!((stimuli.kind, stimuli.name.as_str()), (StreamKind::Stimuli, "poisson"));
+        assert_eq!(stimuli.events()[1].source_id, 2);
+        assert_eq!(loaded.event_count(), 5);
+
+        assert!(EventLog::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 3;
+        corrupt[last] ^= 0xff;
+        assert!(matches!(EventLog::from_bytes(&corrupt), Err(StorageError::CorruptBlock { .. })));
+        let mut foreign = bytes;
+        foreign[..4].copy_from_slice(&magic::VEVT);
+        assert!(matches!(EventLog::from_bytes(&foreign), Err(StorageError::BadMagic { .. })));
+    }
+
+    #[test]
+    fn weight_change_payloads_round_trip() {
+        let change = |time_ns, pre, delta, weight| WeightChange { time_ns, pre, post: 9, delta, weight };
+        let mut log = EventLog::new();
+        log.add_stream(StreamId::new(2), StreamKind::WeightUpdates, "stdp").expect("Should add stream");
+        log.append(StreamId::new(2), &[event(5, 5, 0)]).expect("Should append");
+        log.append_weight_changes(StreamId::new(2), &[change(10, 1, 0.1, 0.6), change(20, 2, -0.2, 0.3)])
+            .expect("Should append");
+        assert!(log.append_with_payloads(StreamId::new(2), &[change(30, 1, 0.1, 0.7).event()], &[0; 4]).is_err());
+
+        let loaded = EventLog::from_bytes(&log.to_bytes()).expect("Should load");
+        let stream = loaded.stream(StreamId::new(2)).expect("Should have updates");
+        assert!(stream.payload(0).is_empty());
+        assert_eq!(stream.payload(2).len(), WeightChange::PAYLOAD_SIZE as usize);
+        let changes: Vec<_> = stream.weight_changes().collect();
+        assert_eq!(changes, vec![change(10, 1, 0.1, 0.6), change(20, 2, -0.2, 0.3)]);
+
+        let dir = tempfile::tempdir().expect("Should create temp dir");
+        let path = dir.path().join("run.vlog");
+        let mut file = FileEventLog::create(&path).expect("Should create");
+        file.add_stream(StreamId::new(2), StreamKind::WeightUpdates, "stdp").expect("Should add stream");
+        file.append_weight_changes(StreamId::new(2), &changes).expect("Should append");
+        drop(file);
+        let reopened = FileEventLog::open(&path).expect("Should reopen");
+        let stream = reopened.log().stream(StreamId::new(2)).expect("Should have updates");
+        assert_eq!(stream.weight_changes().collect::<Vec<_>>(), changes);
+    }
+
+    #[test]
+    fn file_log_appends_and_recovers() {
+        let dir = tempfile::tempdir().expect("Should create temp dir");
+        let path = dir.path().join("run.vlog");
+        let mut file = FileEventLog::create(&path).expect("Should create");
+        file.add_stream(StreamId::new(1), StreamKind::Spikes, "spikes").expect("Should add stream");
+        file.append(StreamId::new(1), &[event(10, 0, 0)]).expect("Should append");
+        file.add_stream(StreamId::new(2), StreamKind::WeightUpdates, "stdp").expect("Should add stream");
+        file.append(StreamId::new(2), &[event(10, 7, 0)]).expect("Should append");
+        assert!(file.append(StreamId::new(1), &[event(5, 0, 0)]).is_err());
+        drop(file);
+
+        // A torn append is dropped on reopen and later appends continue the log
+        let written = std::fs::read(&path).expect("Should read");
+        let mut torn = written.clone();
+        torn.extend_from_slice(&segment_records(StreamId::new(1), &[event(20, 0, 1)], &[])[..10]);
+        std::fs::write(&path, &torn).expect("Should write");
+        let mut file = FileEventLog::open(&path).expect("Should reopen");
+        assert_eq!(file.log().event_count(), 2);
+        file.append(StreamId::new(1), &[event(30, 0, 2)]).expect("Should append");
+        file.sync().expect("Should sync");
+
+        let log = EventLog::from_bytes(&std::fs::read(&path).expect("Should read")).expect("Should load");
+        let spikes = log.stream(StreamId::new(1)).expect("Should have spikes");
+        assert_eq!(spikes.events().iter().map(|e| e.timestamp).collect::<Vec<_>>(), vec![10, 30]);
+        assert_eq!(log.stream(StreamId::new(2)).expect("Should have updates").kind, StreamKind::WeightUpdates);
+    }
+}
@@ -0,0 +1,154 @@
+//! Spike table and rate matrix exporters for the Python analysis stack
+//!
+//! Spike tables have one row per spike with `time_ns` (u64) and `neuron_id`
+//! (u32) columns; as `.npy` they are packed structured arrays. Rate matrices
+//! go to `.npy` through [`RateMatrix::to_npy`](crate::raster::RateMatrix::to_npy).
+//! With the `parquet` feature both also export as uncompressed Parquet files
+//! (rate matrices in long form: one row per neuron and bin) that
+//! `pandas.read_parquet` and `polars.read_parquet` load directly.
+
+use crate::{
+    error::Result,
+    npy::{encode_npy_records, NpyColumn},
+};
+
+/// Spike table `(time_ns, neuron_id)` as a structured `.npy` array
+pub fn spikes_to_npy(spikes: &[(u64, u32)]) -> Result<Vec<u8>> {
+    let (times, ids): (Vec<u64>, Vec<u32>) = spikes.iter().copied().unzip();
+    encode_npy_records(&[("time_ns", NpyColumn::U64(&times)), ("neuron_id", NpyColumn::U32(&ids))])
+}
+
+#[cfg(feature = "parquet")]
+pub use self::parquet_export::{rates_to_parquet, spikes_to_parquet};
+
+#[cfg(feature = "parquet")]
+mod parquet_export {
+    use super::*;
+    use crate::{error::StorageError, raster::RateMatrix};
+
+    use parquet::data_type::{FloatType, Int32Type, Int64Type};
+    use parquet::errors::ParquetError;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::sync::Arc;
+
+    const SPIKES_SCHEMA: &str = "message spikes {
+        REQUIRED INT64 time_ns (INTEGER(64,false));
+        REQUIRED INT32 neuron_id (INTEGER(32,false));
+    }";
+
+    const RATES_SCHEMA: &str = "message rates {
+        REQUIRED INT32 neuron_id (INTEGER(32,false));
+        REQUIRED INT64 bin_start_ns (INTEGER(64,false));
+        REQUIRED INT64 bin_width_ns (INTEGER(64,false));
+        REQUIRED INT32 count (INTEGER(32,false));
+        REQUIRED FLOAT rate_hz;
+    }";
+
+    /// One column's values, in schema order
+    enum Column {
+        Int32(Vec<i32>),
+        Int64(Vec<i64>),
+        Float(Vec<f32>),
+    }
+
+    /// Spike table `(time_ns, neuron_id)` as a Parquet file
+    pub fn spikes_to_parquet(spikes: &[(u64, u32)]) -> Result<Vec<u8>> {
+        // Unsigned logical types are stored in their signed physical type
+        write_table(SPIKES_SCHEMA, vec![
+            Column::Int64(spikes.iter().map(|&(t, _)| t as i64).collect()),
+            Column::Int32(spikes.iter().map(|&(_, id)| id as i32).collect()),
+        ])
+    }
+
+    /// Rate matrix in long form `(neuron_id, bin_start_ns, bin_width_ns, count, rate_hz)`
+    pub fn rates_to_parquet(matrix: &RateMatrix) -> Result<Vec<u8>> {
+        let bins = matrix.bins();
+        let cells = matrix.neurons().len() * bins;
+        let bin_of = |cell: usize| cell % bins;
+        write_table(RATES_SCHEMA, vec![
+            Column::Int32((0..cells).map(|cell| matrix.neurons()[cell / bins] as i32).collect()),
+            Column::Int64((0..cells).map(|cell| matrix.bin_start_ns(bin_of(cell)) as i64).collect()),
+            Column::Int64((0..cells).map(|cell| matrix.bin_width_ns(bin_of(cell)) as i64).collect()),
+            Column::Int32(matrix.counts().iter().map(|&c| c as i32).collect()),
+            Column::Float(matrix.rates_hz()),
+        ])
+    }
+
+    /// Write `columns` as a single row group
+    fn write_table(schema: &str, columns: Vec<Column>) -> Result<Vec<u8>> {
+        let schema = Arc::new(parse_message_type(schema).map_err(parquet_error)?);
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(Vec::new(), schema, props).map_err(parquet_error)?;
+        let mut row_group = writer.next_row_group().map_err(parquet_error)?;
+        for column in &columns {
+            let mut column_writer = row_group
+                .next_column()
+                .map_err(parquet_error)?
+                .ok_or_else(|| parquet_error(ParquetError::General("schema has fewer columns than data".into())))?;
+            match column {
+                Column::Int32(values) => column_writer.typed::<Int32Type>().write_batch(values, None, None),
+                Column::Int64(values) => column_writer.typed::<Int64Type>().write_batch(values, None, None),
+                Column::Float(values) => column_writer.typed::<FloatType>().write_batch(values, None, None),
+            }
+            .map_err(parquet_error)?;
+            column_writer.close().map_err(parquet_error)?;
+        }
+        row_group.close().map_err(parquet_error)?;
+        writer.into_inner().map_err(parquet_error)
+    }
+
+    fn parquet_error(e: ParquetError) -> StorageError {
+        StorageError::OperationError { message: format!("parquet: {}", e) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPIKES: [(u64, u32); 3] = [(1_000, 2), (2_500, 0), (4_000, 2)];
+
+    #[test]
+    fn test_spike_table_npy() {
+        let bytes = spikes_to_npy(&SPIKES).expect("Should encode spike table");
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).expect("Should be ASCII");
+        assert!(header.contains("[('time_ns', '<u8'), ('neuron_id', '<u4')]"));
+        assert!(header.contains("'shape': (3,)"));
+        assert_eq!(bytes.len() - 10 - header_len, 3 * 12);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_round_trip() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::RowAccessor;
+        use crate::raster::{RateMatrix, SpikeWindow};
+
+        let bytes = spikes_to_parquet(&SPIKES).expect("Should write spike table");
+        let reader = SerializedFileReader::new(bytes::Bytes::from(bytes)).expect("Should read parquet");
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+        let rows: Vec<(u64, u32)> = reader
+            .get_row_iter(None)
+            .expect("Should iterate rows")
+            .map(|row| {
+                let row = row.expect("Should decode row");
+                (row.get_ulong(0).expect("time_ns"), row.get_uint(1).expect("neuron_id"))
+            })
+            .collect();
+        assert_eq!(rows, SPIKES.to_vec());
+
+        let window = SpikeWindow::new(0, 5_000).expect("Should accept window");
+        let matrix = RateMatrix::bin(SPIKES, window, 2_000, None).expect("Should bin spikes");
+        let bytes = rates_to_parquet(&matrix).expect("Should write rate matrix");
+        let reader = SerializedFileReader::new(bytes::Bytes::from(bytes)).expect("Should read parquet");
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 6);
+        let last = reader.get_row_iter(None).expect("Should iterate rows").last().expect("Should have rows").expect("Should decode row");
+        // Neuron 2, last bin [4000, 5000): one spike in 1 us
+        assert_eq!((last.get_uint(0).unwrap(), last.get_ulong(1).unwrap(), last.get_ulong(2).unwrap()), (2, 4_000, 1_000));
+        assert_eq!(last.get_uint(3).unwrap(), 1);
+        assert_eq!(last.get_float(4).unwrap(), 1_000_000.0);
+    }
+}
@@ -1,14 +1,29 @@
 //! File-based storage backend
+//!
+//! Each generation is persisted as `gen_<hex>.vcsr` in the base directory and
+//! mirrored in an in-memory cache. Existing generations are loaded on open.
+//! Generation lineage (child/parent pairs) is kept in `lineage.txt`.
+//!
+//! Every write goes through [`crate::atomic`]: files are replaced atomically,
+//! and a derived generation is committed together with its lineage entry, so
+//! an interrupted write never leaves a half-written snapshot behind. Opening a
+//! store finishes or discards a commit that was interrupted.
 
 use crate::{
+    atomic::{self, Commit},
+    delta::generation_file,
     error::{Result, StorageError},
     ids::GenerationId,
     memory::MemoryStore,
-    traits::{HypergraphSnapshot, HypergraphStore, MorphologyOp},
+    morphology::MorphologyReport,
+    traits::{HypergraphStore, MorphologyOp},
+    vcsr::VCSRSnapshot,
 };
 
 use std::path::Path;
 
+const LINEAGE_FILE: &str = "lineage.txt";
+
 /// File-based hypergraph storage implementation
 pub struct FileStore {
     /// In-memory cache
@@ -18,72 +33,227 @@ pub struct FileStore {
 }
 
 impl FileStore {
-    /// Create a new file-based store
+    /// Open (or create) a file-based store, loading any persisted generations
     pub fn new<P: AsRef<Path>>(base_dir: P) -> Result<Self> {
         let base_dir = base_dir.as_ref().to_path_buf();
         std::fs::create_dir_all(&base_dir)?;
-        
+        let recovery = atomic::recover(&base_dir)?;
+        if !recovery.rolled_forward.is_empty() || recovery.discarded > 0 {
+            log::warn!(
+                "Recovered interrupted write in {}: {} file(s) completed, {} temporary file(s) discarded",
+                base_dir.display(),
+                recovery.rolled_forward.len(),
+                recovery.discarded
+            );
+        }
+
+        let mut cache = MemoryStore::new();
+        for entry in std::fs::read_dir(&base_dir)? {
+            let path = entry?.path();
+            let is_generation = path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with("gen_") && n.ends_with(".vcsr"))
+                .unwrap_or(false);
+            if !is_generation {
+                continue;
+            }
+            let snapshot = VCSRSnapshot::from_bytes(&std::fs::read(&path)?)?;
+            cache.add_snapshot(GenerationId::new(snapshot.header.generation), snapshot);
+        }
+
+        let lineage_path = base_dir.join(LINEAGE_FILE);
+        if lineage_path.exists() {
+            for line in std::fs::read_to_string(&lineage_path)?.lines() {
+                let mut fields = line.split_whitespace().map(str::parse::<u64>);
+                if let (Some(Ok(child)), Some(Ok(parent))) = (fields.next(), fields.next()) {
+                    cache.set_parent(GenerationId::new(child), GenerationId::new(parent));
+                }
+            }
+        }
+
         Ok(Self {
-            cache: MemoryStore::new(),
+            cache,
             base_dir,
         })
     }
-    
+
+    /// Base directory of this store
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// Persist a snapshot as a new generation (its header generation is overwritten)
+    pub fn add_snapshot(&mut self, generation: GenerationId, mut snapshot: VCSRSnapshot) -> Result<()> {
+        snapshot.header.generation = generation.raw();
+        snapshot.finalize();
+        atomic::write_atomic(self.generation_path(generation), &snapshot.to_bytes())?;
+        self.cache.add_snapshot(generation, snapshot);
+        Ok(())
+    }
+
+    /// Next unused generation ID
+    pub fn next_generation(&self) -> GenerationId {
+        self.cache.latest_generation()
+            .map(|g| g.next())
+            .unwrap_or(GenerationId::new(1))
+    }
+
+    /// Parent generation a generation was derived from, if known
+    pub fn parent_of(&self, generation: GenerationId) -> Option<GenerationId> {
+        self.cache.parent_of(generation)
+    }
+
+    /// Create and persist a new generation from `base`, reporting what changed
+    pub fn create_generation_with_report(
+        &mut self,
+        base: GenerationId,
+        operations: &[MorphologyOp],
+    ) -> Result<(GenerationId, MorphologyReport)> {
+        let (generation, report) = self.cache.create_generation_with_report(base, operations)?;
+        // Snapshot and lineage land together or not at all
+        let mut commit = Commit::new(&self.base_dir)?;
+        commit.stage(&generation_file(generation, "vcsr"), &self.cache.raw_snapshot(generation)?.to_bytes())?;
+        commit.stage(LINEAGE_FILE, self.lineage_text()?.as_bytes())?;
+        commit.commit()?;
+        Ok((generation, report))
+    }
+
+    fn lineage_text(&self) -> Result<String> {
+        let mut text = String::new();
+        for generation in self.cache.list_generations(None, None)? {
+            if let Some(parent) = self.cache.parent_of(generation) {
+                text.push_str(&format!("{} {}\n", generation.raw(), parent.raw()));
+            }
+        }
+        Ok(text)
+    }
+
+    fn write_lineage(&self) -> Result<()> {
+        atomic::write_atomic(self.base_dir.join(LINEAGE_FILE), self.lineage_text()?.as_bytes())
+    }
+
     /// Get the path for a generation file
     fn generation_path(&self, generation: GenerationId) -> std::path::PathBuf {
-        self.base_dir.join(format!("gen_{:016x}.vcsr", generation.raw()))
+        self.base_dir.join(generation_file(generation, "vcsr"))
     }
 }
 
 impl HypergraphStore for FileStore {
     type Snapshot = <MemoryStore as HypergraphStore>::Snapshot;
     type Error = StorageError;
-    
+
     fn get_snapshot(&self, generation: GenerationId) -> Result<Self::Snapshot> {
-        // Try cache first
-        if let Ok(snapshot) = self.cache.get_snapshot(generation) {
-            return Ok(snapshot);
-        }
-        
-        // TODO: Load from file
-        Err(StorageError::GenerationNotFound { 
-            generation: generation.raw() 
-        })
+        // All persisted generations are loaded into the cache on open
+        self.cache.get_snapshot(generation)
     }
-    
+
     fn latest_generation(&self) -> Result<GenerationId> {
         self.cache.latest_generation()
     }
-    
+
     fn list_generations(
-        &self, 
-        start: Option<GenerationId>, 
+        &self,
+        start: Option<GenerationId>,
         end: Option<GenerationId>
     ) -> Result<Vec<GenerationId>> {
         self.cache.list_generations(start, end)
     }
-    
+
     fn create_generation(
-        &mut self, 
-        base: GenerationId, 
+        &mut self,
+        base: GenerationId,
         operations: &[MorphologyOp]
     ) -> Result<GenerationId> {
-        self.cache.create_generation(base, operations)
+        self.create_generation_with_report(base, operations)
+            .map(|(generation, _)| generation)
     }
-    
+
     fn compact(&mut self, keep_generations: &[GenerationId]) -> Result<()> {
-        self.cache.compact(keep_generations)
+        for generation in self.cache.list_generations(None, None)? {
+            if !keep_generations.contains(&generation) {
+                let path = self.generation_path(generation);
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+            }
+        }
+        self.cache.compact(keep_generations)?;
+        self.write_lineage()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{traits::HypergraphSnapshot, NeuronId};
 
     #[test]
     fn test_file_store_creation() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let store = FileStore::new(temp_dir.path()).unwrap();
+        let _store = FileStore::new(temp_dir.path()).unwrap();
         assert!(temp_dir.path().exists());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_file_store_persistence() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let edges = [(NeuronId::new(0), NeuronId::new(1), 0.5)];
+        let snapshot = VCSRSnapshot::from_edges(GenerationId::new(0), 2, 0, &edges).unwrap();
+
+        {
+            let mut store = FileStore::new(temp_dir.path()).unwrap();
+            assert_eq!(store.next_generation(), GenerationId::new(1));
+            store.add_snapshot(GenerationId::new(1), snapshot).unwrap();
+            store.compact(&[GenerationId::new(1)]).unwrap();
+        }
+
+        let store = FileStore::new(temp_dir.path()).unwrap();
+        assert_eq!(store.latest_generation().unwrap(), GenerationId::new(1));
+        assert_eq!(store.next_generation(), GenerationId::new(2));
+        let loaded = store.get_snapshot(GenerationId::new(1)).unwrap();
+        assert_eq!(loaded.edge_weight(NeuronId::new(0), NeuronId::new(1)), Some(0.5));
+    }
+
+    #[test]
+    fn test_file_store_lineage() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let edges = [(NeuronId::new(0), NeuronId::new(1), 0.5)];
+        let snapshot = VCSRSnapshot::from_edges(GenerationId::new(0), 2, 0, &edges).unwrap();
+
+        {
+            let mut store = FileStore::new(temp_dir.path()).unwrap();
+            store.add_snapshot(GenerationId::new(1), snapshot).unwrap();
+            let ops = [MorphologyOp::SetWeight {
+                source: NeuronId::new(0),
+                target: NeuronId::new(1),
+                weight: 0.9,
+            }];
+            let (generation, report) = store.create_generation_with_report(GenerationId::new(1), &ops).unwrap();
+            assert_eq!(generation, GenerationId::new(2));
+            assert_eq!(report.weights_modified, 1);
+        }
+
+        let store = FileStore::new(temp_dir.path()).unwrap();
+        assert_eq!(store.parent_of(GenerationId::new(2)), Some(GenerationId::new(1)));
+        let snapshot = store.get_snapshot(GenerationId::new(2)).unwrap();
+        assert_eq!(snapshot.edge_weight(NeuronId::new(0), NeuronId::new(1)), Some(0.9));
+    }
+
+    #[test]
+    fn test_file_store_discards_interrupted_write() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let edges = [(NeuronId::new(0), NeuronId::new(1), 0.5)];
+        let snapshot = VCSRSnapshot::from_edges(GenerationId::new(0), 2, 0, &edges).unwrap();
+        {
+            let mut store = FileStore::new(temp_dir.path()).unwrap();
+            store.add_snapshot(GenerationId::new(1), snapshot).unwrap();
+        }
+        // A write cut short before its rename
+        let partial = temp_dir.path().join(".gen_0000000000000002.vcsr.snn-tmp-1-0");
+        std::fs::write(&partial, b"VCSR").unwrap();
+
+        let store = FileStore::new(temp_dir.path()).unwrap();
+        assert_eq!(store.latest_generation().unwrap(), GenerationId::new(1));
+        assert!(!partial.exists());
+    }
+}
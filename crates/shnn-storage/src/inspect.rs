@@ -0,0 +1,473 @@
+//! Header inspection for binary storage artifacts
+//!
+//! [`inspect`] recognizes a VEVT, VCSR, VMSK or VDLT file by its magic number
+//! and reads its header without trusting it: checksums are recomputed and
+//! reported, and inconsistencies (truncated sections, counts that disagree
+//! with the body) are collected as problems instead of failing, so a damaged
+//! file can still be described. Version 2 artifacts also carry a per-block CRC32C
+//! table; blocks that fail it are listed by file offset.
+
+use crate::{
+    error::{Result, StorageError},
+    magic,
+    schemas::{calculate_checksum, corrupt_blocks, validate_version},
+    spike_block::CompressedSpikes,
+    vcsr::{VCSRHeader, VCSRVertex, VCSR_VERSION},
+    vevt::{VEVTEncoding, VEVTEvent, VEVTHeader, VEVT_VERSION},
+    vmsk::{MaskCollection, VMSKHeader, VMSK_VERSION},
+};
+
+use core::mem;
+use std::fmt;
+
+/// Binary format of an artifact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// Event stream
+    Vevt,
+    /// Connectivity snapshot
+    Vcsr,
+    /// Mask or mask collection
+    Vmsk,
+    /// Snapshot delta
+    Vdlt,
+}
+
+impl ArtifactKind {
+    /// Format of `bytes`, from its magic number
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        let found: [u8; 4] = bytes.get(..4)?.try_into().ok()?;
+        match found {
+            magic::VEVT => Some(ArtifactKind::Vevt),
+            magic::VCSR => Some(ArtifactKind::Vcsr),
+            magic::VMSK => Some(ArtifactKind::Vmsk),
+            magic::VDLT => Some(ArtifactKind::Vdlt),
+            _ => None,
+        }
+    }
+
+    /// Upper-case format name
+    pub fn name(self) -> &'static str {
+        match self {
+            ArtifactKind::Vevt => "VEVT",
+            ArtifactKind::Vcsr => "VCSR",
+            ArtifactKind::Vmsk => "VMSK",
+            ArtifactKind::Vdlt => "VDLT",
+        }
+    }
+}
+
+impl fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Outcome of recomputing one stored checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumStatus {
+    /// Stored and recomputed values agree
+    Valid(u32),
+    /// Stored value differs from the recomputed one
+    Mismatch {
+        /// Value in the file
+        expected: u32,
+        /// Value computed from the bytes
+        computed: u32,
+    },
+    /// The writer left the field zero
+    Absent,
+}
+
+/// A named checksum of an artifact
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumCheck {
+    /// `header` or `data`
+    pub name: &'static str,
+    /// Result of the check
+    pub status: ChecksumStatus,
+}
+
+/// Description of an artifact's header and integrity
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactInfo {
+    /// Detected format
+    pub kind: ArtifactKind,
+    /// Schema version field
+    pub version: u32,
+    /// File size in bytes
+    pub size_bytes: u64,
+    /// Header fields in layout order, formatted for display
+    pub fields: Vec<(&'static str, String)>,
+    /// Time range covered (ns), for event streams
+    pub time_range_ns: Option<(u64, u64)>,
+    /// Generation id, for snapshots, deltas and masks
+    pub generation: Option<u64>,
+    /// Records in the body (events, incidences, set bits, changes)
+    pub records: Option<u64>,
+    /// Recomputed checksums
+    pub checksums: Vec<ChecksumCheck>,
+    /// Per-block CRC32C table entries (version 2 artifacts)
+    pub blocks: Option<usize>,
+    /// File offsets of blocks failing their CRC32C
+    pub corrupt_blocks: Vec<u64>,
+    /// Structural inconsistencies found while reading
+    pub problems: Vec<String>,
+}
+
+impl ArtifactInfo {
+    fn new(kind: ArtifactKind, version: u32, size_bytes: usize) -> Self {
+        Self {
+            kind,
+            version,
+            size_bytes: size_bytes as u64,
+            fields: Vec::new(),
+            time_range_ns: None,
+            generation: None,
+            records: None,
+            checksums: Vec::new(),
+            blocks: None,
+            corrupt_blocks: Vec::new(),
+            problems: Vec::new(),
+        }
+    }
+
+    /// Whether every checksum present matches and no problem was found
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+            && self.corrupt_blocks.is_empty()
+            && self.checksums.iter().all(|c| !matches!(c.status, ChecksumStatus::Mismatch { .. }))
+    }
+
+    fn field(&mut self, name: &'static str, value: impl ToString) {
+        self.fields.push((name, value.to_string()));
+    }
+
+    fn checksum(&mut self, name: &'static str, expected: u32, computed: u32) {
+        let status = match expected {
+            0 => ChecksumStatus::Absent,
+            e if e == computed => ChecksumStatus::Valid(e),
+            _ => ChecksumStatus::Mismatch { expected, computed },
+        };
+        self.checksums.push(ChecksumCheck { name, status });
+    }
+
+    fn check_version(&mut self, supported: u32) {
+        if let Err(e) = validate_version(self.version, supported) {
+            self.problems.push(e.to_string());
+        }
+    }
+
+    /// Check `bytes[data_start..blocks_offset]` against the block table that follows it;
+    /// returns the end of the data section
+    fn check_blocks(&mut self, bytes: &[u8], data_start: usize, blocks_offset: u64, block_size: u32) -> usize {
+        let end = match usize::try_from(blocks_offset) {
+            Ok(end) if end >= data_start && end <= bytes.len() => end,
+            _ => {
+                self.problems.push(format!("block table offset {} out of range", blocks_offset));
+                return bytes.len();
+            }
+        };
+        self.field("block_size", block_size);
+        match corrupt_blocks(&bytes[data_start..end], &bytes[end..], block_size, data_start as u64) {
+            Ok(corrupt) => {
+                self.blocks = Some((bytes.len() - end) / 4);
+                self.corrupt_blocks = corrupt;
+            }
+            Err(e) => self.problems.push(format!("block table unreadable: {}", e)),
+        }
+        end
+    }
+}
+
+/// Describe the artifact in `bytes`
+///
+/// Fails only when the magic number is unknown or the header is truncated.
+pub fn inspect(bytes: &[u8]) -> Result<ArtifactInfo> {
+    let kind = ArtifactKind::detect(bytes).ok_or_else(|| match bytes.get(..4) {
+        Some(found) => StorageError::invalid_format(format!(
+            "unrecognized magic {:?} (expected VEVT, VCSR, VMSK or VDLT)",
+            String::from_utf8_lossy(found)
+        )),
+        None => StorageError::invalid_format("file shorter than a magic number"),
+    })?;
+    match kind {
+        ArtifactKind::Vevt => inspect_vevt(bytes),
+        ArtifactKind::Vcsr => inspect_vcsr(bytes),
+        ArtifactKind::Vmsk => inspect_vmsk(bytes),
+        ArtifactKind::Vdlt => inspect_vdlt(bytes),
+    }
+}
+
+/// Copy a `repr(C)` header out of possibly unaligned bytes
+fn read_header<T>(kind: ArtifactKind, bytes: &[u8]) -> Result<T> {
+    if bytes.len() < mem::size_of::<T>() {
+        return Err(StorageError::invalid_format(format!(
+            "{} header truncated: need {} bytes, got {}",
+            kind,
+            mem::size_of::<T>(),
+            bytes.len()
+        )));
+    }
+    // SAFETY: length checked; headers are repr(C) plain data valid for any bit pattern
+    Ok(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) })
+}
+
+/// CRC of the first `size` bytes with the 4-byte checksum field at `offset` zeroed
+fn header_crc(bytes: &[u8], size: usize, offset: usize) -> u32 {
+    let mut header = bytes[..size].to_vec();
+    header[offset..offset + 4].fill(0);
+    calculate_checksum(&header)
+}
+
+fn inspect_vevt(bytes: &[u8]) -> Result<ArtifactInfo> {
+    let header: VEVTHeader = read_header(ArtifactKind::Vevt, bytes)?;
+    let mut info = ArtifactInfo::new(ArtifactKind::Vevt, header.version, bytes.len());
+    info.check_version(VEVT_VERSION);
+    info.field("stream_id", header.stream_id);
+    info.field("time_start_ns", header.time_start);
+    info.field("time_end_ns", header.time_end);
+    info.field("time_resolution_ns", header.time_resolution);
+    info.field("total_events", header.total_events);
+    info.field("spike_events", header.spike_events);
+    info.field("control_events", header.control_events);
+    info.field("compression", header.compression);
+    info.field("encoding", match header.encoding {
+        VEVTEncoding::BINARY => "binary".to_string(),
+        VEVTEncoding::DELTA_COMPRESSED => "delta".to_string(),
+        VEVTEncoding::RLE => "rle".to_string(),
+        other => format!("unknown ({})", other),
+    });
+    info.field("flags", format!("{:#06x}", header.flags));
+    info.field("events_offset", header.events_offset);
+    info.time_range_ns = Some((header.time_start, header.time_end));
+    if header.time_end < header.time_start {
+        info.problems.push("time_end precedes time_start".to_string());
+    }
+
+    let size = mem::size_of::<VEVTHeader>();
+    let computed = header_crc(bytes, size, mem::offset_of!(VEVTHeader, header_checksum));
+    info.checksum("header", header.header_checksum, computed);
+
+    let Some(start) = usize::try_from(header.events_offset).ok().filter(|&offset| offset <= bytes.len()) else {
+        info.problems.push(format!("events_offset {} beyond end of file", header.events_offset));
+        return Ok(info);
+    };
+    let end = match header.version {
+        1 => bytes.len(),
+        _ => info.check_blocks(bytes, start, header.blocks_offset, header.block_size),
+    };
+    let body = &bytes[start..end];
+    info.checksum("data", header.data_checksum, calculate_checksum(body));
+    let events = match header.encoding {
+        VEVTEncoding::DELTA_COMPRESSED => match CompressedSpikes::from_bytes(body) {
+            Ok(spikes) => Some(spikes.len() as u64),
+            Err(e) => {
+                info.problems.push(format!("spike blocks unreadable: {}", e));
+                None
+            }
+        },
+        _ => {
+            let event_size = mem::size_of::<VEVTEvent>();
+            if body.len() % event_size != 0 {
+                info.problems.push(format!("body of {} bytes is not a whole number of {}-byte events", body.len(), event_size));
+            }
+            Some((body.len() / event_size) as u64)
+        }
+    };
+    if let Some(events) = events {
+        if events != header.total_events {
+            info.problems.push(format!("header counts {} events, body holds {}", header.total_events, events));
+        }
+    }
+    info.records = events;
+    Ok(info)
+}
+
+fn inspect_vcsr(bytes: &[u8]) -> Result<ArtifactInfo> {
+    let header: VCSRHeader = read_header(ArtifactKind::Vcsr, bytes)?;
+    let mut info = ArtifactInfo::new(ArtifactKind::Vcsr, header.version, bytes.len());
+    info.check_version(VCSR_VERSION);
+    info.field("generation", header.generation);
+    info.field("timestamp_ns", header.timestamp);
+    info.field("num_vertices", header.num_vertices);
+    info.field("num_hyperedges", header.num_hyperedges);
+    info.field("num_incidences", header.num_incidences);
+    info.field("capabilities", format!("{:#x}", header.capabilities));
+    info.generation = Some(header.generation);
+    info.records = Some(header.num_incidences);
+
+    let size = mem::size_of::<VCSRHeader>();
+    let computed = header_crc(bytes, size, mem::offset_of!(VCSRHeader, header_checksum));
+    info.checksum("header", header.header_checksum, computed);
+
+    // vertices, row pointers (num_vertices + 1), column indices and weights
+    let body_len = (header.num_vertices as u64) * mem::size_of::<VCSRVertex>() as u64
+        + (header.num_vertices as u64 + 1) * 8
+        + header.num_incidences.saturating_mul(8);
+    let end = match header.version {
+        1 => bytes.len(),
+        _ => info.check_blocks(bytes, size, header.blocks_offset, header.block_size),
+    };
+    let available = (end - size) as u64;
+    match available.cmp(&body_len) {
+        std::cmp::Ordering::Less => {
+            info.problems.push(format!("body truncated: header implies {} bytes, {} present", body_len, available));
+        }
+        ordering => {
+            if ordering == std::cmp::Ordering::Greater {
+                info.problems.push(format!("{} unexpected bytes after the weights", available - body_len));
+            }
+            let body = &bytes[size..size + body_len as usize];
+            info.checksum("data", header.data_checksum, calculate_checksum(body));
+        }
+    }
+    Ok(info)
+}
+
+fn inspect_vmsk(bytes: &[u8]) -> Result<ArtifactInfo> {
+    let header: VMSKHeader = read_header(ArtifactKind::Vmsk, bytes)?;
+    let mut info = ArtifactInfo::new(ArtifactKind::Vmsk, header.version, bytes.len());
+    info.check_version(VMSK_VERSION);
+    let size = mem::size_of::<VMSKHeader>();
+    let computed = header_crc(bytes, size, mem::offset_of!(VMSKHeader, header_checksum));
+    info.checksum("header", header.header_checksum, computed);
+    let end = match header.version {
+        1 => bytes.len(),
+        _ => info.check_blocks(bytes, size, header.blocks_offset, header.block_size),
+    };
+    if header.version >= 2 {
+        info.checksum("data", header.data_checksum, calculate_checksum(&bytes[size..end]));
+    }
+
+    if header.flags & MaskCollection::FLAG_COLLECTION != 0 {
+        info.field("layout", "collection");
+        match MaskCollection::from_bytes(bytes) {
+            Ok(collection) => {
+                info.field("masks", collection.len());
+                info.records = Some(collection.len() as u64);
+            }
+            Err(e) => info.problems.push(format!("collection unreadable: {}", e)),
+        }
+        return Ok(info);
+    }
+
+    info.field("mask_id", header.mask_id);
+    info.field("generation", header.generation);
+    info.field("mask_type", header.mask_type);
+    info.field("total_bits", header.total_bits);
+    info.field("active_bits", header.active_bits);
+    info.generation = Some(header.generation);
+
+    let words = header.total_bits.div_ceil(64);
+    let bitmap = usize::try_from(words.saturating_mul(8)).ok().and_then(|len| bytes[..end].get(size..size.checked_add(len)?));
+    match bitmap {
+        Some(bitmap) => {
+            let set: u64 = bitmap.chunks_exact(8)
+                .map(|word| u64::from_le_bytes(word.try_into().unwrap()).count_ones() as u64)
+                .sum();
+            if set != header.active_bits {
+                info.problems.push(format!("header counts {} active bits, bitmap has {}", header.active_bits, set));
+            }
+            info.records = Some(set);
+        }
+        None => info.problems.push(format!("bitmap truncated: {} bits need {} words", header.total_bits, words)),
+    }
+    Ok(info)
+}
+
+fn inspect_vdlt(bytes: &[u8]) -> Result<ArtifactInfo> {
+    const HEADER_SIZE: usize = 40;
+    const CHANGE_SIZE: u64 = 13;
+    if bytes.len() < HEADER_SIZE {
+        return Err(StorageError::invalid_format(format!(
+            "VDLT header truncated: need {} bytes, got {}",
+            HEADER_SIZE,
+            bytes.len()
+        )));
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+    let mut info = ArtifactInfo::new(ArtifactKind::Vdlt, u32_at(4), bytes.len());
+    info.check_version(crate::delta::VDLT_VERSION);
+    let (generation, parent, changes) = (u64_at(8), u64_at(16), u64_at(28));
+    info.field("generation", generation);
+    info.field("parent", parent);
+    info.field("num_vertices", u32_at(24));
+    info.field("num_changes", changes);
+    info.generation = Some(generation);
+    info.records = Some(changes);
+
+    let body = &bytes[HEADER_SIZE..];
+    if body.len() as u64 != changes.saturating_mul(CHANGE_SIZE) {
+        info.problems.push(format!("header counts {} changes, body has {} bytes", changes, body.len()));
+    }
+    // The delta stores its body CRC unconditionally, so zero is a real value here
+    let (expected, computed) = (u32_at(36), calculate_checksum(body));
+    info.checksums.push(ChecksumCheck {
+        name: "data",
+        status: if expected == computed {
+            ChecksumStatus::Valid(expected)
+        } else {
+            ChecksumStatus::Mismatch { expected, computed }
+        },
+    });
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ids::{GenerationId, MaskId, StreamId},
+        traits::{Mask, MaskType},
+        vcsr::VCSRSnapshot,
+        vevt::encode_vevt,
+        vmsk::BitmapMask,
+        NeuronId, Time,
+    };
+
+    #[test]
+    fn test_inspect_formats() {
+        let events: Vec<VEVTEvent> = (0..5)
+            .map(|i| VEVTEvent { timestamp: i * 10, event_type: 0, source_id: i as u32, target_id: u32::MAX, payload_size: 0, reserved: 0 })
+            .collect();
+        let vevt = encode_vevt(StreamId::new(3), Time::from_nanos(0), Time::from_nanos(50), &events).expect("Should encode");
+        let info = inspect(&vevt).expect("Should inspect VEVT");
+        assert_eq!(info.kind, ArtifactKind::Vevt);
+        assert_eq!((info.time_range_ns, info.records), (Some((0, 50)), Some(5)));
+        assert!(info.is_valid(), "{:?}", info);
+
+        let edges = [(NeuronId::new(0), NeuronId::new(1), 0.5), (NeuronId::new(1), NeuronId::new(2), 0.25)];
+        let vcsr = VCSRSnapshot::from_edges(GenerationId::new(7), 3, 0, &edges).expect("Should build").to_bytes();
+        let info = inspect(&vcsr).expect("Should inspect VCSR");
+        assert_eq!((info.generation, info.records), (Some(7), Some(2)));
+        assert!(info.is_valid(), "{:?}", info);
+
+        let mut mask = BitmapMask::new(MaskId::new(1), MaskType::VertexMask, GenerationId::new(2), 100);
+        mask.set_bit(3).expect("Should set bit");
+        let vmsk = mask.export_vmsk().expect("Should export");
+        let info = inspect(&vmsk).expect("Should inspect VMSK");
+        assert_eq!(info.records, Some(1));
+        assert!(info.is_valid(), "{:?}", info);
+
+        assert!(inspect(b"NOPE1234").is_err());
+    }
+
+    #[test]
+    fn test_inspect_reports_corruption() {
+        let edges = [(NeuronId::new(0), NeuronId::new(1), 0.5)];
+        let mut vcsr = VCSRSnapshot::from_edges(GenerationId::new(1), 2, 0, &edges).expect("Should build").to_bytes();
+        // Last weight byte, just before the one-entry block table
+        let last_weight = vcsr.len() - 5;
+        vcsr[last_weight] ^= 0xff;
+        let info = inspect(&vcsr).expect("Should still inspect");
+        assert!(!info.is_valid());
+        assert!(matches!(info.checksums[1].status, ChecksumStatus::Mismatch { .. }));
+        assert_eq!(info.corrupt_blocks, vec![mem::size_of::<VCSRHeader>() as u64]);
+
+        vcsr.truncate(vcsr.len() - 6);
+        let info = inspect(&vcsr).expect("Should still inspect");
+        assert!(info.problems.iter().any(|p| p.contains("truncated")));
+    }
+}
@@ -8,103 +8,8 @@
 #![deny(missing_docs)]
 #![warn(clippy::all)]
 
-// Temporary local type definitions until shnn-core compilation is fixed
-/// Neuron identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct NeuronId(pub u32);
-
-impl NeuronId {
-    /// Create a new neuron ID
-    pub const fn new(id: u32) -> Self {
-        Self(id)
-    }
-    
-    /// Get the raw ID value
-    pub const fn raw(&self) -> u32 {
-        self.0
-    }
-}
-
-/// Hyperedge identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct HyperedgeId(pub u32);
-
-impl HyperedgeId {
-    /// Create a new hyperedge ID
-    pub const fn new(id: u32) -> Self {
-        Self(id)
-    }
-    
-    /// Get the raw ID value
-    pub const fn raw(&self) -> u32 {
-        self.0
-    }
-}
-
-/// Time representation (nanoseconds since epoch)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Time(pub u64);
-
-impl Time {
-    /// Create a new time value
-    pub const fn new(ns: u64) -> Self {
-        Self(ns)
-    }
-    
-    /// Create time from nanoseconds
-    pub const fn from_nanos(ns: u64) -> Self {
-        Self(ns)
-    }
-    
-    /// Create time from milliseconds
-    pub const fn from_millis(ms: u64) -> Self {
-        Self(ms * 1_000_000)
-    }
-    
-    /// Get nanoseconds since epoch
-    pub const fn nanos(&self) -> u64 {
-        self.0
-    }
-    
-    /// Get nanoseconds since epoch (alias for nanos)
-    pub const fn as_nanos(&self) -> u64 {
-        self.0
-    }
-    
-    /// Zero time constant
-    pub const ZERO: Self = Self(0);
-}
-
-/// Spike event
-#[derive(Debug, Clone, PartialEq)]
-pub struct Spike {
-    /// Neuron that spiked
-    pub neuron_id: NeuronId,
-    /// Time of spike
-    pub time: Time,
-    /// Spike amplitude (optional)
-    pub amplitude: f32,
-}
-
-impl Spike {
-    /// Create a new spike
-    pub fn new(neuron_id: NeuronId, time: Time) -> Self {
-        Self {
-            neuron_id,
-            time,
-            amplitude: 1.0,
-        }
-    }
-    
-    /// Create a spike with specific amplitude
-    pub fn with_amplitude(neuron_id: NeuronId, time: Time, amplitude: f32) -> Self {
-        Self {
-            neuron_id,
-            time,
-            amplitude,
-        }
-    }
-}
+// Identifier, time and spike types shared with shnn-core and shnn-runtime
+pub use shnn_types::{HyperedgeId, NeuronId, Spike, Time};
 
 // Core modules
 pub mod error;
@@ -113,11 +18,21 @@ pub mod schemas;
 pub mod traits;
 
 // Storage backends
+pub mod atomic;
 pub mod memory;
 pub mod file;
 
 // Specific format implementations
 pub mod vcsr;
+pub mod delta;
+pub mod event_log;
+pub mod export;
+pub mod inspect;
+pub mod morphology;
+pub mod npy;
+pub mod raster;
+pub mod spike_block;
+pub mod stats;
 pub mod vevt;
 pub mod vmsk;
 
@@ -132,9 +47,15 @@ pub use traits::{
 // Re-export implementations
 pub use memory::{MemoryStore, MemorySnapshot};
 pub use file::FileStore;
-pub use vcsr::{VCSRSnapshot, VCSRHeader, VCSRVertex};
-pub use vevt::{MemoryEventStore, VEVTEvent, VEVTHeader};
-pub use vmsk::{BitmapMask, VMSKHeader};
+pub use vcsr::{MappedVCSR, VCSRSnapshot, VCSRHeader, VCSRVertex, VCSRView};
+pub use delta::{GenerationChain, VCSRDelta, EdgeDelta};
+pub use event_log::{EventLog, FileEventLog, LogStream, StreamKind};
+pub use morphology::{apply_morphology, MorphologyExecutor, MorphologyReport};
+pub use raster::{RateMatrix, SpikeWindow};
+pub use spike_block::{CompressedSpikes, SpikeBlock};
+pub use stats::{Histogram, StructureStats};
+pub use vevt::{MemoryEventStore, VEVTEvent, VEVTEventType, VEVTHeader, WeightChange};
+pub use vmsk::{BitmapMask, MaskCollection, VMSKHeader};
 
 /// Storage crate version for compatibility checking
 pub const STORAGE_VERSION: u32 = 1;
@@ -153,6 +74,10 @@ pub mod magic {
     pub const VGRF: [u8; 4] = [0x47, 0x52, 0x41, 0x46];
     /// VRAS magic number: "RAST"
     pub const VRAS: [u8; 4] = [0x52, 0x41, 0x53, 0x54];
+    /// VDLT magic number: "VDLT"
+    pub const VDLT: [u8; 4] = [0x56, 0x44, 0x4C, 0x54];
+    /// VLOG magic number: "VLOG"
+    pub const VLOG: [u8; 4] = [0x56, 0x4C, 0x4F, 0x47];
 }
 
 #[cfg(test)]
@@ -169,6 +94,8 @@ mod tests {
             magic::VMORF,
             magic::VGRF,
             magic::VRAS,
+            magic::VDLT,
+            magic::VLOG,
         ];
         
         for (i, &magic1) in magics.iter().enumerate() {
@@ -3,9 +3,11 @@
 use crate::{
     error::{Result, StorageError},
     ids::GenerationId,
+    morphology::{apply_morphology, MorphologyReport},
+    stats::graph_stats,
     traits::{GraphStats, HypergraphSnapshot, HypergraphStore, HypergraphSubview, MorphologyOp},
     vcsr::VCSRSnapshot,
-    NeuronId, HyperedgeId, Time,
+    NeuronId, HyperedgeId,
 };
 
 use std::collections::BTreeMap;
@@ -17,6 +19,8 @@ pub struct MemoryStore {
     snapshots: BTreeMap<GenerationId, VCSRSnapshot>,
     /// Next generation ID to assign
     next_generation: GenerationId,
+    /// Parent of each generation created via `create_generation`
+    parents: BTreeMap<GenerationId, GenerationId>,
 }
 
 impl MemoryStore {
@@ -25,6 +29,7 @@ impl MemoryStore {
         Self {
             snapshots: BTreeMap::new(),
             next_generation: GenerationId::new(1),
+            parents: BTreeMap::new(),
         }
     }
     
@@ -35,6 +40,45 @@ impl MemoryStore {
             self.next_generation = GenerationId::new(generation.raw() + 1);
         }
     }
+
+    /// Create a new generation from `base` and report what the operations changed
+    pub fn create_generation_with_report(
+        &mut self,
+        base: GenerationId,
+        operations: &[MorphologyOp],
+    ) -> Result<(GenerationId, MorphologyReport)> {
+        let base_snapshot = self.snapshots.get(&base)
+            .ok_or(StorageError::GenerationNotFound {
+                generation: base.raw()
+            })?;
+
+        let new_gen = self.next_generation;
+        let (new_snapshot, report) = apply_morphology(base_snapshot, new_gen, operations)?;
+
+        self.snapshots.insert(new_gen, new_snapshot);
+        self.parents.insert(new_gen, base);
+        self.next_generation = GenerationId::new(new_gen.raw() + 1);
+
+        Ok((new_gen, report))
+    }
+
+    /// Parent generation a generation was derived from, if known
+    pub fn parent_of(&self, generation: GenerationId) -> Option<GenerationId> {
+        self.parents.get(&generation).copied()
+    }
+
+    /// Record that `generation` was derived from `parent`
+    pub fn set_parent(&mut self, generation: GenerationId, parent: GenerationId) {
+        self.parents.insert(generation, parent);
+    }
+
+    /// Borrow the stored VCSR snapshot for a generation
+    pub fn raw_snapshot(&self, generation: GenerationId) -> Result<&VCSRSnapshot> {
+        self.snapshots.get(&generation)
+            .ok_or(StorageError::GenerationNotFound {
+                generation: generation.raw()
+            })
+    }
 }
 
 impl Default for MemoryStore {
@@ -84,44 +128,14 @@ impl HypergraphStore for MemoryStore {
         base: GenerationId, 
         operations: &[MorphologyOp]
     ) -> Result<GenerationId> {
-        let base_snapshot = self.snapshots.get(&base)
-            .ok_or(StorageError::GenerationNotFound { 
-                generation: base.raw() 
-            })?;
-        
-        let mut new_snapshot = base_snapshot.clone();
-        new_snapshot.header.generation = self.next_generation.raw();
-        
-        // Apply operations (simplified for now)
-        for op in operations {
-            match op {
-                MorphologyOp::AddVertex { id, properties } => {
-                    new_snapshot.add_vertex(crate::vcsr::VCSRVertex::new(*id, properties.vertex_type));
-                }
-                MorphologyOp::AddHyperedge { vertices, weight, .. } => {
-                    // For simple edges, just connect first two vertices
-                    if vertices.len() >= 2 {
-                        new_snapshot.add_edge(vertices[0], vertices[1], *weight);
-                    }
-                }
-                _ => {
-                    // TODO: Implement other operations
-                }
-            }
-        }
-        
-        new_snapshot.finalize();
-        
-        let new_gen = self.next_generation;
-        self.snapshots.insert(new_gen, new_snapshot);
-        self.next_generation = GenerationId::new(new_gen.raw() + 1);
-        
-        Ok(new_gen)
+        self.create_generation_with_report(base, operations)
+            .map(|(generation, _)| generation)
     }
     
     fn compact(&mut self, keep_generations: &[GenerationId]) -> Result<()> {
         let keep_set: std::collections::HashSet<_> = keep_generations.iter().collect();
         self.snapshots.retain(|gen, _| keep_set.contains(gen));
+        self.parents.retain(|gen, _| keep_set.contains(gen));
         Ok(())
     }
 }
@@ -132,26 +146,20 @@ pub struct MemorySnapshot {
     inner: VCSRSnapshot,
 }
 
+impl MemorySnapshot {
+    /// Borrow the underlying VCSR snapshot
+    pub fn as_vcsr(&self) -> &VCSRSnapshot {
+        &self.inner
+    }
+}
+
 impl HypergraphSnapshot for MemorySnapshot {
     type Subview = MemorySubview;
     type NeighborIter = MemoryNeighborIter;
     type HyperedgeIter = MemoryHyperedgeIter;
     
     fn stats(&self) -> GraphStats {
-        GraphStats {
-            num_neurons: self.inner.header.num_vertices,
-            num_hyperedges: self.inner.header.num_hyperedges,
-            num_incidences: self.inner.header.num_incidences,
-            avg_degree: if self.inner.header.num_vertices > 0 {
-                self.inner.header.num_incidences as f32 / self.inner.header.num_vertices as f32
-            } else {
-                0.0
-            },
-            max_degree: self.inner.vertices.len() as u32, // Simplified
-            density: 0.0, // TODO: Calculate actual density
-            generation: GenerationId::new(self.inner.header.generation),
-            timestamp: Time::from_nanos(self.inner.header.timestamp),
-        }
+        graph_stats(&self.inner.header, &self.inner.row_ptr)
     }
     
     fn neighbors(&self, neuron: NeuronId) -> Result<Self::NeighborIter> {
@@ -168,15 +176,8 @@ impl HypergraphSnapshot for MemorySnapshot {
         })
     }
     
-    fn apply_mask(&self, _mask: &dyn crate::traits::Mask) -> Result<Self::Subview> {
-        // Simplified implementation
-        Ok(MemorySubview {
-            active_neurons: self.inner.vertices.iter()
-                .map(|v| v.neuron_id())
-                .collect(),
-            active_hyperedges: Vec::new(),
-            stats: self.stats(),
-        })
+    fn apply_mask(&self, mask: &dyn crate::traits::Mask) -> Result<Self::Subview> {
+        Ok(MemorySubview::new(self.inner.extract(|id| mask.is_active(id.raw()))))
     }
     
     fn k_hop(&self, seeds: &[NeuronId], k: u32) -> Result<Self::Subview> {
@@ -196,11 +197,7 @@ impl HypergraphSnapshot for MemorySnapshot {
             current_level = next_level;
         }
         
-        Ok(MemorySubview {
-            active_neurons: visited.into_iter().collect(),
-            active_hyperedges: Vec::new(),
-            stats: self.stats(),
-        })
+        Ok(MemorySubview::new(self.inner.extract(|id| visited.contains(&id))))
     }
     
     fn has_edge(&self, source: NeuronId, target: NeuronId) -> bool {
@@ -241,11 +238,26 @@ impl Iterator for MemoryHyperedgeIter {
     }
 }
 
-/// Memory-based subview implementation
+/// Memory-based subview implementation, materialized as an induced VCSR snapshot
 pub struct MemorySubview {
     active_neurons: Vec<NeuronId>,
     active_hyperedges: Vec<HyperedgeId>,
-    stats: GraphStats,
+    snapshot: VCSRSnapshot,
+}
+
+impl MemorySubview {
+    fn new(snapshot: VCSRSnapshot) -> Self {
+        Self {
+            active_neurons: snapshot.vertices.iter().map(|v| v.neuron_id()).collect(),
+            active_hyperedges: Vec::new(),
+            snapshot,
+        }
+    }
+
+    /// Borrow the extracted snapshot (vertices renumbered, original IDs in `VCSRVertex::id`)
+    pub fn as_vcsr(&self) -> &VCSRSnapshot {
+        &self.snapshot
+    }
 }
 
 impl HypergraphSubview for MemorySubview {
@@ -258,12 +270,11 @@ impl HypergraphSubview for MemorySubview {
     }
     
     fn stats(&self) -> GraphStats {
-        self.stats.clone()
+        graph_stats(&self.snapshot.header, &self.snapshot.row_ptr)
     }
     
     fn export_vcsr(&self) -> Result<Vec<u8>> {
-        // TODO: Implement VCSR export for subview
-        Ok(Vec::new())
+        Ok(self.snapshot.to_bytes())
     }
     
     fn export_graphml(&self) -> Result<String> {
@@ -275,7 +286,12 @@ impl HypergraphSubview for MemorySubview {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{traits::VertexProperties, vcsr::VCSRVertex};
+    use crate::{
+        traits::{MaskType, VertexProperties},
+        vcsr::VCSRVertex,
+        vmsk::BitmapMask,
+        MaskId,
+    };
 
     #[test]
     fn test_memory_store() {
@@ -330,5 +346,27 @@ mod tests {
         let new_snapshot = store.get_snapshot(new_gen).unwrap();
         let stats = new_snapshot.stats();
         assert_eq!(stats.num_neurons, 2);
+        assert_eq!(store.parent_of(new_gen), Some(GenerationId::new(1)));
+    }
+
+    #[test]
+    fn test_mask_subview_export() {
+        let n = NeuronId::new;
+        let edges = [(n(0), n(1), 0.5), (n(1), n(2), 0.25), (n(2), n(0), 1.0), (n(3), n(1), 0.75)];
+        let snapshot = MemorySnapshot {
+            inner: VCSRSnapshot::from_edges(GenerationId::new(2), 4, 0, &edges).unwrap(),
+        };
+        let mask = BitmapMask::from_indices(MaskId::new(1), MaskType::VertexMask, GenerationId::new(2), 4, [1, 2, 3])
+            .unwrap();
+
+        let subview = snapshot.apply_mask(&mask).unwrap();
+        assert_eq!(subview.active_neurons(), &[n(1), n(2), n(3)]);
+        assert_eq!(subview.stats().num_incidences, 2);
+        let exported = VCSRSnapshot::from_bytes(&subview.export_vcsr().unwrap()).unwrap();
+        let listed: Vec<_> = exported.edges().collect();
+        assert_eq!(listed, vec![(n(0), n(1), 0.25), (n(2), n(0), 0.75)]);
+
+        let hop = snapshot.k_hop(&[n(3)], 2).unwrap();
+        assert_eq!(hop.as_vcsr().header.num_incidences, 1);
     }
 }
\ No newline at end of file
@@ -0,0 +1,284 @@
+//! Structural plasticity: execution of MorphologyOp sequences on VCSR snapshots
+//!
+//! VCSR stores pairwise edges, so hyperedge operations are mapped as follows:
+//! - `AddHyperedge { vertices: [s, t1, t2, ..] }` adds `s -> ti` for every target
+//! - `HyperedgeId(i)` in `RemoveHyperedge`/`ModifyWeight` addresses the i-th edge in
+//!   CSR order of the *base* generation
+//!
+//! Removed vertices keep their index (VCSR rows are indexed by raw neuron ID) but
+//! lose all incident edges and are flagged with [`VCSRVertex::FLAG_REMOVED`].
+
+use crate::{
+    error::{Result, StorageError},
+    ids::GenerationId,
+    traits::MorphologyOp,
+    vcsr::{VCSRSnapshot, VCSRVertex},
+    NeuronId,
+};
+
+use std::collections::BTreeMap;
+
+/// Summary of an executed morphology script
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MorphologyReport {
+    /// Vertices added (or re-activated)
+    pub vertices_added: usize,
+    /// Vertices removed
+    pub vertices_removed: usize,
+    /// Edges added
+    pub edges_added: usize,
+    /// Edges removed
+    pub edges_removed: usize,
+    /// Edge weights modified
+    pub weights_modified: usize,
+}
+
+/// Applies MorphologyOp sequences to a base snapshot
+#[derive(Debug, Clone)]
+pub struct MorphologyExecutor {
+    vertices: Vec<VCSRVertex>,
+    edges: BTreeMap<(u32, u32), f32>,
+    /// Edge keys of the base generation in CSR order (HyperedgeId lookup)
+    base_order: Vec<(u32, u32)>,
+    report: MorphologyReport,
+}
+
+impl MorphologyExecutor {
+    /// Start from an existing snapshot
+    pub fn new(base: &VCSRSnapshot) -> Self {
+        let base_order: Vec<_> = base.edges().map(|(s, t, _)| (s.raw(), t.raw())).collect();
+        let edges = base.edges().map(|(s, t, w)| ((s.raw(), t.raw()), w)).collect();
+        Self {
+            vertices: base.vertices.clone(),
+            edges,
+            base_order,
+            report: MorphologyReport::default(),
+        }
+    }
+
+    /// Apply a single operation
+    pub fn apply(&mut self, op: &MorphologyOp) -> Result<()> {
+        match op {
+            MorphologyOp::AddVertex { id, properties } => {
+                let idx = id.raw() as usize;
+                if idx >= self.vertices.len() {
+                    let start = self.vertices.len() as u32;
+                    for raw in start..=id.raw() {
+                        let mut vertex = VCSRVertex::new(NeuronId::new(raw), properties.vertex_type);
+                        // Gap vertices created implicitly are inactive
+                        if raw != id.raw() {
+                            vertex.flags |= VCSRVertex::FLAG_REMOVED;
+                        }
+                        self.vertices.push(vertex);
+                    }
+                } else if self.is_active(*id) {
+                    return Err(StorageError::invalid_format(format!(
+                        "Vertex {} already exists", id.raw()
+                    )));
+                }
+                let vertex = &mut self.vertices[idx];
+                vertex.vertex_type = properties.vertex_type;
+                vertex.flags = properties.flags & !VCSRVertex::FLAG_REMOVED;
+                self.report.vertices_added += 1;
+            }
+            MorphologyOp::RemoveVertex { id } => {
+                self.require_vertex(*id)?;
+                self.vertices[id.raw() as usize].flags |= VCSRVertex::FLAG_REMOVED;
+                let before = self.edges.len();
+                self.edges.retain(|&(s, t), _| s != id.raw() && t != id.raw());
+                self.report.edges_removed += before - self.edges.len();
+                self.report.vertices_removed += 1;
+            }
+            MorphologyOp::AddHyperedge { vertices, weight, .. } => {
+                let (source, targets) = vertices.split_first().ok_or_else(|| {
+                    StorageError::invalid_format("AddHyperedge requires at least two vertices")
+                })?;
+                if targets.is_empty() {
+                    return Err(StorageError::invalid_format(
+                        "AddHyperedge requires at least two vertices",
+                    ));
+                }
+                for target in targets {
+                    self.add_edge(*source, *target, *weight)?;
+                }
+            }
+            MorphologyOp::RemoveHyperedge { id } => {
+                let (s, t) = self.base_edge(id.raw())?;
+                self.remove_edge(NeuronId::new(s), NeuronId::new(t))?;
+            }
+            MorphologyOp::ModifyWeight { edge_id, weight } => {
+                let (s, t) = self.base_edge(edge_id.raw())?;
+                self.set_weight(NeuronId::new(s), NeuronId::new(t), *weight)?;
+            }
+            MorphologyOp::AddEdge { source, target, weight } => {
+                self.add_edge(*source, *target, *weight)?;
+            }
+            MorphologyOp::RemoveEdge { source, target } => {
+                self.remove_edge(*source, *target)?;
+            }
+            MorphologyOp::SetWeight { source, target, weight } => {
+                self.set_weight(*source, *target, *weight)?;
+            }
+            MorphologyOp::Rewire { source, from, to } => {
+                let weight = self.edges.get(&(source.raw(), from.raw())).copied().ok_or_else(|| {
+                    missing_edge(*source, *from)
+                })?;
+                self.remove_edge(*source, *from)?;
+                self.add_edge(*source, *to, weight)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply a sequence of operations, stopping at the first failure
+    pub fn apply_all(&mut self, ops: &[MorphologyOp]) -> Result<()> {
+        for (i, op) in ops.iter().enumerate() {
+            self.apply(op).map_err(|e| {
+                StorageError::invalid_format(format!("morphology op #{} ({:?}) failed: {}", i, op, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Counters for the operations applied so far
+    pub fn report(&self) -> &MorphologyReport {
+        &self.report
+    }
+
+    /// Produce the finalized snapshot for `generation`
+    pub fn finish(self, generation: GenerationId) -> Result<VCSRSnapshot> {
+        let triples: Vec<_> = self.edges
+            .iter()
+            .map(|(&(s, t), &w)| (NeuronId::new(s), NeuronId::new(t), w))
+            .collect();
+        let mut snapshot = VCSRSnapshot::from_edges(generation, self.vertices.len() as u32, 0, &triples)?;
+        snapshot.vertices = self.vertices;
+        snapshot.finalize();
+        Ok(snapshot)
+    }
+
+    fn is_active(&self, id: NeuronId) -> bool {
+        self.vertices
+            .get(id.raw() as usize)
+            .map(|v| v.flags & VCSRVertex::FLAG_REMOVED == 0)
+            .unwrap_or(false)
+    }
+
+    fn require_vertex(&self, id: NeuronId) -> Result<()> {
+        if self.is_active(id) {
+            Ok(())
+        } else {
+            Err(StorageError::invalid_format(format!("Vertex {} does not exist", id.raw())))
+        }
+    }
+
+    fn base_edge(&self, index: u32) -> Result<(u32, u32)> {
+        self.base_order.get(index as usize).copied().ok_or_else(|| {
+            StorageError::invalid_format(format!(
+                "Hyperedge {} out of range ({} edges in base generation)",
+                index,
+                self.base_order.len()
+            ))
+        })
+    }
+
+    fn add_edge(&mut self, source: NeuronId, target: NeuronId, weight: f32) -> Result<()> {
+        self.require_vertex(source)?;
+        self.require_vertex(target)?;
+        if self.edges.insert((source.raw(), target.raw()), weight).is_some() {
+            return Err(StorageError::invalid_format(format!(
+                "Edge {}->{} already exists", source.raw(), target.raw()
+            )));
+        }
+        self.report.edges_added += 1;
+        Ok(())
+    }
+
+    fn remove_edge(&mut self, source: NeuronId, target: NeuronId) -> Result<()> {
+        self.edges
+            .remove(&(source.raw(), target.raw()))
+            .ok_or_else(|| missing_edge(source, target))?;
+        self.report.edges_removed += 1;
+        Ok(())
+    }
+
+    fn set_weight(&mut self, source: NeuronId, target: NeuronId, weight: f32) -> Result<()> {
+        let slot = self.edges
+            .get_mut(&(source.raw(), target.raw()))
+            .ok_or_else(|| missing_edge(source, target))?;
+        *slot = weight;
+        self.report.weights_modified += 1;
+        Ok(())
+    }
+}
+
+/// Apply `ops` to `base`, producing a snapshot for `generation`
+pub fn apply_morphology(
+    base: &VCSRSnapshot,
+    generation: GenerationId,
+    ops: &[MorphologyOp],
+) -> Result<(VCSRSnapshot, MorphologyReport)> {
+    let mut executor = MorphologyExecutor::new(base);
+    executor.apply_all(ops)?;
+    let report = executor.report().clone();
+    Ok((executor.finish(generation)?, report))
+}
+
+fn missing_edge(source: NeuronId, target: NeuronId) -> StorageError {
+    StorageError::invalid_format(format!("Edge {}->{} does not exist", source.raw(), target.raw()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{traits::VertexProperties, HyperedgeId};
+
+    fn base() -> VCSRSnapshot {
+        let edges = [
+            (NeuronId::new(0), NeuronId::new(1), 0.5),
+            (NeuronId::new(1), NeuronId::new(2), 0.25),
+        ];
+        VCSRSnapshot::from_edges(GenerationId::new(1), 3, 0, &edges).unwrap()
+    }
+
+    #[test]
+    fn test_growth_and_rewire() {
+        let ops = vec![
+            MorphologyOp::AddVertex {
+                id: NeuronId::new(4),
+                properties: VertexProperties { vertex_type: 2, flags: 0 },
+            },
+            MorphologyOp::AddEdge { source: NeuronId::new(2), target: NeuronId::new(4), weight: 0.1 },
+            MorphologyOp::Rewire { source: NeuronId::new(0), from: NeuronId::new(1), to: NeuronId::new(4) },
+            MorphologyOp::ModifyWeight { edge_id: HyperedgeId::new(1), weight: 0.9 },
+        ];
+        let (snapshot, report) = apply_morphology(&base(), GenerationId::new(2), &ops).unwrap();
+
+        assert_eq!(snapshot.header.generation, 2);
+        assert_eq!(snapshot.header.num_vertices, 5);
+        // Gap vertex 3 is inactive, vertex 4 is active with its type
+        assert_ne!(snapshot.vertices[3].flags & VCSRVertex::FLAG_REMOVED, 0);
+        assert_eq!(snapshot.vertices[4].vertex_type, 2);
+
+        let edges: Vec<_> = snapshot.edges().map(|(s, t, w)| (s.raw(), t.raw(), w)).collect();
+        assert_eq!(edges, vec![(0, 4, 0.5), (1, 2, 0.9), (2, 4, 0.1)]);
+        assert_eq!(report.edges_added, 2);
+        assert_eq!(report.edges_removed, 1);
+        assert_eq!(report.weights_modified, 1);
+    }
+
+    #[test]
+    fn test_prune_vertex() {
+        let ops = vec![MorphologyOp::RemoveVertex { id: NeuronId::new(1) }];
+        let (snapshot, report) = apply_morphology(&base(), GenerationId::new(2), &ops).unwrap();
+        assert_eq!(snapshot.header.num_incidences, 0);
+        assert_eq!(report.edges_removed, 2);
+
+        // Edges to a removed vertex are rejected
+        let bad = vec![
+            MorphologyOp::RemoveVertex { id: NeuronId::new(1) },
+            MorphologyOp::AddEdge { source: NeuronId::new(0), target: NeuronId::new(1), weight: 1.0 },
+        ];
+        assert!(apply_morphology(&base(), GenerationId::new(2), &bad).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Minimal NumPy `.npy` writer
+//!
+//! Writes format version 1.0: the `\x93NUMPY` magic, a little-endian `u16`
+//! header length, a Python dict literal with `descr`, `fortran_order` and
+//! `shape` (space-padded so the data starts on a 64-byte boundary), then the
+//! elements in C order. `numpy.load` reads the result directly.
+
+use crate::error::{Result, StorageError};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+const ALIGN: usize = 64;
+
+/// Element type with a fixed little-endian NumPy dtype
+pub trait NpyElement: Copy {
+    /// NumPy type string (e.g. `<f4`)
+    const DESCR: &'static str;
+    /// Append the little-endian bytes of `self`
+    fn write_le(self, out: &mut Vec<u8>);
+}
+
+macro_rules! npy_element {
+    ($($ty:ty => $descr:literal),* $(,)?) => {$(
+        impl NpyElement for $ty {
+            const DESCR: &'static str = $descr;
+            fn write_le(self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+    )*};
+}
+
+npy_element! {
+    u8 => "|u1",
+    u32 => "<u4",
+    u64 => "<u8",
+    i32 => "<i4",
+    i64 => "<i8",
+    f32 => "<f4",
+    f64 => "<f8",
+}
+
+/// Encode a C-order array of the given shape as `.npy` bytes
+pub fn encode_npy<T: NpyElement>(shape: &[usize], data: &[T]) -> Result<Vec<u8>> {
+    let expected: usize = shape.iter().product();
+    if expected != data.len() {
+        return Err(StorageError::OperationError {
+            message: format!("npy shape {:?} needs {} elements, got {}", shape, expected, data.len()),
+        });
+    }
+
+    let mut out = encode_header(&format!("'{}'", T::DESCR), shape, data.len() * core::mem::size_of::<T>())?;
+    for &value in data {
+        value.write_le(&mut out);
+    }
+    Ok(out)
+}
+
+/// Column of a structured (record) array
+#[derive(Debug, Clone, Copy)]
+pub enum NpyColumn<'a> {
+    /// `<u4` values
+    U32(&'a [u32]),
+    /// `<u8` values
+    U64(&'a [u64]),
+    /// `<i8` values
+    I64(&'a [i64]),
+    /// `<f4` values
+    F32(&'a [f32]),
+    /// `<f8` values
+    F64(&'a [f64]),
+}
+
+impl NpyColumn<'_> {
+    fn descr(&self) -> &'static str {
+        match self {
+            NpyColumn::U32(_) => u32::DESCR,
+            NpyColumn::U64(_) => u64::DESCR,
+            NpyColumn::I64(_) => i64::DESCR,
+            NpyColumn::F32(_) => f32::DESCR,
+            NpyColumn::F64(_) => f64::DESCR,
+        }
+    }
+
+    fn rows(&self) -> usize {
+        match self {
+            NpyColumn::U32(values) => values.len(),
+            NpyColumn::U64(values) => values.len(),
+            NpyColumn::I64(values) => values.len(),
+            NpyColumn::F32(values) => values.len(),
+            NpyColumn::F64(values) => values.len(),
+        }
+    }
+
+    fn write_row(&self, row: usize, out: &mut Vec<u8>) {
+        match self {
+            NpyColumn::U32(values) => values[row].write_le(out),
+            NpyColumn::U64(values) => values[row].write_le(out),
+            NpyColumn::I64(values) => values[row].write_le(out),
+            NpyColumn::F32(values) => values[row].write_le(out),
+            NpyColumn::F64(values) => values[row].write_le(out),
+        }
+    }
+}
+
+/// Encode equal-length named columns as a 1-D packed structured array
+///
+/// `numpy.load` returns a record array; `pandas.DataFrame(array)` turns the
+/// field names into columns.
+pub fn encode_npy_records(columns: &[(&str, NpyColumn<'_>)]) -> Result<Vec<u8>> {
+    let rows = columns.first().map_or(0, |(_, column)| column.rows());
+    if let Some((name, column)) = columns.iter().find(|(_, column)| column.rows() != rows) {
+        return Err(StorageError::OperationError {
+            message: format!("npy column '{}' has {} rows, expected {}", name, column.rows(), rows),
+        });
+    }
+    let fields: Vec<String> = columns.iter()
+        .map(|(name, column)| format!("('{}', '{}')", name, column.descr()))
+        .collect();
+    let mut out = encode_header(&format!("[{}]", fields.join(", ")), &[rows], 0)?;
+    for row in 0..rows {
+        for (_, column) in columns {
+            column.write_row(row, &mut out);
+        }
+    }
+    Ok(out)
+}
+
+/// Magic, version and padded header dict, with room for `data_bytes` more
+fn encode_header(descr: &str, shape: &[usize], data_bytes: usize) -> Result<Vec<u8>> {
+    let dims = match shape {
+        [single] => format!("({},)", single),
+        _ => format!("({})", shape.iter().map(usize::to_string).collect::<Vec<_>>().join(", ")),
+    };
+    let mut header = format!("{{'descr': {}, 'fortran_order': False, 'shape': {}, }}", descr, dims);
+    // magic + version + length prefix + header + trailing newline
+    let unpadded = MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.push_str(&" ".repeat(unpadded.next_multiple_of(ALIGN) - unpadded));
+    header.push('\n');
+    let header_len = u16::try_from(header.len()).map_err(|_| StorageError::OperationError {
+        message: format!("npy header for shape {:?} is too long", shape),
+    })?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + 4 + header.len() + data_bytes);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&[1, 0]);
+    out.extend_from_slice(&header_len.to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npy_header_and_data_layout() {
+        let bytes = encode_npy(&[2, 3], &[0.0f32, 1.0, 2.0, 3.0, 4.0, 5.0]).expect("Should encode matrix");
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % ALIGN, 0);
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).expect("Should be ASCII");
+        assert!(header.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), }"));
+        assert!(header.ends_with('\n'));
+        let data = &bytes[10 + header_len..];
+        assert_eq!(data.len(), 6 * 4);
+        assert_eq!(f32::from_le_bytes(data[20..24].try_into().unwrap()), 5.0);
+
+        let vector = encode_npy(&[3], &[7u32, 8, 9]).expect("Should encode vector");
+        assert!(vector.windows(4).any(|w| w == b"(3,)"));
+        assert!(encode_npy(&[2, 2], &[1i64, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_npy_records_are_packed() {
+        let times = [10u64, 20];
+        let ids = [3u32, 4];
+        let bytes = encode_npy_records(&[("time_ns", NpyColumn::U64(&times)), ("neuron_id", NpyColumn::U32(&ids))])
+            .expect("Should encode records");
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).expect("Should be ASCII");
+        assert!(header.starts_with("{'descr': [('time_ns', '<u8'), ('neuron_id', '<u4')], 'fortran_order': False, 'shape': (2,), }"));
+        let data = &bytes[10 + header_len..];
+        assert_eq!(data.len(), 2 * 12);
+        assert_eq!(u64::from_le_bytes(data[12..20].try_into().unwrap()), 20);
+        assert_eq!(u32::from_le_bytes(data[20..24].try_into().unwrap()), 4);
+        assert!(encode_npy_records(&[("a", NpyColumn::U64(&times)), ("b", NpyColumn::U32(&ids[..1]))]).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Windowing, binning and downsampling of recorded spikes
+//!
+//! A [`SpikeWindow`] selects the half-open time range `[start, end)` of a
+//! recording; [`RateMatrix::bin`] counts the spikes inside it per neuron and
+//! fixed-width bin (a trailing partial bin keeps its own width), and
+//! [`RateMatrix::downsample`] merges adjacent bins. Matrices export as CSV or
+//! as a `(neurons, bins)` float32 `.npy` of rates in Hz.
+
+use crate::{
+    error::{Result, StorageError},
+    npy::encode_npy,
+};
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Half-open time range `[start_ns, end_ns)` of a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpikeWindow {
+    /// First included time (ns)
+    pub start_ns: u64,
+    /// First excluded time (ns)
+    pub end_ns: u64,
+}
+
+impl SpikeWindow {
+    /// Window `[start_ns, end_ns)`; must not be empty
+    pub fn new(start_ns: u64, end_ns: u64) -> Result<Self> {
+        if end_ns <= start_ns {
+            return Err(invalid(format!("window {}..{} ns is empty", start_ns, end_ns)));
+        }
+        Ok(Self { start_ns, end_ns })
+    }
+
+    /// Length of the window (ns)
+    pub fn duration_ns(&self) -> u64 {
+        self.end_ns - self.start_ns
+    }
+
+    /// True if `time_ns` falls inside the window
+    pub fn contains(&self, time_ns: u64) -> bool {
+        (self.start_ns..self.end_ns).contains(&time_ns)
+    }
+
+    /// Keep the `(time_ns, neuron_id)` pairs inside the window
+    pub fn select<I>(self, spikes: I) -> impl Iterator<Item = (u64, u32)>
+    where
+        I: IntoIterator<Item = (u64, u32)>,
+    {
+        spikes.into_iter().filter(move |&(time_ns, _)| self.contains(time_ns))
+    }
+}
+
+/// Spike counts per neuron (rows) and time bin (columns)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateMatrix {
+    window: SpikeWindow,
+    bin_ns: u64,
+    neurons: Vec<u32>,
+    bins: usize,
+    counts: Vec<u32>,
+}
+
+impl RateMatrix {
+    /// Count the spikes inside `window` into bins `bin_ns` wide
+    ///
+    /// Rows are the neurons of `neurons` (inclusive id range, silent ones
+    /// included) or, without a range, every neuron that fired in the window.
+    pub fn bin<I>(spikes: I, window: SpikeWindow, bin_ns: u64, neurons: Option<(u32, u32)>) -> Result<Self>
+    where
+        I: IntoIterator<Item = (u64, u32)>,
+    {
+        if bin_ns == 0 {
+            return Err(invalid("bin width must be > 0"));
+        }
+        if let Some((first, last)) = neurons {
+            if first > last {
+                return Err(invalid(format!("neuron range {}..{} is empty", first, last)));
+            }
+        }
+        let bins = window.duration_ns().div_ceil(bin_ns) as usize;
+        let in_range = |id: u32| match neurons {
+            Some((first, last)) => (first..=last).contains(&id),
+            None => true,
+        };
+
+        let mut rows: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+        if let Some((first, last)) = neurons {
+            rows.extend((first..=last).map(|id| (id, vec![0; bins])));
+        }
+        for (time_ns, id) in window.select(spikes) {
+            if !in_range(id) {
+                continue;
+            }
+            let bin = ((time_ns - window.start_ns) / bin_ns) as usize;
+            rows.entry(id).or_insert_with(|| vec![0; bins])[bin] += 1;
+        }
+
+        Ok(Self {
+            window,
+            bin_ns,
+            neurons: rows.keys().copied().collect(),
+            bins,
+            counts: rows.into_values().flatten().collect(),
+        })
+    }
+
+    /// Windowed time range
+    pub fn window(&self) -> SpikeWindow {
+        self.window
+    }
+
+    /// Bin width (ns)
+    pub fn bin_ns(&self) -> u64 {
+        self.bin_ns
+    }
+
+    /// Neuron id of each row, ascending
+    pub fn neurons(&self) -> &[u32] {
+        &self.neurons
+    }
+
+    /// Number of time bins
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    /// Row-major spike counts (`neurons().len()` rows of `bins()` columns)
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// Spikes of row `row` in bin `bin`
+    pub fn count(&self, row: usize, bin: usize) -> u32 {
+        self.counts[row * self.bins + bin]
+    }
+
+    /// Total spikes in the matrix
+    pub fn total_spikes(&self) -> u64 {
+        self.counts.iter().map(|&c| c as u64).sum()
+    }
+
+    /// Start time of bin `bin` (ns)
+    pub fn bin_start_ns(&self, bin: usize) -> u64 {
+        self.window.start_ns + bin as u64 * self.bin_ns
+    }
+
+    /// Width of bin `bin` (the last one may be cut short by the window)
+    pub fn bin_width_ns(&self, bin: usize) -> u64 {
+        (self.window.end_ns - self.bin_start_ns(bin)).min(self.bin_ns)
+    }
+
+    /// Row-major firing rates (Hz)
+    pub fn rates_hz(&self) -> Vec<f32> {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| rate_hz(count as f64, self.bin_width_ns(i % self.bins)))
+            .collect()
+    }
+
+    /// Mean rate over all rows per bin (Hz)
+    pub fn population_rate_hz(&self) -> Vec<f32> {
+        let rows = self.neurons.len().max(1) as f64;
+        (0..self.bins)
+            .map(|bin| {
+                let count: u32 = (0..self.neurons.len()).map(|row| self.count(row, bin)).sum();
+                rate_hz(count as f64 / rows, self.bin_width_ns(bin))
+            })
+            .collect()
+    }
+
+    /// Merge every `factor` adjacent bins into one
+    pub fn downsample(&self, factor: usize) -> Result<Self> {
+        if factor == 0 {
+            return Err(invalid("downsample factor must be > 0"));
+        }
+        let bins = self.bins.div_ceil(factor);
+        let mut counts = vec![0; self.neurons.len() * bins];
+        for (i, &count) in self.counts.iter().enumerate() {
+            let (row, bin) = (i / self.bins, i % self.bins);
+            counts[row * bins + bin / factor] += count;
+        }
+        Ok(Self {
+            window: self.window,
+            bin_ns: self.bin_ns * factor as u64,
+            neurons: self.neurons.clone(),
+            bins,
+            counts,
+        })
+    }
+
+    /// Rates as CSV: a `neuron_id` column, then one column per bin named by its start time (ms)
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("neuron_id");
+        for bin in 0..self.bins {
+            let _ = write!(csv, ",{}", self.bin_start_ns(bin) as f64 / 1e6);
+        }
+        csv.push('\n');
+        let rates = self.rates_hz();
+        for (row, id) in self.neurons.iter().enumerate() {
+            let _ = write!(csv, "{}", id);
+            for rate in &rates[row * self.bins..(row + 1) * self.bins] {
+                let _ = write!(csv, ",{}", rate);
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Rates as a `(neurons, bins)` float32 `.npy` array
+    pub fn to_npy(&self) -> Result<Vec<u8>> {
+        encode_npy(&[self.neurons.len(), self.bins], &self.rates_hz())
+    }
+}
+
+/// Rate of `count` spikes in `width_ns`, rounded once to f32
+fn rate_hz(count: f64, width_ns: u64) -> f32 {
+    (count * 1e9 / width_ns as f64) as f32
+}
+
+fn invalid(message: impl Into<String>) -> StorageError {
+    StorageError::OperationError { message: message.into() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    fn recording() -> Vec<(u64, u32)> {
+        vec![(0, 1), (2 * MS, 1), (5 * MS, 3), (9 * MS, 1), (10 * MS, 3), (12 * MS, 7)]
+    }
+
+    #[test]
+    fn test_window_bins_fired_neurons() {
+        let window = SpikeWindow::new(2 * MS, 12 * MS).expect("Should accept window");
+        let matrix = RateMatrix::bin(recording(), window, 4 * MS, None).expect("Should bin spikes");
+        // Bins [2,6) [6,10) [10,12); spikes at 0 and 12 ms fall outside
+        assert_eq!(matrix.neurons(), &[1, 3]);
+        assert_eq!(matrix.bins(), 3);
+        assert_eq!(matrix.counts(), &[1, 1, 0, 1, 0, 1]);
+        assert_eq!(matrix.total_spikes(), 4);
+        assert_eq!(matrix.bin_width_ns(2), 2 * MS);
+        assert_eq!(matrix.rates_hz(), vec![250.0, 250.0, 0.0, 250.0, 0.0, 500.0]);
+        assert_eq!(matrix.population_rate_hz(), vec![250.0, 125.0, 250.0]);
+
+        assert!(SpikeWindow::new(5, 5).is_err());
+        assert!(RateMatrix::bin(recording(), window, 0, None).is_err());
+    }
+
+    #[test]
+    fn test_neuron_range_keeps_silent_rows() {
+        let window = SpikeWindow::new(0, 20 * MS).expect("Should accept window");
+        let matrix = RateMatrix::bin(recording(), window, 10 * MS, Some((2, 4))).expect("Should bin spikes");
+        assert_eq!(matrix.neurons(), &[2, 3, 4]);
+        assert_eq!(matrix.counts(), &[0, 0, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_downsample_merges_bins_and_exports() {
+        let window = SpikeWindow::new(0, 13 * MS).expect("Should accept window");
+        let fine = RateMatrix::bin(recording(), window, MS, None).expect("Should bin spikes");
+        assert_eq!(fine.bins(), 13);
+        let coarse = fine.downsample(5).expect("Should downsample");
+        assert_eq!((coarse.bins(), coarse.bin_ns()), (3, 5 * MS));
+        assert_eq!(coarse.counts(), &[2, 1, 0, 0, 1, 1, 0, 0, 1]);
+        assert_eq!(coarse.total_spikes(), fine.total_spikes());
+        assert!(fine.downsample(0).is_err());
+
+        let csv = coarse.to_csv();
+        assert_eq!(csv.lines().next(), Some("neuron_id,0,5,10"));
+        assert_eq!(csv.lines().nth(1), Some("1,400,200,0"));
+        // Last bin is 3 ms wide
+        assert_eq!(csv.lines().nth(3), Some("7,0,0,333.33334"));
+        let npy = coarse.to_npy().expect("Should encode npy");
+        assert!(npy.windows(6).any(|w| w == b"(3, 3)"));
+    }
+}
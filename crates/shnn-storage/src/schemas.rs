@@ -13,7 +13,7 @@ pub fn validate_magic(data: &[u8], expected: [u8; 4]) -> Result<()> {
     
     let found = [data[0], data[1], data[2], data[3]];
     if found != expected {
-        return Err(crate::error::StorageError::InvalidMagic { expected, found });
+        return Err(crate::error::StorageError::BadMagic { expected, found });
     }
     
     Ok(())
@@ -33,9 +33,66 @@ pub fn validate_checksum(data: &[u8], expected: u32) -> Result<()> {
     Ok(())
 }
 
+/// Bytes covered by each per-block checksum in v2 artifacts
+pub const BLOCK_SIZE: u32 = 64 * 1024;
+
+/// Calculate CRC32C (Castagnoli) checksum, used for per-block integrity
+pub fn calculate_crc32c(data: &[u8]) -> u32 {
+    crc32c::crc32c(data)
+}
+
+/// Per-block checksum table for `data`: one little-endian CRC32C per `block_size` bytes
+pub fn block_checksums(data: &[u8], block_size: u32) -> Vec<u8> {
+    data.chunks(block_size.max(1) as usize)
+        .flat_map(|block| calculate_crc32c(block).to_le_bytes())
+        .collect()
+}
+
+/// Offsets of the blocks of `data` that disagree with `table`
+///
+/// `base_offset` is where `data` starts in the artifact, so the returned
+/// offsets point into the file. A table of the wrong length is an error.
+pub fn corrupt_blocks(data: &[u8], table: &[u8], block_size: u32, base_offset: u64) -> Result<Vec<u64>> {
+    if block_size == 0 {
+        return Err(crate::error::StorageError::invalid_format("Block size is zero"));
+    }
+    let blocks = data.len().div_ceil(block_size as usize);
+    if table.len() != blocks * 4 {
+        return Err(crate::error::StorageError::InvalidFormat {
+            reason: format!("Block table holds {} bytes, expected {} for {} blocks", table.len(), blocks * 4, blocks),
+        });
+    }
+    Ok(data.chunks(block_size as usize)
+        .zip(table.chunks_exact(4))
+        .enumerate()
+        .filter(|(_, (block, stored))| calculate_crc32c(block).to_le_bytes() != **stored)
+        .map(|(index, _)| base_offset + index as u64 * block_size as u64)
+        .collect())
+}
+
+/// Verify `data` against its block table, failing with the first corrupt block
+pub fn verify_blocks(data: &[u8], table: &[u8], block_size: u32, base_offset: u64) -> Result<()> {
+    match corrupt_blocks(data, table, block_size, base_offset)?.first() {
+        Some(&offset) => Err(crate::error::StorageError::CorruptBlock { offset }),
+        None => Ok(()),
+    }
+}
+
+/// Reject format versions outside `1..=supported`
+pub fn validate_version(version: u32, supported: u32) -> Result<()> {
+    if version == 0 || version > supported {
+        return Err(crate::error::StorageError::BadVersion { version, supported });
+    }
+    Ok(())
+}
+
 /// Get current timestamp in nanoseconds since epoch
 pub fn current_timestamp() -> u64 {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
     use std::time::{SystemTime, UNIX_EPOCH};
+    // std's clock panics in the browser
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    use web_time::{SystemTime, UNIX_EPOCH};
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -144,6 +201,33 @@ mod tests {
         assert!(validate_checksum(data, checksum + 1).is_err());
     }
 
+    #[test]
+    fn test_block_checksums() {
+        // Known CRC32C check value
+        assert_eq!(calculate_crc32c(b"123456789"), 0xe306_9283);
+
+        let mut data: Vec<u8> = (0..10u8).collect();
+        let table = block_checksums(&data, 4);
+        assert_eq!(table.len(), 3 * 4);
+        assert!(verify_blocks(&data, &table, 4, 100).is_ok());
+
+        data[9] ^= 1;
+        assert_eq!(corrupt_blocks(&data, &table, 4, 100).expect("Should check blocks"), vec![108]);
+        assert!(matches!(
+            verify_blocks(&data, &table, 4, 100),
+            Err(crate::error::StorageError::CorruptBlock { offset: 108 })
+        ));
+        assert!(verify_blocks(&data, &table[..8], 4, 100).is_err());
+        assert!(block_checksums(&[], 4).is_empty());
+
+        assert!(validate_version(2, 2).is_ok());
+        assert!(matches!(
+            validate_version(3, 2),
+            Err(crate::error::StorageError::BadVersion { version: 3, supported: 2 })
+        ));
+        assert!(validate_version(0, 2).is_err());
+    }
+
     #[test]
     fn test_capabilities() {
         let mut flags = 0u64;
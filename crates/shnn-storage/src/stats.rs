@@ -0,0 +1,178 @@
+//! Structural statistics of VCSR snapshots
+//!
+//! [`StructureStats`] extends the [`GraphStats`] summary with in/out degree
+//! distributions, weight range and hyperedge cardinalities. VCSR stores a
+//! hyperedge `{s, t1..tk}` as its `k` pairwise edges `s -> ti`, so incidences
+//! are grouped by source: every vertex with `k > 0` outgoing edges counts as
+//! one hyperedge of cardinality `k + 1`.
+
+use crate::{
+    ids::GenerationId,
+    traits::GraphStats,
+    vcsr::{VCSRHeader, VCSRSnapshot, VCSRVertex, VCSRView},
+    Time,
+};
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Sparse histogram as `(value, count)` pairs in ascending value order
+pub type Histogram = Vec<(u32, u64)>;
+
+/// Degree distributions and cardinalities of one snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureStats {
+    /// Counts, average/maximum out-degree and directed density
+    pub summary: GraphStats,
+    /// Edges read from the CSR arrays
+    pub edges: u64,
+    /// Edges from a vertex to itself
+    pub self_loops: u64,
+    /// Vertices flagged as removed by a morphology operation
+    pub removed: u32,
+    /// Vertices (not removed) without incoming or outgoing edges
+    pub isolated: u32,
+    /// Vertices per out-degree
+    pub out_degree: Histogram,
+    /// Vertices per in-degree
+    pub in_degree: Histogram,
+    /// Largest in-degree
+    pub max_in_degree: u32,
+    /// Hyperedges per cardinality (source plus its targets)
+    pub cardinality: Histogram,
+    /// Smallest and largest edge weight
+    pub weight_range: Option<(f32, f32)>,
+    /// Mean edge weight
+    pub mean_weight: Option<f64>,
+}
+
+impl StructureStats {
+    /// Statistics of an in-memory snapshot
+    pub fn of_snapshot(snapshot: &VCSRSnapshot) -> Self {
+        compute(&snapshot.header, &snapshot.vertices, &snapshot.row_ptr, &snapshot.col_indices, &snapshot.weights)
+    }
+
+    /// Statistics of a zero-copy view (rows with out-of-range pointers count as empty)
+    pub fn of_view(view: &VCSRView<'_>) -> Self {
+        compute(&view.header, view.vertices, view.row_ptr, view.col_indices, view.weights)
+    }
+}
+
+/// [`GraphStats`] summary from the header and row pointers
+pub(crate) fn graph_stats(header: &VCSRHeader, row_ptr: &[u64]) -> GraphStats {
+    let n = header.num_vertices as f64;
+    let max_degree = row_ptr.windows(2)
+        .map(|w| w[1].saturating_sub(w[0]) as u32)
+        .max()
+        .unwrap_or(0);
+    GraphStats {
+        num_neurons: header.num_vertices,
+        num_hyperedges: header.num_hyperedges,
+        num_incidences: header.num_incidences,
+        avg_degree: if header.num_vertices > 0 {
+            header.num_incidences as f32 / header.num_vertices as f32
+        } else {
+            0.0
+        },
+        max_degree,
+        // Directed density without self-loops
+        density: if n > 1.0 {
+            (header.num_incidences as f64 / (n * (n - 1.0))) as f32
+        } else {
+            0.0
+        },
+        generation: GenerationId::new(header.generation),
+        timestamp: Time::from_nanos(header.timestamp),
+    }
+}
+
+/// Column range of row `source`, empty if its pointers are out of range
+fn row_range(row_ptr: &[u64], columns: usize, source: usize) -> Range<usize> {
+    match (row_ptr.get(source), row_ptr.get(source + 1)) {
+        (Some(&start), Some(&end)) if start <= end && end <= columns as u64 => start as usize..end as usize,
+        _ => 0..0,
+    }
+}
+
+fn histogram(values: impl IntoIterator<Item = u32>) -> Histogram {
+    let mut counts: BTreeMap<u32, u64> = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+    counts.into_iter().collect()
+}
+
+fn compute(
+    header: &VCSRHeader,
+    vertices: &[VCSRVertex],
+    row_ptr: &[u64],
+    col_indices: &[u32],
+    weights: &[f32],
+) -> StructureStats {
+    let rows = row_ptr.len().saturating_sub(1);
+    let columns = col_indices.len().min(weights.len());
+    let mut out_degree = vec![0u32; rows];
+    let mut in_degree = vec![0u32; rows];
+    let (mut edges, mut self_loops, mut weight_sum) = (0u64, 0u64, 0f64);
+    let mut weight_range: Option<(f32, f32)> = None;
+    for (source, out) in out_degree.iter_mut().enumerate() {
+        let range = row_range(row_ptr, columns, source);
+        *out = range.len() as u32;
+        for (&target, &weight) in col_indices[range.clone()].iter().zip(&weights[range]) {
+            edges += 1;
+            self_loops += (target as usize == source) as u64;
+            if let Some(count) = in_degree.get_mut(target as usize) {
+                *count += 1;
+            }
+            weight_sum += weight as f64;
+            weight_range = Some(weight_range.map_or((weight, weight), |(lo, hi)| (lo.min(weight), hi.max(weight))));
+        }
+    }
+
+    let removed = |id: usize| vertices.get(id).is_some_and(|v| v.flags & VCSRVertex::FLAG_REMOVED != 0);
+    StructureStats {
+        summary: graph_stats(header, row_ptr),
+        edges,
+        self_loops,
+        removed: (0..vertices.len()).filter(|&id| removed(id)).count() as u32,
+        isolated: (0..rows).filter(|&id| out_degree[id] == 0 && in_degree[id] == 0 && !removed(id)).count() as u32,
+        out_degree: histogram(out_degree.iter().copied()),
+        in_degree: histogram(in_degree.iter().copied()),
+        max_in_degree: in_degree.iter().copied().max().unwrap_or(0),
+        cardinality: histogram(out_degree.iter().filter(|&&k| k > 0).map(|&k| k + 1)),
+        weight_range,
+        mean_weight: (edges > 0).then(|| weight_sum / edges as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NeuronId;
+
+    #[test]
+    fn degree_distributions_and_cardinalities() {
+        let n = NeuronId::new;
+        let edges = [(n(0), n(1), 0.5), (n(0), n(2), 1.5), (n(0), n(3), 1.0), (n(2), n(2), -0.5), (n(3), n(1), 0.5)];
+        let mut snapshot = VCSRSnapshot::from_edges(GenerationId::new(3), 5, 0, &edges).expect("Should build");
+        snapshot.vertices[4].flags |= VCSRVertex::FLAG_REMOVED;
+        let stats = StructureStats::of_snapshot(&snapshot);
+
+        assert_eq!((stats.edges, stats.self_loops, stats.removed, stats.isolated), (5, 1, 1, 0));
+        assert_eq!(stats.out_degree, vec![(0, 2), (1, 2), (3, 1)]);
+        assert_eq!(stats.in_degree, vec![(0, 2), (1, 1), (2, 2)]);
+        assert_eq!(stats.max_in_degree, 2);
+        assert_eq!(stats.cardinality, vec![(2, 2), (4, 1)]);
+        assert_eq!(stats.weight_range, Some((-0.5, 1.5)));
+        assert_eq!(stats.mean_weight, Some(0.6));
+        assert_eq!(stats.summary.max_degree, 3);
+        assert_eq!(stats.summary.generation, GenerationId::new(3));
+        assert!((stats.summary.density - 0.25).abs() < 1e-6);
+
+        let bytes = snapshot.to_bytes();
+        let mut aligned = vec![0u64; bytes.len().div_ceil(8)];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut aligned)[..bytes.len()].copy_from_slice(&bytes);
+        let view = VCSRView::from_bytes(&bytemuck::cast_slice::<u64, u8>(&aligned)[..bytes.len()]).expect("Should view");
+        assert_eq!(StructureStats::of_view(&view), stats);
+    }
+}
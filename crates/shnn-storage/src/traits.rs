@@ -178,6 +178,10 @@ pub enum EventType {
     Control,
     /// Temporal marker
     Marker,
+    /// Plasticity weight change
+    WeightUpdate,
+    /// Neuron or synapse added or removed
+    Structural,
 }
 
 /// Interface for masks used in subviews and TTR
@@ -261,6 +265,40 @@ pub enum MorphologyOp {
         /// New weight
         weight: f32,
     },
+    /// Add a directed edge addressed by its endpoints
+    AddEdge {
+        /// Source vertex
+        source: NeuronId,
+        /// Target vertex
+        target: NeuronId,
+        /// Edge weight
+        weight: f32,
+    },
+    /// Remove a directed edge addressed by its endpoints
+    RemoveEdge {
+        /// Source vertex
+        source: NeuronId,
+        /// Target vertex
+        target: NeuronId,
+    },
+    /// Set the weight of an existing edge addressed by its endpoints
+    SetWeight {
+        /// Source vertex
+        source: NeuronId,
+        /// Target vertex
+        target: NeuronId,
+        /// New weight
+        weight: f32,
+    },
+    /// Move an edge `source -> from` to `source -> to`, keeping its weight
+    Rewire {
+        /// Source vertex
+        source: NeuronId,
+        /// Current target
+        from: NeuronId,
+        /// New target
+        to: NeuronId,
+    },
 }
 
 /// Properties for vertices
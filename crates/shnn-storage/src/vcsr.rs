@@ -4,16 +4,24 @@ use crate::{
     error::{Result, StorageError},
     ids::GenerationId,
     magic,
-    schemas::{calculate_checksum, current_timestamp, validate_checksum, validate_magic},
+    schemas::{
+        block_checksums, calculate_checksum, current_timestamp, validate_magic, validate_version, verify_blocks,
+        BLOCK_SIZE,
+    },
     NeuronId, HyperedgeId, Time,
 };
 
-use core::mem;
+use bytemuck::{Pod, Zeroable};
+use core::{mem, ops::Range};
 use std::io::{Read, Write};
+use std::path::Path;
 
-/// VCSR format header
+/// Current VCSR format version; version 1 snapshots (no block table) are still read
+pub const VCSR_VERSION: u32 = 2;
+
+/// VCSR format header (plain data, stored as its in-memory bytes)
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
 pub struct VCSRHeader {
     /// Magic number "VCSR"
     pub magic: [u8; 4],
@@ -53,9 +61,13 @@ pub struct VCSRHeader {
     pub header_checksum: u32,
     /// CRC32 of all data sections
     pub data_checksum: u32,
+    /// Offset to the per-block CRC32C table after the weights (0 in version 1)
+    pub blocks_offset: u64,
+    /// Data bytes covered by each table entry
+    pub block_size: u32,
     
     /// Reserved for future extensions
-    pub reserved: [u8; 32],
+    pub reserved: [u8; 20],
 }
 
 impl VCSRHeader {
@@ -63,7 +75,7 @@ impl VCSRHeader {
     pub fn new(generation: GenerationId, num_vertices: u32, num_hyperedges: u32) -> Self {
         Self {
             magic: magic::VCSR,
-            version: 1,
+            version: VCSR_VERSION,
             generation: generation.raw(),
             timestamp: current_timestamp(),
             num_vertices,
@@ -77,55 +89,35 @@ impl VCSRHeader {
             metadata_offset: 0,
             header_checksum: 0, // Will be calculated later
             data_checksum: 0,   // Will be calculated later
-            reserved: [0; 32],
+            blocks_offset: 0,   // Set on serialization
+            block_size: BLOCK_SIZE,
+            reserved: [0; 20],
         }
     }
     
     /// Validate this header
     pub fn validate(&self) -> Result<()> {
         validate_magic(&self.magic, magic::VCSR)?;
-        
-        if self.version != 1 {
-            return Err(StorageError::UnsupportedVersion {
-                version: self.version,
-                supported: 1,
-            });
-        }
-        
-        Ok(())
+        validate_version(self.version, VCSR_VERSION)
     }
     
     /// Calculate and update the header checksum
     pub fn update_header_checksum(&mut self) {
-        // Temporarily set checksum to 0
-        let original_checksum = self.header_checksum;
+        // Calculate checksum over the header with the checksum field zeroed
         self.header_checksum = 0;
-        
-        // Calculate checksum over the header
-        let header_bytes = unsafe {
-            core::slice::from_raw_parts(
-                self as *const Self as *const u8,
-                mem::size_of::<Self>(),
-            )
-        };
-        
-        self.header_checksum = calculate_checksum(header_bytes);
+        self.header_checksum = calculate_checksum(bytemuck::bytes_of(self));
     }
     
-    /// Verify the header checksum
+    /// Verify the header checksum; a mismatch is a corrupt block at offset 0
     pub fn verify_header_checksum(&self) -> Result<()> {
-        let mut temp_header = self.clone();
+        let mut temp_header = *self;
         let expected_checksum = temp_header.header_checksum;
         temp_header.header_checksum = 0;
         
-        let header_bytes = unsafe {
-            core::slice::from_raw_parts(
-                &temp_header as *const Self as *const u8,
-                mem::size_of::<Self>(),
-            )
-        };
-        
-        validate_checksum(header_bytes, expected_checksum)
+        if calculate_checksum(bytemuck::bytes_of(&temp_header)) != expected_checksum {
+            return Err(StorageError::CorruptBlock { offset: 0 });
+        }
+        Ok(())
     }
 }
 
@@ -147,9 +139,9 @@ impl VCSRCapabilities {
     pub const BIDIRECTIONAL: u64 = 1 << 5;
 }
 
-/// Vertex data structure
+/// Vertex data structure (plain data, stored as its in-memory bytes)
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
 pub struct VCSRVertex {
     /// Vertex ID (NeuronId)
     pub id: u32,
@@ -162,6 +154,9 @@ pub struct VCSRVertex {
 }
 
 impl VCSRVertex {
+    /// Status flag: vertex was removed by a morphology operation
+    pub const FLAG_REMOVED: u8 = 1 << 0;
+
     /// Create a new vertex
     pub fn new(id: NeuronId, vertex_type: u8) -> Self {
         Self {
@@ -210,6 +205,51 @@ impl VCSRSnapshot {
         }
     }
     
+    /// Build a finalized snapshot from (source, target, weight) triples.
+    ///
+    /// Vertices `0..num_vertices` are created with `vertex_type`; edges are
+    /// sorted by (source, target) so the CSR layout is deterministic regardless
+    /// of input order.
+    pub fn from_edges(
+        generation: GenerationId,
+        num_vertices: u32,
+        vertex_type: u8,
+        edges: &[(NeuronId, NeuronId, f32)],
+    ) -> Result<Self> {
+        let mut sorted: Vec<(u32, u32, f32)> = Vec::with_capacity(edges.len());
+        for &(source, target, weight) in edges {
+            if source.raw() >= num_vertices || target.raw() >= num_vertices {
+                return Err(StorageError::invalid_format(format!(
+                    "Edge {}->{} out of range for {} vertices",
+                    source.raw(),
+                    target.raw(),
+                    num_vertices
+                )));
+            }
+            sorted.push((source.raw(), target.raw(), weight));
+        }
+        sorted.sort_by_key(|&(source, target, _)| (source, target));
+
+        let mut snapshot = Self::new(generation, num_vertices);
+        for id in 0..num_vertices {
+            snapshot.add_vertex(VCSRVertex::new(NeuronId::new(id), vertex_type));
+        }
+
+        snapshot.col_indices.reserve(sorted.len());
+        snapshot.weights.reserve(sorted.len());
+        for &(source, target, weight) in &sorted {
+            snapshot.row_ptr[source as usize + 1] += 1;
+            snapshot.col_indices.push(target);
+            snapshot.weights.push(weight);
+        }
+        for i in 1..snapshot.row_ptr.len() {
+            snapshot.row_ptr[i] += snapshot.row_ptr[i - 1];
+        }
+
+        snapshot.finalize();
+        Ok(snapshot)
+    }
+
     /// Add a vertex to the snapshot
     pub fn add_vertex(&mut self, vertex: VCSRVertex) {
         self.vertices.push(vertex);
@@ -257,67 +297,72 @@ impl VCSRSnapshot {
         )
     }
     
+    /// Iterate over all edges as (source, target, weight) in CSR order
+    pub fn edges(&self) -> impl Iterator<Item = (NeuronId, NeuronId, f32)> + '_ {
+        self.row_ptr.windows(2).enumerate().flat_map(move |(source, bounds)| {
+            let (start, end) = (bounds[0] as usize, bounds[1] as usize);
+            self.col_indices[start..end]
+                .iter()
+                .zip(&self.weights[start..end])
+                .map(move |(&target, &weight)| {
+                    (NeuronId::new(source as u32), NeuronId::new(target), weight)
+                })
+        })
+    }
+
+    /// Induced subgraph over the vertices whose ID satisfies `keep`.
+    ///
+    /// Kept vertices are renumbered densely in row order and retain their
+    /// original ID in [`VCSRVertex::id`]; only edges between two kept vertices
+    /// survive. Generation and timestamp are carried over.
+    pub fn extract(&self, keep: impl Fn(NeuronId) -> bool) -> Self {
+        let rows = self.row_ptr.len().saturating_sub(1);
+        let mut index: Vec<Option<u32>> = vec![None; rows];
+        let mut vertices = Vec::new();
+        for (row, slot) in index.iter_mut().enumerate() {
+            let vertex = self.vertices.get(row).copied()
+                .unwrap_or_else(|| VCSRVertex::new(NeuronId::new(row as u32), 0));
+            if keep(vertex.neuron_id()) {
+                *slot = Some(vertices.len() as u32);
+                vertices.push(vertex);
+            }
+        }
+
+        let mut snapshot = Self::new(GenerationId::new(self.header.generation), vertices.len() as u32);
+        snapshot.header.timestamp = self.header.timestamp;
+        snapshot.vertices = vertices;
+        for (source, target, weight) in self.edges() {
+            let mapped = index[source.raw() as usize].zip(index.get(target.raw() as usize).copied().flatten());
+            if let Some((source, target)) = mapped {
+                snapshot.row_ptr[source as usize + 1] += 1;
+                snapshot.col_indices.push(target);
+                snapshot.weights.push(weight);
+            }
+        }
+        for i in 1..snapshot.row_ptr.len() {
+            snapshot.row_ptr[i] += snapshot.row_ptr[i - 1];
+        }
+
+        snapshot.finalize();
+        snapshot
+    }
+
     /// Finalize the snapshot and update checksums
     pub fn finalize(&mut self) {
         // Update header with final counts
         self.header.num_vertices = self.vertices.len() as u32;
         self.header.num_incidences = self.col_indices.len() as u64;
         
-        // Calculate data checksum
-        let mut data_to_hash = Vec::new();
-        
-        // Add vertices
-        for vertex in &self.vertices {
-            data_to_hash.extend_from_slice(unsafe {
-                core::slice::from_raw_parts(
-                    vertex as *const VCSRVertex as *const u8,
-                    mem::size_of::<VCSRVertex>(),
-                )
-            });
-        }
-        
-        // Add row pointers
-        for &ptr in &self.row_ptr {
-            data_to_hash.extend_from_slice(&ptr.to_le_bytes());
-        }
-        
-        // Add column indices
-        for &idx in &self.col_indices {
-            data_to_hash.extend_from_slice(&idx.to_le_bytes());
-        }
-        
-        // Add weights
-        for &weight in &self.weights {
-            data_to_hash.extend_from_slice(&weight.to_le_bytes());
-        }
-        
-        self.header.data_checksum = calculate_checksum(&data_to_hash);
+        self.header.data_checksum = calculate_checksum(&self.data_bytes());
         self.header.update_header_checksum();
     }
-    
-    /// Serialize to bytes
-    pub fn to_bytes(&self) -> Vec<u8> {
+
+    /// Data sections in file order: vertices, row pointers, column indices, weights
+    fn data_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
         
-        // Write header
-        let header_bytes = unsafe {
-            core::slice::from_raw_parts(
-                &self.header as *const VCSRHeader as *const u8,
-                mem::size_of::<VCSRHeader>(),
-            )
-        };
-        bytes.extend_from_slice(header_bytes);
-        
         // Write vertices
-        for vertex in &self.vertices {
-            let vertex_bytes = unsafe {
-                core::slice::from_raw_parts(
-                    vertex as *const VCSRVertex as *const u8,
-                    mem::size_of::<VCSRVertex>(),
-                )
-            };
-            bytes.extend_from_slice(vertex_bytes);
-        }
+        bytes.extend_from_slice(bytemuck::cast_slice(&self.vertices));
         
         // Write row pointers
         for &ptr in &self.row_ptr {
@@ -337,96 +382,43 @@ impl VCSRSnapshot {
         bytes
     }
     
-    /// Load from bytes
+    /// Serialize to bytes (current version, with checksums recomputed and a trailing block table)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let data = self.data_bytes();
+        let mut header = self.header;
+        header.version = VCSR_VERSION;
+        header.data_checksum = calculate_checksum(&data);
+        header.blocks_offset = (mem::size_of::<VCSRHeader>() + data.len()) as u64;
+        header.block_size = BLOCK_SIZE;
+        header.update_header_checksum();
+        let blocks = block_checksums(&data, header.block_size);
+
+        let mut bytes = Vec::with_capacity(header.blocks_offset as usize + blocks.len());
+        bytes.extend_from_slice(bytemuck::bytes_of(&header));
+        bytes.extend_from_slice(&data);
+        bytes.extend_from_slice(&blocks);
+        bytes
+    }
+    
+    /// Load from bytes, copying every section (see [`VCSRView`] to read them in place)
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
-        if data.len() < mem::size_of::<VCSRHeader>() {
-            return Err(StorageError::invalid_format("Data too short for header"));
-        }
-        
-        // Parse header
-        let header = unsafe {
-            *(data.as_ptr() as *const VCSRHeader)
-        };
-        header.validate()?;
-        header.verify_header_checksum()?;
-        
-        let mut offset = mem::size_of::<VCSRHeader>();
-        
-        // Parse vertices
-        let vertices_size = header.num_vertices as usize * mem::size_of::<VCSRVertex>();
-        if offset + vertices_size > data.len() {
-            return Err(StorageError::invalid_format("Data too short for vertices"));
-        }
-        
-        let mut vertices = Vec::with_capacity(header.num_vertices as usize);
-        for i in 0..header.num_vertices as usize {
-            let vertex_offset = offset + i * mem::size_of::<VCSRVertex>();
-            let vertex = unsafe {
-                *(data[vertex_offset..].as_ptr() as *const VCSRVertex)
-            };
-            vertices.push(vertex);
-        }
-        offset += vertices_size;
-        
-        // Parse row pointers
-        let row_ptr_size = (header.num_vertices as usize + 1) * mem::size_of::<u64>();
-        if offset + row_ptr_size > data.len() {
-            return Err(StorageError::invalid_format("Data too short for row pointers"));
-        }
-        
-        let mut row_ptr = Vec::with_capacity(header.num_vertices as usize + 1);
-        for i in 0..=header.num_vertices as usize {
-            let ptr_offset = offset + i * mem::size_of::<u64>();
-            let ptr = u64::from_le_bytes([
-                data[ptr_offset],
-                data[ptr_offset + 1],
-                data[ptr_offset + 2],
-                data[ptr_offset + 3],
-                data[ptr_offset + 4],
-                data[ptr_offset + 5],
-                data[ptr_offset + 6],
-                data[ptr_offset + 7],
-            ]);
-            row_ptr.push(ptr);
-        }
-        offset += row_ptr_size;
-        
-        // Parse column indices
-        let col_indices_size = header.num_incidences as usize * mem::size_of::<u32>();
-        if offset + col_indices_size > data.len() {
-            return Err(StorageError::invalid_format("Data too short for column indices"));
-        }
-        
-        let mut col_indices = Vec::with_capacity(header.num_incidences as usize);
-        for i in 0..header.num_incidences as usize {
-            let idx_offset = offset + i * mem::size_of::<u32>();
-            let idx = u32::from_le_bytes([
-                data[idx_offset],
-                data[idx_offset + 1],
-                data[idx_offset + 2],
-                data[idx_offset + 3],
-            ]);
-            col_indices.push(idx);
-        }
-        offset += col_indices_size;
-        
-        // Parse weights
-        let weights_size = header.num_incidences as usize * mem::size_of::<f32>();
-        if offset + weights_size > data.len() {
-            return Err(StorageError::invalid_format("Data too short for weights"));
-        }
-        
-        let mut weights = Vec::with_capacity(header.num_incidences as usize);
-        for i in 0..header.num_incidences as usize {
-            let weight_offset = offset + i * mem::size_of::<f32>();
-            let weight = f32::from_le_bytes([
-                data[weight_offset],
-                data[weight_offset + 1],
-                data[weight_offset + 2],
-                data[weight_offset + 3],
-            ]);
-            weights.push(weight);
-        }
+        let (header, sections) = read_layout(data, true)?;
+        let vertices = data[sections.vertices]
+            .chunks_exact(mem::size_of::<VCSRVertex>())
+            .map(bytemuck::pod_read_unaligned)
+            .collect();
+        let row_ptr = data[sections.row_ptr]
+            .chunks_exact(mem::size_of::<u64>())
+            .map(|b| u64::from_le_bytes(bytemuck::pod_read_unaligned(b)))
+            .collect();
+        let col_indices = data[sections.col_indices]
+            .chunks_exact(mem::size_of::<u32>())
+            .map(|b| u32::from_le_bytes(bytemuck::pod_read_unaligned(b)))
+            .collect();
+        let weights = data[sections.weights]
+            .chunks_exact(mem::size_of::<f32>())
+            .map(|b| f32::from_le_bytes(bytemuck::pod_read_unaligned(b)))
+            .collect();
         
         Ok(Self {
             header,
@@ -438,6 +430,173 @@ impl VCSRSnapshot {
     }
 }
 
+/// Byte ranges of a serialized snapshot's data sections
+struct Sections {
+    vertices: Range<usize>,
+    row_ptr: Range<usize>,
+    col_indices: Range<usize>,
+    weights: Range<usize>,
+}
+
+/// Check the header (and with `check_blocks`, the block table) and locate the sections
+fn read_layout(data: &[u8], check_blocks: bool) -> Result<(VCSRHeader, Sections)> {
+    let header_size = mem::size_of::<VCSRHeader>();
+    if data.len() < header_size {
+        return Err(StorageError::invalid_format("Data too short for header"));
+    }
+    let header: VCSRHeader = bytemuck::pod_read_unaligned(&data[..header_size]);
+    header.validate()?;
+    header.verify_header_checksum()?;
+    if check_blocks {
+        check_block_table(&header, data)?;
+    }
+
+    let mut offset = header_size;
+    let mut section = |count: u64, width: usize, name: &str| {
+        let end = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_mul(width))
+            .and_then(|len| offset.checked_add(len))
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| StorageError::invalid_format(format!("Data too short for {}", name)))?;
+        let range = offset..end;
+        offset = end;
+        Ok::<_, StorageError>(range)
+    };
+    let vertices = section(header.num_vertices as u64, mem::size_of::<VCSRVertex>(), "vertices")?;
+    let row_ptr = section(header.num_vertices as u64 + 1, mem::size_of::<u64>(), "row pointers")?;
+    let col_indices = section(header.num_incidences, mem::size_of::<u32>(), "column indices")?;
+    let weights = section(header.num_incidences, mem::size_of::<f32>(), "weights")?;
+    Ok((header, Sections { vertices, row_ptr, col_indices, weights }))
+}
+
+/// Verify the data sections against the block table (version 1 snapshots have none)
+fn check_block_table(header: &VCSRHeader, data: &[u8]) -> Result<()> {
+    if header.version < 2 {
+        return Ok(());
+    }
+    let offset = mem::size_of::<VCSRHeader>();
+    let blocks_offset = usize::try_from(header.blocks_offset)
+        .ok()
+        .filter(|&end| end >= offset && end <= data.len())
+        .ok_or_else(|| StorageError::invalid_format("VCSR block table offset out of range"))?;
+    verify_blocks(&data[offset..blocks_offset], &data[blocks_offset..], header.block_size, offset as u64)
+}
+
+/// Snapshot sections read in place from serialized bytes, without copying
+///
+/// The slices borrow the bytes, which must be 8-byte aligned (as memory maps
+/// are) on a little-endian host; [`MappedVCSR`] maps a file for viewing.
+#[derive(Debug, Clone, Copy)]
+pub struct VCSRView<'a> {
+    /// Header information
+    pub header: VCSRHeader,
+    /// Vertex data
+    pub vertices: &'a [VCSRVertex],
+    /// CSR row pointers (length: num_vertices + 1)
+    pub row_ptr: &'a [u64],
+    /// Column indices (target vertices)
+    pub col_indices: &'a [u32],
+    /// Edge weights
+    pub weights: &'a [f32],
+    data: &'a [u8],
+}
+
+impl<'a> VCSRView<'a> {
+    /// View `data` after checking its header and section bounds
+    ///
+    /// Takes constant time: the data is only checksummed by
+    /// [`verify_blocks`](Self::verify_blocks).
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self> {
+        if cfg!(target_endian = "big") {
+            return Err(StorageError::invalid_format("zero-copy VCSR views need a little-endian host"));
+        }
+        let (header, sections) = read_layout(data, false)?;
+        Ok(Self {
+            header,
+            vertices: cast_section(data, sections.vertices, "vertices")?,
+            row_ptr: cast_section(data, sections.row_ptr, "row pointers")?,
+            col_indices: cast_section(data, sections.col_indices, "column indices")?,
+            weights: cast_section(data, sections.weights, "weights")?,
+            data,
+        })
+    }
+
+    /// Check the data against the block table, as [`VCSRSnapshot::from_bytes`] does
+    pub fn verify_blocks(&self) -> Result<()> {
+        check_block_table(&self.header, self.data)
+    }
+
+    /// Get neighbors of a vertex (none if its row pointers are out of range)
+    pub fn neighbors(&self, vertex: NeuronId) -> impl Iterator<Item = (NeuronId, f32)> + 'a {
+        let (col_indices, weights) = (self.col_indices, self.weights);
+        let index = vertex.raw() as usize;
+        let range = match (self.row_ptr.get(index), self.row_ptr.get(index + 1)) {
+            (Some(&start), Some(&end)) if start <= end && end <= col_indices.len() as u64 => {
+                start as usize..end as usize
+            }
+            _ => 0..0,
+        };
+        col_indices[range.clone()]
+            .iter()
+            .zip(&weights[range])
+            .map(|(&target, &weight)| (NeuronId::new(target), weight))
+    }
+
+    /// Iterate over all edges as (source, target, weight) in CSR order
+    pub fn edges(&self) -> impl Iterator<Item = (NeuronId, NeuronId, f32)> + 'a {
+        let view = *self;
+        (0..self.row_ptr.len().saturating_sub(1)).flat_map(move |source| {
+            let source = NeuronId::new(source as u32);
+            view.neighbors(source).map(move |(target, weight)| (source, target, weight))
+        })
+    }
+
+    /// Owned copy of the viewed snapshot
+    pub fn to_snapshot(&self) -> VCSRSnapshot {
+        VCSRSnapshot {
+            header: self.header,
+            vertices: self.vertices.to_vec(),
+            row_ptr: self.row_ptr.to_vec(),
+            col_indices: self.col_indices.to_vec(),
+            weights: self.weights.to_vec(),
+        }
+    }
+}
+
+fn cast_section<'a, T: Pod>(data: &'a [u8], range: Range<usize>, name: &str) -> Result<&'a [T]> {
+    bytemuck::try_cast_slice(&data[range]).map_err(|e| {
+        StorageError::invalid_format(format!("VCSR {} are not aligned for a zero-copy view: {:?}", name, e))
+    })
+}
+
+/// Snapshot file mapped into memory and read through a [`VCSRView`]
+#[derive(Debug)]
+pub struct MappedVCSR {
+    map: memmap2::Mmap,
+}
+
+impl MappedVCSR {
+    /// Map `path` and check its header and section bounds
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the map is read-only, and snapshot files are replaced by
+        // rename (atomic::write_atomic) rather than rewritten in place
+        let map = unsafe { memmap2::Mmap::map(&file) }.map_err(|e| StorageError::MemoryMap {
+            reason: format!("{}: {}", path.display(), e),
+        })?;
+        let mapped = Self { map };
+        mapped.view()?;
+        Ok(mapped)
+    }
+
+    /// View of the mapped snapshot (constant time)
+    pub fn view(&self) -> Result<VCSRView<'_>> {
+        VCSRView::from_bytes(&self.map)
+    }
+}
+
 trait EitherIterator<L, R> {
     type Iter: Iterator;
     fn left_iter(self) -> Self::Iter;
@@ -486,7 +645,7 @@ mod tests {
         header.update_header_checksum();
         
         assert_eq!(header.magic, magic::VCSR);
-        assert_eq!(header.version, 1);
+        assert_eq!(header.version, VCSR_VERSION);
         assert_eq!(header.generation, 1);
         assert_eq!(header.num_vertices, 100);
         assert_eq!(header.num_hyperedges, 500);
@@ -535,4 +694,132 @@ mod tests {
         assert_eq!(loaded.header.num_incidences, 3);
         assert_eq!(loaded.vertices.len(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_vcsr_from_edges() {
+        let edges = [
+            (NeuronId::new(1), NeuronId::new(2), 0.3),
+            (NeuronId::new(0), NeuronId::new(2), 0.8),
+            (NeuronId::new(0), NeuronId::new(1), 0.5),
+        ];
+        let snapshot = VCSRSnapshot::from_edges(GenerationId::new(3), 3, 0, &edges).unwrap();
+
+        assert_eq!(snapshot.row_ptr, vec![0, 2, 3, 3]);
+        assert_eq!(snapshot.col_indices, vec![1, 2, 2]);
+        assert_eq!(snapshot.weights, vec![0.5, 0.8, 0.3]);
+
+        let loaded = VCSRSnapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+        assert_eq!(loaded.header.generation, 3);
+        assert_eq!(loaded.header.num_incidences, 3);
+
+        let listed: Vec<_> = loaded.edges().collect();
+        assert_eq!(listed[2], (NeuronId::new(1), NeuronId::new(2), 0.3));
+
+        let out_of_range = [(NeuronId::new(0), NeuronId::new(3), 1.0)];
+        assert!(VCSRSnapshot::from_edges(GenerationId::new(1), 3, 0, &out_of_range).is_err());
+    }
+
+    #[test]
+    fn test_vcsr_extract() {
+        let n = NeuronId::new;
+        let edges = [(n(0), n(1), 0.5), (n(0), n(3), 0.8), (n(1), n(3), 0.3), (n(2), n(3), 0.1), (n(3), n(3), 0.2)];
+        let mut snapshot = VCSRSnapshot::from_edges(GenerationId::new(5), 4, 2, &edges).unwrap();
+        snapshot.vertices[3].flags |= VCSRVertex::FLAG_REMOVED;
+
+        let sub = snapshot.extract(|id| id != n(2));
+        assert_eq!(sub.header.generation, 5);
+        assert_eq!(sub.header.timestamp, snapshot.header.timestamp);
+        assert_eq!(sub.vertices.iter().map(|v| v.id).collect::<Vec<_>>(), vec![0, 1, 3]);
+        assert_eq!(sub.vertices[2].flags, VCSRVertex::FLAG_REMOVED);
+        assert_eq!(sub.row_ptr, vec![0, 2, 3, 4]);
+        assert_eq!(sub.col_indices, vec![1, 2, 2, 2]);
+        assert_eq!(sub.weights, vec![0.5, 0.8, 0.3, 0.2]);
+
+        let loaded = VCSRSnapshot::from_bytes(&sub.to_bytes()).unwrap();
+        assert_eq!(loaded.header.num_vertices, 3);
+        assert_eq!(loaded.header.num_incidences, 4);
+
+        assert_eq!(snapshot.extract(|_| false).header.num_vertices, 0);
+    }
+
+    #[test]
+    fn test_vcsr_block_checksums() {
+        let edges = [(NeuronId::new(0), NeuronId::new(1), 0.5), (NeuronId::new(1), NeuronId::new(2), 0.25)];
+        let bytes = VCSRSnapshot::from_edges(GenerationId::new(4), 3, 0, &edges).unwrap().to_bytes();
+        let header_size = mem::size_of::<VCSRHeader>();
+
+        let mut corrupt = bytes.clone();
+        corrupt[header_size + 9] ^= 0x01;
+        assert!(matches!(
+            VCSRSnapshot::from_bytes(&corrupt),
+            Err(StorageError::CorruptBlock { offset }) if offset == header_size as u64
+        ));
+
+        let mut corrupt = bytes.clone();
+        corrupt[8] ^= 0x01;
+        assert!(matches!(VCSRSnapshot::from_bytes(&corrupt), Err(StorageError::CorruptBlock { offset: 0 })));
+
+        let mut future = VCSRSnapshot::from_bytes(&bytes).unwrap().header;
+        future.version = VCSR_VERSION + 1;
+        future.update_header_checksum();
+        assert!(matches!(future.validate(), Err(StorageError::BadVersion { .. })));
+    }
+
+    #[test]
+    fn test_vcsr_zero_copy_view() {
+        let edges = [
+            (NeuronId::new(0), NeuronId::new(1), 0.5),
+            (NeuronId::new(0), NeuronId::new(2), 0.8),
+            (NeuronId::new(2), NeuronId::new(1), 0.3),
+        ];
+        let snapshot = VCSRSnapshot::from_edges(GenerationId::new(5), 3, 1, &edges).unwrap();
+        let bytes = snapshot.to_bytes();
+
+        // Sections are read in place from 8-byte aligned bytes
+        let mut words = vec![0u64; bytes.len() / 8 + 1];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut words)[..bytes.len()].copy_from_slice(&bytes);
+        let aligned = &bytemuck::cast_slice::<u64, u8>(&words)[..bytes.len()];
+        let view = VCSRView::from_bytes(aligned).unwrap();
+        assert_eq!(view.header.generation, 5);
+        assert_eq!(view.row_ptr, &snapshot.row_ptr[..]);
+        assert_eq!(view.vertices[2].vertex_type, 1);
+        assert_eq!(view.edges().collect::<Vec<_>>(), snapshot.edges().collect::<Vec<_>>());
+        assert_eq!(view.neighbors(NeuronId::new(2)).collect::<Vec<_>>(), vec![(NeuronId::new(1), 0.3)]);
+        assert_eq!(view.neighbors(NeuronId::new(9)).count(), 0);
+        assert!(view.verify_blocks().is_ok());
+        assert_eq!(view.to_snapshot().to_bytes(), bytes);
+
+        // Corrupt data is only caught by the block check
+        let mut corrupt = words.clone();
+        bytemuck::cast_slice_mut::<u64, u8>(&mut corrupt)[bytes.len() - 5] ^= 0xff;
+        let view = VCSRView::from_bytes(&bytemuck::cast_slice::<u64, u8>(&corrupt)[..bytes.len()]).unwrap();
+        assert!(matches!(view.verify_blocks(), Err(StorageError::CorruptBlock { .. })));
+
+        // Misaligned row pointers cannot be viewed in place
+        let mut shifted = vec![0u64; bytes.len() / 8 + 2];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut shifted)[4..bytes.len() + 4].copy_from_slice(&bytes);
+        let err = VCSRView::from_bytes(&bytemuck::cast_slice::<u64, u8>(&shifted)[4..bytes.len() + 4]).unwrap_err();
+        assert!(err.to_string().contains("row pointers are not aligned"));
+        assert!(matches!(
+            VCSRView::from_bytes(&aligned[..bytes.len() / 2]),
+            Err(StorageError::InvalidFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_vcsr_mapped_file() {
+        let edges = [(NeuronId::new(0), NeuronId::new(1), 0.5), (NeuronId::new(1), NeuronId::new(0), 0.25)];
+        let snapshot = VCSRSnapshot::from_edges(GenerationId::new(2), 2, 0, &edges).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gen_2.vcsr");
+        std::fs::write(&path, snapshot.to_bytes()).unwrap();
+
+        let mapped = MappedVCSR::open(&path).unwrap();
+        let view = mapped.view().unwrap();
+        assert_eq!(view.header.num_incidences, 2);
+        assert_eq!(view.edges().collect::<Vec<_>>(), snapshot.edges().collect::<Vec<_>>());
+
+        std::fs::write(&path, b"VCSR").unwrap();
+        assert!(MappedVCSR::open(&path).is_err());
+    }
+}
@@ -4,12 +4,19 @@
      error::{Result, StorageError},
      ids::StreamId,
      magic,
-     schemas::{calculate_checksum, current_timestamp, validate_magic, cast_slice_to_struct},
+     schemas::{
+        block_checksums, calculate_checksum, cast_slice_to_struct, current_timestamp, validate_magic,
+        validate_version, verify_blocks, BLOCK_SIZE,
+    },
+    spike_block::CompressedSpikes,
      traits::{Event, EventStore, EventType},
      NeuronId, Time,
  };
  
  use core::mem;
+
+/// Current VEVT format version; version 1 streams (no block table) are still read
+pub const VEVT_VERSION: u32 = 2;
  
  /// VEVT format header
  #[repr(C)]
@@ -17,7 +24,7 @@
  pub struct VEVTHeader {
     /// Magic number "VEVT"
     pub magic: [u8; 4],
-    /// Schema version (current: 1)
+    /// Schema version (current: [`VEVT_VERSION`])
     pub version: u32,
     /// Unique stream identifier
     pub stream_id: u64,
@@ -59,9 +66,13 @@
     pub header_checksum: u32,
     /// CRC32 of event data
     pub data_checksum: u32,
+    /// Offset to the per-block CRC32C table after the event data (0 in version 1)
+    pub blocks_offset: u64,
+    /// Event data bytes covered by each table entry
+    pub block_size: u32,
     
     /// Reserved space
-    pub reserved: [u8; 24],
+    pub reserved: [u8; 12],
 }
 
 impl VEVTHeader {
@@ -69,7 +80,7 @@ impl VEVTHeader {
     pub fn new(stream_id: StreamId) -> Self {
         Self {
             magic: magic::VEVT,
-            version: 1,
+            version: VEVT_VERSION,
             stream_id: stream_id.raw(),
             time_start: 0,
             time_end: 0,
@@ -85,7 +96,9 @@ impl VEVTHeader {
             metadata_offset: 0,
             header_checksum: 0,
             data_checksum: 0,
-            reserved: [0; 24],
+            blocks_offset: 0,
+            block_size: BLOCK_SIZE,
+            reserved: [0; 12],
         }
     }
 
@@ -97,15 +110,7 @@ impl VEVTHeader {
     /// Validate this header
     pub fn validate(&self) -> Result<()> {
         validate_magic(&self.magic, magic::VEVT)?;
-        
-        if self.version != 1 {
-            return Err(StorageError::UnsupportedVersion {
-                version: self.version,
-                supported: 1,
-            });
-        }
-        
-        Ok(())
+        validate_version(self.version, VEVT_VERSION)
     }
 }
 
@@ -133,6 +138,35 @@ impl VEVTEncoding {
     pub const RLE: u8 = 2;
 }
 
+/// Event type codes ([`VEVTEvent::event_type`])
+pub struct VEVTEventType;
+impl VEVTEventType {
+    /// Neuron spike (source = neuron)
+    pub const SPIKE: u8 = 0;
+    /// TTR phase entry
+    pub const PHASE_ENTER: u8 = 1;
+    /// TTR phase exit
+    pub const PHASE_EXIT: u8 = 2;
+    /// Neuromodulation
+    pub const NEUROMODULATION: u8 = 3;
+    /// Reward signal
+    pub const REWARD: u8 = 4;
+    /// System control
+    pub const CONTROL: u8 = 5;
+    /// Temporal marker
+    pub const MARKER: u8 = 6;
+    /// Plasticity changed a weight (source = pre, target = post, [`WeightChange`] payload)
+    pub const WEIGHT_UPDATE: u8 = 7;
+    /// Neuron added (source = neuron)
+    pub const NEURON_ADDED: u8 = 8;
+    /// Neuron removed (source = neuron)
+    pub const NEURON_REMOVED: u8 = 9;
+    /// Synapse added (source = pre, target = post)
+    pub const SYNAPSE_ADDED: u8 = 10;
+    /// Synapse removed (source = pre, target = post)
+    pub const SYNAPSE_REMOVED: u8 = 11;
+}
+
 /// Base event structure
 #[repr(C)]
 #[derive(Debug, Clone)]
@@ -165,6 +199,8 @@ impl Event for VEVTEvent {
             4 => EventType::Reward,
             5 => EventType::Control,
             6 => EventType::Marker,
+            7 => EventType::WeightUpdate,
+            8..=11 => EventType::Structural,
             _ => EventType::Control, // Default fallback
         }
     }
@@ -201,14 +237,65 @@ impl VEVTEvent {
     }
 }
 
+/// Weight change carried by a [`VEVTEventType::WEIGHT_UPDATE`] event and its payload
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightChange {
+    /// Time of the update (ns)
+    pub time_ns: u64,
+    /// Presynaptic neuron
+    pub pre: u32,
+    /// Postsynaptic neuron
+    pub post: u32,
+    /// Applied change (new minus old weight)
+    pub delta: f32,
+    /// Weight after the update
+    pub weight: f32,
+}
+
+impl WeightChange {
+    /// Payload bytes: `delta:f32 weight:f32` (little-endian)
+    pub const PAYLOAD_SIZE: u16 = 8;
+
+    /// Event announcing the change; [`WeightChange::payload`] follows it
+    pub fn event(&self) -> VEVTEvent {
+        VEVTEvent {
+            timestamp: self.time_ns,
+            event_type: VEVTEventType::WEIGHT_UPDATE,
+            source_id: self.pre,
+            target_id: self.post,
+            payload_size: Self::PAYLOAD_SIZE,
+            reserved: 0,
+        }
+    }
+
+    /// Payload of [`WeightChange::event`]
+    pub fn payload(&self) -> [u8; 8] {
+        let mut payload = [0; 8];
+        payload[..4].copy_from_slice(&self.delta.to_le_bytes());
+        payload[4..].copy_from_slice(&self.weight.to_le_bytes());
+        payload
+    }
+
+    /// Change from a weight-update event and its payload (`None` for other events)
+    pub fn from_event(event: &VEVTEvent, payload: &[u8]) -> Option<Self> {
+        if event.event_type != VEVTEventType::WEIGHT_UPDATE || payload.len() != Self::PAYLOAD_SIZE as usize {
+            return None;
+        }
+        Some(Self {
+            time_ns: event.timestamp,
+            pre: event.source_id,
+            post: event.target_id,
+            delta: f32::from_le_bytes(payload[..4].try_into().ok()?),
+            weight: f32::from_le_bytes(payload[4..].try_into().ok()?),
+        })
+    }
+}
+
 /// Encode a sorted (by timestamp) list of events into VEVT bytes
 pub fn encode_vevt(stream_id: StreamId, start: Time, end: Time, events: &[VEVTEvent]) -> Result<Vec<u8>> {
     let mut header = VEVTHeader::new(stream_id);
-    header.time_start = start.as_nanos();
-    header.time_end = end.as_nanos();
     header.total_events = events.len() as u64;
     header.spike_events = events.iter().filter(|e| e.event_type == 0).count() as u64;
-    header.events_offset = VEVTHeader::size() as u64;
 
     // Serialize events block
     let event_size = mem::size_of::<VEVTEvent>();
@@ -220,7 +307,28 @@ pub fn encode_vevt(stream_id: StreamId, start: Time, end: Time, events: &[VEVTEv
         body.extend_from_slice(raw);
     }
 
+    Ok(finish_vevt(header, start, end, body))
+}
+
+/// Encode spikes as a `DELTA_COMPRESSED` VEVT stream (spike blocks instead of fixed-size events)
+///
+/// [`decode_vevt`] expands the blocks back into spike events (`event_type` 0, no target).
+pub fn encode_vevt_spikes(stream_id: StreamId, start: Time, end: Time, spikes: &CompressedSpikes) -> Result<Vec<u8>> {
+    let mut header = VEVTHeader::new(stream_id);
+    header.encoding = VEVTEncoding::DELTA_COMPRESSED;
+    header.total_events = spikes.len() as u64;
+    header.spike_events = spikes.len() as u64;
+    Ok(finish_vevt(header, start, end, spikes.to_bytes()))
+}
+
+/// Fill in range, offsets and checksums, then concatenate header, body and block table
+fn finish_vevt(mut header: VEVTHeader, start: Time, end: Time, body: Vec<u8>) -> Vec<u8> {
+    header.time_start = start.as_nanos();
+    header.time_end = end.as_nanos();
+    header.events_offset = VEVTHeader::size() as u64;
     header.data_checksum = calculate_checksum(&body);
+    header.blocks_offset = (VEVTHeader::size() + body.len()) as u64;
+    let blocks = block_checksums(&body, header.block_size);
 
     // Serialize header with header_checksum = 0, compute checksum, then write final header + body
     let mut header_bytes = unsafe {
@@ -229,7 +337,7 @@ pub fn encode_vevt(stream_id: StreamId, start: Time, end: Time, events: &[VEVTEv
 
     // Zero header checksum field before computing checksum
     {
-        let checksum_offset = offset_of_header_checksum();
+        let checksum_offset = mem::offset_of!(VEVTHeader, header_checksum);
         // write zeros
         header_bytes[checksum_offset..checksum_offset+4].copy_from_slice(&[0u8;4]);
     }
@@ -238,16 +346,20 @@ pub fn encode_vevt(stream_id: StreamId, start: Time, end: Time, events: &[VEVTEv
     header.header_checksum = hdr_crc;
 
     // Write final header with checksum
-    let mut final_bytes = Vec::with_capacity(VEVTHeader::size() + body.len());
+    let mut final_bytes = Vec::with_capacity(VEVTHeader::size() + body.len() + blocks.len());
     final_bytes.extend_from_slice(unsafe {
         core::slice::from_raw_parts(&header as *const VEVTHeader as *const u8, VEVTHeader::size())
     });
     final_bytes.extend_from_slice(&body);
+    final_bytes.extend_from_slice(&blocks);
 
-    Ok(final_bytes)
+    final_bytes
 }
 
 /// Decode VEVT bytes into header and event vector
+///
+/// A header checksum failure is reported as [`StorageError::CorruptBlock`] at
+/// offset 0, a damaged event block (version 2) at that block's offset.
 pub fn decode_vevt(bytes: &[u8]) -> Result<(VEVTHeader, Vec<VEVTEvent>)> {
     if bytes.len() < VEVTHeader::size() {
         return Err(StorageError::InvalidFormat { reason: "VEVT too small".into() });
@@ -259,22 +371,25 @@ pub fn decode_vevt(bytes: &[u8]) -> Result<(VEVTHeader, Vec<VEVTEvent>)> {
 
     // Validate header checksum (compute on header with header_checksum zero)
     let mut hdr_copy = bytes[..VEVTHeader::size()].to_vec();
-    let checksum_offset = offset_of_header_checksum();
+    let checksum_offset = mem::offset_of!(VEVTHeader, header_checksum);
     hdr_copy[checksum_offset..checksum_offset+4].copy_from_slice(&[0u8;4]);
-    let computed_hdr = calculate_checksum(&hdr_copy);
-    if computed_hdr != header.header_checksum {
-        return Err(StorageError::ChecksumMismatch { expected: header.header_checksum, computed: computed_hdr });
+    if calculate_checksum(&hdr_copy) != header.header_checksum {
+        return Err(StorageError::CorruptBlock { offset: 0 });
     }
 
-    // Read events block
+    // Read events block; version 1 streams run to the end of the file
     let events_offset = header.events_offset as usize;
-    if bytes.len() < events_offset {
+    let events_end = match header.version {
+        1 => bytes.len(),
+        _ => header.blocks_offset as usize,
+    };
+    if events_offset > events_end || events_end > bytes.len() {
         return Err(StorageError::InvalidFormat { reason: "events_offset out of range".into() });
     }
     let event_size = mem::size_of::<VEVTEvent>();
-    let events_bytes = &bytes[events_offset..];
-    if events_bytes.len() % event_size != 0 {
-        return Err(StorageError::InvalidFormat { reason: "VEVT body size misaligned".into() });
+    let events_bytes = &bytes[events_offset..events_end];
+    if header.version >= 2 {
+        verify_blocks(events_bytes, &bytes[events_end..], header.block_size, events_offset as u64)?;
     }
 
     // Validate data checksum if non-zero
@@ -283,6 +398,24 @@ pub fn decode_vevt(bytes: &[u8]) -> Result<(VEVTHeader, Vec<VEVTEvent>)> {
         return Err(StorageError::ChecksumMismatch { expected: header.data_checksum, computed: data_crc });
     }
 
+    if header.encoding == VEVTEncoding::DELTA_COMPRESSED {
+        let events = CompressedSpikes::from_bytes(events_bytes)?
+            .iter()
+            .map(|spike| VEVTEvent {
+                timestamp: spike.time.nanos(),
+                event_type: 0,
+                source_id: spike.neuron_id.raw(),
+                target_id: u32::MAX,
+                payload_size: 0,
+                reserved: 0,
+            })
+            .collect();
+        return Ok((header.clone(), events));
+    }
+    if events_bytes.len() % event_size != 0 {
+        return Err(StorageError::InvalidFormat { reason: "VEVT body size misaligned".into() });
+    }
+
     let count = events_bytes.len() / event_size;
     let mut out = Vec::with_capacity(count);
     for i in 0..count {
@@ -295,26 +428,6 @@ pub fn decode_vevt(bytes: &[u8]) -> Result<(VEVTHeader, Vec<VEVTEvent>)> {
     Ok((header.clone(), out))
 }
 
-/// Byte offset of header_checksum field within VEVTHeader
-fn offset_of_header_checksum() -> usize {
-    // Compute statically if needed; simple approach by layout
-    // Field order: ... header_checksum (u32) then data_checksum (u32)
-    // Compute via dummy instance
-    // SAFETY: Offsets are stable due to repr(C)
-    let base = 0 as *const VEVTHeader as usize;
-    let dummy: VEVTHeader = VEVTHeader {
-        magic: magic::VEVT, version: 1, stream_id: 0,
-        time_start: 0, time_end: 0, time_resolution: 1,
-        total_events: 0, spike_events: 0, control_events: 0,
-        compression: 0, encoding: 0, flags: 0,
-        events_offset: 0, index_offset: 0, metadata_offset: 0,
-        header_checksum: 0, data_checksum: 0, reserved: [0;24],
-    };
-    let addr_hdr = (&dummy as *const VEVTHeader) as usize;
-    let addr_field = (&dummy.header_checksum as *const u32) as usize;
-    addr_field - addr_hdr
-}
-
 /// Simple in-memory event store implementation
 pub struct MemoryEventStore {
     events: Vec<VEVTEvent>,
@@ -396,8 +509,6 @@ impl EventStore for MemoryEventStore {
     
     fn export_vevt(&self, start: Time, end: Time) -> Result<Vec<u8>> {
         let mut header = VEVTHeader::new(self.stream_id);
-        header.time_start = start.as_nanos();
-        header.time_end = end.as_nanos();
         
         let events_in_range: Vec<_> = self.time_window(start, end)?.collect();
         header.total_events = events_in_range.len() as u64;
@@ -405,18 +516,7 @@ impl EventStore for MemoryEventStore {
             .filter(|e| e.event_type == 0)
             .count() as u64;
         
-        let mut bytes = Vec::new();
-        
-        // Write header
-        let header_bytes = unsafe {
-            core::slice::from_raw_parts(
-                &header as *const VEVTHeader as *const u8,
-                mem::size_of::<VEVTHeader>(),
-            )
-        };
-        bytes.extend_from_slice(header_bytes);
-        
-        // Write events
+        let mut body = Vec::with_capacity(events_in_range.len() * mem::size_of::<VEVTEvent>());
         for event in events_in_range {
             let event_bytes = unsafe {
                 core::slice::from_raw_parts(
@@ -424,10 +524,10 @@ impl EventStore for MemoryEventStore {
                     mem::size_of::<VEVTEvent>(),
                 )
             };
-            bytes.extend_from_slice(event_bytes);
+            body.extend_from_slice(event_bytes);
         }
         
-        Ok(bytes)
+        Ok(finish_vevt(header, start, end, body))
     }
 }
 
@@ -439,7 +539,7 @@ mod tests {
     fn test_vevt_header() {
         let header = VEVTHeader::new(StreamId::new(1));
         assert_eq!(header.magic, magic::VEVT);
-        assert_eq!(header.version, 1);
+        assert_eq!(header.version, VEVT_VERSION);
         assert_eq!(header.stream_id, 1);
         assert!(header.validate().is_ok());
     }
@@ -460,6 +560,20 @@ mod tests {
         assert_eq!(event.source_id(), Some(NeuronId::new(42)));
     }
 
+    #[test]
+    fn test_weight_change_event() {
+        let change = WeightChange { time_ns: 500, pre: 3, post: 7, delta: -0.25, weight: 0.5 };
+        let event = change.event();
+        assert_eq!(event.event_type(), EventType::WeightUpdate);
+        assert_eq!(event.payload_size, WeightChange::PAYLOAD_SIZE);
+        assert_eq!(WeightChange::from_event(&event, &change.payload()), Some(change));
+        assert_eq!(WeightChange::from_event(&event, &[0; 4]), None);
+
+        let removed = VEVTEvent { event_type: VEVTEventType::SYNAPSE_REMOVED, ..event };
+        assert_eq!(removed.event_type(), EventType::Structural);
+        assert_eq!(WeightChange::from_event(&removed, &change.payload()), None);
+    }
+
     #[test]
     fn test_memory_event_store() {
         let mut store = MemoryEventStore::new(StreamId::new(1));
@@ -497,4 +611,64 @@ mod tests {
         assert_eq!(windowed_events.len(), 1);
         assert_eq!(windowed_events[0].timestamp, 2000);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_delta_compressed_spike_stream_round_trip() {
+        let spikes: CompressedSpikes = (0..3000u64)
+            .map(|i| crate::Spike::new(NeuronId::new((i % 97) as u32), Time::from_nanos(i / 4 * 100_000)))
+            .collect::<Vec<_>>()
+            .iter()
+            .collect();
+        let bytes = encode_vevt_spikes(StreamId::new(3), Time::ZERO, Time::from_nanos(75_000_000), &spikes)
+            .expect("Should encode spikes");
+        assert!(bytes.len() < VEVTHeader::size() + spikes.len() * mem::size_of::<VEVTEvent>() / 4);
+
+        let (header, events) = decode_vevt(&bytes).expect("Should decode compressed stream");
+        assert_eq!(header.encoding, VEVTEncoding::DELTA_COMPRESSED);
+        assert_eq!(header.spike_events, 3000);
+        assert_eq!(events.len(), 3000);
+        assert!(events.iter().zip(spikes.iter()).all(|(e, s)| {
+            e.timestamp == s.time.nanos() && e.source_id == s.neuron_id.raw() && e.event_type == 0
+        }));
+    }
+
+    /// Rewrite a header field at `offset` and re-sign the header
+    fn patch_header(bytes: &mut [u8], offset: usize, value: &[u8]) {
+        bytes[offset..offset + value.len()].copy_from_slice(value);
+        let checksum_offset = mem::offset_of!(VEVTHeader, header_checksum);
+        bytes[checksum_offset..checksum_offset + 4].fill(0);
+        let crc = calculate_checksum(&bytes[..VEVTHeader::size()]);
+        bytes[checksum_offset..checksum_offset + 4].copy_from_slice(&crc.to_le_bytes());
+    }
+
+    #[test]
+    fn test_vevt_integrity_errors() {
+        let events: Vec<VEVTEvent> = (0..4)
+            .map(|i| VEVTEvent { timestamp: i * 100, event_type: 0, source_id: i as u32, target_id: u32::MAX, payload_size: 0, reserved: 0 })
+            .collect();
+        let bytes = encode_vevt(StreamId::new(1), Time::ZERO, Time::from_nanos(400), &events).expect("Should encode");
+        assert_eq!(decode_vevt(&bytes).expect("Should decode").1.len(), 4);
+
+        let mut corrupt = bytes.clone();
+        corrupt[VEVTHeader::size() + 3] ^= 0x10;
+        assert!(matches!(decode_vevt(&corrupt), Err(StorageError::CorruptBlock { offset }) if offset == VEVTHeader::size() as u64));
+
+        let mut corrupt = bytes.clone();
+        corrupt[mem::offset_of!(VEVTHeader, stream_id)] ^= 1;
+        assert!(matches!(decode_vevt(&corrupt), Err(StorageError::CorruptBlock { offset: 0 })));
+
+        let mut future = bytes.clone();
+        patch_header(&mut future, mem::offset_of!(VEVTHeader, version), &3u32.to_le_bytes());
+        assert!(matches!(decode_vevt(&future), Err(StorageError::BadVersion { version: 3, supported: VEVT_VERSION })));
+
+        let mut foreign = bytes.clone();
+        foreign[..4].copy_from_slice(&magic::VCSR);
+        assert!(matches!(decode_vevt(&foreign), Err(StorageError::BadMagic { .. })));
+
+        // Version 1 streams have no block table
+        let mut legacy = bytes[..bytes.len() - 4].to_vec();
+        patch_header(&mut legacy, mem::offset_of!(VEVTHeader, version), &1u32.to_le_bytes());
+        patch_header(&mut legacy, mem::offset_of!(VEVTHeader, blocks_offset), &0u64.to_le_bytes());
+        assert_eq!(decode_vevt(&legacy).expect("Should decode version 1").1.len(), 4);
+    }
+}
@@ -4,19 +4,25 @@ use crate::{
     error::{Result, StorageError},
     ids::{GenerationId, MaskId},
     magic,
-    schemas::{calculate_checksum, current_timestamp, validate_magic},
+    schemas::{
+        block_checksums, calculate_checksum, current_timestamp, validate_magic, validate_version, verify_blocks,
+        BLOCK_SIZE,
+    },
     traits::{Mask, MaskType},
 };
 
 use core::mem;
 
+/// Current VMSK format version; version 1 masks (no checksums) are still read
+pub const VMSK_VERSION: u32 = 2;
+
 /// VMSK format header
 #[repr(C)]
 #[derive(Debug, Clone)]
 pub struct VMSKHeader {
     /// Magic number "VMSK"
     pub magic: [u8; 4],
-    /// Schema version (current: 1)
+    /// Schema version (current: [`VMSK_VERSION`])
     pub version: u32,
     /// Unique mask identifier
     pub mask_id: u32,
@@ -48,9 +54,13 @@ pub struct VMSKHeader {
     pub header_checksum: u32,
     /// CRC32 of mask data
     pub data_checksum: u32,
+    /// Offset to the per-block CRC32C table after the mask data (0 in version 1)
+    pub blocks_offset: u64,
+    /// Mask data bytes covered by each table entry
+    pub block_size: u32,
     
     /// Reserved space
-    pub reserved: [u8; 20],
+    pub reserved: [u8; 8],
 }
 
 impl VMSKHeader {
@@ -58,7 +68,7 @@ impl VMSKHeader {
     pub fn new(mask_id: MaskId, generation: GenerationId, mask_type: MaskType, total_bits: u64) -> Self {
         Self {
             magic: magic::VMSK,
-            version: 1,
+            version: VMSK_VERSION,
             mask_id: mask_id.raw(),
             generation: generation.raw(),
             total_bits,
@@ -71,22 +81,66 @@ impl VMSKHeader {
             metadata_offset: 0,
             header_checksum: 0,
             data_checksum: 0,
-            reserved: [0; 20],
+            blocks_offset: 0,
+            block_size: BLOCK_SIZE,
+            reserved: [0; 8],
         }
     }
     
     /// Validate this header
     pub fn validate(&self) -> Result<()> {
         validate_magic(&self.magic, magic::VMSK)?;
-        
-        if self.version != 1 {
-            return Err(StorageError::UnsupportedVersion {
-                version: self.version,
-                supported: 1,
-            });
+        validate_version(self.version, VMSK_VERSION)
+    }
+
+    /// Checksum the header and `data`, returning the container: header, data, block table
+    fn seal(mut self, data: &[u8]) -> Vec<u8> {
+        let header_size = mem::size_of::<VMSKHeader>();
+        self.version = VMSK_VERSION;
+        self.data_checksum = calculate_checksum(data);
+        self.blocks_offset = (header_size + data.len()) as u64;
+        self.header_checksum = 0;
+        let blocks = block_checksums(data, self.block_size);
+
+        let mut bytes = Vec::with_capacity(self.blocks_offset as usize + blocks.len());
+        bytes.extend_from_slice(crate::schemas::to_bytes(&self));
+        let checksum_offset = mem::offset_of!(VMSKHeader, header_checksum);
+        let header_crc = calculate_checksum(&bytes);
+        bytes[checksum_offset..checksum_offset + 4].copy_from_slice(&header_crc.to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes.extend_from_slice(&blocks);
+        bytes
+    }
+
+    /// Validate a container and return its header and data section
+    ///
+    /// Version 2 checksums are verified: a bad header is a corrupt block at
+    /// offset 0, a bad data block is reported at its own offset.
+    fn open(bytes: &[u8]) -> Result<(Self, &[u8])> {
+        let header_size = mem::size_of::<VMSKHeader>();
+        if bytes.len() < header_size {
+            return Err(StorageError::InvalidFormat { reason: "VMSK too small".into() });
         }
-        
-        Ok(())
+        // SAFETY: length checked; the header is plain data valid for any bit pattern
+        let header = unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const VMSKHeader) };
+        header.validate()?;
+        if header.version == 1 {
+            return Ok((header, &bytes[header_size..]));
+        }
+
+        let mut unsigned = bytes[..header_size].to_vec();
+        let checksum_offset = mem::offset_of!(VMSKHeader, header_checksum);
+        unsigned[checksum_offset..checksum_offset + 4].fill(0);
+        if calculate_checksum(&unsigned) != header.header_checksum {
+            return Err(StorageError::CorruptBlock { offset: 0 });
+        }
+        let blocks_offset = usize::try_from(header.blocks_offset)
+            .ok()
+            .filter(|&end| end >= header_size && end <= bytes.len())
+            .ok_or_else(|| StorageError::invalid_format("VMSK block table offset out of range"))?;
+        let data = &bytes[header_size..blocks_offset];
+        verify_blocks(data, &bytes[blocks_offset..], header.block_size, header_size as u64)?;
+        Ok((header, data))
     }
 }
 
@@ -114,6 +168,7 @@ fn u8_to_mask_type(value: u8) -> MaskType {
 }
 
 /// Simple bitmap-based mask implementation
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BitmapMask {
     mask_id: MaskId,
     mask_type: MaskType,
@@ -137,23 +192,36 @@ impl BitmapMask {
         }
     }
 
+    /// Create a mask with the given indices set
+    pub fn from_indices<I>(
+        mask_id: MaskId,
+        mask_type: MaskType,
+        generation: GenerationId,
+        total_bits: u64,
+        indices: I,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        let mut mask = Self::new(mask_id, mask_type, generation, total_bits);
+        for index in indices {
+            mask.set_bit(index as u64)?;
+        }
+        Ok(mask)
+    }
+
     /// Import a VMSK binary into a BitmapMask
     pub fn import_vmsk(bytes: &[u8]) -> Result<Self> {
-        use crate::schemas::cast_slice_to_struct;
-
-        if bytes.len() < core::mem::size_of::<VMSKHeader>() {
-            return Err(StorageError::InvalidFormat { reason: "VMSK too small".into() });
+        let (header, data) = VMSKHeader::open(bytes)?;
+        if header.flags & MaskCollection::FLAG_COLLECTION != 0 {
+            return Err(StorageError::invalid_format("VMSK is a mask collection; use MaskCollection::from_bytes"));
         }
 
-        let header: &VMSKHeader = unsafe { cast_slice_to_struct(bytes)? };
-        header.validate()?;
-
         // Compute expected bitmap length
         let words = ((header.total_bits + 63) / 64) as usize;
-        let header_size = core::mem::size_of::<VMSKHeader>();
         let bitmap_bytes = words * core::mem::size_of::<u64>();
 
-        if bytes.len() < header_size + bitmap_bytes {
+        if data.len() < bitmap_bytes {
             return Err(StorageError::InvalidFormat { reason: "VMSK bitmap truncated".into() });
         }
 
@@ -166,10 +234,10 @@ impl BitmapMask {
 
         // Copy bitmap words
         for i in 0..words {
-            let start = header_size + i * core::mem::size_of::<u64>();
+            let start = i * core::mem::size_of::<u64>();
             let end = start + core::mem::size_of::<u64>();
             let mut arr = [0u8; 8];
-            arr.copy_from_slice(&bytes[start..end]);
+            arr.copy_from_slice(&data[start..end]);
             mask.bitmap[i] = u64::from_le_bytes(arr);
         }
 
@@ -244,6 +312,196 @@ impl BitmapMask {
         
         indices
     }
+
+    /// Generation this mask was derived from
+    pub fn generation(&self) -> GenerationId {
+        self.generation
+    }
+
+    /// Set bits in `self` or `other`
+    pub fn union_with(&self, other: &BitmapMask) -> Result<BitmapMask> {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Set bits in both `self` and `other`
+    pub fn intersect_with(&self, other: &BitmapMask) -> Result<BitmapMask> {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Set bits in `self` but not in `other`
+    pub fn difference_with(&self, other: &BitmapMask) -> Result<BitmapMask> {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Complement within `0..total_bits`
+    pub fn inverted(&self) -> BitmapMask {
+        let mut result = self.clone();
+        for word in &mut result.bitmap {
+            *word = !*word;
+        }
+        result.clear_tail();
+        result.active_bits = result.bitmap.iter().map(|w| w.count_ones() as u64).sum();
+        result
+    }
+
+    /// Number of set bits in `start..end` (clamped to the mask size)
+    pub fn count_range(&self, start: u64, end: u64) -> u64 {
+        let end = end.min(self.total_bits);
+        if start >= end {
+            return 0;
+        }
+        let (first, last) = ((start / 64) as usize, ((end - 1) / 64) as usize);
+        let mut count = 0u64;
+        for (i, &word) in self.bitmap[first..=last].iter().enumerate() {
+            let word_idx = first + i;
+            let mut bits = word;
+            if word_idx == first {
+                bits &= !0u64 << (start % 64);
+            }
+            if word_idx == last && end % 64 != 0 {
+                bits &= (1u64 << (end % 64)) - 1;
+            }
+            count += bits.count_ones() as u64;
+        }
+        count
+    }
+
+    fn combine(&self, other: &BitmapMask, op: impl Fn(u64, u64) -> u64) -> Result<BitmapMask> {
+        if other.total_bits != self.total_bits {
+            return Err(StorageError::invalid_format(format!(
+                "Mask size mismatch: {} vs {} bits",
+                self.total_bits, other.total_bits
+            )));
+        }
+        let mut result = self.clone();
+        for (word, &rhs) in result.bitmap.iter_mut().zip(&other.bitmap) {
+            *word = op(*word, rhs);
+        }
+        result.clear_tail();
+        result.active_bits = result.bitmap.iter().map(|w| w.count_ones() as u64).sum();
+        Ok(result)
+    }
+
+    /// Zero the padding bits past `total_bits` in the last word
+    fn clear_tail(&mut self) {
+        let tail = self.total_bits % 64;
+        if tail != 0 {
+            if let Some(last) = self.bitmap.last_mut() {
+                *last &= (1u64 << tail) - 1;
+            }
+        }
+    }
+}
+
+/// Named masks stored together in one VMSK container
+///
+/// Layout: a VMSK header with [`MaskCollection::FLAG_COLLECTION`] set and
+/// `metadata_offset` pointing at the entry table, then `u32` entry count and
+/// per entry `u16` name length, UTF-8 name, `u64` payload length and a
+/// single-mask VMSK payload (all little-endian).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaskCollection {
+    entries: Vec<(String, BitmapMask)>,
+}
+
+impl MaskCollection {
+    /// Header flag marking a collection container
+    pub const FLAG_COLLECTION: u16 = 1;
+
+    /// Create an empty collection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert or replace a named mask
+    pub fn insert(&mut self, name: impl Into<String>, mask: BitmapMask) {
+        let name = name.into();
+        match self.entries.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = mask,
+            None => self.entries.push((name, mask)),
+        }
+    }
+
+    /// Look up a mask by name
+    pub fn get(&self, name: &str) -> Option<&BitmapMask> {
+        self.entries.iter().find(|(n, _)| n == name).map(|(_, m)| m)
+    }
+
+    /// Names in insertion order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(n, _)| n.as_str())
+    }
+
+    /// Number of masks
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the collection is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether `bytes` hold a collection container (as opposed to a single mask)
+    pub fn is_collection(bytes: &[u8]) -> bool {
+        use crate::schemas::cast_slice_to_struct;
+        if bytes.len() < mem::size_of::<VMSKHeader>() {
+            return false;
+        }
+        match unsafe { cast_slice_to_struct::<VMSKHeader>(bytes) } {
+            Ok(header) => header.validate().is_ok() && header.flags & Self::FLAG_COLLECTION != 0,
+            Err(_) => false,
+        }
+    }
+
+    /// Serialize to a VMSK collection container
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let header_size = mem::size_of::<VMSKHeader>();
+        let mut header = VMSKHeader::new(MaskId::new(0), GenerationId::new(0), MaskType::VertexMask, 0);
+        header.flags = Self::FLAG_COLLECTION;
+        header.metadata_offset = header_size as u64;
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for (name, mask) in &self.entries {
+            let payload = mask.export_vmsk()?;
+            let name_len = u16::try_from(name.len())
+                .map_err(|_| StorageError::invalid_format(format!("Mask name too long: {}", name)))?;
+            bytes.extend_from_slice(&name_len.to_le_bytes());
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&payload);
+        }
+        Ok(header.seal(&bytes))
+    }
+
+    /// Deserialize a VMSK collection container
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if !Self::is_collection(bytes) {
+            return Err(StorageError::invalid_format("Not a VMSK mask collection"));
+        }
+        let (_, data) = VMSKHeader::open(bytes)?;
+        let mut cursor = 0;
+        let mut take = |n: usize| -> Result<&[u8]> {
+            let slice = data
+                .get(cursor..cursor + n)
+                .ok_or_else(|| StorageError::invalid_format("VMSK collection truncated"))?;
+            cursor += n;
+            Ok(slice)
+        };
+
+        let count = u32::from_le_bytes(take(4)?.try_into().unwrap());
+        let mut collection = Self::new();
+        for _ in 0..count {
+            let name_len = u16::from_le_bytes(take(2)?.try_into().unwrap()) as usize;
+            let name = core::str::from_utf8(take(name_len)?)
+                .map_err(|_| StorageError::invalid_format("VMSK collection name is not UTF-8"))?
+                .to_string();
+            let payload_len = u64::from_le_bytes(take(8)?.try_into().unwrap()) as usize;
+            collection.insert(name, BitmapMask::import_vmsk(take(payload_len)?)?);
+        }
+        Ok(collection)
+    }
 }
 
 impl Mask for BitmapMask {
@@ -344,24 +602,15 @@ impl Mask for BitmapMask {
     fn export_vmsk(&self) -> Result<Vec<u8>> {
         let mut header = VMSKHeader::new(self.mask_id, self.generation, self.mask_type, self.total_bits);
         header.active_bits = self.active_bits;
+        header.bitmap_offset = mem::size_of::<VMSKHeader>() as u64;
         
-        let mut bytes = Vec::new();
-        
-        // Write header
-        let header_bytes = unsafe {
-            core::slice::from_raw_parts(
-                &header as *const VMSKHeader as *const u8,
-                mem::size_of::<VMSKHeader>(),
-            )
-        };
-        bytes.extend_from_slice(header_bytes);
-        
-        // Write bitmap data
+        // Bitmap data
+        let mut bitmap = Vec::with_capacity(self.bitmap.len() * mem::size_of::<u64>());
         for &word in &self.bitmap {
-            bytes.extend_from_slice(&word.to_le_bytes());
+            bitmap.extend_from_slice(&word.to_le_bytes());
         }
         
-        Ok(bytes)
+        Ok(header.seal(&bitmap))
     }
 }
 
@@ -379,7 +628,7 @@ mod tests {
         );
         
         assert_eq!(header.magic, magic::VMSK);
-        assert_eq!(header.version, 1);
+        assert_eq!(header.version, VMSK_VERSION);
         assert_eq!(header.mask_id, 1);
         assert_eq!(header.generation, 5);
         assert_eq!(header.total_bits, 1000);
@@ -464,6 +713,89 @@ mod tests {
         assert_eq!(difference.active_count(), 1);
     }
 
+    #[test]
+    fn test_mask_from_indices() {
+        let mask = BitmapMask::from_indices(
+            MaskId::new(3),
+            MaskType::ActivityMask,
+            GenerationId::new(0),
+            70,
+            [2, 65, 2],
+        ).unwrap();
+        assert_eq!(mask.active_indices(), vec![2, 65]);
+        assert_eq!(mask.active_count(), 2);
+
+        let bytes = mask.export_vmsk().unwrap();
+        let decoded = BitmapMask::import_vmsk(&bytes).unwrap();
+        assert_eq!(decoded.mask_type(), MaskType::ActivityMask);
+        assert_eq!(decoded.active_indices(), vec![2, 65]);
+
+        assert!(BitmapMask::from_indices(
+            MaskId::new(3),
+            MaskType::ActivityMask,
+            GenerationId::new(0),
+            70,
+            [70],
+        ).is_err());
+    }
+
+    fn mask_of(total_bits: u64, indices: &[u32]) -> BitmapMask {
+        BitmapMask::from_indices(
+            MaskId::new(1),
+            MaskType::ActivityMask,
+            GenerationId::new(0),
+            total_bits,
+            indices.iter().copied(),
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_mask_algebra() {
+        let a = mask_of(70, &[1, 3, 64, 69]);
+        let b = mask_of(70, &[3, 5, 69]);
+
+        assert_eq!(a.union_with(&b).unwrap().active_indices(), vec![1, 3, 5, 64, 69]);
+        assert_eq!(a.intersect_with(&b).unwrap().active_indices(), vec![3, 69]);
+        assert_eq!(a.difference_with(&b).unwrap().active_indices(), vec![1, 64]);
+
+        let inv = b.inverted();
+        assert_eq!(inv.active_count(), 67);
+        assert!(!inv.is_active(3) && inv.is_active(4) && !inv.is_active(69));
+        assert_eq!(inv.inverted(), b);
+
+        assert!(a.union_with(&mask_of(10, &[])).is_err());
+    }
+
+    #[test]
+    fn test_count_range() {
+        let a = mask_of(130, &[0, 63, 64, 100, 129]);
+        assert_eq!(a.count_range(0, 130), 5);
+        assert_eq!(a.count_range(0, 64), 2);
+        assert_eq!(a.count_range(63, 65), 2);
+        assert_eq!(a.count_range(65, 129), 1);
+        assert_eq!(a.count_range(100, 1_000), 2);
+        assert_eq!(a.count_range(5, 5), 0);
+    }
+
+    #[test]
+    fn test_mask_collection_roundtrip() {
+        let mut collection = MaskCollection::new();
+        collection.insert("task_a", mask_of(70, &[1, 2]));
+        collection.insert("task_b", mask_of(70, &[65]));
+
+        let bytes = collection.to_bytes().unwrap();
+        assert!(MaskCollection::is_collection(&bytes));
+        let single = mask_of(70, &[1]).export_vmsk().unwrap();
+        assert!(!MaskCollection::is_collection(&single));
+        assert!(MaskCollection::from_bytes(&single).is_err());
+        assert!(BitmapMask::import_vmsk(&bytes).is_err());
+
+        let decoded = MaskCollection::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.names().collect::<Vec<_>>(), vec!["task_a", "task_b"]);
+        assert_eq!(decoded.get("task_b").unwrap().active_indices(), vec![65]);
+        assert_eq!(decoded, collection);
+    }
+
     #[test]
     fn test_mask_serialization() {
         let mut mask = BitmapMask::new(
@@ -483,4 +815,33 @@ mod tests {
         // Verify header is at the beginning
         assert_eq!(&bytes[0..4], &magic::VMSK);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_vmsk_integrity_errors() {
+        let bytes = mask_of(200, &[3, 150]).export_vmsk().unwrap();
+        let header_size = mem::size_of::<VMSKHeader>();
+        assert_eq!(BitmapMask::import_vmsk(&bytes).unwrap().active_indices(), vec![3, 150]);
+
+        let mut corrupt = bytes.clone();
+        corrupt[header_size + 17] ^= 0x40;
+        assert!(matches!(
+            BitmapMask::import_vmsk(&corrupt),
+            Err(StorageError::CorruptBlock { offset }) if offset == header_size as u64
+        ));
+
+        let mut corrupt = bytes.clone();
+        corrupt[mem::offset_of!(VMSKHeader, total_bits)] ^= 0x01;
+        assert!(matches!(BitmapMask::import_vmsk(&corrupt), Err(StorageError::CorruptBlock { offset: 0 })));
+
+        let mut future = bytes.clone();
+        future[4..8].copy_from_slice(&(VMSK_VERSION + 1).to_le_bytes());
+        assert!(matches!(BitmapMask::import_vmsk(&future), Err(StorageError::BadVersion { .. })));
+
+        let mut collection = MaskCollection::new();
+        collection.insert("a", mask_of(70, &[1]));
+        let mut corrupt = collection.to_bytes().unwrap();
+        let last = corrupt.len() - 5;
+        corrupt[last] ^= 0x01;
+        assert!(matches!(MaskCollection::from_bytes(&corrupt), Err(StorageError::CorruptBlock { .. })));
+    }
+}
@@ -0,0 +1,40 @@
+//! Errors of the fallible constructors
+
+use core::fmt;
+
+/// Invalid value passed to a shared type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypesError {
+    /// Negative, NaN or infinite time/duration
+    InvalidTime,
+    /// Later time subtracted from an earlier one
+    TimeOrdering,
+    /// Value does not fit the target unit's integer range
+    UnitOverflow,
+    /// Conversion would round away more than the allowed tolerance
+    PrecisionLoss,
+    /// Text is not a number followed by a time unit
+    Unparsable,
+}
+
+impl TypesError {
+    /// Static description
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            TypesError::InvalidTime => "Invalid time value",
+            TypesError::TimeOrdering => "Time ordering violation",
+            TypesError::UnitOverflow => "Unit conversion overflow",
+            TypesError::PrecisionLoss => "Unit conversion loses precision",
+            TypesError::Unparsable => "Expected a number followed by ns, us, ms or s",
+        }
+    }
+}
+
+impl fmt::Display for TypesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TypesError {}
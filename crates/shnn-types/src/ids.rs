@@ -0,0 +1,109 @@
+//! Neuron and hyperedge identifiers
+
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Unique identifier for a neuron in the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NeuronId(pub u32);
+
+impl NeuronId {
+    /// Create a new neuron ID
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Get the raw ID value
+    pub const fn raw(&self) -> u32 {
+        self.0
+    }
+
+    /// Invalid neuron ID constant
+    pub const INVALID: Self = Self(u32::MAX);
+
+    /// Check if this is a valid neuron ID
+    pub const fn is_valid(&self) -> bool {
+        self.0 != u32::MAX
+    }
+
+    /// Convert to usize for array indexing
+    pub const fn as_usize(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for NeuronId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "N{}", self.0)
+    }
+}
+
+impl From<u32> for NeuronId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl From<usize> for NeuronId {
+    fn from(id: usize) -> Self {
+        Self(id as u32)
+    }
+}
+
+impl From<NeuronId> for u32 {
+    fn from(id: NeuronId) -> Self {
+        id.0
+    }
+}
+
+impl From<NeuronId> for usize {
+    fn from(id: NeuronId) -> Self {
+        id.0 as usize
+    }
+}
+
+/// Unique identifier for a hyperedge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HyperedgeId(pub u32);
+
+impl HyperedgeId {
+    /// Create a new hyperedge ID
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Get the raw ID value
+    pub const fn raw(&self) -> u32 {
+        self.0
+    }
+
+    /// Invalid hyperedge ID constant
+    pub const INVALID: Self = Self(u32::MAX);
+
+    /// Check if this is a valid hyperedge ID
+    pub const fn is_valid(&self) -> bool {
+        self.0 != u32::MAX
+    }
+}
+
+impl fmt::Display for HyperedgeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{}", self.0)
+    }
+}
+
+impl From<u32> for HyperedgeId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl From<HyperedgeId> for u32 {
+    fn from(id: HyperedgeId) -> Self {
+        id.0
+    }
+}
@@ -0,0 +1,26 @@
+//! Shared identifier, time and spike types
+//!
+//! `shnn-core`, `shnn-storage` and `shnn-runtime` all re-export these types,
+//! so a [`NeuronId`] or [`Time`] produced by one crate is the same type in
+//! every other and no conversion glue is needed between layers. Times are
+//! always nanoseconds; [`units`] converts model parameters written in other
+//! units with checked arithmetic.
+
+#![no_std]
+#![deny(missing_docs)]
+#![warn(clippy::all)]
+
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod error;
+pub mod ids;
+pub mod spike;
+pub mod time;
+pub mod units;
+
+pub use error::TypesError;
+pub use ids::{HyperedgeId, NeuronId};
+pub use spike::Spike;
+pub use time::{Duration, Time};
+pub use units::{Quantity, TimeUnit};
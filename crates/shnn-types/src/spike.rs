@@ -0,0 +1,74 @@
+//! Spike events
+
+use crate::{ids::NeuronId, time::Time};
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Spike event
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Spike {
+    /// Neuron that spiked
+    pub neuron_id: NeuronId,
+    /// Time of spike
+    pub time: Time,
+    /// Spike amplitude (1.0 for binary spikes)
+    pub amplitude: f32,
+}
+
+impl Spike {
+    /// Create a binary spike
+    pub fn new(neuron_id: NeuronId, time: Time) -> Self {
+        Self {
+            neuron_id,
+            time,
+            amplitude: 1.0,
+        }
+    }
+
+    /// Create a spike with specific amplitude
+    pub fn with_amplitude(neuron_id: NeuronId, time: Time, amplitude: f32) -> Self {
+        Self {
+            neuron_id,
+            time,
+            amplitude,
+        }
+    }
+
+    /// True if the neuron ID is valid and the amplitude finite and non-negative
+    pub fn is_valid(&self) -> bool {
+        self.neuron_id.is_valid() && self.amplitude.is_finite() && self.amplitude >= 0.0
+    }
+}
+
+impl fmt::Display for Spike {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Spike({} @ {} amp={:.3})", self.neuron_id, self.time, self.amplitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Duration;
+
+    #[test]
+    fn test_spike_validity() {
+        let spike = Spike::new(NeuronId::new(3), Time::from_millis(2));
+        assert!(spike.is_valid());
+        assert_eq!(spike.time.nanos(), 2_000_000);
+        assert!(!Spike::new(NeuronId::INVALID, Time::ZERO).is_valid());
+        assert!(!Spike::with_amplitude(NeuronId::new(0), Time::ZERO, f32::NAN).is_valid());
+    }
+
+    #[test]
+    fn test_time_arithmetic() {
+        let t = Time::from_millis(5) + Duration::from_micros(250);
+        assert_eq!(t.as_nanos(), 5_250_000);
+        assert_eq!(t - Time::from_millis(5), Duration::from_micros(250));
+        assert_eq!(Time::ZERO.duration_since(t), Err(crate::TypesError::TimeOrdering));
+        assert_eq!(Time::from_secs_f64(-1.0), Err(crate::TypesError::InvalidTime));
+    }
+}
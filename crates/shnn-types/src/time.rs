@@ -0,0 +1,316 @@
+//! Time points and durations in nanoseconds
+
+use crate::error::TypesError;
+use crate::units::Quantity;
+use core::fmt;
+use core::ops::{Add, Sub, AddAssign, SubAssign};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// High-precision time representation for neuromorphic computation
+///
+/// Time is represented in nanoseconds to provide sufficient precision
+/// for biological time constants while maintaining efficient arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Time(u64);
+
+impl Time {
+    /// Create a new time from nanoseconds (alias of [`Time::from_nanos`])
+    pub const fn new(nanos: u64) -> Self {
+        Self(nanos)
+    }
+    
+    /// Create a new time from nanoseconds
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos)
+    }
+    
+    /// Create a new time from microseconds
+    pub const fn from_micros(micros: u64) -> Self {
+        Self(micros * 1_000)
+    }
+    
+    /// Create a new time from milliseconds
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(millis * 1_000_000)
+    }
+    
+    /// Create a new time from seconds
+    pub const fn from_secs(secs: u64) -> Self {
+        Self(secs * 1_000_000_000)
+    }
+    
+    /// Create a new time from floating-point seconds
+    pub fn from_secs_f64(secs: f64) -> Result<Self, TypesError> {
+        Quantity::s(secs).round_ns().map(Self)
+    }
+    
+    /// Zero time constant
+    pub const ZERO: Self = Self(0);
+    
+    /// Maximum representable time
+    pub const MAX: Self = Self(u64::MAX);
+    
+    /// Get time as nanoseconds
+    pub const fn as_nanos(&self) -> u64 {
+        self.0
+    }
+    
+    /// Get time as nanoseconds (alias of [`Time::as_nanos`])
+    pub const fn nanos(&self) -> u64 {
+        self.0
+    }
+    
+    /// Get time as microseconds
+    pub const fn as_micros(&self) -> u64 {
+        self.0 / 1_000
+    }
+    
+    /// Get time as milliseconds
+    pub const fn as_millis(&self) -> u64 {
+        self.0 / 1_000_000
+    }
+    
+    /// Get time as seconds
+    pub const fn as_secs(&self) -> u64 {
+        self.0 / 1_000_000_000
+    }
+    
+    /// Get time as floating-point seconds
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0 as f64 / 1_000_000_000.0
+    }
+    
+    /// Get time as f32 seconds for compatibility
+    pub fn as_secs_f32(&self) -> f32 {
+        self.0 as f32 / 1_000_000_000.0
+    }
+    
+    /// Calculate the duration since another time
+    pub fn duration_since(&self, earlier: Time) -> Result<Duration, TypesError> {
+        if self.0 >= earlier.0 {
+            Ok(Duration(self.0 - earlier.0))
+        } else {
+            Err(TypesError::TimeOrdering)
+        }
+    }
+    
+    /// Calculate the elapsed time since another time
+    pub fn elapsed_since(&self, earlier: Time) -> Duration {
+        Duration(self.0.saturating_sub(earlier.0))
+    }
+    
+    /// Check if this time is after another time
+    pub const fn is_after(&self, other: Time) -> bool {
+        self.0 > other.0
+    }
+    
+    /// Check if this time is before another time
+    pub const fn is_before(&self, other: Time) -> bool {
+        self.0 < other.0
+    }
+    
+    /// Saturating addition
+    pub fn saturating_add(&self, duration: Duration) -> Self {
+        Self(self.0.saturating_add(duration.0))
+    }
+    
+    /// Saturating subtraction
+    pub fn saturating_sub(&self, duration: Duration) -> Self {
+        Self(self.0.saturating_sub(duration.0))
+    }
+    
+    /// Checked addition
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        self.0.checked_add(duration.0).map(Self)
+    }
+    
+    /// Checked subtraction
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        self.0.checked_sub(duration.0).map(Self)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 >= 1_000_000_000 {
+            write!(f, "{:.3}s", self.as_secs_f64())
+        } else if self.0 >= 1_000_000 {
+            write!(f, "{:.3}ms", self.0 as f64 / 1_000_000.0)
+        } else if self.0 >= 1_000 {
+            write!(f, "{:.3}μs", self.0 as f64 / 1_000.0)
+        } else {
+            write!(f, "{}ns", self.0)
+        }
+    }
+}
+
+/// Duration between two time points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Duration(u64);
+
+impl Duration {
+    /// Create a new duration from nanoseconds
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos)
+    }
+    
+    /// Create a new duration from microseconds
+    pub const fn from_micros(micros: u64) -> Self {
+        Self(micros * 1_000)
+    }
+    
+    /// Create a new duration from milliseconds
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(millis * 1_000_000)
+    }
+    
+    /// Create a new duration from seconds
+    pub const fn from_secs(secs: u64) -> Self {
+        Self(secs * 1_000_000_000)
+    }
+    
+    /// Create a new duration from floating-point seconds
+    pub fn from_secs_f64(secs: f64) -> Result<Self, TypesError> {
+        Quantity::s(secs).round_ns().map(Self)
+    }
+    
+    /// Zero duration constant
+    pub const ZERO: Self = Self(0);
+    
+    /// Maximum representable duration
+    pub const MAX: Self = Self(u64::MAX);
+    
+    /// Get duration as nanoseconds
+    pub const fn as_nanos(&self) -> u64 {
+        self.0
+    }
+    
+    /// Get duration as microseconds
+    pub const fn as_micros(&self) -> u64 {
+        self.0 / 1_000
+    }
+    
+    /// Get duration as milliseconds
+    pub const fn as_millis(&self) -> u64 {
+        self.0 / 1_000_000
+    }
+    
+    /// Get duration as seconds
+    pub const fn as_secs(&self) -> u64 {
+        self.0 / 1_000_000_000
+    }
+    
+    /// Get duration as floating-point seconds
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0 as f64 / 1_000_000_000.0
+    }
+    
+    /// Check if duration is zero
+    pub const fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+    
+    /// Saturating multiplication
+    pub fn saturating_mul(&self, rhs: u32) -> Self {
+        Self(self.0.saturating_mul(rhs as u64))
+    }
+    
+    /// Saturating division
+    pub fn saturating_div(&self, rhs: u32) -> Self {
+        if rhs == 0 {
+            Self::ZERO
+        } else {
+            Self(self.0 / rhs as u64)
+        }
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 >= 1_000_000_000 {
+            write!(f, "{:.3}s", self.as_secs_f64())
+        } else if self.0 >= 1_000_000 {
+            write!(f, "{:.3}ms", self.0 as f64 / 1_000_000.0)
+        } else if self.0 >= 1_000 {
+            write!(f, "{:.3}μs", self.0 as f64 / 1_000.0)
+        } else {
+            write!(f, "{}ns", self.0)
+        }
+    }
+}
+
+// Arithmetic operations for Time
+impl Add<Duration> for Time {
+    type Output = Self;
+    
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign<Duration> for Time {
+    fn add_assign(&mut self, rhs: Duration) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub<Duration> for Time {
+    type Output = Self;
+    
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign<Duration> for Time {
+    fn sub_assign(&mut self, rhs: Duration) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Sub<Time> for Time {
+    type Output = Duration;
+    
+    fn sub(self, rhs: Time) -> Self::Output {
+        Duration(self.0 - rhs.0)
+    }
+}
+
+// Arithmetic operations for Duration
+impl Add<Duration> for Duration {
+    type Output = Self;
+    
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign<Duration> for Duration {
+    fn add_assign(&mut self, rhs: Duration) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub<Duration> for Duration {
+    type Output = Self;
+    
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign<Duration> for Duration {
+    fn sub_assign(&mut self, rhs: Duration) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl From<Time> for Duration {
+    fn from(time: Time) -> Self {
+        Self(time.0)
+    }
+}
@@ -0,0 +1,254 @@
+//! Checked unit arithmetic for times
+//!
+//! Times are stored as integer nanoseconds while model parameters are written
+//! as floating-point milliseconds. `(ms * 1_000_000.0) as u64` multiplies in
+//! `f32` (losing digits above 2^24 ns), truncates instead of rounding and
+//! turns NaN or negative values into 0 without notice. [`Quantity`] converts
+//! in `f64`, rounds to the nearest nanosecond and reports overflow or a
+//! rounding error beyond [`Quantity::TOLERANCE`].
+
+use core::fmt;
+
+use crate::error::TypesError;
+
+/// Time unit of a [`Quantity`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeUnit {
+    /// Nanoseconds
+    Ns,
+    /// Microseconds
+    Us,
+    /// Milliseconds
+    Ms,
+    /// Seconds
+    S,
+}
+
+impl TimeUnit {
+    /// Nanoseconds in one unit
+    pub const fn ns_per_unit(self) -> u64 {
+        match self {
+            TimeUnit::Ns => 1,
+            TimeUnit::Us => 1_000,
+            TimeUnit::Ms => 1_000_000,
+            TimeUnit::S => 1_000_000_000,
+        }
+    }
+
+    /// Unit suffix used in text formats
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            TimeUnit::Ns => "ns",
+            TimeUnit::Us => "us",
+            TimeUnit::Ms => "ms",
+            TimeUnit::S => "s",
+        }
+    }
+}
+
+/// Floating-point time value tagged with its unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quantity {
+    /// Magnitude in `unit`
+    pub value: f64,
+    /// Unit of `value`
+    pub unit: TimeUnit,
+}
+
+impl Quantity {
+    /// Largest relative error [`Quantity::to_ns`] and [`Quantity::to_f32`] accept
+    ///
+    /// Well above `f32` rounding (~6e-8), so any `f32` literal that names a
+    /// whole number of nanoseconds converts cleanly.
+    pub const TOLERANCE: f64 = 1e-6;
+
+    /// Value in `unit`
+    pub const fn new(value: f64, unit: TimeUnit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Value in nanoseconds
+    pub const fn ns(value: f64) -> Self {
+        Self::new(value, TimeUnit::Ns)
+    }
+
+    /// Value in microseconds
+    pub const fn us(value: f64) -> Self {
+        Self::new(value, TimeUnit::Us)
+    }
+
+    /// Value in milliseconds
+    pub const fn ms(value: f64) -> Self {
+        Self::new(value, TimeUnit::Ms)
+    }
+
+    /// Value in seconds
+    pub const fn s(value: f64) -> Self {
+        Self::new(value, TimeUnit::S)
+    }
+
+    /// Integer nanoseconds as a quantity
+    pub fn from_ns(ns: u64) -> Self {
+        Self::ns(ns as f64)
+    }
+
+    /// Exact value in nanoseconds (before rounding)
+    fn exact_ns(self) -> Result<f64, TypesError> {
+        if !self.value.is_finite() || self.value < 0.0 {
+            return Err(TypesError::InvalidTime);
+        }
+        let ns = self.value * self.unit.ns_per_unit() as f64;
+        // u64::MAX as f64 rounds up to 2^64, which is itself out of range
+        if ns >= u64::MAX as f64 {
+            return Err(TypesError::UnitOverflow);
+        }
+        Ok(ns)
+    }
+
+    /// Nearest whole nanosecond, rejecting negative, non-finite or too large values
+    ///
+    /// Use this where rounding is intended (e.g. after quantization);
+    /// [`Quantity::to_ns`] also rejects values that are not close to a whole
+    /// nanosecond.
+    pub fn round_ns(self) -> Result<u64, TypesError> {
+        // floor(x + 0.5) without std's f64::round
+        self.exact_ns().map(|ns| (ns + 0.5) as u64)
+    }
+
+    /// Whole nanoseconds, rejecting values that round by more than [`Quantity::TOLERANCE`]
+    pub fn to_ns(self) -> Result<u64, TypesError> {
+        let exact = self.exact_ns()?;
+        let ns = (exact + 0.5) as u64;
+        if abs(ns as f64 - exact) > Self::TOLERANCE * exact {
+            return Err(TypesError::PrecisionLoss);
+        }
+        Ok(ns)
+    }
+
+    /// Nearest whole nanosecond, saturating instead of failing
+    ///
+    /// NaN and negative values become 0 and values above the `u64` range
+    /// become `u64::MAX`, matching `as u64` but rounding instead of truncating.
+    pub fn saturating_ns(self) -> u64 {
+        match self.round_ns() {
+            Ok(ns) => ns,
+            Err(TypesError::UnitOverflow) => u64::MAX,
+            Err(_) => 0,
+        }
+    }
+
+    /// Value in `unit` (no rounding)
+    pub fn in_unit(self, unit: TimeUnit) -> f64 {
+        self.value * self.unit.ns_per_unit() as f64 / unit.ns_per_unit() as f64
+    }
+
+    /// Value in `unit` as `f32`, rejecting overflow and precision loss beyond [`Quantity::TOLERANCE`]
+    pub fn to_f32(self, unit: TimeUnit) -> Result<f32, TypesError> {
+        if !self.value.is_finite() {
+            return Err(TypesError::InvalidTime);
+        }
+        let exact = self.in_unit(unit);
+        let narrowed = exact as f32;
+        if !narrowed.is_finite() {
+            return Err(TypesError::UnitOverflow);
+        }
+        if abs(narrowed as f64 - exact) > Self::TOLERANCE * abs(exact) {
+            return Err(TypesError::PrecisionLoss);
+        }
+        Ok(narrowed)
+    }
+}
+
+impl core::str::FromStr for Quantity {
+    type Err = TypesError;
+
+    /// Parse `<number><unit>` such as `5ms`, `1.5 s` or `250us`
+    fn from_str(text: &str) -> Result<Self, TypesError> {
+        let text = text.trim();
+        // "s" last: every other symbol ends with it
+        for unit in [TimeUnit::Ns, TimeUnit::Us, TimeUnit::Ms, TimeUnit::S] {
+            if let Some(value) = text.strip_suffix(unit.symbol()) {
+                let value = value.trim_end().parse::<f64>().map_err(|_| TypesError::Unparsable)?;
+                return Ok(Self::new(value, unit));
+            }
+        }
+        Err(TypesError::Unparsable)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit.symbol())
+    }
+}
+
+/// `f64::abs` is not available in `core` on older toolchains
+fn abs(x: f64) -> f64 {
+    if x < 0.0 { -x } else { x }
+}
+
+/// Checked milliseconds to whole nanoseconds
+pub fn ms_to_ns(ms: f32) -> Result<u64, TypesError> {
+    Quantity::ms(ms as f64).to_ns()
+}
+
+/// Nanoseconds to milliseconds with a single rounding step
+///
+/// Cannot fail: every `u64` fits in `f32` range and the `f32` rounding error
+/// is below [`Quantity::TOLERANCE`].
+pub fn ns_to_ms(ns: u64) -> f32 {
+    (ns as f64 / TimeUnit::Ms.ns_per_unit() as f64) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ms_to_ns_rounds_instead_of_truncating() {
+        // 123456789 ns is not representable as an f32 product
+        assert_eq!(Quantity::ms(123.456_789).to_ns(), Ok(123_456_789));
+        assert_eq!(ms_to_ns(0.3), Ok(300_000));
+        assert_eq!(ms_to_ns(0.1), Ok(100_000));
+        assert_eq!(ms_to_ns(0.0), Ok(0));
+        assert_eq!(Quantity::us(1.5).to_ns(), Ok(1_500));
+    }
+
+    #[test]
+    fn test_invalid_and_overflowing_values_are_rejected() {
+        assert_eq!(ms_to_ns(-1.0), Err(TypesError::InvalidTime));
+        assert_eq!(ms_to_ns(f32::NAN), Err(TypesError::InvalidTime));
+        assert_eq!(ms_to_ns(f32::INFINITY), Err(TypesError::InvalidTime));
+        assert_eq!(Quantity::s(1e11).to_ns(), Err(TypesError::UnitOverflow));
+        assert_eq!(Quantity::s(1e11).saturating_ns(), u64::MAX);
+        assert_eq!(Quantity::ms(-2.0).saturating_ns(), 0);
+    }
+
+    #[test]
+    fn test_sub_nanosecond_values_lose_precision() {
+        assert_eq!(Quantity::ns(0.4).to_ns(), Err(TypesError::PrecisionLoss));
+        assert_eq!(Quantity::us(0.0125).to_ns(), Err(TypesError::PrecisionLoss));
+        assert_eq!(Quantity::us(0.0125).round_ns(), Ok(13));
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!("5ms".parse::<Quantity>(), Ok(Quantity::ms(5.0)));
+        assert_eq!(" 1.5 s".parse::<Quantity>(), Ok(Quantity::s(1.5)));
+        assert_eq!("250us".parse::<Quantity>(), Ok(Quantity::us(250.0)));
+        assert_eq!("100ns".parse::<Quantity>().and_then(Quantity::to_ns), Ok(100));
+        assert_eq!("5".parse::<Quantity>(), Err(TypesError::Unparsable));
+        assert_eq!("ms".parse::<Quantity>(), Err(TypesError::Unparsable));
+        assert_eq!("5 min".parse::<Quantity>(), Err(TypesError::Unparsable));
+    }
+
+    #[test]
+    fn test_ns_to_ms_and_f32_narrowing() {
+        assert_eq!(ns_to_ms(1_500_000), 1.5);
+        assert_eq!(ns_to_ms(16_777_217_000), 16_777.217);
+        assert_eq!(Quantity::from_ns(2_500).to_f32(TimeUnit::Us), Ok(2.5));
+        assert_eq!(Quantity::s(1e300).to_f32(TimeUnit::Ns), Err(TypesError::UnitOverflow));
+    }
+}